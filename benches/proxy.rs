@@ -0,0 +1,59 @@
+//! Proxy and route table benchmarks.
+//!
+//! Run with `cargo bench --bench proxy`. Compare against a saved baseline
+//! (`--save-baseline main`, then `--baseline main`) to catch regressions in
+//! route lookup or request forwarding.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rivetr::proxy::bench::{bench_route_table, route_domain, BenchProxy};
+use std::net::SocketAddr;
+
+const ROUTE_COUNTS: [usize; 3] = [10, 1_000, 10_000];
+
+fn route_lookup(c: &mut Criterion) {
+    let backend: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+    let mut group = c.benchmark_group("route_lookup");
+    for routes in ROUTE_COUNTS {
+        let table = bench_route_table(routes, backend);
+        let hit = route_domain(routes / 2);
+        let hit_with_port = format!("{}:8080", hit);
+
+        group.bench_with_input(BenchmarkId::new("hit", routes), &hit, |b, domain| {
+            b.iter(|| table.get_backend(black_box(domain)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("hit_with_port", routes),
+            &hit_with_port,
+            |b, domain| b.iter(|| table.get_backend(black_box(domain))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("miss", routes),
+            "unknown.rivetr.test",
+            |b, domain| b.iter(|| table.get_backend(black_box(domain))),
+        );
+    }
+    group.finish();
+}
+
+fn proxied_request(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("proxied_request");
+    group.throughput(Throughput::Elements(1));
+    for routes in ROUTE_COUNTS {
+        let proxy = rt.block_on(BenchProxy::start(routes)).unwrap();
+        let mut client = rt.block_on(proxy.connect()).unwrap();
+        let domains: Vec<String> = (0..routes).map(route_domain).collect();
+        let mut next = 0;
+
+        group.bench_function(BenchmarkId::from_parameter(routes), |b| {
+            b.iter(|| {
+                next = (next + 1) % domains.len();
+                rt.block_on(client.get(&domains[next])).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, route_lookup, proxied_request);
+criterion_main!(benches);
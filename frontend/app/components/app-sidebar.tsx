@@ -19,6 +19,7 @@ import {
   ExternalLink,
   MessageCircle,
   ShieldCheck,
+  Braces,
 } from "lucide-react";
 
 import { NavMain, type NavMainItem } from "@/components/nav-main";
@@ -61,6 +62,7 @@ const navAccess: NavMainItem[] = [
   { title: "Webhooks", url: "/webhooks", icon: Webhook },
   { title: "Webhook Events", url: "/webhook-events", icon: Activity },
   { title: "API Tokens", url: "/tokens", icon: KeyRound },
+  { title: "API Explorer", url: "/api-explorer", icon: Braces },
 ];
 
 const navSettings: NavMainItem[] = [
@@ -0,0 +1,52 @@
+import { useEffect, useState } from "react";
+import { useQuery } from "@tanstack/react-query";
+import { api } from "@/lib/api";
+import type { CronPreview as CronPreviewData } from "@/lib/api/system";
+
+interface CronPreviewProps {
+  expression: string;
+  /** Number of upcoming runs to show */
+  count?: number;
+  /** IANA timezone the schedule runs in (UTC when empty) */
+  timezone?: string;
+}
+
+/** Validates a cron expression on the server and lists its next run times */
+export function CronPreview({ expression, count = 3, timezone = "" }: CronPreviewProps) {
+  const [debounced, setDebounced] = useState(expression.trim());
+
+  useEffect(() => {
+    const timer = setTimeout(() => setDebounced(expression.trim()), 400);
+    return () => clearTimeout(timer);
+  }, [expression]);
+
+  const { data, error } = useQuery<CronPreviewData>({
+    queryKey: ["cron-preview", debounced, count, timezone],
+    queryFn: () => api.previewCron(debounced, count, timezone),
+    enabled: debounced !== "",
+    retry: false,
+  });
+
+  if (debounced === "") {
+    return null;
+  }
+  if (error) {
+    return (
+      <p className="text-xs text-destructive">
+        {error instanceof Error ? error.message : "Invalid cron expression"}
+      </p>
+    );
+  }
+  if (!data) {
+    return null;
+  }
+
+  return (
+    <p className="text-xs text-muted-foreground">
+      Next runs ({data.timezone}, UTC{data.utc_offset}):{" "}
+      {data.next_runs
+        .map((run) => run.slice(0, 16).replace("T", " "))
+        .join(", ")}
+    </p>
+  );
+}
@@ -0,0 +1,209 @@
+import { useEffect, useRef, useState } from "react";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { FileUp, Play, Sprout, Trash2 } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
+import { api } from "@/lib/api";
+import type { ManagedDatabase } from "@/types/api";
+
+interface DatabaseSeedCardProps {
+  database: ManagedDatabase;
+}
+
+const SEEDABLE_TYPES = ["postgres", "mysql", "mariadb", "mongodb"];
+
+export function DatabaseSeedCard({ database }: DatabaseSeedCardProps) {
+  const queryClient = useQueryClient();
+  const fileInputRef = useRef<HTMLInputElement>(null);
+  const [script, setScript] = useState(database.seed_script ?? "");
+  const [seedOnClone, setSeedOnClone] = useState(database.seed_on_clone);
+  const [isSaving, setIsSaving] = useState(false);
+  const [isUploading, setIsUploading] = useState(false);
+  const [isSeeding, setIsSeeding] = useState(false);
+
+  // Sync state when the database changes
+  useEffect(() => {
+    setScript(database.seed_script ?? "");
+    setSeedOnClone(database.seed_on_clone);
+  }, [database.seed_script, database.seed_on_clone]);
+
+  if (!SEEDABLE_TYPES.includes(database.db_type)) return null;
+
+  const isDirty =
+    script !== (database.seed_script ?? "") || seedOnClone !== database.seed_on_clone;
+  const hasSeed = !!database.seed_script || !!database.seed_dump_file;
+  const refresh = () => queryClient.invalidateQueries({ queryKey: ["database", database.id] });
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      await api.updateDatabase(database.id, { seed_script: script, seed_on_clone: seedOnClone });
+      toast.success("Seed saved");
+      refresh();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save seed");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleUpload = async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    if (!file) return;
+    setIsUploading(true);
+    try {
+      await api.uploadSeedDump(database.id, file);
+      toast.success(`Seed dump ${file.name} uploaded`);
+      refresh();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to upload seed dump");
+    } finally {
+      setIsUploading(false);
+      if (fileInputRef.current) fileInputRef.current.value = "";
+    }
+  };
+
+  const handleRemoveDump = async () => {
+    try {
+      await api.deleteSeedDump(database.id);
+      toast.success("Seed dump removed");
+      refresh();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to remove seed dump");
+    }
+  };
+
+  const handleSeedNow = async () => {
+    setIsSeeding(true);
+    try {
+      const result = await api.runDatabaseSeed(database.id);
+      if (result.success) {
+        toast.success(result.message);
+      } else {
+        toast.error(result.message);
+      }
+      refresh();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to load seed");
+    } finally {
+      setIsSeeding(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="flex items-center gap-2">
+              <Sprout className="h-5 w-5" />
+              Seed Data
+            </CardTitle>
+            <CardDescription>
+              A script and/or dump loaded once, as soon as the database first runs. Loading the
+              seed again has to be requested explicitly.
+            </CardDescription>
+          </div>
+          {database.seeded_at ? (
+            <Badge variant="secondary" className="shrink-0">
+              Seeded {new Date(database.seeded_at).toLocaleString()}
+            </Badge>
+          ) : hasSeed ? (
+            <Badge variant="outline" className="shrink-0">
+              Not seeded yet
+            </Badge>
+          ) : null}
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {database.seed_error && (
+          <div className="rounded-md border border-destructive/50 bg-destructive/10 p-3 text-sm text-destructive whitespace-pre-wrap">
+            Last seed attempt failed: {database.seed_error}
+          </div>
+        )}
+
+        <div className="space-y-2">
+          <Label htmlFor="seed_script">
+            {database.db_type === "mongodb" ? "Seed Script (mongosh)" : "Seed SQL Script"}
+          </Label>
+          <Textarea
+            id="seed_script"
+            placeholder={
+              database.db_type === "mongodb"
+                ? 'db.users.insertOne({ name: "admin" });'
+                : "CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT);\nINSERT INTO users (name) VALUES ('admin');"
+            }
+            value={script}
+            onChange={(e) => setScript(e.target.value)}
+            className="font-mono text-sm min-h-[160px]"
+            disabled={isSaving}
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label>Seed Dump</Label>
+          <div className="flex flex-wrap items-center gap-2">
+            {database.seed_dump_file ? (
+              <>
+                <code className="bg-muted px-2 py-1 rounded text-sm">
+                  {database.seed_dump_file}
+                </code>
+                <Button type="button" variant="ghost" size="icon" onClick={handleRemoveDump}>
+                  <Trash2 className="h-4 w-4 text-destructive" />
+                </Button>
+              </>
+            ) : (
+              <span className="text-sm text-muted-foreground italic">No dump uploaded</span>
+            )}
+            <input ref={fileInputRef} type="file" className="hidden" onChange={handleUpload} />
+            <Button
+              type="button"
+              variant="outline"
+              size="sm"
+              className="gap-2"
+              onClick={() => fileInputRef.current?.click()}
+              disabled={isUploading}
+            >
+              <FileUp className="h-4 w-4" />
+              {isUploading ? "Uploading..." : database.seed_dump_file ? "Replace" : "Upload"}
+            </Button>
+          </div>
+          <p className="text-xs text-muted-foreground">
+            Loaded after the script, in the same formats as Import. Files ending in .gz are
+            restored with pg_restore (PostgreSQL) or mongorestore (MongoDB).
+          </p>
+        </div>
+
+        <div className="flex items-center justify-between gap-4">
+          <div>
+            <Label htmlFor="seed_on_clone">Seed environment clones</Label>
+            <p className="text-xs text-muted-foreground">
+              Copies of this database made by cloning its environment get the seed too.
+            </p>
+          </div>
+          <Switch id="seed_on_clone" checked={seedOnClone} onCheckedChange={setSeedOnClone} />
+        </div>
+
+        <div className="flex flex-wrap gap-2">
+          <Button onClick={handleSave} disabled={isSaving || !isDirty}>
+            {isSaving ? "Saving..." : "Save Seed"}
+          </Button>
+          <Button
+            variant="outline"
+            className="gap-2"
+            onClick={handleSeedNow}
+            disabled={isSeeding || isDirty || !hasSeed || database.status !== "running"}
+          >
+            <Play className="h-4 w-4" />
+            {isSeeding ? "Loading..." : database.seeded_at ? "Load Again" : "Load Now"}
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,182 @@
+import { useState, useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Hourglass, Plus, Trash2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { api } from "@/lib/api";
+import type { App, ManagedDatabase, UpdateAppRequest, WaitCondition } from "@/types/api";
+
+interface DeployWaitConditionsCardProps {
+  app: App;
+}
+
+const DEFAULT_TIMEOUT_SECONDS = 120;
+
+export function DeployWaitConditionsCard({ app }: DeployWaitConditionsCardProps) {
+  const queryClient = useQueryClient();
+  const [conditions, setConditions] = useState<WaitCondition[]>(app.deploy_wait_conditions ?? []);
+  const [isSaving, setIsSaving] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+
+  const { data: databases = [] } = useQuery<ManagedDatabase[]>({
+    queryKey: ["databases"],
+    queryFn: () => api.getDatabases(),
+  });
+
+  // Sync state when app changes
+  useEffect(() => {
+    setConditions(app.deploy_wait_conditions ?? []);
+    setIsDirty(false);
+  }, [app.deploy_wait_conditions]);
+
+  const update = (next: WaitCondition[]) => {
+    setConditions(next);
+    setIsDirty(true);
+  };
+
+  const handleAdd = (type: WaitCondition["type"]) => {
+    const condition: WaitCondition =
+      type === "database"
+        ? { type, database_id: databases[0]?.id ?? "", timeout_seconds: DEFAULT_TIMEOUT_SECONDS }
+        : { type, url: "", timeout_seconds: DEFAULT_TIMEOUT_SECONDS };
+    update([...conditions, condition]);
+  };
+
+  const handleChange = (index: number, condition: WaitCondition) => {
+    update(conditions.map((c, i) => (i === index ? condition : c)));
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const updates: UpdateAppRequest = {
+        deploy_wait_conditions: conditions.filter((c) =>
+          c.type === "database" ? c.database_id !== "" : c.url.trim() !== "",
+        ),
+      };
+      await api.updateApp(app.id, updates);
+      toast.success("Wait conditions saved");
+      queryClient.invalidateQueries({ queryKey: ["app", app.id] });
+      setIsDirty(false);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save wait conditions");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Hourglass className="h-5 w-5" />
+          Wait Conditions
+        </CardTitle>
+        <CardDescription>
+          Dependencies that must be ready before a deployment starts the new container. The
+          deployment fails if one isn't ready within its timeout.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {conditions.length === 0 ? (
+          <p className="text-sm text-muted-foreground italic py-2">
+            No wait conditions configured
+          </p>
+        ) : (
+          <div className="space-y-2">
+            {conditions.map((condition, index) => (
+              <div key={index} className="flex items-end gap-2">
+                <div className="flex-1 space-y-1">
+                  <Label className="text-xs text-muted-foreground">
+                    {condition.type === "database" ? "Database is healthy" : "URL returns 200"}
+                  </Label>
+                  {condition.type === "database" ? (
+                    <Select
+                      value={condition.database_id}
+                      onValueChange={(v) => handleChange(index, { ...condition, database_id: v })}
+                    >
+                      <SelectTrigger>
+                        <SelectValue placeholder="Select a database" />
+                      </SelectTrigger>
+                      <SelectContent>
+                        {databases.map((db) => (
+                          <SelectItem key={db.id} value={db.id}>
+                            {db.name}
+                          </SelectItem>
+                        ))}
+                      </SelectContent>
+                    </Select>
+                  ) : (
+                    <Input
+                      value={condition.url}
+                      onChange={(e) => handleChange(index, { ...condition, url: e.target.value })}
+                      placeholder="http://rivetr-api:3000/health"
+                      className="font-mono text-sm"
+                    />
+                  )}
+                </div>
+                <div className="w-28 space-y-1">
+                  <Label className="text-xs text-muted-foreground">Timeout (s)</Label>
+                  <Input
+                    type="number"
+                    min={1}
+                    max={3600}
+                    value={condition.timeout_seconds}
+                    onChange={(e) =>
+                      handleChange(index, {
+                        ...condition,
+                        timeout_seconds: Math.max(
+                          1,
+                          Math.min(3600, parseInt(e.target.value) || DEFAULT_TIMEOUT_SECONDS),
+                        ),
+                      })
+                    }
+                  />
+                </div>
+                <Button
+                  type="button"
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => update(conditions.filter((_, i) => i !== index))}
+                >
+                  <Trash2 className="h-4 w-4 text-destructive" />
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="flex flex-wrap gap-2">
+          <Button
+            type="button"
+            variant="outline"
+            size="sm"
+            onClick={() => handleAdd("database")}
+            disabled={databases.length === 0}
+          >
+            <Plus className="h-4 w-4 mr-1" />
+            Database
+          </Button>
+          <Button type="button" variant="outline" size="sm" onClick={() => handleAdd("http")}>
+            <Plus className="h-4 w-4 mr-1" />
+            URL
+          </Button>
+        </div>
+
+        <Button onClick={handleSave} disabled={isSaving || !isDirty} className="w-full sm:w-auto">
+          {isSaving ? "Saving..." : "Save Changes"}
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
@@ -27,6 +27,7 @@ export function DeploymentCommandsCard({
 }: DeploymentCommandsCardProps) {
   const [preDeployCommands, setPreDeployCommands] = useState<string[]>([]);
   const [postDeployCommands, setPostDeployCommands] = useState<string[]>([]);
+  const [deployFences, setDeployFences] = useState("");
   const [isSaving, setIsSaving] = useState(false);
 
   // Parse JSON from app on mount or when app changes
@@ -48,7 +49,14 @@ export function DeploymentCommandsCard({
     } catch {
       setPostDeployCommands([]);
     }
-  }, [app.pre_deploy_commands, app.post_deploy_commands]);
+
+    try {
+      const fences = app.deploy_fences ? JSON.parse(app.deploy_fences) : [];
+      setDeployFences(Array.isArray(fences) ? fences.join(", ") : "");
+    } catch {
+      setDeployFences("");
+    }
+  }, [app.pre_deploy_commands, app.post_deploy_commands, app.deploy_fences]);
 
   const handleAddPreCommand = () => {
     setPreDeployCommands([...preDeployCommands, ""]);
@@ -91,6 +99,10 @@ export function DeploymentCommandsCard({
         pre_deploy_commands: filteredPre.length > 0 ? filteredPre : undefined,
         post_deploy_commands:
           filteredPost.length > 0 ? filteredPost : undefined,
+        deploy_fences: deployFences
+          .split(",")
+          .map((fence) => fence.trim())
+          .filter((fence) => fence !== ""),
       };
 
       await api.updateApp(app.id, updates, token);
@@ -233,6 +245,25 @@ export function DeploymentCommandsCard({
           )}
         </div>
 
+        {/* Deployment Fences */}
+        <div className="space-y-2">
+          <Label htmlFor="deploy-fences" className="text-base">
+            Deployment Fences
+          </Label>
+          <Input
+            id="deploy-fences"
+            value={deployFences}
+            onChange={(e) => setDeployFences(e.target.value)}
+            placeholder="e.g., shared-db-migrations"
+            className="font-mono text-sm"
+          />
+          <p className="text-sm text-muted-foreground">
+            Comma-separated names. Apps sharing a fence never run their
+            pre-deploy commands or release process at the same time; a
+            deployment waits until the other one is done.
+          </p>
+        </div>
+
         {/* Save Button */}
         <Button
           onClick={handleSave}
@@ -0,0 +1,98 @@
+import { useQuery } from "@tanstack/react-query";
+import { Timer } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { api } from "@/lib/api";
+import type { DeploymentPhase, DeploymentTimeline } from "@/types/api";
+
+interface DeploymentPhasesCardProps {
+  deploymentId: string;
+  isActive: boolean;
+}
+
+const PHASE_LABELS: Record<DeploymentPhase["phase"], string> = {
+  clone: "Clone",
+  build: "Build",
+  test: "Test",
+  push: "Push",
+  start: "Start",
+  healthcheck: "Health check",
+};
+
+const STATUS_BAR_COLORS: Record<DeploymentPhase["status"], string> = {
+  running: "bg-blue-500",
+  succeeded: "bg-green-500",
+  failed: "bg-red-500",
+  cancelled: "bg-gray-400",
+};
+
+function formatDuration(ms: number): string {
+  if (ms < 1000) return `${ms}ms`;
+  const seconds = Math.round(ms / 1000);
+  if (seconds < 60) return `${seconds}s`;
+  return `${Math.floor(seconds / 60)}m ${seconds % 60}s`;
+}
+
+/** Elapsed time of a phase; running phases are measured up to now */
+function phaseDuration(phase: DeploymentPhase): number {
+  if (phase.duration_ms !== null) return phase.duration_ms;
+  return Math.max(0, Date.now() - new Date(phase.started_at).getTime());
+}
+
+export function DeploymentPhasesCard({ deploymentId, isActive }: DeploymentPhasesCardProps) {
+  const { data: timeline } = useQuery<DeploymentTimeline>({
+    queryKey: ["deployment-timeline", deploymentId],
+    queryFn: () => api.getDeploymentTimeline(deploymentId),
+    refetchInterval: isActive ? 2000 : false,
+  });
+
+  const phases = timeline?.phases ?? [];
+  if (phases.length === 0) return null;
+
+  const total = phases.reduce((sum, phase) => sum + phaseDuration(phase), 0);
+
+  return (
+    <Card>
+      <CardHeader className="pb-3">
+        <CardTitle className="flex items-center justify-between text-base">
+          <span className="flex items-center gap-2">
+            <Timer className="h-4 w-4" />
+            Phases
+          </span>
+          <span className="text-sm font-normal text-muted-foreground">
+            {formatDuration(total)} total
+          </span>
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {phases.map((phase) => {
+          const duration = phaseDuration(phase);
+          const width = total > 0 ? Math.max(1, (duration / total) * 100) : 0;
+          return (
+            <div key={phase.id} className="space-y-1">
+              <div className="flex items-center justify-between text-sm">
+                <span className="flex items-center gap-2">
+                  {PHASE_LABELS[phase.phase] ?? phase.phase}
+                  {phase.status !== "succeeded" && (
+                    <Badge variant="outline" className="text-xs capitalize">
+                      {phase.status}
+                    </Badge>
+                  )}
+                </span>
+                <span className="font-mono text-xs text-muted-foreground">
+                  {formatDuration(duration)}
+                </span>
+              </div>
+              <div className="h-2 w-full rounded-full bg-muted">
+                <div
+                  className={`h-2 rounded-full ${STATUS_BAR_COLORS[phase.status] ?? "bg-gray-400"}`}
+                  style={{ width: `${width}%` }}
+                />
+              </div>
+            </div>
+          );
+        })}
+      </CardContent>
+    </Card>
+  );
+}
@@ -30,6 +30,7 @@ import {
   ThumbsDown,
   GitCompare,
   RefreshCw,
+  Package,
 } from "lucide-react";
 import type { Deployment, DeploymentStatus } from "@/types/api";
 
@@ -123,6 +124,13 @@ const statusConfig: Record<
     icon: XCircle,
     label: "Cancelled",
   },
+  prewarmed: {
+    color: "text-purple-600",
+    bgColor: "bg-purple-100 dark:bg-purple-900/30",
+    borderColor: "border-purple-400",
+    icon: Package,
+    label: "Pre-warmed",
+  },
 };
 
 // Active statuses that show animation
@@ -321,6 +329,16 @@ function TimelineItem({
                       Restart
                     </Badge>
                   )}
+                  {/* Auto-rollback badge */}
+                  {Boolean(deployment.rolled_back) && (
+                    <Badge
+                      variant="outline"
+                      className="gap-1 text-xs border-amber-400 text-amber-700 dark:text-amber-300"
+                    >
+                      <RotateCcw className="w-3 h-3" />
+                      Rolled back
+                    </Badge>
+                  )}
                   <span className="text-sm text-muted-foreground">
                     {formatDate(deployment.started_at)}
                   </span>
@@ -428,6 +446,12 @@ function TimelineItem({
                       <div>
                         <span className="font-medium">Error: </span>
                         <span className="break-words">{deployment.error_message}</span>
+                        {deployment.error_hint && (
+                          <p className="mt-2 text-red-600/90 dark:text-red-300/90">
+                            <span className="font-medium">How to fix: </span>
+                            {deployment.error_hint}
+                          </p>
+                        )}
                       </div>
                     </div>
                   </div>
@@ -0,0 +1,151 @@
+import { useState } from "react";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Copy, FileCode, RefreshCw } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { api } from "@/lib/api";
+import type { App, DockerfileSuggestion } from "@/types/api";
+
+interface DockerfileGeneratorCardProps {
+  app: App;
+}
+
+export function DockerfileGeneratorCard({ app }: DockerfileGeneratorCardProps) {
+  const queryClient = useQueryClient();
+  const [suggestion, setSuggestion] = useState<DockerfileSuggestion | null>(null);
+  const [isGenerating, setIsGenerating] = useState(false);
+  const [isApplying, setIsApplying] = useState(false);
+
+  const handleGenerate = async () => {
+    setIsGenerating(true);
+    try {
+      setSuggestion(await api.generateDockerfile(app.id));
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to generate Dockerfile");
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
+  // Store the Dockerfile as a deployment patch that replaces the repository's
+  // file, and switch the app to Dockerfile builds
+  const handleUseAsOverride = async () => {
+    if (!suggestion?.override_path) return;
+    setIsApplying(true);
+    try {
+      const patches = await api.listPatches(app.id);
+      const existing = patches.find((p) => p.file_path === suggestion.override_path);
+      const data = {
+        file_path: suggestion.override_path,
+        content: suggestion.dockerfile,
+        operation: "create" as const,
+        is_enabled: true,
+      };
+      if (existing) {
+        await api.updatePatch(app.id, existing.id, data);
+      } else {
+        await api.createPatch(app.id, data);
+      }
+      if (app.build_type !== "dockerfile") {
+        await api.updateApp(app.id, { build_type: "dockerfile" });
+      }
+      toast.success(`Dockerfile saved as a patch to ${suggestion.override_path}`);
+      queryClient.invalidateQueries({ queryKey: ["app", app.id] });
+      queryClient.invalidateQueries({ queryKey: ["app-patches", app.id] });
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save Dockerfile override");
+    } finally {
+      setIsApplying(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="flex items-center gap-2">
+              <FileCode className="h-5 w-5" />
+              Dockerfile Generator
+            </CardTitle>
+            <CardDescription>
+              Inspect the repository and generate a recommended Dockerfile for Node.js, Python, Go
+              and Rust projects. Commit it to your repository, or save it as an override that is
+              written into the checkout on every deployment.
+            </CardDescription>
+          </div>
+          <Button
+            type="button"
+            variant="outline"
+            size="sm"
+            className="gap-2 shrink-0"
+            onClick={handleGenerate}
+            disabled={isGenerating}
+          >
+            <RefreshCw className={`h-4 w-4 ${isGenerating ? "animate-spin" : ""}`} />
+            {isGenerating ? "Generating..." : suggestion ? "Regenerate" : "Generate"}
+          </Button>
+        </div>
+      </CardHeader>
+      {suggestion && !isGenerating && (
+        <CardContent className="space-y-4">
+          <div className="flex flex-wrap items-center gap-2 text-sm">
+            <Badge variant="secondary">{suggestion.stack}</Badge>
+            <span className="text-muted-foreground">
+              from {suggestion.detected_from}, listens on port {suggestion.port}
+            </span>
+          </div>
+
+          <pre className="rounded-md border bg-muted p-4 text-xs font-mono overflow-x-auto whitespace-pre max-h-96">
+            {suggestion.dockerfile}
+          </pre>
+
+          {suggestion.notes.length > 0 && (
+            <ul className="space-y-1.5">
+              {suggestion.notes.map((note, i) => (
+                <li key={i} className="flex items-start gap-2 text-sm text-muted-foreground">
+                  <span className="mt-0.5 shrink-0">•</span>
+                  <span>{note}</span>
+                </li>
+              ))}
+            </ul>
+          )}
+
+          <div className="flex flex-wrap gap-2">
+            <Button
+              type="button"
+              variant="outline"
+              size="sm"
+              className="gap-2"
+              onClick={() => {
+                navigator.clipboard.writeText(suggestion.dockerfile);
+                toast.success("Dockerfile copied to clipboard");
+              }}
+            >
+              <Copy className="h-4 w-4" />
+              Copy
+            </Button>
+            {suggestion.override_path && (
+              <Button
+                type="button"
+                size="sm"
+                onClick={handleUseAsOverride}
+                disabled={isApplying}
+              >
+                {isApplying ? "Saving..." : `Use as ${suggestion.override_path} override`}
+              </Button>
+            )}
+          </div>
+          {app.port !== suggestion.port && (
+            <p className="text-xs text-muted-foreground">
+              The app is configured for port {app.port}; change it if you use this
+              Dockerfile.
+            </p>
+          )}
+        </CardContent>
+      )}
+    </Card>
+  );
+}
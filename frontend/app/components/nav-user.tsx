@@ -1,5 +1,5 @@
 import { Link } from "react-router";
-import { ChevronsUpDown, LogOut, Settings, Moon, Sun, Monitor } from "lucide-react";
+import { ChevronsUpDown, LogOut, Settings, Moon, Sun, Monitor, Contrast } from "lucide-react";
 import { useQuery } from "@tanstack/react-query";
 
 import { Avatar, AvatarFallback } from "@/components/ui/avatar";
@@ -21,6 +21,7 @@ import {
   useSidebar,
 } from "@/components/ui/sidebar";
 import { useTheme } from "@/components/providers/theme-provider";
+import { ThemeMenuItems } from "@/components/theme-toggle";
 import { getCurrentUser } from "@/lib/auth";
 
 function getInitials(name: string): string {
@@ -34,7 +35,7 @@ function getInitials(name: string): string {
 
 export function NavUser() {
   const { isMobile } = useSidebar();
-  const { theme, setTheme } = useTheme();
+  const { theme } = useTheme();
 
   const { data: user } = useQuery({
     queryKey: ["current-user"],
@@ -90,6 +91,8 @@ export function NavUser() {
                   <Moon className="mr-2 h-4 w-4" />
                 ) : theme === "light" ? (
                   <Sun className="mr-2 h-4 w-4" />
+                ) : theme === "high-contrast" ? (
+                  <Contrast className="mr-2 h-4 w-4" />
                 ) : (
                   <Monitor className="mr-2 h-4 w-4" />
                 )}
@@ -97,27 +100,7 @@ export function NavUser() {
               </DropdownMenuSubTrigger>
               <DropdownMenuPortal>
                 <DropdownMenuSubContent>
-                  <DropdownMenuItem
-                    onClick={() => setTheme("light")}
-                    className={theme === "light" ? "bg-accent" : ""}
-                  >
-                    <Sun className="mr-2 h-4 w-4" />
-                    Light
-                  </DropdownMenuItem>
-                  <DropdownMenuItem
-                    onClick={() => setTheme("dark")}
-                    className={theme === "dark" ? "bg-accent" : ""}
-                  >
-                    <Moon className="mr-2 h-4 w-4" />
-                    Dark
-                  </DropdownMenuItem>
-                  <DropdownMenuItem
-                    onClick={() => setTheme("system")}
-                    className={theme === "system" ? "bg-accent" : ""}
-                  >
-                    <Monitor className="mr-2 h-4 w-4" />
-                    System
-                  </DropdownMenuItem>
+                  <ThemeMenuItems />
                 </DropdownMenuSubContent>
               </DropdownMenuPortal>
             </DropdownMenuSub>
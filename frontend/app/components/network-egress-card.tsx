@@ -0,0 +1,134 @@
+import { useState, useEffect } from "react";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Label } from "@/components/ui/label";
+import { Button } from "@/components/ui/button";
+import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
+import { Globe, Info } from "lucide-react";
+import { api } from "@/lib/api";
+import type { App, UpdateAppRequest } from "@/types/api";
+
+interface NetworkEgressCardProps {
+  app: App;
+}
+
+function parseAllowlist(text: string): string[] {
+  return text
+    .split(/[\n,]/)
+    .map((entry) => entry.trim())
+    .filter((entry) => entry.length > 0);
+}
+
+export function NetworkEgressCard({ app }: NetworkEgressCardProps) {
+  const queryClient = useQueryClient();
+  const [restricted, setRestricted] = useState(app.egress_policy === "restricted");
+  const [allowlist, setAllowlist] = useState((app.egress_allowlist ?? []).join("\n"));
+  const [isSaving, setIsSaving] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+
+  // Sync state when app changes
+  useEffect(() => {
+    setRestricted(app.egress_policy === "restricted");
+    setAllowlist((app.egress_allowlist ?? []).join("\n"));
+    setIsDirty(false);
+  }, [app.egress_policy, app.egress_allowlist]);
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const updates: UpdateAppRequest = {
+        egress_policy: restricted ? "restricted" : "allow_all",
+        egress_allowlist: parseAllowlist(allowlist),
+      };
+      await api.updateApp(app.id, updates);
+      toast.success("Egress policy saved");
+      queryClient.invalidateQueries({ queryKey: ["app", app.id] });
+      setIsDirty(false);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save egress policy");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Globe className="h-5 w-5" />
+          Network Egress
+        </CardTitle>
+        <CardDescription>
+          Control which hosts the application's containers may connect to.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="egress-restricted" className="text-base">
+              Restrict Outbound Traffic
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              Block outbound connections except to the hosts listed below
+            </p>
+          </div>
+          <Switch
+            id="egress-restricted"
+            checked={restricted}
+            onCheckedChange={(checked) => {
+              setRestricted(checked);
+              setIsDirty(true);
+            }}
+            disabled={isSaving}
+          />
+        </div>
+
+        {restricted && (
+          <>
+            <div className="space-y-2">
+              <Label htmlFor="egress-allowlist">Allowed Destinations</Label>
+              <Textarea
+                id="egress-allowlist"
+                value={allowlist}
+                onChange={(e) => {
+                  setAllowlist(e.target.value);
+                  setIsDirty(true);
+                }}
+                placeholder={"api.stripe.com\n10.0.0.0/8\n203.0.113.7"}
+                rows={5}
+                className="font-mono text-sm"
+                disabled={isSaving}
+              />
+              <p className="text-xs text-muted-foreground">
+                One hostname, IPv4 address or IPv4 CIDR per line
+              </p>
+            </div>
+
+            <div className="flex items-start gap-3 p-3 rounded-lg bg-blue-50 dark:bg-blue-950/30 border border-blue-200 dark:border-blue-900">
+              <Info className="h-5 w-5 text-blue-600 dark:text-blue-500 shrink-0 mt-0.5" />
+              <div className="text-sm text-blue-800 dark:text-blue-200">
+                <p className="font-medium">Always allowed</p>
+                <p className="mt-1 text-blue-700 dark:text-blue-300">
+                  DNS lookups and connections to other Rivetr containers on the app's
+                  networks (such as linked databases) keep working. Hostnames are
+                  re-resolved every minute. Over IPv6, only the app's networks and
+                  DNS are reachable.
+                </p>
+              </div>
+            </div>
+          </>
+        )}
+
+        <Button
+          onClick={handleSave}
+          disabled={isSaving || !isDirty}
+          className="w-full sm:w-auto"
+        >
+          {isSaving ? "Saving..." : "Save Changes"}
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
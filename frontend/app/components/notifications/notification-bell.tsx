@@ -0,0 +1,265 @@
+import { useEffect } from "react";
+import { Link } from "react-router";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Bell, CheckCheck } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import { Popover, PopoverContent, PopoverTrigger } from "@/components/ui/popover";
+import { api, getStoredToken } from "@/lib/api";
+import { useI18n, type TranslateArgs } from "@/lib/i18n-context";
+import type { InboxNotification, InboxResponse, UserStreamEvent } from "@/lib/api";
+
+const INBOX_QUERY_KEY = ["notification-inbox"];
+const RECONNECT_DELAY_MS = 5000;
+
+const SEVERITY_DOT: Record<string, string> = {
+  critical: "bg-red-500",
+  warning: "bg-yellow-500",
+  info: "bg-blue-500",
+};
+
+function formatRelativeTime(
+  timestamp: string,
+  t: (key: string, args?: TranslateArgs) => string
+): string {
+  // SQLite timestamps are UTC without a zone designator
+  const date = new Date(timestamp.includes("T") ? timestamp : `${timestamp.replace(" ", "T")}Z`);
+  const diffMins = Math.floor((Date.now() - date.getTime()) / 60000);
+  const diffHours = Math.floor(diffMins / 60);
+  const diffDays = Math.floor(diffHours / 24);
+
+  if (diffMins < 1) {
+    return t("time.just_now");
+  } else if (diffMins < 60) {
+    return t("time.minutes_ago", { count: diffMins });
+  } else if (diffHours < 24) {
+    return t("time.hours_ago", { count: diffHours });
+  } else if (diffDays < 7) {
+    return t("time.days_ago", { count: diffDays });
+  } else {
+    return date.toLocaleDateString();
+  }
+}
+
+function notificationLink(notification: InboxNotification): string | null {
+  if (!notification.app_id) return null;
+  return notification.deployment_id
+    ? `/apps/${notification.app_id}/deployments`
+    : `/apps/${notification.app_id}`;
+}
+
+/**
+ * Header bell showing the current user's in-app notifications. The unread
+ * count is kept live over the user's event stream; critical notifications
+ * also pop up as a toast.
+ */
+export function NotificationBell() {
+  const queryClient = useQueryClient();
+  const { t } = useI18n();
+
+  const { data } = useQuery<InboxResponse>({
+    queryKey: INBOX_QUERY_KEY,
+    queryFn: () => api.getInbox({ limit: 30 }),
+  });
+  const notifications = data?.notifications ?? [];
+  const unreadCount = data?.unread_count ?? 0;
+
+  // Follow the event stream. EventSource can't send the auth header, so the
+  // stream is read with fetch.
+  useEffect(() => {
+    const abortController = new AbortController();
+    let reconnectTimeout: ReturnType<typeof setTimeout> | null = null;
+
+    const handleEvent = (event: UserStreamEvent) => {
+      if (event.type === "notification") {
+        queryClient.setQueryData<InboxResponse>(INBOX_QUERY_KEY, (prev) => ({
+          notifications: [event.notification, ...(prev?.notifications ?? [])].slice(0, 30),
+          unread_count: event.unread_count,
+        }));
+        if (event.notification.severity === "critical") {
+          toast.error(event.notification.message);
+        }
+      } else {
+        queryClient.setQueryData<InboxResponse>(INBOX_QUERY_KEY, (prev) =>
+          prev ? { ...prev, unread_count: event.unread_count } : prev
+        );
+      }
+    };
+
+    const connect = async () => {
+      try {
+        const response = await fetch(api.getEventStreamUrl(), {
+          method: "GET",
+          headers: {
+            Authorization: `Bearer ${getStoredToken() ?? ""}`,
+            Accept: "text/event-stream",
+          },
+          credentials: "include",
+          signal: abortController.signal,
+        });
+        const reader = response.ok ? response.body?.getReader() : undefined;
+        if (reader) {
+          const decoder = new TextDecoder();
+          let buffer = "";
+          while (true) {
+            const { done, value } = await reader.read();
+            if (done) break;
+            buffer += decoder.decode(value, { stream: true });
+            const lines = buffer.split("\n");
+            buffer = lines.pop() || "";
+            for (const line of lines) {
+              if (!line.startsWith("data: ")) continue;
+              try {
+                handleEvent(JSON.parse(line.slice(6)));
+              } catch {
+                // Ignore keep-alives and parse errors
+              }
+            }
+          }
+        }
+      } catch (err) {
+        if (err instanceof Error && err.name === "AbortError") return;
+      }
+      if (!abortController.signal.aborted) {
+        reconnectTimeout = setTimeout(connect, RECONNECT_DELAY_MS);
+      }
+    };
+
+    connect();
+
+    return () => {
+      abortController.abort();
+      if (reconnectTimeout) clearTimeout(reconnectTimeout);
+    };
+  }, [queryClient]);
+
+  const markReadMutation = useMutation({
+    mutationFn: (id: string) => api.markInboxNotificationRead(id),
+    onSuccess: (result, id) => {
+      queryClient.setQueryData<InboxResponse>(INBOX_QUERY_KEY, (prev) =>
+        prev
+          ? {
+              notifications: prev.notifications.map((n) =>
+                n.id === id ? result.notifications[0] ?? n : n
+              ),
+              unread_count: result.unread_count,
+            }
+          : prev
+      );
+    },
+  });
+
+  const markAllReadMutation = useMutation({
+    mutationFn: () => api.markAllInboxNotificationsRead(),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: INBOX_QUERY_KEY });
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || t("inbox.mark_all_read_failed"));
+    },
+  });
+
+  return (
+    <Popover>
+      <PopoverTrigger asChild>
+        <Button
+          variant="ghost"
+          size="icon"
+          className="relative h-8 w-8"
+          aria-label={
+            unreadCount > 0
+              ? t("inbox.title_with_unread", { count: unreadCount })
+              : t("inbox.title")
+          }
+        >
+          <Bell className="h-4 w-4" aria-hidden="true" />
+          {unreadCount > 0 && (
+            <span
+              aria-hidden="true"
+              className="absolute -top-0.5 -end-0.5 flex h-4 min-w-4 items-center justify-center rounded-full bg-red-500 px-1 text-[10px] font-medium text-white"
+            >
+              {unreadCount > 99 ? "99+" : unreadCount}
+            </span>
+          )}
+        </Button>
+      </PopoverTrigger>
+      {/* Announce new notifications arriving over the event stream */}
+      <span className="sr-only" role="status" aria-live="polite">
+        {unreadCount > 0 ? t("inbox.title_with_unread", { count: unreadCount }) : ""}
+      </span>
+      <PopoverContent align="end" className="w-96 p-0" aria-labelledby="notification-inbox-title">
+        <div className="flex items-center justify-between border-b px-4 py-2">
+          <h2 id="notification-inbox-title" className="text-sm font-medium">
+            {t("inbox.title")}
+          </h2>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            disabled={unreadCount === 0 || markAllReadMutation.isPending}
+            onClick={() => markAllReadMutation.mutate()}
+          >
+            <CheckCheck className="me-1 h-3.5 w-3.5" aria-hidden="true" />
+            {t("inbox.mark_all_read")}
+          </Button>
+        </div>
+        {notifications.length === 0 ? (
+          <p className="px-4 py-8 text-center text-sm text-muted-foreground">
+            {t("inbox.empty")}
+          </p>
+        ) : (
+          <ScrollArea className="max-h-96">
+            <ul className="divide-y" aria-labelledby="notification-inbox-title">
+              {notifications.map((notification) => {
+                const link = notificationLink(notification);
+                const unread = !notification.read_at;
+                const body = (
+                  <div className="flex gap-3 px-4 py-3">
+                    <span
+                      aria-hidden="true"
+                      className={`mt-1.5 h-2 w-2 shrink-0 rounded-full ${
+                        unread ? SEVERITY_DOT[notification.severity] ?? "bg-blue-500" : "bg-transparent"
+                      }`}
+                    />
+                    <div className="min-w-0 flex-1">
+                      <p className={`text-sm ${unread ? "font-medium" : "text-muted-foreground"}`}>
+                        {unread && <span className="sr-only">{t("inbox.unread")}: </span>}
+                        {notification.message}
+                      </p>
+                      {notification.error_message && (
+                        <p className="mt-0.5 truncate text-xs text-muted-foreground">
+                          {notification.error_message}
+                        </p>
+                      )}
+                      <p className="mt-0.5 text-xs text-muted-foreground">
+                        {notification.app_name && `${notification.app_name} · `}
+                        {formatRelativeTime(notification.created_at, t)}
+                      </p>
+                    </div>
+                  </div>
+                );
+                const onOpen = () => {
+                  if (unread) markReadMutation.mutate(notification.id);
+                };
+                return (
+                  <li key={notification.id} className="hover:bg-muted/50">
+                    {link ? (
+                      <Link to={link} onClick={onOpen} className="block">
+                        {body}
+                      </Link>
+                    ) : (
+                      <button type="button" onClick={onOpen} className="block w-full text-start">
+                        {body}
+                      </button>
+                    )}
+                  </li>
+                );
+              })}
+            </ul>
+          </ScrollArea>
+        )}
+      </PopoverContent>
+    </Popover>
+  );
+}
@@ -0,0 +1,456 @@
+import { useState } from "react";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { TimezoneInput } from "@/components/timezone-input";
+import {
+  notificationDigestsApi,
+  type DigestFrequency,
+  type DigestSummary,
+  type NotificationDigest,
+} from "@/lib/api/notification-digests";
+import { useTeamContext } from "@/lib/team-context";
+import type { NotificationChannel } from "@/types/api";
+import { CalendarClock, Eye, Pencil, Plus, Send, Trash2 } from "lucide-react";
+
+const WEEKDAYS = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+function formatDate(dateStr: string | null): string {
+  if (!dateStr) return "Never";
+  return new Date(dateStr).toLocaleString();
+}
+
+function formatSchedule(digest: NotificationDigest): string {
+  const time = `${String(digest.send_hour).padStart(2, "0")}:00`;
+  const when =
+    digest.frequency === "weekly"
+      ? `${WEEKDAYS[digest.send_weekday]}s at ${time}`
+      : `Daily at ${time}`;
+  return `${when} ${digest.timezone ?? "UTC"}`;
+}
+
+function formatBuildDuration(secs: number): string {
+  if (secs >= 3600) return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+  if (secs >= 60) return `${Math.floor(secs / 60)}m ${secs % 60}s`;
+  return `${secs}s`;
+}
+
+function SummaryPreview({ summary }: { summary: DigestSummary }) {
+  return (
+    <div className="space-y-4 text-sm">
+      <p>
+        <span className="font-medium">{summary.deployments}</span> deployments:{" "}
+        {summary.succeeded} succeeded, {summary.failed} failed
+      </p>
+      {summary.slowest_builds.length > 0 && (
+        <div>
+          <p className="font-medium mb-1">Slowest builds</p>
+          <ul className="space-y-0.5 text-muted-foreground">
+            {summary.slowest_builds.map((build) => (
+              <li key={build.deployment_id}>
+                {build.app_name}: {formatBuildDuration(build.duration_secs)}
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+      <div>
+        <p className="font-medium mb-1">Container crashes</p>
+        {summary.crashed_apps.length === 0 ? (
+          <p className="text-muted-foreground">No container crashes</p>
+        ) : (
+          <ul className="space-y-0.5 text-muted-foreground">
+            {summary.crashed_apps.map((app) => (
+              <li key={app.app_name}>
+                {app.app_name}: {app.crashes}
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+      {summary.expiring_certificates.length > 0 && (
+        <div>
+          <p className="font-medium mb-1">Certificates expiring soon</p>
+          <ul className="space-y-0.5 text-muted-foreground">
+            {summary.expiring_certificates.map((cert) => (
+              <li key={cert.domain}>
+                {cert.domain}: {cert.days_left} day{cert.days_left === 1 ? "" : "s"} left
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+      <p className="text-xs text-muted-foreground">
+        {formatDate(summary.period_start)} – {formatDate(summary.period_end)}
+      </p>
+    </div>
+  );
+}
+
+export function NotificationDigestsCard({ channels }: { channels: NotificationChannel[] }) {
+  const queryClient = useQueryClient();
+  const { currentTeamId } = useTeamContext();
+  const [editing, setEditing] = useState<NotificationDigest | "new" | null>(null);
+  const [preview, setPreview] = useState<{ name: string; summary: DigestSummary } | null>(null);
+
+  const [formName, setFormName] = useState("");
+  const [formFrequency, setFormFrequency] = useState<DigestFrequency>("daily");
+  const [formHour, setFormHour] = useState("8");
+  const [formWeekday, setFormWeekday] = useState("1");
+  const [formTimezone, setFormTimezone] = useState("");
+  const [formChannelIds, setFormChannelIds] = useState<string[]>([]);
+  const [formEmails, setFormEmails] = useState("");
+  const [formTeamOnly, setFormTeamOnly] = useState(false);
+  const [formEnabled, setFormEnabled] = useState(true);
+
+  const { data: digests = [] } = useQuery<NotificationDigest[]>({
+    queryKey: ["notification-digests"],
+    queryFn: () => notificationDigestsApi.getNotificationDigests(),
+  });
+
+  const invalidate = () => queryClient.invalidateQueries({ queryKey: ["notification-digests"] });
+
+  const saveMutation = useMutation({
+    mutationFn: () => {
+      const data = {
+        name: formName,
+        frequency: formFrequency,
+        send_hour: Number(formHour),
+        send_weekday: Number(formWeekday),
+        channel_ids: formChannelIds,
+        email_addresses: formEmails
+          .split(/[\s,]+/)
+          .map((e) => e.trim())
+          .filter(Boolean),
+        enabled: formEnabled,
+      };
+      return editing === "new"
+        ? notificationDigestsApi.createNotificationDigest({
+            ...data,
+            timezone: formTimezone.trim() || undefined,
+            team_id: formTeamOnly ? currentTeamId : null,
+          })
+        : notificationDigestsApi.updateNotificationDigest(editing!.id, {
+            ...data,
+            timezone: formTimezone.trim(),
+          });
+    },
+    onSuccess: () => {
+      toast.success(editing === "new" ? "Digest created" : "Digest updated");
+      invalidate();
+      setEditing(null);
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to save digest");
+    },
+  });
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => notificationDigestsApi.deleteNotificationDigest(id),
+    onSuccess: () => {
+      toast.success("Digest deleted");
+      invalidate();
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to delete digest");
+    },
+  });
+
+  const previewMutation = useMutation({
+    mutationFn: (digest: NotificationDigest) =>
+      notificationDigestsApi.previewNotificationDigest(digest.id),
+    onSuccess: (summary, digest) => setPreview({ name: digest.name, summary }),
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to preview digest");
+    },
+  });
+
+  const sendMutation = useMutation({
+    mutationFn: (id: string) => notificationDigestsApi.sendNotificationDigest(id),
+    onSuccess: () => {
+      toast.success("Digest sent");
+      invalidate();
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to send digest");
+    },
+  });
+
+  function openEditor(digest: NotificationDigest | "new") {
+    const existing = digest === "new" ? null : digest;
+    setFormName(existing?.name ?? "");
+    setFormFrequency(existing?.frequency ?? "daily");
+    setFormHour(String(existing?.send_hour ?? 8));
+    setFormWeekday(String(existing?.send_weekday ?? 1));
+    setFormTimezone(existing?.timezone ?? "");
+    setFormChannelIds(existing?.channel_ids ?? []);
+    setFormEmails(existing?.email_addresses.join(", ") ?? "");
+    setFormTeamOnly(false);
+    setFormEnabled(existing?.enabled ?? true);
+    setEditing(digest);
+  }
+
+  function toggleChannel(id: string, checked: boolean) {
+    setFormChannelIds((ids) => (checked ? [...ids, id] : ids.filter((c) => c !== id)));
+  }
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="flex items-center gap-2">
+              <CalendarClock className="h-5 w-5" />
+              Digests
+            </CardTitle>
+            <CardDescription>
+              A daily or weekly summary of deployments, the slowest builds, container crashes and
+              certificates about to expire, sent to channels and email addresses.
+            </CardDescription>
+          </div>
+          <Button variant="outline" onClick={() => openEditor("new")} className="gap-2">
+            <Plus className="h-4 w-4" />
+            New Digest
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent>
+        {digests.length === 0 ? (
+          <p className="text-sm text-muted-foreground">No digests configured</p>
+        ) : (
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Name</TableHead>
+                <TableHead>Schedule</TableHead>
+                <TableHead>Recipients</TableHead>
+                <TableHead>Next send</TableHead>
+                <TableHead className="w-48">Actions</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {digests.map((digest) => (
+                <TableRow key={digest.id}>
+                  <TableCell className="font-medium">
+                    {digest.name}
+                    {!digest.enabled && (
+                      <Badge variant="secondary" className="ml-2">
+                        Disabled
+                      </Badge>
+                    )}
+                  </TableCell>
+                  <TableCell>{formatSchedule(digest)}</TableCell>
+                  <TableCell className="text-muted-foreground">
+                    {digest.channel_ids.length} channel{digest.channel_ids.length === 1 ? "" : "s"},{" "}
+                    {digest.email_addresses.length} email
+                    {digest.email_addresses.length === 1 ? "" : "s"}
+                  </TableCell>
+                  <TableCell>{digest.enabled ? formatDate(digest.next_send_at) : "-"}</TableCell>
+                  <TableCell>
+                    <div className="flex items-center gap-2">
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        title="Preview"
+                        disabled={previewMutation.isPending}
+                        onClick={() => previewMutation.mutate(digest)}
+                      >
+                        <Eye className="h-4 w-4" />
+                      </Button>
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        title="Send now"
+                        disabled={sendMutation.isPending}
+                        onClick={() => sendMutation.mutate(digest.id)}
+                      >
+                        <Send className="h-4 w-4" />
+                      </Button>
+                      <Button variant="outline" size="sm" onClick={() => openEditor(digest)}>
+                        <Pencil className="h-4 w-4" />
+                      </Button>
+                      <Button
+                        variant="destructive"
+                        size="sm"
+                        disabled={deleteMutation.isPending}
+                        onClick={() => deleteMutation.mutate(digest.id)}
+                      >
+                        <Trash2 className="h-4 w-4" />
+                      </Button>
+                    </div>
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        )}
+      </CardContent>
+
+      <Dialog open={editing !== null} onOpenChange={(open) => !open && setEditing(null)}>
+        <DialogContent className="max-w-lg">
+          <DialogHeader>
+            <DialogTitle>{editing === "new" ? "New Digest" : "Edit Digest"}</DialogTitle>
+            <DialogDescription>
+              Each digest covers the period since it was last sent.
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="digest-name">Name</Label>
+              <Input
+                id="digest-name"
+                value={formName}
+                onChange={(e) => setFormName(e.target.value)}
+                placeholder="Weekly ops summary"
+              />
+            </div>
+            <div className="grid grid-cols-3 gap-4">
+              <div className="space-y-2">
+                <Label>Frequency</Label>
+                <Select
+                  value={formFrequency}
+                  onValueChange={(v) => setFormFrequency(v as DigestFrequency)}
+                >
+                  <SelectTrigger>
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="daily">Daily</SelectItem>
+                    <SelectItem value="weekly">Weekly</SelectItem>
+                  </SelectContent>
+                </Select>
+              </div>
+              {formFrequency === "weekly" && (
+                <div className="space-y-2">
+                  <Label>Day</Label>
+                  <Select value={formWeekday} onValueChange={setFormWeekday}>
+                    <SelectTrigger>
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {WEEKDAYS.map((day, i) => (
+                        <SelectItem key={day} value={String(i)}>
+                          {day}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+              )}
+              <div className="space-y-2">
+                <Label>Hour</Label>
+                <Select value={formHour} onValueChange={setFormHour}>
+                  <SelectTrigger>
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {Array.from({ length: 24 }, (_, h) => (
+                      <SelectItem key={h} value={String(h)}>
+                        {String(h).padStart(2, "0")}:00
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="digest-timezone">Timezone</Label>
+              <TimezoneInput id="digest-timezone" value={formTimezone} onChange={setFormTimezone} />
+            </div>
+            <div className="space-y-2">
+              <Label>Channels</Label>
+              {channels.length === 0 ? (
+                <p className="text-sm text-muted-foreground">No notification channels yet</p>
+              ) : (
+                <div className="space-y-2">
+                  {channels.map((channel) => (
+                    <label key={channel.id} className="flex items-center gap-2 text-sm">
+                      <Checkbox
+                        checked={formChannelIds.includes(channel.id)}
+                        onCheckedChange={(checked) => toggleChannel(channel.id, checked === true)}
+                      />
+                      {channel.name}
+                      <span className="text-muted-foreground">({channel.channel_type})</span>
+                    </label>
+                  ))}
+                </div>
+              )}
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="digest-emails">Email addresses</Label>
+              <Input
+                id="digest-emails"
+                value={formEmails}
+                onChange={(e) => setFormEmails(e.target.value)}
+                placeholder="ops@example.com, lead@example.com"
+              />
+            </div>
+            {editing === "new" && currentTeamId && (
+              <label className="flex items-center gap-2 text-sm">
+                <Checkbox
+                  checked={formTeamOnly}
+                  onCheckedChange={(checked) => setFormTeamOnly(checked === true)}
+                />
+                Only summarize the current team&apos;s apps
+              </label>
+            )}
+            <div className="flex items-center gap-2">
+              <Switch id="digest-enabled" checked={formEnabled} onCheckedChange={setFormEnabled} />
+              <Label htmlFor="digest-enabled">Enabled</Label>
+            </div>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setEditing(null)}>
+              Cancel
+            </Button>
+            <Button
+              onClick={() => saveMutation.mutate()}
+              disabled={saveMutation.isPending || !formName.trim()}
+            >
+              {saveMutation.isPending ? "Saving..." : "Save"}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      <Dialog open={preview !== null} onOpenChange={(open) => !open && setPreview(null)}>
+        <DialogContent className="max-w-lg">
+          <DialogHeader>
+            <DialogTitle>{preview?.name}</DialogTitle>
+            <DialogDescription>What the digest would send right now.</DialogDescription>
+          </DialogHeader>
+          {preview && <SummaryPreview summary={preview.summary} />}
+        </DialogContent>
+      </Dialog>
+    </Card>
+  );
+}
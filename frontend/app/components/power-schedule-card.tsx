@@ -0,0 +1,175 @@
+import { useState, useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { Moon } from "lucide-react";
+import { api } from "@/lib/api";
+import { TimezoneInput } from "@/components/timezone-input";
+
+interface PowerScheduleCardProps {
+  appId: string;
+}
+
+const DEFAULT_STOP_CRON = "0 19 * * Mon-Fri";
+const DEFAULT_START_CRON = "0 8 * * Mon-Fri";
+
+function formatRun(at: string | null): string {
+  return at ? new Date(at).toLocaleString() : "—";
+}
+
+export function PowerScheduleCard({ appId }: PowerScheduleCardProps) {
+  const queryClient = useQueryClient();
+  const { data: schedule, isLoading } = useQuery({
+    queryKey: ["power-schedule", appId],
+    queryFn: () => api.getPowerSchedule(appId),
+  });
+
+  const [enabled, setEnabled] = useState(false);
+  const [stopCron, setStopCron] = useState(DEFAULT_STOP_CRON);
+  const [startCron, setStartCron] = useState(DEFAULT_START_CRON);
+  const [message, setMessage] = useState("");
+  const [timezone, setTimezone] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+
+  // Sync state when the schedule loads
+  useEffect(() => {
+    setEnabled(schedule ? schedule.enabled !== 0 : false);
+    setStopCron(schedule?.stop_cron ?? DEFAULT_STOP_CRON);
+    setStartCron(schedule?.start_cron ?? DEFAULT_START_CRON);
+    setMessage(schedule?.stopped_message ?? "");
+    setTimezone(schedule?.timezone ?? "");
+  }, [schedule]);
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      await api.updatePowerSchedule(appId, {
+        enabled,
+        stop_cron: stopCron.trim(),
+        start_cron: startCron.trim(),
+        stopped_message: message,
+        timezone: timezone.trim(),
+      });
+      toast.success("Power schedule saved");
+      queryClient.invalidateQueries({ queryKey: ["power-schedule", appId] });
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save power schedule");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleRemove = async () => {
+    setIsSaving(true);
+    try {
+      await api.deletePowerSchedule(appId);
+      toast.success("Power schedule removed");
+      queryClient.invalidateQueries({ queryKey: ["power-schedule", appId] });
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to remove power schedule");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Moon className="h-5 w-5" />
+          Power Schedule
+          {schedule?.stopped_at && <Badge variant="secondary">Stopped by schedule</Badge>}
+        </CardTitle>
+        <CardDescription>
+          Stop the app outside working hours and start it again automatically. Visitors see a
+          "stopped" page while it is down. Cron expressions are evaluated in the
+          schedule's timezone (UTC by default).
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="power-schedule-enabled" className="text-base">
+              Enable Schedule
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              A manual start always overrides a scheduled stop
+            </p>
+          </div>
+          <Switch
+            id="power-schedule-enabled"
+            checked={enabled}
+            onCheckedChange={setEnabled}
+            disabled={isSaving || isLoading}
+          />
+        </div>
+
+        <div className="grid gap-4 sm:grid-cols-2">
+          <div className="space-y-2">
+            <Label htmlFor="power-schedule-stop">Stop At (cron)</Label>
+            <Input
+              id="power-schedule-stop"
+              value={stopCron}
+              onChange={(e) => setStopCron(e.target.value)}
+              className="font-mono text-sm"
+              disabled={isSaving}
+            />
+            <p className="text-xs text-muted-foreground">
+              Next stop: {formatRun(schedule?.next_stop_at ?? null)}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="power-schedule-start">Start At (cron)</Label>
+            <Input
+              id="power-schedule-start"
+              value={startCron}
+              onChange={(e) => setStartCron(e.target.value)}
+              className="font-mono text-sm"
+              disabled={isSaving}
+            />
+            <p className="text-xs text-muted-foreground">
+              Next start: {formatRun(schedule?.next_start_at ?? null)}
+            </p>
+          </div>
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="power-schedule-timezone">Timezone</Label>
+          <TimezoneInput
+            id="power-schedule-timezone"
+            value={timezone}
+            onChange={setTimezone}
+            disabled={isSaving}
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="power-schedule-message">Stopped Page Message</Label>
+          <Input
+            id="power-schedule-message"
+            value={message}
+            onChange={(e) => setMessage(e.target.value)}
+            placeholder="This application is stopped outside its scheduled hours"
+            disabled={isSaving}
+          />
+        </div>
+
+        <div className="flex gap-2">
+          <Button onClick={handleSave} disabled={isSaving} className="w-full sm:w-auto">
+            {isSaving ? "Saving..." : "Save Schedule"}
+          </Button>
+          {schedule && (
+            <Button variant="outline" onClick={handleRemove} disabled={isSaving}>
+              Remove
+            </Button>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
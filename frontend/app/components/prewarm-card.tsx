@@ -0,0 +1,214 @@
+import { useState, useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { Flame, Rocket } from "lucide-react";
+import { api } from "@/lib/api";
+import { TimezoneInput } from "@/components/timezone-input";
+
+interface PrewarmCardProps {
+  appId: string;
+}
+
+const BUILDING_STATUSES = ["pending", "cloning", "building"];
+
+function formatRun(at: string | null): string {
+  return at ? new Date(at).toLocaleString() : "—";
+}
+
+export function PrewarmCard({ appId }: PrewarmCardProps) {
+  const queryClient = useQueryClient();
+  const { data: status, isLoading } = useQuery({
+    queryKey: ["prewarm", appId],
+    queryFn: () => api.getPrewarm(appId),
+    refetchInterval: (query) =>
+      BUILDING_STATUSES.includes(query.state.data?.slot?.status ?? "") ? 5000 : false,
+  });
+  const settings = status?.settings ?? null;
+  const slot = status?.slot ?? null;
+
+  const [enabled, setEnabled] = useState(true);
+  const [scheduleCron, setScheduleCron] = useState("");
+  const [onPush, setOnPush] = useState(false);
+  const [timezone, setTimezone] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+
+  // Sync state when the settings load
+  useEffect(() => {
+    setEnabled(settings ? settings.enabled !== 0 : true);
+    setScheduleCron(settings?.schedule_cron ?? "");
+    setOnPush(settings ? settings.on_push !== 0 : false);
+    setTimezone(settings?.timezone ?? "");
+  }, [settings]);
+
+  const refresh = () => {
+    queryClient.invalidateQueries({ queryKey: ["prewarm", appId] });
+    queryClient.invalidateQueries({ queryKey: ["deployments", appId] });
+  };
+
+  const run = async (action: () => Promise<unknown>, success: string, failure: string) => {
+    setIsSaving(true);
+    try {
+      await action();
+      toast.success(success);
+      refresh();
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : failure);
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleSave = () =>
+    run(
+      () =>
+        api.updatePrewarm(appId, {
+          enabled,
+          schedule_cron: scheduleCron.trim(),
+          on_push: onPush,
+          timezone: timezone.trim(),
+        }),
+      "Pre-warm settings saved",
+      "Failed to save pre-warm settings",
+    );
+
+  const handleBuild = () =>
+    run(() => api.buildPrewarm(appId), "Pre-warm build queued", "Failed to queue pre-warm build");
+
+  const handlePromote = () =>
+    run(
+      () => api.promotePrewarm(appId),
+      "Pre-warmed build promoted",
+      "Failed to promote pre-warmed build",
+    );
+
+  const handleRemove = () =>
+    run(
+      () => api.deletePrewarm(appId),
+      "Pre-warming disabled",
+      "Failed to remove pre-warm settings",
+    );
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Flame className="h-5 w-5" />
+          Pre-warmed Deployments
+          {slot?.status === "prewarmed" && <Badge variant="secondary">Ready to promote</Badge>}
+          {slot && BUILDING_STATUSES.includes(slot.status) && (
+            <Badge variant="outline">Building</Badge>
+          )}
+        </CardTitle>
+        <CardDescription>
+          Build the next image ahead of time without switching traffic, then promote it to release
+          in seconds. Useful for apps with slow builds or large images.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="prewarm-on-push" className="text-base">
+              Pre-warm on Push
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              Push webhooks build into the slot instead of deploying
+            </p>
+          </div>
+          <Switch
+            id="prewarm-on-push"
+            checked={onPush}
+            onCheckedChange={setOnPush}
+            disabled={isSaving || isLoading}
+          />
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="prewarm-enabled" className="text-base">
+              Enable Schedule
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              Run scheduled pre-warm builds and push captures
+            </p>
+          </div>
+          <Switch
+            id="prewarm-enabled"
+            checked={enabled}
+            onCheckedChange={setEnabled}
+            disabled={isSaving || isLoading}
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="prewarm-schedule">Off-peak Build Schedule (cron)</Label>
+          <Input
+            id="prewarm-schedule"
+            value={scheduleCron}
+            onChange={(e) => setScheduleCron(e.target.value)}
+            placeholder="0 3 * * *"
+            className="font-mono text-sm"
+            disabled={isSaving}
+          />
+          <p className="text-xs text-muted-foreground">
+            Leave empty to build on demand only. Next build:{" "}
+            {formatRun(settings?.next_run_at ?? null)}
+          </p>
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="prewarm-timezone">Timezone</Label>
+          <TimezoneInput
+            id="prewarm-timezone"
+            value={timezone}
+            onChange={setTimezone}
+            disabled={isSaving}
+          />
+        </div>
+
+        {slot && (
+          <div className="rounded-md border p-3 text-sm space-y-1">
+            <div className="flex items-center justify-between">
+              <span className="font-medium">Current slot</span>
+              <Badge variant="outline">{slot.status}</Badge>
+            </div>
+            {slot.commit_sha && (
+              <p className="font-mono text-xs text-muted-foreground">
+                {slot.commit_sha.slice(0, 7)}
+                {slot.commit_message ? ` — ${slot.commit_message}` : ""}
+              </p>
+            )}
+            {slot.image_tag && (
+              <p className="font-mono text-xs text-muted-foreground">{slot.image_tag}</p>
+            )}
+          </div>
+        )}
+
+        <div className="flex flex-wrap gap-2">
+          <Button onClick={handleSave} disabled={isSaving} className="w-full sm:w-auto">
+            {isSaving ? "Saving..." : "Save Settings"}
+          </Button>
+          <Button variant="outline" onClick={handleBuild} disabled={isSaving}>
+            Build Now
+          </Button>
+          {slot?.status === "prewarmed" && (
+            <Button variant="outline" onClick={handlePromote} disabled={isSaving}>
+              <Rocket className="mr-2 h-4 w-4" />
+              Promote
+            </Button>
+          )}
+          {settings && (
+            <Button variant="outline" onClick={handleRemove} disabled={isSaving}>
+              Remove
+            </Button>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,125 @@
+import { useState } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Layers } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { replicasApi, type ProcessStatus } from "@/lib/api/replicas";
+
+interface ProcessesCardProps {
+  appId: string;
+}
+
+function processBadge(process: ProcessStatus) {
+  if (process.name === "release") {
+    return <Badge variant="outline">Runs on deploy</Badge>;
+  }
+  if (process.running >= process.replica_count && process.replica_count > 0) {
+    return <Badge className="bg-green-500 text-white">Running</Badge>;
+  }
+  if (process.running > 0) {
+    return <Badge className="bg-yellow-500 text-white">Degraded</Badge>;
+  }
+  return <Badge variant="secondary">Stopped</Badge>;
+}
+
+/** Process types from the app's Procfile and how many of their containers run */
+export function ProcessesCard({ appId }: ProcessesCardProps) {
+  const queryClient = useQueryClient();
+  const [counts, setCounts] = useState<Record<string, number>>({});
+  const [scaling, setScaling] = useState<string | null>(null);
+
+  const { data: processes = [] } = useQuery<ProcessStatus[]>({
+    queryKey: ["processes", appId],
+    queryFn: () => replicasApi.listProcesses(appId),
+    refetchInterval: 15000,
+  });
+
+  if (processes.length === 0) {
+    return null;
+  }
+
+  const handleScale = async (name: string) => {
+    const replicas = counts[name];
+    if (replicas === undefined) return;
+    setScaling(name);
+    try {
+      const updated = await replicasApi.scaleProcess(appId, name, replicas);
+      queryClient.setQueryData(["processes", appId], updated);
+      setCounts(({ [name]: _, ...rest }) => rest);
+      toast.success(`Process '${name}' scaled to ${replicas}`);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to scale process");
+    } finally {
+      setScaling(null);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Layers className="h-5 w-5" />
+          Processes
+        </CardTitle>
+        <CardDescription>
+          Process types from the app's Procfile. The web process runs the app's replicas; scale
+          it in the Replicas settings.
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="divide-y">
+          {processes.map((process) => {
+            const scalable = process.name !== "web" && process.name !== "release";
+            const count = counts[process.name] ?? process.replica_count;
+            return (
+              <div key={process.name} className="flex items-center gap-4 py-3">
+                <div className="flex-1 min-w-0">
+                  <div className="flex items-center gap-2">
+                    <span className="font-medium">{process.name}</span>
+                    {processBadge(process)}
+                  </div>
+                  <div className="font-mono text-xs text-muted-foreground truncate">
+                    {process.command}
+                  </div>
+                </div>
+                {process.name !== "release" && (
+                  <div className="text-sm text-muted-foreground whitespace-nowrap">
+                    {process.running}/{process.replica_count} running
+                  </div>
+                )}
+                {scalable && (
+                  <div className="flex items-center gap-2">
+                    <Input
+                      type="number"
+                      min={0}
+                      max={10}
+                      value={count}
+                      onChange={(e) =>
+                        setCounts({
+                          ...counts,
+                          [process.name]: Math.max(0, Math.min(10, parseInt(e.target.value) || 0)),
+                        })
+                      }
+                      className="w-20"
+                    />
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => handleScale(process.name)}
+                      disabled={scaling !== null || count === process.replica_count}
+                    >
+                      {scaling === process.name ? "Scaling..." : "Scale"}
+                    </Button>
+                  </div>
+                )}
+              </div>
+            );
+          })}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -38,6 +38,7 @@ import { Textarea } from "@/components/ui/textarea";
 import { Badge } from "@/components/ui/badge";
 import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import { TemplateResourceSelect } from "@/components/template-resource-select";
 import type { ProjectWithApps, Service, ServiceTemplate } from "@/types/api";
 
 interface ServicesTabProps {
@@ -71,6 +72,7 @@ export function ServicesTab({ project, projectId }: ServicesTabProps) {
   const [selectedTemplate, setSelectedTemplate] = useState<ServiceTemplate | null>(null);
   const [templateServiceName, setTemplateServiceName] = useState("");
   const [templateEnvVars, setTemplateEnvVars] = useState<Record<string, string>>({});
+  const [templateResources, setTemplateResources] = useState<Record<string, string>>({});
   const [showTemplateSecrets, setShowTemplateSecrets] = useState<Record<string, boolean>>({});
   const [portConflict, setPortConflict] = useState<string | null>(null);
   const [isCheckingPort, setIsCheckingPort] = useState(false);
@@ -230,6 +232,7 @@ services:
         name: templateServiceName.trim(),
         project_id: projectId,
         env_vars: templateEnvVars,
+        resources: templateResources,
       });
     },
     onSuccess: () => {
@@ -238,6 +241,7 @@ services:
       setSelectedTemplate(null);
       setTemplateServiceName("");
       setTemplateEnvVars({});
+      setTemplateResources({});
       setShowTemplateSecrets({});
       queryClient.invalidateQueries({ queryKey: ["project", projectId] });
       queryClient.invalidateQueries({ queryKey: ["services"] });
@@ -465,6 +469,7 @@ services:
             setTemplateSearch("");
             setSelectedCategory("all");
             setTemplateEnvVars({});
+            setTemplateResources({});
             setShowTemplateSecrets({});
             setPortConflict(null);
           }
@@ -526,6 +531,7 @@ services:
                               }
                             }
                             setTemplateEnvVars(defaults);
+                            setTemplateResources({});
                             setShowTemplateSecrets({});
                             setPortConflict(null);
                           }}
@@ -631,46 +637,60 @@ services:
                           {entry.label}
                           {entry.required && <span className="text-destructive ml-1">*</span>}
                         </Label>
-                        <div className="relative">
-                          <Input
+                        {entry.source ? (
+                          <TemplateResourceSelect
                             id={`template-env-${entry.name}`}
-                            type={
-                              entry.secret && !showTemplateSecrets[entry.name]
-                                ? "password"
-                                : "text"
-                            }
-                            value={templateEnvVars[entry.name] || ""}
-                            onChange={(e) =>
-                              setTemplateEnvVars((prev) => ({
+                            source={entry.source}
+                            value={templateResources[entry.name] || ""}
+                            onChange={(resourceId) =>
+                              setTemplateResources((prev) => ({
                                 ...prev,
-                                [entry.name]: e.target.value,
+                                [entry.name]: resourceId,
                               }))
                             }
-                            placeholder={entry.default || `Enter ${entry.label.toLowerCase()}`}
-                            required={entry.required}
-                            className={entry.secret ? "pr-10" : ""}
                           />
-                          {entry.secret && (
-                            <Button
-                              type="button"
-                              variant="ghost"
-                              size="icon"
-                              className="absolute right-0 top-0 h-full px-3"
-                              onClick={() =>
-                                setShowTemplateSecrets((prev) => ({
+                        ) : (
+                          <div className="relative">
+                            <Input
+                              id={`template-env-${entry.name}`}
+                              type={
+                                entry.secret && !showTemplateSecrets[entry.name]
+                                  ? "password"
+                                  : "text"
+                              }
+                              value={templateEnvVars[entry.name] || ""}
+                              onChange={(e) =>
+                                setTemplateEnvVars((prev) => ({
                                   ...prev,
-                                  [entry.name]: !prev[entry.name],
+                                  [entry.name]: e.target.value,
                                 }))
                               }
-                            >
-                              {showTemplateSecrets[entry.name] ? (
-                                <EyeOff className="h-4 w-4" />
-                              ) : (
-                                <Eye className="h-4 w-4" />
-                              )}
-                            </Button>
-                          )}
-                        </div>
+                              placeholder={entry.default || `Enter ${entry.label.toLowerCase()}`}
+                              required={entry.required}
+                              className={entry.secret ? "pr-10" : ""}
+                            />
+                            {entry.secret && (
+                              <Button
+                                type="button"
+                                variant="ghost"
+                                size="icon"
+                                className="absolute right-0 top-0 h-full px-3"
+                                onClick={() =>
+                                  setShowTemplateSecrets((prev) => ({
+                                    ...prev,
+                                    [entry.name]: !prev[entry.name],
+                                  }))
+                                }
+                              >
+                                {showTemplateSecrets[entry.name] ? (
+                                  <EyeOff className="h-4 w-4" />
+                                ) : (
+                                  <Eye className="h-4 w-4" />
+                                )}
+                              </Button>
+                            )}
+                          </div>
+                        )}
                       </div>
                     ))}
                   </div>
@@ -684,6 +704,7 @@ services:
                     setSelectedTemplate(null);
                     setTemplateServiceName("");
                     setTemplateEnvVars({});
+                    setTemplateResources({});
                     setShowTemplateSecrets({});
                     setPortConflict(null);
                   }}
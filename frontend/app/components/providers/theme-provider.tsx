@@ -1,50 +1,77 @@
-import { createContext, useContext, useEffect, useState } from "react";
+import { createContext, useCallback, useContext, useEffect, useState } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { getStoredToken } from "@/lib/api/core";
+import { preferencesApi, type UpdateUserPreferencesRequest } from "@/lib/api/preferences";
 
-type Theme = "light" | "dark" | "system";
+export type Theme = "light" | "dark" | "high-contrast" | "system";
 
 interface ThemeContextType {
   theme: Theme;
   setTheme: (theme: Theme) => void;
+  /** Light or dark palette in use; high contrast is a dark palette */
   resolvedTheme: "light" | "dark";
+  reducedMotion: boolean;
+  setReducedMotion: (reduced: boolean) => void;
 }
 
 const ThemeContext = createContext<ThemeContextType | undefined>(undefined);
 
 const STORAGE_KEY = "rivetr_theme";
+const REDUCED_MOTION_KEY = "rivetr_reduced_motion";
+
+function isTheme(value: string | null | undefined): value is Theme {
+  return value === "light" || value === "dark" || value === "high-contrast" || value === "system";
+}
 
 function getSystemTheme(): "light" | "dark" {
   if (typeof window === "undefined") return "light";
   return window.matchMedia("(prefers-color-scheme: dark)").matches ? "dark" : "light";
 }
 
+function resolveTheme(theme: Theme): "light" | "dark" {
+  if (theme === "system") return getSystemTheme();
+  return theme === "light" ? "light" : "dark";
+}
+
+function applyTheme(theme: Theme, resolved: "light" | "dark") {
+  const root = document.documentElement;
+  root.classList.toggle("dark", resolved === "dark");
+  root.classList.toggle("high-contrast", theme === "high-contrast");
+}
+
+/**
+ * Theme and reduced motion for the dashboard. Both are cached in
+ * localStorage for the first paint and saved to the user's preferences, so
+ * they follow the user across devices.
+ */
 export function ThemeProvider({ children }: { children: React.ReactNode }) {
+  const queryClient = useQueryClient();
+
   const [theme, setThemeState] = useState<Theme>(() => {
     if (typeof window === "undefined") return "system";
     const stored = localStorage.getItem(STORAGE_KEY);
-    if (stored === "light" || stored === "dark" || stored === "system") {
-      return stored;
-    }
-    return "system";
+    return isTheme(stored) ? stored : "system";
   });
 
-  const [resolvedTheme, setResolvedTheme] = useState<"light" | "dark">(() => {
-    if (theme === "system") return getSystemTheme();
-    return theme;
+  const [resolvedTheme, setResolvedTheme] = useState<"light" | "dark">(() =>
+    resolveTheme(theme)
+  );
+
+  const [reducedMotion, setReducedMotionState] = useState<boolean>(() => {
+    if (typeof window === "undefined") return false;
+    return localStorage.getItem(REDUCED_MOTION_KEY) === "true";
   });
 
   useEffect(() => {
-    const root = document.documentElement;
-    const resolved = theme === "system" ? getSystemTheme() : theme;
-
+    const resolved = resolveTheme(theme);
     setResolvedTheme(resolved);
-
-    if (resolved === "dark") {
-      root.classList.add("dark");
-    } else {
-      root.classList.remove("dark");
-    }
+    applyTheme(theme, resolved);
   }, [theme]);
 
+  useEffect(() => {
+    document.documentElement.classList.toggle("reduce-motion", reducedMotion);
+  }, [reducedMotion]);
+
   // Listen for system theme changes
   useEffect(() => {
     if (theme !== "system") return;
@@ -54,25 +81,61 @@ export function ThemeProvider({ children }: { children: React.ReactNode }) {
     const handleChange = (e: MediaQueryListEvent) => {
       const newTheme = e.matches ? "dark" : "light";
       setResolvedTheme(newTheme);
-
-      if (newTheme === "dark") {
-        document.documentElement.classList.add("dark");
-      } else {
-        document.documentElement.classList.remove("dark");
-      }
+      applyTheme(theme, newTheme);
     };
 
     mediaQuery.addEventListener("change", handleChange);
     return () => mediaQuery.removeEventListener("change", handleChange);
   }, [theme]);
 
+  // Follow the appearance saved in the user's preferences (e.g. set on another device)
+  const { data: prefs } = useQuery({
+    queryKey: ["user-preferences"],
+    queryFn: preferencesApi.getPreferences,
+    enabled: typeof window !== "undefined" && !!getStoredToken(),
+    staleTime: 1000 * 60 * 5,
+  });
+  useEffect(() => {
+    if (isTheme(prefs?.theme)) {
+      setThemeState(prefs.theme);
+      localStorage.setItem(STORAGE_KEY, prefs.theme);
+    }
+  }, [prefs?.theme]);
+  useEffect(() => {
+    if (prefs === undefined) return;
+    setReducedMotionState(prefs.reduced_motion);
+    localStorage.setItem(REDUCED_MOTION_KEY, String(prefs.reduced_motion));
+  }, [prefs?.reduced_motion]);
+
+  const savePreference = useCallback(
+    (data: UpdateUserPreferencesRequest) => {
+      if (!getStoredToken()) return;
+      preferencesApi
+        .updatePreferences(data)
+        .then((updated) => queryClient.setQueryData(["user-preferences"], updated))
+        .catch(() => {
+          // Kept locally; the next successful save syncs it
+        });
+    },
+    [queryClient]
+  );
+
   const setTheme = (newTheme: Theme) => {
     setThemeState(newTheme);
     localStorage.setItem(STORAGE_KEY, newTheme);
+    savePreference({ theme: newTheme });
+  };
+
+  const setReducedMotion = (reduced: boolean) => {
+    setReducedMotionState(reduced);
+    localStorage.setItem(REDUCED_MOTION_KEY, String(reduced));
+    savePreference({ reduced_motion: reduced });
   };
 
   return (
-    <ThemeContext.Provider value={{ theme, setTheme, resolvedTheme }}>
+    <ThemeContext.Provider
+      value={{ theme, setTheme, resolvedTheme, reducedMotion, setReducedMotion }}
+    >
       {children}
     </ThemeContext.Provider>
   );
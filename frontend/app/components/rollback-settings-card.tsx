@@ -2,6 +2,7 @@ import { useState, useEffect } from "react";
 import { useQueryClient } from "@tanstack/react-query";
 import { toast } from "sonner";
 import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Button } from "@/components/ui/button";
 import { Switch } from "@/components/ui/switch";
@@ -34,6 +35,7 @@ export function RollbackSettingsCard({ app }: RollbackSettingsCardProps) {
   const [autoRollbackEnabled, setAutoRollbackEnabled] = useState(Boolean(app.auto_rollback_enabled));
   const [registryPushEnabled, setRegistryPushEnabled] = useState(Boolean(app.registry_push_enabled));
   const [maxVersions, setMaxVersions] = useState(app.max_rollback_versions || 5);
+  const [healthcheckWindow, setHealthcheckWindow] = useState(app.healthcheck_window_secs || 20);
   const [isSaving, setIsSaving] = useState(false);
   const [isDirty, setIsDirty] = useState(false);
 
@@ -42,8 +44,14 @@ export function RollbackSettingsCard({ app }: RollbackSettingsCardProps) {
     setAutoRollbackEnabled(Boolean(app.auto_rollback_enabled));
     setRegistryPushEnabled(Boolean(app.registry_push_enabled));
     setMaxVersions(app.max_rollback_versions || 5);
+    setHealthcheckWindow(app.healthcheck_window_secs || 20);
     setIsDirty(false);
-  }, [app.auto_rollback_enabled, app.registry_push_enabled, app.max_rollback_versions]);
+  }, [
+    app.auto_rollback_enabled,
+    app.registry_push_enabled,
+    app.max_rollback_versions,
+    app.healthcheck_window_secs,
+  ]);
 
   const handleSave = async () => {
     setIsSaving(true);
@@ -52,6 +60,7 @@ export function RollbackSettingsCard({ app }: RollbackSettingsCardProps) {
         auto_rollback_enabled: autoRollbackEnabled,
         registry_push_enabled: registryPushEnabled,
         max_rollback_versions: maxVersions,
+        healthcheck_window_secs: healthcheckWindow,
       };
       await api.updateApp(app.id, updates);
       toast.success("Rollback settings saved");
@@ -105,14 +114,35 @@ export function RollbackSettingsCard({ app }: RollbackSettingsCardProps) {
             <div className="text-sm text-green-800 dark:text-green-200">
               <p className="font-medium">How it works</p>
               <p className="mt-1 text-green-700 dark:text-green-300">
-                If a deployment fails health checks after 10 attempts, the system will
-                automatically revert to the last successful deployment. This ensures
-                minimal downtime for your application.
+                If a deployment does not pass its health check within{" "}
+                {healthcheckWindow} seconds, the system will automatically revert to the last
+                successful deployment. This ensures minimal downtime for your application.
               </p>
             </div>
           </div>
         )}
 
+        {/* Health Check Window */}
+        <div className="space-y-2">
+          <Label htmlFor="healthcheck-window">Health Check Window (seconds)</Label>
+          <Input
+            id="healthcheck-window"
+            type="number"
+            min={10}
+            max={600}
+            value={healthcheckWindow}
+            onChange={(e) => {
+              setHealthcheckWindow(parseInt(e.target.value) || 0);
+              setIsDirty(true);
+            }}
+            disabled={isSaving}
+            className="w-48"
+          />
+          <p className="text-xs text-muted-foreground">
+            How long a new deployment has to become healthy before it is marked failed (10-600)
+          </p>
+        </div>
+
         {/* Registry Push Toggle */}
         <div className="flex items-center justify-between pt-4 border-t">
           <div className="space-y-0.5">
@@ -0,0 +1,395 @@
+import { useState } from "react";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { CronPreview } from "@/components/cron-preview";
+import { TimezoneInput } from "@/components/timezone-input";
+import { tasksApi } from "@/lib/api/tasks";
+import type { ScheduledTask, ScheduledTaskRun } from "@/types/api";
+import { Plus, Play, Pencil, Trash2, History, Container } from "lucide-react";
+
+const DEFAULT_TIMEOUT_SECONDS = 3600;
+
+function formatDate(dateStr: string | null): string {
+  if (!dateStr) return "Never";
+  return new Date(dateStr).toLocaleString();
+}
+
+function formatDuration(ms: number | null): string {
+  if (ms === null) return "-";
+  if (ms < 1000) return `${ms}ms`;
+  if (ms < 60000) return `${(ms / 1000).toFixed(1)}s`;
+  return `${Math.floor(ms / 60000)}m ${Math.floor((ms % 60000) / 1000)}s`;
+}
+
+function TaskRunStatus({ run }: { run: ScheduledTaskRun }) {
+  if (run.status === "running") return <Badge variant="secondary">Running</Badge>;
+  if (run.status === "success") return <Badge className="bg-green-500 text-white">Exit 0</Badge>;
+  return (
+    <Badge variant="destructive">
+      {run.exit_code !== null ? `Exit ${run.exit_code}` : "Failed"}
+    </Badge>
+  );
+}
+
+function TaskRunsDialog({
+  appId,
+  task,
+  onClose,
+}: {
+  appId: string;
+  task: ScheduledTask | null;
+  onClose: () => void;
+}) {
+  const { data: runs, isLoading } = useQuery<ScheduledTaskRun[]>({
+    queryKey: ["taskRuns", appId, task?.id],
+    queryFn: () => tasksApi.getTaskRuns(appId, task!.id),
+    enabled: !!task,
+    refetchInterval: 5000,
+  });
+
+  return (
+    <Dialog open={!!task} onOpenChange={(open) => !open && onClose()}>
+      <DialogContent className="max-w-3xl">
+        <DialogHeader>
+          <DialogTitle>Runs of &quot;{task?.name}&quot;</DialogTitle>
+          <DialogDescription>The last 50 runs, newest first.</DialogDescription>
+        </DialogHeader>
+        {isLoading ? (
+          <p className="text-sm text-muted-foreground">Loading runs...</p>
+        ) : !runs || runs.length === 0 ? (
+          <p className="text-sm text-muted-foreground">No runs yet</p>
+        ) : (
+          <div className="max-h-[60vh] overflow-auto">
+            <Table>
+              <TableHeader>
+                <TableRow>
+                  <TableHead>Result</TableHead>
+                  <TableHead>Trigger</TableHead>
+                  <TableHead>Started</TableHead>
+                  <TableHead>Duration</TableHead>
+                  <TableHead>Output</TableHead>
+                </TableRow>
+              </TableHeader>
+              <TableBody>
+                {runs.map((run) => (
+                  <TableRow key={run.id}>
+                    <TableCell>
+                      <TaskRunStatus run={run} />
+                    </TableCell>
+                    <TableCell className="text-sm capitalize">{run.trigger}</TableCell>
+                    <TableCell className="text-sm">{formatDate(run.started_at)}</TableCell>
+                    <TableCell className="text-sm">{formatDuration(run.duration_ms)}</TableCell>
+                    <TableCell className="max-w-sm">
+                      {run.error_message && (
+                        <p className="text-sm text-destructive truncate" title={run.error_message}>
+                          {run.error_message}
+                        </p>
+                      )}
+                      {run.output && (
+                        <details className="text-sm">
+                          <summary className="cursor-pointer text-muted-foreground hover:text-foreground">
+                            View output
+                          </summary>
+                          <pre className="mt-1 p-2 bg-muted rounded text-xs max-h-40 overflow-auto whitespace-pre-wrap">
+                            {run.output}
+                          </pre>
+                        </details>
+                      )}
+                    </TableCell>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+/** Scheduled tasks: cron commands run in one-off containers from the app's image */
+export function ScheduledTasksSection({ appId }: { appId: string }) {
+  const queryClient = useQueryClient();
+
+  const [editing, setEditing] = useState<ScheduledTask | "new" | null>(null);
+  const [viewingRuns, setViewingRuns] = useState<ScheduledTask | null>(null);
+
+  const [formName, setFormName] = useState("");
+  const [formCommand, setFormCommand] = useState("");
+  const [formCron, setFormCron] = useState("0 3 * * *");
+  const [formTimezone, setFormTimezone] = useState("");
+  const [formTimeout, setFormTimeout] = useState(String(DEFAULT_TIMEOUT_SECONDS));
+  const [formEnabled, setFormEnabled] = useState(true);
+
+  const { data: tasks } = useQuery<ScheduledTask[]>({
+    queryKey: ["tasks", appId],
+    queryFn: () => tasksApi.getTasks(appId),
+    refetchInterval: 30000,
+  });
+
+  const invalidate = () => queryClient.invalidateQueries({ queryKey: ["tasks", appId] });
+
+  const saveMutation = useMutation({
+    mutationFn: () => {
+      const data = {
+        name: formName,
+        command: formCommand,
+        cron_expression: formCron,
+        timeout_seconds: Number(formTimeout) || DEFAULT_TIMEOUT_SECONDS,
+        enabled: formEnabled,
+      };
+      return editing === "new"
+        ? tasksApi.createTask(appId, { ...data, timezone: formTimezone.trim() || undefined })
+        : tasksApi.updateTask(appId, editing!.id, { ...data, timezone: formTimezone.trim() });
+    },
+    onSuccess: () => {
+      toast.success(editing === "new" ? "Scheduled task created" : "Scheduled task updated");
+      invalidate();
+      setEditing(null);
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to save task");
+    },
+  });
+
+  const deleteMutation = useMutation({
+    mutationFn: (taskId: string) => tasksApi.deleteTask(appId, taskId),
+    onSuccess: () => {
+      toast.success("Scheduled task deleted");
+      invalidate();
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to delete task");
+    },
+  });
+
+  const runMutation = useMutation({
+    mutationFn: (taskId: string) => tasksApi.runTask(appId, taskId),
+    onSuccess: (_, taskId) => {
+      toast.success("Task started");
+      queryClient.invalidateQueries({ queryKey: ["taskRuns", appId, taskId] });
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to run task");
+    },
+  });
+
+  function openEditor(task: ScheduledTask | "new") {
+    const existing = task === "new" ? null : task;
+    setFormName(existing?.name ?? "");
+    setFormCommand(existing?.command ?? "");
+    setFormCron(existing?.cron_expression ?? "0 3 * * *");
+    setFormTimezone(existing?.timezone ?? "");
+    setFormTimeout(String(existing?.timeout_seconds ?? DEFAULT_TIMEOUT_SECONDS));
+    setFormEnabled(existing ? existing.enabled !== 0 : true);
+    setEditing(task);
+  }
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="flex items-center gap-2">
+              <Container className="h-5 w-5" />
+              Scheduled Tasks
+            </CardTitle>
+            <CardDescription>
+              Run commands in a fresh container from the app&apos;s current image, with its
+              environment and volumes. Each run records the exit code and output.
+            </CardDescription>
+          </div>
+          <Button onClick={() => openEditor("new")} className="gap-2">
+            <Plus className="h-4 w-4" />
+            New Task
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent>
+        {!tasks || tasks.length === 0 ? (
+          <p className="text-sm text-muted-foreground">No scheduled tasks</p>
+        ) : (
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Name</TableHead>
+                <TableHead>Schedule</TableHead>
+                <TableHead>Last run</TableHead>
+                <TableHead>Next run</TableHead>
+                <TableHead className="text-right">Actions</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {tasks.map((task) => (
+                <TableRow key={task.id}>
+                  <TableCell>
+                    <div className="font-medium">{task.name}</div>
+                    <code className="text-xs text-muted-foreground">{task.command}</code>
+                  </TableCell>
+                  <TableCell>
+                    <code className="text-xs bg-muted px-1.5 py-0.5 rounded">
+                      {task.cron_expression}
+                    </code>
+                    {task.timezone && <span className="text-xs ml-1">{task.timezone}</span>}
+                    {task.enabled === 0 && (
+                      <Badge variant="secondary" className="ml-2">
+                        Disabled
+                      </Badge>
+                    )}
+                  </TableCell>
+                  <TableCell className="text-sm">{formatDate(task.last_run_at)}</TableCell>
+                  <TableCell className="text-sm">
+                    {task.enabled ? formatDate(task.next_run_at) : "-"}
+                  </TableCell>
+                  <TableCell className="text-right space-x-1">
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={() => runMutation.mutate(task.id)}
+                      disabled={runMutation.isPending}
+                      title="Run now"
+                    >
+                      <Play className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => setViewingRuns(task)}
+                      title="Runs"
+                    >
+                      <History className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => openEditor(task)}
+                      title="Edit"
+                    >
+                      <Pencil className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => {
+                        if (confirm(`Delete task "${task.name}" and its run history?`)) {
+                          deleteMutation.mutate(task.id);
+                        }
+                      }}
+                      title="Delete"
+                    >
+                      <Trash2 className="h-3.5 w-3.5 text-destructive" />
+                    </Button>
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        )}
+      </CardContent>
+
+      <Dialog open={editing !== null} onOpenChange={(open) => !open && setEditing(null)}>
+        <DialogContent className="max-w-lg">
+          <DialogHeader>
+            <DialogTitle>
+              {editing === "new" ? "Create Scheduled Task" : "Edit Scheduled Task"}
+            </DialogTitle>
+            <DialogDescription>
+              The app needs a running deployment; its image is used for each run.
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="task-name">Name</Label>
+              <Input
+                id="task-name"
+                value={formName}
+                onChange={(e) => setFormName(e.target.value)}
+                placeholder="e.g., Nightly report"
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="task-command">Command</Label>
+              <Input
+                id="task-command"
+                value={formCommand}
+                onChange={(e) => setFormCommand(e.target.value)}
+                placeholder="e.g., npm run report"
+                className="font-mono text-sm"
+              />
+              <p className="text-xs text-muted-foreground">
+                Executed via <code>/bin/sh -c</code> in a new container.
+              </p>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="task-cron">Cron Expression</Label>
+              <Input
+                id="task-cron"
+                value={formCron}
+                onChange={(e) => setFormCron(e.target.value)}
+                placeholder="0 3 * * *"
+                className="font-mono text-sm"
+              />
+              <CronPreview expression={formCron} timezone={formTimezone.trim()} />
+            </div>
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="task-timezone">Timezone</Label>
+                <TimezoneInput id="task-timezone" value={formTimezone} onChange={setFormTimezone} />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="task-timeout">Timeout (seconds)</Label>
+                <Input
+                  id="task-timeout"
+                  type="number"
+                  min={1}
+                  max={86400}
+                  value={formTimeout}
+                  onChange={(e) => setFormTimeout(e.target.value)}
+                />
+              </div>
+            </div>
+            <div className="flex items-center space-x-2">
+              <Switch id="task-enabled" checked={formEnabled} onCheckedChange={setFormEnabled} />
+              <Label htmlFor="task-enabled">Enabled</Label>
+            </div>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setEditing(null)}>
+              Cancel
+            </Button>
+            <Button
+              onClick={() => saveMutation.mutate()}
+              disabled={!formName || !formCommand || !formCron || saveMutation.isPending}
+            >
+              {saveMutation.isPending ? "Saving..." : "Save"}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      <TaskRunsDialog appId={appId} task={viewingRuns} onClose={() => setViewingRuns(null)} />
+    </Card>
+  );
+}
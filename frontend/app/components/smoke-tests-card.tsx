@@ -0,0 +1,146 @@
+import { useState, useEffect } from "react";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { FlaskConical, Plus, Trash2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { api } from "@/lib/api";
+import type { App, SmokeTest, UpdateAppRequest } from "@/types/api";
+
+interface SmokeTestsCardProps {
+  app: App;
+}
+
+export function SmokeTestsCard({ app }: SmokeTestsCardProps) {
+  const queryClient = useQueryClient();
+  const [tests, setTests] = useState<SmokeTest[]>(app.smoke_tests ?? []);
+  const [isSaving, setIsSaving] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+
+  // Sync state when app changes
+  useEffect(() => {
+    setTests(app.smoke_tests ?? []);
+    setIsDirty(false);
+  }, [app.smoke_tests]);
+
+  const update = (next: SmokeTest[]) => {
+    setTests(next);
+    setIsDirty(true);
+  };
+
+  const handleChange = (index: number, test: SmokeTest) => {
+    update(tests.map((t, i) => (i === index ? test : t)));
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const updates: UpdateAppRequest = {
+        smoke_tests: tests
+          .filter((t) => t.path.trim() !== "")
+          .map((t) => ({
+            path: t.path.trim(),
+            expected_status: t.expected_status,
+            expected_body: t.expected_body?.trim() ? t.expected_body : null,
+          })),
+      };
+      await api.updateApp(app.id, updates);
+      toast.success("Smoke tests saved");
+      queryClient.invalidateQueries({ queryKey: ["app", app.id] });
+      setIsDirty(false);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save smoke tests");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <FlaskConical className="h-5 w-5" />
+          Smoke Tests
+        </CardTitle>
+        <CardDescription>
+          HTTP checks run against the new container after it passes its health check, before
+          traffic switches to it. A failing check fails the deployment and triggers auto-rollback
+          when enabled. Worker apps skip smoke tests.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {tests.length === 0 ? (
+          <p className="text-sm text-muted-foreground italic py-2">No smoke tests configured</p>
+        ) : (
+          <div className="space-y-2">
+            {tests.map((test, index) => (
+              <div key={index} className="flex items-end gap-2">
+                <div className="flex-1 space-y-1">
+                  <Label className="text-xs text-muted-foreground">GET path</Label>
+                  <Input
+                    value={test.path}
+                    onChange={(e) => handleChange(index, { ...test, path: e.target.value })}
+                    placeholder="/api/status"
+                    className="font-mono text-sm"
+                  />
+                </div>
+                <div className="w-24 space-y-1">
+                  <Label className="text-xs text-muted-foreground">Status</Label>
+                  <Input
+                    type="number"
+                    min={100}
+                    max={599}
+                    value={test.expected_status}
+                    onChange={(e) =>
+                      handleChange(index, {
+                        ...test,
+                        expected_status: parseInt(e.target.value) || 200,
+                      })
+                    }
+                  />
+                </div>
+                <div className="flex-1 space-y-1">
+                  <Label className="text-xs text-muted-foreground">Body contains (optional)</Label>
+                  <Input
+                    value={test.expected_body ?? ""}
+                    onChange={(e) =>
+                      handleChange(index, { ...test, expected_body: e.target.value })
+                    }
+                    placeholder='"status":"ok"'
+                    className="font-mono text-sm"
+                  />
+                </div>
+                <Button
+                  type="button"
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => update(tests.filter((_, i) => i !== index))}
+                >
+                  <Trash2 className="h-4 w-4 text-destructive" />
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={() => update([...tests, { path: "/", expected_status: 200 }])}
+        >
+          <Plus className="h-4 w-4 mr-1" />
+          Smoke Test
+        </Button>
+
+        <div>
+          <Button onClick={handleSave} disabled={isSaving || !isDirty} className="w-full sm:w-auto">
+            {isSaving ? "Saving..." : "Save Changes"}
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
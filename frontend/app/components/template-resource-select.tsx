@@ -0,0 +1,64 @@
+import { useQuery } from "@tanstack/react-query";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { api } from "@/lib/api";
+import type { EnvSource } from "@/types/api";
+
+interface TemplateResourceSelectProps {
+  id?: string;
+  source: EnvSource;
+  /** ID of the picked resource */
+  value: string;
+  onChange: (resourceId: string) => void;
+}
+
+/**
+ * Picker for a template env field filled from an existing resource. The
+ * server resolves the picked resource to the field's value when deploying.
+ */
+export function TemplateResourceSelect({ id, source, value, onChange }: TemplateResourceSelectProps) {
+  const isDatabase = source.kind === "database";
+
+  const { data: databases = [] } = useQuery({
+    queryKey: ["databases"],
+    queryFn: () => api.getDatabases(),
+    enabled: isDatabase,
+  });
+  const { data: volumes = [] } = useQuery({
+    queryKey: ["volumes"],
+    queryFn: () => api.getAllVolumes(),
+    enabled: !isDatabase,
+  });
+
+  const options = isDatabase
+    ? databases
+        .filter((db) => !source.db_types?.length || source.db_types.includes(db.db_type))
+        .map((db) => ({ id: db.id, label: `${db.name} (${db.db_type})` }))
+    : volumes.map((volume) => ({ id: volume.id, label: `${volume.name} (${volume.host_path})` }));
+
+  return (
+    <Select value={value} onValueChange={onChange}>
+      <SelectTrigger id={id}>
+        <SelectValue placeholder={isDatabase ? "Select a database" : "Select a volume"} />
+      </SelectTrigger>
+      <SelectContent>
+        {options.length === 0 ? (
+          <div className="px-2 py-1.5 text-sm text-muted-foreground">
+            {isDatabase ? "No matching databases" : "No volumes"}
+          </div>
+        ) : (
+          options.map((option) => (
+            <SelectItem key={option.id} value={option.id}>
+              {option.label}
+            </SelectItem>
+          ))
+        )}
+      </SelectContent>
+    </Select>
+  );
+}
@@ -0,0 +1,135 @@
+import { useState, useEffect } from "react";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { ClipboardCheck, Plus, Trash2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { api } from "@/lib/api";
+import type { App, UpdateAppRequest } from "@/types/api";
+
+interface TestStageCardProps {
+  app: App;
+}
+
+const parseCommands = (json: string | null): string[] => {
+  if (!json) return [];
+  try {
+    return JSON.parse(json);
+  } catch {
+    return [];
+  }
+};
+
+export function TestStageCard({ app }: TestStageCardProps) {
+  const queryClient = useQueryClient();
+  const [commands, setCommands] = useState<string[]>(parseCommands(app.test_commands));
+  const [timeoutSecs, setTimeoutSecs] = useState(
+    app.test_timeout_secs != null ? String(app.test_timeout_secs) : ""
+  );
+  const [isSaving, setIsSaving] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+
+  // Sync state when app changes
+  useEffect(() => {
+    setCommands(parseCommands(app.test_commands));
+    setTimeoutSecs(app.test_timeout_secs != null ? String(app.test_timeout_secs) : "");
+    setIsDirty(false);
+  }, [app.test_commands, app.test_timeout_secs]);
+
+  const update = (next: string[]) => {
+    setCommands(next);
+    setIsDirty(true);
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const updates: UpdateAppRequest = {
+        test_commands: commands.map((c) => c.trim()).filter(Boolean),
+        test_timeout_secs: timeoutSecs.trim() === "" ? 0 : Number(timeoutSecs),
+      };
+      await api.updateApp(app.id, updates);
+      toast.success("Test stage saved");
+      queryClient.invalidateQueries({ queryKey: ["app", app.id] });
+      setIsDirty(false);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to save test stage");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <ClipboardCheck className="h-5 w-5" />
+          Test Stage
+        </CardTitle>
+        <CardDescription>
+          Commands run one after the other in a throwaway container of the freshly built image,
+          before it is pushed or started. A failing command fails the deployment while the current
+          version keeps serving. A <code>test</code> section in rivetr.yaml replaces these.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {commands.length === 0 ? (
+          <p className="text-sm text-muted-foreground italic py-2">No test commands configured</p>
+        ) : (
+          <div className="space-y-2">
+            {commands.map((command, index) => (
+              <div key={index} className="flex items-center gap-2">
+                <Input
+                  value={command}
+                  onChange={(e) =>
+                    update(commands.map((c, i) => (i === index ? e.target.value : c)))
+                  }
+                  placeholder="npm test"
+                  className="font-mono text-sm"
+                />
+                <Button
+                  type="button"
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => update(commands.filter((_, i) => i !== index))}
+                >
+                  <Trash2 className="h-4 w-4 text-destructive" />
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <Button type="button" variant="outline" size="sm" onClick={() => update([...commands, ""])}>
+          <Plus className="h-4 w-4 mr-1" />
+          Test Command
+        </Button>
+
+        <div className="space-y-2 max-w-xs">
+          <Label htmlFor="test_timeout_secs">Timeout (seconds)</Label>
+          <Input
+            id="test_timeout_secs"
+            type="number"
+            min={1}
+            max={3600}
+            value={timeoutSecs}
+            onChange={(e) => {
+              setTimeoutSecs(e.target.value);
+              setIsDirty(true);
+            }}
+            placeholder="600"
+          />
+          <p className="text-xs text-muted-foreground">For all commands together. Empty uses 600.</p>
+        </div>
+
+        <div>
+          <Button onClick={handleSave} disabled={isSaving || !isDirty} className="w-full sm:w-auto">
+            {isSaving ? "Saving..." : "Save Changes"}
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
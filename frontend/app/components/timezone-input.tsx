@@ -0,0 +1,35 @@
+import { Input } from "@/components/ui/input";
+
+interface TimezoneInputProps {
+  id: string;
+  value: string;
+  onChange: (value: string) => void;
+  disabled?: boolean;
+}
+
+/** IANA timezone names known to the browser, for suggestions */
+const TIMEZONES: string[] =
+  typeof Intl !== "undefined" && "supportedValuesOf" in Intl
+    ? Intl.supportedValuesOf("timeZone")
+    : [];
+
+/** Free-text IANA timezone field (e.g. "Europe/Berlin"); empty means UTC */
+export function TimezoneInput({ id, value, onChange, disabled }: TimezoneInputProps) {
+  return (
+    <>
+      <Input
+        id={id}
+        value={value}
+        onChange={(e) => onChange(e.target.value)}
+        placeholder="UTC"
+        list={`${id}-options`}
+        disabled={disabled}
+      />
+      <datalist id={`${id}-options`}>
+        {TIMEZONES.map((tz) => (
+          <option key={tz} value={tz} />
+        ))}
+      </datalist>
+    </>
+  );
+}
@@ -92,6 +92,13 @@ export function WatchPathsCard({ app, token }: WatchPathsCardProps) {
     setNewPath("");
   };
 
+  // Without watch paths, an app built from a subdirectory deploys when it changes
+  const baseDirectory = (app.base_directory ?? "")
+    .trim()
+    .replace(/^\.\//, "")
+    .replace(/^\/+|\/+$/g, "")
+    .replace(/^\.$/, "");
+
   return (
     <Card>
       <CardHeader>
@@ -102,7 +109,9 @@ export function WatchPathsCard({ app, token }: WatchPathsCardProps) {
         <CardDescription>
           Configure file path patterns to control when webhook pushes trigger a
           deployment. Only pushes that modify files matching these patterns will
-          start a new deployment. Leave empty to deploy on every push.
+          start a new deployment. Leave empty to deploy on every push, or, for
+          an app built from a base directory, on pushes that change that
+          directory.
         </CardDescription>
       </CardHeader>
       <CardContent className="space-y-4">
@@ -129,7 +138,9 @@ export function WatchPathsCard({ app, token }: WatchPathsCardProps) {
 
         {paths.length === 0 && (
           <div className="text-sm text-muted-foreground py-3 text-center border rounded-md">
-            No watch paths configured. All pushes will trigger deployments.
+            {baseDirectory
+              ? `No watch paths configured. Pushes that change ${baseDirectory}/ will trigger deployments.`
+              : "No watch paths configured. All pushes will trigger deployments."}
           </div>
         )}
 
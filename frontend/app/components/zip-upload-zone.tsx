@@ -278,6 +278,29 @@ export function ZipUploadZone({
                   {detectionResult.language}
                 </p>
               )}
+
+              {!!detectionResult.exposed_ports?.length && (
+                <p className="text-sm">
+                  <span className="text-muted-foreground">Exposed ports:</span>{" "}
+                  {detectionResult.exposed_ports.join(", ")}
+                </p>
+              )}
+
+              {!!detectionResult.build_targets?.length && (
+                <p className="text-sm">
+                  <span className="text-muted-foreground">Build stages:</span>{" "}
+                  {detectionResult.build_targets.join(", ")}
+                </p>
+              )}
+
+              {detectionResult.healthcheck?.path && (
+                <p className="text-sm">
+                  <span className="text-muted-foreground">Healthcheck:</span>{" "}
+                  <code className="bg-muted px-1 py-0.5 rounded text-xs">
+                    {detectionResult.healthcheck.path}
+                  </code>
+                </p>
+              )}
             </div>
           </div>
         </Card>
@@ -3,19 +3,22 @@
  * Handles application CRUD, deployment, and management operations.
  */
 
-import { apiRequest } from "./core";
+import { apiRequest, apiRawRequest } from "./core";
 import type {
   App,
+  AppLockResponse,
   AppStatus,
   AppShare,
   AppWithSharing,
   CreateAppRequest,
   CreateAppShareRequest,
+  LockAppRequest,
   UpdateAppRequest,
   Deployment,
   DeploymentListResponse,
   DeploymentQuery,
   DeploymentLog,
+  DeploymentTimeline,
   ContainerStats,
   EnvVar,
   CreateEnvVarRequest,
@@ -26,6 +29,7 @@ import type {
   CreateVolumeRequest,
   UpdateVolumeRequest,
   BuildDetectionResult,
+  DockerfileSuggestion,
   UploadDeployResponse,
   AlertConfigResponse,
   CreateAlertConfigRequest,
@@ -34,9 +38,14 @@ import type {
   UploadAppResponse,
   GitCommit,
   GitTag,
+  AppImage,
   TriggerDeployRequest,
   DeploymentFreezeWindow,
   CreateFreezeWindowRequest,
+  AppPowerSchedule,
+  UpdatePowerScheduleRequest,
+  PrewarmStatus,
+  UpdatePrewarmRequest,
   RejectDeploymentRequest,
   AuditLogListResponse,
   AppRedirectRule,
@@ -136,6 +145,21 @@ export const appsApi = {
   restartApp: (id: string, token?: string) =>
     apiRequest<AppStatus>(`/apps/${id}/restart`, { method: "POST" }, token),
 
+  /** Lock an app against deployments */
+  lockApp: (id: string, data: LockAppRequest, token?: string) =>
+    apiRequest<AppLockResponse>(`/apps/${id}/lock`, {
+      method: "POST",
+      body: JSON.stringify(data),
+    }, token),
+
+  /** Generate a recommended Dockerfile from the app's repository */
+  generateDockerfile: (id: string, token?: string) =>
+    apiRequest<DockerfileSuggestion>(`/apps/${id}/dockerfile/generate`, { method: "POST" }, token),
+
+  /** Release an app's deployment lock */
+  unlockApp: (id: string, token?: string) =>
+    apiRequest<AppLockResponse>(`/apps/${id}/unlock`, { method: "POST" }, token),
+
   /** Apply CPU/memory limits to the running container immediately (no redeploy) */
   applyResourceLimits: (id: string, token?: string) =>
     apiRequest<{ message: string; memory_limit: string | null; cpu_limit: string | null }>(`/apps/${id}/apply-limits`, { method: "POST" }, token),
@@ -168,6 +192,16 @@ export const appsApi = {
   getDeploymentLogs: (id: string, token?: string) =>
     apiRequest<DeploymentLog[]>(`/deployments/${id}/logs`, {}, token),
 
+  /** Get the status transition timeline for a deployment */
+  getDeploymentTimeline: (id: string, token?: string) =>
+    apiRequest<DeploymentTimeline>(`/deployments/${id}/timeline`, {}, token),
+
+  /** Download the diagnostics bundle collected when a deployment failed */
+  getDeploymentDiagnostics: async (id: string, token?: string): Promise<Blob> => {
+    const response = await apiRawRequest(`/deployments/${id}/diagnostics`, {}, token);
+    return response.blob();
+  },
+
   /** Trigger a new deployment, optionally targeting a specific commit or tag */
   triggerDeploy: (appId: string, options?: TriggerDeployRequest, token?: string) =>
     apiRequest<Deployment>(
@@ -187,6 +221,10 @@ export const appsApi = {
   getTags: (appId: string, limit = 20, token?: string) =>
     apiRequest<GitTag[]>(`/apps/${appId}/tags?limit=${limit}`, {}, token),
 
+  /** Get the versioned images of an app available on the host, newest first */
+  getAppImages: (appId: string, token?: string) =>
+    apiRequest<AppImage[]>(`/apps/${appId}/images`, {}, token),
+
   /** Rollback to a previous deployment */
   rollbackDeployment: (id: string, token?: string) =>
     apiRequest<Deployment>(
@@ -278,6 +316,44 @@ export const appsApi = {
     return response.json();
   },
 
+  /**
+   * Generate a recommended Dockerfile for an uploaded ZIP file.
+   */
+  generateDockerfileFromUpload: async (
+    file: File,
+    token?: string,
+  ): Promise<DockerfileSuggestion> => {
+    const authToken = token || getStoredToken();
+    const formData = new FormData();
+    formData.append("file", file);
+
+    const headers: Record<string, string> = {};
+    if (authToken) {
+      headers["Authorization"] = `Bearer ${authToken}`;
+    }
+
+    const response = await fetch("/api/build/dockerfile", {
+      method: "POST",
+      headers,
+      body: formData,
+      credentials: "include",
+    });
+
+    if (!response.ok) {
+      const errorText = await response.text();
+      let errorMessage: string;
+      try {
+        const errorJson = JSON.parse(errorText);
+        errorMessage = errorJson.error || errorJson.message || errorText;
+      } catch {
+        errorMessage = errorText || `HTTP ${response.status}`;
+      }
+      throw new Error(errorMessage);
+    }
+
+    return response.json();
+  },
+
   /**
    * Create an app and deploy from uploaded ZIP file in one step.
    * This is the preferred way to deploy from a ZIP file.
@@ -295,6 +371,8 @@ export const appsApi = {
       environment?: string;
       build_type?: string;
       publish_directory?: string;
+      build_target?: string;
+      app_type?: string;
     },
     token?: string,
   ): Promise<UploadAppResponse> => {
@@ -432,6 +510,9 @@ export const appsApi = {
   // Volumes
   // -------------------------------------------------------------------------
 
+  /** Get the volumes of all apps */
+  getAllVolumes: (token?: string) => apiRequest<Volume[]>(`/volumes`, {}, token),
+
   /** Get all volumes for an app */
   getVolumes: (appId: string, token?: string) =>
     apiRequest<Volume[]>(`/apps/${appId}/volumes`, {}, token),
@@ -654,6 +735,68 @@ export const appsApi = {
       token,
     ),
 
+  // -------------------------------------------------------------------------
+  // Power Schedule
+  // -------------------------------------------------------------------------
+
+  /** Get the power schedule for an app (null if none) */
+  getPowerSchedule: (appId: string, token?: string) =>
+    apiRequest<AppPowerSchedule | null>(`/apps/${appId}/power-schedule`, {}, token),
+
+  /** Create or update the power schedule for an app */
+  updatePowerSchedule: (
+    appId: string,
+    data: UpdatePowerScheduleRequest,
+    token?: string,
+  ) =>
+    apiRequest<AppPowerSchedule>(
+      `/apps/${appId}/power-schedule`,
+      {
+        method: "PUT",
+        body: JSON.stringify(data),
+      },
+      token,
+    ),
+
+  /** Remove the power schedule for an app */
+  deletePowerSchedule: (appId: string, token?: string) =>
+    apiRequest<void>(
+      `/apps/${appId}/power-schedule`,
+      { method: "DELETE" },
+      token,
+    ),
+
+  // -------------------------------------------------------------------------
+  // Pre-warmed Deployment Slot
+  // -------------------------------------------------------------------------
+
+  /** Get the pre-warm settings and current slot for an app */
+  getPrewarm: (appId: string, token?: string) =>
+    apiRequest<PrewarmStatus>(`/apps/${appId}/prewarm`, {}, token),
+
+  /** Create or update the pre-warm settings for an app */
+  updatePrewarm: (appId: string, data: UpdatePrewarmRequest, token?: string) =>
+    apiRequest<PrewarmStatus>(
+      `/apps/${appId}/prewarm`,
+      {
+        method: "PUT",
+        body: JSON.stringify(data),
+      },
+      token,
+    ),
+
+  /** Remove the pre-warm settings for an app, discarding its slot */
+  deletePrewarm: (appId: string, token?: string) =>
+    apiRequest<void>(`/apps/${appId}/prewarm`, { method: "DELETE" }, token),
+
+  /** Build the branch head into the slot now */
+  buildPrewarm: (appId: string, token?: string) =>
+    apiRequest<Deployment>(`/apps/${appId}/prewarm/build`, { method: "POST" }, token),
+
+  /** Release the pre-warmed image */
+  promotePrewarm: (appId: string, token?: string) =>
+    apiRequest<Deployment>(`/apps/${appId}/prewarm/promote`, { method: "POST" }, token),
+
   // -------------------------------------------------------------------------
   // Activity (audit log events for this app)
   // -------------------------------------------------------------------------
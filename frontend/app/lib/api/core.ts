@@ -11,6 +11,23 @@ export function getStoredToken(): string | null {
   return localStorage.getItem("rivetr_auth_token");
 }
 
+/**
+ * Get the dashboard locale cached in localStorage (sent as Accept-Language so
+ * API messages come back in the same language).
+ */
+export function getStoredLocale(): string | null {
+  if (typeof window === "undefined") return null;
+  return localStorage.getItem("rivetr_locale");
+}
+
+/**
+ * Cache the dashboard locale in localStorage.
+ */
+export function setStoredLocale(locale: string): void {
+  if (typeof window === "undefined") return;
+  localStorage.setItem("rivetr_locale", locale);
+}
+
 /**
  * Options for API requests including team context
  */
@@ -43,6 +60,12 @@ export async function apiRequest<T>(
     headers["Authorization"] = `Bearer ${authToken}`;
   }
 
+  // Ask for API messages in the dashboard's language
+  const locale = getStoredLocale();
+  if (locale) {
+    headers["Accept-Language"] = locale;
+  }
+
   // Add team context header if provided
   if (teamId) {
     headers["X-Team-Id"] = teamId;
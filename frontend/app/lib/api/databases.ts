@@ -296,4 +296,53 @@ export const databasesApi = {
     }
     return response.json();
   },
+  // -------------------------------------------------------------------------
+  // Database Seeding
+  // -------------------------------------------------------------------------
+
+  /** Upload a dump that seeds the database, replacing any previous one */
+  uploadSeedDump: async (
+    databaseId: string,
+    file: File,
+    token?: string
+  ): Promise<ManagedDatabase> => {
+    const authToken = token || getStoredToken();
+    const headers: Record<string, string> = {};
+    if (authToken) {
+      headers["Authorization"] = `Bearer ${authToken}`;
+    }
+    const formData = new FormData();
+    formData.append("file", file);
+    const response = await fetch(`/api/databases/${databaseId}/seed-dump`, {
+      method: "PUT",
+      headers,
+      credentials: "include",
+      body: formData,
+    });
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({ error: `Upload failed: ${response.status}` }));
+      throw new Error(error.error || `Upload failed: ${response.status}`);
+    }
+    return response.json();
+  },
+
+  /** Remove the database's seed dump */
+  deleteSeedDump: (databaseId: string, token?: string) =>
+    apiRequest<ManagedDatabase>(
+      `/databases/${databaseId}/seed-dump`,
+      {
+        method: "DELETE",
+      },
+      token
+    ),
+
+  /** Load the database's seed now, also when it was loaded before */
+  runDatabaseSeed: (databaseId: string, token?: string) =>
+    apiRequest<{ success: boolean; message: string }>(
+      `/databases/${databaseId}/seed`,
+      {
+        method: "POST",
+      },
+      token
+    ),
 };
@@ -0,0 +1,74 @@
+/**
+ * API Explorer module.
+ * Loads the server's OpenAPI document and sends raw requests for the API explorer.
+ */
+
+import { apiRequest, getStoredLocale, getStoredToken } from "./core";
+
+export type HttpMethod = "get" | "post" | "put" | "patch" | "delete";
+
+export interface OpenApiParameter {
+  name: string;
+  in: "path" | "query" | "header";
+  required?: boolean;
+}
+
+export interface OpenApiOperation {
+  operationId: string;
+  summary: string;
+  tags: string[];
+  parameters?: OpenApiParameter[];
+  requestBody?: unknown;
+  /** Empty for endpoints that need no token */
+  security?: unknown[];
+}
+
+export interface OpenApiDocument {
+  openapi: string;
+  info: { title: string; version: string };
+  servers?: { url: string }[];
+  tags?: { name: string }[];
+  paths: Record<string, Partial<Record<HttpMethod, OpenApiOperation>>>;
+}
+
+export interface ExplorerResponse {
+  status: number;
+  statusText: string;
+  durationMs: number;
+  contentType: string | null;
+  body: string;
+}
+
+export const explorerApi = {
+  /** Get the OpenAPI document of this server */
+  getOpenApiSpec: (): Promise<OpenApiDocument> =>
+    apiRequest<OpenApiDocument>("/openapi.json"),
+
+  /**
+   * Send a request with the current session token and return the raw
+   * response, whatever its status.
+   */
+  sendRequest: async (
+    method: string,
+    path: string,
+    body?: string
+  ): Promise<ExplorerResponse> => {
+    const headers: Record<string, string> = {};
+    const token = getStoredToken();
+    if (token) headers["Authorization"] = `Bearer ${token}`;
+    const locale = getStoredLocale();
+    if (locale) headers["Accept-Language"] = locale;
+    if (body !== undefined) headers["Content-Type"] = "application/json";
+
+    const started = performance.now();
+    const response = await fetch(path, { method, headers, body });
+    const text = await response.text();
+    return {
+      status: response.status,
+      statusText: response.statusText,
+      durationMs: Math.round(performance.now() - started),
+      contentType: response.headers.get("content-type"),
+      body: text,
+    };
+  },
+};
@@ -10,6 +10,7 @@ import type {
   OAuthAuthorizationResponse,
   SshKey,
   CreateSshKeyRequest,
+  SshKeyTestResult,
   GitHubApp,
   GitHubAppInstallation,
   GitHubAppRepository,
@@ -37,6 +38,17 @@ export const gitApi = {
       token
     ),
 
+  /** Check that a repository is reachable with an SSH key (and its jump host) */
+  testSshKey: (id: string, gitUrl?: string, token?: string) =>
+    apiRequest<SshKeyTestResult>(
+      `/ssh-keys/${id}/test`,
+      {
+        method: "POST",
+        body: JSON.stringify({ git_url: gitUrl }),
+      },
+      token
+    ),
+
   /** Delete an SSH key */
   deleteSshKey: (id: string, token?: string) =>
     apiRequest<void>(
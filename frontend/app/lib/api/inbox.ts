@@ -0,0 +1,61 @@
+/**
+ * Notification Inbox API module.
+ * Handles the current user's in-app notifications and their event stream.
+ */
+
+import { apiRequest } from "./core";
+
+export type NotificationSeverity = "info" | "warning" | "critical";
+
+export interface InboxNotification {
+  id: string;
+  event_type: string;
+  severity: NotificationSeverity;
+  app_id: string | null;
+  app_name: string | null;
+  deployment_id: string | null;
+  message: string;
+  error_message: string | null;
+  /** null while unread */
+  read_at: string | null;
+  created_at: string;
+}
+
+export interface InboxResponse {
+  notifications: InboxNotification[];
+  unread_count: number;
+}
+
+/** Event sent over /api/events/stream */
+export type UserStreamEvent =
+  | { type: "connected"; unread_count: number }
+  | { type: "notification"; notification: InboxNotification; unread_count: number }
+  | { type: "unread_count"; unread_count: number };
+
+export const inboxApi = {
+  /** List the current user's notifications, newest first */
+  getInbox: (options: { unread?: boolean; limit?: number } = {}): Promise<InboxResponse> => {
+    const params = new URLSearchParams();
+    if (options.unread) params.set("unread", "true");
+    if (options.limit) params.set("limit", String(options.limit));
+    const query = params.toString();
+    return apiRequest<InboxResponse>(`/notifications/inbox${query ? `?${query}` : ""}`);
+  },
+
+  /** Mark a notification read */
+  markInboxNotificationRead: (id: string): Promise<InboxResponse> =>
+    apiRequest<InboxResponse>(`/notifications/inbox/${id}/read`, {
+      method: "POST",
+    }),
+
+  /** Mark all notifications read */
+  markAllInboxNotificationsRead: (): Promise<{ marked: number; unread_count: number }> =>
+    apiRequest<{ marked: number; unread_count: number }>("/notifications/inbox/read-all", {
+      method: "POST",
+    }),
+
+  /** Get the URL of the current user's event stream (SSE) */
+  getEventStreamUrl: (): string => {
+    return `${window.location.origin}/api/events/stream`;
+  },
+};
@@ -22,13 +22,25 @@ export type {
 export { servicesApi } from "./services";
 export { teamsApi } from "./teams";
 export { notificationsApi } from "./notifications";
+export { notificationDigestsApi } from "./notification-digests";
+export { inboxApi } from "./inbox";
+export type { InboxNotification, InboxResponse, UserStreamEvent } from "./inbox";
 export { gitApi } from "./git";
 export { systemApi } from "./system";
 export { previewsApi } from "./previews";
 export { oauthApi } from "./oauth";
 export { environmentsApi } from "./environments";
 export { twoFactorApi } from "./two-factor";
+export { preferencesApi } from "./preferences";
+export { explorerApi } from "./explorer";
+export type {
+  ExplorerResponse,
+  HttpMethod,
+  OpenApiDocument,
+  OpenApiOperation,
+} from "./explorer";
 export { jobsApi } from "./jobs";
+export { tasksApi } from "./tasks";
 export { logDrainsApi } from "./log-drains";
 export { s3Api } from "./s3";
 export { monitoringApi } from "./monitoring";
@@ -71,13 +83,18 @@ import { databaseLinksApi } from "./database-links";
 import { servicesApi } from "./services";
 import { teamsApi } from "./teams";
 import { notificationsApi } from "./notifications";
+import { notificationDigestsApi } from "./notification-digests";
+import { inboxApi } from "./inbox";
 import { gitApi } from "./git";
 import { systemApi } from "./system";
 import { previewsApi } from "./previews";
 import { oauthApi } from "./oauth";
 import { environmentsApi } from "./environments";
 import { twoFactorApi } from "./two-factor";
+import { preferencesApi } from "./preferences";
+import { explorerApi } from "./explorer";
 import { jobsApi } from "./jobs";
+import { tasksApi } from "./tasks";
 import { logDrainsApi } from "./log-drains";
 import { s3Api } from "./s3";
 import { monitoringApi } from "./monitoring";
@@ -135,13 +152,19 @@ export const api = {
   startApp: appsApi.startApp,
   stopApp: appsApi.stopApp,
   restartApp: appsApi.restartApp,
+  lockApp: appsApi.lockApp,
+  unlockApp: appsApi.unlockApp,
+  generateDockerfile: appsApi.generateDockerfile,
   applyResourceLimits: appsApi.applyResourceLimits,
   getDeployments: appsApi.getDeployments,
   getDeployment: appsApi.getDeployment,
   getDeploymentLogs: appsApi.getDeploymentLogs,
+  getDeploymentTimeline: appsApi.getDeploymentTimeline,
+  getDeploymentDiagnostics: appsApi.getDeploymentDiagnostics,
   triggerDeploy: appsApi.triggerDeploy,
   getCommits: appsApi.getCommits,
   getTags: appsApi.getTags,
+  getAppImages: appsApi.getAppImages,
   rollbackDeployment: appsApi.rollbackDeployment,
   getAppStats: appsApi.getAppStats,
   getEnvVars: appsApi.getEnvVars,
@@ -152,6 +175,7 @@ export const api = {
   getBasicAuth: appsApi.getBasicAuth,
   updateBasicAuth: appsApi.updateBasicAuth,
   deleteBasicAuth: appsApi.deleteBasicAuth,
+  getAllVolumes: appsApi.getAllVolumes,
   getVolumes: appsApi.getVolumes,
   getVolume: appsApi.getVolume,
   createVolume: appsApi.createVolume,
@@ -164,6 +188,7 @@ export const api = {
   // Upload deployments
   uploadDeploy: appsApi.uploadDeploy,
   detectBuildType: appsApi.detectBuildType,
+  generateDockerfileFromUpload: appsApi.generateDockerfileFromUpload,
   uploadCreateApp: appsApi.uploadCreateApp,
 
   // Alert Configurations
@@ -193,6 +218,18 @@ export const api = {
   // App Activity
   getAppActivity: appsApi.getAppActivity,
 
+  // Power Schedule
+  getPowerSchedule: appsApi.getPowerSchedule,
+  updatePowerSchedule: appsApi.updatePowerSchedule,
+  deletePowerSchedule: appsApi.deletePowerSchedule,
+
+  // Pre-warmed Deployment Slot
+  getPrewarm: appsApi.getPrewarm,
+  updatePrewarm: appsApi.updatePrewarm,
+  deletePrewarm: appsApi.deletePrewarm,
+  buildPrewarm: appsApi.buildPrewarm,
+  promotePrewarm: appsApi.promotePrewarm,
+
   // Deployment Cancellation
   cancelDeployment: appsApi.cancelDeployment,
 
@@ -218,6 +255,7 @@ export const api = {
   getSshKeys: gitApi.getSshKeys,
   createSshKey: gitApi.createSshKey,
   deleteSshKey: gitApi.deleteSshKey,
+  testSshKey: gitApi.testSshKey,
 
   // Git Providers
   getGitProviders: gitApi.getGitProviders,
@@ -257,6 +295,9 @@ export const api = {
   deleteDatabaseBackupSchedule: databasesApi.deleteDatabaseBackupSchedule,
   downloadDatabaseBackup: databasesApi.downloadDatabaseBackup,
   importDatabaseDump: databasesApi.importDatabaseDump,
+  uploadSeedDump: databasesApi.uploadSeedDump,
+  deleteSeedDump: databasesApi.deleteSeedDump,
+  runDatabaseSeed: databasesApi.runDatabaseSeed,
   listDatabaseExtensions: databasesApi.listDatabaseExtensions,
   installDatabaseExtension: databasesApi.installDatabaseExtension,
 
@@ -339,6 +380,20 @@ export const api = {
   deleteTeamNotificationChannel: notificationsApi.deleteTeamNotificationChannel,
   testTeamNotificationChannel: notificationsApi.testTeamNotificationChannel,
 
+  // Notification Digests
+  getNotificationDigests: notificationDigestsApi.getNotificationDigests,
+  createNotificationDigest: notificationDigestsApi.createNotificationDigest,
+  updateNotificationDigest: notificationDigestsApi.updateNotificationDigest,
+  deleteNotificationDigest: notificationDigestsApi.deleteNotificationDigest,
+  previewNotificationDigest: notificationDigestsApi.previewNotificationDigest,
+  sendNotificationDigest: notificationDigestsApi.sendNotificationDigest,
+
+  // Notification Inbox
+  getInbox: inboxApi.getInbox,
+  markInboxNotificationRead: inboxApi.markInboxNotificationRead,
+  markAllInboxNotificationsRead: inboxApi.markAllInboxNotificationsRead,
+  getEventStreamUrl: inboxApi.getEventStreamUrl,
+
   // System
   getSystemStats: systemApi.getSystemStats,
   getDiskStats: systemApi.getDiskStats,
@@ -386,6 +441,7 @@ export const api = {
   deleteBackupSchedule: systemApi.deleteBackupSchedule,
   toggleBackupSchedule: systemApi.toggleBackupSchedule,
   runBackupSchedule: systemApi.runBackupSchedule,
+  previewCron: systemApi.previewCron,
 
   // Docker Cleanup
   runDockerCleanup: systemApi.runDockerCleanup,
@@ -412,6 +468,14 @@ export const api = {
   get2FAStatus: twoFactorApi.getStatus,
   validate2FA: twoFactorApi.validate,
 
+  // User Preferences
+  getPreferences: preferencesApi.getPreferences,
+  updatePreferences: preferencesApi.updatePreferences,
+
+  // API Explorer
+  getOpenApiSpec: explorerApi.getOpenApiSpec,
+  sendExplorerRequest: explorerApi.sendRequest,
+
   // Scheduled Jobs
   getJobs: jobsApi.getJobs,
   getJob: jobsApi.getJob,
@@ -421,6 +485,14 @@ export const api = {
   triggerJobRun: jobsApi.triggerJobRun,
   getJobRuns: jobsApi.getJobRuns,
 
+  // Scheduled Tasks
+  getTasks: tasksApi.getTasks,
+  createTask: tasksApi.createTask,
+  updateTask: tasksApi.updateTask,
+  deleteTask: tasksApi.deleteTask,
+  runTask: tasksApi.runTask,
+  getTaskRuns: tasksApi.getTaskRuns,
+
   // Log Drains
   getLogDrains: logDrainsApi.getLogDrains,
   createLogDrain: logDrainsApi.createLogDrain,
@@ -0,0 +1,97 @@
+/**
+ * Notification Digests API module.
+ * Handles scheduled daily/weekly summaries sent to channels and email addresses.
+ */
+
+import { apiRequest } from "./core";
+
+export type DigestFrequency = "daily" | "weekly";
+
+export interface NotificationDigest {
+  id: string;
+  name: string;
+  /** Only this team's apps are summarized (null = all apps) */
+  team_id: string | null;
+  frequency: DigestFrequency;
+  /** Local hour 0-23 */
+  send_hour: number;
+  /** 0 = Sunday ... 6 = Saturday; weekly digests only */
+  send_weekday: number;
+  /** IANA timezone for the send time (null = UTC) */
+  timezone: string | null;
+  channel_ids: string[];
+  email_addresses: string[];
+  enabled: boolean;
+  last_sent_at: string | null;
+  next_send_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateNotificationDigestRequest {
+  name: string;
+  team_id?: string | null;
+  frequency?: DigestFrequency;
+  send_hour?: number;
+  send_weekday?: number;
+  timezone?: string;
+  channel_ids?: string[];
+  email_addresses?: string[];
+  enabled?: boolean;
+}
+
+export type UpdateNotificationDigestRequest = Partial<
+  Omit<CreateNotificationDigestRequest, "team_id">
+>;
+
+export interface DigestSummary {
+  period_start: string;
+  period_end: string;
+  deployments: number;
+  succeeded: number;
+  failed: number;
+  slowest_builds: { app_name: string; deployment_id: string; duration_secs: number }[];
+  crashed_apps: { app_name: string; crashes: number }[];
+  expiring_certificates: { domain: string; expires_at: string; days_left: number }[];
+}
+
+export const notificationDigestsApi = {
+  /** List notification digests */
+  getNotificationDigests: (): Promise<NotificationDigest[]> =>
+    apiRequest<NotificationDigest[]>("/notification-digests"),
+
+  /** Create a notification digest */
+  createNotificationDigest: (
+    data: CreateNotificationDigestRequest
+  ): Promise<NotificationDigest> =>
+    apiRequest<NotificationDigest>("/notification-digests", {
+      method: "POST",
+      body: JSON.stringify(data),
+    }),
+
+  /** Update a notification digest */
+  updateNotificationDigest: (
+    id: string,
+    data: UpdateNotificationDigestRequest
+  ): Promise<NotificationDigest> =>
+    apiRequest<NotificationDigest>(`/notification-digests/${id}`, {
+      method: "PUT",
+      body: JSON.stringify(data),
+    }),
+
+  /** Delete a notification digest */
+  deleteNotificationDigest: (id: string) =>
+    apiRequest<void>(`/notification-digests/${id}`, {
+      method: "DELETE",
+    }),
+
+  /** Summary the digest would send now */
+  previewNotificationDigest: (id: string): Promise<DigestSummary> =>
+    apiRequest<DigestSummary>(`/notification-digests/${id}/preview`),
+
+  /** Send the digest now */
+  sendNotificationDigest: (id: string): Promise<DigestSummary> =>
+    apiRequest<DigestSummary>(`/notification-digests/${id}/send`, {
+      method: "POST",
+    }),
+};
@@ -0,0 +1,49 @@
+/**
+ * User Preferences API module.
+ * Handles the current user's timezone, locale, appearance, notification severities and email digests.
+ */
+
+import { apiRequest } from "./core";
+
+export type NotificationSeverity = "info" | "warning" | "critical";
+
+export type ThemePreference = "system" | "light" | "dark" | "high-contrast";
+
+export interface UserPreferences {
+  /** IANA timezone for digests (null = UTC) */
+  timezone: string | null;
+  /** Batch notification email into one daily digest */
+  email_digests: boolean;
+  /** Severities the user receives by email */
+  notify_severities: NotificationSeverity[];
+  /** Dashboard and message language ("en", "ar") */
+  locale: string;
+  /** Dashboard theme ("system" follows the OS) */
+  theme: ThemePreference;
+  /** Turn off dashboard animations and transitions */
+  reduced_motion: boolean;
+}
+
+export interface UpdateUserPreferencesRequest {
+  /** Empty string resets to UTC */
+  timezone?: string;
+  email_digests?: boolean;
+  notify_severities?: NotificationSeverity[];
+  /** Empty string resets to English */
+  locale?: string;
+  theme?: ThemePreference;
+  reduced_motion?: boolean;
+}
+
+export const preferencesApi = {
+  /** Get the current user's preferences */
+  getPreferences: (): Promise<UserPreferences> =>
+    apiRequest<UserPreferences>("/auth/preferences"),
+
+  /** Update the current user's preferences */
+  updatePreferences: (data: UpdateUserPreferencesRequest): Promise<UserPreferences> =>
+    apiRequest<UserPreferences>("/auth/preferences", {
+      method: "PUT",
+      body: JSON.stringify(data),
+    }),
+};
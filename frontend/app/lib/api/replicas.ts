@@ -16,6 +16,21 @@ export interface AppReplica {
   created_at: string;
 }
 
+/** A Procfile process type with its desired and running container counts */
+export interface ProcessStatus {
+  name: string;
+  command: string;
+  /** The app's replica count for `web`, 0 for `release` */
+  replica_count: number;
+  running: number;
+  container_ids: string[];
+}
+
+export interface RolloutSettings {
+  max_surge: number;
+  max_unavailable: number;
+}
+
 export const replicasApi = {
   /**
    * List all replicas for an app
@@ -33,6 +48,32 @@ export const replicasApi = {
       body: JSON.stringify({ count }),
     }),
 
+  /**
+   * Set how many replicas a rolling deploy may add above, or take out of,
+   * the replica count at once.
+   */
+  setRollout: (appId: string, settings: RolloutSettings): Promise<RolloutSettings> =>
+    apiRequest<RolloutSettings>(`/apps/${appId}/replicas/rollout`, {
+      method: "PUT",
+      body: JSON.stringify(settings),
+    }),
+
+  /**
+   * List the app's Procfile process types and how many of their containers run
+   */
+  listProcesses: (appId: string): Promise<ProcessStatus[]> =>
+    apiRequest<ProcessStatus[]>(`/apps/${appId}/processes`),
+
+  /**
+   * Set the container count of a process type (not `web` or `release`).
+   * If the app is running, starts/stops containers to match.
+   */
+  scaleProcess: (appId: string, name: string, replicas: number): Promise<ProcessStatus[]> =>
+    apiRequest<ProcessStatus[]>(`/apps/${appId}/processes/${encodeURIComponent(name)}`, {
+      method: "PUT",
+      body: JSON.stringify({ replicas }),
+    }),
+
   /**
    * Restart a specific replica by index
    */
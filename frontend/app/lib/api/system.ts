@@ -35,6 +35,8 @@ export interface BackupSchedule {
   last_run_at: string | null;
   next_run_at: string | null;
   created_at: string;
+  /** IANA timezone the cron expression runs in (null = UTC) */
+  timezone: string | null;
 }
 
 /** Request to create a backup schedule */
@@ -44,6 +46,20 @@ export interface CreateBackupScheduleRequest {
   target_id?: string | null;
   s3_config_id?: string | null;
   retention_days?: number;
+  /** IANA timezone, e.g. "Europe/Berlin" (default UTC) */
+  timezone?: string;
+}
+
+/** A validated cron expression and its upcoming run times */
+export interface CronPreview {
+  /** The expression in 6-field form (seconds first) */
+  normalized: string;
+  /** IANA timezone the expression was evaluated in */
+  timezone: string;
+  /** Current UTC offset of that timezone, e.g. "+02:00" */
+  utc_offset: string;
+  /** Upcoming run times in that timezone (RFC 3339) */
+  next_runs: string[];
 }
 
 /** Options for getting system stats */
@@ -382,4 +398,11 @@ export const systemApi = {
       { method: "POST" },
       token
     ),
+
+  /** Validate a cron expression and get its next run times */
+  previewCron: (expression: string, count?: number, timezone?: string, token?: string) =>
+    apiRequest<CronPreview>("/system/cron/preview", {
+      method: "POST",
+      body: JSON.stringify({ expression, count, timezone: timezone || undefined }),
+    }, token),
 };
@@ -0,0 +1,49 @@
+/**
+ * Scheduled Tasks API module.
+ * Handles CRUD operations for cron-based scheduled tasks
+ * that run commands in one-off containers from the app's image.
+ */
+
+import { apiRequest } from "./core";
+import type {
+  ScheduledTask,
+  ScheduledTaskRun,
+  CreateScheduledTaskRequest,
+  UpdateScheduledTaskRequest,
+} from "@/types/api";
+
+export const tasksApi = {
+  /** List all scheduled tasks for an app */
+  getTasks: (appId: string, token?: string) =>
+    apiRequest<ScheduledTask[]>(`/apps/${appId}/tasks`, {}, token),
+
+  /** Create a new scheduled task */
+  createTask: (appId: string, data: CreateScheduledTaskRequest, token?: string) =>
+    apiRequest<ScheduledTask>(`/apps/${appId}/tasks`, {
+      method: "POST",
+      body: JSON.stringify(data),
+    }, token),
+
+  /** Update an existing scheduled task */
+  updateTask: (appId: string, taskId: string, data: UpdateScheduledTaskRequest, token?: string) =>
+    apiRequest<ScheduledTask>(`/apps/${appId}/tasks/${taskId}`, {
+      method: "PUT",
+      body: JSON.stringify(data),
+    }, token),
+
+  /** Delete a scheduled task */
+  deleteTask: (appId: string, taskId: string, token?: string) =>
+    apiRequest<void>(`/apps/${appId}/tasks/${taskId}`, {
+      method: "DELETE",
+    }, token),
+
+  /** Run a task now; the run finishes in the background */
+  runTask: (appId: string, taskId: string, token?: string) =>
+    apiRequest<ScheduledTaskRun>(`/apps/${appId}/tasks/${taskId}/run`, {
+      method: "POST",
+    }, token),
+
+  /** List the most recent runs of a task */
+  getTaskRuns: (appId: string, taskId: string, token?: string) =>
+    apiRequest<ScheduledTaskRun[]>(`/apps/${appId}/tasks/${taskId}/runs`, {}, token),
+};
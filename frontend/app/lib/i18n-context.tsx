@@ -0,0 +1,102 @@
+/**
+ * Localization context.
+ * Holds the dashboard locale (from the user's preferences, cached in
+ * localStorage so the first paint is already localized), applies it to the
+ * document's lang/dir attributes and provides a translate function over the
+ * message catalogs in app/locales.
+ */
+
+import {
+  createContext,
+  useCallback,
+  useContext,
+  useEffect,
+  useMemo,
+  useState,
+  type ReactNode,
+} from "react";
+import { useQuery } from "@tanstack/react-query";
+import en from "@/locales/en.json";
+import ar from "@/locales/ar.json";
+import { getStoredLocale, getStoredToken, setStoredLocale } from "@/lib/api/core";
+import { preferencesApi } from "@/lib/api/preferences";
+
+export type Locale = "en" | "ar";
+
+export const SUPPORTED_LOCALES: Locale[] = ["en", "ar"];
+
+const RTL_LOCALES: Locale[] = ["ar"];
+
+const CATALOGS: Record<Locale, Record<string, string>> = { en, ar };
+
+export type TranslateArgs = Record<string, string | number>;
+
+interface I18nContextValue {
+  locale: Locale;
+  dir: "ltr" | "rtl";
+  setLocale: (locale: Locale) => void;
+  /** Message in the current locale, falling back to English, then the key */
+  t: (key: string, args?: TranslateArgs) => string;
+}
+
+function toLocale(code: string | null | undefined): Locale {
+  const language = code?.split(/[-_]/)[0]?.toLowerCase();
+  return SUPPORTED_LOCALES.find((l) => l === language) ?? "en";
+}
+
+function translate(locale: Locale, key: string, args?: TranslateArgs): string {
+  let text = CATALOGS[locale][key] ?? CATALOGS.en[key] ?? key;
+  if (args) {
+    for (const [name, value] of Object.entries(args)) {
+      text = text.replaceAll(`{${name}}`, String(value));
+    }
+  }
+  return text;
+}
+
+const I18nContext = createContext<I18nContextValue>({
+  locale: "en",
+  dir: "ltr",
+  setLocale: () => {},
+  t: (key, args) => translate("en", key, args),
+});
+
+export function I18nProvider({ children }: { children: ReactNode }) {
+  const [locale, setLocaleState] = useState<Locale>(() => toLocale(getStoredLocale()));
+
+  const setLocale = useCallback((next: Locale) => {
+    setStoredLocale(next);
+    setLocaleState(next);
+  }, []);
+
+  // Follow the locale saved in the user's preferences (e.g. set on another device)
+  const { data: prefs } = useQuery({
+    queryKey: ["user-preferences"],
+    queryFn: preferencesApi.getPreferences,
+    enabled: typeof window !== "undefined" && !!getStoredToken(),
+    staleTime: 1000 * 60 * 5,
+  });
+  useEffect(() => {
+    if (prefs?.locale) setLocale(toLocale(prefs.locale));
+  }, [prefs?.locale, setLocale]);
+
+  const dir = RTL_LOCALES.includes(locale) ? "rtl" : "ltr";
+
+  useEffect(() => {
+    document.documentElement.lang = locale;
+    document.documentElement.dir = dir;
+  }, [locale, dir]);
+
+  const t = useCallback(
+    (key: string, args?: TranslateArgs) => translate(locale, key, args),
+    [locale]
+  );
+
+  const value = useMemo(() => ({ locale, dir, setLocale, t }), [locale, dir, setLocale, t]);
+
+  return <I18nContext.Provider value={value}>{children}</I18nContext.Provider>;
+}
+
+export function useI18n(): I18nContextValue {
+  return useContext(I18nContext);
+}
@@ -12,6 +12,7 @@ import type { Route } from "./+types/root";
 import { ThemeProvider } from "@/components/providers/theme-provider";
 import { Toaster } from "@/components/ui/sonner";
 import { WhiteLabelProvider } from "@/lib/white-label-context";
+import { I18nProvider } from "@/lib/i18n-context";
 import { DeployPanelProvider } from "@/lib/deploy-panel-context";
 import { DeploySidePanel } from "@/components/deploy-side-panel";
 import "./app.css";
@@ -25,13 +26,17 @@ const queryClient = new QueryClient({
   },
 });
 
-// Blocking script to set theme before React hydrates to prevent flicker
+// Blocking script to set theme and reduced motion before React hydrates to prevent flicker
 const themeScript = `
 (function() {
+  const root = document.documentElement;
   const stored = localStorage.getItem('rivetr_theme');
   const theme = stored === 'light' || stored === 'dark' ? stored :
-    (stored === 'system' || !stored) && window.matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light';
-  if (theme === 'dark') document.documentElement.classList.add('dark');
+    stored === 'high-contrast' ? 'dark' :
+    window.matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light';
+  if (theme === 'dark') root.classList.add('dark');
+  if (stored === 'high-contrast') root.classList.add('high-contrast');
+  if (localStorage.getItem('rivetr_reduced_motion') === 'true') root.classList.add('reduce-motion');
 })();
 `;
 
@@ -48,13 +53,15 @@ export function Layout({ children }: { children: React.ReactNode }) {
       <body>
         <QueryClientProvider client={queryClient}>
           <ThemeProvider>
-            <WhiteLabelProvider>
-              <DeployPanelProvider>
-                {children}
-                <DeploySidePanel />
-              </DeployPanelProvider>
-            </WhiteLabelProvider>
-            <Toaster position="top-right" richColors />
+            <I18nProvider>
+              <WhiteLabelProvider>
+                <DeployPanelProvider>
+                  {children}
+                  <DeploySidePanel />
+                </DeployPanelProvider>
+              </WhiteLabelProvider>
+              <Toaster position="top-right" richColors />
+            </I18nProvider>
           </ThemeProvider>
         </QueryClientProvider>
         <ScrollRestoration />
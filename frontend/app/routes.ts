@@ -105,6 +105,7 @@ export default [
     route("webhooks", "routes/settings/webhooks.tsx"),
     route("webhook-events", "routes/settings/webhook-events.tsx"),
     route("tokens", "routes/settings/tokens.tsx"),
+    route("api-explorer", "routes/settings/api-explorer.tsx"),
 
     // Settings
     ...prefix("settings", [
@@ -16,6 +16,7 @@ import {
   SidebarTrigger,
 } from "@/components/ui/sidebar";
 import { ThemeToggle } from "@/components/theme-toggle";
+import { NotificationBell } from "@/components/notifications/notification-bell";
 import { useRequireAuth } from "@/lib/auth";
 import { BreadcrumbProvider, useBreadcrumb, type BreadcrumbItem as BreadcrumbItemType } from "@/lib/breadcrumb-context";
 import { TeamProvider } from "@/lib/team-context";
@@ -322,6 +323,7 @@ function DashboardLayoutInner() {
             </Breadcrumb>
           </div>
           <div className="flex items-center gap-1 px-4">
+            <NotificationBell />
             <ThemeToggle />
           </div>
         </header>
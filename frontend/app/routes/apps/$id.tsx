@@ -92,6 +92,7 @@ const statusColors: Record<DeploymentStatus, string> = {
   stopped: "bg-gray-500",
   replaced: "bg-gray-400",
   cancelled: "bg-gray-400",
+  prewarmed: "bg-purple-500",
 };
 
 function formatDate(dateStr: string): string {
@@ -6,6 +6,7 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { Skeleton } from "@/components/ui/skeleton";
 import { ResourceLimitsCard } from "@/components/resource-limits-card";
+import { ProcessesCard } from "@/components/processes-card";
 import { ResourceMonitor } from "@/components/resource-monitor";
 import { EnvironmentBadge } from "@/components/environment-badge";
 import { api } from "@/lib/api";
@@ -47,6 +48,9 @@ function activityIcon(action: string) {
   if (action === "app.start") return <Play className="h-3.5 w-3.5 text-green-500" />;
   if (action === "app.stop") return <Square className="h-3.5 w-3.5 text-yellow-500" />;
   if (action === "deployment.trigger") return <Rocket className="h-3.5 w-3.5 text-purple-500" />;
+  if (action === "deployment.promote") return <Rocket className="h-3.5 w-3.5 text-purple-500" />;
+  if (action === "deployment.approve") return <Rocket className="h-3.5 w-3.5 text-green-500" />;
+  if (action === "deployment.reject") return <Square className="h-3.5 w-3.5 text-destructive" />;
   if (action === "deployment.rollback") return <RotateCw className="h-3.5 w-3.5 text-orange-500" />;
   if (action === "app.update") return <Pencil className="h-3.5 w-3.5 text-muted-foreground" />;
   if (action === "app.delete") return <Trash2 className="h-3.5 w-3.5 text-destructive" />;
@@ -61,8 +65,14 @@ function activityLabel(action: string): string {
     "app.create": "Created",
     "app.update": "Updated settings",
     "app.delete": "Deleted",
+    "app.scale": "Scaled",
+    "app.lock": "Locked deployments",
+    "app.unlock": "Unlocked deployments",
     "deployment.trigger": "Deployment triggered",
     "deployment.rollback": "Rollback triggered",
+    "deployment.promote": "Pre-warmed build promoted",
+    "deployment.approve": "Deployment approved",
+    "deployment.reject": "Deployment rejected",
   };
   return labels[action] ?? action;
 }
@@ -170,6 +180,8 @@ export default function AppGeneralTab() {
         </Card>
       </div>
 
+      <ProcessesCard appId={app.id} />
+
       <ResourceLimitsCard app={app} token={token} />
 
       {runningDeployment && (
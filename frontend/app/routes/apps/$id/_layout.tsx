@@ -17,7 +17,7 @@ import { getPrimaryDomain } from "@/lib/utils";
 import { bulkApi } from "@/lib/api/bulk";
 import { useBreadcrumb } from "@/lib/breadcrumb-context";
 import { useDeployPanel } from "@/lib/deploy-panel-context";
-import type { App, AppStatus, Deployment, DeploymentStatus, DeploymentListResponse, Project, GitCommit, GitTag, DeploymentFreezeWindow } from "@/types/api";
+import type { App, AppStatus, Deployment, DeploymentStatus, DeploymentListResponse, Project, ProjectEnvironment, GitCommit, GitTag, DeploymentFreezeWindow } from "@/types/api";
 import {
   Play,
   Square,
@@ -32,9 +32,12 @@ import {
   Copy,
   WrenchIcon,
   Link2,
+  Lock,
+  LockOpen,
 } from "lucide-react";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
+import { Checkbox } from "@/components/ui/checkbox";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
 import {
   DropdownMenu,
@@ -148,11 +151,19 @@ export default function AppDetailLayout() {
   // Clone app state
   const [showCloneDialog, setShowCloneDialog] = useState(false);
   const [cloneName, setCloneName] = useState("");
+  const [cloneEnvironmentId, setCloneEnvironmentId] = useState("");
+  const [cloneGenerateDomain, setCloneGenerateDomain] = useState(false);
+  const [cloneCopyVolumes, setCloneCopyVolumes] = useState(false);
   const [isCloning, setIsCloning] = useState(false);
 
   // Maintenance mode state
   const [isMaintenanceLoading, setIsMaintenanceLoading] = useState(false);
 
+  // Deployment lock state
+  const [showLockDialog, setShowLockDialog] = useState(false);
+  const [lockReason, setLockReason] = useState("");
+  const [isLockLoading, setIsLockLoading] = useState(false);
+
   // Deploy by commit/tag state
   const [showDeployOptionsDialog, setShowDeployOptionsDialog] = useState(false);
   const [deployTarget, setDeployTarget] = useState<"latest" | "commit" | "tag">("latest");
@@ -177,6 +188,13 @@ export default function AppDetailLayout() {
     enabled: !!app?.project_id,
   });
 
+  // Environments the clone dialog can target
+  const { data: projectEnvironments = [] } = useQuery<ProjectEnvironment[]>({
+    queryKey: ["environments", app?.project_id],
+    queryFn: () => api.getEnvironments(app!.project_id!),
+    enabled: !!app?.project_id && showCloneDialog,
+  });
+
   // Set breadcrumbs when app and project are loaded
   useEffect(() => {
     if (app) {
@@ -375,10 +393,21 @@ export default function AppDetailLayout() {
     if (!id) return;
     setIsCloning(true);
     try {
-      const result = await bulkApi.cloneApp(id, cloneName ? { name: cloneName } : undefined);
+      const result = await bulkApi.cloneApp(id, {
+        name: cloneName || undefined,
+        environment_id: cloneEnvironmentId || undefined,
+        generate_domain: cloneGenerateDomain,
+        copy_volumes: cloneCopyVolumes,
+      });
       toast.success(`App cloned as "${result.app.name}"`);
+      if (result.volumes_not_copied?.length) {
+        toast.warning(`Volumes not copied: ${result.volumes_not_copied.join(", ")}`);
+      }
       setShowCloneDialog(false);
       setCloneName("");
+      setCloneEnvironmentId("");
+      setCloneGenerateDomain(false);
+      setCloneCopyVolumes(false);
       navigate(`/apps/${result.app.id}`);
     } catch (error) {
       toast.error(error instanceof Error ? error.message : "Failed to clone app");
@@ -403,6 +432,37 @@ export default function AppDetailLayout() {
     }
   };
 
+  // Handle deployment lock / unlock
+  const handleLock = async () => {
+    if (!id) return;
+    setIsLockLoading(true);
+    try {
+      await api.lockApp(id, { reason: lockReason.trim() || undefined });
+      toast.success("Deployments locked");
+      setShowLockDialog(false);
+      setLockReason("");
+      queryClient.invalidateQueries({ queryKey: ["app", id] });
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to lock deployments");
+    } finally {
+      setIsLockLoading(false);
+    }
+  };
+
+  const handleUnlock = async () => {
+    if (!id) return;
+    setIsLockLoading(true);
+    try {
+      await api.unlockApp(id);
+      toast.success("Deployments unlocked");
+      queryClient.invalidateQueries({ queryKey: ["app", id] });
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to unlock deployments");
+    } finally {
+      setIsLockLoading(false);
+    }
+  };
+
   // Determine active tab from path
   const basePath = `/apps/${id}`;
   const currentPath = location.pathname;
@@ -647,6 +707,17 @@ export default function AppDetailLayout() {
                   ? "Disable Maintenance"
                   : "Enable Maintenance"}
               </DropdownMenuItem>
+              {app.locked_at ? (
+                <DropdownMenuItem onClick={handleUnlock} disabled={isLockLoading}>
+                  <LockOpen className="h-4 w-4 mr-2" />
+                  Unlock Deployments
+                </DropdownMenuItem>
+              ) : (
+                <DropdownMenuItem onClick={() => setShowLockDialog(true)} disabled={isLockLoading}>
+                  <Lock className="h-4 w-4 mr-2" />
+                  Lock Deployments
+                </DropdownMenuItem>
+              )}
             </DropdownMenuContent>
           </DropdownMenu>
           {/* Maintenance mode badge */}
@@ -656,6 +727,17 @@ export default function AppDetailLayout() {
               Maintenance
             </Badge>
           )}
+          {/* Deployment lock badge */}
+          {app.locked_at && (
+            <Badge
+              variant="outline"
+              className="gap-1 border-red-500 text-red-600"
+              title={`Locked by ${app.locked_by ?? "unknown"}${app.lock_reason ? `: ${app.lock_reason}` : ""}`}
+            >
+              <Lock className="h-3 w-3" />
+              Locked
+            </Badge>
+          )}
         </div>
       </div>
 
@@ -902,7 +984,7 @@ export default function AppDetailLayout() {
           <DialogHeader>
             <DialogTitle>Clone App</DialogTitle>
             <DialogDescription>
-              Create a deep copy of this app including its configuration, environment variables, and volumes.
+              Create a copy of this app with its configuration, environment variables and volumes. Domains are not copied.
             </DialogDescription>
           </DialogHeader>
           <div className="space-y-4 py-2">
@@ -915,6 +997,42 @@ export default function AppDetailLayout() {
                 placeholder={`${app.name}-copy`}
               />
             </div>
+            {projectEnvironments.length > 1 && (
+              <div className="space-y-2">
+                <Label htmlFor="clone-environment">Environment</Label>
+                <Select
+                  value={cloneEnvironmentId || app.environment_id || ""}
+                  onValueChange={setCloneEnvironmentId}
+                >
+                  <SelectTrigger id="clone-environment">
+                    <SelectValue placeholder="Same environment" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {projectEnvironments.map((env) => (
+                      <SelectItem key={env.id} value={env.id}>
+                        {env.name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            )}
+            <div className="flex items-center gap-2">
+              <Checkbox
+                id="clone-generate-domain"
+                checked={cloneGenerateDomain}
+                onCheckedChange={(checked) => setCloneGenerateDomain(checked === true)}
+              />
+              <Label htmlFor="clone-generate-domain">Generate a new domain</Label>
+            </div>
+            <div className="flex items-center gap-2">
+              <Checkbox
+                id="clone-copy-volumes"
+                checked={cloneCopyVolumes}
+                onCheckedChange={(checked) => setCloneCopyVolumes(checked === true)}
+              />
+              <Label htmlFor="clone-copy-volumes">Copy volume data</Label>
+            </div>
           </div>
           <DialogFooter>
             <Button
@@ -931,6 +1049,50 @@ export default function AppDetailLayout() {
           </DialogFooter>
         </DialogContent>
       </Dialog>
+
+      {/* Lock Deployments Dialog */}
+      <Dialog
+        open={showLockDialog}
+        onOpenChange={(open) => {
+          setShowLockDialog(open);
+          if (!open) setLockReason("");
+        }}
+      >
+        <DialogContent className="max-w-md">
+          <DialogHeader>
+            <DialogTitle>Lock Deployments</DialogTitle>
+            <DialogDescription>
+              Manual and webhook deployments are rejected until the app is unlocked. Rollbacks and
+              restarts still work.
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4 py-2">
+            <div className="space-y-2">
+              <Label htmlFor="lock-reason">Reason</Label>
+              <Input
+                id="lock-reason"
+                value={lockReason}
+                onChange={(e) => setLockReason(e.target.value)}
+                placeholder="Investigating incident"
+                maxLength={500}
+              />
+            </div>
+          </div>
+          <DialogFooter>
+            <Button
+              variant="outline"
+              onClick={() => setShowLockDialog(false)}
+              disabled={isLockLoading}
+            >
+              Cancel
+            </Button>
+            <Button onClick={handleLock} disabled={isLockLoading} className="gap-2">
+              <Lock className="h-4 w-4" />
+              {isLockLoading ? "Locking..." : "Lock Deployments"}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
     </div>
   );
 }
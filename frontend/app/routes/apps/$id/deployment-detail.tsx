@@ -14,6 +14,7 @@ import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Separator } from "@/components/ui/separator";
 import { DeploymentLogs } from "@/components/deployment-logs";
+import { DeploymentPhasesCard } from "@/components/deployment-phases-card";
 import { api } from "@/lib/api";
 import { aiApi } from "@/lib/api/ai";
 import {
@@ -29,6 +30,7 @@ import {
   Package,
   Copy,
   Sparkles,
+  Download,
 } from "lucide-react";
 import type { Deployment } from "@/types/api";
 
@@ -76,6 +78,11 @@ const STATUS_CONFIG: Record<
     icon: <Clock className="h-4 w-4" />,
     variant: "secondary",
   },
+  prewarmed: {
+    label: "Pre-warmed",
+    icon: <Package className="h-4 w-4" />,
+    variant: "outline",
+  },
   success: {
     label: "Success",
     icon: <CheckCircle2 className="h-4 w-4" />,
@@ -145,6 +152,23 @@ export default function DeploymentDetailPage() {
     onError: (e: Error) => toast.error(e.message || "Rollback failed"),
   });
 
+  const handleDownloadDiagnostics = async () => {
+    if (!deploymentId) return;
+    try {
+      const blob = await api.getDeploymentDiagnostics(deploymentId);
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = `deployment-${deploymentId.slice(0, 8)}-diagnostics.json`;
+      document.body.appendChild(link);
+      link.click();
+      document.body.removeChild(link);
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      toast.error(error instanceof Error ? error.message : "Failed to download diagnostics");
+    }
+  };
+
   const handleDiagnose = async () => {
     if (!appId || !deploymentId) return;
     setDiagnosisLoading(true);
@@ -224,6 +248,17 @@ export default function DeploymentDetailPage() {
                       {rollbackMutation.isPending ? "Rolling back..." : "Rollback to this"}
                     </Button>
                   )}
+                  {deployment.status === "failed" && (
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      className="gap-1.5"
+                      onClick={handleDownloadDiagnostics}
+                    >
+                      <Download className="h-3.5 w-3.5" />
+                      Diagnostics
+                    </Button>
+                  )}
                   {deployment.status === "failed" && (
                     <Button
                       variant="outline"
@@ -301,6 +336,14 @@ export default function DeploymentDetailPage() {
                         <Copy className="h-3.5 w-3.5" />
                       </button>
                     </div>
+                    {deployment.version_tag && (
+                      <p className="text-xs text-muted-foreground">
+                        Also tagged{" "}
+                        <code className="bg-muted px-1 rounded font-mono">
+                          {deployment.version_tag}
+                        </code>
+                      </p>
+                    )}
                   </div>
                 </>
               )}
@@ -314,11 +357,31 @@ export default function DeploymentDetailPage() {
                       {deployment.error_message}
                     </pre>
                   </div>
+                  {deployment.error_hint && (
+                    <div className="rounded-md bg-muted border p-3 space-y-1">
+                      <p className="text-xs font-medium">How to fix</p>
+                      <p className="text-xs text-muted-foreground">{deployment.error_hint}</p>
+                    </div>
+                  )}
                 </>
               )}
             </CardContent>
           </Card>
 
+          <DeploymentPhasesCard deploymentId={deployment.id} isActive={isActive} />
+
+          {/* Test stage output */}
+          {deployment.test_output && (
+            <div className="space-y-2">
+              <h2 className="text-sm font-semibold text-muted-foreground uppercase tracking-wide">
+                Test Output
+              </h2>
+              <pre className="max-h-96 overflow-auto rounded-md border bg-muted p-3 text-xs font-mono whitespace-pre-wrap break-words">
+                {deployment.test_output}
+              </pre>
+            </div>
+          )}
+
           {/* Live / historical logs */}
           <div className="space-y-2">
             <div className="flex items-center justify-between">
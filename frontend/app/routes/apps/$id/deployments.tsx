@@ -38,6 +38,15 @@ interface DeploymentDiff {
   summary: string;
   files_changed: string[];
   commit_messages: string[];
+  config_changes: ConfigChange[] | null;
+}
+
+interface ConfigChange {
+  section: "env" | "settings";
+  name: string;
+  change: "added" | "removed" | "changed";
+  old: string | null;
+  new: string | null;
 }
 
 const ACTIVE_STATUSES: DeploymentStatus[] = ["pending", "cloning", "building", "starting", "checking"];
@@ -661,7 +670,43 @@ export default function AppDeploymentsTab() {
                 </div>
               )}
 
-              {diffData.commit_messages.length === 0 && diffData.files_changed.length === 0 && (
+              {/* Configuration changes */}
+              {diffData.config_changes && diffData.config_changes.length > 0 && (
+                <div className="space-y-2">
+                  <p className="text-xs font-medium text-muted-foreground uppercase tracking-wide">
+                    Configuration Changes ({diffData.config_changes.length})
+                  </p>
+                  <div className="space-y-1 max-h-48 overflow-y-auto rounded-lg border p-2">
+                    {diffData.config_changes.map((change) => (
+                      <div
+                        key={`${change.section}:${change.name}`}
+                        className="flex items-start gap-2 text-xs py-0.5"
+                      >
+                        <Badge variant="outline" className="text-[10px] flex-shrink-0">
+                          {change.change}
+                        </Badge>
+                        <span className="font-mono">
+                          {change.section === "env" ? `env ${change.name}` : change.name}
+                        </span>
+                        {change.section === "settings" && change.change === "changed" && (
+                          <span className="text-muted-foreground break-all">
+                            {change.old} → {change.new}
+                          </span>
+                        )}
+                        {change.section === "settings" && change.change !== "changed" && (
+                          <span className="text-muted-foreground break-all">
+                            {change.new ?? change.old}
+                          </span>
+                        )}
+                      </div>
+                    ))}
+                  </div>
+                </div>
+              )}
+
+              {diffData.commit_messages.length === 0 &&
+                diffData.files_changed.length === 0 &&
+                !diffData.config_changes?.length && (
                 <div className="text-center py-6 text-muted-foreground text-sm">
                   No detailed diff information available for this deployment.
                 </div>
@@ -42,6 +42,9 @@ import {
   CollapsibleContent,
   CollapsibleTrigger,
 } from "@/components/ui/collapsible";
+import { CronPreview } from "@/components/cron-preview";
+import { TimezoneInput } from "@/components/timezone-input";
+import { ScheduledTasksSection } from "@/components/scheduled-tasks-section";
 import { jobsApi } from "@/lib/api/jobs";
 import type {
   ScheduledJob,
@@ -202,6 +205,7 @@ export default function JobsPage() {
   const [formCommand, setFormCommand] = useState("");
   const [formCron, setFormCron] = useState("0 0 * * * *");
   const [formEnabled, setFormEnabled] = useState(true);
+  const [formTimezone, setFormTimezone] = useState("");
 
   // Expanded job runs
   const [expandedJobs, setExpandedJobs] = useState<Set<string>>(new Set());
@@ -284,6 +288,7 @@ export default function JobsPage() {
     setFormCommand("");
     setFormCron("0 0 * * * *");
     setFormEnabled(true);
+    setFormTimezone("");
   }
 
   function openEditDialog(job: ScheduledJob) {
@@ -291,6 +296,7 @@ export default function JobsPage() {
     setFormCommand(job.command);
     setFormCron(job.cron_expression);
     setFormEnabled(job.enabled);
+    setFormTimezone(job.timezone ?? "");
     setEditingJob(job);
   }
 
@@ -300,6 +306,7 @@ export default function JobsPage() {
       command: formCommand,
       cron_expression: formCron,
       enabled: formEnabled,
+      timezone: formTimezone.trim() || undefined,
     });
   }
 
@@ -312,6 +319,7 @@ export default function JobsPage() {
         command: formCommand,
         cron_expression: formCron,
         enabled: formEnabled,
+        timezone: formTimezone.trim(),
       },
     });
   }
@@ -414,6 +422,7 @@ export default function JobsPage() {
                         <code className="text-xs bg-muted px-1.5 py-0.5 rounded">
                           {job.cron_expression}
                         </code>
+                        {job.timezone && <span className="text-xs">{job.timezone}</span>}
                       </div>
                       {job.enabled ? (
                         <Badge className="bg-green-500 text-white">Enabled</Badge>
@@ -475,6 +484,8 @@ export default function JobsPage() {
         </div>
       )}
 
+      <ScheduledTasksSection appId={appId!} />
+
       {/* Create Dialog */}
       <Dialog open={showCreateDialog} onOpenChange={setShowCreateDialog}>
         <DialogContent className="max-w-lg">
@@ -516,6 +527,7 @@ export default function JobsPage() {
                 placeholder="0 0 * * * *"
                 className="font-mono text-sm"
               />
+              <CronPreview expression={formCron} timezone={formTimezone.trim()} />
               <div className="flex flex-wrap gap-1.5 mt-1">
                 {CRON_PRESETS.map((preset) => (
                   <button
@@ -529,6 +541,10 @@ export default function JobsPage() {
                 ))}
               </div>
             </div>
+            <div className="space-y-2">
+              <Label htmlFor="timezone">Timezone</Label>
+              <TimezoneInput id="timezone" value={formTimezone} onChange={setFormTimezone} />
+            </div>
             <div className="flex items-center space-x-2">
               <Switch
                 id="enabled"
@@ -587,6 +603,7 @@ export default function JobsPage() {
                 onChange={(e) => setFormCron(e.target.value)}
                 className="font-mono text-sm"
               />
+              <CronPreview expression={formCron} timezone={formTimezone.trim()} />
               <div className="flex flex-wrap gap-1.5 mt-1">
                 {CRON_PRESETS.map((preset) => (
                   <button
@@ -600,6 +617,10 @@ export default function JobsPage() {
                 ))}
               </div>
             </div>
+            <div className="space-y-2">
+              <Label htmlFor="edit-timezone">Timezone</Label>
+              <TimezoneInput id="edit-timezone" value={formTimezone} onChange={setFormTimezone} />
+            </div>
             <div className="flex items-center space-x-2">
               <Switch
                 id="edit-enabled"
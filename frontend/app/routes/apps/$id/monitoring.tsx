@@ -46,6 +46,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { monitoringApi } from "@/lib/api/monitoring";
+import { TimezoneInput } from "@/components/timezone-input";
 import type {
   LogSearchResult,
   LogRetentionPolicy,
@@ -589,6 +590,7 @@ function ScheduledRestartsSection({ appId }: { appId: string }) {
   const queryClient = useQueryClient();
   const [showCreateDialog, setShowCreateDialog] = useState(false);
   const [formCron, setFormCron] = useState("0 3 * * *");
+  const [formTimezone, setFormTimezone] = useState("");
   const [formEnabled, setFormEnabled] = useState(true);
   const [deleteConfirm, setDeleteConfirm] = useState<ScheduledRestart | null>(null);
 
@@ -606,6 +608,7 @@ function ScheduledRestartsSection({ appId }: { appId: string }) {
       queryClient.invalidateQueries({ queryKey: ["scheduledRestarts", appId] });
       setShowCreateDialog(false);
       setFormCron("0 3 * * *");
+      setFormTimezone("");
       setFormEnabled(true);
     },
     onError: (error: Error) => {
@@ -690,6 +693,11 @@ function ScheduledRestartsSection({ appId }: { appId: string }) {
                         <code className="text-xs bg-muted px-2 py-0.5 rounded">
                           {restart.cron_expression}
                         </code>
+                        {restart.timezone && (
+                          <span className="ml-2 text-xs text-muted-foreground">
+                            {restart.timezone}
+                          </span>
+                        )}
                       </TableCell>
                       <TableCell>
                         <div className="flex items-center gap-2">
@@ -766,6 +774,14 @@ function ScheduledRestartsSection({ appId }: { appId: string }) {
                 ))}
               </div>
             </div>
+            <div className="space-y-2">
+              <Label htmlFor="restart-timezone">Timezone</Label>
+              <TimezoneInput
+                id="restart-timezone"
+                value={formTimezone}
+                onChange={setFormTimezone}
+              />
+            </div>
             <div className="flex items-center space-x-2">
               <Switch
                 id="enabled"
@@ -784,6 +800,7 @@ function ScheduledRestartsSection({ appId }: { appId: string }) {
                 createMutation.mutate({
                   cron_expression: formCron,
                   enabled: formEnabled,
+                  timezone: formTimezone.trim() || undefined,
                 })
               }
               disabled={!formCron || createMutation.isPending}
@@ -18,7 +18,7 @@ import { MoveToProjectCard } from "@/components/move-to-project-card";
 import { AppSharingCard } from "@/components/app-sharing-card";
 import { Zap, Copy, Check } from "lucide-react";
 import { api } from "@/lib/api";
-import type { App, AppEnvironment, UpdateAppRequest } from "@/types/api";
+import type { App, AppEnvironment, AppType, BandwidthLimitAction, UpdateAppRequest } from "@/types/api";
 
 function getWebhookProvider(gitUrl: string): string {
   if (gitUrl.includes("github.com")) return "github";
@@ -136,7 +136,15 @@ export default function AppSettingsGeneral() {
     port: app.port,
     environment: app.environment || "development",
     healthcheck: app.healthcheck || "",
+    app_type: app.app_type ?? "web",
+    metrics_port: app.metrics_port != null ? String(app.metrics_port) : "",
+    metrics_path: app.metrics_path || "",
+    idle_timeout_minutes: app.idle_timeout_minutes != null ? String(app.idle_timeout_minutes) : "",
+    bandwidth_limit_gb: app.bandwidth_limit_gb != null ? String(app.bandwidth_limit_gb) : "",
+    bandwidth_limit_action: app.bandwidth_limit_action ?? "notify",
+    bandwidth_throttle_kbps: app.bandwidth_throttle_kbps != null ? String(app.bandwidth_throttle_kbps) : "",
   });
+  const isWorker = generalForm.app_type === "worker";
 
   const handleGeneralSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -149,6 +157,13 @@ export default function AppSettingsGeneral() {
         port: generalForm.port,
         environment: generalForm.environment as AppEnvironment,
         healthcheck: generalForm.healthcheck,
+        app_type: generalForm.app_type,
+        metrics_port: parseInt(generalForm.metrics_port) || 0,
+        metrics_path: generalForm.metrics_path,
+        idle_timeout_minutes: parseInt(generalForm.idle_timeout_minutes) || 0,
+        bandwidth_limit_gb: parseInt(generalForm.bandwidth_limit_gb) || 0,
+        bandwidth_limit_action: generalForm.bandwidth_limit_action,
+        bandwidth_throttle_kbps: parseInt(generalForm.bandwidth_throttle_kbps) || 0,
       };
       // Setting a git URL on a non-registry app implies git-based deploys.
       // This converts an upload-created app into a git app so pushes deploy it.
@@ -216,6 +231,7 @@ export default function AppSettingsGeneral() {
                 <Input
                   id="port"
                   type="number"
+                  disabled={isWorker}
                   value={generalForm.port}
                   onChange={(e) => setGeneralForm({ ...generalForm, port: parseInt(e.target.value) || 0 })}
                 />
@@ -246,6 +262,7 @@ export default function AppSettingsGeneral() {
                 <Input
                   id="healthcheck"
                   placeholder="/health"
+                  disabled={isWorker}
                   value={generalForm.healthcheck}
                   onChange={(e) => setGeneralForm({ ...generalForm, healthcheck: e.target.value })}
                 />
@@ -255,6 +272,123 @@ export default function AppSettingsGeneral() {
               </div>
             </div>
 
+            <div className="grid gap-4 md:grid-cols-2">
+              <div className="space-y-2">
+                <Label htmlFor="metrics_port">Metrics Port</Label>
+                <Input
+                  id="metrics_port"
+                  type="number"
+                  min={1}
+                  max={65535}
+                  placeholder="Not scraped"
+                  value={generalForm.metrics_port}
+                  onChange={(e) => setGeneralForm({ ...generalForm, metrics_port: e.target.value })}
+                />
+                <p className="text-xs text-muted-foreground">
+                  Container port serving Prometheus metrics. The Grafana + Prometheus template scrapes it automatically.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="metrics_path">Metrics Path</Label>
+                <Input
+                  id="metrics_path"
+                  placeholder="/metrics"
+                  value={generalForm.metrics_path}
+                  onChange={(e) => setGeneralForm({ ...generalForm, metrics_path: e.target.value })}
+                />
+              </div>
+            </div>
+
+            <div className="grid gap-4 md:grid-cols-2">
+              <div className="space-y-2">
+                <Label htmlFor="idle_timeout_minutes">Scale to Zero After (minutes)</Label>
+                <Input
+                  id="idle_timeout_minutes"
+                  type="number"
+                  min={1}
+                  placeholder="Always running"
+                  disabled={isWorker}
+                  value={generalForm.idle_timeout_minutes}
+                  onChange={(e) => setGeneralForm({ ...generalForm, idle_timeout_minutes: e.target.value })}
+                />
+                <p className="text-xs text-muted-foreground">
+                  Stop the app after this long without requests. The next request starts it again and waits until it is healthy.
+                  {app.sleeping_since && " The app is sleeping now."}
+                </p>
+              </div>
+            </div>
+
+            <div className="grid gap-4 md:grid-cols-3">
+              <div className="space-y-2">
+                <Label htmlFor="bandwidth_limit_gb">Monthly Bandwidth Limit (GB)</Label>
+                <Input
+                  id="bandwidth_limit_gb"
+                  type="number"
+                  min={1}
+                  placeholder="Unlimited"
+                  disabled={isWorker}
+                  value={generalForm.bandwidth_limit_gb}
+                  onChange={(e) => setGeneralForm({ ...generalForm, bandwidth_limit_gb: e.target.value })}
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="bandwidth_limit_action">When Exceeded</Label>
+                <Select
+                  value={generalForm.bandwidth_limit_action}
+                  onValueChange={(value) =>
+                    setGeneralForm({ ...generalForm, bandwidth_limit_action: value as BandwidthLimitAction })
+                  }
+                >
+                  <SelectTrigger id="bandwidth_limit_action">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="notify">Notify</SelectItem>
+                    <SelectItem value="throttle">Notify and throttle</SelectItem>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="bandwidth_throttle_kbps">Throttle Rate (kbps)</Label>
+                <Input
+                  id="bandwidth_throttle_kbps"
+                  type="number"
+                  min={8}
+                  placeholder="1000"
+                  disabled={generalForm.bandwidth_limit_action !== "throttle"}
+                  value={generalForm.bandwidth_throttle_kbps}
+                  onChange={(e) => setGeneralForm({ ...generalForm, bandwidth_throttle_kbps: e.target.value })}
+                />
+              </div>
+              <p className="text-xs text-muted-foreground md:col-span-3">
+                Counts request and response bytes served through the proxy each calendar month (UTC). Once the limit is
+                exceeded a bandwidth notification is sent and, when throttling, the app's domains are slowed down until the
+                month ends.
+              </p>
+            </div>
+
+            <div className="grid gap-4 md:grid-cols-2">
+              <div className="space-y-2">
+                <Label htmlFor="app_type">App Type</Label>
+                <Select
+                  value={generalForm.app_type}
+                  onValueChange={(value) => setGeneralForm({ ...generalForm, app_type: value as AppType })}
+                >
+                  <SelectTrigger id="app_type">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="web">Web service</SelectItem>
+                    <SelectItem value="worker">Worker (background process)</SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-xs text-muted-foreground">
+                  Workers publish no port and get no domain or proxy route. Their health
+                  check is the process staying up, or the command health check if set.
+                </p>
+              </div>
+            </div>
+
             <Button type="submit" disabled={isSubmitting}>
               {isSubmitting ? "Saving..." : "Save Changes"}
             </Button>
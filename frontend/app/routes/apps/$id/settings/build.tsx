@@ -15,14 +15,15 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
-import { Sparkles, FileCode, Package, Zap, Cloud, Lock, Plus, Trash2, AlertTriangle, Github, Cpu, Wand2, Copy, RefreshCw } from "lucide-react";
+import { Sparkles, FileCode, Package, Zap, Cloud, Layers, Lock, Plus, Trash2, AlertTriangle, Github, Cpu, Wand2, Copy, RefreshCw } from "lucide-react";
 import { Checkbox } from "@/components/ui/checkbox";
 import { DockerRegistryCard } from "@/components/docker-registry-card";
+import { DockerfileGeneratorCard } from "@/components/dockerfile-generator-card";
 import { Badge } from "@/components/ui/badge";
 import { api } from "@/lib/api";
 import { aiApi } from "@/lib/api/ai";
 import { buildServersApi, type BuildServer } from "@/lib/api/build-servers";
-import type { App, BuildType, BuildSecret, NixpacksConfig, UpdateAppRequest } from "@/types/api";
+import type { App, BuildType, BuildSecret, CnbBuilder, CnbConfig, ManagedDatabase, NixpacksConfig, PreviewDatabaseMode, StaticSiteConfig, UpdateAppRequest } from "@/types/api";
 
 export default function AppSettingsBuild() {
   const { app } = useOutletContext<{ app: App }>();
@@ -35,8 +36,13 @@ export default function AppSettingsBuild() {
     base_directory: app.base_directory || "",
     build_target: app.build_target || "",
     watch_paths: app.watch_paths || "",
+    webhook_branch_regex: app.webhook_branch_regex || "",
+    webhook_ignore_authors: app.webhook_ignore_authors || "",
+    webhook_ignore_paths: app.webhook_ignore_paths || "",
     custom_docker_options: app.custom_docker_options || "",
     custom_container_name: app.custom_container_name || "",
+    compose_file: app.compose_file || "",
+    compose_service: app.compose_service || "",
   });
 
   // Git clone options
@@ -52,6 +58,19 @@ export default function AppSettingsBuild() {
 
   const [buildType, setBuildType] = useState<BuildType>(app.build_type || "dockerfile");
   const [previewEnabled, setPreviewEnabled] = useState(app.preview_enabled || false);
+  const [previewMaxLifetime, setPreviewMaxLifetime] = useState(
+    app.preview_max_lifetime_hours != null ? String(app.preview_max_lifetime_hours) : ""
+  );
+  const [previewIdleTimeout, setPreviewIdleTimeout] = useState(
+    app.preview_idle_timeout_hours != null ? String(app.preview_idle_timeout_hours) : ""
+  );
+  const [previewDatabase, setPreviewDatabase] = useState<PreviewDatabaseMode | "none">(
+    app.preview_database || "none"
+  );
+  const [previewDatabaseSourceId, setPreviewDatabaseSourceId] = useState(
+    app.preview_database_source_id || ""
+  );
+  const [previewDatabaseEnv, setPreviewDatabaseEnv] = useState(app.preview_database_env || "");
   const [publishDirectory, setPublishDirectory] = useState(app.publish_directory || "dist");
   const [buildServerId, setBuildServerId] = useState<string>(app.build_server_id || "");
 
@@ -84,6 +103,14 @@ export default function AppSettingsBuild() {
     queryFn: () => buildServersApi.list(),
   });
 
+  // Preview databases are modelled on a database of the app's team
+  const { data: teamDatabases = [] } = useQuery<ManagedDatabase[]>({
+    queryKey: ["databases-for-link", app.team_id ?? ""],
+    queryFn: () => api.getDatabases(app.team_id ? { teamId: app.team_id } : {}),
+    enabled: previewEnabled,
+  });
+  const previewDatabaseSources = teamDatabases.filter((db) => db.team_id === app.team_id);
+
   // Build Secrets state
   const parseBuildSecrets = (json: string | null): BuildSecret[] => {
     if (!json) return [];
@@ -124,12 +151,55 @@ export default function AppSettingsBuild() {
   const [nixpacksConfig, setNixpacksConfig] = useState<NixpacksConfig>(
     parseNixpacksConfig(app.nixpacks_config)
   );
+  const [nixpacksProviders, setNixpacksProviders] = useState(
+    (parseNixpacksConfig(app.nixpacks_config).providers || []).join(", ")
+  );
+
+  const parseCnbConfig = (json: string | null): CnbConfig => {
+    if (!json) return {};
+    try {
+      return JSON.parse(json);
+    } catch {
+      return {};
+    }
+  };
+
+  const [cnbConfig, setCnbConfig] = useState<CnbConfig>(parseCnbConfig(app.cnb_config));
+  const [cnbBuildpacks, setCnbBuildpacks] = useState(
+    (parseCnbConfig(app.cnb_config).buildpacks || []).join(", ")
+  );
+
+  const parseStaticConfig = (json: string | null): StaticSiteConfig => {
+    if (!json) return {};
+    try {
+      return JSON.parse(json);
+    } catch {
+      return {};
+    }
+  };
+
+  const [staticConfig, setStaticConfig] = useState<StaticSiteConfig>(
+    parseStaticConfig(app.static_config)
+  );
 
   useEffect(() => {
     setBuildType(app.build_type || "dockerfile");
     setPreviewEnabled(app.preview_enabled || false);
+    setPreviewMaxLifetime(
+      app.preview_max_lifetime_hours != null ? String(app.preview_max_lifetime_hours) : ""
+    );
+    setPreviewIdleTimeout(
+      app.preview_idle_timeout_hours != null ? String(app.preview_idle_timeout_hours) : ""
+    );
+    setPreviewDatabase(app.preview_database || "none");
+    setPreviewDatabaseSourceId(app.preview_database_source_id || "");
+    setPreviewDatabaseEnv(app.preview_database_env || "");
     setPublishDirectory(app.publish_directory || "dist");
     setNixpacksConfig(parseNixpacksConfig(app.nixpacks_config));
+    setNixpacksProviders((parseNixpacksConfig(app.nixpacks_config).providers || []).join(", "));
+    setCnbConfig(parseCnbConfig(app.cnb_config));
+    setCnbBuildpacks((parseCnbConfig(app.cnb_config).buildpacks || []).join(", "));
+    setStaticConfig(parseStaticConfig(app.static_config));
     setBuildServerId(app.build_server_id || "");
     setSelectedPlatforms(parsePlatforms(app.build_platforms));
     setGitSubmodules(app.git_submodules || false);
@@ -142,8 +212,10 @@ export default function AppSettingsBuild() {
     setBuildForm(prev => ({
       ...prev,
       custom_container_name: app.custom_container_name || "",
+      compose_file: app.compose_file || "",
+      compose_service: app.compose_service || "",
     }));
-  }, [app.build_type, app.preview_enabled, app.publish_directory, app.nixpacks_config, app.build_server_id, app.build_platforms, app.git_submodules, app.git_lfs, app.shallow_clone, app.disable_build_cache, app.include_source_commit, app.custom_container_name, app.is_static_site, app.inline_dockerfile]);
+  }, [app.build_type, app.preview_enabled, app.preview_max_lifetime_hours, app.preview_idle_timeout_hours, app.preview_database, app.preview_database_source_id, app.preview_database_env, app.publish_directory, app.nixpacks_config, app.cnb_config, app.static_config, app.build_server_id, app.build_platforms, app.git_submodules, app.git_lfs, app.shallow_clone, app.disable_build_cache, app.include_source_commit, app.custom_container_name, app.is_static_site, app.inline_dockerfile, app.compose_file, app.compose_service]);
 
   const handleBuildSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -157,6 +229,9 @@ export default function AppSettingsBuild() {
         if (nixpacksConfig.start_cmd) nixpacksConfigToSend.start_cmd = nixpacksConfig.start_cmd;
         if (nixpacksConfig.packages?.length) nixpacksConfigToSend.packages = nixpacksConfig.packages;
         if (nixpacksConfig.apt_packages?.length) nixpacksConfigToSend.apt_packages = nixpacksConfig.apt_packages;
+        const providers = nixpacksProviders.split(",").map((p) => p.trim()).filter(Boolean);
+        if (providers.length) nixpacksConfigToSend.providers = providers;
+        if (nixpacksConfig.version?.trim()) nixpacksConfigToSend.version = nixpacksConfig.version.trim();
         if (Object.keys(nixpacksConfigToSend).length === 0) {
           nixpacksConfigToSend = undefined;
         }
@@ -169,17 +244,50 @@ export default function AppSettingsBuild() {
           ? ""
           : platformsArray.join(",");
 
+      const cnbConfigToSend: CnbConfig = {};
+      if (cnbConfig.builder) cnbConfigToSend.builder = cnbConfig.builder;
+      if (cnbConfig.builder === "custom") cnbConfigToSend.custom_builder = cnbConfig.custom_builder;
+      const buildpacks = cnbBuildpacks.split(",").map((bp) => bp.trim()).filter(Boolean);
+      if (buildpacks.length) cnbConfigToSend.buildpacks = buildpacks;
+      if (cnbConfig.pull_policy) cnbConfigToSend.pull_policy = cnbConfig.pull_policy;
+
+      const staticConfigToSend: StaticSiteConfig = {};
+      if (staticConfig.build_command?.trim()) staticConfigToSend.build_command = staticConfig.build_command.trim();
+      if (staticConfig.install_command?.trim()) staticConfigToSend.install_command = staticConfig.install_command.trim();
+      if (staticConfig.build_image?.trim()) staticConfigToSend.build_image = staticConfig.build_image.trim();
+      if (staticConfig.spa_mode === false) staticConfigToSend.spa_mode = false;
+
       const updates: UpdateAppRequest = {
         dockerfile: buildType === "dockerfile" ? buildForm.dockerfile : undefined,
         dockerfile_path: buildType === "dockerfile" ? buildForm.dockerfile_path : undefined,
         base_directory: buildForm.base_directory,
         build_target: buildType === "dockerfile" ? buildForm.build_target : undefined,
         watch_paths: buildForm.watch_paths,
+        // Webhook filters, empty string clears them
+        webhook_branch_regex: buildForm.webhook_branch_regex,
+        webhook_ignore_authors: buildForm.webhook_ignore_authors,
+        webhook_ignore_paths: buildForm.webhook_ignore_paths,
         custom_docker_options: buildType === "dockerfile" ? buildForm.custom_docker_options : undefined,
         build_type: buildType,
         nixpacks_config: nixpacksConfigToSend,
+        // Empty object clears the buildpacks configuration (auto-select builder)
+        cnb_config: buildType === "cnb" ? cnbConfigToSend : undefined,
         publish_directory: buildType === "staticsite" ? publishDirectory : undefined,
+        // Empty object clears the static site settings (auto-detected defaults)
+        static_config: buildType === "staticsite" ? staticConfigToSend : undefined,
+        // Empty strings clear them (auto-detected file, first service)
+        compose_file: buildType === "docker-compose" ? buildForm.compose_file : undefined,
+        compose_service: buildType === "docker-compose" ? buildForm.compose_service : undefined,
         preview_enabled: previewEnabled,
+        // Empty (0) removes the limit
+        preview_max_lifetime_hours:
+          previewMaxLifetime.trim() === "" ? 0 : Number(previewMaxLifetime),
+        preview_idle_timeout_hours:
+          previewIdleTimeout.trim() === "" ? 0 : Number(previewIdleTimeout),
+        // Empty strings stop provisioning preview databases / clear to DATABASE_URL
+        preview_database: previewDatabase === "none" ? "" : previewDatabase,
+        preview_database_source_id: previewDatabase === "none" ? "" : previewDatabaseSourceId,
+        preview_database_env: previewDatabaseEnv.trim(),
         // Empty string clears the build server assignment on the backend
         build_server_id: buildServerId || "",
         build_platforms: buildPlatformsValue || undefined,
@@ -280,7 +388,7 @@ export default function AppSettingsBuild() {
             {/* Build Type Selection */}
             <div className="space-y-3">
               <Label>Build Type</Label>
-              <div className="grid grid-cols-3 md:grid-cols-5 gap-3">
+              <div className="grid grid-cols-3 md:grid-cols-6 gap-3">
                 <button
                   type="button"
                   onClick={() => setBuildType("nixpacks")}
@@ -356,6 +464,21 @@ export default function AppSettingsBuild() {
                     HTML/CSS/JS
                   </span>
                 </button>
+                <button
+                  type="button"
+                  onClick={() => setBuildType("docker-compose")}
+                  className={`flex flex-col items-center gap-2 p-4 rounded-lg border-2 transition-colors ${
+                    buildType === "docker-compose"
+                      ? "border-primary bg-primary/5"
+                      : "border-border hover:border-muted-foreground/50"
+                  }`}
+                >
+                  <Layers className="h-6 w-6" />
+                  <span className="text-sm font-medium">Compose</span>
+                  <span className="text-xs text-muted-foreground text-center">
+                    Multi-service
+                  </span>
+                </button>
               </div>
             </div>
 
@@ -395,6 +518,34 @@ export default function AppSettingsBuild() {
                     />
                   </div>
                 </div>
+                <div className="grid gap-4 md:grid-cols-2">
+                  <div className="space-y-2">
+                    <Label htmlFor="nixpacks_providers">Providers</Label>
+                    <Input
+                      id="nixpacks_providers"
+                      placeholder="node, python"
+                      value={nixpacksProviders}
+                      onChange={(e) => setNixpacksProviders(e.target.value)}
+                      className="font-mono"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Comma-separated providers to use instead of auto-detection. Use <code>...</code> to keep the detected ones.
+                    </p>
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="nixpacks_version">Nixpacks Version</Label>
+                    <Input
+                      id="nixpacks_version"
+                      placeholder="Installed version"
+                      value={nixpacksConfig.version || ""}
+                      onChange={(e) => setNixpacksConfig({ ...nixpacksConfig, version: e.target.value || undefined })}
+                      className="font-mono"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Pin builds to a release such as <code>1.29.0</code>. It must be installed on the server as <code>nixpacks-1.29.0</code> or be the default <code>nixpacks</code>.
+                    </p>
+                  </div>
+                </div>
               </div>
             )}
 
@@ -416,26 +567,170 @@ export default function AppSettingsBuild() {
               <div className="space-y-4 p-4 bg-muted/50 rounded-lg">
                 <p className="text-sm text-muted-foreground">
                   Cloud Native Buildpacks (Paketo/Heroku) create production-ready, security-focused container images
-                  without requiring a Dockerfile. Auto-selects the best builder for your project.
+                  without requiring a Dockerfile. Auto-selects the best builder for your project unless you pick one below.
                 </p>
                 <p className="text-xs text-muted-foreground">
                   <strong>Note:</strong> Requires Pack CLI installed. Supports Java, Node.js, Python, Go, Ruby, .NET, and more.
                 </p>
+                <div className="grid gap-4 md:grid-cols-2">
+                  <div className="space-y-2">
+                    <Label htmlFor="cnb_builder">Builder</Label>
+                    <Select
+                      value={cnbConfig.builder || "__auto__"}
+                      onValueChange={(v) =>
+                        setCnbConfig({ ...cnbConfig, builder: v === "__auto__" ? undefined : (v as CnbBuilder) })
+                      }
+                    >
+                      <SelectTrigger id="cnb_builder">
+                        <SelectValue placeholder="Auto-select" />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="__auto__">Auto-select</SelectItem>
+                        <SelectItem value="paketobase">Paketo Base</SelectItem>
+                        <SelectItem value="paketofull">Paketo Full</SelectItem>
+                        <SelectItem value="paketotiny">Paketo Tiny</SelectItem>
+                        <SelectItem value="heroku24">Heroku 24</SelectItem>
+                        <SelectItem value="heroku22">Heroku 22</SelectItem>
+                        <SelectItem value="custom">Custom image</SelectItem>
+                      </SelectContent>
+                    </Select>
+                  </div>
+                  {cnbConfig.builder === "custom" && (
+                    <div className="space-y-2">
+                      <Label htmlFor="cnb_custom_builder">Builder Image</Label>
+                      <Input
+                        id="cnb_custom_builder"
+                        placeholder="registry.example.com/builders/base:latest"
+                        value={cnbConfig.custom_builder || ""}
+                        onChange={(e) => setCnbConfig({ ...cnbConfig, custom_builder: e.target.value || undefined })}
+                        className="font-mono"
+                      />
+                    </div>
+                  )}
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="cnb_buildpacks">Additional Buildpacks</Label>
+                  <Input
+                    id="cnb_buildpacks"
+                    placeholder="paketo-buildpacks/nodejs, paketo-buildpacks/procfile"
+                    value={cnbBuildpacks}
+                    onChange={(e) => setCnbBuildpacks(e.target.value)}
+                    className="font-mono"
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Comma-separated buildpack references passed to <code>pack build --buildpack</code>
+                  </p>
+                </div>
               </div>
             )}
 
             {/* Static options */}
             {buildType === "staticsite" && (
-              <div className="space-y-2">
-                <Label htmlFor="publish_directory">Publish Directory</Label>
-                <Input
-                  id="publish_directory"
-                  placeholder="dist, build, public"
-                  value={publishDirectory}
-                  onChange={(e) => setPublishDirectory(e.target.value)}
-                />
+              <div className="space-y-4">
+                <div className="grid gap-4 md:grid-cols-2">
+                  <div className="space-y-2">
+                    <Label htmlFor="static_build_command">Build Command</Label>
+                    <Input
+                      id="static_build_command"
+                      placeholder="npm run build, hugo --minify"
+                      value={staticConfig.build_command || ""}
+                      onChange={(e) => setStaticConfig({ ...staticConfig, build_command: e.target.value })}
+                      className="font-mono text-sm"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Defaults to the package manager&apos;s build script
+                    </p>
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="publish_directory">Output Directory</Label>
+                    <Input
+                      id="publish_directory"
+                      placeholder="dist, build, out, public"
+                      value={publishDirectory}
+                      onChange={(e) => setPublishDirectory(e.target.value)}
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Directory containing your built static files
+                    </p>
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="static_install_command">Install Command</Label>
+                    <Input
+                      id="static_install_command"
+                      placeholder="npm ci"
+                      value={staticConfig.install_command || ""}
+                      onChange={(e) => setStaticConfig({ ...staticConfig, install_command: e.target.value })}
+                      className="font-mono text-sm"
+                    />
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="static_build_image">Build Image</Label>
+                    <Input
+                      id="static_build_image"
+                      placeholder="node:20-alpine"
+                      value={staticConfig.build_image || ""}
+                      onChange={(e) => setStaticConfig({ ...staticConfig, build_image: e.target.value })}
+                      className="font-mono text-sm"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Set for generators without a package.json, e.g. hugomods/hugo:exts
+                    </p>
+                  </div>
+                </div>
+                <div className="flex items-center justify-between p-4 rounded-lg border">
+                  <div className="space-y-0.5">
+                    <Label htmlFor="static_spa_mode" className="text-base">Single-Page App</Label>
+                    <p className="text-sm text-muted-foreground">
+                      Serve index.html for unknown paths so client-side routing works
+                    </p>
+                  </div>
+                  <Switch
+                    id="static_spa_mode"
+                    checked={staticConfig.spa_mode !== false}
+                    onCheckedChange={(checked) => setStaticConfig({ ...staticConfig, spa_mode: checked })}
+                  />
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  The output is packaged into an NGINX image and served on the app&apos;s port.
+                </p>
+              </div>
+            )}
+
+            {/* Docker Compose options */}
+            {buildType === "docker-compose" && (
+              <div className="space-y-4">
+                <div className="grid gap-4 md:grid-cols-2">
+                  <div className="space-y-2">
+                    <Label htmlFor="compose_file">Compose File</Label>
+                    <Input
+                      id="compose_file"
+                      placeholder="docker-compose.yml"
+                      value={buildForm.compose_file}
+                      onChange={(e) => setBuildForm({ ...buildForm, compose_file: e.target.value })}
+                      className="font-mono text-sm"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Relative to the base directory. Defaults to docker-compose.yml or compose.yaml
+                    </p>
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="compose_service">Web Service</Label>
+                    <Input
+                      id="compose_service"
+                      placeholder="web"
+                      value={buildForm.compose_service}
+                      onChange={(e) => setBuildForm({ ...buildForm, compose_service: e.target.value })}
+                      className="font-mono text-sm"
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Service that receives the app&apos;s domains. Defaults to the first service
+                    </p>
+                  </div>
+                </div>
                 <p className="text-xs text-muted-foreground">
-                  Directory containing your built static files
+                  The stack is deployed with <code>docker compose up</code> on every deployment.
+                  The app&apos;s environment variables can be used as <code>${"{VAR}"}</code> in the
+                  compose file, and the web service must listen on the app&apos;s port.
                 </p>
               </div>
             )}
@@ -539,6 +834,53 @@ export default function AppSettingsBuild() {
               </div>
             </div>
 
+            {/* Webhook filters (regular expressions) */}
+            <div className="grid gap-4 md:grid-cols-3">
+              <div className="space-y-2">
+                <Label htmlFor="webhook_branch_regex">Additional Branches</Label>
+                <Input
+                  id="webhook_branch_regex"
+                  placeholder="^release/.*$"
+                  value={buildForm.webhook_branch_regex}
+                  onChange={(e) => setBuildForm({ ...buildForm, webhook_branch_regex: e.target.value })}
+                  className="font-mono"
+                />
+                <p className="text-xs text-muted-foreground">
+                  Pushes to matching branches also deploy this app
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="webhook_ignore_authors">Ignored Authors</Label>
+                <Input
+                  id="webhook_ignore_authors"
+                  placeholder="\[bot\]$"
+                  value={buildForm.webhook_ignore_authors}
+                  onChange={(e) => setBuildForm({ ...buildForm, webhook_ignore_authors: e.target.value })}
+                  className="font-mono"
+                />
+                <p className="text-xs text-muted-foreground">
+                  Pushes by matching commit authors don't deploy
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="webhook_ignore_paths">Ignored Paths</Label>
+                <Input
+                  id="webhook_ignore_paths"
+                  placeholder="^(docs/|.*\.md$)"
+                  value={buildForm.webhook_ignore_paths}
+                  onChange={(e) => setBuildForm({ ...buildForm, webhook_ignore_paths: e.target.value })}
+                  className="font-mono"
+                />
+                <p className="text-xs text-muted-foreground">
+                  Pushes changing only matching files don't deploy
+                </p>
+              </div>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              Filters are regular expressions. Commits with <code>[skip ci]</code> or{" "}
+              <code>[skip deploy]</code> in their message never trigger a deployment.
+            </p>
+
             {/* Build Server selection */}
             <div className="space-y-2">
               <Label htmlFor="build-server">Build Server</Label>
@@ -617,6 +959,98 @@ export default function AppSettingsBuild() {
                 onCheckedChange={setPreviewEnabled}
               />
             </div>
+            {previewEnabled && (
+              <div className="grid gap-4 md:grid-cols-2">
+                <div className="space-y-2">
+                  <Label htmlFor="preview_max_lifetime_hours">Preview Max Lifetime (hours)</Label>
+                  <Input
+                    id="preview_max_lifetime_hours"
+                    type="number"
+                    min={1}
+                    max={720}
+                    placeholder="No limit"
+                    value={previewMaxLifetime}
+                    onChange={(e) => setPreviewMaxLifetime(e.target.value)}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Previews are torn down this long after they were first deployed.
+                  </p>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="preview_idle_timeout_hours">Preview Idle Timeout (hours)</Label>
+                  <Input
+                    id="preview_idle_timeout_hours"
+                    type="number"
+                    min={1}
+                    max={720}
+                    placeholder="No limit"
+                    value={previewIdleTimeout}
+                    onChange={(e) => setPreviewIdleTimeout(e.target.value)}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Previews without requests or pushes for this long are torn down. A warning
+                    notification is sent first; a new push deploys the preview again.
+                  </p>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="preview_database">Preview Database</Label>
+                  <Select
+                    value={previewDatabase}
+                    onValueChange={(v) => setPreviewDatabase(v as PreviewDatabaseMode | "none")}
+                  >
+                    <SelectTrigger id="preview_database">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="none">None</SelectItem>
+                      <SelectItem value="empty">Empty database</SelectItem>
+                      <SelectItem value="schema">Copy of the schema</SelectItem>
+                    </SelectContent>
+                  </Select>
+                  <p className="text-xs text-muted-foreground">
+                    Each preview gets a throwaway database, destroyed with the preview. Schema
+                    copies hold the source&apos;s tables without their rows.
+                  </p>
+                </div>
+                {previewDatabase !== "none" && (
+                  <>
+                    <div className="space-y-2">
+                      <Label htmlFor="preview_database_source">Source Database</Label>
+                      <Select
+                        value={previewDatabaseSourceId}
+                        onValueChange={setPreviewDatabaseSourceId}
+                      >
+                        <SelectTrigger id="preview_database_source">
+                          <SelectValue placeholder="Select a database" />
+                        </SelectTrigger>
+                        <SelectContent>
+                          {previewDatabaseSources.map((db) => (
+                            <SelectItem key={db.id} value={db.id}>
+                              {db.name} ({db.db_type} {db.version})
+                            </SelectItem>
+                          ))}
+                        </SelectContent>
+                      </Select>
+                      <p className="text-xs text-muted-foreground">
+                        Preview databases use this database&apos;s type, version and image.
+                      </p>
+                    </div>
+                    <div className="space-y-2">
+                      <Label htmlFor="preview_database_env">Connection URL Variable</Label>
+                      <Input
+                        id="preview_database_env"
+                        placeholder="DATABASE_URL"
+                        value={previewDatabaseEnv}
+                        onChange={(e) => setPreviewDatabaseEnv(e.target.value)}
+                      />
+                      <p className="text-xs text-muted-foreground">
+                        Overrides this variable in the preview&apos;s environment.
+                      </p>
+                    </div>
+                  </>
+                )}
+              </div>
+            )}
 
             {/* Git Clone Options */}
             <div className="space-y-3">
@@ -639,7 +1073,8 @@ export default function AppSettingsBuild() {
                   <div className="space-y-0.5">
                     <Label htmlFor="git-submodules" className="text-base">Git Submodules</Label>
                     <p className="text-sm text-muted-foreground">
-                      Pass <code className="font-mono text-xs bg-muted px-1 py-0.5 rounded">--recurse-submodules</code> to clone all submodules
+                      Pass <code className="font-mono text-xs bg-muted px-1 py-0.5 rounded">--recurse-submodules</code> to clone all submodules. Private submodules on the same host use the
+                      app's SSH key or git provider token
                     </p>
                   </div>
                   <Switch
@@ -745,6 +1180,7 @@ export default function AppSettingsBuild() {
             Injected during <code className="font-mono text-xs bg-muted px-1 py-0.5 rounded">docker build</code> via BuildKit{" "}
             <code className="font-mono text-xs bg-muted px-1 py-0.5 rounded">--secret</code>. Not stored in image layers. Use{" "}
             <code className="font-mono text-xs bg-muted px-1 py-0.5 rounded">RUN --mount=type=secret,id=KEY</code> in your Dockerfile.
+            Values are stored encrypted and never shown again; leave a masked value as is to keep it.
           </CardDescription>
         </CardHeader>
         <CardContent className="space-y-4">
@@ -868,6 +1304,11 @@ export default function AppSettingsBuild() {
         </CardContent>
       </Card>
 
+      {/* Dockerfile Generator (git apps only) */}
+      {app.git_url && !app.docker_image && app.deployment_source !== "upload" && (
+        <DockerfileGeneratorCard app={app} />
+      )}
+
       {/* AI Dockerfile Optimizer */}
       <Card>
         <CardHeader>
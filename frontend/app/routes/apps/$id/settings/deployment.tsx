@@ -8,6 +8,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Switch } from "@/components/ui/switch";
 import { Badge } from "@/components/ui/badge";
+import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import {
   Dialog,
   DialogContent,
@@ -17,6 +18,9 @@ import {
   DialogTitle,
 } from "@/components/ui/dialog";
 import { Shield, Snowflake, Plus, Trash2 } from "lucide-react";
+import { PowerScheduleCard } from "@/components/power-schedule-card";
+import { PrewarmCard } from "@/components/prewarm-card";
+import { TimezoneInput } from "@/components/timezone-input";
 import { api } from "@/lib/api";
 import type { App, DeploymentFreezeWindow, CreateFreezeWindowRequest } from "@/types/api";
 
@@ -41,12 +45,20 @@ export default function AppSettingsDeployment() {
     days_of_week: "0,1,2,3,4,5,6",
     app_id: app.id,
   });
+  const [freezeWindowKind, setFreezeWindowKind] = useState<"recurring" | "range">("recurring");
+  const [freezeRange, setFreezeRange] = useState({ start: "", end: "" });
   const [isSavingFreezeWindow, setIsSavingFreezeWindow] = useState(false);
 
   // Rollback retention state
   const [rollbackRetentionCount, setRollbackRetentionCount] = useState(
     app.rollback_retention_count ?? 10
   );
+  const [maxDeployments, setMaxDeployments] = useState(
+    app.max_deployments != null ? String(app.max_deployments) : ""
+  );
+  const [keepImages, setKeepImages] = useState(
+    app.keep_images != null ? String(app.keep_images) : ""
+  );
   const [isSavingRetention, setIsSavingRetention] = useState(false);
 
   // Sync approval/maintenance from app data
@@ -60,6 +72,11 @@ export default function AppSettingsDeployment() {
     setRollbackRetentionCount(app.rollback_retention_count ?? 10);
   }, [app.rollback_retention_count]);
 
+  useEffect(() => {
+    setMaxDeployments(app.max_deployments != null ? String(app.max_deployments) : "");
+    setKeepImages(app.keep_images != null ? String(app.keep_images) : "");
+  }, [app.max_deployments, app.keep_images]);
+
   // Freeze windows query
   const { data: freezeWindows = [], refetch: refetchFreezeWindows } = useQuery<
     DeploymentFreezeWindow[]
@@ -85,20 +102,35 @@ export default function AppSettingsDeployment() {
     }
   };
 
+  const resetFreezeWindowForm = () => {
+    setFreezeWindowForm({
+      name: "",
+      start_time: "22:00",
+      end_time: "06:00",
+      days_of_week: "0,1,2,3,4,5,6",
+      app_id: app.id,
+    });
+    setFreezeWindowKind("recurring");
+    setFreezeRange({ start: "", end: "" });
+  };
+
   const handleCreateFreezeWindow = async () => {
     if (!freezeWindowForm.name.trim()) return;
     setIsSavingFreezeWindow(true);
     try {
-      await api.createFreezeWindow(freezeWindowForm);
+      await api.createFreezeWindow(
+        freezeWindowKind === "range"
+          ? {
+              ...freezeWindowForm,
+              // datetime-local values are in the browser's timezone
+              starts_at: new Date(freezeRange.start).toISOString(),
+              ends_at: new Date(freezeRange.end).toISOString(),
+            }
+          : freezeWindowForm
+      );
       toast.success("Freeze window created");
       setShowFreezeWindowDialog(false);
-      setFreezeWindowForm({
-        name: "",
-        start_time: "22:00",
-        end_time: "06:00",
-        days_of_week: "0,1,2,3,4,5,6",
-        app_id: app.id,
-      });
+      resetFreezeWindowForm();
       refetchFreezeWindows();
     } catch (err) {
       toast.error(err instanceof Error ? err.message : "Failed to create freeze window");
@@ -120,7 +152,12 @@ export default function AppSettingsDeployment() {
   const handleSaveRetention = async () => {
     setIsSavingRetention(true);
     try {
-      await api.updateApp(app.id, { rollback_retention_count: rollbackRetentionCount });
+      await api.updateApp(app.id, {
+        rollback_retention_count: rollbackRetentionCount,
+        // Empty clears the override back to the global cleanup settings
+        max_deployments: maxDeployments.trim() === "" ? 0 : Number(maxDeployments),
+        keep_images: keepImages.trim() === "" ? 0 : Number(keepImages),
+      });
       toast.success("Rollback retention saved");
       queryClient.invalidateQueries({ queryKey: ["app", app.id] });
     } catch (err) {
@@ -213,6 +250,8 @@ export default function AppSettingsDeployment() {
             </CardTitle>
             <CardDescription>
               Block deployments during specific time windows (e.g., business hours, weekends).
+              Manual deploys are refused while a window is active; deploys from pushes are held
+              and start when it ends.
             </CardDescription>
           </div>
           <Button
@@ -246,9 +285,18 @@ export default function AppSettingsDeployment() {
                       )}
                     </div>
                     <p className="text-xs text-muted-foreground">
-                      {fw.start_time} – {fw.end_time} UTC
-                      {" "}·{" "}
-                      Days: {fw.days_of_week}
+                      {fw.starts_at && fw.ends_at ? (
+                        <>
+                          {new Date(fw.starts_at).toLocaleString()} –{" "}
+                          {new Date(fw.ends_at).toLocaleString()}
+                        </>
+                      ) : (
+                        <>
+                          {fw.start_time} – {fw.end_time} {fw.timezone ?? "UTC"}
+                          {" "}·{" "}
+                          Days: {fw.days_of_week}
+                        </>
+                      )}
                     </p>
                   </div>
                   <Button
@@ -266,12 +314,19 @@ export default function AppSettingsDeployment() {
         </CardContent>
       </Card>
 
+      {/* Power Schedule */}
+      <PowerScheduleCard appId={app.id} />
+
+      {/* Pre-warmed Deployment Slot */}
+      <PrewarmCard appId={app.id} />
+
       {/* Rollback Retention */}
       <Card>
         <CardHeader>
           <CardTitle>Rollback Retention</CardTitle>
           <CardDescription>
-            Number of previous successful deployments to keep available for rollback.
+            Number of previous successful deployments to keep available for rollback, and how
+            many deployments and images the cleanup task keeps for this app.
           </CardDescription>
         </CardHeader>
         <CardContent className="space-y-4">
@@ -295,10 +350,44 @@ export default function AppSettingsDeployment() {
                 Older successful deployments will be automatically deleted.
               </p>
             </div>
-            <Button onClick={handleSaveRetention} disabled={isSavingRetention}>
-              {isSavingRetention ? "Saving..." : "Save"}
-            </Button>
           </div>
+          <div className="grid gap-4 sm:grid-cols-2">
+            <div className="space-y-2">
+              <Label htmlFor="max-deployments">Deployments Kept by Cleanup</Label>
+              <Input
+                id="max-deployments"
+                type="number"
+                min={1}
+                max={1000}
+                placeholder="Global setting"
+                value={maxDeployments}
+                onChange={(e) => setMaxDeployments(e.target.value)}
+              />
+              <p className="text-xs text-muted-foreground">
+                Overrides the instance-wide limit for this app. Also applies under disk pressure,
+                when other apps keep a single deployment.
+              </p>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="keep-images">Images Kept</Label>
+              <Input
+                id="keep-images"
+                type="number"
+                min={1}
+                max={1000}
+                placeholder="All kept deployments"
+                value={keepImages}
+                onChange={(e) => setKeepImages(e.target.value)}
+              />
+              <p className="text-xs text-muted-foreground">
+                Only the newest kept deployments keep their image. Rolling back to an older one
+                pulls the image from the registry it was pushed to.
+              </p>
+            </div>
+          </div>
+          <Button onClick={handleSaveRetention} disabled={isSavingRetention}>
+            {isSavingRetention ? "Saving..." : "Save"}
+          </Button>
         </CardContent>
       </Card>
 
@@ -308,13 +397,7 @@ export default function AppSettingsDeployment() {
         onOpenChange={(open) => {
           setShowFreezeWindowDialog(open);
           if (!open) {
-            setFreezeWindowForm({
-              name: "",
-              start_time: "22:00",
-              end_time: "06:00",
-              days_of_week: "0,1,2,3,4,5,6",
-              app_id: app.id,
-            });
+            resetFreezeWindowForm();
           }
         }}
       >
@@ -322,7 +405,8 @@ export default function AppSettingsDeployment() {
           <DialogHeader>
             <DialogTitle>Add Freeze Window</DialogTitle>
             <DialogDescription>
-              Define a time window during which deployments will be blocked. Times are in UTC.
+              Define a time window during which deployments will be blocked. Recurring times and
+              days are in the window's timezone (UTC by default).
             </DialogDescription>
           </DialogHeader>
           <div className="space-y-4 py-2">
@@ -337,53 +421,95 @@ export default function AppSettingsDeployment() {
                 }
               />
             </div>
-            <div className="grid grid-cols-2 gap-4">
-              <div className="space-y-2">
-                <Label htmlFor="fw-start">Start Time (UTC)</Label>
-                <Input
-                  id="fw-start"
-                  type="time"
-                  value={freezeWindowForm.start_time}
-                  onChange={(e) =>
-                    setFreezeWindowForm({
-                      ...freezeWindowForm,
-                      start_time: e.target.value,
-                    })
-                  }
-                />
-              </div>
-              <div className="space-y-2">
-                <Label htmlFor="fw-end">End Time (UTC)</Label>
-                <Input
-                  id="fw-end"
-                  type="time"
-                  value={freezeWindowForm.end_time}
-                  onChange={(e) =>
-                    setFreezeWindowForm({
-                      ...freezeWindowForm,
-                      end_time: e.target.value,
-                    })
-                  }
-                />
+            <Tabs
+              value={freezeWindowKind}
+              onValueChange={(value) => setFreezeWindowKind(value as "recurring" | "range")}
+            >
+              <TabsList className="grid w-full grid-cols-2">
+                <TabsTrigger value="recurring">Recurring</TabsTrigger>
+                <TabsTrigger value="range">Date range</TabsTrigger>
+              </TabsList>
+            </Tabs>
+            {freezeWindowKind === "recurring" ? (
+              <>
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="space-y-2">
+                    <Label htmlFor="fw-start">Start Time</Label>
+                    <Input
+                      id="fw-start"
+                      type="time"
+                      value={freezeWindowForm.start_time}
+                      onChange={(e) =>
+                        setFreezeWindowForm({
+                          ...freezeWindowForm,
+                          start_time: e.target.value,
+                        })
+                      }
+                    />
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="fw-end">End Time</Label>
+                    <Input
+                      id="fw-end"
+                      type="time"
+                      value={freezeWindowForm.end_time}
+                      onChange={(e) =>
+                        setFreezeWindowForm({
+                          ...freezeWindowForm,
+                          end_time: e.target.value,
+                        })
+                      }
+                    />
+                  </div>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="fw-days">Days of Week</Label>
+                  <Input
+                    id="fw-days"
+                    placeholder="0,1,2,3,4,5,6"
+                    value={freezeWindowForm.days_of_week}
+                    onChange={(e) =>
+                      setFreezeWindowForm({
+                        ...freezeWindowForm,
+                        days_of_week: e.target.value,
+                      })
+                    }
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Comma-separated: 0=Sunday, 1=Monday, ... 6=Saturday. Leave blank for all days.
+                  </p>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="fw-timezone">Timezone</Label>
+                  <TimezoneInput
+                    id="fw-timezone"
+                    value={freezeWindowForm.timezone ?? ""}
+                    onChange={(timezone) => setFreezeWindowForm({ ...freezeWindowForm, timezone })}
+                  />
+                </div>
+              </>
+            ) : (
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="fw-starts-at">Starts</Label>
+                  <Input
+                    id="fw-starts-at"
+                    type="datetime-local"
+                    value={freezeRange.start}
+                    onChange={(e) => setFreezeRange({ ...freezeRange, start: e.target.value })}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="fw-ends-at">Ends</Label>
+                  <Input
+                    id="fw-ends-at"
+                    type="datetime-local"
+                    value={freezeRange.end}
+                    onChange={(e) => setFreezeRange({ ...freezeRange, end: e.target.value })}
+                  />
+                </div>
               </div>
-            </div>
-            <div className="space-y-2">
-              <Label htmlFor="fw-days">Days of Week</Label>
-              <Input
-                id="fw-days"
-                placeholder="0,1,2,3,4,5,6"
-                value={freezeWindowForm.days_of_week}
-                onChange={(e) =>
-                  setFreezeWindowForm({
-                    ...freezeWindowForm,
-                    days_of_week: e.target.value,
-                  })
-                }
-              />
-              <p className="text-xs text-muted-foreground">
-                Comma-separated: 0=Sunday, 1=Monday, ... 6=Saturday. Leave blank for all days.
-              </p>
-            </div>
+            )}
           </div>
           <DialogFooter>
             <Button
@@ -395,7 +521,11 @@ export default function AppSettingsDeployment() {
             </Button>
             <Button
               onClick={handleCreateFreezeWindow}
-              disabled={isSavingFreezeWindow || !freezeWindowForm.name.trim()}
+              disabled={
+                isSavingFreezeWindow ||
+                !freezeWindowForm.name.trim() ||
+                (freezeWindowKind === "range" && (!freezeRange.start || !freezeRange.end))
+              }
               className="gap-2"
             >
               <Snowflake className="h-4 w-4" />
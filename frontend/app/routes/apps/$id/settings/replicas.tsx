@@ -35,6 +35,9 @@ export default function AppSettingsReplicas() {
   const [replicaCount, setReplicaCount] = useState(app.replica_count ?? 1);
   const [isSavingReplicas, setIsSavingReplicas] = useState(false);
   const [restartingReplica, setRestartingReplica] = useState<number | null>(null);
+  const [maxSurge, setMaxSurge] = useState(app.rolling_max_surge ?? 1);
+  const [maxUnavailable, setMaxUnavailable] = useState(app.rolling_max_unavailable ?? 0);
+  const [isSavingRollout, setIsSavingRollout] = useState(false);
 
   const { data: replicas = [], refetch: refetchReplicas } = useQuery<AppReplica[]>({
     queryKey: ["replicas", app.id],
@@ -72,6 +75,25 @@ export default function AppSettingsReplicas() {
     }
   };
 
+  const handleSaveRollout = async () => {
+    if (maxSurge === 0 && maxUnavailable === 0) {
+      toast.error("Max surge and max unavailable cannot both be 0");
+      return;
+    }
+    setIsSavingRollout(true);
+    try {
+      await replicasApi.setRollout(app.id, {
+        max_surge: maxSurge,
+        max_unavailable: maxUnavailable,
+      });
+      toast.success("Rollout settings updated");
+    } catch (err) {
+      toast.error(err instanceof Error ? err.message : "Failed to update rollout settings");
+    } finally {
+      setIsSavingRollout(false);
+    }
+  };
+
   const handleRestartReplica = async (index: number) => {
     setRestartingReplica(index);
     try {
@@ -172,6 +194,43 @@ export default function AppSettingsReplicas() {
             </Button>
           </div>
 
+          {/* Rolling deploy budget */}
+          <div className="flex items-end gap-4">
+            <div className="space-y-2 flex-1 max-w-xs">
+              <Label htmlFor="max-surge">Max Surge</Label>
+              <Input
+                id="max-surge"
+                type="number"
+                min={0}
+                max={10}
+                value={maxSurge}
+                onChange={(e) => setMaxSurge(Math.max(0, Math.min(10, parseInt(e.target.value) || 0)))}
+              />
+              <p className="text-xs text-muted-foreground">
+                Extra replicas started at once during a deploy
+              </p>
+            </div>
+            <div className="space-y-2 flex-1 max-w-xs">
+              <Label htmlFor="max-unavailable">Max Unavailable</Label>
+              <Input
+                id="max-unavailable"
+                type="number"
+                min={0}
+                max={10}
+                value={maxUnavailable}
+                onChange={(e) =>
+                  setMaxUnavailable(Math.max(0, Math.min(10, parseInt(e.target.value) || 0)))
+                }
+              />
+              <p className="text-xs text-muted-foreground">
+                Old replicas that may stop before replacements are healthy
+              </p>
+            </div>
+            <Button onClick={handleSaveRollout} disabled={isSavingRollout}>
+              {isSavingRollout ? "Saving..." : "Save"}
+            </Button>
+          </div>
+
           {/* Replica status table */}
           {replicas.length > 0 ? (
             <div className="rounded-md border">
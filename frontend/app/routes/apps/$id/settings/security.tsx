@@ -17,6 +17,10 @@ import {
 import { RollbackSettingsCard } from "@/components/rollback-settings-card";
 import { BasicAuthCard } from "@/components/basic-auth-card";
 import { DeploymentCommandsCard } from "@/components/deployment-commands-card";
+import { DeployWaitConditionsCard } from "@/components/deploy-wait-conditions-card";
+import { SmokeTestsCard } from "@/components/smoke-tests-card";
+import { TestStageCard } from "@/components/test-stage-card";
+import { NetworkEgressCard } from "@/components/network-egress-card";
 import { api } from "@/lib/api";
 import type { App } from "@/types/api";
 
@@ -46,6 +50,10 @@ export default function AppSettingsSecurity() {
     <div className="space-y-6">
       <RollbackSettingsCard app={app} />
       <BasicAuthCard appId={app.id} />
+      <NetworkEgressCard app={app} />
+      <DeployWaitConditionsCard app={app} />
+      <TestStageCard app={app} />
+      <SmokeTestsCard app={app} />
       <DeploymentCommandsCard
         app={app}
         onSave={() => queryClient.invalidateQueries({ queryKey: ["app", app.id] })}
@@ -49,6 +49,7 @@ import {
   TableRow,
 } from "@/components/ui/table";
 import { api } from "@/lib/api";
+import { TimezoneInput } from "@/components/timezone-input";
 import type {
   ManagedDatabase,
   DatabaseBackup,
@@ -127,15 +128,16 @@ function BackupStatusBadge({ status }: { status: string }) {
 function getScheduleDescription(schedule: DatabaseBackupSchedule): string {
   const hour = schedule.schedule_hour.toString().padStart(2, "0");
   const days = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+  const timezone = schedule.timezone ?? "UTC";
 
   switch (schedule.schedule_type) {
     case "hourly":
       return "Every hour";
     case "daily":
-      return `Daily at ${hour}:00 UTC`;
+      return `Daily at ${hour}:00 ${timezone}`;
     case "weekly":
       const day = schedule.schedule_day !== undefined ? days[schedule.schedule_day] : "Sunday";
-      return `Every ${day} at ${hour}:00 UTC`;
+      return `Every ${day} at ${hour}:00 ${timezone}`;
     default:
       return schedule.schedule_type;
   }
@@ -153,6 +155,7 @@ export default function DatabaseBackupsPage() {
   const [scheduleHour, setScheduleHour] = useState(2);
   const [scheduleDay, setScheduleDay] = useState(0);
   const [retentionCount, setRetentionCount] = useState(5);
+  const [scheduleTimezone, setScheduleTimezone] = useState("");
 
   // Fetch backups
   const { data: backups, isLoading: backupsLoading } = useQuery<DatabaseBackup[]>({
@@ -203,6 +206,7 @@ export default function DatabaseBackupsPage() {
           schedule_hour: scheduleHour,
           schedule_day: scheduleType === "weekly" ? scheduleDay : undefined,
           retention_count: retentionCount,
+          timezone: scheduleTimezone.trim(),
         }
       ),
     onSuccess: () => {
@@ -246,6 +250,7 @@ export default function DatabaseBackupsPage() {
       setScheduleHour(schedule.schedule_hour);
       setScheduleDay(schedule.schedule_day ?? 0);
       setRetentionCount(schedule.retention_count);
+      setScheduleTimezone(schedule.timezone ?? "");
     }
     setShowScheduleForm(true);
   };
@@ -315,7 +320,7 @@ export default function DatabaseBackupsPage() {
 
                 {scheduleType !== "hourly" && (
                   <div className="space-y-2">
-                    <Label>Time (UTC)</Label>
+                    <Label>Time</Label>
                     <Select
                       value={scheduleHour.toString()}
                       onValueChange={(v) => setScheduleHour(parseInt(v))}
@@ -357,6 +362,17 @@ export default function DatabaseBackupsPage() {
                   </div>
                 )}
 
+                {scheduleType !== "hourly" && (
+                  <div className="space-y-2">
+                    <Label htmlFor="schedule-timezone">Timezone</Label>
+                    <TimezoneInput
+                      id="schedule-timezone"
+                      value={scheduleTimezone}
+                      onChange={setScheduleTimezone}
+                    />
+                  </div>
+                )}
+
                 <div className="space-y-2">
                   <Label>Retention (keep last N backups)</Label>
                   <Select
@@ -29,6 +29,7 @@ import {
 } from "@/components/ui/alert-dialog";
 import { toast } from "sonner";
 import { MoveToProjectCard } from "@/components/move-to-project-card";
+import { DatabaseSeedCard } from "@/components/database-seed-card";
 import { api } from "@/lib/api";
 import {
   AlertTriangle,
@@ -620,6 +621,8 @@ export default function DatabaseSettingsTab() {
         </CardContent>
       </Card>
 
+      <DatabaseSeedCard database={database} />
+
       {/* Resource Limits Card */}
       <Card>
         <CardHeader>
@@ -411,6 +411,20 @@ export default function ProjectEnvironmentsPage() {
     onError: (err: Error) => toast.error(err.message),
   });
 
+  const approvalMutation = useMutation({
+    mutationFn: ({ envId, required }: { envId: string; required: boolean }) =>
+      api.updateEnvironment(envId, { require_approval: required }),
+    onSuccess: (env) => {
+      toast.success(
+        env.require_approval
+          ? "Deployments in this environment now need approval"
+          : "Approval no longer required"
+      );
+      queryClient.invalidateQueries({ queryKey: ["environments", id] });
+    },
+    onError: (err: Error) => toast.error(err.message),
+  });
+
   const deleteMutation = useMutation({
     mutationFn: () => api.deleteEnvironment(selectedEnv!.id),
     onSuccess: () => {
@@ -522,7 +536,26 @@ export default function ProjectEnvironmentsPage() {
                       </p>
                     )}
                   </div>
-                  <div className="flex gap-2">
+                  <div className="flex items-center gap-2">
+                    <div className="flex items-center gap-2 mr-2">
+                      <Checkbox
+                        id={`require-approval-${env.id}`}
+                        checked={env.require_approval}
+                        disabled={approvalMutation.isPending}
+                        onCheckedChange={(checked) =>
+                          approvalMutation.mutate({
+                            envId: env.id,
+                            required: checked === true,
+                          })
+                        }
+                      />
+                      <Label
+                        htmlFor={`require-approval-${env.id}`}
+                        className="text-sm font-normal"
+                      >
+                        Require deploy approval
+                      </Label>
+                    </div>
                     <Button
                       variant="outline"
                       size="sm"
@@ -14,7 +14,7 @@ import {
 } from "@/components/ui/select";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { Switch } from "@/components/ui/switch";
-import { Eye, EyeOff, GitBranch, Package, Sparkles, FileCode, Github, Link2, Upload, Zap, Cloud } from "lucide-react";
+import { Eye, EyeOff, GitBranch, Package, Sparkles, FileCode, Github, Link2, Upload, Zap, Cloud, Layers } from "lucide-react";
 import { CPU_OPTIONS, MEMORY_OPTIONS } from "@/components/resource-limits-card";
 import { GitHubRepoPicker, type SelectedRepo } from "@/components/github-repo-picker";
 import { GitLabRepoPicker, type SelectedGitLabRepo } from "@/components/gitlab-repo-picker";
@@ -29,7 +29,7 @@ const GitLabIcon = ({ className, style }: { className?: string; style?: React.CS
 );
 import { api } from "@/lib/api";
 import { useTeamContext } from "@/lib/team-context";
-import type { AppEnvironment, BuildType, BuildDetectionResult, NixpacksConfig, Project, ProjectWithApps, CreateAppRequest } from "@/types/api";
+import type { AppEnvironment, AppType, BuildType, BuildDetectionResult, NixpacksConfig, Project, ProjectWithApps, CreateAppRequest } from "@/types/api";
 
 const ENVIRONMENT_OPTIONS: { value: AppEnvironment; label: string }[] = [
   { value: "development", label: "Development" },
@@ -44,6 +44,9 @@ export function meta() {
   ];
 }
 
+/** Select value meaning "no --target" (Radix items cannot use an empty value) */
+const FINAL_STAGE = "__final__";
+
 export default function NewAppPage() {
   const navigate = useNavigate();
   const { projectId } = useParams();
@@ -52,6 +55,7 @@ export default function NewAppPage() {
   const [deploymentSource, setDeploymentSource] = useState<"git" | "registry" | "upload">("git");
   const [gitSourceType, setGitSourceType] = useState<"github" | "gitlab" | "bitbucket" | "manual">("github");
   const [buildType, setBuildType] = useState<BuildType>("nixpacks");
+  const [appType, setAppType] = useState<AppType>("web");
   const [previewEnabled, setPreviewEnabled] = useState(false);
   const [showPassword, setShowPassword] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -120,6 +124,8 @@ export default function NewAppPage() {
         environment?: string;
         build_type?: string;
         publish_directory?: string;
+        build_target?: string;
+        app_type?: string;
       };
     }) => {
       return api.uploadCreateApp(projectId!, data.file, data.config);
@@ -146,6 +152,8 @@ export default function NewAppPage() {
       // Auto-set build type based on detection
       if (result.build_type === "dockerfile" || result.build_type === "nixpacks" || result.build_type === "staticsite") {
         setBuildType(result.build_type as BuildType);
+      } else if (result.build_type === "dockercompose") {
+        setBuildType("docker-compose");
       }
     } catch (err) {
       console.warn("Build detection failed:", err);
@@ -161,9 +169,13 @@ export default function NewAppPage() {
     const formData = new FormData(event.currentTarget);
 
     const name = formData.get("name") as string;
-    const port = parseInt(formData.get("port") as string) || 3000;
-    const domain = (formData.get("domain") as string) || undefined;
-    const healthcheck = (formData.get("healthcheck") as string) || undefined;
+    // Workers serve no HTTP: no port, domain or healthcheck path
+    const isWorker = appType === "worker";
+    const port = isWorker ? undefined : parseInt(formData.get("port") as string) || 3000;
+    const domain = isWorker ? undefined : (formData.get("domain") as string) || undefined;
+    const healthcheck = isWorker
+      ? undefined
+      : (formData.get("healthcheck") as string) || undefined;
     const cpu_limit = (formData.get("cpu_limit") as string) || "1";
     const memory_limit = (formData.get("memory_limit") as string) || "512m";
     const environment = (formData.get("environment") || "development") as AppEnvironment;
@@ -181,6 +193,7 @@ export default function NewAppPage() {
       }
 
       const publish_directory = (formData.get("publish_directory") as string) || detectionResult?.publish_directory || undefined;
+      const build_target = formData.get("build_target") as string | null;
 
       uploadCreateMutation.mutate({
         file: uploadFile,
@@ -194,6 +207,11 @@ export default function NewAppPage() {
           environment,
           build_type: buildType,
           publish_directory,
+          build_target:
+            buildType === "dockerfile" && build_target && build_target !== FINAL_STAGE
+              ? build_target
+              : undefined,
+          app_type: appType,
         },
       });
     } else if (deploymentSource === "registry") {
@@ -217,6 +235,7 @@ export default function NewAppPage() {
         registry_username,
         registry_password,
         port,
+        app_type: appType,
         domain,
         healthcheck,
         cpu_limit,
@@ -249,6 +268,7 @@ export default function NewAppPage() {
 
       const dockerfile = (formData.get("dockerfile") as string) || "Dockerfile";
       const publish_directory = (formData.get("publish_directory") as string) || undefined;
+      const static_build_command = (formData.get("static_build_command") as string)?.trim() || undefined;
 
       if (!git_url?.trim()) {
         setError(
@@ -281,6 +301,7 @@ export default function NewAppPage() {
         branch,
         dockerfile: buildType === "dockerfile" ? dockerfile : undefined,
         port,
+        app_type: appType,
         domain,
         healthcheck,
         cpu_limit,
@@ -290,6 +311,10 @@ export default function NewAppPage() {
         build_type: buildType,
         nixpacks_config: nixpacksConfigToSend,
         publish_directory: buildType === "staticsite" ? publish_directory : undefined,
+        static_config:
+          buildType === "staticsite" && static_build_command
+            ? { build_command: static_build_command }
+            : undefined,
         preview_enabled: previewEnabled,
         github_app_installation_id:
           gitSourceType === "github" && selectedRepo
@@ -532,7 +557,7 @@ export default function NewAppPage() {
                   {/* Build Type Selection */}
                   <div className="space-y-3">
                     <Label>Build Type</Label>
-                    <div className="grid grid-cols-5 gap-3">
+                    <div className="grid grid-cols-3 md:grid-cols-6 gap-3">
                       <button
                         type="button"
                         onClick={() => setBuildType("nixpacks")}
@@ -608,6 +633,21 @@ export default function NewAppPage() {
                           HTML/CSS/JS
                         </span>
                       </button>
+                      <button
+                        type="button"
+                        onClick={() => setBuildType("docker-compose")}
+                        className={`flex flex-col items-center gap-2 p-4 rounded-lg border-2 transition-colors ${
+                          buildType === "docker-compose"
+                            ? "border-primary bg-primary/5"
+                            : "border-border hover:border-muted-foreground/50"
+                        }`}
+                      >
+                        <Layers className="h-6 w-6" />
+                        <span className="text-sm font-medium">Compose</span>
+                        <span className="text-xs text-muted-foreground text-center">
+                          Multi-service
+                        </span>
+                      </button>
                     </div>
                   </div>
 
@@ -721,17 +761,31 @@ export default function NewAppPage() {
 
                   {/* Static options */}
                   {buildType === "staticsite" && (
-                    <div className="space-y-2">
-                      <Label htmlFor="publish_directory">Publish Directory</Label>
-                      <Input
-                        id="publish_directory"
-                        name="publish_directory"
-                        placeholder="dist, build, public"
-                        defaultValue="dist"
-                      />
-                      <p className="text-xs text-muted-foreground">
-                        Directory containing your built static files
-                      </p>
+                    <div className="grid gap-4 md:grid-cols-2">
+                      <div className="space-y-2">
+                        <Label htmlFor="static_build_command">Build Command</Label>
+                        <Input
+                          id="static_build_command"
+                          name="static_build_command"
+                          placeholder="npm run build"
+                          className="font-mono text-sm"
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Defaults to the package manager&apos;s build script
+                        </p>
+                      </div>
+                      <div className="space-y-2">
+                        <Label htmlFor="publish_directory">Publish Directory</Label>
+                        <Input
+                          id="publish_directory"
+                          name="publish_directory"
+                          placeholder="dist, build, public"
+                          defaultValue="dist"
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Directory containing your built static files
+                        </p>
+                      </div>
                     </div>
                   )}
 
@@ -844,6 +898,33 @@ export default function NewAppPage() {
                         </div>
                       </div>
 
+                      {/* Multi-stage target (from the detected Dockerfile) */}
+                      {buildType === "dockerfile" && !!detectionResult?.build_targets?.length && (
+                        <div className="space-y-2">
+                          <Label htmlFor="build_target_upload">Build Target</Label>
+                          <Select
+                            key={detectionResult.build_targets.join(",")}
+                            name="build_target"
+                            defaultValue={FINAL_STAGE}
+                          >
+                            <SelectTrigger id="build_target_upload" className="w-full">
+                              <SelectValue placeholder="Final stage" />
+                            </SelectTrigger>
+                            <SelectContent>
+                              <SelectItem value={FINAL_STAGE}>Final stage (default)</SelectItem>
+                              {detectionResult.build_targets.map((target) => (
+                                <SelectItem key={target} value={target}>
+                                  {target}
+                                </SelectItem>
+                              ))}
+                            </SelectContent>
+                          </Select>
+                          <p className="text-xs text-muted-foreground">
+                            Multi-stage Dockerfile stage to build (--target)
+                          </p>
+                        </div>
+                      )}
+
                       {/* Static options */}
                       {buildType === "staticsite" && (
                         <div className="space-y-2">
@@ -952,7 +1033,7 @@ export default function NewAppPage() {
               </Tabs>
             </div>
 
-            {/* Project and Healthcheck row */}
+            {/* Project and App Type row */}
             <div className="grid gap-6 md:grid-cols-2">
               <div className="space-y-2">
                 <Label htmlFor="project">Project</Label>
@@ -974,46 +1055,69 @@ export default function NewAppPage() {
               </div>
 
               <div className="space-y-2">
-                <Label htmlFor="healthcheck">Healthcheck Path</Label>
-                <Input
-                  id="healthcheck"
-                  name="healthcheck"
-                  placeholder="/health"
-                />
+                <Label htmlFor="app_type">App Type</Label>
+                <Select value={appType} onValueChange={(v) => setAppType(v as AppType)}>
+                  <SelectTrigger id="app_type" className="w-full">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="web">Web service</SelectItem>
+                    <SelectItem value="worker">Worker (background process)</SelectItem>
+                  </SelectContent>
+                </Select>
                 <p className="text-xs text-muted-foreground">
-                  Optional endpoint to check if the app is healthy
+                  Workers run without a port, domain or proxy route
                 </p>
               </div>
             </div>
 
-            {/* Port, Domain row */}
-            <div className="grid gap-6 md:grid-cols-2">
-              <div className="space-y-2">
-                <Label htmlFor="port">Port</Label>
-                <Input
-                  id="port"
-                  name="port"
-                  type="number"
-                  placeholder="3000"
-                  defaultValue="3000"
-                />
-                <p className="text-xs text-muted-foreground">
-                  Container port to expose
-                </p>
-              </div>
+            {/* Port, Domain and Healthcheck (web services only) */}
+            {appType === "web" && (
+              <div className="grid gap-6 md:grid-cols-2">
+                <div className="space-y-2">
+                  <Label htmlFor="port">Port</Label>
+                  <Input
+                    key={detectionResult?.suggested_port ?? "port"}
+                    id="port"
+                    name="port"
+                    type="number"
+                    placeholder="3000"
+                    defaultValue={String(detectionResult?.suggested_port ?? 3000)}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    {detectionResult?.suggested_port
+                      ? "Container port to expose (from the Dockerfile)"
+                      : "Container port to expose"}
+                  </p>
+                </div>
 
-              <div className="space-y-2">
-                <Label htmlFor="domain">Domain</Label>
-                <Input
-                  id="domain"
-                  name="domain"
-                  placeholder="app.example.com"
-                />
-                <p className="text-xs text-muted-foreground">
-                  Optional custom domain for your application
-                </p>
+                <div className="space-y-2">
+                  <Label htmlFor="domain">Domain</Label>
+                  <Input
+                    id="domain"
+                    name="domain"
+                    placeholder="app.example.com"
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Optional custom domain for your application
+                  </p>
+                </div>
+
+                <div className="space-y-2">
+                  <Label htmlFor="healthcheck">Healthcheck Path</Label>
+                  <Input
+                    key={detectionResult?.healthcheck?.path ?? "healthcheck"}
+                    id="healthcheck"
+                    name="healthcheck"
+                    placeholder="/health"
+                    defaultValue={detectionResult?.healthcheck?.path ?? undefined}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Optional endpoint to check if the app is healthy
+                  </p>
+                </div>
               </div>
-            </div>
+            )}
 
             {/* Resource Limits row */}
             <div className="grid gap-6 md:grid-cols-2">
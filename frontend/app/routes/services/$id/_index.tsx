@@ -8,6 +8,7 @@ import { Label } from "@/components/ui/label";
 import { toast } from "sonner";
 import type { Service } from "@/types/api";
 import {
+  BookOpen,
   Clock,
   AlertCircle,
   Code,
@@ -244,6 +245,21 @@ export default function ServiceGeneralTab() {
         </Card>
       )}
 
+      {/* Notes from the template the service was deployed from */}
+      {service.post_deploy_notes && (
+        <Card>
+          <CardHeader>
+            <CardTitle className="flex items-center gap-2">
+              <BookOpen className="h-5 w-5" />
+              Getting Started
+            </CardTitle>
+          </CardHeader>
+          <CardContent>
+            <p className="text-sm whitespace-pre-wrap">{service.post_deploy_notes}</p>
+          </CardContent>
+        </Card>
+      )}
+
       {/* Service URL - show domain link if configured, otherwise show port links */}
       {service.status === "running" && service.domain && (
         <Card>
@@ -268,7 +284,7 @@ export default function ServiceGeneralTab() {
               </div>
               <div className="flex gap-1">
                 <CopyButton
-                  text={`https://${service.domain}`}
+                  text={service.url ?? `https://${service.domain}`}
                   field="domain-url"
                 />
                 <Button
@@ -278,7 +294,7 @@ export default function ServiceGeneralTab() {
                   asChild
                 >
                   <a
-                    href={`https://${service.domain}`}
+                    href={service.url ?? `https://${service.domain}`}
                     target="_blank"
                     rel="noopener noreferrer"
                   >
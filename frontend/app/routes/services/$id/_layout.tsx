@@ -226,7 +226,7 @@ export default function ServiceDetailLayout() {
           {/* Open button, shown when running and domain or port is configured */}
           {service.status === "running" && (service.domain || service.port > 0) && (() => {
             const url = service.domain
-              ? `https://${service.domain}`
+              ? service.url ?? `https://${service.domain}`
               : `http://${typeof window !== "undefined" ? window.location.hostname : "localhost"}:${service.port}`;
             return (
               <Button variant="outline" asChild className="gap-2">
@@ -33,6 +33,13 @@ import {
   DialogTitle,
 } from "@/components/ui/dialog";
 import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { toast } from "sonner";
 import { api } from "@/lib/api";
 import type { Service } from "@/types/api";
@@ -68,6 +75,30 @@ function parseContainerNames(composeContent: string): string[] {
   return names;
 }
 
+/** Names of the services under the top-level `services:` key of a docker-compose YAML string */
+function parseComposeServiceNames(composeContent: string): string[] {
+  const names: string[] = [];
+  let inServices = false;
+  let indent: number | null = null;
+  for (const line of composeContent.split("\n")) {
+    if (/^\S/.test(line)) {
+      inServices = /^services:\s*$/.test(line);
+      continue;
+    }
+    const match = inServices ? line.match(/^(\s+)([A-Za-z0-9._-]+):\s*$/) : null;
+    if (match) {
+      indent ??= match[1].length;
+      if (match[1].length === indent) {
+        names.push(match[2]);
+      }
+    }
+  }
+  return names;
+}
+
+/** Select value for routing to the host port instead of a compose service */
+const HOST_PORT_ROUTE = "__host_port";
+
 const DB_KEYWORDS = ["postgres", "mysql", "mariadb", "mongo", "redis"];
 
 /** Returns true when the compose file contains at least one database service */
@@ -85,6 +116,13 @@ export default function ServiceSettingsTab() {
   const [composeContent, setComposeContent] = useState(service.compose_content);
   const [domain, setDomain] = useState(service.domain ?? "");
   const [port, setPort] = useState(service.port ?? 80);
+  const [routeService, setRouteService] = useState(service.route_service ?? HOST_PORT_ROUTE);
+  const [routePort, setRoutePort] = useState(service.route_port ?? 80);
+  const [routeTls, setRouteTls] = useState(service.route_tls ?? false);
+  const [basicAuthEnabled, setBasicAuthEnabled] = useState(service.basic_auth_enabled ?? false);
+  const [basicAuthUsername, setBasicAuthUsername] = useState(service.basic_auth_username ?? "");
+  const [basicAuthPassword, setBasicAuthPassword] = useState("");
+  const composeServiceNames = parseComposeServiceNames(service.compose_content);
   const [isolatedNetwork, setIsolatedNetwork] = useState(service.isolated_network ?? true);
   const [rawComposeMode, setRawComposeMode] = useState(service.raw_compose_mode ?? false);
   const [cpuLimit, setCpuLimit] = useState(service.cpu_limit ?? "");
@@ -117,10 +155,22 @@ export default function ServiceSettingsTab() {
   });
 
   const updateDomainMutation = useMutation({
-    mutationFn: (data: { domain: string; port: number }) =>
-      api.updateService(service.id, { domain: data.domain, port: data.port }),
+    mutationFn: () =>
+      api.updateService(service.id, {
+        domain,
+        port,
+        route_service: routeService === HOST_PORT_ROUTE ? "" : routeService,
+        ...(routeService !== HOST_PORT_ROUTE && { route_port: routePort }),
+        route_tls: routeTls,
+        basic_auth_enabled: basicAuthEnabled,
+        ...(basicAuthEnabled && {
+          basic_auth_username: basicAuthUsername,
+          basic_auth_password: basicAuthPassword,
+        }),
+      }),
     onSuccess: () => {
       toast.success("Domain configuration saved. Restart the service to apply changes.");
+      setBasicAuthPassword("");
       queryClient.invalidateQueries({ queryKey: ["service", service.id] });
     },
     onError: (error) => {
@@ -231,9 +281,24 @@ export default function ServiceSettingsTab() {
   useEffect(() => {
     setDomain(service.domain ?? "");
     setPort(service.port ?? 80);
+    setRouteService(service.route_service ?? HOST_PORT_ROUTE);
+    setRoutePort(service.route_port ?? 80);
+    setRouteTls(service.route_tls ?? false);
+    setBasicAuthEnabled(service.basic_auth_enabled ?? false);
+    setBasicAuthUsername(service.basic_auth_username ?? "");
     setIsolatedNetwork(service.isolated_network ?? true);
     setRawComposeMode(service.raw_compose_mode ?? false);
-  }, [service.domain, service.port, service.isolated_network, service.raw_compose_mode]);
+  }, [
+    service.domain,
+    service.port,
+    service.route_service,
+    service.route_port,
+    service.route_tls,
+    service.basic_auth_enabled,
+    service.basic_auth_username,
+    service.isolated_network,
+    service.raw_compose_mode,
+  ]);
 
   const handleCancelEdit = () => {
     setComposeContent(service.compose_content);
@@ -247,7 +312,7 @@ export default function ServiceSettingsTab() {
 
   const handleSaveDomain = (e: React.FormEvent<HTMLFormElement>) => {
     e.preventDefault();
-    updateDomainMutation.mutate({ domain, port });
+    updateDomainMutation.mutate();
   };
 
   const handleDelete = () => {
@@ -269,7 +334,7 @@ export default function ServiceSettingsTab() {
             Domain & Proxy Port
           </CardTitle>
           <CardDescription>
-            Configure the subdomain and the host port the proxy forwards to. If your service shows a 502 error, verify the port matches the host-side port in your compose <code className="text-xs bg-muted px-1 py-0.5 rounded">ports:</code> mapping.
+            Configure the subdomain and where the proxy forwards it: a container port of one of the compose services, or a host port from your compose <code className="text-xs bg-muted px-1 py-0.5 rounded">ports:</code> mapping.
           </CardDescription>
         </CardHeader>
         <CardContent>
@@ -289,21 +354,108 @@ export default function ServiceSettingsTab() {
                 </p>
               </div>
               <div className="space-y-2">
-                <Label htmlFor="port">Port</Label>
-                <Input
-                  id="port"
-                  type="number"
-                  min={1}
-                  max={65535}
-                  placeholder="80"
-                  value={port}
-                  onChange={(e) => setPort(Number(e.target.value))}
-                />
+                <Label htmlFor="route-service">Route To</Label>
+                <Select value={routeService} onValueChange={setRouteService}>
+                  <SelectTrigger id="route-service">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value={HOST_PORT_ROUTE}>Host port</SelectItem>
+                    {composeServiceNames.map((name) => (
+                      <SelectItem key={name} value={name}>
+                        Compose service: {name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
                 <p className="text-xs text-muted-foreground">
-                  The host port the proxy forwards traffic to (the left side of <code className="font-mono">HOST:CONTAINER</code> in your compose ports mapping). If you see a 502 error, check this matches the port in your compose file.
+                  Routing to a compose service publishes its port on localhost only, so nothing is exposed publicly.
                 </p>
               </div>
+              {routeService === HOST_PORT_ROUTE ? (
+                <div className="space-y-2">
+                  <Label htmlFor="port">Port</Label>
+                  <Input
+                    id="port"
+                    type="number"
+                    min={1}
+                    max={65535}
+                    placeholder="80"
+                    value={port}
+                    onChange={(e) => setPort(Number(e.target.value))}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    The host port the proxy forwards traffic to (the left side of <code className="font-mono">HOST:CONTAINER</code> in your compose ports mapping). If you see a 502 error, check this matches the port in your compose file.
+                  </p>
+                </div>
+              ) : (
+                <div className="space-y-2">
+                  <Label htmlFor="route-port">Container Port</Label>
+                  <Input
+                    id="route-port"
+                    type="number"
+                    min={1}
+                    max={65535}
+                    placeholder="80"
+                    value={routePort}
+                    onChange={(e) => setRoutePort(Number(e.target.value))}
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    The port the <code className="font-mono">{routeService}</code> container listens on (e.g. 3001 for Uptime Kuma, 3000 for Grafana).
+                  </p>
+                </div>
+              )}
+            </div>
+            <div className="flex items-center justify-between">
+              <div className="space-y-1">
+                <Label htmlFor="route-tls" className="text-sm font-medium">
+                  HTTPS Certificate
+                </Label>
+                <p className="text-sm text-muted-foreground">
+                  Request a Let&apos;s Encrypt certificate for this domain. Its DNS must point at this server.
+                </p>
+              </div>
+              <Switch id="route-tls" checked={routeTls} onCheckedChange={setRouteTls} />
+            </div>
+            <div className="flex items-center justify-between">
+              <div className="space-y-1">
+                <Label htmlFor="service-basic-auth" className="text-sm font-medium">
+                  Basic Auth
+                </Label>
+                <p className="text-sm text-muted-foreground">
+                  Require a username and password before the proxy forwards requests.
+                </p>
+              </div>
+              <Switch
+                id="service-basic-auth"
+                checked={basicAuthEnabled}
+                onCheckedChange={setBasicAuthEnabled}
+              />
             </div>
+            {basicAuthEnabled && (
+              <div className="grid gap-4 sm:grid-cols-2">
+                <div className="space-y-2">
+                  <Label htmlFor="basic-auth-username">Username</Label>
+                  <Input
+                    id="basic-auth-username"
+                    autoComplete="off"
+                    value={basicAuthUsername}
+                    onChange={(e) => setBasicAuthUsername(e.target.value)}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="basic-auth-password">Password</Label>
+                  <Input
+                    id="basic-auth-password"
+                    type="password"
+                    autoComplete="new-password"
+                    placeholder={service.basic_auth_enabled ? "Leave empty to keep the current password" : "At least 8 characters"}
+                    value={basicAuthPassword}
+                    onChange={(e) => setBasicAuthPassword(e.target.value)}
+                  />
+                </div>
+              </div>
+            )}
             <div className="flex justify-end">
               <Button
                 type="submit"
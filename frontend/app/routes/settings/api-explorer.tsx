@@ -0,0 +1,430 @@
+import { useMemo, useState } from "react";
+import { useQuery, useMutation } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Braces, Copy, Send, Search } from "lucide-react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Textarea } from "@/components/ui/textarea";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import { api } from "@/lib/api";
+import type { ExplorerResponse, HttpMethod, OpenApiOperation } from "@/lib/api";
+
+export function meta() {
+  return [
+    { title: "API Explorer - Rivetr" },
+    { name: "description", content: "Try API endpoints and copy curl and CLI snippets" },
+  ];
+}
+
+const METHODS: HttpMethod[] = ["get", "post", "put", "patch", "delete"];
+
+const METHOD_STYLES: Record<HttpMethod, string> = {
+  get: "bg-blue-500/15 text-blue-600 dark:text-blue-400",
+  post: "bg-green-500/15 text-green-600 dark:text-green-400",
+  put: "bg-amber-500/15 text-amber-600 dark:text-amber-400",
+  patch: "bg-amber-500/15 text-amber-600 dark:text-amber-400",
+  delete: "bg-red-500/15 text-red-600 dark:text-red-400",
+};
+
+interface Endpoint {
+  method: HttpMethod;
+  path: string;
+  operation: OpenApiOperation;
+}
+
+/** Path parameter names of an OpenAPI path template */
+function pathParams(path: string): string[] {
+  return [...path.matchAll(/\{([^}]+)\}/g)].map((m) => m[1]);
+}
+
+function hasBody(method: HttpMethod): boolean {
+  return method === "post" || method === "put" || method === "patch";
+}
+
+/** Quote a value for a POSIX shell */
+function shellQuote(value: string): string {
+  return `'${value.replace(/'/g, "'\\''")}'`;
+}
+
+function buildPath(path: string, params: Record<string, string>, query: string): string {
+  const filled = path.replace(/\{([^}]+)\}/g, (_, name: string) =>
+    params[name] ? encodeURIComponent(params[name]) : `{${name}}`
+  );
+  const trimmed = query.trim().replace(/^\?/, "");
+  return trimmed ? `${filled}?${trimmed}` : filled;
+}
+
+function curlSnippet(endpoint: Endpoint, url: string, body: string): string {
+  const lines = [`curl -X ${endpoint.method.toUpperCase()} ${shellQuote(url)}`];
+  if (endpoint.operation.security?.length !== 0) {
+    lines.push(`  -H "Authorization: Bearer $RIVETR_TOKEN"`);
+  }
+  if (hasBody(endpoint.method) && body.trim()) {
+    lines.push(`  -H "Content-Type: application/json"`);
+    lines.push(`  -d ${shellQuote(body.trim())}`);
+  }
+  return lines.join(" \\\n");
+}
+
+function cliSnippet(endpoint: Endpoint, path: string, body: string): string {
+  let snippet = `rivetr api ${endpoint.method.toUpperCase()} ${shellQuote(path)}`;
+  if (hasBody(endpoint.method) && body.trim()) {
+    snippet += ` -d ${shellQuote(body.trim())}`;
+  }
+  return snippet;
+}
+
+function formatBody(response: ExplorerResponse): string {
+  if (response.contentType?.includes("json")) {
+    try {
+      return JSON.stringify(JSON.parse(response.body), null, 2);
+    } catch {
+      // Fall through to the raw body
+    }
+  }
+  return response.body;
+}
+
+export default function ApiExplorerPage() {
+  const [search, setSearch] = useState("");
+  const [selected, setSelected] = useState<Endpoint | null>(null);
+  const [params, setParams] = useState<Record<string, string>>({});
+  const [query, setQuery] = useState("");
+  const [body, setBody] = useState("{}");
+  const [confirmDelete, setConfirmDelete] = useState(false);
+
+  const { data: spec, isLoading } = useQuery({
+    queryKey: ["openapi-spec"],
+    queryFn: api.getOpenApiSpec,
+    staleTime: Infinity,
+  });
+
+  const groups = useMemo(() => {
+    if (!spec) return [];
+    const needle = search.trim().toLowerCase();
+    const byTag = new Map<string, Endpoint[]>();
+    for (const [path, item] of Object.entries(spec.paths)) {
+      for (const method of METHODS) {
+        const operation = item[method];
+        if (!operation) continue;
+        const haystack = `${method} ${path} ${operation.summary}`.toLowerCase();
+        if (needle && !haystack.includes(needle)) continue;
+        const tag = operation.tags[0] ?? "Other";
+        byTag.set(tag, [...(byTag.get(tag) ?? []), { method, path, operation }]);
+      }
+    }
+    const order = spec.tags?.map((t) => t.name) ?? [];
+    return [...byTag.entries()].sort(
+      ([a], [b]) => order.indexOf(a) - order.indexOf(b)
+    );
+  }, [spec, search]);
+
+  const serverUrl = spec?.servers?.[0]?.url ?? window.location.origin;
+  const requestPath = selected ? buildPath(selected.path, params, query) : "";
+
+  const sendMutation = useMutation({
+    mutationFn: () =>
+      api.sendExplorerRequest(
+        selected!.method.toUpperCase(),
+        requestPath,
+        hasBody(selected!.method) && body.trim() ? body : undefined
+      ),
+    onError: (error: Error) => {
+      toast.error(error.message || "Request failed");
+    },
+  });
+
+  const selectEndpoint = (endpoint: Endpoint) => {
+    setSelected(endpoint);
+    setParams({});
+    setQuery("");
+    setBody("{}");
+    sendMutation.reset();
+  };
+
+  const handleSend = () => {
+    if (!selected) return;
+    if (hasBody(selected.method) && body.trim()) {
+      try {
+        JSON.parse(body);
+      } catch {
+        toast.error("Request body is not valid JSON");
+        return;
+      }
+    }
+    const missing = pathParams(selected.path).filter((name) => !params[name]?.trim());
+    if (missing.length > 0) {
+      toast.error(`Fill in ${missing.join(", ")}`);
+      return;
+    }
+    if (selected.method === "delete") {
+      setConfirmDelete(true);
+      return;
+    }
+    sendMutation.mutate();
+  };
+
+  const copy = (text: string, label: string) => {
+    navigator.clipboard.writeText(text);
+    toast.success(`${label} copied to clipboard`);
+  };
+
+  const response = sendMutation.data;
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h1 className="text-3xl font-bold flex items-center gap-2">
+          <Braces className="h-7 w-7" aria-hidden="true" />
+          API Explorer
+        </h1>
+        <p className="text-muted-foreground">
+          Try endpoints with your current session and copy curl or CLI snippets.
+          Requests run against this server.{" "}
+          <a href="/api/openapi.json" className="underline" target="_blank" rel="noreferrer">
+            OpenAPI document
+          </a>
+        </p>
+      </div>
+
+      <div className="grid gap-6 lg:grid-cols-[22rem_1fr]">
+        <Card className="h-fit">
+          <CardHeader className="pb-3">
+            <div className="relative">
+              <Search
+                className="absolute left-2.5 top-2.5 h-4 w-4 text-muted-foreground"
+                aria-hidden="true"
+              />
+              <Input
+                placeholder="Search endpoints"
+                aria-label="Search endpoints"
+                value={search}
+                onChange={(e) => setSearch(e.target.value)}
+                className="pl-8"
+              />
+            </div>
+          </CardHeader>
+          <CardContent className="p-0">
+            <ScrollArea className="h-[60vh]">
+              {isLoading && (
+                <p className="px-4 py-6 text-sm text-muted-foreground">Loading endpoints...</p>
+              )}
+              {!isLoading && groups.length === 0 && (
+                <p className="px-4 py-6 text-sm text-muted-foreground">No endpoints match</p>
+              )}
+              <nav aria-label="Endpoints">
+                {groups.map(([tag, endpoints]) => (
+                  <div key={tag} className="pb-2">
+                    <h2 className="px-4 pt-3 pb-1 text-xs font-semibold uppercase text-muted-foreground">
+                      {tag}
+                    </h2>
+                    <ul>
+                      {endpoints.map((endpoint) => {
+                        const isSelected =
+                          selected?.method === endpoint.method && selected.path === endpoint.path;
+                        return (
+                          <li key={`${endpoint.method} ${endpoint.path}`}>
+                            <button
+                              type="button"
+                              aria-current={isSelected ? "true" : undefined}
+                              onClick={() => selectEndpoint(endpoint)}
+                              className={`flex w-full items-center gap-2 px-4 py-1.5 text-left text-sm hover:bg-muted/50 ${
+                                isSelected ? "bg-muted" : ""
+                              }`}
+                            >
+                              <span
+                                className={`w-14 shrink-0 rounded px-1 text-center font-mono text-[10px] font-semibold uppercase ${METHOD_STYLES[endpoint.method]}`}
+                              >
+                                {endpoint.method}
+                              </span>
+                              <span className="truncate font-mono text-xs" title={endpoint.path}>
+                                {endpoint.path}
+                              </span>
+                            </button>
+                          </li>
+                        );
+                      })}
+                    </ul>
+                  </div>
+                ))}
+              </nav>
+            </ScrollArea>
+          </CardContent>
+        </Card>
+
+        {selected ? (
+          <div className="space-y-6">
+            <Card>
+              <CardHeader>
+                <CardTitle className="flex items-center gap-2 font-mono text-base">
+                  <span
+                    className={`rounded px-1.5 text-xs uppercase ${METHOD_STYLES[selected.method]}`}
+                  >
+                    {selected.method}
+                  </span>
+                  {selected.path}
+                </CardTitle>
+                <CardDescription>
+                  {selected.operation.summary}
+                  {selected.operation.security?.length === 0 && " (no token required)"}
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="space-y-4">
+                {pathParams(selected.path).map((name) => (
+                  <div key={name} className="space-y-1">
+                    <Label htmlFor={`param-${name}`} className="font-mono text-xs">
+                      {name}
+                    </Label>
+                    <Input
+                      id={`param-${name}`}
+                      value={params[name] ?? ""}
+                      onChange={(e) => setParams({ ...params, [name]: e.target.value })}
+                      className="font-mono"
+                    />
+                  </div>
+                ))}
+                <div className="space-y-1">
+                  <Label htmlFor="explorer-query" className="text-xs">
+                    Query string
+                  </Label>
+                  <Input
+                    id="explorer-query"
+                    placeholder="limit=20&unread=true"
+                    value={query}
+                    onChange={(e) => setQuery(e.target.value)}
+                    className="font-mono"
+                  />
+                </div>
+                {hasBody(selected.method) && (
+                  <div className="space-y-1">
+                    <Label htmlFor="explorer-body" className="text-xs">
+                      JSON body
+                    </Label>
+                    <Textarea
+                      id="explorer-body"
+                      value={body}
+                      onChange={(e) => setBody(e.target.value)}
+                      rows={8}
+                      className="font-mono text-xs"
+                      spellCheck={false}
+                    />
+                  </div>
+                )}
+                <Button
+                  onClick={handleSend}
+                  disabled={sendMutation.isPending}
+                  variant={selected.method === "delete" ? "destructive" : "default"}
+                >
+                  <Send className="h-4 w-4 mr-2" aria-hidden="true" />
+                  {sendMutation.isPending ? "Sending..." : "Send request"}
+                </Button>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle className="text-base">Snippets</CardTitle>
+                <CardDescription>
+                  Set <code className="font-mono text-xs">RIVETR_TOKEN</code> to an API token
+                  (the CLI also reads <code className="font-mono text-xs">RIVETR_API_URL</code>).
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <Tabs defaultValue="curl">
+                  <TabsList>
+                    <TabsTrigger value="curl">curl</TabsTrigger>
+                    <TabsTrigger value="cli">rivetr CLI</TabsTrigger>
+                  </TabsList>
+                  {[
+                    { value: "curl", snippet: curlSnippet(selected, `${serverUrl}${requestPath}`, body) },
+                    { value: "cli", snippet: cliSnippet(selected, requestPath, body) },
+                  ].map(({ value, snippet }) => (
+                    <TabsContent key={value} value={value} className="relative">
+                      <pre className="overflow-x-auto rounded-md bg-muted p-3 pr-12 font-mono text-xs">
+                        {snippet}
+                      </pre>
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        className="absolute right-1 top-1 h-8 w-8"
+                        aria-label="Copy snippet"
+                        onClick={() => copy(snippet, "Snippet")}
+                      >
+                        <Copy className="h-4 w-4" aria-hidden="true" />
+                      </Button>
+                    </TabsContent>
+                  ))}
+                </Tabs>
+              </CardContent>
+            </Card>
+
+            {response && (
+              <Card>
+                <CardHeader>
+                  <CardTitle className="flex items-center gap-2 text-base">
+                    Response
+                    <Badge variant={response.status < 400 ? "secondary" : "destructive"}>
+                      {response.status} {response.statusText}
+                    </Badge>
+                    <span className="text-xs font-normal text-muted-foreground">
+                      {response.durationMs} ms
+                    </span>
+                  </CardTitle>
+                </CardHeader>
+                <CardContent>
+                  <pre
+                    className="max-h-[50vh] overflow-auto rounded-md bg-muted p-3 font-mono text-xs"
+                    aria-live="polite"
+                  >
+                    {formatBody(response) || "(empty body)"}
+                  </pre>
+                </CardContent>
+              </Card>
+            )}
+          </div>
+        ) : (
+          <Card>
+            <CardContent className="py-16 text-center text-sm text-muted-foreground">
+              Select an endpoint to try it and get its snippets.
+            </CardContent>
+          </Card>
+        )}
+      </div>
+
+      <AlertDialog open={confirmDelete} onOpenChange={setConfirmDelete}>
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle>Send DELETE request?</AlertDialogTitle>
+            <AlertDialogDescription>
+              This runs <span className="font-mono">DELETE {requestPath}</span> against this server
+              with your session and may permanently remove data.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel>Cancel</AlertDialogCancel>
+            <AlertDialogAction
+              className="bg-destructive text-destructive-foreground hover:bg-destructive/90"
+              onClick={() => sendMutation.mutate()}
+            >
+              Send
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+    </div>
+  );
+}
@@ -635,6 +635,7 @@ export default function BackupPage() {
                     </div>
                     <div className="text-xs text-muted-foreground font-mono">
                       {schedule.cron_expression}
+                      {schedule.timezone && ` (${schedule.timezone})`}
                     </div>
                     {schedule.last_run_at && (
                       <div className="text-xs text-muted-foreground">
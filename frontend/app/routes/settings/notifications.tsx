@@ -78,6 +78,7 @@ import {
   ResendConfigFields,
   SendryConfigFields,
 } from "@/components/notifications/channel-config-fields";
+import { NotificationDigestsCard } from "@/components/notifications/notification-digests-card";
 
 function formatDate(dateStr: string): string {
   return new Date(dateStr).toLocaleString();
@@ -149,6 +150,13 @@ const EVENT_TYPES: { value: NotificationEventType; label: string }[] = [
   { value: "app_stopped", label: "App Stopped" },
   { value: "container_crash", label: "Container Crashed" },
   { value: "container_restarted", label: "Container Restarted" },
+  { value: "app_scaled", label: "App Scaled" },
+  { value: "preview_expiring", label: "Preview Expiring" },
+  { value: "bandwidth_exceeded", label: "Bandwidth Limit Reached" },
+  { value: "backend_unhealthy", label: "Backend Unhealthy" },
+  { value: "backend_recovered", label: "Backend Recovered" },
+  { value: "deployment_rolled_back", label: "Deployment Rolled Back" },
+  { value: "deployment_cancelled", label: "Deployment Cancelled" },
 ];
 
 export function meta() {
@@ -695,6 +703,8 @@ export default function SettingsNotificationsPage() {
         </CardContent>
       </Card>
 
+      <NotificationDigestsCard channels={channels} />
+
       {/* Create Channel Dialog */}
       <Dialog
         open={showCreateDialog}
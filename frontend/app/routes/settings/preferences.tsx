@@ -1,5 +1,7 @@
 import { useState, useEffect } from "react";
-import { Moon, Sun, Monitor, SlidersHorizontal } from "lucide-react";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { SlidersHorizontal, Languages } from "lucide-react";
 import {
   Card,
   CardContent,
@@ -17,7 +19,16 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
+import { Checkbox } from "@/components/ui/checkbox";
+import { TimezoneInput } from "@/components/timezone-input";
 import { useTheme } from "@/components/providers/theme-provider";
+import { THEME_OPTIONS } from "@/components/theme-toggle";
+import { SUPPORTED_LOCALES, useI18n, type Locale } from "@/lib/i18n-context";
+import {
+  preferencesApi,
+  type NotificationSeverity,
+  type UpdateUserPreferencesRequest,
+} from "@/lib/api/preferences";
 
 export function meta() {
   return [
@@ -31,6 +42,157 @@ const PREF_LOG_LINES = "pref_log_lines";
 const PREF_DEPLOY_NOTIFY = "pref_deploy_notify";
 const PREF_COMPACT_MODE = "pref_compact_mode";
 
+const SEVERITIES: { value: NotificationSeverity; label: string; description: string }[] = [
+  { value: "info", label: "Info", description: "Deploys started or succeeded, apps started or scaled" },
+  { value: "warning", label: "Warning", description: "Rollbacks, stopped apps, container restarts" },
+  { value: "critical", label: "Critical", description: "Failed deploys and container crashes" },
+];
+
+/** Server-side notification preferences, applied wherever the user's email is a channel recipient */
+function EmailNotificationsCard() {
+  const queryClient = useQueryClient();
+  const { data: prefs } = useQuery({
+    queryKey: ["user-preferences"],
+    queryFn: preferencesApi.getPreferences,
+  });
+  const [timezone, setTimezone] = useState("");
+
+  useEffect(() => {
+    if (prefs) setTimezone(prefs.timezone ?? "");
+  }, [prefs]);
+
+  const updateMutation = useMutation({
+    mutationFn: (data: UpdateUserPreferencesRequest) => preferencesApi.updatePreferences(data),
+    onSuccess: (updated) => {
+      queryClient.setQueryData(["user-preferences"], updated);
+      toast.success("Notification preferences saved");
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || "Failed to save preferences");
+    },
+  });
+
+  const toggleSeverity = (severity: NotificationSeverity, checked: boolean) => {
+    if (!prefs) return;
+    const next = checked
+      ? [...prefs.notify_severities, severity]
+      : prefs.notify_severities.filter((s) => s !== severity);
+    updateMutation.mutate({ notify_severities: next });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>Email Notifications</CardTitle>
+        <CardDescription>
+          Applies to notification channels that email your account address. Other
+          recipients and team channels are not affected.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        <div className="space-y-3">
+          <Label>Severities to receive</Label>
+          {SEVERITIES.map((severity) => (
+            <div key={severity.value} className="flex items-start gap-2">
+              <Checkbox
+                id={`severity-${severity.value}`}
+                checked={prefs?.notify_severities.includes(severity.value) ?? true}
+                onCheckedChange={(checked) => toggleSeverity(severity.value, checked === true)}
+                disabled={!prefs || updateMutation.isPending}
+              />
+              <div className="space-y-0.5">
+                <Label htmlFor={`severity-${severity.value}`}>{severity.label}</Label>
+                <p className="text-xs text-muted-foreground">{severity.description}</p>
+              </div>
+            </div>
+          ))}
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="email-digests">Daily digest</Label>
+            <p className="text-xs text-muted-foreground">
+              Collect notification email into one message sent at 8 AM in your
+              timezone instead of one email per event.
+            </p>
+          </div>
+          <Switch
+            id="email-digests"
+            checked={prefs?.email_digests ?? false}
+            onCheckedChange={(checked) => updateMutation.mutate({ email_digests: checked })}
+            disabled={!prefs || updateMutation.isPending}
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="pref-timezone">Timezone</Label>
+          <div className="flex gap-2 max-w-md">
+            <TimezoneInput id="pref-timezone" value={timezone} onChange={setTimezone} />
+            <Button
+              variant="outline"
+              onClick={() => updateMutation.mutate({ timezone: timezone.trim() })}
+              disabled={!prefs || updateMutation.isPending || timezone === (prefs.timezone ?? "")}
+            >
+              Save
+            </Button>
+          </div>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
+/** Dashboard language, saved to the account so API messages and email use it too */
+function LanguageCard() {
+  const queryClient = useQueryClient();
+  const { locale, setLocale, t } = useI18n();
+
+  const updateMutation = useMutation({
+    mutationFn: (next: Locale) => preferencesApi.updatePreferences({ locale: next }),
+    onSuccess: (updated) => {
+      queryClient.setQueryData(["user-preferences"], updated);
+      toast.success(t("preferences.language.saved"));
+    },
+    onError: (error: Error) => {
+      toast.error(error.message || t("preferences.language.save_failed"));
+    },
+  });
+
+  const handleChange = (next: Locale) => {
+    setLocale(next);
+    updateMutation.mutate(next);
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Languages className="h-5 w-5" />
+          {t("preferences.language.title")}
+        </CardTitle>
+        <CardDescription>{t("preferences.language.description")}</CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="space-y-2">
+          <Label htmlFor="pref-locale">{t("preferences.language.label")}</Label>
+          <Select value={locale} onValueChange={(value) => handleChange(value as Locale)}>
+            <SelectTrigger id="pref-locale" className="w-56" disabled={updateMutation.isPending}>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {SUPPORTED_LOCALES.map((code) => (
+                <SelectItem key={code} value={code}>
+                  {t(`language.${code}`)}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
 function readPref<T>(key: string, fallback: T): T {
   if (typeof window === "undefined") return fallback;
   const stored = localStorage.getItem(key);
@@ -48,7 +210,7 @@ function writePref(key: string, value: unknown) {
 }
 
 export default function SettingsPreferencesPage() {
-  const { theme, setTheme } = useTheme();
+  const { theme, setTheme, reducedMotion, setReducedMotion } = useTheme();
 
   const [dateFormat, setDateFormat] = useState<"relative" | "absolute">(() =>
     readPref(PREF_DATE_FORMAT, "relative")
@@ -102,7 +264,9 @@ export default function SettingsPreferencesPage() {
         </h1>
         <p className="text-muted-foreground mt-1">
           Customize your personal dashboard experience. Changes are saved
-          instantly and stored locally in your browser.
+          instantly; appearance, language and email notification settings are
+          saved to your account, everything else is stored locally in your
+          browser.
         </p>
       </div>
 
@@ -112,47 +276,54 @@ export default function SettingsPreferencesPage() {
           <CardTitle>Appearance</CardTitle>
           <CardDescription>
             Choose how the dashboard looks. "System" follows your OS setting.
+            Saved to your account.
           </CardDescription>
         </CardHeader>
-        <CardContent>
+        <CardContent className="space-y-6">
           <div className="space-y-2">
-            <Label>Theme</Label>
-            <div className="flex gap-2 flex-wrap">
-              <Button
-                variant={theme === "light" ? "default" : "outline"}
-                size="sm"
-                className="gap-2"
-                onClick={() => setTheme("light")}
-              >
-                <Sun className="h-4 w-4" />
-                Light
-              </Button>
-              <Button
-                variant={theme === "dark" ? "default" : "outline"}
-                size="sm"
-                className="gap-2"
-                onClick={() => setTheme("dark")}
-              >
-                <Moon className="h-4 w-4" />
-                Dark
-              </Button>
-              <Button
-                variant={theme === "system" ? "default" : "outline"}
-                size="sm"
-                className="gap-2"
-                onClick={() => setTheme("system")}
-              >
-                <Monitor className="h-4 w-4" />
-                System
-              </Button>
+            <Label id="theme-label">Theme</Label>
+            <div role="radiogroup" aria-labelledby="theme-label" className="flex gap-2 flex-wrap">
+              {THEME_OPTIONS.map(({ value, label, icon: Icon }) => (
+                <Button
+                  key={value}
+                  role="radio"
+                  aria-checked={theme === value}
+                  variant={theme === value ? "default" : "outline"}
+                  size="sm"
+                  className="gap-2"
+                  onClick={() => setTheme(value)}
+                >
+                  <Icon className="h-4 w-4" aria-hidden="true" />
+                  {label}
+                </Button>
+              ))}
             </div>
             <p className="text-xs text-muted-foreground">
-              Currently active: <span className="font-medium">{theme}</span>
+              High contrast uses black and white with solid borders and a bright
+              focus outline.
             </p>
           </div>
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="reduced-motion">Reduce motion</Label>
+              <p id="reduced-motion-description" className="text-xs text-muted-foreground">
+                Turn off animations and transitions. Also applied when your OS
+                asks for reduced motion.
+              </p>
+            </div>
+            <Switch
+              id="reduced-motion"
+              aria-describedby="reduced-motion-description"
+              checked={reducedMotion}
+              onCheckedChange={setReducedMotion}
+            />
+          </div>
         </CardContent>
       </Card>
 
+      <LanguageCard />
+
       {/* Date/time format */}
       <Card>
         <CardHeader>
@@ -240,6 +411,8 @@ export default function SettingsPreferencesPage() {
         </CardContent>
       </Card>
 
+      <EmailNotificationsCard />
+
       {/* Compact mode */}
       <Card>
         <CardHeader>
@@ -47,6 +47,7 @@ export default function SettingsSshKeysPage() {
   const [name, setName] = useState("");
   const [privateKey, setPrivateKey] = useState("");
   const [publicKey, setPublicKey] = useState("");
+  const [jumpHost, setJumpHost] = useState("");
 
   const { data: sshKeys = [], isLoading } = useQuery<SshKey[]>({
     queryKey: ["ssh-keys"],
@@ -59,6 +60,7 @@ export default function SettingsSshKeysPage() {
         name: name.trim(),
         private_key: privateKey.trim(),
         public_key: publicKey.trim() || undefined,
+        jump_host: jumpHost.trim() || undefined,
         is_global: true,
       }),
     onSuccess: () => {
@@ -85,10 +87,25 @@ export default function SettingsSshKeysPage() {
     },
   });
 
+  const testMutation = useMutation({
+    mutationFn: (keyId: string) => api.testSshKey(keyId),
+    onSuccess: (result) => {
+      if (result.success) {
+        toast.success(result.message);
+      } else {
+        toast.error(result.message);
+      }
+    },
+    onError: (error) => {
+      toast.error(error instanceof Error ? error.message : "Failed to test SSH key");
+    },
+  });
+
   const resetForm = () => {
     setName("");
     setPrivateKey("");
     setPublicKey("");
+    setJumpHost("");
   };
 
   const handleCreateSubmit = (e: React.FormEvent) => {
@@ -144,8 +161,9 @@ export default function SettingsSshKeysPage() {
                 <TableRow>
                   <TableHead>Name</TableHead>
                   <TableHead>Scope</TableHead>
+                  <TableHead>Jump Host</TableHead>
                   <TableHead>Created</TableHead>
-                  <TableHead className="w-24">Actions</TableHead>
+                  <TableHead className="w-40">Actions</TableHead>
                 </TableRow>
               </TableHeader>
               <TableBody>
@@ -159,8 +177,21 @@ export default function SettingsSshKeysPage() {
                         <Badge variant="outline">App-specific</Badge>
                       )}
                     </TableCell>
+                    <TableCell className="font-mono text-xs">
+                      {key.jump_host || <span className="text-muted-foreground">None</span>}
+                    </TableCell>
                     <TableCell>{formatDate(key.created_at)}</TableCell>
-                    <TableCell>
+                    <TableCell className="flex gap-2">
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        onClick={() => testMutation.mutate(key.id)}
+                        disabled={testMutation.isPending}
+                      >
+                        {testMutation.isPending && testMutation.variables === key.id
+                          ? "Testing..."
+                          : "Test"}
+                      </Button>
                       <Button
                         variant="destructive"
                         size="sm"
@@ -234,6 +265,20 @@ export default function SettingsSshKeysPage() {
                   Optionally provide the public key for reference
                 </p>
               </div>
+              <div className="space-y-2">
+                <Label htmlFor="jump_host">Jump Host (optional)</Label>
+                <Input
+                  id="jump_host"
+                  value={jumpHost}
+                  onChange={(e) => setJumpHost(e.target.value)}
+                  placeholder="deploy@bastion.example.com:22"
+                  className="font-mono text-sm"
+                />
+                <p className="text-xs text-muted-foreground">
+                  For repositories only reachable through a bastion. Clones connect through this
+                  host, which must accept the same key.
+                </p>
+              </div>
             </div>
             <DialogFooter>
               <Button
@@ -29,6 +29,7 @@ import { Badge } from "@/components/ui/badge";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
+import { TemplateResourceSelect } from "@/components/template-resource-select";
 import {
   Dialog,
   DialogContent,
@@ -121,7 +122,9 @@ export default function TemplatesPage() {
   const [expandedCategories, setExpandedCategories] = useState<Set<string>>(new Set());
   const [selectedTemplate, setSelectedTemplate] = useState<ServiceTemplate | null>(null);
   const [deployName, setDeployName] = useState("");
+  const [deployDomain, setDeployDomain] = useState("");
   const [envVars, setEnvVars] = useState<Record<string, string>>({});
+  const [resources, setResources] = useState<Record<string, string>>({});
   const [showSecrets, setShowSecrets] = useState<Record<string, boolean>>({});
 
   const { data: templates = [], isLoading } = useQuery<ServiceTemplate[]>({
@@ -184,7 +187,9 @@ export default function TemplatesPage() {
       defaults[entry.name] = entry.default || "";
     }
     setEnvVars(defaults);
+    setResources({});
     setShowSecrets({});
+    setDeployDomain("");
   };
 
   const handleDeploy = () => {
@@ -195,6 +200,8 @@ export default function TemplatesPage() {
       request: {
         name: deployName.trim(),
         env_vars: envVars,
+        resources,
+        domain: deployDomain.trim() || undefined,
       },
     });
   };
@@ -401,6 +408,20 @@ export default function TemplatesPage() {
               </p>
             </div>
 
+            <div className="space-y-2">
+              <Label htmlFor="deploy-domain">Domain (optional)</Label>
+              <Input
+                id="deploy-domain"
+                value={deployDomain}
+                onChange={(e) => setDeployDomain(e.target.value)}
+                placeholder="Generated subdomain"
+              />
+              <p className="text-xs text-muted-foreground">
+                Routed to the service by the proxy. Leave empty to get a subdomain of the
+                server's base domain.
+              </p>
+            </div>
+
             {selectedTemplate?.env_schema && selectedTemplate.env_schema.length > 0 && (
               <div className="space-y-4">
                 <Label>Configuration</Label>
@@ -410,34 +431,45 @@ export default function TemplatesPage() {
                       {entry.label}
                       {entry.required && <span className="text-destructive ml-1">*</span>}
                     </Label>
-                    <div className="relative">
-                      <Input
+                    {entry.source ? (
+                      <TemplateResourceSelect
                         id={`env-${entry.name}`}
-                        type={entry.secret && !showSecrets[entry.name] ? "password" : "text"}
-                        value={envVars[entry.name] || ""}
-                        onChange={(e) =>
-                          setEnvVars((prev) => ({ ...prev, [entry.name]: e.target.value }))
+                        source={entry.source}
+                        value={resources[entry.name] || ""}
+                        onChange={(resourceId) =>
+                          setResources((prev) => ({ ...prev, [entry.name]: resourceId }))
                         }
-                        placeholder={entry.default || `Enter ${entry.label.toLowerCase()}`}
-                        required={entry.required}
-                        className={entry.secret ? "pr-10" : ""}
                       />
-                      {entry.secret && (
-                        <Button
-                          type="button"
-                          variant="ghost"
-                          size="icon"
-                          className="absolute right-0 top-0 h-full px-3"
-                          onClick={() => toggleSecretVisibility(entry.name)}
-                        >
-                          {showSecrets[entry.name] ? (
-                            <EyeOff className="h-4 w-4" />
-                          ) : (
-                            <Eye className="h-4 w-4" />
-                          )}
-                        </Button>
-                      )}
-                    </div>
+                    ) : (
+                      <div className="relative">
+                        <Input
+                          id={`env-${entry.name}`}
+                          type={entry.secret && !showSecrets[entry.name] ? "password" : "text"}
+                          value={envVars[entry.name] || ""}
+                          onChange={(e) =>
+                            setEnvVars((prev) => ({ ...prev, [entry.name]: e.target.value }))
+                          }
+                          placeholder={entry.default || `Enter ${entry.label.toLowerCase()}`}
+                          required={entry.required}
+                          className={entry.secret ? "pr-10" : ""}
+                        />
+                        {entry.secret && (
+                          <Button
+                            type="button"
+                            variant="ghost"
+                            size="icon"
+                            className="absolute right-0 top-0 h-full px-3"
+                            onClick={() => toggleSecretVisibility(entry.name)}
+                          >
+                            {showSecrets[entry.name] ? (
+                              <EyeOff className="h-4 w-4" />
+                            ) : (
+                              <Eye className="h-4 w-4" />
+                            )}
+                          </Button>
+                        )}
+                      </div>
+                    )}
                   </div>
                 ))}
               </div>
@@ -10,7 +10,8 @@ export type BuildType =
   | "nixpacks"
   | "railpack"
   | "cnb"
-  | "staticsite";
+  | "staticsite"
+  | "docker-compose";
 
 /** Deployment source type */
 export type DeploymentSource = "git" | "upload" | "registry";
@@ -29,6 +30,40 @@ export interface BuildDetectionResult {
   framework?: string;
   /** Detected language (e.g., "javascript", "typescript", "python") */
   language?: string;
+  /** Named stages of a multi-stage Dockerfile, usable as build_target */
+  build_targets?: string[];
+  /** Ports the Dockerfile's final stage exposes */
+  exposed_ports?: number[];
+  /** Port to pre-fill (first exposed port, else the healthcheck's) */
+  suggested_port?: number | null;
+  /** Hint from the Dockerfile's HEALTHCHECK */
+  healthcheck?: HealthcheckHint | null;
+}
+
+/** Recommended Dockerfile generated for a repository or upload */
+export interface DockerfileSuggestion {
+  /** Build detection of the source */
+  detection: BuildDetectionResult;
+  /** Detected stack: "node", "python", "go" or "rust" */
+  stack: string;
+  /** Files the stack was detected from */
+  detected_from: string;
+  dockerfile: string;
+  /** Port the generated image listens on */
+  port: number;
+  /** Assumptions worth checking before using the Dockerfile */
+  notes: string[];
+  /** Repository path a deployment patch must use to override the app's Dockerfile */
+  override_path?: string;
+}
+
+/** Health check declared by a Dockerfile HEALTHCHECK instruction */
+export interface HealthcheckHint {
+  command: string;
+  /** HTTP path the command requests on the container, if any */
+  path: string | null;
+  /** Port the command requests on the container, if any */
+  port: number | null;
 }
 
 /** Response from upload deploy endpoint */
@@ -62,6 +97,43 @@ export interface NixpacksConfig {
   packages?: string[];
   /** Additional apt packages to install */
   apt_packages?: string[];
+  /** Providers to use instead of auto-detection ("..." keeps the detected ones) */
+  providers?: string[];
+  /** Pinned Nixpacks CLI version (e.g. "1.29.0") */
+  version?: string;
+}
+
+/** Cloud Native Buildpacks builder */
+export type CnbBuilder =
+  | "paketobase"
+  | "paketofull"
+  | "paketotiny"
+  | "heroku24"
+  | "heroku22"
+  | "custom";
+
+/** Cloud Native Buildpacks configuration for "cnb" builds */
+export interface CnbConfig {
+  /** Builder to use (auto-selected from the project files when unset) */
+  builder?: CnbBuilder;
+  /** Builder image when builder is "custom" */
+  custom_builder?: string;
+  /** Additional buildpacks to include */
+  buildpacks?: string[];
+  /** Pull policy for the builder image */
+  pull_policy?: "always" | "if-not-present" | "never";
+}
+
+/** Build settings for "staticsite" builds (output directory is publish_directory) */
+export interface StaticSiteConfig {
+  /** Build command (defaults to the package manager's build script) */
+  build_command?: string;
+  /** Dependency install command */
+  install_command?: string;
+  /** Image the build runs in, e.g. "hugomods/hugo:exts" */
+  build_image?: string;
+  /** Serve index.html for unknown paths (default true) */
+  spa_mode?: boolean;
 }
 
 // -------------------------------------------------------------------------
@@ -105,6 +177,10 @@ export interface PreviewDeployment {
   created_at: string;
   updated_at: string;
   closed_at: string | null;
+  /** Last request the proxy served for the preview */
+  last_activity_at: string | null;
+  /** Throwaway managed database provisioned for the preview */
+  database_id: string | null;
 }
 
 // -------------------------------------------------------------------------
@@ -249,6 +325,8 @@ export interface App {
   // Deployment commands (stored as JSON strings)
   pre_deploy_commands: string | null;
   post_deploy_commands: string | null;
+  /** Deployment fence names (JSON array) */
+  deploy_fences: string | null;
   // Domain management (stored as JSON string)
   domains: string | null;
   auto_subdomain: string | null;
@@ -262,6 +340,14 @@ export interface App {
   // Build type configuration
   build_type: BuildType;
   nixpacks_config: string | null;
+  /** Cloud Native Buildpacks configuration (JSON) */
+  cnb_config: string | null;
+  /** Static site build settings (JSON) */
+  static_config: string | null;
+  /** Compose file of a "docker-compose" app */
+  compose_file: string | null;
+  /** Compose service receiving the app's traffic */
+  compose_service: string | null;
   publish_directory: string | null;
   // Preview deployments
   preview_enabled: boolean;
@@ -274,8 +360,62 @@ export interface App {
   registry_push_enabled: boolean;
   max_rollback_versions: number;
   rollback_retention_count: number;
+  /** Seconds a new deployment has to pass its health check */
+  healthcheck_window_secs: number;
+  /** Regex of further branches whose pushes deploy the app */
+  webhook_branch_regex: string | null;
+  /** Regex of commit authors whose pushes don't deploy the app */
+  webhook_ignore_authors: string | null;
+  /** Regex of paths; pushes changing only matching files don't deploy */
+  webhook_ignore_paths: string | null;
   // Replica settings
   replica_count: number;
+  /** New replicas started above replica_count per rolling deploy batch */
+  rolling_max_surge: number;
+  /** Old replicas that may leave the pool before their replacements are healthy */
+  rolling_max_unavailable: number;
+  /** Dependencies checked before a deployment starts its container */
+  deploy_wait_conditions: WaitCondition[];
+  /** HTTP checks run against a new container once it is healthy */
+  smoke_tests: SmokeTest[];
+  /** Deployments the cleanup task keeps for this app (null = global setting) */
+  max_deployments: number | null;
+  /** How many of the newest kept deployments keep their image (null = all) */
+  keep_images: number | null;
+  /** When the app was locked against deployments (null = unlocked) */
+  locked_at: string | null;
+  /** Name of the user holding the deployment lock */
+  locked_by: string | null;
+  /** Reason given when the deployment lock was taken */
+  lock_reason: string | null;
+  /** Test stage commands run against the built image (JSON array) */
+  test_commands: string | null;
+  /** Seconds all test commands may take together (null = 600) */
+  test_timeout_secs: number | null;
+  /** Hours after creation a preview deployment is torn down (null = never) */
+  preview_max_lifetime_hours: number | null;
+  /** Hours without requests after which a preview is torn down (null = never) */
+  preview_idle_timeout_hours: number | null;
+  /** Container port serving Prometheus metrics (null = not scraped) */
+  metrics_port: number | null;
+  /** Path of the metrics endpoint (null = /metrics) */
+  metrics_path: string | null;
+  /** Database provisioned for each preview (null = none) */
+  preview_database: PreviewDatabaseMode | null;
+  /** Managed database preview databases are modelled on */
+  preview_database_source_id: string | null;
+  /** Env var receiving the preview database's connection URL (null = DATABASE_URL) */
+  preview_database_env: string | null;
+  /** Minutes without requests after which the app sleeps (null = always running) */
+  idle_timeout_minutes: number | null;
+  /** When the app was stopped for inactivity (null = awake) */
+  sleeping_since: string | null;
+  /** Monthly cap on proxy traffic in GB (null = unlimited) */
+  bandwidth_limit_gb: number | null;
+  /** What happens once the cap is exceeded */
+  bandwidth_limit_action: BandwidthLimitAction;
+  /** Throttled rate in kilobits per second (null = 1000) */
+  bandwidth_throttle_kbps: number | null;
   // Deployment approval and maintenance mode
   require_approval: boolean;
   maintenance_mode: boolean;
@@ -334,10 +474,46 @@ export interface App {
   destination_id: string | null;
   /** Custom container labels (JSON array: [{key, value}]) applied at deployment time */
   custom_labels: string | null;
+  /** Outbound network policy */
+  egress_policy: EgressPolicy;
+  /** Hosts and IPv4 CIDRs a restricted app may reach */
+  egress_allowlist: string[];
+  /** How health checks probe the app */
+  healthcheck_type: HealthcheckType;
+  /** Command run in the container for "command" health checks */
+  healthcheck_command: string | null;
+  /** "worker" apps run a background process with no port, domain or proxy route */
+  app_type: AppType;
   created_at: string;
   updated_at: string;
 }
 
+/** "allow_all" leaves outbound traffic open; "restricted" denies all but the allow-list */
+export type EgressPolicy = "allow_all" | "restricted";
+
+/** A dependency a deployment waits for before starting its container */
+export type WaitCondition =
+  | { type: "database"; database_id: string; timeout_seconds: number }
+  | { type: "http"; url: string; timeout_seconds: number };
+
+/** An HTTP check a new container must pass once it is healthy */
+export interface SmokeTest {
+  /** Path requested from the container, starting with "/" */
+  path: string;
+  expected_status: number;
+  /** Text the response body must contain */
+  expected_body?: string | null;
+}
+
+/** "http" GETs the healthcheck path, "tcp" connects to the port, "command" runs healthcheck_command */
+export type HealthcheckType = "http" | "tcp" | "command";
+
+/** "web" serves HTTP behind the proxy, "worker" runs a background process */
+export type AppType = "web" | "worker";
+
+/** "empty" starts each preview database blank, "schema" copies the source's schema */
+export type PreviewDatabaseMode = "empty" | "schema";
+
 // HTTP Basic Auth
 export interface BasicAuthStatus {
   enabled: boolean;
@@ -421,16 +597,20 @@ export interface ProjectEnvironment {
   is_default: boolean;
   created_at: string;
   updated_at: string;
+  /** Deployments of apps in this environment wait for approval */
+  require_approval: boolean;
 }
 
 export interface CreateEnvironmentRequest {
   name: string;
   description?: string;
+  require_approval?: boolean;
 }
 
 export interface UpdateEnvironmentRequest {
   name?: string;
   description?: string;
+  require_approval?: boolean;
 }
 
 export interface EnvironmentEnvVar {
@@ -465,6 +645,8 @@ export interface CreateAppRequest {
   dockerfile?: string;
   domain?: string;
   port?: number;
+  /** "worker" apps need no port or domain */
+  app_type?: AppType;
   healthcheck?: string;
   cpu_limit?: string;
   memory_limit?: string;
@@ -485,6 +667,8 @@ export interface CreateAppRequest {
   // Deployment commands
   pre_deploy_commands?: string[];
   post_deploy_commands?: string[];
+  /** Empty array clears them */
+  deploy_fences?: string[];
   // Domain management
   domains?: Domain[];
   // Docker Registry support (alternative to git-based deployments)
@@ -504,6 +688,7 @@ export interface CreateAppRequest {
   build_type?: BuildType;
   nixpacks_config?: NixpacksConfig;
   publish_directory?: string;
+  static_config?: StaticSiteConfig;
   // Preview deployments
   preview_enabled?: boolean;
   // GitHub App installation
@@ -556,6 +741,14 @@ export interface UpdateAppRequest {
   // Build type configuration
   build_type?: BuildType;
   nixpacks_config?: NixpacksConfig;
+  /** Empty object clears it */
+  cnb_config?: CnbConfig;
+  /** Empty object clears it */
+  static_config?: StaticSiteConfig;
+  /** Empty string clears it */
+  compose_file?: string;
+  /** Empty string clears it */
+  compose_service?: string;
   publish_directory?: string;
   // Preview deployments
   preview_enabled?: boolean;
@@ -568,6 +761,11 @@ export interface UpdateAppRequest {
   registry_push_enabled?: boolean;
   max_rollback_versions?: number;
   rollback_retention_count?: number;
+  healthcheck_window_secs?: number;
+  // Webhook filters (regular expressions); empty string clears
+  webhook_branch_regex?: string;
+  webhook_ignore_authors?: string;
+  webhook_ignore_paths?: string;
   // Deployment approval and maintenance
   require_approval?: boolean;
   maintenance_mode?: boolean;
@@ -626,6 +824,48 @@ export interface UpdateAppRequest {
   destination_id?: string;
   /** Custom container labels (JSON string: [{key, value}]), set to empty string to clear */
   custom_labels?: string;
+  /** Outbound network policy */
+  egress_policy?: EgressPolicy;
+  /** Hosts and IPv4 CIDRs a restricted app may reach, empty array to clear */
+  egress_allowlist?: string[];
+  /** How health checks probe the app */
+  healthcheck_type?: HealthcheckType;
+  /** Command for "command" health checks, set to empty string to clear */
+  healthcheck_command?: string;
+  /** Dependencies checked before the container starts, empty array to clear */
+  deploy_wait_conditions?: WaitCondition[];
+  /** HTTP checks run once the new container is healthy, empty array to clear */
+  smoke_tests?: SmokeTest[];
+  /** Deployments the cleanup task keeps (0 clears to the global setting) */
+  max_deployments?: number;
+  /** How many of the newest kept deployments keep their image (0 clears to all) */
+  keep_images?: number;
+  /** Test stage commands run against the built image, empty array to clear */
+  test_commands?: string[];
+  /** Seconds all test commands may take together (0 clears to the default) */
+  test_timeout_secs?: number;
+  /** Hours after creation a preview is torn down (0 clears the limit) */
+  preview_max_lifetime_hours?: number;
+  /** Hours without requests after which a preview is torn down (0 clears the limit) */
+  preview_idle_timeout_hours?: number;
+  /** Container port serving Prometheus metrics (0 stops scraping) */
+  metrics_port?: number;
+  /** Path of the metrics endpoint; empty string clears it */
+  metrics_path?: string;
+  /** Database provisioned for each preview; empty string stops provisioning */
+  preview_database?: PreviewDatabaseMode | "";
+  /** Managed database preview databases are modelled on; empty string clears it */
+  preview_database_source_id?: string;
+  /** Env var receiving the preview database's URL; empty string clears to DATABASE_URL */
+  preview_database_env?: string;
+  /** Minutes without requests after which the app sleeps (0 keeps it always running) */
+  idle_timeout_minutes?: number;
+  /** Monthly cap on proxy traffic in GB (0 removes the cap) */
+  bandwidth_limit_gb?: number;
+  bandwidth_limit_action?: BandwidthLimitAction;
+  /** Throttled rate in kilobits per second (0 uses the default) */
+  bandwidth_throttle_kbps?: number;
+  app_type?: AppType;
 }
 
 // -------------------------------------------------------------------------
@@ -666,6 +906,8 @@ export interface SshKey {
   public_key: string | null;
   app_id: string | null;
   is_global: boolean;
+  /** Bastion the clone connects through, as [user@]host[:port] */
+  jump_host: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -676,6 +918,7 @@ export interface CreateSshKeyRequest {
   public_key?: string;
   app_id?: string;
   is_global?: boolean;
+  jump_host?: string;
 }
 
 export interface UpdateSshKeyRequest {
@@ -684,6 +927,15 @@ export interface UpdateSshKeyRequest {
   public_key?: string;
   app_id?: string | null;
   is_global?: boolean;
+  /** Empty string removes the jump host */
+  jump_host?: string;
+}
+
+/** Result of an SSH key connectivity check */
+export interface SshKeyTestResult {
+  success: boolean;
+  message: string;
+  git_url?: string;
 }
 
 // -------------------------------------------------------------------------
@@ -984,11 +1236,21 @@ export interface CreateSnapshotRequest {
 /** Request to clone an app */
 export interface CloneAppRequest {
   name?: string;
+  /** Project to create the clone in (defaults to the original's) */
+  project_id?: string;
+  /** Environment to create the clone in (defaults to the original's or the project's default) */
+  environment_id?: string;
+  /** Give the clone a freshly generated domain (otherwise it has none) */
+  generate_domain?: boolean;
+  /** Copy the files in the original's volumes (otherwise they start empty) */
+  copy_volumes?: boolean;
 }
 
 /** Response from clone app */
 export interface CloneAppResponse {
   app: App;
+  /** Volumes whose files could not be copied; they start empty */
+  volumes_not_copied?: string[];
 }
 
 /** Request to toggle maintenance mode */
@@ -1004,6 +1266,18 @@ export interface MaintenanceModeResponse {
   maintenance_message: string | null;
 }
 
+export interface LockAppRequest {
+  reason?: string;
+}
+
+export interface AppLockResponse {
+  app_id: string;
+  locked: boolean;
+  locked_at: string | null;
+  locked_by: string | null;
+  lock_reason: string | null;
+}
+
 // -------------------------------------------------------------------------
 // Project Export / Import
 // -------------------------------------------------------------------------
@@ -1141,3 +1415,30 @@ export interface CloneEnvironmentResponse {
   cloned_databases: number;
   cloned_services: number;
 }
+
+/** What happens once an app exceeds its monthly bandwidth cap */
+export type BandwidthLimitAction = "notify" | "throttle";
+
+/** Proxy traffic of one of an app's domains */
+export interface RouteBandwidth {
+  domain: string;
+  bytes_in: number;
+  bytes_out: number;
+}
+
+/** An app's bandwidth usage for a month (GET /api/apps/:id/bandwidth) */
+export interface AppBandwidth {
+  app_id: string;
+  /** Month as YYYY-MM (UTC) */
+  period: string;
+  bytes_in: number;
+  bytes_out: number;
+  routes: RouteBandwidth[];
+  /** Traffic of the app's containers, including what bypasses the proxy */
+  container_rx_bytes: number;
+  container_tx_bytes: number;
+  limit_gb: number | null;
+  limit_action: BandwidthLimitAction;
+  used_percent: number | null;
+  throttled: boolean;
+}
@@ -52,6 +52,16 @@ export interface ManagedDatabase {
   custom_image: string | null;
   /** JSON array of SQL init commands to run after first start */
   init_commands: string | null;
+  /** SQL (mongosh script for MongoDB) loaded once after first provisioning */
+  seed_script: string | null;
+  /** File name of the uploaded seed dump, loaded after the script */
+  seed_dump_file: string | null;
+  /** Whether environment clones get a copy of the seed */
+  seed_on_clone: boolean;
+  /** When the seed was loaded (null = not yet) */
+  seeded_at: string | null;
+  /** Error of the last failed seed attempt */
+  seed_error: string | null;
 }
 
 /** Request to create a managed database */
@@ -76,6 +86,10 @@ export interface CreateManagedDatabaseRequest {
   custom_image?: string;
   /** JSON array of SQL init commands, e.g. '["CREATE EXTENSION postgis;"]' */
   init_commands?: string;
+  /** Seed script loaded once after first provisioning */
+  seed_script?: string;
+  /** Copy the seed to environment clones */
+  seed_on_clone?: boolean;
 }
 
 /** Request to update a managed database */
@@ -98,6 +112,10 @@ export interface UpdateManagedDatabaseRequest {
   init_commands?: string;
   /** Move to a project (empty string = unassign / no project) */
   project_id?: string;
+  /** Seed script (empty string = remove) */
+  seed_script?: string;
+  /** Copy the seed to environment clones */
+  seed_on_clone?: boolean;
 }
 
 /** Database type configuration (for UI) */
@@ -158,6 +176,8 @@ export interface DatabaseBackupSchedule {
   retention_count: number;
   last_run_at?: string;
   next_run_at?: string;
+  /** IANA timezone of schedule_hour/schedule_day (null = UTC) */
+  timezone: string | null;
   created_at: string;
 }
 
@@ -168,6 +188,8 @@ export interface CreateBackupScheduleRequest {
   schedule_hour?: number;
   schedule_day?: number;
   retention_count?: number;
+  /** IANA timezone; empty string resets to UTC */
+  timezone?: string;
 }
 
 /** Available database configurations */
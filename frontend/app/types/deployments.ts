@@ -13,8 +13,15 @@ export interface Deployment {
   built_at: string | null;
   container_id: string | null;
   error_message: string | null;
+  /** Recognized failure cause, e.g. "clone_auth_failed" */
+  error_code?: string | null;
+  /** How to fix the recognized failure */
+  error_hint?: string | null;
   commit_sha: string | null;
   commit_message: string | null;
+  commit_author?: string | null;
+  commit_branch?: string | null;
+  commit_compare_url?: string | null;
   git_tag: string | null;
   // Approval workflow fields
   approval_status: "pending" | "approved" | "rejected" | null;
@@ -27,6 +34,26 @@ export interface Deployment {
   image_tag: string | null;
   // How the deployment was initiated: 'manual', 'webhook', 'rollback', 'restart', 'scheduled'
   trigger: string | null;
+  /** 1 when the deployment failed its health check and the previous one was restored */
+  rolled_back?: number;
+  /** Output of the test stage commands */
+  test_output?: string | null;
+  /** Versioned tag of the built image, `rivetr/<app>:<short-sha>` */
+  version_tag?: string | null;
+}
+
+/** A locally available versioned image of an app, for rollback selection */
+export interface AppImage {
+  /** Local reference, e.g. "rivetr/web:3f2a9c1" */
+  tag: string;
+  image_id: string;
+  created_at: string | null;
+  size_bytes: number;
+  /** Newest deployment that built this tag */
+  deployment_id: string | null;
+  commit_sha: string | null;
+  commit_message: string | null;
+  deployment_status: DeploymentStatus | null;
 }
 
 /** Git commit info from the commits list API */
@@ -76,7 +103,8 @@ export type DeploymentStatus =
   | "failed"
   | "stopped"
   | "replaced"
-  | "cancelled";
+  | "cancelled"
+  | "prewarmed";
 
 export interface DeploymentLog {
   id: string;
@@ -84,6 +112,38 @@ export interface DeploymentLog {
   level: "info" | "warn" | "error";
   message: string;
   timestamp: string;
+  /** Pipeline phase the line was written in (clone, build, test, push, start, healthcheck) */
+  phase?: string | null;
+}
+
+/** One timed phase of the deployment pipeline */
+export interface DeploymentPhase {
+  id: number;
+  deployment_id: string;
+  phase: "clone" | "build" | "test" | "push" | "start" | "healthcheck";
+  status: "running" | "succeeded" | "failed" | "cancelled";
+  started_at: string;
+  finished_at: string | null;
+  /** Milliseconds spent in this phase; null while it is still running */
+  duration_ms: number | null;
+}
+
+/** One status transition of a deployment, with time spent in that status */
+export interface DeploymentTimelineStep {
+  status: DeploymentStatus;
+  message: string | null;
+  started_at: string;
+  /** Milliseconds spent in this status; null for a terminal final status */
+  duration_ms: number | null;
+}
+
+export interface DeploymentTimeline {
+  deployment_id: string;
+  status: DeploymentStatus;
+  started_at: string;
+  finished_at: string | null;
+  steps: DeploymentTimelineStep[];
+  phases: DeploymentPhase[];
 }
 
 /** Deployment freeze window: prevents deployments during a specified time range */
@@ -92,14 +152,20 @@ export interface DeploymentFreezeWindow {
   app_id: string | null;
   team_id: string | null;
   name: string;
-  /** Start time in HH:MM UTC format */
+  /** Start time in HH:MM format, in `timezone` */
   start_time: string;
-  /** End time in HH:MM UTC format */
+  /** End time in HH:MM format, in `timezone` */
   end_time: string;
   /** Comma-separated days of week: 0=Sun, 1=Mon, ..., 6=Sat */
   days_of_week: string;
   is_active: boolean;
   created_at: string;
+  /** IANA timezone of the times and days (null = UTC) */
+  timezone: string | null;
+  /** Start of a calendar range (RFC 3339); replaces the recurring times and days */
+  starts_at: string | null;
+  /** End of a calendar range (RFC 3339) */
+  ends_at: string | null;
 }
 
 /** Request body for creating a freeze window */
@@ -108,10 +174,78 @@ export interface CreateFreezeWindowRequest {
   start_time: string;
   end_time: string;
   days_of_week: string;
+  /** IANA timezone, e.g. "Europe/Berlin" (default UTC) */
+  timezone?: string;
+  /** Calendar range instead of recurring times (RFC 3339) */
+  starts_at?: string;
+  ends_at?: string;
   app_id?: string;
   team_id?: string;
 }
 
+/** Power schedule: stops an app and starts it again on cron expressions */
+export interface AppPowerSchedule {
+  app_id: string;
+  enabled: number;
+  /** When to stop the app, e.g. "0 19 * * Mon-Fri" */
+  stop_cron: string;
+  /** When to start the app again, e.g. "0 8 * * Mon-Fri" */
+  start_cron: string;
+  /** Message shown on the app's domains while stopped (null = default) */
+  stopped_message: string | null;
+  next_stop_at: string | null;
+  next_start_at: string | null;
+  /** Set while the schedule keeps the app stopped */
+  stopped_at: string | null;
+  created_at: string;
+  updated_at: string;
+  /** IANA timezone the cron expressions run in (null = UTC) */
+  timezone: string | null;
+}
+
+/** Request body for creating or updating a power schedule */
+export interface UpdatePowerScheduleRequest {
+  enabled?: boolean;
+  stop_cron?: string;
+  start_cron?: string;
+  stopped_message?: string;
+  /** IANA timezone; empty string resets to UTC */
+  timezone?: string;
+}
+
+/** Pre-warm settings: build an app's next image ahead of release */
+export interface AppPrewarmSettings {
+  app_id: string;
+  enabled: number;
+  /** Off-peak schedule for pre-warm builds, null = on demand only */
+  schedule_cron: string | null;
+  /** Push webhooks fill the slot instead of deploying */
+  on_push: number;
+  next_run_at: string | null;
+  /** Deployment currently holding the slot */
+  deployment_id: string | null;
+  created_at: string;
+  updated_at: string;
+  /** IANA timezone the schedule runs in (null = UTC) */
+  timezone: string | null;
+}
+
+/** Pre-warm settings together with the current slot deployment */
+export interface PrewarmStatus {
+  settings: AppPrewarmSettings | null;
+  slot: Deployment | null;
+}
+
+/** Request body for creating or updating pre-warm settings */
+export interface UpdatePrewarmRequest {
+  enabled?: boolean;
+  /** Empty string clears the schedule */
+  schedule_cron?: string;
+  on_push?: boolean;
+  /** IANA timezone; empty string resets to UTC */
+  timezone?: string;
+}
+
 /** Request body for rejecting a deployment */
 export interface RejectDeploymentRequest {
   reason?: string;
@@ -131,6 +265,8 @@ export interface ScheduledJob {
   enabled: boolean;
   last_run_at: string | null;
   next_run_at: string | null;
+  /** IANA timezone the cron expression runs in (null = UTC) */
+  timezone: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -156,6 +292,7 @@ export interface CreateScheduledJobRequest {
   command: string;
   cron_expression: string;
   enabled?: boolean;
+  timezone?: string;
 }
 
 /** Request to update an existing scheduled job */
@@ -164,6 +301,67 @@ export interface UpdateScheduledJobRequest {
   command?: string;
   cron_expression?: string;
   enabled?: boolean;
+  /** Empty string resets to UTC */
+  timezone?: string;
+}
+
+// -------------------------------------------------------------------------
+// Scheduled Task types
+// -------------------------------------------------------------------------
+
+/** Scheduled task that runs a command in a one-off container from the app's image */
+export interface ScheduledTask {
+  id: string;
+  app_id: string;
+  name: string;
+  command: string;
+  cron_expression: string;
+  /** IANA timezone the cron expression runs in (null = UTC) */
+  timezone: string | null;
+  /** A run still going after this many seconds is killed */
+  timeout_seconds: number;
+  enabled: number;
+  last_run_at: string | null;
+  next_run_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/** A single execution record of a scheduled task */
+export interface ScheduledTaskRun {
+  id: string;
+  task_id: string;
+  trigger: "schedule" | "manual";
+  status: ScheduledJobRunStatus;
+  /** Image the task ran in */
+  image: string | null;
+  exit_code: number | null;
+  output: string | null;
+  error_message: string | null;
+  started_at: string;
+  finished_at: string | null;
+  duration_ms: number | null;
+}
+
+/** Request to create a new scheduled task */
+export interface CreateScheduledTaskRequest {
+  name: string;
+  command: string;
+  cron_expression: string;
+  timezone?: string;
+  timeout_seconds?: number;
+  enabled?: boolean;
+}
+
+/** Request to update an existing scheduled task */
+export interface UpdateScheduledTaskRequest {
+  name?: string;
+  command?: string;
+  cron_expression?: string;
+  /** Empty string resets to UTC */
+  timezone?: string;
+  timeout_seconds?: number;
+  enabled?: boolean;
 }
 
 // -------------------------------------------------------------------------
@@ -178,6 +376,8 @@ export interface ScheduledRestart {
   enabled: boolean;
   last_restart: string | null;
   next_restart: string | null;
+  /** IANA timezone the cron expression runs in (null = UTC) */
+  timezone: string | null;
   created_at: string;
 }
 
@@ -185,10 +385,13 @@ export interface ScheduledRestart {
 export interface CreateScheduledRestartRequest {
   cron_expression: string;
   enabled?: boolean;
+  timezone?: string;
 }
 
 /** Request to update a scheduled restart */
 export interface UpdateScheduledRestartRequest {
   cron_expression?: string;
   enabled?: boolean;
+  /** Empty string resets to UTC */
+  timezone?: string;
 }
@@ -13,7 +13,14 @@ export type NotificationEventType =
   | "app_stopped"
   | "app_started"
   | "container_crash"
-  | "container_restarted";
+  | "container_restarted"
+  | "app_scaled"
+  | "preview_expiring"
+  | "deployment_rolled_back"
+  | "deployment_cancelled"
+  | "bandwidth_exceeded"
+  | "backend_unhealthy"
+  | "backend_recovered";
 
 /** Slack webhook configuration */
 export interface SlackConfig {
@@ -30,6 +30,21 @@ export interface Service {
   cpu_limit: string | null;
   /** Optional memory limit (e.g. "512M") applied to each compose service. */
   memory_limit: string | null;
+  /** Notes from the template the service was deployed from, with values filled in. */
+  post_deploy_notes: string | null;
+  /** Public URL of the service's primary page ("open app" link). */
+  url: string | null;
+  /** Path the proxy health checker probes. */
+  health_path: string | null;
+  /** Compose service the domain routes to (null = the host port in `port`). */
+  route_service: string | null;
+  /** Container port of `route_service` the domain routes to. */
+  route_port: number | null;
+  /** When true, the domain is included in the ACME (Let's Encrypt) certificate. */
+  route_tls: boolean;
+  /** When true, the proxy requires HTTP Basic Auth for the domain. */
+  basic_auth_enabled: boolean;
+  basic_auth_username: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -64,6 +79,17 @@ export interface UpdateServiceRequest {
   cpu_limit?: string;
   /** Memory limit (e.g. "512M"); empty string clears it */
   memory_limit?: string;
+  /** Compose service the domain routes to; empty string routes to `port` again */
+  route_service?: string;
+  /** Container port of `route_service` the domain routes to */
+  route_port?: number;
+  /** Include the domain in the ACME certificate */
+  route_tls?: boolean;
+  /** Enable/disable HTTP Basic Auth for the domain */
+  basic_auth_enabled?: boolean;
+  basic_auth_username?: string;
+  /** Leave empty to keep the current password */
+  basic_auth_password?: string;
 }
 
 /** Service log entry */
@@ -91,6 +117,15 @@ export type TemplateCategory =
   | "cms"
   | "communication";
 
+/** Existing resource an env schema field is filled from at deploy time */
+export interface EnvSource {
+  kind: "database" | "volume";
+  /** Database types the field accepts; any type when absent */
+  db_types?: string[];
+  /** What a database fills in (defaults to the connection string) */
+  value?: "url" | "host" | "port" | "username" | "password" | "database";
+}
+
 /** Environment variable schema entry */
 export interface EnvSchemaEntry {
   name: string;
@@ -98,6 +133,8 @@ export interface EnvSchemaEntry {
   required: boolean;
   default: string;
   secret: boolean;
+  /** Pick an existing resource instead of typing a value */
+  source?: EnvSource;
 }
 
 /** Service template */
@@ -111,13 +148,23 @@ export interface ServiceTemplate {
   env_schema: EnvSchemaEntry[];
   is_builtin: boolean;
   created_at: string;
+  /** Notes shown after deploying, with ${VAR} placeholders */
+  post_deploy_notes: string | null;
+  /** Path of the primary page the "open app" link points to */
+  url_path: string | null;
+  /** Path the proxy health checker probes */
+  health_path: string | null;
 }
 
 /** Request to deploy a template */
 export interface DeployTemplateRequest {
   name: string;
   env_vars?: Record<string, string>;
+  /** IDs of the resources picked for fields with a `source`, by variable name */
+  resources?: Record<string, string>;
   project_id?: string;
+  /** Domain to route to the service; a subdomain is generated when omitted */
+  domain?: string;
 }
 
 /** Response after deploying a template */
@@ -127,6 +174,12 @@ export interface DeployTemplateResponse {
   template_id: string;
   status: string;
   message: string;
+  /** Domain routed to the service */
+  domain: string | null;
+  /** Public URL of the service's primary page */
+  url: string | null;
+  /** Template notes with the deployed values filled in */
+  post_deploy_notes: string | null;
 }
 
 /** Template category info for UI */
@@ -96,6 +96,28 @@ export interface SystemHealthStatus {
   checks: CheckResult[];
   /** Rivetr version */
   version: string;
+  /** Latest host hygiene check, once one has run */
+  host_hygiene?: HostHygieneReport;
+}
+
+/** A problem found by the host hygiene check */
+export interface HygieneFinding {
+  kind: "security_updates" | "runtime_eol";
+  message: string;
+}
+
+/** Pending security updates and container engine support of the host */
+export interface HostHygieneReport {
+  checked_at: string;
+  /** apt, dnf or yum; null when no supported package manager was found */
+  package_manager: string | null;
+  pending_updates: number | null;
+  security_updates: number | null;
+  runtime: string;
+  runtime_version: string | null;
+  /** Whether the container engine is past its upstream end of life */
+  runtime_eol: boolean;
+  findings: HygieneFinding[];
 }
 
 // -------------------------------------------------------------------------
@@ -605,7 +605,9 @@ Wants=network-online.target
 Requires=docker.service
 
 [Service]
-Type=simple
+Type=notify
+# The process sends READY=1; after a hot restart the successor reports itself as MAINPID
+NotifyAccess=all
 User=$SERVICE_USER
 Group=$SERVICE_USER
 WorkingDirectory=$INSTALL_DIR
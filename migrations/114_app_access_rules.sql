@@ -0,0 +1,18 @@
+-- Migration 114: Per-app proxy access rules
+-- Lightweight request filtering (user agents, paths, allowed methods) enforced at the proxy level
+
+CREATE TABLE IF NOT EXISTS app_access_rules (
+    id TEXT PRIMARY KEY,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    rule_type TEXT NOT NULL,        -- 'block_user_agent', 'block_path' or 'allow_methods'
+    pattern TEXT NOT NULL,          -- regex matched against the User-Agent (block_user_agent) or request path
+    methods TEXT,                   -- comma-separated allowed methods (allow_methods only), e.g. "GET,HEAD"
+    description TEXT,
+    is_enabled INTEGER NOT NULL DEFAULT 1,
+    sort_order INTEGER NOT NULL DEFAULT 0,   -- lower = higher priority
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_access_rules_app_id
+    ON app_access_rules(app_id, is_enabled, sort_order);
@@ -0,0 +1,30 @@
+-- Migration 115: Built-in WAF per app
+-- Per-app enable/mode/threshold for the proxy rule engine, plus a log of
+-- requests that reached the anomaly threshold.
+
+CREATE TABLE IF NOT EXISTS app_waf_settings (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    mode TEXT NOT NULL DEFAULT 'block',         -- 'block' or 'report'
+    anomaly_threshold INTEGER NOT NULL DEFAULT 5,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS waf_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    host TEXT NOT NULL,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    query TEXT,
+    client_ip TEXT,
+    user_agent TEXT,
+    score INTEGER NOT NULL,
+    matched_rules TEXT NOT NULL,                -- comma-separated rule IDs
+    action TEXT NOT NULL,                       -- 'blocked' or 'reported'
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_waf_events_app_created
+    ON waf_events(app_id, created_at);
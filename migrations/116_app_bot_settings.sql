@@ -0,0 +1,11 @@
+-- Migration 116: Per-app bot/crawler management
+-- robots_mode: 'off' (pass through to the app), 'auto' (deny-all robots.txt on
+-- previews and non-production environments) or 'always'
+
+CREATE TABLE IF NOT EXISTS app_bot_settings (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    robots_mode TEXT NOT NULL DEFAULT 'off',
+    challenge_bad_bots INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
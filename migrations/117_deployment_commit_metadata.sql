@@ -0,0 +1,8 @@
+-- Migration 117: Commit metadata on deployments
+-- Webhook pushes carry the commit author, branch and a compare URL; keep them
+-- alongside commit_sha/commit_message so notifications and the deployments API
+-- can say what shipped.
+
+ALTER TABLE deployments ADD COLUMN commit_author TEXT;
+ALTER TABLE deployments ADD COLUMN commit_branch TEXT;
+ALTER TABLE deployments ADD COLUMN commit_compare_url TEXT;
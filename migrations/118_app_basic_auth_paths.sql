@@ -0,0 +1,18 @@
+-- Migration 118: Path-scoped HTTP Basic Auth
+-- Protect individual path prefixes (e.g. /admin) with their own credentials,
+-- independently of the app-wide basic auth settings on the apps table.
+
+CREATE TABLE IF NOT EXISTS app_basic_auth_paths (
+    id TEXT PRIMARY KEY,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    path_prefix TEXT NOT NULL,      -- normalized prefix, e.g. "/admin"
+    username TEXT NOT NULL,
+    password_hash TEXT NOT NULL,    -- Argon2
+    is_enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(app_id, path_prefix)
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_basic_auth_paths_app_id
+    ON app_basic_auth_paths(app_id, is_enabled);
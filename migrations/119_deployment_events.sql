@@ -0,0 +1,14 @@
+-- Migration 119: Deployment status timeline
+-- One row per status transition (pending -> cloning -> building -> ...), so the
+-- UI can render a progress stepper with per-step timings.
+
+CREATE TABLE IF NOT EXISTS deployment_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    deployment_id TEXT NOT NULL REFERENCES deployments(id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    message TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_deployment_events_deployment_id
+    ON deployment_events(deployment_id, id);
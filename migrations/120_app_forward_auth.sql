@@ -0,0 +1,17 @@
+-- Migration 120: Forward authentication at the proxy
+-- Require visitors to sign in (with Rivetr user accounts or an OIDC provider)
+-- before requests reach the app. The proxy keeps a signed session cookie per
+-- domain and passes the identity to the backend in X-Forwarded-* headers.
+-- provider: 'rivetr' (Rivetr user accounts) or 'oidc' (oidc_provider_id)
+
+CREATE TABLE IF NOT EXISTS app_forward_auth (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    provider TEXT NOT NULL DEFAULT 'rivetr',
+    oidc_provider_id TEXT REFERENCES oidc_providers(id) ON DELETE SET NULL,
+    allowed_emails TEXT NOT NULL DEFAULT '',   -- emails or @domains, one per line (empty = anyone who signs in)
+    session_hours INTEGER NOT NULL DEFAULT 24,
+    cookie_secret TEXT NOT NULL,               -- per-app HMAC key for session cookies
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
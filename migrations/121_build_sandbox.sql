@@ -0,0 +1,6 @@
+-- Migration 121: Sandboxed builds for untrusted code
+-- teams.build_sandbox is the default for the team's apps: 'none' or 'sandboxed'.
+-- apps.build_sandbox overrides it; NULL inherits the team setting.
+
+ALTER TABLE teams ADD COLUMN build_sandbox TEXT NOT NULL DEFAULT 'none';
+ALTER TABLE apps ADD COLUMN build_sandbox TEXT;
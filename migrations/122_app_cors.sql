@@ -0,0 +1,17 @@
+-- Migration 122: Per-app CORS policy enforced at the proxy
+-- The proxy answers preflight requests and sets Access-Control-* headers on
+-- responses, replacing whatever the backend sends.
+-- List columns hold entries separated by newlines or commas.
+
+CREATE TABLE IF NOT EXISTS app_cors (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    allowed_origins TEXT NOT NULL DEFAULT '',   -- '*', exact origins or https://*.example.com
+    allowed_methods TEXT NOT NULL DEFAULT 'GET,HEAD,POST,PUT,PATCH,DELETE',
+    allowed_headers TEXT NOT NULL DEFAULT '',   -- empty = allow the headers a preflight asks for
+    expose_headers TEXT NOT NULL DEFAULT '',
+    allow_credentials INTEGER NOT NULL DEFAULT 0,
+    max_age_secs INTEGER NOT NULL DEFAULT 600,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,7 @@
+-- Migration 123: Per-app network egress policies
+-- egress_policy: 'allow_all' (default) or 'restricted' (deny outbound traffic
+-- except to egress_allowlist).
+-- egress_allowlist: JSON array of hostnames, IPv4 addresses and IPv4 CIDRs.
+
+ALTER TABLE apps ADD COLUMN egress_policy TEXT NOT NULL DEFAULT 'allow_all';
+ALTER TABLE apps ADD COLUMN egress_allowlist TEXT;
@@ -0,0 +1,7 @@
+-- Migration 124: TCP and command health checks
+-- healthcheck_type: 'http' (GET the healthcheck path, the default), 'tcp'
+-- (connect to the container port) or 'command' (run healthcheck_command in
+-- the container; exit code 0 is healthy).
+
+ALTER TABLE apps ADD COLUMN healthcheck_type TEXT NOT NULL DEFAULT 'http';
+ALTER TABLE apps ADD COLUMN healthcheck_command TEXT;
@@ -0,0 +1,17 @@
+-- Migration 125: Per-app power schedules (office-hours apps)
+-- Stops an app's containers on one cron expression and starts them again on
+-- another. While stopped, the proxy serves a "stopped" page for the app's domains.
+-- Cron expressions are evaluated in UTC.
+
+CREATE TABLE IF NOT EXISTS app_power_schedules (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    stop_cron TEXT NOT NULL,                    -- e.g. '0 19 * * Mon-Fri'
+    start_cron TEXT NOT NULL,                   -- e.g. '0 8 * * Mon-Fri'
+    stopped_message TEXT,                       -- NULL = default message
+    next_stop_at TEXT,
+    next_start_at TEXT,
+    stopped_at TEXT,                            -- set while the schedule keeps the app stopped
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,16 @@
+-- Migration 126: Pre-warmed deployment slots
+-- Builds (or pulls) an app's next image ahead of release without switching
+-- traffic, on an off-peak cron schedule and/or whenever a new commit lands.
+-- The slot is a deployment row that stops at status 'prewarmed' with its
+-- image_tag set; promoting it only has to start the container.
+
+CREATE TABLE IF NOT EXISTS app_prewarm_settings (
+    app_id TEXT PRIMARY KEY REFERENCES apps(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    schedule_cron TEXT,                         -- e.g. '0 3 * * *' (UTC), NULL = no schedule
+    on_push INTEGER NOT NULL DEFAULT 0,         -- pushes build the slot instead of deploying
+    next_run_at TEXT,
+    deployment_id TEXT REFERENCES deployments(id) ON DELETE SET NULL, -- current slot
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
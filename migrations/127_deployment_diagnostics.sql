@@ -0,0 +1,10 @@
+-- Migration 127: Deployment failure diagnostics bundles
+-- Collected automatically when a deployment fails: container log tail and
+-- inspect state, health check responses, build log tail and host disk/memory,
+-- stored as one JSON document per deployment.
+
+CREATE TABLE IF NOT EXISTS deployment_diagnostics (
+    deployment_id TEXT PRIMARY KEY REFERENCES deployments(id) ON DELETE CASCADE,
+    bundle TEXT NOT NULL,                       -- JSON DiagnosticsBundle
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
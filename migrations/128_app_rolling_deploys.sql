@@ -0,0 +1,7 @@
+-- Migration 128: Rolling deploy budget for multi-replica apps
+-- Replicas are replaced in batches: up to rolling_max_surge new containers
+-- start above replica_count, and up to rolling_max_unavailable old ones may
+-- leave the load-balancing pool before their replacements are healthy.
+
+ALTER TABLE apps ADD COLUMN rolling_max_surge INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE apps ADD COLUMN rolling_max_unavailable INTEGER NOT NULL DEFAULT 0;
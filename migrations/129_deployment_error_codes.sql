@@ -0,0 +1,6 @@
+-- Migration 129: Classified deployment failures
+-- error_code identifies a recognized failure cause (e.g. clone_auth_failed,
+-- dockerfile_not_found); error_hint holds the remediation text shown to users.
+
+ALTER TABLE deployments ADD COLUMN error_code TEXT;
+ALTER TABLE deployments ADD COLUMN error_hint TEXT;
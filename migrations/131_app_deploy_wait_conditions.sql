@@ -0,0 +1,5 @@
+-- Migration 131: Deploy wait conditions
+-- JSON array of conditions (database healthy, URL returns 200) checked before
+-- a deployment starts its container, each with its own timeout.
+
+ALTER TABLE apps ADD COLUMN deploy_wait_conditions TEXT;
@@ -0,0 +1,6 @@
+-- Migration 132: worker apps
+-- app_type: 'web' (serves HTTP on its port behind the proxy, the default) or
+-- 'worker' (background process: no published port, proxy route or domain;
+-- health is process liveness or the command health check).
+
+ALTER TABLE apps ADD COLUMN app_type TEXT NOT NULL DEFAULT 'web';
@@ -0,0 +1,23 @@
+-- Migration 133: Procfile process types
+-- deployments.procfile: the Procfile found in the deployment's source ('' when
+-- there was none, NULL when the source wasn't available, e.g. registry images).
+-- app_processes: the app's process types from its last Procfile. `web` runs in
+-- the primary container and its replicas, `release` runs once per deployment
+-- before the route switch, every other type runs `replica_count` containers of
+-- its own from the same image.
+
+ALTER TABLE deployments ADD COLUMN procfile TEXT;
+
+CREATE TABLE IF NOT EXISTS app_processes (
+    id TEXT PRIMARY KEY,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    replica_count INTEGER NOT NULL DEFAULT 1,
+    container_ids TEXT NOT NULL DEFAULT '[]',   -- JSON array of running container IDs
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(app_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_processes_app_id ON app_processes(app_id);
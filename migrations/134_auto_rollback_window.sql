@@ -0,0 +1,25 @@
+-- Migration 134: automatic rollback window
+-- apps.healthcheck_window_secs: how long a new container may take to become
+-- healthy before the deployment fails (and, with auto-rollback, the previous
+-- image is redeployed).
+-- deployments.rolled_back: set on a failed deployment whose previous version
+-- was restored by an automatic rollback.
+-- notification_subscriptions: allow the deployment_rolled_back event. SQLite
+-- can't ALTER a CHECK, so rebuild the table preserving data + relations.
+
+ALTER TABLE apps ADD COLUMN healthcheck_window_secs INTEGER NOT NULL DEFAULT 20;
+
+ALTER TABLE deployments ADD COLUMN rolled_back INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE notification_subscriptions_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL CHECK(event_type IN ('deployment_started', 'deployment_success', 'deployment_failed', 'deployment_rolled_back', 'app_stopped', 'app_started', 'container_crash', 'container_restarted', 'app_scaled')),
+    app_id TEXT REFERENCES apps(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(channel_id, event_type, app_id)
+);
+INSERT INTO notification_subscriptions_new (id, channel_id, event_type, app_id, created_at)
+    SELECT id, channel_id, event_type, app_id, created_at FROM notification_subscriptions;
+DROP TABLE notification_subscriptions;
+ALTER TABLE notification_subscriptions_new RENAME TO notification_subscriptions;
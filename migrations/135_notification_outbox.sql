@@ -0,0 +1,24 @@
+-- Migration 135: notification outbox
+-- Notification events are written here (in the same transaction as the state
+-- change that caused them, where there is one) and delivered to the subscribed
+-- channels by a background dispatcher with retries, so an event survives a
+-- crash or a failing channel. delivered_channels records the channels an event
+-- already reached so a retry doesn't notify them twice.
+
+CREATE TABLE IF NOT EXISTS notification_outbox (
+    id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    app_id TEXT,
+    payload TEXT NOT NULL,                          -- JSON NotificationPayload
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    delivered_channels TEXT NOT NULL DEFAULT '[]',  -- JSON array of channel IDs
+    last_error TEXT,
+    next_attempt_at TEXT NOT NULL DEFAULT (datetime('now')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    delivered_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_pending
+    ON notification_outbox(status, next_attempt_at);
@@ -0,0 +1,14 @@
+-- Migration 136: per-app webhook filters
+-- apps.webhook_branch_regex: pushes to branches matching it also deploy the
+-- app, in addition to pushes to its own branch.
+-- apps.webhook_ignore_authors: pushes whose commit author matches are ignored
+-- (e.g. bots).
+-- apps.webhook_ignore_paths: pushes where every changed file matches are
+-- ignored (e.g. docs-only commits).
+-- All three are regular expressions; NULL disables the filter.
+
+ALTER TABLE apps ADD COLUMN webhook_branch_regex TEXT;
+
+ALTER TABLE apps ADD COLUMN webhook_ignore_authors TEXT;
+
+ALTER TABLE apps ADD COLUMN webhook_ignore_paths TEXT;
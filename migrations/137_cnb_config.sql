@@ -0,0 +1,6 @@
+-- Migration 137: per-app Cloud Native Buildpacks configuration
+-- apps.cnb_config: JSON pack configuration for "cnb" builds (builder, custom
+-- builder image, extra buildpacks, pull policy). NULL lets Rivetr pick the
+-- builder from the project files.
+
+ALTER TABLE apps ADD COLUMN cnb_config TEXT;
@@ -0,0 +1,6 @@
+-- Migration 138: deployment concurrency fences
+-- apps.deploy_fences: JSON array of fence names (e.g. ["shared-db-migrations"]).
+-- Deployments of apps sharing a fence never run their pre-deploy commands or
+-- release process at the same time. NULL means no fences.
+
+ALTER TABLE apps ADD COLUMN deploy_fences TEXT;
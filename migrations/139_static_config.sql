@@ -0,0 +1,6 @@
+-- Migration 139: static site build settings
+-- apps.static_config: JSON settings for "staticsite" builds (build command,
+-- install command, builder image, SPA mode). The output directory stays in
+-- apps.publish_directory. NULL uses the auto-detected defaults.
+
+ALTER TABLE apps ADD COLUMN static_config TEXT;
@@ -0,0 +1,9 @@
+-- Migration 140: Docker Compose repository deployments
+-- Apps with build_type "docker-compose" deploy the compose file of their
+-- repository as a stack. apps.compose_file: path of the compose file relative
+-- to the base directory (NULL detects docker-compose.yml, compose.yaml, ...).
+-- apps.compose_service: service the proxy routes the app's domains to (NULL
+-- uses the first service of the file).
+
+ALTER TABLE apps ADD COLUMN compose_file TEXT;
+ALTER TABLE apps ADD COLUMN compose_service TEXT;
@@ -0,0 +1,12 @@
+-- Migration 141: per-schedule timezones
+-- IANA timezone (e.g. 'Europe/Berlin') each schedule's cron expression or
+-- time window is evaluated in. NULL keeps evaluating it in UTC. Next-run
+-- timestamps stay in UTC.
+
+ALTER TABLE scheduled_jobs ADD COLUMN timezone TEXT;
+ALTER TABLE scheduled_restarts ADD COLUMN timezone TEXT;
+ALTER TABLE backup_schedules ADD COLUMN timezone TEXT;
+ALTER TABLE database_backup_schedules ADD COLUMN timezone TEXT;
+ALTER TABLE app_power_schedules ADD COLUMN timezone TEXT;
+ALTER TABLE app_prewarm_settings ADD COLUMN timezone TEXT;
+ALTER TABLE deployment_freeze_windows ADD COLUMN timezone TEXT;
@@ -0,0 +1,41 @@
+-- Migration 142: per-app scheduled tasks
+-- Commands run on a cron schedule in a one-off container started from the
+-- image of the app's running deployment, with the app's env vars and volumes.
+-- Unlike scheduled jobs they don't need (or disturb) a running container.
+
+CREATE TABLE IF NOT EXISTS scheduled_tasks (
+    id TEXT PRIMARY KEY,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    cron_expression TEXT NOT NULL,
+    -- IANA timezone the cron expression is evaluated in (NULL = UTC)
+    timezone TEXT,
+    -- A run still going after this long is killed and marked failed
+    timeout_seconds INTEGER NOT NULL DEFAULT 3600,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_run_at TEXT,
+    next_run_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_scheduled_tasks_app_id ON scheduled_tasks(app_id);
+CREATE INDEX IF NOT EXISTS idx_scheduled_tasks_next_run ON scheduled_tasks(enabled, next_run_at);
+
+-- Task run history
+CREATE TABLE IF NOT EXISTS scheduled_task_runs (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL REFERENCES scheduled_tasks(id) ON DELETE CASCADE,
+    -- 'schedule' or 'manual'
+    trigger TEXT NOT NULL DEFAULT 'schedule',
+    status TEXT NOT NULL DEFAULT 'running' CHECK(status IN ('running', 'success', 'failed')),
+    -- Image the task ran in
+    image TEXT,
+    exit_code INTEGER,
+    output TEXT,
+    error_message TEXT,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    finished_at TEXT,
+    duration_ms INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_scheduled_task_runs_task_id ON scheduled_task_runs(task_id, started_at);
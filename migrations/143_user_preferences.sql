@@ -0,0 +1,31 @@
+-- Migration 143: per-user preferences
+-- Lets each user pick a display/digest timezone, mute notification severities
+-- and batch their notification email into a daily digest, without editing the
+-- team-wide notification channels they are a recipient of.
+
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- IANA timezone (NULL = UTC)
+    timezone TEXT,
+    -- Collect notification email into one daily message instead of one per event
+    email_digests INTEGER NOT NULL DEFAULT 0,
+    -- JSON array of the severities to receive ("info", "warning", "critical")
+    notify_severities TEXT NOT NULL DEFAULT '["info","warning","critical"]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Events held back for a user's next digest email
+CREATE TABLE IF NOT EXISTS notification_digest_items (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_digest_items_user
+    ON notification_digest_items(user_id, created_at);
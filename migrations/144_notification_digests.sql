@@ -0,0 +1,31 @@
+-- Migration 144: notification digests
+-- A digest periodically summarizes what happened (deployment counts, slowest
+-- builds, crashed containers, certificates about to expire) and sends it to
+-- the selected notification channels and email addresses, instead of only
+-- per-event notifications.
+
+CREATE TABLE IF NOT EXISTS notification_digests (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    -- Only summarize this team's apps (NULL = all apps)
+    team_id TEXT REFERENCES teams(id) ON DELETE CASCADE,
+    frequency TEXT NOT NULL DEFAULT 'daily' CHECK (frequency IN ('daily', 'weekly')),
+    -- Local hour (0-23) the digest is sent at
+    send_hour INTEGER NOT NULL DEFAULT 8,
+    -- Day of week for weekly digests (0 = Sunday ... 6 = Saturday)
+    send_weekday INTEGER NOT NULL DEFAULT 1,
+    -- IANA timezone for send_hour/send_weekday (NULL = UTC)
+    timezone TEXT,
+    -- JSON array of notification channel IDs
+    channel_ids TEXT NOT NULL DEFAULT '[]',
+    -- JSON array of email addresses (sent through the system email transport)
+    email_addresses TEXT NOT NULL DEFAULT '[]',
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_sent_at TEXT,
+    next_send_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_digests_due
+    ON notification_digests(enabled, next_send_at);
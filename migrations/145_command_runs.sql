@@ -0,0 +1,27 @@
+-- Migration 145: one-off command runs
+-- Ad-hoc commands (migrations, seeds, scripts) run in a fresh container from
+-- the image of the app's running deployment, with the app's env vars and
+-- volumes. The container is removed afterwards; its exit code and output are
+-- kept here.
+
+CREATE TABLE IF NOT EXISTS command_runs (
+    id TEXT PRIMARY KEY,
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    command TEXT NOT NULL,
+    -- A run still going after this long is killed and marked failed
+    timeout_seconds INTEGER NOT NULL DEFAULT 3600,
+    status TEXT NOT NULL DEFAULT 'running' CHECK(status IN ('running', 'success', 'failed')),
+    -- Image the command ran in
+    image TEXT,
+    -- Set while the container exists, so its output can be streamed
+    container_id TEXT,
+    exit_code INTEGER,
+    output TEXT,
+    error_message TEXT,
+    -- User who started the run
+    started_by TEXT,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    finished_at TEXT,
+    duration_ms INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_command_runs_app_id ON command_runs(app_id, started_at);
@@ -0,0 +1,24 @@
+-- Migration 146: in-app notification inbox
+-- Each notification event from the outbox is copied to the inbox of every
+-- user who can see the app (its team's members, or everyone for apps without
+-- a team) and receives the event's severity, so failures show up in the
+-- dashboard without a Slack or email channel.
+
+CREATE TABLE IF NOT EXISTS inbox_notifications (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Outbox event this notification was created from
+    outbox_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    app_id TEXT,
+    app_name TEXT,
+    deployment_id TEXT,
+    message TEXT NOT NULL,
+    error_message TEXT,
+    read_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(user_id, outbox_id)
+);
+CREATE INDEX IF NOT EXISTS idx_inbox_notifications_user
+    ON inbox_notifications(user_id, read_at, created_at);
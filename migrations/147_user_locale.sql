@@ -0,0 +1,4 @@
+-- Migration 147: per-user locale
+-- Language of the dashboard and of messages sent to the user (NULL = English).
+
+ALTER TABLE user_preferences ADD COLUMN locale TEXT;
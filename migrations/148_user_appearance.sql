@@ -0,0 +1,5 @@
+-- Migration 148: appearance preferences on user_preferences
+-- Dashboard theme (NULL = follow the OS) and whether to reduce motion.
+
+ALTER TABLE user_preferences ADD COLUMN theme TEXT;
+ALTER TABLE user_preferences ADD COLUMN reduced_motion INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Migration 149: Manual approval gates per environment
+-- require_approval: deployments of every app in the environment wait for
+--   approval, as with the app-level `require_approval` setting. Push
+--   deployments from webhooks are parked too.
+
+ALTER TABLE environments ADD COLUMN require_approval INTEGER NOT NULL DEFAULT 0;
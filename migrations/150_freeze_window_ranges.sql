@@ -0,0 +1,6 @@
+-- Migration 150: calendar freeze windows
+-- A freeze window with starts_at and ends_at (RFC 3339, UTC) covers that one
+-- range instead of recurring on start_time/end_time/days_of_week.
+
+ALTER TABLE deployment_freeze_windows ADD COLUMN starts_at TEXT;
+ALTER TABLE deployment_freeze_windows ADD COLUMN ends_at TEXT;
@@ -0,0 +1,9 @@
+-- Migration 151: Image vulnerability scan reports
+-- Written by the pipeline when [scan] is enabled: the scanner's findings for
+-- the deployment's image, stored as one JSON document per deployment.
+
+CREATE TABLE IF NOT EXISTS deployment_scans (
+    deployment_id TEXT PRIMARY KEY REFERENCES deployments(id) ON DELETE CASCADE,
+    report TEXT NOT NULL,                       -- JSON ScanReport
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,10 @@
+-- Migration 152: App configuration snapshots per deployment
+-- Env var names (values only as salted hashes), ports, domains, limits and
+-- build settings a deployment ran with, so the deployment diff can show
+-- configuration changes. Stored as one JSON document per deployment.
+
+CREATE TABLE IF NOT EXISTS deployment_config_snapshots (
+    deployment_id TEXT PRIMARY KEY REFERENCES deployments(id) ON DELETE CASCADE,
+    snapshot TEXT NOT NULL,                     -- JSON ConfigSnapshot
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,25 @@
+-- Migration 153: Content-addressed artifact store
+-- artifacts: one row per blob stored under `artifacts/<digest>` in the
+--   configured storage backend; digest is the hex SHA-256 of the content.
+--   last_stored_at is refreshed whenever the blob is stored again, so garbage
+--   collection leaves recently reused blobs alone.
+-- artifact_refs: which deployment refers to which blob, one per deployment
+--   and kind ('diagnostics', 'build_log'). Refs go with their deployment;
+--   blobs without refs are garbage collected.
+
+CREATE TABLE IF NOT EXISTS artifacts (
+    digest TEXT PRIMARY KEY NOT NULL,
+    size INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    last_stored_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS artifact_refs (
+    deployment_id TEXT NOT NULL REFERENCES deployments(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    digest TEXT NOT NULL REFERENCES artifacts(digest),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (deployment_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_refs_digest ON artifact_refs(digest);
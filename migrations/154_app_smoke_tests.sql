@@ -0,0 +1,6 @@
+-- Migration 154: Post-deploy smoke tests
+-- JSON array of HTTP checks (path, expected status, expected body substring)
+-- run against a new container once it passes its health check. A failing
+-- check fails the deployment and triggers auto-rollback when enabled.
+
+ALTER TABLE apps ADD COLUMN smoke_tests TEXT;
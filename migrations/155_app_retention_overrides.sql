@@ -0,0 +1,8 @@
+-- Migration 155: Per-app deployment retention overrides
+-- apps.max_deployments: deployments the cleanup task keeps for this app
+-- (NULL uses cleanup.max_deployments_per_app / the instance setting).
+-- apps.keep_images: how many of the newest kept deployments also keep their
+-- image (NULL keeps the images of every kept deployment).
+
+ALTER TABLE apps ADD COLUMN max_deployments INTEGER;
+ALTER TABLE apps ADD COLUMN keep_images INTEGER;
@@ -0,0 +1,29 @@
+-- Migration 156: outbound email queue
+-- Emails are written here, one row per recipient, and sent by a background
+-- dispatcher over pooled SMTP transports with a per-minute rate limit and
+-- retries with backoff. Sent and failed rows are kept for a while as a
+-- sent-mail log. source says where the SMTP settings come from: 'system' for
+-- the [email] config, otherwise the ID of an email notification channel.
+-- Credentials are looked up at send time and never stored here.
+
+CREATE TABLE IF NOT EXISTS email_queue (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    text_body TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TEXT NOT NULL DEFAULT (datetime('now')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    sent_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_queue_pending
+    ON email_queue(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_email_queue_created
+    ON email_queue(created_at);
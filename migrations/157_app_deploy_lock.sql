@@ -0,0 +1,8 @@
+-- Migration 157: Deployment locks
+-- While apps.locked_at is set, webhook and manual deployments of the app are
+-- rejected. locked_by holds the name of the user who took the lock and
+-- lock_reason the reason they gave.
+
+ALTER TABLE apps ADD COLUMN locked_at TEXT;
+ALTER TABLE apps ADD COLUMN locked_by TEXT;
+ALTER TABLE apps ADD COLUMN lock_reason TEXT;
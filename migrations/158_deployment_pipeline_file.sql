@@ -0,0 +1,5 @@
+-- Migration 158: Repository pipeline files
+-- deployments.pipeline_file: the rivetr.yaml found in the deployment's source
+-- (NULL when there was none, so the app's own settings apply).
+
+ALTER TABLE deployments ADD COLUMN pipeline_file TEXT;
@@ -0,0 +1,10 @@
+-- Migration 159: Pre-release test stage
+-- apps.test_commands: JSON array of commands run in a throwaway container of
+-- the built image before it is pushed or started (NULL = no test stage).
+-- apps.test_timeout_secs: seconds all test commands may take together
+-- (NULL = 600).
+-- deployments.test_output: output of the deployment's test commands.
+
+ALTER TABLE apps ADD COLUMN test_commands TEXT;
+ALTER TABLE apps ADD COLUMN test_timeout_secs INTEGER;
+ALTER TABLE deployments ADD COLUMN test_output TEXT;
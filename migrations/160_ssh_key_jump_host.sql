@@ -0,0 +1,6 @@
+-- Migration 160: SSH jump hosts
+-- ssh_keys.jump_host: bastion the clone connects through, as
+-- [user@]host[:port] (NULL = connect directly). The bastion is authenticated
+-- with the same key.
+
+ALTER TABLE ssh_keys ADD COLUMN jump_host TEXT;
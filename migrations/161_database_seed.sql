@@ -0,0 +1,14 @@
+-- Migration 161: Managed database seeding
+-- databases.seed_script: SQL (mongosh script for MongoDB) loaded once after
+--   first provisioning.
+-- databases.seed_dump_file: file name of an uploaded dump under
+--   {data_dir}/database-seeds/{id}/, loaded after the script.
+-- databases.seed_on_clone: copy the seed to environment clones of the database.
+-- databases.seeded_at: when the seed was loaded (NULL = not yet).
+-- databases.seed_error: error of the last failed attempt.
+
+ALTER TABLE databases ADD COLUMN seed_script TEXT;
+ALTER TABLE databases ADD COLUMN seed_dump_file TEXT;
+ALTER TABLE databases ADD COLUMN seed_on_clone INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE databases ADD COLUMN seeded_at TEXT;
+ALTER TABLE databases ADD COLUMN seed_error TEXT;
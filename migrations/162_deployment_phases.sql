@@ -0,0 +1,21 @@
+-- Migration 162: Deployment phases
+-- One row per pipeline phase a deployment went through (clone, build, test,
+-- push, start, healthcheck) with its timing, so slow deployments can be
+-- diagnosed. finished_at/duration_ms are NULL while the phase is in progress.
+-- deployment_logs.phase: the phase a log line was written in (NULL = outside
+-- any phase).
+
+CREATE TABLE IF NOT EXISTS deployment_phases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    deployment_id TEXT NOT NULL REFERENCES deployments(id) ON DELETE CASCADE,
+    phase TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    duration_ms INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_deployment_phases_deployment_id
+    ON deployment_phases(deployment_id, id);
+
+ALTER TABLE deployment_logs ADD COLUMN phase TEXT;
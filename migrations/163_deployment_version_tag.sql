@@ -0,0 +1,6 @@
+-- Migration 163: Version tag of built images
+-- Reference of the image built by a deployment under the app's versioned
+-- repository, `rivetr/<app>:<short-sha>` (the short deployment ID when there
+-- is no commit). The build is also tagged `rivetr/<app>:latest`.
+
+ALTER TABLE deployments ADD COLUMN version_tag TEXT;
@@ -0,0 +1,26 @@
+-- Migration 164: preview deployment expiry
+-- apps.preview_max_lifetime_hours / preview_idle_timeout_hours: tear a preview
+-- down this long after it was created / after its last request (NULL = never).
+-- preview_deployments.last_activity_at: last request the proxy served for the
+-- preview; expiry_warned_at: when the "expiring soon" notification was queued.
+-- notification_subscriptions: allow the preview_expiring event. SQLite can't
+-- ALTER a CHECK, so rebuild the table preserving data + relations.
+
+ALTER TABLE apps ADD COLUMN preview_max_lifetime_hours INTEGER;
+ALTER TABLE apps ADD COLUMN preview_idle_timeout_hours INTEGER;
+
+ALTER TABLE preview_deployments ADD COLUMN last_activity_at TEXT;
+ALTER TABLE preview_deployments ADD COLUMN expiry_warned_at TEXT;
+
+CREATE TABLE notification_subscriptions_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL CHECK(event_type IN ('deployment_started', 'deployment_success', 'deployment_failed', 'deployment_rolled_back', 'app_stopped', 'app_started', 'container_crash', 'container_restarted', 'app_scaled', 'preview_expiring')),
+    app_id TEXT REFERENCES apps(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(channel_id, event_type, app_id)
+);
+INSERT INTO notification_subscriptions_new (id, channel_id, event_type, app_id, created_at)
+    SELECT id, channel_id, event_type, app_id, created_at FROM notification_subscriptions;
+DROP TABLE notification_subscriptions;
+ALTER TABLE notification_subscriptions_new RENAME TO notification_subscriptions;
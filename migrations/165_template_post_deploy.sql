@@ -0,0 +1,12 @@
+-- Migration 165: service template post-deploy information
+-- service_templates: notes shown after deploying (with ${VAR} placeholders),
+-- the path of the primary page ("open app" link) and the health check path.
+-- services: the same, copied from the template when deployed (notes rendered).
+
+ALTER TABLE service_templates ADD COLUMN post_deploy_notes TEXT;
+ALTER TABLE service_templates ADD COLUMN url_path TEXT;
+ALTER TABLE service_templates ADD COLUMN health_path TEXT;
+
+ALTER TABLE services ADD COLUMN post_deploy_notes TEXT;
+ALTER TABLE services ADD COLUMN url_path TEXT;
+ALTER TABLE services ADD COLUMN health_path TEXT;
@@ -0,0 +1,14 @@
+-- Migration 166: proxy routes to a compose service of a Docker Compose service
+-- route_service / route_port: the compose service and container port the
+-- domain routes to (NULL = the host port in `port`, as before).
+-- route_host_port: the loopback port that container port was last published on.
+-- route_tls: include the domain in the ACME certificate.
+-- basic_auth_*: HTTP Basic Auth in front of the route, as for apps.
+
+ALTER TABLE services ADD COLUMN route_service TEXT;
+ALTER TABLE services ADD COLUMN route_port INTEGER;
+ALTER TABLE services ADD COLUMN route_host_port INTEGER;
+ALTER TABLE services ADD COLUMN route_tls INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE services ADD COLUMN basic_auth_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE services ADD COLUMN basic_auth_username TEXT;
+ALTER TABLE services ADD COLUMN basic_auth_password_hash TEXT;
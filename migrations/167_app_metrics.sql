@@ -0,0 +1,6 @@
+-- Migration 167: Prometheus metrics endpoint of an app
+-- metrics_port: container port serving metrics (NULL = not scraped)
+-- metrics_path: path of the metrics endpoint (NULL = /metrics)
+
+ALTER TABLE apps ADD COLUMN metrics_port INTEGER;
+ALTER TABLE apps ADD COLUMN metrics_path TEXT;
@@ -0,0 +1,14 @@
+-- Migration 168: throwaway databases for preview deployments
+-- apps.preview_database: database provisioned for each preview
+--   ('empty' or 'schema', NULL = none)
+-- apps.preview_database_source_id: managed database the preview database is
+--   modelled on (type, version, image) and whose schema 'schema' copies
+-- apps.preview_database_env: env var receiving the connection URL
+--   (NULL = DATABASE_URL)
+-- preview_deployments.database_id: the preview's database, destroyed with it
+
+ALTER TABLE apps ADD COLUMN preview_database TEXT;
+ALTER TABLE apps ADD COLUMN preview_database_source_id TEXT;
+ALTER TABLE apps ADD COLUMN preview_database_env TEXT;
+
+ALTER TABLE preview_deployments ADD COLUMN database_id TEXT;
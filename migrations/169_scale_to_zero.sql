@@ -0,0 +1,7 @@
+-- Migration 169: Scale-to-zero
+-- idle_timeout_minutes: stop the app after this many minutes without requests
+--   and start it again on the next one (NULL = always running)
+-- sleeping_since: when the app was stopped for inactivity (NULL = awake)
+
+ALTER TABLE apps ADD COLUMN idle_timeout_minutes INTEGER;
+ALTER TABLE apps ADD COLUMN sleeping_since TEXT;
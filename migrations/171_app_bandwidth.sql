@@ -0,0 +1,44 @@
+-- Migration 171: Per-app bandwidth accounting and monthly caps
+-- app_bandwidth: bytes moved per app, calendar month (UTC, 'YYYY-MM') and
+--   source: a routed domain (proxy traffic) or 'container' (the network
+--   counters of the app's containers)
+-- bandwidth_limit_gb: monthly cap on proxy traffic in GB (NULL = unlimited)
+-- bandwidth_limit_action: 'notify' (default) or 'throttle' once the cap is hit
+-- bandwidth_throttle_kbps: rate the app's domains are throttled to, in
+--   kilobits per second (NULL = the built-in default)
+-- bandwidth_exceeded_period: month the cap was last reported exceeded, so the
+--   notification is sent once per month
+-- notification_subscriptions: allow the bandwidth_exceeded event. SQLite can't
+--   ALTER a CHECK, so rebuild the table preserving data + relations.
+
+CREATE TABLE IF NOT EXISTS app_bandwidth (
+    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+    period TEXT NOT NULL,
+    source TEXT NOT NULL,
+    bytes_in INTEGER NOT NULL DEFAULT 0,
+    bytes_out INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (app_id, period, source)
+);
+
+ALTER TABLE apps ADD COLUMN bandwidth_limit_gb INTEGER;
+ALTER TABLE apps ADD COLUMN bandwidth_limit_action TEXT NOT NULL DEFAULT 'notify';
+ALTER TABLE apps ADD COLUMN bandwidth_throttle_kbps INTEGER;
+ALTER TABLE apps ADD COLUMN bandwidth_exceeded_period TEXT;
+
+CREATE TABLE notification_subscriptions_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL CHECK(event_type IN ('deployment_started', 'deployment_success', 'deployment_failed', 'deployment_rolled_back', 'deployment_cancelled', 'app_stopped', 'app_started', 'container_crash', 'container_restarted', 'app_scaled', 'preview_expiring', 'bandwidth_exceeded')),
+    app_id TEXT REFERENCES apps(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(channel_id, event_type, app_id)
+);
+INSERT INTO notification_subscriptions_new (id, channel_id, event_type, app_id, created_at)
+    SELECT id, channel_id, event_type, app_id, created_at FROM notification_subscriptions;
+DROP TABLE notification_subscriptions;
+ALTER TABLE notification_subscriptions_new RENAME TO notification_subscriptions;
+
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_channel_id ON notification_subscriptions(channel_id);
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_event_type ON notification_subscriptions(event_type);
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_app_id ON notification_subscriptions(app_id);
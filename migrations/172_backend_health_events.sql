@@ -0,0 +1,21 @@
+-- Migration 172: backend_unhealthy and backend_recovered notification events,
+-- sent when the proxy health checker marks an app's backend down or up.
+-- SQLite can't ALTER a CHECK, so rebuild notification_subscriptions
+-- preserving data + relations.
+
+CREATE TABLE notification_subscriptions_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL CHECK(event_type IN ('deployment_started', 'deployment_success', 'deployment_failed', 'deployment_rolled_back', 'deployment_cancelled', 'app_stopped', 'app_started', 'container_crash', 'container_restarted', 'app_scaled', 'preview_expiring', 'bandwidth_exceeded', 'backend_unhealthy', 'backend_recovered')),
+    app_id TEXT REFERENCES apps(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(channel_id, event_type, app_id)
+);
+INSERT INTO notification_subscriptions_new (id, channel_id, event_type, app_id, created_at)
+    SELECT id, channel_id, event_type, app_id, created_at FROM notification_subscriptions;
+DROP TABLE notification_subscriptions;
+ALTER TABLE notification_subscriptions_new RENAME TO notification_subscriptions;
+
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_channel_id ON notification_subscriptions(channel_id);
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_event_type ON notification_subscriptions(event_type);
+CREATE INDEX IF NOT EXISTS idx_notification_subscriptions_app_id ON notification_subscriptions(app_id);
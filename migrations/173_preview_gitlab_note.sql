@@ -0,0 +1,5 @@
+-- Migration 173: GitLab merge request note of a preview deployment
+-- gitlab_note_id: the note posted on the merge request with the preview URL,
+--   updated in place as the preview is redeployed or closed
+
+ALTER TABLE preview_deployments ADD COLUMN gitlab_note_id INTEGER;
@@ -0,0 +1,18 @@
+-- Migration 174: delivery log of outgoing webhook notifications, one row per
+-- request with the endpoint's response status (NULL when it never answered).
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    app_id TEXT,
+    deployment_id TEXT,
+    url TEXT NOT NULL,
+    status_code INTEGER,
+    success INTEGER NOT NULL,
+    error TEXT,
+    duration_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_channel ON webhook_deliveries(channel_id, created_at);
@@ -0,0 +1,324 @@
+//! Access Rules API endpoints for applications.
+//!
+//! Provides CRUD operations for per-app proxy-level request filtering: block
+//! user agents or paths, restrict a path to a set of HTTP methods, or allow or
+//! block clients by country (GeoIP, needs `proxy.geoip_db_path`). Rules are
+//! evaluated in sort_order before basic auth, redirects and forwarding.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use regex::Regex;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::{
+    is_country_rule, parse_country_codes, parse_methods, AppAccessRule, CreateAccessRuleRequest,
+    UpdateAccessRuleRequest, ACCESS_RULE_ALLOW_METHODS, ACCESS_RULE_TYPES,
+};
+use crate::AppState;
+
+use super::common::refresh_proxy_routes;
+use super::error::ApiError;
+use super::validation::validate_uuid;
+
+/// HTTP methods accepted in `allow_methods` rules.
+const VALID_METHODS: &[&str] = &[
+    "GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS", "CONNECT", "TRACE",
+];
+
+/// List all access rules for an app.
+pub async fn list_access_rules(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<AppAccessRule>>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    // Ensure app exists
+    let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE id = ?")
+        .bind(&app_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("App not found"));
+    }
+
+    let rules = sqlx::query_as::<_, AppAccessRule>(
+        "SELECT * FROM app_access_rules WHERE app_id = ? ORDER BY sort_order ASC, created_at ASC",
+    )
+    .bind(&app_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(rules))
+}
+
+/// Create a new access rule for an app.
+pub async fn create_access_rule(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<CreateAccessRuleRequest>,
+) -> Result<(StatusCode, Json<AppAccessRule>), ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    validate_rule_type(&req.rule_type)?;
+    let pattern = validate_pattern(&state, &req.rule_type, &req.pattern)?;
+    let methods = validate_methods(&req.rule_type, &req.methods)?;
+
+    // Ensure app exists
+    let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE id = ?")
+        .bind(&app_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("App not found"));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_access_rules
+            (id, app_id, rule_type, pattern, methods, description, is_enabled, sort_order, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&app_id)
+    .bind(&req.rule_type)
+    .bind(&pattern)
+    .bind(&methods)
+    .bind(&req.description)
+    .bind(req.is_enabled)
+    .bind(req.sort_order)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create access rule: {}", e);
+        ApiError::database("Failed to create access rule")
+    })?;
+
+    let rule = sqlx::query_as::<_, AppAccessRule>("SELECT * FROM app_access_rules WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+
+    // Refresh proxy routes for this app
+    apply_access_rules(&state, &app_id).await;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Update an existing access rule.
+pub async fn update_access_rule(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, rule_id)): Path<(String, String)>,
+    Json(req): Json<UpdateAccessRuleRequest>,
+) -> Result<Json<AppAccessRule>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    if let Err(e) = validate_uuid(&rule_id, "rule_id") {
+        return Err(ApiError::validation_field("rule_id", e));
+    }
+
+    // Fetch existing rule
+    let existing = sqlx::query_as::<_, AppAccessRule>(
+        "SELECT * FROM app_access_rules WHERE id = ? AND app_id = ?",
+    )
+    .bind(&rule_id)
+    .bind(&app_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Access rule not found"))?;
+
+    let rule_type = req.rule_type.clone().unwrap_or(existing.rule_type);
+    validate_rule_type(&rule_type)?;
+
+    let pattern = validate_pattern(
+        &state,
+        &rule_type,
+        req.pattern.as_deref().unwrap_or(&existing.pattern),
+    )?;
+
+    let methods_list = match &req.methods {
+        Some(m) => m.clone(),
+        None => parse_methods(existing.methods.as_deref().unwrap_or("")),
+    };
+    let methods = validate_methods(&rule_type, &methods_list)?;
+
+    let description = req.description.clone().or(existing.description);
+    let is_enabled = req.is_enabled.unwrap_or(existing.is_enabled != 0);
+    let sort_order = req.sort_order.unwrap_or(existing.sort_order);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        UPDATE app_access_rules SET
+            rule_type = ?,
+            pattern = ?,
+            methods = ?,
+            description = ?,
+            is_enabled = ?,
+            sort_order = ?,
+            updated_at = ?
+        WHERE id = ? AND app_id = ?
+        "#,
+    )
+    .bind(&rule_type)
+    .bind(&pattern)
+    .bind(&methods)
+    .bind(&description)
+    .bind(is_enabled)
+    .bind(sort_order)
+    .bind(&now)
+    .bind(&rule_id)
+    .bind(&app_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update access rule: {}", e);
+        ApiError::database("Failed to update access rule")
+    })?;
+
+    let updated = sqlx::query_as::<_, AppAccessRule>("SELECT * FROM app_access_rules WHERE id = ?")
+        .bind(&rule_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    // Refresh proxy routes for this app
+    apply_access_rules(&state, &app_id).await;
+
+    Ok(Json(updated))
+}
+
+/// Delete an access rule.
+pub async fn delete_access_rule(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, rule_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    if let Err(e) = validate_uuid(&rule_id, "rule_id") {
+        return Err(ApiError::validation_field("rule_id", e));
+    }
+
+    let result = sqlx::query("DELETE FROM app_access_rules WHERE id = ? AND app_id = ?")
+        .bind(&rule_id)
+        .bind(&app_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Access rule not found"));
+    }
+
+    // Refresh proxy routes for this app
+    apply_access_rules(&state, &app_id).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---- Helpers ----
+
+fn validate_rule_type(rule_type: &str) -> Result<(), ApiError> {
+    if !ACCESS_RULE_TYPES.contains(&rule_type) {
+        return Err(ApiError::validation_field(
+            "rule_type",
+            format!("Rule type must be one of: {}", ACCESS_RULE_TYPES.join(", ")),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a rule's pattern and return it in its stored form: a regex for
+/// request rules, a normalized country code list (e.g. `DE,FR`) for country rules.
+fn validate_pattern(state: &AppState, rule_type: &str, pattern: &str) -> Result<String, ApiError> {
+    if !is_country_rule(rule_type) {
+        validate_regex(pattern)?;
+        return Ok(pattern.to_string());
+    }
+
+    if state.config.proxy.geoip_db_path.is_none() {
+        return Err(ApiError::validation_field(
+            "rule_type",
+            "Country rules need a GeoIP database; set proxy.geoip_db_path in the config",
+        ));
+    }
+    let countries =
+        parse_country_codes(pattern).map_err(|e| ApiError::validation_field("pattern", e))?;
+    Ok(countries.join(","))
+}
+
+/// Validate that a string is a valid regex pattern.
+fn validate_regex(pattern: &str) -> Result<(), ApiError> {
+    if pattern.is_empty() {
+        return Err(ApiError::validation_field(
+            "pattern",
+            "Pattern cannot be empty".to_string(),
+        ));
+    }
+    Regex::new(pattern).map_err(|e| {
+        ApiError::validation_field("pattern", format!("Invalid regex pattern: {}", e))
+    })?;
+    Ok(())
+}
+
+/// Validate the method list and return it in its stored (comma-separated) form.
+/// Only `allow_methods` rules carry methods; other rule types store NULL.
+fn validate_methods(rule_type: &str, methods: &[String]) -> Result<Option<String>, ApiError> {
+    if rule_type != ACCESS_RULE_ALLOW_METHODS {
+        return Ok(None);
+    }
+
+    let methods = parse_methods(&methods.join(","));
+    if methods.is_empty() {
+        return Err(ApiError::validation_field(
+            "methods",
+            "At least one method is required for allow_methods rules".to_string(),
+        ));
+    }
+    if let Some(invalid) = methods
+        .iter()
+        .find(|m| !VALID_METHODS.contains(&m.as_str()))
+    {
+        return Err(ApiError::validation_field(
+            "methods",
+            format!("Unsupported HTTP method: {}", invalid),
+        ));
+    }
+    Ok(Some(methods.join(",")))
+}
+
+/// Reload the access rules for an app into its live proxy backend entries.
+async fn apply_access_rules(state: &Arc<AppState>, app_id: &str) {
+    let proxy_rules = match AppAccessRule::list_enabled_for_proxy(&state.db, app_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(app_id = %app_id, error = %e, "Failed to load access rules for proxy refresh");
+            return;
+        }
+    };
+
+    let domains = refresh_proxy_routes(state, app_id, false, |routes, domain, _| {
+        routes.update_access_rules(domain, proxy_rules.clone());
+    })
+    .await;
+
+    tracing::info!(
+        app_id = %app_id,
+        domains = ?domains,
+        rules_count = proxy_rules.len(),
+        "Access rules refreshed in proxy route table"
+    );
+}
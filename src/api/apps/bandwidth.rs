@@ -0,0 +1,130 @@
+//! Per-app bandwidth usage.
+//!
+//! Usage is recorded per calendar month by `crate::engine::bandwidth`, which
+//! also enforces the app's monthly cap.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::db::{App, BANDWIDTH_ACTION_THROTTLE};
+use crate::engine::bandwidth::{period_of, CONTAINER_SOURCE};
+use crate::proxy::traffic;
+use crate::AppState;
+
+use super::super::error::ApiError;
+use super::super::validation::validate_uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct BandwidthQuery {
+    /// Month as `YYYY-MM` (default: the current month, UTC)
+    pub period: Option<String>,
+}
+
+/// Proxy traffic of one of the app's domains
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct RouteBandwidth {
+    pub domain: String,
+    pub bytes_in: i64,
+    pub bytes_out: i64,
+}
+
+/// Response for GET /api/apps/:id/bandwidth
+#[derive(Debug, Serialize)]
+pub struct AppBandwidth {
+    pub app_id: String,
+    pub period: String,
+    /// Request bytes received by the proxy for the app's domains
+    pub bytes_in: i64,
+    /// Response bytes sent by the proxy for the app's domains
+    pub bytes_out: i64,
+    /// Proxy traffic per domain, busiest first
+    pub routes: Vec<RouteBandwidth>,
+    /// Bytes received by the app's containers, including traffic that didn't
+    /// pass through the proxy
+    pub container_rx_bytes: i64,
+    /// Bytes sent by the app's containers
+    pub container_tx_bytes: i64,
+    /// Monthly cap on proxy traffic in GB (null = unlimited)
+    pub limit_gb: Option<i64>,
+    pub limit_action: String,
+    /// Share of the cap used (null without a cap)
+    pub used_percent: Option<f64>,
+    /// Whether the app's domains are throttled right now
+    pub throttled: bool,
+}
+
+fn check_period(period: &str) -> Result<(), ApiError> {
+    let valid = period.len() == 7
+        && NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d").is_ok();
+    if !valid {
+        return Err(ApiError::validation_field(
+            "period",
+            "period must be a month as YYYY-MM",
+        ));
+    }
+    Ok(())
+}
+
+/// Get an app's bandwidth usage for a month, per domain and for its containers
+/// GET /api/apps/:id/bandwidth
+pub async fn get_app_bandwidth(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Query(query): Query<BandwidthQuery>,
+) -> Result<Json<AppBandwidth>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    let period = query.period.unwrap_or_else(|| period_of(Utc::now()));
+    check_period(&period)?;
+
+    let app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+        .bind(&app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+
+    let rows: Vec<RouteBandwidth> = sqlx::query_as(
+        "SELECT source AS domain, bytes_in, bytes_out FROM app_bandwidth \
+         WHERE app_id = ? AND period = ? ORDER BY bytes_in + bytes_out DESC",
+    )
+    .bind(&app_id)
+    .bind(&period)
+    .fetch_all(&state.db)
+    .await?;
+    let (containers, routes): (Vec<_>, Vec<_>) = rows
+        .into_iter()
+        .partition(|row| row.domain == CONTAINER_SOURCE);
+    let container = containers.first();
+
+    let bytes_in = routes.iter().map(|r| r.bytes_in).sum::<i64>();
+    let bytes_out = routes.iter().map(|r| r.bytes_out).sum::<i64>();
+    let used_percent = app
+        .bandwidth_limit_bytes()
+        .map(|limit| (bytes_in + bytes_out) as f64 / limit as f64 * 100.0);
+    let throttled = app.bandwidth_limit_action == BANDWIDTH_ACTION_THROTTLE && {
+        let throttled_domains = traffic::throttled_domains();
+        app.get_all_domain_names()
+            .iter()
+            .any(|domain| throttled_domains.contains(domain))
+    };
+
+    Ok(Json(AppBandwidth {
+        container_rx_bytes: container.map(|c| c.bytes_in).unwrap_or_default(),
+        container_tx_bytes: container.map(|c| c.bytes_out).unwrap_or_default(),
+        app_id,
+        period,
+        bytes_in,
+        bytes_out,
+        routes,
+        limit_gb: app.bandwidth_limit_gb,
+        limit_action: app.bandwidth_limit_action,
+        used_percent,
+        throttled,
+    }))
+}
@@ -6,8 +6,8 @@ use std::sync::Arc;
 
 use crate::crypto;
 use crate::db::{
-    actions, list_audit_logs, resource_types, App, AuditLogListResponse, AuditLogQuery, Deployment,
-    User,
+    actions, list_audit_logs, resource_types, App, AppPowerSchedule, AuditLogListResponse,
+    AuditLogQuery, Deployment, DeploymentEvent, User,
 };
 use crate::runtime::{PortMapping, RunConfig};
 use crate::AppState;
@@ -55,8 +55,8 @@ async fn collect_env_vars_for_restart(state: &AppState, app: &App) -> Vec<(Strin
         })
         .collect();
 
-    // Inject PORT if not already set
-    if !env_vars.iter().any(|(k, _)| k == "PORT") {
+    // Inject PORT if not already set (workers serve no port)
+    if !app.is_worker() && !env_vars.iter().any(|(k, _)| k == "PORT") {
         env_vars.push(("PORT".to_string(), app.port.to_string()));
     }
 
@@ -278,22 +278,31 @@ pub async fn start_app(
 
     tracing::info!(app = %app.name, container = %container_id, "App container started");
 
+    // A manual start overrides a power schedule that stopped the app
+    if let Err(e) = AppPowerSchedule::clear_stopped(&state.db, &app.id).await {
+        tracing::warn!(app = %app.name, error = %e, "Failed to clear power schedule stop");
+    }
+
     // Restore deployment status to 'running' so the container monitor resumes crash detection.
-    if let Err(e) = sqlx::query(
+    match sqlx::query_scalar::<_, String>(
         "UPDATE deployments SET status = 'running', finished_at = NULL \
-         WHERE app_id = ? AND status = 'stopped' AND container_id = ?",
+         WHERE app_id = ? AND status = 'stopped' AND container_id = ? RETURNING id",
     )
     .bind(&app.id)
     .bind(&container_id)
-    .execute(&state.db)
+    .fetch_all(&state.db)
     .await
     {
-        tracing::warn!(
+        Ok(started) => {
+            DeploymentEvent::record_all(&state.db, &started, "running", Some("Started manually"))
+                .await
+        }
+        Err(e) => tracing::warn!(
             app = %app.name,
             container = %container_id,
             error = %e,
             "Failed to update deployment status to running after manual start"
-        );
+        ),
     }
 
     // Get container info for the port (used for both routing and response)
@@ -310,7 +319,8 @@ pub async fn start_app(
             if let Some(port) = host_port {
                 let backend =
                     crate::proxy::Backend::new(container_id.clone(), "127.0.0.1".to_string(), port)
-                        .with_healthcheck(app.healthcheck.clone());
+                        .with_healthcheck(app.healthcheck.clone())
+                        .with_health_probe(app.health_probe());
 
                 state.routes.load().add_route(domain.clone(), backend);
                 tracing::info!(domain = %domain, "Route re-registered after start");
@@ -389,21 +399,25 @@ pub async fn stop_app(
     // Mark the deployment as stopped so the container monitor does NOT restart it.
     // Without this update the deployment record keeps status = 'running', which causes
     // the monitor to treat the stopped container as a crash and restart it automatically.
-    if let Err(e) = sqlx::query(
+    match sqlx::query_scalar::<_, String>(
         "UPDATE deployments SET status = 'stopped', finished_at = datetime('now') \
-         WHERE app_id = ? AND status = 'running' AND container_id = ?",
+         WHERE app_id = ? AND status = 'running' AND container_id = ? RETURNING id",
     )
     .bind(&app.id)
     .bind(&container_id)
-    .execute(&state.db)
+    .fetch_all(&state.db)
     .await
     {
-        tracing::warn!(
+        Ok(stopped) => {
+            DeploymentEvent::record_all(&state.db, &stopped, "stopped", Some("Stopped manually"))
+                .await
+        }
+        Err(e) => tracing::warn!(
             app = %app.name,
             container = %container_id,
             error = %e,
             "Failed to update deployment status to stopped after manual stop"
-        );
+        ),
     }
 
     // Remove the route if app has a domain
@@ -506,12 +520,13 @@ async fn finish_restart_deployment(
     .execute(&state.db)
     .await;
 
-    if let Err(e) = result {
-        tracing::warn!(
+    match result {
+        Ok(_) => DeploymentEvent::record(&state.db, deployment_id, status, error_message).await,
+        Err(e) => tracing::warn!(
             deployment_id = %deployment_id,
             error = %e,
             "Failed to update restart deployment status"
-        );
+        ),
     }
 }
 
@@ -636,7 +651,8 @@ pub async fn restart_app(
                         "127.0.0.1".to_string(),
                         port,
                     )
-                    .with_healthcheck(app.healthcheck.clone());
+                    .with_healthcheck(app.healthcheck.clone())
+                    .with_health_probe(app.health_probe());
                     backend.www_redirect_target = www_redirect_target;
                     route_table.add_route(domain, backend);
                 }
@@ -719,6 +735,7 @@ pub async fn restart_app(
         .bind(&restart_dep_id)
         .execute(&state.db)
         .await;
+    DeploymentEvent::record(&state.db, &restart_dep_id, "starting", None).await;
 
     // 3. Build RunConfig for the new (blue) container
     let new_container_name = format!(
@@ -783,7 +800,7 @@ pub async fn restart_app(
     let new_run_config = RunConfig {
         image: image_tag.clone(),
         name: new_container_name.clone(),
-        port: app.port as u16,
+        port: app.container_port(),
         env: env_vars,
         memory_limit: app.memory_limit.clone(),
         cpu_limit: app.cpu_limit.clone(),
@@ -803,7 +820,7 @@ pub async fn restart_app(
         gpus: app.docker_gpus.clone(),
         ulimits,
         security_opt,
-        cmd: None,
+        cmd: crate::engine::processes::web_command(&state.db, &app.id).await,
         network: None,
         custom_labels: vec![],
     };
@@ -924,6 +941,7 @@ pub async fn restart_app(
         .bind(&restart_dep_id)
         .execute(&state.db)
         .await;
+    DeploymentEvent::record(&state.db, &restart_dep_id, "checking", None).await;
 
     // 6. Poll the new container's health endpoint (up to 60 seconds)
     let health_path = app.healthcheck.as_deref().unwrap_or("/");
@@ -1030,7 +1048,8 @@ pub async fn restart_app(
                 "127.0.0.1".to_string(),
                 new_port,
             )
-            .with_healthcheck(app.healthcheck.clone());
+            .with_healthcheck(app.healthcheck.clone())
+            .with_health_probe(app.health_probe());
             backend.www_redirect_target = www_redirect_target.clone();
             route_table.add_route(domain.clone(), backend);
             tracing::info!(
@@ -1111,15 +1130,7 @@ pub async fn restart_app(
     );
 
     // 9. Mark the previous 'running' deployment as 'replaced' now that the restart is live.
-    let _ = sqlx::query(
-        "UPDATE deployments SET status = 'replaced', finished_at = ? \
-         WHERE app_id = ? AND status = 'running' AND id != ?",
-    )
-    .bind(chrono::Utc::now().to_rfc3339())
-    .bind(&app.id)
-    .bind(&restart_dep_id)
-    .execute(&state.db)
-    .await;
+    let _ = Deployment::mark_others_replaced(&state.db, &app.id, &restart_dep_id).await;
 
     log_restart_step(
         &state,
@@ -7,10 +7,13 @@ use axum::{
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::crypto;
 use crate::db::{
-    actions, resource_types, App, AppResponse, CreateAppRequest, TeamAuditAction,
-    TeamAuditResourceType, UpdateAppRequest, User,
+    actions, resource_types, App, AppProcess, AppResponse, BuildSecret, CreateAppRequest,
+    ManagedDatabase, TeamAuditAction, TeamAuditResourceType, UpdateAppRequest, User,
+    BUILD_SECRET_MASK, PREVIEW_DATABASE_SCHEMA,
 };
+use crate::engine::static_builder::StaticSiteSettings;
 use crate::AppState;
 use axum::http::header;
 
@@ -25,6 +28,75 @@ use super::{
     DeleteAppRequest, ListAppsQuery,
 };
 
+/// Key length for AES-256 encryption (32 bytes)
+const KEY_LENGTH: usize = 32;
+
+/// Get the derived encryption key from the config, if configured.
+fn get_encryption_key(state: &AppState) -> Option<[u8; KEY_LENGTH]> {
+    state
+        .config
+        .auth
+        .encryption_key
+        .as_ref()
+        .map(|secret| crypto::derive_key(secret))
+}
+
+/// Serialize build secrets for storage, encrypting their values. A value sent
+/// back as `BUILD_SECRET_MASK` keeps the stored value of the same key.
+fn store_build_secrets(
+    secrets: &[BuildSecret],
+    existing: &App,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<String, ApiError> {
+    let stored = existing.get_build_secrets();
+    let mut result = Vec::with_capacity(secrets.len());
+    for secret in secrets {
+        let key = secret.key.trim();
+        if key.is_empty() {
+            return Err(ApiError::validation_field(
+                "build_secrets",
+                "Build secret keys cannot be empty",
+            ));
+        }
+        if !BuildSecret::is_valid_key(key) {
+            return Err(ApiError::validation_field(
+                "build_secrets",
+                format!(
+                    "Build secret key '{}' may only contain letters, digits, '_', '.' and '-'",
+                    key
+                ),
+            ));
+        }
+        let value = if secret.value == BUILD_SECRET_MASK {
+            match stored.iter().find(|s| s.key == key) {
+                Some(kept) => kept.value.clone(),
+                None => {
+                    return Err(ApiError::validation_field(
+                        "build_secrets",
+                        format!("Build secret '{}' needs a value", key),
+                    ))
+                }
+            }
+        } else {
+            secret.value.clone()
+        };
+        // Kept values saved before encryption was enabled get encrypted too
+        let value = if crypto::is_encrypted(&value) {
+            value
+        } else {
+            crypto::encrypt_if_key_available(&value, encryption_key).map_err(|e| {
+                tracing::error!("Failed to encrypt build secret: {}", e);
+                ApiError::internal("Failed to encrypt build secret")
+            })?
+        };
+        result.push(BuildSecret {
+            key: key.to_string(),
+            value,
+        });
+    }
+    serde_json::to_string(&result).map_err(|_| ApiError::internal("Failed to store build secrets"))
+}
+
 pub async fn list_apps(
     State(state): State<Arc<AppState>>,
     user: User,
@@ -153,9 +225,16 @@ pub async fn create_app(
         .as_ref()
         .map(|l| serde_json::to_string(l).unwrap_or_default());
     let nixpacks_config_json = req.nixpacks_config.as_ref().map(|v| v.to_string());
+    let static_config_json = req
+        .static_config
+        .as_ref()
+        .map(|c| serde_json::to_string(c).unwrap_or_default());
 
-    // Generate auto_subdomain: sslip (legacy) → base_domain → traefik.me → None
-    let auto_subdomain = if state.config.proxy.sslip_enabled {
+    // Generate auto_subdomain: sslip (legacy) → base_domain → traefik.me → None.
+    // Workers aren't routed by the proxy, so they get none.
+    let auto_subdomain = if req.app_type == crate::db::APP_TYPE_WORKER {
+        None
+    } else if state.config.proxy.sslip_enabled {
         state.config.proxy.generate_sslip_domain(None)
     } else {
         state.config.proxy.generate_auto_domain(&req.name)
@@ -184,8 +263,8 @@ pub async fn create_app(
 
     sqlx::query(
         r#"
-        INSERT INTO apps (id, name, git_url, branch, dockerfile, domain, port, healthcheck, memory_limit, cpu_limit, ssh_key_id, environment, project_id, team_id, dockerfile_path, base_directory, build_target, watch_paths, custom_docker_options, port_mappings, network_aliases, extra_hosts, domains, auto_subdomain, pre_deploy_commands, post_deploy_commands, docker_image, docker_image_tag, registry_url, registry_username, registry_password, container_labels, build_type, nixpacks_config, publish_directory, preview_enabled, git_provider_id, github_app_installation_id, restart_policy, privileged, cap_add, devices, shm_size, init_process, docker_cap_drop, docker_gpus, docker_ulimits, docker_security_opt, replica_count, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, NULL, NULL, NULL, ?, ?, ?)
+        INSERT INTO apps (id, name, git_url, branch, dockerfile, domain, port, healthcheck, memory_limit, cpu_limit, ssh_key_id, environment, project_id, team_id, dockerfile_path, base_directory, build_target, watch_paths, custom_docker_options, port_mappings, network_aliases, extra_hosts, domains, auto_subdomain, pre_deploy_commands, post_deploy_commands, docker_image, docker_image_tag, registry_url, registry_username, registry_password, container_labels, build_type, nixpacks_config, publish_directory, static_config, preview_enabled, git_provider_id, github_app_installation_id, restart_policy, privileged, cap_add, devices, shm_size, init_process, docker_cap_drop, docker_gpus, docker_ulimits, docker_security_opt, replica_count, app_type, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, NULL, NULL, NULL, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
@@ -223,6 +302,7 @@ pub async fn create_app(
     .bind(&req.build_type)
     .bind(&nixpacks_config_json)
     .bind(&req.publish_directory)
+    .bind(&static_config_json)
     .bind(req.preview_enabled)
     .bind(&req.git_provider_id)
     .bind(&req.github_app_installation_id)
@@ -233,6 +313,7 @@ pub async fn create_app(
     .bind(&req.shm_size)
     .bind(req.init_process)
     .bind(req.replica_count)
+    .bind(&req.app_type)
     .bind(&now)
     .bind(&now)
     .execute(&state.db)
@@ -434,10 +515,14 @@ pub async fn update_app(
         existing.restart_policy.clone()
     };
 
-    // Build secrets (stored as JSON, serialized from Vec<BuildSecret>)
+    // Build secrets (stored as JSON, values encrypted when a key is configured)
     let build_secrets_json = match &req.build_secrets {
         Some(secrets) if secrets.is_empty() => None,
-        Some(secrets) => serde_json::to_string(secrets).ok(),
+        Some(secrets) => Some(store_build_secrets(
+            secrets,
+            &existing,
+            get_encryption_key(&state).as_ref(),
+        )?),
         None => existing.build_secrets.clone(),
     };
 
@@ -510,6 +595,177 @@ pub async fn update_app(
         None => existing.custom_labels.clone(),
     };
 
+    // Build sandbox (migration 121) — empty string inherits the team setting
+    let build_sandbox = merge_optional_string(&req.build_sandbox, &existing.build_sandbox);
+
+    // Network egress policy (migration 123)
+    let egress_policy = req
+        .egress_policy
+        .clone()
+        .unwrap_or(existing.egress_policy.clone());
+    let egress_allowlist = merge_optional_json(&req.egress_allowlist, &existing.egress_allowlist);
+
+    // Deploy wait conditions (migration 131)
+    let deploy_wait_conditions = merge_optional_json(
+        &req.deploy_wait_conditions,
+        &existing.deploy_wait_conditions,
+    );
+
+    // App type (migration 132)
+    let app_type = req.app_type.clone().unwrap_or(existing.app_type.clone());
+
+    // Health check window (migration 134)
+    let healthcheck_window_secs = req
+        .healthcheck_window_secs
+        .unwrap_or(existing.healthcheck_window_secs);
+
+    // Webhook filters (migration 136)
+    let webhook_branch_regex =
+        merge_optional_string(&req.webhook_branch_regex, &existing.webhook_branch_regex);
+    let webhook_ignore_authors = merge_optional_string(
+        &req.webhook_ignore_authors,
+        &existing.webhook_ignore_authors,
+    );
+    let webhook_ignore_paths =
+        merge_optional_string(&req.webhook_ignore_paths, &existing.webhook_ignore_paths);
+
+    // Buildpacks configuration (migration 137)
+    let cnb_config = match &req.cnb_config {
+        Some(v) if v.as_object().is_some_and(|o| o.is_empty()) => None, // Explicit clear
+        Some(v) => Some(v.to_string()),
+        None => existing.cnb_config.clone(),
+    };
+
+    // Static site build settings (migration 139)
+    let static_config = match &req.static_config {
+        Some(c) if *c == StaticSiteSettings::default() => None, // Explicit clear
+        Some(c) => serde_json::to_string(c).ok(),
+        None => existing.static_config.clone(),
+    };
+
+    // Deployment fences (migration 138)
+    let deploy_fences = merge_optional_json(&req.deploy_fences, &existing.deploy_fences);
+
+    // Docker Compose repository deployments (migration 140)
+    let compose_file = merge_optional_string(&req.compose_file, &existing.compose_file);
+    let compose_service = merge_optional_string(&req.compose_service, &existing.compose_service);
+
+    // Smoke tests (migration 154)
+    let smoke_tests = merge_optional_json(&req.smoke_tests, &existing.smoke_tests);
+
+    // Retention overrides (migration 155); 0 or negative clears back to the
+    // global cleanup settings.
+    let max_deployments = match req.max_deployments {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.max_deployments,
+    };
+    let keep_images = match req.keep_images {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.keep_images,
+    };
+
+    // Test stage (migration 159); a timeout of 0 or less clears back to the default
+    let test_commands = merge_optional_json(&req.test_commands, &existing.test_commands);
+    let test_timeout_secs = match req.test_timeout_secs {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.test_timeout_secs,
+    };
+
+    // Preview expiry (migration 164); 0 or negative removes the limit
+    let preview_max_lifetime_hours = match req.preview_max_lifetime_hours {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.preview_max_lifetime_hours,
+    };
+    let preview_idle_timeout_hours = match req.preview_idle_timeout_hours {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.preview_idle_timeout_hours,
+    };
+
+    // Prometheus metrics endpoint (migration 167); 0 or negative stops scraping
+    let metrics_port = match req.metrics_port {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.metrics_port,
+    };
+    let metrics_path = merge_optional_string(&req.metrics_path, &existing.metrics_path);
+
+    // Preview databases (migration 168); the source database must belong to
+    // the app's team and, to copy its schema, be of a type that supports it
+    let preview_database = merge_optional_string(&req.preview_database, &existing.preview_database);
+    let preview_database_source_id = merge_optional_string(
+        &req.preview_database_source_id,
+        &existing.preview_database_source_id,
+    );
+    let preview_database_env =
+        merge_optional_string(&req.preview_database_env, &existing.preview_database_env);
+    if let Some(ref mode) = preview_database {
+        let Some(ref source_id) = preview_database_source_id else {
+            return Err(ApiError::validation_field(
+                "preview_database_source_id",
+                "A source database is required for preview databases".to_string(),
+            ));
+        };
+        let source = sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
+            .bind(source_id)
+            .fetch_optional(&state.db)
+            .await?
+            .filter(|d| d.team_id == existing.team_id)
+            .ok_or_else(|| {
+                ApiError::validation_field(
+                    "preview_database_source_id",
+                    "Source database not found".to_string(),
+                )
+            })?;
+        if mode == PREVIEW_DATABASE_SCHEMA && !source.get_db_type().supports_schema_copy() {
+            return Err(ApiError::validation_field(
+                "preview_database",
+                format!("The schema of {} databases can't be copied", source.db_type),
+            ));
+        }
+    }
+
+    // Scale-to-zero (migration 169); 0 or negative keeps the app always running
+    let idle_timeout_minutes = match req.idle_timeout_minutes {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.idle_timeout_minutes,
+    };
+
+    // Bandwidth cap (migration 171); 0 or negative removes the cap
+    let bandwidth_limit_gb = match req.bandwidth_limit_gb {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.bandwidth_limit_gb,
+    };
+    let bandwidth_limit_action = req
+        .bandwidth_limit_action
+        .clone()
+        .unwrap_or_else(|| existing.bandwidth_limit_action.clone());
+    let bandwidth_throttle_kbps = match req.bandwidth_throttle_kbps {
+        Some(n) if n <= 0 => None,
+        Some(n) => Some(n),
+        None => existing.bandwidth_throttle_kbps,
+    };
+
+    // Health check probe (migration 124)
+    let healthcheck_type = req
+        .healthcheck_type
+        .clone()
+        .unwrap_or(existing.healthcheck_type.clone());
+    let healthcheck_command =
+        merge_optional_string(&req.healthcheck_command, &existing.healthcheck_command);
+    if healthcheck_type == crate::proxy::HEALTHCHECK_COMMAND && healthcheck_command.is_none() {
+        return Err(ApiError::validation_field(
+            "healthcheck_command",
+            "A command is required for command health checks".to_string(),
+        ));
+    }
+
     // Extended Docker run options (migration 085)
     let update_cap_drop = match &req.docker_cap_drop {
         Some(v) if v.is_empty() => None,
@@ -607,6 +863,38 @@ pub async fn update_app(
             inline_dockerfile = ?,
             destination_id = ?,
             custom_labels = ?,
+            build_sandbox = ?,
+            egress_policy = ?,
+            egress_allowlist = ?,
+            healthcheck_type = ?,
+            healthcheck_command = ?,
+            deploy_wait_conditions = ?,
+            app_type = ?,
+            healthcheck_window_secs = ?,
+            webhook_branch_regex = ?,
+            webhook_ignore_authors = ?,
+            webhook_ignore_paths = ?,
+            cnb_config = ?,
+            deploy_fences = ?,
+            static_config = ?,
+            compose_file = ?,
+            compose_service = ?,
+            smoke_tests = ?,
+            max_deployments = ?,
+            keep_images = ?,
+            test_commands = ?,
+            test_timeout_secs = ?,
+            preview_max_lifetime_hours = ?,
+            preview_idle_timeout_hours = ?,
+            metrics_port = ?,
+            metrics_path = ?,
+            preview_database = ?,
+            preview_database_source_id = ?,
+            preview_database_env = ?,
+            idle_timeout_minutes = ?,
+            bandwidth_limit_gb = ?,
+            bandwidth_limit_action = ?,
+            bandwidth_throttle_kbps = ?,
             github_app_installation_id = ?,
             deployment_source = ?,
             updated_at = ?
@@ -679,6 +967,38 @@ pub async fn update_app(
     .bind(&inline_dockerfile)
     .bind(&destination_id)
     .bind(&custom_labels)
+    .bind(&build_sandbox)
+    .bind(&egress_policy)
+    .bind(&egress_allowlist)
+    .bind(&healthcheck_type)
+    .bind(&healthcheck_command)
+    .bind(&deploy_wait_conditions)
+    .bind(&app_type)
+    .bind(healthcheck_window_secs)
+    .bind(&webhook_branch_regex)
+    .bind(&webhook_ignore_authors)
+    .bind(&webhook_ignore_paths)
+    .bind(&cnb_config)
+    .bind(&deploy_fences)
+    .bind(&static_config)
+    .bind(&compose_file)
+    .bind(&compose_service)
+    .bind(&smoke_tests)
+    .bind(max_deployments)
+    .bind(keep_images)
+    .bind(&test_commands)
+    .bind(test_timeout_secs)
+    .bind(preview_max_lifetime_hours)
+    .bind(preview_idle_timeout_hours)
+    .bind(metrics_port)
+    .bind(&metrics_path)
+    .bind(&preview_database)
+    .bind(&preview_database_source_id)
+    .bind(&preview_database_env)
+    .bind(idle_timeout_minutes)
+    .bind(bandwidth_limit_gb)
+    .bind(&bandwidth_limit_action)
+    .bind(bandwidth_throttle_kbps)
     .bind(&github_app_installation_id)
     .bind(&deployment_source)
     .bind(&now)
@@ -739,14 +1059,45 @@ pub async fn update_app(
                         })
                         .collect();
 
-                    let route_table = state.routes.load();
+                    let proxy_access_rules =
+                        crate::db::AppAccessRule::list_enabled_for_proxy(&state.db, &app.id)
+                            .await
+                            .unwrap_or_default();
+                    let basic_auth_paths =
+                        crate::db::AppBasicAuthPath::list_enabled_for_proxy(&state.db, &app.id)
+                            .await
+                            .unwrap_or_default();
+                    let waf_policy = crate::db::AppWafSettings::load_policy(&state.db, &app.id)
+                        .await
+                        .unwrap_or_default();
+                    let bot_policy =
+                        crate::db::AppBotSettings::load_policy(&state.db, &app.id, false)
+                            .await
+                            .unwrap_or_default();
+                    let forward_auth = crate::db::AppForwardAuth::load_policy(&state.db, &app.id)
+                        .await
+                        .unwrap_or_default();
+                    let cors = crate::db::AppCorsSettings::load_policy(&state.db, &app.id)
+                        .await
+                        .unwrap_or_default();
+
+                    // Replace the app's routes in one swap: drop domains the update
+                    // removed and register the current ones together
+                    let passthrough_domains = app.tls_passthrough_domains();
+                    let mut batch = crate::proxy::RouteBatch::new();
+                    for (domain, _) in existing.get_all_domains_with_redirects() {
+                        if !domain_entries.iter().any(|(d, _)| *d == domain) {
+                            batch.remove_route(domain);
+                        }
+                    }
                     for (domain, www_redirect_target) in &domain_entries {
                         let mut backend = crate::proxy::Backend::new(
                             container_id.clone(),
                             "127.0.0.1".to_string(),
                             port,
                         )
-                        .with_healthcheck(app.healthcheck.clone());
+                        .with_healthcheck(app.healthcheck.clone())
+                        .with_health_probe(app.health_probe());
 
                         // Set www redirect target
                         backend.www_redirect_target = www_redirect_target.clone();
@@ -768,8 +1119,22 @@ pub async fn update_app(
                             backend.set_redirect_rules(proxy_redirect_rules.clone());
                         }
 
-                        route_table.add_route(domain.clone(), backend);
+                        // Attach access rules (skip for www-redirect backends)
+                        if www_redirect_target.is_none() && !proxy_access_rules.is_empty() {
+                            backend.set_access_rules(proxy_access_rules.clone());
+                        }
+                        if www_redirect_target.is_none() {
+                            backend.set_basic_auth_paths(basic_auth_paths.clone());
+                            backend.set_waf(waf_policy.clone());
+                            backend.set_bots(bot_policy.clone());
+                            backend.set_forward_auth(forward_auth.clone());
+                            backend.set_cors(cors.clone());
+                        }
+
+                        backend.tls_passthrough = passthrough_domains.contains(domain);
+                        batch.add_route(domain.clone(), backend);
                     }
+                    state.routes.load().apply(&batch);
                     if !all_domains.is_empty() {
                         tracing::info!(domains = ?all_domains, "Proxy routes updated after app settings change");
                     }
@@ -778,6 +1143,19 @@ pub async fn update_app(
         }
     }
 
+    // Apply a changed egress policy to the running containers now rather than
+    // on the enforcer's next pass
+    if app.egress_policy != existing.egress_policy
+        || app.egress_allowlist != existing.egress_allowlist
+    {
+        let db = state.db.clone();
+        let runtime = state.runtime.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            crate::engine::egress::apply_logged(&db, &runtime, &app).await;
+        });
+    }
+
     // Log audit event
     audit_log(
         &state,
@@ -846,12 +1224,16 @@ pub async fn delete_app(
     let app = authz::authorize_app(&state, &user, &id).await?;
 
     // Stop any running containers for this app
-    let deployments: Vec<(String,)> = sqlx::query_as(
+    let mut deployments: Vec<(String,)> = sqlx::query_as(
         "SELECT container_id FROM deployments WHERE app_id = ? AND container_id IS NOT NULL AND container_id != ''"
     )
     .bind(&id)
     .fetch_all(&state.db)
     .await?;
+    // Containers of the Procfile process types
+    for process in AppProcess::list(&state.db, &id).await? {
+        deployments.extend(process.get_container_ids().into_iter().map(|cid| (cid,)));
+    }
 
     for (container_id,) in deployments {
         if !container_id.is_empty() {
@@ -865,6 +1247,11 @@ pub async fn delete_app(
         }
     }
 
+    // The other services of a compose app's stack go with it
+    if app.uses_docker_compose() {
+        crate::engine::remove_compose_stack(&app).await;
+    }
+
     // Remove the proxy route if app has a domain
     if let Some(domain) = &app.domain {
         if !domain.is_empty() {
@@ -873,6 +1260,13 @@ pub async fn delete_app(
         }
     }
 
+    // Drop the app's egress rules along with its containers
+    if crate::engine::egress::is_restricted(&app) {
+        if let Err(e) = crate::engine::egress::remove_rules(&app.id).await {
+            tracing::warn!(app_id = %app.id, error = %e, "Failed to remove egress rules during app deletion");
+        }
+    }
+
     // Delete the app (cascades to deployments, env_vars, volumes, etc.)
     let result = sqlx::query("DELETE FROM apps WHERE id = ?")
         .bind(&id)
@@ -985,3 +1379,81 @@ jobs:
         yaml,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::audit::ClientIp;
+    use crate::db::{load_test_app, test_app, test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_store_build_secrets_rejects_path_like_keys() {
+        let (_dir, db) = test_pool().await;
+        let app = load_test_app(&db, &test_app(&db, "web").await).await;
+        let secret = |key: &str| BuildSecret {
+            key: key.to_string(),
+            value: "s3cret".to_string(),
+        };
+
+        for key in ["..", ".", "../../etc/cron.d/x", "a/b", "id=x,src=/x"] {
+            assert!(
+                store_build_secrets(&[secret(key)], &app, None).is_err(),
+                "{}",
+                key
+            );
+        }
+
+        let stored = store_build_secrets(&[secret("NPM_TOKEN"), secret(".npmrc")], &app, None)
+            .expect("valid keys are stored");
+        let stored: Vec<BuildSecret> = serde_json::from_str(&stored).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[1].key, ".npmrc");
+    }
+
+    #[tokio::test]
+    async fn test_update_sets_the_health_check_window() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        let admin = test_user(&state.db, "admin").await;
+        let update = |secs: i64| {
+            Json(
+                serde_json::from_value::<UpdateAppRequest>(
+                    serde_json::json!({ "healthcheck_window_secs": secs }),
+                )
+                .unwrap(),
+            )
+        };
+
+        assert_eq!(
+            load_test_app(&state.db, &app_id)
+                .await
+                .healthcheck_window_secs,
+            20
+        );
+        let app = update_app(
+            State(state.clone()),
+            admin.clone(),
+            ClientIp(None),
+            Path(app_id.clone()),
+            update(120),
+        )
+        .await
+        .unwrap();
+        assert_eq!(app.healthcheck_window_secs, 120);
+
+        for secs in [5, 601] {
+            let result = update_app(
+                State(state.clone()),
+                admin.clone(),
+                ClientIp(None),
+                Path(app_id.clone()),
+                update(secs),
+            )
+            .await;
+            assert!(result.is_err(), "{}", secs);
+        }
+        let app = load_test_app(&state.db, &app_id).await;
+        assert_eq!(app.healthcheck_window_secs, 120);
+        assert_eq!(app.health_check_attempts(), 60);
+    }
+}
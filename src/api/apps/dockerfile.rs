@@ -0,0 +1,157 @@
+//! Dockerfile generation assistant.
+//!
+//! Inspects an app's repository (or an uploaded ZIP) with the build
+//! detection and generates a recommended Dockerfile for Node, Python, Go and
+//! Rust projects. Nothing is stored: the user commits the Dockerfile or saves
+//! it as a deployment patch at `override_path`, which git deployments write
+//! into the checkout before building.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::crypto;
+use crate::db::{App, User};
+use crate::engine::dockerfile_gen::{generate_dockerfile, GeneratedDockerfile};
+use crate::engine::{clone_app_source, detect_build_type, BuildDetectionResult, BuildType};
+use crate::AppState;
+
+use super::super::error::ApiError;
+use super::super::validation::validate_uuid;
+
+/// Error message for sources without a supported stack
+pub const UNSUPPORTED_STACK: &str = "No supported stack found; Dockerfiles can be \
+     generated for Node.js, Python, Go and Rust projects";
+
+#[derive(Debug, Serialize)]
+pub struct DockerfileSuggestion {
+    /// Build detection of the source, as returned by /api/build/detect
+    pub detection: BuildDetectionResult,
+    #[serde(flatten)]
+    pub generated: GeneratedDockerfile,
+    /// Path, relative to the repository root, the app builds its Dockerfile
+    /// from; a deployment patch at this path overrides the repository's file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_path: Option<String>,
+}
+
+/// Detect the build type of `source_dir` and generate a Dockerfile for it
+pub async fn suggest_dockerfile(
+    source_dir: &FsPath,
+) -> anyhow::Result<Option<DockerfileSuggestion>> {
+    let detection = detect_build_type(source_dir).await?;
+    let Some(mut generated) = generate_dockerfile(source_dir).await? else {
+        return Ok(None);
+    };
+    if detection.build_type == BuildType::Dockerfile {
+        generated.notes.insert(
+            0,
+            format!(
+                "The source already has a Dockerfile ({})",
+                detection.detected_from
+            ),
+        );
+    }
+    Ok(Some(DockerfileSuggestion {
+        detection,
+        generated,
+        override_path: None,
+    }))
+}
+
+/// Where the app's Dockerfile lives relative to the repository root
+fn dockerfile_override_path(app: &App) -> String {
+    let dockerfile = app
+        .dockerfile_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .unwrap_or(&app.dockerfile);
+    match app.base_directory.as_deref().map(|d| d.trim_matches('/')) {
+        Some(base_dir) if !base_dir.is_empty() => format!("{}/{}", base_dir, dockerfile),
+        _ => dockerfile.to_string(),
+    }
+}
+
+/// Generate a Dockerfile for an app's repository
+/// POST /api/apps/:id/dockerfile/generate
+pub async fn generate_app_dockerfile(
+    State(state): State<Arc<AppState>>,
+    _user: User,
+    Path(id): Path<String>,
+) -> Result<Json<DockerfileSuggestion>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+
+    if app.uses_registry_image() {
+        return Err(ApiError::bad_request(
+            "Registry image apps are not built from source",
+        ));
+    }
+    if app.deployment_source.as_deref() == Some("upload") || app.git_url.is_empty() {
+        return Err(ApiError::bad_request(
+            "App has no repository to inspect; upload a ZIP to /api/build/dockerfile instead",
+        ));
+    }
+
+    let encryption_key = state
+        .config
+        .auth
+        .encryption_key
+        .as_ref()
+        .map(|secret| crypto::derive_key(secret));
+    let work_dir = std::env::temp_dir().join(format!("rivetr-dockerfile-{}", Uuid::new_v4()));
+    let result = async {
+        let source_dir =
+            clone_app_source(&state.db, &app, &work_dir, encryption_key.as_ref()).await?;
+        suggest_dockerfile(&source_dir).await
+    }
+    .await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+
+    let mut suggestion = result
+        .map_err(|e| ApiError::bad_request(format!("Failed to inspect repository: {:#}", e)))?
+        .ok_or_else(|| ApiError::bad_request(UNSUPPORTED_STACK))?;
+    suggestion.override_path = Some(dockerfile_override_path(&app));
+    Ok(Json(suggestion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_path_follows_base_directory_and_dockerfile_path() {
+        let mut app: App = serde_json::from_value(serde_json::json!({
+            "id": "app-1",
+            "name": "web",
+            "git_url": "https://github.com/acme/web",
+            "branch": "main",
+            "dockerfile": "Dockerfile",
+            "port": 3000,
+            "environment": "production",
+            "basic_auth_enabled": 0,
+            "created_at": "",
+            "updated_at": "",
+        }))
+        .unwrap();
+        assert_eq!(dockerfile_override_path(&app), "Dockerfile");
+
+        app.base_directory = Some("services/api/".to_string());
+        app.dockerfile_path = Some("docker/Dockerfile.prod".to_string());
+        assert_eq!(
+            dockerfile_override_path(&app),
+            "services/api/docker/Dockerfile.prod"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Deployment locks.
+//!
+//! An operator can freeze an app while investigating an incident or during a
+//! migration. While the lock is held, manual and webhook deployments of the
+//! app are refused; rollbacks and restarts stay available so the lock never
+//! blocks recovery.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::db::{actions, resource_types, App, User};
+use crate::AppState;
+
+use super::super::audit::{audit_log, ClientIp};
+use super::super::error::ApiError;
+use super::super::validation::validate_uuid;
+
+/// Longest accepted lock reason
+const MAX_LOCK_REASON_LEN: usize = 500;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LockAppRequest {
+    /// Why the app is being locked
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AppLockResponse {
+    pub app_id: String,
+    pub locked: bool,
+    pub locked_at: Option<String>,
+    pub locked_by: Option<String>,
+    pub lock_reason: Option<String>,
+}
+
+/// Refuse a deployment of a locked app with 409 Conflict.
+pub fn check_deploy_lock(app: &App) -> Result<(), ApiError> {
+    match app.deploy_lock_message() {
+        Some(message) => Err(ApiError::conflict(message)),
+        None => Ok(()),
+    }
+}
+
+async fn fetch_app(state: &AppState, id: &str) -> Result<App, ApiError> {
+    if let Err(e) = validate_uuid(id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))
+}
+
+/// Lock an app against deployments
+/// POST /api/apps/:id/lock
+pub async fn lock_app(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Path(id): Path<String>,
+    body: Option<Json<LockAppRequest>>,
+) -> Result<Json<AppLockResponse>, ApiError> {
+    let app = fetch_app(&state, &id).await?;
+    let reason = body
+        .and_then(|b| b.0.reason)
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    if reason
+        .as_ref()
+        .is_some_and(|r| r.chars().count() > MAX_LOCK_REASON_LEN)
+    {
+        return Err(ApiError::validation_field(
+            "reason",
+            format!(
+                "Lock reason must be at most {} characters",
+                MAX_LOCK_REASON_LEN
+            ),
+        ));
+    }
+
+    let holder = if user.name.is_empty() {
+        user.email.clone()
+    } else {
+        user.name.clone()
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+
+    // Only take the lock if nobody holds it, so two operators can't
+    // overwrite each other's reason
+    let result = sqlx::query(
+        "UPDATE apps SET locked_at = ?, locked_by = ?, lock_reason = ?, updated_at = ? \
+         WHERE id = ? AND locked_at IS NULL",
+    )
+    .bind(&now)
+    .bind(&holder)
+    .bind(&reason)
+    .bind(&now)
+    .bind(&id)
+    .execute(&state.db)
+    .await?;
+    if result.rows_affected() == 0 {
+        let current = fetch_app(&state, &id).await?;
+        return Err(ApiError::conflict(
+            current
+                .deploy_lock_message()
+                .unwrap_or_else(|| "App lock changed concurrently, try again".to_string()),
+        ));
+    }
+
+    tracing::info!(app = %app.name, holder = %holder, "App locked against deployments");
+
+    audit_log(
+        &state,
+        actions::APP_LOCK,
+        resource_types::APP,
+        Some(&id),
+        Some(&app.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({ "holder": holder, "reason": reason })),
+    )
+    .await;
+
+    Ok(Json(AppLockResponse {
+        app_id: id,
+        locked: true,
+        locked_at: Some(now),
+        locked_by: Some(holder),
+        lock_reason: reason,
+    }))
+}
+
+/// Release an app's deployment lock
+/// POST /api/apps/:id/unlock
+pub async fn unlock_app(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Path(id): Path<String>,
+) -> Result<Json<AppLockResponse>, ApiError> {
+    let app = fetch_app(&state, &id).await?;
+    if app.locked_at.is_none() {
+        return Err(ApiError::conflict("App is not locked"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "UPDATE apps SET locked_at = NULL, locked_by = NULL, lock_reason = NULL, updated_at = ? \
+         WHERE id = ?",
+    )
+    .bind(&now)
+    .bind(&id)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(app = %app.name, user = %user.email, "App deployment lock released");
+
+    audit_log(
+        &state,
+        actions::APP_UNLOCK,
+        resource_types::APP,
+        Some(&id),
+        Some(&app.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "holder": app.locked_by,
+            "reason": app.lock_reason,
+            "locked_at": app.locked_at,
+        })),
+    )
+    .await;
+
+    Ok(Json(AppLockResponse {
+        app_id: id,
+        locked: false,
+        locked_at: None,
+        locked_by: None,
+        lock_reason: None,
+    }))
+}
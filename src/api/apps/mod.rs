@@ -4,20 +4,31 @@ use crate::db::{App, CreateAppRequest, UpdateAppRequest};
 
 use super::error::{ApiError, ValidationErrorBuilder};
 use super::validation::{
-    validate_app_name, validate_base_directory, validate_branch, validate_build_target,
-    validate_build_type, validate_cpu_limit, validate_custom_docker_options,
-    validate_deployment_commands, validate_docker_image, validate_dockerfile, validate_domain,
-    validate_domains, validate_environment, validate_extra_hosts, validate_git_url,
-    validate_healthcheck, validate_memory_limit, validate_network_aliases, validate_port,
-    validate_port_mappings, validate_watch_paths,
+    validate_app_name, validate_app_type, validate_bandwidth_action, validate_bandwidth_limit,
+    validate_bandwidth_throttle, validate_base_directory, validate_branch, validate_build_sandbox,
+    validate_build_target, validate_build_type, validate_cnb_config, validate_compose_file,
+    validate_compose_service, validate_cpu_limit, validate_custom_docker_options,
+    validate_deploy_fences, validate_deployment_commands, validate_docker_image,
+    validate_dockerfile, validate_domain, validate_domains, validate_egress_allowlist,
+    validate_egress_policy, validate_environment, validate_extra_hosts, validate_git_url,
+    validate_healthcheck, validate_healthcheck_type, validate_healthcheck_window,
+    validate_idle_timeout, validate_memory_limit, validate_metrics_path, validate_network_aliases,
+    validate_nixpacks_config, validate_port, validate_port_mappings, validate_preview_database,
+    validate_preview_database_env, validate_preview_ttl, validate_retention_override,
+    validate_smoke_tests, validate_static_config, validate_test_timeout, validate_wait_conditions,
+    validate_watch_paths, validate_webhook_filter,
 };
 
+mod bandwidth;
 mod control;
 mod crud;
+mod dockerfile;
+mod lock;
 mod logs;
 mod sharing;
 mod upload;
 
+pub use bandwidth::get_app_bandwidth;
 pub use control::{
     apply_resource_limits, generate_domain, get_app_activity, get_app_status, restart_app,
     start_app, stop_app,
@@ -25,6 +36,10 @@ pub use control::{
 pub use crud::{
     create_app, delete_app, get_app, get_github_actions_workflow, list_apps, update_app,
 };
+pub use dockerfile::{
+    generate_app_dockerfile, suggest_dockerfile, DockerfileSuggestion, UNSUPPORTED_STACK,
+};
+pub use lock::{check_deploy_lock, lock_app, unlock_app};
 pub use logs::stream_app_logs;
 pub use sharing::{create_app_share, delete_app_share, list_app_shares, list_apps_with_sharing};
 pub use upload::upload_create_app;
@@ -65,8 +80,9 @@ pub struct DeleteAppRequest {
 #[derive(serde::Deserialize)]
 pub struct UploadAppConfig {
     pub name: String,
-    #[serde(default = "default_port")]
-    pub port: u16,
+    /// Container port (defaults to the port the Dockerfile exposes, else 3000)
+    #[serde(default)]
+    pub port: Option<u16>,
     pub domain: Option<String>,
     pub healthcheck: Option<String>,
     #[serde(default = "default_cpu_limit")]
@@ -79,6 +95,12 @@ pub struct UploadAppConfig {
     pub build_type: Option<String>,
     /// Optional publish directory for static sites
     pub publish_directory: Option<String>,
+    /// Optional multi-stage build target (see `build_targets` in the detection result)
+    #[serde(default)]
+    pub build_target: Option<String>,
+    /// "web" (default) or "worker"
+    #[serde(default = "default_app_type")]
+    pub app_type: String,
 }
 
 pub(super) fn default_port() -> u16 {
@@ -97,6 +119,10 @@ pub(super) fn default_environment() -> String {
     "development".to_string()
 }
 
+pub(super) fn default_app_type() -> String {
+    crate::db::APP_TYPE_WEB.to_string()
+}
+
 /// Response for upload app creation
 #[derive(Serialize)]
 pub struct UploadAppResponse {
@@ -168,6 +194,10 @@ pub(super) fn validate_create_request(req: &CreateAppRequest) -> Result<(), ApiE
         errors.add("port", &e);
     }
 
+    if let Err(e) = validate_app_type(&req.app_type) {
+        errors.add("app_type", &e);
+    }
+
     if let Err(e) = validate_healthcheck(&req.healthcheck) {
         errors.add("healthcheck", &e);
     }
@@ -235,6 +265,16 @@ pub(super) fn validate_create_request(req: &CreateAppRequest) -> Result<(), ApiE
         errors.add("build_type", &e);
     }
 
+    if let Some(ref config) = req.nixpacks_config {
+        if let Err(e) = validate_nixpacks_config(config) {
+            errors.add("nixpacks_config", &e);
+        }
+    }
+
+    if let Err(e) = validate_static_config(&req.static_config) {
+        errors.add("static_config", &e);
+    }
+
     errors.finish()
 }
 
@@ -360,6 +400,184 @@ pub(super) fn validate_update_request(req: &UpdateAppRequest) -> Result<(), ApiE
         }
     }
 
+    // Build sandbox (empty string inherits the team setting)
+    if let Some(ref build_sandbox) = req.build_sandbox {
+        if !build_sandbox.is_empty() {
+            if let Err(e) = validate_build_sandbox(build_sandbox) {
+                errors.add("build_sandbox", &e);
+            }
+        }
+    }
+
+    if let Some(ref app_type) = req.app_type {
+        if let Err(e) = validate_app_type(app_type) {
+            errors.add("app_type", &e);
+        }
+    }
+
+    if let Some(window) = req.healthcheck_window_secs {
+        if let Err(e) = validate_healthcheck_window(window) {
+            errors.add("healthcheck_window_secs", &e);
+        }
+    }
+
+    if let Some(ref config) = req.cnb_config {
+        if let Err(e) = validate_cnb_config(config) {
+            errors.add("cnb_config", &e);
+        }
+    }
+
+    if let Some(ref config) = req.nixpacks_config {
+        if let Err(e) = validate_nixpacks_config(config) {
+            errors.add("nixpacks_config", &e);
+        }
+    }
+
+    if let Err(e) = validate_deploy_fences(&req.deploy_fences) {
+        errors.add("deploy_fences", &e);
+    }
+
+    if let Err(e) = validate_static_config(&req.static_config) {
+        errors.add("static_config", &e);
+    }
+
+    if let Err(e) = validate_compose_file(&req.compose_file) {
+        errors.add("compose_file", &e);
+    }
+
+    if let Err(e) = validate_compose_service(&req.compose_service) {
+        errors.add("compose_service", &e);
+    }
+
+    // Webhook filters (empty string clears)
+    for (field, value) in [
+        ("webhook_branch_regex", &req.webhook_branch_regex),
+        ("webhook_ignore_authors", &req.webhook_ignore_authors),
+        ("webhook_ignore_paths", &req.webhook_ignore_paths),
+    ] {
+        if let Some(pattern) = value.as_deref().filter(|p| !p.is_empty()) {
+            if let Err(e) = validate_webhook_filter(pattern) {
+                errors.add(field, &e);
+            }
+        }
+    }
+
+    // Health check probe
+    if let Some(ref healthcheck_type) = req.healthcheck_type {
+        if let Err(e) = validate_healthcheck_type(healthcheck_type) {
+            errors.add("healthcheck_type", &e);
+        }
+    }
+    if let Some(ref command) = req.healthcheck_command {
+        if command.len() > 1024 {
+            errors.add(
+                "healthcheck_command",
+                "Health check command is too long (max 1024 characters)",
+            );
+        }
+    }
+
+    // Network egress policy
+    if let Some(ref egress_policy) = req.egress_policy {
+        if let Err(e) = validate_egress_policy(egress_policy) {
+            errors.add("egress_policy", &e);
+        }
+    }
+    if let Some(ref egress_allowlist) = req.egress_allowlist {
+        if let Err(e) = validate_egress_allowlist(egress_allowlist) {
+            errors.add("egress_allowlist", &e);
+        }
+    }
+
+    if let Some(ref conditions) = req.deploy_wait_conditions {
+        if let Err(e) = validate_wait_conditions(conditions) {
+            errors.add("deploy_wait_conditions", &e);
+        }
+    }
+
+    if let Some(ref tests) = req.smoke_tests {
+        if let Err(e) = validate_smoke_tests(tests) {
+            errors.add("smoke_tests", &e);
+        }
+    }
+
+    // Retention overrides (0/negative clears)
+    for (field, value) in [
+        ("max_deployments", req.max_deployments),
+        ("keep_images", req.keep_images),
+    ] {
+        if let Some(n) = value {
+            if let Err(e) = validate_retention_override(n) {
+                errors.add(field, &e);
+            }
+        }
+    }
+
+    // Test stage
+    if let Err(e) = validate_deployment_commands(&req.test_commands, "test_commands") {
+        errors.add("test_commands", &e);
+    }
+
+    if let Some(secs) = req.test_timeout_secs.filter(|s| *s > 0) {
+        if let Err(e) = validate_test_timeout(secs) {
+            errors.add("test_timeout_secs", &e);
+        }
+    }
+
+    // Preview expiry (0/negative clears)
+    for (field, value) in [
+        ("preview_max_lifetime_hours", req.preview_max_lifetime_hours),
+        ("preview_idle_timeout_hours", req.preview_idle_timeout_hours),
+    ] {
+        if let Some(hours) = value.filter(|h| *h > 0) {
+            if let Err(e) = validate_preview_ttl(hours) {
+                errors.add(field, &e);
+            }
+        }
+    }
+
+    // Scale-to-zero (0/negative keeps the app always running)
+    if let Some(minutes) = req.idle_timeout_minutes.filter(|m| *m > 0) {
+        if let Err(e) = validate_idle_timeout(minutes) {
+            errors.add("idle_timeout_minutes", &e);
+        }
+    }
+
+    // Bandwidth cap (0/negative removes it)
+    if let Some(gb) = req.bandwidth_limit_gb.filter(|g| *g > 0) {
+        if let Err(e) = validate_bandwidth_limit(gb) {
+            errors.add("bandwidth_limit_gb", &e);
+        }
+    }
+    if let Some(ref action) = req.bandwidth_limit_action {
+        if let Err(e) = validate_bandwidth_action(action) {
+            errors.add("bandwidth_limit_action", &e);
+        }
+    }
+    if let Some(kbps) = req.bandwidth_throttle_kbps.filter(|k| *k > 0) {
+        if let Err(e) = validate_bandwidth_throttle(kbps) {
+            errors.add("bandwidth_throttle_kbps", &e);
+        }
+    }
+
+    // Prometheus metrics endpoint (0/negative port stops scraping)
+    if let Some(port) = req.metrics_port.filter(|p| *p > 0) {
+        if let Err(e) = validate_port(port) {
+            errors.add("metrics_port", &e);
+        }
+    }
+    if let Err(e) = validate_metrics_path(&req.metrics_path) {
+        errors.add("metrics_path", &e);
+    }
+
+    // Preview databases
+    if let Err(e) = validate_preview_database(&req.preview_database) {
+        errors.add("preview_database", &e);
+    }
+    if let Err(e) = validate_preview_database_env(&req.preview_database_env) {
+        errors.add("preview_database_env", &e);
+    }
+
     errors.finish()
 }
 
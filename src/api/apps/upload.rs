@@ -13,8 +13,10 @@ use crate::AppState;
 use super::super::audit::{audit_log, ClientIp};
 use super::super::error::ApiError;
 use super::super::teams::log_team_audit;
-use super::super::validation::{validate_app_name, validate_uuid};
-use super::{UploadAppConfig, UploadAppResponse};
+use super::super::validation::{
+    validate_app_name, validate_app_type, validate_build_target, validate_uuid,
+};
+use super::{default_port, UploadAppConfig, UploadAppResponse};
 
 /// Create an app and deploy from uploaded ZIP file
 /// POST /api/projects/:project_id/apps/upload
@@ -85,6 +87,12 @@ pub async fn upload_create_app(
     if let Err(e) = validate_app_name(&config.name) {
         return Err(ApiError::validation_field("name", e));
     }
+    if let Err(e) = validate_build_target(&config.build_target) {
+        return Err(ApiError::validation_field("build_target", e));
+    }
+    if let Err(e) = validate_app_type(&config.app_type) {
+        return Err(ApiError::validation_field("app_type", e));
+    }
 
     // Create a unique deployment ID for the temp directory
     let deployment_id = Uuid::new_v4().to_string();
@@ -115,6 +123,18 @@ pub async fn upload_create_app(
         .publish_directory
         .clone()
         .or_else(|| detected.publish_directory.clone());
+    // Fall back to what the Dockerfile declares before the generic defaults
+    let port = config
+        .port
+        .or(detected.suggested_port)
+        .unwrap_or_else(default_port);
+    let healthcheck = config.healthcheck.clone().or_else(|| {
+        detected
+            .healthcheck
+            .as_ref()
+            .and_then(|hint| hint.path.clone())
+    });
+    let build_target = config.build_target.clone().filter(|t| !t.is_empty());
 
     // Clone detected for audit log before moving
     let detected_from_log = detected.detected_from.clone();
@@ -128,21 +148,23 @@ pub async fn upload_create_app(
         INSERT INTO apps (
             id, name, git_url, branch, dockerfile, domain, port, healthcheck,
             memory_limit, cpu_limit, environment, project_id, build_type,
-            publish_directory, deployment_source, created_at, updated_at
-        ) VALUES (?, ?, '', 'main', 'Dockerfile', ?, ?, ?, ?, ?, ?, ?, ?, ?, 'upload', ?, ?)
+            publish_directory, build_target, app_type, deployment_source, created_at, updated_at
+        ) VALUES (?, ?, '', 'main', 'Dockerfile', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'upload', ?, ?)
         "#,
     )
     .bind(&app_id)
     .bind(&config.name)
     .bind(&config.domain)
-    .bind(config.port as i32)
-    .bind(&config.healthcheck)
+    .bind(port as i32)
+    .bind(&healthcheck)
     .bind(&config.memory_limit)
     .bind(&config.cpu_limit)
     .bind(&config.environment)
     .bind(&project_id)
     .bind(&build_type)
     .bind(&publish_directory)
+    .bind(&build_target)
+    .bind(&config.app_type)
     .bind(&now)
     .bind(&now)
     .execute(&state.db)
@@ -1,7 +1,8 @@
 //! HTTP Basic Auth API endpoints for applications.
 //!
 //! This module provides endpoints to manage HTTP Basic Auth settings
-//! for protecting applications behind the proxy.
+//! for protecting applications behind the proxy, either app-wide or
+//! for individual path prefixes with their own credentials.
 
 use axum::{
     extract::{Path, State},
@@ -10,11 +11,16 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use uuid::Uuid;
 
 use crate::api::auth::hash_password;
-use crate::db::App;
+use crate::db::{
+    normalize_path_prefix, App, AppBasicAuthPath, CreateBasicAuthPathRequest,
+    UpdateBasicAuthPathRequest,
+};
 use crate::AppState;
 
+use super::common::{ensure_app_exists, refresh_proxy_routes};
 use super::error::ApiError;
 use super::validation::validate_uuid;
 
@@ -76,51 +82,9 @@ pub async fn update_basic_auth(
 
     // Validate request
     if req.enabled {
-        // Username is required when enabling basic auth
-        let username = req.username.as_deref().unwrap_or("");
-        if username.is_empty() {
-            return Err(ApiError::validation_field(
-                "username",
-                "Username is required when enabling basic auth".to_string(),
-            ));
-        }
-        if username.len() < 3 {
-            return Err(ApiError::validation_field(
-                "username",
-                "Username must be at least 3 characters".to_string(),
-            ));
-        }
-        if username.len() > 64 {
-            return Err(ApiError::validation_field(
-                "username",
-                "Username must be at most 64 characters".to_string(),
-            ));
-        }
-        // Check for valid username characters (alphanumeric, underscore, dash)
-        if !username
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-        {
-            return Err(ApiError::validation_field(
-                "username",
-                "Username can only contain letters, numbers, underscores, and dashes".to_string(),
-            ));
-        }
-
-        // Password is required when enabling or changing username
-        let password = req.password.as_deref().unwrap_or("");
-        if password.is_empty() {
-            return Err(ApiError::validation_field(
-                "password",
-                "Password is required when enabling basic auth".to_string(),
-            ));
-        }
-        if password.len() < 8 {
-            return Err(ApiError::validation_field(
-                "password",
-                "Password must be at least 8 characters".to_string(),
-            ));
-        }
+        // Username and password are required when enabling basic auth
+        validate_username(req.username.as_deref().unwrap_or(""))?;
+        validate_password(req.password.as_deref().unwrap_or(""))?;
     }
 
     let now = chrono::Utc::now().to_rfc3339();
@@ -211,3 +175,296 @@ pub async fn delete_basic_auth(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// List path-scoped basic auth rules for an app
+pub async fn list_basic_auth_paths(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<AppBasicAuthPath>>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    ensure_app_exists(&state, &app_id).await?;
+
+    let rules = sqlx::query_as::<_, AppBasicAuthPath>(
+        "SELECT * FROM app_basic_auth_paths WHERE app_id = ? ORDER BY path_prefix ASC",
+    )
+    .bind(&app_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(rules))
+}
+
+/// Protect a path prefix of an app with its own basic auth credentials
+pub async fn create_basic_auth_path(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<CreateBasicAuthPathRequest>,
+) -> Result<(StatusCode, Json<AppBasicAuthPath>), ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    let path_prefix = validate_path_prefix(&req.path_prefix)?;
+    validate_username(&req.username)?;
+    validate_password(&req.password)?;
+    ensure_app_exists(&state, &app_id).await?;
+    ensure_prefix_available(&state, &app_id, &path_prefix, None).await?;
+
+    let password_hash = hash_password(&req.password)
+        .map_err(|e| ApiError::internal(format!("Failed to hash password: {}", e)))?;
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_basic_auth_paths
+            (id, app_id, path_prefix, username, password_hash, is_enabled, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&app_id)
+    .bind(&path_prefix)
+    .bind(&req.username)
+    .bind(&password_hash)
+    .bind(req.is_enabled)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create basic auth path rule: {}", e);
+        ApiError::database("Failed to create basic auth path rule")
+    })?;
+
+    let rule =
+        sqlx::query_as::<_, AppBasicAuthPath>("SELECT * FROM app_basic_auth_paths WHERE id = ?")
+            .bind(&id)
+            .fetch_one(&state.db)
+            .await?;
+
+    apply_basic_auth_paths(&state, &app_id).await;
+
+    tracing::info!(app_id = %app_id, path_prefix = %path_prefix, "Added path-scoped basic auth");
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Update a path-scoped basic auth rule
+pub async fn update_basic_auth_path(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, rule_id)): Path<(String, String)>,
+    Json(req): Json<UpdateBasicAuthPathRequest>,
+) -> Result<Json<AppBasicAuthPath>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    if let Err(e) = validate_uuid(&rule_id, "rule_id") {
+        return Err(ApiError::validation_field("rule_id", e));
+    }
+
+    let existing = sqlx::query_as::<_, AppBasicAuthPath>(
+        "SELECT * FROM app_basic_auth_paths WHERE id = ? AND app_id = ?",
+    )
+    .bind(&rule_id)
+    .bind(&app_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Basic auth path rule not found"))?;
+
+    let path_prefix = match &req.path_prefix {
+        Some(p) => {
+            let prefix = validate_path_prefix(p)?;
+            ensure_prefix_available(&state, &app_id, &prefix, Some(&rule_id)).await?;
+            prefix
+        }
+        None => existing.path_prefix,
+    };
+    let username = match &req.username {
+        Some(u) => {
+            validate_username(u)?;
+            u.clone()
+        }
+        None => existing.username,
+    };
+    let password_hash = match &req.password {
+        Some(p) => {
+            validate_password(p)?;
+            hash_password(p)
+                .map_err(|e| ApiError::internal(format!("Failed to hash password: {}", e)))?
+        }
+        None => existing.password_hash,
+    };
+    let is_enabled = req.is_enabled.unwrap_or(existing.is_enabled != 0);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        UPDATE app_basic_auth_paths SET
+            path_prefix = ?,
+            username = ?,
+            password_hash = ?,
+            is_enabled = ?,
+            updated_at = ?
+        WHERE id = ? AND app_id = ?
+        "#,
+    )
+    .bind(&path_prefix)
+    .bind(&username)
+    .bind(&password_hash)
+    .bind(is_enabled)
+    .bind(&now)
+    .bind(&rule_id)
+    .bind(&app_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update basic auth path rule: {}", e);
+        ApiError::database("Failed to update basic auth path rule")
+    })?;
+
+    let updated =
+        sqlx::query_as::<_, AppBasicAuthPath>("SELECT * FROM app_basic_auth_paths WHERE id = ?")
+            .bind(&rule_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    apply_basic_auth_paths(&state, &app_id).await;
+
+    Ok(Json(updated))
+}
+
+/// Remove a path-scoped basic auth rule
+pub async fn delete_basic_auth_path(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, rule_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    if let Err(e) = validate_uuid(&rule_id, "rule_id") {
+        return Err(ApiError::validation_field("rule_id", e));
+    }
+
+    let result = sqlx::query("DELETE FROM app_basic_auth_paths WHERE id = ? AND app_id = ?")
+        .bind(&rule_id)
+        .bind(&app_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Basic auth path rule not found"));
+    }
+
+    apply_basic_auth_paths(&state, &app_id).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---- Helpers ----
+
+pub(crate) fn validate_username(username: &str) -> Result<(), ApiError> {
+    if username.is_empty() {
+        return Err(ApiError::validation_field(
+            "username",
+            "Username is required when enabling basic auth".to_string(),
+        ));
+    }
+    if username.len() < 3 {
+        return Err(ApiError::validation_field(
+            "username",
+            "Username must be at least 3 characters".to_string(),
+        ));
+    }
+    if username.len() > 64 {
+        return Err(ApiError::validation_field(
+            "username",
+            "Username must be at most 64 characters".to_string(),
+        ));
+    }
+    // Check for valid username characters (alphanumeric, underscore, dash)
+    if !username
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(ApiError::validation_field(
+            "username",
+            "Username can only contain letters, numbers, underscores, and dashes".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_password(password: &str) -> Result<(), ApiError> {
+    if password.is_empty() {
+        return Err(ApiError::validation_field(
+            "password",
+            "Password is required when enabling basic auth".to_string(),
+        ));
+    }
+    if password.len() < 8 {
+        return Err(ApiError::validation_field(
+            "password",
+            "Password must be at least 8 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_path_prefix(prefix: &str) -> Result<String, ApiError> {
+    normalize_path_prefix(prefix).ok_or_else(|| {
+        ApiError::validation_field(
+            "path_prefix",
+            "Path prefix must be a URL path such as /admin".to_string(),
+        )
+    })
+}
+
+/// Reject a prefix already protected by another rule of the same app.
+async fn ensure_prefix_available(
+    state: &Arc<AppState>,
+    app_id: &str,
+    path_prefix: &str,
+    exclude_rule_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let taken: Option<(String,)> = sqlx::query_as(
+        "SELECT id FROM app_basic_auth_paths WHERE app_id = ? AND path_prefix = ? AND id != ?",
+    )
+    .bind(app_id)
+    .bind(path_prefix)
+    .bind(exclude_rule_id.unwrap_or(""))
+    .fetch_optional(&state.db)
+    .await?;
+    if taken.is_some() {
+        return Err(ApiError::conflict(format!(
+            "Path prefix {} is already protected",
+            path_prefix
+        )));
+    }
+    Ok(())
+}
+
+/// Reload the path rules for an app into its live proxy backend entries.
+async fn apply_basic_auth_paths(state: &Arc<AppState>, app_id: &str) {
+    let proxy_rules = match AppBasicAuthPath::list_enabled_for_proxy(&state.db, app_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(app_id = %app_id, error = %e, "Failed to load basic auth path rules for proxy refresh");
+            return;
+        }
+    };
+
+    let domains = refresh_proxy_routes(state, app_id, false, |routes, domain, _| {
+        routes.update_basic_auth_paths(domain, proxy_rules.clone());
+    })
+    .await;
+
+    tracing::info!(
+        app_id = %app_id,
+        domains = ?domains,
+        rules_count = proxy_rules.len(),
+        "Basic auth path rules refreshed in proxy route table"
+    );
+}
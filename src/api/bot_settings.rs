@@ -0,0 +1,117 @@
+//! Bot/crawler management API endpoints for applications.
+//!
+//! Controls whether the proxy serves a deny-all robots.txt for an app (always,
+//! or automatically for previews and non-production environments) and whether
+//! known bad bots are challenged before reaching the app.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::db::{AppBotSettings, UpdateBotSettingsRequest, ROBOTS_MODES};
+use crate::AppState;
+
+use super::common::{ensure_app_exists, refresh_proxy_routes};
+use super::error::ApiError;
+
+/// Get bot settings for an app
+pub async fn get_bot_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<AppBotSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let settings = AppBotSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppBotSettings::default_for(&app_id));
+
+    Ok(Json(settings))
+}
+
+/// Update bot settings for an app
+pub async fn update_bot_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdateBotSettingsRequest>,
+) -> Result<Json<AppBotSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let existing = AppBotSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppBotSettings::default_for(&app_id));
+
+    let robots_mode = req.robots_mode.clone().unwrap_or(existing.robots_mode);
+    if !ROBOTS_MODES.contains(&robots_mode.as_str()) {
+        return Err(ApiError::validation_field(
+            "robots_mode",
+            format!("Robots mode must be one of: {}", ROBOTS_MODES.join(", ")),
+        ));
+    }
+    let challenge_bad_bots = req
+        .challenge_bad_bots
+        .unwrap_or(existing.challenge_bad_bots != 0);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_bot_settings (app_id, robots_mode, challenge_bad_bots, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(app_id) DO UPDATE SET
+            robots_mode = excluded.robots_mode,
+            challenge_bad_bots = excluded.challenge_bad_bots,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(&robots_mode)
+    .bind(challenge_bad_bots)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update bot settings: {}", e);
+        ApiError::database("Failed to update bot settings")
+    })?;
+
+    let settings = AppBotSettings::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Bot settings missing after update"))?;
+
+    apply_bot_settings(&state, &settings).await;
+
+    Ok(Json(settings))
+}
+
+/// Apply bot settings to the app's live routes and its running previews.
+async fn apply_bot_settings(state: &Arc<AppState>, settings: &AppBotSettings) {
+    let environment: String = sqlx::query_scalar("SELECT environment FROM apps WHERE id = ?")
+        .bind(&settings.app_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "production".to_string());
+
+    let app_policy = settings.to_policy(&environment, false);
+    let preview_policy = settings.to_policy(&environment, true);
+    let domains = refresh_proxy_routes(state, &settings.app_id, true, |routes, domain, preview| {
+        let policy = if preview {
+            &preview_policy
+        } else {
+            &app_policy
+        };
+        routes.update_bots(domain, policy.clone());
+    })
+    .await;
+
+    tracing::info!(
+        app_id = %settings.app_id,
+        domains = ?domains,
+        robots_mode = %settings.robots_mode,
+        challenge_bad_bots = settings.challenge_bad_bots != 0,
+        "Bot settings refreshed in proxy route table"
+    );
+}
@@ -29,8 +29,9 @@ use crate::db::{
 use crate::AppState;
 
 use super::audit::{audit_log, ClientIp};
+use super::authz::authorize_project;
 use super::error::ApiError;
-use super::validation::validate_uuid;
+use super::validation::{validate_app_name, validate_uuid};
 
 // -------------------------------------------------------------------------
 // Request / Response types
@@ -57,10 +58,23 @@ pub struct BulkOperationResponse {
 }
 
 /// Body for cloning an app
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct CloneAppRequest {
     /// Name for the cloned app (defaults to "{original}-copy")
     pub name: Option<String>,
+    /// Project to create the clone in (defaults to the original's)
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// Environment to create the clone in (defaults to the original's, or the
+    /// target project's default environment)
+    #[serde(default)]
+    pub environment_id: Option<String>,
+    /// Give the clone a freshly generated domain (otherwise it has none)
+    #[serde(default)]
+    pub generate_domain: bool,
+    /// Copy the files in the original's volumes (otherwise they start empty)
+    #[serde(default)]
+    pub copy_volumes: bool,
 }
 
 /// Body for maintenance mode toggle
@@ -171,6 +185,7 @@ async fn start_container(state: &Arc<AppState>, app: &App) -> Result<(), String>
                         port,
                     )
                     .with_healthcheck(app.healthcheck.clone())
+                    .with_health_probe(app.health_probe())
                     .with_strip_prefix(app.strip_prefix.clone());
                     state.routes.load().add_route(domain.clone(), backend);
                 }
@@ -244,6 +259,7 @@ async fn restart_container(state: &Arc<AppState>, app: &App) -> Result<(), Strin
                         port,
                     )
                     .with_healthcheck(app.healthcheck.clone())
+                    .with_health_probe(app.health_probe())
                     .with_strip_prefix(app.strip_prefix.clone());
                     state.routes.load().add_route(domain.clone(), backend);
                 }
@@ -256,6 +272,10 @@ async fn restart_container(state: &Arc<AppState>, app: &App) -> Result<(), Strin
 
 /// Internal deploy trigger logic.
 async fn trigger_deploy_for_app(state: &Arc<AppState>, app: &App) -> Result<(), String> {
+    if let Some(message) = app.deploy_lock_message() {
+        return Err(message);
+    }
+
     // Skip if a deployment is already in progress
     let in_progress: Option<(String,)> = sqlx::query_as(
         "SELECT id FROM deployments WHERE app_id = ? AND status IN ('pending', 'cloning', 'building', 'starting', 'checking') LIMIT 1"
@@ -511,8 +531,119 @@ pub async fn bulk_deploy(
 #[derive(Debug, Serialize)]
 pub struct CloneAppResponse {
     pub app: crate::db::AppResponse,
+    /// Volumes whose files could not be copied (Docker named volumes, or the
+    /// copy failed); they start empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub volumes_not_copied: Vec<String>,
+}
+
+/// App columns set explicitly on a clone rather than copied
+const CLONE_SET_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "domains",
+    "auto_subdomain",
+    "project_id",
+    "environment_id",
+    "team_id",
+    "created_at",
+    "updated_at",
+];
+
+/// App columns a clone leaves at their defaults: routing and runtime state
+/// that belongs to the original
+const CLONE_RESET_COLUMNS: &[&str] = &[
+    "domain",
+    "custom_container_name",
+    // Host ports can only be bound by one container
+    "port_mappings",
+    "sleeping_since",
+    "bandwidth_exceeded_period",
+    "last_crash_notified_at",
+    "locked_at",
+    "locked_by",
+    "lock_reason",
+    "maintenance_mode",
+    "maintenance_message",
+];
+
+/// Resolve the (project, environment, team) a clone is created in
+async fn clone_target(
+    state: &Arc<AppState>,
+    user: &User,
+    original: &App,
+    req: &CloneAppRequest,
+) -> Result<(Option<String>, Option<String>, Option<String>), ApiError> {
+    let mut project_id = req.project_id.clone().filter(|p| !p.is_empty());
+    let environment_id = req.environment_id.clone().filter(|e| !e.is_empty());
+
+    if let Some(ref env_id) = environment_id {
+        if let Err(e) = validate_uuid(env_id, "environment_id") {
+            return Err(ApiError::validation_field("environment_id", e));
+        }
+        let env_project: String =
+            sqlx::query_scalar("SELECT project_id FROM environments WHERE id = ?")
+                .bind(env_id)
+                .fetch_optional(&state.db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Environment not found"))?;
+        if project_id.as_ref().is_some_and(|p| *p != env_project) {
+            return Err(ApiError::validation_field(
+                "environment_id",
+                "Environment does not belong to the target project",
+            ));
+        }
+        project_id = Some(env_project);
+    }
+
+    let Some(project_id) = project_id else {
+        return Ok((
+            original.project_id.clone(),
+            original.environment_id.clone(),
+            original.team_id.clone(),
+        ));
+    };
+    if let Err(e) = validate_uuid(&project_id, "project_id") {
+        return Err(ApiError::validation_field("project_id", e));
+    }
+    let project = authorize_project(state, user, &project_id).await?;
+
+    let environment_id = match environment_id {
+        Some(env_id) => Some(env_id),
+        None if original.project_id.as_deref() == Some(project_id.as_str()) => {
+            original.environment_id.clone()
+        }
+        None => {
+            sqlx::query_scalar(
+                "SELECT id FROM environments WHERE project_id = ? \
+                 ORDER BY is_default DESC, created_at ASC LIMIT 1",
+            )
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?
+        }
+    };
+    let team_id = if original.project_id.as_deref() == Some(project_id.as_str()) {
+        original.team_id.clone()
+    } else {
+        project.team_id
+    };
+    Ok((Some(project_id), environment_id, team_id))
+}
+
+/// Host path for a clone's copy of a volume, next to the original's
+fn cloned_volume_path(host_path: &str, new_app_id: &str) -> String {
+    format!(
+        "{}-{}",
+        host_path.trim_end_matches('/'),
+        &new_app_id[..8.min(new_app_id.len())]
+    )
 }
 
+/// Duplicate an app's configuration, env vars and volumes into a new app.
+/// Domains are not copied; the clone gets none or a generated one, and its
+/// volumes get their own host paths.
+/// POST /api/apps/:id/clone
 pub async fn clone_app(
     State(state): State<Arc<AppState>>,
     user: User,
@@ -523,111 +654,109 @@ pub async fn clone_app(
     if let Err(e) = validate_uuid(&id, "app_id") {
         return Err(ApiError::validation_field("app_id", e));
     }
+    let req = body.map(|Json(r)| r).unwrap_or_default();
 
     let original = get_app(&state, &id).await?;
 
-    let clone_name = body
-        .as_ref()
-        .and_then(|b| b.name.as_ref())
-        .cloned()
+    let clone_name = req
+        .name
+        .clone()
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
         .unwrap_or_else(|| format!("{}-copy", original.name));
+    if let Err(e) = validate_app_name(&clone_name) {
+        return Err(ApiError::validation_field("name", e));
+    }
+
+    let (project_id, environment_id, team_id) =
+        clone_target(&state, &user, &original, &req).await?;
+
+    // Workers aren't routed by the proxy, so they never get a domain
+    let auto_subdomain = if !req.generate_domain || original.is_worker() {
+        None
+    } else if state.config.proxy.sslip_enabled {
+        state.config.proxy.generate_sslip_domain(None)
+    } else {
+        state.config.proxy.generate_auto_domain(&clone_name)
+    };
+    if req.generate_domain && !original.is_worker() && auto_subdomain.is_none() {
+        return Err(ApiError::bad_request(
+            "No domain generation strategy configured. Set base_domain or server_ip in proxy config.",
+        ));
+    }
 
-    // Build the cloned app row
     let new_id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
-    sqlx::query(
-        r#"
-        INSERT INTO apps (
-            id, name, git_url, branch, dockerfile, domain, port, healthcheck,
-            memory_limit, cpu_limit, ssh_key_id, environment, project_id, environment_id, team_id,
-            dockerfile_path, base_directory, build_target, watch_paths, custom_docker_options,
-            port_mappings, network_aliases, extra_hosts,
-            basic_auth_enabled, basic_auth_username, basic_auth_password_hash,
-            pre_deploy_commands, post_deploy_commands,
-            domains, auto_subdomain,
-            docker_image, docker_image_tag, registry_url, registry_username, registry_password,
-            container_labels, build_type, nixpacks_config, publish_directory,
-            preview_enabled, github_app_installation_id, deployment_source,
-            auto_rollback_enabled, registry_push_enabled, max_rollback_versions,
-            require_approval, maintenance_mode, maintenance_message,
-            created_at, updated_at
-        ) VALUES (
-            ?, ?, ?, ?, ?, NULL, ?, ?,
-            ?, ?, ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?,
-            ?, ?, ?,
-            0, NULL, NULL,
-            ?, ?,
-            ?, NULL,
-            ?, ?, ?, ?, NULL,
-            ?, ?, ?, ?,
-            ?, ?, ?,
-            ?, ?, ?,
-            0, 0, NULL,
-            ?, ?
-        )
-        "#,
-    )
-    .bind(&new_id)
-    .bind(&clone_name)
-    .bind(&original.git_url)
-    .bind(&original.branch)
-    .bind(&original.dockerfile)
-    // domain set to NULL (no domain for clone)
-    .bind(original.port)
-    .bind(&original.healthcheck)
-    .bind(&original.memory_limit)
-    .bind(&original.cpu_limit)
-    .bind(&original.ssh_key_id)
-    .bind(&original.environment)
-    .bind(&original.project_id)
-    .bind(&original.environment_id)
-    .bind(&original.team_id)
-    .bind(&original.dockerfile_path)
-    .bind(&original.base_directory)
-    .bind(&original.build_target)
-    .bind(&original.watch_paths)
-    .bind(&original.custom_docker_options)
-    .bind(&original.port_mappings)
-    .bind(&original.network_aliases)
-    .bind(&original.extra_hosts)
-    // basic_auth reset to disabled
-    .bind(&original.pre_deploy_commands)
-    .bind(&original.post_deploy_commands)
-    .bind(&original.domains)
-    // auto_subdomain reset to NULL
-    .bind(&original.docker_image)
-    .bind(&original.docker_image_tag)
-    .bind(&original.registry_url)
-    .bind(&original.registry_username)
-    // registry_password reset to NULL
-    .bind(&original.container_labels)
-    .bind(&original.build_type)
-    .bind(&original.nixpacks_config)
-    .bind(&original.publish_directory)
-    .bind(original.preview_enabled)
-    .bind(&original.github_app_installation_id)
-    .bind(&original.deployment_source)
-    .bind(original.auto_rollback_enabled)
-    .bind(original.registry_push_enabled)
-    .bind(original.max_rollback_versions)
-    // require_approval, maintenance_mode, maintenance_message reset to defaults
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+    // Copy every column of the original, so settings added later are cloned
+    // too, except the ones set here or reset to their defaults
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info('apps')")
+        .fetch_all(&state.db)
+        .await?;
+    let copied: Vec<&String> = columns
+        .iter()
+        .filter(|c| {
+            !CLONE_SET_COLUMNS.contains(&c.as_str()) && !CLONE_RESET_COLUMNS.contains(&c.as_str())
+        })
+        .collect();
+    let column_list = CLONE_SET_COLUMNS
+        .iter()
+        .map(|c| c.to_string())
+        .chain(copied.iter().map(|c| format!("\"{}\"", c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let select_list = CLONE_SET_COLUMNS
+        .iter()
+        .map(|_| "?".to_string())
+        .chain(copied.iter().map(|c| format!("\"{}\"", c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "INSERT INTO apps ({}) SELECT {} FROM apps WHERE id = ?",
+        column_list, select_list
+    );
 
-    // Copy env vars (excluding secret values — clone gets the key structure but masked secrets)
     let env_vars: Vec<EnvVar> =
         sqlx::query_as("SELECT * FROM env_vars WHERE app_id = ? ORDER BY key")
             .bind(&id)
             .fetch_all(&state.db)
             .await?;
+    let volumes: Vec<Volume> =
+        sqlx::query_as("SELECT * FROM volumes WHERE app_id = ? ORDER BY name")
+            .bind(&id)
+            .fetch_all(&state.db)
+            .await?;
+
+    // Create the app with its env vars and volumes atomically, so a failed
+    // clone never leaves a half-configured app behind
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query(&sql)
+        .bind(&new_id)
+        .bind(&clone_name)
+        // domains: the original's would conflict
+        .bind(None::<String>)
+        .bind(&auto_subdomain)
+        .bind(&project_id)
+        .bind(&environment_id)
+        .bind(&team_id)
+        .bind(&now)
+        .bind(&now)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to clone app: {}", e);
+            if e.to_string().contains("UNIQUE constraint failed") {
+                ApiError::conflict("An app with this name already exists")
+            } else {
+                ApiError::database("Failed to clone app")
+            }
+        })?;
 
+    // Copy env vars (secret values included; it's the operator's responsibility)
     for ev in &env_vars {
         let env_id = Uuid::new_v4().to_string();
-        // For secrets, we copy the raw value (it's the operator's responsibility)
         sqlx::query(
             "INSERT INTO env_vars (id, app_id, key, value, is_secret, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
         )
@@ -638,34 +767,53 @@ pub async fn clone_app(
         .bind(ev.is_secret)
         .bind(&now)
         .bind(&now)
-        .execute(&state.db)
+        .execute(&mut *tx)
         .await?;
     }
 
-    // Copy volumes
-    let volumes: Vec<Volume> =
-        sqlx::query_as("SELECT * FROM volumes WHERE app_id = ? ORDER BY name")
-            .bind(&id)
-            .fetch_all(&state.db)
-            .await?;
-
+    // Copy volumes to their own host paths so the clone never writes to the
+    // original's data
     for vol in &volumes {
         let vol_id = Uuid::new_v4().to_string();
+        let host_path = cloned_volume_path(&vol.host_path, &new_id);
         sqlx::query(
             "INSERT INTO volumes (id, app_id, name, host_path, container_path, read_only, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&vol_id)
         .bind(&new_id)
         .bind(&vol.name)
-        .bind(&vol.host_path)
+        .bind(&host_path)
         .bind(&vol.container_path)
         .bind(vol.read_only)
         .bind(&now)
         .bind(&now)
-        .execute(&state.db)
+        .execute(&mut *tx)
         .await?;
     }
 
+    tx.commit().await?;
+
+    // Copy volume files only once the clone exists
+    let mut volumes_not_copied = Vec::new();
+    let to_copy: &[Volume] = if req.copy_volumes { &volumes } else { &[] };
+    for vol in to_copy {
+        let host_path = cloned_volume_path(&vol.host_path, &new_id);
+        let source = std::path::PathBuf::from(&vol.host_path);
+        if !source.is_dir() {
+            // A Docker named volume, or nothing written yet
+            volumes_not_copied.push(vol.name.clone());
+            continue;
+        }
+        let target = std::path::PathBuf::from(&host_path);
+        let result =
+            tokio::task::spawn_blocking(move || crate::backup::copy_dir_all(&source, &target))
+                .await;
+        if !matches!(result, Ok(Ok(()))) {
+            tracing::warn!(volume = %vol.name, "Failed to copy volume files into cloned app");
+            volumes_not_copied.push(vol.name.clone());
+        }
+    }
+
     let cloned_app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
         .bind(&new_id)
         .fetch_one(&state.db)
@@ -679,7 +827,12 @@ pub async fn clone_app(
         Some(&clone_name),
         Some(&user.id),
         client_ip.as_deref(),
-        Some(serde_json::json!({ "cloned_from": id })),
+        Some(serde_json::json!({
+            "cloned_from": id,
+            "project_id": project_id,
+            "environment_id": environment_id,
+            "copy_volumes": req.copy_volumes,
+        })),
     )
     .await;
 
@@ -687,6 +840,7 @@ pub async fn clone_app(
         StatusCode::CREATED,
         Json(CloneAppResponse {
             app: cloned_app.into(),
+            volumes_not_copied,
         }),
     ))
 }
@@ -1181,3 +1335,65 @@ pub async fn set_maintenance_mode(
         maintenance_message: req.message,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloned_volume_path() {
+        let new_id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(
+            cloned_volume_path("/var/lib/rivetr/data/", new_id),
+            "/var/lib/rivetr/data-0f8fad5b"
+        );
+        assert_eq!(cloned_volume_path("pgdata", new_id), "pgdata-0f8fad5b");
+    }
+
+    #[tokio::test]
+    async fn test_clone_copies_settings_but_not_host_ports() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = crate::db::test_app(&state.db, "web").await;
+        let admin = crate::db::test_user(&state.db, "admin").await;
+        sqlx::query("UPDATE apps SET port_mappings = ?, memory_limit = '512m' WHERE id = ?")
+            .bind(r#"[{"host_port":8080,"container_port":80,"protocol":"tcp"}]"#)
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO env_vars (id, app_id, key, value) VALUES ('e1', ?, 'KEY', 'v')")
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        let (status, Json(cloned)) = clone_app(
+            State(state.clone()),
+            admin,
+            ClientIp(None),
+            Path(app_id),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let clone = crate::db::load_test_app(&state.db, &cloned.app.id).await;
+        assert_eq!(clone.name, "web-copy");
+        assert_eq!(clone.memory_limit.as_deref(), Some("512m"));
+        assert!(clone.port_mappings.is_none());
+        let keys: Vec<String> = sqlx::query_scalar("SELECT key FROM env_vars WHERE app_id = ?")
+            .bind(&clone.id)
+            .fetch_all(&state.db)
+            .await
+            .unwrap();
+        assert_eq!(keys, vec!["KEY"]);
+    }
+
+    #[test]
+    fn test_clone_columns_are_disjoint() {
+        assert!(CLONE_SET_COLUMNS
+            .iter()
+            .all(|c| !CLONE_RESET_COLUMNS.contains(c)));
+    }
+}
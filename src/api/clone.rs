@@ -15,6 +15,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::{App, ManagedDatabase, ProjectEnvironment, Service};
+use crate::engine::database_seed;
 use crate::AppState;
 
 use super::error::ApiError;
@@ -104,8 +105,8 @@ pub async fn clone_environment(
     // -----------------------------------------------------------------------
     sqlx::query(
         r#"
-        INSERT INTO environments (id, project_id, name, description, is_default, created_at, updated_at)
-        VALUES (?, ?, ?, ?, 0, ?, ?)
+        INSERT INTO environments (id, project_id, name, description, is_default, created_at, updated_at, require_approval)
+        VALUES (?, ?, ?, ?, 0, ?, ?, ?)
         "#,
     )
     .bind(&new_env_id)
@@ -114,6 +115,7 @@ pub async fn clone_environment(
     .bind(&source_env.description)
     .bind(&now)
     .bind(&now)
+    .bind(source_env.require_approval)
     .execute(&state.db)
     .await
     .map_err(|e| {
@@ -383,14 +385,36 @@ pub async fn clone_environment(
                 .map(|parent| parent.join(&volume_dir).to_string_lossy().to_string())
         });
 
+        // The clone starts unseeded and gets the seed only when the source
+        // opted in with seed_on_clone
+        let seed_on_clone = db.seed_on_clone != 0;
+        let seed_script = db.seed_script.clone().filter(|_| seed_on_clone);
+        let mut seed_dump_file = None;
+        if let Some(ref file_name) = db.seed_dump_file.clone().filter(|_| seed_on_clone) {
+            let data_dir = &state.config.server.data_dir;
+            let target_dir = database_seed::seed_dir(data_dir, &new_db_id);
+            let copy = async {
+                tokio::fs::create_dir_all(&target_dir).await?;
+                tokio::fs::copy(
+                    database_seed::seed_dir(data_dir, &db.id).join(file_name),
+                    target_dir.join(file_name),
+                )
+                .await
+            };
+            match copy.await {
+                Ok(_) => seed_dump_file = Some(file_name.clone()),
+                Err(e) => tracing::warn!("Failed to copy seed dump of database {}: {}", db.id, e),
+            }
+        }
+
         sqlx::query(
             r#"
             INSERT INTO databases (
                 id, name, db_type, version, status, internal_port, external_port,
                 public_access, credentials, volume_name, volume_path, memory_limit,
                 cpu_limit, project_id, team_id, created_at, updated_at, container_slug,
-                custom_image, init_commands
-            ) VALUES (?, ?, ?, ?, 'pending', ?, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                custom_image, init_commands, seed_script, seed_dump_file, seed_on_clone
+            ) VALUES (?, ?, ?, ?, 'pending', ?, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&new_db_id)
@@ -412,6 +436,9 @@ pub async fn clone_environment(
         .bind(&container_slug)
         .bind(&db.custom_image)
         .bind(&db.init_commands)
+        .bind(&seed_script)
+        .bind(&seed_dump_file)
+        .bind(db.seed_on_clone)
         .execute(&state.db)
         .await
         .map_err(|e| {
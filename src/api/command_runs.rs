@@ -0,0 +1,224 @@
+//! One-off command run API endpoints for applications.
+//!
+//! Runs a command once in a fresh container started from the image of the
+//! app's running deployment, with the app's env vars and volumes: for
+//! migrations, seeds and ad-hoc scripts. Starting a run returns immediately;
+//! its output can be followed over SSE, ending with an `exit` event that
+//! carries the exit code.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::stream::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::crypto;
+use crate::db::{
+    CommandRun, CreateCommandRunRequest, User, DEFAULT_TASK_TIMEOUT_SECS, MAX_TASK_TIMEOUT_SECS,
+};
+use crate::engine::command_runs;
+use crate::runtime::LogStream;
+use crate::AppState;
+
+use super::common::ensure_app_exists;
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Key length for AES-256 encryption
+const KEY_LENGTH: usize = 32;
+
+/// Most runs returned by the run history endpoint
+const MAX_RUNS_LISTED: i64 = 50;
+
+/// How often the stream checks on a run it is waiting for
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Get the derived encryption key from the config if configured
+fn get_encryption_key(state: &AppState) -> Option<[u8; KEY_LENGTH]> {
+    state
+        .config
+        .auth
+        .encryption_key
+        .as_ref()
+        .map(|secret| crypto::derive_key(secret))
+}
+
+/// Run a one-off command.
+///
+/// Returns the new run immediately; follow its output with the stream
+/// endpoint or poll the run for its outcome.
+pub async fn create_run(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    user: User,
+    Json(req): Json<CreateCommandRunRequest>,
+) -> Result<(StatusCode, Json<CommandRun>), ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let mut errors = ValidationErrorBuilder::new();
+    let command = req.command.trim();
+    if command.is_empty() {
+        errors.add("command", "Command is required");
+    }
+    let timeout_seconds = req.timeout_seconds.unwrap_or(DEFAULT_TASK_TIMEOUT_SECS);
+    if !(1..=MAX_TASK_TIMEOUT_SECS).contains(&timeout_seconds) {
+        errors.add(
+            "timeout_seconds",
+            format!("Must be between 1 and {}", MAX_TASK_TIMEOUT_SECS),
+        );
+    }
+    errors.finish()?;
+
+    let run =
+        command_runs::create_run(&state.db, &app_id, command, timeout_seconds, Some(&user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create command run: {}", e);
+                ApiError::database("Failed to create command run")
+            })?;
+
+    tracing::info!(
+        run_id = %run.id,
+        app_id = %app_id,
+        user_id = %user.id,
+        "One-off command run started"
+    );
+
+    let db = state.db.clone();
+    let runtime = state.runtime.clone();
+    let encryption_key = get_encryption_key(&state);
+    let spawned_run = run.clone();
+    tokio::spawn(async move {
+        command_runs::execute_run(&db, &runtime, &spawned_run, encryption_key.as_ref()).await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// List the most recent command runs of an app, newest first
+pub async fn list_runs(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<CommandRun>>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    Ok(Json(
+        CommandRun::list_for_app(&state.db, &app_id, MAX_RUNS_LISTED).await?,
+    ))
+}
+
+/// Get a command run
+pub async fn get_run(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, run_id)): Path<(String, String)>,
+) -> Result<Json<CommandRun>, ApiError> {
+    Ok(Json(find_run(&state, &app_id, &run_id).await?))
+}
+
+/// Stream a command run's output via SSE.
+///
+/// Sends `log` events (`stream` is `stdout` or `stderr`) while the container
+/// runs, then one `exit` event with the run's status, exit code and error.
+/// A run that already finished is replayed from its recorded output.
+pub async fn stream_run(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, run_id)): Path<(String, String)>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let mut run = find_run(&state, &app_id, &run_id).await?;
+
+    let stream = async_stream::stream! {
+        // Wait for the container to start (or the run to fail before it does)
+        while run.is_running() && run.container_id.is_none() {
+            tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+            match CommandRun::get(&state.db, &app_id, &run_id).await {
+                Ok(Some(latest)) => run = latest,
+                _ => {
+                    yield Ok(error_event("Command run not found"));
+                    return;
+                }
+            }
+        }
+
+        let mut streamed = false;
+        if let (true, Some(container_id)) = (run.is_running(), run.container_id.as_deref()) {
+            if let Ok(mut logs) = state.runtime.logs_stream(container_id).await {
+                while let Some(line) = logs.next().await {
+                    streamed = true;
+                    let stream = match line.stream {
+                        LogStream::Stdout => "stdout",
+                        LogStream::Stderr => "stderr",
+                    };
+                    yield Ok(log_event(stream, &line.message));
+                }
+            }
+        }
+
+        // Wait for the outcome to be recorded
+        while run.is_running() {
+            tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+            match CommandRun::get(&state.db, &app_id, &run_id).await {
+                Ok(Some(latest)) => run = latest,
+                _ => {
+                    yield Ok(error_event("Command run not found"));
+                    return;
+                }
+            }
+        }
+
+        // The container was gone before it could be followed
+        if !streamed {
+            for line in run.output.as_deref().unwrap_or_default().lines() {
+                match line.strip_prefix("[stderr] ") {
+                    Some(message) => yield Ok(log_event("stderr", message)),
+                    None => yield Ok(log_event("stdout", line)),
+                }
+            }
+        }
+
+        let exit = serde_json::json!({
+            "type": "exit",
+            "status": run.status,
+            "exit_code": run.exit_code,
+            "error": run.error_message,
+        });
+        yield Ok(Event::default().data(exit.to_string()));
+    };
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+fn log_event(stream: &str, message: &str) -> Event {
+    let event = serde_json::json!({
+        "type": "log",
+        "stream": stream,
+        "message": message,
+    });
+    Event::default().data(event.to_string())
+}
+
+fn error_event(message: &str) -> Event {
+    let event = serde_json::json!({
+        "type": "error",
+        "message": message,
+    });
+    Event::default().data(event.to_string())
+}
+
+async fn find_run(
+    state: &Arc<AppState>,
+    app_id: &str,
+    run_id: &str,
+) -> Result<CommandRun, ApiError> {
+    ensure_app_exists(state, app_id).await?;
+    CommandRun::get(&state.db, app_id, run_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Command run not found"))
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by the per-app settings endpoints: app lookups and pushing
+//! changed settings to the live proxy routes without a redeploy.
+
+use std::sync::Arc;
+
+use crate::proxy::RouteTable;
+use crate::AppState;
+
+use super::error::ApiError;
+use super::validation::validate_uuid;
+
+/// Validate an app ID and return 404 if no such app exists
+pub(super) async fn ensure_app_exists(state: &Arc<AppState>, app_id: &str) -> Result<(), ApiError> {
+    if let Err(e) = validate_uuid(app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE id = ?")
+        .bind(app_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("App not found"));
+    }
+    Ok(())
+}
+
+/// Collect every domain the proxy serves an app under (domains JSON, legacy
+/// domain and auto subdomain). Returns `None` if the app does not exist.
+async fn app_domain_names(db: &crate::DbPool, app_id: &str) -> Option<Vec<String>> {
+    // Fetch the app's domain info to find which routes to update
+    let app_info: Option<(
+        Option<String>, // domain
+        Option<String>, // domains (JSON)
+        Option<String>, // auto_subdomain
+    )> = sqlx::query_as("SELECT domain, domains, auto_subdomain FROM apps WHERE id = ?")
+        .bind(app_id)
+        .fetch_optional(db)
+        .await
+        .unwrap_or(None);
+
+    let (legacy_domain, domains_json, auto_subdomain) = app_info?;
+
+    // Collect all domain names for this app
+    let mut domain_names: Vec<String> = Vec::new();
+
+    if let Some(ref json) = domains_json {
+        if let Ok(arr) = serde_json::from_str::<serde_json::Value>(json) {
+            if let Some(list) = arr.as_array() {
+                for entry in list {
+                    if let Some(d) = entry.get("domain").and_then(|v| v.as_str()) {
+                        if !d.is_empty() && !domain_names.contains(&d.to_string()) {
+                            domain_names.push(d.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if let Some(ref d) = legacy_domain {
+        if !d.is_empty() && !domain_names.contains(d) {
+            domain_names.push(d.clone());
+        }
+    }
+    if let Some(ref d) = auto_subdomain {
+        if !d.is_empty() && !domain_names.contains(d) {
+            domain_names.push(d.clone());
+        }
+    }
+
+    Some(domain_names)
+}
+
+/// Apply a changed app setting to its live proxy routes.
+///
+/// `update` is called with the route table for each of the app's domains and,
+/// with `include_previews`, for the domain of each running preview deployment
+/// (the flag passed to `update` tells the two apart). Returns the domains
+/// updated, for logging.
+pub(super) async fn refresh_proxy_routes(
+    state: &Arc<AppState>,
+    app_id: &str,
+    include_previews: bool,
+    update: impl Fn(&RouteTable, &str, bool),
+) -> Vec<String> {
+    let route_table = state.routes.load();
+
+    let mut domains = app_domain_names(&state.db, app_id)
+        .await
+        .unwrap_or_default();
+    for domain in &domains {
+        update(&route_table, domain, false);
+    }
+
+    if include_previews {
+        let preview_domains: Vec<String> = sqlx::query_scalar(
+            "SELECT preview_domain FROM preview_deployments WHERE app_id = ? AND status = 'running'",
+        )
+        .bind(app_id)
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+        for domain in &preview_domains {
+            update(&route_table, domain, true);
+        }
+        domains.extend(preview_domains);
+    }
+
+    domains
+}
@@ -0,0 +1,195 @@
+//! CORS policy API endpoints for applications.
+//!
+//! Controls the CORS policy the proxy enforces for an app: which origins,
+//! methods and headers are allowed, whether credentials may be sent, and how
+//! long preflight responses are cached. The proxy answers preflights itself.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::db::{AppCorsSettings, UpdateCorsSettingsRequest};
+use crate::AppState;
+
+use super::common::{ensure_app_exists, refresh_proxy_routes};
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Longest allowed preflight cache lifetime (1 day)
+const MAX_AGE_LIMIT_SECS: i64 = 86_400;
+
+/// Methods that may be listed in `allowed_methods`
+const CORS_METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// Get CORS settings for an app
+pub async fn get_cors_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<AppCorsSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let settings = AppCorsSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppCorsSettings::default_for(&app_id));
+
+    Ok(Json(settings))
+}
+
+/// Update CORS settings for an app
+pub async fn update_cors_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdateCorsSettingsRequest>,
+) -> Result<Json<AppCorsSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let existing = AppCorsSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppCorsSettings::default_for(&app_id));
+
+    let enabled = req.enabled.unwrap_or(existing.enabled != 0);
+    let allowed_origins = req
+        .allowed_origins
+        .clone()
+        .unwrap_or(existing.allowed_origins);
+    let allowed_methods = req
+        .allowed_methods
+        .clone()
+        .unwrap_or(existing.allowed_methods);
+    let allowed_headers = req
+        .allowed_headers
+        .clone()
+        .unwrap_or(existing.allowed_headers);
+    let expose_headers = req
+        .expose_headers
+        .clone()
+        .unwrap_or(existing.expose_headers);
+    let allow_credentials = req
+        .allow_credentials
+        .unwrap_or(existing.allow_credentials != 0);
+    let max_age_secs = req.max_age_secs.unwrap_or(existing.max_age_secs);
+
+    let mut errors = ValidationErrorBuilder::new();
+    if let Some(invalid) = list_entries(&allowed_origins).find(|o| !is_valid_origin(o)) {
+        errors.add(
+            "allowed_origins",
+            format!(
+                "'{}' is not '*', an origin like https://app.example.com or a wildcard like https://*.example.com",
+                invalid
+            ),
+        );
+    }
+    if enabled && list_entries(&allowed_origins).next().is_none() {
+        errors.add("allowed_origins", "At least one origin is required");
+    }
+    if let Some(invalid) = list_entries(&allowed_methods)
+        .find(|m| !CORS_METHODS.contains(&m.to_ascii_uppercase().as_str()))
+    {
+        errors.add(
+            "allowed_methods",
+            format!("'{}' is not one of: {}", invalid, CORS_METHODS.join(", ")),
+        );
+    }
+    if enabled && list_entries(&allowed_methods).next().is_none() {
+        errors.add("allowed_methods", "At least one method is required");
+    }
+    for (field, list) in [
+        ("allowed_headers", &allowed_headers),
+        ("expose_headers", &expose_headers),
+    ] {
+        if let Some(invalid) = list_entries(list)
+            .find(|h| hyper::header::HeaderName::from_bytes(h.as_bytes()).is_err())
+        {
+            errors.add(field, format!("'{}' is not a valid header name", invalid));
+        }
+    }
+    if !(0..=MAX_AGE_LIMIT_SECS).contains(&max_age_secs) {
+        errors.add(
+            "max_age_secs",
+            format!("Must be between 0 and {} seconds", MAX_AGE_LIMIT_SECS),
+        );
+    }
+    errors.finish()?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_cors
+            (app_id, enabled, allowed_origins, allowed_methods, allowed_headers, expose_headers,
+             allow_credentials, max_age_secs, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(app_id) DO UPDATE SET
+            enabled = excluded.enabled,
+            allowed_origins = excluded.allowed_origins,
+            allowed_methods = excluded.allowed_methods,
+            allowed_headers = excluded.allowed_headers,
+            expose_headers = excluded.expose_headers,
+            allow_credentials = excluded.allow_credentials,
+            max_age_secs = excluded.max_age_secs,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(enabled)
+    .bind(allowed_origins.trim())
+    .bind(allowed_methods.trim())
+    .bind(allowed_headers.trim())
+    .bind(expose_headers.trim())
+    .bind(allow_credentials)
+    .bind(max_age_secs)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update CORS settings: {}", e);
+        ApiError::database("Failed to update CORS settings")
+    })?;
+
+    let settings = AppCorsSettings::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("CORS settings missing after update"))?;
+
+    apply_cors(&state, &settings).await;
+
+    Ok(Json(settings))
+}
+
+/// Apply CORS settings to the app's live routes and its running previews.
+async fn apply_cors(state: &Arc<AppState>, settings: &AppCorsSettings) {
+    let policy = settings.to_policy();
+    let domains = refresh_proxy_routes(state, &settings.app_id, true, |routes, domain, _| {
+        routes.update_cors(domain, policy.clone());
+    })
+    .await;
+
+    tracing::info!(
+        app_id = %settings.app_id,
+        domains = ?domains,
+        enabled = policy.is_some(),
+        "CORS settings refreshed in proxy route table"
+    );
+}
+
+/// Non-empty entries of a newline/comma separated list
+fn list_entries(list: &str) -> impl Iterator<Item = &str> {
+    list.split(['\n', ','])
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+}
+
+/// `*`, `scheme://host[:port]` or `scheme://*.domain`
+fn is_valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+    let Some((scheme, host)) = origin.trim_end_matches('/').split_once("://") else {
+        return false;
+    };
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    matches!(scheme, "http" | "https")
+        && !host.is_empty()
+        && !host.contains(['/', '*', ' ', '?', '#'])
+}
@@ -16,6 +16,7 @@ use crate::db::{
     DatabaseBackupSchedule, DatabaseBackupScheduleResponse, ManagedDatabase, ScheduleType,
 };
 use crate::engine::database_backups::DatabaseBackupTask;
+use crate::utils::cron;
 use crate::AppState;
 
 use super::error::{ApiError, ErrorCode};
@@ -308,10 +309,16 @@ pub async fn upsert_schedule(
         let schedule_hour = req.schedule_hour.unwrap_or(existing.schedule_hour);
         let schedule_day = req.schedule_day.or(existing.schedule_day);
         let retention_count = req.retention_count.unwrap_or(existing.retention_count);
+        let timezone = match req.timezone.as_deref() {
+            Some(timezone) => cron::validate_timezone(Some(timezone))
+                .map_err(|e| ApiError::new(ErrorCode::ValidationError, e))?,
+            None => existing.timezone,
+        };
         let next_run = DatabaseBackupSchedule::calculate_next_run(
             &schedule_type,
             schedule_hour,
             schedule_day,
+            timezone.as_deref(),
             &now,
         );
 
@@ -319,7 +326,7 @@ pub async fn upsert_schedule(
             r#"
             UPDATE database_backup_schedules
             SET enabled = ?, schedule_type = ?, schedule_hour = ?, schedule_day = ?,
-                retention_count = ?, next_run_at = ?, updated_at = ?
+                retention_count = ?, timezone = ?, next_run_at = ?, updated_at = ?
             WHERE database_id = ?
             "#,
         )
@@ -328,6 +335,7 @@ pub async fn upsert_schedule(
         .bind(schedule_hour)
         .bind(schedule_day)
         .bind(retention_count)
+        .bind(&timezone)
         .bind(next_run.to_rfc3339())
         .bind(now.to_rfc3339())
         .bind(&database_id)
@@ -349,10 +357,13 @@ pub async fn upsert_schedule(
         let schedule_hour = req.schedule_hour.unwrap_or(2);
         let schedule_day = req.schedule_day;
         let retention_count = req.retention_count.unwrap_or(5);
+        let timezone = cron::validate_timezone(req.timezone.as_deref())
+            .map_err(|e| ApiError::new(ErrorCode::ValidationError, e))?;
         let next_run = DatabaseBackupSchedule::calculate_next_run(
             &schedule_type,
             schedule_hour,
             schedule_day,
+            timezone.as_deref(),
             &now,
         );
 
@@ -360,8 +371,8 @@ pub async fn upsert_schedule(
             r#"
             INSERT INTO database_backup_schedules
             (id, database_id, enabled, schedule_type, schedule_hour, schedule_day,
-             retention_count, next_run_at, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             retention_count, timezone, next_run_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&schedule.id)
@@ -371,6 +382,7 @@ pub async fn upsert_schedule(
         .bind(schedule_hour)
         .bind(schedule_day)
         .bind(retention_count)
+        .bind(&timezone)
         .bind(next_run.to_rfc3339())
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
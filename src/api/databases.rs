@@ -18,6 +18,7 @@ use crate::db::{
 use crate::engine::database_config::{
     generate_env_vars, generate_password, generate_username, get_config,
 };
+use crate::engine::database_seed::{self, load_command, LoadFormat, MAX_SEED_SCRIPT_BYTES};
 use crate::runtime::{PortMapping, RunConfig};
 use crate::AppState;
 
@@ -150,6 +151,15 @@ pub async fn create_database(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    // Validate the seed script
+    let seed_script = req.seed_script.filter(|s| !s.trim().is_empty());
+    if let Some(ref script) = seed_script {
+        if let Err(e) = check_seed_script(&req.db_type, script) {
+            tracing::warn!("Invalid seed script: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
     // Get database type configuration
     let config = get_config(&req.db_type);
 
@@ -213,8 +223,8 @@ pub async fn create_database(
             id, name, db_type, version, status, internal_port, external_port,
             public_access, credentials, volume_name, volume_path, memory_limit,
             cpu_limit, project_id, team_id, created_at, updated_at, container_slug,
-            custom_image, init_commands
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            custom_image, init_commands, seed_script, seed_on_clone
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
@@ -237,6 +247,8 @@ pub async fn create_database(
     .bind(&container_slug)
     .bind(&req.custom_image)
     .bind(&req.init_commands)
+    .bind(&seed_script)
+    .bind(if req.seed_on_clone { 1 } else { 0 })
     .execute(&state.db)
     .await
     .map_err(|e| {
@@ -347,6 +359,18 @@ pub async fn delete_database(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
+    // Remove the uploaded seed dump, if any
+    let seed_dir = database_seed::seed_dir(&state.config.server.data_dir, &id);
+    if seed_dir.exists() {
+        if let Err(e) = tokio::fs::remove_dir_all(&seed_dir).await {
+            tracing::warn!(
+                "Failed to remove seed directory {}: {}",
+                seed_dir.display(),
+                e
+            );
+        }
+    }
+
     // Log audit event
     audit_log(
         &state,
@@ -636,6 +660,7 @@ async fn start_database_container(state: &Arc<AppState>, id: &str) -> anyhow::Re
             state
                 .start_log_streams
                 .end(&resource_key, "running", "Database is running");
+            spawn_pending_seed(state, id);
         }
         Err(e) => {
             state
@@ -649,6 +674,54 @@ async fn start_database_container(state: &Arc<AppState>, id: &str) -> anyhow::Re
     result
 }
 
+/// Load the database's seed in the background if it was never loaded. The
+/// seed waits for the database to accept connections, which can take a while
+/// on first boot, so callers don't wait for it.
+fn spawn_pending_seed(state: &Arc<AppState>, id: &str) {
+    let state = state.clone();
+    let id = id.to_string();
+    tokio::spawn(async move {
+        let database =
+            match sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(&state.db)
+                .await
+            {
+                Ok(Some(database)) => database,
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::warn!("Failed to load database {} for seeding: {}", id, e);
+                    return;
+                }
+            };
+        if database.get_status() != DatabaseStatus::Running {
+            return;
+        }
+        // run_seed records failures on the database
+        let _ = database_seed::seed_if_pending(
+            &state.db,
+            &state.runtime,
+            &database,
+            &state.config.server.data_dir,
+        )
+        .await;
+    });
+}
+
+/// Check a seed script for a database of type `db_type`
+fn check_seed_script(db_type: &DatabaseType, script: &str) -> Result<(), String> {
+    if !db_type.supports_seeding() {
+        return Err(format!("{} databases can't be seeded", db_type));
+    }
+    if script.len() > MAX_SEED_SCRIPT_BYTES {
+        return Err(format!(
+            "Seed script must be at most {} KB; upload larger seeds as a dump",
+            MAX_SEED_SCRIPT_BYTES / 1024
+        ));
+    }
+    Ok(())
+}
+
 async fn start_database_container_inner(
     state: &Arc<AppState>,
     id: &str,
@@ -1273,6 +1346,17 @@ pub async fn update_database(
         }
     }
 
+    if let Some(ref script) = req.seed_script {
+        if !script.trim().is_empty() {
+            if let Err(e) = check_seed_script(&database.get_db_type(), script) {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "Invalid seed script", "message": e})),
+                ));
+            }
+        }
+    }
+
     // Build the update query dynamically
     let mut updates = Vec::new();
     let mut values: Vec<Box<dyn std::any::Any + Send + Sync>> = Vec::new();
@@ -1322,6 +1406,16 @@ pub async fn update_database(
         // value tracked separately via req.project_id below
     }
 
+    if req.seed_script.is_some() {
+        updates.push("seed_script = ?");
+        // value tracked separately via req.seed_script below
+    }
+
+    if let Some(seed_on_clone) = req.seed_on_clone {
+        updates.push("seed_on_clone = ?");
+        values.push(Box::new(if seed_on_clone { 1i32 } else { 0i32 }));
+    }
+
     if updates.is_empty() {
         // No changes
         let hostname = state.config.public_hostname();
@@ -1369,6 +1463,17 @@ pub async fn update_database(
             query = query.bind(project_id.as_str());
         }
     }
+    if let Some(ref seed_script) = req.seed_script {
+        // Empty string removes the seed script (binds SQL NULL).
+        if seed_script.trim().is_empty() {
+            query = query.bind(None::<String>);
+        } else {
+            query = query.bind(seed_script.as_str());
+        }
+    }
+    if let Some(seed_on_clone) = req.seed_on_clone {
+        query = query.bind(if seed_on_clone { 1i32 } else { 0i32 });
+    }
     query = query.bind(&id);
 
     query.execute(&state.db).await.map_err(|e| {
@@ -1414,6 +1519,12 @@ pub async fn update_database(
     // If public_access changed and database is running, we need to restart it
     let needs_restart = public_access_changed && database.get_status() == DatabaseStatus::Running;
 
+    // A seed attached to a running database that was never seeded loads now
+    // (a restart seeds once the new container is up)
+    if req.seed_script.is_some() && !needs_restart {
+        spawn_pending_seed(&state, &id);
+    }
+
     if needs_restart {
         tracing::info!(
             "Public access changed for database {}, restarting container",
@@ -1565,8 +1676,6 @@ pub async fn import_database_dump(
     Path(id): Path<String>,
     mut multipart: Multipart,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    use base64::Engine as _;
-
     let database = sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
         .bind(&id)
         .fetch_optional(&state.db)
@@ -1599,58 +1708,28 @@ pub async fn import_database_dump(
         )
     })?;
 
-    // Read the uploaded file from multipart
-    let mut file_bytes: Option<Vec<u8>> = None;
-    let mut file_name: Option<String> = None;
+    let (file_name, bytes) = read_dump_field(&mut multipart).await?;
 
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": format!("Failed to read multipart field: {}", e)})),
-        )
-    })? {
-        if field.name() == Some("file") {
-            file_name = field.file_name().map(|s| s.to_string());
-            file_bytes = Some(
-                field
-                    .bytes()
-                    .await
-                    .map_err(|e| {
-                        (
-                            StatusCode::BAD_REQUEST,
-                            Json(
-                                serde_json::json!({"error": format!("Failed to read file: {}", e)}),
-                            ),
-                        )
-                    })?
-                    .to_vec(),
-            );
-            break;
-        }
-    }
-
-    let bytes = file_bytes.ok_or_else(|| {
+    let creds = database.get_credentials().ok_or_else(|| {
         (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "No file field found in request"})),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Failed to read database credentials"})),
         )
     })?;
 
-    if bytes.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Uploaded file is empty"})),
-        ));
-    }
-
-    // Encode the dump as base64 and write it into the container
-    let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    // Build the restore command based on DB type
     let dest_path = "/tmp/rivetr_import_dump";
-    let write_arg = format!("echo '{}' | base64 -d > {}", encoded, dest_path);
+    let format = LoadFormat::for_dump(file_name.as_deref().unwrap_or_default());
+    let restore_cmd =
+        load_command(&database.get_db_type(), &creds, dest_path, format).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Import not supported for this database type"})),
+            )
+        })?;
 
-    let write_result = state
-        .runtime
-        .run_command(container_id, vec!["sh".to_string(), "-c".to_string(), write_arg])
+    // Write the dump into the container
+    database_seed::write_file(state.runtime.as_ref(), container_id, dest_path, &bytes)
         .await
         .map_err(|e| {
             tracing::error!("Failed to write dump into container: {}", e);
@@ -1660,103 +1739,6 @@ pub async fn import_database_dump(
             )
         })?;
 
-    if write_result.exit_code != 0 {
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": "Failed to write dump file to container",
-                "stderr": write_result.stderr
-            })),
-        ));
-    }
-
-    let creds = database.get_credentials().ok_or_else(|| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to read database credentials"})),
-        )
-    })?;
-
-    let db_type_str = database.db_type.as_str();
-    let is_gz = file_name
-        .as_deref()
-        .map(|n| n.ends_with(".gz"))
-        .unwrap_or(false);
-
-    // Build the restore command based on DB type
-    let restore_cmd: Vec<String> = match db_type_str {
-        "postgres" => {
-            let db_name = creds
-                .database
-                .clone()
-                .unwrap_or_else(|| creds.username.clone());
-            if is_gz {
-                // Custom format via pg_restore
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    format!(
-                        "PGPASSWORD='{}' pg_restore -U {} -d {} {}",
-                        creds.password, creds.username, db_name, dest_path
-                    ),
-                ]
-            } else {
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    format!(
-                        "PGPASSWORD='{}' psql -U {} -d {} -f {}",
-                        creds.password, creds.username, db_name, dest_path
-                    ),
-                ]
-            }
-        }
-        "mysql" => {
-            let db_name = creds
-                .database
-                .clone()
-                .unwrap_or_else(|| creds.username.clone());
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                format!(
-                    "mysql -u {} -p'{}' {} < {}",
-                    creds.username, creds.password, db_name, dest_path
-                ),
-            ]
-        }
-        "mariadb" => {
-            let db_name = creds
-                .database
-                .clone()
-                .unwrap_or_else(|| creds.username.clone());
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                format!(
-                    "mariadb -u {} -p'{}' {} < {}",
-                    creds.username, creds.password, db_name, dest_path
-                ),
-            ]
-        }
-        "mongodb" => {
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                format!(
-                    "mongorestore --username {} --password '{}' --authenticationDatabase admin --archive={} --gzip",
-                    creds.username, creds.password, dest_path
-                ),
-            ]
-        }
-        _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Import not supported for this database type"})),
-            ));
-        }
-    };
-
     let restore_result = state
         .runtime
         .run_command(container_id, restore_cmd)
@@ -1800,3 +1782,240 @@ pub async fn import_database_dump(
         "database_id": id
     })))
 }
+
+type JsonError = (StatusCode, Json<serde_json::Value>);
+
+fn json_error(status: StatusCode, error: impl Into<String>) -> JsonError {
+    (status, Json(serde_json::json!({"error": error.into()})))
+}
+
+/// Fetch a database that can have a seed
+async fn get_seedable_database(state: &AppState, id: &str) -> Result<ManagedDatabase, JsonError> {
+    let database = sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get database: {}", e);
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to get database")
+        })?
+        .ok_or_else(|| json_error(StatusCode::NOT_FOUND, "Database not found"))?;
+    if !database.get_db_type().supports_seeding() {
+        return Err(json_error(
+            StatusCode::BAD_REQUEST,
+            format!("{} databases can't be seeded", database.db_type),
+        ));
+    }
+    Ok(database)
+}
+
+/// File name an uploaded seed dump is stored under, keeping the extension
+/// that selects how it is restored
+fn seed_dump_file_name(upload_name: Option<&str>) -> String {
+    let name: String = upload_name
+        .and_then(|n| n.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "seed.dump".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Upload a dump that seeds the database, replacing any previous one
+/// PUT /api/databases/:id/seed-dump
+pub async fn upload_seed_dump(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<ManagedDatabaseResponse>, JsonError> {
+    let database = get_seedable_database(&state, &id).await?;
+    let (upload_name, bytes) = read_dump_field(&mut multipart).await?;
+    let file_name = seed_dump_file_name(upload_name.as_deref());
+
+    let seed_dir = database_seed::seed_dir(&state.config.server.data_dir, &id);
+    let store = async {
+        if seed_dir.exists() {
+            tokio::fs::remove_dir_all(&seed_dir).await?;
+        }
+        tokio::fs::create_dir_all(&seed_dir).await?;
+        tokio::fs::write(seed_dir.join(&file_name), &bytes).await
+    };
+    store.await.map_err(|e| {
+        tracing::error!("Failed to store seed dump for {}: {}", database.name, e);
+        json_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store seed dump",
+        )
+    })?;
+
+    let database = sqlx::query_as::<_, ManagedDatabase>(
+        "UPDATE databases SET seed_dump_file = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(&file_name)
+    .bind(&id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update database: {}", e);
+        json_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to update database")
+    })?;
+
+    tracing::info!(
+        "Stored seed dump {} ({} bytes) for database {}",
+        file_name,
+        bytes.len(),
+        database.name
+    );
+    spawn_pending_seed(&state, &id);
+
+    let hostname = state.config.public_hostname();
+    let host = Some(hostname.as_str());
+    Ok(Json(database.to_response(false, host)))
+}
+
+/// Remove the database's seed dump
+/// DELETE /api/databases/:id/seed-dump
+pub async fn delete_seed_dump(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ManagedDatabaseResponse>, JsonError> {
+    let database = get_seedable_database(&state, &id).await?;
+
+    let seed_dir = database_seed::seed_dir(&state.config.server.data_dir, &id);
+    if seed_dir.exists() {
+        if let Err(e) = tokio::fs::remove_dir_all(&seed_dir).await {
+            tracing::warn!(
+                "Failed to remove seed directory {}: {}",
+                seed_dir.display(),
+                e
+            );
+        }
+    }
+
+    let database = sqlx::query_as::<_, ManagedDatabase>(
+        "UPDATE databases SET seed_dump_file = NULL, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(&database.id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update database: {}", e);
+        json_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to update database")
+    })?;
+
+    let hostname = state.config.public_hostname();
+    let host = Some(hostname.as_str());
+    Ok(Json(database.to_response(false, host)))
+}
+
+/// Load the database's seed now, also when it was loaded before
+/// POST /api/databases/:id/seed
+pub async fn seed_database(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, JsonError> {
+    let database = get_seedable_database(&state, &id).await?;
+    if database.get_status() != DatabaseStatus::Running {
+        return Err(json_error(
+            StatusCode::BAD_REQUEST,
+            "Database must be running to load its seed",
+        ));
+    }
+    if !database.has_seed() {
+        return Err(json_error(
+            StatusCode::BAD_REQUEST,
+            "Database has no seed script or dump",
+        ));
+    }
+
+    let result = database_seed::run_seed(
+        &state.db,
+        &state.runtime,
+        &database,
+        &state.config.server.data_dir,
+    )
+    .await;
+
+    audit_log(
+        &state,
+        actions::DATABASE_UPDATE,
+        resource_types::DATABASE,
+        Some(&database.id),
+        Some(&database.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "seed": true,
+            "success": result.is_ok(),
+        })),
+    )
+    .await;
+
+    Ok(Json(match result {
+        Ok(()) => serde_json::json!({
+            "success": true,
+            "message": "Seed loaded",
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "message": format!("{:#}", e),
+        }),
+    }))
+}
+
+/// Read the non-empty "file" field of a dump upload
+async fn read_dump_field(
+    multipart: &mut Multipart,
+) -> Result<(Option<String>, Vec<u8>), JsonError> {
+    let mut file_bytes: Option<Vec<u8>> = None;
+    let mut file_name: Option<String> = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Failed to read multipart field: {}", e)})),
+        )
+    })? {
+        if field.name() == Some("file") {
+            file_name = field.file_name().map(|s| s.to_string());
+            file_bytes = Some(
+                field
+                    .bytes()
+                    .await
+                    .map_err(|e| {
+                        (
+                            StatusCode::BAD_REQUEST,
+                            Json(
+                                serde_json::json!({"error": format!("Failed to read file: {}", e)}),
+                            ),
+                        )
+                    })?
+                    .to_vec(),
+            );
+            break;
+        }
+    }
+
+    let bytes = file_bytes.ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "No file field found in request"})),
+        )
+    })?;
+
+    if bytes.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Uploaded file is empty"})),
+        ));
+    }
+
+    Ok((file_name, bytes))
+}
@@ -5,10 +5,14 @@ use axum::{
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::db::{App, Deployment, User};
-use crate::AppState;
+use crate::db::{actions, resource_types, App, Deployment, DeploymentEvent, TeamRole, User};
+use crate::{AppState, DbPool};
 
+use crate::api::apps::check_deploy_lock;
+use crate::api::audit::{audit_log, ClientIp};
+use crate::api::authz::is_privileged_user;
 use crate::api::error::ApiError;
+use crate::api::system::check_host_cordon;
 use crate::api::validation::validate_uuid;
 
 /// Request body for rejecting a deployment
@@ -17,22 +21,57 @@ pub struct RejectDeployRequest {
     pub reason: Option<String>,
 }
 
+/// Whether deployments of `app` wait for approval: the app requires it, or
+/// the environment it belongs to does
+pub async fn approval_required(db: &DbPool, app: &App) -> Result<bool, sqlx::Error> {
+    if app.is_require_approval() {
+        return Ok(true);
+    }
+    let Some(environment_id) = &app.environment_id else {
+        return Ok(false);
+    };
+    let required: Option<i32> =
+        sqlx::query_scalar("SELECT require_approval FROM environments WHERE id = ?")
+            .bind(environment_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(required.unwrap_or(0) != 0)
+}
+
+/// Instance admins can approve or reject any deployment. For apps owned by a
+/// team, so can team members whose role can deploy.
+async fn check_approver(state: &AppState, user: &User, app: &App) -> Result<(), ApiError> {
+    if is_privileged_user(user) {
+        return Ok(());
+    }
+    if let Some(team_id) = &app.team_id {
+        let role: Option<String> =
+            sqlx::query_scalar("SELECT role FROM team_members WHERE team_id = ? AND user_id = ?")
+                .bind(team_id)
+                .bind(&user.id)
+                .fetch_optional(&state.db)
+                .await?;
+        if role.is_some_and(|role| TeamRole::from(role).can_deploy()) {
+            return Ok(());
+        }
+    }
+    Err(ApiError::forbidden(
+        "Only users who can deploy this app can approve or reject its deployments",
+    ))
+}
+
 /// Approve a pending deployment
 /// POST /api/deployments/:id/approve
 pub async fn approve_deployment(
     State(state): State<Arc<AppState>>,
     user: User,
+    client_ip: ClientIp,
     Path(deployment_id): Path<String>,
 ) -> Result<Json<Deployment>, ApiError> {
     if let Err(e) = validate_uuid(&deployment_id, "deployment_id") {
         return Err(ApiError::validation_field("deployment_id", e));
     }
 
-    // Only admins can approve deployments
-    if user.role != "admin" {
-        return Err(ApiError::forbidden("Only admins can approve deployments"));
-    }
-
     // Get the deployment
     let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
         .bind(&deployment_id)
@@ -52,6 +91,17 @@ pub async fn approve_deployment(
         ));
     }
 
+    // Get the app so we can queue the deployment (a locked app stays pending)
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&deployment.app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+
+    check_approver(&state, &user, &app).await?;
+    check_deploy_lock(&app)?;
+    check_host_cordon(&state)?;
+
     let now = chrono::Utc::now().to_rfc3339();
 
     // Use NULL for approved_by when using the admin API token (synthetic "system" user
@@ -72,17 +122,30 @@ pub async fn approve_deployment(
     .execute(&state.db)
     .await?;
 
-    // Get the app so we can queue the deployment
-    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
-        .bind(&deployment.app_id)
-        .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| ApiError::not_found("App not found"))?;
-
-    // Queue the deployment job now that it is approved
-    if let Err(e) = state.deploy_tx.send((deployment_id.clone(), app)).await {
-        tracing::error!("Failed to queue approved deployment: {}", e);
-        return Err(ApiError::internal("Failed to queue deployment job"));
+    DeploymentEvent::record(
+        &state.db,
+        &deployment_id,
+        "pending",
+        Some(&format!("Approved by {}", user.email)),
+    )
+    .await;
+
+    // Queue the deployment job now that it is approved. One scheduled for
+    // later is queued by the scheduled deployment checker when its time comes.
+    let held = deployment
+        .scheduled_at
+        .as_deref()
+        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        .is_some_and(|at| at > chrono::Utc::now());
+    if !held {
+        if let Err(e) = state
+            .deploy_tx
+            .send((deployment_id.clone(), app.clone()))
+            .await
+        {
+            tracing::error!("Failed to queue approved deployment: {}", e);
+            return Err(ApiError::internal("Failed to queue deployment job"));
+        }
     }
 
     let updated = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
@@ -96,6 +159,20 @@ pub async fn approve_deployment(
         "Deployment approved and queued"
     );
 
+    audit_log(
+        &state,
+        actions::DEPLOYMENT_APPROVE,
+        resource_types::APP,
+        Some(&app.id),
+        Some(&app.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "deployment_id": deployment_id,
+        })),
+    )
+    .await;
+
     Ok(Json(updated))
 }
 
@@ -104,6 +181,7 @@ pub async fn approve_deployment(
 pub async fn reject_deployment(
     State(state): State<Arc<AppState>>,
     user: User,
+    client_ip: ClientIp,
     Path(deployment_id): Path<String>,
     body: Option<Json<RejectDeployRequest>>,
 ) -> Result<Json<Deployment>, ApiError> {
@@ -111,11 +189,6 @@ pub async fn reject_deployment(
         return Err(ApiError::validation_field("deployment_id", e));
     }
 
-    // Only admins can reject deployments
-    if user.role != "admin" {
-        return Err(ApiError::forbidden("Only admins can reject deployments"));
-    }
-
     // Get the deployment
     let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
         .bind(&deployment_id)
@@ -123,6 +196,13 @@ pub async fn reject_deployment(
         .await?
         .ok_or_else(|| ApiError::not_found("Deployment not found"))?;
 
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&deployment.app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+    check_approver(&state, &user, &app).await?;
+
     // Must be in pending approval state
     if deployment.approval_status.as_deref() != Some("pending") {
         return Err(ApiError::bad_request("Deployment is not pending approval"));
@@ -159,6 +239,13 @@ pub async fn reject_deployment(
     .bind(&deployment_id)
     .execute(&state.db)
     .await?;
+    DeploymentEvent::record(
+        &state.db,
+        &deployment_id,
+        "failed",
+        Some(&format!("Deployment rejected: {}", reason)),
+    )
+    .await;
 
     let updated = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
         .bind(&deployment_id)
@@ -172,6 +259,21 @@ pub async fn reject_deployment(
         "Deployment rejected"
     );
 
+    audit_log(
+        &state,
+        actions::DEPLOYMENT_REJECT,
+        resource_types::APP,
+        Some(&app.id),
+        Some(&app.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "deployment_id": deployment_id,
+            "reason": reason,
+        })),
+    )
+    .await;
+
     Ok(Json(updated))
 }
 
@@ -203,3 +305,97 @@ pub async fn list_pending_deployments(
 
     Ok(Json(deployments))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_pool, test_user};
+
+    async fn add_team(db: &DbPool, app_id: &str) -> String {
+        let team_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO teams (id, name, slug) VALUES (?, 'Team', ?)")
+            .bind(&team_id)
+            .bind(&team_id)
+            .execute(db)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE apps SET team_id = ? WHERE id = ?")
+            .bind(&team_id)
+            .bind(app_id)
+            .execute(db)
+            .await
+            .unwrap();
+        team_id
+    }
+
+    async fn add_member(db: &DbPool, team_id: &str, user: &User, role: &str) {
+        sqlx::query("INSERT INTO team_members (id, team_id, user_id, role) VALUES (?, ?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(team_id)
+            .bind(&user.id)
+            .bind(role)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_approval_required_by_app_or_environment() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        assert!(!approval_required(&db, &load_test_app(&db, &app_id).await)
+            .await
+            .unwrap());
+
+        sqlx::query("INSERT INTO projects (id, name) VALUES ('p1', 'Shop')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO environments (id, project_id, name, require_approval) VALUES ('e1', 'p1', 'production', 1)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE apps SET environment_id = 'e1' WHERE id = ?")
+            .bind(&app_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert!(approval_required(&db, &load_test_app(&db, &app_id).await)
+            .await
+            .unwrap());
+
+        sqlx::query("UPDATE environments SET require_approval = 0")
+            .execute(&db)
+            .await
+            .unwrap();
+        let mut app = load_test_app(&db, &app_id).await;
+        assert!(!approval_required(&db, &app).await.unwrap());
+        app.require_approval = 1;
+        assert!(approval_required(&db, &app).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_approvers_are_admins_or_team_deployers() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let db = &state.db;
+        let app_id = test_app(db, "web").await;
+        let team_id = add_team(db, &app_id).await;
+        let app = load_test_app(db, &app_id).await;
+
+        let admin = test_user(db, "admin").await;
+        assert!(check_approver(&state, &admin, &app).await.is_ok());
+
+        let developer = test_user(db, "member").await;
+        add_member(db, &team_id, &developer, "developer").await;
+        assert!(check_approver(&state, &developer, &app).await.is_ok());
+
+        let viewer = test_user(db, "member").await;
+        add_member(db, &team_id, &viewer, "viewer").await;
+        assert!(check_approver(&state, &viewer, &app).await.is_err());
+
+        let outsider = test_user(db, "member").await;
+        assert!(check_approver(&state, &outsider, &app).await.is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::db::Deployment;
+use crate::engine::diagnostics;
+use crate::storage::artifacts;
+use crate::AppState;
+
+use crate::api::error::ApiError;
+use crate::api::validation::validate_uuid;
+
+/// Download the diagnostics bundle collected when a deployment failed
+/// GET /api/deployments/:id/diagnostics
+pub async fn get_deployment_diagnostics(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Err(e) = validate_uuid(&id, "deployment_id") {
+        return Err(ApiError::validation_field("deployment_id", e));
+    }
+
+    let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Deployment not found"))?;
+
+    let bundle = diagnostics::load(&state.db, state.storage.as_ref(), &deployment.id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to load diagnostics: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("No diagnostics were collected for this deployment"))?;
+    let body = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| ApiError::internal(format!("Failed to encode diagnostics: {}", e)))?;
+
+    let short_id: String = deployment.id.chars().take(8).collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"deployment-{}-diagnostics.json\"",
+                    short_id
+                ),
+            ),
+        ],
+        body,
+    ))
+}
+
+/// Download the archived log of a finished deployment (gzipped text). The
+/// archive is kept after log retention has removed the log lines.
+/// GET /api/deployments/:id/logs/archive
+pub async fn get_deployment_log_archive(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Err(e) = validate_uuid(&id, "deployment_id") {
+        return Err(ApiError::validation_field("deployment_id", e));
+    }
+
+    let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Deployment not found"))?;
+
+    let archive = artifacts::get(
+        &state.db,
+        state.storage.as_ref(),
+        &deployment.id,
+        artifacts::KIND_BUILD_LOG,
+    )
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to load log archive: {}", e)))?
+    .ok_or_else(|| ApiError::not_found("No log archive exists for this deployment"))?;
+
+    let short_id: String = deployment.id.chars().take(8).collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"deployment-{}-log.txt.gz\"",
+                    short_id
+                ),
+            ),
+        ],
+        archive,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment};
+    use crate::runtime::NoopRuntime;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_diagnostics_download() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        let deployment_id = test_deployment(&state.db, &app_id, "failed").await;
+
+        let missing = get_deployment_diagnostics(State(state.clone()), Path(deployment_id.clone()))
+            .await
+            .into_response();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let app = load_test_app(&state.db, &app_id).await;
+        diagnostics::collect_on_failure(
+            &state.db,
+            Some(state.storage.as_ref()),
+            &NoopRuntime,
+            &deployment_id,
+            &app,
+        )
+        .await;
+
+        let response = get_deployment_diagnostics(State(state.clone()), Path(deployment_id))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let disposition = response.headers()[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap();
+        assert!(
+            disposition.ends_with("-diagnostics.json\""),
+            "{}",
+            disposition
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let bundle: diagnostics::DiagnosticsBundle = serde_json::from_slice(&body).unwrap();
+        assert_eq!(bundle.app_name, "web");
+        assert_eq!(bundle.status, "failed");
+    }
+}
@@ -3,27 +3,39 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use chrono::Datelike;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
 use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::db::{App, DeploymentFreezeWindow, User};
+use crate::db::{App, DbPool, DeploymentFreezeWindow, User};
+use crate::utils::cron;
 use crate::AppState;
 
 use crate::api::error::ApiError;
 use crate::api::validation::validate_uuid;
 
 /// Request body for creating a freeze window
+///
+/// A window either recurs (`start_time`, `end_time`, `days_of_week`) or
+/// covers one calendar range (`starts_at`, `ends_at`).
 #[derive(Debug, Deserialize)]
 pub struct CreateFreezeWindowRequest {
     pub name: String,
-    /// Start time in HH:MM UTC format
+    /// Start time in HH:MM format
+    #[serde(default)]
     pub start_time: String,
-    /// End time in HH:MM UTC format
+    /// End time in HH:MM format
+    #[serde(default)]
     pub end_time: String,
     /// Comma-separated days of week (0=Sun, ..., 6=Sat). Default: all days
     pub days_of_week: Option<String>,
+    /// IANA timezone of the times and days. Default: UTC
+    pub timezone: Option<String>,
+    /// Start of a calendar range (RFC 3339)
+    pub starts_at: Option<String>,
+    /// End of a calendar range (RFC 3339)
+    pub ends_at: Option<String>,
     #[serde(default = "default_is_active")]
     pub is_active: bool,
 }
@@ -32,64 +44,149 @@ fn default_is_active() -> bool {
     true
 }
 
-/// Check if current time is within any active freeze window for this app/team.
-/// Returns 409 Conflict if deployment is frozen.
-pub async fn check_freeze_windows(
-    state: &Arc<AppState>,
-    app: &App,
-    now: &str,
-) -> Result<(), ApiError> {
-    // Parse current time to get HH:MM and day-of-week
-    let now_dt = chrono::DateTime::parse_from_rfc3339(now)
-        .map(|dt| dt.with_timezone(&chrono::Utc))
-        .unwrap_or_else(|_| chrono::Utc::now());
+/// A freeze window that is in effect for an app
+#[derive(Debug, Clone)]
+pub struct ActiveFreeze {
+    pub name: String,
+    /// When the window ends and deployments may go out again
+    pub release_at: DateTime<Utc>,
+    /// The window's span, for messages (e.g. "22:00 - 06:00 Europe/Berlin")
+    pub span: String,
+}
+
+/// If `now` falls inside the window, when the window ends.
+///
+/// Recurring windows are evaluated in their own timezone; a window that wraps
+/// midnight belongs to the day it starts on.
+fn window_release_at(window: &DeploymentFreezeWindow, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let (Some(starts_at), Some(ends_at)) = (&window.starts_at, &window.ends_at) {
+        let starts_at = DateTime::parse_from_rfc3339(starts_at).ok()?;
+        let ends_at = DateTime::parse_from_rfc3339(ends_at).ok()?;
+        return (starts_at <= now && now < ends_at).then(|| ends_at.with_timezone(&Utc));
+    }
 
-    let current_time = now_dt.format("%H:%M").to_string();
+    // Current HH:MM and day-of-week in the window's timezone
+    let tz = cron::parse_timezone(window.timezone.as_deref()).unwrap_or(chrono_tz::UTC);
+    let now_local = now.with_timezone(&tz);
+    let current_time = now_local.format("%H:%M").to_string();
     // 0=Sun as per the schema convention
-    let current_dow = now_dt.weekday().num_days_from_sunday().to_string();
-
-    // Fetch active freeze windows for this app and/or team
-    let windows: Vec<DeploymentFreezeWindow> = if let Some(ref team_id) = app.team_id {
-        sqlx::query_as(
-            r#"
-            SELECT * FROM deployment_freeze_windows
-            WHERE is_active = 1
-              AND (app_id = ? OR team_id = ?)
-            "#,
-        )
-        .bind(&app.id)
-        .bind(team_id)
-        .fetch_all(&state.db)
-        .await?
+    let current_dow = now_local.weekday().num_days_from_sunday().to_string();
+
+    // Check if current day-of-week is in the window
+    if !window
+        .days_of_week
+        .split(',')
+        .any(|d| d.trim() == current_dow)
+    {
+        return None;
+    }
+
+    // Check if current time is within start_time..end_time (HH:MM strings)
+    let in_window = if window.start_time <= window.end_time {
+        current_time >= window.start_time && current_time < window.end_time
     } else {
-        sqlx::query_as("SELECT * FROM deployment_freeze_windows WHERE is_active = 1 AND app_id = ?")
-            .bind(&app.id)
-            .fetch_all(&state.db)
-            .await?
+        // Wraps midnight
+        current_time >= window.start_time || current_time < window.end_time
     };
+    if !in_window {
+        return None;
+    }
+
+    // The window ends today if its end time is still ahead, otherwise
+    // tomorrow; an end time like 24:00 means the next midnight
+    let end_time = NaiveTime::parse_from_str(&window.end_time, "%H:%M").ok();
+    let today = now_local.date_naive();
+    let end_date = match end_time {
+        Some(_) if current_time < window.end_time => today,
+        _ => today.succ_opt()?,
+    };
+    let end_local = end_date.and_time(end_time.unwrap_or(NaiveTime::MIN));
+    let release_at = tz
+        .from_local_datetime(&end_local)
+        .earliest()
+        // The end time falls in a DST gap; release an hour later
+        .or_else(|| {
+            tz.from_local_datetime(&(end_local + Duration::hours(1)))
+                .earliest()
+        })?;
+    Some(release_at.with_timezone(&Utc))
+}
 
-    for window in &windows {
-        // Check if current day-of-week is in the window
-        let days: Vec<&str> = window.days_of_week.split(',').collect();
-        if !days.contains(&current_dow.as_str()) {
-            continue;
+fn window_span(window: &DeploymentFreezeWindow) -> String {
+    match (&window.starts_at, &window.ends_at) {
+        (Some(starts_at), Some(ends_at)) => format!("{} - {}", starts_at, ends_at),
+        _ => {
+            let tz = cron::parse_timezone(window.timezone.as_deref()).unwrap_or(chrono_tz::UTC);
+            format!("{} - {} {}", window.start_time, window.end_time, tz.name())
         }
+    }
+}
+
+/// The active freeze window for an app, if any. Windows are set on the app,
+/// or on its team with no app to cover every app in the team. When several
+/// overlap, the one that ends last wins.
+pub async fn active_freeze_window(
+    db: &DbPool,
+    app: &App,
+    now: DateTime<Utc>,
+) -> Result<Option<ActiveFreeze>, sqlx::Error> {
+    let windows: Vec<DeploymentFreezeWindow> = sqlx::query_as(
+        r#"
+        SELECT * FROM deployment_freeze_windows
+        WHERE is_active = 1
+          AND (app_id = ? OR (app_id IS NULL AND team_id IS NOT NULL AND team_id = ?))
+        "#,
+    )
+    .bind(&app.id)
+    .bind(&app.team_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(windows
+        .iter()
+        .filter_map(|window| {
+            window_release_at(window, now).map(|release_at| ActiveFreeze {
+                name: window.name.clone(),
+                release_at,
+                span: window_span(window),
+            })
+        })
+        .max_by_key(|freeze| freeze.release_at))
+}
 
-        // Check if current time is within start_time..end_time (HH:MM strings)
-        let in_window = if window.start_time <= window.end_time {
-            current_time >= window.start_time && current_time < window.end_time
-        } else {
-            // Wraps midnight
-            current_time >= window.start_time || current_time < window.end_time
-        };
-
-        if in_window {
-            return Err(ApiError::conflict(format!(
-                "Deployment frozen: '{}' freeze window is active ({} - {} UTC)",
-                window.name, window.start_time, window.end_time
-            )));
+/// When an automatic deployment of this app should go out, if a freeze
+/// window holds it. Webhook deployments are held rather than rejected: they
+/// are stored with this as their `scheduled_at` and queued once it passes.
+pub async fn freeze_hold(db: &DbPool, app: &App) -> Option<ActiveFreeze> {
+    match active_freeze_window(db, app, Utc::now()).await {
+        Ok(freeze) => freeze,
+        Err(e) => {
+            tracing::warn!(app = %app.name, error = %e, "Failed to check freeze windows");
+            None
         }
     }
+}
+
+/// Check if current time is within any active freeze window for this app/team.
+/// Each window is evaluated in its own timezone.
+/// Returns 409 Conflict if deployment is frozen.
+pub async fn check_freeze_windows(
+    state: &Arc<AppState>,
+    app: &App,
+    now: &str,
+) -> Result<(), ApiError> {
+    let now_utc = DateTime::parse_from_rfc3339(now)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now());
+
+    if let Some(freeze) = active_freeze_window(&state.db, app, now_utc).await? {
+        return Err(ApiError::conflict(format!(
+            "Deployment frozen: '{}' freeze window is active ({}), until {}",
+            freeze.name,
+            freeze.span,
+            freeze.release_at.to_rfc3339()
+        )));
+    }
 
     Ok(())
 }
@@ -146,13 +243,39 @@ pub async fn create_freeze_window(
         .await?
         .ok_or_else(|| ApiError::not_found("App not found"))?;
 
-    // Validate time format (HH:MM)
-    let time_re = regex::Regex::new(r"^\d{2}:\d{2}$").unwrap();
-    if !time_re.is_match(&req.start_time) || !time_re.is_match(&req.end_time) {
-        return Err(ApiError::bad_request(
-            "start_time and end_time must be in HH:MM format (UTC)",
-        ));
-    }
+    let (starts_at, ends_at) = match (&req.starts_at, &req.ends_at) {
+        (None, None) => {
+            // Validate time format (HH:MM)
+            let time_re = regex::Regex::new(r"^\d{2}:\d{2}$").unwrap();
+            if !time_re.is_match(&req.start_time) || !time_re.is_match(&req.end_time) {
+                return Err(ApiError::bad_request(
+                    "start_time and end_time must be in HH:MM format",
+                ));
+            }
+            (None, None)
+        }
+        (Some(starts_at), Some(ends_at)) => {
+            let parse = |value: &str| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| {
+                        ApiError::bad_request("starts_at and ends_at must be RFC 3339 timestamps")
+                    })
+            };
+            let (starts_at, ends_at) = (parse(starts_at)?, parse(ends_at)?);
+            if ends_at <= starts_at {
+                return Err(ApiError::bad_request("ends_at must be after starts_at"));
+            }
+            (Some(starts_at.to_rfc3339()), Some(ends_at.to_rfc3339()))
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                "starts_at and ends_at must be given together",
+            ))
+        }
+    };
+    let timezone =
+        cron::validate_timezone(req.timezone.as_deref()).map_err(ApiError::bad_request)?;
 
     let window_id = Uuid::new_v4().to_string();
     let days_of_week = req
@@ -163,8 +286,9 @@ pub async fn create_freeze_window(
     sqlx::query(
         r#"
         INSERT INTO deployment_freeze_windows
-          (id, app_id, team_id, name, start_time, end_time, days_of_week, is_active, created_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+          (id, app_id, team_id, name, start_time, end_time, days_of_week, timezone,
+           starts_at, ends_at, is_active, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&window_id)
@@ -174,6 +298,9 @@ pub async fn create_freeze_window(
     .bind(&req.start_time)
     .bind(&req.end_time)
     .bind(&days_of_week)
+    .bind(&timezone)
+    .bind(&starts_at)
+    .bind(&ends_at)
     .bind(req.is_active as i32)
     .bind(&now)
     .execute(&state.db)
@@ -220,3 +347,90 @@ pub async fn delete_freeze_window(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start_time: &str, end_time: &str, days_of_week: &str) -> DeploymentFreezeWindow {
+        DeploymentFreezeWindow {
+            id: "w".to_string(),
+            app_id: Some("app".to_string()),
+            team_id: None,
+            name: "No Friday deploys".to_string(),
+            start_time: start_time.to_string(),
+            end_time: end_time.to_string(),
+            days_of_week: days_of_week.to_string(),
+            is_active: 1,
+            created_at: String::new(),
+            timezone: None,
+            starts_at: None,
+            ends_at: None,
+        }
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_recurring_window_releases_at_its_end() {
+        // 2026-10-16 is a Friday
+        let fridays = window("00:00", "24:00", "5");
+        assert_eq!(
+            window_release_at(&fridays, at("2026-10-16T15:30:00Z")),
+            Some(at("2026-10-17T00:00:00Z"))
+        );
+        assert_eq!(
+            window_release_at(&fridays, at("2026-10-17T15:30:00Z")),
+            None
+        );
+
+        let nights = window("22:00", "06:00", "0,1,2,3,4,5,6");
+        assert_eq!(
+            window_release_at(&nights, at("2026-10-16T23:00:00Z")),
+            Some(at("2026-10-17T06:00:00Z"))
+        );
+        assert_eq!(
+            window_release_at(&nights, at("2026-10-17T02:00:00Z")),
+            Some(at("2026-10-17T06:00:00Z"))
+        );
+        assert_eq!(window_release_at(&nights, at("2026-10-17T12:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_recurring_window_uses_its_timezone() {
+        let mut evenings = window("18:00", "20:00", "0,1,2,3,4,5,6");
+        evenings.timezone = Some("Europe/Berlin".to_string());
+        // 17:30 UTC is 19:30 in Berlin (CEST)
+        assert_eq!(
+            window_release_at(&evenings, at("2026-10-16T17:30:00Z")),
+            Some(at("2026-10-16T18:00:00Z"))
+        );
+        assert_eq!(
+            window_release_at(&evenings, at("2026-10-16T18:30:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_calendar_range_replaces_recurring_times() {
+        let mut holidays = window("", "", "");
+        holidays.starts_at = Some("2026-12-20T00:00:00+00:00".to_string());
+        holidays.ends_at = Some("2027-01-04T09:00:00+00:00".to_string());
+        assert_eq!(
+            window_release_at(&holidays, at("2026-12-25T12:00:00Z")),
+            Some(at("2027-01-04T09:00:00Z"))
+        );
+        assert_eq!(
+            window_release_at(&holidays, at("2027-01-04T09:00:00Z")),
+            None
+        );
+        assert_eq!(
+            window_release_at(&holidays, at("2026-12-19T23:59:00Z")),
+            None
+        );
+    }
+}
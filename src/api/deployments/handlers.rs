@@ -8,18 +8,25 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::{
-    actions, resource_types, App, Deployment, DeploymentLog, TeamAuditAction,
-    TeamAuditResourceType, User,
+    actions, resource_types, App, Deployment, DeploymentEvent, DeploymentLog,
+    NotificationEventType, TeamAuditAction, TeamAuditResourceType, User,
 };
+use crate::engine::config_snapshot::{self, ConfigChange};
 use crate::engine::{detect_build_type, extract_zip_and_find_root, BuildDetectionResult};
+use crate::notifications::{outbox, CommitMetadata, NotificationPayload};
 use crate::runtime::ContainerStats;
 use crate::AppState;
 
+use crate::api::apps::{
+    check_deploy_lock, suggest_dockerfile, DockerfileSuggestion, UNSUPPORTED_STACK,
+};
 use crate::api::audit::{audit_log, ClientIp};
 use crate::api::error::ApiError;
+use crate::api::system::check_host_cordon;
 use crate::api::teams::log_team_audit;
 use crate::api::validation::validate_uuid;
 
+use super::approval::approval_required;
 use super::freeze::check_freeze_windows;
 
 /// Maximum upload size (100MB)
@@ -124,9 +131,14 @@ pub async fn trigger_deploy(
         .await?
         .ok_or_else(|| ApiError::not_found("App not found"))?;
 
-    // Check if there's already a deployment in progress
+    check_deploy_lock(&app)?;
+    check_host_cordon(&state)?;
+
+    // Check if there's already a deployment in progress (a pre-warm build
+    // doesn't count: the deployment supersedes it)
     let in_progress: Option<Deployment> = sqlx::query_as(
-        "SELECT * FROM deployments WHERE app_id = ? AND status IN ('pending', 'cloning', 'building', 'starting', 'checking')"
+        "SELECT * FROM deployments WHERE app_id = ? AND status IN ('pending', 'cloning', 'building', 'starting', 'checking') \
+         AND id NOT IN (SELECT deployment_id FROM app_prewarm_settings WHERE deployment_id IS NOT NULL)"
     )
     .bind(&app_id)
     .fetch_optional(&state.db)
@@ -221,8 +233,8 @@ pub async fn trigger_deploy(
     };
 
     // Determine if this deployment needs approval
-    // Approval is required if: app.require_approval is set AND user is not admin
-    let needs_approval = app.require_approval != 0 && user.role != "admin";
+    // Approval is required if: the app or its environment requires it AND user is not admin
+    let needs_approval = approval_required(&state.db, &app).await? && user.role != "admin";
 
     let approval_status: Option<&str> = if needs_approval {
         Some("pending")
@@ -503,12 +515,15 @@ pub async fn upload_deploy(
     }
 
     // Check if app exists
-    let _app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
         .bind(&app_id)
         .fetch_optional(&state.db)
         .await?
         .ok_or_else(|| ApiError::not_found("App not found"))?;
 
+    check_deploy_lock(&app)?;
+    check_host_cordon(&state)?;
+
     // Check if there's already a deployment in progress
     let in_progress: Option<Deployment> = sqlx::query_as(
         "SELECT * FROM deployments WHERE app_id = ? AND status IN ('pending', 'cloning', 'building', 'starting', 'checking')"
@@ -723,9 +738,56 @@ pub async fn upload_deploy(
 /// Upload a ZIP file to detect the build type without creating a deployment.
 /// Useful for previewing detection results before deployment.
 pub async fn detect_build_type_from_upload(
-    mut multipart: Multipart,
+    multipart: Multipart,
 ) -> Result<Json<BuildDetectionResult>, ApiError> {
-    // Extract ZIP file from multipart
+    let zip_data = read_zip_field(multipart).await?;
+
+    // Create temp directory
+    let temp_id = Uuid::new_v4().to_string();
+    let work_dir = std::env::temp_dir().join(format!("rivetr-detect-{}", temp_id));
+
+    // Extract ZIP and detect
+    let result = async {
+        let project_root = extract_zip_and_find_root(&zip_data, &work_dir).await?;
+        detect_build_type(&project_root).await
+    }
+    .await;
+
+    // Cleanup temp directory
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+
+    let detection =
+        result.map_err(|e| ApiError::bad_request(format!("Detection failed: {}", e)))?;
+
+    Ok(Json(detection))
+}
+
+/// Generate a recommended Dockerfile for an uploaded ZIP
+/// POST /api/build/dockerfile
+///
+/// Nothing is stored; apps built from a repository can use
+/// POST /api/apps/:id/dockerfile/generate instead.
+pub async fn generate_dockerfile_from_upload(
+    multipart: Multipart,
+) -> Result<Json<DockerfileSuggestion>, ApiError> {
+    let zip_data = read_zip_field(multipart).await?;
+
+    let work_dir = std::env::temp_dir().join(format!("rivetr-dockerfile-{}", Uuid::new_v4()));
+    let result = async {
+        let project_root = extract_zip_and_find_root(&zip_data, &work_dir).await?;
+        suggest_dockerfile(&project_root).await
+    }
+    .await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+
+    let suggestion = result
+        .map_err(|e| ApiError::bad_request(format!("Detection failed: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request(UNSUPPORTED_STACK))?;
+    Ok(Json(suggestion))
+}
+
+/// Read the ZIP file from the "file" (or "zip") field of a multipart upload
+async fn read_zip_field(mut multipart: Multipart) -> Result<Vec<u8>, ApiError> {
     let mut zip_data: Option<Vec<u8>> = None;
 
     while let Some(field) = multipart
@@ -752,26 +814,7 @@ pub async fn detect_build_type_from_upload(
         }
     }
 
-    let zip_data = zip_data.ok_or_else(|| ApiError::bad_request("No ZIP file provided"))?;
-
-    // Create temp directory
-    let temp_id = Uuid::new_v4().to_string();
-    let work_dir = std::env::temp_dir().join(format!("rivetr-detect-{}", temp_id));
-
-    // Extract ZIP and detect
-    let result = async {
-        let project_root = extract_zip_and_find_root(&zip_data, &work_dir).await?;
-        detect_build_type(&project_root).await
-    }
-    .await;
-
-    // Cleanup temp directory
-    let _ = tokio::fs::remove_dir_all(&work_dir).await;
-
-    let detection =
-        result.map_err(|e| ApiError::bad_request(format!("Detection failed: {}", e)))?;
-
-    Ok(Json(detection))
+    zip_data.ok_or_else(|| ApiError::bad_request("No ZIP file provided"))
 }
 
 /// Helper to parse owner/repo from a git URL
@@ -960,6 +1003,10 @@ pub struct DeploymentDiff {
     pub files_changed: Vec<String>,
     /// Commit messages in the range
     pub commit_messages: Vec<String>,
+    /// Configuration changes (env var names, ports, domains, limits, build
+    /// settings) since the previous deployment; None when either deployment
+    /// has no recorded snapshot
+    pub config_changes: Option<Vec<ConfigChange>>,
 }
 
 /// Get the diff between a deployment and the previous successful one
@@ -1094,6 +1141,18 @@ pub async fn get_deployment_diff(
         }
     }
 
+    let config_changes = match previous_deployment_id {
+        Some(ref previous_id) => {
+            let current = config_snapshot::load(&state.db, &deployment_id).await;
+            let previous = config_snapshot::load(&state.db, previous_id).await;
+            match (current, previous) {
+                (Ok(Some(current)), Ok(Some(previous))) => Some(current.changes_since(&previous)),
+                _ => None,
+            }
+        }
+        None => None,
+    };
+
     Ok(Json(DeploymentDiff {
         deployment_id,
         previous_deployment_id,
@@ -1103,6 +1162,7 @@ pub async fn get_deployment_diff(
         summary,
         files_changed,
         commit_messages,
+        config_changes,
     }))
 }
 
@@ -1123,20 +1183,52 @@ pub async fn cancel_deployment(
         return Err(ApiError::validation_field("deployment_id", e));
     }
 
-    // Signal cancellation if the token exists
-    if let Some(token) = state.deployment_cancel_tokens.get(&deployment_id) {
-        token.cancel();
+    cancel_active_deployment(&state, &user, client_ip.as_deref(), &app_id, &deployment_id).await
+}
+
+/// Cancel an in-progress deployment by ID alone.
+///
+/// POST /api/deployments/:id/cancel
+pub async fn cancel_deployment_by_id(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Path(deployment_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if let Err(e) = validate_uuid(&deployment_id, "deployment_id") {
+        return Err(ApiError::validation_field("deployment_id", e));
     }
 
+    let app_id: String = sqlx::query_scalar("SELECT app_id FROM deployments WHERE id = ?")
+        .bind(&deployment_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| ApiError::database(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Deployment not found"))?;
+
+    cancel_active_deployment(&state, &user, client_ip.as_deref(), &app_id, &deployment_id).await
+}
+
+/// Mark a deployment of `app_id` as cancelled and abort its pipeline. The
+/// engine kills the clone/build processes and removes what the deployment left
+/// behind (temp directories, a started container).
+async fn cancel_active_deployment(
+    state: &AppState,
+    user: &User,
+    client_ip: Option<&str>,
+    app_id: &str,
+    deployment_id: &str,
+) -> Result<StatusCode, ApiError> {
     // Mark as cancelled in DB (only if the deployment belongs to this app and is active)
     let now = chrono::Utc::now().to_rfc3339();
     let rows_affected = sqlx::query(
-        "UPDATE deployments SET status = 'cancelled', finished_at = ?, error_message = 'Cancelled by user'
+        "UPDATE deployments SET status = 'cancelled', cancelled_at = ?, finished_at = ?, error_message = 'Cancelled by user'
          WHERE id = ? AND app_id = ? AND status IN ('pending', 'cloning', 'building', 'starting', 'checking')",
     )
     .bind(&now)
-    .bind(&deployment_id)
-    .bind(&app_id)
+    .bind(&now)
+    .bind(deployment_id)
+    .bind(app_id)
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::database(e.to_string()))?
@@ -1148,8 +1240,8 @@ pub async fn cancel_deployment(
         // a missing resource).
         let exists: Option<String> =
             sqlx::query_scalar("SELECT status FROM deployments WHERE id = ? AND app_id = ?")
-                .bind(&deployment_id)
-                .bind(&app_id)
+                .bind(deployment_id)
+                .bind(app_id)
                 .fetch_optional(&state.db)
                 .await
                 .map_err(|e| ApiError::database(e.to_string()))?;
@@ -1162,14 +1254,50 @@ pub async fn cancel_deployment(
         };
     }
 
+    // Abort the pipeline only once the status is stored, so the engine records
+    // the deployment as cancelled rather than failed
+    if let Some(token) = state.deployment_cancel_tokens.get(deployment_id) {
+        token.cancel();
+    }
+
+    DeploymentEvent::record(
+        &state.db,
+        deployment_id,
+        "cancelled",
+        Some("Cancelled by user"),
+    )
+    .await;
+
+    let app_name: Option<String> = sqlx::query_scalar("SELECT name FROM apps WHERE id = ?")
+        .bind(app_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    if let Some(app_name) = app_name {
+        let payload = NotificationPayload::deployment_event(
+            NotificationEventType::DeploymentCancelled,
+            app_id.to_string(),
+            app_name.clone(),
+            deployment_id.to_string(),
+            "cancelled".to_string(),
+            format!("Deployment cancelled for {}", app_name),
+            Some("Cancelled by user".to_string()),
+        )
+        .with_commit(CommitMetadata::load(&state.db, deployment_id).await);
+        if let Err(e) = outbox::enqueue(&state.db, &payload).await {
+            tracing::warn!(error = %e, "Failed to queue deployment_cancelled notification");
+        }
+    }
+
     audit_log(
-        &state,
+        state,
         actions::DEPLOYMENT_CANCEL,
         resource_types::DEPLOYMENT,
-        Some(&deployment_id),
+        Some(deployment_id),
         None,
         Some(&user.id),
-        client_ip.as_deref(),
+        client_ip,
         Some(serde_json::json!({ "app_id": app_id })),
     )
     .await;
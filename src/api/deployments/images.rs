@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::db::{App, Deployment};
+use crate::engine::version_repository;
+use crate::AppState;
+
+use crate::api::error::ApiError;
+use crate::api::validation::validate_uuid;
+
+/// A locally available versioned image of an app
+#[derive(Debug, Serialize)]
+pub struct AppImage {
+    /// Local reference, e.g. "rivetr/web:3f2a9c1"
+    pub tag: String,
+    pub image_id: String,
+    /// Creation time of the image (RFC 3339)
+    pub created_at: Option<String>,
+    pub size_bytes: i64,
+    /// Newest deployment that built this tag; pass it as `target_deployment_id`
+    /// to the rollback endpoint to go back to this image
+    pub deployment_id: Option<String>,
+    pub commit_sha: Option<String>,
+    pub commit_message: Option<String>,
+    pub deployment_status: Option<String>,
+}
+
+/// List the versioned images of an app available on this host, newest first
+/// GET /api/apps/:id/images
+pub async fn list_app_images(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<AppImage>>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+
+    let repository = version_repository(&app.name);
+    let mut images = state
+        .runtime
+        .list_images(&repository)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to list images: {}", e)))?;
+    images.sort_by_key(|image| std::cmp::Reverse(image.created));
+
+    // Newest first, so the first deployment seen for a tag is the newest one
+    let deployments = sqlx::query_as::<_, Deployment>(
+        "SELECT * FROM deployments WHERE app_id = ? AND version_tag IS NOT NULL \
+         ORDER BY started_at DESC",
+    )
+    .bind(&app_id)
+    .fetch_all(&state.db)
+    .await?;
+    let mut by_tag: HashMap<String, Deployment> = HashMap::new();
+    for deployment in deployments {
+        if let Some(tag) = deployment.version_tag.clone() {
+            by_tag.entry(tag).or_insert(deployment);
+        }
+    }
+
+    let prefix = format!("{}:", repository);
+    let latest = format!("{}latest", prefix);
+    let mut result = Vec::new();
+    for image in images {
+        for tag in image.tags {
+            // `latest` always points at one of the versioned tags
+            if !tag.starts_with(&prefix) || tag == latest {
+                continue;
+            }
+            let deployment = by_tag.get(&tag);
+            result.push(AppImage {
+                image_id: image.id.clone(),
+                created_at: chrono::DateTime::from_timestamp(image.created, 0)
+                    .map(|t| t.to_rfc3339()),
+                size_bytes: image.size,
+                deployment_id: deployment.map(|d| d.id.clone()),
+                commit_sha: deployment.and_then(|d| d.commit_sha.clone()),
+                commit_message: deployment.and_then(|d| d.commit_message.clone()),
+                deployment_status: deployment.map(|d| d.status.clone()),
+                tag,
+            });
+        }
+    }
+
+    Ok(Json(result))
+}
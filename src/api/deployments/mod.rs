@@ -3,17 +3,32 @@
 //! Organized into focused submodules:
 //! - `handlers`  — list, get, trigger, upload, stats, commits, tags
 //! - `rollback`  — rollback to previous deployment
+//! - `diagnostics` — failure diagnostics bundle download
 //! - `approval`  — approve/reject pending deployments
 //! - `freeze`    — deployment freeze windows (CRUD + check helper)
+//! - `images`    — locally available versioned images, for rollback selection
+//! - `stats`     — deployment outcomes and durations over a period
+//! - `timeline`  — status transitions with time spent in each
+//! - `vulnerabilities` — image vulnerability scan report
 //! - `shared`    — shared helpers (encryption key)
 
 mod approval;
+mod diagnostics;
 mod freeze;
 mod handlers;
+mod images;
 mod rollback;
 mod shared;
+mod stats;
+mod timeline;
+mod vulnerabilities;
 
 pub use approval::*;
+pub use diagnostics::*;
 pub use freeze::*;
 pub use handlers::*;
+pub use images::*;
 pub use rollback::*;
+pub use stats::*;
+pub use timeline::*;
+pub use vulnerabilities::*;
@@ -8,9 +8,10 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::{
-    actions, resource_types, App, Deployment, TeamAuditAction, TeamAuditResourceType, User,
+    actions, resource_types, App, Deployment, DeploymentEvent, TeamAuditAction,
+    TeamAuditResourceType, User,
 };
-use crate::engine::run_rollback;
+use crate::engine::{run_instant_rollback, run_rollback};
 use crate::proxy::Backend;
 use crate::AppState;
 
@@ -145,33 +146,44 @@ pub async fn rollback_deployment(
     let target_deployment_clone = target_deployment.clone();
     let app_clone = app.clone();
     let encryption_key = get_encryption_key(&state);
+    let registry = state.config.registry.clone();
 
     let app_id_clone = app.id.clone();
     tokio::spawn(async move {
-        match run_rollback(
+        // Switch straight back to the target's container while it is still kept
+        // running after a blue-green switchover; otherwise start its image again
+        let result = match run_instant_rollback(
             &db,
             runtime.clone(),
             &rollback_id_clone,
             &target_deployment_clone,
             &app_clone,
-            encryption_key.as_ref(),
         )
         .await
         {
+            Ok(Some(result)) => Ok(result),
+            Ok(None) => {
+                run_rollback(
+                    &db,
+                    runtime.clone(),
+                    &rollback_id_clone,
+                    &target_deployment_clone,
+                    &app_clone,
+                    &registry,
+                    encryption_key.as_ref(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
             Ok(result) => {
                 // Mark any previously-running deployments for this app as 'replaced'
                 // (not 'failed').  The previous deployment didn't fail — we deliberately
                 // swapped it out for the rollback, so 'replaced' is the correct terminal
                 // status (matches what regular successful deploys do in src/engine/mod.rs).
-                let _ = sqlx::query(
-                    "UPDATE deployments SET status = 'replaced', finished_at = ?
-                     WHERE app_id = ? AND status = 'running' AND id != ?",
-                )
-                .bind(chrono::Utc::now().to_rfc3339())
-                .bind(&app_id_clone)
-                .bind(&rollback_id_clone)
-                .execute(&db)
-                .await;
+                let _ =
+                    Deployment::mark_others_replaced(&db, &app_id_clone, &rollback_id_clone).await;
 
                 // Update proxy routes on successful rollback for all domains
                 if let Some(port) = result.port {
@@ -205,6 +217,8 @@ pub async fn rollback_deployment(
                 // Zero-downtime: stop old containers AFTER proxy routes are updated.
                 // The rollback container is already serving traffic; old one can be torn down.
                 if !result.old_container_ids.is_empty() {
+                    // Let requests already in flight to the old container finish
+                    routes.load().drain(&result.old_container_ids).await;
                     tracing::info!(
                         old_containers = ?result.old_container_ids,
                         "Stopping old containers after rollback proxy route swap (zero-downtime)"
@@ -228,6 +242,8 @@ pub async fn rollback_deployment(
                 .bind(&rollback_id_clone)
                 .execute(&db)
                 .await;
+                DeploymentEvent::record(&db, &rollback_id_clone, "failed", Some(&e.to_string()))
+                    .await;
             }
         }
     });
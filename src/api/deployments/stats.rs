@@ -0,0 +1,260 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::utils::time::parse_time;
+use crate::AppState;
+
+use crate::api::error::ApiError;
+use crate::api::validation::validate_uuid;
+
+/// Period covered when the request doesn't set one
+const DEFAULT_PERIOD: &str = "7d";
+
+#[derive(Debug, Deserialize)]
+pub struct DeployStatsQuery {
+    /// `24h`, `7d`, `30d` or `90d` (default `7d`)
+    pub period: Option<String>,
+}
+
+/// A deployment in the stats period
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DeploymentOutcome {
+    pub id: String,
+    pub status: String,
+    pub started_at: String,
+    /// Set when the deployment went live
+    pub built_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub error_message: Option<String>,
+}
+
+/// The most recent failed deployment
+#[derive(Debug, Serialize)]
+pub struct LastFailure {
+    pub deployment_id: String,
+    pub started_at: String,
+    pub error_message: Option<String>,
+    /// Pipeline phase the deployment failed in, when recorded
+    pub phase: Option<String>,
+}
+
+/// Average duration and outcomes of one pipeline phase
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PhaseStats {
+    pub phase: String,
+    pub count: i64,
+    pub failed: i64,
+    pub avg_duration_secs: Option<f64>,
+}
+
+/// Response for GET /api/apps/:id/deploy-stats
+#[derive(Debug, Serialize)]
+pub struct DeployStats {
+    pub app_id: String,
+    pub period: String,
+    pub since: String,
+    pub total: i64,
+    /// Deployments that went live
+    pub succeeded: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+    /// Succeeded share of the deployments that succeeded or failed;
+    /// `None` when there were none
+    pub success_rate_percent: Option<f64>,
+    /// Average time from taking a build slot to going live (succeeded) or
+    /// failing (failed)
+    pub avg_duration_secs: Option<f64>,
+    pub last_failure: Option<LastFailure>,
+    /// Pipeline phases in pipeline order
+    pub phases: Vec<PhaseStats>,
+}
+
+fn period_hours(period: &str) -> Option<i64> {
+    match period {
+        "24h" => Some(24),
+        "7d" => Some(168),
+        "30d" => Some(720),
+        "90d" => Some(2160),
+        _ => None,
+    }
+}
+
+/// Outcome counts, success rate and average duration of deployments
+#[derive(Debug, Default, PartialEq)]
+pub struct OutcomeSummary {
+    pub succeeded: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+    pub success_rate_percent: Option<f64>,
+    pub avg_duration_secs: Option<f64>,
+}
+
+/// Count the outcomes of deployments and average how long the completed
+/// ones took
+pub fn summarize(deployments: &[DeploymentOutcome]) -> OutcomeSummary {
+    let mut succeeded = 0;
+    let mut failed = 0;
+    let mut cancelled = 0;
+    let mut durations = Vec::new();
+
+    for deployment in deployments {
+        let ended_at = if deployment.built_at.is_some() {
+            succeeded += 1;
+            deployment.built_at.as_deref()
+        } else if deployment.status == "failed" {
+            failed += 1;
+            deployment.finished_at.as_deref()
+        } else {
+            if deployment.status == "cancelled" {
+                cancelled += 1;
+            }
+            None
+        };
+        let duration = ended_at
+            .and_then(parse_time)
+            .zip(parse_time(&deployment.started_at))
+            .map(|(end, start)| (end - start).num_milliseconds() as f64 / 1000.0)
+            .filter(|secs| *secs >= 0.0);
+        durations.extend(duration);
+    }
+
+    OutcomeSummary {
+        succeeded,
+        failed,
+        cancelled,
+        success_rate_percent: (succeeded + failed > 0)
+            .then(|| succeeded as f64 / (succeeded + failed) as f64 * 100.0),
+        avg_duration_secs: (!durations.is_empty())
+            .then(|| durations.iter().sum::<f64>() / durations.len() as f64),
+    }
+}
+
+/// Summarize an app's deployments over a period: outcomes, success rate,
+/// average duration, per-phase durations and the last failure
+/// GET /api/apps/:id/deploy-stats
+pub async fn get_deploy_stats(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Query(query): Query<DeployStatsQuery>,
+) -> Result<Json<DeployStats>, ApiError> {
+    if let Err(e) = validate_uuid(&app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    let period = query.period.unwrap_or_else(|| DEFAULT_PERIOD.to_string());
+    let hours = period_hours(&period).ok_or_else(|| {
+        ApiError::validation_field("period", "period must be one of 24h, 7d, 30d or 90d")
+    })?;
+
+    let app_exists: Option<String> = sqlx::query_scalar("SELECT id FROM apps WHERE id = ?")
+        .bind(&app_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if app_exists.is_none() {
+        return Err(ApiError::not_found("App not found"));
+    }
+
+    let since = (Utc::now() - chrono::Duration::hours(hours)).to_rfc3339();
+    let deployments: Vec<DeploymentOutcome> = sqlx::query_as(
+        "SELECT id, status, started_at, built_at, finished_at, error_message FROM deployments \
+         WHERE app_id = ? AND julianday(started_at) >= julianday(?) ORDER BY started_at DESC",
+    )
+    .bind(&app_id)
+    .bind(&since)
+    .fetch_all(&state.db)
+    .await?;
+    let summary = summarize(&deployments);
+
+    let last_failure = match deployments.iter().find(|d| d.status == "failed") {
+        Some(deployment) => {
+            let phase: Option<String> = sqlx::query_scalar(
+                "SELECT phase FROM deployment_phases WHERE deployment_id = ? AND status = 'failed' \
+                 ORDER BY id DESC LIMIT 1",
+            )
+            .bind(&deployment.id)
+            .fetch_optional(&state.db)
+            .await?;
+            Some(LastFailure {
+                deployment_id: deployment.id.clone(),
+                started_at: deployment.started_at.clone(),
+                error_message: deployment.error_message.clone(),
+                phase,
+            })
+        }
+        None => None,
+    };
+
+    let phases: Vec<PhaseStats> = sqlx::query_as(
+        "SELECT p.phase, COUNT(*) AS count, \
+         SUM(CASE WHEN p.status = 'failed' THEN 1 ELSE 0 END) AS failed, \
+         AVG(p.duration_ms) / 1000.0 AS avg_duration_secs \
+         FROM deployment_phases p JOIN deployments d ON d.id = p.deployment_id \
+         WHERE d.app_id = ? AND julianday(d.started_at) >= julianday(?) \
+         GROUP BY p.phase ORDER BY MIN(p.id)",
+    )
+    .bind(&app_id)
+    .bind(&since)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(DeployStats {
+        app_id,
+        period,
+        since,
+        total: deployments.len() as i64,
+        succeeded: summary.succeeded,
+        failed: summary.failed,
+        cancelled: summary.cancelled,
+        success_rate_percent: summary.success_rate_percent,
+        avg_duration_secs: summary.avg_duration_secs,
+        last_failure,
+        phases,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(
+        status: &str,
+        built_at: Option<&str>,
+        finished_at: Option<&str>,
+    ) -> DeploymentOutcome {
+        DeploymentOutcome {
+            id: String::new(),
+            status: status.to_string(),
+            started_at: "2026-01-01T00:00:00+00:00".to_string(),
+            built_at: built_at.map(str::to_string),
+            finished_at: finished_at.map(str::to_string),
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let deployments = [
+            deployment("running", Some("2026-01-01T00:01:00+00:00"), None),
+            deployment("replaced", Some("2026-01-01T00:03:00+00:00"), None),
+            deployment("failed", None, Some("2026-01-01T00:02:00+00:00")),
+            deployment("cancelled", None, Some("2026-01-01T00:00:30+00:00")),
+            deployment("building", None, None),
+        ];
+        let summary = summarize(&deployments);
+        assert_eq!(
+            (summary.succeeded, summary.failed, summary.cancelled),
+            (2, 1, 1)
+        );
+        assert!((summary.success_rate_percent.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.avg_duration_secs, Some(120.0));
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        assert_eq!(summarize(&[]), OutcomeSummary::default());
+    }
+}
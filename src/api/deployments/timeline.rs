@@ -0,0 +1,177 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::db::{Deployment, DeploymentEvent, DeploymentPhase};
+use crate::AppState;
+
+use crate::api::error::ApiError;
+use crate::api::validation::validate_uuid;
+
+/// Statuses after which a deployment no longer changes
+const TERMINAL_STATUSES: &[&str] = &["failed", "stopped", "replaced", "cancelled"];
+
+/// One step of a deployment timeline
+#[derive(Debug, Serialize)]
+pub struct TimelineStep {
+    pub status: String,
+    pub message: Option<String>,
+    pub started_at: String,
+    /// Time spent in this status: until the next transition, or until now for a
+    /// non-terminal current status. `None` for a terminal final status.
+    pub duration_ms: Option<i64>,
+}
+
+/// Response for GET /api/deployments/:id/timeline
+#[derive(Debug, Serialize)]
+pub struct DeploymentTimeline {
+    pub deployment_id: String,
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub steps: Vec<TimelineStep>,
+    /// Pipeline phases (clone, build, test, push, start, healthcheck) with
+    /// their durations, in pipeline order
+    pub phases: Vec<DeploymentPhase>,
+}
+
+/// Get the status transitions of a deployment with time spent in each
+/// GET /api/deployments/:id/timeline
+pub async fn get_deployment_timeline(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<DeploymentTimeline>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "deployment_id") {
+        return Err(ApiError::validation_field("deployment_id", e));
+    }
+
+    let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Deployment not found"))?;
+
+    let events = DeploymentEvent::list(&state.db, &id).await?;
+    let steps = build_steps(&deployment.started_at, events, Utc::now());
+    let phases = DeploymentPhase::list(&state.db, &id).await?;
+
+    Ok(Json(DeploymentTimeline {
+        deployment_id: deployment.id,
+        status: deployment.status,
+        started_at: deployment.started_at,
+        finished_at: deployment.finished_at,
+        steps,
+        phases,
+    }))
+}
+
+/// Turn recorded events into timeline steps. Deployments are created as
+/// `pending` without an event, so that step is synthesized from `started_at`.
+fn build_steps(
+    started_at: &str,
+    events: Vec<DeploymentEvent>,
+    now: DateTime<Utc>,
+) -> Vec<TimelineStep> {
+    let mut steps: Vec<TimelineStep> = Vec::with_capacity(events.len() + 1);
+    if events.first().map(|e| e.status.as_str()) != Some("pending") {
+        steps.push(TimelineStep {
+            status: "pending".to_string(),
+            message: None,
+            started_at: started_at.to_string(),
+            duration_ms: None,
+        });
+    }
+    steps.extend(events.into_iter().map(|e| TimelineStep {
+        status: e.status,
+        message: e.message,
+        started_at: e.created_at,
+        duration_ms: None,
+    }));
+
+    let starts: Vec<Option<DateTime<Utc>>> = steps
+        .iter()
+        .map(|s| parse_timestamp(&s.started_at))
+        .collect();
+    let last = steps.len() - 1;
+    for (i, step) in steps.iter_mut().enumerate() {
+        let end = if i < last {
+            starts[i + 1]
+        } else if TERMINAL_STATUSES.contains(&step.status.as_str()) {
+            None
+        } else {
+            Some(now)
+        };
+        step.duration_ms = match (starts[i], end) {
+            (Some(start), Some(end)) => Some((end - start).num_milliseconds().max(0)),
+            _ => None,
+        };
+    }
+
+    steps
+}
+
+/// Parse RFC 3339 timestamps and SQLite's `datetime('now')` format (UTC)
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(status: &str, created_at: &str) -> DeploymentEvent {
+        DeploymentEvent {
+            id: 0,
+            deployment_id: "d".to_string(),
+            status: status.to_string(),
+            message: None,
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_steps() {
+        let now = parse_timestamp("2024-01-01T00:05:00Z").unwrap();
+        let steps = build_steps(
+            "2024-01-01T00:00:00Z",
+            vec![
+                event("cloning", "2024-01-01T00:00:02Z"),
+                event("building", "2024-01-01 00:00:10"),
+                event("running", "2024-01-01T00:01:00Z"),
+            ],
+            now,
+        );
+        let summary: Vec<_> = steps
+            .iter()
+            .map(|s| (s.status.as_str(), s.duration_ms))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("pending", Some(2_000)),
+                ("cloning", Some(8_000)),
+                ("building", Some(50_000)),
+                ("running", Some(240_000)),
+            ]
+        );
+
+        let steps = build_steps(
+            "2024-01-01T00:00:00Z",
+            vec![event("failed", "2024-01-01T00:00:30Z")],
+            now,
+        );
+        assert_eq!(steps[0].duration_ms, Some(30_000));
+        assert_eq!(steps[1].duration_ms, None);
+    }
+}
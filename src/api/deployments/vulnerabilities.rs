@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::engine::scan::{self, ScanReport};
+use crate::AppState;
+
+use crate::api::error::ApiError;
+use crate::api::validation::validate_uuid;
+
+/// Get the vulnerability scan report for a deployment's image
+/// GET /api/deployments/:id/vulnerabilities
+pub async fn get_deployment_vulnerabilities(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanReport>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "deployment_id") {
+        return Err(ApiError::validation_field("deployment_id", e));
+    }
+
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM deployments WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("Deployment not found"));
+    }
+
+    let report = scan::load(&state.db, &id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to load scan report: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("This deployment's image was not scanned"))?;
+
+    Ok(Json(report))
+}
@@ -116,8 +116,8 @@ pub async fn create_environment(
 
     sqlx::query(
         r#"
-        INSERT INTO environments (id, project_id, name, description, is_default, created_at, updated_at)
-        VALUES (?, ?, ?, ?, 0, ?, ?)
+        INSERT INTO environments (id, project_id, name, description, is_default, created_at, updated_at, require_approval)
+        VALUES (?, ?, ?, ?, 0, ?, ?, ?)
         "#,
     )
     .bind(&id)
@@ -126,6 +126,7 @@ pub async fn create_environment(
     .bind(&req.description)
     .bind(&now)
     .bind(&now)
+    .bind(req.require_approval as i32)
     .execute(&state.db)
     .await
     .map_err(|e| {
@@ -190,12 +191,14 @@ pub async fn update_environment(
         UPDATE environments SET
             name = COALESCE(?, name),
             description = COALESCE(?, description),
+            require_approval = COALESCE(?, require_approval),
             updated_at = ?
         WHERE id = ?
         "#,
     )
     .bind(&req.name)
     .bind(&req.description)
+    .bind(req.require_approval.map(i32::from))
     .bind(&now)
     .bind(&id)
     .execute(&state.db)
@@ -11,6 +11,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::i18n;
+
 /// Error codes for API responses
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -191,7 +193,11 @@ impl ApiError {
                 .cloned()
                 .unwrap_or_else(|| "Validation failed".to_string())
         } else {
-            format!("Validation failed for {} fields", errors.len())
+            i18n::t_args(
+                i18n::request_locale(),
+                "error.validation_failed_fields",
+                &[("count", &errors.len().to_string())],
+            )
         };
 
         Self::new(ErrorCode::ValidationError, message).with_validation_errors(errors)
@@ -226,12 +232,29 @@ impl ApiError {
 }
 
 impl IntoResponse for ApiError {
+    /// Messages are translated into the request's locale (see [`crate::i18n`])
     fn into_response(self) -> Response {
+        let locale = i18n::request_locale();
+        let details = match self.details {
+            Some(ErrorDetails::ValidationErrors(errors)) => Some(ErrorDetails::ValidationErrors(
+                errors
+                    .into_iter()
+                    .map(|(field, messages)| {
+                        let messages = messages
+                            .iter()
+                            .map(|message| i18n::localize_text(locale, message))
+                            .collect();
+                        (field, messages)
+                    })
+                    .collect(),
+            )),
+            details => details,
+        };
         let response = ErrorResponse {
             error: ErrorBody {
                 code: self.code.as_str().to_string(),
-                message: self.message,
-                details: self.details,
+                message: i18n::localize_text(locale, &self.message),
+                details,
             },
         };
 
@@ -0,0 +1,177 @@
+//! Forward authentication API endpoints for applications.
+//!
+//! Controls whether the proxy requires visitors to sign in (with Rivetr user
+//! accounts or a configured OIDC provider) before requests reach an app, and
+//! which emails or domains are allowed in.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::db::{
+    generate_cookie_secret, AppForwardAuth, UpdateForwardAuthRequest, FORWARD_AUTH_PROVIDERS,
+    FORWARD_AUTH_PROVIDER_OIDC,
+};
+use crate::AppState;
+
+use super::common::{ensure_app_exists, refresh_proxy_routes};
+use super::error::ApiError;
+
+/// Longest allowed proxy session (30 days)
+const MAX_SESSION_HOURS: i64 = 720;
+
+/// Get forward auth settings for an app
+pub async fn get_forward_auth(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<AppForwardAuth>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let settings = AppForwardAuth::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppForwardAuth::default_for(&app_id));
+
+    Ok(Json(settings))
+}
+
+/// Update forward auth settings for an app
+pub async fn update_forward_auth(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdateForwardAuthRequest>,
+) -> Result<Json<AppForwardAuth>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let existing = AppForwardAuth::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppForwardAuth::default_for(&app_id));
+
+    let enabled = req.enabled.unwrap_or(existing.enabled != 0);
+    let provider = req.provider.clone().unwrap_or(existing.provider);
+    if !FORWARD_AUTH_PROVIDERS.contains(&provider.as_str()) {
+        return Err(ApiError::validation_field(
+            "provider",
+            format!(
+                "Provider must be one of: {}",
+                FORWARD_AUTH_PROVIDERS.join(", ")
+            ),
+        ));
+    }
+
+    let oidc_provider_id = match req.oidc_provider_id.clone() {
+        Some(id) if id.trim().is_empty() => None,
+        Some(id) => Some(id.trim().to_string()),
+        None => existing.oidc_provider_id,
+    };
+    if provider == FORWARD_AUTH_PROVIDER_OIDC {
+        let Some(ref id) = oidc_provider_id else {
+            return Err(ApiError::validation_field(
+                "oidc_provider_id",
+                "An OIDC provider is required when signing in with OIDC",
+            ));
+        };
+        let exists: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM oidc_providers WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&state.db)
+                .await?;
+        if exists.is_none() {
+            return Err(ApiError::validation_field(
+                "oidc_provider_id",
+                "OIDC provider not found",
+            ));
+        }
+    }
+
+    let allowed_emails = req
+        .allowed_emails
+        .clone()
+        .unwrap_or(existing.allowed_emails);
+    if let Some(invalid) = allowed_emails
+        .split(['\n', ','])
+        .map(str::trim)
+        .find(|e| !e.is_empty() && (!e.contains('@') || e.ends_with('@') || e.contains(' ')))
+    {
+        return Err(ApiError::validation_field(
+            "allowed_emails",
+            format!("'{}' is not an email address or @domain entry", invalid),
+        ));
+    }
+
+    let session_hours = req.session_hours.unwrap_or(existing.session_hours);
+    if !(1..=MAX_SESSION_HOURS).contains(&session_hours) {
+        return Err(ApiError::validation_field(
+            "session_hours",
+            format!(
+                "Session length must be between 1 and {} hours",
+                MAX_SESSION_HOURS
+            ),
+        ));
+    }
+
+    let cookie_secret = if req.reset_sessions {
+        generate_cookie_secret()
+    } else {
+        existing.cookie_secret
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_forward_auth
+            (app_id, enabled, provider, oidc_provider_id, allowed_emails, session_hours,
+             cookie_secret, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(app_id) DO UPDATE SET
+            enabled = excluded.enabled,
+            provider = excluded.provider,
+            oidc_provider_id = excluded.oidc_provider_id,
+            allowed_emails = excluded.allowed_emails,
+            session_hours = excluded.session_hours,
+            cookie_secret = excluded.cookie_secret,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(enabled)
+    .bind(&provider)
+    .bind(&oidc_provider_id)
+    .bind(allowed_emails.trim())
+    .bind(session_hours)
+    .bind(&cookie_secret)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update forward auth settings: {}", e);
+        ApiError::database("Failed to update forward auth settings")
+    })?;
+
+    let settings = AppForwardAuth::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Forward auth settings missing after update"))?;
+
+    apply_forward_auth(&state, &settings).await;
+
+    Ok(Json(settings))
+}
+
+/// Apply forward auth settings to the app's live routes.
+async fn apply_forward_auth(state: &Arc<AppState>, settings: &AppForwardAuth) {
+    let policy = settings.to_policy();
+    let domains = refresh_proxy_routes(state, &settings.app_id, false, |routes, domain, _| {
+        routes.update_forward_auth(domain, policy.clone());
+    })
+    .await;
+
+    tracing::info!(
+        app_id = %settings.app_id,
+        domains = ?domains,
+        enabled = policy.is_some(),
+        provider = %settings.provider,
+        "Forward auth settings refreshed in proxy route table"
+    );
+}
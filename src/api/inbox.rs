@@ -0,0 +1,148 @@
+//! In-app notification center API endpoints.
+//!
+//! Each user has an inbox of notification events for the apps they can see,
+//! filled by the notification outbox. New notifications are pushed over the
+//! user's event stream along with the unread count.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::stream::Stream;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::db::{InboxNotification, InboxResponse, User};
+use crate::notifications::inbox;
+use crate::AppState;
+
+use super::error::ApiError;
+
+/// Notifications returned when no limit is given
+const DEFAULT_INBOX_LIMIT: i64 = 50;
+/// Most notifications returned by one request
+const MAX_INBOX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct InboxQuery {
+    /// Only return unread notifications
+    #[serde(default)]
+    pub unread: bool,
+    pub limit: Option<i64>,
+}
+
+/// List the current user's notifications, newest first, with the unread count
+pub async fn list_inbox(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Query(query): Query<InboxQuery>,
+) -> Result<Json<InboxResponse>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_INBOX_LIMIT)
+        .clamp(1, MAX_INBOX_LIMIT);
+    let notifications =
+        InboxNotification::list_for_user(&state.db, &user.id, query.unread, limit).await?;
+    let unread_count = InboxNotification::unread_count(&state.db, &user.id).await?;
+
+    Ok(Json(InboxResponse {
+        notifications,
+        unread_count,
+    }))
+}
+
+/// Mark one of the current user's notifications read
+pub async fn mark_read(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Path(id): Path<String>,
+) -> Result<Json<InboxResponse>, ApiError> {
+    if !InboxNotification::mark_read(&state.db, &user.id, &id).await? {
+        return Err(ApiError::not_found("Notification not found"));
+    }
+    let notification = InboxNotification::get(&state.db, &user.id, &id).await?;
+    let unread_count = InboxNotification::unread_count(&state.db, &user.id).await?;
+
+    Ok(Json(InboxResponse {
+        notifications: notification.into_iter().collect(),
+        unread_count,
+    }))
+}
+
+/// Mark all of the current user's notifications read
+pub async fn mark_all_read(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let marked = InboxNotification::mark_all_read(&state.db, &user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "marked": marked,
+        "unread_count": 0,
+    })))
+}
+
+/// Stream the current user's events via SSE.
+///
+/// Starts with a `connected` event carrying the unread count, then sends a
+/// `notification` event (with the new unread count) whenever a notification
+/// lands in the user's inbox.
+pub async fn event_stream(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    // Subscribe before reading the count so nothing falls in between
+    let mut rx = inbox::subscribe();
+    let unread_count = InboxNotification::unread_count(&state.db, &user.id).await?;
+
+    let stream = async_stream::stream! {
+        yield Ok(json_event(serde_json::json!({
+            "type": "connected",
+            "unread_count": unread_count,
+        })));
+
+        loop {
+            match rx.recv().await {
+                Ok(notification) => {
+                    if notification.user_id != user.id {
+                        continue;
+                    }
+                    let unread_count = InboxNotification::unread_count(&state.db, &user.id)
+                        .await
+                        .unwrap_or_default();
+                    yield Ok(json_event(serde_json::json!({
+                        "type": "notification",
+                        "notification": notification,
+                        "unread_count": unread_count,
+                    })));
+                }
+                // Missed some: send the current count so the badge stays right
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    if let Ok(unread_count) =
+                        InboxNotification::unread_count(&state.db, &user.id).await
+                    {
+                        yield Ok(json_event(serde_json::json!({
+                            "type": "unread_count",
+                            "unread_count": unread_count,
+                        })));
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+fn json_event(event: serde_json::Value) -> Event {
+    Event::default().data(event.to_string())
+}
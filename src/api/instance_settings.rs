@@ -8,7 +8,7 @@ use serde::Serialize;
 use std::sync::Arc;
 
 use crate::db::{InstanceSettings, UpdateInstanceSettingsRequest};
-use crate::proxy::Backend;
+use crate::proxy::{Backend, RouteBatch};
 use crate::AppState;
 
 /// Build an AI client from saved instance settings (may return None if no key set).
@@ -116,12 +116,13 @@ pub async fn update_instance_settings(
     let old_domain = old_settings.instance_domain.clone();
 
     if old_domain != new_domain {
-        let route_table = state.routes.load();
+        // Remove the old route and add the new one in a single swap
+        let mut batch = RouteBatch::new();
 
         // Remove the old domain route if there was one.
         if let Some(ref old) = old_domain {
             if !old.is_empty() {
-                route_table.remove_route(old);
+                batch.remove_route(old.as_str());
                 tracing::info!(domain = %old, "Removed old instance domain proxy route");
             }
         }
@@ -134,7 +135,7 @@ pub async fn update_instance_settings(
                     "127.0.0.1".to_string(),
                     state.config.server.api_port,
                 );
-                route_table.add_route(new.clone(), backend);
+                batch.add_route(new.clone(), backend);
                 tracing::info!(
                     domain = %new,
                     port = state.config.server.api_port,
@@ -146,11 +147,10 @@ pub async fn update_instance_settings(
                     state.config.proxy.acme_enabled && state.config.proxy.acme_email.is_some();
 
                 if acme_enabled {
-                    let acme_cfg = crate::proxy::AcmeConfig {
-                        email: state.config.proxy.acme_email.clone().unwrap_or_default(),
-                        cache_dir: state.config.proxy.acme_cache_dir.clone(),
-                        staging: state.config.proxy.acme_staging,
-                    };
+                    let acme_cfg = crate::proxy::AcmeConfig::from_proxy_config(
+                        &state.config.proxy,
+                        state.config.proxy.acme_email.clone().unwrap_or_default(),
+                    );
                     let domain = new.clone();
 
                     tokio::spawn(async move {
@@ -202,6 +202,7 @@ pub async fn update_instance_settings(
                 }
             }
         }
+        state.routes.load().apply(&batch);
     }
 
     // Hot-reload AI client if the key, provider, or model changed.
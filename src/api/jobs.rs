@@ -8,36 +8,16 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use cron::Schedule;
 use serde::Deserialize;
-use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::db::{
     CreateScheduledJobRequest, ScheduledJob, ScheduledJobResponse, ScheduledJobRun,
     ScheduledJobRunResponse, UpdateScheduledJobRequest,
 };
+use crate::utils::cron;
 use crate::AppState;
 
-/// Normalize a cron expression to the 6-field format expected by the `cron` crate.
-/// Standard 5-field cron (min hour dom month dow) is prefixed with "0 " (seconds=0).
-fn normalize_cron(expr: &str) -> String {
-    let parts: Vec<&str> = expr.split_whitespace().collect();
-    if parts.len() == 5 {
-        format!("0 {}", expr)
-    } else {
-        expr.to_string()
-    }
-}
-
-/// Calculate the next run time from a cron expression
-fn next_run_from_cron(cron_expression: &str) -> Option<String> {
-    let normalized = normalize_cron(cron_expression);
-    let schedule = Schedule::from_str(&normalized).ok()?;
-    let next = schedule.upcoming(chrono::Utc).next()?;
-    Some(next.to_rfc3339())
-}
-
 /// Query parameters for listing job runs
 #[derive(Debug, Deserialize)]
 pub struct JobRunsQuery {
@@ -71,7 +51,7 @@ pub async fn list_jobs(
     let jobs = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs
         WHERE app_id = ?
         ORDER BY name ASC
@@ -123,24 +103,27 @@ pub async fn create_job(
     }
 
     // Normalize and validate cron expression
-    let cron_expr = normalize_cron(&req.cron_expression);
-    if Schedule::from_str(&cron_expr).is_err() {
-        tracing::warn!("Invalid cron expression: {}", req.cron_expression);
-        return Err(StatusCode::BAD_REQUEST);
-    }
+    let cron_expr = cron::validate(&req.cron_expression).map_err(|e| {
+        tracing::warn!("{}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    let timezone = cron::validate_timezone(req.timezone.as_deref()).map_err(|e| {
+        tracing::warn!("{}", e);
+        StatusCode::BAD_REQUEST
+    })?;
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
     let next_run = if req.enabled {
-        next_run_from_cron(&cron_expr)
+        cron::next_run(&cron_expr, timezone.as_deref())
     } else {
         None
     };
 
     sqlx::query(
         r#"
-        INSERT INTO scheduled_jobs (id, app_id, name, command, cron_expression, enabled, next_run_at, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO scheduled_jobs (id, app_id, name, command, cron_expression, enabled, next_run_at, timezone, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
@@ -150,6 +133,7 @@ pub async fn create_job(
     .bind(&cron_expr)
     .bind(if req.enabled { 1 } else { 0 })
     .bind(&next_run)
+    .bind(&timezone)
     .bind(&now)
     .bind(&now)
     .execute(&state.db)
@@ -162,7 +146,7 @@ pub async fn create_job(
     let job = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs WHERE id = ?
         "#,
     )
@@ -184,7 +168,7 @@ pub async fn get_job(
     let job = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs WHERE id = ? AND app_id = ?
         "#,
     )
@@ -213,7 +197,7 @@ pub async fn update_job(
     let existing = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs WHERE id = ? AND app_id = ?
         "#,
     )
@@ -226,26 +210,32 @@ pub async fn update_job(
 
     let new_name = req.name.unwrap_or(existing.name);
     let new_command = req.command.unwrap_or(existing.command);
-    let new_cron = normalize_cron(
-        &req.cron_expression
-            .unwrap_or(existing.cron_expression.clone()),
-    );
+    let new_cron = req
+        .cron_expression
+        .unwrap_or(existing.cron_expression.clone());
     let new_enabled = req
         .enabled
         .map(|b| if b { 1 } else { 0 })
         .unwrap_or(existing.enabled);
 
     // Validate cron expression if changed
-    if Schedule::from_str(&new_cron).is_err() {
-        tracing::warn!("Invalid cron expression: {}", new_cron);
-        return Err(StatusCode::BAD_REQUEST);
-    }
+    let new_cron = cron::validate(&new_cron).map_err(|e| {
+        tracing::warn!("{}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    let new_timezone = match req.timezone {
+        Some(ref tz) => cron::validate_timezone(Some(tz)).map_err(|e| {
+            tracing::warn!("{}", e);
+            StatusCode::BAD_REQUEST
+        })?,
+        None => existing.timezone.clone(),
+    };
 
-    // Recalculate next_run_at if cron changed or re-enabled
-    let cron_changed = new_cron != existing.cron_expression;
+    // Recalculate next_run_at if the schedule changed or the job was re-enabled
+    let cron_changed = new_cron != existing.cron_expression || new_timezone != existing.timezone;
     let re_enabled = new_enabled == 1 && existing.enabled == 0;
     let next_run = if new_enabled == 1 && (cron_changed || re_enabled) {
-        next_run_from_cron(&new_cron)
+        cron::next_run(&new_cron, new_timezone.as_deref())
     } else if new_enabled == 0 {
         None
     } else {
@@ -262,6 +252,7 @@ pub async fn update_job(
             cron_expression = ?,
             enabled = ?,
             next_run_at = ?,
+            timezone = ?,
             updated_at = ?
         WHERE id = ?
         "#,
@@ -271,6 +262,7 @@ pub async fn update_job(
     .bind(&new_cron)
     .bind(new_enabled)
     .bind(&next_run)
+    .bind(&new_timezone)
     .bind(&now)
     .bind(&job_id)
     .execute(&state.db)
@@ -283,7 +275,7 @@ pub async fn update_job(
     let job = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs WHERE id = ?
         "#,
     )
@@ -330,7 +322,7 @@ pub async fn trigger_job_run(
     let job = sqlx::query_as::<_, ScheduledJob>(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs WHERE id = ? AND app_id = ?
         "#,
     )
@@ -4,18 +4,22 @@
 //! - A `/metrics` endpoint that returns Prometheus-formatted metrics
 //! - Middleware for tracking HTTP request counts and durations
 //! - Helper functions to record deployment and app metrics
+//! - Per-domain proxy traffic metrics (requests, latency, bytes)
 
 use axum::{
     body::Body,
     extract::{MatchedPath, State},
-    http::{Request, StatusCode},
+    http::{header, HeaderMap, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
+    routing::get,
+    Router,
 };
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use std::sync::Arc;
 use std::time::Instant;
+use subtle::ConstantTimeEq;
 
 use crate::AppState;
 
@@ -29,6 +33,8 @@ pub const CONTAINERS_RUNNING: &str = "containers_running";
 // Application-level metric names
 pub const RIVETR_DEPLOYMENTS_TOTAL: &str = "rivetr_deployments_total";
 pub const RIVETR_DEPLOYMENT_DURATION_SECONDS: &str = "rivetr_deployment_duration_seconds";
+pub const RIVETR_DEPLOYMENT_PHASE_DURATION_SECONDS: &str =
+    "rivetr_deployment_phase_duration_seconds";
 pub const RIVETR_ACTIVE_APPS_TOTAL: &str = "rivetr_active_apps_total";
 pub const RIVETR_ACTIVE_DATABASES_TOTAL: &str = "rivetr_active_databases_total";
 pub const RIVETR_WEBHOOKS_RECEIVED_TOTAL: &str = "rivetr_webhooks_received_total";
@@ -56,11 +62,47 @@ pub const CONTAINER_NETWORK_TX_BYTES: &str = "rivetr_container_network_tx_bytes"
 pub const CONTAINER_RESTARTS_TOTAL: &str = "rivetr_container_restarts_total";
 pub const CONTAINER_RESTART_BACKOFF_SECONDS: &str = "rivetr_container_restart_backoff_seconds";
 
+// Reverse proxy traffic metrics (labeled by routed domain)
+pub const PROXY_REQUESTS_TOTAL: &str = "rivetr_proxy_requests_total";
+pub const PROXY_REQUEST_DURATION_SECONDS: &str = "rivetr_proxy_request_duration_seconds";
+pub const PROXY_UPSTREAM_DURATION_SECONDS: &str = "rivetr_proxy_upstream_duration_seconds";
+pub const PROXY_RESPONSE_BYTES_TOTAL: &str = "rivetr_proxy_response_bytes_total";
+
+// Deployment engine metrics
+pub const ENGINE_QUEUE_DEPTH: &str = "rivetr_engine_queue_depth";
+pub const ENGINE_ACTIVE_BUILDS: &str = "rivetr_engine_active_builds";
+pub const ENGINE_CONCURRENCY_LIMIT: &str = "rivetr_engine_concurrency_limit";
+pub const ENGINE_SATURATION_PERCENT: &str = "rivetr_engine_saturation_percent";
+pub const ENGINE_AVG_BUILD_DURATION_SECONDS: &str = "rivetr_engine_avg_build_duration_seconds";
+pub const ENGINE_STAGE_FAILURE_RATE_PERCENT: &str = "rivetr_engine_stage_failure_rate_percent";
+
+/// Histogram buckets (seconds) for proxy latency metrics
+const PROXY_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Histogram buckets (seconds) for deployment phase durations
+const DEPLOYMENT_PHASE_BUCKETS: &[f64] = &[
+    1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0,
+];
+
 /// Initialize the Prometheus metrics recorder and return a handle for rendering metrics.
 ///
 /// This should be called once during application startup.
 pub fn init_metrics() -> PrometheusHandle {
-    let builder = PrometheusBuilder::new();
+    // Proxy latency is exported as real histograms so it can be aggregated
+    // across domains in dashboards
+    let builder = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Prefix("rivetr_proxy_".to_string()),
+            PROXY_LATENCY_BUCKETS,
+        )
+        .expect("Proxy latency buckets are not empty")
+        .set_buckets_for_metric(
+            Matcher::Full(RIVETR_DEPLOYMENT_PHASE_DURATION_SECONDS.to_string()),
+            DEPLOYMENT_PHASE_BUCKETS,
+        )
+        .expect("Deployment phase buckets are not empty");
     let handle = builder
         .install_recorder()
         .expect("Failed to install Prometheus recorder");
@@ -144,7 +186,11 @@ pub fn init_metrics() -> PrometheusHandle {
     );
     describe_histogram!(
         RIVETR_DEPLOYMENT_DURATION_SECONDS,
-        "Deployment duration in seconds (labeled by app)"
+        "Deployment duration in seconds (labeled by app and status)"
+    );
+    describe_histogram!(
+        RIVETR_DEPLOYMENT_PHASE_DURATION_SECONDS,
+        "Deployment pipeline phase duration in seconds (labeled by app, phase and status)"
     );
     describe_gauge!(
         RIVETR_ACTIVE_APPS_TOTAL,
@@ -159,27 +205,106 @@ pub fn init_metrics() -> PrometheusHandle {
         "Total webhook events received by provider"
     );
 
+    // Deployment engine metrics
+    describe_gauge!(ENGINE_QUEUE_DEPTH, "Deployments waiting for a build slot");
+    describe_gauge!(ENGINE_ACTIVE_BUILDS, "Deployments holding a build slot");
+    describe_gauge!(
+        ENGINE_CONCURRENCY_LIMIT,
+        "Build slots (max concurrent deployments)"
+    );
+    describe_gauge!(
+        ENGINE_SATURATION_PERCENT,
+        "Share of build slots in use (0-100)"
+    );
+    describe_gauge!(
+        ENGINE_AVG_BUILD_DURATION_SECONDS,
+        "Average time from taking a build slot to going live over the last 24 hours"
+    );
+    describe_gauge!(
+        ENGINE_STAGE_FAILURE_RATE_PERCENT,
+        "Share of deployments that failed in a pipeline stage over the last 24 hours (labeled by stage)"
+    );
+
+    // Reverse proxy traffic metrics
+    describe_counter!(
+        PROXY_REQUESTS_TOTAL,
+        "Total requests handled by the reverse proxy (labeled by domain and status)"
+    );
+    describe_histogram!(
+        PROXY_REQUEST_DURATION_SECONDS,
+        "Time until the proxy sent response headers, in seconds (labeled by domain)"
+    );
+    describe_histogram!(
+        PROXY_UPSTREAM_DURATION_SECONDS,
+        "Time for the app container to return response headers, in seconds (labeled by domain)"
+    );
+    describe_counter!(
+        PROXY_RESPONSE_BYTES_TOTAL,
+        "Response body bytes sent to clients by the reverse proxy (labeled by domain)"
+    );
+
     handle
 }
 
 /// GET /metrics - Returns Prometheus-formatted metrics.
 ///
-/// This endpoint is accessible without authentication.
-pub async fn metrics_endpoint(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+/// When `[observability] metrics_token` is set, scrapers must send
+/// `Authorization: Bearer <token>`; otherwise the endpoint is unauthenticated.
+pub async fn metrics_endpoint(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if let Some(expected) = state.config.observability.metrics_token() {
+        if !is_authorized_scrape(&headers, expected) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer realm=\"metrics\"")],
+                "Unauthorized",
+            )
+                .into_response();
+        }
+    }
+
     // Update gauge metrics before rendering
     update_gauge_metrics(&state).await;
 
     // Render metrics in Prometheus text format
     let handle = state.metrics_handle.as_ref();
     match handle {
-        Some(h) => (StatusCode::OK, h.render()),
+        Some(h) => (StatusCode::OK, h.render()).into_response(),
         None => (
             StatusCode::INTERNAL_SERVER_ERROR,
             "Metrics not initialized".to_string(),
-        ),
+        )
+            .into_response(),
     }
 }
 
+/// Check a scrape request's bearer token against the configured metrics token.
+fn is_authorized_scrape(headers: &HeaderMap, expected: &str) -> bool {
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or("");
+
+    provided.len() == expected.len() && provided.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Router serving only `/metrics`, used when `[observability] metrics_bind`
+/// moves the endpoint off the public API port.
+pub fn metrics_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_endpoint))
+        .with_state(state)
+}
+
+/// Serve `/metrics` on its own listener (see `[observability] metrics_bind`).
+pub async fn serve_metrics(state: Arc<AppState>, bind_addr: String) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+    tracing::info!("Metrics server listening on http://{}/metrics", bind_addr);
+    axum::serve(listener, metrics_router(state)).await?;
+    Ok(())
+}
+
 /// Update gauge metrics (apps_total, containers_running) from current state.
 async fn update_gauge_metrics(state: &AppState) {
     // Count total apps
@@ -217,6 +342,20 @@ async fn update_gauge_metrics(state: &AppState) {
     {
         set_active_databases_total(active_dbs as f64);
     }
+
+    // Deployment engine load
+    let engine = super::system::engine_status(state).await;
+    gauge!(ENGINE_QUEUE_DEPTH).set(engine.queue_depth as f64);
+    gauge!(ENGINE_ACTIVE_BUILDS).set(engine.active_builds as f64);
+    gauge!(ENGINE_CONCURRENCY_LIMIT).set(engine.concurrency_limit as f64);
+    gauge!(ENGINE_SATURATION_PERCENT).set(engine.saturation_percent);
+    if let Some(avg) = engine.avg_build_duration_secs {
+        gauge!(ENGINE_AVG_BUILD_DURATION_SECONDS).set(avg);
+    }
+    for stage in engine.stage_failures {
+        gauge!(ENGINE_STAGE_FAILURE_RATE_PERCENT, "stage" => stage.stage)
+            .set(stage.failure_rate_percent);
+    }
 }
 
 /// Middleware to track HTTP request metrics.
@@ -324,10 +463,31 @@ pub fn increment_deployments_total(app_name: &str, status: &str) {
     counter!(RIVETR_DEPLOYMENTS_TOTAL, "app" => app_name.to_string(), "status" => status.to_string()).increment(1);
 }
 
-/// Record deployment duration histogram observation.
-pub fn observe_deployment_duration(app_name: &str, duration_secs: f64) {
-    histogram!(RIVETR_DEPLOYMENT_DURATION_SECONDS, "app" => app_name.to_string())
-        .record(duration_secs);
+/// Record deployment duration histogram observation by outcome (success,
+/// failed or cancelled).
+pub fn observe_deployment_duration(app_name: &str, status: &str, duration_secs: f64) {
+    histogram!(
+        RIVETR_DEPLOYMENT_DURATION_SECONDS,
+        "app" => app_name.to_string(),
+        "status" => status.to_string()
+    )
+    .record(duration_secs);
+}
+
+/// Record how long a deployment pipeline phase took.
+pub fn observe_deployment_phase_duration(
+    app_name: &str,
+    phase: &str,
+    status: &str,
+    duration_secs: f64,
+) {
+    histogram!(
+        RIVETR_DEPLOYMENT_PHASE_DURATION_SECONDS,
+        "app" => app_name.to_string(),
+        "phase" => phase.to_string(),
+        "status" => status.to_string()
+    )
+    .record(duration_secs);
 }
 
 /// Set the number of currently running app containers.
@@ -345,6 +505,25 @@ pub fn increment_webhooks_received(provider: &str) {
     counter!(RIVETR_WEBHOOKS_RECEIVED_TOTAL, "provider" => provider.to_string()).increment(1);
 }
 
+/// Record a request handled by the reverse proxy.
+pub fn record_proxy_request(domain: &str, status: u16, duration_secs: f64) {
+    counter!(PROXY_REQUESTS_TOTAL, "domain" => domain.to_string(), "status" => status.to_string())
+        .increment(1);
+    histogram!(PROXY_REQUEST_DURATION_SECONDS, "domain" => domain.to_string())
+        .record(duration_secs);
+}
+
+/// Record how long an app container took to return response headers.
+pub fn observe_proxy_upstream_duration(domain: &str, duration_secs: f64) {
+    histogram!(PROXY_UPSTREAM_DURATION_SECONDS, "domain" => domain.to_string())
+        .record(duration_secs);
+}
+
+/// Add response body bytes sent for a domain.
+pub fn add_proxy_response_bytes(domain: &str, bytes: u64) {
+    counter!(PROXY_RESPONSE_BYTES_TOTAL, "domain" => domain.to_string()).increment(bytes);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,4 +535,19 @@ mod tests {
         assert!(DEPLOYMENTS_TOTAL.contains("_total"));
         assert!(HTTP_REQUEST_DURATION_SECONDS.contains("_seconds"));
     }
+
+    #[test]
+    fn test_scrape_token_check() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized_scrape(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!is_authorized_scrape(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, "Basic secret".parse().unwrap());
+        assert!(!is_authorized_scrape(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(is_authorized_scrape(&headers, "secret"));
+    }
 }
@@ -1,3 +1,4 @@
+mod access_rules;
 mod ai_features;
 mod alerts;
 mod api_tokens;
@@ -7,34 +8,46 @@ pub mod auth;
 pub mod authz;
 mod autoscaling;
 mod basic_auth;
+mod bot_settings;
 mod build_servers;
 mod bulk;
 mod ca_certificates;
 mod clone;
 mod cloudflare_tunnels;
+mod command_runs;
+mod common;
+mod cors;
 mod cost_rates;
 mod costs;
 mod database_backups;
 mod database_extensions;
 pub mod database_links;
 mod databases;
-mod deployments;
+pub(crate) mod deployments;
 mod destinations;
 mod env_vars;
 pub mod environments;
 pub mod error;
 mod filesystem;
+mod forward_auth;
 mod git_providers;
 mod github_apps;
+mod inbox;
 mod instance_settings;
 mod jobs;
 mod log_drains;
 pub mod metrics;
 mod monitoring;
+mod notification_digests;
 mod notifications;
 pub mod oauth;
+mod openapi;
 mod patches;
+mod power_schedule;
+mod preferences;
 mod previews;
+mod prewarm;
+pub mod probes;
 mod projects;
 mod proxy_logs;
 pub mod rate_limit;
@@ -45,7 +58,7 @@ mod s3;
 mod sdk;
 mod servers;
 mod service_templates;
-mod services;
+pub(crate) mod services;
 mod shared_env_vars;
 mod ssh_keys;
 pub mod ssrf;
@@ -53,10 +66,12 @@ mod sso;
 pub mod start_logs;
 mod swarm;
 pub mod system;
+mod tasks;
 mod teams;
 mod two_factor;
-mod validation;
+pub(crate) mod validation;
 mod volumes;
+mod waf;
 mod webhook_events;
 mod webhooks;
 mod white_label;
@@ -201,10 +216,29 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/apps/:id/deploy/upload", post(deployments::upload_deploy))
         .route("/apps/:id/deployments", get(deployments::list_deployments))
         .route("/apps/:id/stats", get(deployments::get_app_stats))
+        .route("/apps/:id/deploy-stats", get(deployments::get_deploy_stats))
+        .route("/apps/:id/bandwidth", get(apps::get_app_bandwidth))
         .route("/apps/:id/commits", get(deployments::list_commits))
         .route("/apps/:id/tags", get(deployments::list_tags))
+        .route("/apps/:id/images", get(deployments::list_app_images))
         .route("/deployments/:id", get(deployments::get_deployment))
         .route("/deployments/:id/logs", get(deployments::get_logs))
+        .route(
+            "/deployments/:id/timeline",
+            get(deployments::get_deployment_timeline),
+        )
+        .route(
+            "/deployments/:id/diagnostics",
+            get(deployments::get_deployment_diagnostics),
+        )
+        .route(
+            "/deployments/:id/logs/archive",
+            get(deployments::get_deployment_log_archive),
+        )
+        .route(
+            "/deployments/:id/vulnerabilities",
+            get(deployments::get_deployment_vulnerabilities),
+        )
         .route(
             "/deployments/:id/diff",
             get(deployments::get_deployment_diff),
@@ -231,6 +265,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/apps/:app_id/deployments/:id/cancel",
             post(deployments::cancel_deployment),
         )
+        .route(
+            "/deployments/:id/cancel",
+            post(deployments::cancel_deployment_by_id),
+        )
         // Deployment freeze windows (app-scoped)
         .route(
             "/apps/:id/freeze-windows",
@@ -249,12 +287,21 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/build/detect",
             post(deployments::detect_build_type_from_upload),
         )
+        .route(
+            "/build/dockerfile",
+            post(deployments::generate_dockerfile_from_upload),
+        )
+        .route(
+            "/apps/:id/dockerfile/generate",
+            post(apps::generate_app_dockerfile),
+        )
         // SSH Keys
         .route("/ssh-keys", get(ssh_keys::list_ssh_keys))
         .route("/ssh-keys", post(ssh_keys::create_ssh_key))
         .route("/ssh-keys/:id", get(ssh_keys::get_ssh_key))
         .route("/ssh-keys/:id", put(ssh_keys::update_ssh_key))
         .route("/ssh-keys/:id", delete(ssh_keys::delete_ssh_key))
+        .route("/ssh-keys/:id/test", post(ssh_keys::test_ssh_key))
         .route("/apps/:id/ssh-keys", get(ssh_keys::get_app_ssh_keys))
         // Remote Servers
         .route(
@@ -354,6 +401,14 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/apps/:id/basic-auth",
             delete(basic_auth::delete_basic_auth),
         )
+        .route(
+            "/apps/:id/basic-auth/paths",
+            get(basic_auth::list_basic_auth_paths).post(basic_auth::create_basic_auth_path),
+        )
+        .route(
+            "/apps/:id/basic-auth/paths/:rid",
+            put(basic_auth::update_basic_auth_path).delete(basic_auth::delete_basic_auth_path),
+        )
         // URL Redirect Rules
         .route(
             "/apps/:id/redirects",
@@ -363,7 +418,54 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/apps/:id/redirects/:rid",
             put(redirect_rules::update_redirect_rule).delete(redirect_rules::delete_redirect_rule),
         )
+        // Proxy access rules
+        .route(
+            "/apps/:id/access-rules",
+            get(access_rules::list_access_rules).post(access_rules::create_access_rule),
+        )
+        .route(
+            "/apps/:id/access-rules/:rid",
+            put(access_rules::update_access_rule).delete(access_rules::delete_access_rule),
+        )
+        // Built-in WAF
+        .route(
+            "/apps/:id/waf",
+            get(waf::get_waf_settings).put(waf::update_waf_settings),
+        )
+        .route("/apps/:id/waf/events", get(waf::list_waf_events))
+        // Bot/crawler management
+        .route(
+            "/apps/:id/bots",
+            get(bot_settings::get_bot_settings).put(bot_settings::update_bot_settings),
+        )
+        // Forward auth (sign-in required at the proxy)
+        .route(
+            "/apps/:id/forward-auth",
+            get(forward_auth::get_forward_auth).put(forward_auth::update_forward_auth),
+        )
+        // CORS policy enforced at the proxy
+        .route(
+            "/apps/:id/cors",
+            get(cors::get_cors_settings).put(cors::update_cors_settings),
+        )
+        // Scheduled stop/start (office-hours apps)
+        .route(
+            "/apps/:id/power-schedule",
+            get(power_schedule::get_power_schedule)
+                .put(power_schedule::update_power_schedule)
+                .delete(power_schedule::delete_power_schedule),
+        )
+        // Pre-warmed deployment slot (build ahead, promote at release time)
+        .route(
+            "/apps/:id/prewarm",
+            get(prewarm::get_prewarm)
+                .put(prewarm::update_prewarm)
+                .delete(prewarm::delete_prewarm),
+        )
+        .route("/apps/:id/prewarm/build", post(prewarm::build_prewarm))
+        .route("/apps/:id/prewarm/promote", post(prewarm::promote_prewarm))
         // Volumes
+        .route("/volumes", get(volumes::list_all_volumes))
         .route("/apps/:id/volumes", get(volumes::list_volumes))
         .route("/apps/:id/volumes", post(volumes::create_volume))
         .route("/volumes/:id", get(volumes::get_volume))
@@ -424,6 +526,29 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/apps/:id/jobs/:job_id", delete(jobs::delete_job))
         .route("/apps/:id/jobs/:job_id/run", post(jobs::trigger_job_run))
         .route("/apps/:id/jobs/:job_id/runs", get(jobs::list_job_runs))
+        // Scheduled tasks (one-off containers)
+        .route(
+            "/apps/:id/tasks",
+            get(tasks::list_tasks).post(tasks::create_task),
+        )
+        .route(
+            "/apps/:id/tasks/:task_id",
+            get(tasks::get_task)
+                .put(tasks::update_task)
+                .delete(tasks::delete_task),
+        )
+        .route("/apps/:id/tasks/:task_id/run", post(tasks::run_task))
+        .route("/apps/:id/tasks/:task_id/runs", get(tasks::list_task_runs))
+        // One-off command runs
+        .route(
+            "/apps/:id/runs",
+            get(command_runs::list_runs).post(command_runs::create_run),
+        )
+        .route("/apps/:id/runs/:run_id", get(command_runs::get_run))
+        .route(
+            "/apps/:id/runs/:run_id/stream",
+            get(command_runs::stream_run),
+        )
         // Routes (proxy management)
         .route("/routes", get(routes::list_routes))
         .route("/routes", post(routes::add_route))
@@ -432,6 +557,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/routes/:domain", get(routes::get_route))
         .route("/routes/:domain", delete(routes::remove_route))
         .route("/routes/:domain/health", put(routes::update_route_health))
+        .route(
+            "/routes/:domain/health/history",
+            get(routes::route_health_history),
+        )
         // Git Providers (OAuth connections and PAT)
         .route("/git-providers", get(git_providers::list_providers))
         .route("/git-providers", post(git_providers::add_token_provider))
@@ -594,6 +723,8 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/teams/:id/notification-channels/:channel_id/test",
             post(notifications::test_team_channel),
         )
+        // OpenAPI document for the API explorer
+        .route("/openapi.json", get(openapi::get_openapi_spec))
         // API Tokens
         .route("/tokens", get(api_tokens::list_tokens))
         .route("/tokens", post(api_tokens::create_token))
@@ -638,6 +769,10 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/notification-channels/:id/test",
             post(notifications::test_channel),
         )
+        .route(
+            "/notification-channels/:id/deliveries",
+            get(notifications::list_deliveries),
+        )
         .route(
             "/notification-channels/:id/subscriptions",
             get(notifications::list_subscriptions),
@@ -650,6 +785,29 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/notification-subscriptions/:id",
             delete(notifications::delete_subscription),
         )
+        // Notification Digests (daily/weekly summaries)
+        .route(
+            "/notification-digests",
+            get(notification_digests::list_digests).post(notification_digests::create_digest),
+        )
+        .route(
+            "/notification-digests/:id",
+            get(notification_digests::get_digest)
+                .put(notification_digests::update_digest)
+                .delete(notification_digests::delete_digest),
+        )
+        .route(
+            "/notification-digests/:id/preview",
+            get(notification_digests::preview_digest),
+        )
+        .route(
+            "/notification-digests/:id/send",
+            post(notification_digests::send_digest_now),
+        )
+        // In-app notification center (current user's inbox)
+        .route("/notifications/inbox", get(inbox::list_inbox))
+        .route("/notifications/inbox/read-all", post(inbox::mark_all_read))
+        .route("/notifications/inbox/:id/read", post(inbox::mark_read))
         // Managed Databases
         .route("/databases", get(databases::list_databases))
         .route("/databases", post(databases::create_database))
@@ -668,6 +826,13 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/databases/:id/import",
             post(databases::import_database_dump),
         )
+        // Database seeding
+        .route("/databases/:id/seed", post(databases::seed_database))
+        .route("/databases/:id/seed-dump", put(databases::upload_seed_dump))
+        .route(
+            "/databases/:id/seed-dump",
+            delete(databases::delete_seed_dump),
+        )
         // Database Extensions (PostgreSQL only)
         .route(
             "/databases/:id/extensions",
@@ -757,6 +922,11 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/auth/2fa/verify", post(two_factor::verify_2fa))
         .route("/auth/2fa/disable", post(two_factor::disable_2fa))
         .route("/auth/2fa/status", get(two_factor::status_2fa))
+        // Personal preferences (authenticated)
+        .route(
+            "/auth/preferences",
+            get(preferences::get_preferences).put(preferences::update_preferences),
+        )
         // Settings
         .route("/settings/alert-defaults", get(alerts::get_alert_defaults))
         .route(
@@ -814,13 +984,20 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/system/stats/summary", get(system::get_stats_summary))
         .route("/system/disk", get(system::get_disk_stats))
         .route("/system/health", get(system::get_detailed_health))
+        .route("/system/engine", get(system::get_engine_status))
+        .route("/system/cron/preview", post(system::preview_cron))
         .route("/system/costs", get(costs::get_dashboard_costs))
         .route("/events/recent", get(system::get_recent_events))
+        .route("/events/stream", get(inbox::event_stream))
         // Version and updates
         .route("/system/version", get(system::get_version_info))
         .route("/system/update/check", post(system::check_for_updates))
         .route("/system/update/download", post(system::download_update))
         .route("/system/update/apply", post(system::apply_update))
+        .route(
+            "/system/update/restart",
+            post(system::restart_with_handover),
+        )
         // Instance backup & restore
         .route("/system/backup", post(system::create_backup))
         .route("/system/backup/full", post(system::create_full_backup))
@@ -835,6 +1012,9 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             post(system::upload_backup_to_s3),
         )
         .route("/system/restore", post(system::restore_backup))
+        // ACME account & certificate migration
+        .route("/system/acme/export", get(system::export_acme))
+        .route("/system/acme/import", post(system::import_acme))
         // Backup schedules
         .route(
             "/backups/schedules",
@@ -856,6 +1036,25 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/system/log-cleanup", post(monitoring::trigger_log_cleanup))
         // Docker/Podman resource cleanup (dangling image prune)
         .route("/system/docker-cleanup", post(system::run_docker_cleanup))
+        // Deployment artifact store (diagnostics bundles, log archives)
+        .route("/system/artifacts", get(system::get_artifact_stats))
+        .route("/system/artifacts/gc", post(system::run_artifact_gc))
+        // Sent-mail log (email queue)
+        .route("/system/emails", get(system::list_sent_emails))
+        // Fault injection for resilience testing ([chaos] enabled only)
+        .route(
+            "/system/chaos",
+            get(system::get_chaos)
+                .put(system::set_chaos)
+                .delete(system::clear_chaos),
+        )
+        // Host maintenance: pause deployments, stop apps, resume
+        .route(
+            "/system/cordon",
+            get(system::get_cordon).post(system::cordon_host),
+        )
+        .route("/system/drain", post(system::drain_host))
+        .route("/system/uncordon", post(system::uncordon_host))
         // S3 Storage Configs
         .route("/s3/configs", post(s3::create_config))
         .route("/s3/configs", get(s3::list_configs))
@@ -916,6 +1115,13 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Container Replicas
         .route("/apps/:id/replicas", get(replicas::list_replicas))
         .route("/apps/:id/replicas/count", put(replicas::set_replica_count))
+        .route("/apps/:id/scale", post(replicas::scale_app))
+        .route("/apps/:id/processes", get(replicas::list_processes))
+        .route("/apps/:id/processes/:name", put(replicas::scale_process))
+        .route(
+            "/apps/:id/replicas/rollout",
+            put(replicas::set_rollout_settings),
+        )
         .route(
             "/apps/:id/replicas/:index/restart",
             post(replicas::restart_replica),
@@ -969,6 +1175,9 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         )
         .route("/apps/:id/snapshots/:sid", delete(bulk::delete_snapshot))
         .route("/apps/:id/maintenance", put(bulk::set_maintenance_mode))
+        // Deployment locks
+        .route("/apps/:id/lock", post(apps::lock_app))
+        .route("/apps/:id/unlock", post(apps::unlock_app))
         // Project import/export
         .route("/projects/:id/export", get(bulk::export_project))
         .route("/projects/:id/import", post(bulk::import_project))
@@ -1054,9 +1263,18 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             auth::auth_middleware,
         ));
 
+    // `/metrics` lives on the API port unless a dedicated bind address is configured
+    let metrics_routes = if state.config.observability.metrics_bind().is_some() {
+        Router::new()
+    } else {
+        Router::new().route("/metrics", get(metrics::metrics_endpoint))
+    };
+
     Router::new()
         .route("/health", get(health_check))
-        .route("/metrics", get(metrics::metrics_endpoint))
+        .route("/livez", get(probes::livez))
+        .route("/readyz", get(probes::readyz))
+        .merge(metrics_routes)
         .merge(mcp_routes)
         .nest("/api/auth", auth_routes)
         .nest("/api/auth", auth_info_routes)
@@ -1066,6 +1284,7 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .merge(sso_routes)
         // Fallback to embedded static files for frontend SPA
         .fallback(serve_embedded_static)
+        .layer(middleware::from_fn(crate::i18n::locale_middleware))
         .layer(middleware::from_fn(security_headers))
         .layer(middleware::from_fn(metrics::metrics_middleware))
         // Apply gzip compression to JSON / text responses.  Honours `Accept-Encoding`
@@ -16,6 +16,7 @@ use crate::db::{
     ScheduledRestart, ScheduledRestartResponse, UpdateLogRetentionRequest,
     UpdateScheduledRestartRequest, UptimeCheck, UptimeCheckResponse, UptimeSummary,
 };
+use crate::utils::cron;
 use crate::AppState;
 
 // ---------------------------------------------------------------------------
@@ -551,24 +552,27 @@ pub async fn create_scheduled_restart(
         return Err(StatusCode::NOT_FOUND);
     }
 
-    // Validate cron expression (normalize to 6-field before parsing)
-    let normalized_cron = normalize_cron(&req.cron_expression);
-    if cron::Schedule::from_str(&normalized_cron).is_err() {
-        tracing::warn!("Invalid cron expression: {}", req.cron_expression);
+    // Validate cron expression (5- or 6-field)
+    if let Err(e) = cron::validate(&req.cron_expression) {
+        tracing::warn!("{}", e);
         return Err(StatusCode::BAD_REQUEST);
     }
+    let timezone = cron::validate_timezone(req.timezone.as_deref()).map_err(|e| {
+        tracing::warn!("{}", e);
+        StatusCode::BAD_REQUEST
+    })?;
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
     let enabled_int: i32 = if req.enabled { 1 } else { 0 };
 
     // Calculate next restart time
-    let next_restart = next_run_from_cron(&req.cron_expression);
+    let next_restart = cron::next_run(&req.cron_expression, timezone.as_deref());
 
     sqlx::query(
         r#"
-        INSERT INTO scheduled_restarts (id, app_id, cron_expression, enabled, next_restart, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO scheduled_restarts (id, app_id, cron_expression, enabled, next_restart, timezone, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
@@ -576,6 +580,7 @@ pub async fn create_scheduled_restart(
     .bind(&req.cron_expression)
     .bind(enabled_int)
     .bind(&next_restart)
+    .bind(&timezone)
     .bind(&now)
     .execute(&state.db)
     .await
@@ -585,7 +590,7 @@ pub async fn create_scheduled_restart(
     })?;
 
     let restart: ScheduledRestart = sqlx::query_as(
-        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, created_at FROM scheduled_restarts WHERE id = ?",
+        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, timezone, created_at FROM scheduled_restarts WHERE id = ?",
     )
     .bind(&id)
     .fetch_one(&state.db)
@@ -626,7 +631,7 @@ pub async fn list_scheduled_restarts(
     }
 
     let restarts: Vec<ScheduledRestart> = sqlx::query_as(
-        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, created_at FROM scheduled_restarts WHERE app_id = ? ORDER BY created_at DESC",
+        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, timezone, created_at FROM scheduled_restarts WHERE app_id = ? ORDER BY created_at DESC",
     )
     .bind(&app_id)
     .fetch_all(&state.db)
@@ -651,7 +656,7 @@ pub async fn update_scheduled_restart(
 ) -> Result<Json<ScheduledRestartResponse>, StatusCode> {
     // Get existing
     let existing: Option<ScheduledRestart> = sqlx::query_as(
-        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, created_at FROM scheduled_restarts WHERE id = ?",
+        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, timezone, created_at FROM scheduled_restarts WHERE id = ?",
     )
     .bind(&restart_id)
     .fetch_optional(&state.db)
@@ -673,25 +678,32 @@ pub async fn update_scheduled_restart(
         .map(|e| if e { 1 } else { 0 })
         .unwrap_or(existing.enabled);
 
-    // Validate cron if changed (normalize to 6-field before parsing)
-    let normalized_new_cron = normalize_cron(&new_cron);
-    if cron::Schedule::from_str(&normalized_new_cron).is_err() {
-        tracing::warn!("Invalid cron expression: {}", new_cron);
+    // Validate cron if changed (5- or 6-field)
+    if let Err(e) = cron::validate(&new_cron) {
+        tracing::warn!("{}", e);
         return Err(StatusCode::BAD_REQUEST);
     }
+    let new_timezone = match req.timezone {
+        Some(ref tz) => cron::validate_timezone(Some(tz)).map_err(|e| {
+            tracing::warn!("{}", e);
+            StatusCode::BAD_REQUEST
+        })?,
+        None => existing.timezone,
+    };
 
     let next_restart = if new_enabled != 0 {
-        next_run_from_cron(&new_cron)
+        cron::next_run(&new_cron, new_timezone.as_deref())
     } else {
         None
     };
 
     sqlx::query(
-        "UPDATE scheduled_restarts SET cron_expression = ?, enabled = ?, next_restart = ? WHERE id = ?",
+        "UPDATE scheduled_restarts SET cron_expression = ?, enabled = ?, next_restart = ?, timezone = ? WHERE id = ?",
     )
     .bind(&new_cron)
     .bind(new_enabled)
     .bind(&next_restart)
+    .bind(&new_timezone)
     .bind(&restart_id)
     .execute(&state.db)
     .await
@@ -701,7 +713,7 @@ pub async fn update_scheduled_restart(
     })?;
 
     let updated: ScheduledRestart = sqlx::query_as(
-        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, created_at FROM scheduled_restarts WHERE id = ?",
+        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, timezone, created_at FROM scheduled_restarts WHERE id = ?",
     )
     .bind(&restart_id)
     .fetch_one(&state.db)
@@ -729,7 +741,7 @@ pub async fn delete_scheduled_restart(
 ) -> Result<StatusCode, StatusCode> {
     // Get existing to verify ownership
     let existing: Option<ScheduledRestart> = sqlx::query_as(
-        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, created_at FROM scheduled_restarts WHERE id = ?",
+        "SELECT id, app_id, cron_expression, enabled, last_restart, next_restart, timezone, created_at FROM scheduled_restarts WHERE id = ?",
     )
     .bind(&restart_id)
     .fetch_optional(&state.db)
@@ -762,32 +774,3 @@ pub async fn delete_scheduled_restart(
 
     Ok(StatusCode::NO_CONTENT)
 }
-
-// ---------------------------------------------------------------------------
-// Helper
-// ---------------------------------------------------------------------------
-
-use std::str::FromStr;
-
-/// Normalise a cron expression so it always has exactly 6 fields (seconds
-/// included).  The `cron` crate requires a seconds field as the first field
-/// (`sec min hour day month weekday`), but users and the frontend supply the
-/// conventional 5-field POSIX format (`min hour day month weekday`).
-///
-/// If the expression already has 6 or more whitespace-separated fields it is
-/// returned unchanged; otherwise `"0"` is prepended.
-fn normalize_cron(expr: &str) -> String {
-    let field_count = expr.split_whitespace().count();
-    if field_count >= 6 {
-        expr.to_string()
-    } else {
-        format!("0 {}", expr)
-    }
-}
-
-fn next_run_from_cron(cron_expression: &str) -> Option<String> {
-    let normalized = normalize_cron(cron_expression);
-    let schedule = cron::Schedule::from_str(&normalized).ok()?;
-    let next = schedule.upcoming(chrono::Utc).next()?;
-    Some(next.to_rfc3339())
-}
@@ -0,0 +1,332 @@
+//! Notification digest API endpoints.
+//!
+//! A digest summarizes a day or a week of activity (deployment counts, the
+//! slowest builds, container crashes and certificates close to expiry) and
+//! sends it to notification channels and email addresses on a schedule, for
+//! teams that prefer one summary to a stream of per-event notifications.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::{
+    CreateNotificationDigestRequest, NotificationDigest, NotificationDigestResponse,
+    UpdateNotificationDigestRequest, DIGEST_FREQUENCY_DAILY, DIGEST_FREQUENCY_WEEKLY,
+};
+use crate::notifications::summary::{self, DigestSummary};
+use crate::utils::cron;
+use crate::AppState;
+
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Send time used when none is given (local hour)
+const DEFAULT_SEND_HOUR: i64 = 8;
+/// Weekday weekly digests go out on when none is given (Monday)
+const DEFAULT_SEND_WEEKDAY: i64 = 1;
+
+/// List all notification digests
+pub async fn list_digests(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<NotificationDigestResponse>>, ApiError> {
+    let digests = sqlx::query_as::<_, NotificationDigest>(
+        "SELECT * FROM notification_digests ORDER BY created_at DESC",
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(digests.into_iter().map(Into::into).collect()))
+}
+
+/// Get a notification digest
+pub async fn get_digest(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<NotificationDigestResponse>, ApiError> {
+    let digest = find_digest(&state, &id).await?;
+    Ok(Json(digest.into()))
+}
+
+/// Create a notification digest
+pub async fn create_digest(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateNotificationDigestRequest>,
+) -> Result<(StatusCode, Json<NotificationDigestResponse>), ApiError> {
+    let mut errors = ValidationErrorBuilder::new();
+    check_name(&mut errors, &req.name);
+    let frequency = req
+        .frequency
+        .unwrap_or_else(|| DIGEST_FREQUENCY_DAILY.to_string());
+    check_schedule(
+        &mut errors,
+        &frequency,
+        req.send_hour.unwrap_or(DEFAULT_SEND_HOUR),
+        req.send_weekday.unwrap_or(DEFAULT_SEND_WEEKDAY),
+    );
+    let timezone = cron::validate_timezone(req.timezone.as_deref()).unwrap_or_else(|e| {
+        errors.add("timezone", &e);
+        None
+    });
+    check_recipients(&state, &mut errors, &req.channel_ids, &req.email_addresses).await?;
+    if let Some(team_id) = req.team_id.as_deref() {
+        let team: Option<(String,)> = sqlx::query_as("SELECT id FROM teams WHERE id = ?")
+            .bind(team_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if team.is_none() {
+            errors.add("team_id", "Team not found");
+        }
+    }
+    errors.finish()?;
+
+    let send_hour = req.send_hour.unwrap_or(DEFAULT_SEND_HOUR);
+    let send_weekday = req.send_weekday.unwrap_or(DEFAULT_SEND_WEEKDAY);
+    let next_send_at = cron::next_run(
+        &crate::db::cron_expression(&frequency, send_hour, send_weekday),
+        timezone.as_deref(),
+    );
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO notification_digests
+            (id, name, team_id, frequency, send_hour, send_weekday, timezone,
+             channel_ids, email_addresses, enabled, next_send_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(req.name.trim())
+    .bind(&req.team_id)
+    .bind(&frequency)
+    .bind(send_hour)
+    .bind(send_weekday)
+    .bind(&timezone)
+    .bind(serde_json::to_string(&req.channel_ids).unwrap_or_else(|_| "[]".to_string()))
+    .bind(serde_json::to_string(&req.email_addresses).unwrap_or_else(|_| "[]".to_string()))
+    .bind(if req.enabled { 1 } else { 0 })
+    .bind(&next_send_at)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create notification digest: {}", e);
+        ApiError::database("Failed to create notification digest")
+    })?;
+
+    tracing::info!(digest_id = %id, frequency = %frequency, "Notification digest created");
+
+    let digest = find_digest(&state, &id).await?;
+    Ok((StatusCode::CREATED, Json(digest.into())))
+}
+
+/// Update a notification digest
+pub async fn update_digest(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateNotificationDigestRequest>,
+) -> Result<Json<NotificationDigestResponse>, ApiError> {
+    let existing = find_digest(&state, &id).await?;
+
+    let mut errors = ValidationErrorBuilder::new();
+    let name = req.name.unwrap_or(existing.name.clone());
+    check_name(&mut errors, &name);
+    let frequency = req.frequency.unwrap_or(existing.frequency.clone());
+    let send_hour = req.send_hour.unwrap_or(existing.send_hour);
+    let send_weekday = req.send_weekday.unwrap_or(existing.send_weekday);
+    check_schedule(&mut errors, &frequency, send_hour, send_weekday);
+    let timezone = match req.timezone.as_deref() {
+        Some(timezone) => cron::validate_timezone(Some(timezone)).unwrap_or_else(|e| {
+            errors.add("timezone", &e);
+            None
+        }),
+        None => existing.timezone.clone(),
+    };
+    let channel_ids = req.channel_ids.unwrap_or_else(|| existing.channel_ids());
+    let email_addresses = req
+        .email_addresses
+        .unwrap_or_else(|| existing.email_addresses());
+    check_recipients(&state, &mut errors, &channel_ids, &email_addresses).await?;
+    errors.finish()?;
+
+    let enabled = req.enabled.unwrap_or(existing.enabled != 0);
+    // Reschedule from now: the send time or timezone may have changed
+    let next_send_at = cron::next_run(
+        &crate::db::cron_expression(&frequency, send_hour, send_weekday),
+        timezone.as_deref(),
+    );
+
+    sqlx::query(
+        r#"
+        UPDATE notification_digests
+        SET name = ?, frequency = ?, send_hour = ?, send_weekday = ?, timezone = ?,
+            channel_ids = ?, email_addresses = ?, enabled = ?, next_send_at = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(name.trim())
+    .bind(&frequency)
+    .bind(send_hour)
+    .bind(send_weekday)
+    .bind(&timezone)
+    .bind(serde_json::to_string(&channel_ids).unwrap_or_else(|_| "[]".to_string()))
+    .bind(serde_json::to_string(&email_addresses).unwrap_or_else(|_| "[]".to_string()))
+    .bind(if enabled { 1 } else { 0 })
+    .bind(&next_send_at)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(&id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update notification digest: {}", e);
+        ApiError::database("Failed to update notification digest")
+    })?;
+
+    let digest = find_digest(&state, &id).await?;
+    Ok(Json(digest.into()))
+}
+
+/// Delete a notification digest
+pub async fn delete_digest(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let result = sqlx::query("DELETE FROM notification_digests WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Notification digest not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Summary the digest would send now, without sending it
+pub async fn preview_digest(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<DigestSummary>, ApiError> {
+    let digest = find_digest(&state, &id).await?;
+    let until = chrono::Utc::now();
+    let since = summary::period_start(&digest, until);
+    let acme_cache_dir = acme_cache_dir(&state);
+
+    let summary = summary::collect_summary(
+        &state.db,
+        digest.team_id.as_deref(),
+        since,
+        until,
+        acme_cache_dir.as_deref(),
+    )
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to summarize: {}", e)))?;
+
+    Ok(Json(summary))
+}
+
+/// Send the digest now; the next scheduled send covers the period from here
+pub async fn send_digest_now(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<DigestSummary>, ApiError> {
+    let digest = find_digest(&state, &id).await?;
+    let acme_cache_dir = acme_cache_dir(&state);
+
+    let summary = summary::send_digest(
+        &state.db,
+        &digest,
+        &state.config.email,
+        acme_cache_dir.as_deref(),
+    )
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to send digest: {}", e)))?;
+
+    Ok(Json(summary))
+}
+
+async fn find_digest(state: &AppState, id: &str) -> Result<NotificationDigest, ApiError> {
+    NotificationDigest::get(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Notification digest not found"))
+}
+
+/// Where issued certificates are cached, when ACME is enabled
+fn acme_cache_dir(state: &AppState) -> Option<PathBuf> {
+    state
+        .config
+        .proxy
+        .acme_enabled
+        .then(|| state.config.proxy.acme_cache_dir.clone())
+}
+
+fn check_name(errors: &mut ValidationErrorBuilder, name: &str) {
+    if name.trim().is_empty() {
+        errors.add("name", "Name is required");
+    } else if name.len() > 100 {
+        errors.add("name", "Name must be 100 characters or less");
+    }
+}
+
+fn check_schedule(
+    errors: &mut ValidationErrorBuilder,
+    frequency: &str,
+    send_hour: i64,
+    send_weekday: i64,
+) {
+    if frequency != DIGEST_FREQUENCY_DAILY && frequency != DIGEST_FREQUENCY_WEEKLY {
+        errors.add("frequency", "Frequency must be 'daily' or 'weekly'");
+    }
+    if !(0..=23).contains(&send_hour) {
+        errors.add("send_hour", "Send hour must be between 0 and 23");
+    }
+    if !(0..=6).contains(&send_weekday) {
+        errors.add(
+            "send_weekday",
+            "Send weekday must be between 0 (Sunday) and 6 (Saturday)",
+        );
+    }
+}
+
+async fn check_recipients(
+    state: &AppState,
+    errors: &mut ValidationErrorBuilder,
+    channel_ids: &[String],
+    email_addresses: &[String],
+) -> Result<(), ApiError> {
+    if channel_ids.is_empty() && email_addresses.is_empty() {
+        errors.add(
+            "channel_ids",
+            "Select at least one channel or email address",
+        );
+    }
+    for channel_id in channel_ids {
+        let channel: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM notification_channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&state.db)
+                .await?;
+        if channel.is_none() {
+            errors.add(
+                "channel_ids",
+                format!("Notification channel '{}' not found", channel_id),
+            );
+        }
+    }
+    for address in email_addresses {
+        if !address.contains('@') || !address.contains('.') {
+            errors.add(
+                "email_addresses",
+                format!("'{}' is not a valid email address", address),
+            );
+        }
+    }
+    Ok(())
+}
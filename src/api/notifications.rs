@@ -1,17 +1,19 @@
 //! Notification channels and subscriptions API endpoints.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::{
     CreateNotificationChannelRequest, CreateNotificationSubscriptionRequest, NotificationChannel,
-    NotificationChannelResponse, NotificationSubscription, NotificationSubscriptionResponse,
-    TestNotificationRequest, UpdateNotificationChannelRequest,
+    NotificationChannelResponse, NotificationEventType, NotificationSubscription,
+    NotificationSubscriptionResponse, TestNotificationRequest, UpdateNotificationChannelRequest,
+    WebhookDelivery,
 };
 use crate::notifications::NotificationService;
 use crate::AppState;
@@ -257,6 +259,44 @@ pub async fn test_channel(
     })))
 }
 
+/// Deliveries returned when no limit is given
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+/// Most deliveries returned by one request
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub limit: Option<i64>,
+}
+
+/// List a webhook channel's recent deliveries, newest first
+pub async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "channel_id") {
+        return Err(ApiError::validation_field("channel_id", e));
+    }
+
+    // Verify channel exists
+    let _channel = sqlx::query_as::<_, NotificationChannel>(
+        "SELECT * FROM notification_channels WHERE id = ?",
+    )
+    .bind(&id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Notification channel not found"))?;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let deliveries = WebhookDelivery::list_for_channel(&state.db, &id, limit).await?;
+
+    Ok(Json(deliveries))
+}
+
 // -------------------------------------------------------------------------
 // Notification Subscriptions
 // -------------------------------------------------------------------------
@@ -330,6 +370,13 @@ pub async fn create_subscription(
     .await?
     .ok_or_else(|| ApiError::not_found("Notification channel not found"))?;
 
+    if req.event_type == NotificationEventType::Digest {
+        return Err(ApiError::validation_field(
+            "event_type",
+            "Digests are sent by notification digests, not subscriptions",
+        ));
+    }
+
     // Verify app exists if specified
     let app_name = if let Some(ref app_id) = req.app_id {
         if let Err(e) = validate_uuid(app_id, "app_id") {
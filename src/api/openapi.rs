@@ -0,0 +1,237 @@
+//! OpenAPI document for the REST API.
+//!
+//! GET /api/openapi.json — drives the dashboard's API explorer.
+//!
+//! The document is built from the endpoint catalog in
+//! `docs/reference/api.md`, embedded at compile time, so the catalog stays the
+//! single list of endpoints: every `| Method | Path | Purpose |` row becomes
+//! an operation tagged with its section, and `:name` path segments become
+//! path parameters. Like the catalog, it leaves out request and response
+//! schemas.
+
+use axum::{extract::State, Json};
+use serde_json::{json, Map, Value};
+use std::sync::{Arc, OnceLock};
+
+use crate::AppState;
+
+/// Endpoint catalog the document is generated from
+const API_REFERENCE: &str = include_str!("../../docs/reference/api.md");
+
+const HTTP_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// An endpoint listed in the catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Endpoint {
+    method: String,
+    /// Path in OpenAPI form (`/api/apps/{id}`)
+    path: String,
+    summary: String,
+    /// Catalog section the endpoint is listed under
+    tag: String,
+    /// Whether the endpoint is listed as not requiring a token
+    public: bool,
+}
+
+/// Endpoints in the order the catalog lists them
+fn parse_endpoints(reference: &str) -> Vec<Endpoint> {
+    let mut endpoints = Vec::new();
+    let mut tag = String::new();
+    let mut public = true;
+
+    for line in reference.lines() {
+        if let Some(heading) = line.strip_prefix("# ") {
+            // Everything after the top-level "Protected API" heading needs a token
+            if heading.starts_with("Protected") {
+                public = false;
+            }
+            continue;
+        }
+        if let Some(heading) = line
+            .strip_prefix("## ")
+            .or_else(|| line.strip_prefix("### "))
+        {
+            tag = heading.trim().to_string();
+            continue;
+        }
+
+        let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [_, method, path, summary, ..] = cells.as_slice() else {
+            continue;
+        };
+        if !HTTP_METHODS.contains(method) {
+            continue;
+        }
+        let Some(path) = path.strip_prefix('`').and_then(|p| p.strip_suffix('`')) else {
+            continue;
+        };
+
+        endpoints.push(Endpoint {
+            method: method.to_string(),
+            path: openapi_path(path),
+            summary: summary.trim_end_matches('.').to_string(),
+            public: public || tag.to_lowercase().contains("public"),
+            tag: tag.clone(),
+        });
+    }
+
+    endpoints
+}
+
+/// Convert `:name` path segments to OpenAPI `{name}` templates
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Stable operation ID, e.g. `get_api_apps_id_logs`
+fn operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_lowercase();
+    for part in path.split(|c: char| !c.is_ascii_alphanumeric()) {
+        if !part.is_empty() {
+            id.push('_');
+            id.push_str(part);
+        }
+    }
+    id
+}
+
+fn build_document(endpoints: &[Endpoint]) -> Value {
+    let mut paths = Map::new();
+    let mut tags: Vec<&str> = Vec::new();
+
+    for endpoint in endpoints {
+        if !tags.contains(&endpoint.tag.as_str()) {
+            tags.push(&endpoint.tag);
+        }
+
+        let parameters: Vec<Value> = endpoint
+            .path
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+
+        let mut operation = json!({
+            "operationId": operation_id(&endpoint.method, &endpoint.path),
+            "summary": endpoint.summary,
+            "tags": [endpoint.tag],
+            "parameters": parameters,
+            "responses": { "default": { "description": "See the handler for the payload" } },
+        });
+        if endpoint.public {
+            operation["security"] = json!([]);
+        }
+        if matches!(endpoint.method.as_str(), "POST" | "PUT" | "PATCH") {
+            operation["requestBody"] = json!({
+                "required": false,
+                "content": { "application/json": { "schema": { "type": "object" } } },
+            });
+        }
+
+        let item = paths
+            .entry(endpoint.path.clone())
+            .or_insert_with(|| json!({}));
+        let method = endpoint.method.to_lowercase();
+        // The first listing of an endpoint wins
+        if item.get(&method).is_none() {
+            item[method] = operation;
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Rivetr API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Generated from the endpoint catalog in docs/reference/api.md.",
+        },
+        "tags": tags.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer" },
+            },
+        },
+        "security": [{ "bearerAuth": [] }],
+    })
+}
+
+fn document() -> &'static Value {
+    static DOCUMENT: OnceLock<Value> = OnceLock::new();
+    DOCUMENT.get_or_init(|| build_document(&parse_endpoints(API_REFERENCE)))
+}
+
+/// GET /api/openapi.json
+///
+/// Returns the OpenAPI 3 document for this server, with its external URL
+/// as the server URL.
+pub async fn get_openapi_spec(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let base_url = state
+        .config
+        .server
+        .external_url
+        .clone()
+        .unwrap_or_else(|| format!("http://localhost:{}", state.config.server.api_port));
+
+    let mut document = document().clone();
+    document["servers"] = json!([{ "url": base_url.trim_end_matches('/') }]);
+    Json(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_rows_become_operations() {
+        let endpoints = parse_endpoints(API_REFERENCE);
+        assert!(endpoints.len() > 100);
+
+        let inbox = endpoints
+            .iter()
+            .find(|e| e.method == "GET" && e.path == "/api/notifications/inbox")
+            .expect("inbox endpoint is in the catalog");
+        assert_eq!(inbox.tag, "Notification inbox");
+        assert!(!inbox.public);
+
+        let login = endpoints
+            .iter()
+            .find(|e| e.path == "/api/auth/login")
+            .expect("login endpoint is in the catalog");
+        assert!(login.public);
+    }
+
+    #[test]
+    fn test_path_parameters_are_templated() {
+        assert_eq!(
+            openapi_path("/api/apps/:id/runs/:run_id"),
+            "/api/apps/{id}/runs/{run_id}"
+        );
+        assert_eq!(
+            operation_id("GET", "/api/apps/{id}/runs/{run_id}"),
+            "get_api_apps_id_runs_run_id"
+        );
+
+        let document = build_document(&parse_endpoints(
+            "# Protected API\n## Runs\n| POST | `/api/apps/:id/runs` | Start a run. |\n",
+        ));
+        let operation = &document["paths"]["/api/apps/{id}/runs"]["post"];
+        assert_eq!(operation["summary"], "Start a run");
+        assert_eq!(operation["parameters"][0]["name"], "id");
+        assert!(operation.get("requestBody").is_some());
+        assert!(operation.get("security").is_none());
+    }
+}
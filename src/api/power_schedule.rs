@@ -0,0 +1,170 @@
+//! Power schedule API endpoints for applications.
+//!
+//! An app's power schedule stops its containers on one cron expression and
+//! starts them again on another (e.g. office hours only). While stopped, the
+//! proxy serves a "stopped" page for the app's domains. Cron expressions are
+//! evaluated in the schedule's timezone (UTC by default); standard 5-field
+//! expressions are accepted.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::db::{AppPowerSchedule, UpdatePowerScheduleRequest};
+use crate::utils::cron;
+use crate::AppState;
+
+use super::common::ensure_app_exists;
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Longest allowed stopped-page message
+const MAX_MESSAGE_LEN: usize = 500;
+
+/// Get the power schedule for an app (null if none is configured)
+pub async fn get_power_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Option<AppPowerSchedule>>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    Ok(Json(AppPowerSchedule::get(&state.db, &app_id).await?))
+}
+
+/// Create or update the power schedule for an app
+pub async fn update_power_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdatePowerScheduleRequest>,
+) -> Result<Json<AppPowerSchedule>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let existing = AppPowerSchedule::get(&state.db, &app_id).await?;
+
+    let enabled = req
+        .enabled
+        .unwrap_or_else(|| existing.as_ref().map(|s| s.enabled != 0).unwrap_or(true));
+    let stop_cron = req
+        .stop_cron
+        .clone()
+        .or_else(|| existing.as_ref().map(|s| s.stop_cron.clone()));
+    let start_cron = req
+        .start_cron
+        .clone()
+        .or_else(|| existing.as_ref().map(|s| s.start_cron.clone()));
+    let stopped_message = match req.stopped_message.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(message) => Some(message.to_string()),
+        None => existing.as_ref().and_then(|s| s.stopped_message.clone()),
+    };
+    let timezone = match req.timezone.as_deref() {
+        Some(timezone) => Some(timezone.to_string()),
+        None => existing.as_ref().and_then(|s| s.timezone.clone()),
+    };
+
+    let mut errors = ValidationErrorBuilder::new();
+    let stop_cron = checked_cron(&mut errors, "stop_cron", stop_cron);
+    let start_cron = checked_cron(&mut errors, "start_cron", start_cron);
+    let timezone = cron::validate_timezone(timezone.as_deref()).unwrap_or_else(|e| {
+        errors.add("timezone", &e);
+        None
+    });
+    if stopped_message
+        .as_ref()
+        .is_some_and(|m| m.len() > MAX_MESSAGE_LEN)
+    {
+        errors.add(
+            "stopped_message",
+            format!("Must be at most {} characters", MAX_MESSAGE_LEN),
+        );
+    }
+    errors.finish()?;
+
+    // Changing the schedule recomputes the next runs; an app the schedule has
+    // already stopped stays stopped until the next start (or a manual start).
+    let next_stop_at = cron::next_run(&stop_cron, timezone.as_deref());
+    let next_start_at = cron::next_run(&start_cron, timezone.as_deref());
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_power_schedules
+            (app_id, enabled, stop_cron, start_cron, stopped_message, timezone,
+             next_stop_at, next_start_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(app_id) DO UPDATE SET
+            enabled = excluded.enabled,
+            stop_cron = excluded.stop_cron,
+            start_cron = excluded.start_cron,
+            stopped_message = excluded.stopped_message,
+            timezone = excluded.timezone,
+            next_stop_at = excluded.next_stop_at,
+            next_start_at = excluded.next_start_at,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(enabled)
+    .bind(&stop_cron)
+    .bind(&start_cron)
+    .bind(&stopped_message)
+    .bind(&timezone)
+    .bind(&next_stop_at)
+    .bind(&next_start_at)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update power schedule: {}", e);
+        ApiError::database("Failed to update power schedule")
+    })?;
+
+    let schedule = AppPowerSchedule::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Power schedule missing after update"))?;
+
+    tracing::info!(
+        app_id = %app_id,
+        enabled = enabled,
+        stop_cron = %schedule.stop_cron,
+        start_cron = %schedule.start_cron,
+        "Power schedule updated"
+    );
+
+    Ok(Json(schedule))
+}
+
+/// Remove the power schedule for an app.
+///
+/// An app the schedule currently keeps stopped is not started; start it manually.
+pub async fn delete_power_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let result = sqlx::query("DELETE FROM app_power_schedules WHERE app_id = ?")
+        .bind(&app_id)
+        .execute(&state.db)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("No power schedule configured"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Validate a cron field, returning it normalized (empty if an error was recorded)
+fn checked_cron(errors: &mut ValidationErrorBuilder, field: &str, expr: Option<String>) -> String {
+    let Some(expr) = expr else {
+        errors.add(field, "A cron expression is required");
+        return String::new();
+    };
+    cron::validate(&expr).unwrap_or_else(|e| {
+        errors.add(field, &e);
+        String::new()
+    })
+}
@@ -0,0 +1,217 @@
+//! Preferences API endpoints for the current user.
+//!
+//! Preferences are personal: the timezone used for the user's digests, the
+//! locale of the dashboard and their messages, the dashboard theme and
+//! whether it reduces motion, which notification severities
+//! they receive and whether their notification email is batched into a daily
+//! digest. They apply wherever the user's email address is a recipient of an
+//! email channel, without changing the channel.
+
+use axum::{extract::State, Json};
+use std::sync::Arc;
+
+use crate::db::{
+    UpdateUserPreferencesRequest, User, UserPreferences, UserPreferencesResponse,
+    NOTIFICATION_SEVERITIES, THEMES,
+};
+use crate::i18n::{self, Locale, SUPPORTED_LOCALES};
+use crate::utils::cron;
+use crate::AppState;
+
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Get the current user's preferences (defaults if never saved)
+pub async fn get_preferences(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<UserPreferencesResponse>, ApiError> {
+    let prefs = UserPreferences::get(&state.db, &user.id).await?;
+    Ok(Json(UserPreferencesResponse::from_stored(prefs.as_ref())))
+}
+
+/// Update the current user's preferences
+pub async fn update_preferences(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Json(req): Json<UpdateUserPreferencesRequest>,
+) -> Result<Json<UserPreferencesResponse>, ApiError> {
+    let existing = UserPreferences::get(&state.db, &user.id).await?;
+    let current = UserPreferencesResponse::from_stored(existing.as_ref());
+
+    let mut errors = ValidationErrorBuilder::new();
+    let timezone = match req.timezone.as_deref() {
+        Some(timezone) => cron::validate_timezone(Some(timezone)).unwrap_or_else(|e| {
+            errors.add("timezone", &e);
+            None
+        }),
+        None => current.timezone,
+    };
+    let severities = match req.notify_severities {
+        Some(requested) => {
+            if let Some(unknown) = requested
+                .iter()
+                .find(|s| !NOTIFICATION_SEVERITIES.contains(&s.as_str()))
+            {
+                errors.add(
+                    "notify_severities",
+                    format!(
+                        "Unknown severity '{}' (expected one of: {})",
+                        unknown,
+                        NOTIFICATION_SEVERITIES.join(", ")
+                    ),
+                );
+            }
+            // Stored in canonical order, without duplicates
+            NOTIFICATION_SEVERITIES
+                .iter()
+                .filter(|s| requested.iter().any(|r| r == **s))
+                .map(|s| s.to_string())
+                .collect()
+        }
+        None => current.notify_severities,
+    };
+    let locale = match req.locale.as_deref().map(str::trim) {
+        Some("") => Locale::default(),
+        Some(code) => Locale::from_code(code).unwrap_or_else(|| {
+            let supported: Vec<&str> = SUPPORTED_LOCALES.iter().map(|l| l.code()).collect();
+            errors.add(
+                "locale",
+                i18n::t_args(
+                    i18n::request_locale(),
+                    "error.unknown_locale",
+                    &[("locale", code), ("supported", &supported.join(", "))],
+                ),
+            );
+            Locale::default()
+        }),
+        None => Locale::from_code(&current.locale).unwrap_or_default(),
+    };
+    let theme = match req.theme {
+        Some(theme) => {
+            if !THEMES.contains(&theme.as_str()) {
+                errors.add(
+                    "theme",
+                    format!(
+                        "Unknown theme '{}' (expected one of: {})",
+                        theme,
+                        THEMES.join(", ")
+                    ),
+                );
+            }
+            theme
+        }
+        None => current.theme,
+    };
+    errors.finish()?;
+
+    let email_digests = req.email_digests.unwrap_or(current.email_digests);
+    let reduced_motion = req.reduced_motion.unwrap_or(current.reduced_motion);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_preferences
+            (user_id, timezone, email_digests, notify_severities, locale, theme,
+             reduced_motion, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(user_id) DO UPDATE SET
+            timezone = excluded.timezone,
+            email_digests = excluded.email_digests,
+            notify_severities = excluded.notify_severities,
+            locale = excluded.locale,
+            theme = excluded.theme,
+            reduced_motion = excluded.reduced_motion,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&user.id)
+    .bind(&timezone)
+    .bind(email_digests)
+    .bind(serde_json::to_string(&severities).unwrap_or_else(|_| "[]".to_string()))
+    .bind(locale.code())
+    .bind(&theme)
+    .bind(reduced_motion)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update user preferences: {}", e);
+        ApiError::database("Failed to update preferences")
+    })?;
+
+    tracing::info!(
+        user_id = %user.id,
+        email_digests = email_digests,
+        severities = ?severities,
+        locale = %locale,
+        theme = %theme,
+        reduced_motion = reduced_motion,
+        "User preferences updated"
+    );
+
+    let prefs = UserPreferences::get(&state.db, &user.id).await?;
+    Ok(Json(UserPreferencesResponse::from_stored(prefs.as_ref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_user;
+
+    fn request(body: serde_json::Value) -> Json<UpdateUserPreferencesRequest> {
+        Json(serde_json::from_value(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_theme_and_reduced_motion_are_saved() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let user = test_user(&state.db, "member").await;
+
+        let Json(prefs) = get_preferences(State(state.clone()), user.clone())
+            .await
+            .unwrap();
+        assert_eq!(prefs.theme, "system");
+        assert!(!prefs.reduced_motion);
+
+        let Json(prefs) = update_preferences(
+            State(state.clone()),
+            user.clone(),
+            request(serde_json::json!({ "theme": "high-contrast", "reduced_motion": true })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(prefs.theme, "high-contrast");
+        assert!(prefs.reduced_motion);
+
+        // Unrelated updates keep the appearance
+        let Json(prefs) = update_preferences(
+            State(state.clone()),
+            user.clone(),
+            request(serde_json::json!({ "locale": "ar" })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(prefs.locale, "ar");
+        assert_eq!(prefs.theme, "high-contrast");
+        assert!(prefs.reduced_motion);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_theme_is_rejected() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let user = test_user(&state.db, "member").await;
+
+        let result = update_preferences(
+            State(state.clone()),
+            user.clone(),
+            request(serde_json::json!({ "theme": "solarized", "reduced_motion": true })),
+        )
+        .await;
+        assert!(result.is_err());
+
+        let Json(prefs) = get_preferences(State(state.clone()), user).await.unwrap();
+        assert_eq!(prefs.theme, "system");
+        assert!(!prefs.reduced_motion);
+    }
+}
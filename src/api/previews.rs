@@ -207,6 +207,7 @@ pub async fn redeploy_preview(
         .encryption_key
         .as_ref()
         .map(|secret| crypto::derive_key(secret));
+    let sandbox = state.config.build_sandbox.clone();
 
     tokio::spawn(async move {
         if let Err(e) = run_preview_deployment(
@@ -216,6 +217,7 @@ pub async fn redeploy_preview(
             &preview_clone,
             &app_clone,
             encryption_key.as_ref(),
+            &sandbox,
         )
         .await
         {
@@ -0,0 +1,291 @@
+//! Pre-warmed deployment slot API endpoints.
+//!
+//! A slot holds an app's next image, built (or pulled) ahead of release without
+//! switching traffic. It is filled on demand, on an off-peak cron schedule
+//! (UTC) or, with `on_push`, by push webhooks; promoting it releases the image
+//! through the normal deployment pipeline with the build step skipped.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::db::{
+    actions, resource_types, App, AppPrewarmSettings, Deployment, DeploymentEvent,
+    PrewarmStatusResponse, UpdatePrewarmRequest, User,
+};
+use crate::engine::prewarm::{queue_prewarm, retire_slot, PrewarmCommit};
+use crate::utils::cron;
+use crate::AppState;
+
+use super::apps::check_deploy_lock;
+use super::audit::{audit_log, ClientIp};
+use super::deployments::{approval_required, check_freeze_windows};
+use super::error::{ApiError, ValidationErrorBuilder};
+use super::system::check_host_cordon;
+use super::validation::validate_uuid;
+
+/// Get the pre-warm settings and current slot for an app
+pub async fn get_prewarm(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<PrewarmStatusResponse>, ApiError> {
+    get_app(&state, &app_id).await?;
+
+    Ok(Json(load_status(&state, &app_id).await?))
+}
+
+/// Create or update the pre-warm settings for an app
+pub async fn update_prewarm(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdatePrewarmRequest>,
+) -> Result<Json<PrewarmStatusResponse>, ApiError> {
+    let app = get_app(&state, &app_id).await?;
+    let existing = AppPrewarmSettings::get(&state.db, &app_id).await?;
+
+    let enabled = req
+        .enabled
+        .unwrap_or_else(|| existing.as_ref().map(|s| s.enabled != 0).unwrap_or(true));
+    let on_push = req
+        .on_push
+        .unwrap_or_else(|| existing.as_ref().map(|s| s.on_push != 0).unwrap_or(false));
+    let schedule_cron = match req.schedule_cron.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(expr) => Some(expr.to_string()),
+        None => existing.as_ref().and_then(|s| s.schedule_cron.clone()),
+    };
+    let timezone = match req.timezone.as_deref() {
+        Some(timezone) => Some(timezone.to_string()),
+        None => existing.as_ref().and_then(|s| s.timezone.clone()),
+    };
+
+    let mut errors = ValidationErrorBuilder::new();
+    let schedule_cron = schedule_cron.and_then(|expr| match cron::validate(&expr) {
+        Ok(normalized) => Some(normalized),
+        Err(e) => {
+            errors.add("schedule_cron", &e);
+            None
+        }
+    });
+    let timezone = cron::validate_timezone(timezone.as_deref()).unwrap_or_else(|e| {
+        errors.add("timezone", &e);
+        None
+    });
+    if app.deployment_source.as_deref() == Some("upload") {
+        errors.add(
+            "enabled",
+            "Upload-based apps cannot be pre-warmed; upload a new archive instead",
+        );
+    }
+    errors.finish()?;
+
+    let next_run_at = schedule_cron
+        .as_deref()
+        .and_then(|expr| cron::next_run(expr, timezone.as_deref()));
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_prewarm_settings
+            (app_id, enabled, schedule_cron, on_push, timezone, next_run_at,
+             created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+        ON CONFLICT(app_id) DO UPDATE SET
+            enabled = excluded.enabled,
+            schedule_cron = excluded.schedule_cron,
+            on_push = excluded.on_push,
+            timezone = excluded.timezone,
+            next_run_at = excluded.next_run_at,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(enabled)
+    .bind(&schedule_cron)
+    .bind(on_push)
+    .bind(&timezone)
+    .bind(&next_run_at)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update pre-warm settings: {}", e);
+        ApiError::database("Failed to update pre-warm settings")
+    })?;
+
+    tracing::info!(
+        app_id = %app_id,
+        enabled = enabled,
+        on_push = on_push,
+        schedule = ?schedule_cron,
+        "Pre-warm settings updated"
+    );
+
+    Ok(Json(load_status(&state, &app_id).await?))
+}
+
+/// Remove the pre-warm settings for an app, discarding its slot
+pub async fn delete_prewarm(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    get_app(&state, &app_id).await?;
+
+    let settings = AppPrewarmSettings::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Pre-warming is not configured"))?;
+    if let Some(ref deployment_id) = settings.deployment_id {
+        retire_slot(&state.db, deployment_id)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to discard slot: {}", e)))?;
+    }
+
+    sqlx::query("DELETE FROM app_prewarm_settings WHERE app_id = ?")
+        .bind(&app_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Build the branch head into the slot now (replacing any previous slot)
+pub async fn build_prewarm(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<(StatusCode, Json<Deployment>), ApiError> {
+    let app = get_app(&state, &app_id).await?;
+    if app.deployment_source.as_deref() == Some("upload") {
+        return Err(ApiError::bad_request(
+            "Upload-based apps cannot be pre-warmed; upload a new archive instead",
+        ));
+    }
+
+    let deployment_id = queue_prewarm(&state.db, &state.deploy_tx, &app, PrewarmCommit::default())
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to queue pre-warm build: {}", e)))?;
+
+    let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+        .bind(&deployment_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(deployment)))
+}
+
+/// Release the pre-warmed image: re-queue the slot's deployment, which skips
+/// the build and only starts the container and switches traffic.
+pub async fn promote_prewarm(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Path(app_id): Path<String>,
+) -> Result<(StatusCode, Json<Deployment>), ApiError> {
+    let app = get_app(&state, &app_id).await?;
+
+    let settings = AppPrewarmSettings::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Pre-warming is not configured"))?;
+    let slot = settings
+        .slot(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("No pre-warmed build to promote"))?;
+    if slot.status != "prewarmed" {
+        return Err(ApiError::bad_request(format!(
+            "The pre-warmed build is not ready (status: {})",
+            slot.status
+        )));
+    }
+
+    check_deploy_lock(&app)?;
+    check_host_cordon(&state)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    check_freeze_windows(&state, &app, &now).await?;
+
+    // Same approval rule as a triggered deployment
+    let needs_approval = approval_required(&state.db, &app).await? && user.role != "admin";
+    let approval_status: Option<&str> = needs_approval.then_some("pending");
+
+    // Release the slot first so the engine runs this as a regular deployment
+    sqlx::query(
+        "UPDATE app_prewarm_settings SET deployment_id = NULL, updated_at = datetime('now') \
+         WHERE app_id = ? AND deployment_id = ?",
+    )
+    .bind(&app_id)
+    .bind(&slot.id)
+    .execute(&state.db)
+    .await?;
+    let result = sqlx::query(
+        "UPDATE deployments SET status = 'pending', started_at = ?, finished_at = NULL, \
+         approval_status = ? WHERE id = ? AND status = 'prewarmed'",
+    )
+    .bind(&now)
+    .bind(approval_status)
+    .bind(&slot.id)
+    .execute(&state.db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::conflict(
+            "The pre-warmed build was already promoted",
+        ));
+    }
+    DeploymentEvent::record(
+        &state.db,
+        &slot.id,
+        "pending",
+        Some("Promoted from pre-warmed build"),
+    )
+    .await;
+
+    if !needs_approval {
+        if let Err(e) = state.deploy_tx.send((slot.id.clone(), app.clone())).await {
+            tracing::error!("Failed to queue promoted deployment: {}", e);
+            return Err(ApiError::internal("Failed to queue deployment job"));
+        }
+    }
+
+    audit_log(
+        &state,
+        actions::DEPLOYMENT_PROMOTE,
+        resource_types::APP,
+        Some(&app.id),
+        Some(&app.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "deployment_id": slot.id,
+            "image_tag": slot.image_tag,
+        })),
+    )
+    .await;
+
+    let deployment = sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+        .bind(&slot.id)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(deployment)))
+}
+
+async fn load_status(
+    state: &Arc<AppState>,
+    app_id: &str,
+) -> Result<PrewarmStatusResponse, ApiError> {
+    let settings = AppPrewarmSettings::get(&state.db, app_id).await?;
+    let slot = match settings {
+        Some(ref s) => s.slot(&state.db).await?,
+        None => None,
+    };
+    Ok(PrewarmStatusResponse { settings, slot })
+}
+
+async fn get_app(state: &Arc<AppState>, app_id: &str) -> Result<App, ApiError> {
+    if let Err(e) = validate_uuid(app_id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+    sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))
+}
@@ -0,0 +1,159 @@
+//! Load-balancer health probes.
+//!
+//! - `GET /livez` answers as long as the process is up and serving requests.
+//! - `GET /readyz` reports whether this instance can actually take traffic:
+//!   the database is reachable, the container runtime responds, the proxy
+//!   listener is bound, and startup self-checks passed. Each component is
+//!   reported individually and the endpoint returns 503 until all are ready.
+//!
+//! The legacy `/health` endpoint is kept as-is for existing monitors.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::AppState;
+
+/// Upper bound on each readiness probe so a hung dependency can't stall the
+/// load balancer's health check.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Readiness flags flipped by startup code as subsystems come online.
+#[derive(Debug, Default)]
+pub struct ReadinessState {
+    /// Set once the HTTP proxy listener is bound
+    pub proxy_bound: Arc<AtomicBool>,
+    /// Set once startup self-checks have passed (or were skipped)
+    pub startup_checks_passed: AtomicBool,
+}
+
+impl ReadinessState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of the startup self-checks
+    pub fn set_startup_checks_passed(&self, passed: bool) {
+        self.startup_checks_passed.store(passed, Ordering::Relaxed);
+    }
+}
+
+/// Status of a single readiness component
+#[derive(Debug, Serialize)]
+pub struct ComponentStatus {
+    pub name: &'static str,
+    pub ready: bool,
+    pub message: String,
+}
+
+impl ComponentStatus {
+    fn new(name: &'static str, ready: bool, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            ready,
+            message: message.into(),
+        }
+    }
+}
+
+/// Body returned by `/readyz`
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    /// "ready" or "not_ready"
+    pub status: &'static str,
+    pub components: Vec<ComponentStatus>,
+}
+
+impl ReadinessResponse {
+    fn from_components(components: Vec<ComponentStatus>) -> Self {
+        let ready = components.iter().all(|c| c.ready);
+        Self {
+            status: if ready { "ready" } else { "not_ready" },
+            components,
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+}
+
+/// GET /livez - Process liveness. Never touches dependencies.
+pub async fn livez() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "status": "alive" })),
+    )
+}
+
+/// GET /readyz - Traffic readiness with per-component detail.
+///
+/// Returns 200 when every component is ready, 503 otherwise.
+pub async fn readyz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let database =
+        match tokio::time::timeout(PROBE_TIMEOUT, sqlx::query("SELECT 1").fetch_one(&state.db))
+            .await
+        {
+            Ok(Ok(_)) => ComponentStatus::new("database", true, "reachable"),
+            Ok(Err(e)) => {
+                // Keep driver errors out of the unauthenticated response
+                tracing::warn!(error = %e, "Readiness probe: database query failed");
+                ComponentStatus::new("database", false, "unavailable")
+            }
+            Err(_) => ComponentStatus::new("database", false, "timed out"),
+        };
+
+    let runtime = match tokio::time::timeout(PROBE_TIMEOUT, state.runtime.is_available()).await {
+        Ok(true) => ComponentStatus::new("runtime", true, "reachable"),
+        Ok(false) => ComponentStatus::new("runtime", false, "container runtime unavailable"),
+        Err(_) => ComponentStatus::new("runtime", false, "timed out"),
+    };
+
+    let proxy = if state.readiness.proxy_bound.load(Ordering::Relaxed) {
+        ComponentStatus::new("proxy", true, "listener bound")
+    } else {
+        ComponentStatus::new("proxy", false, "listener not bound")
+    };
+
+    let startup = if state
+        .readiness
+        .startup_checks_passed
+        .load(Ordering::Relaxed)
+    {
+        ComponentStatus::new("startup_checks", true, "passed")
+    } else {
+        ComponentStatus::new("startup_checks", false, "not passed")
+    };
+
+    let response = ReadinessResponse::from_components(vec![database, runtime, proxy, startup]);
+    let status = if response.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_requires_all_components() {
+        let ready = ReadinessResponse::from_components(vec![
+            ComponentStatus::new("database", true, "reachable"),
+            ComponentStatus::new("proxy", true, "listener bound"),
+        ]);
+        assert!(ready.is_ready());
+
+        let not_ready = ReadinessResponse::from_components(vec![
+            ComponentStatus::new("database", true, "reachable"),
+            ComponentStatus::new("proxy", false, "listener not bound"),
+        ]);
+        assert!(!not_ready.is_ready());
+        assert_eq!(not_ready.status, "not_ready");
+    }
+}
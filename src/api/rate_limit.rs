@@ -5,13 +5,13 @@
 
 use axum::{
     body::Body,
-    extract::State,
+    extract::{ConnectInfo, State},
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -51,11 +51,20 @@ impl RateLimitEntry {
     }
 }
 
+/// Who a rate limit applies to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RateLimitKey {
+    /// A client address (normalized by `rate_limit_key`)
+    Ip(IpAddr),
+    /// An account name, e.g. the email a sign-in form was submitted for
+    Account(String),
+}
+
 /// Thread-safe rate limiter using dashmap
 #[derive(Debug)]
 pub struct RateLimiter {
-    /// Map of (IP, Tier) -> RateLimitEntry
-    entries: DashMap<(IpAddr, RateLimitTier), RateLimitEntry>,
+    /// Map of (client IP or account, Tier) -> RateLimitEntry
+    entries: DashMap<(RateLimitKey, RateLimitTier), RateLimitEntry>,
     /// Configuration
     config: RateLimitConfig,
     /// Window duration
@@ -75,6 +84,22 @@ impl RateLimiter {
     /// Check if a request should be allowed and consume a token if so.
     /// Returns Ok(remaining_tokens) if allowed, Err(retry_after_seconds) if rate limited.
     pub fn check_rate_limit(&self, ip: IpAddr, tier: RateLimitTier) -> Result<RateLimitInfo, u64> {
+        self.check(RateLimitKey::Ip(rate_limit_key(ip)), tier)
+    }
+
+    /// Like `check_rate_limit`, but for attempts on one account (e.g. sign-ins
+    /// for an email) no matter which addresses they come from. Names are
+    /// compared case-insensitively.
+    pub fn check_account_rate_limit(
+        &self,
+        account: &str,
+        tier: RateLimitTier,
+    ) -> Result<RateLimitInfo, u64> {
+        let account = account.trim().to_lowercase();
+        self.check(RateLimitKey::Account(account), tier)
+    }
+
+    fn check(&self, key: RateLimitKey, tier: RateLimitTier) -> Result<RateLimitInfo, u64> {
         if !self.config.enabled {
             return Ok(RateLimitInfo {
                 remaining: u32::MAX,
@@ -84,7 +109,7 @@ impl RateLimiter {
         }
 
         let max_tokens = self.get_max_tokens(tier);
-        let key = (ip, tier);
+        let key = (key, tier);
         let now = Instant::now();
 
         let mut entry = self
@@ -163,14 +188,29 @@ pub struct RateLimitInfo {
     pub reset_after: u64,
 }
 
-/// Extract client IP from request headers
+/// Normalize a client IP into the identity that is rate limited.
+///
+/// IPv4-mapped IPv6 addresses (seen on dual-stack sockets) count as the IPv4
+/// client, and IPv6 clients are grouped by /64 since a single host usually
+/// controls a whole /64 and could otherwise rotate addresses to evade limits.
+fn rate_limit_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(v6) => {
+            let prefix = u128::from(v6) & !(u64::MAX as u128);
+            IpAddr::V6(Ipv6Addr::from(prefix))
+        }
+        v4 => v4,
+    }
+}
+
+/// Extract client IP from request headers, falling back to the peer address
 fn extract_client_ip(request: &Request<Body>) -> IpAddr {
     // Check X-Forwarded-For header first (for reverse proxy setups)
     if let Some(forwarded) = request.headers().get("x-forwarded-for") {
         if let Ok(value) = forwarded.to_str() {
             // Take the first IP in the list (original client)
             if let Some(ip_str) = value.split(',').next() {
-                if let Ok(ip) = ip_str.trim().parse::<IpAddr>() {
+                if let Some(ip) = parse_ip(ip_str) {
                     return ip;
                 }
             }
@@ -180,15 +220,29 @@ fn extract_client_ip(request: &Request<Body>) -> IpAddr {
     // Check X-Real-IP header
     if let Some(real_ip) = request.headers().get("x-real-ip") {
         if let Ok(value) = real_ip.to_str() {
-            if let Ok(ip) = value.trim().parse::<IpAddr>() {
+            if let Some(ip) = parse_ip(value) {
                 return ip;
             }
         }
     }
 
-    // Fallback to a default IP (in production, you'd want to get the actual connection IP)
-    // This is a reasonable fallback for local development
-    "127.0.0.1".parse().unwrap()
+    // The connecting peer (IPv4 or IPv6 listener)
+    if let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        return addr.ip().to_canonical();
+    }
+
+    // No connection info (e.g. in tests): treat as a local client
+    IpAddr::from([127, 0, 0, 1])
+}
+
+/// Parse an IP from a header value, accepting bracketed IPv6 (`[2001:db8::1]`)
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(value);
+    value.parse::<IpAddr>().ok().map(|ip| ip.to_canonical())
 }
 
 /// Rate limiting middleware for general API endpoints
@@ -401,4 +455,73 @@ mod tests {
         limiter.cleanup_expired();
         assert_eq!(limiter.entry_count(), 1);
     }
+
+    #[test]
+    fn test_account_limits_span_addresses() {
+        let limiter = RateLimiter::new(test_config());
+        for _ in 0..5 {
+            assert!(limiter
+                .check_account_rate_limit("ada@example.com", RateLimitTier::Auth)
+                .is_ok());
+        }
+        // Same account in another case; the address limit is separate
+        assert!(limiter
+            .check_account_rate_limit(" Ada@Example.com", RateLimitTier::Auth)
+            .is_err());
+        assert!(limiter
+            .check_account_rate_limit("bob@example.com", RateLimitTier::Auth)
+            .is_ok());
+        let ip: IpAddr = "192.168.1.1".parse().unwrap();
+        assert!(limiter.check_rate_limit(ip, RateLimitTier::Auth).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_key_normalizes_ipv6() {
+        let mapped: IpAddr = "::ffff:192.168.1.1".parse().unwrap();
+        let v4: IpAddr = "192.168.1.1".parse().unwrap();
+        assert_eq!(rate_limit_key(mapped), v4);
+
+        let a: IpAddr = "2001:db8:1:2:aaaa::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1:2:bbbb::2".parse().unwrap();
+        let other: IpAddr = "2001:db8:1:3::1".parse().unwrap();
+        assert_eq!(rate_limit_key(a), rate_limit_key(b));
+        assert_ne!(rate_limit_key(a), rate_limit_key(other));
+        assert_eq!(
+            rate_limit_key(a),
+            "2001:db8:1:2::".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ipv6_clients_in_same_prefix_share_limit() {
+        let limiter = RateLimiter::new(test_config());
+        for i in 0..10u16 {
+            let ip = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, i));
+            assert!(limiter.check_rate_limit(ip, RateLimitTier::Api).is_ok());
+        }
+        let rotated: IpAddr = "2001:db8:0:1::ffff".parse().unwrap();
+        assert!(limiter
+            .check_rate_limit(rotated, RateLimitTier::Api)
+            .is_err());
+    }
+
+    #[test]
+    fn test_extract_client_ip_ipv6() {
+        let request = Request::builder()
+            .header("x-forwarded-for", "[2001:db8::7], 10.0.0.1")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            extract_client_ip(&request),
+            "2001:db8::7".parse::<IpAddr>().unwrap()
+        );
+
+        let mut request = Request::builder().body(Body::empty()).unwrap();
+        let peer: SocketAddr = "[::ffff:203.0.113.9]:50000".parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(peer));
+        assert_eq!(
+            extract_client_ip(&request),
+            "203.0.113.9".parse::<IpAddr>().unwrap()
+        );
+    }
 }
@@ -16,6 +16,7 @@ use crate::db::{AppRedirectRule, CreateRedirectRuleRequest, UpdateRedirectRuleRe
 use crate::proxy::RedirectRule;
 use crate::AppState;
 
+use super::common::refresh_proxy_routes;
 use super::error::ApiError;
 use super::validation::validate_uuid;
 
@@ -102,7 +103,7 @@ pub async fn create_redirect_rule(
             .await?;
 
     // Refresh proxy routes for this app
-    refresh_proxy_routes(&state, &app_id).await;
+    apply_redirect_rules(&state, &app_id).await;
 
     Ok((StatusCode::CREATED, Json(rule)))
 }
@@ -179,7 +180,7 @@ pub async fn update_redirect_rule(
             .await?;
 
     // Refresh proxy routes for this app
-    refresh_proxy_routes(&state, &app_id).await;
+    apply_redirect_rules(&state, &app_id).await;
 
     Ok(Json(updated))
 }
@@ -207,7 +208,7 @@ pub async fn delete_redirect_rule(
     }
 
     // Refresh proxy routes for this app
-    refresh_proxy_routes(&state, &app_id).await;
+    apply_redirect_rules(&state, &app_id).await;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -231,7 +232,7 @@ fn validate_regex(pattern: &str) -> Result<(), ApiError> {
 /// Reload the redirect rules for an app into its live proxy backend entries.
 ///
 /// Fetches all enabled rules from DB and updates every domain route for the app.
-async fn refresh_proxy_routes(state: &Arc<AppState>, app_id: &str) {
+async fn apply_redirect_rules(state: &Arc<AppState>, app_id: &str) {
     // Load enabled redirect rules
     let rules: Vec<AppRedirectRule> = match sqlx::query_as(
         "SELECT * FROM app_redirect_rules WHERE app_id = ? AND is_enabled = 1 ORDER BY sort_order ASC, created_at ASC",
@@ -256,58 +257,14 @@ async fn refresh_proxy_routes(state: &Arc<AppState>, app_id: &str) {
         })
         .collect();
 
-    // Fetch the app's domain info to find which routes to update
-    let app_info: Option<(
-        Option<String>, // domain
-        Option<String>, // domains (JSON)
-        Option<String>, // auto_subdomain
-    )> = sqlx::query_as("SELECT domain, domains, auto_subdomain FROM apps WHERE id = ?")
-        .bind(app_id)
-        .fetch_optional(&state.db)
-        .await
-        .unwrap_or(None);
-
-    let (legacy_domain, domains_json, auto_subdomain) = match app_info {
-        Some(t) => t,
-        None => return,
-    };
-
-    // Collect all domain names for this app
-    let mut domain_names: Vec<String> = Vec::new();
-
-    if let Some(ref json) = domains_json {
-        if let Ok(arr) = serde_json::from_str::<serde_json::Value>(json) {
-            if let Some(list) = arr.as_array() {
-                for entry in list {
-                    if let Some(d) = entry.get("domain").and_then(|v| v.as_str()) {
-                        if !d.is_empty() && !domain_names.contains(&d.to_string()) {
-                            domain_names.push(d.to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-    if let Some(ref d) = legacy_domain {
-        if !d.is_empty() && !domain_names.contains(d) {
-            domain_names.push(d.clone());
-        }
-    }
-    if let Some(ref d) = auto_subdomain {
-        if !d.is_empty() && !domain_names.contains(d) {
-            domain_names.push(d.clone());
-        }
-    }
-
-    let route_table = state.routes.load();
-
-    for domain in &domain_names {
-        route_table.update_redirect_rules(domain, proxy_rules.clone());
-    }
+    let domains = refresh_proxy_routes(state, app_id, false, |routes, domain, _| {
+        routes.update_redirect_rules(domain, proxy_rules.clone());
+    })
+    .await;
 
     tracing::info!(
         app_id = %app_id,
-        domains = ?domain_names,
+        domains = ?domains,
         rules_count = proxy_rules.len(),
         "Redirect rules refreshed in proxy route table"
     );
@@ -2,21 +2,54 @@ use axum::{
     extract::{Path, State},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::db::{App, AppReplica};
-use crate::proxy::Backend;
+use crate::crypto;
+use crate::db::{App, AppProcess, AppReplica, ProcessStatus, PROCESS_RELEASE, PROCESS_WEB};
+use crate::engine::processes::{self, MAX_PROCESS_REPLICAS};
 use crate::AppState;
 
 use super::error::ApiError;
 use super::validation::validate_uuid;
 
+/// Key length for AES-256 encryption
+const KEY_LENGTH: usize = 32;
+
+/// Get the derived encryption key from the config if configured
+fn get_encryption_key(state: &AppState) -> Option<[u8; KEY_LENGTH]> {
+    state
+        .config
+        .auth
+        .encryption_key
+        .as_ref()
+        .map(|secret| crypto::derive_key(secret))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SetReplicaCountRequest {
     pub count: i64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ScaleAppRequest {
+    pub replicas: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScaleProcessRequest {
+    pub replicas: i64,
+}
+
+/// Rolling deploy budget for multi-replica apps
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RolloutSettings {
+    /// New replicas started above the replica count at once
+    pub max_surge: i64,
+    /// Old replicas that may leave the pool before their replacements are healthy
+    pub max_unavailable: i64,
+}
+
 /// GET /api/apps/:id/replicas — list replicas with status
 pub async fn list_replicas(
     State(state): State<Arc<AppState>>,
@@ -75,171 +108,16 @@ pub async fn set_replica_count(
         .execute(&state.db)
         .await?;
 
-    // Check if app is currently running by looking for a running deployment
-    let running_deployment: Option<(String, Option<String>)> = sqlx::query_as(
-        "SELECT id, container_id FROM deployments WHERE app_id = ? AND status = 'running' ORDER BY started_at DESC LIMIT 1",
+    crate::engine::scaling::apply_replica_count(
+        &state.db,
+        &state.runtime,
+        &state.routes,
+        &app,
+        current_count,
+        req.count,
     )
-    .bind(&id)
-    .fetch_optional(&state.db)
     .await?;
 
-    if let Some((deployment_id, Some(primary_container_id))) = running_deployment {
-        let _ = deployment_id; // may be used later
-
-        // Get image tag from the running deployment
-        let image_tag: Option<(Option<String>,)> =
-            sqlx::query_as("SELECT image_tag FROM deployments WHERE container_id = ?")
-                .bind(&primary_container_id)
-                .fetch_optional(&state.db)
-                .await
-                .unwrap_or(None);
-
-        let image_tag = image_tag
-            .and_then(|(tag,)| tag)
-            .unwrap_or_else(|| format!("rivetr-{}:latest", app.name));
-
-        if req.count > current_count {
-            // Start additional replicas
-            for i in current_count..req.count {
-                let replica_name = format!("rivetr-{}-{}", app.name, i);
-
-                // Get env vars for new replicas (reuse app's env vars)
-                let env_vars: Vec<(String, String)> =
-                    sqlx::query_as("SELECT key, value FROM env_vars WHERE app_id = ?")
-                        .bind(&app.id)
-                        .fetch_all(&state.db)
-                        .await
-                        .unwrap_or_default();
-
-                let run_config = crate::runtime::RunConfig {
-                    image: image_tag.clone(),
-                    name: replica_name.clone(),
-                    port: app.port as u16,
-                    env: env_vars,
-                    memory_limit: app.memory_limit.clone(),
-                    cpu_limit: app.cpu_limit.clone(),
-                    port_mappings: vec![],
-                    network_aliases: app.get_network_aliases(),
-                    extra_hosts: app.get_extra_hosts(),
-                    labels: app.get_container_labels(),
-                    binds: vec![],
-                    restart_policy: app.restart_policy.clone(),
-                    privileged: app.privileged != 0,
-                    cap_add: app
-                        .cap_add
-                        .as_ref()
-                        .and_then(|s| serde_json::from_str(s).ok())
-                        .unwrap_or_default(),
-                    cap_drop: app
-                        .docker_cap_drop
-                        .as_ref()
-                        .and_then(|s| serde_json::from_str(s).ok())
-                        .unwrap_or_default(),
-                    devices: app
-                        .devices
-                        .as_ref()
-                        .and_then(|s| serde_json::from_str(s).ok())
-                        .unwrap_or_default(),
-                    shm_size: app
-                        .shm_size
-                        .as_ref()
-                        .and_then(|s| crate::runtime::parse_shm_size(s)),
-                    init: app.init_process != 0,
-                    app_id: Some(app.id.clone()),
-                    gpus: app.docker_gpus.clone(),
-                    ulimits: app
-                        .docker_ulimits
-                        .as_ref()
-                        .and_then(|s| serde_json::from_str(s).ok())
-                        .unwrap_or_default(),
-                    security_opt: app
-                        .docker_security_opt
-                        .as_ref()
-                        .and_then(|s| serde_json::from_str(s).ok())
-                        .unwrap_or_default(),
-                    cmd: None,
-                    network: None,
-                    custom_labels: vec![],
-                };
-
-                match state.runtime.run(&run_config).await {
-                    Ok(container_id) => {
-                        let replica_id = uuid::Uuid::new_v4().to_string();
-                        let _ = sqlx::query(
-                            "INSERT INTO app_replicas (id, app_id, replica_index, container_id, status, started_at)
-                             VALUES (?, ?, ?, ?, 'running', datetime('now'))
-                             ON CONFLICT(id) DO NOTHING",
-                        )
-                        .bind(&replica_id)
-                        .bind(&app.id)
-                        .bind(i)
-                        .bind(&container_id)
-                        .execute(&state.db)
-                        .await;
-
-                        // Get the port and add to proxy routes
-                        if let Ok(info) = state.runtime.inspect(&container_id).await {
-                            if let Some(port) = info.port {
-                                let domain_entries = app.get_all_domains_with_redirects();
-                                let route_table = state.routes.load();
-                                for (domain, www_redirect_target) in &domain_entries {
-                                    let mut backend = Backend::new(
-                                        container_id.clone(),
-                                        "127.0.0.1".to_string(),
-                                        port,
-                                    )
-                                    .with_healthcheck(app.healthcheck.clone());
-                                    backend.www_redirect_target = www_redirect_target.clone();
-                                    route_table.add_route(domain.clone(), backend);
-                                }
-                            }
-                        }
-
-                        tracing::info!(replica = i, container = %container_id, "Started additional replica");
-                    }
-                    Err(e) => {
-                        tracing::error!(replica = i, error = %e, "Failed to start replica");
-                        let replica_id = uuid::Uuid::new_v4().to_string();
-                        let _ = sqlx::query(
-                            "INSERT INTO app_replicas (id, app_id, replica_index, container_id, status)
-                             VALUES (?, ?, ?, NULL, 'error')
-                             ON CONFLICT(id) DO NOTHING",
-                        )
-                        .bind(&replica_id)
-                        .bind(&app.id)
-                        .bind(i)
-                        .execute(&state.db)
-                        .await;
-                    }
-                }
-            }
-        } else if req.count < current_count {
-            // Stop excess replicas (stop replicas with index >= new count)
-            let excess_replicas = sqlx::query_as::<_, AppReplica>(
-                "SELECT * FROM app_replicas WHERE app_id = ? AND replica_index >= ? ORDER BY replica_index DESC",
-            )
-            .bind(&app.id)
-            .bind(req.count)
-            .fetch_all(&state.db)
-            .await
-            .unwrap_or_default();
-
-            for replica in excess_replicas {
-                if let Some(ref container_id) = replica.container_id {
-                    let _ = state.runtime.stop(container_id).await;
-                    let _ = state.runtime.remove(container_id).await;
-                }
-
-                let _ = sqlx::query(
-                    "UPDATE app_replicas SET status = 'stopped', stopped_at = datetime('now') WHERE id = ?",
-                )
-                .bind(&replica.id)
-                .execute(&state.db)
-                .await;
-            }
-        }
-    }
-
     // Return updated replica list
     let replicas = sqlx::query_as::<_, AppReplica>(
         "SELECT * FROM app_replicas WHERE app_id = ? ORDER BY replica_index ASC",
@@ -251,6 +129,22 @@ pub async fn set_replica_count(
     Ok(Json(replicas))
 }
 
+/// POST /api/apps/:id/scale — change the number of running replicas
+pub async fn scale_app(
+    state: State<Arc<AppState>>,
+    id: Path<String>,
+    Json(req): Json<ScaleAppRequest>,
+) -> Result<Json<Vec<AppReplica>>, ApiError> {
+    set_replica_count(
+        state,
+        id,
+        Json(SetReplicaCountRequest {
+            count: req.replicas,
+        }),
+    )
+    .await
+}
+
 /// POST /api/apps/:id/replicas/:index/restart — restart specific replica
 pub async fn restart_replica(
     State(state): State<Arc<AppState>>,
@@ -308,7 +202,7 @@ pub async fn restart_replica(
     let run_config = crate::runtime::RunConfig {
         image: image_tag,
         name: replica_name,
-        port: app.port as u16,
+        port: app.container_port(),
         env: env_vars,
         memory_limit: app.memory_limit.clone(),
         cpu_limit: app.cpu_limit.clone(),
@@ -351,7 +245,7 @@ pub async fn restart_replica(
             .as_ref()
             .and_then(|s| serde_json::from_str(s).ok())
             .unwrap_or_default(),
-        cmd: None,
+        cmd: crate::engine::processes::web_command(&state.db, &app.id).await,
         network: None,
         custom_labels: vec![],
     };
@@ -399,3 +293,122 @@ pub async fn restart_replica(
 
     Ok(Json(updated_replica))
 }
+
+/// PUT /api/apps/:id/replicas/rollout — set the rolling deploy budget
+pub async fn set_rollout_settings(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<RolloutSettings>,
+) -> Result<Json<RolloutSettings>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    if !(0..=10).contains(&req.max_surge) || !(0..=10).contains(&req.max_unavailable) {
+        return Err(ApiError::bad_request(
+            "Max surge and max unavailable must be between 0 and 10",
+        ));
+    }
+    if req.max_surge == 0 && req.max_unavailable == 0 {
+        return Err(ApiError::bad_request(
+            "Max surge and max unavailable cannot both be 0",
+        ));
+    }
+
+    let result = sqlx::query(
+        "UPDATE apps SET rolling_max_surge = ?, rolling_max_unavailable = ?, \
+         updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(req.max_surge)
+    .bind(req.max_unavailable)
+    .bind(&id)
+    .execute(&state.db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("App not found"));
+    }
+
+    Ok(Json(req))
+}
+
+/// GET /api/apps/:id/processes — Procfile process types with their running containers
+pub async fn list_processes(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ProcessStatus>>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+
+    let statuses = processes::process_statuses(&state.db, state.runtime.as_ref(), &app).await?;
+    Ok(Json(statuses))
+}
+
+/// PUT /api/apps/:id/processes/:name — set the container count of a process type
+pub async fn scale_process(
+    State(state): State<Arc<AppState>>,
+    Path((id, name)): Path<(String, String)>,
+    Json(req): Json<ScaleProcessRequest>,
+) -> Result<Json<Vec<ProcessStatus>>, ApiError> {
+    if let Err(e) = validate_uuid(&id, "app_id") {
+        return Err(ApiError::validation_field("app_id", e));
+    }
+
+    if !(0..=MAX_PROCESS_REPLICAS).contains(&req.replicas) {
+        return Err(ApiError::bad_request(format!(
+            "Process replica count must be between 0 and {}",
+            MAX_PROCESS_REPLICAS
+        )));
+    }
+    match name.as_str() {
+        PROCESS_WEB => {
+            return Err(ApiError::bad_request(
+                "The web process runs the app's replicas; use /apps/:id/scale",
+            ))
+        }
+        PROCESS_RELEASE => {
+            return Err(ApiError::bad_request(
+                "The release process runs once per deployment and cannot be scaled",
+            ))
+        }
+        _ => {}
+    }
+
+    let app = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("App not found"))?;
+    let process = AppProcess::get(&state.db, &id, &name)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Process type not found in the app's Procfile"))?;
+
+    sqlx::query(
+        "UPDATE app_processes SET replica_count = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(req.replicas)
+    .bind(&process.id)
+    .execute(&state.db)
+    .await?;
+
+    let encryption_key = get_encryption_key(&state);
+    processes::apply_process_count(
+        &state.db,
+        &state.runtime,
+        &app,
+        &process,
+        req.replicas,
+        encryption_key.as_ref(),
+    )
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to scale process: {}", e)))?;
+
+    let statuses = processes::process_statuses(&state.db, state.runtime.as_ref(), &app).await?;
+    Ok(Json(statuses))
+}
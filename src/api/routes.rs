@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::info;
 
+use crate::proxy::health_history::{self, HealthCheckRecord};
 use crate::proxy::Backend;
 use crate::AppState;
 
@@ -31,12 +32,12 @@ impl From<(String, Backend)> for RouteInfo {
     fn from((domain, backend): (String, Backend)) -> Self {
         Self {
             domain,
+            healthy: backend.healthy(),
+            failure_count: backend.failure_count(),
             backend_host: backend.host,
             backend_port: backend.port,
             container_id: backend.container_id,
-            healthy: backend.healthy,
             healthcheck_path: backend.healthcheck_path,
-            failure_count: backend.failure_count,
             basic_auth_enabled: backend.basic_auth.enabled,
         }
     }
@@ -174,6 +175,47 @@ pub async fn update_route_health(
     }
 }
 
+/// Recent health checks of a route
+#[derive(Debug, Serialize)]
+pub struct RouteHealthHistory {
+    pub domain: String,
+    /// Current health (null when the domain is no longer routed)
+    pub healthy: Option<bool>,
+    /// Whether the backend keeps switching between healthy and unhealthy;
+    /// health notifications are paused while it does
+    pub flapping: bool,
+    pub flapping_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Health changes in the checks flap detection looks at
+    pub state_changes: usize,
+    /// Checks since startup, newest first
+    pub checks: Vec<HealthCheckRecord>,
+}
+
+/// Get a route's recent health check results and flap state
+///
+/// GET /api/routes/:domain/health/history
+pub async fn route_health_history(
+    State(state): State<Arc<AppState>>,
+    Path(domain): Path<String>,
+) -> Result<Json<RouteHealthHistory>, StatusCode> {
+    let routes = state.routes.load();
+    let healthy = routes.get_backend(&domain).map(|b| b.healthy());
+    let history = health_history::history(&domain);
+    if healthy.is_none() && history.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let history = history.unwrap_or_default();
+    Ok(Json(RouteHealthHistory {
+        domain,
+        healthy,
+        flapping: history.flapping,
+        flapping_since: history.flapping_since,
+        state_changes: history.state_changes,
+        checks: history.checks,
+    }))
+}
+
 /// Get all registered domains
 ///
 /// GET /api/routes/domains
@@ -190,7 +232,7 @@ pub async fn routes_health(State(state): State<Arc<AppState>>) -> Json<HealthSum
     let backends = routes.all_backends();
 
     let total = backends.len();
-    let healthy = backends.iter().filter(|(_, b)| b.healthy).count();
+    let healthy = backends.iter().filter(|(_, b)| b.healthy()).count();
     let unhealthy = total - healthy;
 
     Json(HealthSummary {
@@ -452,19 +452,16 @@ pub async fn trigger_backup(
                     let config_path = data_dir.join("../rivetr.toml");
                     let acme_cache_dir = &state_clone.config.proxy.acme_cache_dir;
 
-                    let backup_result = crate::backup::create_backup(
+                    crate::backup::create_stored_backup(
                         &db,
+                        state_clone.storage.as_ref(),
                         data_dir,
                         &config_path,
                         acme_cache_dir,
-                        None,
                     )
                     .await
-                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-                    // Read the backup file
-                    std::fs::read(&backup_result.path)
-                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                    .data
                 }
                 _ => {
                     // For database/volume types, we read from the local backup directory
@@ -63,6 +63,7 @@ export interface App {{
   repo_url: string | null;
   branch: string | null;
   domain: string | null;
+  app_type: "web" | "worker";
   created_at: string;
   updated_at: string;
 }}
@@ -74,6 +75,8 @@ export interface Deployment {{
   started_at: string;
   finished_at: string | null;
   error_message: string | null;
+  error_code: string | null;
+  error_hint: string | null;
   git_commit: string | null;
 }}
 
@@ -102,6 +105,7 @@ export interface CreateAppRequest {{
   branch?: string;
   domain?: string;
   project_id?: string;
+  app_type?: "web" | "worker";
 }}
 
 export interface UpdateAppRequest {{
@@ -109,6 +113,7 @@ export interface UpdateAppRequest {{
   repo_url?: string;
   branch?: string;
   domain?: string;
+  app_type?: "web" | "worker";
 }}
 
 export interface CreateDatabaseRequest {{
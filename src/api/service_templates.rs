@@ -10,12 +10,20 @@ use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::api::authz;
+use crate::api::validation::validate_domain_name;
 use crate::db::{
-    DeployTemplateRequest, DeployTemplateResponse, ServiceStatus, ServiceTemplate,
-    ServiceTemplateResponse, ServiceTemplateSummary,
+    DeployTemplateRequest, DeployTemplateResponse, EnvSchemaEntry, EnvSourceKind, ServiceStatus,
+    ServiceTemplate, ServiceTemplateResponse, ServiceTemplateSummary, User, Volume,
 };
+use crate::engine::prometheus_sd;
+use crate::engine::service_routes::register_service_route;
 use crate::AppState;
 
+/// Host directory of the generated Prometheus scrape config, for templates
+/// that scrape Rivetr-managed apps
+const PROMETHEUS_DIR_VAR: &str = "${RIVETR_PROMETHEUS_DIR}";
+
 /// Namespace container names in compose content to prevent global conflicts
 /// Prefixes all container_name values with "rivetr-{service_name}-"
 fn namespace_container_names(content: &str, service_name: &str) -> Result<String, String> {
@@ -129,8 +137,9 @@ pub async fn get_template(
 /// Deploy a service template as a new service
 pub async fn deploy_template(
     State(state): State<Arc<AppState>>,
+    user: User,
     Path(id): Path<String>,
-    Json(req): Json<DeployTemplateRequest>,
+    Json(mut req): Json<DeployTemplateRequest>,
 ) -> Result<(StatusCode, Json<DeployTemplateResponse>), (StatusCode, Json<serde_json::Value>)> {
     let make_internal_err = || {
         (
@@ -183,6 +192,24 @@ pub async fn deploy_template(
         ));
     }
 
+    let requested_domain = req
+        .domain
+        .clone()
+        .map(|d| d.trim().to_lowercase())
+        .filter(|d| !d.is_empty());
+    if let Some(ref domain) = requested_domain {
+        if let Err(e) = validate_domain_name(domain) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": { "code": "bad_request", "message": e } })),
+            ));
+        }
+    }
+
+    // Fill the fields sourced from existing resources
+    let env_schema = template.get_env_schema();
+    let uses_database = resolve_resources(&state, &user, &env_schema, &mut req).await?;
+
     // Check for port conflict if a PORT env var is provided
     if let Some(port_str) = req.env_vars.get("PORT") {
         if let Ok(port) = port_str.parse::<i64>() {
@@ -235,8 +262,7 @@ pub async fn deploy_template(
         }
     }
 
-    // Get the env schema to validate required variables
-    let env_schema = template.get_env_schema();
+    // Validate required variables
     for entry in &env_schema {
         if entry.required && !req.env_vars.contains_key(&entry.name) && entry.default.is_empty() {
             tracing::warn!("Missing required environment variable: {}", entry.name);
@@ -273,6 +299,7 @@ pub async fn deploy_template(
     }
 
     // Auto-generate SERVICE_PASSWORD_*, SERVICE_USER_*, SERVICE_BASE64_* magic variables
+    let mut generated: std::collections::HashMap<String, String> = std::collections::HashMap::new();
     {
         use base64::Engine as _;
         use rand::Rng;
@@ -281,9 +308,6 @@ pub async fn deploy_template(
             regex::Regex::new(r"\$\{(SERVICE_(?:PASSWORD|USER|BASE64)_([A-Z0-9_]+))(?::-[^}]*)?\}")
                 .expect("invalid magic var regex");
 
-        let mut generated: std::collections::HashMap<String, String> =
-            std::collections::HashMap::new();
-
         // Collect all magic variable names first (so each gets a stable generated value)
         for cap in magic_var_re.captures_iter(&compose_content.clone()) {
             let full_var = cap[1].to_string();
@@ -321,8 +345,42 @@ pub async fn deploy_template(
         }
     }
 
-    // Auto-generate subdomain for the service
-    let domain = state.config.proxy.generate_auto_domain(&req.name);
+    // Templates scraping Rivetr-managed apps mount the generated Prometheus
+    // config; write it now so it exists before the container starts
+    let scrapes_apps = compose_content.contains(PROMETHEUS_DIR_VAR);
+    if scrapes_apps {
+        let data_dir = &state.config.server.data_dir;
+        if let Err(e) = prometheus_sd::write_scrape_targets(&state.db, data_dir).await {
+            tracing::warn!("Failed to write Prometheus scrape targets: {}", e);
+        }
+        let dir = prometheus_sd::prometheus_dir(data_dir);
+        let dir = std::fs::canonicalize(&dir).unwrap_or(dir);
+        compose_content = compose_content.replace(PROMETHEUS_DIR_VAR, &dir.to_string_lossy());
+    }
+
+    // Managed databases and scraped apps are reached by hostname on the shared network
+    if uses_database || scrapes_apps {
+        compose_content = super::services::compose::inject_rivetr_network(&compose_content)
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to inject rivetr network into template service: {}",
+                    e
+                );
+                compose_content
+            });
+    }
+
+    // Route the requested domain, or auto-generate a subdomain like apps get
+    let domain = requested_domain.or_else(|| state.config.proxy.generate_auto_domain(&req.name));
+
+    // Post-deploy notes see the same values the compose file was rendered with
+    let mut note_vars: std::collections::HashMap<String, String> = env_schema
+        .iter()
+        .map(|entry| (entry.name.clone(), entry.default.clone()))
+        .collect();
+    note_vars.extend(req.env_vars.clone());
+    note_vars.extend(generated);
+    let post_deploy_notes = template.render_notes(&note_vars, domain.as_deref());
 
     // Determine proxy port: prefer PORT env var, then extract first host port
     // from the rendered compose file (e.g. "3000:3000" → 3000).
@@ -339,8 +397,8 @@ pub async fn deploy_template(
 
     sqlx::query(
         r#"
-        INSERT INTO services (id, name, project_id, compose_content, domain, port, status, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO services (id, name, project_id, compose_content, domain, port, status, post_deploy_notes, url_path, health_path, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&service_id)
@@ -350,6 +408,9 @@ pub async fn deploy_template(
     .bind(&domain)
     .bind(port)
     .bind(ServiceStatus::Pending.to_string())
+    .bind(&post_deploy_notes)
+    .bind(&template.url_path)
+    .bind(&template.health_path)
     .bind(&now)
     .bind(&now)
     .execute(&state.db)
@@ -409,10 +470,88 @@ pub async fn deploy_template(
                 "Service deployment started from template '{}'",
                 template.name
             ),
+            url: domain.as_ref().map(|d| {
+                format!(
+                    "https://{}{}",
+                    d,
+                    template.url_path.as_deref().unwrap_or("")
+                )
+            }),
+            domain,
+            post_deploy_notes,
         }),
     ))
 }
 
+/// Resolve the resources picked for env schema fields with a `source` into
+/// `req.env_vars`. Returns whether a database was picked, so the service joins
+/// the network managed databases are reachable on. Resources the user can't
+/// access are reported as not found.
+async fn resolve_resources(
+    state: &Arc<AppState>,
+    user: &User,
+    env_schema: &[EnvSchemaEntry],
+    req: &mut DeployTemplateRequest,
+) -> Result<bool, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": { "code": "bad_request", "message": message } })),
+        )
+    };
+    let not_found = |message: String| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": { "code": "not_found", "message": message } })),
+        )
+    };
+    let internal_err = |e: sqlx::Error| {
+        tracing::error!("Failed to load template resource: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(
+                serde_json::json!({ "error": { "code": "internal_error", "message": "An internal error occurred" } }),
+            ),
+        )
+    };
+
+    let mut uses_database = false;
+    for (name, resource_id) in &req.resources {
+        let source = env_schema
+            .iter()
+            .find(|entry| entry.name == *name)
+            .and_then(|entry| entry.source.as_ref())
+            .ok_or_else(|| {
+                bad_request(format!("Variable {} can't be filled from a resource", name))
+            })?;
+
+        let value = match source.kind {
+            EnvSourceKind::Database => {
+                let db = authz::authorize_database(state, user, resource_id)
+                    .await
+                    .map_err(|_| not_found(format!("Database for {} not found", name)))?;
+                uses_database = true;
+                source.database_value(&db).map_err(bad_request)?
+            }
+            EnvSourceKind::Volume => {
+                let volume = sqlx::query_as::<_, Volume>("SELECT * FROM volumes WHERE id = ?")
+                    .bind(resource_id)
+                    .fetch_optional(&state.db)
+                    .await
+                    .map_err(internal_err)?
+                    .ok_or_else(|| not_found(format!("Volume for {} not found", name)))?;
+                // A volume belongs to its app's team
+                authz::authorize_app(state, user, &volume.app_id)
+                    .await
+                    .map_err(|_| not_found(format!("Volume for {} not found", name)))?;
+                volume.host_path
+            }
+        };
+        req.env_vars.insert(name.clone(), value);
+    }
+    Ok(uses_database)
+}
+
 /// Internal function to start a compose service
 async fn start_compose_service(
     state: &Arc<AppState>,
@@ -497,22 +636,14 @@ async fn start_compose_service(
         .fetch_optional(&state.db)
         .await?;
 
-    if let Some(ref svc) = service {
-        if let Some(ref domain) = svc.domain {
-            if !domain.is_empty() {
-                let backend = crate::proxy::Backend::new(
-                    format!("rivetr-svc-{}", svc.name),
-                    "127.0.0.1".to_string(),
-                    svc.port as u16,
-                );
-                state.routes.load().add_route(domain.clone(), backend);
-                tracing::info!(
-                    "Registered proxy route for template service: {} -> port {}",
-                    domain,
-                    svc.port
-                );
-            }
-        }
+    if let Some(mut svc) = service {
+        register_service_route(
+            &state.db,
+            &state.routes.load(),
+            &state.config.server.data_dir,
+            &mut svc,
+        )
+        .await;
     }
 
     tracing::info!("Service {} started successfully", name);
@@ -567,3 +698,119 @@ fn extract_first_host_port(compose: &str) -> Option<i32> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_user, DatabaseValue, EnvSource};
+
+    async fn create_team(state: &AppState, member: &str) -> String {
+        let team_id = Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO teams (id, name, slug) VALUES (?, ?, ?)")
+            .bind(&team_id)
+            .bind(&team_id)
+            .bind(&team_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO team_members (id, team_id, user_id, role) VALUES (?, ?, ?, 'developer')",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&team_id)
+        .bind(member)
+        .execute(&state.db)
+        .await
+        .unwrap();
+        team_id
+    }
+
+    fn schema() -> Vec<EnvSchemaEntry> {
+        let entry = |name: &str, kind| EnvSchemaEntry {
+            name: name.to_string(),
+            label: name.to_string(),
+            required: true,
+            default: String::new(),
+            secret: false,
+            source: Some(EnvSource {
+                kind,
+                db_types: Vec::new(),
+                value: DatabaseValue::Password,
+            }),
+        };
+        vec![
+            entry("DB_PASSWORD", EnvSourceKind::Database),
+            entry("DATA_DIR", EnvSourceKind::Volume),
+        ]
+    }
+
+    fn request(resources: &[(&str, &str)]) -> DeployTemplateRequest {
+        DeployTemplateRequest {
+            name: "svc".to_string(),
+            env_vars: Default::default(),
+            resources: resources
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            project_id: None,
+            domain: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resources_of_other_teams_are_not_found() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let owner = test_user(&state.db, "member").await;
+        let outsider = test_user(&state.db, "member").await;
+        let team_id = create_team(&state, &owner.id).await;
+        create_team(&state, &outsider.id).await;
+
+        let database_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO databases (id, name, db_type, version, status, internal_port, credentials, team_id) \
+             VALUES (?, 'pg', 'postgres', '16', 'running', 5432, '{\"username\":\"app\",\"password\":\"secret\"}', ?)",
+        )
+        .bind(&database_id)
+        .bind(&team_id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+        let app_id = test_app(&state.db, "web").await;
+        sqlx::query("UPDATE apps SET team_id = ? WHERE id = ?")
+            .bind(&team_id)
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let volume_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO volumes (id, app_id, name, host_path, container_path) \
+             VALUES (?, ?, 'data', '/srv/web-data', '/data')",
+        )
+        .bind(&volume_id)
+        .bind(&app_id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+        for resources in [
+            [("DB_PASSWORD", database_id.as_str())],
+            [("DATA_DIR", volume_id.as_str())],
+        ] {
+            let mut req = request(&resources);
+            let err = resolve_resources(&state, &outsider, &schema(), &mut req)
+                .await
+                .unwrap_err();
+            assert_eq!(err.0, StatusCode::NOT_FOUND);
+            assert!(req.env_vars.is_empty());
+        }
+
+        let mut req = request(&[("DB_PASSWORD", &database_id), ("DATA_DIR", &volume_id)]);
+        let uses_database = resolve_resources(&state, &owner, &schema(), &mut req)
+            .await
+            .unwrap();
+        assert!(uses_database);
+        assert_eq!(req.env_vars["DB_PASSWORD"], "secret");
+        assert_eq!(req.env_vars["DATA_DIR"], "/srv/web-data");
+    }
+}
@@ -205,6 +205,78 @@ pub fn inject_public_ports(
     serde_yaml::to_string(&yaml).map_err(|e| format!("Failed to serialize YAML: {}", e))
 }
 
+/// Names of the services defined in compose content (empty if it doesn't parse)
+pub fn compose_service_names(content: &str) -> Vec<String> {
+    serde_yaml::from_str::<serde_yaml::Value>(content)
+        .ok()
+        .and_then(|yaml| {
+            yaml.get("services")
+                .and_then(|s| s.as_mapping())
+                .map(|services| {
+                    services
+                        .keys()
+                        .filter_map(|k| k.as_str().map(String::from))
+                        .collect()
+                })
+        })
+        .unwrap_or_default()
+}
+
+/// Publish a container port of the named compose service on an ephemeral
+/// loopback port (`"127.0.0.1::port"`) so the proxy can route to it without
+/// exposing it publicly. The entry is not duplicated.
+pub fn publish_on_loopback(content: &str, service: &str, port: u16) -> Result<String, String> {
+    let mut yaml: serde_yaml::Value =
+        serde_yaml::from_str(content).map_err(|e| format!("Invalid YAML: {}", e))?;
+
+    let svc = yaml
+        .get_mut("services")
+        .and_then(|s| s.as_mapping_mut())
+        .and_then(|services| services.get_mut(service))
+        .and_then(|s| s.as_mapping_mut())
+        .ok_or_else(|| format!("Service '{}' not found in compose file", service))?;
+
+    let binding = serde_yaml::Value::String(format!("127.0.0.1::{}", port));
+    let ports = svc
+        .entry(serde_yaml::Value::String("ports".to_string()))
+        .or_insert_with(|| serde_yaml::Value::Sequence(vec![]));
+    let Some(seq) = ports.as_sequence_mut() else {
+        return Err(format!("The ports of service '{}' must be a list", service));
+    };
+    if !seq.contains(&binding) {
+        seq.push(binding);
+    }
+
+    serde_yaml::to_string(&yaml).map_err(|e| format!("Failed to serialize YAML: {}", e))
+}
+
+/// Host port from the output of `docker compose port` (`127.0.0.1:49153`)
+pub fn parse_published_port(output: &str) -> Option<u16> {
+    output
+        .lines()
+        .next()?
+        .trim()
+        .rsplit(':')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Look up the host port a container port of a running compose service is
+/// published on
+pub async fn lookup_published_port(
+    project_dir: &Path,
+    project_name: &str,
+    service: &str,
+    port: u16,
+) -> Result<u16, String> {
+    let port_str = port.to_string();
+    let output =
+        run_compose_command(project_dir, project_name, &["port", service, &port_str]).await?;
+    parse_published_port(&output)
+        .ok_or_else(|| format!("Service '{}' does not publish port {}", service, port))
+}
+
 /// Inject top-level `cpus` / `mem_limit` keys into every service in the compose
 /// file, so the service-level CPU/memory caps configured in Rivetr are enforced
 /// by Docker's cgroups. These keys (unlike `deploy.resources.limits`, which is
@@ -17,12 +17,13 @@ use tokio::process::Command;
 use crate::db::{
     actions, resource_types, Service, ServiceGeneratedVar, ServiceResponse, ServiceStatus, User,
 };
+use crate::engine::service_routes::register_service_route;
 use crate::AppState;
 
 use super::super::audit::{audit_log, ClientIp};
 use super::compose::{
     get_compose_dir, get_service_compose_dir, inject_public_ports, inject_resource_limits,
-    run_compose_command, run_compose_command_streaming, substitute_magic_vars,
+    publish_on_loopback, run_compose_command, run_compose_command_streaming, substitute_magic_vars,
     write_compose_file_with_options,
 };
 
@@ -62,7 +63,7 @@ pub async fn start_service(
         .info(&resource_key, "info", "Starting service…");
 
     // Get the service
-    let service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
+    let mut service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
         .bind(&id)
         .fetch_optional(&state.db)
         .await
@@ -168,6 +169,9 @@ pub async fn start_service(
         substituted_compose
     };
 
+    // Publish the container port the domain routes to on a loopback port
+    let compose_to_write = inject_route_port(&service, compose_to_write);
+
     // Inject per-service CPU/memory limits (deploy.resources.limits) unless in
     // raw mode (which deploys the compose verbatim) or no limits are configured.
     let compose_to_write = if !raw_mode
@@ -261,21 +265,7 @@ pub async fn start_service(
                 })?;
 
             // Register proxy route if domain is configured
-            if let Some(ref domain) = service.domain {
-                if !domain.is_empty() {
-                    let backend = crate::proxy::Backend::new(
-                        format!("rivetr-svc-{}", service.name),
-                        "127.0.0.1".to_string(),
-                        service.port as u16,
-                    );
-                    state.routes.load().add_route(domain.clone(), backend);
-                    tracing::info!(
-                        "Registered proxy route: {} -> port {}",
-                        domain,
-                        service.port
-                    );
-                }
-            }
+            register_service_route(&state.db, &state.routes.load(), data_dir, &mut service).await;
 
             tracing::info!("Started Docker Compose service: {}", service.name);
             state.start_log_streams.info(
@@ -445,7 +435,7 @@ pub async fn restart_service(
         .info(&resource_key, "info", "Restarting service…");
 
     // Get the service
-    let service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
+    let mut service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
         .bind(&id)
         .fetch_optional(&state.db)
         .await
@@ -549,6 +539,9 @@ pub async fn restart_service(
         substituted_compose
     };
 
+    // Publish the container port the domain routes to on a loopback port
+    let compose_to_write = inject_route_port(&service, compose_to_write);
+
     // Inject per-service CPU/memory limits (deploy.resources.limits) unless in
     // raw mode (which deploys the compose verbatim) or no limits are configured.
     let compose_to_write = if !raw_mode
@@ -638,21 +631,7 @@ pub async fn restart_service(
                 })?;
 
             // Re-register proxy route if domain is configured
-            if let Some(ref domain) = service.domain {
-                if !domain.is_empty() {
-                    let backend = crate::proxy::Backend::new(
-                        format!("rivetr-svc-{}", service.name),
-                        "127.0.0.1".to_string(),
-                        service.port as u16,
-                    );
-                    state.routes.load().add_route(domain.clone(), backend);
-                    tracing::info!(
-                        "Re-registered proxy route after restart: {} -> port {}",
-                        domain,
-                        service.port
-                    );
-                }
-            }
+            register_service_route(&state.db, &state.routes.load(), data_dir, &mut service).await;
 
             tracing::info!("Restarted Docker Compose service: {}", service.name);
             state
@@ -716,7 +695,7 @@ pub async fn restart_service(
 /// Used by the update handler when `public_access` changes on a running service
 /// so that Docker picks up the new port binding without requiring manual restart.
 pub async fn restart_service_internal(state: &Arc<AppState>, id: &str) -> Result<(), String> {
-    let service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
+    let mut service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
         .bind(id)
         .fetch_optional(&state.db)
         .await
@@ -783,6 +762,9 @@ pub async fn restart_service_internal(state: &Arc<AppState>, id: &str) -> Result
         substituted
     };
 
+    // Publish the container port the domain routes to on a loopback port
+    let compose_to_write = inject_route_port(&service, compose_to_write);
+
     // Inject per-service CPU/memory limits unless raw mode / no limits configured.
     let compose_to_write = if !raw_mode
         && (service.cpu_limit.is_some() || service.memory_limit.is_some())
@@ -825,16 +807,7 @@ pub async fn restart_service_internal(state: &Arc<AppState>, id: &str) -> Result
     .map_err(|e| format!("Failed to update service status: {}", e))?;
 
     // Re-register proxy route
-    if let Some(ref domain) = service.domain {
-        if !domain.is_empty() {
-            let backend = crate::proxy::Backend::new(
-                format!("rivetr-svc-{}", service.name),
-                "127.0.0.1".to_string(),
-                service.port as u16,
-            );
-            state.routes.load().add_route(domain.clone(), backend);
-        }
-    }
+    register_service_route(&state.db, &state.routes.load(), data_dir, &mut service).await;
 
     tracing::info!(
         "restart_service_internal: service '{}' restarted successfully",
@@ -843,6 +816,29 @@ pub async fn restart_service_internal(state: &Arc<AppState>, id: &str) -> Result
     Ok(())
 }
 
+/// Publish the container port the service's domain routes to (`route_service`
+/// / `route_port`) on a loopback port for the proxy. Skipped in raw mode, where
+/// the compose file is deployed verbatim and must publish the port itself.
+fn inject_route_port(service: &Service, content: String) -> String {
+    let Some((target, port)) = service
+        .route_target()
+        .filter(|_| service.raw_compose_mode == 0)
+    else {
+        return content;
+    };
+    match publish_on_loopback(&content, target, port) {
+        Ok(injected) => injected,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to publish the route port for service {}: {}. Using compose without it.",
+                service.name,
+                e
+            );
+            content
+        }
+    }
+}
+
 /// Parse docker compose logs output into structured entries
 fn parse_compose_logs(output: &str) -> Vec<ServiceLogEntry> {
     output
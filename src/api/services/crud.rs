@@ -13,14 +13,17 @@ use crate::db::{
     actions, resource_types, CreateServiceRequest, Service, ServiceResponse, ServiceStatus,
     TeamAuditAction, TeamAuditResourceType, UpdateServiceRequest, User,
 };
+use crate::engine::service_routes::{app_using_domain, register_service_route};
 use crate::AppState;
 
 use super::super::audit::{audit_log, ClientIp};
+use super::super::auth::hash_password;
 use super::super::authz;
+use super::super::basic_auth::{validate_password, validate_username};
 use super::super::teams::log_team_audit;
 use super::compose::{
-    get_compose_dir, run_compose_command, validate_compose_content, write_compose_file,
-    write_compose_file_with_options,
+    compose_service_names, get_compose_dir, run_compose_command, validate_compose_content,
+    write_compose_file, write_compose_file_with_options,
 };
 use super::control::restart_service_internal;
 
@@ -212,6 +215,10 @@ pub async fn create_service(
             })
             .or_else(|| Some(format!("{}.local", req.name))),
     };
+    // App domains take precedence: a generated one that clashes is just never routed
+    if let Some(domain) = req.domain.as_deref().filter(|d| !d.is_empty()) {
+        ensure_domain_free(&state, domain).await?;
+    }
 
     // Create service record
     let id = Uuid::new_v4().to_string();
@@ -326,6 +333,70 @@ pub async fn update_service(
         }
     }
 
+    // App domains take precedence over service domains
+    if let Some(domain) = req.domain.as_deref().filter(|d| !d.is_empty()) {
+        ensure_domain_free(&state, domain).await?;
+    }
+
+    // Validate the route target: a service of the compose file and a container port
+    let route_target = match req.route_service.as_deref().map(str::trim) {
+        Some("") => Some((None, None)),
+        Some(name) => {
+            let compose = req
+                .compose_content
+                .as_deref()
+                .unwrap_or(&existing.compose_content);
+            if !compose_service_names(compose).iter().any(|s| s == name) {
+                tracing::warn!("Route service '{}' not found in compose file", name);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            match req.route_port.or(existing.route_port) {
+                Some(port) if (1..=65535).contains(&port) => {
+                    Some((Some(name.to_string()), Some(port)))
+                }
+                _ => {
+                    tracing::warn!("Route service '{}' needs a container port", name);
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+            }
+        }
+        None => match req.route_port {
+            Some(port) if existing.route_service.is_some() && (1..=65535).contains(&port) => {
+                Some((existing.route_service.clone(), Some(port)))
+            }
+            Some(_) => return Err(StatusCode::BAD_REQUEST),
+            None => None,
+        },
+    };
+
+    // Validate basic auth credentials; the stored password is kept when none is given
+    let basic_auth = match req.basic_auth_enabled {
+        Some(true) => {
+            let username = req
+                .basic_auth_username
+                .as_deref()
+                .or(existing.basic_auth_username.as_deref())
+                .unwrap_or("");
+            validate_username(username).map_err(|_| StatusCode::BAD_REQUEST)?;
+            let password_hash = match req.basic_auth_password.as_deref().filter(|p| !p.is_empty()) {
+                Some(password) => {
+                    validate_password(password).map_err(|_| StatusCode::BAD_REQUEST)?;
+                    hash_password(password).map_err(|e| {
+                        tracing::error!("Failed to hash basic auth password: {}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    })?
+                }
+                None => existing
+                    .basic_auth_password_hash
+                    .clone()
+                    .ok_or(StatusCode::BAD_REQUEST)?,
+            };
+            Some(Some((username.to_string(), password_hash)))
+        }
+        Some(false) => Some(None),
+        None => None,
+    };
+
     let now = chrono::Utc::now().to_rfc3339();
 
     // Update compose content if provided
@@ -474,6 +545,66 @@ pub async fn update_service(
             })?;
     }
 
+    // Update the route target (the loopback port is looked up again on start)
+    let route_target = route_target.filter(|(route_service, route_port)| {
+        *route_service != existing.route_service || *route_port != existing.route_port
+    });
+    let route_target_changed = route_target.is_some();
+    if let Some((ref route_service, route_port)) = route_target {
+        sqlx::query(
+            "UPDATE services SET route_service = ?, route_port = ?, route_host_port = NULL, updated_at = ? WHERE id = ?",
+        )
+        .bind(route_service)
+        .bind(route_port)
+        .bind(&now)
+        .bind(&id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update service route target: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    // Update route_tls if provided (picked up by the certificate renewal manager)
+    if let Some(route_tls) = req.route_tls {
+        sqlx::query("UPDATE services SET route_tls = ?, updated_at = ? WHERE id = ?")
+            .bind(route_tls as i32)
+            .bind(&now)
+            .bind(&id)
+            .execute(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to update service route_tls: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    }
+
+    // Update basic auth if provided
+    if let Some(ref credentials) = basic_auth {
+        let (username, password_hash) = match credentials {
+            Some((username, hash)) => (Some(username.as_str()), Some(hash.as_str())),
+            None => (None, None),
+        };
+        sqlx::query(
+            "UPDATE services SET basic_auth_enabled = ?, basic_auth_username = ?, basic_auth_password_hash = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(credentials.is_some() as i32)
+        .bind(username)
+        .bind(password_hash)
+        .bind(&now)
+        .bind(&id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update service basic auth: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    let is_running = existing.status == ServiceStatus::Running.to_string();
+    let mut needs_restart = false;
+
     // Handle public access / external port changes
     let public_access_changed = req.public_access.is_some()
         || req.external_port.is_some()
@@ -549,34 +680,57 @@ pub async fn update_service(
 
         // If public_access changed and service is running, restart it so port binding takes effect.
         let was_public = existing.public_access != 0;
-        if new_public_access != was_public && existing.status == ServiceStatus::Running.to_string()
-        {
+        if new_public_access != was_public && is_running {
             tracing::info!(
                 "public_access changed for running service '{}', triggering restart",
                 existing.name
             );
-            // Restart in background — don't block the API response
-            let state_clone = state.clone();
-            let id_clone = id.clone();
-            tokio::spawn(async move {
-                if let Err(e) = restart_service_internal(&state_clone, &id_clone).await {
-                    tracing::error!(
-                        "Failed to restart service {} after public_access change: {}",
-                        id_clone,
-                        e
-                    );
-                }
-            });
+            needs_restart = true;
         }
     }
 
+    // A new route target needs its port published, so restart a running service
+    if route_target_changed && is_running {
+        tracing::info!(
+            "Route target changed for running service '{}', triggering restart",
+            existing.name
+        );
+        needs_restart = true;
+    }
+
+    if needs_restart {
+        // Restart in background — don't block the API response
+        let state_clone = state.clone();
+        let id_clone = id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = restart_service_internal(&state_clone, &id_clone).await {
+                tracing::error!(
+                    "Failed to restart service {} after a port change: {}",
+                    id_clone,
+                    e
+                );
+            }
+        });
+    }
+
     // Fetch and return the updated service
-    let service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
+    let mut service = sqlx::query_as::<_, Service>("SELECT * FROM services WHERE id = ?")
         .bind(&id)
         .fetch_one(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // Apply basic auth changes to the live route
+    if basic_auth.is_some() && is_running && !needs_restart {
+        register_service_route(
+            &state.db,
+            &state.routes.load(),
+            &state.config.server.data_dir,
+            &mut service,
+        )
+        .await;
+    }
+
     tracing::info!("Updated Docker Compose service: {}", existing.name);
     Ok(Json(service.into()))
 }
@@ -686,6 +840,19 @@ pub struct CheckPortQuery {
     pub port: i32,
 }
 
+/// Reject a service domain that an app already serves
+async fn ensure_domain_free(state: &AppState, domain: &str) -> Result<(), StatusCode> {
+    let app = app_using_domain(&state.db, domain).await.map_err(|e| {
+        tracing::error!("Failed to check domain conflict in apps: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if let Some(name) = app {
+        tracing::warn!("Domain {} already used by app '{}'", domain, name);
+        return Err(StatusCode::CONFLICT);
+    }
+    Ok(())
+}
+
 /// Response body for the check-port endpoint
 #[derive(Debug, Serialize)]
 pub struct CheckPortResponse {
@@ -749,3 +916,50 @@ pub async fn check_port(
         conflict: None,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_app;
+
+    #[tokio::test]
+    async fn test_update_rejects_an_app_domain() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        sqlx::query("UPDATE apps SET domain = 'shop.example.com' WHERE id = ?")
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let service_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO services (id, name, compose_content, domain, port, status) \
+             VALUES (?, 'shop', 'services: {}', 'shop.local', 8080, 'stopped')",
+        )
+        .bind(&service_id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+        let update = |domain: &str| {
+            serde_json::from_value::<UpdateServiceRequest>(serde_json::json!({ "domain": domain }))
+                .unwrap()
+        };
+        let result = update_service(
+            State(state.clone()),
+            Path(service_id.clone()),
+            Json(update("shop.example.com")),
+        )
+        .await;
+        assert_eq!(result.err(), Some(StatusCode::CONFLICT));
+
+        let service = update_service(
+            State(state.clone()),
+            Path(service_id.clone()),
+            Json(update("store.example.com")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(service.domain.as_deref(), Some("store.example.com"));
+    }
+}
@@ -1,6 +1,6 @@
 //! API handlers for Docker Compose services.
 
-mod compose;
+pub(crate) mod compose;
 mod control;
 mod crud;
 mod export_db;
@@ -3,15 +3,34 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use super::audit::{audit_log, ClientIp};
 use crate::db::{
-    actions, resource_types, CreateSshKeyRequest, SshKey, SshKeyResponse, UpdateSshKeyRequest, User,
+    actions, resource_types, App, CreateSshKeyRequest, JumpHost, SshKey, SshKeyResponse,
+    UpdateSshKeyRequest, User,
 };
 use crate::AppState;
 
+/// Reject jump hosts that don't parse as `[user@]host[:port]`; an empty
+/// string clears the jump host
+fn check_jump_host(jump_host: &Option<String>) -> Result<(), StatusCode> {
+    match jump_host.as_deref().map(str::trim) {
+        Some(host) if !host.is_empty() => JumpHost::parse(host).map(|_| ()).map_err(|e| {
+            tracing::warn!("Invalid SSH key jump host: {}", e);
+            StatusCode::BAD_REQUEST
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Normalize a jump host for storage: trimmed, empty = none
+fn normalize_jump_host(jump_host: &Option<String>) -> Option<String> {
+    jump_host.as_deref().map(|h| h.trim().to_string())
+}
+
 /// List all SSH keys (returns public info only, not private keys)
 pub async fn list_ssh_keys(
     State(state): State<Arc<AppState>>,
@@ -57,6 +76,7 @@ pub async fn create_ssh_key(
     if !req.private_key.contains("PRIVATE KEY") {
         return Err(StatusCode::BAD_REQUEST);
     }
+    check_jump_host(&req.jump_host)?;
 
     let id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
@@ -73,8 +93,8 @@ pub async fn create_ssh_key(
 
     sqlx::query(
         r#"
-        INSERT INTO ssh_keys (id, name, private_key, public_key, app_id, is_global, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO ssh_keys (id, name, private_key, public_key, app_id, is_global, jump_host, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, NULLIF(?, ''), ?, ?)
         "#,
     )
     .bind(&id)
@@ -83,6 +103,7 @@ pub async fn create_ssh_key(
     .bind(&req.public_key)
     .bind(&req.app_id)
     .bind(if req.is_global { 1 } else { 0 })
+    .bind(normalize_jump_host(&req.jump_host))
     .bind(&now)
     .bind(&now)
     .execute(&state.db)
@@ -139,6 +160,7 @@ pub async fn update_ssh_key(
             return Err(StatusCode::BAD_REQUEST);
         }
     }
+    check_jump_host(&req.jump_host)?;
 
     let now = chrono::Utc::now().to_rfc3339();
 
@@ -150,6 +172,7 @@ pub async fn update_ssh_key(
             public_key = COALESCE(?, public_key),
             app_id = COALESCE(?, app_id),
             is_global = COALESCE(?, is_global),
+            jump_host = NULLIF(COALESCE(?, jump_host), ''),
             updated_at = ?
         WHERE id = ?
         "#,
@@ -159,6 +182,7 @@ pub async fn update_ssh_key(
     .bind(&req.public_key)
     .bind(&req.app_id)
     .bind(req.is_global.map(|b| if b { 1 } else { 0 }))
+    .bind(normalize_jump_host(&req.jump_host))
     .bind(&now)
     .bind(&id)
     .execute(&state.db)
@@ -250,3 +274,85 @@ pub async fn get_app_ssh_keys(
     let responses: Vec<SshKeyResponse> = keys.into_iter().map(SshKeyResponse::from).collect();
     Ok(Json(responses))
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TestSshKeyRequest {
+    /// SSH git URL to reach; defaults to the repository of an app using the key
+    pub git_url: Option<String>,
+}
+
+/// Check that a repository is reachable with an SSH key (through its jump
+/// host, if it has one) without deploying
+/// POST /api/ssh-keys/:id/test
+pub async fn test_ssh_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    user: User,
+    client_ip: ClientIp,
+    body: Option<Json<TestSshKeyRequest>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let key = sqlx::query_as::<_, SshKey>("SELECT * FROM ssh_keys WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let git_url = match body.and_then(|Json(req)| req.git_url) {
+        Some(url) if !url.trim().is_empty() => url.trim().to_string(),
+        _ => {
+            let app = sqlx::query_as::<_, App>(
+                "SELECT * FROM apps WHERE (ssh_key_id = ? OR id = ?) \
+                 AND (git_url LIKE 'git@%' OR git_url LIKE 'ssh://%') LIMIT 1",
+            )
+            .bind(&key.id)
+            .bind(&key.app_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            match app {
+                Some(app) => app.git_url,
+                None => {
+                    return Ok(Json(serde_json::json!({
+                        "success": false,
+                        "message": "No app uses this key with an SSH git URL; provide git_url"
+                    })))
+                }
+            }
+        }
+    };
+
+    let via = key
+        .jump_host()
+        .map(|jump| format!(" via {}", jump.host))
+        .unwrap_or_default();
+    let result = crate::engine::check_ssh_key_access(&key, &git_url).await;
+
+    audit_log(
+        &state,
+        actions::SSH_KEY_TEST,
+        resource_types::SSH_KEY,
+        Some(&id),
+        Some(&key.name),
+        Some(&user.id),
+        client_ip.as_deref(),
+        Some(serde_json::json!({
+            "git_url": git_url,
+            "success": result.is_ok(),
+        })),
+    )
+    .await;
+
+    match result {
+        Ok(()) => Ok(Json(serde_json::json!({
+            "success": true,
+            "message": format!("Connected to {}{}", git_url, via),
+            "git_url": git_url,
+        }))),
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "message": format!("Connection to {}{} failed: {}", git_url, via, e),
+            "git_url": git_url,
+        }))),
+    }
+}
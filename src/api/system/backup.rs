@@ -12,9 +12,13 @@ use sqlx::FromRow;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::backup::acme::AcmeImportResult;
 use crate::backup::{self, BackupInfo, RestoreResult};
+use crate::db::User;
+use crate::utils::cron;
 use crate::AppState;
 
+use super::super::authz;
 use super::super::error::ApiError;
 
 // ---------------------------------------------------------------------------
@@ -83,6 +87,8 @@ pub struct BackupSchedule {
     pub last_run_at: Option<String>,
     pub next_run_at: Option<String>,
     pub created_at: String,
+    /// IANA timezone the cron expression is evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
 }
 
 /// Request to create a backup schedule
@@ -93,6 +99,8 @@ pub struct CreateBackupScheduleRequest {
     pub target_id: Option<String>,
     pub s3_config_id: Option<String>,
     pub retention_days: Option<i64>,
+    /// IANA timezone for the cron expression (default UTC)
+    pub timezone: Option<String>,
 }
 
 /// Create a backup of the Rivetr instance and return it as a file download
@@ -110,16 +118,21 @@ pub async fn create_backup(State(state): State<Arc<AppState>>) -> Result<Respons
     // The config path is stored in the Config, but we default to "rivetr.toml"
     let config_path = std::path::PathBuf::from("rivetr.toml");
 
-    let result = backup::create_backup(&state.db, data_dir, &config_path, acme_cache_dir, None)
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, "Failed to create backup");
-            ApiError::internal(format!("Failed to create backup: {}", e))
-        })?;
+    let result = backup::create_stored_backup(
+        &state.db,
+        state.storage.as_ref(),
+        data_dir,
+        &config_path,
+        acme_cache_dir,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to create backup");
+        ApiError::internal(format!("Failed to create backup: {}", e))
+    })?;
 
-    // Read the backup file and return it as a download
-    let backup_data = std::fs::read(&result.path)
-        .map_err(|e| ApiError::internal(format!("Failed to read backup file: {}", e)))?;
+    // Return the backup as a download
+    let backup_data = result.data;
 
     // Best-effort: upload to S3 if a default S3 config exists
     if let Some((s3_client, _url_prefix)) = get_default_s3_client(&state).await {
@@ -160,16 +173,16 @@ pub async fn create_backup(State(state): State<Arc<AppState>>) -> Result<Respons
 /// List existing backups
 /// GET /api/system/backups
 ///
-/// Returns a list of backup files in the data/backups/ directory.
+/// Returns the backups kept in the configured storage (`[storage]`).
 pub async fn list_backups(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Vec<BackupInfo>>, ApiError> {
-    let data_dir = &state.config.server.data_dir;
-
-    let backups = backup::list_backups(data_dir).map_err(|e| {
-        tracing::error!(error = %e, "Failed to list backups");
-        ApiError::internal(format!("Failed to list backups: {}", e))
-    })?;
+    let backups = backup::list_backups(state.storage.as_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list backups");
+            ApiError::internal(format!("Failed to list backups: {}", e))
+        })?;
 
     Ok(Json(backups))
 }
@@ -177,17 +190,17 @@ pub async fn list_backups(
 /// Delete a specific backup
 /// DELETE /api/system/backups/:name
 ///
-/// Deletes a backup file from the data/backups/ directory.
+/// Deletes a backup from the configured storage.
 pub async fn delete_backup(
     State(state): State<Arc<AppState>>,
     AxumPath(name): AxumPath<String>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let data_dir = &state.config.server.data_dir;
-
-    backup::delete_backup(data_dir, &name).map_err(|e| {
-        tracing::error!(error = %e, "Failed to delete backup: {}", name);
-        ApiError::internal(format!("Failed to delete backup: {}", e))
-    })?;
+    backup::delete_backup(state.storage.as_ref(), &name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to delete backup: {}", name);
+            ApiError::internal(format!("Failed to delete backup: {}", e))
+        })?;
 
     Ok(Json(serde_json::json!({
         "message": format!("Backup '{}' deleted successfully", name)
@@ -256,12 +269,12 @@ pub async fn download_backup(
     State(state): State<Arc<AppState>>,
     AxumPath(name): AxumPath<String>,
 ) -> Result<Response, ApiError> {
-    let data_dir = &state.config.server.data_dir;
-
-    let backup_data = backup::read_backup_file(data_dir, &name).map_err(|e| {
-        tracing::error!(error = %e, "Failed to read backup: {}", name);
-        ApiError::not_found(format!("Backup not found: {}", name))
-    })?;
+    let backup_data = backup::read_backup_file(state.storage.as_ref(), &name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to read backup: {}", name);
+            ApiError::not_found(format!("Backup not found: {}", name))
+        })?;
 
     let response = Response::builder()
         .status(StatusCode::OK)
@@ -277,10 +290,95 @@ pub async fn download_backup(
     Ok(response)
 }
 
+/// Export the ACME account key and certificates
+/// GET /api/system/acme/export
+///
+/// Returns a .tar.gz bundle of the account credentials and issued certificates
+/// from the ACME cache directory, for migrating to a new server.
+pub async fn export_acme(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Response, ApiError> {
+    // The bundle holds the ACME account key and certificate private keys.
+    if !authz::is_privileged_user(&user) {
+        return Err(ApiError::forbidden(
+            "Only admins can export ACME credentials",
+        ));
+    }
+
+    let acme_cache_dir = &state.config.proxy.acme_cache_dir;
+
+    let bundle = backup::acme::export_acme_bundle(acme_cache_dir).map_err(|e| {
+        tracing::warn!(error = %e, "Failed to export ACME bundle");
+        ApiError::not_found(e.to_string())
+    })?;
+
+    let filename = format!(
+        "rivetr-acme-{}.tar.gz",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/gzip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .header(header::CONTENT_LENGTH, bundle.len().to_string())
+        .body(Body::from(bundle))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+/// Import an ACME account key and certificates
+/// POST /api/system/acme/import
+///
+/// Accepts a multipart upload (field `file`) of a bundle produced by the export
+/// endpoint or `rivetr acme export`. A restart is needed to use the account.
+pub async fn import_acme(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    mut multipart: Multipart,
+) -> Result<Json<AcmeImportResult>, ApiError> {
+    if !authz::is_privileged_user(&user) {
+        return Err(ApiError::forbidden(
+            "Only admins can import ACME credentials",
+        ));
+    }
+
+    let acme_cache_dir = &state.config.proxy.acme_cache_dir;
+
+    let mut bundle: Option<Vec<u8>> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Failed to read upload: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Failed to read file data: {}", e)))?;
+            bundle = Some(data.to_vec());
+            break;
+        }
+    }
+
+    let data = bundle
+        .filter(|data| !data.is_empty())
+        .ok_or_else(|| ApiError::bad_request("No ACME bundle provided in field 'file'"))?;
+
+    let result = backup::acme::import_acme_bundle(&data, acme_cache_dir)
+        .map_err(|e| ApiError::bad_request(format!("Invalid ACME bundle: {:#}", e)))?;
+
+    Ok(Json(result))
+}
+
 /// Response for backup operations that may include an S3 URL
 #[derive(Debug, Serialize)]
 pub struct BackupWithS3Response {
     pub name: String,
+    /// Storage key of the backup, e.g. "backups/rivetr-backup-....tar.gz"
     pub local_path: String,
     pub size: u64,
     /// S3 URL if the backup was uploaded to S3, or null
@@ -290,19 +388,19 @@ pub struct BackupWithS3Response {
 /// Upload an existing local backup to S3
 /// POST /api/system/backups/:name/upload-to-s3
 ///
-/// Reads a backup file from the local data/backups/ directory and uploads it
+/// Reads a backup from the configured storage and uploads it
 /// to the default S3 storage configuration.
 pub async fn upload_backup_to_s3(
     State(state): State<Arc<AppState>>,
     AxumPath(name): AxumPath<String>,
 ) -> Result<Json<BackupWithS3Response>, ApiError> {
-    let data_dir = &state.config.server.data_dir;
-
     // Read the backup file
-    let backup_data = backup::read_backup_file(data_dir, &name).map_err(|e| {
-        tracing::error!(error = %e, "Failed to read backup for S3 upload: {}", name);
-        ApiError::not_found(format!("Backup not found: {}", name))
-    })?;
+    let backup_data = backup::read_backup_file(state.storage.as_ref(), &name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to read backup for S3 upload: {}", name);
+            ApiError::not_found(format!("Backup not found: {}", name))
+        })?;
 
     let size = backup_data.len() as u64;
 
@@ -325,11 +423,7 @@ pub async fn upload_backup_to_s3(
         })?;
 
     let s3_url = format!("{}/{}", url_prefix.trim_end_matches('/'), s3_key);
-    let local_path = data_dir
-        .join("backups")
-        .join(&name)
-        .to_string_lossy()
-        .to_string();
+    let local_path = backup::backup_key(&name).unwrap_or_default();
 
     tracing::info!(
         backup_name = %name,
@@ -381,17 +475,20 @@ pub async fn create_backup_schedule(
             "backup_type must be one of: instance, s3_database, s3_volume",
         ));
     }
+    cron::validate(&req.cron_expression).map_err(ApiError::bad_request)?;
+    let timezone =
+        cron::validate_timezone(req.timezone.as_deref()).map_err(ApiError::bad_request)?;
 
     let id = Uuid::new_v4().to_string();
     let retention_days = req.retention_days.unwrap_or(30);
 
     // Compute initial next_run_at from cron expression
-    let next_run_at = compute_next_run(&req.cron_expression);
+    let next_run_at = compute_next_run(&req.cron_expression, timezone.as_deref());
 
     sqlx::query(
         r#"INSERT INTO backup_schedules
-           (id, backup_type, cron_expression, target_id, s3_config_id, retention_days, enabled, next_run_at)
-           VALUES (?, ?, ?, ?, ?, ?, 1, ?)"#,
+           (id, backup_type, cron_expression, target_id, s3_config_id, retention_days, enabled, next_run_at, timezone)
+           VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)"#,
     )
     .bind(&id)
     .bind(&req.backup_type)
@@ -400,6 +497,7 @@ pub async fn create_backup_schedule(
     .bind(&req.s3_config_id)
     .bind(retention_days)
     .bind(&next_run_at)
+    .bind(&timezone)
     .execute(&state.db)
     .await
     .map_err(|e| {
@@ -512,8 +610,14 @@ pub async fn run_backup_schedule(
             let acme_cache_dir = &state.config.proxy.acme_cache_dir;
             let config_path = std::path::PathBuf::from("rivetr.toml");
 
-            match backup::create_backup(&state.db, data_dir, &config_path, acme_cache_dir, None)
-                .await
+            match backup::create_stored_backup(
+                &state.db,
+                state.storage.as_ref(),
+                data_dir,
+                &config_path,
+                acme_cache_dir,
+            )
+            .await
             {
                 Ok(result) => {
                     tracing::info!(
@@ -524,10 +628,8 @@ pub async fn run_backup_schedule(
                     // Best-effort S3 upload
                     if let Some((s3_client, _)) = get_default_s3_client(&state).await {
                         let s3_key = format!("instance-backups/{}", result.name);
-                        if let Ok(backup_data) = std::fs::read(&result.path) {
-                            if let Err(e) = s3_client.upload_backup(&s3_key, backup_data).await {
-                                tracing::warn!(error = %e, "S3 upload failed for manual run");
-                            }
+                        if let Err(e) = s3_client.upload_backup(&s3_key, result.data).await {
+                            tracing::warn!(error = %e, "S3 upload failed for manual run");
                         }
                     }
                     format!("Instance backup created: {}", result.name)
@@ -550,7 +652,7 @@ pub async fn run_backup_schedule(
     };
 
     // Update last_run_at and compute next_run_at
-    let next_run = compute_next_run(&schedule.cron_expression);
+    let next_run = compute_next_run(&schedule.cron_expression, schedule.timezone.as_deref());
     sqlx::query("UPDATE backup_schedules SET last_run_at = ?, next_run_at = ? WHERE id = ?")
         .bind(&now_str)
         .bind(&next_run)
@@ -571,22 +673,12 @@ pub async fn run_backup_schedule(
     })))
 }
 
-/// Compute the next run time from a cron expression using the `cron` crate.
-/// Falls back to 24-hours-from-now for invalid expressions.
-fn compute_next_run(cron_expression: &str) -> Option<String> {
-    use cron::Schedule;
-    use std::str::FromStr;
-
-    match Schedule::from_str(cron_expression) {
-        Ok(schedule) => schedule
-            .upcoming(chrono::Utc)
-            .next()
-            .map(|t| t.to_rfc3339()),
-        Err(_) => {
-            // Fall back to 24 hours from now for unrecognised expressions
-            Some((chrono::Utc::now() + chrono::Duration::hours(24)).to_rfc3339())
-        }
-    }
+/// Compute the next run time from a (5- or 6-field) cron expression in the
+/// schedule's timezone. Falls back to 24-hours-from-now for invalid expressions.
+fn compute_next_run(cron_expression: &str, timezone: Option<&str>) -> Option<String> {
+    // Fall back to 24 hours from now for unrecognised expressions
+    cron::next_run(cron_expression, timezone)
+        .or_else(|| Some((chrono::Utc::now() + chrono::Duration::hours(24)).to_rfc3339()))
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,128 @@
+//! Fault injection (chaos mode) handlers.
+//!
+//! Only available when `[chaos] enabled = true`; admins switch faults on and
+//! off here. See `crate::chaos` for what each fault does.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::chaos::{FaultInjector, FaultSettings};
+use crate::db::{actions, resource_types, User};
+use crate::AppState;
+
+use super::super::audit::{audit_log, ClientIp};
+use super::super::error::ApiError;
+use super::require_admin;
+
+/// Longest a set of faults may run before switching itself off
+const MAX_DURATION_SECS: u64 = 24 * 60 * 60;
+
+/// Current fault injection state
+#[derive(Debug, Serialize)]
+pub struct ChaosStatus {
+    /// Whether chaos mode is enabled in the server config
+    pub available: bool,
+    /// The active faults, if any
+    pub faults: Option<FaultSettings>,
+}
+
+/// Request to switch faults on
+#[derive(Debug, Deserialize)]
+pub struct SetChaosRequest {
+    #[serde(flatten)]
+    pub faults: FaultSettings,
+    /// Switch the faults off after this many seconds
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+}
+
+fn injector(state: &AppState) -> Result<&Arc<FaultInjector>, ApiError> {
+    state.fault_injector.as_ref().ok_or_else(|| {
+        ApiError::bad_request("Chaos mode is disabled; set [chaos] enabled = true in rivetr.toml")
+    })
+}
+
+/// Get the active faults
+/// GET /api/system/chaos
+pub async fn get_chaos(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<ChaosStatus>, ApiError> {
+    require_admin(&user)?;
+
+    Ok(Json(ChaosStatus {
+        available: state.fault_injector.is_some(),
+        faults: state.fault_injector.as_ref().and_then(|f| f.current()),
+    }))
+}
+
+/// Switch faults on, replacing any active ones
+/// PUT /api/system/chaos
+pub async fn set_chaos(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    Json(req): Json<SetChaosRequest>,
+) -> Result<Json<ChaosStatus>, ApiError> {
+    require_admin(&user)?;
+    let injector = injector(&state)?;
+
+    let mut faults = req.faults;
+    faults
+        .validate()
+        .map_err(|(field, message)| ApiError::validation_field(field, message))?;
+    if let Some(secs) = req.duration_secs {
+        if secs == 0 || secs > MAX_DURATION_SECS {
+            return Err(ApiError::validation_field(
+                "duration_secs",
+                format!("duration_secs must be between 1 and {}", MAX_DURATION_SECS),
+            ));
+        }
+        faults.expires_at = Some(chrono::Utc::now() + chrono::Duration::seconds(secs as i64));
+    }
+
+    injector.set(faults.clone());
+
+    audit_log(
+        &state,
+        actions::CHAOS_ENABLE,
+        resource_types::CHAOS,
+        None,
+        None,
+        Some(&user.id),
+        client_ip.as_deref(),
+        serde_json::to_value(&faults).ok(),
+    )
+    .await;
+
+    Ok(Json(ChaosStatus {
+        available: true,
+        faults: Some(faults),
+    }))
+}
+
+/// Switch all faults off
+/// DELETE /api/system/chaos
+pub async fn clear_chaos(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+) -> Result<StatusCode, ApiError> {
+    require_admin(&user)?;
+    injector(&state)?.clear();
+
+    audit_log(
+        &state,
+        actions::CHAOS_DISABLE,
+        resource_types::CHAOS,
+        None,
+        None,
+        Some(&user.id),
+        client_ip.as_deref(),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,12 +1,15 @@
-//! Docker/Podman resource cleanup handler.
+//! Docker/Podman resource cleanup and artifact store garbage collection handlers.
 
 use axum::{extract::State, Json};
 use serde::Serialize;
 use std::sync::Arc;
 
+use crate::db::User;
+use crate::storage::artifacts::{self, ArtifactStats};
 use crate::AppState;
 
 use super::super::error::ApiError;
+use super::require_admin;
 
 /// Response from a Docker cleanup operation
 #[derive(Debug, Clone, Serialize)]
@@ -47,3 +50,44 @@ pub async fn run_docker_cleanup(
         output: combined.trim().to_string(),
     }))
 }
+
+/// Get the size of the deployment artifact store
+/// GET /api/system/artifacts
+pub async fn get_artifact_stats(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<ArtifactStats>, ApiError> {
+    require_admin(&user)?;
+    let stats = artifacts::stats(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read artifact stats: {}", e)))?;
+    Ok(Json(stats))
+}
+
+/// Response from an artifact garbage collection
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactGcResponse {
+    pub refs_removed: u64,
+    pub blobs_removed: u64,
+    pub orphans_removed: u64,
+    pub bytes_freed: u64,
+}
+
+/// Remove artifact blobs no deployment refers to, without waiting for the
+/// periodic collection
+/// POST /api/system/artifacts/gc
+pub async fn run_artifact_gc(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<ArtifactGcResponse>, ApiError> {
+    require_admin(&user)?;
+    let stats = artifacts::collect_garbage(&state.db, state.storage.as_ref())
+        .await
+        .map_err(|e| ApiError::internal(format!("Artifact garbage collection failed: {}", e)))?;
+    Ok(Json(ArtifactGcResponse {
+        refs_removed: stats.refs_removed,
+        blobs_removed: stats.blobs_removed,
+        orphans_removed: stats.orphans_removed,
+        bytes_freed: stats.bytes_freed,
+    }))
+}
@@ -0,0 +1,65 @@
+//! Cron expression validation and next-run preview, so schedule forms (backup
+//! schedules, scheduled jobs, restarts) can check an expression before saving.
+
+use axum::Json;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::cron::{self, MAX_PREVIEW_RUNS};
+
+use super::super::error::ApiError;
+
+/// Run times returned when the request doesn't ask for a count
+const DEFAULT_PREVIEW_RUNS: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct CronPreviewRequest {
+    /// 5-field (`min hour dom month dow`) or 6-field (with seconds) expression
+    pub expression: String,
+    /// Number of upcoming runs to return (default 5, max 50)
+    pub count: Option<usize>,
+    /// IANA timezone to evaluate the expression in (default UTC, like the
+    /// schedules themselves)
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CronPreviewResponse {
+    /// The expression in the 6-field form the schedulers store
+    pub normalized: String,
+    /// Canonical name of the timezone the expression was evaluated in
+    pub timezone: String,
+    /// Current UTC offset of that timezone, e.g. "+02:00"
+    pub utc_offset: String,
+    /// Upcoming run times in that timezone (RFC 3339)
+    pub next_runs: Vec<String>,
+}
+
+/// Validate a cron expression and preview its next run times
+/// POST /api/system/cron/preview
+///
+/// Returns 400 with the parse error when the expression is invalid.
+pub async fn preview_cron(
+    Json(req): Json<CronPreviewRequest>,
+) -> Result<Json<CronPreviewResponse>, ApiError> {
+    let count = req
+        .count
+        .unwrap_or(DEFAULT_PREVIEW_RUNS)
+        .clamp(1, MAX_PREVIEW_RUNS);
+    let normalized = cron::validate(&req.expression).map_err(ApiError::bad_request)?;
+    let tz = cron::parse_timezone(req.timezone.as_deref()).map_err(ApiError::bad_request)?;
+
+    let now = Utc::now().with_timezone(&tz);
+    let next_runs = cron::upcoming(&normalized, &now, count)
+        .map_err(ApiError::bad_request)?
+        .iter()
+        .map(|t| t.to_rfc3339())
+        .collect();
+
+    Ok(Json(CronPreviewResponse {
+        normalized,
+        timezone: tz.name().to_string(),
+        utc_offset: now.format("%:z").to_string(),
+        next_runs,
+    }))
+}
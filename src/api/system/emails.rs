@@ -0,0 +1,56 @@
+//! Sent-mail log handler.
+//!
+//! Lists what the email queue sent, is still trying to send, or gave up on,
+//! for troubleshooting missing alerts. See `crate::notifications::mail_queue`.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::db::{QueuedEmail, User};
+use crate::AppState;
+
+use super::super::error::ApiError;
+use super::require_admin;
+
+/// Emails returned when no limit is given
+const DEFAULT_LIMIT: i64 = 100;
+/// Most emails returned at once
+const MAX_LIMIT: i64 = 500;
+
+/// Filters for the sent-mail log
+#[derive(Debug, Deserialize)]
+pub struct EmailLogQuery {
+    /// Only emails with this status: `pending`, `sent` or `failed`
+    pub status: Option<String>,
+    /// Only emails to addresses containing this text
+    pub to: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// List queued, sent and failed emails, newest first
+/// GET /api/system/emails
+pub async fn list_sent_emails(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Query(query): Query<EmailLogQuery>,
+) -> Result<Json<Vec<QueuedEmail>>, ApiError> {
+    require_admin(&user)?;
+    let status = query.status.as_deref().filter(|s| !s.is_empty());
+    if let Some(status) = status {
+        if !["pending", "sent", "failed"].contains(&status) {
+            return Err(ApiError::validation_field(
+                "status",
+                "Status must be one of: pending, sent, failed",
+            ));
+        }
+    }
+    let to = query.to.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let emails = QueuedEmail::list(&state.db, status, to, limit).await?;
+    Ok(Json(emails))
+}
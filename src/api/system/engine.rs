@@ -0,0 +1,39 @@
+//! Deployment engine status: queue depth, build slots, build durations and
+//! per-stage failure rates.
+
+use axum::{extract::State, Json};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::db::User;
+use crate::engine::load::EngineStatus;
+use crate::AppState;
+
+use super::super::error::ApiError;
+use super::require_admin;
+
+/// Collect the engine status from the shared counters and recent deployments
+pub async fn engine_status(state: &AppState) -> EngineStatus {
+    let channel_backlog = state.deploy_tx.max_capacity() - state.deploy_tx.capacity();
+    EngineStatus::collect(
+        &state.db,
+        &state.engine_counters,
+        state.deploy_concurrency.load(Ordering::SeqCst),
+        channel_backlog,
+    )
+    .await
+}
+
+/// Get the deployment engine's load
+/// GET /api/system/engine
+///
+/// Returns deployments waiting for and holding build slots, slot saturation,
+/// the average build duration and the failure rate of each pipeline stage
+/// over the last 24 hours.
+pub async fn get_engine_status(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<EngineStatus>, ApiError> {
+    require_admin(&user)?;
+    Ok(Json(engine_status(&state).await))
+}
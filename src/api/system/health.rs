@@ -775,11 +775,17 @@ pub async fn get_disk_stats(
 /// - Container runtime availability
 /// - Disk space status
 /// - Directory writability
+/// - Pending host security updates and container engine support, from the
+///   latest host hygiene check
 /// - Individual check results
 pub async fn get_detailed_health(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<SystemHealthStatus>, ApiError> {
-    let health = get_system_health(&state.config, &state.db).await;
+    let mut health = get_system_health(&state.config, &state.db).await;
+    if let Some(report) = state.host_hygiene.current() {
+        health.checks.extend(report.check_results());
+        health.host_hygiene = Some(report);
+    }
     Ok(Json(health))
 }
 
@@ -0,0 +1,204 @@
+//! Host maintenance handlers: cordon, drain and uncordon.
+//!
+//! Used before kernel upgrades and host moves. See
+//! `crate::engine::host_maintenance` for what each step does.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::db::{actions, resource_types, User};
+use crate::engine::host_maintenance::{AppOutcome, CordonState, CORDONED_MESSAGE};
+use crate::AppState;
+
+use super::super::audit::{audit_log, ClientIp};
+use super::super::error::ApiError;
+use super::require_admin;
+
+/// Longest accepted cordon reason or maintenance page message
+const MAX_TEXT_LEN: usize = 500;
+
+/// Current cordon state
+#[derive(Debug, Serialize)]
+pub struct CordonStatus {
+    pub cordoned: bool,
+    /// Set while the host is cordoned
+    pub cordon: Option<CordonState>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CordonRequest {
+    /// Why the host is being cordoned
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DrainRequest {
+    /// Message shown on the maintenance page of drained apps
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Result of a drain or uncordon
+#[derive(Debug, Serialize)]
+pub struct MaintenanceResponse {
+    pub cordoned: bool,
+    pub cordon: Option<CordonState>,
+    /// Apps stopped by the drain or started by the uncordon
+    pub apps: Vec<AppOutcome>,
+}
+
+fn check_text(field: &str, value: Option<String>) -> Result<Option<String>, ApiError> {
+    let value = value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if value.as_ref().is_some_and(|v| v.len() > MAX_TEXT_LEN) {
+        return Err(ApiError::validation_field(
+            field,
+            format!("{} must be at most {} characters", field, MAX_TEXT_LEN),
+        ));
+    }
+    Ok(value)
+}
+
+/// Refuse a deployment with 409 Conflict while the host is cordoned.
+pub fn check_host_cordon(state: &AppState) -> Result<(), ApiError> {
+    if state.host_cordon.is_cordoned() {
+        return Err(ApiError::conflict(CORDONED_MESSAGE));
+    }
+    Ok(())
+}
+
+fn status(state: &AppState) -> CordonStatus {
+    let cordon = state.host_cordon.current();
+    CordonStatus {
+        cordoned: cordon.is_some(),
+        cordon,
+    }
+}
+
+/// Get the cordon state
+/// GET /api/system/cordon
+pub async fn get_cordon(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<CordonStatus>, ApiError> {
+    require_admin(&user)?;
+    Ok(Json(status(&state)))
+}
+
+/// Stop accepting deployments on this host
+/// POST /api/system/cordon
+pub async fn cordon_host(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    body: Option<Json<CordonRequest>>,
+) -> Result<Json<CordonStatus>, ApiError> {
+    require_admin(&user)?;
+    let req = body.map(|Json(r)| r).unwrap_or_default();
+    let reason = check_text("reason", req.reason)?;
+
+    let cordon = state
+        .host_cordon
+        .cordon(&state.db, Some(&user.id), reason)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to cordon host: {}", e)))?;
+
+    audit_log(
+        &state,
+        actions::HOST_CORDON,
+        resource_types::HOST,
+        None,
+        None,
+        Some(&user.id),
+        client_ip.as_deref(),
+        serde_json::to_value(&cordon).ok(),
+    )
+    .await;
+
+    Ok(Json(CordonStatus {
+        cordoned: true,
+        cordon: Some(cordon),
+    }))
+}
+
+/// Cordon the host and stop its running apps in dependency order, serving a
+/// maintenance page on their domains
+/// POST /api/system/drain
+pub async fn drain_host(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+    body: Option<Json<DrainRequest>>,
+) -> Result<Json<MaintenanceResponse>, ApiError> {
+    require_admin(&user)?;
+    let req = body.map(|Json(r)| r).unwrap_or_default();
+    let message = check_text("message", req.message)?;
+
+    let apps = state
+        .host_cordon
+        .drain(
+            &state.db,
+            &state.runtime,
+            &state.routes,
+            Some(&user.id),
+            message,
+        )
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to drain host: {}", e)))?;
+
+    audit_log(
+        &state,
+        actions::HOST_DRAIN,
+        resource_types::HOST,
+        None,
+        None,
+        Some(&user.id),
+        client_ip.as_deref(),
+        serde_json::to_value(&apps).ok(),
+    )
+    .await;
+
+    let status = status(&state);
+    Ok(Json(MaintenanceResponse {
+        cordoned: status.cordoned,
+        cordon: status.cordon,
+        apps,
+    }))
+}
+
+/// Resume deployments and start the apps the drain stopped
+/// POST /api/system/uncordon
+pub async fn uncordon_host(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    client_ip: ClientIp,
+) -> Result<Json<MaintenanceResponse>, ApiError> {
+    require_admin(&user)?;
+
+    let apps = state
+        .host_cordon
+        .uncordon(&state.db, &state.runtime, &state.routes)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to uncordon host: {}", e)))?;
+
+    audit_log(
+        &state,
+        actions::HOST_UNCORDON,
+        resource_types::HOST,
+        None,
+        None,
+        Some(&user.id),
+        client_ip.as_deref(),
+        serde_json::to_value(&apps).ok(),
+    )
+    .await;
+
+    Ok(Json(MaintenanceResponse {
+        cordoned: false,
+        cordon: None,
+        apps,
+    }))
+}
@@ -1,21 +1,48 @@
 //! System-level API endpoints for dashboard statistics.
 //!
-//! Provides aggregate system stats, disk stats, recent events, and instance backup/restore.
+//! Provides aggregate system stats, disk stats, recent events, deployment engine
+//! status, cron expression previews, instance backup/restore, fault
+//! injection for chaos testing, host maintenance (cordon and drain), and the
+//! sent-mail log.
 
 mod backup;
+mod chaos;
 mod cleanup;
+mod cron_preview;
+mod emails;
+mod engine;
 mod health;
+mod maintenance;
 mod updates;
 
 // Re-export everything callers need
 pub use backup::{
     create_backup, create_backup_schedule, create_full_backup, delete_backup,
-    delete_backup_schedule, download_backup, list_backup_schedules, list_backups, restore_backup,
-    run_backup_schedule, toggle_backup_schedule, upload_backup_to_s3,
+    delete_backup_schedule, download_backup, export_acme, import_acme, list_backup_schedules,
+    list_backups, restore_backup, run_backup_schedule, toggle_backup_schedule, upload_backup_to_s3,
 };
-pub use cleanup::run_docker_cleanup;
+pub use chaos::{clear_chaos, get_chaos, set_chaos};
+pub use cleanup::{get_artifact_stats, run_artifact_gc, run_docker_cleanup};
+pub use cron_preview::preview_cron;
+pub use emails::list_sent_emails;
+pub use engine::{engine_status, get_engine_status};
 pub use health::{
-    get_detailed_health, get_disk_stats, get_host_cpu_percent, get_recent_events,
+    get_detailed_health, get_disk_stats, get_host_cpu_percent, get_host_memory, get_recent_events,
     get_stats_history, get_stats_summary, get_system_stats,
 };
-pub use updates::{apply_update, check_for_updates, download_update, get_version_info};
+pub use maintenance::{check_host_cordon, cordon_host, drain_host, get_cordon, uncordon_host};
+pub use updates::{
+    apply_update, check_for_updates, download_update, get_version_info, restart_with_handover,
+};
+
+use crate::db::User;
+
+use super::error::ApiError;
+
+/// Reject users other than admins from instance-wide operations
+fn require_admin(user: &User) -> Result<(), ApiError> {
+    if user.role != "admin" {
+        return Err(ApiError::forbidden("Admin access required"));
+    }
+    Ok(())
+}
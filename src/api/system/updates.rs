@@ -26,6 +26,15 @@ pub struct ApplyUpdateResponse {
     pub restart_required: bool,
 }
 
+/// Restart response
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartResponse {
+    pub success: bool,
+    pub message: String,
+    /// Process ID of the replacement process
+    pub pid: Option<u32>,
+}
+
 /// Get system version and update status
 /// GET /api/system/version
 ///
@@ -113,3 +122,24 @@ pub async fn apply_update(
         Err(e) => Err(ApiError::internal(format!("Failed to apply update: {}", e))),
     }
 }
+
+/// Restart onto the installed binary without dropping connections
+/// POST /api/system/update/restart
+///
+/// Starts a new process that takes over the proxy and API sockets, waits for
+/// it to serve, then shuts this process down gracefully. Use after applying
+/// an update instead of a service restart.
+pub async fn restart_with_handover() -> Result<Json<RestartResponse>, ApiError> {
+    let pid = crate::utils::handover::hand_over(crate::utils::handover::DEFAULT_READY_TIMEOUT)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to hand over to a new process: {}", e)))?;
+
+    Ok(Json(RestartResponse {
+        success: true,
+        message: format!(
+            "Replacement process {} is serving; this process is shutting down",
+            pid
+        ),
+        pid: Some(pid),
+    }))
+}
@@ -0,0 +1,302 @@
+//! Scheduled task API endpoints for applications.
+//!
+//! A scheduled task runs a shell command on a cron schedule in a one-off
+//! container started from the image of the app's running deployment (unlike
+//! scheduled jobs, which exec into the running container). Each run records
+//! its exit code and output. Cron expressions are evaluated in the task's
+//! timezone (UTC by default); standard 5-field expressions are accepted.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::crypto;
+use crate::db::{
+    CreateScheduledTaskRequest, ScheduledTask, ScheduledTaskRun, UpdateScheduledTaskRequest,
+    DEFAULT_TASK_TIMEOUT_SECS, MAX_TASK_TIMEOUT_SECS, TASK_TRIGGER_MANUAL,
+};
+use crate::engine::scheduled_tasks;
+use crate::utils::cron;
+use crate::AppState;
+
+use super::common::ensure_app_exists;
+use super::error::{ApiError, ValidationErrorBuilder};
+
+/// Key length for AES-256 encryption
+const KEY_LENGTH: usize = 32;
+
+/// Most runs returned by the run history endpoint
+const MAX_RUNS_LISTED: i64 = 50;
+
+/// Get the derived encryption key from the config if configured
+fn get_encryption_key(state: &AppState) -> Option<[u8; KEY_LENGTH]> {
+    state
+        .config
+        .auth
+        .encryption_key
+        .as_ref()
+        .map(|secret| crypto::derive_key(secret))
+}
+
+/// List the scheduled tasks of an app
+pub async fn list_tasks(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<ScheduledTask>>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    Ok(Json(ScheduledTask::list_for_app(&state.db, &app_id).await?))
+}
+
+/// Create a scheduled task
+pub async fn create_task(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<CreateScheduledTaskRequest>,
+) -> Result<(StatusCode, Json<ScheduledTask>), ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let mut errors = ValidationErrorBuilder::new();
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        errors.add("name", "Name is required");
+    }
+    let command = req.command.trim().to_string();
+    if command.is_empty() {
+        errors.add("command", "Command is required");
+    }
+    let cron_expression = checked_cron(&mut errors, &req.cron_expression);
+    let timezone = checked_timezone(&mut errors, req.timezone.as_deref());
+    let timeout_seconds = req.timeout_seconds.unwrap_or(DEFAULT_TASK_TIMEOUT_SECS);
+    check_timeout(&mut errors, timeout_seconds);
+    errors.finish()?;
+
+    let next_run_at = if req.enabled {
+        cron::next_run(&cron_expression, timezone.as_deref())
+    } else {
+        None
+    };
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO scheduled_tasks
+            (id, app_id, name, command, cron_expression, timezone, timeout_seconds,
+             enabled, next_run_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&app_id)
+    .bind(&name)
+    .bind(&command)
+    .bind(&cron_expression)
+    .bind(&timezone)
+    .bind(timeout_seconds)
+    .bind(req.enabled as i32)
+    .bind(&next_run_at)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create scheduled task: {}", e);
+        ApiError::database("Failed to create scheduled task")
+    })?;
+
+    let task = ScheduledTask::get(&state.db, &app_id, &id)
+        .await?
+        .ok_or_else(|| ApiError::internal("Scheduled task missing after insert"))?;
+
+    tracing::info!(app_id = %app_id, task_id = %id, task = %name, "Scheduled task created");
+
+    Ok((StatusCode::CREATED, Json(task)))
+}
+
+/// Get a scheduled task
+pub async fn get_task(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, task_id)): Path<(String, String)>,
+) -> Result<Json<ScheduledTask>, ApiError> {
+    Ok(Json(find_task(&state, &app_id, &task_id).await?))
+}
+
+/// Update a scheduled task.
+///
+/// Changing the schedule or re-enabling the task recomputes its next run.
+pub async fn update_task(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, task_id)): Path<(String, String)>,
+    Json(req): Json<UpdateScheduledTaskRequest>,
+) -> Result<Json<ScheduledTask>, ApiError> {
+    let existing = find_task(&state, &app_id, &task_id).await?;
+
+    let mut errors = ValidationErrorBuilder::new();
+    let name = match req.name.as_deref().map(str::trim) {
+        Some("") => {
+            errors.add("name", "Name is required");
+            String::new()
+        }
+        Some(name) => name.to_string(),
+        None => existing.name.clone(),
+    };
+    let command = match req.command.as_deref().map(str::trim) {
+        Some("") => {
+            errors.add("command", "Command is required");
+            String::new()
+        }
+        Some(command) => command.to_string(),
+        None => existing.command.clone(),
+    };
+    let cron_expression = match req.cron_expression.as_deref() {
+        Some(expr) => checked_cron(&mut errors, expr),
+        None => existing.cron_expression.clone(),
+    };
+    let timezone = match req.timezone.as_deref() {
+        Some(timezone) => checked_timezone(&mut errors, Some(timezone)),
+        None => existing.timezone.clone(),
+    };
+    let timeout_seconds = req.timeout_seconds.unwrap_or(existing.timeout_seconds);
+    check_timeout(&mut errors, timeout_seconds);
+    errors.finish()?;
+
+    let was_enabled = existing.enabled != 0;
+    let enabled = req.enabled.unwrap_or(was_enabled);
+    let schedule_changed =
+        cron_expression != existing.cron_expression || timezone != existing.timezone;
+    let next_run_at = if !enabled {
+        None
+    } else if schedule_changed || !was_enabled {
+        cron::next_run(&cron_expression, timezone.as_deref())
+    } else {
+        existing.next_run_at.clone()
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE scheduled_tasks
+        SET name = ?, command = ?, cron_expression = ?, timezone = ?, timeout_seconds = ?,
+            enabled = ?, next_run_at = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&name)
+    .bind(&command)
+    .bind(&cron_expression)
+    .bind(&timezone)
+    .bind(timeout_seconds)
+    .bind(enabled as i32)
+    .bind(&next_run_at)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(&task_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update scheduled task: {}", e);
+        ApiError::database("Failed to update scheduled task")
+    })?;
+
+    Ok(Json(find_task(&state, &app_id, &task_id).await?))
+}
+
+/// Delete a scheduled task and its run history
+pub async fn delete_task(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, task_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let result = sqlx::query("DELETE FROM scheduled_tasks WHERE id = ? AND app_id = ?")
+        .bind(&task_id)
+        .bind(&app_id)
+        .execute(&state.db)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Scheduled task not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run a scheduled task now.
+///
+/// Returns the new run immediately; poll the run history for its outcome.
+pub async fn run_task(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, task_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<ScheduledTaskRun>), ApiError> {
+    let task = find_task(&state, &app_id, &task_id).await?;
+    if ScheduledTask::is_running(&state.db, &task.id).await {
+        return Err(ApiError::conflict("A run of this task is still going"));
+    }
+
+    let run = scheduled_tasks::create_run(&state.db, &task, TASK_TRIGGER_MANUAL)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create task run: {}", e);
+            ApiError::database("Failed to create task run")
+        })?;
+
+    let db = state.db.clone();
+    let runtime = state.runtime.clone();
+    let encryption_key = get_encryption_key(&state);
+    let spawned_run = run.clone();
+    tokio::spawn(async move {
+        scheduled_tasks::execute_run(&db, &runtime, &task, &spawned_run, encryption_key.as_ref())
+            .await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// List the most recent runs of a scheduled task, newest first
+pub async fn list_task_runs(
+    State(state): State<Arc<AppState>>,
+    Path((app_id, task_id)): Path<(String, String)>,
+) -> Result<Json<Vec<ScheduledTaskRun>>, ApiError> {
+    let task = find_task(&state, &app_id, &task_id).await?;
+
+    Ok(Json(
+        ScheduledTaskRun::list(&state.db, &task.id, MAX_RUNS_LISTED).await?,
+    ))
+}
+
+/// Validate a cron expression, returning it normalized (empty if an error was recorded)
+fn checked_cron(errors: &mut ValidationErrorBuilder, expr: &str) -> String {
+    cron::validate(expr).unwrap_or_else(|e| {
+        errors.add("cron_expression", &e);
+        String::new()
+    })
+}
+
+/// Validate a timezone, returning its canonical name (`None` for UTC)
+fn checked_timezone(errors: &mut ValidationErrorBuilder, timezone: Option<&str>) -> Option<String> {
+    cron::validate_timezone(timezone).unwrap_or_else(|e| {
+        errors.add("timezone", &e);
+        None
+    })
+}
+
+fn check_timeout(errors: &mut ValidationErrorBuilder, timeout_seconds: i64) {
+    if !(1..=MAX_TASK_TIMEOUT_SECS).contains(&timeout_seconds) {
+        errors.add(
+            "timeout_seconds",
+            format!("Must be between 1 and {}", MAX_TASK_TIMEOUT_SECS),
+        );
+    }
+}
+
+async fn find_task(
+    state: &Arc<AppState>,
+    app_id: &str,
+    task_id: &str,
+) -> Result<ScheduledTask, ApiError> {
+    ensure_app_exists(state, app_id).await?;
+    ScheduledTask::get(&state.db, app_id, task_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Scheduled task not found"))
+}
@@ -309,12 +309,14 @@ pub async fn update_team(
         UPDATE teams SET
             name = COALESCE(?, name),
             slug = COALESCE(?, slug),
+            build_sandbox = COALESCE(?, build_sandbox),
             updated_at = ?
         WHERE id = ?
         "#,
     )
     .bind(&req.name)
     .bind(&req.slug)
+    .bind(&req.build_sandbox)
     .bind(&now)
     .bind(&id)
     .execute(&state.db)
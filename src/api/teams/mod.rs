@@ -9,6 +9,7 @@ mod permissions;
 use crate::db::{CreateTeamRequest, TeamMember, TeamRole, UpdateTeamRequest};
 
 use super::error::{ApiError, ValidationErrorBuilder};
+use super::validation::validate_build_sandbox;
 
 // Re-export public handlers
 pub use audit::{list_audit_logs, log_team_audit};
@@ -142,6 +143,12 @@ fn validate_update_request(req: &UpdateTeamRequest) -> Result<(), ApiError> {
         }
     }
 
+    if let Some(ref build_sandbox) = req.build_sandbox {
+        if let Err(e) = validate_build_sandbox(build_sandbox) {
+            errors.add("build_sandbox", &e);
+        }
+    }
+
     errors.finish()
 }
 
@@ -1,7 +1,8 @@
 //! App-specific validation functions.
 //!
 //! Covers: app names, dockerfile paths, healthchecks, build config, resource limits,
-//! deployment commands, port mappings, network aliases, extra hosts, and docker images.
+//! deployment commands, port mappings, network aliases, extra hosts, docker images and
+//! buildpacks configuration.
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -85,6 +86,56 @@ pub fn validate_healthcheck(healthcheck: &Option<String>) -> Result<(), String>
     Ok(())
 }
 
+/// Validate a Prometheus metrics path (optional field)
+pub fn validate_metrics_path(path: &Option<String>) -> Result<(), String> {
+    if let Some(p) = path.as_deref().filter(|p| !p.is_empty()) {
+        if p.len() > 512 {
+            return Err("Metrics path is too long (max 512 characters)".to_string());
+        }
+        if !p.starts_with('/') {
+            return Err("Metrics path must start with '/'".to_string());
+        }
+        if p.chars().any(|c| c.is_whitespace() || c == '?' || c == '#') {
+            return Err("Metrics path must be a plain URL path".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Validate the database provisioned for each preview (optional field)
+pub fn validate_preview_database(mode: &Option<String>) -> Result<(), String> {
+    use crate::db::PREVIEW_DATABASE_MODES;
+    if let Some(m) = mode.as_deref().filter(|m| !m.is_empty()) {
+        if !PREVIEW_DATABASE_MODES.contains(&m) {
+            return Err(format!(
+                "Invalid preview database '{}'. Must be one of: {}",
+                m,
+                PREVIEW_DATABASE_MODES.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate the env var receiving a preview database's connection URL
+/// (optional field)
+pub fn validate_preview_database_env(key: &Option<String>) -> Result<(), String> {
+    if let Some(k) = key.as_deref().filter(|k| !k.is_empty()) {
+        if k.len() > 128 {
+            return Err("Variable name is too long (max 128 characters)".to_string());
+        }
+        if k.starts_with(|c: char| c.is_ascii_digit())
+            || !k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(
+                "Variable name must start with a letter or underscore and contain only letters, digits and underscores"
+                    .to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Validate memory limit format (optional field)
 pub fn validate_memory_limit(memory_limit: &Option<String>) -> Result<(), String> {
     if let Some(m) = memory_limit {
@@ -597,6 +648,227 @@ pub fn validate_docker_image(image: Option<&str>) -> Result<(), String> {
     Ok(())
 }
 
+/// Validate deployment fence names (e.g. "shared-db-migrations")
+pub fn validate_deploy_fences(fences: &Option<Vec<String>>) -> Result<(), String> {
+    let Some(fences) = fences else {
+        return Ok(());
+    };
+    if fences.len() > 10 {
+        return Err("Too many deployment fences (max 10)".to_string());
+    }
+    for fence in fences {
+        if fence.is_empty() || fence.len() > 64 {
+            return Err(format!(
+                "Deployment fence '{}' must be 1 to 64 characters long",
+                fence
+            ));
+        }
+        if !fence
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Deployment fence '{}' must contain only lowercase letters, digits, dashes, and underscores",
+                fence
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate static site build settings
+pub fn validate_static_config(
+    config: &Option<crate::engine::static_builder::StaticSiteSettings>,
+) -> Result<(), String> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    for (name, command) in [
+        ("Build command", &config.build_command),
+        ("Install command", &config.install_command),
+    ] {
+        let Some(command) = command else {
+            continue;
+        };
+        if command.trim().is_empty() {
+            return Err(format!("{} cannot be empty", name));
+        }
+        if command.len() > 2048 {
+            return Err(format!("{} is too long (max 2048 characters)", name));
+        }
+        if command.contains(['\n', '\r']) {
+            return Err(format!("{} must be a single line", name));
+        }
+    }
+    validate_docker_image(config.build_image.as_deref())
+        .map_err(|e| format!("Invalid build image: {}", e))
+}
+
+/// Validate the compose file path of a "docker-compose" app (empty clears it)
+pub fn validate_compose_file(path: &Option<String>) -> Result<(), String> {
+    let Some(path) = path.as_deref().filter(|p| !p.is_empty()) else {
+        return Ok(());
+    };
+    if path.len() > 512 {
+        return Err("Compose file path is too long (max 512 characters)".to_string());
+    }
+    if path.contains("..") {
+        return Err("Compose file path cannot contain '..'".to_string());
+    }
+    if path.starts_with('/') {
+        return Err("Compose file must be a relative path".to_string());
+    }
+    if path.contains(['\0', '\\']) {
+        return Err("Compose file path contains invalid characters".to_string());
+    }
+    if !path.ends_with(".yml") && !path.ends_with(".yaml") {
+        return Err("Compose file must be a .yml or .yaml file".to_string());
+    }
+    Ok(())
+}
+
+/// Validate the compose service receiving a "docker-compose" app's traffic
+/// (empty clears it)
+pub fn validate_compose_service(service: &Option<String>) -> Result<(), String> {
+    let Some(service) = service.as_deref().filter(|s| !s.is_empty()) else {
+        return Ok(());
+    };
+    if service.len() > 63 {
+        return Err("Compose service name is too long (max 63 characters)".to_string());
+    }
+    if !service
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(
+            "Compose service name can only contain letters, digits, '-', '_' and '.'".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Validate a Cloud Native Buildpacks configuration (empty object clears it)
+pub fn validate_cnb_config(config: &serde_json::Value) -> Result<(), String> {
+    use crate::engine::pack_builder::{CnbBuilder, PackConfig};
+
+    if config.as_object().is_some_and(|o| o.is_empty()) {
+        return Ok(());
+    }
+    let config: PackConfig = serde_json::from_value(config.clone())
+        .map_err(|e| format!("Invalid buildpacks configuration: {}", e))?;
+
+    if config.builder == CnbBuilder::Custom {
+        match config.custom_builder.as_deref() {
+            Some(image) if !image.is_empty() => validate_docker_image(Some(image))
+                .map_err(|e| format!("Invalid custom builder image: {}", e))?,
+            _ => return Err("A custom builder requires a builder image".to_string()),
+        }
+    }
+
+    let buildpacks = config.buildpacks.unwrap_or_default();
+    if buildpacks.len() > 20 {
+        return Err("Too many buildpacks (max 20)".to_string());
+    }
+    for buildpack in &buildpacks {
+        if buildpack.is_empty() || buildpack.len() > 512 || buildpack.contains(char::is_whitespace)
+        {
+            return Err(format!("Invalid buildpack reference: '{}'", buildpack));
+        }
+    }
+
+    if let Some(ref policy) = config.pull_policy {
+        if !["always", "if-not-present", "never"].contains(&policy.as_str()) {
+            return Err("Pull policy must be one of: always, if-not-present, never".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a Nixpacks configuration (empty object clears it)
+pub fn validate_nixpacks_config(config: &serde_json::Value) -> Result<(), String> {
+    use crate::engine::nixpacks::NixpacksConfig;
+
+    if config.as_object().is_some_and(|o| o.is_empty()) {
+        return Ok(());
+    }
+    let config: NixpacksConfig = serde_json::from_value(config.clone())
+        .map_err(|e| format!("Invalid Nixpacks configuration: {}", e))?;
+
+    for (name, command) in [
+        ("Install command", &config.install_cmd),
+        ("Build command", &config.build_cmd),
+        ("Start command", &config.start_cmd),
+    ] {
+        let Some(command) = command else {
+            continue;
+        };
+        if command.trim().is_empty() {
+            return Err(format!("{} cannot be empty", name));
+        }
+        if command.len() > 2048 {
+            return Err(format!("{} is too long (max 2048 characters)", name));
+        }
+        if command.contains(['\n', '\r']) {
+            return Err(format!("{} must be a single line", name));
+        }
+    }
+
+    for (name, packages) in [
+        ("Nix package", &config.packages),
+        ("apt package", &config.apt_packages),
+        ("Nix lib", &config.libs),
+    ] {
+        let packages = packages.as_deref().unwrap_or_default();
+        if packages.len() > 50 {
+            return Err(format!("Too many {}s (max 50)", name));
+        }
+        for package in packages {
+            if package.is_empty()
+                || package.len() > 128
+                || !package
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+' | ':'))
+            {
+                return Err(format!("Invalid {}: '{}'", name, package));
+            }
+        }
+    }
+
+    let providers = config.effective_providers();
+    if providers.len() > 10 {
+        return Err("Too many providers (max 10)".to_string());
+    }
+    for provider in &providers {
+        // "..." stands for the auto-detected providers
+        let valid = provider == "..."
+            || (!provider.is_empty()
+                && provider.len() <= 64
+                && provider
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'));
+        if !valid {
+            return Err(format!("Invalid provider: '{}'", provider));
+        }
+    }
+
+    if let Some(ref version) = config.version {
+        let parts: Vec<&str> = version.split('.').collect();
+        if parts.len() != 3
+            || parts
+                .iter()
+                .any(|p| p.is_empty() || p.len() > 5 || !p.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(format!(
+                "Nixpacks version must look like 1.29.0, got '{}'",
+                version
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -924,6 +1196,93 @@ mod tests {
         assert!(validate_docker_image(Some("nginx@sha256:abc123")).is_ok());
     }
 
+    #[test]
+    fn test_validate_deploy_fences() {
+        let fences = |list: &[&str]| Some(list.iter().map(|s| s.to_string()).collect());
+        assert!(validate_deploy_fences(&None).is_ok());
+        assert!(validate_deploy_fences(&fences(&[])).is_ok());
+        assert!(validate_deploy_fences(&fences(&["shared-db-migrations", "cache_1"])).is_ok());
+        assert!(validate_deploy_fences(&fences(&[""])).is_err());
+        assert!(validate_deploy_fences(&fences(&["Shared DB"])).is_err());
+        assert!(validate_deploy_fences(&fences(&["a"; 11])).is_err());
+    }
+
+    #[test]
+    fn test_validate_static_config() {
+        use crate::engine::static_builder::StaticSiteSettings;
+
+        let settings = |build_command: &str| {
+            Some(StaticSiteSettings {
+                build_command: Some(build_command.to_string()),
+                build_image: Some("hugomods/hugo:exts".to_string()),
+                ..Default::default()
+            })
+        };
+        assert!(validate_static_config(&None).is_ok());
+        assert!(validate_static_config(&settings("hugo --minify")).is_ok());
+        assert!(validate_static_config(&settings("  ")).is_err());
+        assert!(validate_static_config(&settings("hugo\nrm -rf /")).is_err());
+    }
+
+    #[test]
+    fn test_validate_compose_settings() {
+        let some = |s: &str| Some(s.to_string());
+        assert!(validate_compose_file(&None).is_ok());
+        assert!(validate_compose_file(&some("")).is_ok());
+        assert!(validate_compose_file(&some("deploy/compose.prod.yaml")).is_ok());
+        assert!(validate_compose_file(&some("../docker-compose.yml")).is_err());
+        assert!(validate_compose_file(&some("/etc/compose.yml")).is_err());
+        assert!(validate_compose_file(&some("Dockerfile")).is_err());
+
+        assert!(validate_compose_service(&some("web_1.api")).is_ok());
+        assert!(validate_compose_service(&some("web app")).is_err());
+    }
+
+    #[test]
+    fn test_validate_cnb_config() {
+        use serde_json::json;
+
+        assert!(validate_cnb_config(&json!({})).is_ok());
+        assert!(validate_cnb_config(&json!({"builder": "heroku24"})).is_ok());
+        assert!(validate_cnb_config(&json!({
+            "builder": "custom",
+            "custom_builder": "registry.example.com/builders/base:1",
+            "buildpacks": ["paketo-buildpacks/nodejs"],
+            "pull_policy": "if-not-present"
+        }))
+        .is_ok());
+
+        assert!(validate_cnb_config(&json!({"builder": "custom"})).is_err());
+        assert!(validate_cnb_config(&json!({"builder": "unknown"})).is_err());
+        assert!(validate_cnb_config(&json!({"buildpacks": ["bad pack"]})).is_err());
+        assert!(validate_cnb_config(&json!({"pull_policy": "sometimes"})).is_err());
+    }
+
+    #[test]
+    fn test_validate_nixpacks_config() {
+        use serde_json::json;
+
+        assert!(validate_nixpacks_config(&json!({})).is_ok());
+        assert!(validate_nixpacks_config(&json!({
+            "install_cmd": "pnpm install --frozen-lockfile",
+            "start_cmd": "node dist/server.js",
+            "packages": ["ffmpeg", "python311Packages.pip"],
+            "providers": ["...", "python"],
+            "version": "1.29.0"
+        }))
+        .is_ok());
+        assert!(validate_nixpacks_config(&json!({"provider": "node"})).is_ok());
+
+        assert!(validate_nixpacks_config(&json!({"providers": ["Node.js"]})).is_err());
+        assert!(validate_nixpacks_config(&json!({"provider": "a b"})).is_err());
+        assert!(validate_nixpacks_config(&json!({"version": "latest"})).is_err());
+        assert!(validate_nixpacks_config(&json!({"version": "1.29"})).is_err());
+        assert!(validate_nixpacks_config(&json!({"start_cmd": "  "})).is_err());
+        assert!(validate_nixpacks_config(&json!({"build_cmd": "a\nb"})).is_err());
+        assert!(validate_nixpacks_config(&json!({"packages": ["bad pkg"]})).is_err());
+        assert!(validate_nixpacks_config(&json!({"packages": "ffmpeg"})).is_err());
+    }
+
     #[test]
     fn test_validate_docker_image_invalid() {
         // Whitespace / injection attempts rejected
@@ -139,13 +139,15 @@ pub fn validate_uuid(id: &str, field_name: &str) -> Result<(), String> {
 const VALID_ENVIRONMENTS: [&str; 3] = ["development", "staging", "production"];
 
 /// Valid build type values
-const VALID_BUILD_TYPES: [&str; 6] = [
+const VALID_BUILD_TYPES: [&str; 8] = [
     "dockerfile",
     "nixpacks",
     "railpack",
     "cnb",
+    "buildpacks",
     "static",
     "staticsite",
+    "docker-compose",
 ];
 
 /// Validate an environment value
@@ -172,6 +174,228 @@ pub fn validate_build_type(build_type: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Validate a build sandbox mode ("none" or "sandboxed")
+pub fn validate_build_sandbox(mode: &str) -> Result<(), String> {
+    use crate::engine::build_sandbox::BUILD_SANDBOX_MODES;
+    if !BUILD_SANDBOX_MODES.contains(&mode) {
+        return Err(format!(
+            "Invalid build_sandbox. Must be one of: {}",
+            BUILD_SANDBOX_MODES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Validate an egress policy ("allow_all" or "restricted")
+pub fn validate_egress_policy(policy: &str) -> Result<(), String> {
+    use crate::engine::egress::EGRESS_POLICIES;
+    if !EGRESS_POLICIES.contains(&policy) {
+        return Err(format!(
+            "Invalid egress_policy. Must be one of: {}",
+            EGRESS_POLICIES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Validate an app type ("web" or "worker")
+pub fn validate_app_type(app_type: &str) -> Result<(), String> {
+    use crate::db::APP_TYPES;
+    if !APP_TYPES.contains(&app_type) {
+        return Err(format!(
+            "Invalid app_type. Must be one of: {}",
+            APP_TYPES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the seconds a new container may take to pass its health check
+pub fn validate_healthcheck_window(secs: i64) -> Result<(), String> {
+    use crate::db::{MAX_HEALTHCHECK_WINDOW_SECS, MIN_HEALTHCHECK_WINDOW_SECS};
+    if !(MIN_HEALTHCHECK_WINDOW_SECS..=MAX_HEALTHCHECK_WINDOW_SECS).contains(&secs) {
+        return Err(format!(
+            "Health check window must be between {} and {} seconds",
+            MIN_HEALTHCHECK_WINDOW_SECS, MAX_HEALTHCHECK_WINDOW_SECS
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the seconds an app's test stage may take
+pub fn validate_test_timeout(secs: i64) -> Result<(), String> {
+    use crate::db::MAX_TEST_TIMEOUT_SECS;
+    if !(1..=MAX_TEST_TIMEOUT_SECS).contains(&secs) {
+        return Err(format!(
+            "Test timeout must be between 1 and {} seconds",
+            MAX_TEST_TIMEOUT_SECS
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a preview lifetime or inactivity timeout in hours
+pub fn validate_preview_ttl(hours: i64) -> Result<(), String> {
+    use crate::db::MAX_PREVIEW_TTL_HOURS;
+    if !(1..=MAX_PREVIEW_TTL_HOURS).contains(&hours) {
+        return Err(format!(
+            "Must be between 1 and {} hours",
+            MAX_PREVIEW_TTL_HOURS
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the minutes without requests after which an app sleeps
+pub fn validate_idle_timeout(minutes: i64) -> Result<(), String> {
+    use crate::db::MAX_IDLE_TIMEOUT_MINUTES;
+    if !(1..=MAX_IDLE_TIMEOUT_MINUTES).contains(&minutes) {
+        return Err(format!(
+            "Idle timeout must be between 1 and {} minutes",
+            MAX_IDLE_TIMEOUT_MINUTES
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a monthly bandwidth cap in GB
+pub fn validate_bandwidth_limit(gb: i64) -> Result<(), String> {
+    use crate::db::MAX_BANDWIDTH_LIMIT_GB;
+    if !(1..=MAX_BANDWIDTH_LIMIT_GB).contains(&gb) {
+        return Err(format!(
+            "Bandwidth limit must be between 1 and {} GB",
+            MAX_BANDWIDTH_LIMIT_GB
+        ));
+    }
+    Ok(())
+}
+
+/// Validate what happens once the bandwidth cap is exceeded
+pub fn validate_bandwidth_action(action: &str) -> Result<(), String> {
+    use crate::db::{BANDWIDTH_ACTION_NOTIFY, BANDWIDTH_ACTION_THROTTLE};
+    if action != BANDWIDTH_ACTION_NOTIFY && action != BANDWIDTH_ACTION_THROTTLE {
+        return Err(format!(
+            "Invalid bandwidth_limit_action. Must be one of: {}, {}",
+            BANDWIDTH_ACTION_NOTIFY, BANDWIDTH_ACTION_THROTTLE
+        ));
+    }
+    Ok(())
+}
+
+/// Largest accepted throttle rate (10 Gbit/s)
+const MAX_BANDWIDTH_THROTTLE_KBPS: i64 = 10_000_000;
+
+/// Validate the rate in kilobits per second a throttled app is limited to
+pub fn validate_bandwidth_throttle(kbps: i64) -> Result<(), String> {
+    if !(8..=MAX_BANDWIDTH_THROTTLE_KBPS).contains(&kbps) {
+        return Err(format!(
+            "Throttle rate must be between 8 and {} kbps",
+            MAX_BANDWIDTH_THROTTLE_KBPS
+        ));
+    }
+    Ok(())
+}
+
+/// Largest per-app retention override (max_deployments, keep_images)
+const MAX_RETENTION_OVERRIDE: i64 = 1000;
+
+/// Validate a per-app retention override (0 or negative clears it)
+pub fn validate_retention_override(count: i64) -> Result<(), String> {
+    if count > MAX_RETENTION_OVERRIDE {
+        return Err(format!("Must be at most {}", MAX_RETENTION_OVERRIDE));
+    }
+    Ok(())
+}
+
+/// Validate a webhook filter regex (empty string clears the filter)
+pub fn validate_webhook_filter(pattern: &str) -> Result<(), String> {
+    if pattern.len() > 512 {
+        return Err("Filter is too long (max 512 characters)".to_string());
+    }
+    regex::RegexBuilder::new(pattern)
+        .size_limit(1 << 20)
+        .build()
+        .map(|_| ())
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+/// Validate a health check type ("http", "tcp" or "command")
+pub fn validate_healthcheck_type(kind: &str) -> Result<(), String> {
+    use crate::proxy::HEALTHCHECK_TYPES;
+    if !HEALTHCHECK_TYPES.contains(&kind) {
+        return Err(format!(
+            "Invalid healthcheck_type. Must be one of: {}",
+            HEALTHCHECK_TYPES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Validate egress allow-list entries (hostnames, IPv4 addresses and IPv4 CIDRs)
+pub fn validate_egress_allowlist(entries: &[String]) -> Result<(), String> {
+    if entries.len() > 100 {
+        return Err("Too many egress allow-list entries (max 100)".to_string());
+    }
+    for entry in entries {
+        crate::engine::egress::EgressTarget::parse(entry)?;
+    }
+    Ok(())
+}
+
+/// Validate deploy wait conditions (database IDs, http(s) URLs and timeouts)
+pub fn validate_wait_conditions(conditions: &[crate::db::WaitCondition]) -> Result<(), String> {
+    if conditions.len() > 20 {
+        return Err("Too many wait conditions (max 20)".to_string());
+    }
+    for condition in conditions {
+        if condition.timeout_seconds == 0 || condition.timeout_seconds > 3600 {
+            return Err("Wait condition timeout must be between 1 and 3600 seconds".to_string());
+        }
+        match condition.target {
+            crate::db::WaitTarget::Database { ref database_id } => {
+                validate_uuid(database_id, "database_id")?;
+            }
+            crate::db::WaitTarget::Http { ref url } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(format!("Wait condition URL must be http(s): '{}'", url));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate post-deploy smoke tests (paths, expected statuses and body text)
+pub fn validate_smoke_tests(tests: &[crate::db::SmokeTest]) -> Result<(), String> {
+    if tests.len() > 20 {
+        return Err("Too many smoke tests (max 20)".to_string());
+    }
+    for test in tests {
+        if !test.path.starts_with('/') || test.path.len() > 2048 {
+            return Err(format!(
+                "Smoke test path must start with '/' and be at most 2048 characters: '{}'",
+                test.path
+            ));
+        }
+        if test.path.contains(char::is_whitespace) {
+            return Err(format!(
+                "Smoke test path must not contain whitespace: '{}'",
+                test.path
+            ));
+        }
+        if !(100..=599).contains(&test.expected_status) {
+            return Err(format!(
+                "Smoke test expected status must be between 100 and 599: {}",
+                test.expected_status
+            ));
+        }
+        if test.expected_body.as_ref().is_some_and(|b| b.len() > 1024) {
+            return Err("Smoke test expected body must be at most 1024 characters".to_string());
+        }
+    }
+    Ok(())
+}
+
 /// Validate a single domain name string (non-optional version)
 pub fn validate_domain_name(domain: &str) -> Result<(), String> {
     if domain.is_empty() {
@@ -254,6 +478,26 @@ mod tests {
         assert!(validate_git_url("ftp://example.com/repo").is_err());
     }
 
+    #[test]
+    fn test_validate_healthcheck_window() {
+        assert!(validate_healthcheck_window(10).is_ok());
+        assert!(validate_healthcheck_window(20).is_ok());
+        assert!(validate_healthcheck_window(600).is_ok());
+
+        assert!(validate_healthcheck_window(9).is_err());
+        assert!(validate_healthcheck_window(0).is_err());
+        assert!(validate_healthcheck_window(601).is_err());
+    }
+
+    #[test]
+    fn test_validate_test_timeout() {
+        assert!(validate_test_timeout(1).is_ok());
+        assert!(validate_test_timeout(3600).is_ok());
+
+        assert!(validate_test_timeout(0).is_err());
+        assert!(validate_test_timeout(3601).is_err());
+    }
+
     #[test]
     fn test_validate_port() {
         assert!(validate_port(80).is_ok());
@@ -289,6 +533,14 @@ mod tests {
         assert!(validate_branch("branch/../hack").is_err());
     }
 
+    #[test]
+    fn test_validate_webhook_filter() {
+        assert!(validate_webhook_filter("^release/.*$").is_ok());
+        assert!(validate_webhook_filter(r"\[bot\]$").is_ok());
+        assert!(validate_webhook_filter("(unclosed").is_err());
+        assert!(validate_webhook_filter(&"a".repeat(513)).is_err());
+    }
+
     #[test]
     fn test_validate_uuid() {
         assert!(validate_uuid("550e8400-e29b-41d4-a716-446655440000", "app_id").is_ok());
@@ -359,4 +611,20 @@ mod tests {
         // Invalid: bad domain format
         assert!(validate_domains(&Some(vec![Domain::new("-invalid.com".to_string()),])).is_err());
     }
+
+    #[test]
+    fn test_validate_smoke_tests() {
+        let test = |path: &str, status: u16| crate::db::SmokeTest {
+            path: path.to_string(),
+            expected_status: status,
+            expected_body: None,
+        };
+        assert!(validate_smoke_tests(&[]).is_ok());
+        assert!(validate_smoke_tests(&[test("/", 200), test("/api/status?full=1", 204)]).is_ok());
+        assert!(validate_smoke_tests(&[test("api/status", 200)]).is_err());
+        assert!(validate_smoke_tests(&[test("/a b", 200)]).is_err());
+        assert!(validate_smoke_tests(&[test("/", 99)]).is_err());
+        assert!(validate_smoke_tests(&[test("/", 600)]).is_err());
+        assert!(validate_smoke_tests(&vec![test("/", 200); 21]).is_err());
+    }
 }
@@ -6,7 +6,8 @@ use axum::{
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::db::{CreateVolumeRequest, UpdateVolumeRequest, Volume, VolumeResponse};
+use crate::api::authz;
+use crate::db::{CreateVolumeRequest, UpdateVolumeRequest, User, Volume, VolumeResponse};
 use crate::AppState;
 
 /// List all volumes for an app
@@ -44,6 +45,38 @@ pub async fn list_volumes(
     Ok(Json(responses))
 }
 
+/// List the volumes of all apps the user can access (e.g. to fill a template
+/// field from one)
+pub async fn list_all_volumes(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<Vec<VolumeResponse>>, StatusCode> {
+    let volumes = if authz::is_privileged_user(&user) {
+        sqlx::query_as::<_, Volume>("SELECT * FROM volumes ORDER BY name ASC")
+            .fetch_all(&state.db)
+            .await
+    } else {
+        // Volumes of apps in the user's teams, plus legacy (team_id IS NULL) apps
+        sqlx::query_as::<_, Volume>(
+            "SELECT v.* FROM volumes v JOIN apps a ON a.id = v.app_id \
+             WHERE a.team_id IS NULL \
+                OR a.team_id IN (SELECT team_id FROM team_members WHERE user_id = ?) \
+             ORDER BY v.name ASC",
+        )
+        .bind(&user.id)
+        .fetch_all(&state.db)
+        .await
+    }
+    .map_err(|e| {
+        tracing::error!("Failed to list volumes: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let responses: Vec<VolumeResponse> = volumes.into_iter().map(VolumeResponse::from).collect();
+
+    Ok(Json(responses))
+}
+
 /// Create a new volume for an app
 pub async fn create_volume(
     State(state): State<Arc<AppState>>,
@@ -434,3 +467,73 @@ async fn create_tar_gz_backup(path: &std::path::Path) -> anyhow::Result<Vec<u8>>
 
     Ok(compressed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_user};
+
+    async fn add_volume(state: &AppState, app_id: &str, name: &str) {
+        sqlx::query(
+            "INSERT INTO volumes (id, app_id, name, host_path, container_path) \
+             VALUES (?, ?, ?, ?, '/data')",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(app_id)
+        .bind(name)
+        .bind(format!("/srv/{}", name))
+        .execute(&state.db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_all_volumes_is_scoped_to_the_users_teams() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let member = test_user(&state.db, "member").await;
+        let admin = test_user(&state.db, "admin").await;
+        let (mine, theirs) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        for team_id in [&mine, &theirs] {
+            sqlx::query("INSERT INTO teams (id, name, slug) VALUES (?, ?, ?)")
+                .bind(team_id)
+                .bind(team_id)
+                .bind(team_id)
+                .execute(&state.db)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO team_members (id, team_id, user_id, role) VALUES (?, ?, ?, 'developer')",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&mine)
+        .bind(&member.id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+        for (name, team_id) in [
+            ("a-own", Some(&mine)),
+            ("b-other", Some(&theirs)),
+            ("c-legacy", None),
+        ] {
+            let app_id = test_app(&state.db, name).await;
+            sqlx::query("UPDATE apps SET team_id = ? WHERE id = ?")
+                .bind(team_id)
+                .bind(&app_id)
+                .execute(&state.db)
+                .await
+                .unwrap();
+            add_volume(&state, &app_id, name).await;
+        }
+
+        let names = |volumes: Vec<VolumeResponse>| -> Vec<String> {
+            volumes.into_iter().map(|v| v.name).collect()
+        };
+        let Json(volumes) = list_all_volumes(State(state.clone()), member)
+            .await
+            .unwrap();
+        assert_eq!(names(volumes), ["a-own", "c-legacy"]);
+        let Json(volumes) = list_all_volumes(State(state.clone()), admin).await.unwrap();
+        assert_eq!(names(volumes), ["a-own", "b-other", "c-legacy"]);
+    }
+}
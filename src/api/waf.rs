@@ -0,0 +1,176 @@
+//! Built-in WAF API endpoints for applications.
+//!
+//! Enables/configures the proxy's rule engine per app (block or report mode,
+//! anomaly threshold) and exposes the log of requests that tripped it.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::db::{AppWafSettings, UpdateWafSettingsRequest, WafEvent};
+use crate::proxy::WafMode;
+use crate::AppState;
+
+use super::common::{ensure_app_exists, refresh_proxy_routes};
+use super::error::ApiError;
+
+#[derive(Debug, Deserialize)]
+pub struct ListWafEventsParams {
+    /// Filter by action: "blocked" or "reported"
+    pub action: Option<String>,
+    /// Page number (1-based, default 1)
+    pub page: Option<i64>,
+    /// Rows per page (default 50, max 500)
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WafEventListResponse {
+    pub items: Vec<WafEvent>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// Get WAF settings for an app
+pub async fn get_waf_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+) -> Result<Json<AppWafSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let settings = AppWafSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppWafSettings::default_for(&app_id));
+
+    Ok(Json(settings))
+}
+
+/// Update WAF settings for an app
+pub async fn update_waf_settings(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Json(req): Json<UpdateWafSettingsRequest>,
+) -> Result<Json<AppWafSettings>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    let existing = AppWafSettings::get(&state.db, &app_id)
+        .await?
+        .unwrap_or_else(|| AppWafSettings::default_for(&app_id));
+
+    let mode = req.mode.clone().unwrap_or(existing.mode);
+    if WafMode::parse(&mode).is_none() {
+        return Err(ApiError::validation_field(
+            "mode",
+            "Mode must be 'block' or 'report'".to_string(),
+        ));
+    }
+
+    let anomaly_threshold = req.anomaly_threshold.unwrap_or(existing.anomaly_threshold);
+    if !(1..=100).contains(&anomaly_threshold) {
+        return Err(ApiError::validation_field(
+            "anomaly_threshold",
+            "Anomaly threshold must be between 1 and 100".to_string(),
+        ));
+    }
+
+    let enabled = req.enabled.unwrap_or(existing.enabled != 0);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_waf_settings (app_id, enabled, mode, anomaly_threshold, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(app_id) DO UPDATE SET
+            enabled = excluded.enabled,
+            mode = excluded.mode,
+            anomaly_threshold = excluded.anomaly_threshold,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&app_id)
+    .bind(enabled)
+    .bind(&mode)
+    .bind(anomaly_threshold)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update WAF settings: {}", e);
+        ApiError::database("Failed to update WAF settings")
+    })?;
+
+    let settings = AppWafSettings::get(&state.db, &app_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("WAF settings missing after update"))?;
+
+    // Apply to the live proxy routes for this app
+    let policy = settings.to_policy();
+    let domains = refresh_proxy_routes(&state, &app_id, false, |routes, domain, _| {
+        routes.update_waf(domain, policy.clone());
+    })
+    .await;
+    tracing::info!(
+        app_id = %app_id,
+        domains = ?domains,
+        enabled = enabled,
+        mode = %mode,
+        "WAF settings refreshed in proxy route table"
+    );
+
+    Ok(Json(settings))
+}
+
+/// List requests that reached the WAF anomaly threshold for an app (newest first)
+pub async fn list_waf_events(
+    State(state): State<Arc<AppState>>,
+    Path(app_id): Path<String>,
+    Query(params): Query<ListWafEventsParams>,
+) -> Result<Json<WafEventListResponse>, ApiError> {
+    ensure_app_exists(&state, &app_id).await?;
+
+    if let Some(ref action) = params.action {
+        if action != "blocked" && action != "reported" {
+            return Err(ApiError::validation_field(
+                "action",
+                "Action must be 'blocked' or 'reported'".to_string(),
+            ));
+        }
+    }
+
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(50).clamp(1, 500);
+    let offset = (page - 1) * per_page;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM waf_events WHERE app_id = ? AND (? IS NULL OR action = ?)",
+    )
+    .bind(&app_id)
+    .bind(&params.action)
+    .bind(&params.action)
+    .fetch_one(&state.db)
+    .await?;
+
+    let items = sqlx::query_as::<_, WafEvent>(
+        "SELECT * FROM waf_events WHERE app_id = ? AND (? IS NULL OR action = ?) \
+         ORDER BY id DESC LIMIT ? OFFSET ?",
+    )
+    .bind(&app_id)
+    .bind(&params.action)
+    .bind(&params.action)
+    .bind(per_page)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(WafEventListResponse {
+        items,
+        total,
+        page,
+        per_page,
+    }))
+}
@@ -7,15 +7,31 @@ use axum::{
 };
 use serde::Deserialize;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
-use super::{handle_generic_preview_cleanup, incr_webhooks, log_wh_event, verify_github_signature};
+use super::{
+    handle_generic_preview_cleanup, incr_webhooks, log_wh_event, needs_changed_files,
+    prewarm_instead_of_deploy, push_filter_skip_reason, queue_or_hold_deployment,
+    should_deploy_for_changed_files, verify_github_signature,
+};
+use crate::api::deployments::freeze_hold;
 use crate::crypto;
 use crate::db::App;
 use crate::engine::preview::{
     find_or_create_preview, run_preview_deployment, PreviewDeploymentInfo,
 };
-use crate::AppState;
+use crate::engine::prewarm::PrewarmCommit;
+use crate::{AppState, DbPool};
+
+/// Bitbucket Cloud REST API base URL
+const BITBUCKET_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// Timeout for each diffstat request
+const DIFFSTAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most diffstat pages read for one push (500 files each)
+const MAX_DIFFSTAT_PAGES: usize = 10;
 
 // ---------------------------------------------------------------------------
 // Bitbucket payload types
@@ -35,6 +51,17 @@ pub struct BitbucketPush {
 #[derive(Debug, Deserialize)]
 pub struct BitbucketChange {
     pub new: Option<BitbucketRef>,
+    /// Branch tip before the push; absent when the branch was created
+    #[serde(default)]
+    pub old: Option<BitbucketRef>,
+    #[serde(default)]
+    pub links: Option<BitbucketChangeLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitbucketChangeLinks {
+    /// Diff view for the pushed range
+    pub html: Option<BitbucketLink>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +76,26 @@ pub struct BitbucketRef {
 pub struct BitbucketTarget {
     pub hash: String,
     pub message: Option<String>,
+    #[serde(default)]
+    pub author: Option<BitbucketCommitAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitbucketCommitAuthor {
+    /// Raw author string, e.g. `Jane Doe <jane@example.com>`
+    pub raw: String,
+}
+
+impl BitbucketCommitAuthor {
+    /// Author name without the email address
+    fn name(&self) -> String {
+        self.raw
+            .split('<')
+            .next()
+            .unwrap_or(&self.raw)
+            .trim()
+            .to_string()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,6 +116,24 @@ pub struct BitbucketLink {
     pub href: String,
 }
 
+/// A page of the diffstat API response
+#[derive(Debug, Deserialize)]
+struct BitbucketDiffstatPage {
+    values: Vec<BitbucketDiffstat>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketDiffstat {
+    old: Option<BitbucketDiffstatFile>,
+    new: Option<BitbucketDiffstatFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketDiffstatFile {
+    path: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BitbucketPullRequestEvent {
     pub pullrequest: BitbucketPullRequest,
@@ -202,8 +267,9 @@ async fn handle_bitbucket_push(
             continue;
         }
 
-        let apps = sqlx::query_as::<_, App>(
-            "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ? OR git_url LIKE ?) AND branch = ?",
+        let mut apps = sqlx::query_as::<_, App>(
+            "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ? OR git_url LIKE ?) \
+             AND (branch = ? OR webhook_branch_regex IS NOT NULL)",
         )
         .bind(format!("%{}", https_url))
         .bind(format!("%{}", ssh_url))
@@ -212,6 +278,7 @@ async fn handle_bitbucket_push(
         .fetch_all(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        apps.retain(|app| super::app_tracks_branch(app, branch));
 
         if apps.is_empty() {
             tracing::warn!(
@@ -221,34 +288,82 @@ async fn handle_bitbucket_push(
             continue;
         }
 
+        // Bitbucket push payloads don't list changed files; look them up only
+        // when an app filters on paths (e.g. apps sharing a monorepo)
+        let changed_files = match &change.old {
+            Some(old_ref) if apps.iter().any(needs_changed_files) => {
+                fetch_changed_files(
+                    &state.db,
+                    &apps,
+                    &payload.repository.full_name,
+                    &new_ref.target.hash,
+                    &old_ref.target.hash,
+                )
+                .await
+            }
+            _ => Vec::new(),
+        };
+
+        let push_author = new_ref.target.author.as_ref().map(|a| a.name());
         for app in apps {
+            if !should_deploy_for_changed_files(&app, &changed_files) {
+                tracing::info!(app = %app.name, "Skipping deployment: no watched paths changed");
+                continue;
+            }
+
+            if let Some(reason) =
+                push_filter_skip_reason(&app, push_author.as_deref(), &changed_files)
+            {
+                tracing::info!(app = %app.name, "Skipping deployment: {}", reason);
+                continue;
+            }
+
+            if let Some(message) = app.deploy_lock_message() {
+                tracing::warn!(app = %app.name, "Skipping deployment: {}", message);
+                continue;
+            }
+
+            let prewarm = PrewarmCommit {
+                sha: Some(new_ref.target.hash.clone()),
+                message: new_ref.target.message.clone(),
+                author: new_ref.target.author.as_ref().map(|a| a.name()),
+            };
+            if prewarm_instead_of_deploy(&state, &app, prewarm).await {
+                continue;
+            }
+            let hold = freeze_hold(&state.db, &app).await;
+
             let deployment_id = Uuid::new_v4().to_string();
             let now = chrono::Utc::now().to_rfc3339();
 
             sqlx::query(
                 r#"
-                INSERT INTO deployments (id, app_id, commit_sha, commit_message, status, started_at)
-                VALUES (?, ?, ?, ?, 'pending', ?)
+                INSERT INTO deployments (id, app_id, commit_sha, commit_message, commit_author,
+                                         commit_branch, commit_compare_url, status, started_at,
+                                         scheduled_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?)
                 "#,
             )
             .bind(&deployment_id)
             .bind(&app.id)
             .bind(Some(&new_ref.target.hash))
             .bind(new_ref.target.message.as_deref())
+            .bind(new_ref.target.author.as_ref().map(|a| a.name()))
+            .bind(branch)
+            .bind(
+                change
+                    .links
+                    .as_ref()
+                    .and_then(|l| l.html.as_ref())
+                    .map(|l| l.href.clone()),
+            )
             .bind(&now)
+            .bind(hold.as_ref().map(|h| h.release_at.to_rfc3339()))
             .execute(&state.db)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-            if let Err(e) = state
-                .deploy_tx
-                .send((deployment_id.clone(), app.clone()))
-                .await
-            {
-                tracing::error!("Failed to queue deployment: {}", e);
-            }
-
-            tracing::info!("Queued deployment {} for app {}", deployment_id, app.name);
+            queue_or_hold_deployment(&state, deployment_id, &app, hold.as_ref()).await;
         }
 
         log_wh_event(
@@ -270,6 +385,89 @@ async fn handle_bitbucket_push(
     Ok(StatusCode::OK)
 }
 
+/// Files changed between two commits, from the Bitbucket diffstat API.
+///
+/// Authenticates with the Bitbucket provider linked to one of the apps, so
+/// private repositories work. Returns no files when the lookup fails, which
+/// deploys every app rather than silently dropping the push.
+async fn fetch_changed_files(
+    db: &DbPool,
+    apps: &[App],
+    full_name: &str,
+    new_hash: &str,
+    old_hash: &str,
+) -> Vec<String> {
+    let mut token = None;
+    for app in apps {
+        token = sqlx::query_scalar::<_, String>(
+            "SELECT gp.access_token FROM apps a \
+             JOIN git_providers gp ON gp.id = a.git_provider_id \
+             WHERE a.id = ? AND gp.provider = 'bitbucket'",
+        )
+        .bind(&app.id)
+        .fetch_optional(db)
+        .await
+        .ok()
+        .flatten();
+        if token.is_some() {
+            break;
+        }
+    }
+
+    let client = match reqwest::Client::builder().timeout(DIFFSTAT_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Failed to build Bitbucket API client: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut files = Vec::new();
+    let mut url = Some(format!(
+        "{}/repositories/{}/diffstat/{}..{}?pagelen=500",
+        BITBUCKET_API_URL, full_name, new_hash, old_hash
+    ));
+    let mut pages = 0;
+    while let Some(page_url) = url.take() {
+        pages += 1;
+        if pages > MAX_DIFFSTAT_PAGES {
+            tracing::warn!(repo = %full_name, "Push diff too large; deploying all matching apps");
+            return Vec::new();
+        }
+
+        let mut request = client.get(&page_url);
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        let page = match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.json::<BitbucketDiffstatPage>().await,
+            Err(e) => Err(e),
+        };
+        let page = match page {
+            Ok(page) => page,
+            Err(e) => {
+                tracing::warn!(
+                    repo = %full_name,
+                    "Failed to fetch Bitbucket diffstat, deploying all matching apps: {}",
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        for entry in page.values {
+            // Renames touch both the old and the new path
+            files.extend(entry.old.map(|f| f.path));
+            files.extend(entry.new.map(|f| f.path));
+        }
+        url = page.next;
+    }
+
+    files.sort();
+    files.dedup();
+    files
+}
+
 async fn handle_bitbucket_pull_request(
     state: Arc<AppState>,
     body: &[u8],
@@ -372,6 +570,7 @@ async fn handle_bitbucket_preview_deploy(
         .encryption_key
         .as_ref()
         .map(|secret| crypto::derive_key(secret));
+    let sandbox = state.config.build_sandbox.clone();
 
     tokio::spawn(async move {
         if let Err(e) = run_preview_deployment(
@@ -381,6 +580,7 @@ async fn handle_bitbucket_preview_deploy(
             &preview,
             &app_clone,
             encryption_key.as_ref(),
+            &sandbox,
         )
         .await
         {
@@ -1,6 +1,7 @@
 //! DockerHub webhook handler — deploy apps when an image is pushed.
 
-use super::{incr_webhooks, log_wh_event};
+use super::{incr_webhooks, log_wh_event, queue_or_hold_deployment};
+use crate::api::deployments::freeze_hold;
 use crate::{db::App, AppState};
 use axum::{
     body::Bytes,
@@ -115,34 +116,28 @@ pub async fn dockerhub_webhook(
 
     let apps_count = apps.len() as i64;
     for app in apps {
+        if let Some(message) = app.deploy_lock_message() {
+            tracing::warn!(app = %app.name, "Skipping deployment: {}", message);
+            continue;
+        }
+        let hold = freeze_hold(&state.db, &app).await;
         let deployment_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
 
         sqlx::query(
-            "INSERT INTO deployments (id, app_id, commit_sha, commit_message, status, started_at) VALUES (?, ?, ?, ?, 'pending', ?)"
+            "INSERT INTO deployments (id, app_id, commit_sha, commit_message, status, started_at, scheduled_at) VALUES (?, ?, ?, ?, 'pending', ?, ?)"
         )
         .bind(&deployment_id)
         .bind(&app.id)
         .bind(format!("{}:{}", image_name, tag))
         .bind(format!("DockerHub push: {}:{}", image_name, tag))
         .bind(&now)
+        .bind(hold.as_ref().map(|h| h.release_at.to_rfc3339()))
         .execute(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        if let Err(e) = state
-            .deploy_tx
-            .send((deployment_id.clone(), app.clone()))
-            .await
-        {
-            tracing::error!("Failed to queue deployment: {}", e);
-        }
-
-        tracing::info!(
-            "Queued deployment {} for app {} on DockerHub push",
-            deployment_id,
-            app.name
-        );
+        queue_or_hold_deployment(&state, deployment_id, &app, hold.as_ref()).await;
     }
 
     log_wh_event(
@@ -11,13 +11,16 @@ use uuid::Uuid;
 
 use super::{
     collect_changed_files, handle_generic_preview_cleanup, incr_webhooks, log_wh_event,
+    prewarm_instead_of_deploy, push_filter_skip_reason, queue_or_hold_deployment,
     should_deploy_for_changed_files, verify_gitea_signature, ChangedFiles,
 };
+use crate::api::deployments::freeze_hold;
 use crate::crypto;
 use crate::db::App;
 use crate::engine::preview::{
     find_or_create_preview, run_preview_deployment, PreviewDeploymentInfo,
 };
+use crate::engine::prewarm::PrewarmCommit;
 use crate::AppState;
 
 // ---------------------------------------------------------------------------
@@ -32,6 +35,9 @@ pub struct GiteaPushEvent {
     pub after: String,
     pub repository: GiteaRepository,
     pub commits: Vec<GiteaCommit>,
+    /// Link to the compare view for the pushed range
+    #[serde(default)]
+    pub compare_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +52,8 @@ pub struct GiteaCommit {
     pub id: String,
     pub message: String,
     #[serde(default)]
+    pub author: Option<GiteaCommitAuthor>,
+    #[serde(default)]
     pub added: Vec<String>,
     #[serde(default)]
     pub modified: Vec<String>,
@@ -53,6 +61,11 @@ pub struct GiteaCommit {
     pub removed: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GiteaCommitAuthor {
+    pub name: String,
+}
+
 impl ChangedFiles for &GiteaCommit {
     fn added_files(&self) -> &[String] {
         &self.added
@@ -176,8 +189,9 @@ async fn handle_gitea_push(state: Arc<AppState>, body: &[u8]) -> Result<StatusCo
         return Ok(StatusCode::OK);
     }
 
-    let apps = sqlx::query_as::<_, App>(
-        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) AND branch = ?",
+    let mut apps = sqlx::query_as::<_, App>(
+        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) \
+         AND (branch = ? OR webhook_branch_regex IS NOT NULL)",
     )
     .bind(format!("%{}", payload.repository.clone_url))
     .bind(format!("%{}", payload.repository.ssh_url))
@@ -185,39 +199,75 @@ async fn handle_gitea_push(state: Arc<AppState>, body: &[u8]) -> Result<StatusCo
     .fetch_all(&state.db)
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    apps.retain(|app| super::app_tracks_branch(app, branch));
 
     let changed_files = collect_changed_files(payload.commits.iter());
     let first_commit_sha = payload.commits.first().map(|c| c.id.as_str());
     let apps_count = apps.len() as i64;
 
+    let push_author = payload
+        .commits
+        .first()
+        .and_then(|c| c.author.as_ref())
+        .map(|a| a.name.as_str());
+
     for app in apps {
         if !should_deploy_for_changed_files(&app, &changed_files) {
             tracing::info!(app = %app.name, "Skipping deployment: no watched files changed");
             continue;
         }
+        if let Some(reason) = push_filter_skip_reason(&app, push_author, &changed_files) {
+            tracing::info!(app = %app.name, "Skipping deployment: {}", reason);
+            continue;
+        }
+
+        if let Some(message) = app.deploy_lock_message() {
+            tracing::warn!(app = %app.name, "Skipping deployment: {}", message);
+            continue;
+        }
+
+        let commit = payload.commits.first();
+        let prewarm = PrewarmCommit {
+            sha: commit.map(|c| c.id.clone()),
+            message: commit.map(|c| c.message.clone()),
+            author: commit
+                .and_then(|c| c.author.as_ref())
+                .map(|a| a.name.clone()),
+        };
+        if prewarm_instead_of_deploy(&state, &app, prewarm).await {
+            continue;
+        }
+        let hold = freeze_hold(&state.db, &app).await;
 
         let deployment_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
-        let commit = payload.commits.first();
 
         sqlx::query(
             r#"
-            INSERT INTO deployments (id, app_id, commit_sha, commit_message, status, started_at)
-            VALUES (?, ?, ?, ?, 'pending', ?)
+            INSERT INTO deployments (id, app_id, commit_sha, commit_message, commit_author,
+                                     commit_branch, commit_compare_url, status, started_at,
+                                     scheduled_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?)
             "#,
         )
         .bind(&deployment_id)
         .bind(&app.id)
         .bind(commit.map(|c| c.id.clone()))
         .bind(commit.map(|c| c.message.clone()))
+        .bind(
+            commit
+                .and_then(|c| c.author.as_ref())
+                .map(|a| a.name.clone()),
+        )
+        .bind(branch)
+        .bind(payload.compare_url.as_deref().filter(|u| !u.is_empty()))
         .bind(&now)
+        .bind(hold.as_ref().map(|h| h.release_at.to_rfc3339()))
         .execute(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        if let Err(e) = state.deploy_tx.send((deployment_id, app.clone())).await {
-            tracing::error!("Failed to queue deployment: {}", e);
-        }
+        queue_or_hold_deployment(&state, deployment_id, &app, hold.as_ref()).await;
     }
 
     log_wh_event(
@@ -341,6 +391,7 @@ async fn handle_gitea_pr_deploy(
         .encryption_key
         .as_ref()
         .map(|secret| crypto::derive_key(secret));
+    let sandbox = state.config.build_sandbox.clone();
 
     tokio::spawn(async move {
         if let Err(e) = run_preview_deployment(
@@ -350,6 +401,7 @@ async fn handle_gitea_pr_deploy(
             &preview,
             &app_clone,
             encryption_key.as_ref(),
+            &sandbox,
         )
         .await
         {
@@ -10,14 +10,18 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use super::{
-    collect_changed_files, handle_generic_preview_cleanup, incr_webhooks, record_delivery_id,
-    should_deploy_for_changed_files, update_wh_event, verify_github_signature, ChangedFiles,
+    collect_changed_files, handle_generic_preview_cleanup, incr_webhooks,
+    post_preview_closed_comment, prewarm_instead_of_deploy, push_filter_skip_reason,
+    queue_or_hold_deployment, record_delivery_id, should_deploy_for_changed_files, update_wh_event,
+    verify_github_signature, ChangedFiles,
 };
+use crate::api::deployments::freeze_hold;
 use crate::crypto;
 use crate::db::{App, PreviewDeployment};
 use crate::engine::preview::{
     find_or_create_preview, post_preview_comment, run_preview_deployment, PreviewDeploymentInfo,
 };
+use crate::engine::prewarm::PrewarmCommit;
 use crate::AppState;
 
 // ---------------------------------------------------------------------------
@@ -36,6 +40,9 @@ pub struct GitHubPushEvent {
     pub head_commit: Option<GitHubHeadCommit>,
     #[serde(default)]
     pub commits: Vec<GitHubCommitDetail>,
+    /// Link to the compare view for the pushed range
+    #[serde(default)]
+    pub compare: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +56,13 @@ pub struct GitHubRepository {
 pub struct GitHubHeadCommit {
     pub id: String,
     pub message: String,
+    #[serde(default)]
+    pub author: Option<GitHubCommitAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitHubCommitAuthor {
+    pub name: String,
 }
 
 /// Detailed commit info including file changes (used in the `commits` array)
@@ -267,8 +281,9 @@ async fn handle_github_push(
         return Ok(StatusCode::OK);
     }
 
-    let apps = sqlx::query_as::<_, App>(
-        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) AND branch = ?",
+    let mut apps = sqlx::query_as::<_, App>(
+        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) \
+         AND (branch = ? OR webhook_branch_regex IS NOT NULL)",
     )
     .bind(format!("%{}", payload.repository.clone_url))
     .bind(format!("%{}", payload.repository.ssh_url))
@@ -276,6 +291,7 @@ async fn handle_github_push(
     .fetch_all(&state.db)
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    apps.retain(|app| super::app_tracks_branch(app, branch));
 
     if apps.is_empty() {
         tracing::warn!("No matching app found for push webhook");
@@ -298,6 +314,11 @@ async fn handle_github_push(
 
     let changed_files = collect_changed_files(payload.commits.iter());
     let apps_count = apps.len() as i64;
+    let push_author = payload
+        .head_commit
+        .as_ref()
+        .and_then(|c| c.author.as_ref())
+        .map(|a| a.name.as_str());
 
     for app in apps {
         if !should_deploy_for_changed_files(&app, &changed_files) {
@@ -307,9 +328,33 @@ async fn handle_github_push(
             );
             continue;
         }
+        if let Some(reason) = push_filter_skip_reason(&app, push_author, &changed_files) {
+            tracing::info!(app = %app.name, "Skipping deployment: {}", reason);
+            continue;
+        }
+
+        if let Some(message) = app.deploy_lock_message() {
+            tracing::warn!(app = %app.name, "Skipping deployment: {}", message);
+            continue;
+        }
 
         let commit_sha = payload.head_commit.as_ref().map(|c| c.id.clone());
         let commit_message = payload.head_commit.as_ref().map(|c| c.message.clone());
+        let commit_author = payload
+            .head_commit
+            .as_ref()
+            .and_then(|c| c.author.as_ref())
+            .map(|a| a.name.clone());
+
+        let prewarm = PrewarmCommit {
+            sha: commit_sha.clone(),
+            message: commit_message.clone(),
+            author: commit_author.clone(),
+        };
+        if prewarm_instead_of_deploy(&state, &app, prewarm).await {
+            continue;
+        }
+        let hold = freeze_hold(&state.db, &app).await;
 
         // Atomically check for an active deployment and insert a new one if none exists.
         // Using BEGIN IMMEDIATE acquires SQLite's write lock upfront, so two concurrent
@@ -360,14 +405,19 @@ async fn handle_github_push(
 
             sqlx::query(
                 "INSERT INTO deployments \
-                 (id, app_id, commit_sha, commit_message, status, started_at) \
-                 VALUES (?, ?, ?, ?, 'pending', ?)",
+                 (id, app_id, commit_sha, commit_message, commit_author, commit_branch, \
+                  commit_compare_url, status, started_at, scheduled_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?)",
             )
             .bind(&id)
             .bind(&app.id)
             .bind(&commit_sha)
             .bind(&commit_message)
+            .bind(&commit_author)
+            .bind(branch)
+            .bind(&payload.compare)
             .bind(&now)
+            .bind(hold.as_ref().map(|h| h.release_at.to_rfc3339()))
             .execute(&mut *conn)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -380,15 +430,7 @@ async fn handle_github_push(
             id
         };
 
-        if let Err(e) = state
-            .deploy_tx
-            .send((deployment_id.clone(), app.clone()))
-            .await
-        {
-            tracing::error!("Failed to queue deployment: {}", e);
-        }
-
-        tracing::info!("Queued deployment {} for app {}", deployment_id, app.name);
+        queue_or_hold_deployment(&state, deployment_id, &app, hold.as_ref()).await;
     }
 
     update_wh_event(
@@ -455,7 +497,7 @@ async fn handle_github_pull_request(
             }
             // Also post closed comment
             for app in apps {
-                handle_preview_closed_comment(&state, &app, &payload).await?;
+                post_preview_closed_comment(&state, &app, payload.number).await?;
             }
         }
         _ => {
@@ -517,6 +559,7 @@ async fn handle_preview_deploy(
         .encryption_key
         .as_ref()
         .map(|secret| crypto::derive_key(secret));
+    let sandbox = state.config.build_sandbox.clone();
 
     tokio::spawn(async move {
         let deploy_result = run_preview_deployment(
@@ -526,6 +569,7 @@ async fn handle_preview_deploy(
             &preview,
             &app_clone,
             encryption_key.as_ref(),
+            &sandbox,
         )
         .await;
 
@@ -582,41 +626,3 @@ async fn handle_preview_deploy(
 
     Ok(())
 }
-
-async fn handle_preview_closed_comment(
-    state: &Arc<AppState>,
-    app: &App,
-    payload: &GitHubPullRequestEvent,
-) -> Result<(), StatusCode> {
-    let preview: Option<PreviewDeployment> =
-        sqlx::query_as("SELECT * FROM preview_deployments WHERE app_id = ? AND pr_number = ?")
-            .bind(&app.id)
-            .bind(payload.number)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    if let Some(preview) = preview {
-        let db = state.db.clone();
-        let encryption_key = state
-            .config
-            .auth
-            .encryption_key
-            .as_ref()
-            .map(|secret| crypto::derive_key(secret));
-
-        tokio::spawn(async move {
-            if let Err(e) =
-                post_preview_comment(&db, &preview, "closed", encryption_key.as_ref()).await
-            {
-                tracing::warn!(
-                    preview_id = %preview.id,
-                    error = %e,
-                    "Failed to post cleanup PR comment"
-                );
-            }
-        });
-    }
-
-    Ok(())
-}
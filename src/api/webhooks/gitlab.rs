@@ -11,13 +11,16 @@ use uuid::Uuid;
 
 use super::{
     collect_changed_files, handle_generic_preview_cleanup, incr_webhooks,
-    should_deploy_for_changed_files, ChangedFiles,
+    post_preview_closed_comment, prewarm_instead_of_deploy, push_filter_skip_reason,
+    queue_or_hold_deployment, should_deploy_for_changed_files, ChangedFiles,
 };
+use crate::api::deployments::freeze_hold;
 use crate::crypto;
 use crate::db::App;
 use crate::engine::preview::{
-    find_or_create_preview, run_preview_deployment, PreviewDeploymentInfo,
+    comment_preview_status, find_or_create_preview, run_preview_deployment, PreviewDeploymentInfo,
 };
+use crate::engine::prewarm::PrewarmCommit;
 use crate::AppState;
 
 // ---------------------------------------------------------------------------
@@ -28,7 +31,8 @@ use crate::AppState;
 pub struct GitLabPushEvent {
     #[serde(rename = "ref")]
     pub git_ref: String,
-    #[allow(dead_code)]
+    #[serde(default)]
+    pub before: String,
     pub after: String,
     pub project: GitLabProject,
     pub commits: Vec<GitLabCommit>,
@@ -39,6 +43,8 @@ pub struct GitLabProject {
     pub git_http_url: String,
     pub git_ssh_url: String,
     pub path_with_namespace: String,
+    #[serde(default)]
+    pub web_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +52,8 @@ pub struct GitLabCommit {
     pub id: String,
     pub message: String,
     #[serde(default)]
+    pub author: Option<GitLabCommitAuthor>,
+    #[serde(default)]
     pub added: Vec<String>,
     #[serde(default)]
     pub modified: Vec<String>,
@@ -53,6 +61,27 @@ pub struct GitLabCommit {
     pub removed: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GitLabCommitAuthor {
+    pub name: String,
+}
+
+impl GitLabPushEvent {
+    /// Compare view URL for the pushed range (none for new branches)
+    fn compare_url(&self) -> Option<String> {
+        let web_url = self.project.web_url.as_deref()?;
+        if self.before.is_empty() || self.before.chars().all(|c| c == '0') {
+            return None;
+        }
+        Some(format!(
+            "{}/-/compare/{}...{}",
+            web_url.trim_end_matches('/'),
+            self.before,
+            self.after
+        ))
+    }
+}
+
 impl ChangedFiles for &GitLabCommit {
     fn added_files(&self) -> &[String] {
         &self.added
@@ -81,6 +110,9 @@ pub struct GitLabMergeRequestAttributes {
     pub url: String,
     pub action: Option<String>,
     pub last_commit: Option<GitLabLastCommit>,
+    /// Previous head commit; only present on "update" events that pushed commits
+    #[serde(default)]
+    pub oldrev: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -179,8 +211,9 @@ async fn handle_gitlab_push(state: Arc<AppState>, body: &[u8]) -> Result<StatusC
         return Ok(StatusCode::OK);
     }
 
-    let apps = sqlx::query_as::<_, App>(
-        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) AND branch = ?",
+    let mut apps = sqlx::query_as::<_, App>(
+        "SELECT * FROM apps WHERE (git_url LIKE ? OR git_url LIKE ?) \
+         AND (branch = ? OR webhook_branch_regex IS NOT NULL)",
     )
     .bind(format!("%{}", payload.project.git_http_url))
     .bind(format!("%{}", payload.project.git_ssh_url))
@@ -188,37 +221,73 @@ async fn handle_gitlab_push(state: Arc<AppState>, body: &[u8]) -> Result<StatusC
     .fetch_all(&state.db)
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    apps.retain(|app| super::app_tracks_branch(app, branch));
 
     let changed_files = collect_changed_files(payload.commits.iter());
+    let compare_url = payload.compare_url();
+    let push_author = payload
+        .commits
+        .last()
+        .and_then(|c| c.author.as_ref())
+        .map(|a| a.name.as_str());
 
     for app in apps {
         if !should_deploy_for_changed_files(&app, &changed_files) {
             tracing::info!(app = %app.name, "Skipping deployment: no watched files changed");
             continue;
         }
+        if let Some(reason) = push_filter_skip_reason(&app, push_author, &changed_files) {
+            tracing::info!(app = %app.name, "Skipping deployment: {}", reason);
+            continue;
+        }
+
+        if let Some(message) = app.deploy_lock_message() {
+            tracing::warn!(app = %app.name, "Skipping deployment: {}", message);
+            continue;
+        }
+
+        let commit = payload.commits.first();
+        let prewarm = PrewarmCommit {
+            sha: commit.map(|c| c.id.clone()),
+            message: commit.map(|c| c.message.clone()),
+            author: commit
+                .and_then(|c| c.author.as_ref())
+                .map(|a| a.name.clone()),
+        };
+        if prewarm_instead_of_deploy(&state, &app, prewarm).await {
+            continue;
+        }
+        let hold = freeze_hold(&state.db, &app).await;
 
         let deployment_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
-        let commit = payload.commits.first();
 
         sqlx::query(
             r#"
-            INSERT INTO deployments (id, app_id, commit_sha, commit_message, status, started_at)
-            VALUES (?, ?, ?, ?, 'pending', ?)
+            INSERT INTO deployments (id, app_id, commit_sha, commit_message, commit_author,
+                                     commit_branch, commit_compare_url, status, started_at,
+                                     scheduled_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?)
             "#,
         )
         .bind(&deployment_id)
         .bind(&app.id)
         .bind(commit.map(|c| c.id.clone()))
         .bind(commit.map(|c| c.message.clone()))
+        .bind(
+            commit
+                .and_then(|c| c.author.as_ref())
+                .map(|a| a.name.clone()),
+        )
+        .bind(branch)
+        .bind(&compare_url)
         .bind(&now)
+        .bind(hold.as_ref().map(|h| h.release_at.to_rfc3339()))
         .execute(&state.db)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        if let Err(e) = state.deploy_tx.send((deployment_id, app.clone())).await {
-            tracing::error!("Failed to queue deployment: {}", e);
-        }
+        queue_or_hold_deployment(&state, deployment_id, &app, hold.as_ref()).await;
     }
 
     Ok(StatusCode::OK)
@@ -260,14 +329,23 @@ async fn handle_gitlab_merge_request(
     }
 
     match action {
-        "open" | "reopen" | "update" => {
+        "open" | "reopen" => {
+            for app in apps {
+                handle_gitlab_mr_deploy(&state, &app, &payload).await?;
+            }
+        }
+        // Title, label or description edits are "update" events too; only
+        // new commits rebuild the preview
+        "update" if payload.object_attributes.oldrev.is_some() => {
             for app in apps {
                 handle_gitlab_mr_deploy(&state, &app, &payload).await?;
             }
         }
         "close" | "merge" => {
+            let iid = payload.object_attributes.iid;
             for app in apps {
-                handle_generic_preview_cleanup(&state, &app, payload.object_attributes.iid).await?;
+                handle_generic_preview_cleanup(&state, &app, iid).await?;
+                post_preview_closed_comment(&state, &app, iid).await?;
             }
         }
         _ => {
@@ -340,25 +418,161 @@ async fn handle_gitlab_mr_deploy(
         .encryption_key
         .as_ref()
         .map(|secret| crypto::derive_key(secret));
+    let sandbox = state.config.build_sandbox.clone();
 
     tokio::spawn(async move {
-        if let Err(e) = run_preview_deployment(
+        let status = match run_preview_deployment(
             &db,
             runtime,
             routes,
             &preview,
             &app_clone,
             encryption_key.as_ref(),
+            &sandbox,
         )
         .await
         {
-            tracing::error!(
-                preview_id = %preview.id,
-                error = %e,
-                "GitLab MR preview deployment failed"
-            );
-        }
+            Ok(()) => "running",
+            Err(e) => {
+                tracing::error!(
+                    preview_id = %preview.id,
+                    error = %e,
+                    "GitLab MR preview deployment failed"
+                );
+                "failed"
+            }
+        };
+        comment_preview_status(&db, &preview.id, status, encryption_key.as_ref()).await;
     });
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, PreviewDeployment};
+
+    const REPO_URL: &str = "https://gitlab.example.test/acme/web.git";
+
+    async fn preview_app(state: &AppState) -> String {
+        let app_id = test_app(&state.db, "web").await;
+        sqlx::query("UPDATE apps SET git_url = ?, preview_enabled = 1 WHERE id = ?")
+            .bind(REPO_URL)
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        app_id
+    }
+
+    fn mr_event(action: &str, head: &str, oldrev: Option<&str>) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "object_kind": "merge_request",
+            "user": { "username": "jane" },
+            "project": {
+                "git_http_url": REPO_URL,
+                "git_ssh_url": "git@gitlab.example.test:acme/web.git",
+                "path_with_namespace": "acme/web",
+                "web_url": "https://gitlab.example.test/acme/web",
+            },
+            "object_attributes": {
+                "iid": 7,
+                "title": "Add search",
+                "source_branch": "search",
+                "target_branch": "main",
+                "url": "https://gitlab.example.test/acme/web/-/merge_requests/7",
+                "action": action,
+                "last_commit": { "id": head, "message": "Add search" },
+                "oldrev": oldrev,
+            },
+        }))
+        .unwrap()
+    }
+
+    async fn preview(state: &AppState, app_id: &str) -> Option<PreviewDeployment> {
+        sqlx::query_as("SELECT * FROM preview_deployments WHERE app_id = ? AND pr_number = 7")
+            .bind(app_id)
+            .fetch_optional(&state.db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_open_creates_a_preview() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = preview_app(&state).await;
+
+        let status = handle_gitlab_merge_request(state.clone(), &mr_event("open", "aaa111", None))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        let preview = preview(&state, &app_id).await.expect("preview created");
+        assert_eq!(preview.provider_type, "gitlab");
+        assert_eq!(preview.repo_full_name, "acme/web");
+        assert_eq!(preview.commit_sha.as_deref(), Some("aaa111"));
+        assert_eq!(preview.pr_source_branch, "search");
+    }
+
+    #[tokio::test]
+    async fn test_update_rebuilds_only_when_commits_were_pushed() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = preview_app(&state).await;
+        handle_gitlab_merge_request(state.clone(), &mr_event("open", "aaa111", None))
+            .await
+            .unwrap();
+        let opened = preview(&state, &app_id).await.unwrap();
+
+        // A title or label edit: no oldrev, nothing to rebuild
+        handle_gitlab_merge_request(state.clone(), &mr_event("update", "aaa111", None))
+            .await
+            .unwrap();
+        let unchanged = preview(&state, &app_id).await.unwrap();
+        assert_eq!(unchanged.last_activity_at, opened.last_activity_at);
+
+        handle_gitlab_merge_request(state.clone(), &mr_event("update", "bbb222", Some("aaa111")))
+            .await
+            .unwrap();
+        let rebuilt = preview(&state, &app_id).await.unwrap();
+        assert_eq!(rebuilt.id, unchanged.id);
+        assert_eq!(rebuilt.commit_sha.as_deref(), Some("bbb222"));
+        assert_ne!(rebuilt.last_activity_at, opened.last_activity_at);
+    }
+
+    #[tokio::test]
+    async fn test_close_removes_the_preview() {
+        let (_dir, state, _rx) = crate::test_state().await;
+        let app_id = preview_app(&state).await;
+        let app = crate::db::load_test_app(&state.db, &app_id).await;
+        let info = PreviewDeploymentInfo {
+            app_id: app_id.clone(),
+            pr_number: 7,
+            pr_title: None,
+            pr_source_branch: "search".to_string(),
+            pr_target_branch: "main".to_string(),
+            pr_author: None,
+            pr_url: None,
+            commit_sha: Some("aaa111".to_string()),
+            commit_message: None,
+            provider_type: "gitlab".to_string(),
+            repo_full_name: "acme/web".to_string(),
+        };
+        find_or_create_preview(&state.db, &app, &info, "preview.localhost")
+            .await
+            .unwrap();
+
+        handle_gitlab_merge_request(state.clone(), &mr_event("close", "aaa111", None))
+            .await
+            .unwrap();
+
+        // Cleanup runs in the background
+        for _ in 0..50 {
+            if preview(&state, &app_id).await.unwrap().status == "closed" {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("preview was not closed");
+    }
+}
@@ -1,7 +1,8 @@
 //! Webhook handlers for Git providers.
 //!
 //! Each provider has its own submodule. Shared utilities (signature verification,
-//! watch-path filtering, preview cleanup) live here.
+//! watch-path filtering, skip markers, per-app branch/author/path filters,
+//! preview cleanup) live here.
 
 mod bitbucket;
 mod dockerhub;
@@ -23,15 +24,108 @@ pub(super) use crate::api::webhook_events::update_webhook_event as update_wh_eve
 use axum::http::StatusCode;
 use glob::Pattern;
 use hmac::{Hmac, Mac};
+use regex::Regex;
 use sha2::Sha256;
 use std::sync::Arc;
 
-use crate::db::{App, PreviewDeployment};
-use crate::engine::preview::cleanup_preview;
+use crate::api::deployments::{approval_required, ActiveFreeze};
+use crate::crypto;
+use crate::db::{App, AppPrewarmSettings, DeploymentEvent, PreviewDeployment};
+use crate::engine::preview::{cleanup_preview, comment_preview_status};
+use crate::engine::prewarm::{queue_prewarm, PrewarmCommit};
 use crate::AppState;
 
 pub(super) type HmacSha256 = Hmac<Sha256>;
 
+/// For apps that pre-warm on push, build the pushed commit into the app's
+/// pre-warm slot instead of deploying it. Returns true if the push was taken.
+pub(super) async fn prewarm_instead_of_deploy(
+    state: &Arc<AppState>,
+    app: &App,
+    commit: PrewarmCommit,
+) -> bool {
+    if !AppPrewarmSettings::captures_pushes(&state.db, &app.id).await {
+        return false;
+    }
+    match queue_prewarm(&state.db, &state.deploy_tx, app, commit).await {
+        Ok(deployment_id) => tracing::info!(
+            app = %app.name,
+            deployment_id = %deployment_id,
+            "Push queued as a pre-warm build (traffic not switched)"
+        ),
+        Err(e) => tracing::error!(app = %app.name, error = %e, "Failed to queue pre-warm build"),
+    }
+    true
+}
+
+/// Queue a push deployment, unless a freeze window holds it. A held
+/// deployment was stored with the window's end as its `scheduled_at`, so the
+/// scheduled deployment checker queues it once the window closes.
+///
+/// Deployments of apps (or environments) that require approval are parked
+/// until someone approves them with `POST /api/deployments/:id/approve`.
+pub(super) async fn queue_or_hold_deployment(
+    state: &Arc<AppState>,
+    deployment_id: String,
+    app: &App,
+    hold: Option<&ActiveFreeze>,
+) {
+    let needs_approval = approval_required(&state.db, app).await.unwrap_or_else(|e| {
+        // Park the deployment rather than skip a required approval
+        tracing::warn!(app = %app.name, error = %e, "Failed to check approval setting");
+        true
+    });
+    if needs_approval {
+        if let Err(e) =
+            sqlx::query("UPDATE deployments SET approval_status = 'pending' WHERE id = ?")
+                .bind(&deployment_id)
+                .execute(&state.db)
+                .await
+        {
+            tracing::error!(deployment_id = %deployment_id, error = %e, "Failed to park deployment for approval");
+            return;
+        }
+        DeploymentEvent::record(
+            &state.db,
+            &deployment_id,
+            "pending",
+            Some("Awaiting approval"),
+        )
+        .await;
+        tracing::info!(
+            app = %app.name,
+            deployment_id = %deployment_id,
+            "Push deployment requires approval, awaiting approver action"
+        );
+        return;
+    }
+
+    if let Some(freeze) = hold {
+        let message = format!(
+            "Held by freeze window '{}' until {}",
+            freeze.name,
+            freeze.release_at.to_rfc3339()
+        );
+        DeploymentEvent::record(&state.db, &deployment_id, "pending", Some(&message)).await;
+        tracing::info!(
+            app = %app.name,
+            deployment_id = %deployment_id,
+            "{}",
+            message
+        );
+        return;
+    }
+
+    if let Err(e) = state
+        .deploy_tx
+        .send((deployment_id.clone(), app.clone()))
+        .await
+    {
+        tracing::error!("Failed to queue deployment: {}", e);
+    }
+    tracing::info!("Queued deployment {} for app {}", deployment_id, app.name);
+}
+
 /// Verify GitHub/GitLab/Bitbucket webhook signature (sha256=<hex> format)
 pub(super) fn verify_github_signature(
     secret: &str,
@@ -73,13 +167,45 @@ pub(super) fn verify_gitea_signature(secret: &str, signature_header: &str, paylo
     mac.verify_slice(&expected).is_ok()
 }
 
+/// Paths a push must touch for the app to deploy: its watch_paths, or for
+/// an app built from a subdirectory of a monorepo, that directory.
+fn effective_watch_paths(app: &App) -> Vec<String> {
+    let watch_paths = app.get_watch_paths();
+    if !watch_paths.is_empty() {
+        return watch_paths;
+    }
+    let base_directory = app
+        .base_directory
+        .as_deref()
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches("./")
+        .trim_matches('/');
+    if base_directory.is_empty() || base_directory == "." {
+        return Vec::new();
+    }
+    vec![format!("{}/", base_directory)]
+}
+
+/// Whether deploy filtering needs the push's changed files
+pub(super) fn needs_changed_files(app: &App) -> bool {
+    !effective_watch_paths(app).is_empty()
+        || app
+            .webhook_ignore_paths
+            .as_deref()
+            .is_some_and(|p| !p.is_empty())
+}
+
 /// Check if any of the changed files match the app's watch_paths patterns.
 /// Returns true if deployment should proceed:
-///   - If watch_paths is empty/null, always deploy (backward compatible)
-///   - If watch_paths is configured, only deploy if at least one changed file matches
+///   - If watch_paths is empty/null, the app's base_directory is watched, so
+///     apps sharing a monorepo only deploy when their own directory changes
+///   - With neither, always deploy (backward compatible)
+///   - Pushes without file information always deploy
+///   - Otherwise only deploy if at least one changed file matches
 pub(super) fn should_deploy_for_changed_files(app: &App, changed_files: &[String]) -> bool {
-    let watch_paths = app.get_watch_paths();
-    if watch_paths.is_empty() {
+    let watch_paths = effective_watch_paths(app);
+    if watch_paths.is_empty() || changed_files.is_empty() {
         return true;
     }
 
@@ -142,6 +268,52 @@ pub(super) fn commit_skips_deploy(message: Option<&str>) -> bool {
     MARKERS.iter().any(|m| lower.contains(m))
 }
 
+/// Compile one of an app's webhook filters. Filters are validated when saved,
+/// so an invalid one only comes from older data; it is logged and ignored.
+fn webhook_filter(app: &App, pattern: Option<&str>) -> Option<Regex> {
+    let pattern = pattern.filter(|p| !p.is_empty())?;
+    match Regex::new(pattern) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            tracing::warn!(app = %app.name, "Ignoring invalid webhook filter '{}': {}", pattern, e);
+            None
+        }
+    }
+}
+
+/// Whether a push to `branch` concerns the app: its own branch, or one
+/// matching its `webhook_branch_regex`
+pub(super) fn app_tracks_branch(app: &App, branch: &str) -> bool {
+    app.branch == branch
+        || webhook_filter(app, app.webhook_branch_regex.as_deref())
+            .is_some_and(|re| re.is_match(branch))
+}
+
+/// Why a push to a tracked branch should not deploy the app, if it shouldn't:
+/// the commit author matches `webhook_ignore_authors`, or every changed file
+/// matches `webhook_ignore_paths`. Pushes without file information are never
+/// skipped for their paths.
+pub(super) fn push_filter_skip_reason(
+    app: &App,
+    author: Option<&str>,
+    changed_files: &[String],
+) -> Option<&'static str> {
+    if let (Some(author), Some(re)) = (
+        author,
+        webhook_filter(app, app.webhook_ignore_authors.as_deref()),
+    ) {
+        if re.is_match(author) {
+            return Some("commit author matches the ignored authors filter");
+        }
+    }
+    if let Some(re) = webhook_filter(app, app.webhook_ignore_paths.as_deref()) {
+        if !changed_files.is_empty() && changed_files.iter().all(|f| re.is_match(f)) {
+            return Some("only files matching the ignored paths filter changed");
+        }
+    }
+    None
+}
+
 /// Collect all changed files from a list of commits with added/modified/removed arrays
 pub(super) fn collect_changed_files(
     commits: impl IntoIterator<Item = impl ChangedFiles>,
@@ -164,34 +336,6 @@ pub(super) trait ChangedFiles {
     fn removed_files(&self) -> &[String];
 }
 
-#[cfg(test)]
-mod tests {
-    use super::commit_skips_deploy;
-
-    #[test]
-    fn skip_markers_are_detected_case_insensitively() {
-        for msg in [
-            "fix typo [skip ci]",
-            "WIP [CI SKIP]",
-            "docs only [skip cd]",
-            "release [CD Skip]",
-            "chore [skip deploy]",
-            "wip [no deploy] more text",
-        ] {
-            assert!(commit_skips_deploy(Some(msg)), "should skip: {msg}");
-        }
-    }
-
-    #[test]
-    fn normal_messages_do_not_skip() {
-        assert!(!commit_skips_deploy(Some("add feature")));
-        assert!(!commit_skips_deploy(Some(
-            "mention skip ci without brackets"
-        )));
-        assert!(!commit_skips_deploy(None));
-    }
-}
-
 /// Generic preview cleanup shared between GitLab and Gitea handlers
 pub(super) async fn handle_generic_preview_cleanup(
     state: &Arc<AppState>,
@@ -237,3 +381,237 @@ pub(super) async fn handle_generic_preview_cleanup(
 
     Ok(())
 }
+
+/// Tell the pull or merge request that its preview was removed
+pub(super) async fn post_preview_closed_comment(
+    state: &Arc<AppState>,
+    app: &App,
+    pr_number: i64,
+) -> Result<(), StatusCode> {
+    let preview_id: Option<String> =
+        sqlx::query_scalar("SELECT id FROM preview_deployments WHERE app_id = ? AND pr_number = ?")
+            .bind(&app.id)
+            .bind(pr_number)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if let Some(preview_id) = preview_id {
+        let db = state.db.clone();
+        let encryption_key = state
+            .config
+            .auth
+            .encryption_key
+            .as_ref()
+            .map(|secret| crypto::derive_key(secret));
+
+        tokio::spawn(async move {
+            comment_preview_status(&db, &preview_id, "closed", encryption_key.as_ref()).await;
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        app_tracks_branch, commit_skips_deploy, push_filter_skip_reason, queue_or_hold_deployment,
+        should_deploy_for_changed_files,
+    };
+    use crate::api::deployments::ActiveFreeze;
+    use crate::db::{load_test_app, test_app, test_deployment, App};
+
+    fn app_with_filters(branch_regex: &str, ignore_authors: &str, ignore_paths: &str) -> App {
+        serde_json::from_value(serde_json::json!({
+            "id": "app-1",
+            "name": "web",
+            "git_url": "https://github.com/acme/web",
+            "branch": "main",
+            "dockerfile": "Dockerfile",
+            "port": 3000,
+            "environment": "production",
+            "basic_auth_enabled": 0,
+            "webhook_branch_regex": branch_regex,
+            "webhook_ignore_authors": ignore_authors,
+            "webhook_ignore_paths": ignore_paths,
+            "created_at": "",
+            "updated_at": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn skip_markers_are_detected_case_insensitively() {
+        for msg in [
+            "fix typo [skip ci]",
+            "WIP [CI SKIP]",
+            "docs only [skip cd]",
+            "release [CD Skip]",
+            "chore [skip deploy]",
+            "wip [no deploy] more text",
+        ] {
+            assert!(commit_skips_deploy(Some(msg)), "should skip: {msg}");
+        }
+    }
+
+    #[test]
+    fn normal_messages_do_not_skip() {
+        assert!(!commit_skips_deploy(Some("add feature")));
+        assert!(!commit_skips_deploy(Some(
+            "mention skip ci without brackets"
+        )));
+        assert!(!commit_skips_deploy(None));
+    }
+
+    #[test]
+    fn branch_filter_extends_the_app_branch() {
+        let app = app_with_filters("^release/.+$", "", "");
+        assert!(app_tracks_branch(&app, "main"));
+        assert!(app_tracks_branch(&app, "release/1.2"));
+        assert!(!app_tracks_branch(&app, "feature/x"));
+
+        let app = app_with_filters("", "", "");
+        assert!(app_tracks_branch(&app, "main"));
+        assert!(!app_tracks_branch(&app, "release/1.2"));
+    }
+
+    #[test]
+    fn ignored_authors_and_paths_skip_the_push() {
+        let app = app_with_filters("", r"\[bot\]$", r"^(docs/|.*\.md$)");
+        let docs = vec!["README.md".to_string(), "docs/setup.md".to_string()];
+        let mixed = vec!["docs/setup.md".to_string(), "src/main.rs".to_string()];
+
+        assert!(push_filter_skip_reason(&app, Some("dependabot[bot]"), &mixed).is_some());
+        assert!(push_filter_skip_reason(&app, Some("Jane Doe"), &docs).is_some());
+        assert!(push_filter_skip_reason(&app, Some("Jane Doe"), &mixed).is_none());
+        // Without file information the path filter can't apply
+        assert!(push_filter_skip_reason(&app, None, &[]).is_none());
+    }
+
+    #[test]
+    fn locked_apps_report_holder_and_reason() {
+        let mut app = app_with_filters("", "", "");
+        assert_eq!(app.deploy_lock_message(), None);
+
+        app.locked_at = Some("2025-01-02T10:00:00+00:00".to_string());
+        app.locked_by = Some("Jane Doe".to_string());
+        assert_eq!(
+            app.deploy_lock_message().as_deref(),
+            Some("App is locked by Jane Doe since 2025-01-02T10:00:00+00:00")
+        );
+
+        app.lock_reason = Some("database migration".to_string());
+        assert_eq!(
+            app.deploy_lock_message().as_deref(),
+            Some("App is locked by Jane Doe since 2025-01-02T10:00:00+00:00: database migration")
+        );
+    }
+
+    fn monorepo_app(base_directory: Option<&str>, watch_paths: Option<&str>) -> App {
+        let mut app = app_with_filters("", "", "");
+        app.base_directory = base_directory.map(String::from);
+        app.watch_paths = watch_paths.map(String::from);
+        app
+    }
+
+    #[test]
+    fn watch_paths_select_apps_whose_files_changed() {
+        let app = monorepo_app(None, Some(r#"["services/api/", "*.lock"]"#));
+        assert!(should_deploy_for_changed_files(
+            &app,
+            &["services/api/src/main.rs".to_string()]
+        ));
+        assert!(should_deploy_for_changed_files(
+            &app,
+            &["Cargo.lock".to_string()]
+        ));
+        assert!(!should_deploy_for_changed_files(
+            &app,
+            &["services/web/index.ts".to_string()]
+        ));
+    }
+
+    #[test]
+    fn base_directory_is_watched_by_default() {
+        let api = monorepo_app(Some("./services/api/"), None);
+        let web = monorepo_app(Some("services/web"), None);
+        let changed = vec!["services/web/src/app.tsx".to_string()];
+        assert!(!should_deploy_for_changed_files(&api, &changed));
+        assert!(should_deploy_for_changed_files(&web, &changed));
+
+        // Explicit watch paths take precedence over the base directory
+        let api = monorepo_app(Some("services/api"), Some(r#"["shared/"]"#));
+        assert!(should_deploy_for_changed_files(
+            &api,
+            &["shared/types.ts".to_string()]
+        ));
+
+        // Apps at the repository root deploy on every push
+        let root = monorepo_app(Some("."), None);
+        assert!(should_deploy_for_changed_files(&root, &changed));
+    }
+
+    #[test]
+    fn pushes_without_file_information_always_deploy() {
+        let app = monorepo_app(Some("services/api"), Some(r#"["services/api/"]"#));
+        assert!(should_deploy_for_changed_files(&app, &[]));
+    }
+
+    async fn approval_status(state: &crate::AppState, deployment_id: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT approval_status FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pushes_are_queued_when_nothing_holds_them() {
+        let (_dir, state, mut deploy_rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        let deployment_id = test_deployment(&state.db, &app_id, "pending").await;
+        let app = load_test_app(&state.db, &app_id).await;
+
+        queue_or_hold_deployment(&state, deployment_id.clone(), &app, None).await;
+        let (queued, _) = deploy_rx.try_recv().expect("deployment queued");
+        assert_eq!(queued, deployment_id);
+    }
+
+    #[tokio::test]
+    async fn pushes_wait_for_approval_when_required() {
+        let (_dir, state, mut deploy_rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        sqlx::query("UPDATE apps SET require_approval = 1 WHERE id = ?")
+            .bind(&app_id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let deployment_id = test_deployment(&state.db, &app_id, "pending").await;
+        let app = load_test_app(&state.db, &app_id).await;
+
+        queue_or_hold_deployment(&state, deployment_id.clone(), &app, None).await;
+        assert!(deploy_rx.try_recv().is_err());
+        assert_eq!(
+            approval_status(&state, &deployment_id).await.as_deref(),
+            Some("pending")
+        );
+    }
+
+    #[tokio::test]
+    async fn pushes_are_held_during_a_freeze() {
+        let (_dir, state, mut deploy_rx) = crate::test_state().await;
+        let app_id = test_app(&state.db, "web").await;
+        let deployment_id = test_deployment(&state.db, &app_id, "pending").await;
+        let app = load_test_app(&state.db, &app_id).await;
+        let freeze = ActiveFreeze {
+            name: "release".to_string(),
+            release_at: chrono::Utc::now() + chrono::Duration::hours(1),
+            span: "22:00 - 06:00 UTC".to_string(),
+        };
+
+        queue_or_hold_deployment(&state, deployment_id.clone(), &app, Some(&freeze)).await;
+        assert!(deploy_rx.try_recv().is_err());
+        assert_eq!(approval_status(&state, &deployment_id).await, None);
+    }
+}
@@ -0,0 +1,285 @@
+//! ACME account and certificate export/import.
+//!
+//! Packs the ACME account credentials (`account*.json`) and issued certificates
+//! (`certs/<domain>/…`) from `proxy.acme_cache_dir` into a tar.gz bundle so an
+//! instance can be moved to a new server without re-registering an account or
+//! re-issuing certificates (and hitting Let's Encrypt rate limits).
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs;
+use std::io::Read as IoRead;
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder, EntryType};
+use tracing::info;
+
+/// Files that may appear inside a `certs/<domain>/` directory of a bundle.
+const CERT_FILES: &[&str] = &["fullchain.pem", "privkey.pem", "domains.json"];
+
+/// Upper bound on the size of a single bundle entry (certs and keys are tiny).
+const MAX_ENTRY_BYTES: u64 = 1024 * 1024;
+
+/// Summary of an ACME bundle import
+#[derive(Debug, Serialize)]
+pub struct AcmeImportResult {
+    /// Account credential files written (e.g. `account.json`)
+    pub accounts: Vec<String>,
+    /// Domains whose certificate material was written
+    pub certificates: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Build a tar.gz bundle of the ACME account keys and certificates.
+///
+/// Fails if the cache directory holds no account credentials, since a bundle
+/// without an account key is useless for migration.
+pub fn export_acme_bundle(acme_cache_dir: &Path) -> Result<Vec<u8>> {
+    let mut accounts = Vec::new();
+    if acme_cache_dir.is_dir() {
+        for entry in fs::read_dir(acme_cache_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_file() && is_account_file(&name) {
+                accounts.push(name);
+            }
+        }
+    }
+    if accounts.is_empty() {
+        anyhow::bail!(
+            "No ACME account found in {}. Has a certificate ever been issued?",
+            acme_cache_dir.display()
+        );
+    }
+    accounts.sort();
+
+    let encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let mut archive = Builder::new(encoder);
+
+    for name in &accounts {
+        archive
+            .append_path_with_name(acme_cache_dir.join(name), name)
+            .with_context(|| format!("Failed to add {} to bundle", name))?;
+    }
+
+    let certs_dir = acme_cache_dir.join("certs");
+    if certs_dir.is_dir() {
+        for entry in fs::read_dir(&certs_dir)? {
+            let entry = entry?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let domain = entry.file_name().to_string_lossy().to_string();
+            for file in CERT_FILES {
+                let path = entry.path().join(file);
+                if path.is_file() {
+                    archive
+                        .append_path_with_name(&path, Path::new("certs").join(&domain).join(file))
+                        .with_context(|| format!("Failed to add {} to bundle", path.display()))?;
+                }
+            }
+        }
+    }
+
+    let encoder = archive.into_inner().context("Failed to finalize bundle")?;
+    encoder.finish().context("Failed to compress bundle")
+}
+
+/// Import a bundle produced by [`export_acme_bundle`] into the cache directory.
+///
+/// Only account files and known certificate files are accepted; any other entry
+/// rejects the whole bundle before anything is written. Existing files with the
+/// same name are overwritten, other certificates are left untouched.
+pub fn import_acme_bundle(bundle: &[u8], acme_cache_dir: &Path) -> Result<AcmeImportResult> {
+    let mut files: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    let mut archive = Archive::new(GzDecoder::new(bundle));
+
+    for entry in archive.entries().context("Failed to read ACME bundle")? {
+        let mut entry = entry.context("Failed to read ACME bundle entry")?;
+        match entry.header().entry_type() {
+            EntryType::Regular => {}
+            EntryType::Directory => continue,
+            other => anyhow::bail!("Unsupported entry type in ACME bundle: {:?}", other),
+        }
+
+        let path = entry
+            .path()
+            .context("Invalid path in ACME bundle")?
+            .to_path_buf();
+        let relative = validate_bundle_path(&path)?;
+
+        if entry.size() > MAX_ENTRY_BYTES {
+            anyhow::bail!("ACME bundle entry too large: {}", path.display());
+        }
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        files.push((relative, data));
+    }
+
+    let mut result = AcmeImportResult {
+        accounts: Vec::new(),
+        certificates: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for (path, data) in &files {
+        if path.components().count() == 1 {
+            serde_json::from_slice::<serde_json::Value>(data)
+                .with_context(|| format!("{} is not valid JSON", path.display()))?;
+            result.accounts.push(path.display().to_string());
+        }
+    }
+    if result.accounts.is_empty() {
+        anyhow::bail!("ACME bundle does not contain an account file");
+    }
+
+    for (path, data) in &files {
+        let target = acme_cache_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&target, data)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        restrict_permissions(&target);
+
+        if let Some(domain) = cert_domain(path) {
+            if !result.certificates.contains(&domain) {
+                result.certificates.push(domain);
+            }
+        }
+    }
+
+    for domain in &result.certificates {
+        let cert_dir = acme_cache_dir.join("certs").join(domain);
+        if !cert_dir.join("fullchain.pem").exists() || !cert_dir.join("privkey.pem").exists() {
+            result.warnings.push(format!(
+                "Certificate for {} is incomplete and will be re-issued",
+                domain
+            ));
+        }
+    }
+    result.certificates.sort();
+    result
+        .warnings
+        .push("Restart Rivetr to load the imported ACME account".to_string());
+
+    info!(
+        accounts = result.accounts.len(),
+        certificates = result.certificates.len(),
+        "Imported ACME bundle into {}",
+        acme_cache_dir.display()
+    );
+    Ok(result)
+}
+
+/// `account.json` or `account-<provider id>.json`
+fn is_account_file(name: &str) -> bool {
+    name == "account.json" || (name.starts_with("account-") && name.ends_with(".json"))
+}
+
+/// Check that a bundle entry is an account file or a known certificate file and
+/// return it as a relative path safe to join onto the cache directory.
+fn validate_bundle_path(path: &Path) -> Result<PathBuf> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => anyhow::bail!("Unsafe path in ACME bundle: {}", path.display()),
+        }
+    }
+
+    let valid = match parts.as_slice() {
+        [name] => is_account_file(name),
+        [certs, domain, file] => {
+            certs == "certs"
+                && !domain.starts_with('.')
+                && domain
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*'))
+                && CERT_FILES.contains(&file.as_str())
+        }
+        _ => false,
+    };
+    if !valid {
+        anyhow::bail!("Unexpected file in ACME bundle: {}", path.display());
+    }
+    Ok(parts.iter().collect())
+}
+
+/// Domain name for a `certs/<domain>/<file>` path
+fn cert_domain(path: &Path) -> Option<String> {
+    let mut components = path.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(certs)), Some(Component::Normal(domain))) if certs == "certs" => {
+            Some(domain.to_string_lossy().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Account keys and private keys should only be readable by the Rivetr user.
+fn restrict_permissions(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed_cache(dir: &Path) {
+        fs::write(dir.join("account.json"), r#"{"kid":"https://acme/acct/1"}"#).unwrap();
+        fs::write(dir.join("notes.txt"), "not exported").unwrap();
+        let cert_dir = dir.join("certs").join("example.com");
+        fs::create_dir_all(&cert_dir).unwrap();
+        fs::write(cert_dir.join("fullchain.pem"), "CERT").unwrap();
+        fs::write(cert_dir.join("privkey.pem"), "KEY").unwrap();
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let source = tempfile::tempdir().unwrap();
+        seed_cache(source.path());
+        let bundle = export_acme_bundle(source.path()).unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let result = import_acme_bundle(&bundle, target.path()).unwrap();
+
+        assert_eq!(result.accounts, vec!["account.json"]);
+        assert_eq!(result.certificates, vec!["example.com"]);
+        assert_eq!(
+            fs::read_to_string(target.path().join("certs/example.com/privkey.pem")).unwrap(),
+            "KEY"
+        );
+        assert!(!target.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_export_requires_account() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(export_acme_bundle(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_validate_bundle_path() {
+        assert!(validate_bundle_path(Path::new("account.json")).is_ok());
+        assert!(validate_bundle_path(Path::new("account-zerossl.json")).is_ok());
+        assert!(validate_bundle_path(Path::new("certs/example.com/fullchain.pem")).is_ok());
+        assert!(validate_bundle_path(Path::new("../account.json")).is_err());
+        assert!(validate_bundle_path(Path::new("/etc/passwd")).is_err());
+        assert!(validate_bundle_path(Path::new("certs/../x/privkey.pem")).is_err());
+        assert!(validate_bundle_path(Path::new("certs/example.com/evil.sh")).is_err());
+        assert!(validate_bundle_path(Path::new("rivetr.db")).is_err());
+    }
+}
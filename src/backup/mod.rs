@@ -3,10 +3,11 @@
 //! Provides functionality to backup and restore the entire Rivetr instance:
 //! - SQLite database (with WAL checkpoint)
 //! - Configuration file (rivetr.toml)
-//! - SSL/ACME certificates
+//! - SSL/ACME certificates (also exportable on their own, see [`acme`])
 //! - S3 remote backup integration
 //! - Service container database dumps (postgres, mysql, redis, mongo)
 
+pub mod acme;
 pub mod s3;
 
 use anyhow::{Context, Result};
@@ -17,12 +18,14 @@ use flate2::Compression;
 use serde::Serialize;
 use sqlx::SqlitePool;
 use std::fs;
-use std::io::{Read as IoRead, Write as IoWrite};
+use std::io::Write as IoWrite;
 use std::path::{Path, PathBuf};
 use tar::{Archive, Builder};
 use tokio::process::Command as TokioCommand;
 use tracing::{info, warn};
 
+use crate::storage::Storage;
+
 /// Information about a backup file
 #[derive(Debug, Clone, Serialize)]
 pub struct BackupInfo {
@@ -156,46 +159,85 @@ pub async fn create_backup(
     Ok(result)
 }
 
-/// List all backups in the data/backups/ directory
-pub fn list_backups(data_dir: &Path) -> Result<Vec<BackupInfo>> {
-    let backups_dir = data_dir.join("backups");
-    if !backups_dir.exists() {
-        return Ok(Vec::new());
+/// An instance backup written to storage
+#[derive(Debug, Clone)]
+pub struct StoredBackup {
+    /// Filename of the backup
+    pub name: String,
+    /// Size in bytes
+    pub size: u64,
+    /// The archive itself, for callers that also return or upload it
+    pub data: Vec<u8>,
+}
+
+/// Storage key of the backup named `name`, rejecting anything that isn't a
+/// backup filename
+pub fn backup_key(name: &str) -> Result<String> {
+    // Validate the backup name to prevent path traversal
+    if name.contains('/') || name.contains('\\') || name.contains("..") {
+        anyhow::bail!("Invalid backup name");
     }
 
-    let mut backups = Vec::new();
+    if !name.starts_with("rivetr-backup-") || !name.ends_with(".tar.gz") {
+        anyhow::bail!("Invalid backup name format");
+    }
 
-    for entry in fs::read_dir(&backups_dir).context("Failed to read backups directory")? {
-        let entry = entry?;
-        let path = entry.path();
+    Ok(format!("backups/{}", name))
+}
 
-        if path.is_file() {
-            let name = path
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
+/// Create a backup (see [`create_backup`]) and store it under `backups/` in
+/// the configured storage. The archive is staged in `data_dir` first.
+pub async fn create_stored_backup(
+    db: &SqlitePool,
+    storage: &dyn Storage,
+    data_dir: &Path,
+    config_path: &Path,
+    acme_cache_dir: &Path,
+) -> Result<StoredBackup> {
+    fs::create_dir_all(data_dir).context("Failed to create data directory")?;
+    let staging = tempfile::Builder::new()
+        .prefix(".rivetr-backup-")
+        .suffix(".tar.gz")
+        .tempfile_in(data_dir)
+        .context("Failed to create backup staging file")?
+        .into_temp_path();
+
+    let result = create_backup(db, data_dir, config_path, acme_cache_dir, Some(&*staging)).await?;
+    let data = tokio::fs::read(&staging)
+        .await
+        .context("Failed to read backup file")?;
+    storage
+        .put_file(&backup_key(&result.name)?, &staging)
+        .await
+        .context("Failed to store backup")?;
+    info!("Stored backup {} ({} storage)", result.name, storage.name());
+
+    Ok(StoredBackup {
+        name: result.name,
+        size: result.size,
+        data,
+    })
+}
 
-            // Only include .tar.gz files that start with "rivetr-backup-"
-            if name.starts_with("rivetr-backup-") && name.ends_with(".tar.gz") {
-                let metadata = fs::metadata(&path)?;
-                let created_at = metadata
-                    .created()
-                    .or_else(|_| metadata.modified())
-                    .map(|t| {
-                        let datetime: chrono::DateTime<Utc> = t.into();
-                        datetime.to_rfc3339()
-                    })
-                    .unwrap_or_else(|_| "unknown".to_string());
-
-                backups.push(BackupInfo {
-                    name,
-                    size: metadata.len(),
-                    created_at,
-                });
-            }
-        }
-    }
+/// List all backups under `backups/` in storage
+pub async fn list_backups(storage: &dyn Storage) -> Result<Vec<BackupInfo>> {
+    let mut backups: Vec<BackupInfo> = storage
+        .list("backups")
+        .await
+        .context("Failed to list backups")?
+        .into_iter()
+        // Only include .tar.gz files that start with "rivetr-backup-"
+        .filter(|obj| obj.name().starts_with("rivetr-backup-") && obj.name().ends_with(".tar.gz"))
+        .map(|obj| BackupInfo {
+            name: obj.name().to_string(),
+            size: obj.size,
+            created_at: obj
+                .modified
+                .or_else(|| backup_timestamp(obj.name()))
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| "unknown".to_string()),
+        })
+        .collect();
 
     // Sort by name descending (newest first, since names contain timestamps)
     backups.sort_by(|a, b| b.name.cmp(&a.name));
@@ -203,23 +245,22 @@ pub fn list_backups(data_dir: &Path) -> Result<Vec<BackupInfo>> {
     Ok(backups)
 }
 
-/// Delete a specific backup file
-pub fn delete_backup(data_dir: &Path, name: &str) -> Result<()> {
-    // Validate the backup name to prevent path traversal
-    if name.contains('/') || name.contains('\\') || name.contains("..") {
-        anyhow::bail!("Invalid backup name");
-    }
-
-    if !name.starts_with("rivetr-backup-") || !name.ends_with(".tar.gz") {
-        anyhow::bail!("Invalid backup name format");
-    }
+/// Creation time encoded in a backup filename, for backends that don't
+/// report modification times
+fn backup_timestamp(name: &str) -> Option<chrono::DateTime<Utc>> {
+    let stamp = name
+        .strip_prefix("rivetr-backup-")?
+        .strip_suffix(".tar.gz")?;
+    chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
 
-    let backup_path = data_dir.join("backups").join(name);
-    if !backup_path.exists() {
+/// Delete a specific backup
+pub async fn delete_backup(storage: &dyn Storage, name: &str) -> Result<()> {
+    if !storage.delete(&backup_key(name)?).await? {
         anyhow::bail!("Backup not found: {}", name);
     }
-
-    fs::remove_file(&backup_path).context("Failed to delete backup file")?;
     info!("Deleted backup: {}", name);
     Ok(())
 }
@@ -706,7 +747,7 @@ fn add_directory_to_archive<W: IoWrite>(
 }
 
 /// Recursively copy a directory
-fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
+pub(crate) fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
     for entry in fs::read_dir(src)? {
         let entry = entry?;
@@ -722,24 +763,10 @@ fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Read a backup file from the backups directory (for download)
-pub fn read_backup_file(data_dir: &Path, name: &str) -> Result<Vec<u8>> {
-    // Validate the backup name to prevent path traversal
-    if name.contains('/') || name.contains('\\') || name.contains("..") {
-        anyhow::bail!("Invalid backup name");
-    }
-
-    if !name.starts_with("rivetr-backup-") || !name.ends_with(".tar.gz") {
-        anyhow::bail!("Invalid backup name format");
-    }
-
-    let backup_path = data_dir.join("backups").join(name);
-    if !backup_path.exists() {
-        anyhow::bail!("Backup not found: {}", name);
-    }
-
-    let mut file = fs::File::open(&backup_path)?;
-    let mut data = Vec::new();
-    file.read_to_end(&mut data)?;
-    Ok(data)
+/// Read a backup from storage (for download)
+pub async fn read_backup_file(storage: &dyn Storage, name: &str) -> Result<Vec<u8>> {
+    storage
+        .get(&backup_key(name)?)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Backup not found: {}", name))
 }
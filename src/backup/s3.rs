@@ -132,6 +132,51 @@ impl S3Client {
         Ok(data)
     }
 
+    /// Download data from S3, or `None` if there is no object under `key`.
+    pub async fn try_download(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let full_key = self.full_key(key);
+
+        let output = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&full_key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => return Ok(None),
+            Err(e) => return Err(e).context(format!("Failed to download from S3: {}", full_key)),
+        };
+
+        let data = output
+            .body
+            .collect()
+            .await
+            .context("Failed to read S3 object body")?
+            .into_bytes()
+            .to_vec();
+        Ok(Some(data))
+    }
+
+    /// Whether there is an object under `key`.
+    pub async fn exists(&self, key: &str) -> Result<bool> {
+        let full_key = self.full_key(key);
+
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&full_key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) => Err(e).context(format!("Failed to stat S3 object: {}", full_key)),
+        }
+    }
+
     /// List objects in S3 under a prefix.
     pub async fn list_backups(&self, prefix: &str) -> Result<Vec<S3Object>> {
         let full_prefix = self.full_key(prefix);
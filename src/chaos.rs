@@ -0,0 +1,221 @@
+//! Fault injection for resilience testing.
+//!
+//! When `[chaos] enabled = true`, an admin can switch on faults through
+//! `PUT /api/system/chaos`: proxied requests fail with a 502 or are delayed,
+//! and container runtime calls fail, each with a configured probability. This
+//! exercises alerting, auto-restart and rollback paths before a real incident
+//! does. Faults are held in memory only, so a restart always clears them, and
+//! they can be given an expiry so a forgotten experiment ends on its own.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest delay a slow response can be given
+pub const MAX_SLOW_RESPONSE_MS: u64 = 60_000;
+
+/// Faults to inject; every rate is a probability between 0 and 1
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaultSettings {
+    /// Proxied requests answered with a 502 instead of reaching the backend
+    #[serde(default)]
+    pub backend_error_rate: f64,
+    /// Proxied requests held for `slow_response_ms` before being forwarded
+    #[serde(default)]
+    pub slow_response_rate: f64,
+    #[serde(default)]
+    pub slow_response_ms: u64,
+    /// Container runtime calls (build, run, stop, inspect, ...) that fail
+    #[serde(default)]
+    pub runtime_error_rate: f64,
+    /// Domains proxy faults apply to; empty means every domain
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// When the faults switch themselves off
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl FaultSettings {
+    /// Check the settings are usable, returning the offending field
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        for (field, rate) in [
+            ("backend_error_rate", self.backend_error_rate),
+            ("slow_response_rate", self.slow_response_rate),
+            ("runtime_error_rate", self.runtime_error_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err((field, format!("{} must be between 0 and 1", field)));
+            }
+        }
+        if self.slow_response_ms > MAX_SLOW_RESPONSE_MS {
+            return Err((
+                "slow_response_ms",
+                format!(
+                    "slow_response_ms must be at most {} ms",
+                    MAX_SLOW_RESPONSE_MS
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn applies_to(&self, domain: Option<&str>) -> bool {
+        if self.domains.is_empty() {
+            return true;
+        }
+        let Some(domain) = domain else {
+            return false;
+        };
+        let host = domain.split(':').next().unwrap_or(domain);
+        self.domains.iter().any(|d| d.eq_ignore_ascii_case(host))
+    }
+}
+
+/// A fault to apply to a proxied request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyFault {
+    /// Answer with a 502 without contacting the backend
+    Error,
+    /// Forward the request after this delay
+    Delay(Duration),
+}
+
+/// Shared switchboard for the active faults
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    faults: RwLock<Option<FaultSettings>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The active faults, if any (expired ones are cleared)
+    pub fn current(&self) -> Option<FaultSettings> {
+        let faults = self.faults.read().clone()?;
+        if faults.expires_at.is_some_and(|at| at <= Utc::now()) {
+            self.clear();
+            return None;
+        }
+        Some(faults)
+    }
+
+    /// Replace the active faults
+    pub fn set(&self, faults: FaultSettings) {
+        tracing::warn!(?faults, "Fault injection enabled");
+        *self.faults.write() = Some(faults);
+    }
+
+    /// Stop injecting faults
+    pub fn clear(&self) {
+        if self.faults.write().take().is_some() {
+            tracing::warn!("Fault injection disabled");
+        }
+    }
+
+    /// Fault to apply to a request for `domain`, if one is drawn
+    pub fn proxy_fault(&self, domain: Option<&str>) -> Option<ProxyFault> {
+        let faults = self.current()?;
+        if !faults.applies_to(domain) {
+            return None;
+        }
+        if roll(faults.backend_error_rate) {
+            return Some(ProxyFault::Error);
+        }
+        if faults.slow_response_ms > 0 && roll(faults.slow_response_rate) {
+            return Some(ProxyFault::Delay(Duration::from_millis(
+                faults.slow_response_ms,
+            )));
+        }
+        None
+    }
+
+    /// Fail a runtime call to `operation` if a fault is drawn
+    pub fn runtime_fault(&self, operation: &str) -> anyhow::Result<()> {
+        match self.current() {
+            Some(faults) if roll(faults.runtime_error_rate) => {
+                anyhow::bail!("Injected fault: container runtime {} failed", operation)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// True with probability `rate`
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && (rate >= 1.0 || rand::random::<f64>() < rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certain_faults_always_fire() {
+        let injector = FaultInjector::new();
+        assert_eq!(injector.proxy_fault(Some("app.example.com")), None);
+        assert!(injector.runtime_fault("run").is_ok());
+
+        injector.set(FaultSettings {
+            backend_error_rate: 1.0,
+            runtime_error_rate: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(
+            injector.proxy_fault(Some("app.example.com")),
+            Some(ProxyFault::Error)
+        );
+        assert!(injector.runtime_fault("run").is_err());
+
+        injector.set(FaultSettings {
+            slow_response_rate: 1.0,
+            slow_response_ms: 250,
+            ..Default::default()
+        });
+        assert_eq!(
+            injector.proxy_fault(None),
+            Some(ProxyFault::Delay(Duration::from_millis(250)))
+        );
+    }
+
+    #[test]
+    fn test_faults_are_limited_to_their_domains() {
+        let injector = FaultInjector::new();
+        injector.set(FaultSettings {
+            backend_error_rate: 1.0,
+            domains: vec!["app.example.com".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(
+            injector.proxy_fault(Some("APP.example.com:8080")),
+            Some(ProxyFault::Error)
+        );
+        assert_eq!(injector.proxy_fault(Some("other.example.com")), None);
+        assert_eq!(injector.proxy_fault(None), None);
+    }
+
+    #[test]
+    fn test_expired_faults_are_cleared() {
+        let injector = FaultInjector::new();
+        injector.set(FaultSettings {
+            backend_error_rate: 1.0,
+            expires_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+            ..Default::default()
+        });
+        assert_eq!(injector.proxy_fault(Some("app.example.com")), None);
+        assert!(injector.current().is_none());
+    }
+
+    #[test]
+    fn test_rates_must_be_probabilities() {
+        let settings = FaultSettings {
+            runtime_error_rate: 1.5,
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().0, "runtime_error_rate");
+        assert!(FaultSettings::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+//! Api subcommand handler for the Rivetr CLI.
+//!
+//! Handles:
+//! - `api <method> <path> [--data <json>|@file]` — Call any API endpoint with
+//!   the configured token and print the response (the form of the API
+//!   explorer's CLI snippets)
+
+use anyhow::{Context, Result};
+use reqwest::Method;
+
+use super::Cli;
+
+/// Full URL for an endpoint path; the `/api` prefix is optional
+fn endpoint_url(base_url: &str, path: &str) -> String {
+    let path = path.trim_start_matches('/');
+    let path = if path == "api" || path.starts_with("api/") {
+        path.to_string()
+    } else {
+        format!("api/{}", path)
+    };
+    format!("{}/{}", base_url.trim_end_matches('/'), path)
+}
+
+/// Call an API endpoint and print its response
+pub async fn cmd_api(cli: &Cli, method: &str, path: &str, data: Option<&str>) -> Result<()> {
+    let client = super::create_client(cli.token.as_deref())?;
+    let method = Method::from_bytes(method.to_uppercase().as_bytes())
+        .with_context(|| format!("Invalid HTTP method: {}", method))?;
+
+    let body = match data {
+        Some(file) if file.starts_with('@') => Some(
+            std::fs::read_to_string(&file[1..])
+                .with_context(|| format!("Failed to read request body from {}", &file[1..]))?,
+        ),
+        Some(json) => Some(json.to_string()),
+        None => None,
+    };
+    if let Some(body) = &body {
+        serde_json::from_str::<serde_json::Value>(body)
+            .context("Request body is not valid JSON")?;
+    }
+
+    let mut request = client.request(method, endpoint_url(&cli.api_url, path));
+    if let Some(body) = body {
+        request = request
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+    }
+    let response = request.send().await.context("Failed to send request")?;
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    // Pretty-print JSON responses; print anything else as is
+    let output = serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or(text);
+
+    if !status.is_success() {
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "Authentication required. Use --token or set RIVETR_TOKEN environment variable."
+            );
+        }
+        anyhow::bail!("Request failed: {} - {}", status, output);
+    }
+
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::endpoint_url;
+
+    #[test]
+    fn test_endpoint_url_adds_the_api_prefix_once() {
+        assert_eq!(
+            endpoint_url("http://localhost:8080/", "/api/apps"),
+            "http://localhost:8080/api/apps"
+        );
+        assert_eq!(
+            endpoint_url("http://localhost:8080", "apps/1/deploy"),
+            "http://localhost:8080/api/apps/1/deploy"
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles:
 //! - `backup` — Create a backup of the Rivetr instance
 //! - `restore <backup_file>` — Restore from a backup file
+//! - `acme export` / `acme import <bundle_file>` — Move ACME credentials between servers
 
 use anyhow::{Context, Result};
 
@@ -41,20 +42,38 @@ pub async fn cmd_backup(cli: &Cli, output: Option<&std::path::Path>) -> Result<(
     println!("ACME/SSL dir:   {}", config.proxy.acme_cache_dir.display());
     println!();
 
-    let result = backup::create_backup(
-        &pool,
-        &config.server.data_dir,
-        config_path,
-        &config.proxy.acme_cache_dir,
-        output,
-    )
-    .await
-    .context("Failed to create backup")?;
+    // An explicit output path is written directly; otherwise the backup goes
+    // to the configured storage, like backups made through the API
+    let (location, size) = if let Some(output) = output {
+        let result = backup::create_backup(
+            &pool,
+            &config.server.data_dir,
+            config_path,
+            &config.proxy.acme_cache_dir,
+            Some(output),
+        )
+        .await
+        .context("Failed to create backup")?;
+        (result.path.display().to_string(), result.size)
+    } else {
+        let storage = crate::storage::from_config(&config.storage, &config.server.data_dir)?;
+        let result = backup::create_stored_backup(
+            &pool,
+            storage.as_ref(),
+            &config.server.data_dir,
+            config_path,
+            &config.proxy.acme_cache_dir,
+        )
+        .await
+        .context("Failed to create backup")?;
+        let key = backup::backup_key(&result.name)?;
+        (format!("{} ({} storage)", key, storage.name()), result.size)
+    };
 
     println!("[OK] Backup created successfully!");
     println!();
-    println!("  File: {}", result.path.display());
-    println!("  Size: {}", super::deploy::format_bytes(result.size));
+    println!("  File: {}", location);
+    println!("  Size: {}", super::deploy::format_bytes(size));
     println!();
 
     Ok(())
@@ -125,3 +144,76 @@ pub async fn cmd_restore(cli: &Cli, backup_file: &std::path::Path) -> Result<()>
 
     Ok(())
 }
+
+/// Export the ACME account key and certificates to a bundle file
+pub fn cmd_acme_export(cli: &Cli, output: Option<&std::path::Path>) -> Result<()> {
+    let config = Config::load(&cli.config)?;
+    let acme_cache_dir = &config.proxy.acme_cache_dir;
+
+    let bundle = backup::acme::export_acme_bundle(acme_cache_dir)
+        .context("Failed to export ACME credentials")?;
+
+    let output = match output {
+        Some(path) => path.to_path_buf(),
+        None => std::path::PathBuf::from(format!(
+            "rivetr-acme-{}.tar.gz",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+    std::fs::write(&output, &bundle)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!();
+    println!(
+        "[OK] ACME credentials exported from {}",
+        acme_cache_dir.display()
+    );
+    println!();
+    println!("  File: {}", output.display());
+    println!(
+        "  Size: {}",
+        super::deploy::format_bytes(bundle.len() as u64)
+    );
+    println!();
+    println!("[WARNING] The bundle contains private keys. Transfer it securely.");
+    println!();
+
+    Ok(())
+}
+
+/// Import an ACME bundle into the local ACME cache directory
+pub fn cmd_acme_import(cli: &Cli, bundle_file: &std::path::Path) -> Result<()> {
+    if !bundle_file.exists() {
+        anyhow::bail!("Bundle file not found: {}", bundle_file.display());
+    }
+
+    let config = Config::load(&cli.config)?;
+    let acme_cache_dir = &config.proxy.acme_cache_dir;
+
+    let bundle = std::fs::read(bundle_file).context("Failed to read bundle file")?;
+    let result = backup::acme::import_acme_bundle(&bundle, acme_cache_dir)
+        .context("Failed to import ACME credentials")?;
+
+    println!();
+    println!(
+        "[OK] ACME credentials imported into {}",
+        acme_cache_dir.display()
+    );
+    println!();
+    println!("  Accounts:     {}", result.accounts.join(", "));
+    println!("  Certificates: {}", result.certificates.len());
+    for domain in &result.certificates {
+        println!("    - {}", domain);
+    }
+
+    if !result.warnings.is_empty() {
+        println!();
+        println!("Warnings:");
+        for warning in &result.warnings {
+            println!("  [!] {}", warning);
+        }
+    }
+    println!();
+
+    Ok(())
+}
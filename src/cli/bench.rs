@@ -0,0 +1,49 @@
+//! Bench subcommand handlers for the Rivetr CLI.
+//!
+//! Handles:
+//! - `bench proxy [--routes N] [--connections M] [--duration S]` — Measure
+//!   requests/sec and latency through the proxy handler
+
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::proxy::bench::{self, BenchOptions};
+
+fn format_latency(latency: Duration) -> String {
+    format!("{:.2}ms", latency.as_secs_f64() * 1000.0)
+}
+
+/// Benchmark the proxy with `routes` routes and `connections` connections
+pub async fn cmd_bench_proxy(routes: usize, connections: usize, duration: u64) -> Result<()> {
+    let options = BenchOptions {
+        routes,
+        connections,
+        duration: Duration::from_secs(duration.max(1)),
+    };
+
+    println!();
+    println!(
+        "Benchmarking the proxy: {} routes, {} connections, {}s",
+        options.routes,
+        options.connections,
+        options.duration.as_secs()
+    );
+
+    let report = bench::run(&options).await?;
+
+    println!();
+    println!("  Requests:     {}", report.requests);
+    println!("  Errors:       {}", report.errors);
+    println!("  Requests/sec: {:.0}", report.requests_per_sec());
+    println!();
+    println!("  Latency p50:  {}", format_latency(report.p50));
+    println!("  Latency p90:  {}", format_latency(report.p90));
+    println!("  Latency p99:  {}", format_latency(report.p99));
+    println!("  Latency max:  {}", format_latency(report.max));
+    println!();
+
+    if report.requests == 0 {
+        anyhow::bail!("No request succeeded");
+    }
+    Ok(())
+}
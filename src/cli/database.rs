@@ -2,6 +2,7 @@
 //!
 //! Handles:
 //! - `db migrate-teams` — Migrate unassigned resources to their owner's first team
+//! - `seed --demo` — Populate the database with demo data
 
 use anyhow::{Context, Result};
 
@@ -303,3 +304,36 @@ pub async fn cmd_migrate_teams(cli: &Cli, execute: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Populate the database with demo projects, apps, a service and stats history
+pub async fn cmd_seed(cli: &Cli, demo: bool) -> Result<()> {
+    if !demo {
+        anyhow::bail!("Nothing to seed. Use --demo to add the demo data set.");
+    }
+
+    let config = Config::load(&cli.config)?;
+    std::fs::create_dir_all(&config.server.data_dir).with_context(|| {
+        format!(
+            "Failed to create data directory {}",
+            config.server.data_dir.display()
+        )
+    })?;
+    // Runs migrations, so this also works on an instance that never started
+    let pool = crate::db::init(&config.server.data_dir).await?;
+
+    match crate::db::seed_demo_data(&pool).await? {
+        Some(summary) => {
+            println!("Demo data added:");
+            println!("  Projects:     {}", summary.projects);
+            println!("  Apps:         {}", summary.apps);
+            println!("  Deployments:  {}", summary.deployments);
+            println!("  Services:     {}", summary.services);
+            println!("  Stats rows:   {}", summary.stats_samples);
+            println!();
+            println!("Apps and the service are not deployed; deploy them from the dashboard to try a build.");
+        }
+        None => println!("Demo data is already present; nothing changed."),
+    }
+
+    Ok(())
+}
@@ -5,11 +5,19 @@
 //! - `apps list` - List all applications
 //! - `deploy <app>` - Trigger deployment for an app
 //! - `logs <app>` - Stream application logs
+//! - `run <app> -- <command>` - Run a one-off command in a fresh app container
+//! - `api <method> <path>` - Call any API endpoint with the configured token
 //! - `config check` - Validate configuration file
+//! - `seed --demo` - Populate the database with demo projects, apps and stats
+//! - `bench proxy` - Measure proxy throughput and latency in-process
+//! - `acme export|import` - Move the ACME account and certificates between servers
 
+pub mod api;
 pub mod backup;
+pub mod bench;
 pub mod database;
 pub mod deploy;
+pub mod run;
 pub mod server;
 
 use anyhow::{Context, Result};
@@ -77,6 +85,35 @@ pub enum Commands {
         follow: bool,
     },
 
+    /// Run a one-off command in a fresh container from an app's current image
+    ///
+    /// The container gets the app's env vars and volumes. Output is streamed
+    /// and rivetr exits with the command's exit code.
+    Run {
+        /// App name or ID
+        app: String,
+        /// Kill the command after this many seconds (default: 3600)
+        #[arg(long)]
+        timeout: Option<i64>,
+        /// Command to run (put it after `--`)
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Call an API endpoint and print the JSON response
+    ///
+    /// Sends the configured token, like the dashboard's API explorer, whose
+    /// snippets use this command.
+    Api {
+        /// HTTP method (GET, POST, PUT, PATCH, DELETE)
+        method: String,
+        /// Endpoint path, e.g. /api/apps (the /api prefix may be omitted)
+        path: String,
+        /// JSON request body, or @file to read it from a file
+        #[arg(short, long)]
+        data: Option<String>,
+    },
+
     /// Reset a user's password directly in the local database.
     ///
     /// Offline admin recovery for when SMTP isn't configured (or the admin is
@@ -97,6 +134,17 @@ pub enum Commands {
     #[command(subcommand)]
     Db(DbCommands),
 
+    /// Populate the database with example data
+    ///
+    /// Works offline against the configured data directory. Creates demo
+    /// projects, apps pointing at public sample repositories, a template
+    /// service and a month of server stats, without deploying anything.
+    Seed {
+        /// Seed the demo data set (required)
+        #[arg(long)]
+        demo: bool,
+    },
+
     /// Create a backup of the Rivetr instance
     Backup {
         /// Output path for the backup file (default: data/backups/)
@@ -110,6 +158,14 @@ pub enum Commands {
         backup_file: PathBuf,
     },
 
+    /// ACME account key and certificate migration commands
+    #[command(subcommand)]
+    Acme(AcmeCommands),
+
+    /// Performance benchmarks
+    #[command(subcommand)]
+    Bench(BenchCommands),
+
     /// Launch the interactive Terminal UI (requires --features tui)
     Tui {
         /// Rivetr API URL (overrides RIVETR_API_URL env var)
@@ -152,6 +208,42 @@ pub enum DbCommands {
     },
 }
 
+/// ACME subcommands
+#[derive(Subcommand, Debug)]
+pub enum AcmeCommands {
+    /// Export the ACME account key and certificates to a .tar.gz bundle
+    Export {
+        /// Output path for the bundle (default: ./rivetr-acme-<timestamp>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import an ACME bundle exported from another Rivetr instance
+    Import {
+        /// Path to the bundle .tar.gz file
+        bundle_file: PathBuf,
+    },
+}
+
+/// Bench subcommands
+#[derive(Subcommand, Debug)]
+pub enum BenchCommands {
+    /// Measure requests/sec and latency through the proxy
+    ///
+    /// Runs in-process on loopback against a built-in backend; no server or
+    /// container runtime is needed.
+    Proxy {
+        /// Number of routes in the route table
+        #[arg(long, default_value_t = 100)]
+        routes: usize,
+        /// Number of concurrent keep-alive connections
+        #[arg(long, default_value_t = 32)]
+        connections: usize,
+        /// How long to run, in seconds
+        #[arg(long, default_value_t = 10)]
+        duration: u64,
+    },
+}
+
 // ============================================================================
 // API Response Types
 // ============================================================================
@@ -271,6 +363,14 @@ pub async fn run_command(cli: &Cli) -> Result<()> {
         Some(Commands::Logs { app, lines, follow }) => {
             deploy::cmd_logs(cli, app, *lines, *follow).await
         }
+        Some(Commands::Run {
+            app,
+            timeout,
+            command,
+        }) => run::cmd_run(cli, app, command, *timeout).await,
+        Some(Commands::Api { method, path, data }) => {
+            api::cmd_api(cli, method, path, data.as_deref()).await
+        }
         Some(Commands::ResetPassword { email, password }) => {
             server::cmd_reset_password(cli, email, password.as_deref()).await
         }
@@ -278,8 +378,20 @@ pub async fn run_command(cli: &Cli) -> Result<()> {
         Some(Commands::Db(DbCommands::MigrateTeams { execute })) => {
             database::cmd_migrate_teams(cli, *execute).await
         }
+        Some(Commands::Seed { demo }) => database::cmd_seed(cli, *demo).await,
         Some(Commands::Backup { output }) => backup::cmd_backup(cli, output.as_deref()).await,
         Some(Commands::Restore { backup_file }) => backup::cmd_restore(cli, backup_file).await,
+        Some(Commands::Acme(AcmeCommands::Export { output })) => {
+            backup::cmd_acme_export(cli, output.as_deref())
+        }
+        Some(Commands::Acme(AcmeCommands::Import { bundle_file })) => {
+            backup::cmd_acme_import(cli, bundle_file)
+        }
+        Some(Commands::Bench(BenchCommands::Proxy {
+            routes,
+            connections,
+            duration,
+        })) => bench::cmd_bench_proxy(*routes, *connections, *duration).await,
         Some(Commands::Tui { url, token }) => {
             #[cfg(feature = "tui")]
             {
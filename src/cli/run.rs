@@ -0,0 +1,175 @@
+//! Run subcommand handler for the Rivetr CLI.
+//!
+//! Handles:
+//! - `run <app> -- <command>` — Run a one-off command in a fresh container
+//!   from the app's current image, streaming its output and exiting with its
+//!   exit code
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io::Write;
+use std::time::Duration;
+
+use super::deploy::find_app;
+use super::Cli;
+
+/// Command run from /api/apps/:id/runs
+#[derive(Debug, Deserialize)]
+struct CommandRun {
+    id: String,
+    timeout_seconds: i64,
+}
+
+/// SSE event from /api/apps/:id/runs/:run_id/stream
+#[derive(Debug, Deserialize)]
+struct RunEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    stream: Option<String>,
+    message: Option<String>,
+    status: Option<String>,
+    exit_code: Option<i64>,
+    error: Option<String>,
+}
+
+/// Join command-line words into one shell command. A single word is used as
+/// is (so a quoted pipeline runs as written); with several, words the shell
+/// would split or expand are single-quoted to keep them intact.
+fn shell_join(words: &[String]) -> String {
+    if let [command] = words {
+        return command.clone();
+    }
+    words
+        .iter()
+        .map(|word| {
+            let plain = !word.is_empty()
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+            if plain {
+                word.clone()
+            } else {
+                format!("'{}'", word.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run a one-off command and stream its output
+pub async fn cmd_run(
+    cli: &Cli,
+    app_identifier: &str,
+    command: &[String],
+    timeout: Option<i64>,
+) -> Result<()> {
+    let client = super::create_client(cli.token.as_deref())?;
+    let base_url = &cli.api_url;
+
+    let app = find_app(&client, base_url, app_identifier).await?;
+    let command = shell_join(command);
+
+    let url = format!("{}/api/apps/{}/runs", base_url, app.id);
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({
+            "command": command,
+            "timeout_seconds": timeout,
+        }))
+        .send()
+        .await
+        .context("Failed to start command")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "Authentication required. Use --token or set RIVETR_TOKEN environment variable."
+            );
+        }
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to start command: {} - {}", status, body);
+    }
+
+    let run: CommandRun = response
+        .json()
+        .await
+        .context("Failed to parse command run response")?;
+
+    eprintln!(
+        "--- Running `{}` in {} (run {}) ---",
+        command, app.name, run.id
+    );
+
+    // The stream lasts as long as the command (plus time to start the container)
+    let url = format!("{}/api/apps/{}/runs/{}/stream", base_url, app.id, run.id);
+    let response = client
+        .get(&url)
+        .header("Accept", "text/event-stream")
+        .timeout(Duration::from_secs(run.timeout_seconds.max(1) as u64 + 300))
+        .send()
+        .await
+        .context("Failed to connect to command output")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to stream command output: {} - {}", status, body);
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Command output stream failed")?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(idx) = buffer.find("\n\n") {
+            let event_str = buffer[..idx].to_string();
+            buffer = buffer[idx + 2..].to_string();
+
+            for line in event_str.lines() {
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<RunEvent>(data.trim()) else {
+                    continue;
+                };
+                match event.event_type.as_str() {
+                    "log" => {
+                        let message = event.message.unwrap_or_default();
+                        if event.stream.as_deref() == Some("stderr") {
+                            eprintln!("{}", message);
+                        } else {
+                            println!("{}", message);
+                        }
+                    }
+                    "exit" => {
+                        let _ = std::io::stdout().flush();
+                        if let Some(error) = event.error.filter(|_| event.exit_code.is_none()) {
+                            eprintln!("--- {} ---", error);
+                        }
+                        let code = event.exit_code.unwrap_or(1);
+                        eprintln!(
+                            "--- Command {} (exit code {}) ---",
+                            event.status.as_deref().unwrap_or("finished"),
+                            code
+                        );
+                        if code != 0 {
+                            // Exit with the command's code so scripts can chain on it
+                            std::process::exit(code.clamp(1, 255) as i32);
+                        }
+                        return Ok(());
+                    }
+                    "error" => {
+                        anyhow::bail!("{}", event.message.unwrap_or_default());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    anyhow::bail!("Command output stream ended before the command finished")
+}
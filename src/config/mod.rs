@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use rand::RngCore;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::path::{Path, PathBuf};
 use tracing::info;
 
@@ -37,13 +38,33 @@ pub struct Config {
     #[serde(default)]
     pub auto_update: AutoUpdateConfig,
     #[serde(default)]
+    pub build_sandbox: BuildSandboxConfig,
+    #[serde(default)]
+    pub build_cache: BuildCacheConfig,
+    #[serde(default)]
+    pub registry: RegistryConfig,
+    #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub chaos: ChaosConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
     pub ai: AiConfig,
+    #[serde(default)]
+    pub observability: ObservabilityConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServerConfig {
+    /// Address the proxy, HTTPS proxy and API bind to. Use "::" to accept both
+    /// IPv4 and IPv6 on one dual-stack socket (where the OS allows it).
     #[serde(default = "default_host")]
     pub host: String,
+    /// Additional IPv6 address to listen on alongside `host` (e.g. "::").
+    /// These sockets are IPv6-only, so they coexist with an IPv4 `host`.
+    #[serde(default)]
+    pub ipv6_host: Option<String>,
     #[serde(default = "default_api_port")]
     pub api_port: u16,
     #[serde(default = "default_proxy_port")]
@@ -62,6 +83,7 @@ impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             host: default_host(),
+            ipv6_host: None,
             api_port: default_api_port(),
             proxy_port: default_proxy_port(),
             proxy_https_port: default_proxy_https_port(),
@@ -71,6 +93,35 @@ impl Default for ServerConfig {
     }
 }
 
+impl ServerConfig {
+    /// Socket address for `host` on `port`. IPv6 hosts may be bracketed.
+    pub fn bind_addr(&self, port: u16) -> Result<SocketAddr> {
+        let ip = parse_bind_ip(&self.host)
+            .ok_or_else(|| anyhow::anyhow!("Invalid server.host: {}", self.host))?;
+        Ok(SocketAddr::new(ip, port))
+    }
+
+    /// Socket address for `ipv6_host` on `port`, if an IPv6 listener is configured
+    pub fn ipv6_bind_addr(&self, port: u16) -> Result<Option<SocketAddr>> {
+        let Some(host) = self.ipv6_host.as_deref() else {
+            return Ok(None);
+        };
+        match parse_bind_ip(host) {
+            Some(ip @ IpAddr::V6(_)) => Ok(Some(SocketAddr::new(ip, port))),
+            _ => anyhow::bail!("server.ipv6_host must be an IPv6 address: {}", host),
+        }
+    }
+}
+
+fn parse_bind_ip(host: &str) -> Option<IpAddr> {
+    let host = host.trim();
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    host.parse().ok()
+}
+
 fn default_host() -> String {
     "0.0.0.0".to_string()
 }
@@ -163,6 +214,19 @@ pub struct RuntimeConfig {
     /// several apps at once). Clamped to a minimum of 1. Default: 2
     #[serde(default = "default_max_concurrent_deployments")]
     pub max_concurrent_deployments: usize,
+    /// Minutes to keep the previous container running (without traffic) after
+    /// a deploy switches over, so rolling back to it only swaps proxy routes
+    /// instead of starting a container. 0 stops it as soon as it has drained.
+    /// Default: 0
+    #[serde(default)]
+    pub keep_previous_minutes: u64,
+    /// Docker host image builds run against instead of the local daemon, in
+    /// `DOCKER_HOST` form (`ssh://user@builder` or `tcp://builder:2376`). Built
+    /// images are streamed back to the local daemon, so heavy builds stay off
+    /// small production servers. Docker runtime only; sandboxed builds always
+    /// build locally. Default: unset (build locally)
+    #[serde(default)]
+    pub build_host: Option<String>,
 }
 
 impl Default for RuntimeConfig {
@@ -178,6 +242,8 @@ impl Default for RuntimeConfig {
             default_log_max_size: default_run_log_max_size(),
             default_log_max_file: default_run_log_max_file(),
             max_concurrent_deployments: default_max_concurrent_deployments(),
+            keep_previous_minutes: 0,
+            build_host: None,
         }
     }
 }
@@ -247,6 +313,20 @@ pub struct ProxyConfig {
     /// Directory to store ACME account and certificates (default: ./data/acme)
     #[serde(default = "default_acme_cache_dir")]
     pub acme_cache_dir: PathBuf,
+    /// Custom ACME directory URL (e.g. ZeroSSL, Buypass, or a private step-ca).
+    /// Overrides `acme_staging` when set. Default: Let's Encrypt
+    #[serde(default)]
+    pub acme_directory_url: Option<String>,
+    /// External Account Binding key ID (required by ZeroSSL and some private CAs)
+    #[serde(default)]
+    pub acme_eab_kid: Option<String>,
+    /// External Account Binding HMAC key (base64url, as issued by the CA)
+    #[serde(default)]
+    pub acme_eab_hmac_key: Option<String>,
+    /// PEM file with an extra root certificate to trust when talking to the ACME
+    /// directory (for internal CAs served over a private TLS chain)
+    #[serde(default)]
+    pub acme_ca_root: Option<PathBuf>,
     /// Interval between health checks in seconds (default: 30)
     #[serde(default = "default_health_check_interval")]
     pub health_check_interval: u64,
@@ -256,6 +336,19 @@ pub struct ProxyConfig {
     /// Number of consecutive failures before marking backend as unhealthy (default: 3)
     #[serde(default = "default_health_check_threshold")]
     pub health_check_threshold: u32,
+    /// Seconds to wait for a health check connection to open (default: 2)
+    #[serde(default = "default_health_check_connect_timeout")]
+    pub health_check_connect_timeout: u64,
+    /// Seconds to wait for each read of a health check response (default: 4)
+    #[serde(default = "default_health_check_read_timeout")]
+    pub health_check_read_timeout: u64,
+    /// Most health checks run at the same time (default: 32)
+    #[serde(default = "default_health_check_concurrency")]
+    pub health_check_concurrency: usize,
+    /// Percentage each backend's check interval is randomly shortened or
+    /// lengthened by, so checks don't line up (default: 10, max: 50)
+    #[serde(default = "default_health_check_jitter_percent")]
+    pub health_check_jitter_percent: u32,
     /// Base domain for auto-generated subdomains (e.g., "rivetr.example.com")
     /// Apps will get subdomains like "my-app.rivetr.example.com"
     pub base_domain: Option<String>,
@@ -274,6 +367,27 @@ pub struct ProxyConfig {
     /// Domain for accessing the Rivetr dashboard/API itself (e.g., "rivetr.example.com")
     /// When set, the proxy forwards requests for this domain to the API server
     pub instance_domain: Option<String>,
+    /// Max seconds to wait for in-flight requests to a replaced container to
+    /// finish before stopping it (default: 30)
+    #[serde(default = "default_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+    /// Seconds to keep a replaced container running after a deploy so the
+    /// proxy can retry idempotent requests against it when the new container
+    /// is unreachable (default: 0, stop as soon as it has drained)
+    #[serde(default)]
+    pub failover_keep_secs: u64,
+    /// Idle keep-alive connections kept open to each backend (default: 32)
+    #[serde(default = "default_upstream_max_idle_per_backend")]
+    pub upstream_max_idle_per_backend: usize,
+    /// Seconds an idle backend connection is kept before it is closed
+    /// (default: 90)
+    #[serde(default = "default_upstream_idle_timeout_secs")]
+    pub upstream_idle_timeout_secs: u64,
+    /// MaxMind-compatible country database (`.mmdb`, e.g. GeoLite2-Country)
+    /// used by per-app `allow_countries` / `block_countries` access rules.
+    /// Country rules are skipped while this is unset
+    #[serde(default)]
+    pub geoip_db_path: Option<PathBuf>,
 }
 
 fn default_acme_cache_dir() -> PathBuf {
@@ -292,6 +406,34 @@ fn default_health_check_threshold() -> u32 {
     3
 }
 
+fn default_health_check_connect_timeout() -> u64 {
+    2
+}
+
+fn default_health_check_read_timeout() -> u64 {
+    4
+}
+
+fn default_health_check_concurrency() -> usize {
+    32
+}
+
+fn default_health_check_jitter_percent() -> u32 {
+    10
+}
+
+fn default_drain_timeout_secs() -> u64 {
+    30
+}
+
+fn default_upstream_max_idle_per_backend() -> usize {
+    32
+}
+
+fn default_upstream_idle_timeout_secs() -> u64 {
+    90
+}
+
 impl Default for ProxyConfig {
     fn default() -> Self {
         Self {
@@ -299,15 +441,28 @@ impl Default for ProxyConfig {
             acme_email: None,
             acme_staging: false,
             acme_cache_dir: default_acme_cache_dir(),
+            acme_directory_url: None,
+            acme_eab_kid: None,
+            acme_eab_hmac_key: None,
+            acme_ca_root: None,
             health_check_interval: default_health_check_interval(),
             health_check_timeout: default_health_check_timeout(),
             health_check_threshold: default_health_check_threshold(),
+            health_check_connect_timeout: default_health_check_connect_timeout(),
+            health_check_read_timeout: default_health_check_read_timeout(),
+            health_check_concurrency: default_health_check_concurrency(),
+            health_check_jitter_percent: default_health_check_jitter_percent(),
             base_domain: None,
             auto_subdomain_enabled: false,
             server_ip: None,
             sslip_enabled: false,
             preview_domain: None,
             instance_domain: None,
+            drain_timeout_secs: default_drain_timeout_secs(),
+            failover_keep_secs: 0,
+            upstream_max_idle_per_backend: default_upstream_max_idle_per_backend(),
+            upstream_idle_timeout_secs: default_upstream_idle_timeout_secs(),
+            geoip_db_path: None,
         }
     }
 }
@@ -751,6 +906,10 @@ pub struct EmailConfig {
     /// From name for outgoing emails (e.g., "Rivetr")
     #[serde(default = "default_from_name")]
     pub from_name: String,
+    /// Emails sent per SMTP server (the `[email]` one and each email
+    /// notification channel) per minute; 0 disables the limit (default: 30)
+    #[serde(default = "default_email_max_per_minute")]
+    pub max_per_minute: u32,
 }
 
 fn default_smtp_port() -> u16 {
@@ -765,6 +924,10 @@ fn default_from_name() -> String {
     "Rivetr".to_string()
 }
 
+fn default_email_max_per_minute() -> u32 {
+    30
+}
+
 impl Default for EmailConfig {
     fn default() -> Self {
         Self {
@@ -776,6 +939,7 @@ impl Default for EmailConfig {
             smtp_tls: default_smtp_tls(),
             from_address: None,
             from_name: default_from_name(),
+            max_per_minute: default_email_max_per_minute(),
         }
     }
 }
@@ -800,6 +964,338 @@ pub struct AiConfig {
     pub max_tokens: Option<u32>,
 }
 
+/// Observability configuration (Prometheus `/metrics` exposure)
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct ObservabilityConfig {
+    /// Bearer token required to scrape `/metrics` (`Authorization: Bearer <token>`).
+    /// If not set, the endpoint is served without authentication.
+    #[serde(default)]
+    pub metrics_token: Option<String>,
+    /// Serve `/metrics` on a separate listener instead of the public API port
+    /// (e.g. "127.0.0.1:9100"). When set, `/metrics` is removed from the API router.
+    #[serde(default)]
+    pub metrics_bind: Option<String>,
+}
+
+impl ObservabilityConfig {
+    /// Metrics bearer token, treating an empty string as unset
+    pub fn metrics_token(&self) -> Option<&str> {
+        self.metrics_token.as_deref().filter(|t| !t.is_empty())
+    }
+
+    /// Separate metrics bind address, treating an empty string as unset
+    pub fn metrics_bind(&self) -> Option<&str> {
+        self.metrics_bind.as_deref().filter(|b| !b.is_empty())
+    }
+}
+
+/// Sandboxed build configuration, for apps and teams that build untrusted code
+/// (e.g. pull requests from external contributors)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildSandboxConfig {
+    /// Registries (or registry/namespace prefixes such as "ghcr.io/acme") that
+    /// sandboxed builds may pull base images from. Default: Docker Hub, GHCR,
+    /// Quay, GCR, MCR, public ECR and the GitLab registry
+    #[serde(default = "default_sandbox_allowed_registries")]
+    pub allowed_registries: Vec<String>,
+    /// Docker: buildx builder used for sandboxed builds, e.g. one whose BuildKit
+    /// daemon runs rootless or under gVisor/Kata. Unset uses the default builder
+    /// (build steps still run without network access).
+    #[serde(default)]
+    pub builder: Option<String>,
+    /// Podman: OCI runtime for sandboxed build containers (e.g. "runsc")
+    #[serde(default)]
+    pub oci_runtime: Option<String>,
+    /// Sandbox every PR preview build, whatever the app and team settings
+    /// (default: false)
+    #[serde(default)]
+    pub previews: bool,
+}
+
+fn default_sandbox_allowed_registries() -> Vec<String> {
+    [
+        "docker.io",
+        "ghcr.io",
+        "quay.io",
+        "gcr.io",
+        "mcr.microsoft.com",
+        "public.ecr.aws",
+        "registry.gitlab.com",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for BuildSandboxConfig {
+    fn default() -> Self {
+        Self {
+            allowed_registries: default_sandbox_allowed_registries(),
+            builder: None,
+            oci_runtime: None,
+            previews: false,
+        }
+    }
+}
+
+impl BuildSandboxConfig {
+    /// Sandbox buildx builder, treating an empty string as unset
+    pub fn builder(&self) -> Option<&str> {
+        self.builder.as_deref().filter(|b| !b.is_empty())
+    }
+
+    /// Sandbox OCI runtime, treating an empty string as unset
+    pub fn oci_runtime(&self) -> Option<&str> {
+        self.oci_runtime.as_deref().filter(|r| !r.is_empty())
+    }
+}
+
+/// Persistent per-app build cache, kept under `data_dir/build-cache` so
+/// incremental builds reuse the layers of the previous build
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildCacheConfig {
+    /// Keep a layer cache between an app's builds (default: true). Docker only;
+    /// apps can opt out with their "disable build cache" setting.
+    #[serde(default = "default_build_cache_enabled")]
+    pub enabled: bool,
+    /// Largest cache kept per app in MB; a larger cache is dropped after the
+    /// build and the next one starts cold (default: 4096)
+    #[serde(default = "default_build_cache_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Caches unused for this many days, and those of deleted apps, are pruned
+    /// (default: 14)
+    #[serde(default = "default_build_cache_max_age_days")]
+    pub max_age_days: u64,
+    /// Docker: buildx builder that exports the caches. The default `docker`
+    /// driver can't; create one with
+    /// `docker buildx create --name rivetr-cache --driver docker-container`.
+    /// Unset uses the default builder.
+    #[serde(default)]
+    pub builder: Option<String>,
+}
+
+fn default_build_cache_enabled() -> bool {
+    true
+}
+
+fn default_build_cache_max_size_mb() -> u64 {
+    4096
+}
+
+fn default_build_cache_max_age_days() -> u64 {
+    14
+}
+
+impl Default for BuildCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_build_cache_enabled(),
+            max_size_mb: default_build_cache_max_size_mb(),
+            max_age_days: default_build_cache_max_age_days(),
+            builder: None,
+        }
+    }
+}
+
+impl BuildCacheConfig {
+    /// Cache buildx builder, treating an empty string as unset
+    pub fn builder(&self) -> Option<&str> {
+        self.builder.as_deref().filter(|b| !b.is_empty())
+    }
+}
+
+/// Registry built images are pushed to, for apps that don't push to their
+/// own, so other servers and rollbacks after image pruning can pull the exact
+/// image a deployment ran
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RegistryConfig {
+    /// Registry and namespace images are pushed under, e.g.
+    /// "registry.example.com/rivetr". Unset disables the global push.
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl RegistryConfig {
+    /// Push registry, treating an empty string as unset
+    pub fn url(&self) -> Option<&str> {
+        self.url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+    }
+}
+
+/// Vulnerability scanning of deployment images before they start
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanConfig {
+    /// Scan every deployment's image (default: false). The scanner CLI must be
+    /// installed on the server.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Scanner to run (default: trivy)
+    #[serde(default)]
+    pub scanner: Scanner,
+    /// Fail the deployment when a vulnerability of this severity or worse is
+    /// found. Unset only reports the findings.
+    #[serde(default)]
+    pub fail_on: Option<ScanSeverity>,
+    /// Leave out vulnerabilities that have no fixed version yet (default: false)
+    #[serde(default)]
+    pub ignore_unfixed: bool,
+    /// Longest a scan may run before it is abandoned (default: 600)
+    #[serde(default = "default_scan_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_scan_timeout_secs() -> u64 {
+    600
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scanner: Scanner::default(),
+            fail_on: None,
+            ignore_unfixed: false,
+            timeout_secs: default_scan_timeout_secs(),
+        }
+    }
+}
+
+/// Image vulnerability scanner CLI
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scanner {
+    #[default]
+    Trivy,
+    Grype,
+}
+
+/// Severity of a reported vulnerability, ordered from least to most severe
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanSeverity {
+    #[default]
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// Fault injection for resilience testing (see `crate::chaos`). Only
+/// enable this on staging or test instances.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChaosConfig {
+    /// Allow admins to switch faults on through `/api/system/chaos`
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Where instance backups and deployment diagnostics bundles are stored
+/// (see `crate::storage`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StorageConfig {
+    /// Storage backend (default: local)
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// Root directory for the local backend (default: server.data_dir)
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub s3: S3StorageConfig,
+    #[serde(default)]
+    pub sftp: SftpStorageConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    S3,
+    Sftp,
+}
+
+/// S3 or S3-compatible bucket for `backend = "s3"`
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3StorageConfig {
+    /// Custom endpoint for MinIO, R2, etc. Unset uses AWS.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    #[serde(default)]
+    pub access_key: String,
+    #[serde(default)]
+    pub secret_key: String,
+    /// Prefix for every key, e.g. "rivetr/prod"
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl Default for S3StorageConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            bucket: String::new(),
+            region: default_s3_region(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            prefix: None,
+        }
+    }
+}
+
+/// Remote directory for `backend = "sftp"`. Authentication is by key only.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SftpStorageConfig {
+    #[serde(default)]
+    pub host: String,
+    #[serde(default = "default_sftp_port")]
+    pub port: u16,
+    #[serde(default = "default_sftp_username")]
+    pub username: String,
+    /// Private key to authenticate with. Unset uses the ssh agent and
+    /// default keys.
+    #[serde(default)]
+    pub identity_file: Option<PathBuf>,
+    /// Remote directory objects are stored under (default: the login directory)
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+fn default_sftp_username() -> String {
+    "root".to_string()
+}
+
+impl Default for SftpStorageConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: default_sftp_port(),
+            username: default_sftp_username(),
+            identity_file: None,
+            path: None,
+        }
+    }
+}
+
 /// Auto-update configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct AutoUpdateConfig {
@@ -819,6 +1315,12 @@ pub struct AutoUpdateConfig {
     /// Include pre-release versions (default: false)
     #[serde(default)]
     pub include_prereleases: bool,
+    /// After auto-applying an update, start the new binary and hand the proxy
+    /// and API sockets over to it instead of waiting for a service restart
+    /// (default: false). Under systemd this needs `Type=notify` and
+    /// `NotifyAccess=all` so the new process can become the main process.
+    #[serde(default)]
+    pub hot_restart: bool,
 }
 
 fn default_update_check_enabled() -> bool {
@@ -841,6 +1343,7 @@ impl Default for AutoUpdateConfig {
             check_interval_hours: default_update_check_interval_hours(),
             github_repo: default_github_repo(),
             include_prereleases: false,
+            hot_restart: false,
         }
     }
 }
@@ -928,7 +1431,14 @@ impl Config {
             stats_retention: StatsRetentionConfig::default(),
             email: EmailConfig::default(),
             auto_update: AutoUpdateConfig::default(),
+            build_sandbox: BuildSandboxConfig::default(),
+            build_cache: BuildCacheConfig::default(),
+            registry: RegistryConfig::default(),
+            scan: ScanConfig::default(),
+            chaos: ChaosConfig::default(),
+            storage: StorageConfig::default(),
             ai: AiConfig::default(),
+            observability: ObservabilityConfig::default(),
         }
     }
 }
@@ -2,7 +2,7 @@ mod models;
 mod seeders;
 
 pub use models::*;
-pub use seeders::seed_service_templates;
+pub use seeders::{seed_demo_data, seed_service_templates, DemoSummary};
 
 use anyhow::Result;
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
@@ -1582,9 +1582,848 @@ async fn run_migrations(pool: &SqlitePool) -> Result<()> {
         .await?;
     }
 
-    // Seed/update built-in templates (runs on every startup to add new templates)
+    // Migration 114: app_access_rules table (per-app proxy request filtering).
+    let has_access_rules: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_access_rules'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_access_rules.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/114_app_access_rules.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 115: built-in WAF settings and event log.
+    let has_waf: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_waf_settings'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_waf.is_none() {
+        execute_sql(pool, include_str!("../../migrations/115_app_waf.sql")).await?;
+    }
+
+    // Migration 116: app_bot_settings table (robots.txt / bad bot handling).
+    let has_bot_settings: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_bot_settings'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_bot_settings.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/116_app_bot_settings.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 117: commit author/branch/compare URL on deployments.
+    let has_commit_author: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployments') WHERE name = 'commit_author'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_commit_author.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/117_deployment_commit_metadata.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 118: app_basic_auth_paths table (path-scoped basic auth).
+    let has_basic_auth_paths: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_basic_auth_paths'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_basic_auth_paths.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/118_app_basic_auth_paths.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 119: deployment_events table (status transition timeline).
+    let has_deployment_events: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'deployment_events'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_deployment_events.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/119_deployment_events.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 120: app_forward_auth table (proxy forward authentication).
+    let has_forward_auth: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_forward_auth'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_forward_auth.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/120_app_forward_auth.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 121: build_sandbox on teams and apps (sandboxed builds).
+    let has_build_sandbox: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'build_sandbox'")
+            .fetch_optional(pool)
+            .await?;
+    if has_build_sandbox.is_none() {
+        execute_sql(pool, include_str!("../../migrations/121_build_sandbox.sql")).await?;
+    }
+
+    // Migration 122: app_cors table (CORS policy enforced at the proxy).
+    let has_app_cors: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_cors'")
+            .fetch_optional(pool)
+            .await?;
+    if has_app_cors.is_none() {
+        execute_sql(pool, include_str!("../../migrations/122_app_cors.sql")).await?;
+    }
+
+    // Migration 123: egress_policy and egress_allowlist on apps (network egress policies).
+    let has_egress_policy: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'egress_policy'")
+            .fetch_optional(pool)
+            .await?;
+    if has_egress_policy.is_none() {
+        execute_sql(pool, include_str!("../../migrations/123_app_egress.sql")).await?;
+    }
+
+    // Migration 124: healthcheck_type and healthcheck_command on apps (TCP/command health checks).
+    let has_healthcheck_type: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'healthcheck_type'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_healthcheck_type.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/124_healthcheck_type.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 125: app_power_schedules table (scheduled app stop/start).
+    let has_power_schedules: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_power_schedules'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_power_schedules.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/125_app_power_schedules.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 126: app_prewarm_settings table (pre-warmed deployment slots).
+    let has_prewarm: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_prewarm_settings'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_prewarm.is_none() {
+        execute_sql(pool, include_str!("../../migrations/126_app_prewarm.sql")).await?;
+    }
+
+    // Migration 127: deployment_diagnostics table (failure diagnostics bundles).
+    let has_diagnostics: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'deployment_diagnostics'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_diagnostics.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/127_deployment_diagnostics.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 128: rolling_max_surge and rolling_max_unavailable on apps (rolling deploys).
+    let has_rolling_budget: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'rolling_max_surge'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_rolling_budget.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/128_app_rolling_deploys.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 129: error_code and error_hint on deployments (failure classification).
+    let has_error_code: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployments') WHERE name = 'error_code'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_error_code.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/129_deployment_error_codes.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 130: allow the app_scaled notification event (autoscaler).
+    let subs_allows_app_scaled: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'notification_subscriptions' AND sql LIKE '%app_scaled%'",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false);
+    if !subs_allows_app_scaled {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/130_notification_app_scaled.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 131: deploy_wait_conditions on apps (readiness of dependencies).
+    let has_wait_conditions: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'deploy_wait_conditions'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_wait_conditions.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/131_app_deploy_wait_conditions.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 132: app_type on apps (worker apps without a port or proxy route).
+    let has_app_type: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'app_type'")
+            .fetch_optional(pool)
+            .await?;
+    if has_app_type.is_none() {
+        execute_sql(pool, include_str!("../../migrations/132_app_type.sql")).await?;
+    }
+
+    // Migration 133: app_processes table and procfile on deployments (Procfile support).
+    let has_processes: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_processes'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_processes.is_none() {
+        execute_sql(pool, include_str!("../../migrations/133_app_processes.sql")).await?;
+    }
+
+    // Migration 134: healthcheck_window_secs on apps, rolled_back on deployments and
+    // the deployment_rolled_back notification event (automatic rollback).
+    let has_rolled_back: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployments') WHERE name = 'rolled_back'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_rolled_back.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/134_auto_rollback_window.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 135: notification_outbox table (durable notification dispatch).
+    let has_outbox: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'notification_outbox'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_outbox.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/135_notification_outbox.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 136: webhook branch/author/path filters on apps.
+    let has_webhook_filters: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'webhook_branch_regex'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_webhook_filters.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/136_webhook_filters.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 137: cnb_config on apps (buildpacks builder selection).
+    let has_cnb_config: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'cnb_config'")
+            .fetch_optional(pool)
+            .await?;
+    if has_cnb_config.is_none() {
+        execute_sql(pool, include_str!("../../migrations/137_cnb_config.sql")).await?;
+    }
+
+    // Migration 138: deploy_fences on apps (serialized pre-deploy/release steps).
+    let has_deploy_fences: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'deploy_fences'")
+            .fetch_optional(pool)
+            .await?;
+    if has_deploy_fences.is_none() {
+        execute_sql(pool, include_str!("../../migrations/138_deploy_fences.sql")).await?;
+    }
+
+    // Migration 139: static_config on apps (static site build settings).
+    let has_static_config: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'static_config'")
+            .fetch_optional(pool)
+            .await?;
+    if has_static_config.is_none() {
+        execute_sql(pool, include_str!("../../migrations/139_static_config.sql")).await?;
+    }
+
+    // Migration 140: compose_file/compose_service on apps (compose repository deployments).
+    let has_compose_file: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'compose_file'")
+            .fetch_optional(pool)
+            .await?;
+    if has_compose_file.is_none() {
+        execute_sql(pool, include_str!("../../migrations/140_compose_apps.sql")).await?;
+    }
+
+    // Migration 141: timezone on every schedule (IANA name, NULL = UTC).
+    let has_schedule_timezones: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('scheduled_jobs') WHERE name = 'timezone'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_schedule_timezones.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/141_schedule_timezones.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 142: scheduled_tasks tables (cron commands in one-off containers).
+    let has_scheduled_tasks: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'scheduled_tasks'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_scheduled_tasks.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/142_scheduled_tasks.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 143: user_preferences and notification_digest_items tables.
+    let has_user_preferences: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'user_preferences'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_user_preferences.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/143_user_preferences.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 144: notification_digests table (periodic summary notifications).
+    let has_notification_digests: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'notification_digests'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_notification_digests.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/144_notification_digests.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 145: command_runs table (one-off commands in app containers).
+    let has_command_runs: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'command_runs'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_command_runs.is_none() {
+        execute_sql(pool, include_str!("../../migrations/145_command_runs.sql")).await?;
+    }
+
+    // Migration 146: inbox_notifications table (in-app notification center).
+    let has_inbox_notifications: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'inbox_notifications'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_inbox_notifications.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/146_inbox_notifications.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 147: locale on user_preferences (localized dashboard and messages).
+    let has_user_locale: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('user_preferences') WHERE name = 'locale'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_user_locale.is_none() {
+        execute_sql(pool, include_str!("../../migrations/147_user_locale.sql")).await?;
+    }
+
+    // Migration 148: theme and reduced motion on user_preferences.
+    let has_user_theme: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('user_preferences') WHERE name = 'theme'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_user_theme.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/148_user_appearance.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 149: environment-level deployment approval
+    let has_env_approval: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('environments') WHERE name = 'require_approval'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_env_approval.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/149_environment_approval.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 150: calendar ranges on deployment freeze windows.
+    let has_freeze_ranges: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployment_freeze_windows') WHERE name = 'starts_at'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_freeze_ranges.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/150_freeze_window_ranges.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 151: deployment_scans table (image vulnerability scan reports).
+    let has_scans: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'deployment_scans'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_scans.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/151_deployment_scans.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 152: deployment_config_snapshots table (config diff between deployments).
+    let has_config_snapshots: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'deployment_config_snapshots'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_config_snapshots.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/152_deployment_config_snapshots.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 153: content-addressed artifact store
+    let has_artifacts: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'artifacts'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_artifacts.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/153_artifact_store.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 154: smoke_tests on apps (HTTP checks after the health check).
+    let has_smoke_tests: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'smoke_tests'")
+            .fetch_optional(pool)
+            .await?;
+    if has_smoke_tests.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/154_app_smoke_tests.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 155: max_deployments/keep_images on apps (retention overrides).
+    let has_max_deployments: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'max_deployments'")
+            .fetch_optional(pool)
+            .await?;
+    if has_max_deployments.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/155_app_retention_overrides.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 156: email_queue table (rate-limited email delivery, sent-mail log).
+    let has_email_queue: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'email_queue'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_email_queue.is_none() {
+        execute_sql(pool, include_str!("../../migrations/156_email_queue.sql")).await?;
+    }
+
+    // Migration 157: locked_at/locked_by/lock_reason on apps (deployment locks).
+    let has_locked_at: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'locked_at'")
+            .fetch_optional(pool)
+            .await?;
+    if has_locked_at.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/157_app_deploy_lock.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 158: pipeline_file on deployments (rivetr.yaml pipelines).
+    let has_pipeline_file: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployments') WHERE name = 'pipeline_file'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_pipeline_file.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/158_deployment_pipeline_file.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 159: test stage (apps.test_commands, apps.test_timeout_secs,
+    // deployments.test_output).
+    let has_test_commands: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'test_commands'")
+            .fetch_optional(pool)
+            .await?;
+    if has_test_commands.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/159_app_test_stage.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 160: ssh_keys.jump_host (clone through a bastion).
+    let has_jump_host: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('ssh_keys') WHERE name = 'jump_host'")
+            .fetch_optional(pool)
+            .await?;
+    if has_jump_host.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/160_ssh_key_jump_host.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 161: databases seed columns (seed newly provisioned databases).
+    let has_seed_script: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('databases') WHERE name = 'seed_script'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_seed_script.is_none() {
+        execute_sql(pool, include_str!("../../migrations/161_database_seed.sql")).await?;
+    }
+
+    // Migration 162: deployment_phases table and deployment_logs.phase
+    // (per-phase deployment timings).
+    let has_deployment_phases: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'deployment_phases'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_deployment_phases.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/162_deployment_phases.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 163: version_tag on deployments (rivetr/<app>:<short-sha>).
+    let has_version_tag: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('deployments') WHERE name = 'version_tag'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_version_tag.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/163_deployment_version_tag.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 164: preview expiry limits on apps, activity tracking on
+    // preview_deployments and the preview_expiring notification event.
+    let has_preview_expiry: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'preview_idle_timeout_hours'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_preview_expiry.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/164_preview_expiry.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 165: post-deploy notes, primary URL path and health path on
+    // service templates and the services deployed from them.
+    let has_template_notes: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('service_templates') WHERE name = 'post_deploy_notes'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_template_notes.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/165_template_post_deploy.sql"),
+        )
+        .await?;
+    }
+
+    let has_service_routes: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('services') WHERE name = 'route_service'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_service_routes.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/166_service_routes.sql"),
+        )
+        .await?;
+    }
+
+    let has_app_metrics: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('apps') WHERE name = 'metrics_port'")
+            .fetch_optional(pool)
+            .await?;
+    if has_app_metrics.is_none() {
+        execute_sql(pool, include_str!("../../migrations/167_app_metrics.sql")).await?;
+    }
+
+    let has_preview_database: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'preview_database'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_preview_database.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/168_preview_database.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 169: scale-to-zero idle timeout and sleep state of apps
+    let has_scale_to_zero: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'idle_timeout_minutes'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_scale_to_zero.is_none() {
+        execute_sql(pool, include_str!("../../migrations/169_scale_to_zero.sql")).await?;
+    }
+
+    // Migration 170: deployment_cancelled notification event
+    let subs_allows_cancelled: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'notification_subscriptions' AND sql LIKE '%deployment_cancelled%'",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false);
+    if !subs_allows_cancelled {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/170_deployment_cancelled_event.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 171: per-app bandwidth ledger, monthly caps and the
+    // bandwidth_exceeded notification event
+    let has_bandwidth: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('apps') WHERE name = 'bandwidth_limit_gb'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_bandwidth.is_none() {
+        execute_sql(pool, include_str!("../../migrations/171_app_bandwidth.sql")).await?;
+    }
+
+    // Migration 172: backend_unhealthy / backend_recovered notification events
+    let subs_allows_backend_health: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'notification_subscriptions' AND sql LIKE '%backend_unhealthy%'",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap_or(false);
+    if !subs_allows_backend_health {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/172_backend_health_events.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 173: GitLab merge request note of a preview deployment
+    let has_gitlab_note: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM pragma_table_info('preview_deployments') WHERE name = 'gitlab_note_id'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_gitlab_note.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/173_preview_gitlab_note.sql"),
+        )
+        .await?;
+    }
+
+    // Migration 174: outgoing webhook delivery log
+    let has_webhook_deliveries: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'webhook_deliveries'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if has_webhook_deliveries.is_none() {
+        execute_sql(
+            pool,
+            include_str!("../../migrations/174_webhook_deliveries.sql"),
+        )
+        .await?;
+    }
+
     seeders::seed_service_templates(pool).await?;
 
     info!("Migrations completed");
     Ok(())
 }
+
+/// Fresh, fully migrated database in a temporary directory, for tests.
+/// The directory must outlive the pool.
+#[cfg(test)]
+pub(crate) async fn test_pool() -> (tempfile::TempDir, DbPool) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let pool = init(dir.path()).await.expect("test database");
+    (dir, pool)
+}
+
+/// Insert an app with only the required fields set, for tests
+#[cfg(test)]
+pub(crate) async fn test_app(db: &DbPool, name: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO apps (id, name, git_url) VALUES (?, ?, ?)")
+        .bind(&id)
+        .bind(name)
+        .bind(format!("https://example.com/{}.git", name))
+        .execute(db)
+        .await
+        .expect("insert test app");
+    id
+}
+
+/// Insert a deployment of `app_id` in `status`, for tests
+#[cfg(test)]
+pub(crate) async fn test_deployment(db: &DbPool, app_id: &str, status: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO deployments (id, app_id, status) VALUES (?, ?, ?)")
+        .bind(&id)
+        .bind(app_id)
+        .bind(status)
+        .execute(db)
+        .await
+        .expect("insert test deployment");
+    id
+}
+
+/// Insert a user with `role` ("admin" or "member"), for tests
+#[cfg(test)]
+pub(crate) async fn test_user(db: &DbPool, role: &str) -> User {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO users (id, email, password_hash, name, role) VALUES (?, ?, '', ?, ?)")
+        .bind(&id)
+        .bind(format!("{}@example.com", id))
+        .bind(role)
+        .bind(role)
+        .execute(db)
+        .await
+        .expect("insert test user");
+    sqlx::query_as("SELECT * FROM users WHERE id = ?")
+        .bind(&id)
+        .fetch_one(db)
+        .await
+        .expect("load test user")
+}
+
+/// Load an app, for tests
+#[cfg(test)]
+pub(crate) async fn load_test_app(db: &DbPool, id: &str) -> App {
+    sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+        .bind(id)
+        .fetch_one(db)
+        .await
+        .expect("load test app")
+}
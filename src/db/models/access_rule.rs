@@ -0,0 +1,191 @@
+//! App access rule models and DTOs.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Block requests whose User-Agent matches the pattern.
+pub const ACCESS_RULE_BLOCK_USER_AGENT: &str = "block_user_agent";
+/// Block requests whose path matches the pattern.
+pub const ACCESS_RULE_BLOCK_PATH: &str = "block_path";
+/// On paths matching the pattern, allow only the listed methods.
+pub const ACCESS_RULE_ALLOW_METHODS: &str = "allow_methods";
+/// Allow only clients from the listed countries (GeoIP).
+pub const ACCESS_RULE_ALLOW_COUNTRIES: &str = "allow_countries";
+/// Block clients from the listed countries (GeoIP).
+pub const ACCESS_RULE_BLOCK_COUNTRIES: &str = "block_countries";
+
+/// All accepted `rule_type` values.
+pub const ACCESS_RULE_TYPES: &[&str] = &[
+    ACCESS_RULE_BLOCK_USER_AGENT,
+    ACCESS_RULE_BLOCK_PATH,
+    ACCESS_RULE_ALLOW_METHODS,
+    ACCESS_RULE_ALLOW_COUNTRIES,
+    ACCESS_RULE_BLOCK_COUNTRIES,
+];
+
+/// Returns true for rule types whose pattern is a country code list.
+pub fn is_country_rule(rule_type: &str) -> bool {
+    rule_type == ACCESS_RULE_ALLOW_COUNTRIES || rule_type == ACCESS_RULE_BLOCK_COUNTRIES
+}
+
+/// A request filtering rule associated with an app, enforced at the proxy level.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppAccessRule {
+    pub id: String,
+    pub app_id: String,
+    /// One of `block_user_agent`, `block_path`, `allow_methods`,
+    /// `allow_countries`, `block_countries`
+    pub rule_type: String,
+    /// Regex matched against the User-Agent (`block_user_agent`) or request path,
+    /// or comma-separated ISO country codes for country rules, e.g. `DE,FR`
+    pub pattern: String,
+    /// Comma-separated allowed methods (`allow_methods` only), e.g. `GET,HEAD`
+    pub methods: Option<String>,
+    /// Optional human-readable note
+    pub description: Option<String>,
+    /// 1 = enabled, 0 = disabled
+    pub is_enabled: i32,
+    /// Evaluation priority; lower value = evaluated first
+    pub sort_order: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppAccessRule {
+    /// Returns true if the rule is currently enabled.
+    pub fn enabled(&self) -> bool {
+        self.is_enabled != 0
+    }
+
+    /// Load the enabled rules for an app, converted for the proxy route table.
+    pub async fn list_enabled_for_proxy(
+        db: &SqlitePool,
+        app_id: &str,
+    ) -> Result<Vec<AccessRule>, sqlx::Error> {
+        let rules: Vec<AppAccessRule> = sqlx::query_as(
+            "SELECT * FROM app_access_rules WHERE app_id = ? AND is_enabled = 1 \
+             ORDER BY sort_order ASC, created_at ASC",
+        )
+        .bind(app_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(rules.into_iter().map(AccessRule::from).collect())
+    }
+}
+
+// ---- DTOs ----
+
+/// Request to create a new access rule.
+#[derive(Debug, Deserialize)]
+pub struct CreateAccessRuleRequest {
+    /// One of `block_user_agent`, `block_path`, `allow_methods`,
+    /// `allow_countries`, `block_countries`.
+    pub rule_type: String,
+    /// Regex matched against the User-Agent or request path, or
+    /// comma-separated country codes for country rules.
+    pub pattern: String,
+    /// Allowed methods for `allow_methods` rules.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether the rule is active. Defaults to true.
+    #[serde(default = "default_enabled")]
+    pub is_enabled: bool,
+    /// Sort order (lower = evaluated first). Defaults to 0.
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// Request to update an existing access rule.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAccessRuleRequest {
+    pub rule_type: Option<String>,
+    pub pattern: Option<String>,
+    pub methods: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub is_enabled: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A lightweight version of the rule used in the proxy route table.
+#[derive(Debug, Clone)]
+pub struct AccessRule {
+    /// One of the `ACCESS_RULE_TYPES`.
+    pub rule_type: String,
+    /// Regex pattern (country code list for country rules).
+    pub pattern: String,
+    /// Upper-cased allowed methods (`allow_methods` only).
+    pub methods: Vec<String>,
+    /// Upper-cased country codes (country rules only).
+    pub countries: Vec<String>,
+    /// `pattern` compiled once when the rule is loaded (request rules only,
+    /// `None` if the pattern doesn't compile).
+    pub regex: Option<Regex>,
+}
+
+impl AccessRule {
+    /// Returns true if this rule needs the client's country.
+    pub fn is_country_rule(&self) -> bool {
+        is_country_rule(&self.rule_type)
+    }
+}
+
+impl From<AppAccessRule> for AccessRule {
+    fn from(rule: AppAccessRule) -> Self {
+        let (countries, regex) = if is_country_rule(&rule.rule_type) {
+            (parse_country_codes(&rule.pattern).unwrap_or_default(), None)
+        } else {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| {
+                    tracing::warn!(pattern = %rule.pattern, error = %e, "Invalid access rule regex");
+                })
+                .ok();
+            (Vec::new(), regex)
+        };
+        Self {
+            rule_type: rule.rule_type,
+            pattern: rule.pattern,
+            methods: parse_methods(rule.methods.as_deref().unwrap_or("")),
+            countries,
+            regex,
+        }
+    }
+}
+
+/// Split a comma-separated method list into upper-cased method names.
+pub fn parse_methods(methods: &str) -> Vec<String> {
+    methods
+        .split(',')
+        .map(|m| m.trim().to_ascii_uppercase())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Parse a comma-separated list of ISO 3166-1 alpha-2 country codes into
+/// upper-cased codes, e.g. `"de, fr"` -> `["DE", "FR"]`.
+pub fn parse_country_codes(codes: &str) -> Result<Vec<String>, String> {
+    let mut parsed: Vec<String> = Vec::new();
+    for code in codes.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!(
+                "Invalid country code '{}': use two-letter ISO 3166-1 codes like US or DE",
+                code
+            ));
+        }
+        let code = code.to_ascii_uppercase();
+        if !parsed.contains(&code) {
+            parsed.push(code);
+        }
+    }
+    if parsed.is_empty() {
+        return Err("At least one country code is required".to_string());
+    }
+    Ok(parsed)
+}
@@ -3,8 +3,54 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-use super::common::{parse_domains, Domain, Environment, PortMapping};
+use super::common::{parse_domains, tls_passthrough_domains, Domain, Environment, PortMapping};
 use crate::engine::nixpacks::NixpacksConfig;
+use crate::engine::pack_builder::PackConfig;
+use crate::engine::static_builder::StaticSiteSettings;
+
+/// An app serving HTTP on its port behind the proxy
+pub const APP_TYPE_WEB: &str = "web";
+/// A background process: no published port, proxy route or domain, and
+/// process-liveness (or command) health checks
+pub const APP_TYPE_WORKER: &str = "worker";
+
+/// All accepted `app_type` values.
+pub const APP_TYPES: &[&str] = &[APP_TYPE_WEB, APP_TYPE_WORKER];
+
+/// Bounds of `healthcheck_window_secs`
+pub const MIN_HEALTHCHECK_WINDOW_SECS: i64 = 10;
+pub const MAX_HEALTHCHECK_WINDOW_SECS: i64 = 600;
+
+/// Test stage time limit when the app doesn't set one, and the longest accepted
+pub const DEFAULT_TEST_TIMEOUT_SECS: i64 = 600;
+pub const MAX_TEST_TIMEOUT_SECS: i64 = 3600;
+
+/// Longest accepted preview lifetime or inactivity timeout (30 days)
+pub const MAX_PREVIEW_TTL_HOURS: i64 = 720;
+
+/// Longest accepted scale-to-zero idle timeout (7 days)
+pub const MAX_IDLE_TIMEOUT_MINUTES: i64 = 7 * 24 * 60;
+
+/// Largest accepted monthly bandwidth cap (1 PB)
+pub const MAX_BANDWIDTH_LIMIT_GB: i64 = 1_000_000;
+/// Send a notification when an app exceeds its monthly bandwidth cap
+pub const BANDWIDTH_ACTION_NOTIFY: &str = "notify";
+/// Notify and throttle an app's domains once it exceeds its monthly cap
+pub const BANDWIDTH_ACTION_THROTTLE: &str = "throttle";
+/// Rate a throttled app is limited to when it doesn't set one (1 Mbit/s)
+pub const DEFAULT_BANDWIDTH_THROTTLE_KBPS: i64 = 1000;
+
+/// A preview database that starts out blank
+pub const PREVIEW_DATABASE_EMPTY: &str = "empty";
+/// A preview database holding the source database's schema, without its rows
+pub const PREVIEW_DATABASE_SCHEMA: &str = "schema";
+
+/// All accepted `preview_database` values.
+pub const PREVIEW_DATABASE_MODES: &[&str] = &[PREVIEW_DATABASE_EMPTY, PREVIEW_DATABASE_SCHEMA];
+
+/// Env var receiving the preview database's connection URL unless the app
+/// names another
+pub const DEFAULT_PREVIEW_DATABASE_ENV: &str = "DATABASE_URL";
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct App {
@@ -174,10 +220,156 @@ pub struct App {
     pub destination_id: Option<String>,
     /// Custom container labels (JSON array: [{key, value}]) applied at deployment time
     pub custom_labels: Option<String>,
+    /// Build sandbox mode: "none" or "sandboxed". NULL inherits the team setting.
+    pub build_sandbox: Option<String>,
+    /// Outbound network policy: "allow_all" or "restricted"
+    #[serde(default = "default_egress_policy")]
+    pub egress_policy: String,
+    /// Hosts and IPv4 CIDRs a restricted app may reach (JSON array)
+    pub egress_allowlist: Option<String>,
+    /// Health probe: "http", "tcp" or "command"
+    #[serde(default = "default_healthcheck_type")]
+    pub healthcheck_type: String,
+    /// Command run in the container for "command" health checks
+    pub healthcheck_command: Option<String>,
+    /// Rolling deploys: new replicas started above replica_count at once
+    #[serde(default = "default_rolling_max_surge")]
+    pub rolling_max_surge: i64,
+    /// Rolling deploys: old replicas that may leave the pool before their
+    /// replacements are healthy
+    #[serde(default)]
+    pub rolling_max_unavailable: i64,
+    /// Conditions checked before the container starts (JSON array of WaitCondition)
+    #[serde(default)]
+    pub deploy_wait_conditions: Option<String>,
+    /// "web" (HTTP behind the proxy) or "worker" (background process)
+    #[serde(default = "default_app_type")]
+    pub app_type: String,
+    /// Seconds a new container may take to pass its health check before the
+    /// deployment fails (and auto-rollback, if enabled, kicks in)
+    #[serde(default = "default_healthcheck_window_secs")]
+    pub healthcheck_window_secs: i64,
+    /// Regex of further branches whose pushes deploy the app
+    #[serde(default)]
+    pub webhook_branch_regex: Option<String>,
+    /// Regex of commit authors whose pushes don't deploy the app
+    #[serde(default)]
+    pub webhook_ignore_authors: Option<String>,
+    /// Regex of paths; pushes changing only matching files don't deploy the app
+    #[serde(default)]
+    pub webhook_ignore_paths: Option<String>,
+    /// Cloud Native Buildpacks configuration (JSON PackConfig)
+    #[serde(default)]
+    pub cnb_config: Option<String>,
+    /// JSON array of deployment fence names shared with other apps
+    #[serde(default)]
+    pub deploy_fences: Option<String>,
+    /// Static site build settings (JSON StaticSiteSettings)
+    #[serde(default)]
+    pub static_config: Option<String>,
+    /// Compose file of a "docker-compose" app, relative to the base directory
+    #[serde(default)]
+    pub compose_file: Option<String>,
+    /// Compose service the proxy routes a "docker-compose" app's domains to
+    #[serde(default)]
+    pub compose_service: Option<String>,
+    /// HTTP checks run against a new container once it is healthy (JSON array of SmokeTest)
+    #[serde(default)]
+    pub smoke_tests: Option<String>,
+    /// Deployments the cleanup task keeps for this app (NULL = global setting)
+    #[serde(default)]
+    pub max_deployments: Option<i64>,
+    /// How many of the newest kept deployments keep their image (NULL = all)
+    #[serde(default)]
+    pub keep_images: Option<i64>,
+    /// When the app was locked against deployments (NULL = unlocked)
+    #[serde(default)]
+    pub locked_at: Option<String>,
+    /// Name of the user holding the deployment lock
+    #[serde(default)]
+    pub locked_by: Option<String>,
+    /// Reason given when the deployment lock was taken
+    #[serde(default)]
+    pub lock_reason: Option<String>,
+    /// Commands run in a throwaway container of the built image before it is
+    /// released (JSON array of strings)
+    #[serde(default)]
+    pub test_commands: Option<String>,
+    /// Seconds all test commands may take together (NULL = default)
+    #[serde(default)]
+    pub test_timeout_secs: Option<i64>,
+    /// Hours after creation a preview deployment is torn down (NULL = never)
+    #[serde(default)]
+    pub preview_max_lifetime_hours: Option<i64>,
+    /// Hours without requests after which a preview deployment is torn down
+    /// (NULL = never)
+    #[serde(default)]
+    pub preview_idle_timeout_hours: Option<i64>,
+    /// Container port serving Prometheus metrics (NULL = not scraped)
+    #[serde(default)]
+    pub metrics_port: Option<i32>,
+    /// Path of the metrics endpoint (NULL = /metrics)
+    #[serde(default)]
+    pub metrics_path: Option<String>,
+    /// Database provisioned for each preview deployment ("empty" or
+    /// "schema", NULL = none)
+    #[serde(default)]
+    pub preview_database: Option<String>,
+    /// Managed database preview databases are modelled on
+    #[serde(default)]
+    pub preview_database_source_id: Option<String>,
+    /// Env var receiving the preview database's connection URL
+    /// (NULL = DATABASE_URL)
+    #[serde(default)]
+    pub preview_database_env: Option<String>,
+    /// Minutes without requests after which the app is stopped until the next
+    /// request (NULL = always running)
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<i64>,
+    /// When the app was stopped for inactivity (NULL = awake)
+    #[serde(default)]
+    pub sleeping_since: Option<String>,
+    /// Monthly cap on proxy traffic in GB (NULL = unlimited)
+    #[serde(default)]
+    pub bandwidth_limit_gb: Option<i64>,
+    /// What happens once the cap is exceeded: "notify" or "throttle"
+    #[serde(default = "default_bandwidth_limit_action")]
+    pub bandwidth_limit_action: String,
+    /// Rate the app's domains are throttled to, in kilobits per second
+    /// (NULL = 1000)
+    #[serde(default)]
+    pub bandwidth_throttle_kbps: Option<i64>,
+    /// Month ("YYYY-MM") the cap was last reported exceeded
+    #[serde(default)]
+    pub bandwidth_exceeded_period: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+fn default_egress_policy() -> String {
+    "allow_all".to_string()
+}
+
+fn default_healthcheck_type() -> String {
+    "http".to_string()
+}
+
+fn default_app_type() -> String {
+    APP_TYPE_WEB.to_string()
+}
+
+fn default_healthcheck_window_secs() -> i64 {
+    20
+}
+
+fn default_rolling_max_surge() -> i64 {
+    1
+}
+
+fn default_bandwidth_limit_action() -> String {
+    BANDWIDTH_ACTION_NOTIFY.to_string()
+}
+
 /// Response DTO for App that excludes sensitive fields (password hash)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppResponse {
@@ -306,6 +498,83 @@ pub struct AppResponse {
     pub destination_id: Option<String>,
     /// Custom container labels (JSON array: [{key, value}]) applied at deployment time
     pub custom_labels: Option<String>,
+    /// Build sandbox mode: "none" or "sandboxed" (NULL inherits the team setting)
+    pub build_sandbox: Option<String>,
+    /// Outbound network policy: "allow_all" or "restricted"
+    pub egress_policy: String,
+    /// Hosts and IPv4 CIDRs a restricted app may reach
+    pub egress_allowlist: Vec<String>,
+    /// Health probe: "http", "tcp" or "command"
+    pub healthcheck_type: String,
+    /// Command run in the container for "command" health checks
+    pub healthcheck_command: Option<String>,
+    /// Rolling deploys: new replicas started above replica_count at once
+    pub rolling_max_surge: i64,
+    /// Rolling deploys: old replicas that may leave the pool before their
+    /// replacements are healthy
+    pub rolling_max_unavailable: i64,
+    /// Conditions checked before the container starts
+    pub deploy_wait_conditions: Vec<WaitCondition>,
+    /// "web" (HTTP behind the proxy) or "worker" (background process)
+    pub app_type: String,
+    /// Seconds a new container may take to pass its health check
+    pub healthcheck_window_secs: i64,
+    /// Regex of further branches whose pushes deploy the app
+    pub webhook_branch_regex: Option<String>,
+    /// Regex of commit authors whose pushes don't deploy the app
+    pub webhook_ignore_authors: Option<String>,
+    /// Regex of paths; pushes changing only matching files don't deploy the app
+    pub webhook_ignore_paths: Option<String>,
+    /// Cloud Native Buildpacks configuration (JSON)
+    pub cnb_config: Option<String>,
+    /// JSON array of deployment fence names
+    pub deploy_fences: Option<String>,
+    /// Static site build settings (JSON)
+    pub static_config: Option<String>,
+    /// Compose file of a "docker-compose" app
+    pub compose_file: Option<String>,
+    /// Compose service receiving the app's traffic
+    pub compose_service: Option<String>,
+    /// HTTP checks run against a new container once it is healthy
+    pub smoke_tests: Vec<SmokeTest>,
+    /// Deployments the cleanup task keeps for this app (null = global setting)
+    pub max_deployments: Option<i64>,
+    /// How many of the newest kept deployments keep their image (null = all)
+    pub keep_images: Option<i64>,
+    /// When the app was locked against deployments (null = unlocked)
+    pub locked_at: Option<String>,
+    /// Name of the user holding the deployment lock
+    pub locked_by: Option<String>,
+    /// Reason given when the deployment lock was taken
+    pub lock_reason: Option<String>,
+    /// Test stage commands (JSON array)
+    pub test_commands: Option<String>,
+    /// Seconds all test commands may take together (null = default)
+    pub test_timeout_secs: Option<i64>,
+    /// Hours after creation a preview deployment is torn down (null = never)
+    pub preview_max_lifetime_hours: Option<i64>,
+    /// Hours without requests after which a preview is torn down (null = never)
+    pub preview_idle_timeout_hours: Option<i64>,
+    /// Container port serving Prometheus metrics (null = not scraped)
+    pub metrics_port: Option<i32>,
+    /// Path of the metrics endpoint (null = /metrics)
+    pub metrics_path: Option<String>,
+    /// Database provisioned for each preview ("empty" or "schema", null = none)
+    pub preview_database: Option<String>,
+    /// Managed database preview databases are modelled on
+    pub preview_database_source_id: Option<String>,
+    /// Env var receiving the preview database's connection URL (null = DATABASE_URL)
+    pub preview_database_env: Option<String>,
+    /// Minutes without requests after which the app sleeps (null = always running)
+    pub idle_timeout_minutes: Option<i64>,
+    /// When the app was stopped for inactivity (null = awake)
+    pub sleeping_since: Option<String>,
+    /// Monthly cap on proxy traffic in GB (null = unlimited)
+    pub bandwidth_limit_gb: Option<i64>,
+    /// What happens once the cap is exceeded: "notify" or "throttle"
+    pub bandwidth_limit_action: String,
+    /// Throttled rate in kilobits per second (null = 1000)
+    pub bandwidth_throttle_kbps: Option<i64>,
     /// Stable internal Docker network hostname for this app (derived).
     /// Other containers on the shared `rivetr` network can reach this app at this
     /// hostname.  Always equal to `custom_container_name` or `rivetr-<app-name>`.
@@ -320,6 +589,9 @@ impl From<App> for AppResponse {
             Some(name) if !name.is_empty() => name.to_string(),
             _ => format!("rivetr-{}", app.name),
         };
+        let egress_allowlist = app.get_egress_allowlist();
+        let deploy_wait_conditions = app.get_deploy_wait_conditions();
+        let smoke_tests = app.get_smoke_tests();
         Self {
             id: app.id,
             name: app.name,
@@ -377,7 +649,7 @@ impl From<App> for AppResponse {
             devices: app.devices,
             shm_size: app.shm_size,
             init_process: app.init_process != 0,
-            build_secrets: app.build_secrets,
+            build_secrets: mask_build_secrets(app.build_secrets.as_deref()),
             build_platforms: app.build_platforms,
             docker_cap_drop: app.docker_cap_drop,
             docker_gpus: app.docker_gpus,
@@ -395,6 +667,44 @@ impl From<App> for AppResponse {
             inline_dockerfile: app.inline_dockerfile,
             destination_id: app.destination_id,
             custom_labels: app.custom_labels,
+            build_sandbox: app.build_sandbox,
+            egress_policy: app.egress_policy,
+            egress_allowlist,
+            healthcheck_type: app.healthcheck_type,
+            healthcheck_command: app.healthcheck_command,
+            rolling_max_surge: app.rolling_max_surge,
+            rolling_max_unavailable: app.rolling_max_unavailable,
+            deploy_wait_conditions,
+            app_type: app.app_type,
+            healthcheck_window_secs: app.healthcheck_window_secs,
+            webhook_branch_regex: app.webhook_branch_regex,
+            webhook_ignore_authors: app.webhook_ignore_authors,
+            webhook_ignore_paths: app.webhook_ignore_paths,
+            cnb_config: app.cnb_config,
+            deploy_fences: app.deploy_fences,
+            static_config: app.static_config,
+            compose_file: app.compose_file,
+            compose_service: app.compose_service,
+            smoke_tests,
+            max_deployments: app.max_deployments,
+            keep_images: app.keep_images,
+            locked_at: app.locked_at,
+            locked_by: app.locked_by,
+            lock_reason: app.lock_reason,
+            test_commands: app.test_commands,
+            test_timeout_secs: app.test_timeout_secs,
+            preview_max_lifetime_hours: app.preview_max_lifetime_hours,
+            preview_idle_timeout_hours: app.preview_idle_timeout_hours,
+            metrics_port: app.metrics_port,
+            metrics_path: app.metrics_path,
+            preview_database: app.preview_database,
+            preview_database_source_id: app.preview_database_source_id,
+            preview_database_env: app.preview_database_env,
+            idle_timeout_minutes: app.idle_timeout_minutes,
+            sleeping_since: app.sleeping_since,
+            bandwidth_limit_gb: app.bandwidth_limit_gb,
+            bandwidth_limit_action: app.bandwidth_limit_action,
+            bandwidth_throttle_kbps: app.bandwidth_throttle_kbps,
             internal_hostname,
             created_at: app.created_at,
             updated_at: app.updated_at,
@@ -408,6 +718,11 @@ impl App {
         parse_domains(self.domains.as_deref())
     }
 
+    /// Domains configured for TLS passthrough (raw TLS routed by SNI)
+    pub fn tls_passthrough_domains(&self) -> Vec<String> {
+        tls_passthrough_domains(self.domains.as_deref())
+    }
+
     /// Get the primary domain (from domains list or legacy domain field)
     pub fn get_primary_domain(&self) -> Option<String> {
         // First check the domains array for a primary domain
@@ -435,8 +750,13 @@ impl App {
     /// Get all domain names with optional www redirect targets.
     /// Returns `Vec<(domain, Option<redirect_target>)>` where `redirect_target` is `Some` if the
     /// domain should redirect to another domain (for `www_redirect_mode`).
+    ///
+    /// Workers aren't routed by the proxy, so they have none.
     pub fn get_all_domains_with_redirects(&self) -> Vec<(String, Option<String>)> {
         let mut result: Vec<(String, Option<String>)> = Vec::new();
+        if self.is_worker() {
+            return result;
+        }
 
         let contains = |entries: &Vec<(String, Option<String>)>, d: &str| {
             entries.iter().any(|(name, _)| name == d)
@@ -534,8 +854,11 @@ impl App {
         result
     }
 
-    /// Parse port mappings from JSON string
+    /// Parse port mappings from JSON string. Workers publish no ports.
     pub fn get_port_mappings(&self) -> Vec<PortMapping> {
+        if self.is_worker() {
+            return Vec::new();
+        }
         self.port_mappings
             .as_ref()
             .and_then(|s| serde_json::from_str(s).ok())
@@ -586,6 +909,64 @@ impl App {
             .unwrap_or_default()
     }
 
+    /// Whether this app is a background process rather than an HTTP service
+    pub fn is_worker(&self) -> bool {
+        self.app_type == APP_TYPE_WORKER
+    }
+
+    /// Container port published for the proxy; 0 (none) for workers
+    pub fn container_port(&self) -> u16 {
+        if self.is_worker() {
+            0
+        } else {
+            self.port as u16
+        }
+    }
+
+    /// How the proxy and deployments probe this app's health
+    pub fn health_probe(&self) -> crate::proxy::HealthProbe {
+        crate::proxy::HealthProbe::from_settings(
+            &self.healthcheck_type,
+            self.healthcheck_command.as_deref(),
+        )
+    }
+
+    /// Rolling deploy budget as (max surge, max unavailable). At least one of
+    /// them is non-zero so a rollout always makes progress.
+    pub fn rolling_budget(&self) -> (usize, usize) {
+        let surge = self.rolling_max_surge.max(0) as usize;
+        let unavailable = self.rolling_max_unavailable.max(0) as usize;
+        if surge == 0 && unavailable == 0 {
+            (1, 0)
+        } else {
+            (surge, unavailable)
+        }
+    }
+
+    /// Monthly bandwidth cap in bytes, if the app has one
+    pub fn bandwidth_limit_bytes(&self) -> Option<u64> {
+        self.bandwidth_limit_gb
+            .filter(|gb| *gb > 0)
+            .map(|gb| gb as u64 * 1_000_000_000)
+    }
+
+    /// Bytes per second the app's domains are limited to while throttled
+    pub fn bandwidth_throttle_bytes_per_sec(&self) -> u64 {
+        let kbps = self
+            .bandwidth_throttle_kbps
+            .filter(|k| *k > 0)
+            .unwrap_or(DEFAULT_BANDWIDTH_THROTTLE_KBPS);
+        kbps as u64 * 1000 / 8
+    }
+
+    /// Parse the egress allow-list from JSON string
+    pub fn get_egress_allowlist(&self) -> Vec<String> {
+        self.egress_allowlist
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
     /// Parse pre_deploy_commands JSON into Vec<String>
     pub fn get_pre_deploy_commands(&self) -> Vec<String> {
         self.pre_deploy_commands
@@ -602,6 +983,21 @@ impl App {
             .unwrap_or_default()
     }
 
+    /// Parse test_commands JSON into Vec<String>
+    pub fn get_test_commands(&self) -> Vec<String> {
+        self.test_commands
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Seconds all test commands may take together
+    pub fn test_timeout_secs(&self) -> i64 {
+        self.test_timeout_secs
+            .unwrap_or(DEFAULT_TEST_TIMEOUT_SECS)
+            .clamp(1, MAX_TEST_TIMEOUT_SECS)
+    }
+
     /// Check if this app uses a Docker registry image instead of building from git
     pub fn uses_registry_image(&self) -> bool {
         self.docker_image
@@ -650,6 +1046,14 @@ impl App {
             .unwrap_or_default()
     }
 
+    /// Env var receiving the preview database's connection URL
+    pub fn preview_database_env_key(&self) -> &str {
+        self.preview_database_env
+            .as_deref()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_PREVIEW_DATABASE_ENV)
+    }
+
     /// Get the build type, defaulting to "dockerfile" if empty or not set
     pub fn get_build_type(&self) -> &str {
         self.build_type
@@ -664,6 +1068,11 @@ impl App {
         self.get_build_type() == "nixpacks"
     }
 
+    /// Check if this app deploys the Docker Compose file of its repository
+    pub fn uses_docker_compose(&self) -> bool {
+        self.get_build_type() == "docker-compose"
+    }
+
     /// Parse nixpacks_config JSON into NixpacksConfig
     pub fn get_nixpacks_config(&self) -> Option<NixpacksConfig> {
         self.nixpacks_config
@@ -671,11 +1080,39 @@ impl App {
             .and_then(|s| NixpacksConfig::from_json(s).ok())
     }
 
+    /// Parse static_config JSON into StaticSiteSettings
+    pub fn get_static_site_settings(&self) -> StaticSiteSettings {
+        self.static_config
+            .as_ref()
+            .and_then(|s| StaticSiteSettings::from_json(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse deploy_fences JSON into Vec<String>
+    pub fn get_deploy_fences(&self) -> Vec<String> {
+        self.deploy_fences
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse cnb_config JSON into PackConfig
+    pub fn get_pack_config(&self) -> Option<PackConfig> {
+        self.cnb_config
+            .as_ref()
+            .and_then(|s| PackConfig::from_json(s).ok())
+    }
+
     /// Check if automatic rollback is enabled for this app
     pub fn is_auto_rollback_enabled(&self) -> bool {
         self.auto_rollback_enabled != 0
     }
 
+    /// Health check attempts (2 seconds apart) that fit in the health check window
+    pub fn health_check_attempts(&self) -> u32 {
+        (self.healthcheck_window_secs / 2).clamp(1, MAX_HEALTHCHECK_WINDOW_SECS / 2) as u32
+    }
+
     /// Check if deployment approval is required for this app
     pub fn is_require_approval(&self) -> bool {
         self.require_approval != 0
@@ -715,13 +1152,72 @@ impl App {
             .unwrap_or_default()
     }
 
-    /// Parse build_secrets JSON into Vec<BuildSecret>
+    /// Parse build_secrets JSON into Vec<BuildSecret> (values as stored,
+    /// i.e. encrypted when an encryption key is configured)
     pub fn get_build_secrets(&self) -> Vec<BuildSecret> {
         self.build_secrets
             .as_ref()
             .and_then(|s| serde_json::from_str(s).ok())
             .unwrap_or_default()
     }
+
+    /// Build secrets as plaintext (key, value) pairs for a `BuildContext`.
+    /// Secrets that can't be decrypted are left out.
+    pub fn decrypted_build_secrets(
+        &self,
+        encryption_key: Option<&[u8; 32]>,
+    ) -> Vec<(String, String)> {
+        self.get_build_secrets()
+            .into_iter()
+            .filter(|s| {
+                let valid = BuildSecret::is_valid_key(&s.key);
+                if !valid {
+                    tracing::warn!(app = %self.name, key = %s.key, "Skipping build secret with an invalid key");
+                }
+                valid
+            })
+            .filter_map(
+                |s| match crate::crypto::decrypt_if_encrypted(&s.value, encryption_key) {
+                    Ok(value) => Some((s.key, value)),
+                    Err(e) => {
+                        tracing::warn!(app = %self.name, key = %s.key, "Failed to decrypt build secret: {}", e);
+                        None
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Parse deploy_wait_conditions JSON into Vec<WaitCondition>
+    pub fn get_deploy_wait_conditions(&self) -> Vec<WaitCondition> {
+        self.deploy_wait_conditions
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse smoke_tests JSON into Vec<SmokeTest>
+    pub fn get_smoke_tests(&self) -> Vec<SmokeTest> {
+        self.smoke_tests
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Why deployments of this app are refused, if it is locked
+    pub fn deploy_lock_message(&self) -> Option<String> {
+        let locked_at = self.locked_at.as_deref()?;
+        let mut message = format!(
+            "App is locked by {} since {}",
+            self.locked_by.as_deref().unwrap_or("unknown"),
+            locked_at
+        );
+        if let Some(reason) = self.lock_reason.as_deref().filter(|r| !r.is_empty()) {
+            message.push_str(": ");
+            message.push_str(reason);
+        }
+        Some(message)
+    }
 }
 
 /// A single build-time secret injected via BuildKit `--secret`.
@@ -731,6 +1227,80 @@ pub struct BuildSecret {
     pub value: String,
 }
 
+impl BuildSecret {
+    /// Keys name the secret's tmpfile and its BuildKit id, so they are limited
+    /// to `[A-Za-z0-9_.-]` and can't be `.` or `..`
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key != "."
+            && key != ".."
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    }
+}
+
+/// Shown instead of build secret values in API responses. Sent back
+/// unchanged in an update, it keeps the stored value of that key.
+pub const BUILD_SECRET_MASK: &str = "********";
+
+/// The stored build_secrets JSON with every value replaced by `BUILD_SECRET_MASK`
+pub fn mask_build_secrets(stored: Option<&str>) -> Option<String> {
+    let secrets: Vec<BuildSecret> = serde_json::from_str(stored?).ok()?;
+    let masked: Vec<BuildSecret> = secrets
+        .into_iter()
+        .map(|s| BuildSecret {
+            key: s.key,
+            value: BUILD_SECRET_MASK.to_string(),
+        })
+        .collect();
+    serde_json::to_string(&masked).ok()
+}
+
+/// A dependency a deployment waits for before starting its container, e.g.
+/// `{"type": "database", "database_id": "...", "timeout_seconds": 120}` or
+/// `{"type": "http", "url": "http://rivetr-api:3000/health"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WaitCondition {
+    #[serde(flatten)]
+    pub target: WaitTarget,
+    /// How long to wait before failing the deployment
+    #[serde(default = "default_wait_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+/// What a [`WaitCondition`] waits for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WaitTarget {
+    /// A managed database is running and accepting connections
+    Database { database_id: String },
+    /// A URL (e.g. another app's health endpoint) returns 200
+    Http { url: String },
+}
+
+fn default_wait_timeout_seconds() -> u64 {
+    120
+}
+
+/// An HTTP check a new container must pass after its health check, e.g.
+/// `{"path": "/api/status", "expected_status": 200, "expected_body": "ok"}`.
+/// A failure fails the deployment (and triggers auto-rollback, if enabled).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SmokeTest {
+    /// Path requested from the container, starting with "/"
+    pub path: String,
+    #[serde(default = "default_smoke_test_status")]
+    pub expected_status: u16,
+    /// Text the response body must contain
+    #[serde(default)]
+    pub expected_body: Option<String>,
+}
+
+fn default_smoke_test_status() -> u16 {
+    200
+}
+
 // DTOs for API
 
 #[derive(Debug, Deserialize)]
@@ -746,6 +1316,9 @@ pub struct CreateAppRequest {
     pub domain: Option<String>,
     #[serde(default = "default_port")]
     pub port: i32,
+    /// "web" (default) or "worker"; workers need no port or domain
+    #[serde(default = "default_app_type")]
+    pub app_type: String,
     pub healthcheck: Option<String>,
     pub memory_limit: Option<String>,
     pub cpu_limit: Option<String>,
@@ -792,6 +1365,8 @@ pub struct CreateAppRequest {
     pub nixpacks_config: Option<serde_json::Value>,
     /// Publish directory for static site builds (e.g., "dist", "build", "out")
     pub publish_directory: Option<String>,
+    /// Static site build settings (build command, builder image, SPA mode)
+    pub static_config: Option<StaticSiteSettings>,
     /// Enable PR preview deployments
     #[serde(default)]
     pub preview_enabled: bool,
@@ -985,6 +1560,74 @@ pub struct UpdateAppRequest {
     pub destination_id: Option<String>,
     /// Custom container labels (JSON string: [{key, value}]) — set to empty string to clear
     pub custom_labels: Option<String>,
+    /// Build sandbox mode: "none" or "sandboxed" — set to empty string to inherit the team setting
+    pub build_sandbox: Option<String>,
+    /// Outbound network policy: "allow_all" or "restricted"
+    pub egress_policy: Option<String>,
+    /// Hosts and IPv4 CIDRs a restricted app may reach — set to empty array to clear
+    pub egress_allowlist: Option<Vec<String>>,
+    /// Health probe: "http", "tcp" or "command"
+    pub healthcheck_type: Option<String>,
+    /// Command run in the container for "command" health checks — set to empty string to clear
+    pub healthcheck_command: Option<String>,
+    /// Conditions checked before the container starts — set to empty array to clear
+    pub deploy_wait_conditions: Option<Vec<WaitCondition>>,
+    /// "web" or "worker"
+    pub app_type: Option<String>,
+    /// Seconds a new container may take to pass its health check
+    pub healthcheck_window_secs: Option<i64>,
+    /// Regex of further branches whose pushes deploy the app — set to empty string to clear
+    pub webhook_branch_regex: Option<String>,
+    /// Regex of commit authors whose pushes are ignored — set to empty string to clear
+    pub webhook_ignore_authors: Option<String>,
+    /// Regex of paths only changed by ignored pushes — set to empty string to clear
+    pub webhook_ignore_paths: Option<String>,
+    /// Cloud Native Buildpacks configuration — set to an empty object to clear
+    pub cnb_config: Option<serde_json::Value>,
+    /// Deployment fences shared with other apps — empty array to clear
+    pub deploy_fences: Option<Vec<String>>,
+    /// Static site build settings — set to an empty object to clear
+    pub static_config: Option<StaticSiteSettings>,
+    /// Compose file of a "docker-compose" app — set to empty string to clear
+    pub compose_file: Option<String>,
+    /// Compose service receiving the app's traffic — set to empty string to clear
+    pub compose_service: Option<String>,
+    /// HTTP checks run once the new container is healthy — set to empty array to clear
+    pub smoke_tests: Option<Vec<SmokeTest>>,
+    /// Deployments the cleanup task keeps for this app (0/negative clears to the global setting)
+    pub max_deployments: Option<i64>,
+    /// How many of the newest kept deployments keep their image (0/negative clears to all)
+    pub keep_images: Option<i64>,
+    /// Test stage commands run against the built image — set to empty array to clear
+    pub test_commands: Option<Vec<String>>,
+    /// Seconds all test commands may take together (0/negative clears to the default)
+    pub test_timeout_secs: Option<i64>,
+    /// Hours after creation a preview is torn down (0/negative clears the limit)
+    pub preview_max_lifetime_hours: Option<i64>,
+    /// Hours without requests after which a preview is torn down (0/negative clears the limit)
+    pub preview_idle_timeout_hours: Option<i64>,
+    /// Container port serving Prometheus metrics (0/negative stops scraping)
+    pub metrics_port: Option<i32>,
+    /// Path of the metrics endpoint — set to empty string to clear
+    pub metrics_path: Option<String>,
+    /// Database provisioned for each preview ("empty" or "schema") — set to
+    /// empty string to stop provisioning
+    pub preview_database: Option<String>,
+    /// Managed database preview databases are modelled on — set to empty
+    /// string to clear
+    pub preview_database_source_id: Option<String>,
+    /// Env var receiving the preview database's connection URL — set to empty
+    /// string to clear back to DATABASE_URL
+    pub preview_database_env: Option<String>,
+    /// Minutes without requests after which the app sleeps (0/negative keeps
+    /// it always running)
+    pub idle_timeout_minutes: Option<i64>,
+    /// Monthly cap on proxy traffic in GB (0/negative removes the cap)
+    pub bandwidth_limit_gb: Option<i64>,
+    /// What happens once the cap is exceeded: "notify" or "throttle"
+    pub bandwidth_limit_action: Option<String>,
+    /// Throttled rate in kilobits per second (0/negative uses the default)
+    pub bandwidth_throttle_kbps: Option<i64>,
 }
 
 /// Request specifically for updating domains
@@ -1058,3 +1701,60 @@ pub struct AppWithSharing {
     /// The team that owns this app (when is_shared is true)
     pub owner_team_name: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_build_secrets_hides_values() {
+        let stored = r#"[{"key":"NPM_TOKEN","value":"ENC:abc"},{"key":"API_KEY","value":"plain"}]"#;
+        let masked: Vec<BuildSecret> =
+            serde_json::from_str(&mask_build_secrets(Some(stored)).unwrap()).unwrap();
+        assert_eq!(masked.len(), 2);
+        assert_eq!(masked[0].key, "NPM_TOKEN");
+        assert!(masked.iter().all(|s| s.value == BUILD_SECRET_MASK));
+        assert_eq!(mask_build_secrets(None), None);
+    }
+
+    #[test]
+    fn test_build_secret_keys_cannot_leave_their_tmpfile() {
+        for key in ["NPM_TOKEN", "npm-token", "pip.conf", "a_b.c-d"] {
+            assert!(BuildSecret::is_valid_key(key), "{}", key);
+        }
+        for key in ["", ".", "..", "../etc/passwd", "a/b", "id,src=x", "A B"] {
+            assert!(!BuildSecret::is_valid_key(key), "{}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_check_attempts_follow_the_window() {
+        let (_dir, db) = crate::db::test_pool().await;
+        let mut app = crate::db::load_test_app(&db, &crate::db::test_app(&db, "web").await).await;
+        assert_eq!(app.healthcheck_window_secs, 20);
+        assert_eq!(app.health_check_attempts(), 10);
+
+        for (secs, attempts) in [(10, 5), (45, 22), (600, 300), (0, 1), (100_000, 300)] {
+            app.healthcheck_window_secs = secs;
+            assert_eq!(app.health_check_attempts(), attempts, "{}", secs);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_test_stage_settings() {
+        let (_dir, db) = crate::db::test_pool().await;
+        let mut app = crate::db::load_test_app(&db, &crate::db::test_app(&db, "web").await).await;
+        assert!(app.get_test_commands().is_empty());
+        assert_eq!(app.test_timeout_secs(), DEFAULT_TEST_TIMEOUT_SECS);
+
+        app.test_commands = Some(r#"["npm test","./smoke.sh"]"#.to_string());
+        assert_eq!(app.get_test_commands(), vec!["npm test", "./smoke.sh"]);
+        app.test_commands = Some("not json".to_string());
+        assert!(app.get_test_commands().is_empty());
+
+        for (secs, clamped) in [(90, 90), (0, 1), (10_000, MAX_TEST_TIMEOUT_SECS)] {
+            app.test_timeout_secs = Some(secs);
+            assert_eq!(app.test_timeout_secs(), clamped);
+        }
+    }
+}
@@ -74,11 +74,17 @@ pub mod actions {
     pub const APP_START: &str = "app.start";
     pub const APP_STOP: &str = "app.stop";
     pub const APP_RESTART: &str = "app.restart";
+    pub const APP_SCALE: &str = "app.scale";
+    pub const APP_LOCK: &str = "app.lock";
+    pub const APP_UNLOCK: &str = "app.unlock";
 
     // Deployment actions
     pub const DEPLOYMENT_TRIGGER: &str = "deployment.trigger";
     pub const DEPLOYMENT_ROLLBACK: &str = "deployment.rollback";
     pub const DEPLOYMENT_CANCEL: &str = "deployment.cancel";
+    pub const DEPLOYMENT_PROMOTE: &str = "deployment.promote";
+    pub const DEPLOYMENT_APPROVE: &str = "deployment.approve";
+    pub const DEPLOYMENT_REJECT: &str = "deployment.reject";
 
     // Database actions
     pub const DATABASE_CREATE: &str = "database.create";
@@ -127,6 +133,7 @@ pub mod actions {
     pub const SSH_KEY_CREATE: &str = "ssh_key.create";
     pub const SSH_KEY_UPDATE: &str = "ssh_key.update";
     pub const SSH_KEY_DELETE: &str = "ssh_key.delete";
+    pub const SSH_KEY_TEST: &str = "ssh_key.test";
 
     // GitHub App actions
     pub const GITHUB_APP_CREATE: &str = "github_app.create";
@@ -150,6 +157,15 @@ pub mod actions {
     pub const NOTIFICATION_CHANNEL_CREATE: &str = "notification_channel.create";
     pub const NOTIFICATION_CHANNEL_UPDATE: &str = "notification_channel.update";
     pub const NOTIFICATION_CHANNEL_DELETE: &str = "notification_channel.delete";
+
+    // Fault injection actions
+    pub const CHAOS_ENABLE: &str = "chaos.enable";
+    pub const CHAOS_DISABLE: &str = "chaos.disable";
+
+    // Host maintenance actions
+    pub const HOST_CORDON: &str = "host.cordon";
+    pub const HOST_DRAIN: &str = "host.drain";
+    pub const HOST_UNCORDON: &str = "host.uncordon";
 }
 
 /// Common resource types
@@ -168,6 +184,8 @@ pub mod resource_types {
     pub const NOTIFICATION_CHANNEL: &str = "notification_channel";
     pub const TOKEN: &str = "token";
     pub const DOMAIN: &str = "domain";
+    pub const CHAOS: &str = "chaos";
+    pub const HOST: &str = "host";
 }
 
 /// Log an audit event to the database
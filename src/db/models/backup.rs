@@ -182,12 +182,14 @@ pub struct DatabaseBackupSchedule {
     pub next_run_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// IANA timezone `schedule_hour`/`schedule_day` are in (NULL = UTC)
+    pub timezone: Option<String>,
 }
 
 impl DatabaseBackupSchedule {
     pub fn new(database_id: &str, schedule_type: ScheduleType) -> Self {
         let now = chrono::Utc::now();
-        let next_run = Self::calculate_next_run(&schedule_type, 2, None, &now);
+        let next_run = Self::calculate_next_run(&schedule_type, 2, None, None, &now);
 
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -201,6 +203,7 @@ impl DatabaseBackupSchedule {
             next_run_at: Some(next_run.to_rfc3339()),
             created_at: now.to_rfc3339(),
             updated_at: now.to_rfc3339(),
+            timezone: None,
         }
     }
 
@@ -232,15 +235,20 @@ impl DatabaseBackupSchedule {
         }
     }
 
+    /// Next run after `from`, with `hour` and `day` taken in `timezone` (UTC
+    /// when `None` or unknown)
     pub fn calculate_next_run(
         schedule_type: &ScheduleType,
         hour: i32,
         day: Option<i32>,
+        timezone: Option<&str>,
         from: &chrono::DateTime<chrono::Utc>,
     ) -> chrono::DateTime<chrono::Utc> {
         use chrono::{Datelike, Duration, Timelike};
 
-        match schedule_type {
+        let tz = crate::utils::cron::parse_timezone(timezone).unwrap_or(chrono_tz::UTC);
+        let from = &from.with_timezone(&tz);
+        let next = match schedule_type {
             ScheduleType::Hourly => {
                 // Next hour
                 *from + Duration::hours(1)
@@ -286,7 +294,8 @@ impl DatabaseBackupSchedule {
                     .unwrap_or(next);
                 next
             }
-        }
+        };
+        next.with_timezone(&chrono::Utc)
     }
 
     pub fn update_next_run(&mut self) {
@@ -297,6 +306,7 @@ impl DatabaseBackupSchedule {
                 &self.get_schedule_type(),
                 self.schedule_hour,
                 self.schedule_day,
+                self.timezone.as_deref(),
                 &now,
             )
             .to_rfc3339(),
@@ -317,6 +327,7 @@ pub struct DatabaseBackupScheduleResponse {
     pub retention_count: i32,
     pub last_run_at: Option<String>,
     pub next_run_at: Option<String>,
+    pub timezone: Option<String>,
     pub created_at: String,
 }
 
@@ -333,6 +344,7 @@ impl From<DatabaseBackupSchedule> for DatabaseBackupScheduleResponse {
             retention_count: schedule.retention_count,
             last_run_at: schedule.last_run_at,
             next_run_at: schedule.next_run_at,
+            timezone: schedule.timezone,
             created_at: schedule.created_at,
         }
     }
@@ -346,4 +358,6 @@ pub struct CreateBackupScheduleRequest {
     pub schedule_hour: Option<i32>,
     pub schedule_day: Option<i32>,
     pub retention_count: Option<i32>,
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
 }
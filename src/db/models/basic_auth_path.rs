@@ -0,0 +1,115 @@
+//! Path-scoped HTTP Basic Auth models and DTOs.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Basic auth credentials protecting a path prefix of an app.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppBasicAuthPath {
+    pub id: String,
+    pub app_id: String,
+    /// Normalized path prefix, e.g. `/admin`
+    pub path_prefix: String,
+    pub username: String,
+    /// Argon2 password hash (never returned by the API)
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    /// 1 = enabled, 0 = disabled
+    pub is_enabled: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppBasicAuthPath {
+    /// Load the enabled path rules for an app, converted for the proxy route table.
+    pub async fn list_enabled_for_proxy(
+        db: &SqlitePool,
+        app_id: &str,
+    ) -> Result<Vec<PathBasicAuth>, sqlx::Error> {
+        let rules: Vec<AppBasicAuthPath> = sqlx::query_as(
+            "SELECT * FROM app_basic_auth_paths WHERE app_id = ? AND is_enabled = 1 \
+             ORDER BY path_prefix ASC",
+        )
+        .bind(app_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(rules.into_iter().map(PathBasicAuth::from).collect())
+    }
+}
+
+// ---- DTOs ----
+
+/// Request to protect a path prefix with basic auth.
+#[derive(Debug, Deserialize)]
+pub struct CreateBasicAuthPathRequest {
+    pub path_prefix: String,
+    pub username: String,
+    /// Password in plain text - will be hashed before storing
+    pub password: String,
+    /// Whether the rule is active. Defaults to true.
+    #[serde(default = "default_enabled")]
+    pub is_enabled: bool,
+}
+
+/// Request to update a path-scoped basic auth rule.
+/// Omitting `password` keeps the current one.
+#[derive(Debug, Deserialize)]
+pub struct UpdateBasicAuthPathRequest {
+    pub path_prefix: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub is_enabled: Option<bool>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A lightweight version of the rule used in the proxy route table.
+#[derive(Debug, Clone)]
+pub struct PathBasicAuth {
+    /// Normalized path prefix
+    pub path_prefix: String,
+    pub username: String,
+    /// Argon2 password hash
+    pub password_hash: String,
+}
+
+impl PathBasicAuth {
+    /// Whether the request path falls under this rule's prefix. `/admin`
+    /// matches `/admin` and `/admin/users`, but not `/administrator`.
+    pub fn matches(&self, path: &str) -> bool {
+        if self.path_prefix == "/" {
+            return true;
+        }
+        match path.strip_prefix(self.path_prefix.as_str()) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+impl From<AppBasicAuthPath> for PathBasicAuth {
+    fn from(rule: AppBasicAuthPath) -> Self {
+        Self {
+            path_prefix: rule.path_prefix,
+            username: rule.username,
+            password_hash: rule.password_hash,
+        }
+    }
+}
+
+/// Normalize a path prefix: leading slash, no trailing slash (except `/`).
+/// Returns `None` for prefixes containing query strings, fragments or whitespace.
+pub fn normalize_path_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim();
+    if prefix.is_empty() || prefix.contains(['?', '#']) || prefix.chars().any(char::is_whitespace) {
+        return None;
+    }
+    let trimmed = prefix.trim_matches('/');
+    if trimmed.is_empty() {
+        return Some("/".to_string());
+    }
+    Some(format!("/{}", trimmed))
+}
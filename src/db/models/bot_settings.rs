@@ -0,0 +1,88 @@
+//! Per-app bot/crawler management settings.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::proxy::bots::BotPolicy;
+
+/// Never touch robots.txt; the app serves its own.
+pub const ROBOTS_MODE_OFF: &str = "off";
+/// Deny robots on preview deployments and non-production environments.
+pub const ROBOTS_MODE_AUTO: &str = "auto";
+/// Always deny robots.
+pub const ROBOTS_MODE_ALWAYS: &str = "always";
+
+/// All accepted `robots_mode` values.
+pub const ROBOTS_MODES: &[&str] = &[ROBOTS_MODE_OFF, ROBOTS_MODE_AUTO, ROBOTS_MODE_ALWAYS];
+
+/// Bot handling settings for an app.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppBotSettings {
+    pub app_id: String,
+    /// One of `off`, `auto`, `always`
+    pub robots_mode: String,
+    /// 1 = challenge known bad bots, 0 = pass them through
+    pub challenge_bad_bots: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppBotSettings {
+    /// Default settings for an app with no row yet.
+    pub fn default_for(app_id: &str) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            app_id: app_id.to_string(),
+            robots_mode: ROBOTS_MODE_OFF.to_string(),
+            challenge_bad_bots: 0,
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_bot_settings WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// The proxy policy for a route of this app, or `None` when nothing applies.
+    /// `environment` is the app's environment; `is_preview` is true for PR preview routes.
+    pub fn to_policy(&self, environment: &str, is_preview: bool) -> Option<BotPolicy> {
+        let deny_robots = match self.robots_mode.as_str() {
+            ROBOTS_MODE_ALWAYS => true,
+            ROBOTS_MODE_AUTO => is_preview || environment != "production",
+            _ => false,
+        };
+        let policy = BotPolicy {
+            deny_robots,
+            challenge_bad_bots: self.challenge_bad_bots != 0,
+        };
+        policy.is_active().then_some(policy)
+    }
+
+    /// Load the proxy policy for a route of an app.
+    pub async fn load_policy(
+        db: &SqlitePool,
+        app_id: &str,
+        is_preview: bool,
+    ) -> Result<Option<BotPolicy>, sqlx::Error> {
+        let Some(settings) = Self::get(db, app_id).await? else {
+            return Ok(None);
+        };
+        let environment: Option<String> =
+            sqlx::query_scalar("SELECT environment FROM apps WHERE id = ?")
+                .bind(app_id)
+                .fetch_optional(db)
+                .await?;
+        Ok(settings.to_policy(environment.as_deref().unwrap_or("production"), is_preview))
+    }
+}
+
+/// Request to update bot settings for an app.
+#[derive(Debug, Deserialize)]
+pub struct UpdateBotSettingsRequest {
+    pub robots_mode: Option<String>,
+    pub challenge_bad_bots: Option<bool>,
+}
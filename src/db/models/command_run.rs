@@ -0,0 +1,75 @@
+//! One-off command runs: ad-hoc commands run in a fresh container started from
+//! the app's image.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Run status while the container is going
+pub const COMMAND_RUN_RUNNING: &str = "running";
+
+/// A command run once in a fresh container from the image of the app's running
+/// deployment
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommandRun {
+    pub id: String,
+    pub app_id: String,
+    /// Shell command (run with `/bin/sh -c`)
+    pub command: String,
+    /// A run still going after this long is killed and marked failed
+    pub timeout_seconds: i64,
+    /// `running`, `success` or `failed`
+    pub status: String,
+    /// Image the command ran in
+    pub image: Option<String>,
+    /// Set while the container exists
+    pub container_id: Option<String>,
+    pub exit_code: Option<i64>,
+    /// Combined stdout/stderr of the container (tail)
+    pub output: Option<String>,
+    pub error_message: Option<String>,
+    pub started_by: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl CommandRun {
+    pub async fn get(
+        db: &SqlitePool,
+        app_id: &str,
+        run_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM command_runs WHERE id = ? AND app_id = ?")
+            .bind(run_id)
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Most recent runs of an app, newest first
+    pub async fn list_for_app(
+        db: &SqlitePool,
+        app_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM command_runs WHERE app_id = ? ORDER BY started_at DESC LIMIT ?",
+        )
+        .bind(app_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == COMMAND_RUN_RUNNING
+    }
+}
+
+/// Request to run a one-off command
+#[derive(Debug, Deserialize)]
+pub struct CreateCommandRunRequest {
+    pub command: String,
+    /// Defaults to the scheduled task default (1 hour)
+    pub timeout_seconds: Option<i64>,
+}
@@ -16,6 +16,10 @@ pub struct Domain {
     /// WWW redirect mode: "both" (allow both), "to_www" (redirect non-www → www),
     /// "to_non_www" (redirect www → non-www). Overrides `redirect_www` when set.
     pub www_redirect_mode: Option<String>,
+    /// Route raw TLS for this domain to the app by SNI without terminating it.
+    /// The app must serve TLS on its port and manage its own certificates.
+    #[serde(default)]
+    pub tls_passthrough: bool,
 }
 
 impl Domain {
@@ -25,6 +29,7 @@ impl Domain {
             primary: false,
             redirect_www: false,
             www_redirect_mode: None,
+            tls_passthrough: false,
         }
     }
 
@@ -34,6 +39,7 @@ impl Domain {
             primary: true,
             redirect_www: false,
             www_redirect_mode: None,
+            tls_passthrough: false,
         }
     }
 
@@ -87,6 +93,15 @@ pub fn parse_domains(json: Option<&str>) -> Vec<Domain> {
         .unwrap_or_default()
 }
 
+/// Names of the domains in a domains JSON column that use TLS passthrough
+pub fn tls_passthrough_domains(json: Option<&str>) -> Vec<String> {
+    parse_domains(json)
+        .into_iter()
+        .filter(|d| d.tls_passthrough)
+        .map(|d| d.domain)
+        .collect()
+}
+
 /// Helper to serialize domains to JSON for database
 pub fn serialize_domains(domains: &[Domain]) -> Option<String> {
     if domains.is_empty() {
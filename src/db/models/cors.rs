@@ -0,0 +1,103 @@
+//! Per-app CORS settings enforced by the proxy.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::proxy::cors::{CorsPolicy, DEFAULT_ALLOWED_METHODS, DEFAULT_MAX_AGE_SECS};
+
+/// CORS settings for an app.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppCorsSettings {
+    pub app_id: String,
+    pub enabled: i32,
+    /// Allowed origins (`*`, exact origins or `https://*.example.com`),
+    /// separated by newlines or commas
+    pub allowed_origins: String,
+    /// Allowed request methods
+    pub allowed_methods: String,
+    /// Allowed request headers. Empty allows the headers a preflight asks for.
+    pub allowed_headers: String,
+    /// Response headers exposed to the browser
+    pub expose_headers: String,
+    /// 1 = allow cookies and HTTP auth on cross-origin requests
+    pub allow_credentials: i32,
+    /// How long browsers may cache a preflight response
+    pub max_age_secs: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppCorsSettings {
+    /// Default (disabled) settings for an app with no row yet.
+    pub fn default_for(app_id: &str) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            app_id: app_id.to_string(),
+            enabled: 0,
+            allowed_origins: String::new(),
+            allowed_methods: DEFAULT_ALLOWED_METHODS.join(","),
+            allowed_headers: String::new(),
+            expose_headers: String::new(),
+            allow_credentials: 0,
+            max_age_secs: DEFAULT_MAX_AGE_SECS as i64,
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_cors WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// The proxy policy for the app's routes, or `None` when disabled.
+    pub fn to_policy(&self) -> Option<CorsPolicy> {
+        if self.enabled == 0 {
+            return None;
+        }
+        Some(CorsPolicy {
+            allowed_origins: split_list(&self.allowed_origins),
+            allowed_methods: split_list(&self.allowed_methods)
+                .into_iter()
+                .map(|m| m.to_ascii_uppercase())
+                .collect(),
+            allowed_headers: split_list(&self.allowed_headers)
+                .into_iter()
+                .map(|h| h.to_ascii_lowercase())
+                .collect(),
+            expose_headers: split_list(&self.expose_headers),
+            allow_credentials: self.allow_credentials != 0,
+            max_age_secs: self.max_age_secs.max(0) as u64,
+        })
+    }
+
+    /// Load the proxy policy for an app.
+    pub async fn load_policy(
+        db: &SqlitePool,
+        app_id: &str,
+    ) -> Result<Option<CorsPolicy>, sqlx::Error> {
+        Ok(Self::get(db, app_id).await?.and_then(|s| s.to_policy()))
+    }
+}
+
+/// Entries of a newline/comma separated list, trimmed, without blanks.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(['\n', ','])
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// Request to update CORS settings for an app.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCorsSettingsRequest {
+    pub enabled: Option<bool>,
+    pub allowed_origins: Option<String>,
+    pub allowed_methods: Option<String>,
+    pub allowed_headers: Option<String>,
+    pub expose_headers: Option<String>,
+    pub allow_credentials: Option<bool>,
+    pub max_age_secs: Option<i64>,
+}
@@ -53,6 +53,22 @@ impl std::str::FromStr for DatabaseType {
     }
 }
 
+impl DatabaseType {
+    /// Whether databases of this type can have a seed script or dump
+    pub fn supports_seeding(&self) -> bool {
+        matches!(
+            self,
+            Self::Postgres | Self::Mysql | Self::Mariadb | Self::Mongodb
+        )
+    }
+
+    /// Whether the schema of databases of this type can be copied without
+    /// their rows
+    pub fn supports_schema_copy(&self) -> bool {
+        matches!(self, Self::Postgres | Self::Mysql | Self::Mariadb)
+    }
+}
+
 impl From<String> for DatabaseType {
     fn from(s: String) -> Self {
         s.parse().unwrap_or(Self::Postgres)
@@ -157,6 +173,22 @@ pub struct ManagedDatabase {
     /// JSON array of SQL command strings to execute after the database first starts.
     /// Example: ["CREATE EXTENSION IF NOT EXISTS postgis;", "CREATE SCHEMA app;"]
     pub init_commands: Option<String>,
+    /// Script (SQL, or JavaScript for MongoDB) loaded once the database is
+    /// first provisioned
+    #[sqlx(default)]
+    pub seed_script: Option<String>,
+    /// File name of the uploaded seed dump, restored after `seed_script`
+    #[sqlx(default)]
+    pub seed_dump_file: Option<String>,
+    /// Whether environment clones of this database copy its seed
+    #[sqlx(default)]
+    pub seed_on_clone: i32,
+    /// When the seed was loaded; a seed is loaded at most once
+    #[sqlx(default)]
+    pub seeded_at: Option<String>,
+    /// Error of the last failed seeding attempt
+    #[sqlx(default)]
+    pub seed_error: Option<String>,
 }
 
 impl ManagedDatabase {
@@ -180,6 +212,19 @@ impl ManagedDatabase {
         self.public_access != 0
     }
 
+    /// Whether a seed script or dump is attached
+    pub fn has_seed(&self) -> bool {
+        self.seed_script
+            .as_deref()
+            .is_some_and(|s| !s.trim().is_empty())
+            || self.seed_dump_file.is_some()
+    }
+
+    /// Whether the attached seed still has to be loaded
+    pub fn needs_seed(&self) -> bool {
+        self.has_seed() && self.seeded_at.is_none()
+    }
+
     /// Check if SSL is enabled
     pub fn is_ssl_enabled(&self) -> bool {
         self.ssl_enabled != 0
@@ -345,6 +390,11 @@ pub struct ManagedDatabaseResponse {
     pub custom_image: Option<String>,
     /// JSON array of SQL init commands to run after first start
     pub init_commands: Option<String>,
+    pub seed_script: Option<String>,
+    pub seed_dump_file: Option<String>,
+    pub seed_on_clone: bool,
+    pub seeded_at: Option<String>,
+    pub seed_error: Option<String>,
 }
 
 impl ManagedDatabase {
@@ -394,6 +444,11 @@ impl ManagedDatabase {
             ssl_mode: self.ssl_mode.clone(),
             custom_image: self.custom_image.clone(),
             init_commands: self.init_commands.clone(),
+            seed_script: self.seed_script.clone(),
+            seed_dump_file: self.seed_dump_file.clone(),
+            seed_on_clone: self.seed_on_clone != 0,
+            seeded_at: self.seeded_at.clone(),
+            seed_error: self.seed_error.clone(),
         }
     }
 }
@@ -429,6 +484,11 @@ pub struct CreateManagedDatabaseRequest {
     pub custom_image: Option<String>,
     /// JSON array of SQL command strings to execute after the database first starts
     pub init_commands: Option<String>,
+    /// Seed script loaded once the database is first provisioned
+    pub seed_script: Option<String>,
+    /// Copy the seed to environment clones of this database
+    #[serde(default)]
+    pub seed_on_clone: bool,
 }
 
 fn default_db_version() -> String {
@@ -457,4 +517,8 @@ pub struct UpdateManagedDatabaseRequest {
     pub init_commands: Option<String>,
     /// Move the database to a project (empty string = unassign / no project)
     pub project_id: Option<String>,
+    /// Seed script (empty string = remove)
+    pub seed_script: Option<String>,
+    /// Copy the seed to environment clones of this database
+    pub seed_on_clone: Option<bool>,
 }
@@ -1,7 +1,7 @@
 //! Deployment models and DTOs.
 
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, SqlitePool};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -53,9 +53,29 @@ pub struct Deployment {
     pub app_id: String,
     pub commit_sha: Option<String>,
     pub commit_message: Option<String>,
+    /// Commit author name, from the push webhook or the cloned HEAD
+    #[sqlx(default)]
+    #[serde(default)]
+    pub commit_author: Option<String>,
+    /// Branch the commit was pushed to
+    #[sqlx(default)]
+    #[serde(default)]
+    pub commit_branch: Option<String>,
+    /// Link to the provider's compare view for the pushed range
+    #[sqlx(default)]
+    #[serde(default)]
+    pub commit_compare_url: Option<String>,
     pub status: String,
     pub container_id: Option<String>,
     pub error_message: Option<String>,
+    /// Recognized failure cause, e.g. `clone_auth_failed` (see `engine::failure`)
+    #[sqlx(default)]
+    #[serde(default)]
+    pub error_code: Option<String>,
+    /// Remediation text for `error_code`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub error_hint: Option<String>,
     pub started_at: String,
     pub finished_at: Option<String>,
     /// Timestamp when the deployment went live (status -> 'running'). Set once
@@ -70,6 +90,11 @@ pub struct Deployment {
     /// Whether this deployment was an automatic rollback triggered by health check failure
     #[serde(default)]
     pub is_auto_rollback: i32,
+    /// Set on a failed deployment whose previous version was restored by an
+    /// automatic rollback
+    #[sqlx(default)]
+    #[serde(default)]
+    pub rolled_back: i32,
     /// Git tag name when deploying from a specific tag
     #[sqlx(default)]
     pub git_tag: Option<String>,
@@ -95,12 +120,174 @@ pub struct Deployment {
     /// How the deployment was initiated: 'manual', 'webhook', 'rollback', 'restart', 'scheduled'
     #[sqlx(default)]
     pub trigger: Option<String>,
+    /// Output of the test stage commands (tail, when long)
+    #[sqlx(default)]
+    #[serde(default)]
+    pub test_output: Option<String>,
+    /// Versioned reference of the built image, `rivetr/<app>:<short-sha>`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub version_tag: Option<String>,
 }
 
 impl Deployment {
     pub fn status_enum(&self) -> DeploymentStatus {
         DeploymentStatus::from(self.status.clone())
     }
+
+    /// Mark an app's running deployments other than `keep_id` as `replaced`,
+    /// recording the transition on each deployment's timeline.
+    pub async fn mark_others_replaced(
+        db: &SqlitePool,
+        app_id: &str,
+        keep_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let replaced: Vec<String> = sqlx::query_scalar(
+            "UPDATE deployments SET status = 'replaced', finished_at = ? \
+             WHERE app_id = ? AND status = 'running' AND id != ? RETURNING id",
+        )
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(app_id)
+        .bind(keep_id)
+        .fetch_all(db)
+        .await?;
+
+        DeploymentEvent::record_all(db, &replaced, "replaced", None).await;
+        Ok(())
+    }
+}
+
+/// A deployment status transition, recorded for the deployment timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeploymentEvent {
+    pub id: i64,
+    pub deployment_id: String,
+    /// The status the deployment moved to
+    pub status: String,
+    /// Optional detail, e.g. the error for a `failed` transition
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+impl DeploymentEvent {
+    /// Record a status transition. Failures are logged rather than returned:
+    /// the timeline is informational and must never fail a deployment.
+    pub async fn record(db: &SqlitePool, deployment_id: &str, status: &str, message: Option<&str>) {
+        let result = sqlx::query(
+            "INSERT INTO deployment_events (deployment_id, status, message, created_at) \
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(deployment_id)
+        .bind(status)
+        .bind(message)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(db)
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!(
+                deployment_id = %deployment_id,
+                status = %status,
+                error = %e,
+                "Failed to record deployment event"
+            );
+        }
+    }
+
+    /// Record the same transition for several deployments (bulk status updates).
+    pub async fn record_all(
+        db: &SqlitePool,
+        deployment_ids: &[String],
+        status: &str,
+        message: Option<&str>,
+    ) {
+        for id in deployment_ids {
+            Self::record(db, id, status, message).await;
+        }
+    }
+
+    /// All transitions of a deployment, oldest first.
+    pub async fn list(db: &SqlitePool, deployment_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM deployment_events WHERE deployment_id = ? ORDER BY id ASC")
+            .bind(deployment_id)
+            .fetch_all(db)
+            .await
+    }
+}
+
+/// A timed phase of a deployment's pipeline (clone, build, test, push, start,
+/// healthcheck).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeploymentPhase {
+    pub id: i64,
+    pub deployment_id: String,
+    pub phase: String,
+    /// running, succeeded, failed or cancelled
+    pub status: String,
+    pub started_at: String,
+    /// When the phase ended; `None` while it is in progress
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl DeploymentPhase {
+    /// Start a phase, ending the one in progress as succeeded. Returns the
+    /// ended phases. Like events, phases are informational: failures are
+    /// logged rather than returned.
+    pub async fn begin(db: &SqlitePool, deployment_id: &str, phase: &str) -> Vec<Self> {
+        let ended = Self::end(db, deployment_id, "succeeded").await;
+        let result = sqlx::query(
+            "INSERT INTO deployment_phases (deployment_id, phase, status, started_at) \
+             VALUES (?, ?, 'running', ?)",
+        )
+        .bind(deployment_id)
+        .bind(phase)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(db)
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!(
+                deployment_id = %deployment_id,
+                phase = %phase,
+                error = %e,
+                "Failed to record deployment phase"
+            );
+        }
+        ended
+    }
+
+    /// End the phase in progress, if any, with `status`. Returns the ended
+    /// phases.
+    pub async fn end(db: &SqlitePool, deployment_id: &str, status: &str) -> Vec<Self> {
+        let result = sqlx::query_as(
+            "UPDATE deployment_phases SET status = ?1, finished_at = ?2, duration_ms = \
+             MAX(0, CAST(ROUND((julianday(?2) - julianday(started_at)) * 86400000) AS INTEGER)) \
+             WHERE deployment_id = ?3 AND finished_at IS NULL RETURNING *",
+        )
+        .bind(status)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(deployment_id)
+        .fetch_all(db)
+        .await;
+
+        result.unwrap_or_else(|e| {
+            tracing::warn!(
+                deployment_id = %deployment_id,
+                error = %e,
+                "Failed to end deployment phase"
+            );
+            Vec::new()
+        })
+    }
+
+    /// All phases of a deployment, in pipeline order.
+    pub async fn list(db: &SqlitePool, deployment_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM deployment_phases WHERE deployment_id = ? ORDER BY id ASC")
+            .bind(deployment_id)
+            .fetch_all(db)
+            .await
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -110,6 +297,9 @@ pub struct DeploymentLog {
     pub timestamp: String,
     pub level: String,
     pub message: String,
+    /// Pipeline phase the line was written in
+    #[sqlx(default)]
+    pub phase: Option<String>,
 }
 
 /// Freeze window for preventing deployments during specific time windows.
@@ -119,13 +309,22 @@ pub struct DeploymentFreezeWindow {
     pub app_id: Option<String>,
     pub team_id: Option<String>,
     pub name: String,
-    /// Start time in HH:MM UTC format
+    /// Start time in HH:MM format, in `timezone`
     pub start_time: String,
-    /// End time in HH:MM UTC format
+    /// End time in HH:MM format, in `timezone`
     pub end_time: String,
     /// Comma-separated days of week: 0=Sun, 1=Mon, ..., 6=Sat
     pub days_of_week: String,
     #[serde(default)]
     pub is_active: i32,
     pub created_at: String,
+    /// IANA timezone of the times and days (NULL = UTC)
+    pub timezone: Option<String>,
+    /// Start of a calendar range (RFC 3339); with `ends_at` it replaces the
+    /// recurring times and days
+    #[sqlx(default)]
+    pub starts_at: Option<String>,
+    /// End of a calendar range (RFC 3339)
+    #[sqlx(default)]
+    pub ends_at: Option<String>,
 }
@@ -13,6 +13,8 @@ pub struct ProjectEnvironment {
     pub is_default: i32,
     pub created_at: String,
     pub updated_at: String,
+    /// Deployments of apps in this environment wait for approval
+    pub require_approval: i32,
 }
 
 /// Response DTO for an environment
@@ -25,6 +27,7 @@ pub struct EnvironmentResponse {
     pub is_default: bool,
     pub created_at: String,
     pub updated_at: String,
+    pub require_approval: bool,
 }
 
 impl ProjectEnvironment {
@@ -37,6 +40,7 @@ impl ProjectEnvironment {
             is_default: self.is_default != 0,
             created_at: self.created_at.clone(),
             updated_at: self.updated_at.clone(),
+            require_approval: self.require_approval != 0,
         }
     }
 }
@@ -45,12 +49,15 @@ impl ProjectEnvironment {
 pub struct CreateEnvironmentRequest {
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub require_approval: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateEnvironmentRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub require_approval: Option<bool>,
 }
 
 /// An environment-scoped environment variable
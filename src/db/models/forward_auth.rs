@@ -0,0 +1,117 @@
+//! Per-app forward authentication settings.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::proxy::forward_auth::{ForwardAuthPolicy, ForwardAuthProvider};
+
+/// Sign in with Rivetr user accounts.
+pub const FORWARD_AUTH_PROVIDER_RIVETR: &str = "rivetr";
+/// Sign in with a configured OIDC provider.
+pub const FORWARD_AUTH_PROVIDER_OIDC: &str = "oidc";
+
+/// All accepted `provider` values.
+pub const FORWARD_AUTH_PROVIDERS: &[&str] =
+    &[FORWARD_AUTH_PROVIDER_RIVETR, FORWARD_AUTH_PROVIDER_OIDC];
+
+/// Forward authentication settings for an app.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppForwardAuth {
+    pub app_id: String,
+    pub enabled: i32,
+    /// One of `rivetr`, `oidc`
+    pub provider: String,
+    /// OIDC provider used when `provider` is `oidc`
+    pub oidc_provider_id: Option<String>,
+    /// Allowed emails or `@domain` entries, one per line. Empty allows anyone
+    /// who can sign in with the provider.
+    pub allowed_emails: String,
+    /// Lifetime of the proxy session cookie
+    pub session_hours: i64,
+    /// HMAC key for this app's session cookies
+    #[serde(skip_serializing, default)]
+    pub cookie_secret: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppForwardAuth {
+    /// Default (disabled) settings for an app with no row yet.
+    pub fn default_for(app_id: &str) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            app_id: app_id.to_string(),
+            enabled: 0,
+            provider: FORWARD_AUTH_PROVIDER_RIVETR.to_string(),
+            oidc_provider_id: None,
+            allowed_emails: String::new(),
+            session_hours: 24,
+            cookie_secret: generate_cookie_secret(),
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_forward_auth WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Allowed email entries, normalized to lowercase.
+    pub fn allowed_email_list(&self) -> Vec<String> {
+        self.allowed_emails
+            .split(['\n', ','])
+            .map(|e| e.trim().to_ascii_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect()
+    }
+
+    /// The proxy policy for the app's routes, or `None` when disabled.
+    pub fn to_policy(&self) -> Option<ForwardAuthPolicy> {
+        if self.enabled == 0 {
+            return None;
+        }
+        let provider = match self.provider.as_str() {
+            FORWARD_AUTH_PROVIDER_OIDC => ForwardAuthProvider::Oidc(self.oidc_provider_id.clone()?),
+            _ => ForwardAuthProvider::Rivetr,
+        };
+        Some(ForwardAuthPolicy {
+            app_id: self.app_id.clone(),
+            provider,
+            allowed_emails: self.allowed_email_list(),
+            session_secs: self.session_hours.max(1) * 3600,
+            cookie_secret: self.cookie_secret.clone(),
+        })
+    }
+
+    /// Load the proxy policy for an app.
+    pub async fn load_policy(
+        db: &SqlitePool,
+        app_id: &str,
+    ) -> Result<Option<ForwardAuthPolicy>, sqlx::Error> {
+        Ok(Self::get(db, app_id).await?.and_then(|s| s.to_policy()))
+    }
+}
+
+/// Random hex-encoded 256-bit key for signing session cookies.
+pub fn generate_cookie_secret() -> String {
+    let mut secret = [0u8; 32];
+    rand::rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+/// Request to update forward authentication settings for an app.
+#[derive(Debug, Deserialize)]
+pub struct UpdateForwardAuthRequest {
+    pub enabled: Option<bool>,
+    pub provider: Option<String>,
+    pub oidc_provider_id: Option<String>,
+    pub allowed_emails: Option<String>,
+    pub session_hours: Option<i64>,
+    /// Rotate the cookie key, signing out every current session
+    #[serde(default)]
+    pub reset_sessions: bool,
+}
@@ -0,0 +1,102 @@
+//! In-app notifications: a per-user copy of each notification event, shown in
+//! the dashboard's notification center until read.
+
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+/// A notification in a user's inbox
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct InboxNotification {
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub user_id: String,
+    /// Outbox event this notification was created from
+    #[serde(skip_serializing)]
+    pub outbox_id: String,
+    pub event_type: String,
+    /// `info`, `warning` or `critical`
+    pub severity: String,
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    pub deployment_id: Option<String>,
+    pub message: String,
+    pub error_message: Option<String>,
+    /// NULL while unread
+    pub read_at: Option<String>,
+    pub created_at: String,
+}
+
+impl InboxNotification {
+    pub async fn get(
+        db: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM inbox_notifications WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// A user's most recent notifications, newest first
+    pub async fn list_for_user(
+        db: &SqlitePool,
+        user_id: &str,
+        unread_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM inbox_notifications WHERE user_id = ? \
+             AND (? = 0 OR read_at IS NULL) ORDER BY created_at DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+
+    pub async fn unread_count(db: &SqlitePool, user_id: &str) -> Result<i64, sqlx::Error> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM inbox_notifications WHERE user_id = ? AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(db)
+        .await?;
+        Ok(count)
+    }
+
+    /// Mark one notification read. Returns false if the user has no such
+    /// notification.
+    pub async fn mark_read(db: &SqlitePool, user_id: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE inbox_notifications SET read_at = COALESCE(read_at, datetime('now')) \
+             WHERE id = ? AND user_id = ?",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark all of a user's notifications read; returns how many were unread
+    pub async fn mark_all_read(db: &SqlitePool, user_id: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE inbox_notifications SET read_at = datetime('now') \
+             WHERE user_id = ? AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Inbox listing with the user's unread count
+#[derive(Debug, Clone, Serialize)]
+pub struct InboxResponse {
+    pub notifications: Vec<InboxNotification>,
+    pub unread_count: i64,
+}
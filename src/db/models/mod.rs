@@ -2,37 +2,50 @@
 //!
 //! This module re-exports all types for backwards compatibility.
 
+pub mod access_rule;
 pub mod alert_config;
 pub mod alert_event;
 pub mod app;
 pub mod audit;
 pub mod backup;
+pub mod basic_auth_path;
+pub mod bot_settings;
 pub mod build_server;
 pub mod bulk;
 pub mod cloudflare_tunnel;
+pub mod command_run;
 pub mod common;
+pub mod cors;
 pub mod cost_rate;
 pub mod cost_snapshot;
 pub mod database;
 pub mod deployment;
 pub mod env_var;
 pub mod environment;
+pub mod forward_auth;
 pub mod git_provider;
 pub mod github_app;
+pub mod inbox_notification;
 pub mod instance_settings;
 pub mod log_drain;
 pub mod monitoring;
 pub mod notification;
+pub mod notification_digest;
 pub mod oauth;
 pub mod oidc;
 pub mod patch;
+pub mod power_schedule;
 pub mod preview_deployment;
+pub mod prewarm;
+pub mod process;
 pub mod project;
+pub mod queued_email;
 pub mod redirect_rule;
 pub mod replica;
 pub mod resource_metric;
 pub mod s3;
 pub mod scheduled_job;
+pub mod scheduled_task;
 pub mod server;
 pub mod service;
 pub mod service_generated_var;
@@ -43,41 +56,56 @@ pub mod stats;
 pub mod swarm;
 pub mod team;
 pub mod user;
+pub mod user_preferences;
 pub mod volume;
+pub mod waf;
 pub mod white_label;
 
 // Re-export all types for backwards compatibility
+pub use access_rule::*;
 pub use alert_config::*;
 pub use alert_event::*;
 pub use app::*;
 pub use audit::*;
 pub use backup::*;
+pub use basic_auth_path::*;
+pub use bot_settings::*;
 pub use build_server::*;
 pub use bulk::*;
 pub use cloudflare_tunnel::*;
+pub use command_run::*;
 pub use common::*;
+pub use cors::*;
 pub use cost_rate::*;
 pub use cost_snapshot::*;
 pub use database::*;
 pub use deployment::*;
 pub use env_var::*;
 pub use environment::*;
+pub use forward_auth::*;
 pub use git_provider::*;
 pub use github_app::*;
+pub use inbox_notification::*;
 pub use instance_settings::*;
 pub use log_drain::*;
 pub use monitoring::*;
 pub use notification::*;
+pub use notification_digest::*;
 pub use oauth::*;
 pub use oidc::*;
 pub use patch::*;
+pub use power_schedule::*;
 pub use preview_deployment::*;
+pub use prewarm::*;
+pub use process::*;
 pub use project::*;
+pub use queued_email::*;
 pub use redirect_rule::*;
 pub use replica::*;
 pub use resource_metric::*;
 pub use s3::*;
 pub use scheduled_job::*;
+pub use scheduled_task::*;
 pub use server::*;
 pub use service::*;
 pub use service_generated_var::*;
@@ -88,7 +116,9 @@ pub use stats::*;
 pub use swarm::*;
 pub use team::*;
 pub use user::*;
+pub use user_preferences::*;
 pub use volume::*;
+pub use waf::*;
 pub use white_label::*;
 
 pub mod ca_certificate;
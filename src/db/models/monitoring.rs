@@ -110,6 +110,8 @@ pub struct ScheduledRestart {
     pub enabled: i32,
     pub last_restart: Option<String>,
     pub next_restart: Option<String>,
+    /// IANA timezone the cron expression is evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
     pub created_at: String,
 }
 
@@ -121,6 +123,7 @@ pub struct ScheduledRestartResponse {
     pub enabled: bool,
     pub last_restart: Option<String>,
     pub next_restart: Option<String>,
+    pub timezone: Option<String>,
     pub created_at: String,
 }
 
@@ -133,6 +136,7 @@ impl From<ScheduledRestart> for ScheduledRestartResponse {
             enabled: r.enabled != 0,
             last_restart: r.last_restart,
             next_restart: r.next_restart,
+            timezone: r.timezone,
             created_at: r.created_at,
         }
     }
@@ -143,6 +147,8 @@ pub struct CreateScheduledRestartRequest {
     pub cron_expression: String,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// IANA timezone for the cron expression (default UTC)
+    pub timezone: Option<String>,
 }
 
 fn default_enabled() -> bool {
@@ -153,6 +159,8 @@ fn default_enabled() -> bool {
 pub struct UpdateScheduledRestartRequest {
     pub cron_expression: Option<String>,
     pub enabled: Option<bool>,
+    /// IANA timezone for the cron expression — set to empty string for UTC
+    pub timezone: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
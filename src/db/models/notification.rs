@@ -1,7 +1,7 @@
 //! Notification channel and subscription models.
 
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, SqlitePool};
 
 /// Notification channel types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -71,6 +71,15 @@ impl From<String> for NotificationChannelType {
     }
 }
 
+/// Routine events (deploys starting or succeeding, apps started or scaled)
+pub const SEVERITY_INFO: &str = "info";
+/// Events worth a look (rollbacks, stops, restarts)
+pub const SEVERITY_WARNING: &str = "warning";
+/// Events that need action (failed deploys, crashes)
+pub const SEVERITY_CRITICAL: &str = "critical";
+/// All severities, least urgent first
+pub const NOTIFICATION_SEVERITIES: [&str; 3] = [SEVERITY_INFO, SEVERITY_WARNING, SEVERITY_CRITICAL];
+
 /// Notification event types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -78,10 +87,19 @@ pub enum NotificationEventType {
     DeploymentStarted,
     DeploymentSuccess,
     DeploymentFailed,
+    DeploymentRolledBack,
+    DeploymentCancelled,
     AppStopped,
     AppStarted,
     ContainerCrash,
     ContainerRestarted,
+    AppScaled,
+    PreviewExpiring,
+    BandwidthExceeded,
+    BackendUnhealthy,
+    BackendRecovered,
+    /// Periodic summary sent by a notification digest (not subscribable)
+    Digest,
 }
 
 impl std::fmt::Display for NotificationEventType {
@@ -90,10 +108,41 @@ impl std::fmt::Display for NotificationEventType {
             Self::DeploymentStarted => write!(f, "deployment_started"),
             Self::DeploymentSuccess => write!(f, "deployment_success"),
             Self::DeploymentFailed => write!(f, "deployment_failed"),
+            Self::DeploymentRolledBack => write!(f, "deployment_rolled_back"),
+            Self::DeploymentCancelled => write!(f, "deployment_cancelled"),
             Self::AppStopped => write!(f, "app_stopped"),
             Self::AppStarted => write!(f, "app_started"),
             Self::ContainerCrash => write!(f, "container_crash"),
             Self::ContainerRestarted => write!(f, "container_restarted"),
+            Self::AppScaled => write!(f, "app_scaled"),
+            Self::PreviewExpiring => write!(f, "preview_expiring"),
+            Self::BandwidthExceeded => write!(f, "bandwidth_exceeded"),
+            Self::BackendUnhealthy => write!(f, "backend_unhealthy"),
+            Self::BackendRecovered => write!(f, "backend_recovered"),
+            Self::Digest => write!(f, "digest"),
+        }
+    }
+}
+
+impl NotificationEventType {
+    /// How urgent the event is: `info`, `warning` or `critical`
+    pub fn severity(&self) -> &'static str {
+        match self {
+            Self::DeploymentFailed | Self::ContainerCrash | Self::BackendUnhealthy => {
+                SEVERITY_CRITICAL
+            }
+            Self::DeploymentRolledBack
+            | Self::DeploymentCancelled
+            | Self::AppStopped
+            | Self::ContainerRestarted
+            | Self::PreviewExpiring
+            | Self::BandwidthExceeded => SEVERITY_WARNING,
+            Self::DeploymentStarted
+            | Self::DeploymentSuccess
+            | Self::AppStarted
+            | Self::AppScaled
+            | Self::BackendRecovered
+            | Self::Digest => SEVERITY_INFO,
         }
     }
 }
@@ -106,10 +155,18 @@ impl std::str::FromStr for NotificationEventType {
             "deployment_started" => Ok(Self::DeploymentStarted),
             "deployment_success" => Ok(Self::DeploymentSuccess),
             "deployment_failed" => Ok(Self::DeploymentFailed),
+            "deployment_rolled_back" => Ok(Self::DeploymentRolledBack),
+            "deployment_cancelled" => Ok(Self::DeploymentCancelled),
             "app_stopped" => Ok(Self::AppStopped),
             "app_started" => Ok(Self::AppStarted),
             "container_crash" => Ok(Self::ContainerCrash),
             "container_restarted" => Ok(Self::ContainerRestarted),
+            "app_scaled" => Ok(Self::AppScaled),
+            "preview_expiring" => Ok(Self::PreviewExpiring),
+            "bandwidth_exceeded" => Ok(Self::BandwidthExceeded),
+            "backend_unhealthy" => Ok(Self::BackendUnhealthy),
+            "backend_recovered" => Ok(Self::BackendRecovered),
+            "digest" => Ok(Self::Digest),
             _ => Err(format!("Unknown event type: {}", s)),
         }
     }
@@ -498,6 +555,41 @@ pub struct NotificationSubscriptionResponse {
     pub created_at: String,
 }
 
+/// One request sent to a webhook channel, kept for the channel's delivery log
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub channel_id: String,
+    pub event_type: String,
+    pub app_id: Option<String>,
+    pub deployment_id: Option<String>,
+    pub url: String,
+    /// Response status, NULL when the endpoint couldn't be reached
+    pub status_code: Option<i64>,
+    pub success: i32,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub created_at: String,
+}
+
+impl WebhookDelivery {
+    /// The channel's most recent deliveries, newest first
+    pub async fn list_for_channel(
+        db: &SqlitePool,
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM webhook_deliveries WHERE channel_id = ? \
+             ORDER BY created_at DESC, rowid DESC LIMIT ?",
+        )
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+}
+
 /// Request to create a notification channel
 #[derive(Debug, Deserialize)]
 pub struct CreateNotificationChannelRequest {
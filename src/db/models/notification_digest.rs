@@ -0,0 +1,170 @@
+//! Notification digests: periodic summaries sent to channels and email addresses.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Sent every day at `send_hour`
+pub const DIGEST_FREQUENCY_DAILY: &str = "daily";
+/// Sent once a week on `send_weekday` at `send_hour`
+pub const DIGEST_FREQUENCY_WEEKLY: &str = "weekly";
+
+/// Cron day-of-week names, indexed by `send_weekday` (0 = Sunday)
+const WEEKDAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// A digest as stored (`channel_ids` and `email_addresses` are JSON arrays)
+#[derive(Debug, Clone, FromRow)]
+pub struct NotificationDigest {
+    pub id: String,
+    pub name: String,
+    /// Only this team's apps are summarized (NULL = all apps)
+    pub team_id: Option<String>,
+    pub frequency: String,
+    pub send_hour: i64,
+    pub send_weekday: i64,
+    /// IANA timezone for the send time (NULL = UTC)
+    pub timezone: Option<String>,
+    pub channel_ids: String,
+    pub email_addresses: String,
+    pub enabled: i32,
+    pub last_sent_at: Option<String>,
+    pub next_send_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl NotificationDigest {
+    pub async fn get(db: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM notification_digests WHERE id = ?")
+            .bind(id)
+            .fetch_optional(db)
+            .await
+    }
+
+    pub fn channel_ids(&self) -> Vec<String> {
+        serde_json::from_str(&self.channel_ids).unwrap_or_default()
+    }
+
+    pub fn email_addresses(&self) -> Vec<String> {
+        serde_json::from_str(&self.email_addresses).unwrap_or_default()
+    }
+
+    /// Length of the period a digest summarizes
+    pub fn period(&self) -> chrono::Duration {
+        if self.frequency == DIGEST_FREQUENCY_WEEKLY {
+            chrono::Duration::days(7)
+        } else {
+            chrono::Duration::days(1)
+        }
+    }
+
+    /// The send schedule as a 5-field cron expression
+    pub fn cron_expression(&self) -> String {
+        cron_expression(&self.frequency, self.send_hour, self.send_weekday)
+    }
+}
+
+/// Cron expression for a digest schedule
+pub fn cron_expression(frequency: &str, send_hour: i64, send_weekday: i64) -> String {
+    if frequency == DIGEST_FREQUENCY_WEEKLY {
+        let weekday = WEEKDAY_NAMES[send_weekday.clamp(0, 6) as usize];
+        format!("0 {} * * {}", send_hour, weekday)
+    } else {
+        format!("0 {} * * *", send_hour)
+    }
+}
+
+/// A digest as returned by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationDigestResponse {
+    pub id: String,
+    pub name: String,
+    pub team_id: Option<String>,
+    pub frequency: String,
+    pub send_hour: i64,
+    pub send_weekday: i64,
+    pub timezone: Option<String>,
+    pub channel_ids: Vec<String>,
+    pub email_addresses: Vec<String>,
+    pub enabled: bool,
+    pub last_sent_at: Option<String>,
+    pub next_send_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<NotificationDigest> for NotificationDigestResponse {
+    fn from(digest: NotificationDigest) -> Self {
+        Self {
+            channel_ids: digest.channel_ids(),
+            email_addresses: digest.email_addresses(),
+            id: digest.id,
+            name: digest.name,
+            team_id: digest.team_id,
+            frequency: digest.frequency,
+            send_hour: digest.send_hour,
+            send_weekday: digest.send_weekday,
+            timezone: digest.timezone,
+            enabled: digest.enabled != 0,
+            last_sent_at: digest.last_sent_at,
+            next_send_at: digest.next_send_at,
+            created_at: digest.created_at,
+            updated_at: digest.updated_at,
+        }
+    }
+}
+
+/// Request to create a notification digest
+#[derive(Debug, Deserialize)]
+pub struct CreateNotificationDigestRequest {
+    pub name: String,
+    pub team_id: Option<String>,
+    /// `daily` (default) or `weekly`
+    pub frequency: Option<String>,
+    /// Local hour 0-23 (default 8)
+    pub send_hour: Option<i64>,
+    /// 0 = Sunday ... 6 = Saturday (default Monday); weekly digests only
+    pub send_weekday: Option<i64>,
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub email_addresses: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request to update a notification digest (omitted fields are kept)
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationDigestRequest {
+    pub name: Option<String>,
+    pub frequency: Option<String>,
+    pub send_hour: Option<i64>,
+    pub send_weekday: Option<i64>,
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
+    pub channel_ids: Option<Vec<String>>,
+    pub email_addresses: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cron_expression() {
+        assert_eq!(cron_expression(DIGEST_FREQUENCY_DAILY, 8, 1), "0 8 * * *");
+        assert_eq!(
+            cron_expression(DIGEST_FREQUENCY_WEEKLY, 17, 5),
+            "0 17 * * Fri"
+        );
+        assert_eq!(
+            cron_expression(DIGEST_FREQUENCY_WEEKLY, 9, 0),
+            "0 9 * * Sun"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Per-app power schedules: stop an app on one cron expression, start it on another.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Message shown by the proxy while a schedule keeps an app stopped
+pub const DEFAULT_STOPPED_MESSAGE: &str = "This application is stopped outside its scheduled hours";
+
+/// Power schedule for an app. Cron expressions are evaluated in `timezone`
+/// (UTC when unset).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppPowerSchedule {
+    pub app_id: String,
+    pub enabled: i32,
+    /// When to stop the app (e.g. `0 19 * * Mon-Fri`)
+    pub stop_cron: String,
+    /// When to start the app again (e.g. `0 8 * * Mon-Fri`)
+    pub start_cron: String,
+    /// Message served for the app's domains while stopped (NULL = default)
+    pub stopped_message: Option<String>,
+    pub next_stop_at: Option<String>,
+    pub next_start_at: Option<String>,
+    /// Set while the schedule keeps the app stopped
+    pub stopped_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// IANA timezone the cron expressions are evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
+}
+
+impl AppPowerSchedule {
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_power_schedules WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Message the proxy serves while the app is stopped
+    pub fn message(&self) -> &str {
+        self.stopped_message
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_STOPPED_MESSAGE)
+    }
+
+    /// Forget that the schedule stopped the app, e.g. after a manual start.
+    pub async fn clear_stopped(db: &SqlitePool, app_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE app_power_schedules SET stopped_at = NULL, updated_at = datetime('now') \
+             WHERE app_id = ? AND stopped_at IS NOT NULL",
+        )
+        .bind(app_id)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Request to create or update an app's power schedule.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePowerScheduleRequest {
+    pub enabled: Option<bool>,
+    pub stop_cron: Option<String>,
+    pub start_cron: Option<String>,
+    pub stopped_message: Option<String>,
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
+}
@@ -82,6 +82,8 @@ pub struct PreviewDeployment {
 
     // Comment tracking
     pub github_comment_id: Option<i64>,
+    #[serde(default)]
+    pub gitlab_note_id: Option<i64>,
 
     // Resource limits
     pub memory_limit: Option<String>,
@@ -91,6 +93,18 @@ pub struct PreviewDeployment {
     pub created_at: String,
     pub updated_at: String,
     pub closed_at: Option<String>,
+
+    // Expiry tracking
+    /// Last request the proxy served for the preview
+    #[serde(default)]
+    pub last_activity_at: Option<String>,
+    /// When the "expiring soon" notification was queued
+    #[serde(default)]
+    pub expiry_warned_at: Option<String>,
+
+    /// Managed database provisioned for the preview, destroyed with it
+    #[serde(default)]
+    pub database_id: Option<String>,
 }
 
 impl PreviewDeployment {
@@ -137,6 +151,8 @@ pub struct PreviewDeploymentResponse {
     pub created_at: String,
     pub updated_at: String,
     pub closed_at: Option<String>,
+    pub last_activity_at: Option<String>,
+    pub database_id: Option<String>,
 }
 
 impl From<PreviewDeployment> for PreviewDeploymentResponse {
@@ -165,6 +181,8 @@ impl From<PreviewDeployment> for PreviewDeploymentResponse {
             created_at: p.created_at,
             updated_at: p.updated_at,
             closed_at: p.closed_at,
+            last_activity_at: p.last_activity_at,
+            database_id: p.database_id,
         }
     }
 }
@@ -0,0 +1,88 @@
+//! Pre-warmed deployment slots: build an app's next image ahead of release.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use super::Deployment;
+
+/// Pre-warm settings for an app. The cron schedule is evaluated in `timezone`
+/// (UTC when unset).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppPrewarmSettings {
+    pub app_id: String,
+    pub enabled: i32,
+    /// Off-peak time to pre-build the branch head (e.g. `0 3 * * *`)
+    pub schedule_cron: Option<String>,
+    /// Pushes build the slot instead of deploying
+    pub on_push: i32,
+    pub next_run_at: Option<String>,
+    /// Deployment holding the current slot (status `prewarmed` once ready)
+    pub deployment_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// IANA timezone the schedule is evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
+}
+
+impl AppPrewarmSettings {
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_prewarm_settings WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Whether pushes for this app should fill the slot rather than deploy
+    pub async fn captures_pushes(db: &SqlitePool, app_id: &str) -> bool {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM app_prewarm_settings \
+             WHERE app_id = ? AND enabled = 1 AND on_push = 1",
+        )
+        .bind(app_id)
+        .fetch_one(db)
+        .await
+        .map(|count| count > 0)
+        .unwrap_or(false)
+    }
+
+    /// Whether a deployment is a pre-warm build (build only, no traffic switch)
+    pub async fn is_slot(db: &SqlitePool, deployment_id: &str) -> bool {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM app_prewarm_settings WHERE deployment_id = ?",
+        )
+        .bind(deployment_id)
+        .fetch_one(db)
+        .await
+        .map(|count| count > 0)
+        .unwrap_or(false)
+    }
+
+    /// The deployment holding the slot, if any
+    pub async fn slot(&self, db: &SqlitePool) -> Result<Option<Deployment>, sqlx::Error> {
+        let Some(ref deployment_id) = self.deployment_id else {
+            return Ok(None);
+        };
+        sqlx::query_as("SELECT * FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await
+    }
+}
+
+/// Request to create or update an app's pre-warm settings.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePrewarmRequest {
+    pub enabled: Option<bool>,
+    /// Empty string clears the schedule
+    pub schedule_cron: Option<String>,
+    pub on_push: Option<bool>,
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
+}
+
+/// Pre-warm settings together with the current slot
+#[derive(Debug, Serialize)]
+pub struct PrewarmStatusResponse {
+    pub settings: Option<AppPrewarmSettings>,
+    pub slot: Option<Deployment>,
+}
@@ -0,0 +1,84 @@
+//! Procfile process types of an app.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Process type served by the app's primary container and its replicas
+pub const PROCESS_WEB: &str = "web";
+/// Process type run once per deployment before the route switch
+pub const PROCESS_RELEASE: &str = "release";
+
+/// A process type from the app's Procfile
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppProcess {
+    pub id: String,
+    pub app_id: String,
+    /// Process type name, e.g. `web`, `worker`, `release`
+    pub name: String,
+    /// Shell command from the Procfile
+    pub command: String,
+    /// Containers to run; not used by `web` (see the app's replica count) or `release`
+    pub replica_count: i64,
+    /// JSON array of the container IDs currently running this process
+    pub container_ids: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppProcess {
+    pub async fn list(db: &SqlitePool, app_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_processes WHERE app_id = ? ORDER BY name ASC")
+            .bind(app_id)
+            .fetch_all(db)
+            .await
+    }
+
+    pub async fn get(
+        db: &SqlitePool,
+        app_id: &str,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_processes WHERE app_id = ? AND name = ?")
+            .bind(app_id)
+            .bind(name)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Store the containers currently running this process
+    pub async fn set_containers(
+        db: &SqlitePool,
+        id: &str,
+        container_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE app_processes SET container_ids = ?, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(serde_json::to_string(container_ids).unwrap_or_else(|_| "[]".to_string()))
+        .bind(id)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub fn get_container_ids(&self) -> Vec<String> {
+        serde_json::from_str(&self.container_ids).unwrap_or_default()
+    }
+
+    /// Whether this process runs in containers of its own (not `web` or `release`)
+    pub fn runs_own_containers(&self) -> bool {
+        self.name != PROCESS_WEB && self.name != PROCESS_RELEASE
+    }
+}
+
+/// Status of one process type for the app view
+#[derive(Debug, Serialize)]
+pub struct ProcessStatus {
+    pub name: String,
+    pub command: String,
+    /// Desired container count (the app's replica count for `web`, 0 for `release`)
+    pub replica_count: i64,
+    /// Containers of the process that are running
+    pub running: i64,
+    pub container_ids: Vec<String>,
+}
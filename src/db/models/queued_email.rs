@@ -0,0 +1,52 @@
+//! Outbound emails: the queue the email dispatcher sends from, which doubles
+//! as a log of sent and failed mail.
+
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+/// One email to one recipient
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct QueuedEmail {
+    pub id: String,
+    /// `system` for the `[email]` config, otherwise a notification channel ID
+    pub source: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub subject: String,
+    #[serde(skip_serializing)]
+    pub html_body: String,
+    #[serde(skip_serializing)]
+    pub text_body: String,
+    /// `pending`, `sent` or `failed`
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+impl QueuedEmail {
+    /// Most recent emails, newest first, optionally only those with `status`
+    /// or to an address containing `to`
+    pub async fn list(
+        db: &SqlitePool,
+        status: Option<&str>,
+        to: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM email_queue \
+             WHERE (? IS NULL OR status = ?) \
+             AND (? IS NULL OR instr(lower(to_address), lower(?)) > 0) \
+             ORDER BY created_at DESC LIMIT ?",
+        )
+        .bind(status)
+        .bind(status)
+        .bind(to)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+}
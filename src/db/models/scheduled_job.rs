@@ -14,6 +14,8 @@ pub struct ScheduledJob {
     pub enabled: i32,
     pub last_run_at: Option<String>,
     pub next_run_at: Option<String>,
+    /// IANA timezone the cron expression is evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -29,6 +31,7 @@ pub struct ScheduledJobResponse {
     pub enabled: bool,
     pub last_run_at: Option<String>,
     pub next_run_at: Option<String>,
+    pub timezone: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -44,6 +47,7 @@ impl From<ScheduledJob> for ScheduledJobResponse {
             enabled: j.enabled != 0,
             last_run_at: j.last_run_at,
             next_run_at: j.next_run_at,
+            timezone: j.timezone,
             created_at: j.created_at,
             updated_at: j.updated_at,
         }
@@ -99,6 +103,8 @@ pub struct CreateScheduledJobRequest {
     pub cron_expression: String,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// IANA timezone for the cron expression (default UTC)
+    pub timezone: Option<String>,
 }
 
 fn default_enabled() -> bool {
@@ -112,4 +118,6 @@ pub struct UpdateScheduledJobRequest {
     pub command: Option<String>,
     pub cron_expression: Option<String>,
     pub enabled: Option<bool>,
+    /// IANA timezone for the cron expression — set to empty string for UTC
+    pub timezone: Option<String>,
 }
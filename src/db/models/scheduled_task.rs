@@ -0,0 +1,144 @@
+//! Per-app scheduled tasks: cron commands run in one-off containers started
+//! from the app's image.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Run limit for tasks that don't set one
+pub const DEFAULT_TASK_TIMEOUT_SECS: i64 = 3600;
+/// Longest run limit a task may set
+pub const MAX_TASK_TIMEOUT_SECS: i64 = 86400;
+
+/// Run started by the task's cron schedule
+pub const TASK_TRIGGER_SCHEDULE: &str = "schedule";
+/// Run started through the API
+pub const TASK_TRIGGER_MANUAL: &str = "manual";
+
+/// A command run on a cron schedule in a fresh container from the image of
+/// the app's running deployment
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub app_id: String,
+    pub name: String,
+    /// Shell command (run with `/bin/sh -c`)
+    pub command: String,
+    pub cron_expression: String,
+    /// IANA timezone the cron expression is evaluated in (NULL = UTC)
+    pub timezone: Option<String>,
+    /// A run still going after this long is killed and marked failed
+    pub timeout_seconds: i64,
+    pub enabled: i32,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ScheduledTask {
+    pub async fn get(
+        db: &SqlitePool,
+        app_id: &str,
+        task_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM scheduled_tasks WHERE id = ? AND app_id = ?")
+            .bind(task_id)
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    pub async fn list_for_app(db: &SqlitePool, app_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM scheduled_tasks WHERE app_id = ? ORDER BY name ASC")
+            .bind(app_id)
+            .fetch_all(db)
+            .await
+    }
+
+    /// Whether a run of the task is still going
+    pub async fn is_running(db: &SqlitePool, task_id: &str) -> bool {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM scheduled_task_runs WHERE task_id = ? AND status = 'running'",
+        )
+        .bind(task_id)
+        .fetch_one(db)
+        .await
+        .map(|count| count > 0)
+        .unwrap_or(false)
+    }
+}
+
+/// One run of a scheduled task
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledTaskRun {
+    pub id: String,
+    pub task_id: String,
+    /// `schedule` or `manual`
+    pub trigger: String,
+    /// `running`, `success` or `failed`
+    pub status: String,
+    /// Image the task ran in
+    pub image: Option<String>,
+    pub exit_code: Option<i64>,
+    /// Combined stdout/stderr of the container (tail)
+    pub output: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl ScheduledTaskRun {
+    pub async fn get(db: &SqlitePool, run_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM scheduled_task_runs WHERE id = ?")
+            .bind(run_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Most recent runs of a task, newest first
+    pub async fn list(
+        db: &SqlitePool,
+        task_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM scheduled_task_runs WHERE task_id = ? \
+             ORDER BY started_at DESC LIMIT ?",
+        )
+        .bind(task_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+}
+
+/// Request to create a scheduled task
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduledTaskRequest {
+    pub name: String,
+    pub command: String,
+    pub cron_expression: String,
+    /// IANA timezone for the cron expression (default UTC)
+    pub timezone: Option<String>,
+    /// Run limit in seconds (default 3600)
+    pub timeout_seconds: Option<i64>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request to update a scheduled task
+#[derive(Debug, Deserialize)]
+pub struct UpdateScheduledTaskRequest {
+    pub name: Option<String>,
+    pub command: Option<String>,
+    pub cron_expression: Option<String>,
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
+    pub timeout_seconds: Option<i64>,
+    pub enabled: Option<bool>,
+}
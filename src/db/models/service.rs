@@ -81,6 +81,34 @@ pub struct Service {
     /// Optional memory limit (e.g. "512M"), injected the same way.
     #[serde(default)]
     pub memory_limit: Option<String>,
+    /// Notes shown after deploying from a template, with variables filled in
+    #[serde(default)]
+    pub post_deploy_notes: Option<String>,
+    /// Path of the page the "open app" link points to (e.g. "/admin")
+    #[serde(default)]
+    pub url_path: Option<String>,
+    /// Path the proxy health checker probes (NULL = "/")
+    #[serde(default)]
+    pub health_path: Option<String>,
+    /// Compose service the domain routes to. NULL = the host port in `port`.
+    #[serde(default)]
+    pub route_service: Option<String>,
+    /// Container port of `route_service` the domain routes to
+    #[serde(default)]
+    pub route_port: Option<i32>,
+    /// Loopback port `route_port` was last published on
+    #[serde(default)]
+    pub route_host_port: Option<i32>,
+    /// When 1, the domain is included in the ACME certificate
+    #[serde(default)]
+    pub route_tls: i32,
+    /// When 1, the proxy requires HTTP Basic Auth for the domain
+    #[serde(default)]
+    pub basic_auth_enabled: i32,
+    #[serde(default)]
+    pub basic_auth_username: Option<String>,
+    #[serde(skip_serializing, default)]
+    pub basic_auth_password_hash: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -105,6 +133,51 @@ impl Service {
         self.domain.clone()
     }
 
+    /// Public URL of the service's primary page ("open app" link)
+    pub fn url(&self) -> Option<String> {
+        let domain = self.domain.as_deref().filter(|d| !d.is_empty())?;
+        Some(format!(
+            "https://{}{}",
+            domain,
+            self.url_path.as_deref().unwrap_or("")
+        ))
+    }
+
+    /// Compose service and container port the domain routes to, if set
+    pub fn route_target(&self) -> Option<(&str, u16)> {
+        let service = self.route_service.as_deref().filter(|s| !s.is_empty())?;
+        let port = self.route_port.filter(|p| (1..=65535).contains(p))?;
+        Some((service, port as u16))
+    }
+
+    /// Host port the proxy forwards the domain to
+    pub fn proxy_port(&self) -> i32 {
+        match self.route_target() {
+            Some(_) => self.route_host_port.unwrap_or(self.port),
+            None => self.port,
+        }
+    }
+
+    /// Proxy backend for the service's domain
+    pub fn proxy_backend(&self) -> crate::proxy::Backend {
+        let backend = crate::proxy::Backend::new(
+            self.compose_project_name(),
+            "127.0.0.1".to_string(),
+            self.proxy_port() as u16,
+        )
+        .with_healthcheck(self.health_path.clone());
+        match (
+            self.basic_auth_enabled != 0,
+            &self.basic_auth_username,
+            &self.basic_auth_password_hash,
+        ) {
+            (true, Some(username), Some(hash)) => {
+                backend.with_basic_auth(username.clone(), hash.clone())
+            }
+            _ => backend,
+        }
+    }
+
     /// Convert to response DTO
     pub fn to_response(self) -> ServiceResponse {
         ServiceResponse::from(self)
@@ -137,12 +210,28 @@ pub struct ServiceResponse {
     pub cpu_limit: Option<String>,
     /// Optional memory limit injected into each compose service's deploy limits.
     pub memory_limit: Option<String>,
+    /// Notes shown after deploying from a template.
+    pub post_deploy_notes: Option<String>,
+    /// Public URL of the service's primary page ("open app" link).
+    pub url: Option<String>,
+    /// Path the proxy health checker probes.
+    pub health_path: Option<String>,
+    /// Compose service the domain routes to (None = the host port in `port`).
+    pub route_service: Option<String>,
+    /// Container port of `route_service` the domain routes to.
+    pub route_port: Option<i32>,
+    /// Whether the domain is included in the ACME certificate.
+    pub route_tls: bool,
+    /// Whether the proxy requires HTTP Basic Auth for the domain.
+    pub basic_auth_enabled: bool,
+    pub basic_auth_username: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
 impl From<Service> for ServiceResponse {
     fn from(service: Service) -> Self {
+        let url = service.url();
         ServiceResponse {
             id: service.id,
             name: service.name,
@@ -160,6 +249,14 @@ impl From<Service> for ServiceResponse {
             expose_container_port: service.expose_container_port,
             cpu_limit: service.cpu_limit,
             memory_limit: service.memory_limit,
+            post_deploy_notes: service.post_deploy_notes,
+            url,
+            health_path: service.health_path,
+            route_service: service.route_service,
+            route_port: service.route_port,
+            route_tls: service.route_tls != 0,
+            basic_auth_enabled: service.basic_auth_enabled != 0,
+            basic_auth_username: service.basic_auth_username,
             created_at: service.created_at,
             updated_at: service.updated_at,
         }
@@ -211,4 +308,15 @@ pub struct UpdateServiceRequest {
     pub cpu_limit: Option<String>,
     /// Memory limit (e.g. "512M"); empty string clears it
     pub memory_limit: Option<String>,
+    /// Compose service the domain routes to; empty string routes to `port` again
+    pub route_service: Option<String>,
+    /// Container port of `route_service` the domain routes to
+    pub route_port: Option<i32>,
+    /// Include the domain in the ACME certificate
+    pub route_tls: Option<bool>,
+    /// Enable/disable HTTP Basic Auth for the domain
+    pub basic_auth_enabled: Option<bool>,
+    pub basic_auth_username: Option<String>,
+    /// Password in plain text - will be hashed before storing
+    pub basic_auth_password: Option<String>,
 }
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use super::ManagedDatabase;
+
 /// Template categories for organizing services
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -76,6 +78,85 @@ pub struct EnvSchemaEntry {
     pub default: String,
     #[serde(default)]
     pub secret: bool,
+    /// Fill the variable from an existing resource picked at deploy time
+    /// instead of a typed value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<EnvSource>,
+}
+
+/// Kind of resource an env schema field is filled from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvSourceKind {
+    /// A managed database
+    Database,
+    /// An app volume; fills in its host path
+    Volume,
+}
+
+/// What a database-sourced field is filled with
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseValue {
+    /// Internal connection string
+    #[default]
+    Url,
+    Host,
+    Port,
+    Username,
+    Password,
+    Database,
+}
+
+impl DatabaseValue {
+    /// Human-readable name, for errors
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Url => "connection string",
+            Self::Host => "host",
+            Self::Port => "port",
+            Self::Username => "username",
+            Self::Password => "password",
+            Self::Database => "database name",
+        }
+    }
+}
+
+/// Existing resource an env schema field is filled from, e.g.
+/// `{"kind": "database", "db_types": ["postgres"], "value": "url"}`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvSource {
+    pub kind: EnvSourceKind,
+    /// Database types the field accepts; any type when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub db_types: Vec<String>,
+    /// Database value filled in (databases only)
+    #[serde(default)]
+    pub value: DatabaseValue,
+}
+
+impl EnvSource {
+    /// Value of this field for the managed database `db`
+    pub fn database_value(&self, db: &ManagedDatabase) -> Result<String, String> {
+        if !self.db_types.is_empty() && !self.db_types.contains(&db.db_type) {
+            return Err(format!(
+                "Database '{}' is {}, expected {}",
+                db.name,
+                db.db_type,
+                self.db_types.join(" or ")
+            ));
+        }
+        let creds = db.get_credentials();
+        let value = match self.value {
+            DatabaseValue::Url => db.internal_connection_string(),
+            DatabaseValue::Host => Some(db.container_name()),
+            DatabaseValue::Port => Some(db.internal_port.to_string()),
+            DatabaseValue::Username => creds.map(|c| c.username),
+            DatabaseValue::Password => creds.map(|c| c.password),
+            DatabaseValue::Database => creds.and_then(|c| c.database),
+        };
+        value.ok_or_else(|| format!("Database '{}' has no {}", db.name, self.value.label()))
+    }
 }
 
 /// Service template entity
@@ -90,6 +171,17 @@ pub struct ServiceTemplate {
     pub env_schema: Option<String>,
     pub is_builtin: i32,
     pub created_at: String,
+    /// Notes shown after deploying; `${VAR}` placeholders are filled with the
+    /// deployed values, `${SERVICE_URL}` / `${SERVICE_FQDN}` with the service's
+    /// URL / domain
+    #[serde(default)]
+    pub post_deploy_notes: Option<String>,
+    /// Path of the primary page the "open app" link points to (e.g. "/admin")
+    #[serde(default)]
+    pub url_path: Option<String>,
+    /// Path the proxy health checker probes (e.g. "/api/health")
+    #[serde(default)]
+    pub health_path: Option<String>,
 }
 
 impl ServiceTemplate {
@@ -111,6 +203,31 @@ impl ServiceTemplate {
             .unwrap_or_default()
     }
 
+    /// The post-deploy notes with `vars` and the service's URL filled in
+    pub fn render_notes(
+        &self,
+        vars: &std::collections::HashMap<String, String>,
+        domain: Option<&str>,
+    ) -> Option<String> {
+        let notes = self
+            .post_deploy_notes
+            .as_deref()
+            .filter(|n| !n.is_empty())?;
+        let domain = domain.unwrap_or_default();
+        let url = if domain.is_empty() {
+            String::new()
+        } else {
+            format!("https://{}", domain)
+        };
+        let mut rendered = notes
+            .replace("${SERVICE_URL}", &url)
+            .replace("${SERVICE_FQDN}", domain);
+        for (name, value) in vars {
+            rendered = rendered.replace(&format!("${{{}}}", name), value);
+        }
+        Some(rendered)
+    }
+
     /// Convert to API response
     pub fn to_response(&self) -> ServiceTemplateResponse {
         ServiceTemplateResponse {
@@ -123,6 +240,9 @@ impl ServiceTemplate {
             env_schema: self.get_env_schema(),
             is_builtin: self.is_builtin(),
             created_at: self.created_at.clone(),
+            post_deploy_notes: self.post_deploy_notes.clone(),
+            url_path: self.url_path.clone(),
+            health_path: self.health_path.clone(),
         }
     }
 }
@@ -139,6 +259,12 @@ pub struct ServiceTemplateResponse {
     pub env_schema: Vec<EnvSchemaEntry>,
     pub is_builtin: bool,
     pub created_at: String,
+    /// Notes shown after deploying, with `${VAR}` placeholders
+    pub post_deploy_notes: Option<String>,
+    /// Path of the primary page the "open app" link points to
+    pub url_path: Option<String>,
+    /// Path the proxy health checker probes
+    pub health_path: Option<String>,
 }
 
 /// Lightweight DTO for the templates LIST endpoint.  Omits `compose_template` and
@@ -179,8 +305,14 @@ pub struct DeployTemplateRequest {
     /// Environment variable values (key-value pairs)
     #[serde(default)]
     pub env_vars: std::collections::HashMap<String, String>,
+    /// IDs of the resources picked for env schema fields with a `source`,
+    /// keyed by variable name. Resolved to values when deploying.
+    #[serde(default)]
+    pub resources: std::collections::HashMap<String, String>,
     /// Associated project ID
     pub project_id: Option<String>,
+    /// Domain to route to the service; a subdomain is generated when omitted
+    pub domain: Option<String>,
 }
 
 /// Response after deploying a template
@@ -191,6 +323,12 @@ pub struct DeployTemplateResponse {
     pub template_id: String,
     pub status: String,
     pub message: String,
+    /// Domain routed to the service
+    pub domain: Option<String>,
+    /// Public URL of the service's primary page ("open app" link)
+    pub url: Option<String>,
+    /// Template notes with the deployed values filled in
+    pub post_deploy_notes: Option<String>,
 }
 
 /// Request to create a custom service template
@@ -203,3 +341,74 @@ pub struct CreateTemplateRequest {
     pub compose_template: String,
     pub env_schema: Option<Vec<EnvSchemaEntry>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_schema_source_parsing() {
+        let schema: Vec<EnvSchemaEntry> = serde_json::from_str(
+            r#"[
+                {"name": "DATABASE_URL", "label": "Database", "required": true,
+                 "source": {"kind": "database", "db_types": ["postgres"]}},
+                {"name": "DATA_DIR", "label": "Data", "source": {"kind": "volume"}},
+                {"name": "ADMIN_EMAIL", "label": "Admin email"}
+            ]"#,
+        )
+        .unwrap();
+
+        let database = schema[0].source.as_ref().unwrap();
+        assert_eq!(database.kind, EnvSourceKind::Database);
+        assert_eq!(database.db_types, vec!["postgres"]);
+        assert_eq!(database.value, DatabaseValue::Url);
+        assert_eq!(
+            schema[1].source.as_ref().unwrap().kind,
+            EnvSourceKind::Volume
+        );
+        assert!(schema[2].source.is_none());
+
+        // Plain fields serialize as before
+        let plain = serde_json::to_value(&schema[2]).unwrap();
+        assert!(plain.get("source").is_none());
+    }
+
+    #[test]
+    fn test_render_notes() {
+        let template = ServiceTemplate {
+            id: "grafana".to_string(),
+            name: "Grafana".to_string(),
+            description: None,
+            category: "monitoring".to_string(),
+            icon: None,
+            compose_template: String::new(),
+            env_schema: None,
+            is_builtin: 1,
+            created_at: String::new(),
+            post_deploy_notes: Some(
+                "Sign in at ${SERVICE_URL}/login as ${ADMIN_USER} (${SERVICE_FQDN})".to_string(),
+            ),
+            url_path: Some("/login".to_string()),
+            health_path: None,
+        };
+        let vars =
+            std::collections::HashMap::from([("ADMIN_USER".to_string(), "admin".to_string())]);
+
+        assert_eq!(
+            template
+                .render_notes(&vars, Some("grafana.example.com"))
+                .as_deref(),
+            Some("Sign in at https://grafana.example.com/login as admin (grafana.example.com)")
+        );
+        assert_eq!(
+            template.render_notes(&vars, None).as_deref(),
+            Some("Sign in at /login as admin ()")
+        );
+
+        let plain = ServiceTemplate {
+            post_deploy_notes: None,
+            ..template
+        };
+        assert!(plain.render_notes(&vars, None).is_none());
+    }
+}
@@ -1,5 +1,7 @@
 //! SSH key models and DTOs.
 
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -13,6 +15,115 @@ pub struct SshKey {
     pub is_global: i32,
     pub created_at: String,
     pub updated_at: String,
+    /// Bastion the clone connects through, as `[user@]host[:port]`
+    #[sqlx(default)]
+    pub jump_host: Option<String>,
+}
+
+impl SshKey {
+    /// The configured jump host, if any
+    pub fn jump_host(&self) -> Option<JumpHost> {
+        self.jump_host
+            .as_deref()
+            .filter(|h| !h.trim().is_empty())
+            .and_then(|h| JumpHost::parse(h).ok())
+    }
+
+    /// `GIT_SSH_COMMAND` authenticating with the key written to `key_file`.
+    /// With a jump host, the connection is tunnelled through it (ProxyJump
+    /// semantics) and the bastion is authenticated with the same key.
+    pub fn git_ssh_command(&self, key_file: &Path) -> String {
+        let ssh = format!(
+            "ssh -i {} -o StrictHostKeyChecking=accept-new -o UserKnownHostsFile=/dev/null",
+            key_file.display()
+        );
+        match self.jump_host() {
+            Some(jump) => format!("{} -o \"ProxyCommand={} {}\"", ssh, ssh, jump.proxy_args()),
+            None => ssh,
+        }
+    }
+}
+
+/// An SSH jump host (`[user@]host[:port]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpHost {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl JumpHost {
+    /// Parse `[user@]host[:port]`. Only characters that are safe inside a
+    /// shell-quoted ProxyCommand are accepted; IPv6 hosts use brackets.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid jump host: '{}'", value);
+        let value = value.trim();
+        let (user, rest) = match value.split_once('@') {
+            Some((user, rest)) => (Some(user), rest),
+            None => (None, value),
+        };
+        if let Some(user) = user {
+            if user.is_empty() || !user.chars().all(is_name_char) {
+                return Err(invalid());
+            }
+        }
+
+        let (host, port) = match rest.strip_prefix('[') {
+            // [IPv6]:port
+            Some(bracketed) => {
+                let (host, after) = bracketed.split_once(']').ok_or_else(invalid)?;
+                if host.is_empty() || !host.chars().all(|c| c.is_ascii_hexdigit() || c == ':') {
+                    return Err(invalid());
+                }
+                match after {
+                    "" => (host, None),
+                    _ => (host, Some(after.strip_prefix(':').ok_or_else(invalid)?)),
+                }
+            }
+            None => {
+                let (host, port) = match rest.split_once(':') {
+                    Some((host, port)) => (host, Some(port)),
+                    None => (rest, None),
+                };
+                if host.is_empty() || host.starts_with('-') || !host.chars().all(is_name_char) {
+                    return Err(invalid());
+                }
+                (host, port)
+            }
+        };
+        let port = match port {
+            Some(port) => match port.parse::<u16>() {
+                Ok(port) if port > 0 => Some(port),
+                _ => return Err(format!("Invalid jump host port: '{}'", port)),
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            user: user.map(str::to_string),
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// Arguments for an `ssh -W` proxy connection through this host
+    fn proxy_args(&self) -> String {
+        let mut args = String::from("-W %h:%p");
+        if let Some(port) = self.port {
+            args.push_str(&format!(" -p {}", port));
+        }
+        args.push(' ');
+        if let Some(ref user) = self.user {
+            args.push_str(user);
+            args.push('@');
+        }
+        args.push_str(&self.host);
+        args
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
 }
 
 /// Response DTO that excludes the private key for security
@@ -23,6 +134,7 @@ pub struct SshKeyResponse {
     pub public_key: Option<String>,
     pub app_id: Option<String>,
     pub is_global: bool,
+    pub jump_host: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -35,6 +147,7 @@ impl From<SshKey> for SshKeyResponse {
             public_key: key.public_key,
             app_id: key.app_id,
             is_global: key.is_global != 0,
+            jump_host: key.jump_host,
             created_at: key.created_at,
             updated_at: key.updated_at,
         }
@@ -49,6 +162,7 @@ pub struct CreateSshKeyRequest {
     pub app_id: Option<String>,
     #[serde(default)]
     pub is_global: bool,
+    pub jump_host: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,4 +172,58 @@ pub struct UpdateSshKeyRequest {
     pub public_key: Option<String>,
     pub app_id: Option<String>,
     pub is_global: Option<bool>,
+    /// Empty string removes the jump host
+    pub jump_host: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jump_host() {
+        assert_eq!(
+            JumpHost::parse("deploy@bastion.internal:2222").unwrap(),
+            JumpHost {
+                user: Some("deploy".to_string()),
+                host: "bastion.internal".to_string(),
+                port: Some(2222),
+            }
+        );
+        assert_eq!(JumpHost::parse("10.0.0.1").unwrap().port, None);
+        assert_eq!(JumpHost::parse("[fd00::1]:22").unwrap().host, "fd00::1");
+
+        assert!(JumpHost::parse("").is_err());
+        assert!(JumpHost::parse("@bastion").is_err());
+        assert!(JumpHost::parse("bastion:0").is_err());
+        assert!(JumpHost::parse("-oProxyCommand=x").is_err());
+        assert!(JumpHost::parse("bastion;rm -rf /").is_err());
+    }
+
+    #[test]
+    fn test_git_ssh_command_tunnels_through_jump_host() {
+        let mut key = SshKey {
+            id: "key-1".to_string(),
+            name: "deploy".to_string(),
+            private_key: String::new(),
+            public_key: None,
+            app_id: None,
+            is_global: 1,
+            created_at: String::new(),
+            updated_at: String::new(),
+            jump_host: None,
+        };
+        let key_file = Path::new("/tmp/rivetr-ssh-1");
+        let direct = key.git_ssh_command(key_file);
+        assert!(!direct.contains("ProxyCommand"));
+
+        key.jump_host = Some("deploy@bastion:2222".to_string());
+        assert_eq!(
+            key.git_ssh_command(key_file),
+            format!(
+                "{} -o \"ProxyCommand={} -W %h:%p -p 2222 deploy@bastion\"",
+                direct, direct
+            )
+        );
+    }
 }
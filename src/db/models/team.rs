@@ -132,10 +132,17 @@ pub struct Team {
     /// Whether 2FA is required for all team members
     #[serde(default)]
     pub require_2fa: i64,
+    /// Default build sandbox mode for the team's apps: "none" or "sandboxed"
+    #[serde(default = "default_build_sandbox")]
+    pub build_sandbox: String,
     pub created_at: String,
     pub updated_at: String,
 }
 
+fn default_build_sandbox() -> String {
+    "none".to_string()
+}
+
 /// Team member entity linking users to teams with roles
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TeamMember {
@@ -206,6 +213,8 @@ pub struct CreateTeamRequest {
 pub struct UpdateTeamRequest {
     pub name: Option<String>,
     pub slug: Option<String>,
+    /// Default build sandbox mode for the team's apps: "none" or "sandboxed"
+    pub build_sandbox: Option<String>,
 }
 
 /// Request to invite/add a member to a team
@@ -0,0 +1,147 @@
+//! Per-user preferences: timezone, locale, appearance, notification
+//! severities and email digests.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use super::NOTIFICATION_SEVERITIES;
+use crate::i18n::Locale;
+
+/// Dashboard themes; `system` follows the OS light/dark setting
+pub const THEMES: [&str; 4] = ["system", "light", "dark", "high-contrast"];
+
+/// A user's preferences as stored (`notify_severities` is a JSON array)
+#[derive(Debug, Clone, FromRow)]
+pub struct UserPreferences {
+    pub user_id: String,
+    /// IANA timezone (NULL = UTC); digests are sent in the morning of this zone
+    pub timezone: Option<String>,
+    /// Batch notification email into a daily digest
+    pub email_digests: i32,
+    pub notify_severities: String,
+    /// Language code of the dashboard and the user's messages (NULL = English)
+    pub locale: Option<String>,
+    /// Dashboard theme, one of [`THEMES`] (NULL = system)
+    pub theme: Option<String>,
+    /// Turn off dashboard animations and transitions
+    pub reduced_motion: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl UserPreferences {
+    /// Stored preferences of a user, if they ever saved any
+    pub async fn get(db: &SqlitePool, user_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM user_preferences WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// Severities the user receives; unreadable values fall back to all
+    pub fn severities(&self) -> Vec<String> {
+        serde_json::from_str(&self.notify_severities)
+            .unwrap_or_else(|_| NOTIFICATION_SEVERITIES.map(String::from).to_vec())
+    }
+
+    /// Whether the user receives events of `severity`
+    pub fn receives(&self, severity: &str) -> bool {
+        self.severities().iter().any(|s| s == severity)
+    }
+
+    /// Locale for the user's messages; unknown codes fall back to English
+    pub fn locale(&self) -> Locale {
+        self.locale
+            .as_deref()
+            .and_then(Locale::from_code)
+            .unwrap_or_default()
+    }
+
+    /// Dashboard theme; unknown values fall back to `system`
+    pub fn theme(&self) -> &str {
+        self.theme
+            .as_deref()
+            .filter(|theme| THEMES.contains(theme))
+            .unwrap_or(THEMES[0])
+    }
+}
+
+/// Preferences as returned by the API (defaults for users who never saved any)
+#[derive(Debug, Clone, Serialize)]
+pub struct UserPreferencesResponse {
+    pub timezone: Option<String>,
+    pub email_digests: bool,
+    pub notify_severities: Vec<String>,
+    pub locale: String,
+    pub theme: String,
+    pub reduced_motion: bool,
+}
+
+impl UserPreferencesResponse {
+    pub fn from_stored(prefs: Option<&UserPreferences>) -> Self {
+        match prefs {
+            Some(prefs) => Self {
+                timezone: prefs.timezone.clone(),
+                email_digests: prefs.email_digests != 0,
+                notify_severities: prefs.severities(),
+                locale: prefs.locale().code().to_string(),
+                theme: prefs.theme().to_string(),
+                reduced_motion: prefs.reduced_motion != 0,
+            },
+            None => Self {
+                timezone: None,
+                email_digests: false,
+                notify_severities: NOTIFICATION_SEVERITIES.map(String::from).to_vec(),
+                locale: Locale::default().code().to_string(),
+                theme: THEMES[0].to_string(),
+                reduced_motion: false,
+            },
+        }
+    }
+}
+
+/// Request to update the current user's preferences (omitted fields are kept)
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserPreferencesRequest {
+    /// IANA timezone; empty resets to UTC
+    pub timezone: Option<String>,
+    pub email_digests: Option<bool>,
+    /// Severities to receive: any of `info`, `warning`, `critical`
+    pub notify_severities: Option<Vec<String>>,
+    /// Language code (`en`, `ar`); empty resets to English
+    pub locale: Option<String>,
+    /// One of `system`, `light`, `dark`, `high-contrast`
+    pub theme: Option<String>,
+    pub reduced_motion: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(theme: Option<&str>) -> UserPreferences {
+        UserPreferences {
+            user_id: "u".to_string(),
+            timezone: None,
+            email_digests: 0,
+            notify_severities: "[]".to_string(),
+            locale: None,
+            theme: theme.map(String::from),
+            reduced_motion: 1,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_theme_falls_back_to_system() {
+        assert_eq!(stored(Some("dark")).theme(), "dark");
+        assert_eq!(stored(Some("high-contrast")).theme(), "high-contrast");
+        assert_eq!(stored(None).theme(), "system");
+        assert_eq!(stored(Some("neon")).theme(), "system");
+
+        let response = UserPreferencesResponse::from_stored(Some(&stored(Some("neon"))));
+        assert_eq!(response.theme, "system");
+        assert!(response.reduced_motion);
+    }
+}
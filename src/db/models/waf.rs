@@ -0,0 +1,89 @@
+//! Built-in WAF settings and event models.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::proxy::waf::{WafMode, WafPolicy, DEFAULT_ANOMALY_THRESHOLD};
+
+/// Per-app WAF settings.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AppWafSettings {
+    pub app_id: String,
+    /// 1 = enabled, 0 = disabled
+    pub enabled: i32,
+    /// "block" or "report"
+    pub mode: String,
+    /// Anomaly score at which a request is blocked/reported
+    pub anomaly_threshold: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AppWafSettings {
+    /// Default (disabled) settings for an app with no row yet.
+    pub fn default_for(app_id: &str) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            app_id: app_id.to_string(),
+            enabled: 0,
+            mode: WafMode::Block.as_str().to_string(),
+            anomaly_threshold: DEFAULT_ANOMALY_THRESHOLD as i32,
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub async fn get(db: &SqlitePool, app_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM app_waf_settings WHERE app_id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await
+    }
+
+    /// The proxy policy for these settings, or `None` when the WAF is off.
+    pub fn to_policy(&self) -> Option<WafPolicy> {
+        if self.enabled == 0 {
+            return None;
+        }
+        Some(WafPolicy {
+            app_id: self.app_id.clone(),
+            mode: WafMode::parse(&self.mode).unwrap_or(WafMode::Block),
+            anomaly_threshold: self.anomaly_threshold.max(1) as u32,
+        })
+    }
+
+    /// Load the proxy policy for an app, or `None` when the WAF is off.
+    pub async fn load_policy(
+        db: &SqlitePool,
+        app_id: &str,
+    ) -> Result<Option<WafPolicy>, sqlx::Error> {
+        Ok(Self::get(db, app_id).await?.and_then(|s| s.to_policy()))
+    }
+}
+
+/// Request to update WAF settings for an app.
+#[derive(Debug, Deserialize)]
+pub struct UpdateWafSettingsRequest {
+    pub enabled: Option<bool>,
+    pub mode: Option<String>,
+    pub anomaly_threshold: Option<i32>,
+}
+
+/// A request that reached an app's WAF anomaly threshold.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WafEvent {
+    pub id: i64,
+    pub app_id: String,
+    pub host: String,
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub score: i64,
+    /// Comma-separated IDs of the rules that matched
+    pub matched_rules: String,
+    /// "blocked" or "reported"
+    pub action: String,
+    pub created_at: String,
+}
@@ -0,0 +1,370 @@
+//! Demo data for `rivetr seed --demo`.
+//!
+//! Populates a fresh instance with example projects, apps pointing at public
+//! sample repositories, a service created from a built-in template, and a
+//! month of server stats, so the dashboard can be explored without deploying
+//! real workloads. Nothing here starts a container: the newest deployment of
+//! each app is marked running without a container ID (startup reconciliation
+//! leaves it alone) and the service is stopped.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Timelike, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Project whose presence marks the demo data as already seeded
+const MARKER_PROJECT: &str = "Demo Storefront";
+
+/// Demo projects: (name, description)
+const DEMO_PROJECTS: [(&str, &str); 2] = [
+    (
+        MARKER_PROJECT,
+        "Example web apps built from public sample repositories",
+    ),
+    ("Demo Tools", "Example internal tooling"),
+];
+
+/// An example app, built from a public sample repository
+struct DemoApp {
+    name: &'static str,
+    /// Index into `DEMO_PROJECTS`
+    project: usize,
+    git_url: &'static str,
+    branch: &'static str,
+    build_type: &'static str,
+    port: i32,
+    healthcheck: Option<&'static str>,
+}
+
+const DEMO_APPS: [DemoApp; 3] = [
+    DemoApp {
+        name: "demo-todo",
+        project: 0,
+        git_url: "https://github.com/docker/getting-started-app",
+        branch: "main",
+        build_type: "dockerfile",
+        port: 3000,
+        healthcheck: None,
+    },
+    DemoApp {
+        name: "demo-node",
+        project: 0,
+        git_url: "https://github.com/heroku/node-js-getting-started",
+        branch: "main",
+        build_type: "nixpacks",
+        port: 5006,
+        healthcheck: Some("/"),
+    },
+    DemoApp {
+        name: "demo-python",
+        project: 1,
+        git_url: "https://github.com/heroku/python-getting-started",
+        branch: "main",
+        build_type: "nixpacks",
+        port: 5006,
+        healthcheck: Some("/"),
+    },
+];
+
+/// Deployment history for each app, oldest first: (commit message, status, age in hours)
+const DEMO_DEPLOYMENTS: [(&str, &str, i64); 4] = [
+    ("Initial commit", "stopped", 24 * 9),
+    ("Add health endpoint", "failed", 24 * 4 + 3),
+    ("Fix start script", "stopped", 24 * 4),
+    ("Update dependencies", "running", 5),
+];
+
+/// Built-in template the demo service is created from
+const DEMO_SERVICE_TEMPLATE: &str = "uptime-kuma";
+const DEMO_SERVICE_NAME: &str = "demo-uptime-kuma";
+
+/// Days of hourly and daily stats to generate
+const STATS_DAYS: i64 = 30;
+
+/// What `seed_demo_data` created
+#[derive(Debug, Default)]
+pub struct DemoSummary {
+    pub projects: usize,
+    pub apps: usize,
+    pub deployments: usize,
+    pub services: usize,
+    pub stats_samples: usize,
+}
+
+/// Insert the demo data. Returns `None` when it was already seeded.
+pub async fn seed_demo_data(pool: &SqlitePool) -> Result<Option<DemoSummary>> {
+    let seeded: Option<(String,)> = sqlx::query_as("SELECT id FROM projects WHERE name = ?")
+        .bind(MARKER_PROJECT)
+        .fetch_optional(pool)
+        .await?;
+    if seeded.is_some() {
+        return Ok(None);
+    }
+
+    // Demo resources belong to the first team so team-scoped views show them
+    let team_id: Option<(String,)> =
+        sqlx::query_as("SELECT id FROM teams ORDER BY created_at ASC LIMIT 1")
+            .fetch_optional(pool)
+            .await?;
+    let team_id = team_id.map(|(id,)| id);
+
+    let now = Utc::now();
+    let mut summary = DemoSummary::default();
+    let mut tx = pool.begin().await?;
+
+    let mut project_ids = Vec::new();
+    for (name, description) in DEMO_PROJECTS {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO projects (id, name, description, team_id, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(description)
+        .bind(&team_id)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        project_ids.push(id);
+        summary.projects += 1;
+    }
+
+    for (app_index, app) in DEMO_APPS.iter().enumerate() {
+        let app_id = Uuid::new_v4().to_string();
+        let created_at = now - Duration::days(10);
+        sqlx::query(
+            r#"
+            INSERT INTO apps (id, name, git_url, branch, dockerfile, port, healthcheck, environment, project_id, team_id, build_type, created_at, updated_at)
+            VALUES (?, ?, ?, ?, './Dockerfile', ?, ?, 'development', ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&app_id)
+        .bind(app.name)
+        .bind(app.git_url)
+        .bind(app.branch)
+        .bind(app.port)
+        .bind(app.healthcheck)
+        .bind(&project_ids[app.project])
+        .bind(&team_id)
+        .bind(app.build_type)
+        .bind(created_at.to_rfc3339())
+        .bind(created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        summary.apps += 1;
+
+        for (deploy_index, (message, status, age_hours)) in DEMO_DEPLOYMENTS.iter().enumerate() {
+            let deployment_id = Uuid::new_v4().to_string();
+            let started_at =
+                now - Duration::hours(*age_hours) + Duration::minutes(app_index as i64);
+            let finished_at = started_at + Duration::seconds(40 + 15 * deploy_index as i64);
+            let error_message =
+                (*status == "failed").then_some("Health check failed after 3 attempts");
+            sqlx::query(
+                "INSERT INTO deployments \
+                 (id, app_id, commit_sha, commit_message, status, error_message, started_at, finished_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&deployment_id)
+            .bind(&app_id)
+            .bind(fake_commit_sha(app_index, deploy_index))
+            .bind(message)
+            .bind(status)
+            .bind(error_message)
+            .bind(started_at.to_rfc3339())
+            .bind(finished_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+
+            let mut logs = vec![
+                ("info", format!("Cloning {} ({})", app.git_url, app.branch)),
+                ("info", format!("Building image with {}", app.build_type)),
+                ("info", "Starting container".to_string()),
+            ];
+            match error_message {
+                Some(error) => logs.push(("error", error.to_string())),
+                None => logs.push(("info", "Deployment is healthy".to_string())),
+            }
+            for (level, line) in logs {
+                sqlx::query(
+                    "INSERT INTO deployment_logs (deployment_id, timestamp, level, message) \
+                     VALUES (?, ?, ?, ?)",
+                )
+                .bind(&deployment_id)
+                .bind(started_at.format("%Y-%m-%d %H:%M:%S").to_string())
+                .bind(level)
+                .bind(line)
+                .execute(&mut *tx)
+                .await?;
+            }
+            summary.deployments += 1;
+        }
+    }
+
+    let template: Option<(String,)> =
+        sqlx::query_as("SELECT compose_template FROM service_templates WHERE id = ?")
+            .bind(DEMO_SERVICE_TEMPLATE)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if let Some((compose,)) = template {
+        sqlx::query(
+            "INSERT INTO services (id, name, project_id, team_id, compose_content, port, status, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, 3001, 'stopped', ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(DEMO_SERVICE_NAME)
+        .bind(&project_ids[1])
+        .bind(&team_id)
+        .bind(compose)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to create the demo service")?;
+        summary.services += 1;
+    }
+
+    summary.stats_samples = seed_stats(&mut tx, now).await?;
+
+    tx.commit().await?;
+    Ok(Some(summary))
+}
+
+/// Fill the stats tables the dashboard charts read: 5-minute samples for the
+/// last day, hourly rollups and daily rollups for the last month. Existing
+/// rows are kept.
+async fn seed_stats(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    const MEMORY_TOTAL: i64 = 8 * 1024 * 1024 * 1024;
+    let mut samples = 0;
+
+    let now = now.with_second(0).unwrap_or(now);
+    for step in 0..(24 * 12) {
+        let at = now - Duration::minutes(5 * step);
+        let (cpu, memory) = sample_at(at, MEMORY_TOTAL);
+        sqlx::query(
+            "INSERT INTO stats_history \
+             (timestamp, cpu_percent, memory_used_bytes, memory_total_bytes, running_apps, running_databases, running_services) \
+             VALUES (?, ?, ?, ?, ?, 0, 0)",
+        )
+        .bind(at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(cpu)
+        .bind(memory)
+        .bind(MEMORY_TOTAL)
+        .bind(DEMO_APPS.len() as i64)
+        .execute(&mut **tx)
+        .await?;
+        samples += 1;
+    }
+
+    for hour in 1..=(STATS_DAYS * 24) {
+        let at = now - Duration::hours(hour);
+        let (cpu, memory) = sample_at(at, MEMORY_TOTAL);
+        sqlx::query(
+            "INSERT OR IGNORE INTO stats_hourly \
+             (hour_timestamp, avg_cpu_percent, max_cpu_percent, min_cpu_percent, avg_memory_used_bytes, \
+              max_memory_used_bytes, avg_memory_total_bytes, avg_running_apps, avg_running_databases, \
+              avg_running_services, sample_count) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0, 0, 12)",
+        )
+        .bind(at.format("%Y-%m-%d %H:00:00").to_string())
+        .bind(cpu)
+        .bind(cpu * 1.6)
+        .bind(cpu * 0.5)
+        .bind(memory)
+        .bind(memory + memory / 10)
+        .bind(MEMORY_TOTAL)
+        .bind(DEMO_APPS.len() as f64)
+        .execute(&mut **tx)
+        .await?;
+        samples += 1;
+    }
+
+    for day in 1..=STATS_DAYS {
+        let at = now - Duration::days(day);
+        let (cpu, memory) = sample_at(at.with_hour(12).unwrap_or(at), MEMORY_TOTAL);
+        sqlx::query(
+            "INSERT OR IGNORE INTO stats_daily \
+             (day_timestamp, avg_cpu_percent, max_cpu_percent, min_cpu_percent, avg_memory_used_bytes, \
+              max_memory_used_bytes, avg_memory_total_bytes, avg_running_apps, max_running_apps, \
+              avg_running_databases, max_running_databases, avg_running_services, max_running_services, \
+              sample_count) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 0, 0, 0, 288)",
+        )
+        .bind(at.format("%Y-%m-%d").to_string())
+        .bind(cpu * 0.8)
+        .bind(cpu * 2.2)
+        .bind(cpu * 0.2)
+        .bind(memory)
+        .bind(memory + memory / 5)
+        .bind(MEMORY_TOTAL)
+        .bind(DEMO_APPS.len() as f64)
+        .bind(DEMO_APPS.len() as i64)
+        .execute(&mut **tx)
+        .await?;
+        samples += 1;
+    }
+
+    Ok(samples)
+}
+
+/// CPU percent and memory used at a point in time: a daily cycle peaking in
+/// the afternoon with some minute-to-minute noise
+fn sample_at(at: DateTime<Utc>, memory_total: i64) -> (f64, i64) {
+    let minutes = (at.timestamp() / 60) as f64;
+    let day_phase = (minutes / (24.0 * 60.0)) * std::f64::consts::TAU;
+    let daily = (day_phase - std::f64::consts::FRAC_PI_2 * 3.0).sin();
+    let noise = (minutes * 0.37).sin() * 0.5 + (minutes * 1.13).cos() * 0.5;
+
+    let cpu = (22.0 + 14.0 * daily + 5.0 * noise).clamp(1.0, 100.0);
+    let memory_share = 0.42 + 0.08 * daily + 0.02 * noise;
+    (
+        (cpu * 10.0).round() / 10.0,
+        (memory_total as f64 * memory_share) as i64,
+    )
+}
+
+/// Stable 40-character hex SHA for a demo deployment
+fn fake_commit_sha(app_index: usize, deploy_index: usize) -> String {
+    let seed =
+        (app_index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (deploy_index as u64 + 1);
+    let mut sha = String::with_capacity(40);
+    let mut state = seed;
+    while sha.len() < 40 {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        sha.push_str(&format!("{:016x}", state));
+    }
+    sha.truncate(40);
+    sha
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_stay_in_range() {
+        let start = Utc::now();
+        for hour in 0..48 {
+            let (cpu, memory) = sample_at(start - Duration::hours(hour), 1000);
+            assert!((1.0..=100.0).contains(&cpu));
+            assert!((0..1000).contains(&memory));
+        }
+    }
+
+    #[test]
+    fn test_fake_commit_shas_are_stable_and_distinct() {
+        let sha = fake_commit_sha(0, 0);
+        assert_eq!(sha.len(), 40);
+        assert!(sha.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(sha, fake_commit_sha(0, 0));
+        assert_ne!(sha, fake_commit_sha(0, 1));
+        assert_ne!(sha, fake_commit_sha(1, 0));
+    }
+}
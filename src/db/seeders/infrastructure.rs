@@ -196,7 +196,7 @@ volumes:
         (
             "grafana-prometheus",
             "Grafana + Prometheus",
-            "Complete monitoring stack with Prometheus for metrics collection and Grafana for visualization.",
+            "Complete monitoring stack with Prometheus for metrics collection and Grafana for visualization. Prometheus scrapes Rivetr apps that set a metrics port.",
             "monitoring",
             "grafana",
             r#"services:
@@ -208,8 +208,9 @@ volumes:
       - "${PROMETHEUS_PORT:-9090}:9090"
     volumes:
       - prometheus_data:/prometheus
+      - ${RIVETR_PROMETHEUS_DIR}:/etc/prometheus/rivetr:ro
     command:
-      - "--config.file=/etc/prometheus/prometheus.yml"
+      - "--config.file=/etc/prometheus/rivetr/prometheus.yml"
       - "--storage.tsdb.path=/prometheus"
       - "--web.enable-lifecycle"
     labels:
@@ -9,6 +9,7 @@ mod cms_communication;
 mod cms_extra;
 mod communication_extra;
 mod databases_tools;
+mod demo;
 mod devtools;
 mod devtools_extra;
 mod documentation;
@@ -19,6 +20,7 @@ mod media_productivity;
 mod misc_extras;
 mod monitoring_extra;
 mod networking_extra;
+mod post_deploy;
 mod project_mgmt;
 mod security_search;
 mod sprint15;
@@ -33,6 +35,8 @@ mod sprint24;
 mod sprint25;
 mod sprint26;
 
+pub use demo::{seed_demo_data, DemoSummary};
+
 use anyhow::Result;
 use sqlx::SqlitePool;
 use tracing::info;
@@ -117,6 +121,18 @@ pub async fn seed_service_templates(pool: &SqlitePool) -> Result<()> {
         .await?;
     }
 
+    for (id, notes, url_path, health_path) in post_deploy::entries() {
+        sqlx::query(
+            "UPDATE service_templates SET post_deploy_notes = ?, url_path = ?, health_path = ? WHERE id = ?",
+        )
+        .bind(notes)
+        .bind(url_path)
+        .bind(health_path)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    }
+
     info!("Seeded {} built-in service templates", template_count);
     Ok(())
 }
@@ -0,0 +1,46 @@
+//! Post-deploy information for built-in service templates: notes shown after
+//! deploying (`${VAR}` placeholders are filled with the deployed values,
+//! `${SERVICE_URL}` with the service's URL), the primary page path and the
+//! health check path.
+
+pub type PostDeployEntry = (
+    &'static str,         // template id
+    &'static str,         // post_deploy_notes
+    Option<&'static str>, // url_path
+    Option<&'static str>, // health_path
+);
+
+pub fn entries() -> Vec<PostDeployEntry> {
+    vec![
+        (
+            "portainer",
+            "Create the admin account at ${SERVICE_URL} within 5 minutes of the first start. After that Portainer locks the setup page until the service is restarted.",
+            None,
+            Some("/api/system/status"),
+        ),
+        (
+            "gitea",
+            "Finish the installation at ${SERVICE_URL}. Set the base URL to ${SERVICE_URL}/ so clone links are correct; Git over SSH listens on port ${SSH_PORT}.",
+            None,
+            Some("/api/healthz"),
+        ),
+        (
+            "uptime-kuma",
+            "Open ${SERVICE_URL} and create the admin account. The first visitor becomes the administrator, so do this right away.",
+            None,
+            None,
+        ),
+        (
+            "tpl-grafana",
+            "Sign in at ${SERVICE_URL}/login as ${GF_SECURITY_ADMIN_USER} with the admin password you set. Set the Root URL to ${SERVICE_URL} for links in alerts to work.",
+            Some("/login"),
+            Some("/api/health"),
+        ),
+        (
+            "minio",
+            "The S3 API is at ${SERVICE_URL}. The web console listens on port ${CONSOLE_PORT} of the server; sign in as ${ROOT_USER}.",
+            None,
+            Some("/minio/health/live"),
+        ),
+    ]
+}
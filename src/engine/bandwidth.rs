@@ -0,0 +1,287 @@
+//! Per-app bandwidth accounting and monthly caps.
+//!
+//! Every 60 seconds, the request and response body bytes the proxy counted
+//! per domain are added to the `app_bandwidth` ledger of the app owning the
+//! domain, under the current calendar month (UTC). The network counters of
+//! each app's running containers are recorded alongside under the `container`
+//! source. They also cover traffic that never passes through the proxy
+//! (outgoing API calls, database connections) and are informational only.
+//!
+//! Apps with `bandwidth_limit_gb` set are checked against their proxy traffic
+//! for the month. The first time an app exceeds its cap in a month, a
+//! `bandwidth_exceeded` notification is sent. With the `throttle` action its
+//! domains are also limited to `bandwidth_throttle_kbps` until the month ends
+//! or the cap is raised. WebSocket traffic is not counted.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::time::interval;
+
+use crate::db::{App, NotificationEventType, BANDWIDTH_ACTION_THROTTLE};
+use crate::notifications::{outbox, NotificationPayload};
+use crate::proxy::traffic;
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+/// Ledger source for the network counters of an app's containers
+pub const CONTAINER_SOURCE: &str = "container";
+
+/// The ledger period (calendar month, `YYYY-MM`) a time falls in
+pub fn period_of(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m").to_string()
+}
+
+/// Bytes a cumulative container counter grew by since the previous reading.
+/// The first reading only sets the baseline; a counter that went down means
+/// the container restarted, so everything it counted since is new.
+pub fn counter_delta(previous: Option<u64>, current: u64) -> u64 {
+    match previous {
+        None => 0,
+        Some(previous) if current < previous => current,
+        Some(previous) => current - previous,
+    }
+}
+
+/// Add bytes to an app's ledger row for a period and source
+async fn record(
+    db: &DbPool,
+    app_id: &str,
+    period: &str,
+    source: &str,
+    bytes_in: u64,
+    bytes_out: u64,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO app_bandwidth (app_id, period, source, bytes_in, bytes_out, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(app_id, period, source) DO UPDATE SET \
+         bytes_in = bytes_in + excluded.bytes_in, \
+         bytes_out = bytes_out + excluded.bytes_out, \
+         updated_at = excluded.updated_at",
+    )
+    .bind(app_id)
+    .bind(period)
+    .bind(source)
+    .bind(bytes_in as i64)
+    .bind(bytes_out as i64)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Proxy traffic (bytes in + out) an app has used in a period
+pub async fn proxy_bytes_used(db: &DbPool, app_id: &str, period: &str) -> Result<u64> {
+    let used: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(bytes_in + bytes_out), 0) FROM app_bandwidth \
+         WHERE app_id = ? AND period = ? AND source != ?",
+    )
+    .bind(app_id)
+    .bind(period)
+    .bind(CONTAINER_SOURCE)
+    .fetch_one(db)
+    .await?;
+    Ok(used.max(0) as u64)
+}
+
+fn exceeded_message(app: &App, used: u64, period: &str) -> String {
+    let mut message = format!(
+        "{} used {:.1} GB of its {} GB bandwidth limit for {}",
+        app.name,
+        used as f64 / 1_000_000_000.0,
+        app.bandwidth_limit_gb.unwrap_or_default(),
+        period
+    );
+    if app.bandwidth_limit_action == BANDWIDTH_ACTION_THROTTLE {
+        message.push_str(&format!(
+            ". Its domains are throttled to {} kbps until the end of the month.",
+            app.bandwidth_throttle_bytes_per_sec() * 8 / 1000
+        ));
+    }
+    message
+}
+
+/// Flushes proxy and container traffic into the ledger and enforces caps
+struct BandwidthAccountant {
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    /// Last (rx, tx) reading per running container
+    baselines: HashMap<String, (u64, u64)>,
+}
+
+impl BandwidthAccountant {
+    /// Add the bytes the proxy counted per domain to the owning apps
+    async fn flush_proxy(&self, apps: &[App], period: &str) -> Result<()> {
+        let owners: HashMap<String, &str> = apps
+            .iter()
+            .flat_map(|app| {
+                app.get_all_domain_names()
+                    .into_iter()
+                    .map(move |domain| (domain, app.id.as_str()))
+            })
+            .collect();
+        for (domain, bytes_in, bytes_out) in traffic::take_bandwidth() {
+            // Domains of services, previews or deleted apps aren't billed
+            if let Some(app_id) = owners.get(&domain) {
+                record(&self.db, app_id, period, &domain, bytes_in, bytes_out).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the growth of each running container's network counters
+    async fn flush_containers(&mut self, period: &str) -> Result<()> {
+        let containers: Vec<(String, String)> = sqlx::query_as(
+            "SELECT app_id, container_id FROM deployments WHERE status = 'running' \
+             AND container_id IS NOT NULL AND container_id != ''",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut baselines = HashMap::new();
+        let mut per_app: HashMap<String, (u64, u64)> = HashMap::new();
+        for (app_id, container_id) in containers {
+            let Ok(stats) = self.runtime.stats(&container_id).await else {
+                continue;
+            };
+            let previous = self.baselines.get(&container_id);
+            let rx = counter_delta(previous.map(|p| p.0), stats.network_rx);
+            let tx = counter_delta(previous.map(|p| p.1), stats.network_tx);
+            baselines.insert(container_id, (stats.network_rx, stats.network_tx));
+            let totals = per_app.entry(app_id).or_default();
+            totals.0 += rx;
+            totals.1 += tx;
+        }
+        // Stopped containers drop out of the baselines
+        self.baselines = baselines;
+
+        for (app_id, (rx, tx)) in per_app {
+            if rx > 0 || tx > 0 {
+                record(&self.db, &app_id, period, CONTAINER_SOURCE, rx, tx).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Notify about apps over their cap and return the rate each domain of a
+    /// throttled app is limited to
+    async fn enforce_limits(&self, apps: &[App], period: &str) -> Result<HashMap<String, u64>> {
+        let mut throttles = HashMap::new();
+        for app in apps {
+            let Some(limit) = app.bandwidth_limit_bytes() else {
+                continue;
+            };
+            let used = proxy_bytes_used(&self.db, &app.id, period).await?;
+            if used < limit {
+                continue;
+            }
+
+            if app.bandwidth_limit_action == BANDWIDTH_ACTION_THROTTLE {
+                let rate = app.bandwidth_throttle_bytes_per_sec();
+                for domain in app.get_all_domain_names() {
+                    throttles.insert(domain, rate);
+                }
+            }
+            if app.bandwidth_exceeded_period.as_deref() == Some(period) {
+                continue;
+            }
+
+            sqlx::query("UPDATE apps SET bandwidth_exceeded_period = ? WHERE id = ?")
+                .bind(period)
+                .bind(&app.id)
+                .execute(&self.db)
+                .await?;
+            tracing::warn!(
+                app = %app.name,
+                used_bytes = used,
+                limit_gb = app.bandwidth_limit_gb,
+                action = %app.bandwidth_limit_action,
+                "App exceeded its monthly bandwidth limit"
+            );
+            let payload = NotificationPayload::app_event(
+                NotificationEventType::BandwidthExceeded,
+                app.id.clone(),
+                app.name.clone(),
+                exceeded_message(app, used, period),
+            );
+            if let Err(e) = outbox::enqueue(&self.db, &payload).await {
+                tracing::warn!(error = %e, "Failed to queue bandwidth_exceeded notification");
+            }
+        }
+        Ok(throttles)
+    }
+
+    async fn run_cycle(&mut self) -> Result<()> {
+        let period = period_of(Utc::now());
+        let apps: Vec<App> = sqlx::query_as("SELECT * FROM apps")
+            .fetch_all(&self.db)
+            .await?;
+
+        self.flush_proxy(&apps, &period).await?;
+        if let Err(e) = self.flush_containers(&period).await {
+            tracing::warn!(error = %e, "Failed to record container network traffic");
+        }
+
+        let throttles = self.enforce_limits(&apps, &period).await?;
+        let wanted: HashSet<&String> = throttles.keys().collect();
+        for domain in traffic::throttled_domains() {
+            if !wanted.contains(&domain) {
+                tracing::info!(domain = %domain, "Lifting bandwidth throttle");
+                traffic::set_throttle(&domain, None);
+            }
+        }
+        for (domain, rate) in &throttles {
+            traffic::set_throttle(domain, Some(*rate));
+        }
+        Ok(())
+    }
+}
+
+/// Spawn the background bandwidth accountant (runs every 60 seconds)
+pub fn spawn_bandwidth_accountant(db: DbPool, runtime: Arc<dyn ContainerRuntime>) {
+    tracing::info!("Starting bandwidth accountant (60s interval)");
+
+    tokio::spawn(async move {
+        let mut accountant = BandwidthAccountant {
+            db,
+            runtime,
+            baselines: HashMap::new(),
+        };
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("bandwidth", async {
+                if let Err(e) = accountant.run_cycle().await {
+                    tracing::warn!(error = %e, "Bandwidth accounting cycle failed");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_period_of() {
+        let time = Utc.with_ymd_and_hms(2026, 3, 31, 23, 59, 59).unwrap();
+        assert_eq!(period_of(time), "2026-03");
+    }
+
+    #[test]
+    fn test_counter_delta() {
+        assert_eq!(counter_delta(None, 5000), 0);
+        assert_eq!(counter_delta(Some(1000), 5000), 4000);
+        // Container restarted and its counters began again from zero
+        assert_eq!(counter_delta(Some(5000), 300), 300);
+    }
+}
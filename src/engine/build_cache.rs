@@ -0,0 +1,282 @@
+//! Persistent per-app build caches.
+//!
+//! Docker builds of an app import the BuildKit layer cache of its previous
+//! build from `<data_dir>/build-cache/<app_id>` and export their own there
+//! (config: `[build_cache]`), so incremental builds only redo the changed
+//! layers. A cache over `max_size_mb` is dropped after the build; the pruner
+//! removes caches unused for `max_age_days` and those of deleted apps.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::time::interval;
+
+use crate::config::BuildCacheConfig;
+use crate::db::App;
+use crate::runtime::BuildCache;
+use crate::DbPool;
+
+use super::{add_deployment_log, BuildLimits};
+
+/// Directory under `data_dir` holding the per-app caches
+pub const CACHE_DIR_NAME: &str = "build-cache";
+
+/// How often caches are checked for pruning
+const PRUNE_INTERVAL_SECS: u64 = 3600;
+
+/// Directory holding the per-app build caches
+pub fn cache_root(data_dir: &Path) -> PathBuf {
+    data_dir.join(CACHE_DIR_NAME)
+}
+
+/// Build cache for a build of `app`, or `None` when caching is disabled for
+/// the instance or the app. Sandboxed builds never use it: untrusted code must
+/// not be able to poison the layers of the app's regular builds.
+pub fn for_app(limits: &BuildLimits, app: &App, sandboxed: bool) -> Option<BuildCache> {
+    let root = limits.cache_root.as_ref()?;
+    if !limits.cache.enabled || sandboxed || app.disable_build_cache != 0 {
+        return None;
+    }
+    Some(BuildCache {
+        dir: root.join(&app.id).to_string_lossy().to_string(),
+        builder: limits.cache.builder().map(String::from),
+    })
+}
+
+/// Total size of the files under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Drop the cache a build exported when it exceeds the size limit, so the
+/// next build starts cold instead of the cache growing without bound
+pub async fn enforce_size_limit(
+    db: &DbPool,
+    deployment_id: &str,
+    limits: &BuildLimits,
+    cache: &BuildCache,
+) {
+    let dir = PathBuf::from(&cache.dir);
+    let size = tokio::task::spawn_blocking({
+        let dir = dir.clone();
+        move || dir_size(&dir)
+    })
+    .await
+    .unwrap_or(0);
+    if size == 0 {
+        return;
+    }
+
+    let size_mb = size / (1024 * 1024);
+    let message = if size_mb > limits.cache.max_size_mb {
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        format!(
+            "Build cache ({} MB) exceeds the {} MB limit and was dropped; the next build starts cold",
+            size_mb, limits.cache.max_size_mb
+        )
+    } else {
+        format!("Build cache stored ({} MB)", size_mb)
+    };
+    let _ = add_deployment_log(db, deployment_id, "info", &message).await;
+}
+
+/// Remove caches of deleted apps and caches unused for `max_age_days`.
+/// Returns how many were removed.
+pub async fn prune(db: &DbPool, root: &Path, config: &BuildCacheConfig) -> usize {
+    let Ok(mut entries) = tokio::fs::read_dir(root).await else {
+        return 0;
+    };
+    let app_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM apps")
+        .fetch_all(db)
+        .await
+        .unwrap_or_default();
+    if app_ids.is_empty() {
+        // Don't wipe every cache on a transient database error
+        return 0;
+    }
+    let max_age = Duration::from_secs(config.max_age_days * 24 * 3600);
+
+    let mut removed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // `<app_id>.new` is the staging directory of a build in progress
+        let app_id = name.strip_suffix(".new").unwrap_or(&name);
+        let unused_for = entry
+            .metadata()
+            .await
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+        if app_ids.iter().any(|id| id == app_id) && unused_for < max_age {
+            continue;
+        }
+        if tokio::fs::remove_dir_all(entry.path()).await.is_ok() {
+            tracing::info!(cache = %name, "Pruned build cache");
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Spawn the task pruning build caches hourly
+pub fn spawn_build_cache_pruner(db: DbPool, data_dir: PathBuf, config: BuildCacheConfig) {
+    if !config.enabled {
+        return;
+    }
+    let root = cache_root(&data_dir);
+    tracing::info!(
+        "Starting build cache pruner (max age {} days)",
+        config.max_age_days
+    );
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(PRUNE_INTERVAL_SECS));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("build_cache_pruner", prune(&db, &root, &config))
+                .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuntimeConfig;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool};
+
+    fn limits(root: &Path) -> BuildLimits {
+        let mut limits = BuildLimits::from_runtime_config(&RuntimeConfig::default());
+        limits.cache_root = Some(cache_root(root));
+        limits
+    }
+
+    fn write_file(path: &Path, len: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; len]).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_for_app() {
+        let (dir, db) = test_pool().await;
+        let mut app = load_test_app(&db, &test_app(&db, "web").await).await;
+        let mut limits = limits(dir.path());
+
+        let cache = for_app(&limits, &app, false).unwrap();
+        assert_eq!(
+            PathBuf::from(&cache.dir),
+            dir.path().join(CACHE_DIR_NAME).join(&app.id)
+        );
+        assert_eq!(cache.builder, None);
+
+        // An empty builder is the default builder
+        limits.cache.builder = Some(String::new());
+        assert_eq!(for_app(&limits, &app, false).unwrap().builder, None);
+        limits.cache.builder = Some("rivetr-cache".to_string());
+        assert_eq!(
+            for_app(&limits, &app, false).unwrap().builder.as_deref(),
+            Some("rivetr-cache")
+        );
+
+        // Sandboxed builds, opted-out apps and disabled caching build without one
+        assert!(for_app(&limits, &app, true).is_none());
+        app.disable_build_cache = 1;
+        assert!(for_app(&limits, &app, false).is_none());
+        app.disable_build_cache = 0;
+        limits.cache.enabled = false;
+        assert!(for_app(&limits, &app, false).is_none());
+        limits.cache.enabled = true;
+        limits.cache_root = None;
+        assert!(for_app(&limits, &app, false).is_none());
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(dir_size(&dir.path().join("missing")), 0);
+        write_file(&dir.path().join("index.json"), 100);
+        write_file(&dir.path().join("blobs/sha256/a"), 1000);
+        write_file(&dir.path().join("blobs/sha256/b"), 24);
+        assert_eq!(dir_size(dir.path()), 1124);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_size_limit() {
+        let (dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let deployment_id = test_deployment(&db, &app_id, "building").await;
+        let mut limits = limits(dir.path());
+        limits.cache.max_size_mb = 1;
+        let cache_dir = cache_root(dir.path()).join(&app_id);
+        let cache = BuildCache {
+            dir: cache_dir.to_string_lossy().to_string(),
+            builder: None,
+        };
+        let logs = || async {
+            sqlx::query_scalar::<_, String>(
+                "SELECT message FROM deployment_logs WHERE deployment_id = ? ORDER BY id",
+            )
+            .bind(&deployment_id)
+            .fetch_all(&db)
+            .await
+            .unwrap()
+        };
+
+        // No cache exported: nothing to report
+        enforce_size_limit(&db, &deployment_id, &limits, &cache).await;
+        assert!(logs().await.is_empty());
+
+        write_file(&cache_dir.join("blobs/a"), 512 * 1024);
+        enforce_size_limit(&db, &deployment_id, &limits, &cache).await;
+        assert!(cache_dir.exists());
+        assert_eq!(logs().await, vec!["Build cache stored (0 MB)".to_string()]);
+
+        write_file(&cache_dir.join("blobs/b"), 2 * 1024 * 1024);
+        enforce_size_limit(&db, &deployment_id, &limits, &cache).await;
+        assert!(!cache_dir.exists());
+        assert!(logs().await[1].starts_with("Build cache (2 MB) exceeds the 1 MB limit"));
+    }
+
+    #[tokio::test]
+    async fn test_prune() {
+        let (dir, db) = test_pool().await;
+        let root = cache_root(dir.path());
+        let mut config = BuildCacheConfig::default();
+
+        // Without a cache directory or apps there is nothing to do
+        assert_eq!(prune(&db, &root, &config).await, 0);
+        write_file(&root.join("deleted-app/index.json"), 10);
+        assert_eq!(prune(&db, &root, &config).await, 0);
+        assert!(root.join("deleted-app").exists());
+
+        let app_id = test_app(&db, "web").await;
+        write_file(&root.join(&app_id).join("index.json"), 10);
+        write_file(&root.join(format!("{}.new", app_id)).join("index.json"), 10);
+        write_file(&root.join("deleted-app.new/index.json"), 10);
+
+        // Caches of deleted apps go, including their staging directories
+        assert_eq!(prune(&db, &root, &config).await, 2);
+        assert!(!root.join("deleted-app").exists());
+        assert!(!root.join("deleted-app.new").exists());
+        assert!(root.join(&app_id).exists());
+        assert!(root.join(format!("{}.new", app_id)).exists());
+
+        // Caches unused for longer than max_age_days go too
+        config.max_age_days = 0;
+        assert_eq!(prune(&db, &root, &config).await, 2);
+        assert!(!root.join(&app_id).exists());
+    }
+}
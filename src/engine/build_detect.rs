@@ -4,11 +4,16 @@
 //! for deploying applications.
 
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use tokio::fs;
 use tracing::{debug, info};
 
+use super::build_sandbox::instructions;
+
 /// Supported build types for applications
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -78,6 +83,18 @@ pub struct BuildDetectionResult {
     pub confidence: f32,
     /// Additional recommendations or notes
     pub notes: Option<String>,
+    /// Named stages of a multi-stage Dockerfile, usable as `build_target`
+    #[serde(default)]
+    pub build_targets: Vec<String>,
+    /// Ports the Dockerfile's final stage exposes
+    #[serde(default)]
+    pub exposed_ports: Vec<u16>,
+    /// Port to pre-fill for the app (first exposed port, else the healthcheck's)
+    #[serde(default)]
+    pub suggested_port: Option<u16>,
+    /// Hint taken from the final stage's `HEALTHCHECK`
+    #[serde(default)]
+    pub healthcheck: Option<HealthcheckHint>,
 }
 
 impl BuildDetectionResult {
@@ -88,6 +105,10 @@ impl BuildDetectionResult {
             detected_from: detected_from.into(),
             confidence: 1.0,
             notes: None,
+            build_targets: Vec::new(),
+            exposed_ports: Vec::new(),
+            suggested_port: None,
+            healthcheck: None,
         }
     }
 
@@ -105,6 +126,153 @@ impl BuildDetectionResult {
         self.notes = Some(notes.into());
         self
     }
+
+    fn with_dockerfile_hints(mut self, hints: DockerfileHints) -> Self {
+        self.suggested_port = hints.suggested_port();
+        self.build_targets = hints.targets;
+        self.exposed_ports = hints.exposed_ports;
+        self.healthcheck = hints.healthcheck;
+        self
+    }
+}
+
+/// Health check declared by a Dockerfile `HEALTHCHECK` instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthcheckHint {
+    /// The check command as written after `CMD`
+    pub command: String,
+    /// HTTP path the command requests on the container itself, if any
+    pub path: Option<String>,
+    /// Port the command requests on the container itself, if any
+    pub port: Option<u16>,
+}
+
+/// Settings a Dockerfile declares that an app can be pre-filled from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DockerfileHints {
+    /// Named stages in declaration order
+    pub targets: Vec<String>,
+    /// TCP ports exposed by the final stage (including inherited stages)
+    pub exposed_ports: Vec<u16>,
+    /// Health check of the final stage (including inherited stages)
+    pub healthcheck: Option<HealthcheckHint>,
+}
+
+lazy_static! {
+    /// A URL on the container itself, as probed by curl/wget health checks
+    static ref LOCAL_URL: Regex = Regex::new(
+        r"https?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1\])(?::(\d+))?(/[^\s'\x22,\]]*)?"
+    )
+    .expect("local URL pattern must compile");
+}
+
+impl DockerfileHints {
+    /// Parse stages, `EXPOSE` and `HEALTHCHECK` from Dockerfile contents.
+    ///
+    /// Exposed ports and health checks carry over when a stage is built
+    /// `FROM` an earlier one, as they do in the image config.
+    pub fn parse(contents: &str) -> Self {
+        // Per-stage state: (exposed ports, healthcheck)
+        type Stage = (Vec<u16>, Option<HealthcheckHint>);
+        let mut stages: HashMap<String, Stage> = HashMap::new();
+        let mut targets = Vec::new();
+        let mut current: Stage = (Vec::new(), None);
+        let mut current_name: Option<String> = None;
+
+        for instruction in instructions(contents) {
+            let (keyword, rest) = instruction
+                .trim()
+                .split_once(char::is_whitespace)
+                .unwrap_or((instruction.trim(), ""));
+            let rest = rest.trim();
+
+            match keyword.to_ascii_uppercase().as_str() {
+                "FROM" => {
+                    if let Some(name) = current_name.take() {
+                        stages.insert(name, std::mem::take(&mut current));
+                    }
+                    let operands: Vec<&str> = rest
+                        .split_whitespace()
+                        .filter(|arg| !arg.starts_with("--"))
+                        .collect();
+                    let base = operands.first().map(|b| b.to_ascii_lowercase());
+                    current = base
+                        .and_then(|b| stages.get(&b).cloned())
+                        .unwrap_or_default();
+                    if operands.len() >= 3 && operands[1].eq_ignore_ascii_case("as") {
+                        let name = operands[2].to_string();
+                        current_name = Some(name.to_ascii_lowercase());
+                        targets.push(name);
+                    }
+                }
+                "EXPOSE" => {
+                    for spec in rest.split_whitespace() {
+                        let (port, protocol) = spec.split_once('/').unwrap_or((spec, "tcp"));
+                        if !protocol.eq_ignore_ascii_case("tcp") {
+                            continue;
+                        }
+                        if let Ok(port) = port.parse::<u16>() {
+                            if port > 0 && !current.0.contains(&port) {
+                                current.0.push(port);
+                            }
+                        }
+                    }
+                }
+                "HEALTHCHECK" => {
+                    current.1 = parse_healthcheck(rest);
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            targets,
+            exposed_ports: current.0,
+            healthcheck: current.1,
+        }
+    }
+
+    /// Port to pre-fill for the app: the first exposed port, else the port
+    /// the health check probes
+    pub fn suggested_port(&self) -> Option<u16> {
+        self.exposed_ports
+            .first()
+            .copied()
+            .or_else(|| self.healthcheck.as_ref().and_then(|h| h.port))
+    }
+}
+
+/// Parse the arguments of a `HEALTHCHECK` instruction (`NONE` clears it)
+fn parse_healthcheck(args: &str) -> Option<HealthcheckHint> {
+    let command = args
+        .split_whitespace()
+        .skip_while(|arg| arg.starts_with("--"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let command = command
+        .strip_prefix("CMD")
+        .or_else(|| command.strip_prefix("cmd"))?
+        .trim()
+        .to_string();
+    if command.is_empty() {
+        return None;
+    }
+
+    let url = LOCAL_URL.captures(&command);
+    let port = url
+        .as_ref()
+        .and_then(|c| c.get(1))
+        .and_then(|p| p.as_str().parse().ok());
+    let path = url.as_ref().map(|c| {
+        c.get(2)
+            .map(|p| p.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string())
+    });
+    Some(HealthcheckHint {
+        command,
+        path,
+        port,
+    })
 }
 
 /// Detect the build type by analyzing files in the source directory
@@ -222,10 +390,10 @@ async fn detect_dockerfile(source_dir: &Path) -> Result<Option<BuildDetectionRes
         let path = source_dir.join(name);
         if path.exists() {
             debug!("Found {} at {:?}", name, path);
-            return Ok(Some(BuildDetectionResult::new(
-                BuildType::Dockerfile,
-                format!("{} found", name),
-            )));
+            return Ok(Some(
+                BuildDetectionResult::new(BuildType::Dockerfile, format!("{} found", name))
+                    .with_dockerfile_hints(read_dockerfile_hints(&path).await),
+            ));
         }
     }
 
@@ -241,7 +409,8 @@ async fn detect_dockerfile(source_dir: &Path) -> Result<Option<BuildDetectionRes
                         BuildType::Dockerfile,
                         format!("{}/{} found", subdir, name),
                     )
-                    .with_notes(format!("Dockerfile path: {}/{}", subdir, name)),
+                    .with_notes(format!("Dockerfile path: {}/{}", subdir, name))
+                    .with_dockerfile_hints(read_dockerfile_hints(&path).await),
                 ));
             }
         }
@@ -250,6 +419,17 @@ async fn detect_dockerfile(source_dir: &Path) -> Result<Option<BuildDetectionRes
     Ok(None)
 }
 
+/// Hints from a Dockerfile; an unreadable file simply yields none
+async fn read_dockerfile_hints(path: &Path) -> DockerfileHints {
+    match fs::read_to_string(path).await {
+        Ok(contents) => DockerfileHints::parse(&contents),
+        Err(e) => {
+            debug!("Could not read {:?} for build hints: {}", path, e);
+            DockerfileHints::default()
+        }
+    }
+}
+
 /// Check for docker-compose files
 async fn detect_docker_compose(source_dir: &Path) -> Result<Option<BuildDetectionResult>> {
     let compose_files = [
@@ -1335,4 +1515,73 @@ export default { kit: { adapter: adapter() } };
             Some(".".to_string())
         );
     }
+
+    #[test]
+    fn test_dockerfile_hints_multi_stage() {
+        let hints = DockerfileHints::parse(
+            r#"
+FROM node:20 AS deps
+RUN npm ci
+
+FROM deps AS builder
+EXPOSE 9229
+RUN npm run build
+
+FROM node:20-slim AS runner
+EXPOSE 8080/tcp 53/udp
+HEALTHCHECK --interval=30s --timeout=3s \
+  CMD curl -f http://localhost:8080/healthz || exit 1
+CMD ["node", "server.js"]
+"#,
+        );
+
+        assert_eq!(hints.targets, vec!["deps", "builder", "runner"]);
+        assert_eq!(hints.exposed_ports, vec![8080]);
+        assert_eq!(hints.suggested_port(), Some(8080));
+        let healthcheck = hints.healthcheck.unwrap();
+        assert_eq!(healthcheck.path.as_deref(), Some("/healthz"));
+        assert_eq!(healthcheck.port, Some(8080));
+        assert!(healthcheck.command.starts_with("curl -f"));
+    }
+
+    #[test]
+    fn test_dockerfile_hints_inherit_from_stage() {
+        let hints = DockerfileHints::parse(
+            r#"
+FROM python:3.12 AS base
+EXPOSE 8000
+HEALTHCHECK CMD ["wget", "-qO-", "http://127.0.0.1:8000"]
+
+FROM base
+HEALTHCHECK NONE
+"#,
+        );
+
+        assert_eq!(hints.targets, vec!["base"]);
+        assert_eq!(hints.exposed_ports, vec![8000]);
+        assert_eq!(hints.healthcheck, None);
+
+        let hints = DockerfileHints::parse(
+            "FROM python:3.12 AS base\nHEALTHCHECK CMD [\"wget\", \"-qO-\", \"http://127.0.0.1:8000\"]\nFROM base\n",
+        );
+        let healthcheck = hints.healthcheck.clone().unwrap();
+        assert_eq!(healthcheck.path.as_deref(), Some("/"));
+        assert_eq!(hints.suggested_port(), Some(8000));
+    }
+
+    #[tokio::test]
+    async fn test_detect_dockerfile_hints() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+        std::fs::write(
+            temp_path.join("Dockerfile"),
+            "FROM golang:1.22 AS build\nFROM alpine AS app\nEXPOSE 4000\n",
+        )
+        .unwrap();
+
+        let result = detect_build_type(temp_path).await.unwrap();
+        assert_eq!(result.build_type, BuildType::Dockerfile);
+        assert_eq!(result.build_targets, vec!["build", "app"]);
+        assert_eq!(result.suggested_port, Some(4000));
+    }
 }
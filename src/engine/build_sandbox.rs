@@ -0,0 +1,333 @@
+//! Sandboxed builds for untrusted code.
+//!
+//! Teams and apps that build code from external contributors (e.g. PR
+//! previews) can opt into sandboxed builds. A sandboxed build:
+//! - is Dockerfile-only (Nixpacks, Railpack and buildpacks need the network),
+//! - runs on the local host, never on an assigned build server,
+//! - runs build steps without network access, optionally on a dedicated
+//!   rootless/gVisor/Kata builder,
+//! - gets no build secrets,
+//! - may only pull base images from allow-listed registries, and may not use
+//!   Dockerfile features that reach the network or host (`ADD <url>`,
+//!   `RUN --network`, `RUN --security=insecure`).
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::config::BuildSandboxConfig;
+use crate::db::App;
+use crate::runtime::BuildIsolation;
+use crate::DbPool;
+
+/// Builds run with the runtime's normal settings
+pub const BUILD_SANDBOX_NONE: &str = "none";
+/// Builds are sandboxed
+pub const BUILD_SANDBOX_SANDBOXED: &str = "sandboxed";
+
+/// All accepted `build_sandbox` values.
+pub const BUILD_SANDBOX_MODES: &[&str] = &[BUILD_SANDBOX_NONE, BUILD_SANDBOX_SANDBOXED];
+
+/// Whether builds of `app` must be sandboxed: the app's own setting if set,
+/// otherwise its team's.
+pub async fn is_required(db: &DbPool, app: &App) -> Result<bool> {
+    if let Some(mode) = app.build_sandbox.as_deref().filter(|m| !m.is_empty()) {
+        return Ok(mode == BUILD_SANDBOX_SANDBOXED);
+    }
+    let Some(ref team_id) = app.team_id else {
+        return Ok(false);
+    };
+    let team_mode: Option<(String,)> =
+        sqlx::query_as("SELECT build_sandbox FROM teams WHERE id = ?")
+            .bind(team_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(team_mode.is_some_and(|(mode,)| mode == BUILD_SANDBOX_SANDBOXED))
+}
+
+/// Check that a sandboxed build of `dockerfile` (relative to `build_path`) can
+/// go ahead, and return the isolation to build it with.
+pub async fn check_build(
+    build_type: &str,
+    build_path: &Path,
+    dockerfile: &str,
+    config: &BuildSandboxConfig,
+) -> Result<BuildIsolation> {
+    if !matches!(build_type, "dockerfile" | "") {
+        anyhow::bail!(
+            "Sandboxed builds support Dockerfile builds only; '{}' builds need network access while building",
+            build_type
+        );
+    }
+
+    let path = build_path.join(dockerfile.trim_start_matches("./"));
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("Failed to read {} for sandbox checks", dockerfile))?;
+    let problems = dockerfile_violations(&contents, &config.allowed_registries);
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "Dockerfile is not allowed in a sandboxed build:\n  - {}",
+            problems.join("\n  - ")
+        );
+    }
+
+    Ok(isolation(config))
+}
+
+/// Build isolation for sandboxed builds under `config`
+pub fn isolation(config: &BuildSandboxConfig) -> BuildIsolation {
+    BuildIsolation {
+        no_network: true,
+        builder: config.builder().map(String::from),
+        oci_runtime: config.oci_runtime().map(String::from),
+    }
+}
+
+/// Everything in a Dockerfile that a sandboxed build refuses.
+pub fn dockerfile_violations(contents: &str, allowed_registries: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut stages: HashSet<String> = HashSet::new();
+
+    let check_image = |image: &str, what: &str, problems: &mut Vec<String>| {
+        if let Some(problem) = image_violation(image, allowed_registries) {
+            problems.push(format!("{} {}", what, problem));
+        }
+    };
+
+    // Parser directives come before the first instruction; `# syntax=` names
+    // a frontend image that BuildKit pulls and runs. Blank lines are skipped
+    // so a directive is never missed.
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix('#') else {
+            break;
+        };
+        if let Some((key, value)) = comment.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("syntax") {
+                check_image(value.trim(), "syntax frontend", &mut problems);
+            }
+        }
+    }
+
+    for instruction in instructions(contents) {
+        let mut tokens = instruction.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+        let (flags, operands): (Vec<&str>, Vec<&str>) =
+            args.iter().partition(|arg| arg.starts_with("--"));
+
+        match keyword.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                let Some(image) = operands.first() else {
+                    continue;
+                };
+                if !is_stage_reference(image, &stages) {
+                    check_image(image, "base image", &mut problems);
+                }
+                if operands.len() >= 3 && operands[1].eq_ignore_ascii_case("as") {
+                    stages.insert(operands[2].to_ascii_lowercase());
+                }
+            }
+            "COPY" | "ADD" => {
+                for flag in &flags {
+                    if let Some(source) = flag.strip_prefix("--from=") {
+                        if !is_stage_reference(source, &stages) {
+                            check_image(source, "COPY --from image", &mut problems);
+                        }
+                    }
+                }
+                if keyword.eq_ignore_ascii_case("ADD") {
+                    // Every operand but the last (the destination) is a source
+                    for source in operands.iter().take(operands.len().saturating_sub(1)) {
+                        if source.contains("://") || source.starts_with("git@") {
+                            problems.push(format!("ADD fetches '{}' over the network", source));
+                        }
+                    }
+                }
+            }
+            "RUN" => {
+                for flag in &flags {
+                    if let Some(mode) = flag.strip_prefix("--network=") {
+                        if mode != "none" && mode != "default" {
+                            problems.push(format!("RUN --network={} is not allowed", mode));
+                        }
+                    } else if flag.starts_with("--security=") && *flag != "--security=sandbox" {
+                        problems.push(format!("RUN {} is not allowed", flag));
+                    } else if let Some(mount) = flag.strip_prefix("--mount=") {
+                        let source = mount
+                            .split(',')
+                            .find_map(|option| option.strip_prefix("from="));
+                        if let Some(source) = source {
+                            if !is_stage_reference(source, &stages) {
+                                check_image(source, "RUN --mount image", &mut problems);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    problems
+}
+
+/// Logical Dockerfile instructions: comments dropped and `\` continuations
+/// joined.
+pub(crate) fn instructions(contents: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_empty()) {
+            continue;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                result.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.trim().is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+/// Whether a FROM / --from value refers to an earlier stage (by name or index)
+/// rather than an image
+fn is_stage_reference(source: &str, stages: &HashSet<String>) -> bool {
+    let source = source.to_ascii_lowercase();
+    source == "scratch" || stages.contains(&source) || source.parse::<usize>().is_ok()
+}
+
+/// Why `image` can't be pulled by a sandboxed build, if it can't
+fn image_violation(image: &str, allowed_registries: &[String]) -> Option<String> {
+    let name = image_name(image);
+    if name.contains('$') {
+        return Some(format!(
+            "'{}' picks its registry or repository with a build argument",
+            image
+        ));
+    }
+    let normalized = normalize_image_name(&name);
+    let allowed = allowed_registries.iter().any(|entry| {
+        let entry = entry.trim().trim_end_matches('/').to_ascii_lowercase();
+        !entry.is_empty() && (normalized == entry || normalized.starts_with(&format!("{}/", entry)))
+    });
+    if allowed {
+        None
+    } else {
+        Some(format!("'{}' is not from an allowed registry", image))
+    }
+}
+
+/// An image reference without its tag or digest
+fn image_name(image: &str) -> String {
+    let without_digest = image.split('@').next().unwrap_or(image);
+    match without_digest.rfind(':') {
+        Some(colon) if !without_digest[colon..].contains('/') => {
+            without_digest[..colon].to_string()
+        }
+        _ => without_digest.to_string(),
+    }
+}
+
+/// Fully qualified image name: `node` becomes `docker.io/library/node`,
+/// `acme/api` becomes `docker.io/acme/api`.
+fn normalize_image_name(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    let (first, rest) = match name.split_once('/') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (name.as_str(), None),
+    };
+    let is_registry = first.contains('.') || first.contains(':') || first == "localhost";
+    match (is_registry, rest) {
+        (true, Some(rest)) => {
+            let registry = match first {
+                "index.docker.io" | "registry-1.docker.io" => "docker.io",
+                other => other,
+            };
+            format!("{}/{}", registry, rest)
+        }
+        (_, None) => format!("docker.io/library/{}", name),
+        (false, Some(_)) => format!("docker.io/{}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registries(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_image_name() {
+        assert_eq!(normalize_image_name("node"), "docker.io/library/node");
+        assert_eq!(normalize_image_name("acme/api"), "docker.io/acme/api");
+        assert_eq!(normalize_image_name("ghcr.io/acme/api"), "ghcr.io/acme/api");
+        assert_eq!(
+            normalize_image_name("localhost:5000/api"),
+            "localhost:5000/api"
+        );
+        assert_eq!(image_name("node:20-alpine"), "node");
+        assert_eq!(
+            image_name("localhost:5000/api:1.2@sha256:abc"),
+            "localhost:5000/api"
+        );
+    }
+
+    #[test]
+    fn test_allowed_dockerfile() {
+        let dockerfile = r#"
+# syntax=docker/dockerfile:1
+ARG NODE_VERSION=20
+FROM node:${NODE_VERSION}-alpine AS deps
+WORKDIR /app
+COPY package.json ./
+RUN --mount=type=cache,target=/root/.npm \
+    npm ci
+
+FROM ghcr.io/acme/runtime:1 AS runtime
+COPY --from=deps /app/node_modules ./node_modules
+COPY --from=0 /app/package.json ./
+CMD ["node", "server.js"]
+"#;
+        let problems =
+            dockerfile_violations(dockerfile, &registries(&["docker.io", "ghcr.io/acme"]));
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_rejected_dockerfile() {
+        let dockerfile = r#"
+# syntax=evil.example.com/frontend
+FROM evil.example.com/base:latest AS build
+FROM ${REGISTRY}/app
+RUN --network=host curl https://example.com
+RUN --security=insecure make
+RUN --mount=type=bind,from=quay.io/other/tools,target=/tools ls
+ADD https://example.com/payload.tar.gz /tmp/
+COPY --from=evil.example.com/assets /a /b
+"#;
+        let problems = dockerfile_violations(dockerfile, &registries(&["docker.io"]));
+        assert_eq!(problems.len(), 8, "{:?}", problems);
+        assert!(problems[0].starts_with("syntax frontend"));
+        assert!(problems.iter().any(|p| p.contains("build argument")));
+        assert!(problems.iter().any(|p| p.contains("--network=host")));
+        assert!(problems.iter().any(|p| p.contains("ADD fetches")));
+    }
+}
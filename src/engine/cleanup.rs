@@ -5,12 +5,17 @@
 //! - Removes old deployment records from the database
 //! - Stops and removes associated containers
 //! - Optionally prunes unused container images
+//!
+//! Apps can override how many deployments are kept (`apps.max_deployments`)
+//! and how many of those keep their image (`apps.keep_images`), so critical
+//! apps keep more rollback targets than toy projects.
 
 use crate::config::CleanupConfig;
 use crate::db::Deployment;
 use crate::runtime::ContainerRuntime;
 use crate::DbPool;
 use anyhow::Result;
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 
@@ -33,6 +38,10 @@ fn disk_usage_percent(path: &str) -> Option<f64> {
     Some((total - free) as f64 / total as f64 * 100.0)
 }
 
+/// Deployment statuses cleanup never touches
+const ACTIVE_STATUSES: &str =
+    "'running', 'pending', 'cloning', 'building', 'starting', 'checking', 'prewarmed'";
+
 /// Handles cleanup of old deployments
 pub struct DeploymentCleanup {
     db: DbPool,
@@ -85,8 +94,9 @@ impl DeploymentCleanup {
         self.run_cleanup_with_pressure(false).await
     }
 
-    /// Run cleanup; if `aggressive` is true, keep only 1 deployment per app and
-    /// prune build cache regardless of the prune_images setting.
+    /// Run cleanup; if `aggressive` is true, keep only 1 deployment per app
+    /// (apps with their own max_deployments keep that many) and prune build
+    /// cache regardless of the prune_images setting.
     pub async fn run_cleanup_with_pressure(&self, aggressive: bool) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
 
@@ -118,14 +128,20 @@ impl DeploymentCleanup {
             "Starting deployment cleanup cycle"
         );
 
-        // Get all apps
-        let apps: Vec<(String, String)> = sqlx::query_as("SELECT id, name FROM apps")
-            .fetch_all(&self.db)
-            .await?;
+        // Get all apps with their retention overrides
+        let apps: Vec<(String, String, Option<i64>, Option<i64>)> =
+            sqlx::query_as("SELECT id, name, max_deployments, keep_images FROM apps")
+                .fetch_all(&self.db)
+                .await?;
 
-        for (app_id, app_name) in apps {
+        for (app_id, app_name, app_max_deployments, app_keep_images) in apps {
+            let max_deployments = app_max_deployments
+                .filter(|n| *n > 0)
+                .map(|n| n as u32)
+                .unwrap_or(max_deployments);
+            let keep_images = app_keep_images.filter(|n| *n > 0).map(|n| n as u32);
             match self
-                .cleanup_app_deployments(&app_id, &app_name, max_deployments)
+                .cleanup_app_deployments(&app_id, &app_name, max_deployments, keep_images)
                 .await
             {
                 Ok(app_stats) => {
@@ -199,27 +215,37 @@ impl DeploymentCleanup {
         Ok(stats)
     }
 
-    /// Cleanup old deployments for a specific app
+    /// Cleanup old deployments for a specific app. Of the deployments kept,
+    /// only the newest `keep_images` (all when `None`) keep their image.
     async fn cleanup_app_deployments(
         &self,
         app_id: &str,
         app_name: &str,
         max_deployments: u32,
+        keep_images: Option<u32>,
     ) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
         let max_deployments = max_deployments as i64;
 
+        if let Some(keep_images) = keep_images {
+            stats.images_removed += self
+                .remove_surplus_images(app_id, app_name, max_deployments, keep_images)
+                .await?;
+        }
+
         // Get deployments ordered by started_at descending, skip the first N (most recent)
-        // We only clean up deployments that are not "running" status
-        let old_deployments: Vec<Deployment> = sqlx::query_as(
+        // We only clean up deployments that are not "running" status (or a
+        // pre-warmed image waiting to be promoted)
+        let old_deployments: Vec<Deployment> = sqlx::query_as(&format!(
             r#"
             SELECT * FROM deployments
             WHERE app_id = ?
-              AND status NOT IN ('running', 'pending', 'cloning', 'building', 'starting', 'checking')
+              AND status NOT IN ({})
             ORDER BY started_at DESC
             LIMIT -1 OFFSET ?
             "#,
-        )
+            ACTIVE_STATUSES
+        ))
         .bind(app_id)
         .bind(max_deployments)
         .fetch_all(&self.db)
@@ -292,6 +318,27 @@ impl DeploymentCleanup {
                 }
             }
 
+            // The versioned tag also holds the image, unless another
+            // deployment was built from the same commit
+            if let Some(version_tag) = &deployment.version_tag {
+                let shared: i64 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM deployments WHERE version_tag = ? AND id != ?",
+                )
+                .bind(version_tag)
+                .bind(&deployment.id)
+                .fetch_one(&self.db)
+                .await?;
+                if shared == 0 {
+                    if let Err(e) = self.runtime.remove_image(version_tag).await {
+                        tracing::debug!(
+                            image = %version_tag,
+                            error = %e,
+                            "Failed to remove version tag (may not exist)"
+                        );
+                    }
+                }
+            }
+
             // Delete deployment logs first (foreign key constraint)
             sqlx::query("DELETE FROM deployment_logs WHERE deployment_id = ?")
                 .bind(&deployment.id)
@@ -314,6 +361,103 @@ impl DeploymentCleanup {
 
         Ok(stats)
     }
+
+    /// Remove the images of kept deployments beyond the newest `keep_images`.
+    /// The deployment records stay; rolling back to one of them pulls the
+    /// image from the registry it was pushed to. Returns the images removed.
+    async fn remove_surplus_images(
+        &self,
+        app_id: &str,
+        app_name: &str,
+        max_deployments: i64,
+        keep_images: u32,
+    ) -> Result<u64> {
+        let kept: Vec<(Option<String>, Option<String>)> = sqlx::query_as(&format!(
+            r#"
+            SELECT image_tag, version_tag FROM deployments
+            WHERE app_id = ?
+              AND status NOT IN ({})
+            ORDER BY started_at DESC
+            LIMIT ?
+            "#,
+            ACTIVE_STATUSES
+        ))
+        .bind(app_id)
+        .bind(max_deployments)
+        .fetch_all(&self.db)
+        .await?;
+
+        // Rollback deployments reuse their target's image tag, so never remove
+        // an image an active deployment still runs
+        let in_use: Vec<(Option<String>, Option<String>)> = sqlx::query_as(&format!(
+            "SELECT image_tag, version_tag FROM deployments \
+             WHERE app_id = ? AND status IN ({})",
+            ACTIVE_STATUSES
+        ))
+        .bind(app_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let (kept, kept_versions): (Vec<_>, Vec<_>) = kept.into_iter().unzip();
+        let (in_use, in_use_versions): (Vec<_>, Vec<_>) = in_use.into_iter().unzip();
+        let in_use: HashSet<String> = in_use.into_iter().flatten().collect();
+        let in_use_versions: HashSet<String> = in_use_versions.into_iter().flatten().collect();
+
+        // Versioned tags hold the same images, so they go with them
+        for version_tag in surplus_images(&kept_versions, &in_use_versions, keep_images as usize) {
+            if let Err(e) = self.runtime.remove_image(&version_tag).await {
+                tracing::debug!(
+                    image = %version_tag,
+                    error = %e,
+                    "Failed to remove version tag (may not exist)"
+                );
+            }
+        }
+
+        let mut removed = 0;
+        for image_tag in surplus_images(&kept, &in_use, keep_images as usize) {
+            match self.runtime.remove_image(&image_tag).await {
+                Ok(_) => {
+                    removed += 1;
+                    tracing::debug!(
+                        image = %image_tag,
+                        app = %app_name,
+                        "Removed image beyond the app's keep_images"
+                    );
+                }
+                Err(e) => {
+                    // Usually already removed by an earlier cycle
+                    tracing::debug!(
+                        image = %image_tag,
+                        error = %e,
+                        "Failed to remove image (may be in use or not exist)"
+                    );
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Images of `kept` (image tags, newest deployment first) that fall beyond
+/// the newest `keep_images` deployments. Tags still used by one of those
+/// newest deployments or by an `in_use` deployment are left alone.
+fn surplus_images(
+    kept: &[Option<String>],
+    in_use: &HashSet<String>,
+    keep_images: usize,
+) -> Vec<String> {
+    let mut protected: HashSet<&str> = in_use.iter().map(String::as_str).collect();
+    protected.extend(kept.iter().take(keep_images).flatten().map(String::as_str));
+
+    let mut surplus = Vec::new();
+    for tag in kept.iter().skip(keep_images).flatten() {
+        if tag.is_empty() || protected.contains(tag.as_str()) || surplus.contains(tag) {
+            continue;
+        }
+        surplus.push(tag.clone());
+    }
+    surplus
 }
 
 /// Statistics from a cleanup run
@@ -390,4 +534,27 @@ mod tests {
         assert_eq!(format_bytes(1048576), "1.00 MB");
         assert_eq!(format_bytes(1073741824), "1.00 GB");
     }
+
+    #[test]
+    fn test_surplus_images() {
+        let tag = |t: &str| Some(t.to_string());
+        let kept = vec![
+            tag("app:v5"),
+            tag("app:v4"),
+            None,
+            tag("app:v3"),
+            tag("app:v5"),
+            tag("app:v2"),
+            tag("app:v1"),
+            tag("app:v1"),
+        ];
+        let in_use: HashSet<String> = ["app:v2".to_string()].into();
+
+        assert_eq!(surplus_images(&kept, &in_use, 2), vec!["app:v3", "app:v1"]);
+        assert_eq!(
+            surplus_images(&kept, &HashSet::new(), 1),
+            vec!["app:v4", "app:v3", "app:v2", "app:v1"]
+        );
+        assert!(surplus_images(&kept, &in_use, 10).is_empty());
+    }
 }
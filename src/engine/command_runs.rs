@@ -0,0 +1,219 @@
+//! One-off command runs ("rivetr run").
+//!
+//! A command is run once in a fresh container started from the image of the
+//! app's running deployment, the same way a scheduled task runs: with the
+//! app's env vars, volumes and networks but no ports or proxy routes. The
+//! container ID is recorded while it exists so its output can be streamed;
+//! afterwards the exit code and output are kept and the container is removed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::db::{CommandRun, COMMAND_RUN_RUNNING};
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+use super::scheduled_tasks::{collect_output, start_container};
+use super::KEY_LENGTH;
+
+/// Record a new run of `command` as running
+pub async fn create_run(
+    db: &DbPool,
+    app_id: &str,
+    command: &str,
+    timeout_seconds: i64,
+    started_by: Option<&str>,
+) -> Result<CommandRun> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO command_runs \
+         (id, app_id, command, timeout_seconds, status, started_by, started_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&run_id)
+    .bind(app_id)
+    .bind(command)
+    .bind(timeout_seconds)
+    .bind(COMMAND_RUN_RUNNING)
+    .bind(started_by)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    CommandRun::get(db, app_id, &run_id)
+        .await?
+        .context("Command run missing after insert")
+}
+
+/// Run the command in a one-off container and record the outcome on `run`
+pub async fn execute_run(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    run: &CommandRun,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) {
+    tracing::info!(run_id = %run.id, app_id = %run.app_id, "Running one-off command");
+
+    let suffix: String = run.id.chars().take(8).collect();
+    let started = start_container(
+        db,
+        runtime,
+        &run.app_id,
+        &run.command,
+        &format!("run-{}", suffix),
+        encryption_key,
+    )
+    .await;
+
+    let (image, exit_code, output, error) = match started {
+        Ok((container_id, image)) => {
+            let _ = sqlx::query("UPDATE command_runs SET container_id = ?, image = ? WHERE id = ?")
+                .bind(&container_id)
+                .bind(&image)
+                .bind(&run.id)
+                .execute(db)
+                .await;
+
+            let timeout = Duration::from_secs(run.timeout_seconds.max(1) as u64);
+            let (exit_code, error) =
+                match tokio::time::timeout(timeout, runtime.wait(&container_id)).await {
+                    Ok(Ok(0)) => (Some(0), None),
+                    Ok(Ok(code)) => (
+                        Some(code),
+                        Some(format!("Command exited with code {}", code)),
+                    ),
+                    Ok(Err(e)) => (None, Some(format!("Failed to wait for container: {:#}", e))),
+                    Err(_) => {
+                        let _ = runtime.stop(&container_id).await;
+                        (
+                            None,
+                            Some(format!("Timed out after {} seconds", run.timeout_seconds)),
+                        )
+                    }
+                };
+            let output = collect_output(runtime.as_ref(), &container_id).await;
+            let _ = runtime.remove(&container_id).await;
+            (Some(image), exit_code, output, error)
+        }
+        Err(e) => (None, None, None, Some(format!("{:#}", e))),
+    };
+
+    let status = if error.is_none() && exit_code == Some(0) {
+        "success"
+    } else {
+        "failed"
+    };
+    if status == "failed" {
+        tracing::warn!(
+            run_id = %run.id,
+            app_id = %run.app_id,
+            exit_code = ?exit_code,
+            error = ?error,
+            "One-off command failed"
+        );
+    }
+
+    let finished_at = Utc::now();
+    let duration_ms = chrono::DateTime::parse_from_rfc3339(&run.started_at)
+        .ok()
+        .map(|started| (finished_at - started.with_timezone(&Utc)).num_milliseconds());
+    if let Err(e) = sqlx::query(
+        "UPDATE command_runs SET status = ?, image = ?, container_id = NULL, exit_code = ?, \
+         output = ?, error_message = ?, finished_at = ?, duration_ms = ? WHERE id = ?",
+    )
+    .bind(status)
+    .bind(&image)
+    .bind(exit_code)
+    .bind(&output)
+    .bind(&error)
+    .bind(finished_at.to_rfc3339())
+    .bind(duration_ms)
+    .bind(&run.id)
+    .execute(db)
+    .await
+    {
+        tracing::error!(run_id = %run.id, error = %e, "Failed to record command run");
+    }
+}
+
+/// Mark runs left `running` by a previous process as failed
+pub(crate) async fn fail_interrupted_runs(db: &DbPool) {
+    let _ = sqlx::query(
+        "UPDATE command_runs SET status = 'failed', container_id = NULL, \
+         error_message = 'Interrupted by a server restart', finished_at = ? \
+         WHERE status = 'running'",
+    )
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_create_run_starts_running() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let user = test_user(&db, "admin").await;
+
+        let run = create_run(&db, &app_id, "./migrate", 60, Some(&user.id))
+            .await
+            .unwrap();
+        assert!(run.is_running());
+        assert_eq!(run.command, "./migrate");
+        assert_eq!(run.timeout_seconds, 60);
+        assert_eq!(run.started_by.as_deref(), Some(user.id.as_str()));
+        assert_eq!(run.exit_code, None);
+        assert_eq!(run.finished_at, None);
+
+        // Runs are only found through their own app
+        let other_app = test_app(&db, "api").await;
+        assert!(CommandRun::get(&db, &other_app, &run.id)
+            .await
+            .unwrap()
+            .is_none());
+        let listed = CommandRun::list_for_app(&db, &app_id, 10).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, run.id);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_runs_are_failed() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let interrupted = create_run(&db, &app_id, "sleep 600", 900, None)
+            .await
+            .unwrap();
+        let finished = create_run(&db, &app_id, "true", 60, None).await.unwrap();
+        sqlx::query("UPDATE command_runs SET status = 'success', exit_code = 0 WHERE id = ?")
+            .bind(&finished.id)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        fail_interrupted_runs(&db).await;
+
+        let run = CommandRun::get(&db, &app_id, &interrupted.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.status, "failed");
+        assert_eq!(
+            run.error_message.as_deref(),
+            Some("Interrupted by a server restart")
+        );
+        assert!(run.finished_at.is_some());
+        let run = CommandRun::get(&db, &app_id, &finished.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.status, "success");
+        assert_eq!(run.error_message, None);
+    }
+}
@@ -0,0 +1,260 @@
+//! App configuration snapshots taken with each deployment.
+//!
+//! The pipeline records the settings a deployment ran with (env var names,
+//! ports, domains, resource limits and build settings) so
+//! `GET /api/deployments/:id/diff` can show what changed since the previous
+//! deployment. Env var values are never stored: each is kept as a salted hash,
+//! enough to tell that a value changed without revealing it.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::db::App;
+use crate::DbPool;
+
+use super::pipeline::collect_env_vars;
+use super::KEY_LENGTH;
+
+/// Configuration an app was deployed with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    /// Env var name -> hash of its value
+    pub env: BTreeMap<String, String>,
+    /// Setting name -> value, e.g. "memory_limit" -> "512m"; unset ones are left out
+    pub settings: BTreeMap<String, String>,
+}
+
+/// How a configuration entry changed between two deployments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One changed configuration entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigChange {
+    /// "env" or "settings"
+    pub section: &'static str,
+    pub name: String,
+    pub change: ChangeKind,
+    /// Previous value (always None for env vars)
+    pub old: Option<String>,
+    /// New value (always None for env vars)
+    pub new: Option<String>,
+}
+
+impl ConfigSnapshot {
+    /// Snapshot `app`'s settings and the env vars its container receives
+    pub async fn capture(
+        db: &DbPool,
+        app: &App,
+        encryption_key: Option<&[u8; KEY_LENGTH]>,
+    ) -> Self {
+        let env = collect_env_vars(db, app, encryption_key, None)
+            .await
+            .into_iter()
+            .map(|(key, value)| {
+                let hash = value_hash(&app.id, &key, &value);
+                (key, hash)
+            })
+            .collect();
+        Self {
+            env,
+            settings: app_settings(app),
+        }
+    }
+
+    /// Entries that differ from `previous`, env vars first, each section sorted by name
+    pub fn changes_since(&self, previous: &ConfigSnapshot) -> Vec<ConfigChange> {
+        let mut changes = diff_section("env", &previous.env, &self.env);
+        for change in &mut changes {
+            change.old = None;
+            change.new = None;
+        }
+        changes.extend(diff_section("settings", &previous.settings, &self.settings));
+        changes
+    }
+}
+
+fn app_settings(app: &App) -> BTreeMap<String, String> {
+    let mut domains: Vec<String> = app.get_domains().into_iter().map(|d| d.domain).collect();
+    domains.sort();
+    let port_mappings: Vec<String> = app
+        .get_port_mappings()
+        .into_iter()
+        .map(|pm| format!("{}:{}/{}", pm.host_port, pm.container_port, pm.protocol))
+        .collect();
+
+    let settings = [
+        ("port", Some(app.port.to_string())),
+        ("port_mappings", Some(port_mappings.join(", "))),
+        ("domains", Some(domains.join(", "))),
+        ("cpu_limit", app.cpu_limit.clone()),
+        ("memory_limit", app.memory_limit.clone()),
+        ("replica_count", Some(app.replica_count.to_string())),
+        ("healthcheck", app.healthcheck.clone()),
+        ("restart_policy", Some(app.restart_policy.clone())),
+        ("branch", Some(app.branch.clone())),
+        ("build_type", app.build_type.clone()),
+        ("dockerfile", Some(app.dockerfile.clone())),
+    ];
+    settings
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .filter(|v| !v.is_empty())
+                .map(|v| (name.to_string(), v))
+        })
+        .collect()
+}
+
+/// Salted so equal values in different apps or keys hash differently
+fn value_hash(app_id: &str, key: &str, value: &str) -> String {
+    let digest = Sha256::digest(format!("{}\0{}\0{}", app_id, key, value).as_bytes());
+    hex::encode(&digest[..12])
+}
+
+fn diff_section(
+    section: &'static str,
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<ConfigChange> {
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (before, after) = (old.get(name), new.get(name));
+            let change = match (before, after) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(b), Some(a)) if b != a => ChangeKind::Changed,
+                _ => return None,
+            };
+            Some(ConfigChange {
+                section,
+                name: name.clone(),
+                change,
+                old: before.cloned(),
+                new: after.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Load the snapshot recorded for a deployment
+pub async fn load(db: &DbPool, deployment_id: &str) -> anyhow::Result<Option<ConfigSnapshot>> {
+    let snapshot: Option<String> = sqlx::query_scalar(
+        "SELECT snapshot FROM deployment_config_snapshots WHERE deployment_id = ?",
+    )
+    .bind(deployment_id)
+    .fetch_optional(db)
+    .await?;
+    Ok(snapshot.and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+/// Store `snapshot` for a deployment, replacing any earlier one
+pub async fn save(
+    db: &DbPool,
+    deployment_id: &str,
+    snapshot: &ConfigSnapshot,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO deployment_config_snapshots (deployment_id, snapshot, created_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(deployment_id) DO UPDATE SET
+            snapshot = excluded.snapshot,
+            created_at = excluded.created_at
+        "#,
+    )
+    .bind(deployment_id)
+    .bind(serde_json::to_string(snapshot)?)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Capture and store `app`'s configuration for a deployment. Failures are
+/// logged only; a missing snapshot just leaves the config diff unavailable.
+pub async fn record(
+    db: &DbPool,
+    deployment_id: &str,
+    app: &App,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) {
+    let snapshot = ConfigSnapshot::capture(db, app, encryption_key).await;
+    if let Err(e) = save(db, deployment_id, &snapshot).await {
+        tracing::warn!(deployment_id = %deployment_id, "Failed to record config snapshot: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(env: &[(&str, &str)], settings: &[(&str, &str)]) -> ConfigSnapshot {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        ConfigSnapshot {
+            env: map(env),
+            settings: map(settings),
+        }
+    }
+
+    #[test]
+    fn test_changes_since_previous() {
+        let previous = snapshot(
+            &[("API_KEY", "aaa"), ("OLD", "x"), ("SAME", "s")],
+            &[("memory_limit", "256m"), ("port", "3000")],
+        );
+        let current = snapshot(
+            &[("API_KEY", "bbb"), ("NEW", "y"), ("SAME", "s")],
+            &[
+                ("memory_limit", "512m"),
+                ("port", "3000"),
+                ("cpu_limit", "1"),
+            ],
+        );
+
+        let changes = current.changes_since(&previous);
+        let summary: Vec<(&str, &str, ChangeKind)> = changes
+            .iter()
+            .map(|c| (c.section, c.name.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("env", "API_KEY", ChangeKind::Changed),
+                ("env", "NEW", ChangeKind::Added),
+                ("env", "OLD", ChangeKind::Removed),
+                ("settings", "cpu_limit", ChangeKind::Added),
+                ("settings", "memory_limit", ChangeKind::Changed),
+            ]
+        );
+        // Env var hashes are never exposed; setting values are
+        assert!(changes[0].old.is_none() && changes[0].new.is_none());
+        assert_eq!(changes[4].old.as_deref(), Some("256m"));
+        assert_eq!(changes[4].new.as_deref(), Some("512m"));
+
+        assert!(current.changes_since(&current).is_empty());
+    }
+
+    #[test]
+    fn test_value_hash_is_salted() {
+        let hash = value_hash("app-1", "SECRET", "hunter2");
+        assert_eq!(hash, value_hash("app-1", "SECRET", "hunter2"));
+        assert_ne!(hash, value_hash("app-2", "SECRET", "hunter2"));
+        assert_ne!(hash, value_hash("app-1", "SECRET", "hunter3"));
+        assert!(!hash.contains("hunter2"));
+    }
+}
@@ -7,7 +7,7 @@ use std::time::{Duration, Instant};
 use crate::api::metrics::{increment_container_restarts, set_container_restart_backoff_seconds};
 use crate::config::ContainerMonitorConfig;
 use crate::db::{App, Deployment, ManagedDatabase, NotificationEventType, Service};
-use crate::notifications::{NotificationPayload, NotificationService};
+use crate::notifications::{outbox, NotificationPayload};
 use crate::runtime::ContainerRuntime;
 use crate::DbPool;
 
@@ -233,15 +233,14 @@ async fn send_crash_notification_if_due(db: &DbPool, app_id: &str, app_name: &st
         );
     }
 
-    let notification_service = NotificationService::new(db.clone());
     let payload = NotificationPayload::app_event(
         NotificationEventType::ContainerCrash,
         app_id.to_string(),
         app_name.to_string(),
         message.to_string(),
     );
-    if let Err(e) = notification_service.send(&payload).await {
-        tracing::warn!(error = %e, "Failed to send container_crash notification");
+    if let Err(e) = outbox::enqueue(db, &payload).await {
+        tracing::warn!(error = %e, "Failed to queue container_crash notification");
     }
 }
 
@@ -396,7 +395,6 @@ async fn handle_crashed_container(
             .await;
 
             // Send ContainerRestarted notification
-            let notification_service = NotificationService::new(db.clone());
             let payload = NotificationPayload::app_event(
                 NotificationEventType::ContainerRestarted,
                 deployment.app_id.clone(),
@@ -406,8 +404,8 @@ async fn handle_crashed_container(
                     app_name, restart_count, max_attempts, new_backoff
                 ),
             );
-            if let Err(e) = notification_service.send(&payload).await {
-                tracing::warn!(error = %e, "Failed to send container_restarted notification");
+            if let Err(e) = outbox::enqueue(db, &payload).await {
+                tracing::warn!(error = %e, "Failed to queue container_restarted notification");
             }
         }
         Err(e) => {
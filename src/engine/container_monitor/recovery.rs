@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::db::{Deployment, ManagedDatabase, Service};
+use crate::db::{Deployment, DeploymentEvent, ManagedDatabase, Service};
 use crate::runtime::ContainerRuntime;
 use crate::DbPool;
 
@@ -91,6 +91,7 @@ pub(super) async fn mark_deployment_failed(
     .bind(deployment_id)
     .execute(db)
     .await?;
+    DeploymentEvent::record(db, deployment_id, "failed", Some(error)).await;
 
     Ok(())
 }
@@ -201,6 +202,13 @@ pub(super) async fn reconcile_deployments(
             {
                 tracing::warn!(deployment = %deployment.id, error = %e, "Failed to update deployment status during reconciliation");
             } else {
+                DeploymentEvent::record(
+                    db,
+                    &deployment.id,
+                    "running",
+                    Some("Reconciled on startup"),
+                )
+                .await;
                 tracing::info!(deployment = %deployment.id, container = %container_id, "Deployment status reconciled: starting -> running");
                 updated += 1;
             }
@@ -218,6 +226,7 @@ pub(super) async fn reconcile_deployments(
                     "Failed to update deployment status during reconciliation"
                 );
             } else {
+                DeploymentEvent::record(db, &deployment.id, "stopped", Some("Reconciled on startup")).await;
                 tracing::info!(
                     deployment = %deployment.id,
                     container = %container_id,
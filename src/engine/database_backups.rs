@@ -55,7 +55,8 @@ impl DatabaseBackupTask {
         let due_schedules: Vec<DatabaseBackupSchedule> = sqlx::query_as(
             r#"
             SELECT id, database_id, enabled, schedule_type, schedule_hour, schedule_day,
-                   retention_count, last_run_at, next_run_at, created_at, updated_at
+                   retention_count, last_run_at, next_run_at, created_at, updated_at,
+                   timezone
             FROM database_backup_schedules
             WHERE enabled = 1
               AND next_run_at IS NOT NULL
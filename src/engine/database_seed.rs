@@ -0,0 +1,452 @@
+//! Seeding of newly provisioned managed databases.
+//!
+//! A managed database can carry a seed: a script (SQL, or JavaScript for
+//! MongoDB) and/or an uploaded dump stored under
+//! `{data_dir}/database-seeds/{id}/`. The seed is loaded once, as soon as the
+//! database is running and has never been seeded: after its first
+//! provisioning, or when a seed is attached to a running database that was
+//! never seeded. `seeded_at` marks the load, so restarts and recreated
+//! containers don't seed twice. A failed attempt is kept in `seed_error` and
+//! retried on the next start.
+//!
+//! Environment clones copy the seed (and start unseeded) when the source
+//! database has `seed_on_clone` set.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine as _;
+
+use crate::db::{DatabaseCredentials, DatabaseType, ManagedDatabase};
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+/// Directory under the data dir holding uploaded seed dumps
+pub const SEED_DIR: &str = "database-seeds";
+
+/// Largest accepted seed script
+pub const MAX_SEED_SCRIPT_BYTES: usize = 1024 * 1024;
+
+/// Base64 bytes written per exec, well below the kernel's per-argument limit
+const WRITE_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Readiness probes before giving up, 2 seconds apart
+const READY_ATTEMPTS: u32 = 60;
+
+/// IDs of databases whose seed is being loaded
+static SEEDING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Marks a database as being seeded until dropped
+struct SeedingGuard(String);
+
+impl SeedingGuard {
+    fn acquire(database_id: &str) -> Option<Self> {
+        let mut seeding = SEEDING.lock().unwrap();
+        if seeding.iter().any(|id| id == database_id) {
+            return None;
+        }
+        seeding.push(database_id.to_string());
+        Some(Self(database_id.to_string()))
+    }
+}
+
+impl Drop for SeedingGuard {
+    fn drop(&mut self) {
+        SEEDING.lock().unwrap().retain(|id| id != &self.0);
+    }
+}
+
+/// Directory holding a database's uploaded seed dump
+pub fn seed_dir(data_dir: &Path, database_id: &str) -> PathBuf {
+    data_dir.join(SEED_DIR).join(database_id)
+}
+
+/// How a file is loaded into a database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadFormat {
+    /// SQL script, or a mongosh script for MongoDB
+    Script,
+    /// Dump as produced by the database's backup tool
+    Dump { compressed: bool },
+}
+
+impl LoadFormat {
+    /// Format of an uploaded dump, from its file name
+    pub fn for_dump(file_name: &str) -> Self {
+        Self::Dump {
+            compressed: file_name.ends_with(".gz"),
+        }
+    }
+}
+
+/// Command loading the file at `path` inside the container into the
+/// database. `None` for database types that can't load files.
+pub fn load_command(
+    db_type: &DatabaseType,
+    creds: &DatabaseCredentials,
+    path: &str,
+    format: LoadFormat,
+) -> Option<Vec<String>> {
+    let db_name = creds
+        .database
+        .clone()
+        .unwrap_or_else(|| creds.username.clone());
+    let script = match (db_type, format) {
+        (DatabaseType::Postgres, LoadFormat::Dump { compressed: true }) => format!(
+            "PGPASSWORD='{}' pg_restore -U {} -d {} {}",
+            creds.password, creds.username, db_name, path
+        ),
+        (DatabaseType::Postgres, LoadFormat::Dump { compressed: false }) => format!(
+            "PGPASSWORD='{}' psql -U {} -d {} -f {}",
+            creds.password, creds.username, db_name, path
+        ),
+        (DatabaseType::Postgres, LoadFormat::Script) => format!(
+            "PGPASSWORD='{}' psql -v ON_ERROR_STOP=1 -U {} -d {} -f {}",
+            creds.password, creds.username, db_name, path
+        ),
+        (DatabaseType::Mysql, _) => format!(
+            "mysql -u {} -p'{}' {} < {}",
+            creds.username, creds.password, db_name, path
+        ),
+        (DatabaseType::Mariadb, _) => format!(
+            "mariadb -u {} -p'{}' {} < {}",
+            creds.username, creds.password, db_name, path
+        ),
+        (DatabaseType::Mongodb, LoadFormat::Script) => format!(
+            "mongosh --quiet --username {} --password '{}' --authenticationDatabase admin {} {}",
+            creds.username,
+            creds.password,
+            creds.database.as_deref().unwrap_or("admin"),
+            path
+        ),
+        (DatabaseType::Mongodb, LoadFormat::Dump { .. }) => format!(
+            "mongorestore --username {} --password '{}' --authenticationDatabase admin \
+             --archive={} --gzip",
+            creds.username, creds.password, path
+        ),
+        _ => return None,
+    };
+    Some(vec!["sh".to_string(), "-c".to_string(), script])
+}
+
+/// Command that succeeds once the database accepts TCP connections. The
+/// images' first-boot init servers only listen on a local socket, so this
+/// doesn't pass while the entrypoint is still initializing.
+fn ping_command(db_type: &DatabaseType, creds: &DatabaseCredentials) -> Option<Vec<String>> {
+    let script = match db_type {
+        DatabaseType::Postgres => format!("pg_isready -h 127.0.0.1 -U {}", creds.username),
+        DatabaseType::Mysql => format!(
+            "mysqladmin ping -h 127.0.0.1 --protocol=tcp -u {} -p'{}' --silent",
+            creds.username, creds.password
+        ),
+        DatabaseType::Mariadb => format!(
+            "mariadb-admin ping -h 127.0.0.1 --protocol=tcp -u {} -p'{}' --silent",
+            creds.username, creds.password
+        ),
+        DatabaseType::Mongodb => format!(
+            "mongosh --quiet --host 127.0.0.1 --username {} --password '{}' \
+             --authenticationDatabase admin --eval 'db.runCommand({{ ping: 1 }})'",
+            creds.username, creds.password
+        ),
+        _ => return None,
+    };
+    Some(vec!["sh".to_string(), "-c".to_string(), script])
+}
+
+/// Wait until the database answers two probes in a row
+pub async fn wait_until_ready(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+    db_type: &DatabaseType,
+    creds: &DatabaseCredentials,
+) -> Result<()> {
+    let Some(ping) = ping_command(db_type, creds) else {
+        return Ok(());
+    };
+    let mut passed = 0;
+    for _ in 0..READY_ATTEMPTS {
+        match runtime.run_command(container_id, ping.clone()).await {
+            Ok(result) if result.exit_code == 0 => {
+                passed += 1;
+                if passed == 2 {
+                    return Ok(());
+                }
+            }
+            _ => passed = 0,
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+    anyhow::bail!(
+        "Database did not accept connections within {} seconds",
+        READY_ATTEMPTS * 2
+    )
+}
+
+/// Write `bytes` to `path` inside the container, passing them as base64 in
+/// chunks small enough for a single exec argument
+pub async fn write_file(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+    path: &str,
+    bytes: &[u8],
+) -> Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    let staging = format!("{}.b64", path);
+    let mut scripts = vec![format!(": > {}", staging)];
+    // Base64 is ASCII, so byte chunks are valid UTF-8
+    scripts.extend(encoded.as_bytes().chunks(WRITE_CHUNK_BYTES).map(|chunk| {
+        format!(
+            "printf '%s' '{}' >> {}",
+            String::from_utf8_lossy(chunk),
+            staging
+        )
+    }));
+    scripts.push(format!(
+        "base64 -d {} > {} && rm -f {}",
+        staging, path, staging
+    ));
+
+    for script in scripts {
+        let result = runtime
+            .run_command(
+                container_id,
+                vec!["sh".to_string(), "-c".to_string(), script],
+            )
+            .await
+            .context("Failed to write file into the container")?;
+        if result.exit_code != 0 {
+            anyhow::bail!(
+                "Failed to write file into the container: {}",
+                result.stderr.trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Copy `bytes` into the container and load them into the database
+pub async fn load_file(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+    db_type: &DatabaseType,
+    creds: &DatabaseCredentials,
+    bytes: &[u8],
+    format: LoadFormat,
+) -> Result<()> {
+    let path = format!("/tmp/rivetr-seed-{}", uuid::Uuid::new_v4());
+    let command = load_command(db_type, creds, &path, format)
+        .with_context(|| format!("{} databases can't be seeded", db_type))?;
+    write_file(runtime, container_id, &path, bytes).await?;
+    let result = runtime.run_command(container_id, command).await;
+    let _ = runtime
+        .run_command(container_id, vec!["rm".to_string(), "-f".to_string(), path])
+        .await;
+
+    let result = result?;
+    if result.exit_code != 0 {
+        let output = if result.stderr.trim().is_empty() {
+            result.stdout
+        } else {
+            result.stderr
+        };
+        anyhow::bail!("exited with code {}: {}", result.exit_code, output.trim());
+    }
+    Ok(())
+}
+
+async fn load_seed(
+    runtime: &dyn ContainerRuntime,
+    database: &ManagedDatabase,
+    data_dir: &Path,
+) -> Result<()> {
+    let db_type = database.get_db_type();
+    if !db_type.supports_seeding() {
+        anyhow::bail!("{} databases can't be seeded", db_type);
+    }
+    let container_id = database
+        .container_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+        .context("Database has no container")?;
+    let creds = database
+        .get_credentials()
+        .context("Invalid database credentials")?;
+
+    wait_until_ready(runtime, container_id, &db_type, &creds).await?;
+
+    if let Some(script) = database
+        .seed_script
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        load_file(
+            runtime,
+            container_id,
+            &db_type,
+            &creds,
+            script.as_bytes(),
+            LoadFormat::Script,
+        )
+        .await
+        .context("Seed script failed")?;
+    }
+
+    if let Some(ref file_name) = database.seed_dump_file {
+        let dump_path = seed_dir(data_dir, &database.id).join(file_name);
+        let bytes = tokio::fs::read(&dump_path)
+            .await
+            .with_context(|| format!("Failed to read seed dump {}", dump_path.display()))?;
+        load_file(
+            runtime,
+            container_id,
+            &db_type,
+            &creds,
+            &bytes,
+            LoadFormat::for_dump(file_name),
+        )
+        .await
+        .context("Seed dump failed")?;
+    }
+    Ok(())
+}
+
+/// Load the seed and record the outcome on the database
+async fn load_and_record(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    database: &ManagedDatabase,
+    data_dir: &Path,
+) -> Result<()> {
+    tracing::info!("Seeding database {}", database.name);
+    let result = load_seed(runtime.as_ref(), database, data_dir).await;
+    match &result {
+        Ok(()) => {
+            sqlx::query("UPDATE databases SET seeded_at = ?, seed_error = NULL WHERE id = ?")
+                .bind(chrono::Utc::now().to_rfc3339())
+                .bind(&database.id)
+                .execute(db)
+                .await?;
+            tracing::info!("Seeded database {}", database.name);
+        }
+        Err(e) => {
+            sqlx::query("UPDATE databases SET seed_error = ? WHERE id = ?")
+                .bind(format!("{:#}", e))
+                .bind(&database.id)
+                .execute(db)
+                .await?;
+            tracing::warn!("Failed to seed database {}: {:#}", database.name, e);
+        }
+    }
+    result
+}
+
+/// Load the database's seed now, whether or not it was loaded before, and
+/// record the outcome on the database. Fails without loading anything while
+/// another load of the same database is in progress.
+pub async fn run_seed(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    database: &ManagedDatabase,
+    data_dir: &Path,
+) -> Result<()> {
+    let Some(_guard) = SeedingGuard::acquire(&database.id) else {
+        anyhow::bail!(
+            "The seed of database {} is already being loaded",
+            database.name
+        );
+    };
+    load_and_record(db, runtime, database, data_dir).await
+}
+
+/// Load the seed if the database has one that was never loaded and no load
+/// is in progress. Returns whether a seed was loaded.
+pub async fn seed_if_pending(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    database: &ManagedDatabase,
+    data_dir: &Path,
+) -> Result<bool> {
+    if !database.needs_seed() {
+        return Ok(false);
+    }
+    let Some(_guard) = SeedingGuard::acquire(&database.id) else {
+        return Ok(false);
+    };
+    // A load that finished since `database` was read already seeded it
+    let seeded_at: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT seeded_at FROM databases WHERE id = ?")
+            .bind(&database.id)
+            .fetch_optional(db)
+            .await?;
+    if !matches!(seeded_at, Some((None,))) {
+        return Ok(false);
+    }
+    load_and_record(db, runtime, database, data_dir).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creds() -> DatabaseCredentials {
+        DatabaseCredentials {
+            username: "app".to_string(),
+            password: "secret".to_string(),
+            database: Some("shop".to_string()),
+            root_password: None,
+        }
+    }
+
+    #[test]
+    fn test_load_command_per_format() {
+        let script = load_command(
+            &DatabaseType::Postgres,
+            &creds(),
+            "/tmp/s",
+            LoadFormat::Script,
+        )
+        .unwrap();
+        assert!(script[2].contains("ON_ERROR_STOP=1"));
+        assert!(script[2].ends_with("-d shop -f /tmp/s"));
+
+        let dump = load_command(
+            &DatabaseType::Postgres,
+            &creds(),
+            "/tmp/d",
+            LoadFormat::for_dump("shop.dump.gz"),
+        )
+        .unwrap();
+        assert!(dump[2].contains("pg_restore"));
+
+        let mongo = load_command(
+            &DatabaseType::Mongodb,
+            &creds(),
+            "/tmp/s",
+            LoadFormat::Script,
+        )
+        .unwrap();
+        assert!(mongo[2].starts_with("mongosh"));
+
+        assert!(
+            load_command(&DatabaseType::Redis, &creds(), "/tmp/s", LoadFormat::Script).is_none()
+        );
+    }
+
+    #[test]
+    fn test_seed_types_have_probes_and_loaders() {
+        for db_type in [
+            DatabaseType::Postgres,
+            DatabaseType::Mysql,
+            DatabaseType::Mariadb,
+            DatabaseType::Mongodb,
+        ] {
+            assert!(db_type.supports_seeding());
+            assert!(ping_command(&db_type, &creds()).is_some());
+            assert!(load_command(&db_type, &creds(), "/tmp/s", LoadFormat::Script).is_some());
+        }
+        assert!(!DatabaseType::ClickHouse.supports_seeding());
+    }
+}
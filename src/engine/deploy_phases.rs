@@ -0,0 +1,99 @@
+//! Timed phases of the deployment pipeline.
+//!
+//! The pipeline marks where each phase begins; a phase ends when the next one
+//! begins or when the deployment reaches a final status, so early returns and
+//! errors never leave a phase open. Each ended phase is stored with its
+//! duration (`GET /api/deployments/:id/timeline`) and observed in the
+//! `rivetr_deployment_phase_duration_seconds` histogram. Deployment log lines
+//! are tagged with the phase they were written in.
+
+use crate::api::metrics::observe_deployment_phase_duration;
+use crate::db::DeploymentPhase;
+use crate::DbPool;
+
+/// A phase of the deployment pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployPhase {
+    Clone,
+    Build,
+    Test,
+    Push,
+    Start,
+    Healthcheck,
+}
+
+impl DeployPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Clone => "clone",
+            Self::Build => "build",
+            Self::Test => "test",
+            Self::Push => "push",
+            Self::Start => "start",
+            Self::Healthcheck => "healthcheck",
+        }
+    }
+}
+
+/// Begin `phase`, ending the phase in progress as succeeded
+pub async fn begin(db: &DbPool, deployment_id: &str, phase: DeployPhase) {
+    let ended = DeploymentPhase::begin(db, deployment_id, phase.as_str()).await;
+    observe(db, deployment_id, &ended).await;
+}
+
+/// End the phase in progress, if any, with `status` (succeeded, failed or
+/// cancelled)
+pub async fn end(db: &DbPool, deployment_id: &str, status: &str) {
+    let ended = DeploymentPhase::end(db, deployment_id, status).await;
+    observe(db, deployment_id, &ended).await;
+}
+
+/// Phase status for a deployment reaching `deployment_status`, if that status
+/// ends the pipeline
+pub fn final_status(deployment_status: &str) -> Option<&'static str> {
+    match deployment_status {
+        "running" | "prewarmed" => Some("succeeded"),
+        "failed" => Some("failed"),
+        "stopped" | "cancelled" => Some("cancelled"),
+        _ => None,
+    }
+}
+
+async fn observe(db: &DbPool, deployment_id: &str, phases: &[DeploymentPhase]) {
+    if phases.is_empty() {
+        return;
+    }
+    let app_name: Option<String> = sqlx::query_scalar(
+        "SELECT a.name FROM deployments d JOIN apps a ON a.id = d.app_id WHERE d.id = ?",
+    )
+    .bind(deployment_id)
+    .fetch_optional(db)
+    .await
+    .ok()
+    .flatten();
+    let app_name = app_name.unwrap_or_default();
+    for phase in phases {
+        if let Some(duration_ms) = phase.duration_ms {
+            observe_deployment_phase_duration(
+                &app_name,
+                &phase.phase,
+                &phase.status,
+                duration_ms as f64 / 1000.0,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_final_status() {
+        assert_eq!(final_status("running"), Some("succeeded"));
+        assert_eq!(final_status("failed"), Some("failed"));
+        assert_eq!(final_status("cancelled"), Some("cancelled"));
+        assert_eq!(final_status("building"), None);
+        assert_eq!(final_status("checking"), None);
+    }
+}
@@ -0,0 +1,396 @@
+//! Deployment failure diagnostics bundles.
+//!
+//! When a deployment fails, everything needed to debug it is gathered into one
+//! JSON document stored with the deployment: the tail of the container's logs
+//! and its inspect state, the health check responses, the tail of the build log
+//! and the host's disk and memory. Users download it from
+//! `GET /api/deployments/:id/diagnostics` instead of pasting screenshots.
+//!
+//! Failed containers are usually removed before the engine sees the error, so
+//! the pipeline calls [`capture_container`] right before removing one, staging
+//! a partial bundle in the database; the engine then completes the bundle with
+//! [`collect_on_failure`] and moves it to the artifact store
+//! (`crate::storage::artifacts`) in the configured storage (`[storage]`).
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::db::{App, Deployment};
+use crate::runtime::ContainerRuntime;
+use crate::storage::{artifacts, Storage};
+use crate::DbPool;
+
+use super::DiskStats;
+
+/// Container log lines kept in a bundle
+pub const CONTAINER_LOG_LINES: usize = 200;
+
+/// Deployment (build) log lines kept in a bundle
+pub const BUILD_LOG_LINES: usize = 200;
+
+/// Max time spent reading container logs
+const LOG_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Everything collected about a failed deployment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiagnosticsBundle {
+    pub deployment_id: String,
+    pub app_id: String,
+    pub app_name: String,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub commit_sha: Option<String>,
+    pub image_tag: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub collected_at: String,
+    /// `None` when the deployment failed before a container was started
+    pub container: Option<ContainerDiagnostics>,
+    /// Health check attempts and their failure reasons
+    pub healthcheck: Vec<String>,
+    /// Last lines of the deployment log (clone, build, start)
+    pub build_log_tail: Vec<String>,
+    pub host: HostDiagnostics,
+}
+
+/// State of the deployment's container when it failed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContainerDiagnostics {
+    pub container_id: String,
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub running: Option<bool>,
+    pub restart_count: Option<u32>,
+    pub host_port: Option<u16>,
+    /// Last [`CONTAINER_LOG_LINES`] lines of stdout/stderr
+    pub logs: Vec<String>,
+}
+
+/// Host resources at collection time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostDiagnostics {
+    pub disk_total_bytes: Option<u64>,
+    pub disk_free_bytes: Option<u64>,
+    pub disk_usage_percent: Option<f64>,
+    pub memory_total_bytes: u64,
+    pub memory_available_bytes: u64,
+    pub swap_used_bytes: u64,
+}
+
+impl HostDiagnostics {
+    fn collect() -> Self {
+        let memory = crate::api::system::get_host_memory();
+        let disk = DiskStats::for_path(Path::new(".")).ok();
+        Self {
+            disk_total_bytes: disk.as_ref().map(|d| d.total_bytes),
+            disk_free_bytes: disk.as_ref().map(|d| d.free_bytes),
+            disk_usage_percent: disk.as_ref().map(|d| d.usage_percent),
+            memory_total_bytes: memory.total_bytes,
+            memory_available_bytes: memory.available_bytes,
+            swap_used_bytes: memory.swap_used_bytes,
+        }
+    }
+}
+
+/// Storage key of a bundle stored before the artifact store
+fn legacy_bundle_key(deployment_id: &str) -> String {
+    format!("diagnostics/{}.json", deployment_id)
+}
+
+/// Load the bundle stored for a deployment. Bundles from before the artifact
+/// store are still read from their old key, and ones from before storage
+/// backends (and partial ones) from the database.
+pub async fn load(
+    db: &DbPool,
+    storage: &dyn Storage,
+    deployment_id: &str,
+) -> anyhow::Result<Option<DiagnosticsBundle>> {
+    let stored =
+        match artifacts::get(db, storage, deployment_id, artifacts::KIND_DIAGNOSTICS).await? {
+            Some(data) => Some(data),
+            None => storage.get(&legacy_bundle_key(deployment_id)).await?,
+        };
+    if let Some(data) = stored {
+        return Ok(serde_json::from_slice(&data).ok());
+    }
+    load_staged(db, deployment_id).await
+}
+
+async fn load_staged(
+    db: &DbPool,
+    deployment_id: &str,
+) -> anyhow::Result<Option<DiagnosticsBundle>> {
+    let bundle: Option<String> =
+        sqlx::query_scalar("SELECT bundle FROM deployment_diagnostics WHERE deployment_id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(bundle.and_then(|b| serde_json::from_str(&b).ok()))
+}
+
+async fn save(db: &DbPool, bundle: &DiagnosticsBundle) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO deployment_diagnostics (deployment_id, bundle, created_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(deployment_id) DO UPDATE SET
+            bundle = excluded.bundle,
+            created_at = excluded.created_at
+        "#,
+    )
+    .bind(&bundle.deployment_id)
+    .bind(serde_json::to_string(bundle)?)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Store a completed bundle, in `storage` when the engine has one
+async fn store(
+    db: &DbPool,
+    storage: Option<&dyn Storage>,
+    bundle: &DiagnosticsBundle,
+) -> anyhow::Result<()> {
+    let Some(storage) = storage else {
+        return save(db, bundle).await;
+    };
+    artifacts::put(
+        db,
+        storage,
+        &bundle.deployment_id,
+        artifacts::KIND_DIAGNOSTICS,
+        serde_json::to_vec(bundle)?,
+    )
+    .await?;
+    sqlx::query("DELETE FROM deployment_diagnostics WHERE deployment_id = ?")
+        .bind(&bundle.deployment_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+async fn container_diagnostics(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+) -> ContainerDiagnostics {
+    let mut diagnostics = ContainerDiagnostics {
+        container_id: container_id.to_string(),
+        ..Default::default()
+    };
+    if let Ok(info) = runtime.inspect(container_id).await {
+        diagnostics.name = Some(info.name);
+        diagnostics.status = Some(info.status);
+        diagnostics.running = Some(info.running);
+        diagnostics.restart_count = Some(info.restart_count);
+        diagnostics.host_port = info.host_port;
+    }
+    if let Ok(mut stream) = runtime.logs(container_id).await {
+        let mut tail: VecDeque<String> = VecDeque::with_capacity(CONTAINER_LOG_LINES);
+        let read = async {
+            while let Some(line) = stream.next().await {
+                if tail.len() == CONTAINER_LOG_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(format!("{} {}", line.timestamp, line.message.trim_end()));
+            }
+        };
+        let _ = tokio::time::timeout(LOG_READ_TIMEOUT, read).await;
+        diagnostics.logs = tail.into();
+    }
+    diagnostics
+}
+
+/// Record a failing container's logs and state before it is removed
+pub async fn capture_container(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    container_id: &str,
+) {
+    let mut bundle = load_staged(db, deployment_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DiagnosticsBundle {
+            deployment_id: deployment_id.to_string(),
+            ..Default::default()
+        });
+    bundle.container = Some(container_diagnostics(runtime, container_id).await);
+    if let Err(e) = save(db, &bundle).await {
+        tracing::warn!(deployment_id = %deployment_id, "Failed to save container diagnostics: {}", e);
+    }
+}
+
+/// Complete and store the diagnostics bundle of a failed deployment
+pub async fn collect_on_failure(
+    db: &DbPool,
+    storage: Option<&dyn Storage>,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+) {
+    let Ok(Some(deployment)) =
+        sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await
+    else {
+        return;
+    };
+
+    let mut container = load_staged(db, deployment_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|b| b.container);
+    if container.is_none() {
+        if let Some(ref container_id) = deployment.container_id {
+            container = Some(container_diagnostics(runtime, container_id).await);
+        }
+    }
+
+    let logs: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT timestamp, level, message FROM deployment_logs WHERE deployment_id = ? \
+         ORDER BY id DESC LIMIT ?",
+    )
+    .bind(deployment_id)
+    .bind(BUILD_LOG_LINES as i64)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+    let build_log_tail: Vec<String> = logs
+        .iter()
+        .rev()
+        .map(|(timestamp, level, message)| format!("{} [{}] {}", timestamp, level, message))
+        .collect();
+    let healthcheck: Vec<String> = sqlx::query_scalar(
+        "SELECT message FROM deployment_logs WHERE deployment_id = ? \
+         AND message LIKE 'Health check%' ORDER BY id",
+    )
+    .bind(deployment_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    let bundle = DiagnosticsBundle {
+        deployment_id: deployment.id,
+        app_id: app.id.clone(),
+        app_name: app.name.clone(),
+        status: deployment.status,
+        error_message: deployment.error_message,
+        commit_sha: deployment.commit_sha,
+        image_tag: deployment.image_tag,
+        started_at: deployment.started_at,
+        finished_at: deployment.finished_at,
+        collected_at: chrono::Utc::now().to_rfc3339(),
+        container,
+        healthcheck,
+        build_log_tail,
+        host: HostDiagnostics::collect(),
+    };
+    match store(db, storage, &bundle).await {
+        Ok(()) => tracing::info!(deployment_id = %deployment_id, "Saved diagnostics bundle"),
+        Err(e) => {
+            tracing::warn!(deployment_id = %deployment_id, "Failed to save diagnostics bundle: {}", e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool};
+    use crate::runtime::NoopRuntime;
+    use crate::storage::LocalStorage;
+
+    async fn failed_deployment(db: &DbPool) -> (App, String) {
+        let app_id = test_app(db, "web").await;
+        let deployment_id = test_deployment(db, &app_id, "failed").await;
+        sqlx::query("UPDATE deployments SET error_message = 'Health check failed' WHERE id = ?")
+            .bind(&deployment_id)
+            .execute(db)
+            .await
+            .unwrap();
+        for i in 0..BUILD_LOG_LINES {
+            super::super::add_deployment_log(db, &deployment_id, "info", &format!("step {}", i))
+                .await
+                .unwrap();
+        }
+        for message in [
+            "Health check attempt 1 failed: connection refused",
+            "Health check attempt 2 failed: status 502",
+        ] {
+            super::super::add_deployment_log(db, &deployment_id, "warn", message)
+                .await
+                .unwrap();
+        }
+        (load_test_app(db, &app_id).await, deployment_id)
+    }
+
+    #[tokio::test]
+    async fn test_collects_staged_container_and_log_tails() {
+        let (dir, db) = test_pool().await;
+        let (app, deployment_id) = failed_deployment(&db).await;
+        let runtime = NoopRuntime;
+
+        // The pipeline stages the container before removing it
+        capture_container(&db, &runtime, &deployment_id, "c-failed").await;
+        let staged = load_staged(&db, &deployment_id).await.unwrap().unwrap();
+        assert_eq!(staged.container.unwrap().container_id, "c-failed");
+
+        collect_on_failure(&db, None, &runtime, &deployment_id, &app).await;
+        let storage = LocalStorage::new(dir.path().join("storage"));
+        let bundle = load(&db, &storage, &deployment_id).await.unwrap().unwrap();
+        assert_eq!(bundle.app_name, "web");
+        assert_eq!(bundle.status, "failed");
+        assert_eq!(bundle.error_message.as_deref(), Some("Health check failed"));
+        let container = bundle.container.unwrap();
+        assert_eq!(container.container_id, "c-failed");
+        assert!(container.logs.is_empty());
+        assert_eq!(bundle.healthcheck.len(), 2);
+        assert!(bundle.healthcheck[0].contains("attempt 1"));
+
+        // The newest lines, oldest first
+        assert_eq!(bundle.build_log_tail.len(), BUILD_LOG_LINES);
+        assert!(bundle.build_log_tail[0].ends_with("[info] step 2"));
+        assert!(bundle
+            .build_log_tail
+            .last()
+            .unwrap()
+            .ends_with("[warn] Health check attempt 2 failed: status 502"));
+    }
+
+    #[tokio::test]
+    async fn test_completed_bundle_moves_to_storage() {
+        let (dir, db) = test_pool().await;
+        let (app, deployment_id) = failed_deployment(&db).await;
+        let storage = LocalStorage::new(dir.path().join("storage"));
+        capture_container(&db, &NoopRuntime, &deployment_id, "c-failed").await;
+
+        collect_on_failure(&db, Some(&storage), &NoopRuntime, &deployment_id, &app).await;
+
+        assert!(load_staged(&db, &deployment_id).await.unwrap().is_none());
+        let stored = artifacts::get(&db, &storage, &deployment_id, artifacts::KIND_DIAGNOSTICS)
+            .await
+            .unwrap();
+        assert!(stored.is_some());
+        let bundle = load(&db, &storage, &deployment_id).await.unwrap().unwrap();
+        assert_eq!(bundle.deployment_id, deployment_id);
+        assert_eq!(bundle.container.unwrap().container_id, "c-failed");
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_stored_for_an_unknown_deployment() {
+        let (dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let app = load_test_app(&db, &app_id).await;
+        let storage = LocalStorage::new(dir.path().join("storage"));
+
+        collect_on_failure(&db, Some(&storage), &NoopRuntime, "missing", &app).await;
+
+        assert!(load(&db, &storage, "missing").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,598 @@
+//! Dockerfile generation for common stacks.
+//!
+//! Build detection tells which builder fits a repository; this module goes
+//! one step further for Node, Python, Go and Rust projects and writes a
+//! recommended Dockerfile from the project's own files (lockfile, scripts,
+//! language version, entry point). The result is a starting point the user
+//! reviews and then commits to the repository or stores as a deployment
+//! patch; nothing here is used by deployments on its own.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::fs;
+
+/// Node.js major version used when `engines.node` doesn't pin one
+const DEFAULT_NODE_VERSION: &str = "20";
+
+/// Python version used without a `.python-version` file
+const DEFAULT_PYTHON_VERSION: &str = "3.12";
+
+/// Go version used when `go.mod` has no `go` directive
+const DEFAULT_GO_VERSION: &str = "1.22";
+
+/// A generated Dockerfile and what it was generated from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GeneratedDockerfile {
+    /// Stack the Dockerfile targets: "node", "python", "go" or "rust"
+    pub stack: String,
+    /// Project file the stack was recognized from
+    pub detected_from: String,
+    /// Dockerfile contents
+    pub dockerfile: String,
+    /// Port the generated image listens on
+    pub port: u16,
+    /// Assumptions worth checking before using the Dockerfile
+    pub notes: Vec<String>,
+}
+
+/// Generate a Dockerfile for the project in `source_dir`, or `None` when it
+/// isn't a Node, Python, Go or Rust project.
+pub async fn generate_dockerfile(source_dir: &Path) -> Result<Option<GeneratedDockerfile>> {
+    if let Some(content) = read_optional(source_dir, "package.json").await? {
+        let package: serde_json::Value =
+            serde_json::from_str(&content).context("Failed to parse package.json")?;
+        let manager = PackageManager::detect(source_dir);
+        return Ok(Some(node_dockerfile(&package, manager)));
+    }
+
+    for file in ["requirements.txt", "pyproject.toml"] {
+        if let Some(content) = read_optional(source_dir, file).await? {
+            let version = read_optional(source_dir, ".python-version").await?;
+            let entry = PythonEntry::detect(source_dir, &content.to_lowercase()).await?;
+            return Ok(Some(python_dockerfile(file, version.as_deref(), &entry)));
+        }
+    }
+
+    if let Some(content) = read_optional(source_dir, "go.mod").await? {
+        let has_go_sum = source_dir.join("go.sum").exists();
+        let package = go_main_package(source_dir).await?;
+        return Ok(Some(go_dockerfile(&content, has_go_sum, &package)));
+    }
+
+    if let Some(content) = read_optional(source_dir, "Cargo.toml").await? {
+        let manifest: toml::Value =
+            toml::from_str(&content).context("Failed to parse Cargo.toml")?;
+        let has_lock = source_dir.join("Cargo.lock").exists();
+        return Ok(rust_dockerfile(&manifest, has_lock));
+    }
+
+    Ok(None)
+}
+
+async fn read_optional(dir: &Path, name: &str) -> Result<Option<String>> {
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    fs::read_to_string(&path)
+        .await
+        .map(Some)
+        .with_context(|| format!("Failed to read {}", name))
+}
+
+// -------------------------------------------------------------------------
+// Node.js
+// -------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Npm { locked: bool },
+    Yarn,
+    Pnpm,
+}
+
+impl PackageManager {
+    fn detect(dir: &Path) -> Self {
+        if dir.join("pnpm-lock.yaml").exists() {
+            PackageManager::Pnpm
+        } else if dir.join("yarn.lock").exists() {
+            PackageManager::Yarn
+        } else {
+            PackageManager::Npm {
+                locked: dir.join("package-lock.json").exists(),
+            }
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PackageManager::Npm { .. } => "npm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Pnpm => "pnpm",
+        }
+    }
+
+    fn manifest_files(self) -> &'static str {
+        match self {
+            PackageManager::Npm { locked: true } => "package.json package-lock.json",
+            PackageManager::Npm { locked: false } => "package.json",
+            PackageManager::Yarn => "package.json yarn.lock",
+            PackageManager::Pnpm => "package.json pnpm-lock.yaml",
+        }
+    }
+
+    fn install(self) -> &'static str {
+        match self {
+            PackageManager::Npm { locked: true } => "npm ci",
+            PackageManager::Npm { locked: false } => "npm install",
+            PackageManager::Yarn => "yarn install --frozen-lockfile",
+            PackageManager::Pnpm => "pnpm install --frozen-lockfile",
+        }
+    }
+}
+
+/// Major version from an `engines.node` range such as ">=18", "20.x" or "^22.1"
+fn node_major_version(package: &serde_json::Value) -> Option<String> {
+    let range = package.get("engines")?.get("node")?.as_str()?;
+    let start = range.find(|c: char| c.is_ascii_digit())?;
+    let major: String = range[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    Some(major)
+}
+
+fn node_dockerfile(package: &serde_json::Value, manager: PackageManager) -> GeneratedDockerfile {
+    let mut notes = Vec::new();
+    let version = node_major_version(package).unwrap_or_else(|| {
+        notes.push(format!(
+            "No engines.node in package.json; using Node.js {}",
+            DEFAULT_NODE_VERSION
+        ));
+        DEFAULT_NODE_VERSION.to_string()
+    });
+    let scripts = package.get("scripts").and_then(|s| s.as_object());
+    let has_script = |name: &str| scripts.is_some_and(|s| s.contains_key(name));
+
+    let corepack = if matches!(manager, PackageManager::Npm { .. }) {
+        ""
+    } else {
+        "RUN corepack enable\n"
+    };
+    let build = if has_script("build") {
+        format!("RUN {} run build\n", manager.name())
+    } else {
+        String::new()
+    };
+    let cmd = if has_script("start") {
+        format!("[\"{}\", \"start\"]", manager.name())
+    } else {
+        let main = package
+            .get("main")
+            .and_then(|m| m.as_str())
+            .unwrap_or_else(|| {
+                notes.push(
+                    "No start script or main entry in package.json; assuming index.js".to_string(),
+                );
+                "index.js"
+            });
+        format!("[\"node\", \"{}\"]", main)
+    };
+    notes.push("The app should listen on $PORT (3000)".to_string());
+
+    let dockerfile = format!(
+        "FROM node:{version}-slim AS deps\n\
+         WORKDIR /app\n\
+         {corepack}\
+         COPY {files} ./\n\
+         RUN {install}\n\
+         \n\
+         FROM node:{version}-slim\n\
+         WORKDIR /app\n\
+         {corepack}\
+         COPY --from=deps /app/node_modules ./node_modules\n\
+         COPY . .\n\
+         {build}\
+         ENV NODE_ENV=production\n\
+         ENV PORT=3000\n\
+         EXPOSE 3000\n\
+         CMD {cmd}\n",
+        files = manager.manifest_files(),
+        install = manager.install(),
+    );
+
+    GeneratedDockerfile {
+        stack: "node".to_string(),
+        detected_from: "package.json".to_string(),
+        dockerfile,
+        port: 3000,
+        notes,
+    }
+}
+
+// -------------------------------------------------------------------------
+// Python
+// -------------------------------------------------------------------------
+
+/// How a Python app is started
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PythonEntry {
+    /// Django project with `<module>/wsgi.py`
+    Django { module: String },
+    /// ASGI app object, e.g. "main:app"
+    Asgi { target: String },
+    /// WSGI app object, e.g. "app:app"
+    Wsgi { target: String },
+    /// Plain script
+    Script { file: String },
+}
+
+impl PythonEntry {
+    /// Pick the entry point from the dependency list (lowercased) and the
+    /// project's top-level files
+    async fn detect(dir: &Path, dependencies: &str) -> Result<Self> {
+        let script = ["main.py", "app.py", "server.py", "wsgi.py"]
+            .into_iter()
+            .find(|f| dir.join(f).is_file());
+        let module = script.map(|f| f.trim_end_matches(".py").to_string());
+
+        if dependencies.contains("django") {
+            let mut entries = fs::read_dir(dir)
+                .await
+                .context("Failed to read directory")?;
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.path().join("wsgi.py").is_file() {
+                    return Ok(PythonEntry::Django {
+                        module: entry.file_name().to_string_lossy().to_string(),
+                    });
+                }
+            }
+        }
+        if dependencies.contains("fastapi") || dependencies.contains("starlette") {
+            let module = module.unwrap_or_else(|| "main".to_string());
+            return Ok(PythonEntry::Asgi {
+                target: format!("{}:app", module),
+            });
+        }
+        if dependencies.contains("flask") {
+            let module = module.unwrap_or_else(|| "app".to_string());
+            return Ok(PythonEntry::Wsgi {
+                target: format!("{}:app", module),
+            });
+        }
+        Ok(PythonEntry::Script {
+            file: script.unwrap_or("main.py").to_string(),
+        })
+    }
+}
+
+fn python_dockerfile(
+    manifest: &str,
+    version: Option<&str>,
+    entry: &PythonEntry,
+) -> GeneratedDockerfile {
+    let mut notes = Vec::new();
+    let version = version
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.split('.').take(2).collect::<Vec<_>>().join("."))
+        .unwrap_or_else(|| {
+            notes.push(format!(
+                "No .python-version file; using Python {}",
+                DEFAULT_PYTHON_VERSION
+            ));
+            DEFAULT_PYTHON_VERSION.to_string()
+        });
+
+    let install = if manifest == "requirements.txt" {
+        "COPY requirements.txt ./\n\
+         RUN pip install --no-cache-dir -r requirements.txt\n\
+         COPY . .\n"
+    } else {
+        "COPY . .\n\
+         RUN pip install --no-cache-dir .\n"
+    };
+    let (server, cmd) = match entry {
+        PythonEntry::Django { module } => (
+            "RUN pip install --no-cache-dir gunicorn\n",
+            format!(
+                "[\"gunicorn\", \"{}.wsgi\", \"--bind\", \"0.0.0.0:8000\"]",
+                module
+            ),
+        ),
+        PythonEntry::Asgi { target } => (
+            "RUN pip install --no-cache-dir uvicorn\n",
+            format!(
+                "[\"uvicorn\", \"{}\", \"--host\", \"0.0.0.0\", \"--port\", \"8000\"]",
+                target
+            ),
+        ),
+        PythonEntry::Wsgi { target } => (
+            "RUN pip install --no-cache-dir gunicorn\n",
+            format!(
+                "[\"gunicorn\", \"{}\", \"--bind\", \"0.0.0.0:8000\"]",
+                target
+            ),
+        ),
+        PythonEntry::Script { file } => {
+            notes.push(format!(
+                "No web framework recognized; running {} directly",
+                file
+            ));
+            ("", format!("[\"python\", \"{}\"]", file))
+        }
+    };
+    notes.push("The app should listen on port 8000".to_string());
+
+    let dockerfile = format!(
+        "FROM python:{version}-slim\n\
+         WORKDIR /app\n\
+         ENV PYTHONDONTWRITEBYTECODE=1\n\
+         ENV PYTHONUNBUFFERED=1\n\
+         {install}\
+         {server}\
+         EXPOSE 8000\n\
+         CMD {cmd}\n"
+    );
+
+    GeneratedDockerfile {
+        stack: "python".to_string(),
+        detected_from: manifest.to_string(),
+        dockerfile,
+        port: 8000,
+        notes,
+    }
+}
+
+// -------------------------------------------------------------------------
+// Go
+// -------------------------------------------------------------------------
+
+/// Package to build: "." when the module root is a main package, else the
+/// single directory under `cmd/`
+async fn go_main_package(dir: &Path) -> Result<String> {
+    if dir.join("main.go").is_file() {
+        return Ok(".".to_string());
+    }
+    let cmd_dir = dir.join("cmd");
+    if cmd_dir.is_dir() {
+        let mut entries = fs::read_dir(&cmd_dir)
+            .await
+            .context("Failed to read cmd directory")?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.path().join("main.go").is_file() {
+                return Ok(format!("./cmd/{}", entry.file_name().to_string_lossy()));
+            }
+        }
+    }
+    Ok(".".to_string())
+}
+
+/// "1.22" from a `go 1.22.3` directive
+fn go_version(go_mod: &str) -> Option<String> {
+    let version = go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("go "))?
+        .trim();
+    let minor: Vec<&str> = version.split('.').take(2).collect();
+    (minor.len() == 2).then(|| minor.join("."))
+}
+
+fn go_dockerfile(go_mod: &str, has_go_sum: bool, package: &str) -> GeneratedDockerfile {
+    let mut notes = Vec::new();
+    let version = go_version(go_mod).unwrap_or_else(|| {
+        notes.push(format!(
+            "No go directive in go.mod; using Go {}",
+            DEFAULT_GO_VERSION
+        ));
+        DEFAULT_GO_VERSION.to_string()
+    });
+    let modules = if has_go_sum {
+        "go.mod go.sum"
+    } else {
+        "go.mod"
+    };
+    notes.push(
+        "The binary is built with CGO disabled; switch the runtime image if it needs cgo"
+            .to_string(),
+    );
+    notes.push("The app should listen on port 8080".to_string());
+
+    let dockerfile = format!(
+        "FROM golang:{version}-alpine AS builder\n\
+         WORKDIR /src\n\
+         COPY {modules} ./\n\
+         RUN go mod download\n\
+         COPY . .\n\
+         RUN CGO_ENABLED=0 go build -trimpath -ldflags=\"-s -w\" -o /out/app {package}\n\
+         \n\
+         FROM gcr.io/distroless/static-debian12\n\
+         COPY --from=builder /out/app /app\n\
+         EXPOSE 8080\n\
+         ENTRYPOINT [\"/app\"]\n"
+    );
+
+    GeneratedDockerfile {
+        stack: "go".to_string(),
+        detected_from: "go.mod".to_string(),
+        dockerfile,
+        port: 8080,
+        notes,
+    }
+}
+
+// -------------------------------------------------------------------------
+// Rust
+// -------------------------------------------------------------------------
+
+/// The binary `cargo build` produces: the first `[[bin]]`, else the package
+fn rust_binary_name(manifest: &toml::Value) -> Option<String> {
+    let from_bin = manifest
+        .get("bin")
+        .and_then(|b| b.as_array())
+        .and_then(|bins| bins.first())
+        .and_then(|b| b.get("name"))
+        .and_then(|n| n.as_str());
+    let from_package = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str());
+    from_bin.or(from_package).map(str::to_string)
+}
+
+fn rust_dockerfile(manifest: &toml::Value, has_lock: bool) -> Option<GeneratedDockerfile> {
+    // A virtual workspace manifest doesn't say which member to ship
+    let binary = rust_binary_name(manifest)?;
+    let locked = if has_lock { " --locked" } else { "" };
+    let notes = vec![
+        "The runtime image is debian:bookworm-slim with CA certificates; \
+         add any shared libraries the binary links"
+            .to_string(),
+        "The app should listen on port 8080".to_string(),
+    ];
+
+    let dockerfile = format!(
+        "FROM rust:1-slim AS builder\n\
+         WORKDIR /src\n\
+         COPY . .\n\
+         RUN cargo build --release{locked} --bin {binary}\n\
+         \n\
+         FROM debian:bookworm-slim\n\
+         RUN apt-get update \\\n    \
+         && apt-get install -y --no-install-recommends ca-certificates \\\n    \
+         && rm -rf /var/lib/apt/lists/*\n\
+         COPY --from=builder /src/target/release/{binary} /usr/local/bin/{binary}\n\
+         EXPOSE 8080\n\
+         CMD [\"{binary}\"]\n"
+    );
+
+    Some(GeneratedDockerfile {
+        stack: "rust".to_string(),
+        detected_from: "Cargo.toml".to_string(),
+        dockerfile,
+        port: 8080,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, content).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_node_project() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "package.json",
+            r#"{"engines": {"node": ">=18.17"},
+                "scripts": {"build": "vite build", "start": "node server.js"}}"#,
+        )
+        .await;
+        write(dir.path(), "pnpm-lock.yaml", "").await;
+
+        let generated = generate_dockerfile(dir.path()).await.unwrap().unwrap();
+        assert_eq!(generated.stack, "node");
+        assert_eq!(generated.port, 3000);
+        let dockerfile = &generated.dockerfile;
+        assert!(dockerfile.starts_with("FROM node:18-slim AS deps\n"));
+        assert!(dockerfile.contains("COPY package.json pnpm-lock.yaml ./\n"));
+        assert!(dockerfile.contains("RUN pnpm install --frozen-lockfile\n"));
+        assert!(dockerfile.contains("RUN pnpm run build\n"));
+        assert!(dockerfile.ends_with("CMD [\"pnpm\", \"start\"]\n"));
+    }
+
+    #[test]
+    fn test_node_without_start_script() {
+        let package = serde_json::json!({"main": "dist/index.js"});
+        let generated = node_dockerfile(&package, PackageManager::Npm { locked: false });
+        assert!(generated.dockerfile.contains("FROM node:20-slim"));
+        assert!(generated.dockerfile.contains("RUN npm install\n"));
+        assert!(!generated.dockerfile.contains("corepack"));
+        assert!(!generated.dockerfile.contains("run build"));
+        assert!(generated
+            .dockerfile
+            .ends_with("CMD [\"node\", \"dist/index.js\"]\n"));
+    }
+
+    #[tokio::test]
+    async fn test_python_projects() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "requirements.txt", "Django==5.0\npsycopg\n").await;
+        write(dir.path(), "mysite/wsgi.py", "").await;
+        write(dir.path(), ".python-version", "3.11.4\n").await;
+
+        let generated = generate_dockerfile(dir.path()).await.unwrap().unwrap();
+        assert_eq!(generated.stack, "python");
+        assert!(generated.dockerfile.starts_with("FROM python:3.11-slim\n"));
+        assert!(generated
+            .dockerfile
+            .contains("[\"gunicorn\", \"mysite.wsgi\", \"--bind\", \"0.0.0.0:8000\"]"));
+
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "pyproject.toml",
+            "dependencies = [\"fastapi\"]\n",
+        )
+        .await;
+        write(dir.path(), "app.py", "").await;
+        let generated = generate_dockerfile(dir.path()).await.unwrap().unwrap();
+        assert_eq!(generated.detected_from, "pyproject.toml");
+        assert!(generated
+            .dockerfile
+            .contains("RUN pip install --no-cache-dir .\n"));
+        assert!(generated.dockerfile.contains("[\"uvicorn\", \"app:app\""));
+    }
+
+    #[tokio::test]
+    async fn test_go_project() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "go.mod",
+            "module example.com/api\n\ngo 1.21.5\n",
+        )
+        .await;
+        write(dir.path(), "cmd/api/main.go", "package main\n").await;
+
+        let generated = generate_dockerfile(dir.path()).await.unwrap().unwrap();
+        assert_eq!(generated.stack, "go");
+        assert!(generated
+            .dockerfile
+            .starts_with("FROM golang:1.21-alpine AS builder\n"));
+        assert!(generated.dockerfile.contains("COPY go.mod ./\n"));
+        assert!(generated.dockerfile.contains("-o /out/app ./cmd/api\n"));
+    }
+
+    #[test]
+    fn test_rust_projects() {
+        let manifest: toml::Value =
+            toml::from_str("[package]\nname = \"api\"\n\n[[bin]]\nname = \"server\"\n").unwrap();
+        let generated = rust_dockerfile(&manifest, true).unwrap();
+        assert!(generated
+            .dockerfile
+            .contains("RUN cargo build --release --locked --bin server\n"));
+        assert!(generated.dockerfile.ends_with("CMD [\"server\"]\n"));
+
+        let workspace: toml::Value =
+            toml::from_str("[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        assert!(rust_dockerfile(&workspace, true).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_project() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "Gemfile", "source 'https://rubygems.org'\n").await;
+        assert!(generate_dockerfile(dir.path()).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,640 @@
+//! Per-app network egress policies.
+//!
+//! An app's outbound traffic is either unrestricted (`allow_all`, the
+//! default) or `restricted`: denied except to allow-listed hosts and IPv4
+//! CIDRs. Restricted apps get a dedicated iptables chain
+//! (`RIVETR-EG-<app id prefix>`) that the host's forward hook
+//! (`DOCKER-USER`, or `FORWARD` when Docker's chain is absent) jumps to for
+//! every address the app's containers hold. The chain lets through:
+//! - replies on established connections,
+//! - traffic to the networks the container is attached to (the shared
+//!   `rivetr` network and the app's `rivetr-app-<id>` network), so linked
+//!   databases and services stay reachable,
+//! - DNS to the host's nameservers, so allow-listed hostnames resolve
+//!   inside the container,
+//! - the allow-list,
+//!
+//! and rejects everything else. Containers on user-defined networks resolve
+//! names through Docker's embedded DNS server (127.0.0.11), which answers
+//! inside the container and forwards upstream from the host, so those
+//! lookups never cross the forward hook. Containers on the default bridge
+//! query the host's nameservers directly (Docker copies them in, skipping
+//! loopback ones); those are the only DNS servers the chain lets through.
+//!
+//! Containers holding IPv6 addresses get the same chain in ip6tables. The
+//! allow-list is IPv4-only, so over IPv6 they can reach only their own
+//! networks and the host's IPv6 nameservers.
+//!
+//! Hostnames are resolved when the rules are applied; the enforcer task
+//! re-applies every policy periodically, which picks up DNS changes, new
+//! replicas and containers restarted with a new address.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::interval;
+
+use crate::db::App;
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+/// Outbound traffic is not filtered
+pub const EGRESS_ALLOW_ALL: &str = "allow_all";
+/// Outbound traffic is denied except to the allow-list
+pub const EGRESS_RESTRICTED: &str = "restricted";
+
+/// All accepted `egress_policy` values.
+pub const EGRESS_POLICIES: &[&str] = &[EGRESS_ALLOW_ALL, EGRESS_RESTRICTED];
+
+/// Prefix of the per-app iptables chains
+const CHAIN_PREFIX: &str = "RIVETR-EG-";
+
+/// How often the enforcer re-applies every restricted app's rules
+const ENFORCE_INTERVAL_SECS: u64 = 60;
+
+/// Nameservers Docker hands to containers that don't use its embedded DNS
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+/// Upstream nameservers when the host runs systemd-resolved's local stub
+const SYSTEMD_RESOLV_CONF: &str = "/run/systemd/resolve/resolv.conf";
+/// Docker's fallback when the host has no usable nameserver
+const DOCKER_DEFAULT_RESOLVERS: &[&str] = &[
+    "8.8.8.8",
+    "8.8.4.4",
+    "2001:4860:4860::8888",
+    "2001:4860:4860::8844",
+];
+
+/// Address family of a set of rules: iptables for IPv4, ip6tables for IPv6
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    const ALL: [Family; 2] = [Family::V4, Family::V6];
+
+    fn command(self) -> &'static str {
+        match self {
+            Family::V4 => "iptables",
+            Family::V6 => "ip6tables",
+        }
+    }
+
+    fn of(ip: &IpAddr) -> Self {
+        if ip.is_ipv4() {
+            Family::V4
+        } else {
+            Family::V6
+        }
+    }
+}
+
+/// A parsed allow-list entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EgressTarget {
+    /// An IPv4 network (a single address is a /32)
+    Network(Ipv4Addr, u8),
+    /// A hostname, resolved to its IPv4 addresses when rules are applied
+    Host(String),
+}
+
+impl EgressTarget {
+    /// Parse an allow-list entry: an IPv4 address, an IPv4 CIDR or a hostname.
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return Err("Entry is empty".to_string());
+        }
+        if entry.contains(':') {
+            return Err(format!(
+                "'{}': IPv6 addresses and ports are not supported",
+                entry
+            ));
+        }
+
+        if let Some((addr, prefix)) = entry.split_once('/') {
+            let addr: Ipv4Addr = addr
+                .parse()
+                .map_err(|_| format!("'{}' is not a valid IPv4 CIDR", entry))?;
+            let prefix: u8 = prefix
+                .parse()
+                .ok()
+                .filter(|p| *p <= 32)
+                .ok_or_else(|| format!("'{}' has an invalid prefix length", entry))?;
+            return Ok(Self::Network(mask(addr, prefix), prefix));
+        }
+        if let Ok(addr) = entry.parse::<Ipv4Addr>() {
+            return Ok(Self::Network(addr, 32));
+        }
+
+        let host = entry.trim_end_matches('.').to_ascii_lowercase();
+        let valid = host.len() <= 253
+            && host.split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !valid || host.split('.').all(|label| label.parse::<u32>().is_ok()) {
+            return Err(format!(
+                "'{}' is not a hostname, IPv4 address or IPv4 CIDR",
+                entry
+            ));
+        }
+        Ok(Self::Host(host))
+    }
+}
+
+/// `addr` with the host bits below `prefix` cleared
+fn mask(addr: Ipv4Addr, prefix: u8) -> Ipv4Addr {
+    let bits = u32::from(addr);
+    let mask = if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    };
+    Ipv4Addr::from(bits & mask)
+}
+
+/// `addr` with the host bits below `prefix` cleared, for either family
+fn mask_ip(addr: IpAddr, prefix: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(v4) => IpAddr::V4(mask(v4, prefix)),
+        IpAddr::V6(v6) => {
+            let bits = u128::from(v6);
+            let mask = if prefix == 0 {
+                0
+            } else {
+                u128::MAX << (128 - u32::from(prefix.min(128)))
+            };
+            IpAddr::V6(Ipv6Addr::from(bits & mask))
+        }
+    }
+}
+
+/// Nameservers listed in a resolv.conf, without loopback ones (containers
+/// cannot reach the host's loopback)
+pub fn parse_nameservers(resolv_conf: &str) -> Vec<IpAddr> {
+    resolv_conf
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver "))
+        .filter_map(|server| server.trim().parse::<IpAddr>().ok())
+        .filter(|ip| !ip.is_loopback())
+        .collect()
+}
+
+/// The nameservers containers outside Docker's embedded DNS resolve through,
+/// picked the way Docker picks them
+async fn host_resolvers() -> Vec<IpAddr> {
+    for path in [RESOLV_CONF, SYSTEMD_RESOLV_CONF] {
+        if let Ok(contents) = tokio::fs::read_to_string(path).await {
+            let servers = parse_nameservers(&contents);
+            if !servers.is_empty() {
+                return servers;
+            }
+        }
+    }
+    DOCKER_DEFAULT_RESOLVERS
+        .iter()
+        .filter_map(|server| server.parse().ok())
+        .collect()
+}
+
+/// Whether `app` restricts its outbound traffic
+pub fn is_restricted(app: &App) -> bool {
+    app.egress_policy == EGRESS_RESTRICTED
+}
+
+/// The app's allow-list entries (unparseable entries are skipped; the API
+/// validates them on save)
+pub fn allowlist(app: &App) -> Vec<EgressTarget> {
+    app.get_egress_allowlist()
+        .iter()
+        .filter_map(|entry| EgressTarget::parse(entry).ok())
+        .collect()
+}
+
+/// Name of the iptables chain holding an app's rules
+fn chain_name(app_id: &str) -> String {
+    let id: String = app_id.chars().filter(|c| *c != '-').take(12).collect();
+    format!("{}{}", CHAIN_PREFIX, id)
+}
+
+/// Arguments of every rule in an app's chain, in order: established
+/// connections, the container's own networks, DNS to `resolvers`, the
+/// allow-list, then reject. All addresses must be of one family.
+pub fn chain_rules(
+    attached: &[(IpAddr, u8)],
+    resolvers: &[IpAddr],
+    allowed: &[(IpAddr, u8)],
+) -> Vec<Vec<String>> {
+    let args = |parts: &[&str]| parts.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let mut rules = vec![args(&[
+        "-m",
+        "conntrack",
+        "--ctstate",
+        "ESTABLISHED,RELATED",
+        "-j",
+        "RETURN",
+    ])];
+
+    let mut seen = HashSet::new();
+    let mut allow_nets = |nets: &[(IpAddr, u8)], rules: &mut Vec<Vec<String>>| {
+        for (addr, prefix) in nets {
+            let net = format!("{}/{}", mask_ip(*addr, *prefix), prefix);
+            if seen.insert(net.clone()) {
+                rules.push(args(&["-d", &net, "-j", "RETURN"]));
+            }
+        }
+    };
+    allow_nets(attached, &mut rules);
+    for resolver in resolvers {
+        let host = format!("{}/{}", resolver, if resolver.is_ipv4() { 32 } else { 128 });
+        for proto in ["udp", "tcp"] {
+            rules.push(args(&[
+                "-d", &host, "-p", proto, "--dport", "53", "-j", "RETURN",
+            ]));
+        }
+    }
+    allow_nets(allowed, &mut rules);
+
+    rules.push(args(&["-j", "REJECT"]));
+    rules
+}
+
+/// Run `iptables -w <args>` (or ip6tables), returning stdout
+async fn iptables(family: Family, args: &[String]) -> Result<String> {
+    let output = Command::new(family.command())
+        .arg("-w")
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", family.command()))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            family.command(),
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The chain Rivetr hooks app chains into: `DOCKER-USER` when Docker manages
+/// the host firewall, otherwise `FORWARD`
+async fn hook_chain(family: Family) -> &'static str {
+    let docker_user = ["-S".to_string(), "DOCKER-USER".to_string()];
+    if iptables(family, &docker_user).await.is_ok() {
+        "DOCKER-USER"
+    } else {
+        "FORWARD"
+    }
+}
+
+/// Delete every jump from `hook` to `chain`
+async fn remove_jumps(family: Family, hook: &str, chain: &str) -> Result<()> {
+    let listing = iptables(family, &["-S".to_string(), hook.to_string()]).await?;
+    for line in listing.lines() {
+        let mut parts: Vec<String> = line.split_whitespace().map(String::from).collect();
+        let jumps_to_chain = parts.windows(2).any(|w| w[0] == "-j" && w[1] == chain);
+        if parts.first().map(String::as_str) == Some("-A") && jumps_to_chain {
+            parts[0] = "-D".to_string();
+            iptables(family, &parts).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove `chain` and the jumps to it, if it exists
+async fn remove_chain(family: Family, chain: &str) -> Result<()> {
+    if iptables(family, &["-S".to_string(), chain.to_string()])
+        .await
+        .is_err()
+    {
+        return Ok(());
+    }
+    remove_jumps(family, hook_chain(family).await, chain).await?;
+    iptables(family, &["-F".to_string(), chain.to_string()]).await?;
+    iptables(family, &["-X".to_string(), chain.to_string()]).await?;
+    Ok(())
+}
+
+/// Remove an app's egress rules, if any
+pub async fn remove_rules(app_id: &str) -> Result<()> {
+    let chain = chain_name(app_id);
+    for family in Family::ALL {
+        remove_chain(family, &chain).await?;
+    }
+    Ok(())
+}
+
+/// Container IDs currently serving an app (primary and replicas)
+async fn running_containers(db: &DbPool, app_id: &str) -> Result<Vec<String>> {
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT container_id FROM deployments \
+         WHERE app_id = ? AND status = 'running' AND container_id IS NOT NULL AND container_id != '' \
+         UNION \
+         SELECT container_id FROM app_replicas \
+         WHERE app_id = ? AND status = 'running' AND container_id IS NOT NULL AND container_id != ''",
+    )
+    .bind(app_id)
+    .bind(app_id)
+    .fetch_all(db)
+    .await?;
+    Ok(ids)
+}
+
+/// Bring the host firewall in line with `app`'s egress policy: install its
+/// chain for the addresses of its running containers, or remove it when the
+/// app is not restricted.
+pub async fn apply(db: &DbPool, runtime: &Arc<dyn ContainerRuntime>, app: &App) -> Result<()> {
+    if !is_restricted(app) {
+        return remove_rules(&app.id).await;
+    }
+
+    let mut sources: Vec<IpAddr> = Vec::new();
+    let mut attached: Vec<(IpAddr, u8)> = Vec::new();
+    for container_id in running_containers(db, &app.id).await? {
+        match runtime.network_addresses(&container_id).await {
+            Ok(addresses) => {
+                for address in addresses {
+                    sources.push(IpAddr::V4(address.ip));
+                    attached.push((IpAddr::V4(address.ip), address.prefix_len));
+                    if let Some((ip, prefix_len)) = address.ipv6 {
+                        sources.push(IpAddr::V6(ip));
+                        attached.push((IpAddr::V6(ip), prefix_len));
+                    }
+                }
+            }
+            Err(e) => tracing::debug!(
+                app_id = %app.id,
+                container_id = %container_id,
+                error = %e,
+                "Could not read container addresses for egress rules"
+            ),
+        }
+    }
+
+    let mut allowed: Vec<(IpAddr, u8)> = Vec::new();
+    for target in allowlist(app) {
+        match target {
+            EgressTarget::Network(addr, prefix) => allowed.push((IpAddr::V4(addr), prefix)),
+            EgressTarget::Host(host) => match tokio::net::lookup_host((host.as_str(), 0)).await {
+                Ok(resolved) => allowed.extend(
+                    resolved
+                        .map(|sa| sa.ip())
+                        .filter(IpAddr::is_ipv4)
+                        .map(|ip| (ip, 32)),
+                ),
+                Err(e) => tracing::warn!(
+                    app_id = %app.id,
+                    host = %host,
+                    error = %e,
+                    "Could not resolve egress allow-list host"
+                ),
+            },
+        }
+    }
+
+    let resolvers = host_resolvers().await;
+    let chain = chain_name(&app.id);
+    sources.sort();
+    sources.dedup();
+    for family in Family::ALL {
+        let of_family = |ip: &IpAddr| Family::of(ip) == family;
+        let family_sources: Vec<IpAddr> = sources.iter().copied().filter(of_family).collect();
+        if family_sources.is_empty() {
+            remove_chain(family, &chain).await?;
+            continue;
+        }
+        let family_attached: Vec<(IpAddr, u8)> = attached
+            .iter()
+            .copied()
+            .filter(|(ip, _)| of_family(ip))
+            .collect();
+        let family_resolvers: Vec<IpAddr> = resolvers.iter().copied().filter(of_family).collect();
+        let family_allowed: Vec<(IpAddr, u8)> = allowed
+            .iter()
+            .copied()
+            .filter(|(ip, _)| of_family(ip))
+            .collect();
+        install_chain(
+            family,
+            &chain,
+            &family_sources,
+            chain_rules(&family_attached, &family_resolvers, &family_allowed),
+        )
+        .await?;
+    }
+
+    tracing::debug!(
+        app_id = %app.id,
+        chain = %chain,
+        containers = sources.len(),
+        allowed = allowed.len(),
+        "Egress rules applied"
+    );
+    Ok(())
+}
+
+/// Create or rebuild `chain` with `rules` and point the hook at it for
+/// traffic from `sources`
+async fn install_chain(
+    family: Family,
+    chain: &str,
+    sources: &[IpAddr],
+    rules: Vec<Vec<String>>,
+) -> Result<()> {
+    let hook = hook_chain(family).await;
+
+    // Create the chain on first use, otherwise rebuild it in place
+    if iptables(family, &["-N".to_string(), chain.to_string()])
+        .await
+        .is_err()
+    {
+        iptables(family, &["-F".to_string(), chain.to_string()]).await?;
+    }
+    for rule in rules {
+        let mut args = vec!["-A".to_string(), chain.to_string()];
+        args.extend(rule);
+        iptables(family, &args).await?;
+    }
+
+    // Re-point the hook at the containers' current addresses
+    remove_jumps(family, hook, chain).await?;
+    for source in sources {
+        let host = if source.is_ipv4() { 32 } else { 128 };
+        iptables(
+            family,
+            &[
+                "-I".to_string(),
+                hook.to_string(),
+                "-s".to_string(),
+                format!("{}/{}", source, host),
+                "-j".to_string(),
+                chain.to_string(),
+            ],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Apply an app's egress policy, logging instead of failing
+pub async fn apply_logged(db: &DbPool, runtime: &Arc<dyn ContainerRuntime>, app: &App) {
+    if let Err(e) = apply(db, runtime, app).await {
+        tracing::warn!(app_id = %app.id, error = %e, "Failed to apply egress policy");
+    }
+}
+
+/// One enforcement pass: re-apply every restricted app's rules and drop the
+/// chains of apps that are no longer restricted (or no longer exist).
+async fn enforce_cycle(db: &DbPool, runtime: &Arc<dyn ContainerRuntime>) {
+    let restricted: Vec<App> = match sqlx::query_as("SELECT * FROM apps WHERE egress_policy = ?")
+        .bind(EGRESS_RESTRICTED)
+        .fetch_all(db)
+        .await
+    {
+        Ok(apps) => apps,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load apps for egress enforcement");
+            return;
+        }
+    };
+
+    for app in &restricted {
+        apply_logged(db, runtime, app).await;
+    }
+
+    // Stale chains: the chain name only keeps a prefix of the app ID, so
+    // compare chain names rather than IDs
+    let wanted: HashSet<String> = restricted.iter().map(|a| chain_name(&a.id)).collect();
+    for family in Family::ALL {
+        let Ok(listing) = iptables(family, &["-S".to_string()]).await else {
+            continue;
+        };
+        for chain in listing
+            .lines()
+            .filter_map(|line| line.strip_prefix("-N "))
+            .filter(|chain| chain.starts_with(CHAIN_PREFIX) && !wanted.contains(*chain))
+        {
+            if let Err(e) = remove_chain(family, chain).await {
+                tracing::warn!(chain = %chain, error = %e, "Failed to remove stale egress chain");
+            }
+        }
+    }
+}
+
+/// Spawn the background task that keeps egress rules in line with app policies
+pub fn spawn_egress_enforcer(db: DbPool, runtime: Arc<dyn ContainerRuntime>) {
+    tracing::info!(
+        "Starting egress policy enforcer ({}s interval)",
+        ENFORCE_INTERVAL_SECS
+    );
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(ENFORCE_INTERVAL_SECS));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("egress_enforcer", enforce_cycle(&db, &runtime)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(
+            EgressTarget::parse("10.1.2.3"),
+            Ok(EgressTarget::Network(Ipv4Addr::new(10, 1, 2, 3), 32))
+        );
+        assert_eq!(
+            EgressTarget::parse("10.1.2.3/16"),
+            Ok(EgressTarget::Network(Ipv4Addr::new(10, 1, 0, 0), 16))
+        );
+        assert_eq!(
+            EgressTarget::parse(" API.Stripe.com. "),
+            Ok(EgressTarget::Host("api.stripe.com".to_string()))
+        );
+        assert!(EgressTarget::parse("10.0.0.0/33").is_err());
+        assert!(EgressTarget::parse("2001:db8::/32").is_err());
+        assert!(EgressTarget::parse("example.com:443").is_err());
+        assert!(EgressTarget::parse("300.1.1.1").is_err());
+        assert!(EgressTarget::parse("-bad-.example.com").is_err());
+        assert!(EgressTarget::parse("").is_err());
+    }
+
+    #[test]
+    fn test_chain_name_fits_iptables_limit() {
+        let name = chain_name("3f2b8c1e-9a4d-4e6f-8b2a-1c3d5e7f9a0b");
+        assert_eq!(name, "RIVETR-EG-3f2b8c1e9a4d");
+        // iptables chain names are limited to 28 characters
+        assert!(name.len() <= 28);
+    }
+
+    #[test]
+    fn test_chain_rules_order() {
+        let rules = chain_rules(
+            &[(IpAddr::V4(Ipv4Addr::new(172, 18, 0, 5)), 16)],
+            &[IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))],
+            &[
+                (IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 32),
+                (IpAddr::V4(Ipv4Addr::new(172, 18, 9, 9)), 16),
+            ],
+        );
+        let joined: Vec<String> = rules.iter().map(|r| r.join(" ")).collect();
+        assert_eq!(
+            joined,
+            vec![
+                "-m conntrack --ctstate ESTABLISHED,RELATED -j RETURN",
+                "-d 172.18.0.0/16 -j RETURN",
+                "-d 10.0.0.2/32 -p udp --dport 53 -j RETURN",
+                "-d 10.0.0.2/32 -p tcp --dport 53 -j RETURN",
+                "-d 1.2.3.4/32 -j RETURN",
+                "-j REJECT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chain_rules_ipv6() {
+        let resolver: IpAddr = "2001:4860:4860::8888".parse().unwrap();
+        let rules = chain_rules(&[("fd00:1:2::5".parse().unwrap(), 64)], &[resolver], &[]);
+        let joined: Vec<String> = rules.iter().map(|r| r.join(" ")).collect();
+        assert_eq!(
+            joined,
+            vec![
+                "-m conntrack --ctstate ESTABLISHED,RELATED -j RETURN",
+                "-d fd00:1:2::/64 -j RETURN",
+                "-d 2001:4860:4860::8888/128 -p udp --dport 53 -j RETURN",
+                "-d 2001:4860:4860::8888/128 -p tcp --dport 53 -j RETURN",
+                "-j REJECT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nameservers() {
+        let conf = "# Generated\nnameserver 127.0.0.53\nnameserver 10.0.0.2\n\
+                    options edns0\nnameserver ::1\nnameserver 2001:db8::53\nsearch lan\n";
+        assert_eq!(
+            parse_nameservers(conf),
+            vec![
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)),
+                "2001:db8::53".parse::<IpAddr>().unwrap(),
+            ]
+        );
+        assert!(parse_nameservers("nameserver 127.0.0.11\n").is_empty());
+    }
+}
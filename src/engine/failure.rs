@@ -0,0 +1,218 @@
+//! Classification of deployment failures into known causes.
+//!
+//! Raw `git`/`docker` stderr rarely tells users what to do next. When a
+//! deployment fails, its error and the tail of its log are matched against
+//! common failure signatures; a match is stored on the deployment as a typed
+//! `error_code` with remediation text (`error_hint`), shown in the UI and
+//! included in failure notifications.
+
+use crate::DbPool;
+
+/// Deployment log lines searched for failure signatures
+const LOG_LINES: i64 = 100;
+
+/// A recognized cause of a failed deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The git provider rejected the clone credentials (or the repo is private)
+    CloneAuth,
+    /// The Dockerfile doesn't exist at the configured path
+    DockerfileNotFound,
+    /// The container started but nothing answered on the app's port
+    PortNotListening,
+    /// The build ran out of memory and was killed
+    BuildOutOfMemory,
+    /// The ACME CA refused to issue a certificate due to rate limits
+    AcmeRateLimited,
+}
+
+impl FailureKind {
+    /// Stable identifier stored in `deployments.error_code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            FailureKind::CloneAuth => "clone_auth_failed",
+            FailureKind::DockerfileNotFound => "dockerfile_not_found",
+            FailureKind::PortNotListening => "port_not_listening",
+            FailureKind::BuildOutOfMemory => "build_out_of_memory",
+            FailureKind::AcmeRateLimited => "acme_rate_limited",
+        }
+    }
+
+    /// What the user can do about it
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            FailureKind::CloneAuth => {
+                "The git provider rejected the credentials used to clone the repository. \
+                 Check that the repository URL is correct, and for private repositories \
+                 attach a git provider, GitHub App installation or deploy key with read access."
+            }
+            FailureKind::DockerfileNotFound => {
+                "No Dockerfile was found at the configured path. Check the app's Dockerfile \
+                 path and base directory (paths are relative to the repository root and are \
+                 case-sensitive), or switch the build type to Nixpacks or Railpack."
+            }
+            FailureKind::PortNotListening => {
+                "The container started but nothing answered on the app's port. Make the app \
+                 listen on 0.0.0.0 and the port in $PORT, or change the app's port to the one \
+                 it actually listens on."
+            }
+            FailureKind::BuildOutOfMemory => {
+                "The build ran out of memory and was killed. Raise the build memory limit, \
+                 free memory on the server or offload builds to a build server; for Node.js \
+                 builds, setting NODE_OPTIONS=--max-old-space-size can also help."
+            }
+            FailureKind::AcmeRateLimited => {
+                "The certificate authority rate-limited certificate requests for this domain. \
+                 Wait for the limit to reset (usually up to a week for Let's Encrypt) and avoid \
+                 repeated reissues; use the staging directory while testing."
+            }
+        }
+    }
+}
+
+/// Match failure text (error chain and/or log lines) against known signatures
+pub fn classify(text: &str) -> Option<FailureKind> {
+    let text = text.to_lowercase();
+    let matches = |patterns: &[&str]| patterns.iter().any(|p| text.contains(p));
+
+    if matches(&[
+        "ratelimited",
+        "too many certificates",
+        "too many failed authorizations",
+        "too many new orders",
+    ]) {
+        return Some(FailureKind::AcmeRateLimited);
+    }
+    if matches(&[
+        "authentication failed for",
+        "could not read username",
+        "could not read password",
+        "permission denied (publickey)",
+        "invalid username or password",
+        "terminal prompts disabled",
+        "repository not found",
+        "http basic: access denied",
+    ]) {
+        return Some(FailureKind::CloneAuth);
+    }
+    if matches(&[
+        "failed to read dockerfile",
+        "cannot locate specified dockerfile",
+        "dockerfile: no such file or directory",
+        "dockerfile not found",
+    ]) {
+        return Some(FailureKind::DockerfileNotFound);
+    }
+    if matches(&[
+        "exit code: 137",
+        "exit code 137",
+        "oomkilled",
+        "heap out of memory",
+        "cannot allocate memory",
+        "signal: killed",
+    ]) {
+        return Some(FailureKind::BuildOutOfMemory);
+    }
+    // Port signatures only mean something once the health check gave up
+    if text.contains("health check failed")
+        && matches(&[
+            "connection refused",
+            "error sending request",
+            "nothing is listening on a tcp port",
+            "but the container listens on",
+            "only on localhost",
+        ])
+    {
+        return Some(FailureKind::PortNotListening);
+    }
+    None
+}
+
+/// Classify a failed deployment from its error and the tail of its log, and
+/// store the result on the deployment. Returns the recognized cause, if any.
+pub async fn classify_deployment(
+    db: &DbPool,
+    deployment_id: &str,
+    error: &str,
+) -> Option<FailureKind> {
+    let logs: Vec<String> = sqlx::query_scalar(
+        "SELECT message FROM deployment_logs WHERE deployment_id = ? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(deployment_id)
+    .bind(LOG_LINES)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    let kind = classify(&format!("{}\n{}", error, logs.join("\n")))?;
+    if let Err(e) =
+        sqlx::query("UPDATE deployments SET error_code = ?, error_hint = ? WHERE id = ?")
+            .bind(kind.code())
+            .bind(kind.remediation())
+            .bind(deployment_id)
+            .execute(db)
+            .await
+    {
+        tracing::warn!(deployment_id = %deployment_id, "Failed to save error classification: {}", e);
+    }
+    Some(kind)
+}
+
+/// Failure message for notifications, with remediation text when recognized
+pub fn with_hint(error: &str, kind: Option<FailureKind>) -> String {
+    match kind {
+        Some(kind) => format!("{}\n\nHow to fix: {}", error, kind.remediation()),
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_known_failures() {
+        assert_eq!(
+            classify(
+                "Git clone failed: fatal: Authentication failed for 'https://github.com/a/b.git/'"
+            ),
+            Some(FailureKind::CloneAuth)
+        );
+        assert_eq!(
+            classify("Git clone with SSH failed: git@github.com: Permission denied (publickey)."),
+            Some(FailureKind::CloneAuth)
+        );
+        assert_eq!(
+            classify("Build failed: failed to solve: failed to read dockerfile: open Dockerfile: no such file or directory"),
+            Some(FailureKind::DockerfileNotFound)
+        );
+        assert_eq!(
+            classify(
+                "FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory"
+            ),
+            Some(FailureKind::BuildOutOfMemory)
+        );
+        assert_eq!(
+            classify(
+                "Health check failed after 10 attempts\nHealth check attempt 3: connection refused"
+            ),
+            Some(FailureKind::PortNotListening)
+        );
+        assert_eq!(
+            classify(
+                "urn:ietf:params:acme:error:rateLimited: too many certificates already issued"
+            ),
+            Some(FailureKind::AcmeRateLimited)
+        );
+    }
+
+    #[test]
+    fn test_classify_unknown_failure() {
+        assert_eq!(
+            classify("Build failed: npm ERR! missing script: build"),
+            None
+        );
+        // A refused connection without a failed health check is not a port problem
+        assert_eq!(classify("docker push failed: connection refused"), None);
+    }
+}
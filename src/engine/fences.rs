@@ -0,0 +1,132 @@
+//! Deployment concurrency fences.
+//!
+//! Apps can name fences (`deploy_fences`, e.g. "shared-db-migrations") for
+//! resources they share with other apps. The engine keeps one lock per fence
+//! name, and a deployment holds the locks of all its app's fences while it
+//! runs its pre-deploy commands and its Procfile release process, so apps
+//! sharing a fence never run those steps at the same time. Locks are taken in
+//! name order, so apps sharing several fences can't deadlock each other.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OwnedMutexGuard;
+
+use crate::db::App;
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// Locks of the fences deployments have used, by name. Cloning shares them.
+#[derive(Debug, Clone, Default)]
+pub struct DeployFences {
+    locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+/// Fences held by a deployment; released when dropped
+#[derive(Debug)]
+pub struct FenceGuards {
+    _guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl DeployFences {
+    fn lock_for(&self, name: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.entry(name.to_string()).or_default().clone()
+    }
+
+    /// Take the locks of `names` in name order, waiting for deployments that
+    /// hold any of them. `on_wait` is called with each fence that is busy.
+    pub async fn acquire(&self, names: &[String], mut on_wait: impl FnMut(&str)) -> FenceGuards {
+        let names: BTreeSet<&str> = names.iter().map(String::as_str).collect();
+        let mut guards = Vec::with_capacity(names.len());
+        for name in names {
+            let lock = self.lock_for(name);
+            let guard = match lock.clone().try_lock_owned() {
+                Ok(guard) => guard,
+                Err(_) => {
+                    on_wait(name);
+                    lock.lock_owned().await
+                }
+            };
+            guards.push(guard);
+        }
+        FenceGuards { _guards: guards }
+    }
+
+    /// Take the fences of `app` for a deployment step, logging to the
+    /// deployment when it has to wait. `None` when the app has no fences.
+    pub async fn enter(
+        &self,
+        db: &DbPool,
+        deployment_id: &str,
+        app: &App,
+        step: &str,
+    ) -> Option<FenceGuards> {
+        let names = app.get_deploy_fences();
+        if names.is_empty() {
+            return None;
+        }
+
+        let mut waited = Vec::new();
+        let guards = self
+            .acquire(&names, |name| waited.push(name.to_string()))
+            .await;
+        if !waited.is_empty() {
+            let _ = add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!(
+                    "Waited for deployment fence(s) {} before running {} step",
+                    waited.join(", "),
+                    step
+                ),
+            )
+            .await;
+        }
+        Some(guards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_shared_fence_serializes_holders() {
+        let fences = DeployFences::default();
+        let held = fences.acquire(&names(&["db", "cache"]), |_| {}).await;
+
+        let other = fences.clone();
+        let waiter = tokio::spawn(async move {
+            let mut waited = Vec::new();
+            let _guards = other
+                .acquire(&names(&["cache"]), |name| waited.push(name.to_string()))
+                .await;
+            waited
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        // Unrelated fences don't wait
+        let mut waited = false;
+        fences.acquire(&names(&["queue"]), |_| waited = true).await;
+        assert!(!waited);
+
+        drop(held);
+        assert_eq!(waiter.await.unwrap(), names(&["cache"]));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_names_are_taken_once() {
+        let fences = DeployFences::default();
+        let guards = fences.acquire(&names(&["db", "db"]), |_| {}).await;
+        assert_eq!(guards._guards.len(), 1);
+    }
+}
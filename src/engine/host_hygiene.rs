@@ -0,0 +1,391 @@
+//! Host hygiene: pending OS security updates and container engine support.
+//!
+//! Every 6 hours the host's package manager (apt, dnf or yum) is asked which
+//! updates are pending, and the Docker or Podman version is compared with the
+//! oldest release still maintained upstream. The latest report is included in
+//! `GET /api/system/health` and kept in `instance_settings`, so it survives a
+//! restart. Admins are emailed when a check finds a problem the previous report
+//! didn't have, rather than on every run.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio::time::interval;
+
+use crate::config::EmailConfig;
+use crate::notifications::SystemEmailService;
+use crate::runtime::ContainerRuntime;
+use crate::startup::CheckResult;
+use crate::DbPool;
+
+/// `instance_settings` key holding the JSON-encoded [`HostHygieneReport`]
+const SETTING_KEY: &str = "host_hygiene";
+
+/// How often the host is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Longest a package manager query may take
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Oldest Docker Engine major release still maintained upstream. Bump as
+/// releases reach end of life.
+const MIN_SUPPORTED_DOCKER_MAJOR: u32 = 28;
+
+/// Oldest Podman major release still maintained upstream
+const MIN_SUPPORTED_PODMAN_MAJOR: u32 = 5;
+
+/// Kind of a [`HygieneFinding`]
+pub const FINDING_SECURITY_UPDATES: &str = "security_updates";
+pub const FINDING_RUNTIME_EOL: &str = "runtime_eol";
+
+/// A problem found on the host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HygieneFinding {
+    /// `security_updates` or `runtime_eol`
+    pub kind: String,
+    pub message: String,
+}
+
+/// Result of one host hygiene check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostHygieneReport {
+    pub checked_at: String,
+    /// `apt`, `dnf` or `yum`; `None` when no supported package manager was found
+    pub package_manager: Option<String>,
+    /// Upgradable packages, when the package manager reports them
+    pub pending_updates: Option<u32>,
+    /// Upgradable packages that fix security issues
+    pub security_updates: Option<u32>,
+    /// `Docker` or `Podman`
+    pub runtime: String,
+    pub runtime_version: Option<String>,
+    /// Whether the runtime's major release is past its upstream end of life
+    pub runtime_eol: bool,
+    pub findings: Vec<HygieneFinding>,
+}
+
+impl HostHygieneReport {
+    /// The report as health check results
+    pub fn check_results(&self) -> Vec<CheckResult> {
+        let updates = match (self.security_updates, &self.package_manager) {
+            (Some(0), Some(manager)) => {
+                CheckResult::pass("security_updates", "No pending security updates")
+                    .with_details(format!("Package manager: {}", manager))
+            }
+            (Some(n), Some(manager)) => CheckResult::fail(
+                "security_updates",
+                format!(
+                    "{} pending security update{}",
+                    n,
+                    if n == 1 { "" } else { "s" }
+                ),
+                false,
+            )
+            .with_details(format!("Package manager: {}", manager)),
+            _ => CheckResult::pass("security_updates", "Security updates not checked")
+                .with_details("No supported package manager (apt, dnf or yum) found"),
+        };
+
+        let version = self.runtime_version.as_deref().unwrap_or("unknown");
+        let runtime = if self.runtime_eol {
+            CheckResult::fail(
+                "runtime_version",
+                format!("{} {} is past its end of life", self.runtime, version),
+                false,
+            )
+            .with_details("Upgrade the container engine to keep receiving security fixes")
+        } else {
+            CheckResult::pass(
+                "runtime_version",
+                format!("{} {} is supported", self.runtime, version),
+            )
+        };
+
+        vec![updates, runtime]
+    }
+}
+
+/// Major version of a version string such as `27.3.1` or `v5.2.0`
+fn major_version(version: &str) -> Option<u32> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Whether a container engine version is past its upstream end of life
+pub fn runtime_is_eol(runtime: &str, version: &str) -> bool {
+    let minimum = match runtime {
+        "Docker" => MIN_SUPPORTED_DOCKER_MAJOR,
+        "Podman" => MIN_SUPPORTED_PODMAN_MAJOR,
+        _ => return false,
+    };
+    major_version(version).is_some_and(|major| major < minimum)
+}
+
+/// Count the upgrades and security upgrades in `apt-get -s upgrade` output
+fn parse_apt_simulation(output: &str) -> (u32, u32) {
+    output
+        .lines()
+        .filter(|line| line.starts_with("Inst "))
+        .fold((0, 0), |(all, security), line| {
+            (all + 1, security + u32::from(line.contains("-security")))
+        })
+}
+
+/// Count the packages in `dnf updateinfo list --security` output
+fn parse_updateinfo(output: &str) -> u32 {
+    output
+        .lines()
+        .filter(|line| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with("Last metadata") && line.contains("/Sec.")
+        })
+        .count() as u32
+}
+
+async fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::time::timeout(
+        COMMAND_TIMEOUT,
+        Command::new(program).args(args).kill_on_drop(true).output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Pending updates reported by the host's package manager: the manager's
+/// name, upgradable packages (if reported) and security upgrades
+async fn pending_updates() -> Option<(String, Option<u32>, u32)> {
+    // Simulated upgrade against the cached package lists; needs no root
+    if let Some(output) = run("apt-get", &["-s", "-o", "Debug::NoLocking=1", "upgrade"]).await {
+        let (all, security) = parse_apt_simulation(&output);
+        return Some(("apt".to_string(), Some(all), security));
+    }
+    for manager in ["dnf", "yum"] {
+        if let Some(output) = run(manager, &["-q", "updateinfo", "list", "--security"]).await {
+            return Some((manager.to_string(), None, parse_updateinfo(&output)));
+        }
+    }
+    None
+}
+
+/// Check the host once
+pub async fn check_host(runtime: &dyn ContainerRuntime) -> HostHygieneReport {
+    let mut findings = Vec::new();
+
+    let updates = pending_updates().await;
+    let security_updates = updates.as_ref().map(|(_, _, security)| *security);
+    if let Some(n) = security_updates.filter(|n| *n > 0) {
+        findings.push(HygieneFinding {
+            kind: FINDING_SECURITY_UPDATES.to_string(),
+            message: format!(
+                "{} pending security update{} on the host",
+                n,
+                if n == 1 { "" } else { "s" }
+            ),
+        });
+    }
+
+    let runtime_version = runtime.engine_version().await.ok().flatten();
+    let runtime_eol = runtime_version
+        .as_deref()
+        .is_some_and(|v| runtime_is_eol(runtime.name(), v));
+    if runtime_eol {
+        findings.push(HygieneFinding {
+            kind: FINDING_RUNTIME_EOL.to_string(),
+            message: format!(
+                "{} {} is past its end of life and no longer receives security fixes",
+                runtime.name(),
+                runtime_version.as_deref().unwrap_or_default()
+            ),
+        });
+    }
+
+    HostHygieneReport {
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        package_manager: updates.as_ref().map(|(manager, _, _)| manager.clone()),
+        pending_updates: updates.and_then(|(_, all, _)| all),
+        security_updates,
+        runtime: runtime.name().to_string(),
+        runtime_version,
+        runtime_eol,
+        findings,
+    }
+}
+
+/// Findings in `current` whose kind `previous` didn't report
+pub fn new_findings<'a>(
+    previous: Option<&HostHygieneReport>,
+    current: &'a HostHygieneReport,
+) -> Vec<&'a HygieneFinding> {
+    current
+        .findings
+        .iter()
+        .filter(|finding| {
+            !previous.is_some_and(|p| p.findings.iter().any(|f| f.kind == finding.kind))
+        })
+        .collect()
+}
+
+/// Latest host hygiene report, shared by the API and the checker
+#[derive(Debug, Clone, Default)]
+pub struct HostHygiene {
+    report: Arc<parking_lot::RwLock<Option<HostHygieneReport>>>,
+}
+
+impl HostHygiene {
+    /// Load the persisted report
+    pub async fn load(db: &DbPool) -> Result<Self> {
+        let value: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT value FROM instance_settings WHERE key = ?")
+                .bind(SETTING_KEY)
+                .fetch_optional(db)
+                .await?;
+        let report = value
+            .and_then(|(v,)| v)
+            .and_then(|v| serde_json::from_str(&v).ok());
+        let hygiene = Self::default();
+        *hygiene.report.write() = report;
+        Ok(hygiene)
+    }
+
+    /// The latest report, `None` before the first check
+    pub fn current(&self) -> Option<HostHygieneReport> {
+        self.report.read().clone()
+    }
+
+    async fn save(&self, db: &DbPool, report: HostHygieneReport) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO instance_settings (key, value, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(SETTING_KEY)
+        .bind(serde_json::to_string(&report)?)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(db)
+        .await?;
+        *self.report.write() = Some(report);
+        Ok(())
+    }
+
+    /// Check the host, store the report and email admins about new findings
+    async fn run_check(
+        &self,
+        db: &DbPool,
+        runtime: &dyn ContainerRuntime,
+        email: &SystemEmailService,
+    ) -> Result<()> {
+        let report = check_host(runtime).await;
+        let previous = self.current();
+        let new: Vec<String> = new_findings(previous.as_ref(), &report)
+            .into_iter()
+            .map(|f| f.message.clone())
+            .collect();
+        self.save(db, report).await?;
+
+        if new.is_empty() {
+            return Ok(());
+        }
+        tracing::warn!(findings = ?new, "Host hygiene check found problems");
+        let admins: Vec<String> =
+            sqlx::query_scalar("SELECT email FROM users WHERE role = 'admin' AND email != ''")
+                .fetch_all(db)
+                .await?;
+        for address in admins {
+            if let Err(e) = email.send_host_hygiene_email(&address, &new).await {
+                tracing::warn!(error = %e, "Failed to email host hygiene findings");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawn the background host hygiene checker (runs every 6 hours)
+pub fn spawn_host_hygiene_checker(
+    hygiene: HostHygiene,
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    email_config: EmailConfig,
+) {
+    tracing::info!("Starting host hygiene checker (6h interval)");
+
+    tokio::spawn(async move {
+        let email = SystemEmailService::with_db(email_config, db.clone());
+        let mut tick = interval(CHECK_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("host_hygiene", async {
+                if let Err(e) = hygiene.run_check(&db, runtime.as_ref(), &email).await {
+                    tracing::warn!(error = %e, "Host hygiene check failed");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_is_eol() {
+        assert!(runtime_is_eol("Docker", "20.10.24"));
+        assert!(!runtime_is_eol("Docker", "28.0.4"));
+        assert!(runtime_is_eol("Podman", "4.9.3"));
+        assert!(!runtime_is_eol("Podman", "v5.2.0"));
+        assert!(!runtime_is_eol("None", "1.0"));
+        assert!(!runtime_is_eol("Docker", "unknown"));
+    }
+
+    #[test]
+    fn test_parse_apt_simulation() {
+        let output = "Reading package lists...\n\
+            Inst openssl [3.0.2-0ubuntu1.15] (3.0.2-0ubuntu1.18 Ubuntu:22.04/jammy-updates, Ubuntu:22.04/jammy-security [amd64])\n\
+            Inst tzdata [2024a-0ubuntu0.22.04] (2024b-0ubuntu0.22.04 Ubuntu:22.04/jammy-updates [all])\n\
+            Conf openssl (3.0.2-0ubuntu1.18 Ubuntu:22.04/jammy-updates, Ubuntu:22.04/jammy-security [amd64])\n";
+        assert_eq!(parse_apt_simulation(output), (2, 1));
+    }
+
+    #[test]
+    fn test_new_findings() {
+        let report = |kinds: &[&str]| HostHygieneReport {
+            checked_at: String::new(),
+            package_manager: Some("apt".to_string()),
+            pending_updates: None,
+            security_updates: None,
+            runtime: "Docker".to_string(),
+            runtime_version: None,
+            runtime_eol: false,
+            findings: kinds
+                .iter()
+                .map(|kind| HygieneFinding {
+                    kind: kind.to_string(),
+                    message: String::new(),
+                })
+                .collect(),
+        };
+        let current = report(&[FINDING_SECURITY_UPDATES, FINDING_RUNTIME_EOL]);
+        assert_eq!(new_findings(None, &current).len(), 2);
+        let previous = report(&[FINDING_SECURITY_UPDATES]);
+        let new = new_findings(Some(&previous), &current);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].kind, FINDING_RUNTIME_EOL);
+    }
+}
@@ -0,0 +1,354 @@
+//! Host maintenance: cordon, drain and uncordon.
+//!
+//! Cordoning pauses deployments on this host: the deployment engine cancels
+//! every job it receives while the host is cordoned. Draining cordons the
+//! host and stops every running app, each one before the apps it depends on,
+//! parking their domains on a maintenance page. Uncordoning resumes
+//! deployments and starts the drained apps again, dependencies first.
+//!
+//! The state lives in `instance_settings`, so a host rebooted for a kernel
+//! upgrade comes back cordoned, with its maintenance pages in place.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{App, WaitTarget};
+use crate::proxy::RouteTable;
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+use super::power_schedule::{start_app, stop_app};
+
+/// `instance_settings` key holding the JSON-encoded [`CordonState`]
+const SETTING_KEY: &str = "host_cordon";
+
+/// Maintenance page shown for drained apps unless the drain sets another
+pub const DEFAULT_DRAIN_MESSAGE: &str = "Down for scheduled maintenance. We'll be back shortly.";
+
+/// Why deployments are refused while the host is cordoned
+pub const CORDONED_MESSAGE: &str = "Host is cordoned for maintenance; deployments are paused";
+
+/// A cordoned host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CordonState {
+    pub cordoned_at: String,
+    /// ID of the user who cordoned the host
+    #[serde(default)]
+    pub cordoned_by: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// When the last drain finished
+    #[serde(default)]
+    pub drained_at: Option<String>,
+    /// Maintenance page message of drained apps
+    #[serde(default)]
+    pub drain_message: Option<String>,
+    /// Apps stopped by drains, in the order they were stopped
+    #[serde(default)]
+    pub drained_app_ids: Vec<String>,
+}
+
+/// What a drain or uncordon did to one app
+#[derive(Debug, Clone, Serialize)]
+pub struct AppOutcome {
+    pub app_id: String,
+    pub app_name: String,
+    /// Why the app could not be stopped or started
+    pub error: Option<String>,
+}
+
+/// Cordon switch shared by the API and the deployment engine
+#[derive(Debug, Clone, Default)]
+pub struct HostCordon {
+    state: Arc<parking_lot::RwLock<Option<CordonState>>>,
+    /// Serializes cordon changes, drains and uncordons
+    op: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl HostCordon {
+    /// Load the persisted cordon state
+    pub async fn load(db: &DbPool) -> Result<Self> {
+        let value: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT value FROM instance_settings WHERE key = ?")
+                .bind(SETTING_KEY)
+                .fetch_optional(db)
+                .await?;
+        let state = value
+            .and_then(|(v,)| v)
+            .and_then(|v| serde_json::from_str(&v).ok());
+        let cordon = Self::default();
+        *cordon.state.write() = state;
+        Ok(cordon)
+    }
+
+    /// The current state, `None` when the host isn't cordoned
+    pub fn current(&self) -> Option<CordonState> {
+        self.state.read().clone()
+    }
+
+    pub fn is_cordoned(&self) -> bool {
+        self.state.read().is_some()
+    }
+
+    async fn save(&self, db: &DbPool, state: Option<CordonState>) -> Result<()> {
+        match &state {
+            Some(s) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO instance_settings (key, value, updated_at)
+                    VALUES (?, ?, ?)
+                    ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+                    "#,
+                )
+                .bind(SETTING_KEY)
+                .bind(serde_json::to_string(s)?)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .execute(db)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM instance_settings WHERE key = ?")
+                    .bind(SETTING_KEY)
+                    .execute(db)
+                    .await?;
+            }
+        }
+        *self.state.write() = state;
+        Ok(())
+    }
+
+    /// Cordon the host, keeping the existing state if it already is
+    async fn cordon_locked(
+        &self,
+        db: &DbPool,
+        user_id: Option<&str>,
+        reason: Option<String>,
+    ) -> Result<CordonState> {
+        if let Some(state) = self.current() {
+            return Ok(state);
+        }
+        let state = CordonState {
+            cordoned_at: chrono::Utc::now().to_rfc3339(),
+            cordoned_by: user_id.map(str::to_string),
+            reason,
+            drained_at: None,
+            drain_message: None,
+            drained_app_ids: vec![],
+        };
+        self.save(db, Some(state.clone())).await?;
+        tracing::info!("Host cordoned; deployments are paused");
+        Ok(state)
+    }
+
+    /// Stop accepting deployments
+    pub async fn cordon(
+        &self,
+        db: &DbPool,
+        user_id: Option<&str>,
+        reason: Option<String>,
+    ) -> Result<CordonState> {
+        let _op = self.op.lock().await;
+        self.cordon_locked(db, user_id, reason).await
+    }
+
+    /// Cordon the host and stop every running app, dependents before their
+    /// dependencies, parking their domains on a page showing `message`
+    pub async fn drain(
+        &self,
+        db: &DbPool,
+        runtime: &Arc<dyn ContainerRuntime>,
+        routes: &Arc<ArcSwap<RouteTable>>,
+        user_id: Option<&str>,
+        message: Option<String>,
+    ) -> Result<Vec<AppOutcome>> {
+        let _op = self.op.lock().await;
+        let mut state = self.cordon_locked(db, user_id, None).await?;
+        let message = message
+            .filter(|m| !m.trim().is_empty())
+            .or_else(|| state.drain_message.clone())
+            .unwrap_or_else(|| DEFAULT_DRAIN_MESSAGE.to_string());
+        state.drain_message = Some(message.clone());
+
+        let apps: Vec<App> = sqlx::query_as(
+            "SELECT * FROM apps WHERE EXISTS \
+             (SELECT 1 FROM deployments d WHERE d.app_id = apps.id AND d.status = 'running') \
+             ORDER BY name",
+        )
+        .fetch_all(db)
+        .await?;
+        tracing::info!(apps = apps.len(), "Draining host");
+
+        let mut outcomes = Vec::with_capacity(apps.len());
+        for i in stop_order(&app_dependencies(&apps)) {
+            let app = &apps[i];
+            let result = stop_app(
+                db,
+                runtime,
+                routes,
+                app,
+                &message,
+                "Stopped for host maintenance",
+            )
+            .await;
+            if result.is_ok() && !state.drained_app_ids.contains(&app.id) {
+                state.drained_app_ids.push(app.id.clone());
+                // Saved as it goes so an interrupted drain is still undone
+                self.save(db, Some(state.clone())).await?;
+            }
+            if let Err(ref e) = result {
+                tracing::error!(app = %app.name, error = %e, "Failed to drain app");
+            }
+            outcomes.push(AppOutcome {
+                app_id: app.id.clone(),
+                app_name: app.name.clone(),
+                error: result.err().map(|e| format!("{:#}", e)),
+            });
+        }
+
+        state.drained_at = Some(chrono::Utc::now().to_rfc3339());
+        self.save(db, Some(state)).await?;
+        Ok(outcomes)
+    }
+
+    /// Resume deployments and start the drained apps again, dependencies first
+    pub async fn uncordon(
+        &self,
+        db: &DbPool,
+        runtime: &Arc<dyn ContainerRuntime>,
+        routes: &Arc<ArcSwap<RouteTable>>,
+    ) -> Result<Vec<AppOutcome>> {
+        let _op = self.op.lock().await;
+        let Some(state) = self.current() else {
+            return Ok(vec![]);
+        };
+        self.save(db, None).await?;
+        tracing::info!("Host uncordoned; deployments resumed");
+
+        let mut outcomes = Vec::with_capacity(state.drained_app_ids.len());
+        for app_id in state.drained_app_ids.iter().rev() {
+            let app: Option<App> = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+                .bind(app_id)
+                .fetch_optional(db)
+                .await?;
+            let Some(app) = app else { continue };
+            let result =
+                start_app(db, runtime, routes, &app, "Started after host maintenance").await;
+            if let Err(ref e) = result {
+                tracing::error!(app = %app.name, error = %e, "Failed to start drained app");
+            }
+            outcomes.push(AppOutcome {
+                app_id: app.id.clone(),
+                app_name: app.name.clone(),
+                error: result.err().map(|e| format!("{:#}", e)),
+            });
+        }
+        Ok(outcomes)
+    }
+
+    /// Park the domains of drained apps again (the route table starts empty
+    /// after a restart)
+    pub async fn restore_parked_routes(&self, db: &DbPool, routes: &Arc<ArcSwap<RouteTable>>) {
+        let Some(state) = self.current() else {
+            return;
+        };
+        let message = state
+            .drain_message
+            .as_deref()
+            .unwrap_or(DEFAULT_DRAIN_MESSAGE);
+        for app_id in &state.drained_app_ids {
+            let app: Option<App> = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+                .bind(app_id)
+                .fetch_optional(db)
+                .await
+                .unwrap_or_default();
+            let Some(app) = app else { continue };
+            for domain in app.get_all_domain_names() {
+                // Never shadow a live route (e.g. the app was started by hand)
+                if routes.load().get_backend(&domain).is_none() {
+                    routes.load().park(&domain, message);
+                }
+            }
+        }
+    }
+}
+
+/// For each app, the indexes of the other apps it depends on: those its
+/// HTTP wait conditions point at by internal hostname or domain
+fn app_dependencies(apps: &[App]) -> Vec<Vec<usize>> {
+    apps.iter()
+        .enumerate()
+        .map(|(i, app)| {
+            let mut deps: Vec<usize> = app
+                .get_deploy_wait_conditions()
+                .iter()
+                .filter_map(|condition| match &condition.target {
+                    WaitTarget::Http { url } => reqwest::Url::parse(url)
+                        .ok()?
+                        .host_str()
+                        .map(str::to_string),
+                    WaitTarget::Database { .. } => None,
+                })
+                .filter_map(|host| {
+                    apps.iter().position(|other| {
+                        other.internal_hostname() == host
+                            || other.get_all_domain_names().contains(&host)
+                    })
+                })
+                .filter(|&j| j != i)
+                .collect();
+            deps.sort_unstable();
+            deps.dedup();
+            deps
+        })
+        .collect()
+}
+
+/// Order in which apps are stopped: each app before the apps it depends on.
+/// `dependencies[i]` lists the apps app `i` depends on. Apps in a dependency
+/// cycle come last, in their original order.
+fn stop_order(dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let n = dependencies.len();
+    let mut dependents = vec![0usize; n];
+    for deps in dependencies {
+        for &d in deps {
+            dependents[d] += 1;
+        }
+    }
+
+    let mut done = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    while let Some(next) = (0..n).find(|&i| !done[i] && dependents[i] == 0) {
+        done[next] = true;
+        order.push(next);
+        for &d in &dependencies[next] {
+            dependents[d] -= 1;
+        }
+    }
+    order.extend((0..n).filter(|&i| !done[i]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_order_stops_dependents_first() {
+        // 0 (web) -> 1 (api) -> 2 (auth); 3 is independent
+        let order = stop_order(&[vec![1], vec![2], vec![], vec![]]);
+        assert_eq!(order, vec![0, 1, 2, 3]);
+
+        // Listed dependencies-first, still stopped dependents-first
+        let order = stop_order(&[vec![], vec![0], vec![1]]);
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_stop_order_keeps_cycles() {
+        let order = stop_order(&[vec![1], vec![0], vec![]]);
+        assert_eq!(order, vec![2, 0, 1]);
+    }
+}
@@ -0,0 +1,252 @@
+//! Load of the deployment engine itself.
+//!
+//! Live counters of deployments waiting for a build slot and holding one are
+//! shared between the engine and the API. Together with build durations and
+//! per-stage failures read from the database they form the engine status
+//! (`GET /api/system/engine` and the `rivetr_engine_*` gauges), which tells
+//! whether the engine or the apps are the bottleneck.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::DbPool;
+
+/// Pipeline stages failure rates are reported for, in pipeline order
+pub const PIPELINE_STAGES: &[&str] = &["cloning", "building", "starting", "checking"];
+
+/// Hours of deployment history the build duration and failure rates cover
+pub const STATS_WINDOW_HOURS: i64 = 24;
+
+/// Deployments waiting for a build slot and holding one. Shared between the
+/// engine (which updates them) and the API (which reports them).
+#[derive(Debug, Default)]
+pub struct EngineCounters {
+    queued: AtomicUsize,
+    active: AtomicUsize,
+}
+
+pub type SharedEngineCounters = Arc<EngineCounters>;
+
+impl EngineCounters {
+    /// Deployments waiting for a build slot
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// Deployments holding a build slot
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Count a deployment as queued until the guard is dropped
+    pub(crate) fn track_queued(self: &Arc<Self>) -> CounterGuard {
+        CounterGuard::new(self.clone(), |c| &c.queued)
+    }
+
+    /// Count a deployment as active until the guard is dropped
+    pub(crate) fn track_active(self: &Arc<Self>) -> CounterGuard {
+        CounterGuard::new(self.clone(), |c| &c.active)
+    }
+}
+
+/// Decrements the counter it incremented when dropped
+pub(crate) struct CounterGuard {
+    counters: SharedEngineCounters,
+    counter: fn(&EngineCounters) -> &AtomicUsize,
+}
+
+impl CounterGuard {
+    fn new(counters: SharedEngineCounters, counter: fn(&EngineCounters) -> &AtomicUsize) -> Self {
+        counter(&counters).fetch_add(1, Ordering::Relaxed);
+        Self { counters, counter }
+    }
+}
+
+impl Drop for CounterGuard {
+    fn drop(&mut self) {
+        (self.counter)(&self.counters).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// How often deployments that reached a pipeline stage failed in it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageFailureRate {
+    pub stage: String,
+    /// Deployments that reached the stage
+    pub entered: i64,
+    /// Deployments that failed while in the stage
+    pub failed: i64,
+    /// `failed / entered` as a percentage
+    pub failure_rate_percent: f64,
+}
+
+/// Status of the deployment engine
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatus {
+    /// Deployments waiting for a build slot
+    pub queue_depth: usize,
+    /// Jobs sent to the engine it hasn't picked up yet
+    pub channel_backlog: usize,
+    /// Deployments holding a build slot
+    pub active_builds: usize,
+    /// Build slots (`max_concurrent_deployments`)
+    pub concurrency_limit: usize,
+    /// Share of build slots in use, as a percentage
+    pub saturation_percent: f64,
+    /// Hours of history `avg_build_duration_secs` and `stage_failures` cover
+    pub window_hours: i64,
+    /// Average time from taking a build slot to going live
+    pub avg_build_duration_secs: Option<f64>,
+    /// Deployments that went live in the window
+    pub builds_completed: i64,
+    pub stage_failures: Vec<StageFailureRate>,
+}
+
+impl EngineStatus {
+    /// Combine the live counters with the recent deployment history
+    pub async fn collect(
+        db: &DbPool,
+        counters: &EngineCounters,
+        concurrency_limit: usize,
+        channel_backlog: usize,
+    ) -> Self {
+        let since = (chrono::Utc::now() - chrono::Duration::hours(STATS_WINDOW_HOURS)).to_rfc3339();
+
+        // started_at is reset when the deployment takes a build slot, and
+        // built_at is stamped when it goes live
+        let (avg_build_duration_secs, builds_completed): (Option<f64>, i64) = sqlx::query_as(
+            "SELECT AVG((julianday(built_at) - julianday(started_at)) * 86400), COUNT(*) \
+             FROM deployments WHERE built_at IS NOT NULL AND built_at >= ?",
+        )
+        .bind(&since)
+        .fetch_one(db)
+        .await
+        .unwrap_or((None, 0));
+
+        let events: Vec<(String, String)> = sqlx::query_as(
+            "SELECT deployment_id, status FROM deployment_events WHERE created_at >= ? \
+             ORDER BY deployment_id, id",
+        )
+        .bind(&since)
+        .fetch_all(db)
+        .await
+        .unwrap_or_default();
+
+        let active_builds = counters.active();
+        let saturation_percent = if concurrency_limit > 0 {
+            active_builds as f64 / concurrency_limit as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            queue_depth: counters.queued(),
+            channel_backlog,
+            active_builds,
+            concurrency_limit,
+            saturation_percent,
+            window_hours: STATS_WINDOW_HOURS,
+            avg_build_duration_secs,
+            builds_completed,
+            stage_failures: stage_failure_rates(&events),
+        }
+    }
+}
+
+/// Failure rate of each pipeline stage from `(deployment_id, status)`
+/// transitions ordered by deployment and time. A failure counts against the
+/// stage the deployment was in before it failed.
+pub fn stage_failure_rates(events: &[(String, String)]) -> Vec<StageFailureRate> {
+    let mut entered = [0i64; PIPELINE_STAGES.len()];
+    let mut failed = [0i64; PIPELINE_STAGES.len()];
+
+    let mut deployment: Option<&str> = None;
+    let mut current: Option<usize> = None;
+    let mut reached = [false; PIPELINE_STAGES.len()];
+    for (deployment_id, status) in events {
+        if deployment != Some(deployment_id.as_str()) {
+            deployment = Some(deployment_id);
+            current = None;
+            reached = [false; PIPELINE_STAGES.len()];
+        }
+        if let Some(stage) = PIPELINE_STAGES.iter().position(|s| s == status) {
+            // Count each stage once per deployment
+            if !reached[stage] {
+                reached[stage] = true;
+                entered[stage] += 1;
+            }
+            current = Some(stage);
+        } else if status == "failed" {
+            if let Some(stage) = current.take() {
+                failed[stage] += 1;
+            }
+        }
+    }
+
+    PIPELINE_STAGES
+        .iter()
+        .enumerate()
+        .map(|(i, stage)| StageFailureRate {
+            stage: stage.to_string(),
+            entered: entered[i],
+            failed: failed[i],
+            failure_rate_percent: if entered[i] > 0 {
+                failed[i] as f64 / entered[i] as f64 * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(d, s)| (d.to_string(), s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stage_failure_rates() {
+        let rates = stage_failure_rates(&events(&[
+            ("a", "cloning"),
+            ("a", "building"),
+            ("a", "failed"),
+            ("b", "cloning"),
+            ("b", "building"),
+            ("b", "starting"),
+            ("b", "checking"),
+            ("b", "running"),
+            ("c", "building"),
+            ("c", "starting"),
+            ("c", "checking"),
+            ("c", "failed"),
+        ]));
+
+        let building = &rates[1];
+        assert_eq!(building.stage, "building");
+        assert_eq!((building.entered, building.failed), (3, 1));
+        assert!((building.failure_rate_percent - 100.0 / 3.0).abs() < 1e-9);
+
+        let checking = &rates[3];
+        assert_eq!((checking.entered, checking.failed), (2, 1));
+        assert_eq!(checking.failure_rate_percent, 50.0);
+
+        assert_eq!((rates[0].entered, rates[0].failed), (2, 0));
+    }
+
+    #[test]
+    fn test_counter_guards() {
+        let counters: SharedEngineCounters = Arc::default();
+        let queued = counters.track_queued();
+        assert_eq!((counters.queued(), counters.active()), (1, 0));
+        drop(queued);
+        let _active = counters.track_active();
+        assert_eq!((counters.queued(), counters.active()), (0, 1));
+    }
+}
@@ -1,22 +1,53 @@
 mod alert_evaluator;
+pub mod bandwidth;
+pub mod build_cache;
 pub mod build_detect;
+pub mod build_sandbox;
 mod cleanup;
+pub mod command_runs;
+pub mod config_snapshot;
 mod container_monitor;
 mod cost_calculator;
 pub mod database_backups;
 pub mod database_config;
+pub mod database_seed;
+pub mod deploy_phases;
+pub mod diagnostics;
 mod disk_monitor;
+pub mod dockerfile_gen;
+pub mod egress;
+pub mod failure;
+pub mod fences;
+pub mod host_hygiene;
+pub mod host_maintenance;
+pub mod load;
 pub mod nixpacks;
 pub mod pack_builder;
 mod pipeline;
+pub mod pipeline_file;
+pub mod port_check;
+pub mod power_schedule;
 pub mod preview;
+pub mod preview_database;
+pub mod preview_expiry;
+pub mod prewarm;
+pub mod processes;
+pub mod prometheus_sd;
 pub mod railpack;
 pub mod remote;
 mod resource_metrics_collector;
+pub mod scale_to_zero;
+pub mod scaling;
+pub mod scan;
+pub mod scheduled_tasks;
 pub mod scheduler;
+pub mod service_routes;
+pub mod smoke_tests;
 pub mod static_builder;
 mod stats_collector;
+pub mod test_stage;
 pub mod updater;
+pub mod wait_conditions;
 pub mod zip_extract;
 
 pub use alert_evaluator::*;
@@ -38,11 +69,16 @@ use crate::api::metrics::{
     increment_deployments_total, observe_deployment_duration, record_deployment_failed,
     record_deployment_success,
 };
-use crate::config::{AuthConfig, RuntimeConfig};
+use crate::config::{
+    AuthConfig, BuildCacheConfig, BuildSandboxConfig, RegistryConfig, RuntimeConfig, ScanConfig,
+};
 use crate::crypto;
-use crate::db::{App, NotificationEventType};
-use crate::notifications::{NotificationPayload, NotificationService};
-use crate::proxy::{Backend, BasicAuthConfig, RouteTable};
+use crate::db::{
+    App, AppAccessRule, AppBasicAuthPath, AppBotSettings, AppCorsSettings, AppForwardAuth,
+    AppPrewarmSettings, AppWafSettings, Deployment, DeploymentEvent, NotificationEventType,
+};
+use crate::notifications::{outbox, CommitMetadata, NotificationPayload};
+use crate::proxy::{Backend, BasicAuthConfig, FallbackTarget, PoolMember, RouteBatch, RouteTable};
 use crate::runtime::ContainerRuntime;
 use crate::DbPool;
 use arc_swap::ArcSwap;
@@ -54,11 +90,39 @@ const KEY_LENGTH: usize = 32;
 
 pub type DeploymentJob = (String, App); // (deployment_id, app)
 
+/// Cancellation tokens of in-flight deployments, keyed by deployment ID. Shared
+/// between the engine (which registers them) and the API (which cancels them).
+pub type CancelTokens = Arc<dashmap::DashMap<String, tokio_util::sync::CancellationToken>>;
+
+/// Removes a deployment's cancellation token once its task ends
+struct CancelTokenGuard {
+    tokens: CancelTokens,
+    deployment_id: String,
+}
+
+impl Drop for CancelTokenGuard {
+    fn drop(&mut self) {
+        self.tokens.remove(&self.deployment_id);
+    }
+}
+
 /// Build resource limits configuration
 #[derive(Debug, Clone)]
 pub struct BuildLimits {
     pub cpu_limit: Option<String>,
     pub memory_limit: Option<String>,
+    /// Restrictions applied to sandboxed builds
+    pub sandbox: BuildSandboxConfig,
+    /// Persistent per-app build cache settings
+    pub cache: BuildCacheConfig,
+    /// Directory of the per-app build caches; `None` builds without them
+    pub cache_root: Option<std::path::PathBuf>,
+    /// Docker host to run builds on instead of the local daemon
+    pub build_host: Option<String>,
+    /// Global registry built images are pushed to
+    pub registry: RegistryConfig,
+    /// Vulnerability scanning of deployment images
+    pub scan: ScanConfig,
 }
 
 impl BuildLimits {
@@ -66,8 +130,39 @@ impl BuildLimits {
         Self {
             cpu_limit: Some(config.build_cpu_limit.clone()),
             memory_limit: Some(config.build_memory_limit.clone()),
+            sandbox: BuildSandboxConfig::default(),
+            cache: BuildCacheConfig::default(),
+            cache_root: None,
+            build_host: config.build_host.clone().filter(|host| !host.is_empty()),
+            registry: RegistryConfig::default(),
+            scan: ScanConfig::default(),
         }
     }
+
+    /// Push built images to `registry` for apps without their own push registry
+    pub fn with_registry(mut self, registry: RegistryConfig) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Scan deployment images as `scan` configures
+    pub fn with_scan(mut self, scan: ScanConfig) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    /// Use `sandbox` for builds of apps that require sandboxing
+    pub fn with_sandbox(mut self, sandbox: BuildSandboxConfig) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Keep per-app build caches under `data_dir`
+    pub fn with_build_cache(mut self, cache: BuildCacheConfig, data_dir: &std::path::Path) -> Self {
+        self.cache_root = Some(build_cache::cache_root(data_dir));
+        self.cache = cache;
+        self
+    }
 }
 
 pub struct DeploymentEngine {
@@ -79,6 +174,17 @@ pub struct DeploymentEngine {
     encryption_key: Option<[u8; KEY_LENGTH]>,
     /// Caps how many deployments build concurrently; extra deploys queue on it.
     deploy_semaphore: Arc<tokio::sync::Semaphore>,
+    /// How long a replaced container is kept running for instant rollback
+    keep_previous: std::time::Duration,
+    cancel_tokens: CancelTokens,
+    counters: load::SharedEngineCounters,
+    /// Serializes the pre-deploy and release steps of apps sharing a fence
+    fences: fences::DeployFences,
+    /// Where diagnostics bundles of failed deployments are kept; without one
+    /// they stay in the database
+    storage: Option<Arc<dyn crate::storage::Storage>>,
+    /// Cancels deployments while the host is cordoned for maintenance
+    cordon: host_maintenance::HostCordon,
 }
 
 impl DeploymentEngine {
@@ -105,9 +211,47 @@ impl DeploymentEngine {
             build_limits,
             encryption_key,
             deploy_semaphore,
+            keep_previous: std::time::Duration::ZERO,
+            cancel_tokens: CancelTokens::default(),
+            counters: load::SharedEngineCounters::default(),
+            fences: fences::DeployFences::default(),
+            storage: None,
+            cordon: host_maintenance::HostCordon::default(),
         }
     }
 
+    /// Keep the replaced container running for `keep` after each switchover so
+    /// a rollback to it only swaps routes (config: runtime.keep_previous_minutes)
+    pub fn with_keep_previous(mut self, keep: std::time::Duration) -> Self {
+        self.keep_previous = keep;
+        self
+    }
+
+    /// Share the cancellation token map with the API so cancelling a
+    /// deployment aborts its pipeline
+    pub fn with_cancel_tokens(mut self, tokens: CancelTokens) -> Self {
+        self.cancel_tokens = tokens;
+        self
+    }
+
+    /// Share the queued/active build counters with the API's engine status
+    pub fn with_counters(mut self, counters: load::SharedEngineCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Store diagnostics bundles in the configured storage backend
+    pub fn with_storage(mut self, storage: Arc<dyn crate::storage::Storage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Share the host cordon with the API so cordoning pauses deployments
+    pub fn with_cordon(mut self, cordon: host_maintenance::HostCordon) -> Self {
+        self.cordon = cordon;
+        self
+    }
+
     pub async fn run(mut self) {
         tracing::info!("Deployment engine started");
 
@@ -124,15 +268,64 @@ impl DeploymentEngine {
             let build_limits = self.build_limits.clone();
             let encryption_key = self.encryption_key;
             let deploy_semaphore = self.deploy_semaphore.clone();
+            let keep_previous = self.keep_previous;
+            let cancel_tokens = self.cancel_tokens.clone();
+            let counters = self.counters.clone();
+            let fences = self.fences.clone();
+            let storage = self.storage.clone();
+            let cordon = self.cordon.clone();
+
+            // Registered before the task waits for a build slot so queued
+            // deployments can be cancelled too
+            let cancel_token = tokio_util::sync::CancellationToken::new();
+            cancel_tokens.insert(deployment_id.clone(), cancel_token.clone());
+            let cancel_guard = CancelTokenGuard {
+                tokens: cancel_tokens.clone(),
+                deployment_id: deployment_id.clone(),
+            };
 
             tokio::spawn(async move {
+                let _cancel_guard = cancel_guard;
                 // Gate concurrent deployments. The task is spawned immediately so
                 // the channel keeps draining, but the heavy build work waits here
                 // until a slot is free (config: runtime.max_concurrent_deployments).
+                let queued = counters.track_queued();
                 let _permit = match deploy_semaphore.acquire_owned().await {
                     Ok(p) => p,
                     Err(_) => return, // semaphore closed — engine shutting down
                 };
+                drop(queued);
+
+                // Checked once a slot is free so deployments queued before the
+                // host was cordoned don't start either
+                if cordon.is_cordoned() {
+                    let now_ts = chrono::Utc::now().to_rfc3339();
+                    let _ = sqlx::query(
+                        "UPDATE deployments
+                            SET status = 'cancelled', cancelled_at = ?, finished_at = ?, error_message = ?
+                          WHERE id = ? AND status != 'cancelled'",
+                    )
+                    .bind(&now_ts)
+                    .bind(&now_ts)
+                    .bind(host_maintenance::CORDONED_MESSAGE)
+                    .bind(&deployment_id)
+                    .execute(&db)
+                    .await;
+                    DeploymentEvent::record(
+                        &db,
+                        &deployment_id,
+                        "cancelled",
+                        Some(host_maintenance::CORDONED_MESSAGE),
+                    )
+                    .await;
+                    tracing::info!(
+                        deployment = %deployment_id,
+                        app = %app.name,
+                        "Deployment cancelled: host is cordoned"
+                    );
+                    return;
+                }
+                let _active = counters.track_active();
 
                 // The deployment record's started_at was set when it was queued.
                 // Now that a build slot is free and work actually begins, reset it
@@ -144,6 +337,21 @@ impl DeploymentEngine {
                     .execute(&db)
                     .await;
 
+                // Pre-warm builds only produce an image for the app's slot: they
+                // neither supersede in-flight deployments nor touch containers/routes.
+                if AppPrewarmSettings::is_slot(&db, &deployment_id).await {
+                    prewarm::run_prewarm(
+                        &db,
+                        runtime,
+                        &deployment_id,
+                        &app,
+                        &build_limits,
+                        encryption_key.as_ref(),
+                    )
+                    .await;
+                    return;
+                }
+
                 // Supersede any older in-flight builds for this same app. The job
                 // channel is FIFO, so the deployment starting now is the newest —
                 // any other non-terminal build for the app is stale. Cancel it:
@@ -152,27 +360,40 @@ impl DeploymentEngine {
                 // same image tag / proxy route (observed: two parallel builds, one
                 // left a zombie 'building' row when its build died).
                 let now_ts = chrono::Utc::now().to_rfc3339();
-                match sqlx::query(
+                match sqlx::query_scalar::<_, String>(
                     "UPDATE deployments
                         SET status = 'cancelled', cancelled_at = ?, finished_at = ?,
                             error_message = 'Superseded by a newer deployment'
                       WHERE app_id = ? AND id != ?
-                        AND status IN ('pending', 'cloning', 'building', 'starting', 'checking')",
+                        AND status IN ('pending', 'cloning', 'building', 'starting', 'checking')
+                  RETURNING id",
                 )
                 .bind(&now_ts)
                 .bind(&now_ts)
                 .bind(&app.id)
                 .bind(&deployment_id)
-                .execute(&db)
+                .fetch_all(&db)
                 .await
                 {
-                    Ok(res) if res.rows_affected() > 0 => {
+                    Ok(superseded) if !superseded.is_empty() => {
+                        for id in &superseded {
+                            if let Some(token) = cancel_tokens.get(id) {
+                                token.cancel();
+                            }
+                        }
                         tracing::info!(
                             "Deployment {} superseded {} older in-flight deployment(s) for app {}",
                             deployment_id,
-                            res.rows_affected(),
+                            superseded.len(),
                             app.name
                         );
+                        DeploymentEvent::record_all(
+                            &db,
+                            &superseded,
+                            "cancelled",
+                            Some("Superseded by a newer deployment"),
+                        )
+                        .await;
                     }
                     Ok(_) => {}
                     Err(e) => {
@@ -181,7 +402,6 @@ impl DeploymentEngine {
                 }
 
                 let deploy_start = std::time::Instant::now();
-                let notification_service = NotificationService::new(db.clone());
 
                 // Send deployment_started notification
                 let started_payload = NotificationPayload::deployment_event(
@@ -192,21 +412,29 @@ impl DeploymentEngine {
                     "started".to_string(),
                     format!("Deployment started for {}", app.name),
                     None,
-                );
-                if let Err(e) = notification_service.send(&started_payload).await {
-                    tracing::warn!(error = %e, "Failed to send deployment_started notification");
+                )
+                .with_commit(CommitMetadata::load(&db, &deployment_id).await);
+                if let Err(e) = outbox::enqueue(&db, &started_payload).await {
+                    tracing::warn!(error = %e, "Failed to queue deployment_started notification");
                 }
 
-                match run_deployment(
-                    &db,
-                    runtime.clone(),
-                    &deployment_id,
-                    &app,
-                    &build_limits,
-                    encryption_key.as_ref(),
-                )
-                .await
-                {
+                // Cancelling drops the pipeline future mid-step, which kills its
+                // clone/build child processes
+                let result = tokio::select! {
+                    result = run_deployment(
+                        &db,
+                        runtime.clone(),
+                        &routes,
+                        &deployment_id,
+                        &app,
+                        &build_limits,
+                        &fences,
+                        encryption_key.as_ref(),
+                    ) => result,
+                    _ = cancel_token.cancelled() => Err(anyhow::anyhow!("Deployment was cancelled")),
+                };
+
+                match result {
                     Ok(container_info) => {
                         // Guard the rare race where a newer deployment superseded this
                         // one after start_container returned but before we swap routes.
@@ -224,8 +452,10 @@ impl DeploymentEngine {
                                 "Deployment {} superseded during build; discarding its container without swapping routes",
                                 deployment_id
                             );
-                            let _ = runtime.stop(&container_info.container_id).await;
-                            let _ = runtime.remove(&container_info.container_id).await;
+                            let duration_secs = deploy_start.elapsed().as_secs_f64();
+                            increment_deployments_total(&app.name, "cancelled");
+                            observe_deployment_duration(&app.name, "cancelled", duration_secs);
+                            cleanup_cancelled(&db, runtime.as_ref(), &deployment_id, &app).await;
                             return;
                         }
 
@@ -233,7 +463,7 @@ impl DeploymentEngine {
                         record_deployment_success();
                         let duration_secs = deploy_start.elapsed().as_secs_f64();
                         increment_deployments_total(&app.name, "success");
-                        observe_deployment_duration(&app.name, duration_secs);
+                        observe_deployment_duration(&app.name, "success", duration_secs);
 
                         // Send deployment_success notification
                         let success_payload = NotificationPayload::deployment_event(
@@ -244,21 +474,15 @@ impl DeploymentEngine {
                             "success".to_string(),
                             format!("Deployment successful for {}", app.name),
                             None,
-                        );
-                        if let Err(e) = notification_service.send(&success_payload).await {
-                            tracing::warn!(error = %e, "Failed to send deployment_success notification");
+                        )
+                        .with_commit(CommitMetadata::load(&db, &deployment_id).await);
+                        if let Err(e) = outbox::enqueue(&db, &success_payload).await {
+                            tracing::warn!(error = %e, "Failed to queue deployment_success notification");
                         }
 
                         // Mark all previous "running" deployments for this app as "replaced"
-                        let _ = sqlx::query(
-                            "UPDATE deployments SET status = 'replaced', finished_at = ?
-                             WHERE app_id = ? AND status = 'running' AND id != ?",
-                        )
-                        .bind(chrono::Utc::now().to_rfc3339())
-                        .bind(&app.id)
-                        .bind(&deployment_id)
-                        .execute(&db)
-                        .await;
+                        let _ =
+                            Deployment::mark_others_replaced(&db, &app.id, &deployment_id).await;
 
                         // Update proxy routes on successful deployment for all domains
                         if let Some(port) = container_info.port {
@@ -269,6 +493,43 @@ impl DeploymentEngine {
                                 .map(|(d, _)| d.clone())
                                 .collect();
                             let route_table = routes.load();
+                            let access_rules = AppAccessRule::list_enabled_for_proxy(&db, &app.id)
+                                .await
+                                .unwrap_or_default();
+                            let basic_auth_paths =
+                                AppBasicAuthPath::list_enabled_for_proxy(&db, &app.id)
+                                    .await
+                                    .unwrap_or_default();
+                            let waf_policy = AppWafSettings::load_policy(&db, &app.id)
+                                .await
+                                .unwrap_or_default();
+                            let bot_policy = AppBotSettings::load_policy(&db, &app.id, false)
+                                .await
+                                .unwrap_or_default();
+                            let forward_auth = AppForwardAuth::load_policy(&db, &app.id)
+                                .await
+                                .unwrap_or_default();
+                            let cors = AppCorsSettings::load_policy(&db, &app.id)
+                                .await
+                                .unwrap_or_default();
+
+                            // The container currently serving the app becomes the failover
+                            // target while it is kept running (see proxy.failover_keep_secs)
+                            let fallback = all_domains
+                                .first()
+                                .or(app.domain.as_ref())
+                                .and_then(|d| route_table.get_backend(d))
+                                .filter(|b| {
+                                    b.container_id != container_info.container_id
+                                        && container_info
+                                            .old_container_ids
+                                            .contains(&b.container_id)
+                                })
+                                .map(|b| FallbackTarget {
+                                    container_id: b.container_id,
+                                    host: b.host,
+                                    port: b.port,
+                                });
 
                             // Helper to create primary backend with basic auth if configured
                             let create_backend = |www_redirect_target: Option<String>| {
@@ -278,6 +539,7 @@ impl DeploymentEngine {
                                     port,
                                 )
                                 .with_healthcheck(app.healthcheck.clone())
+                                .with_health_probe(app.health_probe())
                                 .with_strip_prefix(app.strip_prefix.clone());
 
                                 backend.www_redirect_target = www_redirect_target;
@@ -295,12 +557,24 @@ impl DeploymentEngine {
                                         ));
                                     }
                                 }
+                                if backend.www_redirect_target.is_none() {
+                                    backend.set_access_rules(access_rules.clone());
+                                    backend.set_basic_auth_paths(basic_auth_paths.clone());
+                                    backend.set_waf(waf_policy.clone());
+                                    backend.set_bots(bot_policy.clone());
+                                    backend.set_forward_auth(forward_auth.clone());
+                                    backend.set_cors(cors.clone());
+                                    backend.fallback = fallback.clone();
+                                }
                                 backend
                             };
 
-                            // Collect all replica backend addresses for round-robin load balancing
-                            let replica_backends: Vec<String> = {
-                                let mut addrs = vec![format!("127.0.0.1:{}", port)];
+                            // Collect all replica backends for round-robin load balancing
+                            let replica_backends: Vec<PoolMember> = {
+                                let mut addrs = vec![PoolMember::new(
+                                    container_info.container_id.clone(),
+                                    format!("127.0.0.1:{}", port),
+                                )];
                                 // Fetch running replicas (index > 0)
                                 if let Ok(replicas) = sqlx::query_as::<_, crate::db::AppReplica>(
                                     "SELECT * FROM app_replicas WHERE app_id = ? AND replica_index > 0 AND status = 'running'",
@@ -313,7 +587,10 @@ impl DeploymentEngine {
                                         if let Some(ref cid) = replica.container_id {
                                             if let Ok(info) = runtime.inspect(cid).await {
                                                 if let Some(rport) = info.port {
-                                                    addrs.push(format!("127.0.0.1:{}", rport));
+                                                    addrs.push(PoolMember::new(
+                                                        cid.clone(),
+                                                        format!("127.0.0.1:{}", rport),
+                                                    ));
                                                 }
                                             }
                                         }
@@ -334,20 +611,24 @@ impl DeploymentEngine {
                                     }
                                 }
 
+                                // Swap every domain of the app in one go so requests never
+                                // see a mix of old and new backends
+                                let passthrough_domains = app.tls_passthrough_domains();
+                                let mut batch = RouteBatch::new();
                                 for (domain, www_redirect_target) in &domain_entries {
+                                    let mut backend = create_backend(www_redirect_target.clone());
+                                    backend.tls_passthrough = passthrough_domains.contains(domain);
                                     if www_redirect_target.is_some() {
-                                        route_table.add_route(
-                                            domain.clone(),
-                                            create_backend(www_redirect_target.clone()),
-                                        );
+                                        batch.add_route(domain.clone(), backend);
                                     } else {
-                                        route_table.add_backends(
+                                        batch.add_backends(
                                             domain.clone(),
                                             replica_backends.clone(),
-                                            create_backend(None),
+                                            backend,
                                         );
                                     }
                                 }
+                                route_table.apply(&batch);
 
                                 tracing::info!(
                                     domains = ?all_domains,
@@ -391,17 +672,57 @@ impl DeploymentEngine {
                         // Zero-downtime: stop old containers AFTER proxy routes are updated.
                         // New container is already serving traffic; old one can now be torn down.
                         if !container_info.old_container_ids.is_empty() {
-                            tracing::info!(
-                                old_containers = ?container_info.old_container_ids,
-                                "Stopping old containers after proxy route swap (zero-downtime)"
-                            );
+                            // Let requests already in flight to the old container finish
+                            routes.load().drain(&container_info.old_container_ids).await;
+
+                            let failover_keep = routes.load().failover_keep();
+                            let keep = failover_keep.max(keep_previous);
+                            let routes = routes.clone();
+                            let runtime = runtime.clone();
+                            let db = db.clone();
+                            let new_container_id = container_info.container_id.clone();
+                            // Resolve "-prev" names now: a later deploy reuses the name
+                            let mut old_container_ids = Vec::new();
                             for old_id in &container_info.old_container_ids {
-                                // Skip if the old ID is the same as the new container (no previous deployment)
-                                if old_id == &container_info.container_id {
-                                    continue;
+                                match runtime.inspect(old_id).await {
+                                    Ok(info) => old_container_ids.push(info.id),
+                                    Err(_) => old_container_ids.push(old_id.clone()),
                                 }
-                                let _ = runtime.stop(old_id).await;
-                                let _ = runtime.remove(old_id).await;
+                            }
+                            let fallback_ids = container_info.old_container_ids.clone();
+                            let retire = async move {
+                                // Idempotent requests fail over to the old container until it stops
+                                tokio::time::sleep(failover_keep).await;
+                                routes.load().clear_fallback(&fallback_ids);
+                                // Blue-green: stay available for an instant rollback
+                                tokio::time::sleep(keep.saturating_sub(failover_keep)).await;
+                                tracing::info!(
+                                    old_containers = ?old_container_ids,
+                                    "Stopping old containers after proxy route swap (zero-downtime)"
+                                );
+                                for old_id in &old_container_ids {
+                                    // Skip if the old ID is the same as the new container (no previous deployment)
+                                    if old_id == &new_container_id {
+                                        continue;
+                                    }
+                                    // Traffic was switched back to it by an instant rollback
+                                    if container_is_serving(&db, old_id).await {
+                                        continue;
+                                    }
+                                    let _ = runtime.stop(old_id).await;
+                                    let _ = runtime.remove(old_id).await;
+                                }
+                            };
+                            if keep.is_zero() {
+                                retire.await;
+                            } else {
+                                tracing::info!(
+                                    old_containers = ?container_info.old_container_ids,
+                                    keep_secs = keep.as_secs(),
+                                    "Keeping old containers for failover and instant rollback"
+                                );
+                                // Don't hold the deployment slot while the old containers are kept
+                                tokio::spawn(retire);
                             }
                         }
                     }
@@ -420,11 +741,26 @@ impl DeploymentEngine {
                                 &deployment_id,
                                 "failed",
                                 Some(&format!(
-                                    "Health check failed. Auto-rollback triggered to {}",
+                                    "{}. Auto-rollback triggered to {}",
+                                    auto_rollback.cause.failure(),
                                     auto_rollback.target_deployment_id
                                 )),
                             )
                             .await;
+                            diagnostics::collect_on_failure(
+                                &db,
+                                storage.as_deref(),
+                                runtime.as_ref(),
+                                &deployment_id,
+                                &app,
+                            )
+                            .await;
+                            let failure_kind = failure::classify_deployment(
+                                &db,
+                                &deployment_id,
+                                &format!("{:#}", e),
+                            )
+                            .await;
 
                             // Get the rollback deployment info to update routes
                             if let Ok(Some(rollback_deployment)) =
@@ -447,7 +783,34 @@ impl DeploymentEngine {
                                                 .map(|(d, _)| d.clone())
                                                 .collect();
                                             let route_table = routes.load();
+                                            let access_rules =
+                                                AppAccessRule::list_enabled_for_proxy(&db, &app.id)
+                                                    .await
+                                                    .unwrap_or_default();
+                                            let basic_auth_paths =
+                                                AppBasicAuthPath::list_enabled_for_proxy(
+                                                    &db, &app.id,
+                                                )
+                                                .await
+                                                .unwrap_or_default();
+                                            let waf_policy =
+                                                AppWafSettings::load_policy(&db, &app.id)
+                                                    .await
+                                                    .unwrap_or_default();
+                                            let bot_policy =
+                                                AppBotSettings::load_policy(&db, &app.id, false)
+                                                    .await
+                                                    .unwrap_or_default();
+                                            let forward_auth =
+                                                AppForwardAuth::load_policy(&db, &app.id)
+                                                    .await
+                                                    .unwrap_or_default();
+                                            let cors = AppCorsSettings::load_policy(&db, &app.id)
+                                                .await
+                                                .unwrap_or_default();
 
+                                            let passthrough_domains = app.tls_passthrough_domains();
+                                            let mut batch = RouteBatch::new();
                                             for (domain, www_redirect_target) in &domain_entries {
                                                 let mut backend = Backend::new(
                                                     container_id.clone(),
@@ -455,6 +818,7 @@ impl DeploymentEngine {
                                                     port,
                                                 )
                                                 .with_healthcheck(app.healthcheck.clone())
+                                                .with_health_probe(app.health_probe())
                                                 .with_strip_prefix(app.strip_prefix.clone());
 
                                                 backend.www_redirect_target =
@@ -475,8 +839,21 @@ impl DeploymentEngine {
                                                         );
                                                     }
                                                 }
-                                                route_table.add_route(domain.clone(), backend);
+                                                if www_redirect_target.is_none() {
+                                                    backend.set_access_rules(access_rules.clone());
+                                                    backend.set_basic_auth_paths(
+                                                        basic_auth_paths.clone(),
+                                                    );
+                                                    backend.set_waf(waf_policy.clone());
+                                                    backend.set_bots(bot_policy.clone());
+                                                    backend.set_forward_auth(forward_auth.clone());
+                                                    backend.set_cors(cors.clone());
+                                                }
+                                                backend.tls_passthrough =
+                                                    passthrough_domains.contains(domain);
+                                                batch.add_route(domain.clone(), backend);
                                             }
+                                            route_table.apply(&batch);
 
                                             tracing::info!(
                                                 domains = ?all_domains,
@@ -491,6 +868,7 @@ impl DeploymentEngine {
 
                             // Zero-downtime: stop old containers AFTER proxy routes are updated.
                             if !auto_rollback.old_container_ids.is_empty() {
+                                routes.load().drain(&auto_rollback.old_container_ids).await;
                                 tracing::info!(
                                     old_containers = ?auto_rollback.old_container_ids,
                                     "Stopping old containers after auto-rollback proxy route swap (zero-downtime)"
@@ -516,14 +894,11 @@ impl DeploymentEngine {
                             }
 
                             // Mark previous running deployments as replaced (except the rollback)
-                            let _ = sqlx::query(
-                                "UPDATE deployments SET status = 'replaced', finished_at = ?
-                                 WHERE app_id = ? AND status = 'running' AND id != ?",
+                            let _ = Deployment::mark_others_replaced(
+                                &db,
+                                &app.id,
+                                &auto_rollback.rollback_deployment_id,
                             )
-                            .bind(chrono::Utc::now().to_rfc3339())
-                            .bind(&app.id)
-                            .bind(&auto_rollback.rollback_deployment_id)
-                            .execute(&db)
                             .await;
 
                             // Send auto-rollback notification
@@ -537,15 +912,40 @@ impl DeploymentEngine {
                                     "Deployment failed for {}. Auto-rollback to previous version completed.",
                                     app.name
                                 ),
-                                Some(format!(
-                                    "Health check failed. Rolled back to deployment {}",
-                                    auto_rollback.target_deployment_id
+                                Some(failure::with_hint(
+                                    &format!(
+                                        "{}. Rolled back to deployment {}",
+                                        auto_rollback.cause.failure(),
+                                        auto_rollback.target_deployment_id
+                                    ),
+                                    failure_kind,
                                 )),
-                            );
-                            if let Err(notify_err) =
-                                notification_service.send(&rollback_payload).await
+                            )
+                            .with_commit(CommitMetadata::load(&db, &deployment_id).await);
+                            let rolled_back_payload = NotificationPayload::deployment_event(
+                                NotificationEventType::DeploymentRolledBack,
+                                app.id.clone(),
+                                app.name.clone(),
+                                deployment_id.clone(),
+                                "rolled_back".to_string(),
+                                format!(
+                                    "{} was rolled back to deployment {} after the new version \
+                                     failed {}",
+                                    app.name,
+                                    auto_rollback.target_deployment_id,
+                                    auto_rollback.cause.describe(&app)
+                                ),
+                                None,
+                            )
+                            .with_commit(CommitMetadata::load(&db, &deployment_id).await);
+                            if let Err(notify_err) = mark_rolled_back(
+                                &db,
+                                &deployment_id,
+                                &[rollback_payload, rolled_back_payload],
+                            )
+                            .await
                             {
-                                tracing::warn!(error = %notify_err, "Failed to send auto-rollback notification");
+                                tracing::warn!(error = %notify_err, "Failed to record auto-rollback");
                             }
                         } else {
                             // Regular failure - no auto-rollback
@@ -563,18 +963,30 @@ impl DeploymentEngine {
                                     "Deployment {} was cancelled — skipping failed status update",
                                     deployment_id
                                 );
+                                let duration_secs = deploy_start.elapsed().as_secs_f64();
+                                increment_deployments_total(&app.name, "cancelled");
+                                observe_deployment_duration(&app.name, "cancelled", duration_secs);
+                                cleanup_cancelled(&db, runtime.as_ref(), &deployment_id, &app)
+                                    .await;
                             } else {
                                 record_deployment_failed();
                                 let duration_secs = deploy_start.elapsed().as_secs_f64();
                                 increment_deployments_total(&app.name, "failed");
-                                observe_deployment_duration(&app.name, duration_secs);
+                                observe_deployment_duration(&app.name, "failed", duration_secs);
 
                                 tracing::error!("Deployment {} failed: {}", deployment_id, e);
-                                let _ = update_deployment_status(
+                                diagnostics::collect_on_failure(
                                     &db,
+                                    storage.as_deref(),
+                                    runtime.as_ref(),
                                     &deployment_id,
-                                    "failed",
-                                    Some(&e.to_string()),
+                                    &app,
+                                )
+                                .await;
+                                let failure_kind = failure::classify_deployment(
+                                    &db,
+                                    &deployment_id,
+                                    &format!("{:#}", e),
                                 )
                                 .await;
 
@@ -594,7 +1006,8 @@ impl DeploymentEngine {
                                     }
                                 }
 
-                                // Send deployment_failed notification
+                                // Mark the deployment failed and queue the deployment_failed
+                                // notification together
                                 let failed_payload = NotificationPayload::deployment_event(
                                     NotificationEventType::DeploymentFailed,
                                     app.id.clone(),
@@ -602,17 +1015,31 @@ impl DeploymentEngine {
                                     deployment_id.clone(),
                                     "failed".to_string(),
                                     format!("Deployment failed for {}", app.name),
-                                    Some(e.to_string()),
-                                );
-                                if let Err(notify_err) =
-                                    notification_service.send(&failed_payload).await
+                                    Some(failure::with_hint(&e.to_string(), failure_kind)),
+                                )
+                                .with_commit(CommitMetadata::load(&db, &deployment_id).await);
+                                if let Err(status_err) = update_deployment_status_notify(
+                                    &db,
+                                    &deployment_id,
+                                    "failed",
+                                    Some(&e.to_string()),
+                                    &[failed_payload],
+                                )
+                                .await
                                 {
-                                    tracing::warn!(error = %notify_err, "Failed to send deployment_failed notification");
+                                    tracing::warn!(error = %status_err, "Failed to mark deployment failed");
                                 }
                             } // end else (not cancelled)
                         }
                     }
                 }
+
+                // Keep the build log in the artifact store, where it outlives
+                // log retention
+                if let Some(storage) = storage.as_deref() {
+                    crate::storage::artifacts::archive_build_log(&db, storage, &deployment_id)
+                        .await;
+                }
             });
         }
     }
@@ -623,10 +1050,24 @@ async fn update_deployment_status(
     deployment_id: &str,
     status: &str,
     error: Option<&str>,
+) -> anyhow::Result<()> {
+    update_deployment_status_notify(db, deployment_id, status, error, &[]).await
+}
+
+/// Update a deployment's status and queue the notifications reporting the
+/// change in the same transaction. Nothing is queued when the update doesn't
+/// apply (the deployment was cancelled).
+async fn update_deployment_status_notify(
+    db: &DbPool,
+    deployment_id: &str,
+    status: &str,
+    error: Option<&str>,
+    notifications: &[NotificationPayload],
 ) -> anyhow::Result<()> {
     let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
 
-    if status == "running" {
+    let result = if status == "running" {
         // Going live: stamp built_at (once, never overwritten) so the UI can
         // show live uptime, and finished_at = go-live time (later overwritten
         // to the replaced/stopped moment when a newer deployment takes over).
@@ -638,9 +1079,9 @@ async fn update_deployment_status(
         .bind(&now)
         .bind(&now)
         .bind(deployment_id)
-        .execute(db)
-        .await?;
-    } else if status == "failed" || status == "stopped" {
+        .execute(&mut *tx)
+        .await?
+    } else if status == "failed" || status == "stopped" || status == "prewarmed" {
         sqlx::query(
             "UPDATE deployments SET status = ?, error_message = ?, finished_at = ? WHERE id = ? AND status != 'cancelled'",
         )
@@ -648,32 +1089,83 @@ async fn update_deployment_status(
         .bind(error)
         .bind(&now)
         .bind(deployment_id)
-        .execute(db)
-        .await?;
+        .execute(&mut *tx)
+        .await?
     } else {
         sqlx::query("UPDATE deployments SET status = ?, error_message = ? WHERE id = ? AND status != 'cancelled'")
             .bind(status)
             .bind(error)
             .bind(deployment_id)
-            .execute(db)
-            .await?;
+            .execute(&mut *tx)
+            .await?
+    };
+
+    let applied = result.rows_affected() > 0;
+    if applied {
+        for payload in notifications {
+            outbox::enqueue(&mut *tx, payload).await?;
+        }
     }
+    tx.commit().await?;
 
+    if applied {
+        DeploymentEvent::record(db, deployment_id, status, error).await;
+        if let Some(phase_status) = deploy_phases::final_status(status) {
+            deploy_phases::end(db, deployment_id, phase_status).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Flag a deployment as automatically rolled back and queue the notifications
+/// about the rollback in one transaction
+async fn mark_rolled_back(
+    db: &DbPool,
+    deployment_id: &str,
+    notifications: &[NotificationPayload],
+) -> anyhow::Result<()> {
+    let mut tx = db.begin().await?;
+    sqlx::query("UPDATE deployments SET rolled_back = 1 WHERE id = ?")
+        .bind(deployment_id)
+        .execute(&mut *tx)
+        .await?;
+    for payload in notifications {
+        outbox::enqueue(&mut *tx, payload).await?;
+    }
+    tx.commit().await?;
     Ok(())
 }
 
+/// Whether a running deployment uses this container (it was rolled back to)
+async fn container_is_serving(db: &DbPool, container_id: &str) -> bool {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM deployments WHERE container_id = ? AND status = 'running'",
+    )
+    .bind(container_id)
+    .fetch_one(db)
+    .await
+    .map(|count| count > 0)
+    .unwrap_or(false)
+}
+
 async fn add_deployment_log(
     db: &DbPool,
     deployment_id: &str,
     level: &str,
     message: &str,
 ) -> anyhow::Result<()> {
-    sqlx::query("INSERT INTO deployment_logs (deployment_id, level, message) VALUES (?, ?, ?)")
-        .bind(deployment_id)
-        .bind(level)
-        .bind(message)
-        .execute(db)
-        .await?;
+    // Tag the line with the pipeline phase in progress
+    sqlx::query(
+        "INSERT INTO deployment_logs (deployment_id, level, message, phase) VALUES (?1, ?2, ?3, \
+         (SELECT phase FROM deployment_phases \
+          WHERE deployment_id = ?1 AND finished_at IS NULL ORDER BY id DESC LIMIT 1))",
+    )
+    .bind(deployment_id)
+    .bind(level)
+    .bind(message)
+    .execute(db)
+    .await?;
 
     // Also forward to log drains for the app
     // Look up the app_id from the deployment
@@ -700,3 +1192,47 @@ async fn add_deployment_log(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_deployment, test_pool};
+
+    #[tokio::test]
+    async fn test_container_is_serving_only_for_running_deployments() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let running = test_deployment(&db, &app_id, "running").await;
+        let replaced = test_deployment(&db, &app_id, "replaced").await;
+        for (id, container_id) in [(&running, "c-live"), (&replaced, "c-old")] {
+            sqlx::query("UPDATE deployments SET container_id = ? WHERE id = ?")
+                .bind(container_id)
+                .bind(id)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+
+        assert!(container_is_serving(&db, "c-live").await);
+        assert!(!container_is_serving(&db, "c-old").await);
+        assert!(!container_is_serving(&db, "c-unknown").await);
+    }
+
+    #[test]
+    fn test_build_limits_take_the_build_host() {
+        let config: crate::config::Config =
+            toml::from_str("[runtime]\nbuild_host = \"ssh://builder@build.example.com\"\n")
+                .unwrap();
+        let limits = BuildLimits::from_runtime_config(&config.runtime);
+        assert_eq!(
+            limits.build_host.as_deref(),
+            Some("ssh://builder@build.example.com")
+        );
+
+        // Empty builds locally
+        let mut runtime = crate::config::RuntimeConfig::default();
+        assert_eq!(BuildLimits::from_runtime_config(&runtime).build_host, None);
+        runtime.build_host = Some(String::new());
+        assert_eq!(BuildLimits::from_runtime_config(&runtime).build_host, None);
+    }
+}
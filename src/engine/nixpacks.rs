@@ -10,6 +10,7 @@
 //! - Environment variable support during builds
 //! - Real-time build output streaming
 //! - Build plan generation for debugging
+//! - Per-app provider overrides and Nixpacks version pinning
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -40,6 +41,9 @@ pub enum NixpacksError {
 
     #[error("Unsupported project type or language not detected")]
     UnsupportedProject,
+
+    #[error("Nixpacks {wanted} is pinned but not installed (found {found}). Install it as `nixpacks-{wanted}` on the PATH")]
+    VersionNotInstalled { wanted: String, found: String },
 }
 
 /// Configuration for Nixpacks builds
@@ -80,6 +84,15 @@ pub struct NixpacksConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<String>,
 
+    /// Providers to use instead of auto-detection, in order
+    /// (e.g., ["node", "python"]); takes precedence over `provider`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<String>>,
+
+    /// Pin the Nixpacks CLI version used for builds (e.g., "1.29.0")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
     /// Disable build caching
     #[serde(default, skip_serializing_if = "is_false")]
     pub no_cache: bool,
@@ -159,6 +172,12 @@ impl NixpacksConfig {
         if other.provider.is_some() {
             self.provider = other.provider.clone();
         }
+        if other.providers.is_some() {
+            self.providers = other.providers.clone();
+        }
+        if other.version.is_some() {
+            self.version = other.version.clone();
+        }
         if other.no_cache {
             self.no_cache = true;
         }
@@ -174,8 +193,19 @@ impl NixpacksConfig {
             && self.static_dir.is_none()
             && self.libs.is_none()
             && self.provider.is_none()
+            && self.providers.is_none()
+            && self.version.is_none()
             && !self.no_cache
     }
+
+    /// Providers to force, falling back to the single legacy `provider`
+    pub fn effective_providers(&self) -> Vec<String> {
+        match (&self.providers, &self.provider) {
+            (Some(providers), _) => providers.clone(),
+            (None, Some(provider)) => vec![provider.clone()],
+            (None, None) => Vec::new(),
+        }
+    }
 }
 
 /// Check if Nixpacks CLI is available on the system
@@ -190,13 +220,9 @@ pub async fn is_available() -> bool {
         .unwrap_or(false)
 }
 
-/// Get Nixpacks version if available
-pub async fn get_version() -> Option<String> {
-    let output = Command::new("nixpacks")
-        .arg("--version")
-        .output()
-        .await
-        .ok()?;
+/// Get the version of a Nixpacks binary if available
+pub async fn get_version(binary: &str) -> Option<String> {
+    let output = Command::new(binary).arg("--version").output().await.ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
@@ -205,6 +231,43 @@ pub async fn get_version() -> Option<String> {
     }
 }
 
+/// Pick the Nixpacks binary to run for a (possibly pinned) version.
+///
+/// Without a pin this is the `nixpacks` on the PATH. With a pin, a
+/// side-by-side install named `nixpacks-<version>` is preferred; otherwise
+/// the default binary is used only if it already is that version, so a
+/// pinned app never silently builds with a different Nixpacks release.
+pub async fn resolve_binary(version: Option<&str>) -> Result<String> {
+    let Some(wanted) = version else {
+        if !is_available().await {
+            return Err(NixpacksError::NotInstalled.into());
+        }
+        return Ok("nixpacks".to_string());
+    };
+
+    let pinned = format!("nixpacks-{}", wanted);
+    if get_version(&pinned).await.is_some() {
+        return Ok(pinned);
+    }
+
+    match get_version("nixpacks").await {
+        Some(found) if version_matches(&found, wanted) => Ok("nixpacks".to_string()),
+        Some(found) => Err(NixpacksError::VersionNotInstalled {
+            wanted: wanted.to_string(),
+            found,
+        }
+        .into()),
+        None => Err(NixpacksError::NotInstalled.into()),
+    }
+}
+
+/// Whether `nixpacks --version` output (e.g. "nixpacks 1.29.0") is `wanted`
+fn version_matches(output: &str, wanted: &str) -> bool {
+    output
+        .split_whitespace()
+        .any(|word| word.trim_start_matches('v') == wanted)
+}
+
 /// Check if a nixpacks.toml configuration file exists in the source directory
 pub async fn has_config_file(source_path: &Path) -> bool {
     let toml_path = source_path.join("nixpacks.toml");
@@ -243,7 +306,7 @@ fn apply_config_to_command(cmd: &mut Command, config: &NixpacksConfig) {
             cmd.arg("--lib").arg(lib);
         }
     }
-    if let Some(ref provider) = config.provider {
+    for provider in config.effective_providers() {
         debug!("Forcing provider: {}", provider);
         cmd.arg("--provider").arg(provider);
     }
@@ -260,6 +323,7 @@ fn apply_config_to_command(cmd: &mut Command, config: &NixpacksConfig) {
 ///
 /// # Arguments
 ///
+/// * `binary` - Nixpacks binary to run (see [`resolve_binary`])
 /// * `source_path` - Path to the source code directory
 /// * `image_tag` - Docker image tag to use (e.g., "rivetr-myapp:abc123")
 /// * `config` - Optional Nixpacks configuration (from database or API)
@@ -269,6 +333,7 @@ fn apply_config_to_command(cmd: &mut Command, config: &NixpacksConfig) {
 ///
 /// Returns the image tag on success.
 pub async fn build_image(
+    binary: &str,
     source_path: &Path,
     image_tag: &str,
     config: Option<&NixpacksConfig>,
@@ -278,11 +343,6 @@ pub async fn build_image(
     info!("Building image with Nixpacks: {}", image_tag);
     debug!("Source path: {:?}", source_path);
 
-    // Check if Nixpacks is available
-    if !is_available().await {
-        return Err(NixpacksError::NotInstalled.into());
-    }
-
     // Load config from nixpacks.toml if present, and merge with provided config
     let mut effective_config = NixpacksConfig::default();
 
@@ -300,7 +360,7 @@ pub async fn build_image(
         }
     }
 
-    let mut cmd = Command::new("nixpacks");
+    let mut cmd = Command::new(binary);
     cmd.arg("build")
         .arg(source_path)
         .arg("--name")
@@ -327,11 +387,13 @@ pub async fn build_image(
     }
 
     // Set up for streaming output
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
 
     debug!(
-        "Executing Nixpacks command: nixpacks build {:?}",
-        source_path
+        "Executing Nixpacks command: {} build {:?}",
+        binary, source_path
     );
 
     let mut child = cmd.spawn().context("Failed to spawn nixpacks process")?;
@@ -495,15 +557,8 @@ pub async fn generate_dockerfile(
     source_path: &Path,
     config: Option<&NixpacksConfig>,
 ) -> Result<String> {
-    if !is_available().await {
-        return Err(NixpacksError::NotInstalled.into());
-    }
-
     let output_dir = source_path.join(".nixpacks");
 
-    let mut cmd = Command::new("nixpacks");
-    cmd.arg("build").arg(source_path).arg("-o").arg(&output_dir);
-
     // Load config from nixpacks.toml if present
     let mut effective_config = NixpacksConfig::default();
     if let Some(repo_config) = NixpacksConfig::load_from_repo(source_path).await {
@@ -513,6 +568,10 @@ pub async fn generate_dockerfile(
         effective_config.merge(cfg);
     }
 
+    let binary = resolve_binary(effective_config.version.as_deref()).await?;
+    let mut cmd = Command::new(&binary);
+    cmd.arg("build").arg(source_path).arg("-o").arg(&output_dir);
+
     if !effective_config.is_empty() {
         apply_config_to_command(&mut cmd, &effective_config);
     }
@@ -555,6 +614,8 @@ mod tests {
             static_dir: None,
             libs: None,
             provider: None,
+            providers: Some(vec!["node".to_string()]),
+            version: Some("1.29.0".to_string()),
             no_cache: false,
         };
 
@@ -565,6 +626,8 @@ mod tests {
         assert_eq!(parsed.build_cmd, config.build_cmd);
         assert_eq!(parsed.start_cmd, config.start_cmd);
         assert_eq!(parsed.packages, config.packages);
+        assert_eq!(parsed.providers, config.providers);
+        assert_eq!(parsed.version, config.version);
     }
 
     #[test]
@@ -631,6 +694,35 @@ mod tests {
         assert!(base.no_cache);
     }
 
+    #[test]
+    fn test_effective_providers() {
+        assert!(NixpacksConfig::default().effective_providers().is_empty());
+
+        let legacy = NixpacksConfig {
+            provider: Some("node".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(legacy.effective_providers(), vec!["node".to_string()]);
+
+        let both = NixpacksConfig {
+            provider: Some("node".to_string()),
+            providers: Some(vec!["python".to_string(), "node".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            both.effective_providers(),
+            vec!["python".to_string(), "node".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("nixpacks 1.29.0", "1.29.0"));
+        assert!(version_matches("nixpacks v1.29.0\n", "1.29.0"));
+        assert!(!version_matches("nixpacks 1.29.1", "1.29.0"));
+        assert!(!version_matches("nixpacks 1.29.0", "1.29"));
+    }
+
     #[test]
     fn test_config_is_empty() {
         let empty = NixpacksConfig::default();
@@ -401,7 +401,9 @@ pub async fn build_image(
     }
 
     // Set up for streaming output
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
 
     debug!(
         "Executing Pack command: pack build {} --builder {}",
@@ -3,18 +3,156 @@ use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+use crate::config::RegistryConfig;
 use crate::db::App;
+use crate::engine::build_cache;
+use crate::engine::build_sandbox;
 use crate::engine::nixpacks;
 use crate::engine::pack_builder;
 use crate::engine::railpack;
 use crate::engine::static_builder::{StaticSiteBuilder, StaticSiteConfig};
-use crate::runtime::{BuildContext, ContainerRuntime};
+use crate::runtime::{BuildContext, BuildIsolation, ContainerRuntime, RegistryAuth};
 use crate::DbPool;
 
+use super::super::deploy_phases::{self, DeployPhase};
 use super::super::{add_deployment_log, BuildLimits, KEY_LENGTH};
 use super::clone;
 
-/// Push a built image to a Docker registry if the app has registry push enabled.
+/// A registry built images are pushed to, with the credentials to use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct PushTarget {
+    /// Registry and namespace, e.g. "registry.example.com/rivetr"
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl PushTarget {
+    /// Whether `image` was pushed to this registry
+    pub fn holds(&self, image: &str) -> bool {
+        image
+            .strip_prefix(self.url.as_str())
+            .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Credentials for pulling from this registry
+    pub fn auth(&self) -> Option<RegistryAuth> {
+        let auth = RegistryAuth::new(
+            self.username.clone(),
+            self.password.clone(),
+            Some(registry_host(&self.url).to_string()),
+        );
+        (!auth.is_empty()).then_some(auth)
+    }
+}
+
+/// Host part of a registry URL ("registry.example.com/rivetr" → "registry.example.com")
+fn registry_host(url: &str) -> &str {
+    url.split('/').next().unwrap_or(url)
+}
+
+/// Where an app's built images are pushed: its own registry when it has
+/// registry push enabled, otherwise the global `[registry]`, if configured.
+pub(super) fn push_target(
+    app: &App,
+    registry: &RegistryConfig,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Option<PushTarget> {
+    if app.can_push_to_registry() {
+        let password = app
+            .registry_password
+            .clone()
+            .filter(|p| !p.is_empty())
+            .map(|raw| crate::crypto::decrypt_if_encrypted(&raw, encryption_key).unwrap_or(raw));
+        return Some(PushTarget {
+            url: app
+                .registry_url
+                .as_deref()
+                .unwrap_or_default()
+                .trim_end_matches('/')
+                .to_string(),
+            username: app.registry_username.clone().filter(|u| !u.is_empty()),
+            password,
+        });
+    }
+
+    registry.url().map(|url| PushTarget {
+        url: url.to_string(),
+        username: registry.username.clone().filter(|u| !u.is_empty()),
+        password: registry.password.clone().filter(|p| !p.is_empty()),
+    })
+}
+
+/// Repository of the versioned tags of an app's built images
+pub fn version_repository(app_name: &str) -> String {
+    format!("rivetr/{}", app_name)
+}
+
+/// Version label of a build: the short commit SHA, or the short deployment ID
+/// when the deployment has no commit
+fn version_label(commit_sha: Option<&str>, deployment_id: &str) -> String {
+    match commit_sha.filter(|sha| !sha.is_empty() && !sha.contains("rivetr-upload-")) {
+        Some(sha) => sha[..sha.len().min(7)].to_string(),
+        None => deployment_id[..deployment_id.len().min(8)].to_string(),
+    }
+}
+
+/// Tag a built image as `rivetr/<app>:<short-sha>` and `rivetr/<app>:latest`
+/// and record the versioned tag on the deployment. The deployment keeps
+/// running its own `image_tag`, so a later build of the same commit doesn't
+/// change what a rollback to it restores. Tagging failures are logged and
+/// don't fail the deployment.
+pub(super) async fn tag_built_image(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    image_tag: &str,
+) -> Result<()> {
+    let commit_sha: Option<String> =
+        sqlx::query_scalar("SELECT commit_sha FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+
+    let repository = version_repository(&app.name);
+    let version_tag = format!(
+        "{}:{}",
+        repository,
+        version_label(commit_sha.as_deref(), deployment_id)
+    );
+    let latest_tag = format!("{}:latest", repository);
+
+    for tag in [&version_tag, &latest_tag] {
+        if let Err(e) = runtime.tag_image(image_tag, tag).await {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "warn",
+                &format!("Failed to tag image as {} (non-fatal): {}", tag, e),
+            )
+            .await?;
+            return Ok(());
+        }
+    }
+
+    sqlx::query("UPDATE deployments SET version_tag = ? WHERE id = ?")
+        .bind(&version_tag)
+        .bind(deployment_id)
+        .execute(db)
+        .await?;
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!("Tagged image as {} and {}", version_tag, latest_tag),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Push a built image to the app's push registry (see `push_target`).
 /// Uses `docker tag` + `docker login` + `docker push` CLI commands.
 ///
 /// Tags the image with both the commit SHA (or short deployment ID as fallback)
@@ -25,18 +163,13 @@ pub(super) async fn push_image_to_registry(
     deployment_id: &str,
     app: &App,
     image_tag: &str,
+    registry: &RegistryConfig,
     encryption_key: Option<&[u8; super::super::KEY_LENGTH]>,
 ) -> Result<Option<String>> {
-    use crate::crypto;
     use tokio::process::Command;
 
-    if !app.is_registry_push_enabled() {
-        return Ok(None);
-    }
-
-    let registry_url = match app.registry_url.as_deref().filter(|s| !s.is_empty()) {
-        Some(url) => url.to_string(),
-        None => {
+    let Some(target) = push_target(app, registry, encryption_key) else {
+        if app.is_registry_push_enabled() {
             add_deployment_log(
                 db,
                 deployment_id,
@@ -44,9 +177,11 @@ pub(super) async fn push_image_to_registry(
                 "Registry push is enabled but no registry URL is configured — skipping push",
             )
             .await?;
-            return Ok(None);
         }
+        return Ok(None);
     };
+    deploy_phases::begin(db, deployment_id, DeployPhase::Push).await;
+    let registry_url = target.url.clone();
 
     // Prefer commit SHA for deterministic rollbacks; fall back to short deployment ID.
     let commit_sha: Option<String> =
@@ -97,40 +232,38 @@ pub(super) async fn push_image_to_registry(
     }
 
     // Step 2: docker login (only if credentials are provided)
-    if let Some(username) = app.registry_username.as_deref().filter(|s| !s.is_empty()) {
-        let raw_password = app.registry_password.clone().unwrap_or_default();
-        let password =
-            crypto::decrypt_if_encrypted(&raw_password, encryption_key).unwrap_or(raw_password);
-
-        if !password.is_empty() {
-            let login_output = Command::new("docker")
-                .args(["login", &registry_url, "-u", username, "--password-stdin"])
-                .stdin(std::process::Stdio::piped())
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped())
-                .spawn()
-                .context("Failed to spawn docker login")?
-                .wait_with_output()
-                .await;
-
-            // If spawn/piped stdin approach is not available, fall back to -p flag
-            // (less secure but simpler)
-            let login_output = match login_output {
-                Ok(o) if o.status.success() => o,
-                _ => {
-                    let fallback = Command::new("docker")
-                        .args(["login", &registry_url, "-u", username, "-p", &password])
-                        .output()
-                        .await
-                        .context("Failed to run docker login")?;
-                    fallback
-                }
-            };
-
-            if !login_output.status.success() {
-                let stderr = String::from_utf8_lossy(&login_output.stderr);
-                anyhow::bail!("docker login failed: {}", stderr);
+    if let (Some(username), Some(password)) = (&target.username, &target.password) {
+        let login_host = registry_host(&registry_url);
+        let mut login = Command::new("docker")
+            .args(["login", login_host, "-u", username, "--password-stdin"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .context("Failed to spawn docker login")?;
+        if let Some(mut stdin) = login.stdin.take() {
+            use tokio::io::AsyncWriteExt;
+            let _ = stdin.write_all(password.as_bytes()).await;
+        }
+        let login_output = login.wait_with_output().await;
+
+        // If spawn/piped stdin approach is not available, fall back to -p flag
+        // (less secure but simpler)
+        let login_output = match login_output {
+            Ok(o) if o.status.success() => o,
+            _ => {
+                let fallback = Command::new("docker")
+                    .args(["login", login_host, "-u", username, "-p", password])
+                    .output()
+                    .await
+                    .context("Failed to run docker login")?;
+                fallback
             }
+        };
+
+        if !login_output.status.success() {
+            let stderr = String::from_utf8_lossy(&login_output.stderr);
+            anyhow::bail!("docker login failed: {}", stderr);
         }
     }
 
@@ -299,6 +432,151 @@ pub(super) async fn execute_deployment_commands(
     Ok(())
 }
 
+/// Run the sandbox checks for apps that build untrusted code.
+///
+/// Returns the isolation to build with, or `None` when the app builds normally.
+/// Fails the deployment when the build can't be sandboxed (non-Dockerfile build
+/// type, disallowed base image, network access from the Dockerfile, ...).
+async fn sandbox_isolation(
+    db: &DbPool,
+    deployment_id: &str,
+    app: &App,
+    build_path: &Path,
+    build_limits: &BuildLimits,
+) -> Result<Option<BuildIsolation>> {
+    if !build_sandbox::is_required(db, app).await? {
+        return Ok(None);
+    }
+
+    let dockerfile = app
+        .dockerfile_path
+        .as_ref()
+        .filter(|p| !p.is_empty())
+        .cloned()
+        .unwrap_or_else(|| app.dockerfile.clone());
+    let isolation = build_sandbox::check_build(
+        app.get_build_type(),
+        build_path,
+        &dockerfile,
+        &build_limits.sandbox,
+    )
+    .await?;
+
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        "Sandboxed build: build steps run without network access, build secrets and custom build options are withheld",
+    )
+    .await?;
+    if let Some(ref builder) = isolation.builder {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            &format!("Using sandbox builder '{}'", builder),
+        )
+        .await?;
+    }
+    if let Some(ref oci_runtime) = isolation.oci_runtime {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            &format!("Using sandbox OCI runtime '{}'", oci_runtime),
+        )
+        .await?;
+    }
+
+    Ok(Some(isolation))
+}
+
+/// Pack configuration for a buildpacks build: the app's `cnb_config`, or the
+/// builder best suited to the project files when it has none
+async fn pack_config_for(app: &App, build_path: &Path) -> pack_builder::PackConfig {
+    let mut config = match app.get_pack_config() {
+        Some(config) => config,
+        None => pack_builder::PackConfig {
+            builder: pack_builder::suggest_builder(build_path).await,
+            ..Default::default()
+        },
+    };
+    config.trust_builder = true;
+    if app.disable_build_cache != 0 {
+        config.clear_cache = true;
+    }
+    config
+}
+
+/// Static site build configuration for `app`: its build settings, the
+/// configured or auto-detected publish directory and its env vars
+async fn static_site_config(
+    db: &DbPool,
+    deployment_id: &str,
+    app: &App,
+    build_path: &Path,
+    build_limits: &BuildLimits,
+) -> Result<StaticSiteConfig> {
+    let env_vars: Vec<(String, String)> =
+        sqlx::query_as::<_, (String, String)>("SELECT key, value FROM env_vars WHERE app_id = ?")
+            .bind(&app.id)
+            .fetch_all(db)
+            .await
+            .unwrap_or_default();
+
+    // Use the app's publish directory or auto-detect it
+    let publish_dir = match app.publish_directory.as_deref() {
+        Some(dir) if !dir.is_empty() => {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!("Using configured publish directory: {}", dir),
+            )
+            .await?;
+            dir.to_string()
+        }
+        _ => {
+            let detected = StaticSiteBuilder::detect_publish_dir(build_path).await;
+            add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!("Auto-detected publish directory: {}", detected),
+            )
+            .await?;
+            detected
+        }
+    };
+
+    let settings = app.get_static_site_settings();
+    if let Some(ref command) = settings.build_command {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            &format!("Using build command: {}", command),
+        )
+        .await?;
+    }
+
+    Ok(StaticSiteConfig {
+        source_dir: build_path.to_string_lossy().to_string(),
+        publish_dir,
+        custom_build_command: settings.build_command,
+        custom_install_command: settings.install_command,
+        env_vars,
+        build_image: settings.build_image,
+        // Default to SPA mode for better client-side routing
+        spa_mode: settings.spa_mode.unwrap_or(true),
+        cpu_limit: build_limits.cpu_limit.clone(),
+        memory_limit: build_limits.memory_limit.clone(),
+        port: app.port as u16,
+        build_host: build_limits.build_host.clone(),
+        ..Default::default()
+    })
+}
+
 /// Build the image for a git-based deployment
 pub(super) async fn build_git_image(
     db: &DbPool,
@@ -311,8 +589,11 @@ pub(super) async fn build_git_image(
 ) -> Result<String> {
     let image_tag = format!("rivetr-{}:{}", app.name, deployment_id);
 
-    // If a build server is assigned, offload the Docker build step to that remote server
-    if let Some(ref build_server_id) = app.build_server_id {
+    let sandbox = sandbox_isolation(db, deployment_id, app, build_path, build_limits).await?;
+
+    // If a build server is assigned, offload the Docker build step to that remote server.
+    // Sandboxed builds always run locally, where the sandbox is enforced.
+    if let (Some(ref build_server_id), None) = (&app.build_server_id, &sandbox) {
         return run_remote_build(
             db,
             deployment_id,
@@ -324,6 +605,15 @@ pub(super) async fn build_git_image(
         )
         .await;
     }
+    if sandbox.is_some() && app.build_server_id.is_some() {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            "Building locally: sandboxed builds don't run on build servers",
+        )
+        .await?;
+    }
 
     let build_type = app.get_build_type();
 
@@ -338,15 +628,17 @@ pub(super) async fn build_git_image(
             )
             .await?;
 
-            // Check if Nixpacks is available
-            if !nixpacks::is_available().await {
-                anyhow::bail!(
-                    "Nixpacks is not installed. Please install it with: curl -sSL https://nixpacks.com/install.sh | bash"
-                );
-            }
+            // Get Nixpacks config if provided
+            let nixpacks_config = app.get_nixpacks_config();
+
+            // Resolve the Nixpacks binary, honouring a pinned version
+            let nixpacks_bin = nixpacks::resolve_binary(
+                nixpacks_config.as_ref().and_then(|c| c.version.as_deref()),
+            )
+            .await?;
 
             // Log Nixpacks version
-            if let Some(version) = nixpacks::get_version().await {
+            if let Some(version) = nixpacks::get_version(&nixpacks_bin).await {
                 add_deployment_log(
                     db,
                     deployment_id,
@@ -356,8 +648,6 @@ pub(super) async fn build_git_image(
                 .await?;
             }
 
-            // Get Nixpacks config if provided
-            let nixpacks_config = app.get_nixpacks_config();
             if nixpacks_config.is_some() {
                 add_deployment_log(
                     db,
@@ -389,6 +679,7 @@ pub(super) async fn build_git_image(
 
             // Build with Nixpacks (nix_log_tx is moved in, dropped when build_image returns)
             nixpacks::build_image(
+                &nixpacks_bin,
                 build_path,
                 &image_tag,
                 nixpacks_config.as_ref(),
@@ -419,60 +710,8 @@ pub(super) async fn build_git_image(
             )
             .await?;
 
-            // Get env vars for the build
-            let env_vars: Vec<(String, String)> = sqlx::query_as::<_, (String, String)>(
-                "SELECT key, value FROM env_vars WHERE app_id = ?",
-            )
-            .bind(&app.id)
-            .fetch_all(db)
-            .await
-            .unwrap_or_default();
-
-            // Determine publish directory - use app setting or auto-detect
-            let publish_dir = if let Some(ref dir) = app.publish_directory {
-                if !dir.is_empty() {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "info",
-                        &format!("Using configured publish directory: {}", dir),
-                    )
-                    .await?;
-                    dir.clone()
-                } else {
-                    let detected = StaticSiteBuilder::detect_publish_dir(build_path).await;
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "info",
-                        &format!("Auto-detected publish directory: {}", detected),
-                    )
-                    .await?;
-                    detected
-                }
-            } else {
-                let detected = StaticSiteBuilder::detect_publish_dir(build_path).await;
-                add_deployment_log(
-                    db,
-                    deployment_id,
-                    "info",
-                    &format!("Auto-detected publish directory: {}", detected),
-                )
-                .await?;
-                detected
-            };
-
-            // Create static site config
-            let static_config = StaticSiteConfig {
-                source_dir: build_path.to_string_lossy().to_string(),
-                publish_dir,
-                env_vars,
-                spa_mode: true, // Default to SPA mode for better client-side routing
-                cpu_limit: build_limits.cpu_limit.clone(),
-                memory_limit: build_limits.memory_limit.clone(),
-                port: app.port as u16,
-                ..Default::default()
-            };
+            let static_config =
+                static_site_config(db, deployment_id, app, build_path, build_limits).await?;
 
             // Build with StaticSiteBuilder
             let static_builder = StaticSiteBuilder::new(runtime.clone());
@@ -552,7 +791,7 @@ pub(super) async fn build_git_image(
             )
             .await?;
         }
-        "cnb" | "paketo" | "heroku-cnb" => {
+        "cnb" | "buildpacks" | "paketo" | "heroku-cnb" => {
             // Cloud Native Buildpacks build (Paketo/Heroku via pack CLI)
             add_deployment_log(
                 db,
@@ -580,13 +819,13 @@ pub(super) async fn build_git_image(
                 .await?;
             }
 
-            // Suggest best builder based on project files
-            let suggested_builder = pack_builder::suggest_builder(build_path).await;
+            // The app's builder, or the best one for the project files
+            let pack_config = pack_config_for(app, build_path).await;
             add_deployment_log(
                 db,
                 deployment_id,
                 "info",
-                &format!("Using CNB builder: {}", suggested_builder.image()),
+                &format!("Using CNB builder: {}", pack_config.get_builder_image()?),
             )
             .await?;
 
@@ -600,12 +839,6 @@ pub(super) async fn build_git_image(
             .unwrap_or_default();
 
             // Build with Pack CLI
-            let pack_config = pack_builder::PackConfig {
-                builder: suggested_builder,
-                trust_builder: true,
-                ..Default::default()
-            };
-
             pack_builder::build_image(build_path, &image_tag, Some(&pack_config), &env_vars)
                 .await
                 .context("Pack CLI build failed")?;
@@ -640,11 +873,12 @@ pub(super) async fn build_git_image(
                 }
             });
 
-            let build_secrets: Vec<(String, String)> = app
-                .get_build_secrets()
-                .into_iter()
-                .map(|s| (s.key, s.value))
-                .collect();
+            // Sandboxed builds never see build secrets
+            let build_secrets: Vec<(String, String)> = if sandbox.is_some() {
+                vec![]
+            } else {
+                app.decrypted_build_secrets(encryption_key)
+            };
 
             if !build_secrets.is_empty() {
                 add_deployment_log(
@@ -675,7 +909,10 @@ pub(super) async fn build_git_image(
                 tag: image_tag.clone(),
                 build_args: vec![],
                 build_target: app.build_target.clone(),
-                custom_options: app.custom_docker_options.clone(),
+                custom_options: app
+                    .custom_docker_options
+                    .clone()
+                    .filter(|_| sandbox.is_none()),
                 cpu_limit: build_limits.cpu_limit.clone(),
                 memory_limit: build_limits.memory_limit.clone(),
                 log_tx: Some(log_tx),
@@ -683,6 +920,12 @@ pub(super) async fn build_git_image(
                 build_platforms: app.build_platforms.clone(),
                 no_cache: app.disable_build_cache != 0,
                 source_commit,
+                cache: build_cache::for_app(build_limits, app, sandbox.is_some()),
+                build_host: build_limits
+                    .build_host
+                    .clone()
+                    .filter(|_| sandbox.is_none()),
+                isolation: sandbox.unwrap_or_default(),
             };
 
             // Log build resource limits if configured
@@ -735,11 +978,19 @@ pub(super) async fn build_git_image(
                 }
             }
 
+            if build_ctx.cache.is_some() {
+                add_deployment_log(db, deployment_id, "info", "Using persistent build cache")
+                    .await?;
+            }
             runtime.build(&build_ctx).await.context("Build failed")?;
+            let cache = build_ctx.cache.clone();
             // Drop build_ctx (closes log_tx) then wait for all buffered logs to reach DB
             drop(build_ctx);
             let _ = log_drain.await;
             add_deployment_log(db, deployment_id, "info", "Image built successfully").await?;
+            if let Some(ref cache) = cache {
+                build_cache::enforce_size_limit(db, deployment_id, build_limits, cache).await;
+            }
         }
     }
 
@@ -1161,8 +1412,10 @@ pub(super) async fn build_upload_image(
     app: &App,
     build_path: &Path,
     build_limits: &BuildLimits,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<String> {
     let image_tag = format!("rivetr-{}:{}", app.name, deployment_id);
+    let sandbox = sandbox_isolation(db, deployment_id, app, build_path, build_limits).await?;
     let build_type = app.get_build_type();
 
     match build_type {
@@ -1175,13 +1428,13 @@ pub(super) async fn build_upload_image(
             )
             .await?;
 
-            if !nixpacks::is_available().await {
-                anyhow::bail!(
-                    "Nixpacks is not installed. Please install it with: curl -sSL https://nixpacks.com/install.sh | bash"
-                );
-            }
+            let nixpacks_config = app.get_nixpacks_config();
+            let nixpacks_bin = nixpacks::resolve_binary(
+                nixpacks_config.as_ref().and_then(|c| c.version.as_deref()),
+            )
+            .await?;
 
-            if let Some(version) = nixpacks::get_version().await {
+            if let Some(version) = nixpacks::get_version(&nixpacks_bin).await {
                 add_deployment_log(
                     db,
                     deployment_id,
@@ -1191,7 +1444,6 @@ pub(super) async fn build_upload_image(
                 .await?;
             }
 
-            let nixpacks_config = app.get_nixpacks_config();
             let env_vars: Vec<(String, String)> = sqlx::query_as::<_, (String, String)>(
                 "SELECT key, value FROM env_vars WHERE app_id = ?",
             )
@@ -1210,6 +1462,7 @@ pub(super) async fn build_upload_image(
             });
 
             nixpacks::build_image(
+                &nixpacks_bin,
                 build_path,
                 &image_tag,
                 nixpacks_config.as_ref(),
@@ -1239,57 +1492,8 @@ pub(super) async fn build_upload_image(
             )
             .await?;
 
-            let env_vars: Vec<(String, String)> = sqlx::query_as::<_, (String, String)>(
-                "SELECT key, value FROM env_vars WHERE app_id = ?",
-            )
-            .bind(&app.id)
-            .fetch_all(db)
-            .await
-            .unwrap_or_default();
-
-            let publish_dir = if let Some(ref dir) = app.publish_directory {
-                if !dir.is_empty() {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "info",
-                        &format!("Using configured publish directory: {}", dir),
-                    )
-                    .await?;
-                    dir.clone()
-                } else {
-                    let detected = StaticSiteBuilder::detect_publish_dir(build_path).await;
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "info",
-                        &format!("Auto-detected publish directory: {}", detected),
-                    )
-                    .await?;
-                    detected
-                }
-            } else {
-                let detected = StaticSiteBuilder::detect_publish_dir(build_path).await;
-                add_deployment_log(
-                    db,
-                    deployment_id,
-                    "info",
-                    &format!("Auto-detected publish directory: {}", detected),
-                )
-                .await?;
-                detected
-            };
-
-            let static_config = StaticSiteConfig {
-                source_dir: build_path.to_string_lossy().to_string(),
-                publish_dir,
-                env_vars,
-                spa_mode: true,
-                cpu_limit: build_limits.cpu_limit.clone(),
-                memory_limit: build_limits.memory_limit.clone(),
-                port: app.port as u16,
-                ..Default::default()
-            };
+            let static_config =
+                static_site_config(db, deployment_id, app, build_path, build_limits).await?;
 
             let static_builder = StaticSiteBuilder::new(runtime.clone());
             static_builder
@@ -1364,7 +1568,7 @@ pub(super) async fn build_upload_image(
             )
             .await?;
         }
-        "cnb" | "paketo" | "heroku-cnb" => {
+        "cnb" | "buildpacks" | "paketo" | "heroku-cnb" => {
             add_deployment_log(
                 db,
                 deployment_id,
@@ -1389,12 +1593,12 @@ pub(super) async fn build_upload_image(
                 .await?;
             }
 
-            let suggested_builder = pack_builder::suggest_builder(build_path).await;
+            let pack_config = pack_config_for(app, build_path).await;
             add_deployment_log(
                 db,
                 deployment_id,
                 "info",
-                &format!("Using CNB builder: {}", suggested_builder.image()),
+                &format!("Using CNB builder: {}", pack_config.get_builder_image()?),
             )
             .await?;
 
@@ -1406,12 +1610,6 @@ pub(super) async fn build_upload_image(
             .await
             .unwrap_or_default();
 
-            let pack_config = pack_builder::PackConfig {
-                builder: suggested_builder,
-                trust_builder: true,
-                ..Default::default()
-            };
-
             pack_builder::build_image(build_path, &image_tag, Some(&pack_config), &env_vars)
                 .await
                 .context("Pack CLI build failed")?;
@@ -1449,11 +1647,11 @@ pub(super) async fn build_upload_image(
                 }
             });
 
-            let build_secrets2: Vec<(String, String)> = app
-                .get_build_secrets()
-                .into_iter()
-                .map(|s| (s.key, s.value))
-                .collect();
+            let build_secrets2: Vec<(String, String)> = if sandbox.is_some() {
+                vec![]
+            } else {
+                app.decrypted_build_secrets(encryption_key)
+            };
 
             if !build_secrets2.is_empty() {
                 add_deployment_log(
@@ -1474,7 +1672,10 @@ pub(super) async fn build_upload_image(
                 tag: image_tag.clone(),
                 build_args: vec![],
                 build_target: app.build_target.clone(),
-                custom_options: app.custom_docker_options.clone(),
+                custom_options: app
+                    .custom_docker_options
+                    .clone()
+                    .filter(|_| sandbox.is_none()),
                 cpu_limit: build_limits.cpu_limit.clone(),
                 memory_limit: build_limits.memory_limit.clone(),
                 log_tx: Some(log_tx2),
@@ -1482,15 +1683,86 @@ pub(super) async fn build_upload_image(
                 build_platforms: app.build_platforms.clone(),
                 no_cache: app.disable_build_cache != 0,
                 source_commit: None,
+                cache: build_cache::for_app(build_limits, app, sandbox.is_some()),
+                build_host: build_limits
+                    .build_host
+                    .clone()
+                    .filter(|_| sandbox.is_none()),
+                isolation: sandbox.unwrap_or_default(),
             };
 
+            if build_ctx.cache.is_some() {
+                add_deployment_log(db, deployment_id, "info", "Using persistent build cache")
+                    .await?;
+            }
             runtime.build(&build_ctx).await.context("Build failed")?;
+            let cache = build_ctx.cache.clone();
             // Drop build_ctx (closes log_tx2) then wait for all buffered logs to reach DB
             drop(build_ctx);
             let _ = log_drain2.await;
             add_deployment_log(db, deployment_id, "info", "Image built successfully").await?;
+            if let Some(ref cache) = cache {
+                build_cache::enforce_size_limit(db, deployment_id, build_limits, cache).await;
+            }
         }
     }
 
     Ok(image_tag)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(url: &str) -> PushTarget {
+        PushTarget {
+            url: url.to_string(),
+            username: Some("rivetr".to_string()),
+            password: Some("secret".to_string()),
+        }
+    }
+
+    #[test]
+    fn push_target_holds_only_its_own_images() {
+        let target = target("registry.example.com/rivetr");
+        assert!(target.holds("registry.example.com/rivetr/web:abc123"));
+        assert!(!target.holds("registry.example.com/rivetr-other/web:abc123"));
+        assert!(!target.holds("rivetr-web:abc123"));
+    }
+
+    #[test]
+    fn push_target_logs_in_to_the_registry_host() {
+        let auth = target("registry.example.com/rivetr").auth().unwrap();
+        assert_eq!(auth.server.as_deref(), Some("registry.example.com"));
+
+        let anonymous = PushTarget {
+            username: None,
+            password: None,
+            ..target("registry.example.com")
+        };
+        assert!(anonymous.auth().is_none());
+    }
+
+    #[test]
+    fn global_registry_url_ignores_blank_and_trailing_slash() {
+        let mut registry = RegistryConfig {
+            url: Some("registry.example.com/rivetr/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(registry.url(), Some("registry.example.com/rivetr"));
+        registry.url = Some(String::new());
+        assert_eq!(registry.url(), None);
+    }
+
+    #[test]
+    fn version_label_prefers_the_short_commit_sha() {
+        let sha = "3f2a9c1d8e7b6a5f4e3d2c1b0a9f8e7d6c5b4a39";
+        assert_eq!(version_label(Some(sha), "0b6f1c2e-1111"), "3f2a9c1");
+        assert_eq!(version_label(None, "0b6f1c2e-1111"), "0b6f1c2e");
+        assert_eq!(version_label(Some(""), "0b6f1c2e-1111"), "0b6f1c2e");
+        assert_eq!(
+            version_label(Some("/tmp/rivetr-upload-42"), "0b6f1c2e-1111"),
+            "0b6f1c2e"
+        );
+    }
+}
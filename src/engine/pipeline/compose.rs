@@ -0,0 +1,409 @@
+//! Docker Compose repository deployments.
+//!
+//! Apps with the "docker-compose" build type deploy the compose file of their
+//! repository as a stack (project `rivetr-app-<name>`) instead of building a
+//! single image. The app's env vars are available for `${VAR}` interpolation
+//! in the file, every service joins the shared `rivetr` network, and the web
+//! service (`compose_service`, the first service by default) publishes the app
+//! port on localhost so the proxy routes the app's domains to it. Redeploys
+//! (pushes, manual deploys) run `up` again, which only recreates the services
+//! that changed.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+
+use crate::api::services::compose::{
+    inject_resource_limits, inject_rivetr_network, parse_published_port, run_compose_command,
+    run_compose_command_streaming, validate_compose_content,
+};
+use crate::db::App;
+use crate::proxy::{probe_backend, Backend, HealthProbe};
+use crate::DbPool;
+
+use super::super::deploy_phases::{self, DeployPhase};
+use super::super::{add_deployment_log, update_deployment_status, KEY_LENGTH};
+use super::{app_source_dir, start::collect_env_vars, DeploymentResult};
+
+/// Compose files looked up when the app doesn't name one, in order
+const DEFAULT_COMPOSE_FILES: &[&str] = &[
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "compose.yml",
+    "compose.yaml",
+];
+
+/// Compose file written next to the repository's, with Rivetr's additions
+const GENERATED_COMPOSE_FILE: &str = ".rivetr-compose.yml";
+
+/// Env file holding the app's env vars for interpolation
+const GENERATED_ENV_FILE: &str = ".rivetr.env";
+
+/// Compose project of an app's stack
+pub fn compose_project_name(app: &App) -> String {
+    format!("rivetr-app-{}", app.name)
+}
+
+/// Directory the stack of an app runs from. It outlives the deployment, so
+/// bind mounts of repository files keep working.
+fn stack_dir(app: &App) -> PathBuf {
+    std::env::temp_dir().join(format!("rivetr-compose-{}", app.id))
+}
+
+/// Tear down the stack of a compose app and remove its directory
+pub async fn remove_compose_stack(app: &App) {
+    let project = compose_project_name(app);
+    if let Err(e) = run_compose_command(
+        &std::env::temp_dir(),
+        &project,
+        &["down", "--remove-orphans"],
+    )
+    .await
+    {
+        tracing::warn!(project = %project, error = %e, "Failed to remove compose stack");
+    }
+    let _ = tokio::fs::remove_dir_all(stack_dir(app)).await;
+}
+
+/// Find the compose file of a checkout: `compose_file` when the app names
+/// one, otherwise the first of the conventional names that exists
+fn find_compose_file(project_dir: &Path, compose_file: Option<&str>) -> Option<PathBuf> {
+    match compose_file.filter(|f| !f.is_empty()) {
+        Some(file) => Some(project_dir.join(file)).filter(|p| p.is_file()),
+        None => DEFAULT_COMPOSE_FILES
+            .iter()
+            .map(|name| project_dir.join(name))
+            .find(|p| p.is_file()),
+    }
+}
+
+/// Prepare a repository's compose file for deployment: every service joins
+/// the `rivetr` network and, when `port` is set, the web service publishes it
+/// on an ephemeral localhost port for the proxy. Returns the compose content
+/// and the name of the web service (the first one unless `web_service` names
+/// one).
+pub fn prepare_compose(
+    content: &str,
+    web_service: Option<&str>,
+    port: Option<u16>,
+) -> Result<(String, String), String> {
+    validate_compose_content(content)?;
+    let mut yaml: serde_yaml::Value =
+        serde_yaml::from_str(content).map_err(|e| format!("Invalid YAML: {}", e))?;
+
+    let services = yaml
+        .get_mut("services")
+        .and_then(|s| s.as_mapping_mut())
+        .ok_or_else(|| "The 'services' key of the compose file must be a mapping".to_string())?;
+
+    let service = match web_service.filter(|s| !s.is_empty()) {
+        Some(name) if services.contains_key(name) => name.to_string(),
+        Some(name) => return Err(format!("Service '{}' not found in compose file", name)),
+        None => services
+            .keys()
+            .next()
+            .and_then(|k| k.as_str())
+            .map(String::from)
+            .ok_or_else(|| "Compose file defines no services".to_string())?,
+    };
+
+    if let Some(port) = port {
+        let svc = services
+            .get_mut(service.as_str())
+            .and_then(|s| s.as_mapping_mut())
+            .ok_or_else(|| format!("Service '{}' must be a mapping", service))?;
+        let binding = serde_yaml::Value::String(format!("127.0.0.1::{}", port));
+        let ports = svc
+            .entry(serde_yaml::Value::String("ports".to_string()))
+            .or_insert_with(|| serde_yaml::Value::Sequence(vec![]));
+        let Some(seq) = ports.as_sequence_mut() else {
+            return Err(format!("The ports of service '{}' must be a list", service));
+        };
+        if !seq.contains(&binding) {
+            seq.push(binding);
+        }
+    }
+
+    let content =
+        serde_yaml::to_string(&yaml).map_err(|e| format!("Failed to serialize YAML: {}", e))?;
+    Ok((inject_rivetr_network(&content)?, service))
+}
+
+/// Render env vars as a compose env file. Values are double-quoted with
+/// escapes, and `$` is doubled so values are never interpolated themselves.
+pub fn env_file_content(vars: &[(String, String)]) -> String {
+    vars.iter()
+        .map(|(key, value)| {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('$', "$$");
+            format!("{}=\"{}\"\n", key, escaped)
+        })
+        .collect()
+}
+
+/// Deploy the compose stack of a "docker-compose" app from a checkout of its
+/// source. The checkout is moved to the app's stack directory.
+pub(super) async fn run_compose_deployment(
+    db: &DbPool,
+    deployment_id: &str,
+    app: &App,
+    work_dir: &Path,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<DeploymentResult> {
+    deploy_phases::begin(db, deployment_id, DeployPhase::Build).await;
+    update_deployment_status(db, deployment_id, "building", None).await?;
+
+    let stack_dir = stack_dir(app);
+    let _ = tokio::fs::remove_dir_all(&stack_dir).await;
+    tokio::fs::rename(work_dir, &stack_dir)
+        .await
+        .context("Failed to move the checkout to the stack directory")?;
+    let project_dir = app_source_dir(&stack_dir, app);
+
+    let compose_path =
+        find_compose_file(&project_dir, app.compose_file.as_deref()).ok_or_else(|| {
+            match app.compose_file.as_deref().filter(|f| !f.is_empty()) {
+                Some(file) => anyhow::anyhow!("Compose file {} not found in the repository", file),
+                None => anyhow::anyhow!(
+                    "No compose file found (looked for {})",
+                    DEFAULT_COMPOSE_FILES.join(", ")
+                ),
+            }
+        })?;
+    let compose_dir = compose_path.parent().unwrap_or(&project_dir).to_path_buf();
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Deploying compose file {}",
+            compose_path
+                .strip_prefix(&stack_dir)
+                .unwrap_or(&compose_path)
+                .display()
+        ),
+    )
+    .await?;
+
+    let content = tokio::fs::read_to_string(&compose_path)
+        .await
+        .context("Failed to read compose file")?;
+    let port = Some(app.container_port()).filter(|p| *p != 0);
+    let (content, web_service) = prepare_compose(&content, app.compose_service.as_deref(), port)
+        .map_err(|e| anyhow::anyhow!("Invalid compose file: {}", e))?;
+    let content = inject_resource_limits(
+        &content,
+        app.cpu_limit.as_deref(),
+        app.memory_limit.as_deref(),
+    )
+    .map_err(anyhow::Error::msg)?;
+    tokio::fs::write(compose_dir.join(GENERATED_COMPOSE_FILE), content)
+        .await
+        .context("Failed to write compose file")?;
+
+    // The app's env vars override the repository's .env for interpolation
+    let env_vars = collect_env_vars(db, app, encryption_key, Some(deployment_id)).await;
+    tokio::fs::write(
+        compose_dir.join(GENERATED_ENV_FILE),
+        env_file_content(&env_vars),
+    )
+    .await
+    .context("Failed to write env file")?;
+
+    let project = compose_project_name(app);
+    let mut file_args = vec!["-f", GENERATED_COMPOSE_FILE];
+    if compose_dir.join(".env").is_file() {
+        file_args.extend(["--env-file", ".env"]);
+    }
+    file_args.extend(["--env-file", GENERATED_ENV_FILE]);
+
+    // Stream `up` output into the deployment log as it arrives
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn({
+        let db = db.clone();
+        let deployment_id = deployment_id.to_string();
+        async move {
+            while let Some(line) = rx.recv().await {
+                let _ = add_deployment_log(&db, &deployment_id, "info", &line).await;
+            }
+        }
+    });
+    let up_args: Vec<&str> = file_args
+        .iter()
+        .copied()
+        .chain(["up", "-d", "--build", "--remove-orphans"])
+        .collect();
+    let up_result =
+        run_compose_command_streaming(&compose_dir, &project, &up_args, |line, _is_stderr| {
+            if !line.trim().is_empty() {
+                let _ = tx.send(line.to_string());
+            }
+        })
+        .await;
+    drop(tx);
+    let _ = writer.await;
+    up_result.map_err(|e| anyhow::anyhow!("docker compose up failed: {}", e.trim()))?;
+
+    // The web service's container is the one the app's domains route to
+    let ps_args: Vec<&str> = file_args
+        .iter()
+        .copied()
+        .chain(["ps", "-q", web_service.as_str()])
+        .collect();
+    let container_id = run_compose_command(&compose_dir, &project, &ps_args)
+        .await
+        .ok()
+        .and_then(|out| out.lines().next().map(|l| l.trim().to_string()))
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Service '{}' has no running container", web_service))?;
+
+    let host_port = match port {
+        Some(port) => {
+            let port_str = port.to_string();
+            let port_args: Vec<&str> = file_args
+                .iter()
+                .copied()
+                .chain(["port", web_service.as_str(), port_str.as_str()])
+                .collect();
+            let output = run_compose_command(&compose_dir, &project, &port_args)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to look up published port: {}", e.trim()))?;
+            Some(parse_published_port(&output).with_context(|| {
+                format!("Service '{}' does not publish port {}", web_service, port)
+            })?)
+        }
+        None => None,
+    };
+
+    sqlx::query("UPDATE deployments SET container_id = ? WHERE id = ?")
+        .bind(&container_id)
+        .bind(deployment_id)
+        .execute(db)
+        .await?;
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Stack {} is up, routing to service {}",
+            project, web_service
+        ),
+    )
+    .await?;
+
+    // Health check the web service when one is configured
+    let health_probe = app.health_probe();
+    if let Some(host_port) = host_port {
+        if app.healthcheck.is_some() || health_probe != HealthProbe::Http {
+            deploy_phases::begin(db, deployment_id, DeployPhase::Healthcheck).await;
+            update_deployment_status(db, deployment_id, "checking", None).await?;
+            let backend = Backend::new(container_id.clone(), "127.0.0.1".to_string(), host_port)
+                .with_healthcheck(app.healthcheck.clone())
+                .with_health_probe(health_probe);
+            let client = reqwest::Client::new();
+
+            let mut healthy = false;
+            for attempt in 1..=app.health_check_attempts() {
+                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                match probe_backend(&backend, &client, None, std::time::Duration::from_secs(10))
+                    .await
+                {
+                    Ok(_) => {
+                        healthy = true;
+                        break;
+                    }
+                    Err(reason) => {
+                        add_deployment_log(
+                            db,
+                            deployment_id,
+                            "warn",
+                            &format!("Health check attempt {}: {}", attempt, reason),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            if !healthy {
+                anyhow::bail!(
+                    "Health check of service '{}' failed within {}s",
+                    web_service,
+                    app.healthcheck_window_secs
+                );
+            }
+            add_deployment_log(db, deployment_id, "info", "Health check passed").await?;
+        }
+    }
+
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        "Deployment completed successfully",
+    )
+    .await?;
+    update_deployment_status(db, deployment_id, "running", None).await?;
+
+    Ok(DeploymentResult {
+        container_id,
+        image_tag: project,
+        port: host_port,
+        auto_rollback_from: None,
+        old_container_ids: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = r#"
+services:
+  db:
+    image: postgres:16
+  web:
+    build: .
+    ports:
+      - "8080:3000"
+"#;
+
+    #[test]
+    fn test_prepare_compose() {
+        let (content, service) = prepare_compose(COMPOSE, Some("web"), Some(3000)).unwrap();
+        assert_eq!(service, "web");
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        let ports = yaml["services"]["web"]["ports"].as_sequence().unwrap();
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[1].as_str(), Some("127.0.0.1::3000"));
+        assert!(yaml["services"]["db"].get("ports").is_none());
+        assert!(yaml["networks"].get("rivetr").is_some());
+
+        // Without a named service the first one serves traffic
+        let (_, service) = prepare_compose(COMPOSE, None, None).unwrap();
+        assert_eq!(service, "db");
+        assert!(prepare_compose(COMPOSE, Some("api"), Some(3000)).is_err());
+        assert!(prepare_compose("version: '3'", None, None).is_err());
+    }
+
+    #[test]
+    fn test_env_file_content() {
+        let vars = vec![
+            ("PLAIN".to_string(), "value".to_string()),
+            ("TRICKY".to_string(), "a\"b$c\nd".to_string()),
+        ];
+        assert_eq!(
+            env_file_content(&vars),
+            "PLAIN=\"value\"\nTRICKY=\"a\\\"b$$c\\nd\"\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_published_port() {
+        assert_eq!(parse_published_port("127.0.0.1:49153\n"), Some(49153));
+        assert_eq!(parse_published_port("[::]:8080"), Some(8080));
+        assert_eq!(parse_published_port(""), None);
+    }
+}
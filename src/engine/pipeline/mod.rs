@@ -1,17 +1,31 @@
 mod build;
 mod clone;
+mod compose;
 mod rollback;
+mod rolling;
 mod start;
 
-pub use rollback::run_rollback;
+pub use build::version_repository;
+pub use compose::{compose_project_name, remove_compose_stack};
+pub use rollback::{run_instant_rollback, run_rollback};
+pub(crate) use start::{build_run_config, collect_env_vars};
 
 use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::db::App;
+use crate::db::{App, SshKey};
+use crate::proxy::RouteTable;
 use crate::runtime::{ContainerRuntime, RegistryAuth};
 use crate::DbPool;
 
+use super::config_snapshot;
+use super::deploy_phases::{self, DeployPhase};
+use super::fences::DeployFences;
+use super::pipeline_file;
+use super::scan;
+use super::test_stage;
 use super::{add_deployment_log, update_deployment_status, BuildLimits, KEY_LENGTH};
 
 /// Information about a successfully deployed container
@@ -25,12 +39,40 @@ pub struct DeploymentResult {
     pub old_container_ids: Vec<String>,
 }
 
-/// Error returned when health check fails and auto-rollback is triggered
+/// Check a new container failed, making the deployment roll back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollbackCause {
+    HealthCheck,
+    SmokeTests,
+}
+
+impl RollbackCause {
+    /// "Health check failed" / "Smoke tests failed"
+    pub fn failure(self) -> &'static str {
+        match self {
+            Self::HealthCheck => "Health check failed",
+            Self::SmokeTests => "Smoke tests failed",
+        }
+    }
+
+    /// What the new version failed, e.g. "its health check within 60s"
+    pub fn describe(self, app: &App) -> String {
+        match self {
+            Self::HealthCheck => {
+                format!("its health check within {}s", app.healthcheck_window_secs)
+            }
+            Self::SmokeTests => "its smoke tests".to_string(),
+        }
+    }
+}
+
+/// Error returned when a new container fails its checks and auto-rollback is triggered
 #[derive(Debug)]
 pub struct AutoRollbackTriggered {
     pub failed_deployment_id: String,
     pub rollback_deployment_id: String,
     pub target_deployment_id: String,
+    pub cause: RollbackCause,
     /// Old container IDs to stop AFTER proxy routes are updated to the rollback container
     pub old_container_ids: Vec<String>,
 }
@@ -39,7 +81,8 @@ impl std::fmt::Display for AutoRollbackTriggered {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Health check failed, auto-rollback triggered to deployment {}",
+            "{}, auto-rollback triggered to deployment {}",
+            self.cause.failure(),
             self.target_deployment_id
         )
     }
@@ -58,6 +101,7 @@ async fn run_registry_deployment(
         .get_full_image_reference()
         .ok_or_else(|| anyhow::anyhow!("Docker image not configured"))?;
 
+    deploy_phases::begin(db, deployment_id, DeployPhase::Build).await;
     add_deployment_log(
         db,
         deployment_id,
@@ -96,11 +140,11 @@ async fn run_upload_deployment(
     app: &App,
     source_path: &str,
     build_limits: &BuildLimits,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<String> {
-    use std::path::PathBuf;
-
     let work_dir = PathBuf::from(source_path);
 
+    deploy_phases::begin(db, deployment_id, DeployPhase::Build).await;
     add_deployment_log(db, deployment_id, "info", "Using uploaded source files...").await?;
     update_deployment_status(db, deployment_id, "building", None).await?;
 
@@ -115,9 +159,22 @@ async fn run_upload_deployment(
         work_dir.clone()
     };
 
-    let image_tag =
-        build::build_upload_image(db, runtime, deployment_id, app, &build_path, build_limits)
-            .await?;
+    // A pipeline file in the source replaces the app's build settings
+    let pipeline = pipeline_file::load(db, deployment_id, &build_path).await?;
+    let overridden = pipeline.map(|p| p.apply(app));
+    let app = overridden.as_ref().unwrap_or(app);
+
+    let image_tag = build::build_upload_image(
+        db,
+        runtime,
+        deployment_id,
+        app,
+        &build_path,
+        build_limits,
+        encryption_key,
+    )
+    .await?;
+    super::processes::store_procfile(db, deployment_id, &build_path).await?;
 
     // Cleanup work directory after build
     let _ = tokio::fs::remove_dir_all(&work_dir).await;
@@ -135,6 +192,7 @@ async fn run_inline_dockerfile_deployment(
     build_limits: &BuildLimits,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<String> {
+    deploy_phases::begin(db, deployment_id, DeployPhase::Build).await;
     add_deployment_log(
         db,
         deployment_id,
@@ -175,17 +233,15 @@ async fn run_inline_dockerfile_deployment(
     Ok(image_tag)
 }
 
-/// Handle git-based deployment (clone and build)
-async fn run_git_deployment(
+/// Clone the app's repository for a deployment, check out the requested
+/// commit or tag and apply the app's deployment patches. Returns the work
+/// directory; callers remove it when they are done.
+async fn checkout_git_source(
     db: &DbPool,
-    runtime: Arc<dyn ContainerRuntime>,
     deployment_id: &str,
     app: &App,
-    build_limits: &BuildLimits,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
-) -> Result<String> {
-    use std::path::PathBuf;
-
+) -> Result<PathBuf> {
     let work_dir = std::env::temp_dir().join(format!("rivetr-{}", deployment_id));
 
     // Check if this deployment targets a specific commit or tag
@@ -206,6 +262,7 @@ async fn run_git_deployment(
     let needs_full_clone = target_commit_sha.is_some() || target_git_tag.is_some();
 
     // Step 1: Clone
+    deploy_phases::begin(db, deployment_id, DeployPhase::Clone).await;
     add_deployment_log(
         db,
         deployment_id,
@@ -221,10 +278,17 @@ async fn run_git_deployment(
     // Build the effective clone URL (inject OAuth/PAT/GitHub App token for HTTPS repos)
     let clone_url = clone::get_authenticated_url(db, app, encryption_key).await?;
 
+    let submodules = app.git_submodules != 0;
     let clone_opts = clone::CloneOptions {
         shallow: app.shallow_clone != 0,
-        submodules: app.git_submodules != 0,
+        submodules,
         lfs: app.git_lfs != 0,
+        // Private submodules on the app's git host reuse its provider token
+        git_config: if submodules {
+            clone::submodule_auth_config(&clone_url)
+        } else {
+            Vec::new()
+        },
     };
 
     if needs_full_clone {
@@ -265,16 +329,38 @@ async fn run_git_deployment(
         add_deployment_log(db, deployment_id, "info", "Tag checked out successfully").await?;
     }
 
-    // Update deployment record with actual commit SHA and message from the checked-out HEAD
-    if let Ok(commit_info) = clone::get_git_commit_info(&work_dir).await {
-        sqlx::query("UPDATE deployments SET commit_sha = ?, commit_message = ? WHERE id = ?")
-            .bind(&commit_info.0)
-            .bind(&commit_info.1)
-            .bind(deployment_id)
-            .execute(db)
+    // The full clone skips submodules and LFS; fetch them for the checked-out ref
+    if needs_full_clone && (clone_opts.submodules || clone_opts.lfs) {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            "Fetching submodules and LFS objects",
+        )
+        .await?;
+        clone::fetch_submodules_and_lfs(&clone_url, &work_dir, ssh_key.as_ref(), &clone_opts)
             .await?;
     }
 
+    // Update deployment record with actual commit SHA, message and author from the
+    // checked-out HEAD. The branch is only known for branch (not tag) deployments.
+    if let Ok(commit_info) = clone::get_git_commit_info(&work_dir).await {
+        let author = clone::get_git_commit_author(&work_dir).await;
+        let branch = target_git_tag.is_none().then(|| app.branch.clone());
+        sqlx::query(
+            "UPDATE deployments SET commit_sha = ?, commit_message = ?, \
+             commit_author = COALESCE(?, commit_author), \
+             commit_branch = COALESCE(commit_branch, ?) WHERE id = ?",
+        )
+        .bind(&commit_info.0)
+        .bind(&commit_info.1)
+        .bind(&author)
+        .bind(&branch)
+        .bind(deployment_id)
+        .execute(db)
+        .await?;
+    }
+
     // Step 1c: Apply deployment patches (file injection)
     {
         let patches = sqlx::query_as::<_, crate::db::AppPatch>(
@@ -377,19 +463,68 @@ async fn run_git_deployment(
         }
     }
 
+    Ok(work_dir)
+}
+
+/// Shallow-clone the app's branch into `work_dir` to inspect its source
+/// outside of a deployment. Returns the app's source directory; callers
+/// remove `work_dir` when they are done.
+pub async fn clone_app_source(
+    db: &DbPool,
+    app: &App,
+    work_dir: &Path,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<PathBuf> {
+    let ssh_key = clone::get_ssh_key_for_app(db, app).await?;
+    let clone_url = clone::get_authenticated_url(db, app, encryption_key).await?;
+    clone::clone_repository(
+        &clone_url,
+        &app.branch,
+        &work_dir.to_path_buf(),
+        ssh_key.as_ref(),
+        &clone::CloneOptions::default(),
+    )
+    .await?;
+    Ok(app_source_dir(work_dir, app))
+}
+
+/// Check that the SSH repository `git_url` is reachable with `ssh_key`,
+/// through the key's jump host if it has one
+pub async fn check_ssh_key_access(ssh_key: &SshKey, git_url: &str) -> Result<()> {
+    if !clone::is_ssh_url(git_url) {
+        anyhow::bail!("{} is not an SSH git URL", git_url);
+    }
+    clone::ls_remote_with_ssh_key(git_url, ssh_key).await
+}
+
+/// Directory of the app's source within a checkout (its base directory)
+fn app_source_dir(work_dir: &Path, app: &App) -> PathBuf {
+    match app.base_directory.as_deref() {
+        Some(base_dir) if !base_dir.is_empty() => work_dir.join(base_dir),
+        _ => work_dir.to_path_buf(),
+    }
+}
+
+/// Handle git-based deployment (clone and build)
+async fn run_git_deployment(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    build_limits: &BuildLimits,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<String> {
+    let work_dir = checkout_git_source(db, deployment_id, app, encryption_key).await?;
+
     // Step 2: Build
+    deploy_phases::begin(db, deployment_id, DeployPhase::Build).await;
     update_deployment_status(db, deployment_id, "building", None).await?;
+    let build_path = app_source_dir(&work_dir, app);
 
-    // Determine the actual build path (consider base_directory)
-    let build_path: PathBuf = if let Some(ref base_dir) = app.base_directory {
-        if !base_dir.is_empty() {
-            work_dir.join(base_dir)
-        } else {
-            work_dir.clone()
-        }
-    } else {
-        work_dir.clone()
-    };
+    // A pipeline file in the source replaces the app's build settings
+    let pipeline = pipeline_file::load(db, deployment_id, &build_path).await?;
+    let overridden = pipeline.map(|p| p.apply(app));
+    let app = overridden.as_ref().unwrap_or(app);
 
     let image_tag = build::build_git_image(
         db,
@@ -401,6 +536,7 @@ async fn run_git_deployment(
         encryption_key,
     )
     .await?;
+    super::processes::store_procfile(db, deployment_id, &build_path).await?;
 
     // Cleanup work directory
     let _ = tokio::fs::remove_dir_all(&work_dir).await;
@@ -408,6 +544,51 @@ async fn run_git_deployment(
     Ok(image_tag)
 }
 
+/// Build (or pull) the image for a pre-warm deployment without starting it.
+/// The returned tag is stored on the deployment so promoting it skips the build.
+pub async fn build_prewarm_image(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    build_limits: &BuildLimits,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<String> {
+    bail_if_cancelled(db, deployment_id).await?;
+
+    if app.uses_registry_image() {
+        run_registry_deployment(db, runtime, deployment_id, app).await
+    } else if let Some(ref dockerfile_content) =
+        app.inline_dockerfile.clone().filter(|s| !s.is_empty())
+    {
+        run_inline_dockerfile_deployment(
+            db,
+            runtime,
+            deployment_id,
+            app,
+            dockerfile_content,
+            build_limits,
+            encryption_key,
+        )
+        .await
+    } else if app.deployment_source.as_deref() == Some("upload") {
+        anyhow::bail!("Upload-based apps cannot be pre-warmed; upload a new archive instead")
+    } else {
+        run_git_deployment(
+            db,
+            runtime,
+            deployment_id,
+            app,
+            build_limits,
+            encryption_key,
+        )
+        .await
+    }
+}
+
+/// Container label holding the ID of the deployment that started the container
+pub(crate) const DEPLOYMENT_LABEL: &str = "rivetr.deployment";
+
 /// Bail out of the pipeline if the deployment has been cancelled — either
 /// explicitly by a user or implicitly by a newer deployment that superseded it.
 /// Called at pipeline checkpoints so a stale build stops before it wastes work
@@ -423,17 +604,99 @@ async fn bail_if_cancelled(db: &DbPool, deployment_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Undo what a cancelled deployment left behind when its pipeline was aborted
+/// mid-flight: the clone/upload directory, the container it started (if any)
+/// and the "-prev" rename of the container that is still serving the app.
+/// Build processes are killed when the aborted pipeline future is dropped.
+pub async fn cleanup_cancelled(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+) {
+    deploy_phases::end(db, deployment_id, "cancelled").await;
+
+    let work_dir = std::env::temp_dir().join(format!("rivetr-{}", deployment_id));
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+
+    let deployment: Option<(Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT container_id, commit_sha FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await
+            .ok()
+            .flatten();
+    let Some((container_id, commit_sha)) = deployment else {
+        return;
+    };
+    if let Some(source_path) = commit_sha.filter(|path| path.contains("rivetr-upload-")) {
+        let _ = tokio::fs::remove_dir_all(&source_path).await;
+    }
+
+    // The recorded container, plus any the pipeline started but was aborted
+    // before recording (and extra replicas)
+    let mut container_ids: Vec<String> = container_id.into_iter().collect();
+    let label = format!("{}={}", DEPLOYMENT_LABEL, deployment_id);
+    match runtime.list_labeled_containers(&label).await {
+        Ok(containers) => {
+            for container in containers {
+                if !container_ids.contains(&container.id) {
+                    container_ids.push(container.id);
+                }
+            }
+        }
+        Err(e) => tracing::warn!(
+            deployment_id = %deployment_id,
+            error = %e,
+            "Failed to list containers of cancelled deployment"
+        ),
+    }
+    for container_id in &container_ids {
+        let _ = runtime.stop(container_id).await;
+        let _ = runtime.remove(container_id).await;
+        let _ = sqlx::query("DELETE FROM app_replicas WHERE container_id = ?")
+            .bind(container_id)
+            .execute(db)
+            .await;
+    }
+
+    // Give the old container its canonical name back so restarts and the next
+    // deployment find it
+    let container_name = app
+        .custom_container_name
+        .as_ref()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("rivetr-{}", app.name));
+    let prev_name = format!("{}-prev", container_name);
+    if runtime.inspect(&prev_name).await.is_ok() && runtime.inspect(&container_name).await.is_err()
+    {
+        if let Err(e) = runtime.rename_container(&prev_name, &container_name).await {
+            tracing::warn!(
+                error = %e,
+                "Failed to rename old container back after deployment cancellation"
+            );
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run_deployment(
     db: &DbPool,
     runtime: Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
     deployment_id: &str,
     app: &App,
     build_limits: &BuildLimits,
+    fences: &DeployFences,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<DeploymentResult> {
     // Check if the deployment was cancelled while it was queued (before the pipeline picked it up)
     bail_if_cancelled(db, deployment_id).await?;
 
+    // Record the configuration this deployment runs with for the deployment diff
+    config_snapshot::record(db, deployment_id, app, encryption_key).await;
+
     // Log remote deployment intent if a server is assigned to this app
     if let Some(ref server_id) = app.server_id {
         add_deployment_log(
@@ -463,14 +726,23 @@ pub async fn run_deployment(
         })
         .unwrap_or((None, None));
 
-    // Determine the image to use based on deployment source.
-    // `remote_image_tag` is Some(...) when the image was pushed to a registry —
-    // after start_container stores the local image tag we overwrite it with the
-    // remote reference so rollbacks can pull from registry.
-    let (image_tag, remote_image_tag): (String, Option<String>) = if app.uses_registry_image() {
+    // Compose apps deploy the stack of their repository instead of an image
+    if app.uses_docker_compose() && !app.uses_registry_image() {
+        let work_dir = match upload_source_path {
+            Some(source_path) => PathBuf::from(source_path),
+            None => checkout_git_source(db, deployment_id, app, encryption_key).await?,
+        };
+        bail_if_cancelled(db, deployment_id).await?;
+        return compose::run_compose_deployment(db, deployment_id, app, &work_dir, encryption_key)
+            .await;
+    }
+
+    // Determine the image to use based on deployment source. `built` is true
+    // when the image was built here, so it is pushed to a registry if one is set.
+    let (image_tag, built): (String, bool) = if app.uses_registry_image() {
         // Registry-based deployment: pull pre-built image (no push needed)
         let tag = run_registry_deployment(db, runtime.clone(), deployment_id, app).await?;
-        (tag, None)
+        (tag, false)
     } else if let Some(ref existing_tag) = existing_image_tag {
         // Restart from existing image (for upload apps without source)
         add_deployment_log(
@@ -488,7 +760,7 @@ pub async fn run_deployment(
             "Skipping build - using existing image",
         )
         .await?;
-        (existing_tag.clone(), None)
+        (existing_tag.clone(), false)
     } else if let Some(source_path) = upload_source_path {
         // Upload-based deployment: use pre-extracted source
         let tag = run_upload_deployment(
@@ -498,25 +770,10 @@ pub async fn run_deployment(
             app,
             &source_path,
             build_limits,
+            encryption_key,
         )
         .await?;
-        // Optionally push to registry; capture remote tag for image_tag update
-        let remote =
-            match build::push_image_to_registry(db, deployment_id, app, &tag, encryption_key).await
-            {
-                Ok(rt) => rt,
-                Err(e) => {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "warn",
-                        &format!("Registry push failed (non-fatal): {}", e),
-                    )
-                    .await?;
-                    None
-                }
-            };
-        (tag, remote)
+        (tag, true)
     } else if let Some(ref dockerfile_content) =
         app.inline_dockerfile.clone().filter(|s| !s.is_empty())
     {
@@ -531,23 +788,7 @@ pub async fn run_deployment(
             encryption_key,
         )
         .await?;
-        // Optionally push to registry; capture remote tag for image_tag update
-        let remote =
-            match build::push_image_to_registry(db, deployment_id, app, &tag, encryption_key).await
-            {
-                Ok(rt) => rt,
-                Err(e) => {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "warn",
-                        &format!("Registry push failed (non-fatal): {}", e),
-                    )
-                    .await?;
-                    None
-                }
-            };
-        (tag, remote)
+        (tag, true)
     } else {
         // Git-based deployment: clone and build
         let tag = run_git_deployment(
@@ -559,23 +800,58 @@ pub async fn run_deployment(
             encryption_key,
         )
         .await?;
-        // Optionally push to registry; capture remote tag for image_tag update
-        let remote =
-            match build::push_image_to_registry(db, deployment_id, app, &tag, encryption_key).await
-            {
-                Ok(rt) => rt,
-                Err(e) => {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "warn",
-                        &format!("Registry push failed (non-fatal): {}", e),
-                    )
-                    .await?;
-                    None
-                }
-            };
-        (tag, remote)
+        (tag, true)
+    };
+
+    if built {
+        build::tag_built_image(db, &runtime, deployment_id, app, &image_tag).await?;
+    }
+    deploy_phases::end(db, deployment_id, "succeeded").await;
+
+    // The pipeline file stored by the build replaces the app's settings for
+    // the rest of the deployment
+    let pipeline = pipeline_file::for_deployment(db, deployment_id).await?;
+    let overridden = pipeline.map(|p| p.apply(app));
+    let app = overridden.as_ref().unwrap_or(app);
+
+    // Scan the image before anything is pushed or started, so a vulnerable
+    // image fails the deployment when [scan] fail_on is set
+    if build_limits.scan.enabled {
+        scan::scan_deployment_image(db, deployment_id, &image_tag, &build_limits.scan).await?;
+    }
+
+    // The test stage runs against the image before it is pushed or started
+    test_stage::run_tests(db, &runtime, deployment_id, app, &image_tag, encryption_key).await?;
+
+    // Optionally push to registry. `remote_image_tag` is Some(...) when the image
+    // was pushed — after start_container stores the local image tag we overwrite
+    // it with the remote reference so rollbacks can pull from registry.
+    let remote_image_tag = if built {
+        match build::push_image_to_registry(
+            db,
+            deployment_id,
+            app,
+            &image_tag,
+            &build_limits.registry,
+            encryption_key,
+        )
+        .await
+        {
+            Ok(rt) => rt,
+            Err(e) => {
+                deploy_phases::end(db, deployment_id, "failed").await;
+                add_deployment_log(
+                    db,
+                    deployment_id,
+                    "warn",
+                    &format!("Registry push failed (non-fatal): {}", e),
+                )
+                .await?;
+                None
+            }
+        }
+    } else {
+        None
     };
 
     // Abort if a newer deployment superseded this one while it was building,
@@ -584,8 +860,18 @@ pub async fn run_deployment(
     bail_if_cancelled(db, deployment_id).await?;
 
     // Start container, health check, and finalize
-    let result =
-        start::start_container(db, runtime, deployment_id, app, image_tag, encryption_key).await?;
+    let result = start::start_container(
+        db,
+        runtime,
+        routes,
+        deployment_id,
+        app,
+        image_tag,
+        fences,
+        &build_limits.registry,
+        encryption_key,
+    )
+    .await?;
 
     // If the image was pushed to a registry, update the deployment's image_tag to the remote
     // reference. start_container stored the local image name, but rollbacks need the registry URL.
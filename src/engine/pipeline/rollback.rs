@@ -1,13 +1,16 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
 
+use crate::config::RegistryConfig;
 use crate::db::App;
 use crate::runtime::{ContainerRuntime, PortMapping, RunConfig};
 use crate::DbPool;
 
+use super::super::config_snapshot;
 use super::super::{add_deployment_log, update_deployment_status, KEY_LENGTH};
+use super::build::push_target;
 use super::start::collect_env_vars;
-use super::{AutoRollbackTriggered, DeploymentResult};
+use super::{AutoRollbackTriggered, DeploymentResult, RollbackCause};
 
 /// Trim old successful deployments to keep only the last `retention` entries.
 /// Also removes deployment logs for the trimmed deployments.
@@ -36,14 +39,120 @@ pub async fn trim_old_deployments(db: &DbPool, app_id: &str, retention: i64) ->
     Ok(())
 }
 
+/// Roll back by switching traffic to the target deployment's container, if it
+/// is still running (kept after a blue-green switchover, see
+/// runtime.keep_previous_minutes). Returns `None` when it has been stopped (or
+/// the app runs replicas) and a regular rollback is needed.
+pub async fn run_instant_rollback(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    rollback_deployment_id: &str,
+    target_deployment: &crate::db::Deployment,
+    app: &App,
+) -> Result<Option<DeploymentResult>> {
+    if app.replica_count > 1 {
+        return Ok(None);
+    }
+    let (Some(target_container_id), Some(image_tag)) = (
+        target_deployment.container_id.as_ref(),
+        target_deployment.image_tag.as_ref(),
+    ) else {
+        return Ok(None);
+    };
+    let kept = match runtime.inspect(target_container_id).await {
+        Ok(info) if info.running => info,
+        _ => return Ok(None),
+    };
+
+    add_deployment_log(
+        db,
+        rollback_deployment_id,
+        "info",
+        &format!(
+            "Container of deployment {} is still running; switching traffic back to it",
+            target_deployment.id
+        ),
+    )
+    .await?;
+    update_deployment_status(db, rollback_deployment_id, "starting", None).await?;
+
+    // The kept container still runs with the target deployment's configuration
+    if let Ok(Some(snapshot)) = config_snapshot::load(db, &target_deployment.id).await {
+        if let Err(e) = config_snapshot::save(db, rollback_deployment_id, &snapshot).await {
+            tracing::warn!(
+                "Failed to copy config snapshot to rollback deployment: {}",
+                e
+            );
+        }
+    }
+
+    // Give the kept container the canonical name back so later deploys find it
+    let container_name = app
+        .custom_container_name
+        .as_ref()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("rivetr-{}", app.name));
+    let mut old_container_ids: Vec<String> = Vec::new();
+    match runtime.inspect(&container_name).await {
+        Ok(current) if current.id == kept.id => {}
+        Ok(current) => {
+            let replaced_name = format!("{}-rolledback", container_name);
+            let _ = runtime.stop(&replaced_name).await;
+            let _ = runtime.remove(&replaced_name).await;
+            runtime
+                .rename_container(&current.id, &replaced_name)
+                .await
+                .context("Failed to rename the current container")?;
+            runtime
+                .rename_container(&kept.id, &container_name)
+                .await
+                .context("Failed to rename the kept container")?;
+            old_container_ids.push(current.id);
+        }
+        Err(_) => {
+            runtime
+                .rename_container(&kept.id, &container_name)
+                .await
+                .context("Failed to rename the kept container")?;
+        }
+    }
+
+    sqlx::query("UPDATE deployments SET container_id = ?, image_tag = ? WHERE id = ?")
+        .bind(&kept.id)
+        .bind(image_tag)
+        .bind(rollback_deployment_id)
+        .execute(db)
+        .await?;
+
+    add_deployment_log(
+        db,
+        rollback_deployment_id,
+        "info",
+        "Rollback completed successfully",
+    )
+    .await?;
+    update_deployment_status(db, rollback_deployment_id, "running", None).await?;
+
+    Ok(Some(DeploymentResult {
+        container_id: kept.id,
+        image_tag: image_tag.clone(),
+        port: kept.port,
+        auto_rollback_from: None,
+        old_container_ids,
+    }))
+}
+
 /// Rollback to a previous deployment by restarting with the old image.
-/// This does NOT rebuild the image - it reuses the existing image from the target deployment.
+/// This does NOT rebuild the image - it reuses the existing image from the target deployment,
+/// pulling it again from the app's push registry (see `push_target`) if it has been pruned.
 pub async fn run_rollback(
     db: &DbPool,
     runtime: Arc<dyn ContainerRuntime>,
     rollback_deployment_id: &str,
     target_deployment: &crate::db::Deployment,
     app: &App,
+    registry: &RegistryConfig,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<DeploymentResult> {
     let image_tag = target_deployment
@@ -62,6 +171,7 @@ pub async fn run_rollback(
     )
     .await?;
     update_deployment_status(db, rollback_deployment_id, "starting", None).await?;
+    config_snapshot::record(db, rollback_deployment_id, app, encryption_key).await;
 
     // Rename the current container so the rollback container can claim the canonical name
     // while the old one keeps serving traffic until proxy routes are swapped.
@@ -71,10 +181,18 @@ pub async fn run_rollback(
 
     match runtime.inspect(&container_name).await {
         Ok(_) => {
-            if let Err(e) = runtime
+            let mut renamed = runtime
                 .rename_container(&container_name, &old_container_prev_name)
-                .await
-            {
+                .await;
+            if renamed.is_err() {
+                // A container kept for instant rollback may still hold the -prev name
+                let _ = runtime.stop(&old_container_prev_name).await;
+                let _ = runtime.remove(&old_container_prev_name).await;
+                renamed = runtime
+                    .rename_container(&container_name, &old_container_prev_name)
+                    .await;
+            }
+            if let Err(e) = renamed {
                 tracing::warn!(
                     error = %e,
                     container = %container_name,
@@ -150,7 +268,7 @@ pub async fn run_rollback(
     let run_config = RunConfig {
         image: image_tag.clone(),
         name: container_name.clone(),
-        port: app.port as u16,
+        port: app.container_port(),
         env: env_vars,
         memory_limit: app.memory_limit.clone(),
         cpu_limit: app.cpu_limit.clone(),
@@ -170,7 +288,7 @@ pub async fn run_rollback(
         gpus: app.docker_gpus.clone(),
         ulimits: rollback_ulimits,
         security_opt: rollback_security_opt,
-        cmd: None,
+        cmd: crate::engine::processes::web_command(db, &app.id).await,
         network: None,
         custom_labels: vec![],
     };
@@ -182,10 +300,36 @@ pub async fn run_rollback(
         "Starting rollback container...",
     )
     .await?;
-    let container_id = runtime
-        .run(&run_config)
-        .await
-        .context("Failed to start rollback container")?;
+    let container_id = match runtime.run(&run_config).await {
+        Ok(container_id) => container_id,
+        Err(e) => {
+            // The image may have been pruned since; pull it again from the
+            // registry it was pushed to
+            let Some(target) =
+                push_target(app, registry, encryption_key).filter(|t| t.holds(image_tag))
+            else {
+                return Err(e).context("Failed to start rollback container");
+            };
+            add_deployment_log(
+                db,
+                rollback_deployment_id,
+                "info",
+                &format!(
+                    "Image {} is not available locally; pulling it from {}",
+                    image_tag, target.url
+                ),
+            )
+            .await?;
+            runtime
+                .pull_image(image_tag, target.auth().as_ref())
+                .await
+                .context("Failed to pull rollback image from registry")?;
+            runtime
+                .run(&run_config)
+                .await
+                .context("Failed to start rollback container")?
+        }
+    };
 
     // Update deployment with container ID and image tag
     sqlx::query("UPDATE deployments SET container_id = ?, image_tag = ? WHERE id = ?")
@@ -211,7 +355,7 @@ pub async fn run_rollback(
             let health_url = format!("http://127.0.0.1:{}{}", port, healthcheck);
 
             let mut healthy = false;
-            for attempt in 1..=10 {
+            for attempt in 1..=app.health_check_attempts() {
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
                 match reqwest::get(&health_url).await {
@@ -243,7 +387,10 @@ pub async fn run_rollback(
             if !healthy {
                 let _ = runtime.stop(&container_id).await;
                 let _ = runtime.remove(&container_id).await;
-                anyhow::bail!("Health check failed after 10 attempts during rollback");
+                anyhow::bail!(
+                    "Health check failed within {}s during rollback",
+                    app.healthcheck_window_secs
+                );
             }
         }
 
@@ -272,18 +419,22 @@ pub async fn run_rollback(
 }
 
 /// Trigger an automatic rollback to the previous successful deployment.
-/// Called when health check fails and auto_rollback_enabled is true.
+/// Called when the new container fails its health check or smoke tests and
+/// auto_rollback_enabled is true.
 ///
 /// `prev_old_container_ids`: container IDs that were already renamed/captured before the
 /// failed deployment started (i.e. the containers that were serving traffic before this
 /// deployment).  They should be stopped after the rollback's proxy routes are updated.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn trigger_auto_rollback(
     db: &DbPool,
     runtime: Arc<dyn ContainerRuntime>,
     failed_deployment_id: &str,
     app: &App,
+    registry: &RegistryConfig,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
     prev_old_container_ids: Vec<String>,
+    cause: RollbackCause,
 ) -> Result<AutoRollbackTriggered> {
     use crate::db::Deployment;
 
@@ -331,7 +482,8 @@ pub(super) async fn trigger_auto_rollback(
         &rollback_deployment_id,
         "info",
         &format!(
-            "Auto-rollback initiated due to health check failure in deployment {}",
+            "Auto-rollback initiated: {} in deployment {}",
+            cause.failure(),
             failed_deployment_id
         ),
     )
@@ -344,6 +496,7 @@ pub(super) async fn trigger_auto_rollback(
         &rollback_deployment_id,
         &target,
         app,
+        registry,
         encryption_key,
     )
     .await
@@ -368,6 +521,7 @@ pub(super) async fn trigger_auto_rollback(
                 failed_deployment_id: failed_deployment_id.to_string(),
                 rollback_deployment_id,
                 target_deployment_id: target.id.clone(),
+                cause,
                 old_container_ids: all_old,
             })
         }
@@ -384,3 +538,64 @@ pub(super) async fn trigger_auto_rollback(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool, Deployment};
+    use crate::runtime::NoopRuntime;
+
+    async fn target(db: &DbPool, app_id: &str, container_id: Option<&str>) -> Deployment {
+        let id = test_deployment(db, app_id, "replaced").await;
+        sqlx::query("UPDATE deployments SET container_id = ?, image_tag = 'web:1' WHERE id = ?")
+            .bind(container_id)
+            .bind(&id)
+            .execute(db)
+            .await
+            .unwrap();
+        sqlx::query_as("SELECT * FROM deployments WHERE id = ?")
+            .bind(&id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_instant_rollback_falls_back_without_a_kept_container() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let app = load_test_app(&db, &app_id).await;
+        let rollback_id = test_deployment(&db, &app_id, "pending").await;
+        let runtime: Arc<dyn ContainerRuntime> = Arc::new(NoopRuntime);
+
+        // No container recorded
+        let no_container = target(&db, &app_id, None).await;
+        let result = run_instant_rollback(&db, runtime.clone(), &rollback_id, &no_container, &app)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // The container is gone
+        let stopped = target(&db, &app_id, Some("c-old")).await;
+        let result = run_instant_rollback(&db, runtime.clone(), &rollback_id, &stopped, &app)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // Replicas are always started again
+        let mut replicated = app.clone();
+        replicated.replica_count = 3;
+        let result = run_instant_rollback(&db, runtime, &rollback_id, &stopped, &replicated)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // Nothing was changed for the regular rollback to trip over
+        let status: String = sqlx::query_scalar("SELECT status FROM deployments WHERE id = ?")
+            .bind(&rollback_id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(status, "pending");
+    }
+}
@@ -0,0 +1,329 @@
+//! Rolling replacement of a multi-replica app's additional replicas.
+//!
+//! The primary container is swapped blue-green by the engine once the deploy
+//! finishes. Its replicas (index > 0) are replaced in batches before that:
+//! up to `rolling_max_surge` new replicas run above `replica_count` at once,
+//! and up to `rolling_max_unavailable` old ones may leave the load-balancing
+//! pool before their replacements pass the health check. Each new replica
+//! joins the pool as soon as it is healthy, so capacity never drops below
+//! `replica_count - rolling_max_unavailable`.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+
+use crate::db::App;
+use crate::proxy::{probe_backend, Backend, HealthProbe, PoolMember, RouteTable};
+use crate::runtime::{ContainerRuntime, RunConfig};
+use crate::DbPool;
+
+use super::super::{add_deployment_log, egress};
+use super::start;
+
+/// Health check attempts per new replica, 2 seconds apart
+const HEALTH_ATTEMPTS: u32 = 10;
+
+/// A replica container and the address it is load-balanced at
+struct Replica {
+    container_id: String,
+    addr: Option<String>,
+}
+
+/// Replace `old_replicas` with new replicas started from `run_config`, a batch
+/// at a time. Returns the old replica containers that were stopped; any left
+/// over (e.g. when scaling down) are stopped by the engine after the final
+/// route swap.
+pub(super) async fn roll_replicas(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    deployment_id: &str,
+    app: &App,
+    run_config: &RunConfig,
+    old_replicas: &[String],
+) -> Result<Vec<String>> {
+    let replica_count = app.replica_count.max(1) as usize;
+    let (surge, unavailable) = app.rolling_budget();
+    let domains = app_domains(app);
+
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Rolling out {} replica(s) (max surge {}, max unavailable {})...",
+            replica_count - 1,
+            surge,
+            unavailable
+        ),
+    )
+    .await?;
+
+    let mut old: VecDeque<Replica> = VecDeque::new();
+    for container_id in old_replicas {
+        let addr = replica_addr(runtime.as_ref(), container_id).await;
+        old.push_back(Replica {
+            container_id: container_id.clone(),
+            addr,
+        });
+    }
+
+    let indices: Vec<usize> = (1..replica_count).collect();
+    let mut retired: Vec<String> = Vec::new();
+    for batch in indices.chunks(surge + unavailable) {
+        // Take old replicas out of the pool within the unavailable budget first
+        let mut made_room = 0;
+        while made_room < unavailable.min(batch.len()) {
+            let Some(replica) = old.pop_front() else {
+                break;
+            };
+            retire(db, runtime, routes, deployment_id, &domains, &replica).await?;
+            retired.push(replica.container_id);
+            made_room += 1;
+        }
+
+        let mut started: Vec<(usize, String)> = Vec::new();
+        for &index in batch {
+            if let Some(container_id) =
+                start_replica(db, runtime, deployment_id, app, run_config, index).await?
+            {
+                started.push((index, container_id));
+            }
+        }
+        if !started.is_empty() && egress::is_restricted(app) {
+            egress::apply_logged(db, runtime, app).await;
+        }
+
+        let mut healthy = 0;
+        for (index, container_id) in started {
+            match wait_healthy(db, runtime.as_ref(), deployment_id, app, &container_id).await {
+                Some(addr) => {
+                    update_pool(
+                        routes,
+                        &domains,
+                        None,
+                        Some(PoolMember::new(container_id.clone(), addr)),
+                    );
+                    add_deployment_log(
+                        db,
+                        deployment_id,
+                        "info",
+                        &format!("Replica {} is healthy and serving traffic", index),
+                    )
+                    .await?;
+                    healthy += 1;
+                }
+                None => {
+                    add_deployment_log(
+                        db,
+                        deployment_id,
+                        "warn",
+                        &format!("Replica {} failed its health check; removing it", index),
+                    )
+                    .await?;
+                    let _ = runtime.stop(&container_id).await;
+                    let _ = runtime.remove(&container_id).await;
+                    let _ = sqlx::query("DELETE FROM app_replicas WHERE container_id = ?")
+                        .bind(&container_id)
+                        .execute(db)
+                        .await;
+                }
+            }
+        }
+
+        // Then retire the old replicas the healthy new ones replaced
+        for _ in made_room..healthy {
+            let Some(replica) = old.pop_front() else {
+                break;
+            };
+            retire(db, runtime, routes, deployment_id, &domains, &replica).await?;
+            retired.push(replica.container_id);
+        }
+    }
+
+    Ok(retired)
+}
+
+/// Domains the app is load-balanced on (redirect-only domains excluded)
+fn app_domains(app: &App) -> Vec<String> {
+    if app.is_worker() {
+        return Vec::new();
+    }
+    let mut domains: Vec<String> = app
+        .get_all_domains_with_redirects()
+        .into_iter()
+        .filter(|(_, redirect)| redirect.is_none())
+        .map(|(domain, _)| domain)
+        .collect();
+    if domains.is_empty() {
+        domains.extend(app.domain.clone());
+    }
+    domains
+}
+
+async fn replica_addr(runtime: &dyn ContainerRuntime, container_id: &str) -> Option<String> {
+    let info = runtime.inspect(container_id).await.ok()?;
+    info.port.map(|port| format!("127.0.0.1:{}", port))
+}
+
+/// Move one address out of and/or one replica into the pool of every domain
+/// of the app
+fn update_pool(
+    routes: &Arc<ArcSwap<RouteTable>>,
+    domains: &[String],
+    remove: Option<&str>,
+    add: Option<PoolMember>,
+) {
+    let table = routes.load();
+    for domain in domains {
+        let mut pool = table.pool(domain);
+        if pool.is_empty() {
+            continue;
+        }
+        if let Some(addr) = remove {
+            pool.retain(|m| m.addr != addr);
+        }
+        if let Some(ref member) = add {
+            pool.push(member.clone());
+        }
+        table.set_pool(domain, pool);
+    }
+}
+
+async fn retire(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    deployment_id: &str,
+    domains: &[String],
+    replica: &Replica,
+) -> Result<()> {
+    if let Some(ref addr) = replica.addr {
+        update_pool(routes, domains, Some(addr), None);
+    }
+    // The stop grace period lets in-flight requests finish
+    let _ = runtime.stop(&replica.container_id).await;
+    let _ = runtime.remove(&replica.container_id).await;
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!("Stopped old replica {}", replica.container_id),
+    )
+    .await
+}
+
+async fn start_replica(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    run_config: &RunConfig,
+    index: usize,
+) -> Result<Option<String>> {
+    let replica_name = format!("rivetr-{}-{}", app.name, index);
+
+    // The old replica keeps serving under "-prev" until it is retired
+    if runtime.inspect(&replica_name).await.is_ok() {
+        let prev_name = format!("{}-prev", replica_name);
+        let _ = runtime.stop(&prev_name).await;
+        let _ = runtime.remove(&prev_name).await;
+        if let Err(e) = runtime.rename_container(&replica_name, &prev_name).await {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "warn",
+                &format!("Failed to start replica {}: {}", index, e),
+            )
+            .await?;
+            return Ok(None);
+        }
+    }
+
+    let mut replica_config = run_config.clone();
+    replica_config.name = replica_name;
+    // Additional replicas don't need explicit port mappings (ephemeral ports)
+    replica_config.port_mappings = vec![];
+
+    match runtime.run(&replica_config).await {
+        Ok(container_id) => {
+            let _ = sqlx::query(
+                "INSERT INTO app_replicas (id, app_id, replica_index, container_id, status, started_at)
+                 VALUES (?, ?, ?, ?, 'running', datetime('now'))",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&app.id)
+            .bind(index as i64)
+            .bind(&container_id)
+            .execute(db)
+            .await;
+            add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!("Replica {} started: {}", index, container_id),
+            )
+            .await?;
+            Ok(Some(container_id))
+        }
+        Err(e) => {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "warn",
+                &format!("Failed to start replica {}: {}", index, e),
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Wait for a new replica to pass the app's health check (or, without one, to
+/// keep running). Returns its pool address when healthy; workers have none, so
+/// their container ID stands in.
+async fn wait_healthy(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+    container_id: &str,
+) -> Option<String> {
+    if app.is_worker() {
+        return start::wait_worker_healthy(db, runtime, deployment_id, app, container_id)
+            .await
+            .unwrap_or(false)
+            .then(|| container_id.to_string());
+    }
+    let health_probe = app.health_probe();
+    let has_check = app.healthcheck.is_some() || health_probe != HealthProbe::Http;
+    let client = reqwest::Client::new();
+
+    for attempt in 1..=HEALTH_ATTEMPTS {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let info = runtime.inspect(container_id).await.ok()?;
+        let port = info.port?;
+        if !has_check {
+            return info.running.then(|| format!("127.0.0.1:{}", port));
+        }
+        let backend = Backend::new(container_id.to_string(), "127.0.0.1".to_string(), port)
+            .with_healthcheck(app.healthcheck.clone())
+            .with_health_probe(health_probe.clone());
+        match probe_backend(&backend, &client, Some(runtime), Duration::from_secs(10)).await {
+            Ok(_) => return Some(backend.addr()),
+            Err(reason) => {
+                let _ = add_deployment_log(
+                    db,
+                    deployment_id,
+                    "warn",
+                    &format!("Replica health check attempt {}: {}", attempt, reason),
+                )
+                .await;
+            }
+        }
+    }
+    None
+}
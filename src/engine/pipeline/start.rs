@@ -1,17 +1,24 @@
 use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
 use std::sync::Arc;
 
+use crate::config::RegistryConfig;
 use crate::crypto;
-use crate::db::App;
+use crate::db::{App, AppProcess, PROCESS_RELEASE, PROCESS_WEB};
+use crate::proxy::{probe_backend, Backend, HealthProbe, RouteTable};
 use crate::runtime::{ContainerRuntime, PortMapping, RunConfig};
 use crate::DbPool;
 
-use super::super::{add_deployment_log, update_deployment_status, KEY_LENGTH};
-use super::DeploymentResult;
+use super::super::deploy_phases::{self, DeployPhase};
+use super::super::{
+    add_deployment_log, diagnostics, fences::DeployFences, port_check, processes,
+    update_deployment_status, KEY_LENGTH,
+};
+use super::{rolling, DeploymentResult, RollbackCause, DEPLOYMENT_LABEL};
 
 /// Collect and decrypt all env vars for an app (app + environment + project + team layers).
 /// `deployment_id` is used to look up the deployment's commit SHA for the SOURCE_COMMIT variable.
-pub(super) async fn collect_env_vars(
+pub(crate) async fn collect_env_vars(
     db: &DbPool,
     app: &App,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
@@ -38,8 +45,8 @@ pub(super) async fn collect_env_vars(
         })
         .collect();
 
-    // Automatically set PORT environment variable if not already set
-    if !env_vars.iter().any(|(k, _)| k == "PORT") {
+    // Automatically set PORT environment variable if not already set (workers serve no port)
+    if !app.is_worker() && !env_vars.iter().any(|(k, _)| k == "PORT") {
         env_vars.push(("PORT".to_string(), app.port.to_string()));
     }
 
@@ -160,17 +167,323 @@ pub(super) async fn collect_env_vars(
     env_vars
 }
 
+/// Container config for running the app's image under `name`: volumes, port
+/// mappings, Docker run options, destination network and labels from the app
+pub(crate) async fn build_run_config(
+    db: &DbPool,
+    app: &App,
+    image: String,
+    name: String,
+    env: Vec<(String, String)>,
+) -> RunConfig {
+    // Get volumes from database
+    let volumes = sqlx::query_as::<_, crate::db::Volume>(
+        "SELECT id, app_id, name, host_path, container_path, read_only, created_at, updated_at FROM volumes WHERE app_id = ?",
+    )
+    .bind(&app.id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    // Convert volumes to bind mount strings
+    let binds: Vec<String> = volumes.iter().map(|v| v.to_bind_mount()).collect();
+
+    // Parse network configuration from app
+    let port_mappings: Vec<PortMapping> = app
+        .get_port_mappings()
+        .into_iter()
+        .map(|pm| PortMapping {
+            host_port: pm.host_port,
+            container_port: pm.container_port,
+            protocol: pm.protocol,
+        })
+        .collect();
+
+    // Parse custom Docker run options from app settings
+    let cap_add: Vec<String> = app
+        .cap_add
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let devices: Vec<String> = app
+        .devices
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let shm_size: Option<i64> = app
+        .shm_size
+        .as_ref()
+        .and_then(|s| crate::runtime::parse_shm_size(s));
+    let cap_drop: Vec<String> = app
+        .docker_cap_drop
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let ulimits: Vec<String> = app
+        .docker_ulimits
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let security_opt: Vec<String> = app
+        .docker_security_opt
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+
+    // Look up the destination network name if a destination_id is set on the app.
+    let destination_network: Option<String> = if let Some(ref dest_id) = app.destination_id {
+        sqlx::query_scalar::<_, String>("SELECT network_name FROM destinations WHERE id = ?")
+            .bind(dest_id)
+            .fetch_optional(db)
+            .await
+            .unwrap_or(None)
+    } else {
+        None
+    };
+
+    // Parse custom_labels from JSON array format: [{key, value}, ...]
+    let custom_labels: Vec<(String, String)> = app
+        .custom_labels
+        .as_ref()
+        .and_then(|s| serde_json::from_str::<Vec<serde_json::Value>>(s).ok())
+        .map(|arr| {
+            arr.into_iter()
+                .filter_map(|v| {
+                    let key = v.get("key")?.as_str()?.to_string();
+                    let value = v.get("value")?.as_str()?.to_string();
+                    if key.is_empty() {
+                        None
+                    } else {
+                        Some((key, value))
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    RunConfig {
+        image,
+        name,
+        port: app.container_port(),
+        env,
+        memory_limit: app.memory_limit.clone(),
+        cpu_limit: app.cpu_limit.clone(),
+        port_mappings,
+        network_aliases: app.get_network_aliases(),
+        extra_hosts: app.get_extra_hosts(),
+        labels: app.get_container_labels(),
+        binds,
+        restart_policy: app.restart_policy.clone(),
+        privileged: app.privileged != 0,
+        cap_add,
+        cap_drop,
+        devices,
+        shm_size,
+        init: app.init_process != 0,
+        app_id: Some(app.id.clone()),
+        gpus: app.docker_gpus.clone(),
+        ulimits,
+        security_opt,
+        cmd: None,
+        network: destination_network,
+        custom_labels,
+    }
+}
+
+/// Consecutive passing checks before a worker counts as healthy
+const WORKER_STABLE_CHECKS: u32 = 3;
+
+/// Check a worker container once: it must be running, not have restarted
+/// since `restarts` was recorded and, with a command probe, pass it.
+async fn probe_worker(
+    runtime: &dyn ContainerRuntime,
+    app: &App,
+    container_id: &str,
+    restarts: u32,
+) -> Result<String, String> {
+    let info = runtime
+        .inspect(container_id)
+        .await
+        .map_err(|e| format!("container not found: {}", e))?;
+    if !info.running {
+        return Err(format!("process exited (container is {})", info.status));
+    }
+    if info.restart_count > restarts {
+        return Err(format!(
+            "process restarted {} time(s)",
+            info.restart_count - restarts
+        ));
+    }
+    match app.health_probe() {
+        probe @ HealthProbe::Command(_) => {
+            let backend = Backend::new(container_id.to_string(), "127.0.0.1".to_string(), 0)
+                .with_health_probe(probe);
+            probe_backend(
+                &backend,
+                &reqwest::Client::new(),
+                Some(runtime),
+                std::time::Duration::from_secs(10),
+            )
+            .await
+        }
+        _ => Ok("process is running".to_string()),
+    }
+}
+
+/// Wait for a worker to pass `WORKER_STABLE_CHECKS` checks in a row, 2 seconds
+/// apart, within the app's health check window. Returns whether it did.
+pub(super) async fn wait_worker_healthy(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+    container_id: &str,
+) -> Result<bool> {
+    let restarts = runtime
+        .inspect(container_id)
+        .await
+        .map(|info| info.restart_count)
+        .unwrap_or(0);
+    let mut passed = 0;
+    for attempt in 1..=app.health_check_attempts().max(WORKER_STABLE_CHECKS) {
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        match probe_worker(runtime, app, container_id, restarts).await {
+            Ok(_) => {
+                passed += 1;
+                if passed >= WORKER_STABLE_CHECKS {
+                    return Ok(true);
+                }
+            }
+            Err(reason) => {
+                passed = 0;
+                add_deployment_log(
+                    db,
+                    deployment_id,
+                    "warn",
+                    &format!("Health check attempt {}: {}", attempt, reason),
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Remove a new container that failed its health check or smoke tests and fail
+/// the deployment, rolling back to the previous version when auto-rollback is
+/// enabled. `old_container_ids` are stopped once the rollback has taken over.
+#[allow(clippy::too_many_arguments)]
+async fn reject_container<T>(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    container_id: &str,
+    registry: &RegistryConfig,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+    old_container_ids: Vec<String>,
+    cause: RollbackCause,
+) -> Result<T> {
+    use super::rollback::trigger_auto_rollback;
+
+    let failure = match cause {
+        RollbackCause::HealthCheck => {
+            format!(
+                "Health check failed within {}s",
+                app.healthcheck_window_secs
+            )
+        }
+        RollbackCause::SmokeTests => cause.failure().to_string(),
+    };
+
+    // Stop the failed container
+    diagnostics::capture_container(db, runtime.as_ref(), deployment_id, container_id).await;
+    let _ = runtime.stop(container_id).await;
+    let _ = runtime.remove(container_id).await;
+
+    if !app.is_auto_rollback_enabled() {
+        anyhow::bail!(failure);
+    }
+
+    add_deployment_log(
+        db,
+        deployment_id,
+        "warn",
+        &format!(
+            "{}. Auto-rollback is enabled, attempting to rollback to previous version...",
+            cause.failure()
+        ),
+    )
+    .await?;
+
+    // Try to trigger auto-rollback, passing old container IDs so they get
+    // cleaned up after the rollback proxy swap.
+    match trigger_auto_rollback(
+        db,
+        runtime,
+        deployment_id,
+        app,
+        registry,
+        encryption_key,
+        old_container_ids,
+        cause,
+    )
+    .await
+    {
+        Ok(rollback_info) => {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!(
+                    "Auto-rollback initiated to deployment {}. Rollback deployment ID: {}",
+                    rollback_info.target_deployment_id, rollback_info.rollback_deployment_id
+                ),
+            )
+            .await?;
+
+            // Return the auto-rollback error so the engine knows what happened
+            Err(rollback_info.into())
+        }
+        Err(rollback_err) => {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "error",
+                &format!(
+                    "Auto-rollback failed: {}. No previous deployment available for rollback.",
+                    rollback_err
+                ),
+            )
+            .await?;
+            anyhow::bail!("{}. Auto-rollback also failed: {}", failure, rollback_err);
+        }
+    }
+}
+
 /// Start the container, run replicas, execute deploy commands, health check, and finalize
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn start_container(
     db: &DbPool,
     runtime: Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
     deployment_id: &str,
     app: &App,
     image_tag: String,
+    fences: &DeployFences,
+    registry: &RegistryConfig,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
 ) -> Result<DeploymentResult> {
     use super::build::execute_deployment_commands;
-    use super::rollback::{trigger_auto_rollback, trim_old_deployments};
+    use super::rollback::trim_old_deployments;
+
+    // Wait for the app's dependencies before touching the running containers
+    crate::engine::wait_conditions::wait_for_conditions(db, runtime.as_ref(), deployment_id, app)
+        .await?;
+
+    // Process types of this deployment's Procfile
+    let (app_processes, dropped_process_containers) =
+        processes::load_for_deployment(db, deployment_id, &app.id).await?;
 
     // Step 3: Capture and rename old containers for zero-downtime swap.
     //
@@ -267,9 +580,13 @@ pub(super) async fn start_container(
     .fetch_all(db)
     .await
     .unwrap_or_default();
+    let mut old_replica_ids: Vec<String> = Vec::new();
     for old_replica in &old_replicas {
         if let Some(ref cid) = old_replica.container_id {
             old_container_ids.push(cid.clone());
+            if old_replica.replica_index > 0 {
+                old_replica_ids.push(cid.clone());
+            }
         }
     }
 
@@ -280,123 +597,27 @@ pub(super) async fn start_container(
         .await;
 
     // Step 4: Start new container
+    deploy_phases::begin(db, deployment_id, DeployPhase::Start).await;
     add_deployment_log(db, deployment_id, "info", "Starting container...").await?;
     update_deployment_status(db, deployment_id, "starting", None).await?;
 
     let env_vars = collect_env_vars(db, app, encryption_key, Some(deployment_id)).await;
-
-    // Get volumes from database
-    let volumes = sqlx::query_as::<_, crate::db::Volume>(
-        "SELECT id, app_id, name, host_path, container_path, read_only, created_at, updated_at FROM volumes WHERE app_id = ?",
-    )
-    .bind(&app.id)
-    .fetch_all(db)
-    .await
-    .unwrap_or_default();
-
-    // Convert volumes to bind mount strings
-    let binds: Vec<String> = volumes.iter().map(|v| v.to_bind_mount()).collect();
-
-    // Parse network configuration from app
-    let port_mappings: Vec<PortMapping> = app
-        .get_port_mappings()
-        .into_iter()
-        .map(|pm| PortMapping {
-            host_port: pm.host_port,
-            container_port: pm.container_port,
-            protocol: pm.protocol,
-        })
-        .collect();
-
-    // Parse custom Docker run options from app settings
-    let cap_add: Vec<String> = app
-        .cap_add
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-    let devices: Vec<String> = app
-        .devices
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-    let shm_size: Option<i64> = app
-        .shm_size
-        .as_ref()
-        .and_then(|s| crate::runtime::parse_shm_size(s));
-    let cap_drop: Vec<String> = app
-        .docker_cap_drop
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-    let ulimits: Vec<String> = app
-        .docker_ulimits
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-    let security_opt: Vec<String> = app
-        .docker_security_opt
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-
-    // Look up the destination network name if a destination_id is set on the app.
-    let destination_network: Option<String> = if let Some(ref dest_id) = app.destination_id {
-        sqlx::query_scalar::<_, String>("SELECT network_name FROM destinations WHERE id = ?")
-            .bind(dest_id)
-            .fetch_optional(db)
-            .await
-            .unwrap_or(None)
-    } else {
-        None
-    };
-
-    // Parse custom_labels from JSON array format: [{key, value}, ...]
-    let custom_labels: Vec<(String, String)> = app
-        .custom_labels
-        .as_ref()
-        .and_then(|s| serde_json::from_str::<Vec<serde_json::Value>>(s).ok())
-        .map(|arr| {
-            arr.into_iter()
-                .filter_map(|v| {
-                    let key = v.get("key")?.as_str()?.to_string();
-                    let value = v.get("value")?.as_str()?.to_string();
-                    if key.is_empty() {
-                        None
-                    } else {
-                        Some((key, value))
-                    }
-                })
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let run_config = RunConfig {
-        image: image_tag,
-        name: container_name.clone(),
-        port: app.port as u16,
-        env: env_vars,
-        memory_limit: app.memory_limit.clone(),
-        cpu_limit: app.cpu_limit.clone(),
-        port_mappings,
-        network_aliases: app.get_network_aliases(),
-        extra_hosts: app.get_extra_hosts(),
-        labels: app.get_container_labels(),
-        binds,
-        restart_policy: app.restart_policy.clone(),
-        privileged: app.privileged != 0,
-        cap_add,
-        cap_drop,
-        devices,
-        shm_size,
-        init: app.init_process != 0,
-        app_id: Some(app.id.clone()),
-        gpus: app.docker_gpus.clone(),
-        ulimits,
-        security_opt,
-        cmd: None,
-        network: destination_network,
-        custom_labels,
-    };
+    let port_env = env_vars
+        .iter()
+        .find(|(k, _)| k == "PORT")
+        .map(|(_, v)| v.clone());
+
+    let mut run_config =
+        build_run_config(db, app, image_tag, container_name.clone(), env_vars).await;
+    // The Procfile's web command replaces the image's default command
+    if let Some(web) = app_processes.iter().find(|p| p.name == PROCESS_WEB) {
+        run_config.cmd = Some(processes::shell_command(&web.command));
+    }
+    // Lets a cancel find this deployment's containers even when it lands
+    // before their IDs are recorded
+    run_config
+        .labels
+        .insert(DEPLOYMENT_LABEL.to_string(), deployment_id.to_string());
 
     let container_id = runtime
         .run(&run_config)
@@ -425,9 +646,11 @@ pub(super) async fn start_container(
         .await;
     }
 
-    // Start additional replicas if replica_count > 1
+    // Start additional replicas if replica_count > 1. When old replicas are
+    // serving, they are replaced one batch at a time after the health check.
     let replica_count = app.replica_count.max(1);
-    if replica_count > 1 {
+    let rolling = replica_count > 1 && !old_replica_ids.is_empty();
+    if replica_count > 1 && !rolling {
         add_deployment_log(
             db,
             deployment_id,
@@ -478,12 +701,26 @@ pub(super) async fn start_container(
         }
     }
 
+    // Restricted egress applies before any of the app's own code talks to the network
+    if crate::engine::egress::is_restricted(app) {
+        if let Err(e) = crate::engine::egress::apply(db, &runtime, app).await {
+            add_deployment_log(
+                db,
+                deployment_id,
+                "warn",
+                &format!("Failed to apply egress policy: {}", e),
+            )
+            .await?;
+        }
+    }
+
     // Step 5: Execute pre-deploy commands (before health check)
     let pre_deploy_commands = app.get_pre_deploy_commands();
     if !pre_deploy_commands.is_empty() {
         // Wait a brief moment for container to be ready
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
+        let _fences = fences.enter(db, deployment_id, app, "pre-deploy").await;
         if let Err(e) = execute_deployment_commands(
             db,
             runtime.clone(),
@@ -495,115 +732,193 @@ pub(super) async fn start_container(
         .await
         {
             // Rollback: stop the container if pre-deploy commands fail
+            diagnostics::capture_container(db, runtime.as_ref(), deployment_id, &container_id)
+                .await;
             let _ = runtime.stop(&container_id).await;
             let _ = runtime.remove(&container_id).await;
             return Err(e);
         }
     }
 
-    // Step 6: Health check
-    if let Some(healthcheck) = &app.healthcheck {
+    // Warn (without failing) when the process listens on a different port than configured
+    if !app.is_worker() {
+        port_check::warn_on_port_mismatch(
+            db,
+            runtime.clone(),
+            deployment_id,
+            &container_id,
+            app,
+            port_env.as_deref(),
+        )
+        .await;
+    }
+
+    // Step 6: Health check (HTTP when a path is set; TCP and command probes
+    // don't need one). Workers have no port: their process must stay up.
+    let health_probe = app.health_probe();
+    if app.is_worker() || app.healthcheck.is_some() || health_probe != HealthProbe::Http {
+        deploy_phases::begin(db, deployment_id, DeployPhase::Healthcheck).await;
         add_deployment_log(db, deployment_id, "info", "Running health check...").await?;
         update_deployment_status(db, deployment_id, "checking", None).await?;
 
         // Get the assigned port
         let info = runtime.inspect(&container_id).await?;
-        if let Some(port) = info.port {
-            let health_url = format!("http://127.0.0.1:{}{}", port, healthcheck);
+        let healthy = if app.is_worker() {
+            Some(
+                wait_worker_healthy(db, runtime.as_ref(), deployment_id, app, &container_id)
+                    .await?,
+            )
+        } else if let Some(port) = info.port {
+            let backend = Backend::new(container_id.clone(), "127.0.0.1".to_string(), port)
+                .with_healthcheck(app.healthcheck.clone())
+                .with_health_probe(health_probe);
+            let client = reqwest::Client::new();
 
-            // Retry health check a few times
+            // Retry the health check until the app's health check window runs out
             let mut healthy = false;
-            for attempt in 1..=10 {
+            for attempt in 1..=app.health_check_attempts() {
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
-                match reqwest::get(&health_url).await {
-                    Ok(resp) if resp.status().is_success() => {
+                match probe_backend(
+                    &backend,
+                    &client,
+                    Some(runtime.as_ref()),
+                    std::time::Duration::from_secs(10),
+                )
+                .await
+                {
+                    Ok(_) => {
                         healthy = true;
                         break;
                     }
-                    Ok(resp) => {
+                    Err(reason) => {
                         add_deployment_log(
                             db,
                             deployment_id,
                             "warn",
-                            &format!("Health check attempt {}: status {}", attempt, resp.status()),
-                        )
-                        .await?;
-                    }
-                    Err(e) => {
-                        add_deployment_log(
-                            db,
-                            deployment_id,
-                            "warn",
-                            &format!("Health check attempt {}: {}", attempt, e),
+                            &format!("Health check attempt {}: {}", attempt, reason),
                         )
                         .await?;
                     }
                 }
             }
 
-            if !healthy {
-                // Stop the unhealthy container
-                let _ = runtime.stop(&container_id).await;
-                let _ = runtime.remove(&container_id).await;
+            Some(healthy)
+        } else {
+            None
+        };
 
-                // Check if auto-rollback is enabled
-                if app.is_auto_rollback_enabled() {
-                    add_deployment_log(
-                        db,
-                        deployment_id,
-                        "warn",
-                        "Health check failed. Auto-rollback is enabled, attempting to rollback to previous version...",
-                    )
-                    .await?;
+        if healthy == Some(false) {
+            return reject_container(
+                db,
+                runtime,
+                deployment_id,
+                app,
+                &container_id,
+                registry,
+                encryption_key,
+                old_container_ids,
+                RollbackCause::HealthCheck,
+            )
+            .await;
+        }
 
-                    // Try to trigger auto-rollback, passing old container IDs so they get
-                    // cleaned up after the rollback proxy swap.
-                    match trigger_auto_rollback(
-                        db,
-                        runtime.clone(),
-                        deployment_id,
-                        app,
-                        encryption_key,
-                        old_container_ids.clone(),
-                    )
-                    .await
-                    {
-                        Ok(rollback_info) => {
-                            add_deployment_log(
-                                db,
-                                deployment_id,
-                                "info",
-                                &format!(
-                                    "Auto-rollback initiated to deployment {}. Rollback deployment ID: {}",
-                                    rollback_info.target_deployment_id,
-                                    rollback_info.rollback_deployment_id
-                                ),
-                            )
-                            .await?;
-
-                            // Return the auto-rollback error so the engine knows what happened
-                            return Err(rollback_info.into());
-                        }
-                        Err(rollback_err) => {
-                            add_deployment_log(
-                                db,
-                                deployment_id,
-                                "error",
-                                &format!("Auto-rollback failed: {}. No previous deployment available for rollback.", rollback_err),
-                            )
-                            .await?;
-                            anyhow::bail!("Health check failed after 10 attempts. Auto-rollback also failed: {}", rollback_err);
-                        }
-                    }
-                }
+        add_deployment_log(db, deployment_id, "info", "Health check passed").await?;
+    }
 
-                anyhow::bail!("Health check failed after 10 attempts");
+    // Step 6a: Smoke tests against the healthy container, before any traffic
+    // or old replicas move to it
+    if !app.is_worker() && !app.get_smoke_tests().is_empty() {
+        let port = runtime.inspect(&container_id).await?.port;
+        let result = match port {
+            Some(port) => {
+                crate::engine::smoke_tests::run_smoke_tests(db, deployment_id, app, port).await
             }
+            None => Err(anyhow::anyhow!("Container has no published port to test")),
+        };
+        if let Err(e) = result {
+            add_deployment_log(db, deployment_id, "error", &format!("{:#}", e)).await?;
+            return reject_container(
+                db,
+                runtime,
+                deployment_id,
+                app,
+                &container_id,
+                registry,
+                encryption_key,
+                old_container_ids,
+                RollbackCause::SmokeTests,
+            )
+            .await;
         }
+    }
 
-        add_deployment_log(db, deployment_id, "info", "Health check passed").await?;
+    // Step 6b: Rolling replacement of the old replicas
+    if rolling {
+        let retired = rolling::roll_replicas(
+            db,
+            &runtime,
+            routes,
+            deployment_id,
+            app,
+            &run_config,
+            &old_replica_ids,
+        )
+        .await?;
+        old_container_ids.retain(|id| !retired.contains(id));
+    }
+
+    // Step 6c: Procfile release process, before traffic switches to the new version
+    if let Some(release) = app_processes.iter().find(|p| p.name == PROCESS_RELEASE) {
+        let _fences = fences.enter(db, deployment_id, app, "release").await;
+        if let Err(e) = execute_deployment_commands(
+            db,
+            runtime.clone(),
+            deployment_id,
+            &container_id,
+            std::slice::from_ref(&release.command),
+            "release",
+        )
+        .await
+        {
+            diagnostics::capture_container(db, runtime.as_ref(), deployment_id, &container_id)
+                .await;
+            let _ = runtime.stop(&container_id).await;
+            let _ = runtime.remove(&container_id).await;
+            return Err(e.context("Release process failed"));
+        }
+    }
+
+    // Step 6d: Containers of the other Procfile process types. Their old
+    // containers are stopped with the old primary after the route swap.
+    let mut started_processes: Vec<(&AppProcess, Vec<String>)> = Vec::new();
+    for process in app_processes.iter().filter(|p| p.runs_own_containers()) {
+        match processes::start_process(
+            db,
+            runtime.as_ref(),
+            deployment_id,
+            app,
+            process,
+            &run_config,
+        )
+        .await
+        {
+            Ok(container_ids) => started_processes.push((process, container_ids)),
+            Err(e) => {
+                let started = started_processes.iter().flat_map(|(_, ids)| ids);
+                for id in started.chain(std::iter::once(&container_id)) {
+                    let _ = runtime.stop(id).await;
+                    let _ = runtime.remove(id).await;
+                }
+                return Err(e);
+            }
+        }
+    }
+    for (process, container_ids) in &started_processes {
+        old_container_ids.extend(process.get_container_ids());
+        AppProcess::set_containers(db, &process.id, container_ids).await?;
     }
+    old_container_ids.extend(dropped_process_containers);
 
     // Step 7: Execute post-deploy commands (after health check)
     let post_deploy_commands = app.get_post_deploy_commands();
@@ -0,0 +1,282 @@
+//! Repository pipeline files (`rivetr.yaml`).
+//!
+//! An app's source can describe its own deployment pipeline in a
+//! `rivetr.yaml` (or `rivetr.yml`) next to its Dockerfile:
+//!
+//! ```yaml
+//! build:
+//!   type: dockerfile            # build type, as in the app settings
+//!   dockerfile: docker/Dockerfile.prod
+//!   target: production
+//! test:
+//!   timeout: 600                # seconds, for all commands together
+//!   commands:
+//!     - npm test
+//! healthcheck:
+//!   path: /health
+//!   window: 120
+//! pre_deploy:
+//!   - npm run migrate
+//! post_deploy:
+//!   - npm run warm-cache
+//! ```
+//!
+//! Every section is optional and replaces the matching app setting for that
+//! deployment only; apps without a pipeline file keep the implicit pipeline
+//! built from their settings. The `test` section replaces the app's test
+//! stage (see `engine::test_stage`).
+//!
+//! The file is read after the checkout and stored on the deployment, so a
+//! promoted pre-warm build runs with the pipeline of its own commit.
+//! Registry-image, inline Dockerfile and compose apps have no pipeline file.
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::api::validation::{
+    validate_build_target, validate_build_type, validate_deployment_commands, validate_dockerfile,
+    validate_healthcheck, validate_healthcheck_window, validate_test_timeout,
+};
+use crate::db::App;
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// Pipeline file names, in the order they are looked up
+pub const PIPELINE_FILE_NAMES: &[&str] = &["rivetr.yaml", "rivetr.yml"];
+
+/// A parsed pipeline file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineFile {
+    #[serde(default)]
+    pub build: BuildSection,
+    #[serde(default)]
+    pub test: TestSection,
+    #[serde(default)]
+    pub healthcheck: HealthcheckSection,
+    /// Replaces the app's pre-deploy commands
+    pub pre_deploy: Option<Vec<String>>,
+    /// Replaces the app's post-deploy commands
+    pub post_deploy: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BuildSection {
+    #[serde(rename = "type")]
+    pub build_type: Option<String>,
+    /// Dockerfile path relative to the app's source directory
+    pub dockerfile: Option<String>,
+    /// Multi-stage build target
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestSection {
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Seconds all commands may take together
+    pub timeout: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthcheckSection {
+    pub path: Option<String>,
+    /// Seconds the new container has to pass its health check
+    pub window: Option<i64>,
+}
+
+impl PipelineFile {
+    /// Parse and validate a pipeline file
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: PipelineFile = if content.trim().is_empty() {
+            PipelineFile::default()
+        } else {
+            serde_yaml::from_str(content).map_err(|e| e.to_string())?
+        };
+        file.validate()?;
+        Ok(file)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref build_type) = self.build.build_type {
+            validate_build_type(build_type).map_err(|e| format!("build.type: {}", e))?;
+            if build_type.eq_ignore_ascii_case("docker-compose") {
+                return Err("build.type: compose apps can't change their build type".to_string());
+            }
+        }
+        if let Some(ref dockerfile) = self.build.dockerfile {
+            validate_dockerfile(dockerfile).map_err(|e| format!("build.dockerfile: {}", e))?;
+        }
+        validate_build_target(&self.build.target).map_err(|e| format!("build.target: {}", e))?;
+        validate_deployment_commands(&Some(self.test.commands.clone()), "test.commands")?;
+        if let Some(timeout) = self.test.timeout {
+            validate_test_timeout(timeout).map_err(|e| format!("test.timeout: {}", e))?;
+        }
+        validate_healthcheck(&self.healthcheck.path)
+            .map_err(|e| format!("healthcheck.path: {}", e))?;
+        if let Some(window) = self.healthcheck.window {
+            validate_healthcheck_window(window)
+                .map_err(|e| format!("healthcheck.window: {}", e))?;
+        }
+        validate_deployment_commands(&self.pre_deploy, "pre_deploy")?;
+        validate_deployment_commands(&self.post_deploy, "post_deploy")?;
+        Ok(())
+    }
+
+    /// The app with this file's settings in place of its own
+    pub fn apply(&self, app: &App) -> App {
+        let mut app = app.clone();
+        if let Some(ref build_type) = self.build.build_type {
+            app.build_type = Some(build_type.to_lowercase());
+        }
+        if let Some(ref dockerfile) = self.build.dockerfile {
+            app.dockerfile_path = Some(dockerfile.clone());
+        }
+        if let Some(ref target) = self.build.target {
+            app.build_target = Some(target.clone()).filter(|t| !t.is_empty());
+        }
+        if let Some(ref path) = self.healthcheck.path {
+            app.healthcheck = Some(path.clone()).filter(|p| !p.is_empty());
+        }
+        if let Some(window) = self.healthcheck.window {
+            app.healthcheck_window_secs = window;
+        }
+        if let Some(ref commands) = self.pre_deploy {
+            app.pre_deploy_commands = serde_json::to_string(commands).ok();
+        }
+        if let Some(ref commands) = self.post_deploy {
+            app.post_deploy_commands = serde_json::to_string(commands).ok();
+        }
+        if !self.test.commands.is_empty() {
+            app.test_commands = serde_json::to_string(&self.test.commands).ok();
+        }
+        if let Some(timeout) = self.test.timeout {
+            app.test_timeout_secs = Some(timeout);
+        }
+        app
+    }
+}
+
+/// Read the pipeline file in `source_dir`, if there is one, and store it on
+/// the deployment. An invalid file fails the deployment.
+pub async fn load(
+    db: &DbPool,
+    deployment_id: &str,
+    source_dir: &Path,
+) -> Result<Option<PipelineFile>> {
+    let mut found = None;
+    for name in PIPELINE_FILE_NAMES {
+        if let Ok(content) = tokio::fs::read_to_string(source_dir.join(name)).await {
+            found = Some((*name, content));
+            break;
+        }
+    }
+    let Some((name, content)) = found else {
+        return Ok(None);
+    };
+
+    let file =
+        PipelineFile::parse(&content).map_err(|e| anyhow::anyhow!("Invalid {}: {}", name, e))?;
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!("Using the deployment pipeline from {}", name),
+    )
+    .await?;
+    sqlx::query("UPDATE deployments SET pipeline_file = ? WHERE id = ?")
+        .bind(&content)
+        .bind(deployment_id)
+        .execute(db)
+        .await?;
+    Ok(Some(file))
+}
+
+/// The pipeline file stored on the deployment, if its source had one
+pub async fn for_deployment(db: &DbPool, deployment_id: &str) -> Result<Option<PipelineFile>> {
+    let content: Option<String> =
+        sqlx::query_scalar("SELECT pipeline_file FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+    content
+        .map(|content| {
+            PipelineFile::parse(&content)
+                .map_err(|e| anyhow::anyhow!("Invalid stored pipeline file: {}", e))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let file = PipelineFile::parse(
+            "build:\n  type: Nixpacks\n  target: prod\n\
+             test:\n  commands:\n    - npm test\n\
+             healthcheck:\n  path: /health\n  window: 90\n\
+             post_deploy:\n  - npm run warm\n",
+        )
+        .unwrap();
+        assert_eq!(file.build.build_type.as_deref(), Some("Nixpacks"));
+        assert_eq!(file.test.commands, vec!["npm test".to_string()]);
+        assert_eq!(file.test.timeout, None);
+        assert_eq!(file.healthcheck.window, Some(90));
+        assert_eq!(file.pre_deploy, None);
+
+        assert_eq!(PipelineFile::parse("").unwrap(), PipelineFile::default());
+    }
+
+    #[test]
+    fn test_apply_overrides_only_set_sections() {
+        let app: App = serde_json::from_value(serde_json::json!({
+            "id": "app-1",
+            "name": "web",
+            "git_url": "https://github.com/acme/web",
+            "branch": "main",
+            "dockerfile": "Dockerfile",
+            "port": 3000,
+            "healthcheck": "/",
+            "environment": "production",
+            "basic_auth_enabled": 0,
+            "pre_deploy_commands": "[\"make migrate\"]",
+            "created_at": "",
+            "updated_at": "",
+        }))
+        .unwrap();
+
+        let file = PipelineFile::parse(
+            "build:\n  type: Nixpacks\nhealthcheck:\n  path: /ready\npost_deploy: [make warm]\n\
+             test:\n  timeout: 120\n  commands: [make test]\n",
+        )
+        .unwrap();
+        let applied = file.apply(&app);
+        assert_eq!(applied.build_type.as_deref(), Some("nixpacks"));
+        assert_eq!(applied.healthcheck.as_deref(), Some("/ready"));
+        assert_eq!(applied.healthcheck_window_secs, app.healthcheck_window_secs);
+        assert_eq!(applied.get_pre_deploy_commands(), vec!["make migrate"]);
+        assert_eq!(applied.get_post_deploy_commands(), vec!["make warm"]);
+        assert_eq!(applied.get_test_commands(), vec!["make test"]);
+        assert_eq!(applied.test_timeout_secs(), 120);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_files() {
+        // Unknown keys are typos, not silently ignored settings
+        assert!(PipelineFile::parse("tests:\n  commands: [make test]\n").is_err());
+        assert!(PipelineFile::parse("build:\n  type: magic\n").is_err());
+        assert!(PipelineFile::parse("build:\n  dockerfile: ../Dockerfile\n").is_err());
+        assert!(PipelineFile::parse("healthcheck:\n  path: health\n").is_err());
+        assert!(PipelineFile::parse("test:\n  timeout: 0\n").is_err());
+        assert!(PipelineFile::parse("pre_deploy:\n  - ''\n").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Listening-port detection for freshly started containers.
+//!
+//! A container whose process listens on a different port than the app is
+//! configured for (or only on localhost) starts fine but answers every proxied
+//! request with a 502. After start, the deployment reads the container's
+//! `/proc/net/tcp{,6}` to see which ports are actually listening and warns in
+//! the deployment log when they don't match the configuration.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::App;
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// `st` value of a listening socket in /proc/net/tcp
+const TCP_LISTEN: &str = "0A";
+
+/// Times to look for a listener before giving up (the process may still be booting)
+const ATTEMPTS: u32 = 5;
+
+/// A TCP socket listening inside the container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Listener {
+    pub port: u16,
+    /// Bound to 127.0.0.1 / ::1 only, so unreachable from the proxy
+    pub loopback_only: bool,
+}
+
+/// Parse listening sockets from `/proc/net/tcp` and `/proc/net/tcp6` contents.
+/// A port listed on both a loopback and a wildcard address counts as reachable.
+pub fn parse_proc_net_tcp(contents: &str) -> Vec<Listener> {
+    let mut listeners: Vec<Listener> = Vec::new();
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        // sl local_address rem_address st ...
+        if fields.len() < 4 || fields[3] != TCP_LISTEN {
+            continue;
+        }
+        let Some((addr, port)) = fields[1].split_once(':') else {
+            continue;
+        };
+        let Ok(port) = u16::from_str_radix(port, 16) else {
+            continue;
+        };
+        let loopback_only = is_loopback_hex(addr);
+        match listeners.iter_mut().find(|l| l.port == port) {
+            Some(existing) => existing.loopback_only &= loopback_only,
+            None => listeners.push(Listener {
+                port,
+                loopback_only,
+            }),
+        }
+    }
+    listeners.sort_by_key(|l| l.port);
+    listeners
+}
+
+/// Whether a hex address from /proc/net/tcp{,6} is a loopback address.
+/// IPv4 addresses are stored as little-endian u32, so 127.x.x.x ends in `7F`.
+fn is_loopback_hex(addr: &str) -> bool {
+    match addr.len() {
+        8 => addr.ends_with("7F"),
+        // ::1, and IPv4-mapped ::ffff:127.x.x.x
+        32 => {
+            addr == "00000000000000000000000001000000"
+                || (addr.starts_with("0000000000000000FFFF0000") && addr.ends_with("7F"))
+        }
+        _ => false,
+    }
+}
+
+/// Listening sockets in a running container, or `None` if they can't be read
+/// (no `cat` in the image, e.g. distroless or scratch)
+pub async fn listening_ports(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+) -> Option<Vec<Listener>> {
+    let cmd = vec![
+        "cat".to_string(),
+        "/proc/net/tcp".to_string(),
+        "/proc/net/tcp6".to_string(),
+    ];
+    // cat exits non-zero when tcp6 is missing (IPv6 disabled) but still prints tcp
+    let output = runtime.run_command(container_id, cmd).await.ok()?;
+    if output.stdout.trim().is_empty() {
+        return None;
+    }
+    Some(parse_proc_net_tcp(&output.stdout))
+}
+
+/// Check that the container listens on the app's port and log a warning to
+/// the deployment when it doesn't. Never fails the deployment.
+pub async fn warn_on_port_mismatch(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    container_id: &str,
+    app: &App,
+    port_env: Option<&str>,
+) {
+    let expected = app.port as u16;
+
+    // A user-set PORT overrides the injected one; the app then listens elsewhere
+    if let Some(port) = port_env {
+        if port.trim() != expected.to_string() {
+            let _ = add_deployment_log(
+                db,
+                deployment_id,
+                "warn",
+                &format!(
+                    "The PORT environment variable is set to {} but the app's port is {}; \
+                     the proxy forwards to port {}",
+                    port, expected, expected
+                ),
+            )
+            .await;
+        }
+    }
+
+    let mut listeners = Vec::new();
+    for attempt in 1..=ATTEMPTS {
+        match listening_ports(runtime.as_ref(), container_id).await {
+            Some(found) => listeners = found,
+            None => {
+                tracing::debug!(
+                    container = %container_id,
+                    "Could not read listening ports from the container; skipping port check"
+                );
+                return;
+            }
+        }
+        if listeners.iter().any(|l| l.port == expected) || attempt == ATTEMPTS {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+
+    let message = match listeners.iter().find(|l| l.port == expected) {
+        Some(listener) if !listener.loopback_only => return,
+        Some(_) => format!(
+            "The app listens on port {} only on localhost (127.0.0.1), so the proxy \
+             cannot reach it. Bind to 0.0.0.0 instead.",
+            expected
+        ),
+        None if listeners.is_empty() => format!(
+            "Nothing is listening on a TCP port in the container yet (expected port {}). \
+             If requests fail with 502, check that the app binds to $PORT.",
+            expected
+        ),
+        None => {
+            let ports = listeners
+                .iter()
+                .map(|l| l.port.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "The app is configured for port {} but the container listens on {}. \
+                 Update the app's port (or make the app listen on $PORT) to avoid 502 errors.",
+                expected, ports
+            )
+        }
+    };
+
+    tracing::warn!(app = %app.name, deployment_id = %deployment_id, "{}", message);
+    let _ = add_deployment_log(db, deployment_id, "warn", &message).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_NET_TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1 1
+   1: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 2 1
+   2: 0200000A:1F90 0500000A:C350 01 00000000:00000000 00:00000000 00000000     0        0 3 1
+  sl  local_address                         remote_address                        st
+   0: 00000000000000000000000001000000:1538 00000000000000000000000000000000:0000 0A
+   1: 00000000000000000000000000000000:0BB8 00000000000000000000000000000000:0000 0A
+   2: 0000000000000000FFFF00000100007F:0BB8 00000000000000000000000000000000:0000 0A
+";
+
+    #[test]
+    fn test_parse_proc_net_tcp() {
+        let listeners = parse_proc_net_tcp(PROC_NET_TCP);
+        assert_eq!(
+            listeners,
+            vec![
+                // ::ffff:127.0.0.1 and [::] on 3000: reachable
+                Listener {
+                    port: 3000,
+                    loopback_only: false
+                },
+                // 127.0.0.1:3306
+                Listener {
+                    port: 3306,
+                    loopback_only: true
+                },
+                // [::1]:5432
+                Listener {
+                    port: 5432,
+                    loopback_only: true
+                },
+                // 0.0.0.0:8080; the established connection on 8080 is ignored
+                Listener {
+                    port: 8080,
+                    loopback_only: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_proc_net_tcp_empty() {
+        assert!(parse_proc_net_tcp("").is_empty());
+        assert!(parse_proc_net_tcp("garbage line\n").is_empty());
+    }
+}
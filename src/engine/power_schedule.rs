@@ -0,0 +1,383 @@
+//! Power schedules: stop apps outside office hours and start them again.
+//!
+//! Every 60 seconds, enabled schedules whose `next_stop_at` / `next_start_at`
+//! has passed are acted on. Stopping parks the app's domains in the proxy so
+//! visitors get a "stopped" page instead of a 404; starting restarts the same
+//! container and restores its routes. Only apps the schedule itself stopped are
+//! started again, so a manual stop is never undone by the schedule.
+
+use crate::db::{
+    App, AppAccessRule, AppBasicAuthPath, AppBotSettings, AppCorsSettings, AppForwardAuth,
+    AppPowerSchedule, AppWafSettings, DeploymentEvent,
+};
+use crate::proxy::{Backend, BasicAuthConfig, RouteBatch, RouteTable};
+use crate::runtime::ContainerRuntime;
+use crate::utils::cron::next_run;
+use crate::DbPool;
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+/// A missing time is not due; the cycle initializes it instead.
+pub(crate) fn is_due(at: Option<&str>, now: DateTime<Utc>) -> bool {
+    at.and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t.with_timezone(&Utc) <= now)
+}
+
+/// Stop the app's running containers and park its domains on a page showing
+/// `message`. `reason` is recorded on the stopped deployments.
+pub(crate) async fn stop_app(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    app: &App,
+    message: &str,
+    reason: &str,
+) -> Result<()> {
+    // Park first so requests arriving during shutdown get the stopped page
+    let route_table = routes.load();
+    for domain in app.get_all_domain_names() {
+        route_table.park(&domain, message);
+    }
+    stop_containers(db, runtime, app, reason).await
+}
+
+/// Stop the app's running containers, marking their deployments stopped with
+/// `reason`. Leaves the app's routes alone.
+pub(crate) async fn stop_containers(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    app: &App,
+    reason: &str,
+) -> Result<()> {
+    let containers: Vec<(String,)> = sqlx::query_as(
+        "SELECT container_id FROM deployments \
+         WHERE app_id = ? AND status = 'running' AND container_id IS NOT NULL AND container_id != ''",
+    )
+    .bind(&app.id)
+    .fetch_all(db)
+    .await?;
+
+    for (container_id,) in &containers {
+        runtime
+            .stop_timeout(container_id, app.stop_grace_period)
+            .await
+            .with_context(|| format!("Failed to stop container {}", container_id))?;
+
+        // Mark stopped so the container monitor does not treat it as a crash
+        let stopped: Vec<String> = sqlx::query_scalar(
+            "UPDATE deployments SET status = 'stopped', finished_at = datetime('now') \
+             WHERE app_id = ? AND status = 'running' AND container_id = ? RETURNING id",
+        )
+        .bind(&app.id)
+        .bind(container_id)
+        .fetch_all(db)
+        .await?;
+        DeploymentEvent::record_all(db, &stopped, "stopped", Some(reason)).await;
+    }
+
+    tracing::info!(app = %app.name, containers = containers.len(), "{}", reason);
+    Ok(())
+}
+
+/// Start the app's most recently stopped container and restore its routes.
+/// `reason` is recorded on the restarted deployment.
+pub(crate) async fn start_app(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    app: &App,
+    reason: &str,
+) -> Result<()> {
+    let Some((container_id, port)) = start_container(db, runtime, app, reason).await? else {
+        // Redeployed (or removed) while stopped; nothing left to start
+        let route_table = routes.load();
+        for domain in app.get_all_domain_names() {
+            if route_table.parked_message(&domain).is_some() {
+                route_table.remove_route(&domain);
+            }
+        }
+        return Ok(());
+    };
+
+    match port {
+        Some(port) => restore_routes(db, routes, app, &container_id, port).await,
+        None => tracing::warn!(
+            app = %app.name,
+            container = %container_id,
+            "No port found after start; routes not restored"
+        ),
+    }
+    Ok(())
+}
+
+/// Start the app's most recently stopped container, marking its deployment
+/// running with `reason`. Returns the container ID and its host port, or
+/// `None` when there is no stopped container (e.g. it was redeployed).
+pub(crate) async fn start_container(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    app: &App,
+    reason: &str,
+) -> Result<Option<(String, Option<u16>)>> {
+    let deployment: Option<(String, String)> = sqlx::query_as(
+        "SELECT id, container_id FROM deployments \
+         WHERE app_id = ? AND status = 'stopped' AND container_id IS NOT NULL AND container_id != '' \
+         ORDER BY started_at DESC LIMIT 1",
+    )
+    .bind(&app.id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some((deployment_id, container_id)) = deployment else {
+        return Ok(None);
+    };
+
+    runtime
+        .start(&container_id)
+        .await
+        .with_context(|| format!("Failed to start container {}", container_id))?;
+
+    sqlx::query("UPDATE deployments SET status = 'running', finished_at = NULL WHERE id = ?")
+        .bind(&deployment_id)
+        .execute(db)
+        .await?;
+    DeploymentEvent::record_all(
+        db,
+        std::slice::from_ref(&deployment_id),
+        "running",
+        Some(reason),
+    )
+    .await;
+
+    let port = runtime
+        .inspect(&container_id)
+        .await
+        .ok()
+        .and_then(|info| info.port);
+
+    tracing::info!(app = %app.name, container = %container_id, "{}", reason);
+    Ok(Some((container_id, port)))
+}
+
+/// Route every domain of the app to the restarted container, replacing the parked pages
+pub(crate) async fn restore_routes(
+    db: &DbPool,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    app: &App,
+    container_id: &str,
+    port: u16,
+) {
+    let access_rules = AppAccessRule::list_enabled_for_proxy(db, &app.id)
+        .await
+        .unwrap_or_default();
+    let basic_auth_paths = AppBasicAuthPath::list_enabled_for_proxy(db, &app.id)
+        .await
+        .unwrap_or_default();
+    let waf_policy = AppWafSettings::load_policy(db, &app.id)
+        .await
+        .unwrap_or_default();
+    let bot_policy = AppBotSettings::load_policy(db, &app.id, false)
+        .await
+        .unwrap_or_default();
+    let forward_auth = AppForwardAuth::load_policy(db, &app.id)
+        .await
+        .unwrap_or_default();
+    let cors = AppCorsSettings::load_policy(db, &app.id)
+        .await
+        .unwrap_or_default();
+    let passthrough_domains = app.tls_passthrough_domains();
+
+    let mut batch = RouteBatch::new();
+    for (domain, www_redirect_target) in app.get_all_domains_with_redirects() {
+        let mut backend = Backend::new(container_id.to_string(), "127.0.0.1".to_string(), port)
+            .with_healthcheck(app.healthcheck.clone())
+            .with_health_probe(app.health_probe())
+            .with_strip_prefix(app.strip_prefix.clone());
+        backend.tls_passthrough = passthrough_domains.contains(&domain);
+
+        if www_redirect_target.is_some() {
+            backend.www_redirect_target = www_redirect_target;
+        } else {
+            if app.basic_auth_enabled != 0 {
+                if let (Some(username), Some(password_hash)) =
+                    (&app.basic_auth_username, &app.basic_auth_password_hash)
+                {
+                    backend.set_basic_auth(BasicAuthConfig::new(
+                        username.clone(),
+                        password_hash.clone(),
+                    ));
+                }
+            }
+            backend.set_access_rules(access_rules.clone());
+            backend.set_basic_auth_paths(basic_auth_paths.clone());
+            backend.set_waf(waf_policy.clone());
+            backend.set_bots(bot_policy.clone());
+            backend.set_forward_auth(forward_auth.clone());
+            backend.set_cors(cors.clone());
+        }
+        batch.add_route(domain, backend);
+    }
+    routes.load().apply(&batch);
+}
+
+/// Act on one schedule and advance its next run times
+async fn run_schedule(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    schedule: &AppPowerSchedule,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let stop_due = is_due(schedule.next_stop_at.as_deref(), now);
+    let start_due = is_due(schedule.next_start_at.as_deref(), now);
+    let mut stopped_at = schedule.stopped_at.clone();
+
+    if stop_due && stopped_at.is_none() {
+        let app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+            .bind(&schedule.app_id)
+            .fetch_one(db)
+            .await?;
+        match stop_app(
+            db,
+            runtime,
+            routes,
+            &app,
+            schedule.message(),
+            "Stopped by power schedule",
+        )
+        .await
+        {
+            Ok(()) => stopped_at = Some(now.to_rfc3339()),
+            Err(e) => tracing::error!(app = %app.name, error = %e, "Scheduled stop failed"),
+        }
+    } else if start_due && stopped_at.is_some() {
+        let app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+            .bind(&schedule.app_id)
+            .fetch_one(db)
+            .await?;
+        match start_app(db, runtime, routes, &app, "Started by power schedule").await {
+            Ok(()) => stopped_at = None,
+            Err(e) => tracing::error!(app = %app.name, error = %e, "Scheduled start failed"),
+        }
+    }
+
+    let next_stop_at = if stop_due || schedule.next_stop_at.is_none() {
+        next_run(&schedule.stop_cron, schedule.timezone.as_deref())
+    } else {
+        schedule.next_stop_at.clone()
+    };
+    let next_start_at = if start_due || schedule.next_start_at.is_none() {
+        next_run(&schedule.start_cron, schedule.timezone.as_deref())
+    } else {
+        schedule.next_start_at.clone()
+    };
+
+    sqlx::query(
+        "UPDATE app_power_schedules SET next_stop_at = ?, next_start_at = ?, stopped_at = ?, \
+         updated_at = datetime('now') WHERE app_id = ?",
+    )
+    .bind(&next_stop_at)
+    .bind(&next_start_at)
+    .bind(&stopped_at)
+    .bind(&schedule.app_id)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// One check cycle over every enabled schedule
+async fn power_schedule_cycle(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+) {
+    let schedules: Vec<AppPowerSchedule> =
+        match sqlx::query_as("SELECT * FROM app_power_schedules WHERE enabled = 1")
+            .fetch_all(db)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to fetch power schedules");
+                return;
+            }
+        };
+
+    let now = Utc::now();
+    for schedule in &schedules {
+        if let Err(e) = run_schedule(db, runtime, routes, schedule, now).await {
+            tracing::warn!(app_id = %schedule.app_id, error = %e, "Power schedule check failed");
+        }
+    }
+}
+
+/// Re-park the domains of apps a schedule is keeping stopped (the route table
+/// starts empty after a restart).
+async fn restore_parked_routes(db: &DbPool, routes: &Arc<ArcSwap<RouteTable>>) {
+    let schedules: Vec<AppPowerSchedule> = sqlx::query_as(
+        "SELECT * FROM app_power_schedules WHERE enabled = 1 AND stopped_at IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    for schedule in &schedules {
+        let app: Option<App> = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+            .bind(&schedule.app_id)
+            .fetch_optional(db)
+            .await
+            .unwrap_or_default();
+        let Some(app) = app else { continue };
+        for domain in app.get_all_domain_names() {
+            // Never shadow a live route (e.g. the app was redeployed meanwhile)
+            if routes.load().get_backend(&domain).is_none() {
+                routes.load().park(&domain, schedule.message());
+            }
+        }
+    }
+}
+
+/// Spawn the background power schedule checker (runs every 60 seconds)
+pub fn spawn_power_scheduler(
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    routes: Arc<ArcSwap<RouteTable>>,
+) {
+    tracing::info!("Starting app power scheduler (60s interval)");
+
+    tokio::spawn(async move {
+        restore_parked_routes(&db, &routes).await;
+
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded(
+                "power_scheduler",
+                power_schedule_cycle(&db, &runtime, &routes),
+            )
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let past = (now - chrono::Duration::minutes(1)).to_rfc3339();
+        let future = (now + chrono::Duration::minutes(1)).to_rfc3339();
+        assert!(is_due(Some(past.as_str()), now));
+        assert!(!is_due(Some(future.as_str()), now));
+        assert!(!is_due(None, now));
+    }
+}
@@ -11,12 +11,18 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
+use crate::config::BuildSandboxConfig;
 use crate::crypto;
-use crate::db::{App, PreviewDeployment, PreviewDeploymentStatus, SshKey};
+use crate::db::{
+    App, AppBotSettings, AppCorsSettings, PreviewDeployment, PreviewDeploymentStatus, SshKey,
+};
 use crate::proxy::{Backend, RouteTable};
-use crate::runtime::{BuildContext, ContainerRuntime, RunConfig};
+use crate::runtime::{BuildContext, BuildIsolation, ContainerRuntime, RunConfig};
 use crate::DbPool;
 
+use super::build_sandbox;
+use super::preview_database;
+
 /// Key length for AES-256 encryption
 const KEY_LENGTH: usize = 32;
 
@@ -122,7 +128,8 @@ pub async fn update_preview_status(
 ///
 /// This function:
 /// 1. Clones the PR branch
-/// 2. Builds the Docker image
+/// 2. Builds the Docker image (sandboxed when `sandbox.previews` is set or the
+///    app/team requires it)
 /// 3. Starts the container with resource limits
 /// 4. Updates the proxy route table
 pub async fn run_preview_deployment(
@@ -132,6 +139,7 @@ pub async fn run_preview_deployment(
     preview: &PreviewDeployment,
     app: &App,
     encryption_key: Option<&[u8; KEY_LENGTH]>,
+    sandbox: &BuildSandboxConfig,
 ) -> Result<()> {
     let preview_id = &preview.id;
     info!(
@@ -193,13 +201,37 @@ pub async fn run_preview_deployment(
         app.name, preview.pr_number, preview_id
     );
 
+    // PR previews build code from outside contributors; sandbox them when asked
+    let sandboxed = sandbox.previews || build_sandbox::is_required(db, app).await?;
+    let isolation = if sandboxed {
+        match build_sandbox::check_build(app.get_build_type(), &build_path, &dockerfile, sandbox)
+            .await
+        {
+            Ok(isolation) => isolation,
+            Err(e) => {
+                error!(error = %e, "Preview build refused by sandbox checks");
+                update_preview_status(
+                    db,
+                    preview_id,
+                    PreviewDeploymentStatus::Failed,
+                    Some(&format!("Build failed: {}", e)),
+                )
+                .await?;
+                let _ = tokio::fs::remove_dir_all(&work_dir).await;
+                return Err(e);
+            }
+        }
+    } else {
+        BuildIsolation::default()
+    };
+
     let build_ctx = BuildContext {
         path: build_path.to_string_lossy().to_string(),
         dockerfile,
         tag: image_tag.clone(),
         build_args: vec![],
         build_target: app.build_target.clone(),
-        custom_options: app.custom_docker_options.clone(),
+        custom_options: app.custom_docker_options.clone().filter(|_| !sandboxed),
         // Use reduced resource limits for preview builds
         cpu_limit: Some("1".to_string()),
         memory_limit: Some("1g".to_string()),
@@ -208,6 +240,10 @@ pub async fn run_preview_deployment(
         build_platforms: None,
         no_cache: false,
         source_commit: None,
+        isolation,
+        // PR builds don't share the app's cache, so they can't poison it
+        cache: None,
+        build_host: None,
     };
 
     if let Err(e) = runtime.build(&build_ctx).await {
@@ -261,6 +297,26 @@ pub async fn run_preview_deployment(
         env_vars.push(("PORT".to_string(), app.port.to_string()));
     }
 
+    // Point the preview at its own throwaway database, if the app asks for one
+    match preview_database::ensure_preview_database(db, runtime.as_ref(), preview, app).await {
+        Ok(Some((key, url))) => {
+            env_vars.retain(|(k, _)| k != &key);
+            env_vars.push((key, url));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!(error = %e, "Failed to provision preview database");
+            update_preview_status(
+                db,
+                preview_id,
+                PreviewDeploymentStatus::Failed,
+                Some(&format!("Database provisioning failed: {:#}", e)),
+            )
+            .await?;
+            return Err(e);
+        }
+    }
+
     // Use preview-specific resource limits (lower than production)
     let memory_limit = preview
         .memory_limit
@@ -274,7 +330,7 @@ pub async fn run_preview_deployment(
     let run_config = RunConfig {
         image: image_tag.clone(),
         name: container_name.clone(),
-        port: app.port as u16,
+        port: app.container_port(),
         env: env_vars,
         memory_limit,
         cpu_limit,
@@ -344,8 +400,21 @@ pub async fn run_preview_deployment(
     // Step 5: Update proxy routes
     if let Some(p) = port {
         let route_table = routes.load();
-        let backend = Backend::new(container_id.clone(), "127.0.0.1".to_string(), p)
-            .with_healthcheck(app.healthcheck.clone());
+        let mut backend = Backend::new(container_id.clone(), "127.0.0.1".to_string(), p)
+            .with_healthcheck(app.healthcheck.clone())
+            .with_health_probe(app.health_probe());
+        // Previews inherit the app's bot settings (robots "auto" denies indexing here)
+        backend.set_bots(
+            AppBotSettings::load_policy(db, &app.id, true)
+                .await
+                .unwrap_or_default(),
+        );
+        // Frontends previewed against the same API need the app's CORS policy
+        backend.set_cors(
+            AppCorsSettings::load_policy(db, &app.id)
+                .await
+                .unwrap_or_default(),
+        );
 
         route_table.add_route(preview.preview_domain.clone(), backend);
 
@@ -366,7 +435,8 @@ pub async fn run_preview_deployment(
 /// 1. Stops and removes the container
 /// 2. Removes the proxy route
 /// 3. Optionally removes the Docker image
-/// 4. Updates the database status to 'closed'
+/// 4. Destroys the preview's throwaway database, if it has one
+/// 5. Updates the database status to 'closed'
 pub async fn cleanup_preview(
     db: &DbPool,
     runtime: Arc<dyn ContainerRuntime>,
@@ -400,6 +470,12 @@ pub async fn cleanup_preview(
         }
     }
 
+    if let Err(e) =
+        preview_database::destroy_preview_database(db, runtime.as_ref(), &preview.id).await
+    {
+        warn!(error = %e, "Failed to destroy preview database");
+    }
+
     // Update status to closed
     update_preview_status(db, &preview.id, PreviewDeploymentStatus::Closed, None).await?;
 
@@ -515,10 +591,7 @@ async fn clone_with_ssh_key(url: &str, branch: &str, dest: &Path, ssh_key: &SshK
     }
 
     // Build GIT_SSH_COMMAND to use our key file
-    let git_ssh_command = format!(
-        "ssh -i {} -o StrictHostKeyChecking=accept-new -o UserKnownHostsFile=/dev/null",
-        key_file.display()
-    );
+    let git_ssh_command = ssh_key.git_ssh_command(&key_file);
 
     let output = Command::new("git")
         .env("GIT_SSH_COMMAND", &git_ssh_command)
@@ -564,13 +637,17 @@ pub async fn find_or_create_preview(
             .await?;
 
     if let Some(mut preview) = existing {
-        // Update existing preview with new commit info
+        // Update existing preview with new commit info. A push counts as
+        // activity, and a preview that was closed (e.g. expired) starts a new
+        // lifetime.
         let now = chrono::Utc::now().to_rfc3339();
         sqlx::query(
             r#"
             UPDATE preview_deployments
             SET commit_sha = ?, commit_message = ?, pr_title = ?, status = 'pending',
-                error_message = NULL, updated_at = ?
+                error_message = NULL, updated_at = ?, last_activity_at = ?,
+                expiry_warned_at = NULL,
+                created_at = CASE WHEN status = 'closed' THEN ? ELSE created_at END
             WHERE id = ?
             "#,
         )
@@ -578,6 +655,8 @@ pub async fn find_or_create_preview(
         .bind(&info.commit_message)
         .bind(&info.pr_title)
         .bind(&now)
+        .bind(&now)
+        .bind(&now)
         .bind(&preview.id)
         .execute(db)
         .await?;
@@ -585,7 +664,12 @@ pub async fn find_or_create_preview(
         preview.commit_sha = info.commit_sha.clone();
         preview.commit_message = info.commit_message.clone();
         preview.pr_title = info.pr_title.clone();
+        if preview.status == "closed" {
+            preview.created_at = now.clone();
+        }
         preview.status = "pending".to_string();
+        preview.last_activity_at = Some(now);
+        preview.expiry_warned_at = None;
 
         return Ok(preview);
     }
@@ -645,17 +729,22 @@ pub async fn find_or_create_preview(
         status: "pending".to_string(),
         error_message: None,
         github_comment_id: None,
+        gitlab_note_id: None,
         memory_limit: Some("256m".to_string()),
         cpu_limit: Some("0.5".to_string()),
         created_at: now.clone(),
         updated_at: now,
         closed_at: None,
+        last_activity_at: None,
+        expiry_warned_at: None,
+        database_id: None,
     };
 
     Ok(preview)
 }
 
 /// Post or update a GitHub PR comment with the preview deployment URL.
+/// GitLab previews get a merge request note instead.
 ///
 /// This function:
 /// 1. Finds the GitHub App and installation for the repo
@@ -672,9 +761,10 @@ pub async fn post_preview_comment(
     use crate::db::{GitHubApp, GitHubAppInstallation};
     use crate::github::{get_installation_token, GitHubClient};
 
-    // Only post comments for GitHub provider
-    if preview.provider_type != "github" {
-        return Ok(());
+    match preview.provider_type.as_str() {
+        "github" => {}
+        "gitlab" => return post_gitlab_preview_note(db, preview, status, encryption_key).await,
+        _ => return Ok(()),
     }
 
     // Parse owner/repo from repo_full_name
@@ -736,8 +826,53 @@ pub async fn post_preview_comment(
 
     let client = GitHubClient::new(token_response.token);
 
-    // Build the comment body
-    let comment_body = match status {
+    let comment_body = preview_comment_body(preview, status);
+
+    // Post new comment or update existing one
+    if let Some(comment_id) = preview.github_comment_id {
+        // Update existing comment
+        if let Err(e) = client
+            .update_comment(owner, repo, comment_id as u64, &comment_body)
+            .await
+        {
+            warn!(error = %e, "Failed to update GitHub PR comment, posting new one");
+            // Fall back to posting a new comment
+            let new_comment_id = client
+                .post_comment(owner, repo, preview.pr_number as u64, &comment_body)
+                .await?;
+            // Store the new comment ID
+            sqlx::query("UPDATE preview_deployments SET github_comment_id = ? WHERE id = ?")
+                .bind(new_comment_id as i64)
+                .bind(&preview.id)
+                .execute(db)
+                .await?;
+        }
+    } else {
+        // Post new comment
+        let comment_id = client
+            .post_comment(owner, repo, preview.pr_number as u64, &comment_body)
+            .await?;
+
+        // Store the comment ID for future updates
+        sqlx::query("UPDATE preview_deployments SET github_comment_id = ? WHERE id = ?")
+            .bind(comment_id as i64)
+            .bind(&preview.id)
+            .execute(db)
+            .await?;
+
+        info!(
+            preview_id = %preview.id,
+            comment_id = comment_id,
+            "Posted GitHub PR comment with preview URL"
+        );
+    }
+
+    Ok(())
+}
+
+/// Markdown body of the preview status comment (or note) for `status`
+fn preview_comment_body(preview: &PreviewDeployment, status: &str) -> String {
+    match status {
         "running" => {
             format!(
                 "## Preview Deployment Ready\n\n\
@@ -795,50 +930,131 @@ pub async fn post_preview_comment(
                 preview.preview_domain, preview.preview_domain, preview.pr_source_branch, status,
             )
         }
+    }
+}
+/// Post or update the merge request note of a GitLab preview, with the token
+/// of the git provider the app is linked to
+async fn post_gitlab_preview_note(
+    db: &DbPool,
+    preview: &PreviewDeployment,
+    status: &str,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<()> {
+    use crate::db::GitProvider;
+
+    let Some(api_base) = preview
+        .pr_url
+        .as_deref()
+        .and_then(|url| gitlab_api_base(url, &preview.repo_full_name))
+    else {
+        warn!(preview_id = %preview.id, "Can't tell the GitLab instance of the merge request");
+        return Ok(());
     };
 
-    // Post new comment or update existing one
-    if let Some(comment_id) = preview.github_comment_id {
-        // Update existing comment
-        if let Err(e) = client
-            .update_comment(owner, repo, comment_id as u64, &comment_body)
-            .await
-        {
-            warn!(error = %e, "Failed to update GitHub PR comment, posting new one");
-            // Fall back to posting a new comment
-            let new_comment_id = client
-                .post_comment(owner, repo, preview.pr_number as u64, &comment_body)
-                .await?;
-            // Store the new comment ID
-            sqlx::query("UPDATE preview_deployments SET github_comment_id = ? WHERE id = ?")
-                .bind(new_comment_id as i64)
-                .bind(&preview.id)
-                .execute(db)
-                .await?;
-        }
-    } else {
-        // Post new comment
-        let comment_id = client
-            .post_comment(owner, repo, preview.pr_number as u64, &comment_body)
-            .await?;
-
-        // Store the comment ID for future updates
-        sqlx::query("UPDATE preview_deployments SET github_comment_id = ? WHERE id = ?")
-            .bind(comment_id as i64)
-            .bind(&preview.id)
-            .execute(db)
-            .await?;
-
+    let provider: Option<GitProvider> = sqlx::query_as(
+        "SELECT git_providers.* FROM git_providers \
+         JOIN apps ON apps.git_provider_id = git_providers.id \
+         WHERE apps.id = ? AND git_providers.provider = 'gitlab'",
+    )
+    .bind(&preview.app_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(provider) = provider else {
         info!(
             preview_id = %preview.id,
-            comment_id = comment_id,
-            "Posted GitHub PR comment with preview URL"
+            "No GitLab account linked to the app, skipping merge request note"
         );
+        return Ok(());
+    };
+    let token = crypto::decrypt_if_encrypted(&provider.access_token, encryption_key)
+        .context("Failed to decrypt GitLab token")?;
+
+    let notes_url = format!(
+        "{}/projects/{}/merge_requests/{}/notes",
+        api_base,
+        preview.repo_full_name.replace('/', "%2F"),
+        preview.pr_number
+    );
+    let body = serde_json::json!({ "body": preview_comment_body(preview, status) });
+    let client = reqwest::Client::new();
+
+    if let Some(note_id) = preview.gitlab_note_id {
+        let updated = client
+            .put(format!("{}/{}", notes_url, note_id))
+            .bearer_auth(&token)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match updated {
+            Ok(_) => return Ok(()),
+            Err(e) => warn!(error = %e, "Failed to update GitLab MR note, posting new one"),
+        }
     }
 
+    #[derive(serde::Deserialize)]
+    struct Note {
+        id: i64,
+    }
+    let note: Note = client
+        .post(&notes_url)
+        .bearer_auth(&token)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    sqlx::query("UPDATE preview_deployments SET gitlab_note_id = ? WHERE id = ?")
+        .bind(note.id)
+        .bind(&preview.id)
+        .execute(db)
+        .await?;
+
+    info!(
+        preview_id = %preview.id,
+        note_id = note.id,
+        "Posted GitLab MR note with preview URL"
+    );
+
     Ok(())
 }
 
+/// API root of the GitLab instance hosting a merge request, e.g.
+/// `https://git.example.com/gitlab/api/v4` for
+/// `https://git.example.com/gitlab/acme/web/-/merge_requests/3` of `acme/web`
+fn gitlab_api_base(mr_url: &str, project_path: &str) -> Option<String> {
+    let (project_url, _) = mr_url.split_once("/-/merge_requests/")?;
+    let instance = project_url
+        .strip_suffix(project_path)?
+        .trim_end_matches('/');
+    instance
+        .contains("://")
+        .then(|| format!("{}/api/v4", instance))
+}
+
+/// Post the preview's current status to its pull or merge request, logging
+/// failures
+pub async fn comment_preview_status(
+    db: &DbPool,
+    preview_id: &str,
+    status: &str,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) {
+    let preview: Option<PreviewDeployment> =
+        sqlx::query_as("SELECT * FROM preview_deployments WHERE id = ?")
+            .bind(preview_id)
+            .fetch_optional(db)
+            .await
+            .unwrap_or(None);
+    if let Some(preview) = preview {
+        if let Err(e) = post_preview_comment(db, &preview, status, encryption_key).await {
+            warn!(preview_id = %preview_id, error = %e, "Failed to post preview status comment");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -869,4 +1085,25 @@ mod tests {
             "rivetr-preview-my-app-pr-123"
         );
     }
+
+    #[test]
+    fn test_gitlab_api_base() {
+        assert_eq!(
+            gitlab_api_base("https://gitlab.com/acme/web/-/merge_requests/3", "acme/web")
+                .as_deref(),
+            Some("https://gitlab.com/api/v4")
+        );
+        assert_eq!(
+            gitlab_api_base(
+                "https://git.example.com/gitlab/acme/tools/web/-/merge_requests/12",
+                "acme/tools/web"
+            )
+            .as_deref(),
+            Some("https://git.example.com/gitlab/api/v4")
+        );
+        assert_eq!(
+            gitlab_api_base("https://gitlab.com/acme/web/-/merge_requests/3", "acme/api"),
+            None
+        );
+    }
 }
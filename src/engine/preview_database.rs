@@ -0,0 +1,399 @@
+//! Throwaway managed databases of preview deployments.
+//!
+//! An app with `preview_database` set gets a database for each preview,
+//! modelled on its `preview_database_source_id` database (same type, version
+//! and image). It starts out blank (`empty`) or with the source's tables and
+//! indexes but none of its rows (`schema`), and its connection URL is
+//! injected into the preview's env under `preview_database_env`. Redeploys of
+//! the preview keep the database; it is destroyed with the preview. It has no
+//! volume, so removing its container discards the data.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use crate::db::{
+    App, DatabaseCredentials, DatabaseStatus, DatabaseType, ManagedDatabase, PreviewDeployment,
+    PREVIEW_DATABASE_SCHEMA,
+};
+use crate::runtime::{ContainerRuntime, RunConfig};
+use crate::DbPool;
+
+use super::database_config::{generate_env_vars, generate_password, generate_username, get_config};
+use super::database_seed::{load_file, wait_until_ready, LoadFormat};
+use super::preview::generate_preview_container_name;
+
+/// Resource limits of preview databases, in line with preview containers
+const MEMORY_LIMIT: &str = "256mb";
+const CPU_LIMIT: &str = "0.5";
+
+/// Name of the managed database of a preview. The preview ID suffix keeps it
+/// unique when a pull request is reopened before the old one is destroyed.
+pub fn preview_database_name(app_name: &str, pr_number: i64, preview_id: &str) -> String {
+    let container_name = generate_preview_container_name(app_name, pr_number);
+    format!(
+        "{}-{}-db",
+        container_name.trim_start_matches("rivetr-"),
+        &preview_id[..8.min(preview_id.len())]
+    )
+}
+
+/// Command printing the schema of a database without its rows. `None` for
+/// database types whose schema can't be copied.
+fn schema_dump_command(db_type: &DatabaseType, creds: &DatabaseCredentials) -> Option<Vec<String>> {
+    let db_name = creds
+        .database
+        .clone()
+        .unwrap_or_else(|| creds.username.clone());
+    let root_password = creds.root_password.as_deref().unwrap_or(&creds.password);
+    let script = match db_type {
+        DatabaseType::Postgres => format!(
+            "PGPASSWORD='{}' pg_dump --schema-only --no-owner --no-privileges -U {} -d {}",
+            creds.password, creds.username, db_name
+        ),
+        DatabaseType::Mysql => format!(
+            "mysqldump --no-data --no-tablespaces -u root -p'{}' {}",
+            root_password, db_name
+        ),
+        DatabaseType::Mariadb => format!(
+            "mariadb-dump --no-data -u root -p'{}' {}",
+            root_password, db_name
+        ),
+        _ => return None,
+    };
+    Some(vec!["sh".to_string(), "-c".to_string(), script])
+}
+
+async fn load_database(db: &DbPool, id: &str) -> Result<Option<ManagedDatabase>> {
+    Ok(
+        sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
+            .bind(id)
+            .fetch_optional(db)
+            .await?,
+    )
+}
+
+/// ID of the database currently attached to a preview
+async fn attached_database_id(db: &DbPool, preview_id: &str) -> Result<Option<String>> {
+    let row: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT database_id FROM preview_deployments WHERE id = ?")
+            .bind(preview_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(row.and_then(|(id,)| id))
+}
+
+/// Provision the preview's database unless it already has one, and return
+/// the env var pointing the preview at it. `None` when the app doesn't
+/// provision preview databases.
+pub async fn ensure_preview_database(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    preview: &PreviewDeployment,
+    app: &App,
+) -> Result<Option<(String, String)>> {
+    let Some(mode) = app.preview_database.as_deref().filter(|m| !m.is_empty()) else {
+        return Ok(None);
+    };
+
+    let database = match reuse_preview_database(db, runtime, &preview.id).await? {
+        Some(database) => database,
+        None => {
+            let source_id = app
+                .preview_database_source_id
+                .as_deref()
+                .context("No source database is set for preview databases")?;
+            let source = load_database(db, source_id)
+                .await?
+                .context("The source database of preview databases no longer exists")?;
+            let database = create_preview_database(db, runtime, preview, app, &source).await?;
+            if mode == PREVIEW_DATABASE_SCHEMA {
+                if let Err(e) = copy_schema(runtime, &source, &database).await {
+                    // Start over on the next deploy rather than keep a
+                    // database without the schema
+                    destroy_preview_database(db, runtime, &preview.id).await?;
+                    return Err(e.context("Failed to copy the schema"));
+                }
+            }
+            database
+        }
+    };
+
+    let url = database
+        .internal_connection_string()
+        .context("Preview database has no connection URL")?;
+    Ok(Some((app.preview_database_env_key().to_string(), url)))
+}
+
+/// The preview's existing database, started again if it was stopped. A
+/// database whose container is gone is destroyed, as its data went with it.
+async fn reuse_preview_database(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    preview_id: &str,
+) -> Result<Option<ManagedDatabase>> {
+    let Some(database_id) = attached_database_id(db, preview_id).await? else {
+        return Ok(None);
+    };
+    let Some(database) = load_database(db, &database_id).await? else {
+        return Ok(None);
+    };
+    let container_id = database.container_id.clone().unwrap_or_default();
+    let running = match runtime.inspect(&container_id).await {
+        Ok(info) => info.running || runtime.start(&container_id).await.is_ok(),
+        Err(_) => false,
+    };
+    if running {
+        return Ok(Some(database));
+    }
+    destroy_preview_database(db, runtime, preview_id).await?;
+    Ok(None)
+}
+
+/// Create and start a blank database like `source` for the preview
+async fn create_preview_database(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    preview: &PreviewDeployment,
+    app: &App,
+    source: &ManagedDatabase,
+) -> Result<ManagedDatabase> {
+    let db_type = source.get_db_type();
+    let config = get_config(&db_type);
+    let source_creds = source
+        .get_credentials()
+        .context("Invalid credentials of the source database")?;
+
+    // The database keeps the source's name so the app finds its tables
+    let username = generate_username();
+    let credentials = DatabaseCredentials {
+        username: username.clone(),
+        password: generate_password(24),
+        database: source_creds.database.clone().or(Some(username)),
+        root_password: source_creds
+            .root_password
+            .as_ref()
+            .map(|_| generate_password(32)),
+    };
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let name = preview_database_name(&app.name, preview.pr_number, &preview.id);
+    sqlx::query(
+        r#"
+        INSERT INTO databases (
+            id, name, db_type, version, status, internal_port, external_port,
+            public_access, credentials, memory_limit, cpu_limit, project_id, team_id,
+            created_at, updated_at, container_slug, custom_image
+        ) VALUES (?, ?, ?, ?, ?, ?, 0, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(&source.db_type)
+    .bind(&source.version)
+    .bind(DatabaseStatus::Pulling.to_string())
+    .bind(config.port as i32)
+    .bind(serde_json::to_string(&credentials)?)
+    .bind(MEMORY_LIMIT)
+    .bind(CPU_LIMIT)
+    .bind(&app.project_id)
+    .bind(&app.team_id)
+    .bind(&now)
+    .bind(&now)
+    .bind(ManagedDatabase::build_slug(&id))
+    .bind(&source.custom_image)
+    .execute(db)
+    .await
+    .context("Failed to create the preview database")?;
+
+    // Attach it right away so cleanup finds it even if starting fails
+    sqlx::query("UPDATE preview_deployments SET database_id = ? WHERE id = ?")
+        .bind(&id)
+        .bind(&preview.id)
+        .execute(db)
+        .await?;
+
+    let database = load_database(db, &id)
+        .await?
+        .context("Preview database disappeared")?;
+    let image = source
+        .custom_image
+        .clone()
+        .unwrap_or_else(|| format!("{}:{}", config.image, source.version));
+    runtime.pull_image(&image, None).await?;
+
+    let container_name = database.container_name();
+    let run_config = RunConfig {
+        image,
+        name: container_name.clone(),
+        port: config.port,
+        env: generate_env_vars(&db_type, &credentials),
+        memory_limit: Some(MEMORY_LIMIT.to_string()),
+        cpu_limit: Some(CPU_LIMIT.to_string()),
+        port_mappings: vec![],
+        network_aliases: vec![container_name],
+        extra_hosts: vec![],
+        labels: HashMap::from([
+            ("rivetr.preview".to_string(), "true".to_string()),
+            ("rivetr.app".to_string(), app.id.clone()),
+            ("rivetr.pr".to_string(), preview.pr_number.to_string()),
+        ]),
+        binds: vec![],
+        restart_policy: "unless-stopped".to_string(),
+        privileged: false,
+        cap_add: vec![],
+        cap_drop: vec![],
+        devices: vec![],
+        shm_size: None,
+        init: false,
+        app_id: None,
+        gpus: None,
+        ulimits: vec![],
+        security_opt: vec![],
+        cmd: (!config.cmd_args.is_empty())
+            .then(|| config.cmd_args.iter().map(|s| s.to_string()).collect()),
+        network: None,
+        custom_labels: vec![],
+    };
+    let container_id = runtime.run(&run_config).await?;
+
+    sqlx::query(
+        "UPDATE databases SET container_id = ?, status = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(&container_id)
+    .bind(DatabaseStatus::Running.to_string())
+    .bind(&id)
+    .execute(db)
+    .await?;
+
+    tracing::info!(
+        preview_id = %preview.id,
+        database = %name,
+        "Provisioned preview database"
+    );
+    load_database(db, &id)
+        .await?
+        .context("Preview database disappeared")
+}
+
+/// Load the schema of `source` into the freshly started `target`
+async fn copy_schema(
+    runtime: &dyn ContainerRuntime,
+    source: &ManagedDatabase,
+    target: &ManagedDatabase,
+) -> Result<()> {
+    let db_type = source.get_db_type();
+    let source_container = source
+        .container_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+        .context("The source database isn't running")?;
+    let target_container = target
+        .container_id
+        .as_deref()
+        .context("Preview database has no container")?;
+    let source_creds = source
+        .get_credentials()
+        .context("Invalid credentials of the source database")?;
+    let target_creds = target
+        .get_credentials()
+        .context("Invalid credentials of the preview database")?;
+
+    let command = schema_dump_command(&db_type, &source_creds)
+        .with_context(|| format!("The schema of {} databases can't be copied", db_type))?;
+    let dump = runtime.run_command(source_container, command).await?;
+    if dump.exit_code != 0 {
+        anyhow::bail!(
+            "Schema dump exited with code {}: {}",
+            dump.exit_code,
+            dump.stderr.trim()
+        );
+    }
+
+    wait_until_ready(runtime, target_container, &db_type, &target_creds).await?;
+    load_file(
+        runtime,
+        target_container,
+        &db_type,
+        &target_creds,
+        dump.stdout.as_bytes(),
+        LoadFormat::Script,
+    )
+    .await
+}
+
+/// Destroy the preview's database, if it has one
+pub async fn destroy_preview_database(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    preview_id: &str,
+) -> Result<()> {
+    let Some(database_id) = attached_database_id(db, preview_id).await? else {
+        return Ok(());
+    };
+
+    if let Some(database) = load_database(db, &database_id).await? {
+        if let Some(container_id) = database.container_id.as_deref().filter(|id| !id.is_empty()) {
+            if let Err(e) = runtime.stop(container_id).await {
+                tracing::warn!(error = %e, "Failed to stop preview database container");
+            }
+            if let Err(e) = runtime.remove(container_id).await {
+                tracing::warn!(error = %e, "Failed to remove preview database container");
+            }
+        }
+        sqlx::query("DELETE FROM databases WHERE id = ?")
+            .bind(&database_id)
+            .execute(db)
+            .await?;
+        tracing::info!(
+            preview_id = %preview_id,
+            database = %database.name,
+            "Destroyed preview database"
+        );
+    }
+
+    sqlx::query("UPDATE preview_deployments SET database_id = NULL WHERE id = ?")
+        .bind(preview_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_dump_command_matches_schema_copy_support() {
+        let creds = DatabaseCredentials {
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            database: Some("app".to_string()),
+            root_password: None,
+        };
+        for db_type in [
+            DatabaseType::Postgres,
+            DatabaseType::Mysql,
+            DatabaseType::Mariadb,
+            DatabaseType::Mongodb,
+            DatabaseType::Redis,
+        ] {
+            assert_eq!(
+                schema_dump_command(&db_type, &creds).is_some(),
+                db_type.supports_schema_copy(),
+                "{}",
+                db_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_preview_database_name() {
+        assert_eq!(
+            preview_database_name("My App", 42, "0123456789abcdef"),
+            "preview-my-app-pr-42-01234567-db"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Preview deployment expiry: tear previews down after a maximum lifetime or
+//! a period without traffic.
+//!
+//! Every 60 seconds, running previews of apps with `preview_max_lifetime_hours`
+//! or `preview_idle_timeout_hours` set are checked. Activity is the last request
+//! the proxy served for the preview's domain (kept in memory and persisted to
+//! `last_activity_at` here), a push to the pull request, or the preview becoming
+//! ready. A `preview_expiring` notification is queued ahead of the deadline;
+//! once it has passed the preview's container, image and route are removed like
+//! when the pull request is closed. A later push deploys the preview again.
+
+use crate::db::{App, NotificationEventType, PreviewDeployment};
+use crate::notifications::{outbox, NotificationPayload};
+use crate::proxy::{traffic, RouteTable};
+use crate::runtime::ContainerRuntime;
+use crate::utils::time::parse_time;
+use crate::DbPool;
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::preview::cleanup_preview;
+
+/// Longest notice given before a preview is torn down
+const MAX_WARNING_LEAD_MINUTES: i64 = 60;
+
+/// Which limit a preview runs into first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryReason {
+    Lifetime,
+    Idle,
+}
+
+/// When a preview expires and how long before that it is warned about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expiry {
+    pub at: DateTime<Utc>,
+    pub lead: ChronoDuration,
+    pub reason: ExpiryReason,
+}
+
+impl Expiry {
+    pub fn warn_at(&self) -> DateTime<Utc> {
+        self.at - self.lead
+    }
+}
+
+/// A quarter of the limit, at most an hour, so short limits aren't warned
+/// about as soon as the preview starts
+fn warning_lead(limit: ChronoDuration) -> ChronoDuration {
+    std::cmp::min(limit / 4, ChronoDuration::minutes(MAX_WARNING_LEAD_MINUTES))
+}
+
+/// The earliest of the lifetime and inactivity deadlines, if either limit is set
+pub fn preview_expiry(
+    created_at: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    max_lifetime_hours: Option<i64>,
+    idle_timeout_hours: Option<i64>,
+) -> Option<Expiry> {
+    let candidates = [
+        (created_at, max_lifetime_hours, ExpiryReason::Lifetime),
+        (last_activity, idle_timeout_hours, ExpiryReason::Idle),
+    ];
+    candidates
+        .into_iter()
+        .filter_map(|(from, hours, reason)| {
+            let limit = ChronoDuration::hours(hours.filter(|h| *h > 0)?);
+            Some(Expiry {
+                at: from + limit,
+                lead: warning_lead(limit),
+                reason,
+            })
+        })
+        .min_by_key(|expiry| expiry.at)
+}
+
+fn expiry_message(
+    app: &App,
+    preview: &PreviewDeployment,
+    expiry: &Expiry,
+    now: DateTime<Utc>,
+) -> String {
+    let minutes = (expiry.at - now).num_minutes().max(1);
+    let because = match expiry.reason {
+        ExpiryReason::Lifetime => format!(
+            "it reaches its maximum lifetime of {} hours",
+            app.preview_max_lifetime_hours.unwrap_or_default()
+        ),
+        ExpiryReason::Idle => format!(
+            "it has had no requests for {} hours",
+            app.preview_idle_timeout_hours.unwrap_or_default()
+        ),
+    };
+    format!(
+        "The preview of PR #{} ({}) will be removed in about {} minutes because {}. \
+         Push to the pull request to deploy it again.",
+        preview.pr_number, preview.preview_domain, minutes, because
+    )
+}
+
+/// Check one running preview: persist its activity, warn about or carry out
+/// its expiry
+async fn check_preview(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    app: &App,
+    preview: &PreviewDeployment,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let Some(created_at) = parse_time(&preview.created_at) else {
+        return Ok(());
+    };
+
+    let stored_activity = preview.last_activity_at.as_deref().and_then(parse_time);
+    let seen = traffic::last_request(&preview.preview_domain);
+    if let Some(seen) = seen.filter(|s| stored_activity.map_or(true, |stored| *s > stored)) {
+        sqlx::query("UPDATE preview_deployments SET last_activity_at = ? WHERE id = ?")
+            .bind(seen.to_rfc3339())
+            .bind(&preview.id)
+            .execute(db)
+            .await?;
+    }
+    let last_activity = [stored_activity, seen, parse_time(&preview.updated_at)]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(created_at);
+
+    let Some(expiry) = preview_expiry(
+        created_at,
+        last_activity,
+        app.preview_max_lifetime_hours,
+        app.preview_idle_timeout_hours,
+    ) else {
+        return Ok(());
+    };
+
+    let warned_at = preview.expiry_warned_at.as_deref().and_then(parse_time);
+    match warned_at {
+        // Activity moved the deadline out of the warning window
+        Some(_) if now < expiry.warn_at() => {
+            sqlx::query("UPDATE preview_deployments SET expiry_warned_at = NULL WHERE id = ?")
+                .bind(&preview.id)
+                .execute(db)
+                .await?;
+        }
+        // A late warning (e.g. after downtime) still gets the full notice
+        Some(warned) if now >= std::cmp::max(expiry.at, warned + expiry.lead) => {
+            tracing::info!(
+                app = %app.name,
+                pr = preview.pr_number,
+                reason = ?expiry.reason,
+                "Preview deployment expired"
+            );
+            cleanup_preview(db, runtime.clone(), routes.clone(), preview).await?;
+        }
+        Some(_) => {}
+        None if now >= expiry.warn_at() => {
+            sqlx::query("UPDATE preview_deployments SET expiry_warned_at = ? WHERE id = ?")
+                .bind(now.to_rfc3339())
+                .bind(&preview.id)
+                .execute(db)
+                .await?;
+            let payload = NotificationPayload::app_event(
+                NotificationEventType::PreviewExpiring,
+                app.id.clone(),
+                app.name.clone(),
+                expiry_message(app, preview, &expiry, now),
+            );
+            if let Err(e) = outbox::enqueue(db, &payload).await {
+                tracing::warn!(error = %e, "Failed to queue preview_expiring notification");
+            }
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+async fn preview_expiry_cycle(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+) {
+    let apps: Vec<App> = match sqlx::query_as(
+        "SELECT * FROM apps \
+         WHERE preview_max_lifetime_hours IS NOT NULL OR preview_idle_timeout_hours IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch apps with preview expiry");
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for app in &apps {
+        let previews: Vec<PreviewDeployment> = match sqlx::query_as(
+            "SELECT * FROM preview_deployments WHERE app_id = ? AND status = 'running'",
+        )
+        .bind(&app.id)
+        .fetch_all(db)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!(app = %app.name, error = %e, "Failed to fetch preview deployments");
+                continue;
+            }
+        };
+
+        for preview in &previews {
+            if let Err(e) = check_preview(db, runtime, routes, app, preview, now).await {
+                tracing::warn!(
+                    preview_id = %preview.id,
+                    error = %e,
+                    "Preview expiry check failed"
+                );
+            }
+        }
+    }
+}
+
+/// Spawn the background preview expiry checker (runs every 60 seconds)
+pub fn spawn_preview_expiry_checker(
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    routes: Arc<ArcSwap<RouteTable>>,
+) {
+    tracing::info!("Starting preview expiry checker (60s interval)");
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded(
+                "preview_expiry",
+                preview_expiry_cycle(&db, &runtime, &routes),
+            )
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_expiry_takes_the_earliest_limit() {
+        let created = Utc::now() - ChronoDuration::hours(10);
+        let active = Utc::now() - ChronoDuration::hours(1);
+
+        assert_eq!(preview_expiry(created, active, None, None), None);
+        assert_eq!(preview_expiry(created, active, Some(0), None), None);
+
+        let lifetime = preview_expiry(created, active, Some(12), Some(4)).unwrap();
+        assert_eq!(lifetime.reason, ExpiryReason::Lifetime);
+        assert_eq!(lifetime.at, created + ChronoDuration::hours(12));
+        assert_eq!(lifetime.lead, ChronoDuration::hours(1));
+
+        let idle = preview_expiry(created, active, Some(48), Some(2)).unwrap();
+        assert_eq!(idle.reason, ExpiryReason::Idle);
+        assert_eq!(idle.at, active + ChronoDuration::hours(2));
+        assert_eq!(idle.lead, ChronoDuration::minutes(30));
+        assert_eq!(idle.warn_at(), active + ChronoDuration::minutes(90));
+    }
+}
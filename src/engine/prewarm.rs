@@ -0,0 +1,396 @@
+//! Pre-warmed deployment slots: build the next image ahead of release.
+//!
+//! A pre-warm build clones and builds (or pulls) an app's image exactly like a
+//! deployment but stops before starting a container, leaving the deployment at
+//! status `prewarmed` with its image tag set. Promoting the slot re-queues that
+//! deployment; with the image already in place the pipeline skips straight to
+//! starting the container, so the release itself takes seconds.
+//!
+//! Slots are filled on demand, on an off-peak cron schedule (checked every 60
+//! seconds, UTC) or, with `on_push`, by push webhooks instead of deploying.
+//! Each app has one slot; filling it again retires the previous build.
+
+use crate::db::{App, AppPrewarmSettings, DeploymentEvent};
+use crate::runtime::ContainerRuntime;
+use crate::utils::cron::next_run;
+use crate::DbPool;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+use super::power_schedule::is_due;
+use super::{
+    add_deployment_log, build_prewarm_image, update_deployment_status, BuildLimits, DeploymentJob,
+    KEY_LENGTH,
+};
+
+/// Statuses of a slot whose build has not finished yet
+const IN_PROGRESS: &[&str] = &["pending", "cloning", "building"];
+
+/// Commit a pre-warm build targets (all None = the branch head at build time)
+#[derive(Debug, Clone, Default)]
+pub struct PrewarmCommit {
+    pub sha: Option<String>,
+    pub message: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Create a pre-warm deployment for `app`, make it the app's slot and queue it.
+pub async fn queue_prewarm(
+    db: &DbPool,
+    deploy_tx: &mpsc::Sender<DeploymentJob>,
+    app: &App,
+    commit: PrewarmCommit,
+) -> Result<String> {
+    if let Some(previous) = AppPrewarmSettings::get(db, &app.id)
+        .await?
+        .and_then(|s| s.deployment_id)
+    {
+        retire_slot(db, &previous).await?;
+    }
+
+    let deployment_id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO deployments \
+         (id, app_id, commit_sha, commit_message, commit_author, commit_branch, status, started_at) \
+         VALUES (?, ?, ?, ?, ?, ?, 'pending', ?)",
+    )
+    .bind(&deployment_id)
+    .bind(&app.id)
+    .bind(&commit.sha)
+    .bind(&commit.message)
+    .bind(&commit.author)
+    .bind(&app.branch)
+    .bind(&now)
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO app_prewarm_settings (app_id, deployment_id) VALUES (?, ?) \
+         ON CONFLICT(app_id) DO UPDATE SET \
+            deployment_id = excluded.deployment_id, updated_at = datetime('now')",
+    )
+    .bind(&app.id)
+    .bind(&deployment_id)
+    .execute(db)
+    .await?;
+
+    DeploymentEvent::record(db, &deployment_id, "pending", Some("Pre-warm build queued")).await;
+
+    deploy_tx
+        .send((deployment_id.clone(), app.clone()))
+        .await
+        .context("Failed to queue pre-warm build")?;
+
+    tracing::info!(app = %app.name, deployment_id = %deployment_id, "Queued pre-warm build");
+    Ok(deployment_id)
+}
+
+/// Cancel a slot that is being replaced; its image is left to deployment cleanup
+pub async fn retire_slot(db: &DbPool, deployment_id: &str) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    let message = "Replaced by a newer pre-warm build";
+    let result = sqlx::query(
+        "UPDATE deployments SET status = 'cancelled', cancelled_at = ?, \
+            finished_at = COALESCE(finished_at, ?), error_message = ? \
+          WHERE id = ? AND status IN ('pending', 'cloning', 'building', 'prewarmed')",
+    )
+    .bind(&now)
+    .bind(&now)
+    .bind(message)
+    .bind(deployment_id)
+    .execute(db)
+    .await?;
+    if result.rows_affected() > 0 {
+        DeploymentEvent::record(db, deployment_id, "cancelled", Some(message)).await;
+    }
+    Ok(())
+}
+
+/// Run a pre-warm build: produce the image, record it and stop short of starting it.
+/// Called by the deployment engine for deployments that hold an app's slot.
+pub async fn run_prewarm(
+    db: &DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    build_limits: &BuildLimits,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) {
+    let _ = add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        "Pre-warm build: traffic is not switched until this deployment is promoted",
+    )
+    .await;
+
+    match build_prewarm_image(
+        db,
+        runtime,
+        deployment_id,
+        app,
+        build_limits,
+        encryption_key,
+    )
+    .await
+    {
+        Ok(image_tag) => {
+            let stored = sqlx::query(
+                "UPDATE deployments SET image_tag = ? WHERE id = ? AND status != 'cancelled'",
+            )
+            .bind(&image_tag)
+            .bind(deployment_id)
+            .execute(db)
+            .await;
+            if !matches!(stored, Ok(ref r) if r.rows_affected() > 0) {
+                tracing::info!(
+                    deployment_id = %deployment_id,
+                    "Pre-warm build was cancelled while building; discarding it"
+                );
+                return;
+            }
+            let _ = update_deployment_status(db, deployment_id, "prewarmed", None).await;
+            let _ = add_deployment_log(
+                db,
+                deployment_id,
+                "info",
+                &format!(
+                    "Image {} is ready; promote the slot to release it",
+                    image_tag
+                ),
+            )
+            .await;
+            tracing::info!(app = %app.name, image = %image_tag, "Pre-warm build ready");
+        }
+        Err(e) => {
+            let message = format!("Pre-warm build failed: {}", e);
+            let _ = add_deployment_log(db, deployment_id, "error", &message).await;
+            let _ = update_deployment_status(db, deployment_id, "failed", Some(&message)).await;
+            tracing::warn!(app = %app.name, error = %e, "Pre-warm build failed");
+        }
+    }
+}
+
+/// Queue a scheduled build if due, then advance the schedule
+async fn run_schedule(
+    db: &DbPool,
+    deploy_tx: &mpsc::Sender<DeploymentJob>,
+    settings: &AppPrewarmSettings,
+    cron: &str,
+) -> Result<()> {
+    let due = is_due(settings.next_run_at.as_deref(), Utc::now());
+
+    if due {
+        let slot_status = settings.slot(db).await?.map(|slot| slot.status);
+        if slot_status.is_some_and(|s| IN_PROGRESS.contains(&s.as_str())) {
+            tracing::debug!(app_id = %settings.app_id, "Pre-warm build already in progress");
+        } else {
+            let app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+                .bind(&settings.app_id)
+                .fetch_one(db)
+                .await?;
+            // Advance the schedule even if queueing fails, so it is not retried every minute
+            if let Err(e) = queue_prewarm(db, deploy_tx, &app, PrewarmCommit::default()).await {
+                tracing::warn!(app = %app.name, error = %e, "Scheduled pre-warm failed");
+            }
+        }
+    }
+
+    if due || settings.next_run_at.is_none() {
+        sqlx::query(
+            "UPDATE app_prewarm_settings SET next_run_at = ?, updated_at = datetime('now') \
+             WHERE app_id = ?",
+        )
+        .bind(next_run(cron, settings.timezone.as_deref()))
+        .bind(&settings.app_id)
+        .execute(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// One check cycle over every enabled pre-warm schedule
+async fn prewarm_cycle(db: &DbPool, deploy_tx: &mpsc::Sender<DeploymentJob>) {
+    let schedules: Vec<AppPrewarmSettings> = match sqlx::query_as(
+        "SELECT * FROM app_prewarm_settings WHERE enabled = 1 AND schedule_cron IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch pre-warm schedules");
+            return;
+        }
+    };
+
+    for settings in &schedules {
+        let Some(ref cron) = settings.schedule_cron else {
+            continue;
+        };
+        if let Err(e) = run_schedule(db, deploy_tx, settings, cron).await {
+            tracing::warn!(app_id = %settings.app_id, error = %e, "Pre-warm schedule check failed");
+        }
+    }
+}
+
+/// Spawn the background pre-warm scheduler (runs every 60 seconds)
+pub fn spawn_prewarm_scheduler(db: DbPool, deploy_tx: mpsc::Sender<DeploymentJob>) {
+    tracing::info!("Starting pre-warm scheduler (60s interval)");
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("prewarm_scheduler", prewarm_cycle(&db, &deploy_tx))
+                .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool};
+
+    async fn status(db: &DbPool, deployment_id: &str) -> String {
+        sqlx::query_scalar("SELECT status FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn settings(db: &DbPool, app_id: &str) -> AppPrewarmSettings {
+        AppPrewarmSettings::get(db, app_id).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_queueing_replaces_the_slot() {
+        let (_dir, db) = test_pool().await;
+        let app = load_test_app(&db, &test_app(&db, "web").await).await;
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let first = queue_prewarm(&db, &tx, &app, PrewarmCommit::default())
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.unwrap().0, first);
+        assert_eq!(
+            settings(&db, &app.id).await.deployment_id,
+            Some(first.clone())
+        );
+        assert!(AppPrewarmSettings::is_slot(&db, &first).await);
+
+        // A second build evicts the first, which is cancelled
+        let commit = PrewarmCommit {
+            sha: Some("abc123".to_string()),
+            ..Default::default()
+        };
+        let second = queue_prewarm(&db, &tx, &app, commit).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap().0, second);
+        assert_eq!(status(&db, &first).await, "cancelled");
+        assert!(!AppPrewarmSettings::is_slot(&db, &first).await);
+
+        let slot = settings(&db, &app.id)
+            .await
+            .slot(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(slot.id, second);
+        assert_eq!(slot.status, "pending");
+        assert_eq!(slot.commit_sha.as_deref(), Some("abc123"));
+    }
+
+    #[tokio::test]
+    async fn test_retire_slot_leaves_finished_deployments() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+
+        for live in ["pending", "building", "prewarmed"] {
+            let id = test_deployment(&db, &app_id, live).await;
+            retire_slot(&db, &id).await.unwrap();
+            assert_eq!(status(&db, &id).await, "cancelled", "{} slot", live);
+        }
+
+        // A slot that was promoted (or failed) is no longer the pool's to cancel
+        for done in ["running", "failed"] {
+            let id = test_deployment(&db, &app_id, done).await;
+            retire_slot(&db, &id).await.unwrap();
+            assert_eq!(status(&db, &id).await, done);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_captures_pushes_only_when_enabled() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        assert!(!AppPrewarmSettings::captures_pushes(&db, &app_id).await);
+
+        sqlx::query("INSERT INTO app_prewarm_settings (app_id, on_push) VALUES (?, 1)")
+            .bind(&app_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert!(AppPrewarmSettings::captures_pushes(&db, &app_id).await);
+
+        sqlx::query("UPDATE app_prewarm_settings SET enabled = 0 WHERE app_id = ?")
+            .bind(&app_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert!(!AppPrewarmSettings::captures_pushes(&db, &app_id).await);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_skips_a_slot_still_building() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let building = test_deployment(&db, &app_id, "building").await;
+        let past = (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        sqlx::query(
+            "INSERT INTO app_prewarm_settings (app_id, schedule_cron, next_run_at, deployment_id) \
+             VALUES (?, '0 3 * * *', ?, ?)",
+        )
+        .bind(&app_id)
+        .bind(&past)
+        .bind(&building)
+        .execute(&db)
+        .await
+        .unwrap();
+        let (tx, mut rx) = mpsc::channel(4);
+
+        prewarm_cycle(&db, &tx).await;
+        assert!(rx.try_recv().is_err());
+        let current = settings(&db, &app_id).await;
+        assert_eq!(current.deployment_id, Some(building.clone()));
+        // The schedule still moves on to the next run
+        assert_ne!(current.next_run_at, Some(past.clone()));
+
+        // Once the build is done, a due schedule replaces it
+        sqlx::query("UPDATE deployments SET status = 'prewarmed' WHERE id = ?")
+            .bind(&building)
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE app_prewarm_settings SET next_run_at = ? WHERE app_id = ?")
+            .bind(&past)
+            .bind(&app_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        prewarm_cycle(&db, &tx).await;
+        let (queued, _) = rx.try_recv().unwrap();
+        assert_ne!(queued, building);
+        assert_eq!(status(&db, &building).await, "cancelled");
+        assert_eq!(settings(&db, &app_id).await.deployment_id, Some(queued));
+    }
+}
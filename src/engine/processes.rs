@@ -0,0 +1,358 @@
+//! Procfile process types.
+//!
+//! A `Procfile` in the app's source (`<type>: <command>` per line) splits the
+//! app into process types that all run from the deployment's image:
+//! - `web` replaces the command of the primary container and its replicas
+//! - `release` runs once in the new primary container after its health check,
+//!   before the route switch; a failure fails the deployment
+//! - every other type runs `replica_count` containers of its own with no port
+//!   or proxy route, whose predecessors are stopped with the old primary
+//!
+//! The Procfile is read after the build and stored on the deployment; the
+//! app's process types are synced from it when the deployment starts.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::db::{App, AppProcess, ProcessStatus, PROCESS_RELEASE, PROCESS_WEB};
+use crate::runtime::{ContainerRuntime, RunConfig};
+use crate::DbPool;
+
+use super::{add_deployment_log, KEY_LENGTH};
+
+/// Upper bound on a process type's container count
+pub const MAX_PROCESS_REPLICAS: i64 = 10;
+
+/// A `<type>: <command>` line of a Procfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcfileEntry {
+    pub name: String,
+    pub command: String,
+}
+
+/// Parse a Procfile. Blank lines, comments and malformed lines are skipped;
+/// a repeated process type keeps its last command.
+pub fn parse_procfile(content: &str) -> Vec<ProcfileEntry> {
+    let mut entries: Vec<ProcfileEntry> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, command)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, command) = (name.trim(), command.trim());
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid_name || command.is_empty() {
+            continue;
+        }
+        entries.retain(|e| e.name != name);
+        entries.push(ProcfileEntry {
+            name: name.to_string(),
+            command: command.to_string(),
+        });
+    }
+    entries
+}
+
+/// Container command running a Procfile command through the shell
+pub fn shell_command(command: &str) -> Vec<String> {
+    vec!["/bin/sh".to_string(), "-c".to_string(), command.to_string()]
+}
+
+/// Store the Procfile found in `source_dir` on the deployment (an empty
+/// string when there is none, so the next start clears the app's processes)
+pub async fn store_procfile(db: &DbPool, deployment_id: &str, source_dir: &Path) -> Result<()> {
+    let content = tokio::fs::read_to_string(source_dir.join("Procfile"))
+        .await
+        .unwrap_or_default();
+    let entries = parse_procfile(&content);
+    if !entries.is_empty() {
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            &format!("Procfile found with process types: {}", names.join(", ")),
+        )
+        .await?;
+    }
+    sqlx::query("UPDATE deployments SET procfile = ? WHERE id = ?")
+        .bind(&content)
+        .bind(deployment_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Sync the app's process types with the deployment's Procfile and return
+/// them, along with the containers of process types the Procfile dropped.
+/// Deployments without a stored Procfile (e.g. registry images) keep the
+/// app's current process types.
+pub async fn load_for_deployment(
+    db: &DbPool,
+    deployment_id: &str,
+    app_id: &str,
+) -> Result<(Vec<AppProcess>, Vec<String>)> {
+    let procfile: Option<String> =
+        sqlx::query_scalar("SELECT procfile FROM deployments WHERE id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+
+    let mut removed = Vec::new();
+    if let Some(procfile) = procfile {
+        let entries = parse_procfile(&procfile);
+        for process in AppProcess::list(db, app_id).await? {
+            if !entries.iter().any(|e| e.name == process.name) {
+                removed.extend(process.get_container_ids());
+                sqlx::query("DELETE FROM app_processes WHERE id = ?")
+                    .bind(&process.id)
+                    .execute(db)
+                    .await?;
+            }
+        }
+        for entry in &entries {
+            sqlx::query(
+                "INSERT INTO app_processes (id, app_id, name, command) VALUES (?, ?, ?, ?) \
+                 ON CONFLICT(app_id, name) DO UPDATE SET command = excluded.command, \
+                 updated_at = datetime('now')",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(app_id)
+            .bind(&entry.name)
+            .bind(&entry.command)
+            .execute(db)
+            .await?;
+        }
+    }
+
+    Ok((AppProcess::list(db, app_id).await?, removed))
+}
+
+/// Command of the app's `web` process, if its Procfile has one
+pub async fn web_command(db: &DbPool, app_id: &str) -> Option<Vec<String>> {
+    AppProcess::get(db, app_id, PROCESS_WEB)
+        .await
+        .ok()
+        .flatten()
+        .map(|web| shell_command(&web.command))
+}
+
+/// Name of a process container. The deployment suffix lets the new
+/// containers start while the previous deployment's are still running.
+fn container_name(app: &App, process: &str, index: i64, deployment_id: &str) -> String {
+    let suffix: String = deployment_id.chars().take(8).collect();
+    format!("rivetr-{}-{}-{}-{}", app.name, process, index, suffix)
+}
+
+/// Config of a process container, derived from the primary container's
+fn process_config(base: &RunConfig, name: String, command: &str) -> RunConfig {
+    let mut config = base.clone();
+    config.name = name;
+    config.port = 0;
+    config.port_mappings = vec![];
+    // The app's network aliases belong to its web process
+    config.network_aliases = vec![];
+    config.cmd = Some(shell_command(command));
+    config
+}
+
+/// Start containers `from..to` of a process. Stops the ones it started if
+/// one fails.
+async fn start_containers(
+    runtime: &dyn ContainerRuntime,
+    app: &App,
+    process: &AppProcess,
+    base: &RunConfig,
+    deployment_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<String>> {
+    let mut started = Vec::new();
+    for index in from..to {
+        let name = container_name(app, &process.name, index, deployment_id);
+        match runtime
+            .run(&process_config(base, name, &process.command))
+            .await
+        {
+            Ok(container_id) => started.push(container_id),
+            Err(e) => {
+                for container_id in &started {
+                    let _ = runtime.stop(container_id).await;
+                    let _ = runtime.remove(container_id).await;
+                }
+                return Err(e).with_context(|| {
+                    format!("Failed to start '{}' process container", process.name)
+                });
+            }
+        }
+    }
+    Ok(started)
+}
+
+/// Start a deployment's containers of a process type from the config of its
+/// primary container
+pub async fn start_process(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+    process: &AppProcess,
+    base: &RunConfig,
+) -> Result<Vec<String>> {
+    let count = process.replica_count.clamp(0, MAX_PROCESS_REPLICAS);
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Starting {} container(s) for process '{}': {}",
+            count, process.name, process.command
+        ),
+    )
+    .await?;
+    start_containers(runtime, app, process, base, deployment_id, 0, count).await
+}
+
+/// Start or stop containers of a process type so the running deployment has
+/// `count` of them. The caller stores the new count. Does nothing when the
+/// app has no running deployment.
+pub async fn apply_process_count(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    app: &App,
+    process: &AppProcess,
+    count: i64,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<()> {
+    let running: Option<(String, Option<String>)> = sqlx::query_as(
+        "SELECT id, image_tag FROM deployments WHERE app_id = ? AND status = 'running' \
+         ORDER BY started_at DESC LIMIT 1",
+    )
+    .bind(&app.id)
+    .fetch_optional(db)
+    .await?;
+    let Some((deployment_id, Some(image_tag))) = running else {
+        return Ok(());
+    };
+
+    let mut container_ids = process.get_container_ids();
+    let current = container_ids.len() as i64;
+    if count > current {
+        let env =
+            super::pipeline::collect_env_vars(db, app, encryption_key, Some(&deployment_id)).await;
+        let base = super::pipeline::build_run_config(
+            db,
+            app,
+            image_tag,
+            format!("rivetr-{}", app.name),
+            env,
+        )
+        .await;
+        let started = start_containers(
+            runtime.as_ref(),
+            app,
+            process,
+            &base,
+            &deployment_id,
+            current,
+            count,
+        )
+        .await?;
+        container_ids.extend(started);
+    } else {
+        for container_id in container_ids.split_off(count.max(0) as usize) {
+            let _ = runtime.stop(&container_id).await;
+            let _ = runtime.remove(&container_id).await;
+        }
+    }
+
+    AppProcess::set_containers(db, &process.id, &container_ids).await?;
+    Ok(())
+}
+
+/// Status of each of the app's process types for the app view
+pub async fn process_statuses(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    app: &App,
+) -> Result<Vec<ProcessStatus>, sqlx::Error> {
+    let mut statuses = Vec::new();
+    for process in AppProcess::list(db, &app.id).await? {
+        let (replica_count, container_ids) = match process.name.as_str() {
+            PROCESS_WEB => (
+                app.replica_count.max(1),
+                super::scaling::serving_containers(db, &app.id).await,
+            ),
+            PROCESS_RELEASE => (0, vec![]),
+            _ => (process.replica_count, process.get_container_ids()),
+        };
+        let mut running = 0;
+        for container_id in &container_ids {
+            if matches!(runtime.inspect(container_id).await, Ok(info) if info.running) {
+                running += 1;
+            }
+        }
+        statuses.push(ProcessStatus {
+            name: process.name,
+            command: process.command,
+            replica_count,
+            running,
+            container_ids,
+        });
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_procfile() {
+        let entries = parse_procfile(
+            "# Heroku-style Procfile\n\
+             web: bundle exec puma -C config/puma.rb\n\
+             \n\
+             worker:   bundle exec sidekiq -q default:2\n\
+             release: bin/rails db:migrate\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                ProcfileEntry {
+                    name: "web".to_string(),
+                    command: "bundle exec puma -C config/puma.rb".to_string(),
+                },
+                ProcfileEntry {
+                    name: "worker".to_string(),
+                    command: "bundle exec sidekiq -q default:2".to_string(),
+                },
+                ProcfileEntry {
+                    name: "release".to_string(),
+                    command: "bin/rails db:migrate".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_procfile_skips_malformed_lines() {
+        let entries = parse_procfile("no colon here\nbad name: x\nempty:\nweb: a\nweb: b\n");
+        assert_eq!(
+            entries,
+            vec![ProcfileEntry {
+                name: "web".to_string(),
+                command: "b".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Prometheus scrape targets of Rivetr-managed apps.
+//!
+//! Running apps with a `metrics_port` are written to a file-based service
+//! discovery list (`<data_dir>/prometheus/targets.json`) next to a
+//! `prometheus.yml` that scrapes it. The Grafana + Prometheus template mounts
+//! that directory, and Prometheus re-reads the list when it changes, so apps
+//! are picked up as they are deployed. Targets use each app's internal
+//! hostname, which Prometheus resolves on the shared `rivetr` network.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::time::interval;
+
+use crate::db::App;
+use crate::DbPool;
+
+/// Directory under the data dir holding the generated files
+const PROMETHEUS_DIR: &str = "prometheus";
+
+const TARGETS_FILE: &str = "targets.json";
+const CONFIG_FILE: &str = "prometheus.yml";
+
+/// Where the Grafana + Prometheus template mounts the directory
+pub const CONTAINER_DIR: &str = "/etc/prometheus/rivetr";
+
+/// Scraped when an app sets a metrics port but no path
+const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+/// One file_sd target group
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct TargetGroup {
+    pub targets: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Directory the scrape config and target list are written to
+pub fn prometheus_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(PROMETHEUS_DIR)
+}
+
+/// Scrape target group of an app, if it declares a metrics port
+pub fn target_group(app: &App) -> Option<TargetGroup> {
+    let port = app.metrics_port.filter(|p| *p > 0)?;
+    let path = app
+        .metrics_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .unwrap_or(DEFAULT_METRICS_PATH);
+
+    let mut labels = BTreeMap::from([
+        ("__metrics_path__".to_string(), path.to_string()),
+        ("rivetr_app".to_string(), app.name.clone()),
+        ("rivetr_app_id".to_string(), app.id.clone()),
+    ]);
+    if let Some(project_id) = &app.project_id {
+        labels.insert("rivetr_project_id".to_string(), project_id.clone());
+    }
+
+    Some(TargetGroup {
+        targets: vec![format!("{}:{}", app.internal_hostname(), port)],
+        labels,
+    })
+}
+
+fn scrape_config() -> String {
+    format!(
+        "# Generated by Rivetr. Apps with a metrics port are listed in {file},\n\
+         # which Prometheus re-reads whenever it changes.\n\
+         global:\n  scrape_interval: 15s\n\n\
+         scrape_configs:\n\
+         \x20 - job_name: prometheus\n\
+         \x20   static_configs:\n\
+         \x20     - targets: [\"localhost:9090\"]\n\
+         \x20 - job_name: rivetr-apps\n\
+         \x20   file_sd_configs:\n\
+         \x20     - files: [\"{dir}/{file}\"]\n",
+        dir = CONTAINER_DIR,
+        file = TARGETS_FILE,
+    )
+}
+
+/// Write `content` to `path` unless it already holds it. Goes through a
+/// temporary file so Prometheus never reads a partial list.
+async fn write_if_changed(path: &Path, content: &str) -> Result<()> {
+    if tokio::fs::read_to_string(path).await.ok().as_deref() == Some(content) {
+        return Ok(());
+    }
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, content).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// Regenerate the scrape config and the target list of running apps
+pub async fn write_scrape_targets(db: &DbPool, data_dir: &Path) -> Result<()> {
+    let apps: Vec<App> = sqlx::query_as(
+        "SELECT * FROM apps WHERE metrics_port IS NOT NULL AND EXISTS \
+         (SELECT 1 FROM deployments d WHERE d.app_id = apps.id AND d.status = 'running') \
+         ORDER BY name",
+    )
+    .fetch_all(db)
+    .await?;
+    let groups: Vec<TargetGroup> = apps.iter().filter_map(target_group).collect();
+
+    let dir = prometheus_dir(data_dir);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    write_if_changed(&dir.join(CONFIG_FILE), &scrape_config()).await?;
+    write_if_changed(
+        &dir.join(TARGETS_FILE),
+        &serde_json::to_string_pretty(&groups)?,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Spawn the background task keeping the scrape targets current (every 30 seconds)
+pub fn spawn_prometheus_sd_task(db: DbPool, data_dir: PathBuf) {
+    tracing::info!("Starting Prometheus scrape target generator (30s interval)");
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(30));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("prometheus_sd", async {
+                if let Err(e) = write_scrape_targets(&db, &data_dir).await {
+                    tracing::warn!(error = %e, "Failed to write Prometheus scrape targets");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrape_config_reads_the_target_list() {
+        let config: serde_yaml::Value = serde_yaml::from_str(&scrape_config()).unwrap();
+        let jobs = config["scrape_configs"].as_sequence().unwrap();
+        assert_eq!(jobs[1]["job_name"].as_str(), Some("rivetr-apps"));
+        assert_eq!(
+            jobs[1]["file_sd_configs"][0]["files"][0].as_str(),
+            Some("/etc/prometheus/rivetr/targets.json")
+        );
+    }
+}
@@ -370,7 +370,9 @@ pub async fn build_image(
     }
 
     // Set up for streaming output
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
 
     debug!(
         "Executing Railpack command: railpack build {:?}",
@@ -229,7 +229,7 @@ pub fn spawn_resource_metrics_collector_task_full(
     let notification_service = Arc::new(notification_service);
 
     // Spawn the notification worker
-    spawn_alert_notification_worker(notification_rx);
+    spawn_alert_notification_worker(db.clone(), notification_rx);
 
     // Create the alert evaluator with notifications
     let alert_evaluator =
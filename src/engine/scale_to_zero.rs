@@ -0,0 +1,343 @@
+//! Scale-to-zero: stop idle apps and start them again on the next request.
+//!
+//! Every 60 seconds, running apps with `idle_timeout_minutes` set are checked.
+//! Activity is the last request the proxy served for one of the app's domains
+//! (kept in memory), the app's deployment starting, or Rivetr starting. Once
+//! the timeout has passed, the app's domains are marked sleeping in the proxy
+//! and its container is stopped. The next request for one of those domains is
+//! held while the same container is started and passes its health check, then
+//! forwarded as usual.
+//!
+//! Apps without domains, workers and apps with several replicas are never put
+//! to sleep: nothing would wake them, or only one replica would come back.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dashmap::DashMap;
+use tokio::time::interval;
+
+use crate::db::App;
+use crate::proxy::{probe_backend, traffic, Backend, HealthProbe, RouteTable};
+use crate::runtime::ContainerRuntime;
+use crate::utils::time::parse_time;
+use crate::DbPool;
+
+use super::power_schedule::{restore_routes, start_container, stop_containers};
+
+/// Longest a request is held while its app wakes up
+const WAKE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Pause between health probes of a waking app
+const WAKE_PROBE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Whether an app last active at `last_activity` has been idle for longer
+/// than its timeout
+pub fn is_idle(
+    last_activity: DateTime<Utc>,
+    now: DateTime<Utc>,
+    idle_timeout_minutes: i64,
+) -> bool {
+    idle_timeout_minutes > 0 && now - last_activity >= ChronoDuration::minutes(idle_timeout_minutes)
+}
+
+/// Puts idle apps to sleep and wakes them. Shared by the proxy, which wakes
+/// apps on request, and the idle checker.
+pub struct AppWaker {
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    /// Serializes sleeping and waking per app, so concurrent requests wait
+    /// for a single wake
+    locks: DashMap<String, Arc<tokio::sync::Mutex<()>>>,
+    started_at: DateTime<Utc>,
+}
+
+impl AppWaker {
+    pub fn new(db: DbPool, runtime: Arc<dyn ContainerRuntime>) -> Self {
+        Self {
+            db,
+            runtime,
+            locks: DashMap::new(),
+            started_at: Utc::now(),
+        }
+    }
+
+    fn lock(&self, app_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.locks
+            .entry(app_id.to_string())
+            .or_default()
+            .value()
+            .clone()
+    }
+
+    async fn set_sleeping_since(&self, app_id: &str, since: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE apps SET sleeping_since = ? WHERE id = ?")
+            .bind(since)
+            .bind(app_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Mark the app's domains sleeping and stop its container
+    async fn sleep(&self, routes: &Arc<ArcSwap<RouteTable>>, app: &App) -> Result<()> {
+        let lock = self.lock(&app.id);
+        let _guard = lock.lock().await;
+
+        // Marked first so requests arriving during shutdown wait for the wake
+        let route_table = routes.load();
+        for domain in app.get_all_domain_names() {
+            route_table.sleep(&domain, &app.id);
+        }
+        self.set_sleeping_since(&app.id, Some(&Utc::now().to_rfc3339()))
+            .await?;
+        if let Err(e) =
+            stop_containers(&self.db, &self.runtime, app, "Stopped after inactivity").await
+        {
+            self.undo_sleep(routes, app).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Route the app back to a container that failed to stop
+    async fn undo_sleep(&self, routes: &Arc<ArcSwap<RouteTable>>, app: &App) {
+        let container_id: Option<String> = sqlx::query_scalar(
+            "SELECT container_id FROM deployments WHERE app_id = ? AND status = 'running' \
+             AND container_id IS NOT NULL AND container_id != '' ORDER BY started_at DESC LIMIT 1",
+        )
+        .bind(&app.id)
+        .fetch_optional(&self.db)
+        .await
+        .ok()
+        .flatten();
+        let Some(container_id) = container_id else {
+            return;
+        };
+        if let Some(port) = self
+            .runtime
+            .inspect(&container_id)
+            .await
+            .ok()
+            .and_then(|i| i.port)
+        {
+            restore_routes(&self.db, routes, app, &container_id, port).await;
+        }
+        let _ = self.set_sleeping_since(&app.id, None).await;
+    }
+
+    /// Start a sleeping app and wait until it passes its health check. Returns
+    /// once its routes are back, also when another request already woke it.
+    pub async fn wake(&self, app_id: &str, routes: &Arc<ArcSwap<RouteTable>>) -> Result<()> {
+        let lock = self.lock(app_id);
+        let _guard = lock.lock().await;
+
+        let app: Option<App> = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+            .bind(app_id)
+            .fetch_optional(&self.db)
+            .await?;
+        let Some(app) = app else {
+            return Err(anyhow!("App {} no longer exists", app_id));
+        };
+        if app.sleeping_since.is_none() {
+            // Woken while this request waited for the lock
+            return Ok(());
+        }
+
+        let started = start_container(&self.db, &self.runtime, &app, "Woken by request").await?;
+        let Some((container_id, port)) = started else {
+            // Redeployed or removed while asleep; nothing left to wake
+            self.set_sleeping_since(&app.id, None).await?;
+            let route_table = routes.load();
+            for domain in app.get_all_domain_names() {
+                if route_table.sleeping_app(&domain).is_some() {
+                    route_table.remove_route(&domain);
+                }
+            }
+            return Err(anyhow!("App {} has no stopped container to wake", app.name));
+        };
+
+        let healthy =
+            tokio::time::timeout(WAKE_TIMEOUT, self.wait_healthy(&app, &container_id, port)).await;
+        let port = match healthy {
+            Ok(port) => port,
+            Err(_) => {
+                // Left running with its routes restored; the health checker
+                // takes it from here
+                tracing::warn!(app = %app.name, "App did not become healthy after waking");
+                match self
+                    .runtime
+                    .inspect(&container_id)
+                    .await
+                    .ok()
+                    .and_then(|i| i.port)
+                {
+                    Some(port) => port,
+                    None => return Err(anyhow!("App {} has no port after waking", app.name)),
+                }
+            }
+        };
+
+        restore_routes(&self.db, routes, &app, &container_id, port).await;
+        self.set_sleeping_since(&app.id, None).await?;
+        tracing::info!(app = %app.name, "App woken by request");
+        Ok(())
+    }
+
+    /// Probe the woken container until it passes the app's health check.
+    /// Returns its host port.
+    async fn wait_healthy(&self, app: &App, container_id: &str, port: Option<u16>) -> u16 {
+        let health_probe = app.health_probe();
+        let has_check = app.healthcheck.is_some() || health_probe != HealthProbe::Http;
+        let client = reqwest::Client::new();
+        let mut port = port;
+
+        loop {
+            if port.is_none() {
+                port = self
+                    .runtime
+                    .inspect(container_id)
+                    .await
+                    .ok()
+                    .and_then(|info| info.port);
+            }
+            if let Some(port) = port {
+                let backend = Backend::new(container_id.to_string(), "127.0.0.1".to_string(), port)
+                    .with_healthcheck(app.healthcheck.clone())
+                    .with_health_probe(health_probe.clone());
+                let ready = if has_check {
+                    probe_backend(
+                        &backend,
+                        &client,
+                        Some(self.runtime.as_ref()),
+                        Duration::from_secs(5),
+                    )
+                    .await
+                    .is_ok()
+                } else {
+                    // Without a health check, wait for the port to accept connections
+                    tokio::net::TcpStream::connect(("127.0.0.1", port))
+                        .await
+                        .is_ok()
+                };
+                if ready {
+                    return port;
+                }
+            }
+            tokio::time::sleep(WAKE_PROBE_INTERVAL).await;
+        }
+    }
+
+    /// Last activity of a running app: its latest request, its deployment
+    /// starting, or Rivetr starting
+    fn last_activity(&self, app: &App, deployment_started_at: Option<&str>) -> DateTime<Utc> {
+        app.get_all_domain_names()
+            .iter()
+            .filter_map(|domain| traffic::last_request(domain))
+            .chain(deployment_started_at.and_then(parse_time))
+            .fold(self.started_at, std::cmp::max)
+    }
+
+    /// Put running apps past their idle timeout to sleep
+    async fn check_idle_apps(&self, routes: &Arc<ArcSwap<RouteTable>>) -> Result<()> {
+        let apps: Vec<App> = sqlx::query_as(
+            "SELECT * FROM apps WHERE idle_timeout_minutes > 0 AND EXISTS \
+             (SELECT 1 FROM deployments d WHERE d.app_id = apps.id AND d.status = 'running')",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        let now = Utc::now();
+
+        for app in &apps {
+            if app.sleeping_since.is_some() {
+                // Started by hand or redeployed while asleep
+                self.set_sleeping_since(&app.id, None).await?;
+                continue;
+            }
+            if app.is_worker() || app.replica_count > 1 || app.get_all_domain_names().is_empty() {
+                continue;
+            }
+            let started_at: Option<String> = sqlx::query_scalar(
+                "SELECT started_at FROM deployments WHERE app_id = ? AND status = 'running' \
+                 ORDER BY started_at DESC LIMIT 1",
+            )
+            .bind(&app.id)
+            .fetch_optional(&self.db)
+            .await?
+            .flatten();
+            let last_activity = self.last_activity(app, started_at.as_deref());
+            if !is_idle(
+                last_activity,
+                now,
+                app.idle_timeout_minutes.unwrap_or_default(),
+            ) {
+                continue;
+            }
+
+            tracing::info!(
+                app = %app.name,
+                idle_minutes = (now - last_activity).num_minutes(),
+                "Putting idle app to sleep"
+            );
+            if let Err(e) = self.sleep(routes, app).await {
+                tracing::error!(app = %app.name, error = %e, "Failed to put idle app to sleep");
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark the domains of sleeping apps again (the route table starts empty
+    /// after a restart)
+    async fn restore_sleeping_routes(&self, routes: &Arc<ArcSwap<RouteTable>>) {
+        let apps: Vec<App> = sqlx::query_as("SELECT * FROM apps WHERE sleeping_since IS NOT NULL")
+            .fetch_all(&self.db)
+            .await
+            .unwrap_or_default();
+        for app in &apps {
+            for domain in app.get_all_domain_names() {
+                // Never shadow a live route (e.g. the app was redeployed meanwhile)
+                if !routes.load().has_domain(&domain) {
+                    routes.load().sleep(&domain, &app.id);
+                }
+            }
+        }
+    }
+}
+
+/// Spawn the background idle checker (runs every 60 seconds)
+pub fn spawn_idle_checker(waker: Arc<AppWaker>, routes: Arc<ArcSwap<RouteTable>>) {
+    tracing::info!("Starting scale-to-zero idle checker (60s interval)");
+
+    tokio::spawn(async move {
+        waker.restore_sleeping_routes(&routes).await;
+
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("scale_to_zero", async {
+                if let Err(e) = waker.check_idle_apps(&routes).await {
+                    tracing::warn!(error = %e, "Scale-to-zero idle check failed");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_idle() {
+        let now = Utc::now();
+        assert!(is_idle(now - ChronoDuration::minutes(15), now, 15));
+        assert!(!is_idle(now - ChronoDuration::minutes(14), now, 15));
+        assert!(!is_idle(now - ChronoDuration::days(1), now, 0));
+    }
+}
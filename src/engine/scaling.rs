@@ -0,0 +1,268 @@
+//! Runtime replica scaling.
+//!
+//! Changing an app's replica count while it runs starts or stops its
+//! additional replicas (`rivetr-<app>-<index>`, index > 0) from the image of
+//! the running deployment and updates the proxy's load-balancing pool. Used by
+//! the replicas API and the autoscaler.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::db::{App, AppReplica};
+use crate::proxy::{Backend, RouteTable};
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+/// Upper bound on an app's replica count
+pub const MAX_REPLICAS: i64 = 10;
+
+/// Average utilization across an app's running containers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utilization {
+    pub cpu_percent: f64,
+    /// `None` when no container has a memory limit to measure against
+    pub memory_percent: Option<f64>,
+}
+
+/// Start or stop additional replicas so a running app has `count` containers.
+/// `current_count` is the replica count before the change; the caller stores
+/// the new count. Does nothing when the app has no running deployment.
+pub async fn apply_replica_count(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+    app: &App,
+    current_count: i64,
+    count: i64,
+) -> Result<(), sqlx::Error> {
+    // Check if app is currently running by looking for a running deployment
+    let running_deployment: Option<(String, Option<String>)> = sqlx::query_as(
+        "SELECT id, container_id FROM deployments WHERE app_id = ? AND status = 'running' ORDER BY started_at DESC LIMIT 1",
+    )
+    .bind(&app.id)
+    .fetch_optional(db)
+    .await?;
+
+    if let Some((_, Some(primary_container_id))) = running_deployment {
+        // Get image tag from the running deployment
+        let image_tag: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT image_tag FROM deployments WHERE container_id = ?")
+                .bind(&primary_container_id)
+                .fetch_optional(db)
+                .await
+                .unwrap_or(None);
+
+        let image_tag = image_tag
+            .and_then(|(tag,)| tag)
+            .unwrap_or_else(|| format!("rivetr-{}:latest", app.name));
+
+        if count > current_count {
+            // Start additional replicas
+            for i in current_count..count {
+                let replica_name = format!("rivetr-{}-{}", app.name, i);
+
+                // Get env vars for new replicas (reuse app's env vars)
+                let env_vars: Vec<(String, String)> =
+                    sqlx::query_as("SELECT key, value FROM env_vars WHERE app_id = ?")
+                        .bind(&app.id)
+                        .fetch_all(db)
+                        .await
+                        .unwrap_or_default();
+
+                let run_config = crate::runtime::RunConfig {
+                    image: image_tag.clone(),
+                    name: replica_name.clone(),
+                    port: app.container_port(),
+                    env: env_vars,
+                    memory_limit: app.memory_limit.clone(),
+                    cpu_limit: app.cpu_limit.clone(),
+                    port_mappings: vec![],
+                    network_aliases: app.get_network_aliases(),
+                    extra_hosts: app.get_extra_hosts(),
+                    labels: app.get_container_labels(),
+                    binds: vec![],
+                    restart_policy: app.restart_policy.clone(),
+                    privileged: app.privileged != 0,
+                    cap_add: app
+                        .cap_add
+                        .as_ref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    cap_drop: app
+                        .docker_cap_drop
+                        .as_ref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    devices: app
+                        .devices
+                        .as_ref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    shm_size: app
+                        .shm_size
+                        .as_ref()
+                        .and_then(|s| crate::runtime::parse_shm_size(s)),
+                    init: app.init_process != 0,
+                    app_id: Some(app.id.clone()),
+                    gpus: app.docker_gpus.clone(),
+                    ulimits: app
+                        .docker_ulimits
+                        .as_ref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    security_opt: app
+                        .docker_security_opt
+                        .as_ref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    cmd: crate::engine::processes::web_command(db, &app.id).await,
+                    network: None,
+                    custom_labels: vec![],
+                };
+
+                match runtime.run(&run_config).await {
+                    Ok(container_id) => {
+                        let replica_id = uuid::Uuid::new_v4().to_string();
+                        let _ = sqlx::query(
+                            "INSERT INTO app_replicas (id, app_id, replica_index, container_id, status, started_at)
+                             VALUES (?, ?, ?, ?, 'running', datetime('now'))
+                             ON CONFLICT(id) DO NOTHING",
+                        )
+                        .bind(&replica_id)
+                        .bind(&app.id)
+                        .bind(i)
+                        .bind(&container_id)
+                        .execute(db)
+                        .await;
+
+                        // Get the port and add to proxy routes
+                        if let Ok(info) = runtime.inspect(&container_id).await {
+                            if let Some(port) = info.port {
+                                let domain_entries = app.get_all_domains_with_redirects();
+                                let route_table = routes.load();
+                                for (domain, www_redirect_target) in &domain_entries {
+                                    let mut backend = Backend::new(
+                                        container_id.clone(),
+                                        "127.0.0.1".to_string(),
+                                        port,
+                                    )
+                                    .with_healthcheck(app.healthcheck.clone())
+                                    .with_health_probe(app.health_probe());
+                                    backend.www_redirect_target = www_redirect_target.clone();
+                                    route_table.add_route(domain.clone(), backend);
+                                }
+                            }
+                        }
+
+                        tracing::info!(replica = i, container = %container_id, "Started additional replica");
+                    }
+                    Err(e) => {
+                        tracing::error!(replica = i, error = %e, "Failed to start replica");
+                        let replica_id = uuid::Uuid::new_v4().to_string();
+                        let _ = sqlx::query(
+                            "INSERT INTO app_replicas (id, app_id, replica_index, container_id, status)
+                             VALUES (?, ?, ?, NULL, 'error')
+                             ON CONFLICT(id) DO NOTHING",
+                        )
+                        .bind(&replica_id)
+                        .bind(&app.id)
+                        .bind(i)
+                        .execute(db)
+                        .await;
+                    }
+                }
+            }
+        } else if count < current_count {
+            // Stop excess replicas (stop replicas with index >= new count)
+            let excess_replicas = sqlx::query_as::<_, AppReplica>(
+                "SELECT * FROM app_replicas WHERE app_id = ? AND replica_index >= ? ORDER BY replica_index DESC",
+            )
+            .bind(&app.id)
+            .bind(count)
+            .fetch_all(db)
+            .await
+            .unwrap_or_default();
+
+            let domains: Vec<String> = app
+                .get_all_domains_with_redirects()
+                .into_iter()
+                .map(|(domain, _)| domain)
+                .collect();
+            for replica in excess_replicas {
+                if let Some(ref container_id) = replica.container_id {
+                    // Take the replica out of the load-balancing pool before stopping it
+                    let route_table = routes.load();
+                    for domain in &domains {
+                        let mut pool = route_table.pool(domain);
+                        if pool.len() > 1 && pool.iter().any(|m| &m.container_id == container_id) {
+                            pool.retain(|m| &m.container_id != container_id);
+                            route_table.set_pool(domain, pool);
+                        }
+                    }
+                    let _ = runtime.stop(container_id).await;
+                    let _ = runtime.remove(container_id).await;
+                }
+
+                let _ = sqlx::query(
+                    "UPDATE app_replicas SET status = 'stopped', stopped_at = datetime('now') WHERE id = ?",
+                )
+                .bind(&replica.id)
+                .execute(db)
+                .await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Container IDs currently serving an app: the primary and its replicas
+pub async fn serving_containers(db: &DbPool, app_id: &str) -> Vec<String> {
+    let mut ids: Vec<String> = sqlx::query_scalar(
+        "SELECT container_id FROM deployments WHERE app_id = ? AND status = 'running' \
+         AND container_id IS NOT NULL ORDER BY started_at DESC LIMIT 1",
+    )
+    .bind(app_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+    let replicas: Vec<String> = sqlx::query_scalar(
+        "SELECT container_id FROM app_replicas WHERE app_id = ? AND status = 'running' \
+         AND replica_index > 0 AND container_id IS NOT NULL",
+    )
+    .bind(app_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+    ids.extend(replicas);
+    ids
+}
+
+/// Read CPU and memory usage of an app's running containers from the runtime
+/// and average them. Returns `None` if no container reported stats.
+pub async fn app_utilization(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    app_id: &str,
+) -> Option<Utilization> {
+    let mut cpu = Vec::new();
+    let mut memory = Vec::new();
+    for container_id in serving_containers(db, app_id).await {
+        let Ok(stats) = runtime.stats(&container_id).await else {
+            continue;
+        };
+        cpu.push(stats.cpu_percent);
+        if stats.memory_limit > 0 {
+            memory.push(stats.memory_usage as f64 / stats.memory_limit as f64 * 100.0);
+        }
+    }
+    if cpu.is_empty() {
+        return None;
+    }
+    let average = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    Some(Utilization {
+        cpu_percent: average(&cpu),
+        memory_percent: (!memory.is_empty()).then(|| average(&memory)),
+    })
+}
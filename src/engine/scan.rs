@@ -0,0 +1,430 @@
+//! Image vulnerability scanning.
+//!
+//! With `[scan] enabled`, the pipeline runs Trivy or Grype against every
+//! deployment's image before its container starts. The findings are stored
+//! with the deployment and served from
+//! `GET /api/deployments/:id/vulnerabilities`. With `fail_on` set, a finding of
+//! that severity or worse fails the deployment; otherwise scanning only
+//! reports. A scanner that is missing or errors never blocks a deployment.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::config::{ScanConfig, ScanSeverity, Scanner};
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// A vulnerability reported in the image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    /// CVE or advisory ID, e.g. "CVE-2024-3094"
+    pub id: String,
+    pub severity: ScanSeverity,
+    pub package: String,
+    pub installed_version: String,
+    /// First version with a fix, if there is one
+    pub fixed_version: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Number of vulnerabilities at each severity
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeverityCounts {
+    pub critical: u32,
+    pub high: u32,
+    pub medium: u32,
+    pub low: u32,
+    pub unknown: u32,
+}
+
+impl SeverityCounts {
+    pub fn from_vulnerabilities(vulnerabilities: &[Vulnerability]) -> Self {
+        let mut counts = Self::default();
+        for vulnerability in vulnerabilities {
+            match vulnerability.severity {
+                ScanSeverity::Critical => counts.critical += 1,
+                ScanSeverity::High => counts.high += 1,
+                ScanSeverity::Medium => counts.medium += 1,
+                ScanSeverity::Low => counts.low += 1,
+                ScanSeverity::Unknown => counts.unknown += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// Result of scanning a deployment's image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub deployment_id: String,
+    pub image: String,
+    /// "trivy" or "grype"
+    pub scanner: String,
+    pub scanned_at: String,
+    /// Severity that fails the deployment, if one was configured
+    pub fail_on: Option<ScanSeverity>,
+    /// False when a vulnerability at or above `fail_on` was found
+    pub passed: bool,
+    /// Why the scan could not be completed
+    pub error: Option<String>,
+    pub summary: SeverityCounts,
+    /// Most severe first
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+/// Load the report stored for a deployment
+pub async fn load(db: &DbPool, deployment_id: &str) -> Result<Option<ScanReport>> {
+    let report: Option<String> =
+        sqlx::query_scalar("SELECT report FROM deployment_scans WHERE deployment_id = ?")
+            .bind(deployment_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(report.and_then(|r| serde_json::from_str(&r).ok()))
+}
+
+async fn save(db: &DbPool, report: &ScanReport) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO deployment_scans (deployment_id, report, created_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(deployment_id) DO UPDATE SET
+            report = excluded.report,
+            created_at = excluded.created_at
+        "#,
+    )
+    .bind(&report.deployment_id)
+    .bind(serde_json::to_string(report)?)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Scan `image` for a deployment, store the report and log a summary.
+///
+/// Returns an error only when the image has a vulnerability at or above the
+/// configured `fail_on` severity.
+pub async fn scan_deployment_image(
+    db: &DbPool,
+    deployment_id: &str,
+    image: &str,
+    config: &ScanConfig,
+) -> Result<()> {
+    let scanner = scanner_name(config.scanner);
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Scanning image {} for vulnerabilities with {}",
+            image, scanner
+        ),
+    )
+    .await?;
+
+    let (mut vulnerabilities, error) = match scan_image(config, image).await {
+        Ok(vulnerabilities) => (vulnerabilities, None),
+        Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+    };
+    vulnerabilities.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+    let blocking = config
+        .fail_on
+        .map(|threshold| {
+            vulnerabilities
+                .iter()
+                .filter(|v| v.severity >= threshold)
+                .count()
+        })
+        .unwrap_or(0);
+
+    let report = ScanReport {
+        deployment_id: deployment_id.to_string(),
+        image: image.to_string(),
+        scanner: scanner.to_string(),
+        scanned_at: chrono::Utc::now().to_rfc3339(),
+        fail_on: config.fail_on,
+        passed: blocking == 0,
+        error,
+        summary: SeverityCounts::from_vulnerabilities(&vulnerabilities),
+        vulnerabilities,
+    };
+    if let Err(e) = save(db, &report).await {
+        tracing::warn!(deployment_id = %deployment_id, "Failed to store scan report: {}", e);
+    }
+
+    if let Some(ref error) = report.error {
+        add_deployment_log(
+            db,
+            deployment_id,
+            "warn",
+            &format!("Vulnerability scan failed (non-fatal): {}", error),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let summary = &report.summary;
+    add_deployment_log(
+        db,
+        deployment_id,
+        if summary.critical + summary.high > 0 {
+            "warn"
+        } else {
+            "info"
+        },
+        &format!(
+            "Vulnerability scan: {} critical, {} high, {} medium, {} low, {} unknown",
+            summary.critical, summary.high, summary.medium, summary.low, summary.unknown
+        ),
+    )
+    .await?;
+
+    if let Some(threshold) = config.fail_on.filter(|_| blocking > 0) {
+        anyhow::bail!(
+            "Image has {} vulnerabilities of {} severity or worse",
+            blocking,
+            severity_name(threshold)
+        );
+    }
+    Ok(())
+}
+
+/// Run the configured scanner against `image` and parse its findings
+pub async fn scan_image(config: &ScanConfig, image: &str) -> Result<Vec<Vulnerability>> {
+    let scanner = scanner_name(config.scanner);
+    let mut command = tokio::process::Command::new(scanner);
+    match config.scanner {
+        Scanner::Trivy => {
+            command.args(["image", "--quiet", "--format", "json", "--scanners", "vuln"]);
+            if config.ignore_unfixed {
+                command.arg("--ignore-unfixed");
+            }
+        }
+        Scanner::Grype => {
+            command.args(["--quiet", "--output", "json"]);
+            if config.ignore_unfixed {
+                command.arg("--only-fixed");
+            }
+        }
+    }
+    command.arg(image).kill_on_drop(true);
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(config.timeout_secs.max(1)),
+        command.output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("{} timed out after {}s", scanner, config.timeout_secs))?
+    .with_context(|| format!("Failed to run {} (is it installed?)", scanner))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "{} exited with {}: {}",
+            scanner,
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    match config.scanner {
+        Scanner::Trivy => parse_trivy(&output.stdout),
+        Scanner::Grype => parse_grype(&output.stdout),
+    }
+}
+
+fn scanner_name(scanner: Scanner) -> &'static str {
+    match scanner {
+        Scanner::Trivy => "trivy",
+        Scanner::Grype => "grype",
+    }
+}
+
+fn severity_name(severity: ScanSeverity) -> &'static str {
+    match severity {
+        ScanSeverity::Critical => "critical",
+        ScanSeverity::High => "high",
+        ScanSeverity::Medium => "medium",
+        ScanSeverity::Low => "low",
+        ScanSeverity::Unknown => "unknown",
+    }
+}
+
+/// Map a scanner's severity label; Grype's "Negligible" counts as low
+fn parse_severity(label: &str) -> ScanSeverity {
+    match label.to_ascii_lowercase().as_str() {
+        "critical" => ScanSeverity::Critical,
+        "high" => ScanSeverity::High,
+        "medium" => ScanSeverity::Medium,
+        "low" | "negligible" => ScanSeverity::Low,
+        _ => ScanSeverity::Unknown,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyReport {
+    #[serde(default)]
+    results: Vec<TrivyResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyResult {
+    #[serde(default)]
+    vulnerabilities: Option<Vec<TrivyVulnerability>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyVulnerability {
+    #[serde(rename = "VulnerabilityID")]
+    vulnerability_id: String,
+    pkg_name: String,
+    #[serde(default)]
+    installed_version: String,
+    #[serde(default)]
+    fixed_version: Option<String>,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+/// Parse `trivy image --format json` output
+fn parse_trivy(json: &[u8]) -> Result<Vec<Vulnerability>> {
+    let report: TrivyReport =
+        serde_json::from_slice(json).context("Failed to parse trivy output")?;
+    Ok(report
+        .results
+        .into_iter()
+        .flat_map(|result| result.vulnerabilities.unwrap_or_default())
+        .map(|v| Vulnerability {
+            id: v.vulnerability_id,
+            severity: parse_severity(&v.severity),
+            package: v.pkg_name,
+            installed_version: v.installed_version,
+            fixed_version: v.fixed_version.filter(|f| !f.is_empty()),
+            title: v.title,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct GrypeReport {
+    #[serde(default)]
+    matches: Vec<GrypeMatch>,
+}
+
+#[derive(Deserialize)]
+struct GrypeMatch {
+    vulnerability: GrypeVulnerability,
+    artifact: GrypeArtifact,
+}
+
+#[derive(Deserialize)]
+struct GrypeVulnerability {
+    id: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    fix: Option<GrypeFix>,
+}
+
+#[derive(Deserialize)]
+struct GrypeFix {
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GrypeArtifact {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// Parse `grype --output json` output
+fn parse_grype(json: &[u8]) -> Result<Vec<Vulnerability>> {
+    let report: GrypeReport =
+        serde_json::from_slice(json).context("Failed to parse grype output")?;
+    Ok(report
+        .matches
+        .into_iter()
+        .map(|m| Vulnerability {
+            id: m.vulnerability.id,
+            severity: parse_severity(&m.vulnerability.severity),
+            package: m.artifact.name,
+            installed_version: m.artifact.version,
+            fixed_version: m
+                .vulnerability
+                .fix
+                .and_then(|fix| fix.versions.into_iter().next()),
+            title: m.vulnerability.description,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trivy() {
+        let json = br#"{
+            "Results": [
+                {"Target": "alpine:3.19 (alpine 3.19.0)", "Vulnerabilities": [
+                    {"VulnerabilityID": "CVE-2024-0727", "PkgName": "libcrypto3",
+                     "InstalledVersion": "3.1.4-r2", "FixedVersion": "3.1.4-r5",
+                     "Severity": "MEDIUM", "Title": "openssl: denial of service"}
+                ]},
+                {"Target": "app/package-lock.json"}
+            ]
+        }"#;
+        let vulnerabilities = parse_trivy(json).unwrap();
+        assert_eq!(
+            vulnerabilities,
+            vec![Vulnerability {
+                id: "CVE-2024-0727".to_string(),
+                severity: ScanSeverity::Medium,
+                package: "libcrypto3".to_string(),
+                installed_version: "3.1.4-r2".to_string(),
+                fixed_version: Some("3.1.4-r5".to_string()),
+                title: Some("openssl: denial of service".to_string()),
+            }]
+        );
+        assert!(parse_trivy(b"{}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_grype() {
+        let json = br#"{"matches": [
+            {"vulnerability": {"id": "CVE-2023-5678", "severity": "Negligible",
+                               "fix": {"versions": [], "state": "not-fixed"}},
+             "artifact": {"name": "libssl3", "version": "3.0.11"}},
+            {"vulnerability": {"id": "GHSA-xxxx", "severity": "Critical",
+                               "fix": {"versions": ["1.2.3"], "state": "fixed"}},
+             "artifact": {"name": "lodash", "version": "1.0.0"}}
+        ]}"#;
+        let vulnerabilities = parse_grype(json).unwrap();
+        assert_eq!(vulnerabilities.len(), 2);
+        assert_eq!(vulnerabilities[0].severity, ScanSeverity::Low);
+        assert_eq!(vulnerabilities[0].fixed_version, None);
+        assert_eq!(vulnerabilities[1].severity, ScanSeverity::Critical);
+        assert_eq!(vulnerabilities[1].fixed_version.as_deref(), Some("1.2.3"));
+
+        let counts = SeverityCounts::from_vulnerabilities(&vulnerabilities);
+        assert_eq!((counts.critical, counts.low), (1, 1));
+    }
+
+    #[test]
+    fn test_severity_order() {
+        assert!(ScanSeverity::Critical > ScanSeverity::High);
+        assert!(ScanSeverity::Low > ScanSeverity::Unknown);
+        assert_eq!(parse_severity("HIGH"), ScanSeverity::High);
+        assert_eq!(parse_severity("weird"), ScanSeverity::Unknown);
+    }
+}
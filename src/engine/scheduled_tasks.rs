@@ -0,0 +1,350 @@
+//! Scheduled tasks: cron commands run in one-off containers.
+//!
+//! Every 60 seconds, enabled tasks whose `next_run_at` has passed are run in a
+//! fresh container started from the image of the app's running deployment,
+//! with the app's env vars, volumes and networks but no ports or proxy routes.
+//! The container's exit code and output are recorded as a run and the
+//! container is removed afterwards. A due task whose previous run is still
+//! going is skipped until its next scheduled time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures::StreamExt;
+use tokio::time::interval;
+
+use crate::db::{App, ScheduledTask, ScheduledTaskRun, TASK_TRIGGER_SCHEDULE};
+use crate::runtime::{ContainerRuntime, LogStream};
+use crate::utils::cron;
+use crate::DbPool;
+
+use super::processes::shell_command;
+use super::KEY_LENGTH;
+
+/// Most output kept per run; longer output keeps its tail
+pub(crate) const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// How a finished run ended
+struct RunOutcome {
+    image: Option<String>,
+    exit_code: Option<i64>,
+    output: Option<String>,
+    error: Option<String>,
+}
+
+/// Record a new run of `task` as running
+pub async fn create_run(
+    db: &DbPool,
+    task: &ScheduledTask,
+    trigger: &str,
+) -> Result<ScheduledTaskRun> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO scheduled_task_runs (id, task_id, trigger, status, started_at) \
+         VALUES (?, ?, ?, 'running', ?)",
+    )
+    .bind(&run_id)
+    .bind(&task.id)
+    .bind(trigger)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    ScheduledTaskRun::get(db, &run_id)
+        .await?
+        .context("Task run missing after insert")
+}
+
+/// Run `task` in a one-off container and record the outcome on `run`
+pub async fn execute_run(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    task: &ScheduledTask,
+    run: &ScheduledTaskRun,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) {
+    tracing::info!(task_id = %task.id, task = %task.name, "Running scheduled task");
+
+    let outcome = match run_container(db, runtime, task, &run.id, encryption_key).await {
+        Ok(outcome) => outcome,
+        Err(e) => RunOutcome {
+            image: None,
+            exit_code: None,
+            output: None,
+            error: Some(format!("{:#}", e)),
+        },
+    };
+    let status = if outcome.error.is_none() && outcome.exit_code == Some(0) {
+        "success"
+    } else {
+        "failed"
+    };
+    if status == "failed" {
+        tracing::warn!(
+            task_id = %task.id,
+            task = %task.name,
+            exit_code = ?outcome.exit_code,
+            error = ?outcome.error,
+            "Scheduled task failed"
+        );
+    }
+
+    let finished_at = Utc::now();
+    let duration_ms = chrono::DateTime::parse_from_rfc3339(&run.started_at)
+        .ok()
+        .map(|started| (finished_at - started.with_timezone(&Utc)).num_milliseconds());
+    if let Err(e) = sqlx::query(
+        "UPDATE scheduled_task_runs SET status = ?, image = ?, exit_code = ?, output = ?, \
+         error_message = ?, finished_at = ?, duration_ms = ? WHERE id = ?",
+    )
+    .bind(status)
+    .bind(&outcome.image)
+    .bind(outcome.exit_code)
+    .bind(&outcome.output)
+    .bind(&outcome.error)
+    .bind(finished_at.to_rfc3339())
+    .bind(duration_ms)
+    .bind(&run.id)
+    .execute(db)
+    .await
+    {
+        tracing::error!(run_id = %run.id, error = %e, "Failed to record task run");
+    }
+
+    let _ = sqlx::query("UPDATE scheduled_tasks SET last_run_at = ? WHERE id = ?")
+        .bind(&run.started_at)
+        .bind(&task.id)
+        .execute(db)
+        .await;
+}
+
+/// Start the task's container, wait for it (up to the task's timeout),
+/// collect its output and remove it
+async fn run_container(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    task: &ScheduledTask,
+    run_id: &str,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<RunOutcome> {
+    let suffix: String = run_id.chars().take(8).collect();
+    let (container_id, image) = start_container(
+        db,
+        runtime,
+        &task.app_id,
+        &task.command,
+        &format!("task-{}", suffix),
+        encryption_key,
+    )
+    .await?;
+
+    let timeout = Duration::from_secs(task.timeout_seconds.max(1) as u64);
+    let waited = tokio::time::timeout(timeout, runtime.wait(&container_id)).await;
+    let (exit_code, error) = match waited {
+        Ok(Ok(0)) => (Some(0), None),
+        Ok(Ok(code)) => (
+            Some(code),
+            Some(format!("Command exited with code {}", code)),
+        ),
+        Ok(Err(e)) => (
+            None,
+            Some(format!("Failed to wait for task container: {:#}", e)),
+        ),
+        Err(_) => {
+            let _ = runtime.stop(&container_id).await;
+            (
+                None,
+                Some(format!("Timed out after {} seconds", task.timeout_seconds)),
+            )
+        }
+    };
+
+    let output = collect_output(runtime.as_ref(), &container_id).await;
+    let _ = runtime.remove(&container_id).await;
+
+    Ok(RunOutcome {
+        image: Some(image),
+        exit_code,
+        output,
+        error,
+    })
+}
+
+/// Start `command` in a fresh container from the image of the app's running
+/// deployment, with the app's env vars, volumes and networks but no ports or
+/// network aliases. The container is named `rivetr-<app>-<label>`. Returns the
+/// container ID and the image.
+pub(crate) async fn start_container(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    app_id: &str,
+    command: &str,
+    label: &str,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<(String, String)> {
+    let app: App = sqlx::query_as("SELECT * FROM apps WHERE id = ?")
+        .bind(app_id)
+        .fetch_one(db)
+        .await?;
+    let running: Option<(String, String)> = sqlx::query_as(
+        "SELECT id, image_tag FROM deployments \
+         WHERE app_id = ? AND status = 'running' AND image_tag IS NOT NULL \
+         ORDER BY started_at DESC LIMIT 1",
+    )
+    .bind(&app.id)
+    .fetch_optional(db)
+    .await?;
+    let Some((deployment_id, image)) = running else {
+        anyhow::bail!("App has no running deployment to take the image from");
+    };
+
+    let env =
+        super::pipeline::collect_env_vars(db, &app, encryption_key, Some(&deployment_id)).await;
+    let mut config = super::pipeline::build_run_config(
+        db,
+        &app,
+        image.clone(),
+        format!("rivetr-{}-{}", app.name, label),
+        env,
+    )
+    .await;
+    config.port = 0;
+    config.port_mappings = vec![];
+    // The app's network aliases belong to its web process
+    config.network_aliases = vec![];
+    config.restart_policy = "never".to_string();
+    config.cmd = Some(shell_command(command));
+
+    let container_id = runtime
+        .run(&config)
+        .await
+        .context("Failed to start container")?;
+    Ok((container_id, image))
+}
+
+/// The container's stdout and stderr, trimmed to the last `MAX_OUTPUT_BYTES`
+pub(crate) async fn collect_output(
+    runtime: &dyn ContainerRuntime,
+    container_id: &str,
+) -> Option<String> {
+    let lines: Vec<String> = runtime
+        .logs(container_id)
+        .await
+        .ok()?
+        .map(|line| match line.stream {
+            LogStream::Stdout => line.message,
+            LogStream::Stderr => format!("[stderr] {}", line.message),
+        })
+        .collect()
+        .await;
+    let output = lines.join("\n");
+    Some(tail(&output, MAX_OUTPUT_BYTES).to_string())
+}
+
+/// The last `max` bytes of `text`, cut at a character boundary
+pub(crate) fn tail(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+/// One scheduler cycle: start the runs of due tasks and advance their schedules
+async fn scheduler_cycle(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    encryption_key: Option<[u8; KEY_LENGTH]>,
+) -> Result<()> {
+    let tasks: Vec<ScheduledTask> = sqlx::query_as(
+        "SELECT * FROM scheduled_tasks \
+         WHERE enabled = 1 AND (next_run_at IS NULL OR next_run_at <= ?)",
+    )
+    .bind(Utc::now().to_rfc3339())
+    .fetch_all(db)
+    .await?;
+
+    for task in tasks {
+        // A missing next run is initialized without running the task
+        let due = task.next_run_at.is_some();
+        let next_run_at = cron::next_run(&task.cron_expression, task.timezone.as_deref());
+        sqlx::query("UPDATE scheduled_tasks SET next_run_at = ? WHERE id = ?")
+            .bind(&next_run_at)
+            .bind(&task.id)
+            .execute(db)
+            .await?;
+        if !due {
+            continue;
+        }
+
+        if ScheduledTask::is_running(db, &task.id).await {
+            tracing::info!(task_id = %task.id, task = %task.name, "Previous run still going; skipping");
+            continue;
+        }
+        let run = create_run(db, &task, TASK_TRIGGER_SCHEDULE).await?;
+        let db = db.clone();
+        let runtime = runtime.clone();
+        tokio::spawn(async move {
+            execute_run(&db, &runtime, &task, &run, encryption_key.as_ref()).await;
+        });
+    }
+    Ok(())
+}
+
+/// Mark runs left `running` by a previous process as failed
+async fn fail_interrupted_runs(db: &DbPool) {
+    let _ = sqlx::query(
+        "UPDATE scheduled_task_runs SET status = 'failed', \
+         error_message = 'Interrupted by a server restart', finished_at = ? \
+         WHERE status = 'running'",
+    )
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await;
+}
+
+/// Spawn the scheduled task runner (checks every 60 seconds)
+pub fn spawn_task_scheduler(
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    encryption_key: Option<[u8; KEY_LENGTH]>,
+) {
+    tracing::info!("Starting scheduled task runner (60s interval)");
+
+    tokio::spawn(async move {
+        fail_interrupted_runs(&db).await;
+        // One-off command runs share the container lifecycle (and its restart gap)
+        super::command_runs::fail_interrupted_runs(&db).await;
+
+        let mut tick = interval(Duration::from_secs(60));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("task_scheduler", async {
+                if let Err(e) = scheduler_cycle(&db, &runtime, encryption_key).await {
+                    tracing::warn!(error = %e, "Scheduled task cycle failed");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_keeps_char_boundaries() {
+        assert_eq!(tail("short", 10), "short");
+        assert_eq!(tail("abcdef", 3), "def");
+        // "é" is two bytes; the cut moves past it rather than splitting it
+        assert_eq!(tail("aéb", 2), "b");
+    }
+}
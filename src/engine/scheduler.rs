@@ -3,17 +3,24 @@
 //! Checks every 60 seconds for jobs whose `next_run_at` has passed,
 //! then executes them in the app's running container using the container runtime.
 
-use crate::db::{App, ScheduledJob};
+use crate::api::deployments::active_freeze_window;
+use crate::db::{actions, log_audit, resource_types, App, NotificationEventType, ScheduledJob};
+use crate::notifications::{outbox, NotificationPayload};
+use crate::proxy::RouteTable;
 use crate::runtime::ContainerRuntime;
+use crate::storage::Storage;
+use crate::utils::cron;
 use crate::DbPool;
-use chrono::Utc;
-use cron::Schedule;
-use std::str::FromStr;
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::interval;
 
+use super::scaling::{app_utilization, apply_replica_count, Utilization, MAX_REPLICAS};
+
 // ---------------------------------------------------------------------------
 // Backup Scheduler
 // ---------------------------------------------------------------------------
@@ -31,18 +38,11 @@ struct BackupScheduleRow {
     // enabled is filtered in the WHERE clause
     last_run_at: Option<String>,
     next_run_at: Option<String>,
-}
-
-/// Compute the next run time from a cron expression
-fn next_run_from_cron_expr(cron_expression: &str) -> Option<String> {
-    Schedule::from_str(cron_expression)
-        .ok()
-        .and_then(|s| s.upcoming(Utc).next())
-        .map(|t| t.to_rfc3339())
+    timezone: Option<String>,
 }
 
 /// Execute a single backup schedule entry
-async fn execute_backup_schedule(db: &DbPool, schedule: &BackupScheduleRow) {
+async fn execute_backup_schedule(db: &DbPool, storage: &dyn Storage, schedule: &BackupScheduleRow) {
     tracing::info!(
         schedule_id = %schedule.id,
         backup_type = %schedule.backup_type,
@@ -65,9 +65,15 @@ async fn execute_backup_schedule(db: &DbPool, schedule: &BackupScheduleRow) {
             let config_path = std::path::PathBuf::from("rivetr.toml");
             let acme_cache_dir = std::path::PathBuf::from("data/acme");
 
-            crate::backup::create_backup(db, &data_dir, &config_path, &acme_cache_dir, None)
-                .await
-                .map(|_| ())
+            crate::backup::create_stored_backup(
+                db,
+                storage,
+                &data_dir,
+                &config_path,
+                &acme_cache_dir,
+            )
+            .await
+            .map(|_| ())
         }
         "s3_database" | "s3_volume" => {
             // S3-based backups require a config and target
@@ -93,7 +99,7 @@ async fn execute_backup_schedule(db: &DbPool, schedule: &BackupScheduleRow) {
     };
 
     let now = Utc::now().to_rfc3339();
-    let next_run = next_run_from_cron_expr(&schedule.cron_expression);
+    let next_run = cron::next_run(&schedule.cron_expression, schedule.timezone.as_deref());
 
     if let Err(e) = &result {
         tracing::error!(
@@ -121,12 +127,12 @@ async fn execute_backup_schedule(db: &DbPool, schedule: &BackupScheduleRow) {
 }
 
 /// One cycle of the backup scheduler: find due schedules and run them
-async fn backup_scheduler_cycle(db: &DbPool) {
+async fn backup_scheduler_cycle(db: &DbPool, storage: &Arc<dyn Storage>) {
     let now = Utc::now().to_rfc3339();
 
     let due: Vec<BackupScheduleRow> = match sqlx::query_as(
         r#"SELECT id, backup_type, cron_expression, target_id, s3_config_id,
-                  retention_days, last_run_at, next_run_at
+                  retention_days, last_run_at, next_run_at, timezone
            FROM backup_schedules
            WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?"#,
     )
@@ -144,14 +150,15 @@ async fn backup_scheduler_cycle(db: &DbPool) {
 
     for schedule in due {
         let db_clone = db.clone();
+        let storage = storage.clone();
         tokio::spawn(async move {
-            execute_backup_schedule(&db_clone, &schedule).await;
+            execute_backup_schedule(&db_clone, storage.as_ref(), &schedule).await;
         });
     }
 }
 
 /// Spawn the background backup scheduler (checks every 60 seconds)
-pub fn spawn_backup_scheduler(db: DbPool) {
+pub fn spawn_backup_scheduler(db: DbPool, storage: Arc<dyn Storage>) {
     tracing::info!("Starting backup scheduler (60s interval)");
 
     tokio::spawn(async move {
@@ -163,24 +170,21 @@ pub fn spawn_backup_scheduler(db: DbPool) {
 
         loop {
             tick.tick().await;
-            crate::utils::supervise::guarded("backup_scheduler", backup_scheduler_cycle(&db)).await;
+            crate::utils::supervise::guarded(
+                "backup_scheduler",
+                backup_scheduler_cycle(&db, &storage),
+            )
+            .await;
         }
     });
 }
 
-/// Calculate the next run time from a cron expression, starting from now
-fn next_run_from_cron(cron_expression: &str) -> Option<String> {
-    let schedule = Schedule::from_str(cron_expression).ok()?;
-    let next = schedule.upcoming(Utc).next()?;
-    Some(next.to_rfc3339())
-}
-
 /// Initialize `next_run_at` for all enabled jobs that have a null value
 async fn initialize_next_run_times(db: &DbPool) {
     let jobs: Vec<ScheduledJob> = match sqlx::query_as(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs
         WHERE enabled = 1 AND next_run_at IS NULL
         "#,
@@ -196,7 +200,7 @@ async fn initialize_next_run_times(db: &DbPool) {
     };
 
     for job in jobs {
-        if let Some(next_run) = next_run_from_cron(&job.cron_expression) {
+        if let Some(next_run) = cron::next_run(&job.cron_expression, job.timezone.as_deref()) {
             if let Err(e) = sqlx::query("UPDATE scheduled_jobs SET next_run_at = ? WHERE id = ?")
                 .bind(&next_run)
                 .bind(&job.id)
@@ -381,7 +385,7 @@ async fn execute_job(db: &DbPool, runtime: &Arc<dyn ContainerRuntime>, job: &Sch
 
     // Update job's last_run_at and next_run_at
     let last_run = Utc::now().to_rfc3339();
-    let next_run = next_run_from_cron(&job.cron_expression);
+    let next_run = cron::next_run(&job.cron_expression, job.timezone.as_deref());
 
     if let Err(e) = sqlx::query(
         "UPDATE scheduled_jobs SET last_run_at = ?, next_run_at = ?, updated_at = ? WHERE id = ?",
@@ -453,7 +457,7 @@ async fn scheduler_cycle(db: &DbPool, runtime: &Arc<dyn ContainerRuntime>) {
     let due_jobs: Vec<ScheduledJob> = match sqlx::query_as(
         r#"
         SELECT id, app_id, name, command, cron_expression, enabled,
-               last_run_at, next_run_at, created_at, updated_at
+               last_run_at, next_run_at, timezone, created_at, updated_at
         FROM scheduled_jobs
         WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?
         "#,
@@ -566,6 +570,51 @@ async fn check_scheduled_deployments(db: &DbPool, deploy_tx: &mpsc::Sender<(Stri
             continue;
         };
 
+        // A locked app keeps its scheduled deployments waiting until unlocked
+        if app.locked_at.is_some() {
+            tracing::debug!(
+                deployment_id = %deployment_id,
+                app_name = %app.name,
+                "Scheduled deployment waiting for the app's deployment lock"
+            );
+            continue;
+        }
+
+        // A freeze window that is (still) active pushes the deployment back to
+        // its end, e.g. when windows are chained or one was added meanwhile
+        match active_freeze_window(db, &app, Utc::now()).await {
+            Ok(Some(freeze)) => {
+                if let Err(e) = sqlx::query("UPDATE deployments SET scheduled_at = ? WHERE id = ?")
+                    .bind(freeze.release_at.to_rfc3339())
+                    .bind(&deployment_id)
+                    .execute(db)
+                    .await
+                {
+                    tracing::warn!(
+                        deployment_id = %deployment_id,
+                        error = %e,
+                        "Failed to reschedule deployment held by freeze window"
+                    );
+                }
+                tracing::info!(
+                    deployment_id = %deployment_id,
+                    app_name = %app.name,
+                    release_at = %freeze.release_at,
+                    "Scheduled deployment held by freeze window '{}'",
+                    freeze.name
+                );
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(
+                    deployment_id = %deployment_id,
+                    error = %e,
+                    "Failed to check freeze windows for scheduled deployment"
+                );
+            }
+        }
+
         // Clear scheduled_at so this doesn't get picked up again, then queue
         if let Err(e) = sqlx::query("UPDATE deployments SET scheduled_at = NULL WHERE id = ?")
             .bind(&deployment_id)
@@ -636,8 +685,52 @@ struct AutoscalingRuleRow {
     last_scaled_at: Option<String>,
 }
 
-/// One autoscaling check cycle — evaluates every enabled rule
-async fn autoscaling_cycle(db: &DbPool) {
+impl AutoscalingRuleRow {
+    /// Whether the rule scaled too recently to act again
+    fn cooling_down(&self, now: DateTime<Utc>) -> bool {
+        self.last_scaled_at
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|last| {
+                now.signed_duration_since(last.with_timezone(&Utc))
+                    .num_seconds()
+                    < self.cooldown_seconds
+            })
+    }
+
+    /// The utilization the rule watches, when the runtime reports it
+    fn metric_value(&self, usage: Utilization) -> Option<f64> {
+        match self.metric.as_str() {
+            "cpu" => Some(usage.cpu_percent),
+            "memory" => usage.memory_percent,
+            _ => None, // request_rate not yet implemented
+        }
+    }
+
+    /// Replica count to move to at `value`, one step at a time within the
+    /// rule's bounds (capped at `MAX_REPLICAS`). `None` leaves it unchanged.
+    fn target_replicas(&self, value: f64, current_replicas: i64) -> Option<i64> {
+        let max_replicas = self.max_replicas.min(MAX_REPLICAS);
+
+        let new_replicas = if value >= self.scale_up_threshold {
+            (current_replicas + 1).min(max_replicas)
+        } else if value <= self.scale_down_threshold {
+            (current_replicas - 1).max(self.min_replicas).max(1)
+        } else {
+            return None; // within comfortable range
+        };
+
+        (new_replicas != current_replicas).then_some(new_replicas)
+    }
+}
+
+/// One autoscaling check cycle — evaluates every enabled rule against the
+/// CPU/memory utilization the runtime reports for the app's containers
+async fn autoscaling_cycle(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    routes: &Arc<ArcSwap<RouteTable>>,
+) {
     let now = Utc::now();
 
     let rules: Vec<AutoscalingRuleRow> = match sqlx::query_as(
@@ -658,68 +751,37 @@ async fn autoscaling_cycle(db: &DbPool) {
         }
     };
 
+    // Utilization per app, read once per cycle even when an app has several rules
+    let mut utilization: HashMap<String, Option<Utilization>> = HashMap::new();
+
     for rule in rules {
         // Respect cooldown
-        if let Some(ref last_scaled) = rule.last_scaled_at {
-            if let Ok(last_dt) = chrono::DateTime::parse_from_rfc3339(last_scaled) {
-                let elapsed = now
-                    .signed_duration_since(last_dt.with_timezone(&Utc))
-                    .num_seconds();
-                if elapsed < rule.cooldown_seconds {
-                    continue;
-                }
-            }
+        if rule.cooling_down(now) {
+            continue;
         }
 
-        // Fetch the latest resource metric for this app
-        let metric_value: Option<f64> = match rule.metric.as_str() {
-            "cpu" => sqlx::query_scalar::<_, f64>(
-                "SELECT cpu_percent FROM resource_metrics WHERE app_id = ? \
-                     ORDER BY recorded_at DESC LIMIT 1",
-            )
-            .bind(&rule.app_id)
-            .fetch_optional(db)
-            .await
-            .ok()
-            .flatten(),
-            "memory" => sqlx::query_scalar::<_, f64>(
-                "SELECT memory_percent FROM resource_metrics WHERE app_id = ? \
-                     ORDER BY recorded_at DESC LIMIT 1",
-            )
-            .bind(&rule.app_id)
-            .fetch_optional(db)
-            .await
-            .ok()
-            .flatten(),
-            _ => None, // request_rate not yet implemented
+        if !utilization.contains_key(&rule.app_id) {
+            let usage = app_utilization(db, runtime.as_ref(), &rule.app_id).await;
+            utilization.insert(rule.app_id.clone(), usage);
+        }
+        let Some(usage) = utilization.get(&rule.app_id).copied().flatten() else {
+            continue;
         };
-
-        let Some(value) = metric_value else {
+        let Some(value) = rule.metric_value(usage) else {
             continue;
         };
 
-        // Fetch current replica count
-        let current_replicas: i64 =
-            sqlx::query_scalar::<_, i64>("SELECT replica_count FROM apps WHERE id = ?")
-                .bind(&rule.app_id)
-                .fetch_optional(db)
-                .await
-                .ok()
-                .flatten()
-                .unwrap_or(1)
-                .max(1);
-
-        let new_replicas = if value >= rule.scale_up_threshold {
-            (current_replicas + 1).min(rule.max_replicas)
-        } else if value <= rule.scale_down_threshold {
-            (current_replicas - 1).max(rule.min_replicas)
-        } else {
-            continue; // within comfortable range
+        let Ok(Some(app)) = sqlx::query_as::<_, App>("SELECT * FROM apps WHERE id = ?")
+            .bind(&rule.app_id)
+            .fetch_optional(db)
+            .await
+        else {
+            continue;
         };
-
-        if new_replicas == current_replicas {
+        let current_replicas = app.replica_count.max(1);
+        let Some(new_replicas) = rule.target_replicas(value, current_replicas) else {
             continue;
-        }
+        };
 
         tracing::info!(
             app_id = %rule.app_id,
@@ -749,11 +811,59 @@ async fn autoscaling_cycle(db: &DbPool) {
             .bind(&rule.id)
             .execute(db)
             .await;
+
+        // Start or stop the replicas of the running app
+        if let Err(e) =
+            apply_replica_count(db, runtime, routes, &app, current_replicas, new_replicas).await
+        {
+            tracing::warn!(error = %e, app_id = %rule.app_id, "Failed to apply autoscaled replica count");
+        }
+
+        let _ = log_audit(
+            db,
+            actions::APP_SCALE,
+            resource_types::APP,
+            Some(&app.id),
+            Some(&app.name),
+            None,
+            None,
+            Some(serde_json::json!({
+                "source": "autoscaler",
+                "rule_id": rule.id,
+                "metric": rule.metric,
+                "value": value,
+                "from": current_replicas,
+                "to": new_replicas,
+            })),
+        )
+        .await;
+
+        let direction = if new_replicas > current_replicas {
+            "up"
+        } else {
+            "down"
+        };
+        let payload = NotificationPayload::app_event(
+            NotificationEventType::AppScaled,
+            app.id.clone(),
+            app.name.clone(),
+            format!(
+                "Autoscaled {} {} from {} to {} replicas ({} at {:.1}%)",
+                app.name, direction, current_replicas, new_replicas, rule.metric, value
+            ),
+        );
+        if let Err(e) = outbox::enqueue(db, &payload).await {
+            tracing::warn!(error = %e, "Failed to queue app_scaled notification");
+        }
     }
 }
 
 /// Spawn the background autoscaling checker (runs every 60 seconds)
-pub fn spawn_autoscaling_checker(db: DbPool) {
+pub fn spawn_autoscaling_checker(
+    db: DbPool,
+    runtime: Arc<dyn ContainerRuntime>,
+    routes: Arc<ArcSwap<RouteTable>>,
+) {
     tracing::info!("Starting autoscaling checker (60s interval)");
 
     tokio::spawn(async move {
@@ -765,7 +875,99 @@ pub fn spawn_autoscaling_checker(db: DbPool) {
 
         loop {
             tick.tick().await;
-            crate::utils::supervise::guarded("autoscaling", autoscaling_cycle(&db)).await;
+            crate::utils::supervise::guarded(
+                "autoscaling",
+                autoscaling_cycle(&db, &runtime, &routes),
+            )
+            .await;
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(metric: &str) -> AutoscalingRuleRow {
+        AutoscalingRuleRow {
+            id: "rule-1".to_string(),
+            app_id: "app-1".to_string(),
+            metric: metric.to_string(),
+            scale_up_threshold: 80.0,
+            scale_down_threshold: 20.0,
+            min_replicas: 2,
+            max_replicas: 4,
+            cooldown_seconds: 300,
+            last_scaled_at: None,
+        }
+    }
+
+    #[test]
+    fn test_metric_value() {
+        let usage = Utilization {
+            cpu_percent: 55.0,
+            memory_percent: None,
+        };
+        assert_eq!(rule("cpu").metric_value(usage), Some(55.0));
+        // No memory limit to measure against
+        assert_eq!(rule("memory").metric_value(usage), None);
+        assert_eq!(rule("request_rate").metric_value(usage), None);
+
+        let usage = Utilization {
+            memory_percent: Some(70.0),
+            ..usage
+        };
+        assert_eq!(rule("memory").metric_value(usage), Some(70.0));
+    }
+
+    #[test]
+    fn test_target_replicas_thresholds() {
+        let rule = rule("cpu");
+        // One step at a time, at or past a threshold
+        assert_eq!(rule.target_replicas(80.0, 2), Some(3));
+        assert_eq!(rule.target_replicas(95.0, 3), Some(4));
+        assert_eq!(rule.target_replicas(20.0, 4), Some(3));
+        assert_eq!(rule.target_replicas(5.0, 3), Some(2));
+        // Within the comfortable range
+        assert_eq!(rule.target_replicas(50.0, 3), None);
+        assert_eq!(rule.target_replicas(79.9, 3), None);
+        assert_eq!(rule.target_replicas(20.1, 3), None);
+    }
+
+    #[test]
+    fn test_target_replicas_bounds() {
+        let mut rule = rule("cpu");
+        // Already at the rule's bounds
+        assert_eq!(rule.target_replicas(99.0, 4), None);
+        assert_eq!(rule.target_replicas(1.0, 2), None);
+        // An app above the maximum is brought back down to it
+        assert_eq!(rule.target_replicas(99.0, 6), Some(4));
+
+        // The rule can't go past MAX_REPLICAS or below one replica
+        rule.max_replicas = 50;
+        assert_eq!(
+            rule.target_replicas(99.0, MAX_REPLICAS - 1),
+            Some(MAX_REPLICAS)
+        );
+        assert_eq!(rule.target_replicas(99.0, MAX_REPLICAS), None);
+        rule.min_replicas = 0;
+        assert_eq!(rule.target_replicas(0.0, 1), None);
+        assert_eq!(rule.target_replicas(0.0, 2), Some(1));
+    }
+
+    #[test]
+    fn test_cooling_down() {
+        let now = Utc::now();
+        let mut rule = rule("cpu");
+        assert!(!rule.cooling_down(now));
+
+        rule.last_scaled_at = Some((now - chrono::Duration::seconds(299)).to_rfc3339());
+        assert!(rule.cooling_down(now));
+        rule.last_scaled_at = Some((now - chrono::Duration::seconds(300)).to_rfc3339());
+        assert!(!rule.cooling_down(now));
+
+        // An unreadable timestamp doesn't block scaling
+        rule.last_scaled_at = Some("yesterday".to_string());
+        assert!(!rule.cooling_down(now));
+    }
+}
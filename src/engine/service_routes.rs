@@ -0,0 +1,231 @@
+//! Proxy routes of Docker Compose services.
+//!
+//! A service's domain routes to the host port in `port` or, when
+//! `route_service` and `route_port` are set, to a container port of one of
+//! its compose services. That port is published on an ephemeral loopback port
+//! when the stack starts (see `publish_on_loopback`), which changes whenever
+//! the container is recreated, so it is looked up again each time the route
+//! is registered.
+//!
+//! App domains take precedence: a service domain that an app also serves is
+//! rejected by the API and never registered over the app's route.
+
+use std::path::Path;
+
+use crate::api::services::compose::{get_service_compose_dir, lookup_published_port};
+use crate::db::Service;
+use crate::proxy::RouteTable;
+use crate::DbPool;
+
+/// Look up and store the loopback port the service's route target is
+/// published on. Keeps the last known port when the lookup fails.
+pub async fn refresh_route_port(db: &DbPool, data_dir: &Path, service: &mut Service) {
+    let Some((target, port)) = service
+        .route_target()
+        .map(|(target, port)| (target.to_string(), port))
+    else {
+        return;
+    };
+    let compose_dir = get_service_compose_dir(data_dir, &service.name);
+    let compose_dir = if compose_dir.exists() {
+        compose_dir
+    } else {
+        std::env::temp_dir()
+    };
+
+    let project_name = service.compose_project_name();
+    match lookup_published_port(&compose_dir, &project_name, &target, port).await {
+        Ok(host_port) => {
+            service.route_host_port = Some(host_port as i32);
+            if let Err(e) = sqlx::query("UPDATE services SET route_host_port = ? WHERE id = ?")
+                .bind(host_port as i32)
+                .bind(&service.id)
+                .execute(db)
+                .await
+            {
+                tracing::warn!(service = %service.name, error = %e, "Failed to store route port");
+            }
+        }
+        Err(e) => tracing::warn!(
+            service = %service.name,
+            route_service = %target,
+            port,
+            error = %e.trim(),
+            "Failed to look up the published route port"
+        ),
+    }
+}
+
+/// Name of an app serving `domain` (as its domain, one of its domains or its
+/// auto-generated subdomain), if any
+pub async fn app_using_domain(db: &DbPool, domain: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT name FROM apps WHERE LOWER(domain) = LOWER(?1) \
+         OR LOWER(auto_subdomain) = LOWER(?1) \
+         OR EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(apps.domains) \
+             THEN apps.domains ELSE '[]' END) \
+             WHERE LOWER(json_extract(value, '$.domain')) = LOWER(?1)) \
+         LIMIT 1",
+    )
+    .bind(domain)
+    .fetch_optional(db)
+    .await
+}
+
+/// Register the proxy route of a running service's domain, if it has one.
+/// Skipped when an app serves the same domain.
+pub async fn register_service_route(
+    db: &DbPool,
+    routes: &RouteTable,
+    data_dir: &Path,
+    service: &mut Service,
+) {
+    let Some(domain) = service.domain.clone().filter(|d| !d.is_empty()) else {
+        return;
+    };
+    if let Ok(Some(app_name)) = app_using_domain(db, &domain).await {
+        tracing::warn!(
+            domain = %domain,
+            app = %app_name,
+            "Not routing service {}: the domain belongs to an app",
+            service.name
+        );
+        return;
+    }
+    refresh_route_port(db, data_dir, service).await;
+    routes.add_route(domain.clone(), service.proxy_backend());
+    tracing::info!(
+        domain = %domain,
+        port = service.proxy_port(),
+        route_service = service.route_service.as_deref().unwrap_or(""),
+        "Registered proxy route for service {}",
+        service.name
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_pool};
+
+    async fn create_service(db: &DbPool, name: &str, domain: &str) -> Service {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO services (id, name, compose_content, domain, port, status) \
+             VALUES (?, ?, 'services: {}', ?, 8080, 'running')",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(domain)
+        .execute(db)
+        .await
+        .unwrap();
+        sqlx::query_as("SELECT * FROM services WHERE id = ?")
+            .bind(&id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn set_app(db: &DbPool, app_id: &str, column: &str, value: &str) {
+        sqlx::query(&format!("UPDATE apps SET {} = ? WHERE id = ?", column))
+            .bind(value)
+            .bind(app_id)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_app_using_domain() {
+        let (_dir, db) = test_pool().await;
+        let legacy = test_app(&db, "legacy").await;
+        set_app(&db, &legacy, "domain", "legacy.example.com").await;
+        let multi = test_app(&db, "multi").await;
+        set_app(
+            &db,
+            &multi,
+            "domains",
+            r#"[{"domain": "a.example.com", "primary": true}, {"domain": "b.example.com"}]"#,
+        )
+        .await;
+        let auto = test_app(&db, "auto").await;
+        set_app(&db, &auto, "auto_subdomain", "auto.rivetr.example.com").await;
+        // Unreadable domains JSON doesn't break the lookup
+        let broken = test_app(&db, "broken").await;
+        set_app(&db, &broken, "domains", "not json").await;
+
+        for (domain, app) in [
+            ("legacy.example.com", Some("legacy")),
+            ("LEGACY.example.com", Some("legacy")),
+            ("b.example.com", Some("multi")),
+            ("auto.rivetr.example.com", Some("auto")),
+            ("free.example.com", None),
+        ] {
+            assert_eq!(
+                app_using_domain(&db, domain).await.unwrap().as_deref(),
+                app,
+                "{}",
+                domain
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_service_route() {
+        let (dir, db) = test_pool().await;
+        let routes = RouteTable::new();
+
+        let mut service = create_service(&db, "grafana", "grafana.example.com").await;
+        register_service_route(&db, &routes, dir.path(), &mut service).await;
+        let backend = routes.get_backend("grafana.example.com").unwrap();
+        assert_eq!(backend.container_id, "rivetr-svc-grafana");
+        assert_eq!(backend.addr(), "127.0.0.1:8080");
+        assert!(!backend.basic_auth.enabled);
+
+        // A compose service target goes to the port it was last published on
+        // when it can't be looked up again
+        sqlx::query(
+            "UPDATE services SET route_service = 'web', route_port = 3000, \
+             route_host_port = 49153, basic_auth_enabled = 1, basic_auth_username = 'admin', \
+             basic_auth_password_hash = 'hash' WHERE id = ?",
+        )
+        .bind(&service.id)
+        .execute(&db)
+        .await
+        .unwrap();
+        let mut service: Service = sqlx::query_as("SELECT * FROM services WHERE id = ?")
+            .bind(&service.id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        register_service_route(&db, &routes, dir.path(), &mut service).await;
+        let backend = routes.get_backend("grafana.example.com").unwrap();
+        assert_eq!(backend.port, service.route_host_port.unwrap() as u16);
+        assert!(backend.basic_auth.enabled);
+        assert_eq!(backend.basic_auth.username.as_deref(), Some("admin"));
+
+        // Services without a domain get no route
+        let mut worker = create_service(&db, "queue", "").await;
+        register_service_route(&db, &routes, dir.path(), &mut worker).await;
+        assert_eq!(routes.domains(), vec!["grafana.example.com".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_app_domains_win_over_service_domains() {
+        let (dir, db) = test_pool().await;
+        let routes = RouteTable::new();
+        let app_id = test_app(&db, "web").await;
+        set_app(&db, &app_id, "domain", "shop.example.com").await;
+        routes.add_route(
+            "shop.example.com".to_string(),
+            crate::proxy::Backend::new("app-container".into(), "127.0.0.1".into(), 3000),
+        );
+
+        let mut service = create_service(&db, "shop", "shop.example.com").await;
+        register_service_route(&db, &routes, dir.path(), &mut service).await;
+
+        let backend = routes.get_backend("shop.example.com").unwrap();
+        assert_eq!(backend.container_id, "app-container");
+    }
+}
@@ -0,0 +1,143 @@
+//! Post-deploy smoke tests.
+//!
+//! A health check only proves the app answers on one endpoint. Apps can list
+//! further HTTP checks (path, expected status, text the body must contain)
+//! that are run once against the new container after it passes its health
+//! check and before traffic switches to it. Every result is written to the
+//! deployment log; any failure fails the deployment, which then takes the
+//! same auto-rollback path as a failed health check.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::db::{App, SmokeTest};
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// Timeout of a single smoke test request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest slice of an unexpected response body quoted in the log
+const BODY_EXCERPT_CHARS: usize = 200;
+
+/// Run the app's smoke tests against the container listening on `port`.
+/// All tests run even after a failure; fails if any of them did.
+pub async fn run_smoke_tests(db: &DbPool, deployment_id: &str, app: &App, port: u16) -> Result<()> {
+    let tests = app.get_smoke_tests();
+    if tests.is_empty() {
+        return Ok(());
+    }
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!("Running {} smoke test(s)...", tests.len()),
+    )
+    .await?;
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let base_url = format!("http://127.0.0.1:{}", port);
+
+    let mut failed = 0;
+    for test in &tests {
+        match check(&client, &base_url, test).await {
+            Ok(()) => {
+                add_deployment_log(
+                    db,
+                    deployment_id,
+                    "info",
+                    &format!(
+                        "Smoke test GET {} passed ({})",
+                        test.path, test.expected_status
+                    ),
+                )
+                .await?;
+            }
+            Err(reason) => {
+                failed += 1;
+                add_deployment_log(
+                    db,
+                    deployment_id,
+                    "error",
+                    &format!("Smoke test GET {} failed: {}", test.path, reason),
+                )
+                .await?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} smoke test(s) failed", failed, tests.len());
+    }
+    add_deployment_log(db, deployment_id, "info", "Smoke tests passed").await?;
+    Ok(())
+}
+
+/// Run one test. Returns why it failed.
+async fn check(client: &reqwest::Client, base_url: &str, test: &SmokeTest) -> Result<(), String> {
+    let response = client
+        .get(format!("{}{}", base_url, test.path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    evaluate(test, status, &body)
+}
+
+fn evaluate(test: &SmokeTest, status: u16, body: &str) -> Result<(), String> {
+    if status != test.expected_status {
+        return Err(format!(
+            "expected status {}, got {}",
+            test.expected_status, status
+        ));
+    }
+    if let Some(ref expected) = test.expected_body {
+        if !body.contains(expected.as_str()) {
+            let excerpt: String = body.chars().take(BODY_EXCERPT_CHARS).collect();
+            return Err(format!(
+                "response body does not contain {:?} (body starts with {:?})",
+                expected, excerpt
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(status: u16, body: Option<&str>) -> SmokeTest {
+        SmokeTest {
+            path: "/api/status".to_string(),
+            expected_status: status,
+            expected_body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert!(evaluate(&test(200, None), 200, "").is_ok());
+        assert!(evaluate(&test(200, Some("\"ok\"")), 200, r#"{"status":"ok"}"#).is_ok());
+        assert!(evaluate(&test(404, None), 404, "not found").is_ok());
+
+        let err = evaluate(&test(200, None), 500, "boom").unwrap_err();
+        assert_eq!(err, "expected status 200, got 500");
+
+        let err = evaluate(&test(200, Some("ready")), 200, "starting").unwrap_err();
+        assert!(err.contains("\"ready\"") && err.contains("\"starting\""));
+    }
+
+    #[test]
+    fn test_defaults() {
+        let test: SmokeTest = serde_json::from_str(r#"{"path": "/"}"#).unwrap();
+        assert_eq!(test.expected_status, 200);
+        assert_eq!(test.expected_body, None);
+    }
+}
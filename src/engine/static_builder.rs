@@ -8,14 +8,17 @@
 //! - Generates optimized multi-stage Dockerfiles
 //! - Configures NGINX for SPA routing with try_files
 //! - Supports custom build commands and publish directories
+//! - Runs non-Node generators (Hugo, Zola, mdBook, ...) in a builder image of
+//!   the app's choice when the project has no package.json
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
 use tokio::fs;
 use tracing::{debug, info};
 
-use crate::runtime::{BuildContext, ContainerRuntime};
+use crate::runtime::{BuildContext, BuildIsolation, ContainerRuntime};
 
 /// Package manager detected from lock files
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -76,6 +79,32 @@ impl PackageManager {
     }
 }
 
+/// Per-app static site settings, stored as JSON in `apps.static_config`.
+/// The output directory is the app's `publish_directory`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StaticSiteSettings {
+    /// Build command (defaults to the package manager's build script)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_command: Option<String>,
+    /// Dependency install command (defaults to the package manager's)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_command: Option<String>,
+    /// Image the build runs in (e.g. "hugomods/hugo:exts"); defaults to Node
+    /// for package.json projects and Alpine otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_image: Option<String>,
+    /// Serve index.html for unknown paths (client-side routing); default on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spa_mode: Option<bool>,
+}
+
+impl StaticSiteSettings {
+    /// Parse from JSON string
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Failed to parse static site config from JSON")
+    }
+}
+
 /// Configuration for building a static site
 #[derive(Debug, Clone)]
 pub struct StaticSiteConfig {
@@ -91,6 +120,8 @@ pub struct StaticSiteConfig {
     pub env_vars: Vec<(String, String)>,
     /// Node.js version to use (default: 20)
     pub node_version: Option<String>,
+    /// Image to build in, overriding the Node image
+    pub build_image: Option<String>,
     /// Whether to enable SPA mode (try_files for client-side routing)
     pub spa_mode: bool,
     /// Custom NGINX configuration (optional)
@@ -103,6 +134,8 @@ pub struct StaticSiteConfig {
     pub memory_limit: Option<String>,
     /// Port to listen on (default: 3000)
     pub port: u16,
+    /// Docker host to build on instead of the local daemon
+    pub build_host: Option<String>,
 }
 
 impl Default for StaticSiteConfig {
@@ -114,12 +147,14 @@ impl Default for StaticSiteConfig {
             custom_install_command: None,
             env_vars: Vec::new(),
             node_version: None,
+            build_image: None,
             spa_mode: true,
             custom_nginx_config: None,
             build_args: Vec::new(),
             cpu_limit: None,
             memory_limit: None,
             port: 3000,
+            build_host: None,
         }
     }
 }
@@ -341,10 +376,75 @@ CMD ["nginx", "-g", "daemon off;"]
         )
     }
 
+    /// `ENV` lines exposing the app's environment variables to the build
+    fn env_lines(config: &StaticSiteConfig) -> String {
+        config
+            .env_vars
+            .iter()
+            .map(|(k, v)| format!("ENV {}=\"{}\"", k, v.replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Generate a Dockerfile running the build command in the builder image,
+    /// for generators that don't use a package.json (Hugo, Zola, mdBook, ...)
+    fn generate_command_dockerfile(config: &StaticSiteConfig, build_cmd: &str) -> String {
+        let base_image = config.build_image.as_deref().unwrap_or("alpine:3");
+        let install_line = config
+            .custom_install_command
+            .as_deref()
+            .map(|cmd| format!("RUN {}\n", cmd))
+            .unwrap_or_default();
+
+        format!(
+            r#"# Build stage
+FROM {base_image} AS builder
+
+WORKDIR /app
+
+# Copy source code
+COPY . .
+
+# Build environment variables
+{env_lines}
+
+{install_line}# Build the site
+RUN {build_cmd}
+
+# Production stage
+FROM nginx:alpine AS production
+
+# Copy built assets from builder
+COPY --from=builder /app/{publish_dir} /usr/share/nginx/html
+
+# Copy NGINX configuration
+COPY nginx.conf /etc/nginx/conf.d/default.conf
+
+# Expose port {port}
+EXPOSE {port}
+
+# Health check
+HEALTHCHECK --interval=30s --timeout=3s --start-period=5s --retries=3 \
+    CMD wget --no-verbose --tries=1 --spider http://localhost:{port}/health || exit 1
+
+# Start NGINX
+CMD ["nginx", "-g", "daemon off;"]
+"#,
+            base_image = base_image,
+            env_lines = Self::env_lines(config),
+            install_line = install_line,
+            build_cmd = build_cmd,
+            publish_dir = config.publish_dir,
+            port = config.port,
+        )
+    }
+
     /// Generate the Dockerfile for building the static site
     fn generate_dockerfile(package_manager: PackageManager, config: &StaticSiteConfig) -> String {
         let node_version = config.node_version.as_deref().unwrap_or("20");
-        let base_image = if package_manager == PackageManager::Bun {
+        let base_image = if let Some(ref image) = config.build_image {
+            image.clone()
+        } else if package_manager == PackageManager::Bun {
             package_manager.base_image().to_string()
         } else {
             format!("node:{}-alpine", node_version)
@@ -360,13 +460,7 @@ CMD ["nginx", "-g", "daemon off;"]
             .as_deref()
             .unwrap_or_else(|| package_manager.build_command());
 
-        // Build environment variables string
-        let env_lines: String = config
-            .env_vars
-            .iter()
-            .map(|(k, v)| format!("ENV {}=\"{}\"", k, v.replace('"', "\\\"")))
-            .collect::<Vec<_>>()
-            .join("\n");
+        let env_lines = Self::env_lines(config);
 
         // Copy dependency files for better caching
         let dep_files = package_manager.dependency_files();
@@ -458,6 +552,12 @@ CMD ["nginx", "-g", "daemon off;"]
                 "Building static site with package manager"
             );
             Self::generate_dockerfile(package_manager, config)
+        } else if let Some(ref build_cmd) = config.custom_build_command {
+            info!(
+                build_image = config.build_image.as_deref().unwrap_or("alpine:3"),
+                "Building static site with custom build command"
+            );
+            Self::generate_command_dockerfile(config, build_cmd)
         } else {
             // Plain HTML site - no build step needed
             info!("Building plain HTML static site (no package.json, no build step)");
@@ -496,6 +596,9 @@ CMD ["nginx", "-g", "daemon off;"]
             build_platforms: None,
             no_cache: false,
             source_commit: None,
+            isolation: BuildIsolation::default(),
+            cache: None,
+            build_host: config.build_host.clone(),
         };
 
         let result = self
@@ -586,6 +689,30 @@ mod tests {
         assert!(dockerfile.contains("ENV NODE_ENV=\"production\""));
     }
 
+    #[test]
+    fn test_generate_command_dockerfile() {
+        let config = StaticSiteConfig {
+            publish_dir: "public".to_string(),
+            build_image: Some("hugomods/hugo:exts".to_string()),
+            ..Default::default()
+        };
+
+        let dockerfile = StaticSiteBuilder::generate_command_dockerfile(&config, "hugo --minify");
+        assert!(dockerfile.contains("FROM hugomods/hugo:exts AS builder"));
+        assert!(dockerfile.contains("RUN hugo --minify"));
+        assert!(dockerfile.contains("COPY --from=builder /app/public"));
+    }
+
+    #[test]
+    fn test_static_site_settings_from_json() {
+        let settings =
+            StaticSiteSettings::from_json(r#"{"build_command":"npm run export","spa_mode":false}"#)
+                .unwrap();
+        assert_eq!(settings.build_command.as_deref(), Some("npm run export"));
+        assert_eq!(settings.spa_mode, Some(false));
+        assert!(settings.build_image.is_none());
+    }
+
     #[test]
     fn test_generate_dockerfile_with_pnpm() {
         let config = StaticSiteConfig::default();
@@ -0,0 +1,179 @@
+//! Pre-release test stage.
+//!
+//! Apps can configure test commands (`npm test`, a smoke script, ...) that
+//! run in a throwaway container of the freshly built image, after the image
+//! scan and before the image is pushed or started. The commands run one
+//! after the other with the app's env vars but no ports, volumes or network
+//! aliases. The first failing command, or running past the app's test
+//! timeout, fails the deployment while the old container is still serving.
+//!
+//! The combined output is written to the deployment log and stored on the
+//! deployment (`test_output`). A pipeline file's `test` section replaces the
+//! app's commands and timeout for that deployment.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::db::App;
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+use super::deploy_phases::{self, DeployPhase};
+use super::processes::shell_command;
+use super::scheduled_tasks::{collect_output, tail, MAX_OUTPUT_BYTES};
+use super::{add_deployment_log, KEY_LENGTH};
+
+/// Run the app's test commands against `image`. Does nothing when the app
+/// has none.
+pub async fn run_tests(
+    db: &DbPool,
+    runtime: &Arc<dyn ContainerRuntime>,
+    deployment_id: &str,
+    app: &App,
+    image: &str,
+    encryption_key: Option<&[u8; KEY_LENGTH]>,
+) -> Result<()> {
+    let commands = app.get_test_commands();
+    if commands.is_empty() {
+        return Ok(());
+    }
+    deploy_phases::begin(db, deployment_id, DeployPhase::Test).await;
+    add_deployment_log(
+        db,
+        deployment_id,
+        "info",
+        &format!(
+            "Running {} test command(s) in the built image...",
+            commands.len()
+        ),
+    )
+    .await?;
+
+    let env = super::pipeline::collect_env_vars(db, app, encryption_key, Some(deployment_id)).await;
+    let container_name = format!("rivetr-{}-test", app.name);
+    let mut config =
+        super::pipeline::build_run_config(db, app, image.to_string(), container_name.clone(), env)
+            .await;
+    config.port = 0;
+    config.port_mappings = vec![];
+    config.network_aliases = vec![];
+    config.binds = vec![];
+    config.restart_policy = "never".to_string();
+
+    let timeout = Duration::from_secs(app.test_timeout_secs() as u64);
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut output = String::new();
+    let mut failure = None;
+    for command in &commands {
+        // A container left behind by an interrupted deployment holds the name
+        let _ = runtime.remove(&container_name).await;
+
+        add_deployment_log(db, deployment_id, "info", &format!("$ {}", command)).await?;
+        output.push_str(&format!("$ {}\n", command));
+        config.cmd = Some(shell_command(command));
+        let container_id = match runtime.run(&config).await {
+            Ok(id) => id,
+            Err(e) => {
+                failure = Some(e.context("Failed to start test container"));
+                break;
+            }
+        };
+
+        let exit = tokio::time::timeout_at(deadline, runtime.wait(&container_id)).await;
+        if exit.is_err() {
+            let _ = runtime.stop(&container_id).await;
+        }
+        if let Some(logs) = collect_output(runtime.as_ref(), &container_id).await {
+            for line in logs.lines().filter(|l| !l.trim().is_empty()) {
+                add_deployment_log(db, deployment_id, "info", line).await?;
+            }
+            output.push_str(&logs);
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+        let _ = runtime.remove(&container_id).await;
+
+        failure = match exit {
+            Ok(Ok(0)) => continue,
+            Ok(Ok(code)) => Some(anyhow::anyhow!(
+                "Test command `{}` exited with code {}",
+                command,
+                code
+            )),
+            Ok(Err(e)) => Some(e.context("Failed to wait for test container")),
+            Err(_) => Some(anyhow::anyhow!(
+                "Tests timed out after {} seconds",
+                timeout.as_secs()
+            )),
+        };
+        break;
+    }
+
+    sqlx::query("UPDATE deployments SET test_output = ? WHERE id = ?")
+        .bind(tail(&output, MAX_OUTPUT_BYTES))
+        .bind(deployment_id)
+        .execute(db)
+        .await
+        .context("Failed to store test output")?;
+
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    add_deployment_log(db, deployment_id, "info", "Tests passed").await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool};
+    use crate::runtime::NoopRuntime;
+
+    #[tokio::test]
+    async fn test_apps_without_commands_skip_the_stage() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let app = load_test_app(&db, &app_id).await;
+        let deployment_id = test_deployment(&db, &app_id, "building").await;
+        let runtime: Arc<dyn ContainerRuntime> = Arc::new(NoopRuntime);
+
+        run_tests(&db, &runtime, &deployment_id, &app, "web:1", None)
+            .await
+            .unwrap();
+
+        let output: Option<String> =
+            sqlx::query_scalar("SELECT test_output FROM deployments WHERE id = ?")
+                .bind(&deployment_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(output, None);
+    }
+
+    #[tokio::test]
+    async fn test_a_container_that_cannot_start_fails_the_stage() {
+        let (_dir, db) = test_pool().await;
+        let app_id = test_app(&db, "web").await;
+        let mut app = load_test_app(&db, &app_id).await;
+        app.test_commands = Some(r#"["npm test","./smoke.sh"]"#.to_string());
+        let deployment_id = test_deployment(&db, &app_id, "building").await;
+        let runtime: Arc<dyn ContainerRuntime> = Arc::new(NoopRuntime);
+
+        let err = run_tests(&db, &runtime, &deployment_id, &app, "web:1", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to start test container"));
+
+        // Stops at the first command, and keeps what ran
+        let output: Option<String> =
+            sqlx::query_scalar("SELECT test_output FROM deployments WHERE id = ?")
+                .bind(&deployment_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(output.as_deref(), Some("$ npm test\n"));
+    }
+}
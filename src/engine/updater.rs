@@ -1,7 +1,8 @@
 //! Auto-update system for Rivetr
 //!
 //! Checks for new releases on GitHub and optionally downloads/applies updates.
-//! Updates are atomic - the binary is replaced and requires a service restart.
+//! Updates are atomic - the binary is replaced and requires a service restart,
+//! or a socket handover to the new binary when `hot_restart` is enabled.
 
 use anyhow::{anyhow, Context, Result};
 use parking_lot::RwLock;
@@ -389,11 +390,22 @@ pub fn start_update_checker(config: AutoUpdateConfig) -> Arc<UpdateChecker> {
                 if should_apply {
                     info!("Auto-applying update...");
                     match checker_clone.download_update().await {
-                        Ok(path) => {
-                            if let Err(e) = checker_clone.apply_update(&path).await {
+                        Ok(path) => match checker_clone.apply_update(&path).await {
+                            Ok(_) if checker_clone.config.hot_restart => {
+                                info!("Handing over to the updated binary...");
+                                if let Err(e) = crate::utils::handover::hand_over(
+                                    crate::utils::handover::DEFAULT_READY_TIMEOUT,
+                                )
+                                .await
+                                {
+                                    error!("Failed to hand over to the updated binary: {}", e);
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
                                 error!("Failed to apply update: {}", e);
                             }
-                        }
+                        },
                         Err(e) => {
                             error!("Failed to download update: {}", e);
                         }
@@ -0,0 +1,346 @@
+//! Deploy wait conditions.
+//!
+//! An app can declare dependencies that must be ready before its new container
+//! starts: a managed database accepting connections, or a URL (typically
+//! another app's health endpoint) returning 200. Each condition is polled until
+//! it holds or its timeout expires, in which case the deployment fails with a
+//! message naming the dependency, rather than starting a container that
+//! crash-loops or sleeping in a pre-deploy command.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::db::{App, DatabaseStatus, DatabaseType, ManagedDatabase, WaitCondition, WaitTarget};
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+use super::add_deployment_log;
+
+/// Delay between checks of a condition
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Timeout of a single HTTP check
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Exit code of a shell command that doesn't exist in the image
+const COMMAND_NOT_FOUND: i32 = 127;
+
+/// Wait for every condition of the app in order. Fails on the first one that
+/// doesn't hold within its timeout.
+pub async fn wait_for_conditions(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    deployment_id: &str,
+    app: &App,
+) -> Result<()> {
+    let conditions = app.get_deploy_wait_conditions();
+    if conditions.is_empty() {
+        return Ok(());
+    }
+    let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+
+    for condition in &conditions {
+        let label = describe(db, condition).await;
+        add_deployment_log(
+            db,
+            deployment_id,
+            "info",
+            &format!(
+                "Waiting for {} (timeout {}s)...",
+                label, condition.timeout_seconds
+            ),
+        )
+        .await?;
+
+        let deadline = Instant::now() + Duration::from_secs(condition.timeout_seconds);
+        loop {
+            let reason = match check(db, runtime, &client, condition).await {
+                Ok(()) => break,
+                Err(reason) => reason,
+            };
+            if Instant::now() + POLL_INTERVAL > deadline {
+                anyhow::bail!(
+                    "Timed out after {}s waiting for {}: {}",
+                    condition.timeout_seconds,
+                    label,
+                    reason
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        add_deployment_log(db, deployment_id, "info", &format!("{} is ready", label)).await?;
+    }
+    Ok(())
+}
+
+/// Human-readable name of a condition's dependency for the deployment log
+async fn describe(db: &DbPool, condition: &WaitCondition) -> String {
+    match condition.target {
+        WaitTarget::Database { ref database_id } => {
+            let name: Option<String> =
+                sqlx::query_scalar("SELECT name FROM databases WHERE id = ?")
+                    .bind(database_id)
+                    .fetch_optional(db)
+                    .await
+                    .ok()
+                    .flatten();
+            format!("database '{}'", name.as_deref().unwrap_or(database_id))
+        }
+        WaitTarget::Http { ref url } => format!("{} to return 200", url),
+    }
+}
+
+/// Check a condition once. Returns why it doesn't hold yet.
+async fn check(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    client: &reqwest::Client,
+    condition: &WaitCondition,
+) -> Result<(), String> {
+    match condition.target {
+        WaitTarget::Database { ref database_id } => check_database(db, runtime, database_id).await,
+        WaitTarget::Http { ref url } => match client.get(url).send().await {
+            Ok(response) if response.status() == reqwest::StatusCode::OK => Ok(()),
+            Ok(response) => Err(format!("returned {}", response.status())),
+            Err(e) => Err(e.to_string()),
+        },
+    }
+}
+
+async fn check_database(
+    db: &DbPool,
+    runtime: &dyn ContainerRuntime,
+    database_id: &str,
+) -> Result<(), String> {
+    let database = sqlx::query_as::<_, ManagedDatabase>("SELECT * FROM databases WHERE id = ?")
+        .bind(database_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "database not found".to_string())?;
+
+    if database.get_status() != DatabaseStatus::Running {
+        return Err(format!("database is {}", database.status));
+    }
+    let container_id = database
+        .container_id
+        .as_deref()
+        .ok_or_else(|| "database has no container".to_string())?;
+    let info = runtime
+        .inspect(container_id)
+        .await
+        .map_err(|e| format!("container not found: {}", e))?;
+    if !info.running {
+        return Err(format!("container is {}", info.status));
+    }
+
+    let Some(cmd) = readiness_command(&database) else {
+        return Ok(());
+    };
+    match runtime.run_command(container_id, cmd).await {
+        Ok(result) if result.exit_code == 0 || result.exit_code == COMMAND_NOT_FOUND => Ok(()),
+        Ok(result) => Err(format!(
+            "not accepting connections yet ({})",
+            result
+                .stderr
+                .trim()
+                .lines()
+                .last()
+                .unwrap_or("readiness check failed")
+        )),
+        // Exec unavailable: the running container is the best signal we have
+        Err(_) => Ok(()),
+    }
+}
+
+/// Command that exits 0 once the database accepts connections
+fn readiness_command(database: &ManagedDatabase) -> Option<Vec<String>> {
+    let sh = |script: &str| Some(vec!["sh".to_string(), "-c".to_string(), script.to_string()]);
+    match database.get_db_type() {
+        DatabaseType::Postgres => {
+            let user = database
+                .get_credentials()
+                .map(|c| c.username)
+                .unwrap_or_else(|| "postgres".to_string());
+            Some(vec!["pg_isready".to_string(), "-U".to_string(), user])
+        }
+        DatabaseType::Mysql | DatabaseType::Mariadb => {
+            sh("mariadb-admin ping -h 127.0.0.1 --silent 2>/dev/null \
+             || mysqladmin ping -h 127.0.0.1 --silent")
+        }
+        DatabaseType::Redis | DatabaseType::Keydb | DatabaseType::Dragonfly => {
+            sh("redis-cli ping || keydb-cli ping")
+        }
+        DatabaseType::Mongodb => sh("mongosh --quiet --eval 'db.adminCommand(\"ping\")'"),
+        DatabaseType::ClickHouse | DatabaseType::Libsql => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{load_test_app, test_app, test_deployment, test_pool};
+    use crate::runtime::NoopRuntime;
+    use axum::{http::StatusCode, routing::get, Router};
+
+    /// An app with `conditions` and a deployment of it
+    async fn app_with(db: &DbPool, conditions: serde_json::Value) -> (App, String) {
+        let app_id = test_app(db, "web").await;
+        sqlx::query("UPDATE apps SET deploy_wait_conditions = ? WHERE id = ?")
+            .bind(conditions.to_string())
+            .bind(&app_id)
+            .execute(db)
+            .await
+            .unwrap();
+        let deployment_id = test_deployment(db, &app_id, "starting").await;
+        (load_test_app(db, &app_id).await, deployment_id)
+    }
+
+    async fn insert_database(db: &DbPool, db_type: &str, status: &str) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO databases (id, name, db_type, version, status, internal_port, credentials) \
+             VALUES (?, ?, ?, '16', ?, 5432, '{\"username\":\"app\",\"password\":\"secret\"}')",
+        )
+        .bind(&id)
+        .bind(format!("{}-{}", db_type, &id[..8]))
+        .bind(db_type)
+        .bind(status)
+        .execute(db)
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn serve() -> std::net::SocketAddr {
+        let router = Router::new()
+            .route("/ready", get(|| async { StatusCode::OK }))
+            .route("/down", get(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_http_conditions() {
+        let (_dir, db) = test_pool().await;
+        let addr = serve().await;
+
+        let ready = format!("http://{}/ready", addr);
+        let (app, deployment_id) =
+            app_with(&db, serde_json::json!([{"type": "http", "url": ready}])).await;
+        wait_for_conditions(&db, &NoopRuntime, &deployment_id, &app)
+            .await
+            .unwrap();
+        let logged: Vec<String> =
+            sqlx::query_scalar("SELECT message FROM deployment_logs WHERE deployment_id = ?")
+                .bind(&deployment_id)
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert!(logged.iter().any(|m| m.contains("(timeout 120s)")));
+        assert!(logged.iter().any(|m| m.ends_with("to return 200 is ready")));
+
+        // Conditions are checked in order, failing on the first that doesn't hold
+        let down = format!("http://{}/down", addr);
+        let (app, deployment_id) = app_with(
+            &db,
+            serde_json::json!([
+                {"type": "http", "url": ready, "timeout_seconds": 1},
+                {"type": "http", "url": down, "timeout_seconds": 1},
+            ]),
+        )
+        .await;
+        let err = wait_for_conditions(&db, &NoopRuntime, &deployment_id, &app)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Timed out after 1s waiting for {} to return 200: returned 503 Service Unavailable",
+                down
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_database_conditions() {
+        let (_dir, db) = test_pool().await;
+
+        let missing = uuid::Uuid::new_v4().to_string();
+        let (app, deployment_id) = app_with(
+            &db,
+            serde_json::json!([{"type": "database", "database_id": missing, "timeout_seconds": 1}]),
+        )
+        .await;
+        let err = wait_for_conditions(&db, &NoopRuntime, &deployment_id, &app)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().ends_with(": database not found"), "{}", err);
+
+        let stopped = insert_database(&db, "postgres", "stopped").await;
+        let (app, deployment_id) = app_with(
+            &db,
+            serde_json::json!([{"type": "database", "database_id": stopped, "timeout_seconds": 1}]),
+        )
+        .await;
+        let err = wait_for_conditions(&db, &NoopRuntime, &deployment_id, &app)
+            .await
+            .unwrap_err()
+            .to_string();
+        // The log names the database rather than its ID
+        assert!(err.contains("waiting for database 'postgres-"), "{}", err);
+        assert!(err.ends_with(": database is stopped"), "{}", err);
+
+        // Running, but without a container to check
+        let running = insert_database(&db, "postgres", "running").await;
+        let database: ManagedDatabase = sqlx::query_as("SELECT * FROM databases WHERE id = ?")
+            .bind(&running)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            check_database(&db, &NoopRuntime, &running).await,
+            Err("database has no container".to_string())
+        );
+        assert_eq!(
+            readiness_command(&database),
+            Some(vec![
+                "pg_isready".to_string(),
+                "-U".to_string(),
+                "app".to_string()
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_readiness_commands() {
+        let (_dir, db) = test_pool().await;
+        for (db_type, expected) in [
+            ("redis", Some("redis-cli ping")),
+            ("mysql", Some("mysqladmin ping")),
+            ("mongodb", Some("mongosh")),
+            ("clickhouse", None),
+        ] {
+            let id = insert_database(&db, db_type, "running").await;
+            let database: ManagedDatabase = sqlx::query_as("SELECT * FROM databases WHERE id = ?")
+                .bind(&id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+            let command = readiness_command(&database).map(|cmd| cmd.join(" "));
+            match expected {
+                Some(needle) => assert!(
+                    command.as_deref().is_some_and(|c| c.contains(needle)),
+                    "{}: {:?}",
+                    db_type,
+                    command
+                ),
+                None => assert_eq!(command, None, "{}", db_type),
+            }
+        }
+    }
+}
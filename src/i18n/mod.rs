@@ -0,0 +1,278 @@
+//! Localization of user-facing messages.
+//!
+//! Messages live in a catalog per locale (`locales/<code>.json`), a flat map
+//! from message key to text with `{name}` placeholders. English is the source
+//! catalog: a key missing from another locale falls back to English, and a
+//! key missing everywhere is shown as is.
+//!
+//! API requests are answered in the locale negotiated from `Accept-Language`
+//! (the dashboard sends the locale chosen in the user's preferences); messages
+//! sent outside a request, such as digest emails, use the recipient's stored
+//! preference. Error messages still written as English text at their call
+//! site are translated by looking the text up in the English catalog, so they
+//! can be localized by adding them there.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use axum::{body::Body, extract::Request, http::header, middleware::Next, response::Response};
+
+/// A supported locale
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    /// Arabic (right-to-left)
+    Ar,
+}
+
+/// Locales with a catalog, in the order offered to users
+pub const SUPPORTED_LOCALES: [Locale; 2] = [Locale::En, Locale::Ar];
+
+impl Locale {
+    /// BCP 47 language code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ar => "ar",
+        }
+    }
+
+    /// Locale for a language tag; regional variants (`ar-EG`) map to their language
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        SUPPORTED_LOCALES
+            .into_iter()
+            .find(|locale| locale.code() == language)
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        matches!(self, Locale::Ar)
+    }
+
+    /// Text direction for the HTML `dir` attribute
+    pub fn dir(&self) -> &'static str {
+        if self.is_rtl() {
+            "rtl"
+        } else {
+            "ltr"
+        }
+    }
+
+    /// The supported locale the client prefers most in an `Accept-Language`
+    /// header, if any
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_code) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && best.map_or(true, |(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+fn parse_catalog(json: &str) -> Catalog {
+    serde_json::from_str(json).expect("message catalog must be a JSON object of strings")
+}
+
+fn catalog(locale: Locale) -> &'static Catalog {
+    static EN: OnceLock<Catalog> = OnceLock::new();
+    static AR: OnceLock<Catalog> = OnceLock::new();
+    match locale {
+        Locale::En => EN.get_or_init(|| parse_catalog(include_str!("locales/en.json"))),
+        Locale::Ar => AR.get_or_init(|| parse_catalog(include_str!("locales/ar.json"))),
+    }
+}
+
+/// Message keys by English text, for messages without placeholders
+fn keys_by_english_text() -> &'static HashMap<&'static str, &'static str> {
+    static INDEX: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        catalog(Locale::En)
+            .iter()
+            .filter(|(_, text)| !text.contains('{'))
+            .map(|(key, text)| (text.as_str(), key.as_str()))
+            .collect()
+    })
+}
+
+/// Text of a message in `locale`
+pub fn t(locale: Locale, key: &str) -> String {
+    catalog(locale)
+        .get(key)
+        .or_else(|| catalog(Locale::En).get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Text of a message in `locale` with its `{name}` placeholders filled in
+pub fn t_args(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = t(locale, key);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Text of a counted message: `<key>_one` for a count of one, `<key>_other`
+/// otherwise, with `{count}` filled in along with `args`
+pub fn t_count(locale: Locale, key: &str, count: usize, args: &[(&str, &str)]) -> String {
+    let form = if count == 1 { "one" } else { "other" };
+    let count = count.to_string();
+    let mut all_args = vec![("count", count.as_str())];
+    all_args.extend_from_slice(args);
+    t_args(locale, &format!("{}_{}", key, form), &all_args)
+}
+
+/// Translate a message written as English text. Text that isn't in the
+/// English catalog is returned unchanged.
+pub fn localize_text(locale: Locale, text: &str) -> String {
+    if locale == Locale::En {
+        return text.to_string();
+    }
+    match keys_by_english_text().get(text) {
+        Some(key) => t(locale, key),
+        None => text.to_string(),
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// Locale of the API request being handled (English outside a request)
+pub fn request_locale() -> Locale {
+    REQUEST_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// Middleware negotiating the response locale from `Accept-Language`. The
+/// locale is available to handlers (and error responses) through
+/// [`request_locale`] and is echoed in `Content-Language`.
+pub async fn locale_middleware(request: Request<Body>, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default();
+
+    let mut response = REQUEST_LOCALE.scope(locale, next.run(request)).await;
+    if let Ok(value) = locale.code().parse() {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LANGUAGE, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_catalogs_have_the_same_keys_and_placeholders() {
+        let english = catalog(Locale::En);
+        for locale in SUPPORTED_LOCALES {
+            let messages = catalog(locale);
+            assert_eq!(messages.len(), english.len(), "{} catalog size", locale);
+            for (key, text) in english {
+                let translated = messages
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", locale, key));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(text),
+                    "{} placeholders of {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_code_accepts_regional_variants() {
+        assert_eq!(Locale::from_code("ar-EG"), Some(Locale::Ar));
+        assert_eq!(Locale::from_code("EN_us"), Some(Locale::En));
+        assert_eq!(Locale::from_code("fr"), None);
+        assert!(Locale::Ar.is_rtl());
+        assert_eq!(Locale::En.dir(), "ltr");
+    }
+
+    #[test]
+    fn test_accept_language_picks_highest_quality_supported_locale() {
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, ar;q=0.8, en;q=0.5"),
+            Some(Locale::Ar)
+        );
+        assert_eq!(
+            Locale::from_accept_language("ar;q=0.3, en-GB;q=0.9"),
+            Some(Locale::En)
+        );
+        assert_eq!(Locale::from_accept_language("ar;q=0"), None);
+        assert_eq!(Locale::from_accept_language("de, fr"), None);
+    }
+
+    #[test]
+    fn test_translation_falls_back_to_english_then_key() {
+        assert_eq!(
+            t_args(
+                Locale::En,
+                "notification.title.app_stopped",
+                &[("app", "web")]
+            ),
+            "App Stopped: web"
+        );
+        assert_eq!(t(Locale::Ar, "no.such.key"), "no.such.key");
+        assert_eq!(
+            t_count(Locale::En, "email.digest.subject", 1, &[]),
+            "Rivetr digest: 1 event"
+        );
+        assert_eq!(
+            t_count(Locale::En, "email.digest.subject", 3, &[]),
+            "Rivetr digest: 3 events"
+        );
+    }
+
+    #[test]
+    fn test_localize_text_translates_catalog_messages_only() {
+        assert_eq!(
+            localize_text(Locale::Ar, "App not found"),
+            t(Locale::Ar, "error.app_not_found")
+        );
+        assert_eq!(
+            localize_text(Locale::Ar, "Something else"),
+            "Something else"
+        );
+        assert_eq!(localize_text(Locale::En, "App not found"), "App not found");
+    }
+}
@@ -1,12 +1,14 @@
 pub mod ai;
 pub mod api;
 pub mod backup;
+pub mod chaos;
 pub mod cli;
 pub mod config;
 pub mod crypto;
 pub mod db;
 pub mod engine;
 pub mod github;
+pub mod i18n;
 pub mod logging;
 pub mod mcp;
 pub mod monitoring;
@@ -14,6 +16,9 @@ pub mod notifications;
 pub mod proxy;
 pub mod runtime;
 pub mod startup;
+pub mod storage;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 #[cfg(feature = "tui")]
 pub mod tui;
 pub mod ui;
@@ -27,6 +32,7 @@ use metrics_exporter_prometheus::PrometheusHandle;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+use crate::api::probes::ReadinessState;
 use crate::api::rate_limit::RateLimiter;
 use crate::api::start_logs::StartLogRegistry;
 use crate::db::App;
@@ -44,7 +50,8 @@ pub struct AppState {
     pub metrics_handle: Option<PrometheusHandle>,
     pub update_checker: Arc<UpdateChecker>,
     /// Cancellation tokens for in-progress deployments. Keyed by deployment ID.
-    pub deployment_cancel_tokens: dashmap::DashMap<String, tokio_util::sync::CancellationToken>,
+    /// Shared with the deployment engine, which registers them.
+    pub deployment_cancel_tokens: crate::engine::CancelTokens,
     /// Optional AI client — configured from instance settings (dashboard) or [ai] in rivetr.toml.
     /// Wrapped in RwLock so it can be hot-swapped when the API key changes at runtime.
     pub ai_client: parking_lot::RwLock<Option<Arc<crate::ai::AiClient>>>,
@@ -59,6 +66,23 @@ pub struct AppState {
     /// The current concurrent-deployment limit (mirrors the semaphore's permit
     /// count) so the API can compute add/remove deltas and report the value.
     pub deploy_concurrency: Arc<std::sync::atomic::AtomicUsize>,
+    /// Readiness flags reported by `/readyz` (proxy bound, startup checks passed).
+    pub readiness: Arc<ReadinessState>,
+    /// Deployments waiting for and holding build slots. Shared with the
+    /// deployment engine, which updates them.
+    pub engine_counters: crate::engine::load::SharedEngineCounters,
+    /// Fault injection switchboard, present only when `[chaos] enabled` is set.
+    /// Shared with the proxy and the container runtime wrapper.
+    pub fault_injector: Option<Arc<crate::chaos::FaultInjector>>,
+    /// Where instance backups and diagnostics bundles are kept (`[storage]`).
+    /// Shared with the deployment engine and the backup scheduler.
+    pub storage: Arc<dyn crate::storage::Storage>,
+    /// Host maintenance cordon. Shared with the deployment engine, which
+    /// cancels deployments while the host is cordoned.
+    pub host_cordon: crate::engine::host_maintenance::HostCordon,
+    /// Latest host hygiene report (pending security updates, container engine
+    /// support). Shared with the background checker, which refreshes it.
+    pub host_hygiene: crate::engine::host_hygiene::HostHygiene,
 }
 
 impl AppState {
@@ -71,6 +95,10 @@ impl AppState {
         update_checker: Arc<UpdateChecker>,
     ) -> Self {
         let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
+        // Placeholder; overridden at startup via with_storage with the configured backend
+        let storage = Arc::new(crate::storage::LocalStorage::new(
+            config.server.data_dir.clone(),
+        ));
         Self {
             config,
             db,
@@ -80,16 +108,29 @@ impl AppState {
             rate_limiter,
             metrics_handle: None,
             update_checker,
-            deployment_cancel_tokens: dashmap::DashMap::new(),
+            deployment_cancel_tokens: crate::engine::CancelTokens::default(),
             ai_client: parking_lot::RwLock::new(None),
             start_log_streams: Arc::new(StartLogRegistry::new()),
             // Placeholder; overridden at startup via with_deploy_concurrency so it
             // shares the same semaphore instance as the deployment engine.
             deploy_semaphore: Arc::new(tokio::sync::Semaphore::new(2)),
             deploy_concurrency: Arc::new(std::sync::atomic::AtomicUsize::new(2)),
+            readiness: Arc::new(ReadinessState::new()),
+            engine_counters: crate::engine::load::SharedEngineCounters::default(),
+            fault_injector: None,
+            storage,
+            host_cordon: Default::default(),
+            host_hygiene: Default::default(),
         }
     }
 
+    /// Share `limiter` with the proxy so sign-in attempts there count
+    /// against the same limits as the API.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
     /// Share the deployment-concurrency semaphore (and its current-limit mirror)
     /// with the engine so the instance-settings API can live-adjust it.
     pub fn with_deploy_concurrency(
@@ -102,15 +143,86 @@ impl AppState {
         self
     }
 
+    /// Share the deployment engine's cancellation tokens so cancelling a
+    /// deployment through the API aborts its pipeline.
+    pub fn with_cancel_tokens(mut self, tokens: crate::engine::CancelTokens) -> Self {
+        self.deployment_cancel_tokens = tokens;
+        self
+    }
+
+    /// Share the deployment engine's build counters so the engine status
+    /// endpoint and metrics can report its load.
+    pub fn with_engine_counters(
+        mut self,
+        counters: crate::engine::load::SharedEngineCounters,
+    ) -> Self {
+        self.engine_counters = counters;
+        self
+    }
+
+    /// Share the readiness flags that startup code flips as subsystems come online.
+    pub fn with_readiness(mut self, readiness: Arc<ReadinessState>) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
     /// Set the initial AI client (called once at startup).
     pub fn with_ai_client(self, client: Option<Arc<crate::ai::AiClient>>) -> Self {
         *self.ai_client.write() = client;
         self
     }
 
+    /// Share the fault injector that the proxy and runtime wrapper draw from.
+    pub fn with_fault_injector(mut self, faults: Option<Arc<crate::chaos::FaultInjector>>) -> Self {
+        self.fault_injector = faults;
+        self
+    }
+
+    /// Share the storage backend built from `[storage]`.
+    pub fn with_storage(mut self, storage: Arc<dyn crate::storage::Storage>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Share the host cordon loaded at startup with the deployment engine.
+    pub fn with_host_cordon(mut self, cordon: crate::engine::host_maintenance::HostCordon) -> Self {
+        self.host_cordon = cordon;
+        self
+    }
+
+    /// Share the host hygiene report loaded at startup with its checker.
+    pub fn with_host_hygiene(mut self, hygiene: crate::engine::host_hygiene::HostHygiene) -> Self {
+        self.host_hygiene = hygiene;
+        self
+    }
+
     /// Set the Prometheus metrics handle
     pub fn with_metrics(mut self, handle: PrometheusHandle) -> Self {
         self.metrics_handle = Some(handle);
         self
     }
 }
+
+/// State over a fresh, migrated database and no container runtime, for unit
+/// tests. Deployments handed to the engine arrive on the returned receiver;
+/// the directory must outlive the state.
+#[cfg(test)]
+pub(crate) async fn test_state() -> (
+    tempfile::TempDir,
+    Arc<AppState>,
+    mpsc::Receiver<(String, App)>,
+) {
+    let (dir, db) = db::test_pool().await;
+    let mut config = Config::default();
+    config.server.data_dir = dir.path().to_path_buf();
+    let (deploy_tx, deploy_rx) = mpsc::channel(16);
+    let state = AppState::new(
+        config.clone(),
+        db,
+        deploy_tx,
+        Arc::new(runtime::NoopRuntime),
+        Arc::new(ArcSwap::from_pointee(RouteTable::new())),
+        Arc::new(UpdateChecker::new(config.auto_update)),
+    );
+    (dir, Arc::new(state), deploy_rx)
+}
@@ -7,11 +7,21 @@ use tokio::sync::mpsc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use rivetr::api::rate_limit::spawn_cleanup_task as spawn_rate_limit_cleanup_task;
+use rivetr::api::rate_limit::RateLimiter;
 use rivetr::cli::{self, Cli};
 use rivetr::config::Config;
+use rivetr::db::tls_passthrough_domains;
+use rivetr::db::AppAccessRule;
+use rivetr::db::AppBasicAuthPath;
+use rivetr::db::AppBotSettings;
+use rivetr::db::AppCorsSettings;
+use rivetr::db::AppForwardAuth;
 use rivetr::db::AppRedirectRule;
+use rivetr::db::AppWafSettings;
+use rivetr::db::DeploymentEvent;
 use rivetr::db::InstanceSettings;
 use rivetr::db::Service;
+use rivetr::engine::service_routes::register_service_route;
 use rivetr::engine::{
     reconcile_container_status, spawn_cleanup_task as spawn_deployment_cleanup_task,
     spawn_container_monitor_task, spawn_cost_calculator_task, spawn_disk_monitor_task,
@@ -20,10 +30,12 @@ use rivetr::engine::{
 };
 use rivetr::proxy::{
     AcmeClient, AcmeConfig, Backend, BasicAuthConfig, CertificateRenewalManager, HealthChecker,
-    HealthCheckerConfig, HttpsProxyServer, ProxyServer, RedirectRule, RouteTable,
+    HealthCheckerConfig, HealthProbe, HttpsProxyServer, ProxyServer, RedirectRule, RouteTable,
+    UpstreamPoolConfig,
 };
-use rivetr::runtime::{detect_runtime, ContainerRuntime};
+use rivetr::runtime::{detect_runtime, ChaosRuntime, ContainerRuntime};
 use rivetr::startup::run_startup_checks;
+use rivetr::utils::handover::{self, ListenerRole};
 use rivetr::AppState;
 use rivetr::DbPool;
 
@@ -63,7 +75,7 @@ async fn main() -> Result<()> {
 
     // Initialize Prometheus metrics
     let metrics_handle = rivetr::api::metrics::init_metrics();
-    tracing::info!("Prometheus metrics initialized at /metrics");
+    tracing::info!("Prometheus metrics initialized");
 
     // Ensure data directory exists
     rivetr::utils::ensure_dir(&config.server.data_dir)?;
@@ -86,9 +98,13 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Readiness flags reported by /readyz
+    let readiness = Arc::new(rivetr::api::probes::ReadinessState::new());
+
     // Run startup self-checks
     if cli.skip_checks {
         tracing::warn!("Startup self-checks skipped (--skip-checks flag)");
+        readiness.set_startup_checks_passed(true);
     } else {
         let check_report = run_startup_checks(&config, &db).await;
 
@@ -106,6 +122,7 @@ async fn main() -> Result<()> {
             }
             std::process::exit(1);
         }
+        readiness.set_startup_checks_passed(true);
 
         if !check_report.all_passed {
             tracing::warn!(
@@ -117,21 +134,108 @@ async fn main() -> Result<()> {
     // Detect container runtime
     let runtime = detect_runtime(&config.runtime).await?;
 
+    // Chaos mode: route runtime calls (and, below, proxied requests) through the
+    // fault injector so admins can switch failures on at runtime
+    let fault_injector = config
+        .chaos
+        .enabled
+        .then(|| Arc::new(rivetr::chaos::FaultInjector::new()));
+    let runtime: Arc<dyn ContainerRuntime> = match fault_injector {
+        Some(ref faults) => {
+            tracing::warn!(
+                "Chaos mode is enabled: faults can be injected through /api/system/chaos. \
+                 Do not use this on production instances."
+            );
+            Arc::new(ChaosRuntime::new(runtime, faults.clone()))
+        }
+        None => runtime,
+    };
+
     // Create deployment channel
     let (deploy_tx, deploy_rx) = mpsc::channel(100);
 
+    // Scale-to-zero: the proxy wakes sleeping apps on request
+    let app_waker = Arc::new(rivetr::engine::scale_to_zero::AppWaker::new(
+        db.clone(),
+        runtime.clone(),
+    ));
+
     // Start proxy server
-    let proxy_addr: SocketAddr = format!("{}:{}", config.server.host, config.server.proxy_port)
-        .parse()
-        .expect("Invalid proxy address");
-    let proxy_server = ProxyServer::new(proxy_addr).with_db(db.clone());
+    let proxy_addr = config.server.bind_addr(config.server.proxy_port)?;
+    let encryption_key = config
+        .auth
+        .encryption_key
+        .as_deref()
+        .map(rivetr::crypto::derive_key);
+    // GeoIP country database for access rules; a bad path only disables country rules
+    let geoip = config.proxy.geoip_db_path.as_deref().and_then(|path| {
+        match rivetr::proxy::GeoIp::open(path) {
+            Ok(db) => {
+                tracing::info!(
+                    path = %path.display(),
+                    database = %db.database_type(),
+                    "Loaded GeoIP database"
+                );
+                Some(Arc::new(db))
+            }
+            Err(e) => {
+                tracing::warn!("{}; country access rules are disabled", e);
+                None
+            }
+        }
+    });
+    // Shared by the API and the proxy's forward auth sign-in form
+    let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
+    let proxy_server = ProxyServer::new(proxy_addr)
+        .with_db(db.clone())
+        .with_encryption_key(encryption_key)
+        .with_geoip(geoip.clone())
+        .with_fault_injector(fault_injector.clone())
+        .with_app_waker(Some(app_waker.clone()))
+        .with_login_limiter(Some(rate_limiter.clone()))
+        .with_bound_flag(readiness.proxy_bound.clone())
+        .with_upstream_pool(UpstreamPoolConfig {
+            max_idle_per_backend: config.proxy.upstream_max_idle_per_backend,
+            idle_timeout: std::time::Duration::from_secs(config.proxy.upstream_idle_timeout_secs),
+        });
     let routes = proxy_server.routes();
+    let proxy_service = proxy_server.proxy_service();
+    routes
+        .load()
+        .set_drain_timeout(std::time::Duration::from_secs(
+            config.proxy.drain_timeout_secs,
+        ));
+    routes
+        .load()
+        .set_failover_keep(std::time::Duration::from_secs(
+            config.proxy.failover_keep_secs,
+        ));
 
     // Restore routes from running containers
-    if let Err(e) = restore_routes(&db, &runtime, &routes).await {
+    if let Err(e) = restore_routes(&db, &runtime, &routes, &config.server.data_dir).await {
         tracing::warn!("Failed to restore routes: {}", e);
     }
 
+    // A host cordoned for maintenance stays cordoned across restarts, with
+    // the maintenance pages of drained apps in place
+    let host_cordon = rivetr::engine::host_maintenance::HostCordon::load(&db)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load host cordon state: {}", e);
+            Default::default()
+        });
+    if host_cordon.is_cordoned() {
+        tracing::warn!("Host is cordoned for maintenance; deployments are paused until uncordoned");
+        host_cordon.restore_parked_routes(&db, &routes).await;
+    }
+
+    let host_hygiene = rivetr::engine::host_hygiene::HostHygiene::load(&db)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load host hygiene report: {}", e);
+            Default::default()
+        });
+
     // Register instance domain → API server so users can access the dashboard via a custom domain
     if let Some(ref instance_domain) = config.proxy.instance_domain {
         let backend = Backend::new(
@@ -152,14 +256,22 @@ async fn main() -> Result<()> {
     // deployment record was left in "building"/"cloning"/etc. state. Fail them now.
     let stuck_statuses = ["pending", "cloning", "building", "starting", "checking"];
     for status in &stuck_statuses {
-        let _ = sqlx::query(
+        let failed: Vec<String> = sqlx::query_scalar(
             "UPDATE deployments SET status = 'failed', \
              error_message = 'Server restarted during deployment', \
              finished_at = datetime('now') \
-             WHERE status = ?",
+             WHERE status = ? RETURNING id",
         )
         .bind(*status)
-        .execute(&db)
+        .fetch_all(&db)
+        .await
+        .unwrap_or_default();
+        DeploymentEvent::record_all(
+            &db,
+            &failed,
+            "failed",
+            Some("Server restarted during deployment"),
+        )
         .await;
     }
     tracing::info!("Cleaned up any stuck in-progress deployments from previous server run");
@@ -247,6 +359,13 @@ async fn main() -> Result<()> {
     let deploy_semaphore = Arc::new(tokio::sync::Semaphore::new(deploy_limit));
     let deploy_concurrency = Arc::new(std::sync::atomic::AtomicUsize::new(deploy_limit));
 
+    // Storage for instance backups and diagnostics bundles ([storage])
+    let storage = rivetr::storage::from_config(&config.storage, &config.server.data_dir)?;
+    tracing::info!("Storage backend: {}", storage.name());
+
+    let cancel_tokens = rivetr::engine::CancelTokens::default();
+    let engine_counters = rivetr::engine::load::SharedEngineCounters::default();
+
     // Create app state (now includes routes for rollback functionality)
     let state = Arc::new(
         AppState::new(
@@ -258,8 +377,16 @@ async fn main() -> Result<()> {
             update_checker,
         )
         .with_metrics(metrics_handle)
+        .with_readiness(readiness.clone())
+        .with_rate_limiter(rate_limiter.clone())
         .with_ai_client(ai_client)
-        .with_deploy_concurrency(deploy_semaphore.clone(), deploy_concurrency.clone()),
+        .with_deploy_concurrency(deploy_semaphore.clone(), deploy_concurrency.clone())
+        .with_cancel_tokens(cancel_tokens.clone())
+        .with_engine_counters(engine_counters.clone())
+        .with_fault_injector(fault_injector.clone())
+        .with_storage(storage.clone())
+        .with_host_cordon(host_cordon.clone())
+        .with_host_hygiene(host_hygiene.clone()),
     );
 
     // Start rate limiter cleanup task
@@ -275,12 +402,26 @@ async fn main() -> Result<()> {
     );
 
     // Start deployment engine with route table and build limits
-    let build_limits = BuildLimits::from_runtime_config(&config.runtime);
+    let build_limits = BuildLimits::from_runtime_config(&config.runtime)
+        .with_sandbox(config.build_sandbox.clone())
+        .with_build_cache(config.build_cache.clone(), &config.server.data_dir)
+        .with_registry(config.registry.clone())
+        .with_scan(config.scan.clone());
     tracing::info!(
         "Build resource limits: cpu={}, memory={}",
         config.runtime.build_cpu_limit,
         config.runtime.build_memory_limit
     );
+    if let Some(ref build_host) = build_limits.build_host {
+        if runtime.name() == "Docker" {
+            tracing::info!("Image builds run on Docker host {}", build_host);
+        } else {
+            tracing::warn!(
+                "runtime.build_host is only supported with Docker; building on the {} host",
+                runtime.name()
+            );
+        }
+    }
     tracing::info!("Max concurrent deployments: {}", deploy_limit);
     let engine = DeploymentEngine::new(
         db.clone(),
@@ -290,7 +431,14 @@ async fn main() -> Result<()> {
         build_limits,
         &config.auth,
         deploy_semaphore.clone(),
-    );
+    )
+    .with_keep_previous(std::time::Duration::from_secs(
+        config.runtime.keep_previous_minutes * 60,
+    ))
+    .with_cancel_tokens(cancel_tokens)
+    .with_counters(engine_counters)
+    .with_storage(storage.clone())
+    .with_cordon(host_cordon);
     tokio::spawn(async move {
         // Each deployment already runs in its own isolated child task inside
         // run(); guard the consumer loop itself so a panic in dispatch logs
@@ -322,6 +470,9 @@ async fn main() -> Result<()> {
         config.server.data_dir.clone(),
     );
 
+    // Keep per-app egress firewall rules in line with app policies
+    rivetr::engine::egress::spawn_egress_enforcer(db.clone(), runtime.clone());
+
     // Start database backup scheduler
     rivetr::engine::spawn_database_backup_task(
         db.clone(),
@@ -348,6 +499,13 @@ async fn main() -> Result<()> {
     // Start scheduled jobs scheduler (cron-based commands in containers)
     rivetr::engine::scheduler::spawn_scheduler(db.clone(), runtime.clone());
 
+    // Start scheduled task runner (cron commands in one-off containers from the app's image)
+    rivetr::engine::scheduled_tasks::spawn_task_scheduler(
+        db.clone(),
+        runtime.clone(),
+        encryption_key,
+    );
+
     // Start scheduled deployment checker (queues deployments whose scheduled_at has passed)
     rivetr::engine::scheduler::spawn_scheduled_deployment_checker(
         db.clone(),
@@ -355,15 +513,96 @@ async fn main() -> Result<()> {
     );
 
     // Start backup scheduler (runs backup_schedules entries on their cron expressions)
-    rivetr::engine::scheduler::spawn_backup_scheduler(db.clone());
+    rivetr::engine::scheduler::spawn_backup_scheduler(db.clone(), storage.clone());
+
+    // Start artifact garbage collector (removes blobs no deployment refers to)
+    rivetr::storage::artifacts::spawn_artifact_gc(db.clone(), storage.clone());
 
     // Start autoscaling checker (evaluates autoscaling rules every 60s)
-    rivetr::engine::scheduler::spawn_autoscaling_checker(db.clone());
+    rivetr::engine::scheduler::spawn_autoscaling_checker(
+        db.clone(),
+        runtime.clone(),
+        routes.clone(),
+    );
+
+    // Start the app power scheduler (scheduled stop/start of office-hours apps)
+    rivetr::engine::power_schedule::spawn_power_scheduler(
+        db.clone(),
+        runtime.clone(),
+        routes.clone(),
+    );
+
+    // Put apps with an idle timeout to sleep once they stop receiving requests
+    rivetr::engine::scale_to_zero::spawn_idle_checker(app_waker.clone(), routes.clone());
+
+    // Start the preview expiry checker (tears down previews past their lifetime or idle timeout)
+    rivetr::engine::preview_expiry::spawn_preview_expiry_checker(
+        db.clone(),
+        runtime.clone(),
+        routes.clone(),
+    );
+
+    // Check the host for pending security updates and an end-of-life container engine
+    rivetr::engine::host_hygiene::spawn_host_hygiene_checker(
+        host_hygiene,
+        db.clone(),
+        runtime.clone(),
+        config.email.clone(),
+    );
+
+    // Record per-app bandwidth and enforce monthly bandwidth caps
+    rivetr::engine::bandwidth::spawn_bandwidth_accountant(db.clone(), runtime.clone());
+
+    // Keep the Prometheus scrape targets of apps with a metrics port current
+    rivetr::engine::prometheus_sd::spawn_prometheus_sd_task(
+        db.clone(),
+        config.server.data_dir.clone(),
+    );
+
+    // Start the pre-warm scheduler (off-peak image builds into deployment slots)
+    rivetr::engine::prewarm::spawn_prewarm_scheduler(db.clone(), state.deploy_tx.clone());
 
     // Start advanced monitoring tasks (uptime checker + log cleaner)
     rivetr::monitoring::spawn_uptime_checker_task(db.clone());
     rivetr::monitoring::spawn_log_cleaner_task(db.clone());
 
+    // Start the build cache pruner (drops caches of deleted apps and stale ones)
+    rivetr::engine::build_cache::spawn_build_cache_pruner(
+        db.clone(),
+        config.server.data_dir.clone(),
+        config.build_cache.clone(),
+    );
+
+    // Start the notification outbox dispatcher (delivers queued notifications with retries)
+    rivetr::notifications::spawn_outbox_dispatcher(db.clone());
+
+    // Start the email dispatcher (sends queued email within the rate limit, with retries)
+    rivetr::notifications::spawn_email_dispatcher(db.clone(), config.email.clone());
+
+    // Start the notification digest sender (daily per-user digests of held events)
+    rivetr::notifications::spawn_digest_sender(db.clone(), config.email.clone());
+
+    // Start the notification digest report sender (scheduled daily/weekly summaries)
+    rivetr::notifications::spawn_summary_sender(
+        db.clone(),
+        config.email.clone(),
+        config
+            .proxy
+            .acme_enabled
+            .then(|| config.proxy.acme_cache_dir.clone()),
+    );
+
+    // Serve /metrics on a dedicated (typically localhost-only) listener if configured
+    if let Some(metrics_bind) = config.observability.metrics_bind() {
+        let metrics_state = state.clone();
+        let metrics_bind = metrics_bind.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = rivetr::api::metrics::serve_metrics(metrics_state, metrics_bind).await {
+                tracing::error!(error = %e, "Metrics server error");
+            }
+        });
+    }
+
     // Create API router
     let api_router = rivetr::api::create_router(state.clone());
 
@@ -372,26 +611,24 @@ async fn main() -> Result<()> {
     // Start HTTP proxy server and optionally HTTPS with ACME
     let https_port = config.server.proxy_https_port;
 
-    // Pre-bind proxy listeners using systemd socket activation (fd 0 = HTTP, fd 1 = HTTPS).
-    // We MUST call ListenFd::from_env() exactly once — the crate clears LISTEN_FDS after the
-    // first call, so any subsequent from_env() would see zero fds and fall back to a fresh bind
+    // Pre-bind listeners from systemd socket activation (fd 0 = HTTP, fd 1 = HTTPS) or
+    // from a previous Rivetr process handing its sockets over during an upgrade.
+    // We MUST read LISTEN_FDS exactly once — the listenfd crate clears it after the
+    // first call, so any later read would see zero fds and fall back to a fresh bind
     // that fails with "Address already in use" when the socket unit still holds the port.
-    let (http_proxy_listener, https_proxy_listener) = {
-        let mut listenfd = listenfd::ListenFd::from_env();
-        let http_l = if let Ok(Some(std_l)) = listenfd.take_tcp_listener(0) {
-            std_l.set_nonblocking(true).ok();
-            tokio::net::TcpListener::from_std(std_l).ok()
-        } else {
-            None
-        };
-        let https_l = if let Ok(Some(std_l)) = listenfd.take_tcp_listener(1) {
-            std_l.set_nonblocking(true).ok();
-            tokio::net::TcpListener::from_std(std_l).ok()
-        } else {
-            None
-        };
-        (http_l, https_l)
+    let mut inherited_listeners = handover::inherited_listeners();
+    let http_proxy_listener = inherited_listeners.remove(&ListenerRole::Http);
+    let https_proxy_listener = inherited_listeners.remove(&ListenerRole::Https);
+
+    // Dual-stack: IPv6-only companions of the proxy listeners (`server.ipv6_host`)
+    let proxy_server = match config.server.ipv6_bind_addr(config.server.proxy_port)? {
+        Some(addr) => {
+            proxy_server.with_ipv6(addr, inherited_listeners.remove(&ListenerRole::Http6))
+        }
+        None => proxy_server,
     };
+    let https_ipv6_addr = config.server.ipv6_bind_addr(https_port)?;
+    let https_ipv6_listener = inherited_listeners.remove(&ListenerRole::Https6);
 
     let acme_enabled = config.proxy.acme_enabled
         && config.proxy.acme_email.is_some()
@@ -400,11 +637,7 @@ async fn main() -> Result<()> {
     if acme_enabled {
         let acme_email = config.proxy.acme_email.clone().unwrap();
         let instance_domain = config.proxy.instance_domain.clone().unwrap();
-        let acme_cfg = AcmeConfig {
-            email: acme_email,
-            cache_dir: config.proxy.acme_cache_dir.clone(),
-            staging: config.proxy.acme_staging,
-        };
+        let acme_cfg = AcmeConfig::from_proxy_config(&config.proxy, acme_email);
 
         match AcmeClient::new(acme_cfg).await {
             Ok(acme_client) => {
@@ -466,7 +699,11 @@ async fn main() -> Result<()> {
                     }
                     AcmeClient::load_certificate(&cert_dir).await.ok()
                 } else {
-                    tracing::info!(domain = %instance_domain, "Requesting Let's Encrypt certificate");
+                    tracing::info!(
+                        domain = %instance_domain,
+                        directory = %acme_client.directory_url(),
+                        "Requesting ACME certificate"
+                    );
                     match acme_client.request_certificate(&all_cert_domains).await {
                         Ok(result) => {
                             let _ = acme_client
@@ -488,18 +725,25 @@ async fn main() -> Result<()> {
                 if let Some(tls_config) = tls_config_result {
                     // TLS cert is available — enable HTTP→HTTPS redirect now
                     https_redirect_flag.store(true, std::sync::atomic::Ordering::Relaxed);
-                    let https_addr: SocketAddr = format!("{}:{}", config.server.host, https_port)
-                        .parse()
-                        .expect("Invalid HTTPS proxy address");
+                    let https_addr = config.server.bind_addr(https_port)?;
 
                     // Wrap the TLS acceptor in a hot-reload handle so cert renewals take
                     // effect immediately without restarting the HTTPS server.
                     let tls_reload = std::sync::Arc::new(rivetr::proxy::TlsReloadHandle::new(
                         tls_config.acceptor,
                     ));
-                    let https_server =
+                    let mut https_server =
                         HttpsProxyServer::new(https_addr, routes.clone(), tls_reload.clone())
-                            .with_db(db.clone());
+                            .with_db(db.clone())
+                            .with_encryption_key(encryption_key)
+                            .with_geoip(geoip.clone())
+                            .with_fault_injector(fault_injector.clone())
+                            .with_app_waker(Some(app_waker.clone()))
+                            .with_login_limiter(Some(rate_limiter.clone()))
+                            .with_proxy_service(proxy_service.clone());
+                    if let Some(addr) = https_ipv6_addr {
+                        https_server = https_server.with_ipv6(addr, https_ipv6_listener);
+                    }
                     tokio::spawn(async move {
                         if let Err(e) = https_server.run(https_proxy_listener).await {
                             tracing::error!(error = %e, "HTTPS proxy server error");
@@ -554,19 +798,50 @@ async fn main() -> Result<()> {
 
     // Start health checker for backend health monitoring
     let health_config = HealthCheckerConfig::from_proxy_config(&config.proxy);
-    let health_checker = HealthChecker::new(routes.clone(), health_config);
+    let health_checker = HealthChecker::new(routes.clone(), health_config)
+        .with_runtime(runtime.clone())
+        .with_db(db.clone());
     tokio::spawn(async move {
         health_checker.run().await;
     });
 
     // Start API server
-    let api_addr = format!("{}:{}", config.server.host, config.server.api_port);
-    let listener = tokio::net::TcpListener::bind(&api_addr).await?;
+    let api_addr = config.server.bind_addr(config.server.api_port)?;
+    let listener = match inherited_listeners.remove(&ListenerRole::Api) {
+        Some(listener) => listener,
+        None => tokio::net::TcpListener::bind(api_addr).await?,
+    };
+    handover::register(ListenerRole::Api, &listener);
+
+    // Serve the API on the IPv6-only companion socket too, until shutdown
+    if let Some(api_ipv6_addr) = config.server.ipv6_bind_addr(config.server.api_port)? {
+        let listener_v6 = match inherited_listeners.remove(&ListenerRole::Api6) {
+            Some(listener) => listener,
+            None => rivetr::utils::net::bind_ipv6_only(api_ipv6_addr)?,
+        };
+        handover::register(ListenerRole::Api6, &listener_v6);
+        tracing::info!("API server listening on http://{}", api_ipv6_addr);
+        let app_v6 = app.clone();
+        tokio::spawn(async move {
+            let served = axum::serve(
+                listener_v6,
+                app_v6.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(handover::shutdown_requested())
+            .await;
+            if let Err(e) = served {
+                tracing::error!(error = %e, "IPv6 API server error");
+            }
+        });
+    }
 
     tracing::info!("API server listening on http://{}", api_addr);
     tracing::info!("Proxy server listening on http://{}", proxy_addr);
     tracing::info!("Admin token: {}", config.auth.admin_token);
 
+    // Listeners are up: tell systemd, or the process handing over to us
+    handover::notify_ready();
+
     // `into_make_service_with_connect_info` exposes the peer SocketAddr to handlers
     // via `ConnectInfo<SocketAddr>` extractors.  We use this in the audit-log helper
     // to capture the request IP when it isn't fronted by a reverse proxy that sets
@@ -578,6 +853,12 @@ async fn main() -> Result<()> {
     .with_graceful_shutdown(shutdown_signal())
     .await?;
 
+    // Let proxied requests that are still in flight finish
+    handover::wait_for_connections(std::time::Duration::from_secs(
+        config.proxy.drain_timeout_secs,
+    ))
+    .await;
+
     tracing::info!("Server stopped");
     Ok(())
 }
@@ -603,11 +884,15 @@ async fn shutdown_signal() {
     tokio::select! {
         _ = ctrl_c => {},
         _ = terminate => {},
+        _ = handover::shutdown_requested() => {},
     }
 
     tracing::info!("Shutdown signal received");
+    // Stop the proxy listeners accepting as well
+    handover::request_shutdown();
 }
-
+/// Collect all configured domain names across all apps and TLS-enabled
+/// services (for TLS SAN list)
 /// Collect all configured domain names across all apps (for TLS SAN list)
 async fn collect_all_app_domains(db: &DbPool) -> Result<Vec<String>> {
     let apps: Vec<(Option<String>, Option<String>, Option<String>)> =
@@ -626,6 +911,14 @@ async fn collect_all_app_domains(db: &DbPool) -> Result<Vec<String>> {
             if let Ok(arr) = serde_json::from_str::<serde_json::Value>(json) {
                 if let Some(list) = arr.as_array() {
                     for entry in list {
+                        // Passthrough domains terminate TLS in the app itself
+                        let passthrough = entry
+                            .get("tls_passthrough")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false);
+                        if passthrough {
+                            continue;
+                        }
                         if let Some(d) = entry.get("domain").and_then(|v| v.as_str()) {
                             let d = d.to_string();
                             if !d.is_empty() && !result.contains(&d) {
@@ -646,6 +939,18 @@ async fn collect_all_app_domains(db: &DbPool) -> Result<Vec<String>> {
             }
         }
     }
+
+    // Docker Compose services with TLS enabled for their domain
+    let services: Vec<(String,)> = sqlx::query_as(
+        "SELECT domain FROM services WHERE route_tls = 1 AND domain IS NOT NULL AND domain != ''",
+    )
+    .fetch_all(db)
+    .await?;
+    for (d,) in services {
+        if !result.contains(&d) {
+            result.push(d);
+        }
+    }
     Ok(result)
 }
 
@@ -654,6 +959,7 @@ async fn restore_routes(
     db: &DbPool,
     runtime: &Arc<dyn ContainerRuntime>,
     routes: &Arc<ArcSwap<RouteTable>>,
+    data_dir: &std::path::Path,
 ) -> Result<()> {
     // Fetch all apps that have any domain configured (domain, domains JSON, or auto_subdomain),
     // including basic auth fields so they can be re-applied to the restored routes.
@@ -669,14 +975,17 @@ async fn restore_routes(
         Option<String>,
         Option<String>,
         Option<String>, // strip_prefix
+        String,         // healthcheck_type
+        Option<String>, // healthcheck_command
     )> = sqlx::query_as(
         "SELECT id, name, domain, domains, healthcheck, auto_subdomain, \
                 basic_auth_enabled, basic_auth_username, basic_auth_password_hash, \
-                strip_prefix \
+                strip_prefix, healthcheck_type, healthcheck_command \
          FROM apps \
-         WHERE (domain IS NOT NULL AND domain != '') \
+         WHERE app_type != 'worker' \
+           AND ((domain IS NOT NULL AND domain != '') \
             OR (domains IS NOT NULL AND domains != '' AND domains != '[]') \
-            OR (auto_subdomain IS NOT NULL AND auto_subdomain != '')",
+            OR (auto_subdomain IS NOT NULL AND auto_subdomain != ''))",
     )
     .fetch_all(db)
     .await?;
@@ -702,6 +1011,8 @@ async fn restore_routes(
         basic_auth_username,
         basic_auth_password_hash,
         strip_prefix,
+        healthcheck_type,
+        healthcheck_command,
     ) in apps
     {
         let container_name = format!("rivetr-{}", app_name);
@@ -797,12 +1108,35 @@ async fn restore_routes(
                     })
                     .collect();
 
+                let proxy_access_rules = AppAccessRule::list_enabled_for_proxy(db, &app_id)
+                    .await
+                    .unwrap_or_default();
+                let basic_auth_paths = AppBasicAuthPath::list_enabled_for_proxy(db, &app_id)
+                    .await
+                    .unwrap_or_default();
+                let waf_policy = AppWafSettings::load_policy(db, &app_id)
+                    .await
+                    .unwrap_or_default();
+                let bot_policy = AppBotSettings::load_policy(db, &app_id, false)
+                    .await
+                    .unwrap_or_default();
+                let forward_auth = AppForwardAuth::load_policy(db, &app_id)
+                    .await
+                    .unwrap_or_default();
+                let cors = AppCorsSettings::load_policy(db, &app_id)
+                    .await
+                    .unwrap_or_default();
+                let passthrough_domains = tls_passthrough_domains(domains_json.as_deref());
+                let health_probe =
+                    HealthProbe::from_settings(&healthcheck_type, healthcheck_command.as_deref());
+
                 let route_table = routes.load();
 
                 for domain in &domain_names {
                     let mut backend =
                         Backend::new(container.id.clone(), "127.0.0.1".to_string(), port)
                             .with_healthcheck(healthcheck.clone())
+                            .with_health_probe(health_probe.clone())
                             .with_strip_prefix(strip_prefix.clone());
 
                     // Restore HTTP Basic Auth configuration if it was enabled
@@ -822,6 +1156,17 @@ async fn restore_routes(
                         backend.set_redirect_rules(proxy_redirect_rules.clone());
                     }
 
+                    // Restore access rules
+                    if !proxy_access_rules.is_empty() {
+                        backend.set_access_rules(proxy_access_rules.clone());
+                    }
+                    backend.set_basic_auth_paths(basic_auth_paths.clone());
+                    backend.set_waf(waf_policy.clone());
+                    backend.set_bots(bot_policy.clone());
+                    backend.set_forward_auth(forward_auth.clone());
+                    backend.set_cors(cors.clone());
+                    backend.tls_passthrough = passthrough_domains.contains(domain);
+
                     route_table.add_route(domain.clone(), backend);
                     tracing::info!(
                         domain = %domain,
@@ -829,6 +1174,8 @@ async fn restore_routes(
                         container = %container_name,
                         basic_auth = basic_auth_enabled != 0,
                         redirect_rules = proxy_redirect_rules.len(),
+                        access_rules = proxy_access_rules.len(),
+                        waf = waf_policy.is_some(),
                         "Restored proxy route for app {}",
                         app_name
                     );
@@ -863,21 +1210,8 @@ async fn restore_routes(
     );
 
     let route_table = routes.load();
-    for service in services {
-        if let Some(ref domain) = service.domain {
-            let backend = Backend::new(
-                format!("rivetr-svc-{}", service.name),
-                "127.0.0.1".to_string(),
-                service.port as u16,
-            );
-            route_table.add_route(domain.clone(), backend);
-            tracing::info!(
-                domain = %domain,
-                port = service.port,
-                "Restored service proxy route for {}",
-                service.name
-            );
-        }
+    for mut service in services {
+        register_service_route(db, &route_table, data_dir, &mut service).await;
     }
 
     Ok(())
@@ -77,6 +77,10 @@ pub async fn mcp_handler(
                             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
                     match app {
+                        Some(a) if a.deploy_lock_message().is_some() => Ok(Json(McpResponse {
+                            result: None,
+                            error: a.deploy_lock_message(),
+                        })),
                         Some(a) => {
                             let deployment_id = uuid::Uuid::new_v4().to_string();
                             let now = chrono::Utc::now().to_rfc3339();
@@ -145,6 +149,10 @@ pub async fn mcp_handler(
                             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
                     match app {
+                        Some(a) if a.deploy_lock_message().is_some() => Ok(Json(McpResponse {
+                            result: None,
+                            error: a.deploy_lock_message(),
+                        })),
                         Some(a) => {
                             let deployment_id = uuid::Uuid::new_v4().to_string();
                             let now = chrono::Utc::now().to_rfc3339();
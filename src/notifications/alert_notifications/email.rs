@@ -1,22 +1,23 @@
 //! Email alert notification implementation.
 
 use anyhow::Result;
-use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
-    transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
-};
+use lettre::message::Mailbox;
 
 use crate::db::EmailConfig;
+use crate::notifications::mail_queue::{self, Email};
+use crate::DbPool;
 
 use super::AlertNotificationPayload;
 
-/// Send an alert email using the provided configuration
+/// Queue an alert email to each of the channel's recipients; the email queue
+/// sends it with the channel's SMTP settings. Returns the number queued.
 pub async fn send_alert_email(
+    db: &DbPool,
+    channel_id: &str,
     config: &EmailConfig,
     payload: &AlertNotificationPayload,
 ) -> Result<usize> {
-    let from: Mailbox = config.from_address.parse()?;
+    config.from_address.parse::<Mailbox>()?;
 
     // Build HTML content
     let dashboard_link = payload.dashboard_link();
@@ -142,72 +143,25 @@ pub async fn send_alert_email(
             .unwrap_or_default(),
     );
 
-    let mut sent_count = 0;
-
+    let mut emails = Vec::new();
     for to_address in &config.to_addresses {
-        let to: Mailbox = match to_address.parse() {
-            Ok(m) => m,
-            Err(e) => {
-                tracing::warn!(
-                    address = %to_address,
-                    error = %e,
-                    "Invalid email address, skipping"
-                );
-                continue;
-            }
-        };
-
-        let email = Message::builder()
-            .from(from.clone())
-            .to(to)
-            .subject(payload.title())
-            .multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.clone()),
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body.clone()),
-                    ),
-            )?;
-
-        // Build SMTP transport
-        let mailer = if config.smtp_tls {
-            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
-        } else {
-            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
-        }
-        .port(config.smtp_port);
-
-        let mailer = if let (Some(username), Some(password)) =
-            (&config.smtp_username, &config.smtp_password)
-        {
-            mailer.credentials(Credentials::new(username.clone(), password.clone()))
-        } else {
-            mailer
-        };
-
-        match mailer.build().send(email).await {
-            Ok(_) => {
-                sent_count += 1;
-                tracing::debug!(
-                    to = %to_address,
-                    "Alert email delivered successfully"
-                );
-            }
-            Err(e) => {
-                tracing::error!(
-                    to = %to_address,
-                    error = %e,
-                    "Failed to deliver alert email"
-                );
-            }
+        if let Err(e) = to_address.parse::<Mailbox>() {
+            tracing::warn!(
+                address = %to_address,
+                error = %e,
+                "Invalid email address, skipping"
+            );
+            continue;
         }
+        emails.push(Email {
+            from: config.from_address.clone(),
+            to: to_address.clone(),
+            subject: payload.title(),
+            html_body: html_body.clone(),
+            text_body: text_body.clone(),
+        });
     }
 
-    Ok(sent_count)
+    mail_queue::enqueue(db, channel_id, &emails).await?;
+    Ok(emails.len())
 }
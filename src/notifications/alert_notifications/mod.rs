@@ -137,6 +137,7 @@ pub enum AlertNotificationCommand {
     SendEmail {
         payload: AlertNotificationPayload,
         email_config: EmailConfig,
+        channel_id: String,
     },
     /// Send an alert webhook notification
     SendWebhook {
@@ -168,11 +169,13 @@ impl AlertNotificationService {
         &self,
         payload: AlertNotificationPayload,
         email_config: EmailConfig,
+        channel_id: String,
     ) -> Result<()> {
         self.tx
             .send(AlertNotificationCommand::SendEmail {
                 payload,
                 email_config,
+                channel_id,
             })
             .await
             .map_err(|e| anyhow::anyhow!("Failed to queue email alert notification: {}", e))
@@ -256,7 +259,10 @@ impl AlertNotificationService {
         let email_channels = self.get_email_channels().await?;
         for channel in email_channels {
             if let Some(email_config) = channel.get_email_config() {
-                if let Err(e) = self.queue_email_alert(payload.clone(), email_config).await {
+                if let Err(e) = self
+                    .queue_email_alert(payload.clone(), email_config, channel.id.clone())
+                    .await
+                {
                     tracing::warn!(
                         channel_id = %channel.id,
                         channel_type = "email",
@@ -310,6 +316,7 @@ impl AlertNotificationService {
 
 /// Spawn the alert notification worker that processes the notification queue
 pub fn spawn_alert_notification_worker(
+    db: DbPool,
     mut rx: mpsc::Receiver<AlertNotificationCommand>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
@@ -320,8 +327,9 @@ pub fn spawn_alert_notification_worker(
                 AlertNotificationCommand::SendEmail {
                     payload,
                     email_config,
+                    channel_id,
                 } => {
-                    let result = send_alert_email(&email_config, &payload).await;
+                    let result = send_alert_email(&db, &channel_id, &email_config, &payload).await;
                     match result {
                         Ok(recipients) => {
                             tracing::info!(
@@ -329,7 +337,7 @@ pub fn spawn_alert_notification_worker(
                                 metric_type = %payload.metric_type,
                                 status = %payload.status,
                                 recipients = recipients,
-                                "Alert email sent successfully"
+                                "Alert email queued"
                             );
                         }
                         Err(e) => {
@@ -337,7 +345,7 @@ pub fn spawn_alert_notification_worker(
                                 app_id = %payload.app_id,
                                 metric_type = %payload.metric_type,
                                 error = %e,
-                                "Failed to send alert email"
+                                "Failed to queue alert email"
                             );
                         }
                     }
@@ -0,0 +1,172 @@
+//! Per-user notification preferences for email recipients.
+//!
+//! Email channels list their recipients as addresses. When an address belongs
+//! to a user, the user's preferences decide what they get: events of a
+//! severity they muted are dropped, and users on digests have events held in
+//! `notification_digest_items` and mailed as one summary each morning in
+//! their timezone. Addresses that aren't users get every event, and the
+//! channel itself is left untouched for its other recipients.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{Timelike, Utc};
+use sqlx::FromRow;
+use tokio::time::interval;
+
+use super::{NotificationPayload, SystemEmailService};
+use crate::config::EmailConfig;
+use crate::db::UserPreferences;
+use crate::utils::cron;
+use crate::DbPool;
+
+/// Local hour (in the user's timezone) digests are sent at
+const DIGEST_HOUR: u32 = 8;
+/// Most events listed in one digest; older ones are only counted
+pub const MAX_DIGEST_ITEMS: usize = 100;
+
+/// An event held for a user's digest
+#[derive(Debug, Clone, FromRow)]
+pub struct DigestItem {
+    pub id: String,
+    pub user_id: String,
+    pub event_type: String,
+    pub severity: String,
+    pub app_name: String,
+    pub title: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+/// Filter a channel's email recipients by their owners' preferences, holding
+/// the event for those on digests
+pub(crate) async fn route_recipients(
+    db: &DbPool,
+    to_addresses: Vec<String>,
+    payload: &NotificationPayload,
+) -> Vec<String> {
+    let severity = payload.event_type.severity();
+    let mut recipients = Vec::with_capacity(to_addresses.len());
+
+    for address in to_addresses {
+        let user: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM users WHERE lower(email) = lower(?)")
+                .bind(address.trim())
+                .fetch_optional(db)
+                .await
+                .unwrap_or(None);
+        let prefs = match user {
+            Some((user_id,)) => UserPreferences::get(db, &user_id).await.unwrap_or(None),
+            None => None,
+        };
+        let Some(prefs) = prefs else {
+            recipients.push(address);
+            continue;
+        };
+
+        if !prefs.receives(severity) {
+            tracing::debug!(user_id = %prefs.user_id, severity, "Event muted by user preferences");
+        } else if prefs.email_digests != 0 {
+            if let Err(e) = hold_for_digest(db, &prefs, payload).await {
+                tracing::warn!(user_id = %prefs.user_id, error = %e, "Failed to hold event for digest");
+            }
+        } else {
+            recipients.push(address);
+        }
+    }
+    recipients
+}
+
+async fn hold_for_digest(
+    db: &DbPool,
+    prefs: &UserPreferences,
+    payload: &NotificationPayload,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO notification_digest_items \
+         (id, user_id, event_type, severity, app_name, title, message) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&prefs.user_id)
+    .bind(payload.event_type.to_string())
+    .bind(payload.event_type.severity())
+    .bind(&payload.app_name)
+    .bind(payload.title_in(prefs.locale()))
+    .bind(&payload.message)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Send the digests that are due: users on digests whose local time is
+/// `DIGEST_HOUR`, and right away for users who turned digests off while
+/// events were held
+async fn send_due_digests(db: &DbPool, email: &SystemEmailService) -> Result<()> {
+    let users: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT DISTINCT u.id, u.email, u.name FROM notification_digest_items i \
+         JOIN users u ON u.id = i.user_id",
+    )
+    .fetch_all(db)
+    .await?;
+
+    for (user_id, address, name) in users {
+        let prefs = UserPreferences::get(db, &user_id).await?;
+        let on_digests = prefs.as_ref().is_some_and(|p| p.email_digests != 0);
+        if on_digests {
+            let tz = cron::parse_timezone(prefs.as_ref().and_then(|p| p.timezone.as_deref()))
+                .unwrap_or(chrono_tz::Tz::UTC);
+            if Utc::now().with_timezone(&tz).hour() != DIGEST_HOUR {
+                continue;
+            }
+        }
+
+        let items: Vec<DigestItem> = sqlx::query_as(
+            "SELECT * FROM notification_digest_items WHERE user_id = ? ORDER BY created_at ASC",
+        )
+        .bind(&user_id)
+        .fetch_all(db)
+        .await?;
+        if items.is_empty() {
+            continue;
+        }
+
+        let locale = prefs.as_ref().map(|p| p.locale()).unwrap_or_default();
+        if let Err(e) = email
+            .send_digest_email(&address, &name, &items, locale)
+            .await
+        {
+            tracing::warn!(user_id = %user_id, error = %e, "Failed to send notification digest");
+            continue;
+        }
+        for item in &items {
+            sqlx::query("DELETE FROM notification_digest_items WHERE id = ?")
+                .bind(&item.id)
+                .execute(db)
+                .await?;
+        }
+        tracing::info!(user_id = %user_id, events = items.len(), "Notification digest sent");
+    }
+    Ok(())
+}
+
+/// Spawn the digest sender (checks hourly)
+pub fn spawn_digest_sender(db: DbPool, email_config: EmailConfig) {
+    tracing::info!("Starting notification digest sender (hourly)");
+
+    tokio::spawn(async move {
+        let email = SystemEmailService::with_db(email_config, db.clone());
+        let mut tick = interval(Duration::from_secs(3600));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("digest_sender", async {
+                if let Err(e) = send_due_digests(&db, &email).await {
+                    tracing::warn!(error = %e, "Notification digest cycle failed");
+                }
+            })
+            .await;
+        }
+    });
+}
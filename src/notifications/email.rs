@@ -4,14 +4,14 @@
 //! using the SMTP configuration from the main config file.
 
 use anyhow::Result;
-use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
-    transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
-};
 
 use crate::config::EmailConfig;
 use crate::db::{NotificationChannel, SendryConfig};
+use crate::i18n::{self, Locale};
+
+use super::digest::{DigestItem, MAX_DIGEST_ITEMS};
+use super::mail_queue::{self, Email, SmtpServer, SYSTEM_SOURCE};
+use super::summary::{format_duration, DigestSummary};
 
 /// Service for sending system emails.
 ///
@@ -99,6 +99,59 @@ impl SystemEmailService {
             .await
     }
 
+    /// Send a notification digest listing held events, oldest first
+    pub async fn send_digest_email(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        items: &[DigestItem],
+        locale: Locale,
+    ) -> Result<()> {
+        let subject = i18n::t_count(locale, "email.digest.subject", items.len(), &[]);
+        let html_body = render_digest_html(user_name, items, locale);
+        let text_body = render_digest_text(user_name, items, locale);
+
+        self.send_email(to_email, &subject, &html_body, &text_body)
+            .await
+    }
+
+    /// Send a notification digest's summary of its period
+    pub async fn send_summary_email(
+        &self,
+        to_email: &str,
+        digest_name: &str,
+        summary: &DigestSummary,
+    ) -> Result<()> {
+        let subject = format!("Rivetr digest: {}", digest_name);
+        let html_body = render_summary_html(digest_name, summary);
+        let text_body = format!(
+            "{}\n\n{}\n\n---\nSent by Rivetr - Deploy your apps with ease\nhttps://rivetr.io",
+            digest_name,
+            summary.to_text()
+        );
+
+        self.send_email(to_email, &subject, &html_body, &text_body)
+            .await
+    }
+
+    /// Send host hygiene findings (pending security updates, end-of-life
+    /// container engine) to an admin
+    pub async fn send_host_hygiene_email(&self, to_email: &str, findings: &[String]) -> Result<()> {
+        let subject = "Rivetr host needs attention".to_string();
+        let html_body = render_host_hygiene_html(findings);
+        let text_body = format!(
+            "The host running Rivetr needs attention:\n\n{}\n\nSee GET /api/system/health for the latest check.\n\n---\nSent by Rivetr - Deploy your apps with ease\nhttps://rivetr.io",
+            findings
+                .iter()
+                .map(|f| format!("- {}", f))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+
+        self.send_email(to_email, &subject, &html_body, &text_body)
+            .await
+    }
+
     /// Send an email, choosing a transport: toml SMTP first, then a Sendry
     /// notification channel. If neither is configured, logs a warning and
     /// returns Ok so callers' flows aren't broken.
@@ -129,7 +182,8 @@ impl SystemEmailService {
         Ok(())
     }
 
-    /// Send an email via toml-configured SMTP.
+    /// Send an email via toml-configured SMTP: queued when the service has a
+    /// database (see [`super::mail_queue`]), otherwise sent right away.
     async fn send_smtp(
         &self,
         to_email: &str,
@@ -137,10 +191,7 @@ impl SystemEmailService {
         html_body: &str,
         text_body: &str,
     ) -> Result<()> {
-        let smtp_host = self
-            .config
-            .smtp_host
-            .as_ref()
+        let server = SmtpServer::from_system(&self.config)
             .ok_or_else(|| anyhow::anyhow!("SMTP host not configured"))?;
         let from_address = self
             .config
@@ -148,52 +199,24 @@ impl SystemEmailService {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("From address not configured"))?;
 
-        // Build the from mailbox with name
-        let from_mailbox = format!("{} <{}>", self.config.from_name, from_address);
-        let from: Mailbox = from_mailbox.parse()?;
-        let to: Mailbox = to_email.parse()?;
-
-        let email = Message::builder()
-            .from(from)
-            .to(to)
-            .subject(subject)
-            .multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.to_string()),
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body.to_string()),
-                    ),
-            )?;
-
-        // Build SMTP transport
-        let mailer = if self.config.smtp_tls {
-            AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host)?
-        } else {
-            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp_host)
-        }
-        .port(self.config.smtp_port);
-
-        let mailer = if let (Some(username), Some(password)) =
-            (&self.config.smtp_username, &self.config.smtp_password)
-        {
-            mailer.credentials(Credentials::new(username.clone(), password.clone()))
-        } else {
-            mailer
+        let email = Email {
+            from: format!("{} <{}>", self.config.from_name, from_address),
+            to: to_email.to_string(),
+            subject: subject.to_string(),
+            html_body: html_body.to_string(),
+            text_body: text_body.to_string(),
         };
 
-        mailer.build().send(email).await?;
-
-        tracing::info!(
-            to = %to_email,
-            subject = %subject,
-            "Email sent successfully"
-        );
+        match self.db {
+            Some(ref db) => {
+                mail_queue::enqueue(db, SYSTEM_SOURCE, std::slice::from_ref(&email)).await?;
+                tracing::info!(to = %to_email, subject = %subject, "Email queued");
+            }
+            None => {
+                mail_queue::send_now(&server, &email).await?;
+                tracing::info!(to = %to_email, subject = %subject, "Email sent successfully");
+            }
+        }
 
         Ok(())
     }
@@ -473,6 +496,279 @@ https://rivetr.io"#,
     )
 }
 
+/// The digest items listed individually (the newest `MAX_DIGEST_ITEMS`) and
+/// how many older ones are left out
+fn listed_digest_items(items: &[DigestItem]) -> (&[DigestItem], usize) {
+    let omitted = items.len().saturating_sub(MAX_DIGEST_ITEMS);
+    (&items[omitted..], omitted)
+}
+
+/// Render the HTML version of the notification digest email
+fn render_digest_html(user_name: &str, items: &[DigestItem], locale: Locale) -> String {
+    let (listed, omitted) = listed_digest_items(items);
+    let rows: String = listed
+        .iter()
+        .map(|item| {
+            format!(
+                r#"<tr><td class="severity {severity}">{label}</td><td><strong>{title}</strong><br>{message}<div class="meta">{app} &middot; {time}</div></td></tr>"#,
+                severity = html_escape(&item.severity),
+                label = html_escape(&severity_label(locale, &item.severity)),
+                title = html_escape(&item.title),
+                message = html_escape(&item.message),
+                app = html_escape(&item.app_name),
+                time = html_escape(&item.created_at),
+            )
+        })
+        .collect();
+    let omitted_note = if omitted > 0 {
+        format!(
+            r#"<p class="note">{}</p>"#,
+            html_escape(&i18n::t_count(locale, "email.digest.omitted", omitted, &[]))
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}" dir="{dir}">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{heading}</title>
+    <style>
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; margin: 0; padding: 0; background-color: #f5f5f5; -webkit-font-smoothing: antialiased; }}
+        .container {{ max-width: 560px; margin: 0 auto; padding: 40px 20px; }}
+        .card {{ background-color: #ffffff; border-radius: 8px; box-shadow: 0 2px 8px rgba(0,0,0,0.06); overflow: hidden; }}
+        .header {{ background: linear-gradient(135deg, #3b82f6 0%, #2563eb 100%); color: white; padding: 32px 24px; text-align: center; }}
+        .header h1 {{ margin: 0; font-size: 24px; font-weight: 600; }}
+        .content {{ padding: 32px 24px; }}
+        .content p {{ margin: 0 0 16px; color: #374151; line-height: 1.6; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        td {{ padding: 10px 8px; border-top: 1px solid #f3f4f6; color: #374151; font-size: 14px; vertical-align: top; }}
+        .severity {{ font-size: 12px; font-weight: 600; text-transform: uppercase; white-space: nowrap; }}
+        .info {{ color: #2563eb; }}
+        .warning {{ color: #d97706; }}
+        .critical {{ color: #dc2626; }}
+        .meta {{ color: #9ca3af; font-size: 12px; margin-top: 4px; }}
+        .note {{ color: #6b7280; font-size: 13px; text-align: center; margin-top: 24px; }}
+        .footer {{ padding: 24px; text-align: center; color: #9ca3af; font-size: 12px; border-top: 1px solid #f3f4f6; }}
+        .footer a {{ color: #3b82f6; text-decoration: none; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="card">
+            <div class="header">
+                <h1>{heading}</h1>
+            </div>
+            <div class="content">
+                <p>{greeting}</p>
+                <p>{intro}</p>
+                <table>{rows}</table>
+                {omitted_note}
+                <p class="note">{reason}</p>
+            </div>
+            <div class="footer">
+                <p>{footer}</p>
+            </div>
+        </div>
+    </div>
+</body>
+</html>"#,
+        lang = locale.code(),
+        dir = locale.dir(),
+        heading = html_escape(&i18n::t(locale, "email.digest.heading")),
+        greeting = html_escape(&i18n::t_args(
+            locale,
+            "email.digest.greeting",
+            &[("name", user_name)]
+        )),
+        intro = html_escape(&i18n::t(locale, "email.digest.intro")),
+        rows = rows,
+        omitted_note = omitted_note,
+        reason = html_escape(&i18n::t(locale, "email.digest.reason")),
+        footer = html_escape(&i18n::t(locale, "email.footer"))
+            .replace("{rivetr}", r#"<a href="https://rivetr.io">Rivetr</a>"#),
+    )
+}
+
+/// Render the plain text version of the notification digest email
+fn render_digest_text(user_name: &str, items: &[DigestItem], locale: Locale) -> String {
+    let (listed, omitted) = listed_digest_items(items);
+    let mut text = format!(
+        "{}\n\n{}\n\n{}\n\n",
+        i18n::t(locale, "email.digest.heading"),
+        i18n::t_args(locale, "email.digest.greeting", &[("name", user_name)]),
+        i18n::t(locale, "email.digest.intro")
+    );
+    for item in listed {
+        text.push_str(&format!(
+            "[{}] {}\n{}\n{} - {}\n\n",
+            severity_label(locale, &item.severity).to_uppercase(),
+            item.title,
+            item.message,
+            item.app_name,
+            item.created_at
+        ));
+    }
+    if omitted > 0 {
+        text.push_str(&i18n::t_count(locale, "email.digest.omitted", omitted, &[]));
+        text.push_str("\n\n");
+    }
+    text.push_str(&format!(
+        "---\n{}\nhttps://rivetr.io",
+        i18n::t_args(locale, "email.footer", &[("rivetr", "Rivetr")])
+    ));
+    text
+}
+
+/// Label of a notification severity in `locale`
+fn severity_label(locale: Locale, severity: &str) -> String {
+    i18n::t(locale, &format!("severity.{}", severity))
+}
+
+/// Render the HTML version of a notification digest summary email
+fn render_summary_html(digest_name: &str, summary: &DigestSummary) -> String {
+    let mut sections = format!(
+        r#"<p><strong>{}</strong> deployment{}: {} succeeded, {} failed.</p>"#,
+        summary.deployments,
+        if summary.deployments == 1 { "" } else { "s" },
+        summary.succeeded,
+        summary.failed
+    );
+    if !summary.slowest_builds.is_empty() {
+        sections.push_str("<h2>Slowest builds</h2><table>");
+        for build in &summary.slowest_builds {
+            sections.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td></tr>",
+                html_escape(&build.app_name),
+                format_duration(build.duration_secs)
+            ));
+        }
+        sections.push_str("</table>");
+    }
+    if summary.crashed_apps.is_empty() {
+        sections.push_str("<h2>Container crashes</h2><p>No container crashes.</p>");
+    } else {
+        sections.push_str("<h2>Container crashes</h2><table>");
+        for app in &summary.crashed_apps {
+            sections.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td></tr>",
+                html_escape(&app.app_name),
+                app.crashes
+            ));
+        }
+        sections.push_str("</table>");
+    }
+    if !summary.expiring_certificates.is_empty() {
+        sections.push_str("<h2>Certificates expiring soon</h2><table>");
+        for cert in &summary.expiring_certificates {
+            sections.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{} day{} left</td></tr>",
+                html_escape(&cert.domain),
+                cert.days_left,
+                if cert.days_left == 1 { "" } else { "s" }
+            ));
+        }
+        sections.push_str("</table>");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{digest_name}</title>
+    <style>
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; margin: 0; padding: 0; background-color: #f5f5f5; -webkit-font-smoothing: antialiased; }}
+        .container {{ max-width: 560px; margin: 0 auto; padding: 40px 20px; }}
+        .card {{ background-color: #ffffff; border-radius: 8px; box-shadow: 0 2px 8px rgba(0,0,0,0.06); overflow: hidden; }}
+        .header {{ background: linear-gradient(135deg, #3b82f6 0%, #2563eb 100%); color: white; padding: 32px 24px; text-align: center; }}
+        .header h1 {{ margin: 0; font-size: 24px; font-weight: 600; }}
+        .content {{ padding: 32px 24px; }}
+        .content p {{ margin: 0 0 16px; color: #374151; line-height: 1.6; }}
+        h2 {{ font-size: 16px; color: #111827; margin: 24px 0 8px; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        td {{ padding: 8px; border-top: 1px solid #f3f4f6; color: #374151; font-size: 14px; }}
+        .num {{ text-align: right; white-space: nowrap; }}
+        .note {{ color: #6b7280; font-size: 13px; text-align: center; margin-top: 24px; }}
+        .footer {{ padding: 24px; text-align: center; color: #9ca3af; font-size: 12px; border-top: 1px solid #f3f4f6; }}
+        .footer a {{ color: #3b82f6; text-decoration: none; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="card">
+            <div class="header">
+                <h1>{digest_name}</h1>
+            </div>
+            <div class="content">
+                {sections}
+                <p class="note">{period_start} &ndash; {period_end}</p>
+            </div>
+            <div class="footer">
+                <p>Sent by <a href="https://rivetr.io">Rivetr</a> - Deploy your apps with ease</p>
+            </div>
+        </div>
+    </div>
+</body>
+</html>"#,
+        digest_name = html_escape(digest_name),
+        sections = sections,
+        period_start = html_escape(&summary.period_start),
+        period_end = html_escape(&summary.period_end),
+    )
+}
+
+fn render_host_hygiene_html(findings: &[String]) -> String {
+    let items: String = findings
+        .iter()
+        .map(|f| format!("<li>{}</li>", html_escape(f)))
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Host needs attention</title>
+    <style>
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; margin: 0; padding: 0; background-color: #f5f5f5; -webkit-font-smoothing: antialiased; }}
+        .container {{ max-width: 560px; margin: 0 auto; padding: 40px 20px; }}
+        .card {{ background-color: #ffffff; border-radius: 8px; box-shadow: 0 2px 8px rgba(0,0,0,0.06); overflow: hidden; }}
+        .header {{ background: linear-gradient(135deg, #f59e0b 0%, #d97706 100%); color: white; padding: 32px 24px; text-align: center; }}
+        .header h1 {{ margin: 0; font-size: 24px; font-weight: 600; }}
+        .content {{ padding: 32px 24px; }}
+        .content p, .content li {{ margin: 0 0 16px; color: #374151; line-height: 1.6; }}
+        .footer {{ padding: 24px; text-align: center; color: #9ca3af; font-size: 12px; border-top: 1px solid #f3f4f6; }}
+        .footer a {{ color: #3b82f6; text-decoration: none; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="card">
+            <div class="header">
+                <h1>Host needs attention</h1>
+            </div>
+            <div class="content">
+                <p>The host running Rivetr needs attention:</p>
+                <ul>{items}</ul>
+                <p>See <code>GET /api/system/health</code> for the latest check.</p>
+            </div>
+            <div class="footer">
+                <p>Sent by <a href="https://rivetr.io">Rivetr</a> - Deploy your apps with ease</p>
+            </div>
+        </div>
+    </div>
+</body>
+</html>"#,
+        items = items,
+    )
+}
+
 /// Escape HTML special characters
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -542,4 +838,50 @@ mod tests {
         assert!(html.contains("7 days"));
         assert!(html.contains("<!DOCTYPE html>"));
     }
+
+    #[test]
+    fn test_render_digest_caps_listed_items() {
+        let items: Vec<DigestItem> = (0..MAX_DIGEST_ITEMS + 2)
+            .map(|i| DigestItem {
+                id: i.to_string(),
+                user_id: "user-1".to_string(),
+                event_type: "deployment_failed".to_string(),
+                severity: "critical".to_string(),
+                app_name: "<api>".to_string(),
+                title: format!("Deployment failed #{}", i),
+                message: "Build error".to_string(),
+                created_at: "2026-10-17 08:00:00".to_string(),
+            })
+            .collect();
+
+        let html = render_digest_html("Jane", &items, Locale::En);
+        assert!(html.contains("&lt;api&gt;"));
+        assert!(!html.contains("Deployment failed #1<"));
+        assert!(html.contains(&format!("Deployment failed #{}", MAX_DIGEST_ITEMS + 1)));
+        assert!(html.contains("2 older events not shown."));
+
+        let text = render_digest_text("Jane", &items, Locale::En);
+        assert!(text.contains("[CRITICAL] Deployment failed #2\n"));
+        assert!(!text.contains("Deployment failed #1\n"));
+    }
+
+    #[test]
+    fn test_render_digest_in_rtl_locale() {
+        let items = vec![DigestItem {
+            id: "1".to_string(),
+            user_id: "user-1".to_string(),
+            event_type: "container_crash".to_string(),
+            severity: "critical".to_string(),
+            app_name: "api".to_string(),
+            title: "Container Crashed: api".to_string(),
+            message: "Exited with code 137".to_string(),
+            created_at: "2026-10-17 08:00:00".to_string(),
+        }];
+
+        let html = render_digest_html("Jane", &items, Locale::Ar);
+        assert!(html.contains(r#"<html lang="ar" dir="rtl">"#));
+        assert!(html.contains(&i18n::t(Locale::Ar, "email.digest.heading")));
+        assert!(html.contains(r#"<a href="https://rivetr.io">Rivetr</a>"#));
+        assert!(!html.contains("Your Rivetr digest"));
+    }
 }
@@ -55,6 +55,10 @@ fn format_gotify_message(payload: &NotificationPayload) -> String {
         msg.push_str(&format!("\nDeployment ID: {}", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        msg.push_str(&format!("\n{}: {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         msg.push_str(&format!("\n\nError: {}", error));
     }
@@ -78,6 +82,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_gotify_message(&payload);
@@ -98,10 +103,39 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_gotify_message(&payload);
         assert!(msg.contains("Error: Build error: missing Dockerfile"));
         assert!(!msg.contains("Deployment ID:"));
     }
+
+    #[test]
+    fn test_format_gotify_message_with_commit() {
+        let payload = NotificationPayload {
+            event_type: NotificationEventType::DeploymentSuccess,
+            app_id: "app-123".to_string(),
+            app_name: "my-app".to_string(),
+            deployment_id: Some("dep-456".to_string()),
+            status: "success".to_string(),
+            message: "Deployment completed successfully".to_string(),
+            error_message: None,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: Some(crate::notifications::CommitMetadata {
+                sha: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+                message: Some("Fix login redirect\n\nLonger description".to_string()),
+                author: Some("Jane Doe".to_string()),
+                branch: Some("main".to_string()),
+                compare_url: Some("https://github.com/acme/web/compare/abc...def".to_string()),
+            }),
+        };
+
+        let msg = format_gotify_message(&payload);
+        assert!(msg.contains("Commit: 0123456 Fix login redirect"));
+        assert!(!msg.contains("Longer description"));
+        assert!(msg.contains("Author: Jane Doe"));
+        assert!(msg.contains("Branch: main"));
+        assert!(msg.contains("Compare: https://github.com/acme/web/compare/abc...def"));
+    }
 }
@@ -0,0 +1,355 @@
+//! In-app notification center.
+//!
+//! Every event the outbox dispatches is also copied to the inbox of each user
+//! who can see the app: members of its team and of teams it is shared with,
+//! plus admins (everyone, for apps without a team), less those who muted the
+//! event's severity in their preferences. New notifications are broadcast so
+//! open dashboards can show them over the event stream straight away.
+
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use tokio::sync::broadcast;
+
+use super::NotificationPayload;
+use crate::db::{InboxNotification, UserPreferences};
+use crate::DbPool;
+
+/// Notifications buffered for slow event stream subscribers
+const BROADCAST_CAPACITY: usize = 256;
+/// Days read notifications are kept
+const READ_RETENTION_DAYS: i64 = 30;
+/// Days unread notifications are kept
+const UNREAD_RETENTION_DAYS: i64 = 90;
+
+static INBOX_EVENTS: OnceLock<broadcast::Sender<InboxNotification>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<InboxNotification> {
+    INBOX_EVENTS.get_or_init(|| broadcast::channel(BROADCAST_CAPACITY).0)
+}
+
+/// Receive notifications as they are added to any user's inbox
+pub fn subscribe() -> broadcast::Receiver<InboxNotification> {
+    sender().subscribe()
+}
+
+/// Users who can see the app an event is about
+async fn recipients(db: &DbPool, app_id: &str) -> Result<Vec<String>> {
+    let team_id: Option<Option<String>> =
+        sqlx::query_scalar("SELECT team_id FROM apps WHERE id = ?")
+            .bind(app_id)
+            .fetch_optional(db)
+            .await?;
+
+    let user_ids = match team_id.flatten() {
+        Some(team_id) => {
+            sqlx::query_scalar(
+                "SELECT id FROM users WHERE role = 'admin' \
+                 UNION SELECT user_id FROM team_members WHERE team_id = ? \
+                 UNION SELECT m.user_id FROM app_shares s \
+                 JOIN team_members m ON m.team_id = s.shared_with_team_id WHERE s.app_id = ?",
+            )
+            .bind(&team_id)
+            .bind(app_id)
+            .fetch_all(db)
+            .await?
+        }
+        // Apps without a team are visible to every user
+        None => {
+            sqlx::query_scalar("SELECT id FROM users")
+                .fetch_all(db)
+                .await?
+        }
+    };
+    Ok(user_ids)
+}
+
+/// Copy an outbox event to the inboxes of the users who should see it. Safe
+/// to call again for the same event: users who already have it are skipped.
+pub(crate) async fn record(
+    db: &DbPool,
+    outbox_id: &str,
+    payload: &NotificationPayload,
+) -> Result<()> {
+    let severity = payload.event_type.severity();
+
+    for user_id in recipients(db, &payload.app_id).await? {
+        let muted = UserPreferences::get(db, &user_id)
+            .await?
+            .is_some_and(|prefs| !prefs.receives(severity));
+        if muted {
+            continue;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let inserted = sqlx::query(
+            "INSERT OR IGNORE INTO inbox_notifications \
+             (id, user_id, outbox_id, event_type, severity, app_id, app_name, deployment_id, \
+             message, error_message) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&user_id)
+        .bind(outbox_id)
+        .bind(payload.event_type.to_string())
+        .bind(severity)
+        .bind(&payload.app_id)
+        .bind(&payload.app_name)
+        .bind(&payload.deployment_id)
+        .bind(&payload.message)
+        .bind(&payload.error_message)
+        .execute(db)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if inserted {
+            if let Some(notification) = InboxNotification::get(db, &user_id, &id).await? {
+                // No subscribers just means no dashboard is open
+                let _ = sender().send(notification);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Delete read notifications after a month and unread ones after three
+pub(crate) async fn prune(db: &DbPool) {
+    let _ = sqlx::query(
+        "DELETE FROM inbox_notifications \
+         WHERE (read_at IS NOT NULL AND created_at < datetime('now', ?)) \
+         OR created_at < datetime('now', ?)",
+    )
+    .bind(format!("-{} days", READ_RETENTION_DAYS))
+    .bind(format!("-{} days", UNREAD_RETENTION_DAYS))
+    .execute(db)
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_app, test_pool, test_user, NotificationEventType};
+
+    async fn create_team(db: &DbPool, members: &[&str]) -> String {
+        let team_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO teams (id, name, slug) VALUES (?, ?, ?)")
+            .bind(&team_id)
+            .bind(&team_id)
+            .bind(&team_id)
+            .execute(db)
+            .await
+            .unwrap();
+        for user_id in members {
+            sqlx::query(
+                "INSERT INTO team_members (id, team_id, user_id, role) \
+                 VALUES (?, ?, ?, 'developer')",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&team_id)
+            .bind(user_id)
+            .execute(db)
+            .await
+            .unwrap();
+        }
+        team_id
+    }
+
+    fn payload(app_id: &str, event_type: NotificationEventType) -> NotificationPayload {
+        NotificationPayload {
+            event_type,
+            app_id: app_id.to_string(),
+            app_name: "web".to_string(),
+            deployment_id: None,
+            status: "failed".to_string(),
+            message: "Deployment failed".to_string(),
+            error_message: None,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            commit: None,
+        }
+    }
+
+    async fn inbox(db: &DbPool, user_id: &str) -> Vec<InboxNotification> {
+        InboxNotification::list_for_user(db, user_id, false, 50)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_reaches_users_who_can_see_the_app() {
+        let (_dir, db) = test_pool().await;
+        let admin = test_user(&db, "admin").await;
+        let member = test_user(&db, "member").await;
+        let shared = test_user(&db, "member").await;
+        let outsider = test_user(&db, "member").await;
+        let team_id = create_team(&db, &[&member.id]).await;
+        let other_team = create_team(&db, &[&shared.id]).await;
+        let app_id = test_app(&db, "web").await;
+        sqlx::query("UPDATE apps SET team_id = ? WHERE id = ?")
+            .bind(&team_id)
+            .bind(&app_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO app_shares (id, app_id, shared_with_team_id) VALUES ('s', ?, ?)")
+            .bind(&app_id)
+            .bind(&other_team)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let event = payload(&app_id, NotificationEventType::DeploymentFailed);
+        record(&db, "outbox-1", &event).await.unwrap();
+        // Recording the same event again adds nothing
+        record(&db, "outbox-1", &event).await.unwrap();
+
+        for user in [&admin, &member, &shared] {
+            let notifications = inbox(&db, &user.id).await;
+            assert_eq!(notifications.len(), 1, "{}", user.email);
+            assert_eq!(notifications[0].severity, "critical");
+            assert_eq!(notifications[0].read_at, None);
+        }
+        assert!(inbox(&db, &outsider.id).await.is_empty());
+
+        // Apps without a team are visible to everyone
+        let open_app = test_app(&db, "open").await;
+        record(
+            &db,
+            "outbox-2",
+            &payload(&open_app, NotificationEventType::AppStarted),
+        )
+        .await
+        .unwrap();
+        assert_eq!(inbox(&db, &outsider.id).await.len(), 1);
+        assert_eq!(inbox(&db, &member.id).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_muted_severities_are_skipped() {
+        let (_dir, db) = test_pool().await;
+        let user = test_user(&db, "member").await;
+        sqlx::query(
+            "INSERT INTO user_preferences (user_id, notify_severities) VALUES (?, '[\"critical\"]')",
+        )
+        .bind(&user.id)
+        .execute(&db)
+        .await
+        .unwrap();
+        let app_id = test_app(&db, "web").await;
+
+        record(
+            &db,
+            "outbox-1",
+            &payload(&app_id, NotificationEventType::AppStarted),
+        )
+        .await
+        .unwrap();
+        record(
+            &db,
+            "outbox-2",
+            &payload(&app_id, NotificationEventType::DeploymentFailed),
+        )
+        .await
+        .unwrap();
+
+        let notifications = inbox(&db, &user.id).await;
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].event_type, "deployment_failed");
+    }
+
+    #[tokio::test]
+    async fn test_read_state_is_per_user() {
+        let (_dir, db) = test_pool().await;
+        let alice = test_user(&db, "member").await;
+        let bob = test_user(&db, "member").await;
+        let app_id = test_app(&db, "web").await;
+        for outbox_id in ["outbox-1", "outbox-2", "outbox-3"] {
+            record(
+                &db,
+                outbox_id,
+                &payload(&app_id, NotificationEventType::AppStarted),
+            )
+            .await
+            .unwrap();
+        }
+        let alices = inbox(&db, &alice.id).await;
+
+        // Nobody can mark another user's notification read
+        assert!(!InboxNotification::mark_read(&db, &bob.id, &alices[0].id)
+            .await
+            .unwrap());
+        assert!(InboxNotification::get(&db, &bob.id, &alices[0].id)
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(InboxNotification::mark_read(&db, &alice.id, &alices[0].id)
+            .await
+            .unwrap());
+        // Marking it again keeps it read
+        assert!(InboxNotification::mark_read(&db, &alice.id, &alices[0].id)
+            .await
+            .unwrap());
+        assert_eq!(
+            InboxNotification::unread_count(&db, &alice.id)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            InboxNotification::unread_count(&db, &bob.id).await.unwrap(),
+            3
+        );
+
+        let unread = InboxNotification::list_for_user(&db, &alice.id, true, 50)
+            .await
+            .unwrap();
+        assert_eq!(unread.len(), 2);
+        assert!(unread.iter().all(|n| n.id != alices[0].id));
+
+        assert_eq!(
+            InboxNotification::mark_all_read(&db, &alice.id)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            InboxNotification::unread_count(&db, &alice.id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            InboxNotification::unread_count(&db, &bob.id).await.unwrap(),
+            3
+        );
+        assert!(inbox(&db, &bob.id)
+            .await
+            .iter()
+            .all(|n| n.read_at.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_new_notifications_are_broadcast() {
+        let (_dir, db) = test_pool().await;
+        let user = test_user(&db, "member").await;
+        let app_id = test_app(&db, "web").await;
+        let mut rx = subscribe();
+
+        record(
+            &db,
+            "outbox-1",
+            &payload(&app_id, NotificationEventType::AppStarted),
+        )
+        .await
+        .unwrap();
+
+        // Other tests broadcast too; look for this user's notification
+        loop {
+            let notification = rx.recv().await.unwrap();
+            if notification.user_id == user.id {
+                assert_eq!(notification.app_id.as_deref(), Some(app_id.as_str()));
+                break;
+            }
+        }
+    }
+}
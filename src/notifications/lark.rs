@@ -50,10 +50,18 @@ fn format_lark_message(payload: &NotificationPayload) -> String {
         crate::db::NotificationEventType::DeploymentStarted => "[DEPLOY]",
         crate::db::NotificationEventType::DeploymentSuccess => "[SUCCESS]",
         crate::db::NotificationEventType::DeploymentFailed => "[FAILED]",
+        crate::db::NotificationEventType::DeploymentRolledBack => "[ROLLED BACK]",
+        crate::db::NotificationEventType::DeploymentCancelled => "[CANCELLED]",
         crate::db::NotificationEventType::AppStopped => "[STOPPED]",
         crate::db::NotificationEventType::AppStarted => "[STARTED]",
         crate::db::NotificationEventType::ContainerCrash => "[CRASH]",
         crate::db::NotificationEventType::ContainerRestarted => "[RESTARTED]",
+        crate::db::NotificationEventType::AppScaled => "[SCALED]",
+        crate::db::NotificationEventType::PreviewExpiring => "[EXPIRING]",
+        crate::db::NotificationEventType::BandwidthExceeded => "[BANDWIDTH]",
+        crate::db::NotificationEventType::BackendUnhealthy => "[UNHEALTHY]",
+        crate::db::NotificationEventType::BackendRecovered => "[RECOVERED]",
+        crate::db::NotificationEventType::Digest => "[DIGEST]",
     };
 
     let mut msg = format!(
@@ -69,6 +77,10 @@ fn format_lark_message(payload: &NotificationPayload) -> String {
         msg.push_str(&format!("\nDeployment ID: {}", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        msg.push_str(&format!("\n{}: {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         msg.push_str(&format!("\n\nError: {}", error));
     }
@@ -94,6 +106,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_lark_message(&payload);
@@ -115,6 +128,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_lark_message(&payload);
@@ -0,0 +1,463 @@
+//! Outbound email queue.
+//!
+//! Emails aren't sent inline. Each recipient's message is written to the
+//! `email_queue` table and a background dispatcher sends it over a pooled SMTP
+//! transport, at most `[email] max_per_minute` messages per SMTP server per
+//! minute, retrying transient failures with exponential backoff. Sent and
+//! failed messages are kept for a while as a sent-mail log
+//! (`GET /api/system/emails`).
+//!
+//! SMTP credentials are never written to the queue: a message records where
+//! they come from ([`SYSTEM_SOURCE`] for the `[email]` config, otherwise the
+//! ID of an email notification channel) and they are looked up at send time.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use lettre::{
+    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    transport::smtp::{authentication::Credentials, PoolConfig},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tokio::time::interval;
+
+use crate::config::EmailConfig as SystemEmailConfig;
+use crate::db::{EmailConfig, NotificationChannel, QueuedEmail};
+use crate::DbPool;
+
+/// Source of messages sent with the `[email]` SMTP settings
+pub const SYSTEM_SOURCE: &str = "system";
+
+/// How often the dispatcher looks for due messages
+const DISPATCH_INTERVAL_SECS: u64 = 5;
+/// Messages considered per dispatch cycle
+const DISPATCH_BATCH_SIZE: i64 = 100;
+/// Attempts before a message is given up as failed
+const MAX_ATTEMPTS: i64 = 5;
+/// Delay before the first retry; doubled on every further attempt
+const RETRY_BASE_SECS: i64 = 60;
+/// Upper bound on the delay between retries
+const RETRY_MAX_SECS: i64 = 3600;
+/// Days sent and failed messages are kept in the log
+const RETENTION_DAYS: i64 = 14;
+/// Connections kept open per SMTP server
+const POOL_MAX_SIZE: u32 = 4;
+/// Idle pooled connections are closed after this long
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Pooled transports kept before the cache is reset (credentials changes
+/// leave stale entries behind)
+const MAX_TRANSPORTS: usize = 32;
+
+/// An SMTP server and its credentials
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmtpServer {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl SmtpServer {
+    /// The server of an email notification channel
+    pub fn from_channel(config: &EmailConfig) -> Self {
+        Self {
+            host: config.smtp_host.clone(),
+            port: config.smtp_port,
+            tls: config.smtp_tls,
+            username: config.smtp_username.clone(),
+            password: config.smtp_password.clone(),
+        }
+    }
+
+    /// The `[email]` server, if it is configured
+    pub fn from_system(config: &SystemEmailConfig) -> Option<Self> {
+        if !config.is_configured() {
+            return None;
+        }
+        Some(Self {
+            host: config.smtp_host.clone()?,
+            port: config.smtp_port,
+            tls: config.smtp_tls,
+            username: config.smtp_username.clone(),
+            password: config.smtp_password.clone(),
+        })
+    }
+}
+
+/// One email to one recipient
+#[derive(Debug, Clone)]
+pub struct Email {
+    /// From mailbox, e.g. `Rivetr <noreply@example.com>`
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+impl Email {
+    fn to_message(&self) -> Result<Message> {
+        let from: Mailbox = self.from.parse()?;
+        let to: Mailbox = self.to.parse()?;
+        Ok(Message::builder()
+            .from(from)
+            .to(to)
+            .subject(&self.subject)
+            .multipart(
+                MultiPart::alternative()
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_PLAIN)
+                            .body(self.text_body.clone()),
+                    )
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_HTML)
+                            .body(self.html_body.clone()),
+                    ),
+            )?)
+    }
+}
+
+type Transport = AsyncSmtpTransport<Tokio1Executor>;
+
+static TRANSPORTS: OnceLock<Mutex<HashMap<SmtpServer, Transport>>> = OnceLock::new();
+
+/// Pooled transport for `server`, shared by every message sent to it
+fn transport(server: &SmtpServer) -> Result<Transport> {
+    let mut transports = TRANSPORTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(transport) = transports.get(server) {
+        return Ok(transport.clone());
+    }
+
+    let builder = if server.tls {
+        Transport::relay(&server.host)?
+    } else {
+        Transport::builder_dangerous(&server.host)
+    }
+    .port(server.port)
+    .pool_config(
+        PoolConfig::new()
+            .max_size(POOL_MAX_SIZE)
+            .idle_timeout(POOL_IDLE_TIMEOUT),
+    );
+    let builder = match (&server.username, &server.password) {
+        (Some(username), Some(password)) => {
+            builder.credentials(Credentials::new(username.clone(), password.clone()))
+        }
+        _ => builder,
+    };
+    let transport = builder.build();
+
+    if transports.len() >= MAX_TRANSPORTS {
+        transports.clear();
+    }
+    transports.insert(server.clone(), transport.clone());
+    Ok(transport)
+}
+
+/// Send a message right away, bypassing the queue. For test sends, whose
+/// caller reports the result.
+pub async fn send_now(server: &SmtpServer, email: &Email) -> Result<()> {
+    transport(server)?.send(email.to_message()?).await?;
+    Ok(())
+}
+
+/// Queue messages for the dispatcher. `source` is [`SYSTEM_SOURCE`] or the
+/// ID of the email channel whose SMTP server sends them. Nothing is queued if
+/// any address can't be parsed, so a caller retrying the whole batch doesn't
+/// send duplicates.
+pub async fn enqueue(db: &DbPool, source: &str, emails: &[Email]) -> Result<()> {
+    for email in emails {
+        email.to_message()?;
+    }
+    let mut tx = db.begin().await?;
+    for email in emails {
+        sqlx::query(
+            "INSERT INTO email_queue (id, source, from_address, to_address, subject, \
+             html_body, text_body) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(source)
+        .bind(&email.from)
+        .bind(&email.to)
+        .bind(&email.subject)
+        .bind(&email.html_body)
+        .bind(&email.text_body)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Delay before retry number `attempts` (1-based)
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (RETRY_BASE_SECS * 2_i64.pow(exponent)).min(RETRY_MAX_SECS)
+}
+
+/// Whether a source still has room under the per-minute limit (0 = no limit)
+fn within_limit(sent_last_minute: u32, max_per_minute: u32) -> bool {
+    max_per_minute == 0 || sent_last_minute < max_per_minute
+}
+
+/// Why a send failed, and whether a retry could help
+struct SendError {
+    message: String,
+    permanent: bool,
+}
+
+impl SendError {
+    fn permanent(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            permanent: true,
+        }
+    }
+}
+
+/// SMTP server of a source, looked up fresh so credential changes apply
+async fn resolve_server(
+    db: &DbPool,
+    system: &SystemEmailConfig,
+    source: &str,
+) -> Result<SmtpServer, SendError> {
+    if source == SYSTEM_SOURCE {
+        return SmtpServer::from_system(system)
+            .ok_or_else(|| SendError::permanent("[email] SMTP is no longer configured"));
+    }
+    let channel: Option<NotificationChannel> =
+        sqlx::query_as("SELECT * FROM notification_channels WHERE id = ?")
+            .bind(source)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| SendError {
+                message: e.to_string(),
+                permanent: false,
+            })?;
+    channel
+        .and_then(|channel| channel.get_email_config())
+        .map(|config| SmtpServer::from_channel(&config))
+        .ok_or_else(|| SendError::permanent("Email channel no longer exists"))
+}
+
+async fn send(server: &SmtpServer, email: &QueuedEmail) -> Result<(), SendError> {
+    let message = Email {
+        from: email.from_address.clone(),
+        to: email.to_address.clone(),
+        subject: email.subject.clone(),
+        html_body: email.html_body.clone(),
+        text_body: email.text_body.clone(),
+    }
+    .to_message()
+    .map_err(|e| SendError::permanent(e.to_string()))?;
+    let transport = transport(server).map_err(|e| SendError::permanent(e.to_string()))?;
+    transport.send(message).await.map_err(|e| SendError {
+        // 5xx replies (unknown recipient, rejected sender, ...) won't change
+        permanent: e.is_permanent(),
+        message: e.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Record the outcome of one send
+async fn record(db: &DbPool, email: &QueuedEmail, result: Result<(), SendError>) -> Result<()> {
+    let error = match result {
+        Ok(()) => {
+            sqlx::query(
+                "UPDATE email_queue SET status = 'sent', attempts = attempts + 1, \
+                 last_error = NULL, sent_at = datetime('now') WHERE id = ?",
+            )
+            .bind(&email.id)
+            .execute(db)
+            .await?;
+            tracing::debug!(to = %email.to_address, subject = %email.subject, "Email sent");
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+
+    let attempts = email.attempts + 1;
+    if error.permanent || attempts >= MAX_ATTEMPTS {
+        tracing::error!(
+            email_id = %email.id,
+            to = %email.to_address,
+            error = %error.message,
+            "Giving up on email after {} attempt(s)",
+            attempts
+        );
+        sqlx::query(
+            "UPDATE email_queue SET status = 'failed', attempts = ?, last_error = ? WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(&error.message)
+        .bind(&email.id)
+        .execute(db)
+        .await?;
+    } else {
+        let delay = retry_delay_secs(attempts);
+        tracing::warn!(
+            email_id = %email.id,
+            to = %email.to_address,
+            error = %error.message,
+            "Email delivery failed, retrying in {}s",
+            delay
+        );
+        sqlx::query(
+            "UPDATE email_queue SET attempts = ?, last_error = ?, \
+             next_attempt_at = datetime('now', ?) WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(&error.message)
+        .bind(format!("+{} seconds", delay))
+        .bind(&email.id)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Send the messages that are due, oldest first, within each source's rate
+/// limit, and prune old log entries. Messages over the limit wait for a
+/// later cycle.
+async fn dispatch_cycle(db: &DbPool, system: &SystemEmailConfig) -> Result<()> {
+    let due: Vec<QueuedEmail> = sqlx::query_as(
+        "SELECT * FROM email_queue WHERE status = 'pending' \
+         AND next_attempt_at <= datetime('now') ORDER BY created_at ASC LIMIT ?",
+    )
+    .bind(DISPATCH_BATCH_SIZE)
+    .fetch_all(db)
+    .await?;
+
+    if !due.is_empty() {
+        let recent: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT source, COUNT(*) FROM email_queue \
+             WHERE sent_at > datetime('now', '-60 seconds') GROUP BY source",
+        )
+        .fetch_all(db)
+        .await?;
+        let mut sent_last_minute: HashMap<String, u32> = recent
+            .into_iter()
+            .map(|(source, count)| (source, count as u32))
+            .collect();
+        let mut servers: HashMap<String, Result<SmtpServer, String>> = HashMap::new();
+
+        for email in due {
+            let sent = sent_last_minute.entry(email.source.clone()).or_default();
+            if !within_limit(*sent, system.max_per_minute) {
+                continue;
+            }
+
+            let server = match servers.get(&email.source) {
+                Some(server) => server.clone(),
+                None => {
+                    let server = resolve_server(db, system, &email.source).await;
+                    // Lookup errors other than a missing source are retried
+                    // on the next message rather than cached
+                    match server {
+                        Ok(server) => {
+                            servers.insert(email.source.clone(), Ok(server.clone()));
+                            Ok(server)
+                        }
+                        Err(error) if error.permanent => {
+                            servers.insert(email.source.clone(), Err(error.message.clone()));
+                            Err(error.message)
+                        }
+                        Err(error) => {
+                            record(db, &email, Err(error)).await?;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let result = match server {
+                Ok(server) => {
+                    *sent += 1;
+                    send(&server, &email).await
+                }
+                Err(message) => Err(SendError::permanent(message)),
+            };
+            record(db, &email, result).await?;
+        }
+    }
+
+    sqlx::query(
+        "DELETE FROM email_queue WHERE status != 'pending' AND created_at < datetime('now', ?)",
+    )
+    .bind(format!("-{} days", RETENTION_DAYS))
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Spawn the background task sending queued email. Messages left pending by
+/// a previous run are picked up on its first cycle.
+pub fn spawn_email_dispatcher(db: DbPool, system: SystemEmailConfig) {
+    tracing::info!(
+        max_per_minute = system.max_per_minute,
+        "Starting email dispatcher ({}s interval)",
+        DISPATCH_INTERVAL_SECS
+    );
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(DISPATCH_INTERVAL_SECS));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            if let Some(Err(e)) =
+                crate::utils::supervise::guarded("email_dispatcher", dispatch_cycle(&db, &system))
+                    .await
+            {
+                tracing::error!(error = %e, "Email dispatch cycle failed");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay_secs(1), 60);
+        assert_eq!(retry_delay_secs(2), 120);
+        assert_eq!(retry_delay_secs(4), 480);
+        assert_eq!(retry_delay_secs(10), RETRY_MAX_SECS);
+    }
+
+    #[test]
+    fn test_within_limit() {
+        assert!(within_limit(0, 30));
+        assert!(within_limit(29, 30));
+        assert!(!within_limit(30, 30));
+        assert!(within_limit(1000, 0));
+    }
+
+    #[test]
+    fn test_invalid_addresses_are_rejected() {
+        let email = Email {
+            from: "Rivetr <noreply@example.com>".to_string(),
+            to: "ops@example.com".to_string(),
+            subject: "Deployment failed".to_string(),
+            html_body: "<p>Deployment failed</p>".to_string(),
+            text_body: "Deployment failed".to_string(),
+        };
+        assert!(email.to_message().is_ok());
+
+        let bad = Email {
+            to: "not an address".to_string(),
+            ..email
+        };
+        assert!(bad.to_message().is_err());
+    }
+}
@@ -48,10 +48,18 @@ fn format_mattermost_message(payload: &NotificationPayload) -> String {
         crate::db::NotificationEventType::DeploymentStarted => ":rocket:",
         crate::db::NotificationEventType::DeploymentSuccess => ":white_check_mark:",
         crate::db::NotificationEventType::DeploymentFailed => ":x:",
+        crate::db::NotificationEventType::DeploymentRolledBack => ":rewind:",
+        crate::db::NotificationEventType::DeploymentCancelled => ":no_entry_sign:",
         crate::db::NotificationEventType::AppStopped => ":octagonal_sign:",
         crate::db::NotificationEventType::AppStarted => ":arrow_forward:",
         crate::db::NotificationEventType::ContainerCrash => ":skull:",
         crate::db::NotificationEventType::ContainerRestarted => ":arrows_counterclockwise:",
+        crate::db::NotificationEventType::AppScaled => ":chart_with_upwards_trend:",
+        crate::db::NotificationEventType::PreviewExpiring => ":hourglass:",
+        crate::db::NotificationEventType::BandwidthExceeded => ":signal_strength:",
+        crate::db::NotificationEventType::BackendUnhealthy => ":red_circle:",
+        crate::db::NotificationEventType::BackendRecovered => ":large_green_circle:",
+        crate::db::NotificationEventType::Digest => ":bar_chart:",
     };
 
     let mut msg = format!(
@@ -67,6 +75,10 @@ fn format_mattermost_message(payload: &NotificationPayload) -> String {
         msg.push_str(&format!("\n**Deployment ID:** `{}`", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        msg.push_str(&format!("\n**{}:** {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         msg.push_str(&format!("\n\n**Error:**\n```\n{}\n```", error));
     }
@@ -92,6 +104,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_mattermost_message(&payload);
@@ -113,6 +126,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_mattermost_message(&payload);
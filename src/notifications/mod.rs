@@ -4,38 +4,44 @@
 //! on deployment events and app state changes.
 
 pub mod alert_notifications;
+pub mod digest;
 pub mod email;
 pub mod gotify;
+pub mod inbox;
 pub mod lark;
+pub mod mail_queue;
 pub mod mattermost;
 pub mod ntfy;
+pub mod outbox;
 pub mod pushover;
 pub mod resend;
 pub mod sendry;
+pub mod summary;
 pub mod teams;
 pub mod telegram;
+pub mod webhook_log;
 
 pub use alert_notifications::{
     spawn_alert_notification_worker, AlertNotificationPayload, AlertNotificationService,
 };
+pub use digest::spawn_digest_sender;
 pub use email::SystemEmailService;
+pub use mail_queue::spawn_email_dispatcher;
+pub use outbox::spawn_outbox_dispatcher;
+pub use summary::spawn_summary_sender;
 
 use anyhow::Result;
-use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
-    transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
-};
 use serde_json::json;
 
 use crate::db::{
     DiscordConfig, EmailConfig, NotificationChannel, NotificationChannelType,
     NotificationEventType, NotificationSubscription, SlackConfig, WebhookConfig,
 };
+use crate::i18n::{self, Locale};
 use crate::DbPool;
 
 /// Notification payload with event details
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NotificationPayload {
     pub event_type: NotificationEventType,
     pub app_id: String,
@@ -45,6 +51,53 @@ pub struct NotificationPayload {
     pub message: String,
     pub error_message: Option<String>,
     pub timestamp: String,
+    /// Source commit for deployment events, when known
+    pub commit: Option<CommitMetadata>,
+}
+
+/// What a deployment shipped: the commit it was built from and where it came from
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct CommitMetadata {
+    pub sha: Option<String>,
+    pub message: Option<String>,
+    pub author: Option<String>,
+    pub branch: Option<String>,
+    pub compare_url: Option<String>,
+}
+
+impl CommitMetadata {
+    /// Load commit metadata recorded on a deployment. Returns `None` when the
+    /// deployment has none (e.g. image or upload deployments).
+    pub async fn load(db: &DbPool, deployment_id: &str) -> Option<Self> {
+        let mut metadata: Self = sqlx::query_as(
+            "SELECT commit_sha AS sha, commit_message AS message, commit_author AS author, \
+             commit_branch AS branch, commit_compare_url AS compare_url \
+             FROM deployments WHERE id = ?",
+        )
+        .bind(deployment_id)
+        .fetch_optional(db)
+        .await
+        .ok()
+        .flatten()?;
+
+        // Upload deployments reuse commit_sha for the source path
+        metadata.sha = metadata.sha.filter(|s| !s.contains("rivetr-upload-"));
+        (metadata.sha.is_some() || metadata.message.is_some()).then_some(metadata)
+    }
+
+    /// Abbreviated commit SHA (7 characters)
+    pub fn short_sha(&self) -> Option<&str> {
+        self.sha.as_deref().map(|s| &s[..s.len().min(7)])
+    }
+
+    /// First line of the commit message
+    pub fn headline(&self) -> Option<&str> {
+        self.message
+            .as_deref()
+            .and_then(|m| m.lines().next())
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+    }
 }
 
 impl NotificationPayload {
@@ -67,9 +120,16 @@ impl NotificationPayload {
             message,
             error_message,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            commit: None,
         }
     }
 
+    /// Attach the commit a deployment was built from
+    pub fn with_commit(mut self, commit: Option<CommitMetadata>) -> Self {
+        self.commit = commit;
+        self
+    }
+
     /// Create a new notification payload for an app event
     pub fn app_event(
         event_type: NotificationEventType,
@@ -87,30 +147,62 @@ impl NotificationPayload {
             message,
             error_message: None,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            commit: None,
+        }
+    }
+
+    /// Labelled commit details for channels that render key/value fields:
+    /// commit (short SHA and headline), author, branch and compare URL.
+    pub fn commit_fields(&self) -> Vec<(&'static str, String)> {
+        let Some(ref commit) = self.commit else {
+            return Vec::new();
+        };
+
+        let mut fields = Vec::new();
+        let summary = match (commit.short_sha(), commit.headline()) {
+            (Some(sha), Some(headline)) => Some(format!("{} {}", sha, headline)),
+            (Some(sha), None) => Some(sha.to_string()),
+            (None, Some(headline)) => Some(headline.to_string()),
+            (None, None) => None,
+        };
+        if let Some(summary) = summary {
+            fields.push(("Commit", summary));
+        }
+        if let Some(ref author) = commit.author {
+            fields.push(("Author", author.clone()));
+        }
+        if let Some(ref branch) = commit.branch {
+            fields.push(("Branch", branch.clone()));
+        }
+        if let Some(ref url) = commit.compare_url {
+            fields.push(("Compare", url.clone()));
         }
+        fields
+    }
+
+    /// Commit details as HTML field rows for email bodies
+    pub fn commit_fields_html(&self) -> String {
+        self.commit_fields()
+            .iter()
+            .map(|(label, value)| {
+                format!(
+                    r#"<div class="field"><span class="field-label">{}:</span> <span class="field-value">{}</span></div>"#,
+                    label,
+                    html_escape(value)
+                )
+            })
+            .collect()
     }
 
     /// Get the title for the notification
     pub fn title(&self) -> String {
-        match self.event_type {
-            NotificationEventType::DeploymentStarted => {
-                format!("Deployment Started: {}", self.app_name)
-            }
-            NotificationEventType::DeploymentSuccess => {
-                format!("Deployment Successful: {}", self.app_name)
-            }
-            NotificationEventType::DeploymentFailed => {
-                format!("Deployment Failed: {}", self.app_name)
-            }
-            NotificationEventType::AppStopped => format!("App Stopped: {}", self.app_name),
-            NotificationEventType::AppStarted => format!("App Started: {}", self.app_name),
-            NotificationEventType::ContainerCrash => {
-                format!("Container Crashed: {}", self.app_name)
-            }
-            NotificationEventType::ContainerRestarted => {
-                format!("Container Restarted: {}", self.app_name)
-            }
-        }
+        self.title_in(Locale::En)
+    }
+
+    /// Get the title for the notification in `locale`
+    pub fn title_in(&self, locale: Locale) -> String {
+        let key = format!("notification.title.{}", self.event_type);
+        i18n::t_args(locale, &key, &[("app", &self.app_name)])
     }
 
     /// Get the color for the notification (for Slack/Discord)
@@ -125,6 +217,14 @@ impl NotificationPayload {
             } // Red
             NotificationEventType::AppStopped => "#f39c12",        // Orange
             NotificationEventType::ContainerRestarted => "#f39c12", // Orange
+            NotificationEventType::DeploymentRolledBack => "#f39c12", // Orange
+            NotificationEventType::DeploymentCancelled => "#95a5a6", // Gray
+            NotificationEventType::AppScaled => "#3498db",         // Blue
+            NotificationEventType::PreviewExpiring => "#f39c12",   // Orange
+            NotificationEventType::BandwidthExceeded => "#f39c12", // Orange
+            NotificationEventType::BackendUnhealthy => "#e74c3c",  // Red
+            NotificationEventType::BackendRecovered => "#2ecc71",  // Green
+            NotificationEventType::Digest => "#3498db",            // Blue
         }
     }
 
@@ -134,14 +234,30 @@ impl NotificationPayload {
             NotificationEventType::DeploymentStarted => ":rocket:",
             NotificationEventType::DeploymentSuccess => ":white_check_mark:",
             NotificationEventType::DeploymentFailed => ":x:",
+            NotificationEventType::DeploymentRolledBack => ":rewind:",
+            NotificationEventType::DeploymentCancelled => ":no_entry_sign:",
             NotificationEventType::AppStopped => ":octagonal_sign:",
             NotificationEventType::AppStarted => ":arrow_forward:",
             NotificationEventType::ContainerCrash => ":boom:",
             NotificationEventType::ContainerRestarted => ":arrows_counterclockwise:",
+            NotificationEventType::AppScaled => ":chart_with_upwards_trend:",
+            NotificationEventType::PreviewExpiring => ":hourglass:",
+            NotificationEventType::BandwidthExceeded => ":signal_strength:",
+            NotificationEventType::BackendUnhealthy => ":red_circle:",
+            NotificationEventType::BackendRecovered => ":large_green_circle:",
+            NotificationEventType::Digest => ":bar_chart:",
         }
     }
 }
 
+/// Escape text for inclusion in an HTML body
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Notification service for sending alerts via various channels
 pub struct NotificationService {
     db: DbPool,
@@ -204,6 +320,22 @@ impl NotificationService {
         Ok(())
     }
 
+    /// IDs of the channels subscribed to the payload's event, deduplicated
+    pub(crate) async fn subscribed_channel_ids(
+        &self,
+        payload: &NotificationPayload,
+    ) -> Result<Vec<String>> {
+        let mut channel_ids: Vec<String> = self
+            .get_matching_subscriptions(payload)
+            .await?
+            .into_iter()
+            .map(|s| s.channel_id)
+            .collect();
+        channel_ids.sort();
+        channel_ids.dedup();
+        Ok(channel_ids)
+    }
+
     /// Get subscriptions matching the event type and optionally app ID
     async fn get_matching_subscriptions(
         &self,
@@ -229,7 +361,10 @@ impl NotificationService {
     }
 
     /// Get an enabled channel by ID
-    async fn get_enabled_channel(&self, channel_id: &str) -> Result<Option<NotificationChannel>> {
+    pub(crate) async fn get_enabled_channel(
+        &self,
+        channel_id: &str,
+    ) -> Result<Option<NotificationChannel>> {
         let channel = sqlx::query_as::<_, NotificationChannel>(
             "SELECT * FROM notification_channels WHERE id = ? AND enabled = 1",
         )
@@ -240,11 +375,31 @@ impl NotificationService {
         Ok(channel)
     }
 
-    /// Send notification to a specific channel
-    async fn send_to_channel(
+    /// Send notification to a specific channel. Email recipients who are
+    /// users get the event according to their notification preferences.
+    pub(crate) async fn send_to_channel(
+        &self,
+        channel: &NotificationChannel,
+        payload: &NotificationPayload,
+    ) -> Result<()> {
+        self.deliver_to_channel(channel, payload, true).await
+    }
+
+    /// Send a notification digest's summary to a channel. Every recipient gets
+    /// it: the digest was set up for them explicitly.
+    pub(crate) async fn send_digest(
+        &self,
+        channel: &NotificationChannel,
+        payload: &NotificationPayload,
+    ) -> Result<()> {
+        self.deliver_to_channel(channel, payload, false).await
+    }
+
+    async fn deliver_to_channel(
         &self,
         channel: &NotificationChannel,
         payload: &NotificationPayload,
+        apply_preferences: bool,
     ) -> Result<()> {
         match channel.get_channel_type() {
             NotificationChannelType::Slack => {
@@ -268,8 +423,13 @@ impl NotificationService {
                 }
             }
             NotificationChannelType::Email => {
-                if let Some(config) = channel.get_email_config() {
-                    self.send_email(&config, payload).await?;
+                if let Some(mut config) = channel.get_email_config() {
+                    config.to_addresses = self
+                        .recipients(config.to_addresses, payload, apply_preferences)
+                        .await;
+                    if !config.to_addresses.is_empty() {
+                        self.send_email(&channel.id, &config, payload).await?;
+                    }
                 } else {
                     tracing::warn!(
                         channel_id = %channel.id,
@@ -279,7 +439,7 @@ impl NotificationService {
             }
             NotificationChannelType::Webhook => {
                 if let Some(config) = channel.get_webhook_config() {
-                    self.send_webhook(&config, payload).await?;
+                    self.send_webhook(&channel.id, &config, payload).await?;
                 } else {
                     tracing::warn!(
                         channel_id = %channel.id,
@@ -358,8 +518,13 @@ impl NotificationService {
                 }
             }
             NotificationChannelType::Resend => {
-                if let Some(config) = channel.get_resend_config() {
-                    resend::send_resend(&self.http_client, &config, payload).await?;
+                if let Some(mut config) = channel.get_resend_config() {
+                    config.to_addresses = self
+                        .recipients(config.to_addresses, payload, apply_preferences)
+                        .await;
+                    if !config.to_addresses.is_empty() {
+                        resend::send_resend(&self.http_client, &config, payload).await?;
+                    }
                 } else {
                     tracing::warn!(
                         channel_id = %channel.id,
@@ -368,8 +533,13 @@ impl NotificationService {
                 }
             }
             NotificationChannelType::Sendry => {
-                if let Some(config) = channel.get_sendry_config() {
-                    sendry::send_sendry(&self.http_client, &config, payload).await?;
+                if let Some(mut config) = channel.get_sendry_config() {
+                    config.to_addresses = self
+                        .recipients(config.to_addresses, payload, apply_preferences)
+                        .await;
+                    if !config.to_addresses.is_empty() {
+                        sendry::send_sendry(&self.http_client, &config, payload).await?;
+                    }
                 } else {
                     tracing::warn!(
                         channel_id = %channel.id,
@@ -417,6 +587,14 @@ impl NotificationService {
             }));
         }
 
+        for (label, value) in payload.commit_fields() {
+            fields.push(json!({
+                "title": label,
+                "value": value,
+                "short": label != "Commit"
+            }));
+        }
+
         if let Some(ref error) = payload.error_message {
             fields.push(json!({
                 "title": "Error",
@@ -473,6 +651,14 @@ impl NotificationService {
             }));
         }
 
+        for (label, value) in payload.commit_fields() {
+            fields.push(json!({
+                "name": label,
+                "value": value,
+                "inline": label != "Commit"
+            }));
+        }
+
         if let Some(ref error) = payload.error_message {
             fields.push(json!({
                 "name": "Error",
@@ -508,14 +694,19 @@ impl NotificationService {
         Ok(())
     }
 
-    /// Send an email notification
+    /// Queue an email notification to each of the channel's recipients. The
+    /// email queue sends it with the channel's SMTP settings.
     pub async fn send_email(
         &self,
+        channel_id: &str,
         config: &EmailConfig,
         payload: &NotificationPayload,
     ) -> Result<()> {
-        let from: Mailbox = config.from_address.parse()?;
+        mail_queue::enqueue(&self.db, channel_id, &Self::emails(config, payload)).await
+    }
 
+    /// The email notification for each of the channel's recipients
+    fn emails(config: &EmailConfig, payload: &NotificationPayload) -> Vec<mail_queue::Email> {
         // Build HTML content
         let html_body = format!(
             r#"
@@ -551,6 +742,7 @@ impl NotificationService {
                         </div>
                         {}
                         {}
+                        {}
                     </div>
                     <div class="footer">
                         Rivetr Deployment Engine
@@ -572,6 +764,7 @@ impl NotificationService {
                     id
                 ))
                 .unwrap_or_default(),
+            payload.commit_fields_html(),
             payload
                 .error_message
                 .as_ref()
@@ -581,7 +774,7 @@ impl NotificationService {
 
         // Build plain text version
         let text_body = format!(
-            "{}\n\n{}\n\nApplication: {}\nStatus: {}{}{}\n\n---\nRivetr Deployment Engine",
+            "{}\n\n{}\n\nApplication: {}\nStatus: {}{}{}{}\n\n---\nRivetr Deployment Engine",
             payload.title(),
             payload.message,
             payload.app_name,
@@ -591,6 +784,11 @@ impl NotificationService {
                 .as_ref()
                 .map(|id| format!("\nDeployment ID: {}", id))
                 .unwrap_or_default(),
+            payload
+                .commit_fields()
+                .iter()
+                .map(|(label, value)| format!("\n{}: {}", label, value))
+                .collect::<String>(),
             payload
                 .error_message
                 .as_ref()
@@ -598,76 +796,125 @@ impl NotificationService {
                 .unwrap_or_default(),
         );
 
-        for to_address in &config.to_addresses {
-            let to: Mailbox = to_address.parse()?;
-
-            let email = Message::builder()
-                .from(from.clone())
-                .to(to)
-                .subject(payload.title())
-                .multipart(
-                    MultiPart::alternative()
-                        .singlepart(
-                            SinglePart::builder()
-                                .header(ContentType::TEXT_PLAIN)
-                                .body(text_body.clone()),
-                        )
-                        .singlepart(
-                            SinglePart::builder()
-                                .header(ContentType::TEXT_HTML)
-                                .body(html_body.clone()),
-                        ),
-                )?;
-
-            // Build SMTP transport
-            let mailer = if config.smtp_tls {
-                AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
-            } else {
-                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
-            }
-            .port(config.smtp_port);
-
-            let mailer = if let (Some(username), Some(password)) =
-                (&config.smtp_username, &config.smtp_password)
-            {
-                mailer.credentials(Credentials::new(username.clone(), password.clone()))
-            } else {
-                mailer
-            };
-
-            mailer.build().send(email).await?;
-        }
-
-        Ok(())
+        config
+            .to_addresses
+            .iter()
+            .map(|to| mail_queue::Email {
+                from: config.from_address.clone(),
+                to: to.clone(),
+                subject: payload.title(),
+                html_body: html_body.clone(),
+                text_body: text_body.clone(),
+            })
+            .collect()
     }
 
-    /// Send a generic webhook notification
+    /// Send a generic webhook notification and add it to the channel's
+    /// delivery log
     pub async fn send_webhook(
         &self,
+        channel_id: &str,
         config: &WebhookConfig,
         payload: &NotificationPayload,
     ) -> Result<()> {
-        // Build the payload based on template type
-        let body = match config.payload_template.as_str() {
+        // Serialize body to bytes so we can optionally sign it
+        let body_bytes = serde_json::to_vec(&Self::webhook_body(config, payload))?;
+
+        // Build the request with custom headers
+        let client = reqwest::Client::new();
+        let mut request = client
+            .post(&config.url)
+            .header("Content-Type", "application/json")
+            .header("X-Rivetr-Event", payload.event_type.to_string())
+            .body(body_bytes.clone());
+
+        // Sign the body if a secret is configured
+        if let Some(ref secret) = config.webhook_secret {
+            request = request.header(
+                "X-Rivetr-Signature",
+                Self::webhook_signature(secret, &body_bytes),
+            );
+        }
+
+        // Add custom headers
+        for (key, value) in &config.headers {
+            request = request.header(key, value);
+        }
+
+        // Send the request
+        let started = std::time::Instant::now();
+        let result = match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(response.status()),
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                tracing::warn!(
+                    url = %config.url,
+                    status = %status,
+                    response_body = %body,
+                    "Webhook request failed"
+                );
+                Err((
+                    Some(status),
+                    format!("Webhook request failed with status {}: {}", status, body),
+                ))
+            }
+            Err(e) => Err((None, e.to_string())),
+        };
+
+        let (status, error) = match &result {
+            Ok(status) => (Some(*status), None),
+            Err((status, error)) => (*status, Some(error.as_str())),
+        };
+        webhook_log::record(
+            &self.db,
+            channel_id,
+            &config.url,
+            payload,
+            status.map(|s| s.as_u16()),
+            error,
+            started.elapsed(),
+        )
+        .await;
+
+        result
+            .map(|_| ())
+            .map_err(|(_, error)| anyhow::anyhow!(error))
+    }
+
+    /// The request body for a webhook channel, in the shape of its payload
+    /// template
+    fn webhook_body(config: &WebhookConfig, payload: &NotificationPayload) -> serde_json::Value {
+        let commit = payload.commit.as_ref();
+
+        match config.payload_template.as_str() {
             "slack" => {
                 // Slack-compatible format
+                let mut fields = vec![
+                    json!({
+                        "title": "Application",
+                        "value": &payload.app_name,
+                        "short": true
+                    }),
+                    json!({
+                        "title": "Status",
+                        "value": &payload.status,
+                        "short": true
+                    }),
+                ];
+                for (label, value) in payload.commit_fields() {
+                    fields.push(json!({
+                        "title": label,
+                        "value": value,
+                        "short": label != "Commit"
+                    }));
+                }
                 json!({
                     "attachments": [{
                         "color": payload.color(),
                         "title": payload.title(),
                         "text": &payload.message,
-                        "fields": [
-                            {
-                                "title": "Application",
-                                "value": &payload.app_name,
-                                "short": true
-                            },
-                            {
-                                "title": "Status",
-                                "value": &payload.status,
-                                "short": true
-                            }
-                        ],
+                        "fields": fields,
                         "footer": "Rivetr Deployment Engine",
                         "ts": chrono::Utc::now().timestamp()
                     }]
@@ -676,6 +923,25 @@ impl NotificationService {
             "discord" => {
                 // Discord-compatible format
                 let color_str = payload.color();
+                let mut fields = vec![
+                    json!({
+                        "name": "Application",
+                        "value": &payload.app_name,
+                        "inline": true
+                    }),
+                    json!({
+                        "name": "Status",
+                        "value": &payload.status,
+                        "inline": true
+                    }),
+                ];
+                for (label, value) in payload.commit_fields() {
+                    fields.push(json!({
+                        "name": label,
+                        "value": value,
+                        "inline": label != "Commit"
+                    }));
+                }
                 json!({
                     "embeds": [{
                         "color": match color_str {
@@ -686,18 +952,7 @@ impl NotificationService {
                         },
                         "title": payload.title(),
                         "description": &payload.message,
-                        "fields": [
-                            {
-                                "name": "Application",
-                                "value": &payload.app_name,
-                                "inline": true
-                            },
-                            {
-                                "name": "Status",
-                                "value": &payload.status,
-                                "inline": true
-                            }
-                        ],
+                        "fields": fields,
                         "footer": {
                             "text": "Rivetr Deployment Engine"
                         },
@@ -716,7 +971,27 @@ impl NotificationService {
                         .replace("{{timestamp}}", &payload.timestamp)
                         .replace("{{severity}}", &payload.status)
                         .replace("{{message}}", &payload.message)
-                        .replace("{{event_type}}", &payload.event_type.to_string());
+                        .replace("{{event_type}}", &payload.event_type.to_string())
+                        .replace(
+                            "{{commit_sha}}",
+                            commit.and_then(|c| c.sha.as_deref()).unwrap_or(""),
+                        )
+                        .replace(
+                            "{{commit_message}}",
+                            commit.and_then(|c| c.headline()).unwrap_or(""),
+                        )
+                        .replace(
+                            "{{commit_author}}",
+                            commit.and_then(|c| c.author.as_deref()).unwrap_or(""),
+                        )
+                        .replace(
+                            "{{branch}}",
+                            commit.and_then(|c| c.branch.as_deref()).unwrap_or(""),
+                        )
+                        .replace(
+                            "{{compare_url}}",
+                            commit.and_then(|c| c.compare_url.as_deref()).unwrap_or(""),
+                        );
 
                     // Try to parse as JSON, if it fails, wrap in a message object
                     serde_json::from_str(&result).unwrap_or_else(|_| json!({"message": result}))
@@ -741,56 +1016,23 @@ impl NotificationService {
                     "status": &payload.status,
                     "message": &payload.message,
                     "error_message": &payload.error_message,
+                    "commit": commit,
                     "timestamp": &payload.timestamp
                 })
             }
-        };
-
-        // Serialize body to bytes so we can optionally sign it
-        let body_bytes = serde_json::to_vec(&body)?;
-
-        // Build the request with custom headers
-        let client = reqwest::Client::new();
-        let mut request = client
-            .post(&config.url)
-            .header("Content-Type", "application/json")
-            .body(body_bytes.clone());
-
-        // Compute HMAC-SHA256 signature if a secret is configured
-        if let Some(ref secret) = config.webhook_secret {
-            use hmac::{Hmac, Mac};
-            use sha2::Sha256;
-            type HmacSha256 = Hmac<Sha256>;
-
-            if let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) {
-                mac.update(&body_bytes);
-                let result = mac.finalize();
-                let hex_sig = hex::encode(result.into_bytes());
-                request = request.header("X-Rivetr-Signature", format!("sha256={}", hex_sig));
-            }
         }
+    }
 
-        // Add custom headers
-        for (key, value) in &config.headers {
-            request = request.header(key, value);
-        }
+    /// `X-Rivetr-Signature` value for a webhook body: its HMAC-SHA256 under
+    /// the channel's secret, as `sha256=<hex_digest>`
+    fn webhook_signature(secret: &str, body: &[u8]) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
 
-        // Send the request
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            tracing::warn!(
-                url = %config.url,
-                status = %status,
-                response_body = %body,
-                "Webhook request failed"
-            );
-            anyhow::bail!("Webhook request failed with status {}: {}", status, body);
-        }
-
-        Ok(())
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
     }
 
     /// Send a test notification
@@ -811,8 +1053,190 @@ impl NotificationService {
             message: test_message,
             error_message: None,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            commit: None,
         };
 
-        self.send_to_channel(channel, &payload).await
+        // Test emails skip the queue so SMTP errors are reported straight away
+        if channel.get_channel_type() == NotificationChannelType::Email {
+            let config = channel
+                .get_email_config()
+                .ok_or_else(|| anyhow::anyhow!("Invalid Email config"))?;
+            let server = mail_queue::SmtpServer::from_channel(&config);
+            for email in Self::emails(&config, &payload) {
+                mail_queue::send_now(&server, &email).await?;
+            }
+            return Ok(());
+        }
+
+        // Tests go to every recipient, whatever their preferences
+        self.deliver_to_channel(channel, &payload, false).await
+    }
+
+    /// Email recipients who get the event now: users who muted its severity
+    /// are dropped and users on digests have it held for their next digest
+    async fn recipients(
+        &self,
+        to_addresses: Vec<String>,
+        payload: &NotificationPayload,
+        apply_preferences: bool,
+    ) -> Vec<String> {
+        if !apply_preferences {
+            return to_addresses;
+        }
+        digest::route_recipients(&self.db, to_addresses, payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_pool, WebhookDelivery};
+    use axum::{body::Bytes, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use tokio::sync::mpsc;
+
+    fn payload() -> NotificationPayload {
+        NotificationPayload {
+            event_type: NotificationEventType::DeploymentSuccess,
+            app_id: "app-1".to_string(),
+            app_name: "web".to_string(),
+            deployment_id: Some("dep-1".to_string()),
+            status: "running".to_string(),
+            message: "Deployed".to_string(),
+            error_message: None,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            commit: Some(CommitMetadata {
+                sha: Some("abc123".to_string()),
+                branch: Some("main".to_string()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn config(url: &str, template: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            headers: Default::default(),
+            payload_template: template.to_string(),
+            custom_template: None,
+            webhook_secret: None,
+        }
+    }
+
+    #[test]
+    fn test_rolled_back_event() {
+        let event: NotificationEventType = "deployment_rolled_back".parse().unwrap();
+        assert_eq!(event, NotificationEventType::DeploymentRolledBack);
+        assert_eq!(event.to_string(), "deployment_rolled_back");
+
+        let mut payload = payload();
+        payload.event_type = event;
+        assert_eq!(payload.title(), "Deployment Rolled Back: web");
+        assert_eq!(payload.color(), "#f39c12");
+        assert_eq!(payload.emoji(), ":rewind:");
+    }
+
+    #[test]
+    fn test_default_webhook_body() {
+        let body = NotificationService::webhook_body(&config("https://x", "json"), &payload());
+        assert_eq!(body["event_type"], "deployment_success");
+        assert_eq!(body["app_id"], "app-1");
+        assert_eq!(body["app_name"], "web");
+        assert_eq!(body["deployment_id"], "dep-1");
+        assert_eq!(body["status"], "running");
+        assert_eq!(body["message"], "Deployed");
+        assert!(body["error_message"].is_null());
+        assert_eq!(body["commit"]["sha"], "abc123");
+        assert_eq!(body["commit"]["branch"], "main");
+        assert_eq!(body["timestamp"], "2026-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_custom_webhook_body() {
+        let mut config = config("https://x", "custom");
+        config.custom_template =
+            Some(r#"{"text": "{{app_name}} {{event_type}} on {{branch}}"}"#.to_string());
+        let body = NotificationService::webhook_body(&config, &payload());
+        assert_eq!(body, json!({"text": "web deployment_success on main"}));
+
+        // Templates that aren't JSON are wrapped in a message
+        config.custom_template = Some("{{app_name}} is {{value}}".to_string());
+        let body = NotificationService::webhook_body(&config, &payload());
+        assert_eq!(body, json!({"message": "web is running"}));
+    }
+
+    #[test]
+    fn test_webhook_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            NotificationService::webhook_signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_webhook_signs_and_logs_deliveries() {
+        let (_dir, db) = test_pool().await;
+        let channel_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO notification_channels (id, name, channel_type, config) \
+             VALUES (?, 'hooks', 'webhook', '{}')",
+        )
+        .bind(&channel_id)
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<(HeaderMap, Bytes)>();
+        let router = Router::new()
+            .route(
+                "/ok",
+                post(move |headers: HeaderMap, body: Bytes| async move {
+                    tx.send((headers, body)).unwrap();
+                    StatusCode::NO_CONTENT
+                }),
+            )
+            .route(
+                "/fail",
+                post(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "down") }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let service = NotificationService::new(db.clone());
+        let mut ok = config(&format!("http://{}/ok", addr), "json");
+        ok.webhook_secret = Some("s3cret".to_string());
+        service
+            .send_webhook(&channel_id, &ok, &payload())
+            .await
+            .unwrap();
+
+        let (headers, body) = rx.recv().await.unwrap();
+        assert_eq!(headers["x-rivetr-event"], "deployment_success");
+        assert_eq!(
+            headers["x-rivetr-signature"],
+            NotificationService::webhook_signature("s3cret", &body).as_str()
+        );
+        let sent: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(sent["deployment_id"], "dep-1");
+
+        let fail = config(&format!("http://{}/fail", addr), "json");
+        let err = service
+            .send_webhook(&channel_id, &fail, &payload())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("500"));
+
+        let deliveries = WebhookDelivery::list_for_channel(&db, &channel_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].url, fail.url);
+        assert_eq!(deliveries[0].status_code, Some(500));
+        assert_eq!(deliveries[0].success, 0);
+        assert_eq!(deliveries[1].url, ok.url);
+        assert_eq!(deliveries[1].status_code, Some(204));
+        assert_eq!(deliveries[1].success, 1);
+        assert_eq!(deliveries[1].deployment_id.as_deref(), Some("dep-1"));
     }
 }
@@ -0,0 +1,249 @@
+//! Notification outbox.
+//!
+//! Instead of sending notifications inline, event producers write them to the
+//! `notification_outbox` table, inside the transaction of the state change
+//! that caused them where there is one. A background dispatcher delivers each
+//! event to its subscribed channels and retries failed channels with
+//! exponential backoff, so an event survives a crash or a channel outage.
+//! Channels an event already reached are recorded on the row, so a retry only
+//! goes to the ones that failed (delivery is at-least-once only for a crash
+//! mid-send). Each event is also copied to the in-app inbox of the users who
+//! can see its app (see [`super::inbox`]).
+
+use std::time::Duration;
+
+use anyhow::Result;
+use sqlx::{FromRow, Sqlite};
+use tokio::time::interval;
+
+use super::{NotificationPayload, NotificationService};
+use crate::DbPool;
+
+/// How often the dispatcher looks for due events
+const DISPATCH_INTERVAL_SECS: u64 = 5;
+/// Events delivered per dispatch cycle
+const DISPATCH_BATCH_SIZE: i64 = 50;
+/// Attempts before an event is given up as failed
+pub const MAX_ATTEMPTS: i64 = 8;
+/// Delay before the first retry; doubled on every further attempt
+const RETRY_BASE_SECS: i64 = 30;
+/// Upper bound on the delay between retries
+const RETRY_MAX_SECS: i64 = 3600;
+/// Days delivered and failed events are kept for inspection (a little over a
+/// week, so weekly notification digests can still count a week's crashes)
+const RETENTION_DAYS: i64 = 8;
+
+/// An event waiting in (or done with) the outbox
+#[derive(Debug, Clone, FromRow)]
+struct OutboxEvent {
+    id: String,
+    payload: String,
+    attempts: i64,
+    delivered_channels: String,
+}
+
+/// Write a notification event to the outbox. Pass a transaction to commit the
+/// event together with the state change it reports.
+pub async fn enqueue<'e, E>(executor: E, payload: &NotificationPayload) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        "INSERT INTO notification_outbox (id, event_type, app_id, payload) VALUES (?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(payload.event_type.to_string())
+    .bind(&payload.app_id)
+    .bind(serde_json::to_string(payload)?)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Delay before retry number `attempts` (1-based)
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (RETRY_BASE_SECS * 2_i64.pow(exponent)).min(RETRY_MAX_SECS)
+}
+
+/// Deliver one event to the subscribed channels it hasn't reached yet and
+/// record the outcome
+async fn deliver(db: &DbPool, service: &NotificationService, event: OutboxEvent) -> Result<()> {
+    let payload: NotificationPayload = match serde_json::from_str(&event.payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            // Unreadable payloads can't succeed on retry
+            sqlx::query(
+                "UPDATE notification_outbox SET status = 'failed', last_error = ? WHERE id = ?",
+            )
+            .bind(format!("Invalid payload: {}", e))
+            .bind(&event.id)
+            .execute(db)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    // The in-app inbox is filled on the first attempt; channel retries
+    // don't need it again
+    if event.attempts == 0 {
+        if let Err(e) = super::inbox::record(db, &event.id, &payload).await {
+            tracing::warn!(
+                event_id = %event.id,
+                error = %e,
+                "Failed to add notification to inboxes"
+            );
+        }
+    }
+
+    let mut delivered: Vec<String> =
+        serde_json::from_str(&event.delivered_channels).unwrap_or_default();
+    let mut errors = Vec::new();
+    for channel_id in service.subscribed_channel_ids(&payload).await? {
+        if delivered.contains(&channel_id) {
+            continue;
+        }
+        // Channels deleted or disabled since the event was written are skipped
+        let Some(channel) = service.get_enabled_channel(&channel_id).await? else {
+            continue;
+        };
+        match service.send_to_channel(&channel, &payload).await {
+            Ok(()) => {
+                delivered.push(channel_id);
+                // Persist each delivery right away so a crash doesn't resend it
+                sqlx::query("UPDATE notification_outbox SET delivered_channels = ? WHERE id = ?")
+                    .bind(serde_json::to_string(&delivered)?)
+                    .bind(&event.id)
+                    .execute(db)
+                    .await?;
+            }
+            Err(e) => errors.push(format!("{}: {}", channel.name, e)),
+        }
+    }
+
+    if errors.is_empty() {
+        sqlx::query(
+            "UPDATE notification_outbox SET status = 'delivered', last_error = NULL, \
+             delivered_at = datetime('now') WHERE id = ?",
+        )
+        .bind(&event.id)
+        .execute(db)
+        .await?;
+        return Ok(());
+    }
+
+    let attempts = event.attempts + 1;
+    let last_error = errors.join("; ");
+    if attempts >= MAX_ATTEMPTS {
+        tracing::error!(
+            event_id = %event.id,
+            event_type = %payload.event_type,
+            error = %last_error,
+            "Giving up on notification after {} attempts",
+            attempts
+        );
+        sqlx::query(
+            "UPDATE notification_outbox SET status = 'failed', attempts = ?, last_error = ? \
+             WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(&last_error)
+        .bind(&event.id)
+        .execute(db)
+        .await?;
+    } else {
+        let delay = retry_delay_secs(attempts);
+        tracing::warn!(
+            event_id = %event.id,
+            event_type = %payload.event_type,
+            error = %last_error,
+            "Notification delivery failed, retrying in {}s",
+            delay
+        );
+        sqlx::query(
+            "UPDATE notification_outbox SET attempts = ?, last_error = ?, \
+             next_attempt_at = datetime('now', ?) WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(&last_error)
+        .bind(format!("+{} seconds", delay))
+        .bind(&event.id)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Deliver the events that are due, oldest first, and prune old finished ones
+async fn dispatch_cycle(db: &DbPool, service: &NotificationService) {
+    let due: Vec<OutboxEvent> = match sqlx::query_as(
+        "SELECT id, payload, attempts, delivered_channels FROM notification_outbox \
+         WHERE status = 'pending' AND next_attempt_at <= datetime('now') \
+         ORDER BY created_at ASC LIMIT ?",
+    )
+    .bind(DISPATCH_BATCH_SIZE)
+    .fetch_all(db)
+    .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to load pending notifications");
+            return;
+        }
+    };
+
+    for event in due {
+        let event_id = event.id.clone();
+        if let Err(e) = deliver(db, service, event).await {
+            tracing::error!(event_id = %event_id, error = %e, "Failed to dispatch notification");
+        }
+    }
+
+    let _ = sqlx::query(
+        "DELETE FROM notification_outbox WHERE status != 'pending' \
+         AND created_at < datetime('now', ?)",
+    )
+    .bind(format!("-{} days", RETENTION_DAYS))
+    .execute(db)
+    .await;
+    super::inbox::prune(db).await;
+    super::webhook_log::prune(db).await;
+}
+
+/// Spawn the background task delivering outbox events. Events left pending
+/// by a previous run are picked up on its first cycle.
+pub fn spawn_outbox_dispatcher(db: DbPool) {
+    tracing::info!(
+        "Starting notification outbox dispatcher ({}s interval)",
+        DISPATCH_INTERVAL_SECS
+    );
+
+    tokio::spawn(async move {
+        let service = NotificationService::new(db.clone());
+        let mut tick = interval(Duration::from_secs(DISPATCH_INTERVAL_SECS));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded(
+                "notification_outbox_dispatcher",
+                dispatch_cycle(&db, &service),
+            )
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(2), 60);
+        assert_eq!(retry_delay_secs(3), 120);
+        assert_eq!(retry_delay_secs(7), 1920);
+        assert_eq!(retry_delay_secs(40), RETRY_MAX_SECS);
+    }
+}
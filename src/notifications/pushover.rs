@@ -64,6 +64,10 @@ fn format_pushover_message(payload: &NotificationPayload) -> String {
         msg.push_str(&format!("\nDeployment ID: {}", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        msg.push_str(&format!("\n{}: {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         msg.push_str(&format!("\n\nError: {}", error));
     }
@@ -87,6 +91,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_pushover_message(&payload);
@@ -107,6 +112,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_pushover_message(&payload);
@@ -124,6 +130,7 @@ mod tests {
             message: "App has been stopped".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_pushover_message(&payload);
@@ -87,7 +87,7 @@ fn build_html_body(payload: &NotificationPayload) -> String {
                 <span class="field-label">Status:</span>
                 <span class="field-value">{}</span>
             </div>
-            {}{}
+            {}{}{}
         </div>
         <div class="footer">
             Rivetr Deployment Engine
@@ -108,6 +108,7 @@ fn build_html_body(payload: &NotificationPayload) -> String {
                 id
             ))
             .unwrap_or_default(),
+        payload.commit_fields_html(),
         payload
             .error_message
             .as_ref()
@@ -130,6 +131,10 @@ fn build_text_body(payload: &NotificationPayload) -> String {
         body.push_str(&format!("\nDeployment ID: {}", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        body.push_str(&format!("\n{}: {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         body.push_str(&format!("\n\nError: {}", error));
     }
@@ -155,6 +160,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let body = build_text_body(&payload);
@@ -176,6 +182,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let body = build_text_body(&payload);
@@ -194,6 +201,7 @@ mod tests {
             message: "App has been stopped".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let html = build_html_body(&payload);
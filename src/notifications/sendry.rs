@@ -131,7 +131,7 @@ fn build_html_body(payload: &NotificationPayload) -> String {
                 <span class="field-label">Status:</span>
                 <span class="field-value">{}</span>
             </div>
-            {}{}
+            {}{}{}
         </div>
         <div class="footer">
             Rivetr Deployment Engine
@@ -152,6 +152,7 @@ fn build_html_body(payload: &NotificationPayload) -> String {
                 id
             ))
             .unwrap_or_default(),
+        payload.commit_fields_html(),
         payload
             .error_message
             .as_ref()
@@ -174,6 +175,10 @@ fn build_text_body(payload: &NotificationPayload) -> String {
         body.push_str(&format!("\nDeployment ID: {}", deployment_id));
     }
 
+    for (label, value) in payload.commit_fields() {
+        body.push_str(&format!("\n{}: {}", label, value));
+    }
+
     if let Some(ref error) = payload.error_message {
         body.push_str(&format!("\n\nError: {}", error));
     }
@@ -199,6 +204,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let body = build_text_body(&payload);
@@ -220,6 +226,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let body = build_text_body(&payload);
@@ -238,6 +245,7 @@ mod tests {
             message: "App has been stopped".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let html = build_html_body(&payload);
@@ -0,0 +1,393 @@
+//! Notification digests: periodic summaries instead of per-event pings.
+//!
+//! Each enabled digest is sent on its daily or weekly schedule (in its
+//! timezone) to its notification channels and email addresses. The summary
+//! covers the period since the digest was last sent: deployment counts, the
+//! slowest builds, containers that crashed, and TLS certificates that expire
+//! soon. A team digest only covers the team's apps; certificates aren't tied
+//! to a team and are only listed in instance-wide digests.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::time::interval;
+
+use super::{NotificationPayload, NotificationService, SystemEmailService};
+use crate::config::EmailConfig;
+use crate::db::{NotificationDigest, NotificationEventType};
+use crate::utils::cron;
+use crate::DbPool;
+
+/// How often the sender looks for due digests
+const CHECK_INTERVAL_SECS: u64 = 300;
+/// Slowest builds listed per digest
+const SLOWEST_BUILDS_LISTED: i64 = 5;
+/// Apps with crashes listed per digest
+const CRASHED_APPS_LISTED: i64 = 10;
+/// Certificates expiring within this many days of the send time are listed
+const CERT_EXPIRY_WINDOW_DAYS: i64 = 14;
+
+/// A build and how long it took
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SlowBuild {
+    pub app_name: String,
+    pub deployment_id: String,
+    pub duration_secs: i64,
+}
+
+/// An app and how many crash notifications it raised
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CrashedApp {
+    pub app_name: String,
+    pub crashes: i64,
+}
+
+/// A TLS certificate close to expiry
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiringCertificate {
+    pub domain: String,
+    pub expires_at: String,
+    pub days_left: i64,
+}
+
+/// What happened over a digest's period
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestSummary {
+    pub period_start: String,
+    pub period_end: String,
+    pub deployments: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    pub slowest_builds: Vec<SlowBuild>,
+    pub crashed_apps: Vec<CrashedApp>,
+    pub expiring_certificates: Vec<ExpiringCertificate>,
+}
+
+impl DigestSummary {
+    /// Plain-text summary, used as the notification message
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Deployments: {} ({} succeeded, {} failed)",
+            self.deployments, self.succeeded, self.failed
+        );
+        if !self.slowest_builds.is_empty() {
+            text.push_str("\n\nSlowest builds:");
+            for build in &self.slowest_builds {
+                text.push_str(&format!(
+                    "\n- {}: {}",
+                    build.app_name,
+                    format_duration(build.duration_secs)
+                ));
+            }
+        }
+        if self.crashed_apps.is_empty() {
+            text.push_str("\n\nNo container crashes.");
+        } else {
+            text.push_str("\n\nContainer crashes:");
+            for app in &self.crashed_apps {
+                text.push_str(&format!("\n- {}: {}", app.app_name, app.crashes));
+            }
+        }
+        if !self.expiring_certificates.is_empty() {
+            text.push_str("\n\nCertificates expiring soon:");
+            for cert in &self.expiring_certificates {
+                text.push_str(&format!(
+                    "\n- {}: {} day{} left",
+                    cert.domain,
+                    cert.days_left,
+                    if cert.days_left == 1 { "" } else { "s" }
+                ));
+            }
+        }
+        text
+    }
+}
+
+/// "1h 2m", "3m 4s" or "5s"
+pub(crate) fn format_duration(secs: i64) -> String {
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Summarize `since..until` for the apps of `team_id` (all apps when `None`).
+/// Certificates are read from `acme_cache_dir` for instance-wide summaries.
+pub async fn collect_summary(
+    db: &DbPool,
+    team_id: Option<&str>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    acme_cache_dir: Option<&Path>,
+) -> Result<DigestSummary> {
+    let since_str = since.to_rfc3339();
+    let until_str = until.to_rfc3339();
+
+    let (deployments, succeeded, failed): (i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*),
+               COALESCE(SUM(CASE WHEN d.built_at IS NOT NULL AND d.status != 'failed' THEN 1 ELSE 0 END), 0),
+               COALESCE(SUM(CASE WHEN d.status = 'failed' THEN 1 ELSE 0 END), 0)
+        FROM deployments d JOIN apps a ON a.id = d.app_id
+        WHERE datetime(d.started_at) >= datetime(?) AND datetime(d.started_at) < datetime(?)
+          AND (? IS NULL OR a.team_id = ?)
+        "#,
+    )
+    .bind(&since_str)
+    .bind(&until_str)
+    .bind(team_id)
+    .bind(team_id)
+    .fetch_one(db)
+    .await?;
+
+    let slowest_builds: Vec<SlowBuild> = sqlx::query_as(
+        r#"
+        SELECT a.name AS app_name, d.id AS deployment_id,
+               CAST((julianday(d.built_at) - julianday(d.started_at)) * 86400 AS INTEGER)
+                   AS duration_secs
+        FROM deployments d JOIN apps a ON a.id = d.app_id
+        WHERE d.built_at IS NOT NULL
+          AND datetime(d.started_at) >= datetime(?) AND datetime(d.started_at) < datetime(?)
+          AND (? IS NULL OR a.team_id = ?)
+        ORDER BY duration_secs DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&since_str)
+    .bind(&until_str)
+    .bind(team_id)
+    .bind(team_id)
+    .bind(SLOWEST_BUILDS_LISTED)
+    .fetch_all(db)
+    .await?;
+
+    // Crash notifications go through the outbox whether or not a channel is
+    // subscribed, so it doubles as the crash history
+    let crashed_apps: Vec<CrashedApp> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(a.name, json_extract(o.payload, '$.app_name')) AS app_name,
+               COUNT(*) AS crashes
+        FROM notification_outbox o LEFT JOIN apps a ON a.id = o.app_id
+        WHERE o.event_type = 'container_crash'
+          AND datetime(o.created_at) >= datetime(?) AND datetime(o.created_at) < datetime(?)
+          AND (? IS NULL OR a.team_id = ?)
+        GROUP BY o.app_id
+        ORDER BY crashes DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&since_str)
+    .bind(&until_str)
+    .bind(team_id)
+    .bind(team_id)
+    .bind(CRASHED_APPS_LISTED)
+    .fetch_all(db)
+    .await?;
+
+    let mut expiring_certificates = Vec::new();
+    if let (None, Some(dir)) = (team_id, acme_cache_dir) {
+        let horizon = until + chrono::Duration::days(CERT_EXPIRY_WINDOW_DAYS);
+        for (domain, expiry) in crate::proxy::acme::certificate_expiries(dir).await {
+            if expiry > horizon {
+                break;
+            }
+            expiring_certificates.push(ExpiringCertificate {
+                domain,
+                expires_at: expiry.to_rfc3339(),
+                days_left: (expiry - until).num_days().max(0),
+            });
+        }
+    }
+
+    Ok(DigestSummary {
+        period_start: since_str,
+        period_end: until_str,
+        deployments,
+        succeeded,
+        failed,
+        slowest_builds,
+        crashed_apps,
+        expiring_certificates,
+    })
+}
+
+/// Start of the period a digest's next summary covers: its last send, or one
+/// period back if it was never sent
+pub fn period_start(digest: &NotificationDigest, until: DateTime<Utc>) -> DateTime<Utc> {
+    digest
+        .last_sent_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| until - digest.period())
+}
+
+/// Summarize the period since the digest was last sent and send it to its
+/// channels and email addresses, then advance its schedule
+pub async fn send_digest(
+    db: &DbPool,
+    digest: &NotificationDigest,
+    email_config: &EmailConfig,
+    acme_cache_dir: Option<&Path>,
+) -> Result<DigestSummary> {
+    let until = Utc::now();
+    let since = period_start(digest, until);
+    let summary =
+        collect_summary(db, digest.team_id.as_deref(), since, until, acme_cache_dir).await?;
+
+    let payload = NotificationPayload {
+        event_type: NotificationEventType::Digest,
+        app_id: String::new(),
+        app_name: digest.name.clone(),
+        deployment_id: None,
+        status: digest.frequency.clone(),
+        message: summary.to_text(),
+        error_message: None,
+        timestamp: until.to_rfc3339(),
+        commit: None,
+    };
+
+    let service = NotificationService::new(db.clone());
+    for channel_id in digest.channel_ids() {
+        let Some(channel) = service.get_enabled_channel(&channel_id).await? else {
+            tracing::warn!(digest_id = %digest.id, channel_id = %channel_id, "Digest channel not found or disabled");
+            continue;
+        };
+        if let Err(e) = service.send_digest(&channel, &payload).await {
+            tracing::warn!(digest_id = %digest.id, channel_id = %channel_id, error = %e, "Failed to send digest to channel");
+        }
+    }
+
+    let addresses = digest.email_addresses();
+    if !addresses.is_empty() {
+        let email = SystemEmailService::with_db(email_config.clone(), db.clone());
+        for address in addresses {
+            if let Err(e) = email
+                .send_summary_email(&address, &digest.name, &summary)
+                .await
+            {
+                tracing::warn!(digest_id = %digest.id, error = %e, "Failed to email digest");
+            }
+        }
+    }
+
+    let next_send_at = cron::next_run(&digest.cron_expression(), digest.timezone.as_deref());
+    sqlx::query("UPDATE notification_digests SET last_sent_at = ?, next_send_at = ? WHERE id = ?")
+        .bind(until.to_rfc3339())
+        .bind(&next_send_at)
+        .bind(&digest.id)
+        .execute(db)
+        .await?;
+
+    tracing::info!(digest_id = %digest.id, digest = %digest.name, "Notification digest sent");
+    Ok(summary)
+}
+
+/// Send the digests that are due; digests without a next send time only get
+/// one scheduled
+async fn send_due_digests(
+    db: &DbPool,
+    email_config: &EmailConfig,
+    acme_cache_dir: Option<&Path>,
+) -> Result<()> {
+    let digests: Vec<NotificationDigest> = sqlx::query_as(
+        "SELECT * FROM notification_digests \
+         WHERE enabled = 1 AND (next_send_at IS NULL OR next_send_at <= ?)",
+    )
+    .bind(Utc::now().to_rfc3339())
+    .fetch_all(db)
+    .await?;
+
+    for digest in digests {
+        if digest.next_send_at.is_none() {
+            let next_send_at =
+                cron::next_run(&digest.cron_expression(), digest.timezone.as_deref());
+            sqlx::query("UPDATE notification_digests SET next_send_at = ? WHERE id = ?")
+                .bind(&next_send_at)
+                .bind(&digest.id)
+                .execute(db)
+                .await?;
+            continue;
+        }
+        if let Err(e) = send_digest(db, &digest, email_config, acme_cache_dir).await {
+            tracing::warn!(digest_id = %digest.id, error = %e, "Failed to send notification digest");
+        }
+    }
+    Ok(())
+}
+
+/// Spawn the notification digest sender (checks every 5 minutes).
+/// `acme_cache_dir` is where TLS certificates are cached, when ACME is enabled.
+pub fn spawn_summary_sender(
+    db: DbPool,
+    email_config: EmailConfig,
+    acme_cache_dir: Option<PathBuf>,
+) {
+    tracing::info!(
+        "Starting notification digest sender ({}s interval)",
+        CHECK_INTERVAL_SECS
+    );
+
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("summary_sender", async {
+                if let Err(e) =
+                    send_due_digests(&db, &email_config, acme_cache_dir.as_deref()).await
+                {
+                    tracing::warn!(error = %e, "Notification digest cycle failed");
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_text() {
+        let summary = DigestSummary {
+            period_start: "2026-10-16T08:00:00+00:00".to_string(),
+            period_end: "2026-10-17T08:00:00+00:00".to_string(),
+            deployments: 12,
+            succeeded: 10,
+            failed: 2,
+            slowest_builds: vec![SlowBuild {
+                app_name: "api".to_string(),
+                deployment_id: "d1".to_string(),
+                duration_secs: 754,
+            }],
+            crashed_apps: vec![],
+            expiring_certificates: vec![ExpiringCertificate {
+                domain: "example.com".to_string(),
+                expires_at: "2026-10-18T00:00:00+00:00".to_string(),
+                days_left: 1,
+            }],
+        };
+
+        let text = summary.to_text();
+        assert!(text.starts_with("Deployments: 12 (10 succeeded, 2 failed)"));
+        assert!(text.contains("- api: 12m 34s"));
+        assert!(text.contains("No container crashes."));
+        assert!(text.contains("- example.com: 1 day left"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(125), "2m 5s");
+        assert_eq!(format_duration(3725), "1h 2m");
+    }
+}
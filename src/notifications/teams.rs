@@ -40,13 +40,21 @@ pub async fn send_teams(
 /// Build a Microsoft Teams Adaptive Card payload
 fn build_adaptive_card(payload: &NotificationPayload) -> serde_json::Value {
     let status_color = match payload.event_type {
-        crate::db::NotificationEventType::DeploymentStarted => "accent",
+        crate::db::NotificationEventType::DeploymentStarted
+        | crate::db::NotificationEventType::AppScaled
+        | crate::db::NotificationEventType::Digest => "accent",
         crate::db::NotificationEventType::DeploymentSuccess
         | crate::db::NotificationEventType::AppStarted
-        | crate::db::NotificationEventType::ContainerRestarted => "good",
+        | crate::db::NotificationEventType::ContainerRestarted
+        | crate::db::NotificationEventType::BackendRecovered => "good",
         crate::db::NotificationEventType::DeploymentFailed
-        | crate::db::NotificationEventType::ContainerCrash => "attention",
-        crate::db::NotificationEventType::AppStopped => "warning",
+        | crate::db::NotificationEventType::ContainerCrash
+        | crate::db::NotificationEventType::BackendUnhealthy => "attention",
+        crate::db::NotificationEventType::AppStopped
+        | crate::db::NotificationEventType::DeploymentRolledBack
+        | crate::db::NotificationEventType::DeploymentCancelled
+        | crate::db::NotificationEventType::PreviewExpiring
+        | crate::db::NotificationEventType::BandwidthExceeded => "warning",
     };
 
     let mut body = vec![
@@ -116,6 +124,10 @@ fn build_facts(payload: &NotificationPayload) -> Vec<serde_json::Value> {
         facts.push(json!({ "title": "Deployment ID", "value": deployment_id }));
     }
 
+    for (label, value) in payload.commit_fields() {
+        facts.push(json!({ "title": label, "value": value }));
+    }
+
     facts
 }
 
@@ -135,6 +147,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let card = build_adaptive_card(&payload);
@@ -166,6 +179,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let card = build_adaptive_card(&payload);
@@ -187,6 +201,7 @@ mod tests {
             message: "Done".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let facts = build_facts(&payload);
@@ -56,10 +56,18 @@ fn format_telegram_message(payload: &NotificationPayload) -> String {
         crate::db::NotificationEventType::DeploymentStarted => "🚀",
         crate::db::NotificationEventType::DeploymentSuccess => "✅",
         crate::db::NotificationEventType::DeploymentFailed => "❌",
+        crate::db::NotificationEventType::DeploymentRolledBack => "⏪",
+        crate::db::NotificationEventType::DeploymentCancelled => "🚫",
         crate::db::NotificationEventType::AppStopped => "🛑",
         crate::db::NotificationEventType::AppStarted => "▶️",
         crate::db::NotificationEventType::ContainerCrash => "💥",
         crate::db::NotificationEventType::ContainerRestarted => "🔄",
+        crate::db::NotificationEventType::AppScaled => "📈",
+        crate::db::NotificationEventType::PreviewExpiring => "⏳",
+        crate::db::NotificationEventType::BandwidthExceeded => "📶",
+        crate::db::NotificationEventType::BackendUnhealthy => "🔴",
+        crate::db::NotificationEventType::BackendRecovered => "🟢",
+        crate::db::NotificationEventType::Digest => "📊",
     };
 
     let mut msg = format!(
@@ -78,6 +86,10 @@ fn format_telegram_message(payload: &NotificationPayload) -> String {
         ));
     }
 
+    for (label, value) in payload.commit_fields() {
+        msg.push_str(&format!("\n<b>{}:</b> {}", label, html_escape(&value)));
+    }
+
     if let Some(ref error) = payload.error_message {
         msg.push_str(&format!(
             "\n\n<b>Error:</b>\n<code>{}</code>",
@@ -113,6 +125,7 @@ mod tests {
             message: "Deployment completed successfully".to_string(),
             error_message: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_telegram_message(&payload);
@@ -133,6 +146,7 @@ mod tests {
             message: "Deployment failed".to_string(),
             error_message: Some("Build error: missing Dockerfile".to_string()),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            commit: None,
         };
 
         let msg = format_telegram_message(&payload);
@@ -0,0 +1,202 @@
+//! Delivery log of outgoing webhook notifications.
+//!
+//! Every request sent to a webhook channel is recorded with the endpoint's
+//! response status and how long it took, so failing endpoints can be spotted
+//! from the channel's delivery list. Retries of an event the endpoint
+//! rejected show up as separate deliveries.
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use super::NotificationPayload;
+use crate::DbPool;
+
+/// Days deliveries are kept
+const RETENTION_DAYS: i64 = 14;
+
+/// Record a webhook request. `status_code` is `None` when the endpoint
+/// couldn't be reached, `error` is `None` when it accepted the request.
+pub(crate) async fn record(
+    db: &DbPool,
+    channel_id: &str,
+    url: &str,
+    payload: &NotificationPayload,
+    status_code: Option<u16>,
+    error: Option<&str>,
+    duration: Duration,
+) {
+    let result = sqlx::query(
+        "INSERT INTO webhook_deliveries \
+         (id, channel_id, event_type, app_id, deployment_id, url, status_code, success, error, duration_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(channel_id)
+    .bind(payload.event_type.to_string())
+    .bind(Some(&payload.app_id).filter(|id| !id.is_empty()))
+    .bind(&payload.deployment_id)
+    .bind(url)
+    .bind(status_code.map(i64::from))
+    .bind(error.is_none() as i32)
+    .bind(error)
+    .bind(duration.as_millis() as i64)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(channel_id = %channel_id, error = %e, "Failed to record webhook delivery");
+    }
+}
+
+/// Drop deliveries older than the retention period
+pub(crate) async fn prune(db: &DbPool) {
+    let _ = sqlx::query("DELETE FROM webhook_deliveries WHERE created_at < datetime('now', ?)")
+        .bind(format!("-{} days", RETENTION_DAYS))
+        .execute(db)
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_pool, NotificationEventType, WebhookDelivery};
+
+    async fn create_channel(db: &DbPool) -> String {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO notification_channels (id, name, channel_type, config) \
+             VALUES (?, 'hooks', 'webhook', '{\"url\":\"https://hooks.example.com\"}')",
+        )
+        .bind(&id)
+        .execute(db)
+        .await
+        .unwrap();
+        id
+    }
+
+    fn payload() -> NotificationPayload {
+        NotificationPayload {
+            event_type: NotificationEventType::DeploymentFailed,
+            app_id: String::new(),
+            app_name: "web".to_string(),
+            deployment_id: Some("dep-1".to_string()),
+            status: "failed".to_string(),
+            message: "Build failed".to_string(),
+            error_message: None,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            commit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_accepted_and_failed_deliveries() {
+        let (_dir, db) = test_pool().await;
+        let channel_id = create_channel(&db).await;
+        let url = "https://hooks.example.com";
+
+        record(
+            &db,
+            &channel_id,
+            url,
+            &payload(),
+            Some(200),
+            None,
+            Duration::from_millis(42),
+        )
+        .await;
+        record(
+            &db,
+            &channel_id,
+            url,
+            &payload(),
+            Some(500),
+            Some("Webhook request failed with status 500"),
+            Duration::from_millis(7),
+        )
+        .await;
+        record(
+            &db,
+            &channel_id,
+            url,
+            &payload(),
+            None,
+            Some("connection refused"),
+            Duration::ZERO,
+        )
+        .await;
+
+        let deliveries = WebhookDelivery::list_for_channel(&db, &channel_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 3);
+
+        // Newest first
+        let unreachable = &deliveries[0];
+        assert_eq!(unreachable.status_code, None);
+        assert_eq!(unreachable.success, 0);
+        assert_eq!(unreachable.error.as_deref(), Some("connection refused"));
+
+        let rejected = &deliveries[1];
+        assert_eq!(rejected.status_code, Some(500));
+        assert_eq!(rejected.success, 0);
+
+        let accepted = &deliveries[2];
+        assert_eq!(accepted.status_code, Some(200));
+        assert_eq!(accepted.success, 1);
+        assert_eq!(accepted.error, None);
+        assert_eq!(accepted.duration_ms, 42);
+        assert_eq!(accepted.event_type, "deployment_failed");
+        assert_eq!(accepted.app_id, None);
+        assert_eq!(accepted.deployment_id.as_deref(), Some("dep-1"));
+        assert_eq!(accepted.url, url);
+
+        // The limit keeps the newest
+        let latest = WebhookDelivery::list_for_channel(&db, &channel_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].id, unreachable.id);
+    }
+
+    #[tokio::test]
+    async fn test_prune_drops_old_deliveries() {
+        let (_dir, db) = test_pool().await;
+        let channel_id = create_channel(&db).await;
+        record(
+            &db,
+            &channel_id,
+            "https://a",
+            &payload(),
+            Some(200),
+            None,
+            Duration::ZERO,
+        )
+        .await;
+        record(
+            &db,
+            &channel_id,
+            "https://b",
+            &payload(),
+            Some(200),
+            None,
+            Duration::ZERO,
+        )
+        .await;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET created_at = datetime('now', '-15 days') \
+             WHERE url = 'https://a'",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        prune(&db).await;
+
+        let deliveries = WebhookDelivery::list_for_channel(&db, &channel_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].url, "https://b");
+    }
+}
@@ -26,6 +26,16 @@ use super::tls::{TlsConfig, TlsReloadHandle};
 pub const LETS_ENCRYPT_STAGING: &str = "https://acme-staging-v02.api.letsencrypt.org/directory";
 pub const LETS_ENCRYPT_PRODUCTION: &str = "https://acme-v02.api.letsencrypt.org/directory";
 
+/// External Account Binding credentials (RFC 8555 §7.3.4), issued by CAs such
+/// as ZeroSSL or step-ca to tie a new ACME account to an existing CA account.
+#[derive(Debug, Clone)]
+pub struct ExternalAccountBinding {
+    /// Key identifier issued by the CA
+    pub kid: String,
+    /// HMAC key issued by the CA (base64url encoded)
+    pub hmac_key: String,
+}
+
 /// ACME configuration
 #[derive(Debug, Clone)]
 pub struct AcmeConfig {
@@ -35,6 +45,12 @@ pub struct AcmeConfig {
     pub cache_dir: PathBuf,
     /// Use staging environment (for testing)
     pub staging: bool,
+    /// Custom ACME directory URL; overrides `staging` when set
+    pub directory_url: Option<String>,
+    /// External Account Binding credentials, if the CA requires them
+    pub eab: Option<ExternalAccountBinding>,
+    /// Extra root certificate (PEM file) trusted when contacting the directory
+    pub ca_root: Option<PathBuf>,
 }
 
 impl Default for AcmeConfig {
@@ -43,6 +59,61 @@ impl Default for AcmeConfig {
             email: String::new(),
             cache_dir: PathBuf::from("./data/acme"),
             staging: true,
+            directory_url: None,
+            eab: None,
+            ca_root: None,
+        }
+    }
+}
+
+impl AcmeConfig {
+    /// Build an ACME config from the `[proxy]` section of rivetr.toml
+    pub fn from_proxy_config(proxy: &crate::config::ProxyConfig, email: String) -> Self {
+        let eab = match (&proxy.acme_eab_kid, &proxy.acme_eab_hmac_key) {
+            (Some(kid), Some(hmac_key)) if !kid.is_empty() && !hmac_key.is_empty() => {
+                Some(ExternalAccountBinding {
+                    kid: kid.clone(),
+                    hmac_key: hmac_key.clone(),
+                })
+            }
+            _ => None,
+        };
+
+        Self {
+            email,
+            cache_dir: proxy.acme_cache_dir.clone(),
+            staging: proxy.acme_staging,
+            directory_url: proxy
+                .acme_directory_url
+                .clone()
+                .filter(|u| !u.trim().is_empty()),
+            eab,
+            ca_root: proxy.acme_ca_root.clone(),
+        }
+    }
+
+    /// The ACME directory URL to use: explicit override, else Let's Encrypt
+    /// staging or production.
+    pub fn directory_url(&self) -> &str {
+        match self.directory_url.as_deref() {
+            Some(url) => url,
+            None if self.staging => LETS_ENCRYPT_STAGING,
+            None => LETS_ENCRYPT_PRODUCTION,
+        }
+    }
+
+    /// Account credentials file for the configured directory.
+    ///
+    /// Let's Encrypt keeps the historical `account.json`; other directories get
+    /// their own file so switching CAs never reuses an account another CA issued.
+    pub fn account_path(&self) -> PathBuf {
+        match self.directory_url.as_deref() {
+            None => self.cache_dir.join("account.json"),
+            Some(url) => {
+                let digest = Sha256::digest(url.as_bytes());
+                let id = hex::encode(&digest[..6]);
+                self.cache_dir.join(format!("account-{}.json", id))
+            }
         }
     }
 }
@@ -169,10 +240,16 @@ impl AcmeClient {
             .await
             .context("Failed to create ACME cache directory")?;
 
-        let http = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
+        let mut http = reqwest::Client::builder().timeout(Duration::from_secs(30));
+        if let Some(ref ca_root) = config.ca_root {
+            let pem = fs::read(ca_root)
+                .await
+                .with_context(|| format!("Failed to read ACME CA root {}", ca_root.display()))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .context("Failed to parse ACME CA root certificate")?;
+            http = http.add_root_certificate(cert);
+        }
+        let http = http.build().context("Failed to create HTTP client")?;
 
         let client = Self {
             config,
@@ -195,13 +272,14 @@ impl AcmeClient {
         self.challenges.clone()
     }
 
+    /// The ACME directory URL this client talks to
+    pub fn directory_url(&self) -> &str {
+        self.config.directory_url()
+    }
+
     /// Fetch the ACME directory
     async fn fetch_directory(&self) -> Result<()> {
-        let url = if self.config.staging {
-            LETS_ENCRYPT_STAGING
-        } else {
-            LETS_ENCRYPT_PRODUCTION
-        };
+        let url = self.config.directory_url();
 
         debug!(url = %url, "Fetching ACME directory");
 
@@ -243,7 +321,7 @@ impl AcmeClient {
 
     /// Load existing account or create a new one
     async fn load_or_create_account(&self) -> Result<()> {
-        let account_path = self.config.cache_dir.join("account.json");
+        let account_path = self.config.account_path();
 
         if account_path.exists() {
             // Load existing account
@@ -281,11 +359,18 @@ impl AcmeClient {
                 directory.new_account.clone()
             };
 
-            let payload = serde_json::json!({
+            let mut payload = serde_json::json!({
                 "termsOfServiceAgreed": true,
                 "contact": [format!("mailto:{}", self.config.email)]
             });
 
+            if let Some(ref eab) = self.config.eab {
+                let jwk = self.account_jwk(&key_bytes)?;
+                payload["externalAccountBinding"] =
+                    external_account_binding(eab, &jwk, &new_account_url)?;
+                info!(kid = %eab.kid, "Using External Account Binding for ACME registration");
+            }
+
             let (response, _) = self
                 .signed_request(&new_account_url, Some(payload), true)
                 .await?;
@@ -403,6 +488,24 @@ impl AcmeClient {
         Ok((response, new_nonce))
     }
 
+    /// Public JWK for the account key (used as the EAB payload)
+    fn account_jwk(&self, key_bytes: &[u8]) -> Result<serde_json::Value> {
+        let key_pair = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            key_bytes,
+            &SystemRandom::new(),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to load key pair: {}", e))?;
+
+        let public_key = key_pair.public_key().as_ref();
+        Ok(serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": URL_SAFE_NO_PAD.encode(&public_key[1..33]),
+            "y": URL_SAFE_NO_PAD.encode(&public_key[33..65])
+        }))
+    }
+
     /// Get the JWK thumbprint for key authorization
     fn jwk_thumbprint(&self, key_bytes: &[u8]) -> Result<String> {
         let key_pair = EcdsaKeyPair::from_pkcs8(
@@ -854,8 +957,8 @@ impl CertificateRenewalManager {
         }
     }
 
-    /// Query the DB for all current app domains. If any are not in the current SAN list,
-    /// reissue the cert immediately and hot-reload the TLS acceptor.
+    /// Query the DB for all current app and TLS-enabled service domains. If any are not in
+    /// the current SAN list, reissue the cert immediately and hot-reload the TLS acceptor.
     async fn check_and_add_new_domains(&mut self) {
         let db = match &self.db {
             Some(db) => db.clone(),
@@ -892,6 +995,15 @@ impl CertificateRenewalManager {
                     for v in values {
                         let d = match v {
                             serde_json::Value::String(s) => s,
+                            // Passthrough domains terminate TLS in the app itself
+                            serde_json::Value::Object(ref map)
+                                if map
+                                    .get("tls_passthrough")
+                                    .and_then(|x| x.as_bool())
+                                    .unwrap_or(false) =>
+                            {
+                                String::new()
+                            }
                             serde_json::Value::Object(ref map) => map
                                 .get("domain")
                                 .and_then(|x| x.as_str())
@@ -912,6 +1024,23 @@ impl CertificateRenewalManager {
             }
         }
 
+        // Docker Compose services with TLS enabled for their domain
+        match sqlx::query_as::<_, (String,)>(
+            "SELECT domain FROM services WHERE route_tls = 1 AND domain IS NOT NULL AND domain != ''",
+        )
+        .fetch_all(&db)
+        .await
+        {
+            Ok(services) => {
+                for (d,) in services {
+                    if !self.domains.contains(&d) && !new_domains.contains(&d) {
+                        new_domains.push(d);
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "Could not query service domains for cert check"),
+        }
+
         if new_domains.is_empty() {
             return;
         }
@@ -953,7 +1082,12 @@ impl CertificateRenewalManager {
                 }
             }
             Err(e) => {
-                warn!(error = %e, new_domains = ?new_domains, "Failed to reissue cert for new subdomains");
+                warn!(
+                    error = %e,
+                    new_domains = ?new_domains,
+                    hint = failure_hint(&e),
+                    "Failed to reissue cert for new subdomains"
+                );
             }
         }
     }
@@ -964,7 +1098,12 @@ impl CertificateRenewalManager {
 
         for domain in domains {
             if let Err(e) = self.check_domain_renewal(&domain).await {
-                warn!(domain = %domain, error = %e, "Failed to check/renew certificate");
+                warn!(
+                    domain = %domain,
+                    error = %e,
+                    hint = failure_hint(&e),
+                    "Failed to check/renew certificate"
+                );
             }
         }
 
@@ -1032,7 +1171,13 @@ impl CertificateRenewalManager {
     }
 }
 
-/// Parse certificate PEM to get expiry date
+/// Remediation text for a recognized certificate request failure (e.g. rate limits)
+fn failure_hint(e: &anyhow::Error) -> &'static str {
+    crate::engine::failure::classify(&format!("{:#}", e))
+        .map(|kind| kind.remediation())
+        .unwrap_or_default()
+}
+
 /// Extract DNS SANs from the first certificate in a PEM chain.
 /// Returns an empty vec if parsing fails rather than propagating an error.
 fn extract_sans_from_pem(pem_str: &str) -> Vec<String> {
@@ -1061,7 +1206,10 @@ fn extract_sans_from_pem(pem_str: &str) -> Vec<String> {
     sans
 }
 
+/// Parse certificate PEM to get the expiry (`notAfter`) of the first certificate
 fn parse_cert_expiry(pem_str: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use x509_parser::prelude::*;
+
     // Extract the first certificate from the chain
     let pem_block: ::pem::Pem = ::pem::parse(pem_str).ok()?;
 
@@ -1069,17 +1217,85 @@ fn parse_cert_expiry(pem_str: &str) -> Option<chrono::DateTime<chrono::Utc>> {
         return None;
     }
 
-    // Parse X.509 certificate to get not_after
-    // This is a simplified check - for production use x509-parser
-    // For now, we'll return None to trigger a renewal check
+    let (_, cert) = X509Certificate::from_der(pem_block.contents()).ok()?;
+    chrono::DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+}
+
+/// Expiry of every cached certificate under an ACME cache directory, soonest
+/// first. Certificates that can't be read or parsed are skipped.
+pub async fn certificate_expiries(
+    cache_dir: &std::path::Path,
+) -> Vec<(String, chrono::DateTime<chrono::Utc>)> {
+    let certs_dir = cache_dir.join("certs");
+    let Ok(mut entries) = fs::read_dir(&certs_dir).await else {
+        return Vec::new();
+    };
 
-    None
+    let mut expiries = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Some(domain) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let Ok(pem) = fs::read_to_string(entry.path().join("fullchain.pem")).await else {
+            continue;
+        };
+        if let Some(expiry) = parse_cert_expiry(&pem) {
+            expiries.push((domain, expiry));
+        }
+    }
+    expiries.sort_by_key(|(_, expiry)| *expiry);
+    expiries
+}
+
+/// Build the `externalAccountBinding` JWS for a newAccount request: the
+/// account's public JWK signed with the CA-issued HMAC key (HS256).
+fn external_account_binding(
+    eab: &ExternalAccountBinding,
+    jwk: &serde_json::Value,
+    new_account_url: &str,
+) -> Result<serde_json::Value> {
+    let hmac_key = URL_SAFE_NO_PAD
+        .decode(eab.hmac_key.trim_end_matches('='))
+        .context("EAB HMAC key must be base64url encoded")?;
+
+    let protected = serde_json::json!({
+        "alg": "HS256",
+        "kid": eab.kid,
+        "url": new_account_url
+    });
+    let protected_b64 = URL_SAFE_NO_PAD.encode(protected.to_string().as_bytes());
+    let payload_b64 = URL_SAFE_NO_PAD.encode(jwk.to_string().as_bytes());
+
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, &hmac_key);
+    let signing_input = format!("{}.{}", protected_b64, payload_b64);
+    let signature = ring::hmac::sign(&key, signing_input.as_bytes());
+
+    Ok(serde_json::json!({
+        "protected": protected_b64,
+        "payload": payload_b64,
+        "signature": URL_SAFE_NO_PAD.encode(signature.as_ref())
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_cert_expiry() {
+        use rcgen::{CertificateParams, KeyPair};
+
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec!["example.com".to_string()]).unwrap();
+        params.not_after = rcgen::date_time_ymd(2031, 3, 14);
+        let cert = params.self_signed(&key_pair).unwrap();
+        let cert_pem = pem::encode(&pem::Pem::new("CERTIFICATE", cert.der().to_vec()));
+
+        let expiry = parse_cert_expiry(&cert_pem).unwrap();
+        assert_eq!(expiry.format("%Y-%m-%d").to_string(), "2031-03-14");
+        assert!(parse_cert_expiry("not a certificate").is_none());
+    }
+
     #[test]
     fn test_acme_challenges() {
         let challenges = AcmeChallenges::new();
@@ -1096,5 +1312,42 @@ mod tests {
         let config = AcmeConfig::default();
         assert!(config.staging);
         assert!(config.email.is_empty());
+        assert_eq!(config.directory_url(), LETS_ENCRYPT_STAGING);
+    }
+
+    #[test]
+    fn test_acme_custom_directory() {
+        let config = AcmeConfig {
+            directory_url: Some("https://acme.zerossl.com/v2/DV90".to_string()),
+            ..AcmeConfig::default()
+        };
+        assert_eq!(config.directory_url(), "https://acme.zerossl.com/v2/DV90");
+        // Custom directories never share the Let's Encrypt account file
+        assert_ne!(config.account_path(), AcmeConfig::default().account_path());
+    }
+
+    #[test]
+    fn test_external_account_binding_verifies() {
+        let eab = ExternalAccountBinding {
+            kid: "kid-123".to_string(),
+            hmac_key: URL_SAFE_NO_PAD.encode(b"super-secret-hmac-key"),
+        };
+        let jwk = serde_json::json!({"kty": "EC", "crv": "P-256", "x": "a", "y": "b"});
+        let binding = external_account_binding(&eab, &jwk, "https://ca/new-acct").unwrap();
+
+        let protected = binding["protected"].as_str().unwrap();
+        let payload = binding["payload"].as_str().unwrap();
+        let signature = URL_SAFE_NO_PAD
+            .decode(binding["signature"].as_str().unwrap())
+            .unwrap();
+
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, b"super-secret-hmac-key");
+        let signing_input = format!("{}.{}", protected, payload);
+        assert!(ring::hmac::verify(&key, signing_input.as_bytes(), &signature).is_ok());
+
+        let header: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(protected).unwrap()).unwrap();
+        assert_eq!(header["kid"], "kid-123");
+        assert_eq!(header["alg"], "HS256");
     }
 }
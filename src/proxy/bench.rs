@@ -0,0 +1,277 @@
+//! Load benchmark for the proxy and route table.
+//!
+//! Runs everything in-process on loopback: a minimal HTTP backend, a
+//! `ProxyHandler` with N routes all pointing at it, and M keep-alive client
+//! connections that spread requests across the routes for a fixed duration.
+//! Used by `rivetr bench proxy` and the criterion benches in `benches/`.
+
+use arc_swap::ArcSwap;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{Backend, ProxyHandler, RouteTable};
+
+/// Load to put on the proxy
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Routes in the route table
+    pub routes: usize,
+    /// Concurrent client connections
+    pub connections: usize,
+    /// How long to send requests for
+    pub duration: Duration,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            routes: 100,
+            connections: 32,
+            duration: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Results of a benchmark run
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub routes: usize,
+    pub connections: usize,
+    pub elapsed: Duration,
+    /// Requests that got a 2xx response
+    pub requests: u64,
+    /// Requests that failed or got a non-2xx response
+    pub errors: u64,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl BenchReport {
+    pub fn requests_per_sec(&self) -> f64 {
+        self.requests as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Domain of the `i`th benchmark route
+pub fn route_domain(i: usize) -> String {
+    format!("bench-{}.rivetr.test", i)
+}
+
+/// A route table with `routes` routes, all pointing at `backend`
+pub fn bench_route_table(routes: usize, backend: SocketAddr) -> RouteTable {
+    let table = RouteTable::new();
+    for i in 0..routes {
+        table.add_route(
+            route_domain(i),
+            Backend::new(
+                format!("bench-{}", i),
+                backend.ip().to_string(),
+                backend.port(),
+            ),
+        );
+    }
+    table
+}
+
+/// A proxy with benchmark routes in front of a loopback backend, both served
+/// in the background
+pub struct BenchProxy {
+    /// Address the proxy listens on
+    pub addr: SocketAddr,
+    pub routes: Arc<ArcSwap<RouteTable>>,
+}
+
+impl BenchProxy {
+    pub async fn start(routes: usize) -> anyhow::Result<Self> {
+        let backend = spawn_backend().await?;
+        let table = Arc::new(ArcSwap::from_pointee(bench_route_table(routes, backend)));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let handler = ProxyHandler::new(table.clone());
+        tokio::spawn(async move {
+            while let Ok((stream, remote_addr)) = listener.accept().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let _ = handler.handle_connection(stream, remote_addr).await;
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            routes: table,
+        })
+    }
+
+    /// Open a keep-alive client connection to the proxy
+    pub async fn connect(&self) -> anyhow::Result<BenchClient> {
+        BenchClient::connect(self.addr).await
+    }
+}
+
+/// A keep-alive client connection to a `BenchProxy`
+pub struct BenchClient {
+    sender: hyper::client::conn::http1::SendRequest<Empty<Bytes>>,
+}
+
+impl BenchClient {
+    pub async fn connect(addr: SocketAddr) -> anyhow::Result<Self> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        let (sender, connection) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        Ok(Self { sender })
+    }
+
+    /// Send one request for `host` and read the whole response; returns
+    /// whether it succeeded
+    pub async fn get(&mut self, host: &str) -> anyhow::Result<bool> {
+        self.sender.ready().await?;
+        let request = Request::get("/")
+            .header(hyper::header::HOST, host)
+            .body(Empty::new())?;
+        let response = self.sender.send_request(request).await?;
+        let success = response.status().is_success();
+        response.into_body().collect().await?;
+        Ok(success)
+    }
+}
+
+/// Serve a fixed 200 response on a loopback port
+async fn spawn_backend() -> anyhow::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let _ = stream.set_nodelay(true);
+            tokio::spawn(async move {
+                let service = service_fn(|_req| async {
+                    Ok::<_, Infallible>(Response::new(Full::new(Bytes::from_static(b"ok"))))
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Run the benchmark described by `options`
+pub async fn run(options: &BenchOptions) -> anyhow::Result<BenchReport> {
+    anyhow::ensure!(options.routes > 0, "At least one route is required");
+    anyhow::ensure!(
+        options.connections > 0,
+        "At least one connection is required"
+    );
+
+    let proxy = BenchProxy::start(options.routes).await?;
+    let domains: Arc<Vec<String>> = Arc::new((0..options.routes).map(route_domain).collect());
+
+    let mut clients = Vec::with_capacity(options.connections);
+    for _ in 0..options.connections {
+        clients.push(proxy.connect().await?);
+    }
+
+    let addr = proxy.addr;
+    let started = Instant::now();
+    let deadline = started + options.duration;
+    let mut workers = Vec::with_capacity(options.connections);
+    for (worker, mut client) in clients.into_iter().enumerate() {
+        let domains = domains.clone();
+        workers.push(tokio::spawn(async move {
+            let mut latencies = Vec::new();
+            let mut errors = 0u64;
+            // Workers start at different routes so every route gets traffic
+            let mut next = worker;
+            while Instant::now() < deadline {
+                let host = &domains[next % domains.len()];
+                next += 1;
+                let sent = Instant::now();
+                match client.get(host).await {
+                    Ok(true) => latencies.push(sent.elapsed()),
+                    Ok(false) => errors += 1,
+                    Err(_) => {
+                        errors += 1;
+                        // The connection is gone; open a fresh one
+                        match BenchClient::connect(addr).await {
+                            Ok(fresh) => client = fresh,
+                            Err(_) => break,
+                        }
+                    }
+                }
+            }
+            (latencies, errors)
+        }));
+    }
+
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    for worker in workers {
+        let (worker_latencies, worker_errors) = worker.await?;
+        latencies.extend(worker_latencies);
+        errors += worker_errors;
+    }
+    let elapsed = started.elapsed();
+    latencies.sort_unstable();
+
+    Ok(BenchReport {
+        routes: options.routes,
+        connections: options.connections,
+        elapsed,
+        requests: latencies.len() as u64,
+        errors,
+        p50: percentile(&latencies, 50.0),
+        p90: percentile(&latencies, 90.0),
+        p99: percentile(&latencies, 99.0),
+        max: latencies.last().copied().unwrap_or_default(),
+    })
+}
+
+/// The `p`th percentile of sorted samples (nearest rank)
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&samples, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&samples, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_proxied_to_every_route() {
+        let proxy = BenchProxy::start(3).await.unwrap();
+        let mut client = proxy.connect().await.unwrap();
+        for i in 0..3 {
+            assert!(client.get(&route_domain(i)).await.unwrap());
+        }
+        assert!(!client.get("unknown.rivetr.test").await.unwrap());
+    }
+}
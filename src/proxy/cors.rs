@@ -0,0 +1,267 @@
+// Per-app CORS handling
+//
+// The proxy answers CORS preflight requests itself and sets the
+// `Access-Control-*` headers on proxied responses, so apps don't each need
+// their own CORS middleware. When a policy is attached to a route it is
+// authoritative: any CORS headers the backend sets are replaced.
+
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::Method;
+
+/// Default methods allowed for cross-origin requests
+pub const DEFAULT_ALLOWED_METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Default preflight cache lifetime in seconds
+pub const DEFAULT_MAX_AGE_SECS: u64 = 600;
+
+/// Per-app CORS policy attached to a proxy backend
+#[derive(Debug, Clone, Default)]
+pub struct CorsPolicy {
+    /// Allowed origins: `*`, exact origins (`https://app.example.com`) or
+    /// wildcard subdomains (`https://*.example.com`)
+    pub allowed_origins: Vec<String>,
+    /// Allowed request methods (uppercase)
+    pub allowed_methods: Vec<String>,
+    /// Allowed request headers (lowercase). Empty allows whatever the
+    /// preflight asks for.
+    pub allowed_headers: Vec<String>,
+    /// Response headers exposed to the browser
+    pub expose_headers: Vec<String>,
+    /// Allow cookies and HTTP auth on cross-origin requests
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response
+    pub max_age_secs: u64,
+}
+
+/// Outcome of a preflight request
+#[derive(Debug, PartialEq, Eq)]
+pub enum Preflight {
+    /// Answer with these headers
+    Allowed(Vec<(header::HeaderName, String)>),
+    /// The origin, method or headers are not allowed
+    Denied(&'static str),
+}
+
+impl CorsPolicy {
+    /// Whether `origin` may make cross-origin requests
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+        self.allowed_origins.iter().any(|allowed| {
+            let allowed = allowed.trim_end_matches('/').to_ascii_lowercase();
+            if allowed == "*" || allowed == origin {
+                return true;
+            }
+            // "https://*.example.com" matches any subdomain, not the apex
+            match allowed.split_once("*.") {
+                Some((scheme, domain)) => origin
+                    .strip_prefix(scheme)
+                    .and_then(|host| host.strip_suffix(domain))
+                    .is_some_and(|sub| sub.ends_with('.') && sub.len() > 1 && !sub.contains('/')),
+                None => false,
+            }
+        })
+    }
+
+    /// Value for `Access-Control-Allow-Origin`: `*` when any origin is allowed
+    /// and credentials are not, otherwise the request's origin
+    fn allow_origin_value(&self, origin: &str) -> String {
+        if !self.allow_credentials && self.allowed_origins.iter().any(|o| o == "*") {
+            "*".to_string()
+        } else {
+            origin.to_string()
+        }
+    }
+
+    /// Evaluate a preflight request
+    pub fn preflight(
+        &self,
+        origin: &str,
+        requested_method: &str,
+        requested_headers: Option<&str>,
+    ) -> Preflight {
+        if !self.allows_origin(origin) {
+            return Preflight::Denied("origin not allowed");
+        }
+        let method = requested_method.trim().to_ascii_uppercase();
+        if !self.allowed_methods.contains(&method) {
+            return Preflight::Denied("method not allowed");
+        }
+        let requested: Vec<String> = requested_headers
+            .unwrap_or("")
+            .split(',')
+            .map(|h| h.trim().to_ascii_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect();
+        if !self.allowed_headers.is_empty()
+            && requested.iter().any(|h| !self.allowed_headers.contains(h))
+        {
+            return Preflight::Denied("header not allowed");
+        }
+
+        let mut headers = vec![
+            (
+                header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                self.allow_origin_value(origin),
+            ),
+            (
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                self.allowed_methods.join(", "),
+            ),
+            (
+                header::ACCESS_CONTROL_MAX_AGE,
+                self.max_age_secs.to_string(),
+            ),
+            (header::VARY, "Origin".to_string()),
+        ];
+        let allow_headers = if self.allowed_headers.is_empty() {
+            requested
+        } else {
+            self.allowed_headers.clone()
+        };
+        if !allow_headers.is_empty() {
+            headers.push((
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                allow_headers.join(", "),
+            ));
+        }
+        if self.allow_credentials {
+            headers.push((header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true".to_string()));
+        }
+        Preflight::Allowed(headers)
+    }
+
+    /// Replace the CORS headers of a proxied response. Responses to requests
+    /// from disallowed origins get no CORS headers, so the browser blocks them.
+    pub fn apply_response_headers(&self, origin: Option<&str>, headers: &mut HeaderMap) {
+        for name in [
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            header::ACCESS_CONTROL_MAX_AGE,
+        ] {
+            headers.remove(name);
+        }
+        headers.append(header::VARY, HeaderValue::from_static("Origin"));
+
+        let Some(origin) = origin.filter(|o| self.allows_origin(o)) else {
+            return;
+        };
+        if let Ok(value) = HeaderValue::from_str(&self.allow_origin_value(origin)) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+        }
+        if self.allow_credentials {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        if !self.expose_headers.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&self.expose_headers.join(", ")) {
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, value);
+            }
+        }
+    }
+}
+
+/// Whether a request is a CORS preflight (an OPTIONS request carrying
+/// `Origin` and `Access-Control-Request-Method`)
+pub fn is_preflight(method: &Method, headers: &HeaderMap) -> bool {
+    method == Method::OPTIONS
+        && headers.contains_key(header::ORIGIN)
+        && headers.contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(origins: &[&str]) -> CorsPolicy {
+        CorsPolicy {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allowed_methods: DEFAULT_ALLOWED_METHODS
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            allowed_headers: vec!["content-type".to_string(), "authorization".to_string()],
+            expose_headers: vec!["X-Request-Id".to_string()],
+            allow_credentials: false,
+            max_age_secs: DEFAULT_MAX_AGE_SECS,
+        }
+    }
+
+    #[test]
+    fn test_origin_matching() {
+        let cors = policy(&["https://app.example.com", "https://*.example.org"]);
+        assert!(cors.allows_origin("https://app.example.com"));
+        assert!(cors.allows_origin("https://APP.example.com/"));
+        assert!(!cors.allows_origin("http://app.example.com"));
+        assert!(cors.allows_origin("https://admin.example.org"));
+        assert!(cors.allows_origin("https://a.b.example.org"));
+        assert!(!cors.allows_origin("https://example.org"));
+        assert!(!cors.allows_origin("https://evilexample.org"));
+        assert!(policy(&["*"]).allows_origin("https://anything.test"));
+    }
+
+    #[test]
+    fn test_preflight() {
+        let cors = policy(&["https://app.example.com"]);
+        match cors.preflight(
+            "https://app.example.com",
+            "put",
+            Some("Content-Type, Authorization"),
+        ) {
+            Preflight::Allowed(headers) => {
+                assert!(headers.contains(&(
+                    header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                    "https://app.example.com".to_string()
+                )));
+                assert!(headers.contains(&(header::ACCESS_CONTROL_MAX_AGE, "600".to_string())));
+            }
+            Preflight::Denied(reason) => panic!("preflight denied: {}", reason),
+        }
+        assert_eq!(
+            cors.preflight("https://evil.test", "GET", None),
+            Preflight::Denied("origin not allowed")
+        );
+        assert_eq!(
+            cors.preflight("https://app.example.com", "TRACE", None),
+            Preflight::Denied("method not allowed")
+        );
+        assert_eq!(
+            cors.preflight("https://app.example.com", "GET", Some("x-secret")),
+            Preflight::Denied("header not allowed")
+        );
+    }
+
+    #[test]
+    fn test_response_headers_replace_backend_cors() {
+        let mut cors = policy(&["*"]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static("https://other.test"),
+        );
+        cors.apply_response_headers(Some("https://app.example.com"), &mut headers);
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_EXPOSE_HEADERS],
+            "X-Request-Id"
+        );
+
+        // Credentials can't be combined with a wildcard origin; echo the origin
+        cors.allow_credentials = true;
+        cors.apply_response_headers(Some("https://app.example.com"), &mut headers);
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+        let mut denied = HeaderMap::new();
+        policy(&["https://app.example.com"])
+            .apply_response_headers(Some("https://evil.test"), &mut denied);
+        assert!(!denied.contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
@@ -0,0 +1,155 @@
+// Connection draining for route replacement
+//
+// The proxy counts in-flight requests per backend container. When a deployment
+// swaps a route to a new container, the engine waits for the old container's
+// count to reach zero (or a timeout) before stopping it, so requests that were
+// already being served are not cut off mid-response.
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use http_body_util::{combinators::BoxBody, BodyExt};
+use hyper::body::{Body, Frame, SizeHint};
+use hyper::Response;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// How often `drain` re-checks the in-flight count
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// In-flight request counters keyed by backend container ID
+#[derive(Debug, Default)]
+pub struct ConnectionTracker {
+    active: DashMap<String, Arc<AtomicUsize>>,
+}
+
+impl ConnectionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request as in flight until the returned guard is dropped
+    pub fn track(&self, container_id: &str) -> ConnectionGuard {
+        let counter = self
+            .active
+            .entry(container_id.to_string())
+            .or_insert_with(|| Arc::new(AtomicUsize::new(0)))
+            .clone();
+        counter.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard { counter }
+    }
+
+    /// Number of requests currently in flight to a container
+    pub fn active(&self, container_id: &str) -> usize {
+        self.active
+            .get(container_id)
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Wait until no requests are in flight to any of the given containers,
+    /// or until `timeout` elapses. Returns true if they fully drained.
+    pub async fn drain(&self, container_ids: &[String], timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let drained = loop {
+            let in_flight: usize = container_ids.iter().map(|id| self.active(id)).sum();
+            if in_flight == 0 {
+                break true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                tracing::warn!(
+                    containers = ?container_ids,
+                    in_flight = in_flight,
+                    timeout_secs = timeout.as_secs(),
+                    "Connection drain timed out; stopping old containers anyway"
+                );
+                break false;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        };
+
+        // Drop idle counters for containers that are about to go away
+        for id in container_ids {
+            self.active
+                .remove_if(id, |_, c| c.load(Ordering::Relaxed) == 0);
+        }
+        drained
+    }
+}
+
+/// Decrements a container's in-flight count when dropped
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    counter: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Response body that keeps its request counted until fully streamed
+struct TrackedBody {
+    inner: BoxBody<Bytes, hyper::Error>,
+    _guard: ConnectionGuard,
+}
+
+impl Body for TrackedBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Attach a guard to a response so the request stays counted as in flight
+/// until the body has been sent (or the client goes away).
+pub fn track_response(
+    response: Response<BoxBody<Bytes, hyper::Error>>,
+    guard: ConnectionGuard,
+) -> Response<BoxBody<Bytes, hyper::Error>> {
+    let (parts, body) = response.into_parts();
+    let body = TrackedBody {
+        inner: body,
+        _guard: guard,
+    };
+    Response::from_parts(parts, body.boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight_requests() {
+        let tracker = Arc::new(ConnectionTracker::new());
+        let guard = tracker.track("old");
+        assert_eq!(tracker.active("old"), 1);
+
+        let ids = vec!["old".to_string()];
+        assert!(!tracker.drain(&ids, Duration::from_millis(150)).await);
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+        assert!(tracker.drain(&ids, Duration::from_secs(5)).await);
+        release.await.unwrap();
+        assert_eq!(tracker.active("old"), 0);
+    }
+}
@@ -0,0 +1,657 @@
+// Forward authentication at the proxy
+//
+// Requires visitors to sign in before requests reach an app, similar to
+// oauth2-proxy but built in. Sign-in runs on reserved paths under
+// `/_rivetr/auth/` on the app's own domain:
+// - Rivetr accounts: a login form checked against the users table (plus the
+//   TOTP code for accounts with 2FA enabled)
+// - OIDC: the authorization code flow against a configured provider. The
+//   provider must allow `<scheme>://<app domain>/_rivetr/auth/callback` as a
+//   redirect URI.
+// A successful sign-in sets an HMAC-signed session cookie for the domain, and
+// the identity is passed to the backend in X-Forwarded-User/-Email headers.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use hyper::header::{HeaderMap, HeaderValue};
+use lazy_static::lazy_static;
+use rand::RngCore;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::api::auth::{hash_password, verify_password};
+use crate::db::{OidcProvider, User};
+
+/// Reserved path prefix for the sign-in endpoints
+pub const AUTH_PATH_PREFIX: &str = "/_rivetr/auth/";
+pub const LOGIN_PATH: &str = "/_rivetr/auth/login";
+pub const CALLBACK_PATH: &str = "/_rivetr/auth/callback";
+pub const LOGOUT_PATH: &str = "/_rivetr/auth/logout";
+
+/// Cookie holding the signed session
+pub const SESSION_COOKIE: &str = "rivetr_auth";
+/// Cookie binding an OIDC `state` to the browser that started the flow
+pub const STATE_COOKIE: &str = "rivetr_auth_state";
+
+/// How long an OIDC sign-in may take before its state expires
+const STATE_TTL_SECS: i64 = 600;
+
+/// The only error a failed sign-in shows, so the form doesn't reveal whether
+/// an account exists, its password was right or it has 2FA enabled
+pub const INVALID_CREDENTIALS: &str = "Invalid email, password or 2FA code";
+
+lazy_static! {
+    /// Checked against for unknown emails, so they take as long to reject as
+    /// a wrong password
+    static ref DUMMY_PASSWORD_HASH: String =
+        hash_password("rivetr-forward-auth-dummy").unwrap_or_default();
+}
+
+/// Headers carrying the signed-in identity to the backend. Always stripped
+/// from client requests so they cannot be spoofed.
+pub const USER_HEADER: &str = "X-Forwarded-User";
+pub const EMAIL_HEADER: &str = "X-Forwarded-Email";
+pub const NAME_HEADER: &str = "X-Forwarded-Preferred-Username";
+
+/// Where visitors sign in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForwardAuthProvider {
+    /// Rivetr's own user accounts
+    Rivetr,
+    /// A configured OIDC provider (by id)
+    Oidc(String),
+}
+
+/// Per-app forward auth policy attached to a proxy backend
+#[derive(Debug, Clone)]
+pub struct ForwardAuthPolicy {
+    pub app_id: String,
+    pub provider: ForwardAuthProvider,
+    /// Lowercase emails or `@domain` entries; empty allows any signed-in user
+    pub allowed_emails: Vec<String>,
+    /// Session cookie lifetime
+    pub session_secs: i64,
+    /// Hex-encoded HMAC key for session cookies
+    pub cookie_secret: String,
+}
+
+/// A signed-in visitor, as stored in the session cookie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    pub sub: String,
+    pub email: String,
+    pub name: Option<String>,
+    /// Unix timestamp after which the session is invalid
+    pub exp: i64,
+}
+
+impl ForwardAuthPolicy {
+    fn key(&self) -> hmac::Key {
+        let secret = hex::decode(&self.cookie_secret)
+            .unwrap_or_else(|_| self.cookie_secret.as_bytes().to_vec());
+        hmac::Key::new(hmac::HMAC_SHA256, &secret)
+    }
+
+    /// `payload.signature`, both URL-safe
+    fn sign(&self, payload: &str) -> String {
+        let tag = hmac::sign(&self.key(), payload.as_bytes());
+        format!("{}.{}", payload, BASE64_URL.encode(tag.as_ref()))
+    }
+
+    /// The payload of a signed value, if the signature is valid
+    fn verify<'a>(&self, signed: &'a str) -> Option<&'a str> {
+        let (payload, signature) = signed.rsplit_once('.')?;
+        let signature = BASE64_URL.decode(signature).ok()?;
+        hmac::verify(&self.key(), payload.as_bytes(), &signature).ok()?;
+        Some(payload)
+    }
+
+    /// Build the identity for a freshly signed-in user
+    pub fn identity(&self, sub: &str, email: &str, name: Option<&str>) -> Identity {
+        Identity {
+            sub: sub.to_string(),
+            email: email.to_string(),
+            name: name.filter(|n| !n.is_empty()).map(str::to_string),
+            exp: chrono::Utc::now().timestamp() + self.session_secs,
+        }
+    }
+
+    /// `Set-Cookie` value carrying a signed session
+    pub fn session_cookie(&self, identity: &Identity, secure: bool) -> String {
+        let payload = BASE64_URL.encode(serde_json::to_vec(identity).unwrap_or_default());
+        cookie(
+            SESSION_COOKIE,
+            &self.sign(&payload),
+            self.session_secs,
+            secure,
+        )
+    }
+
+    /// The identity in a request's session cookie, if signed by this policy and unexpired
+    pub fn verify_session(&self, cookie_header: Option<&str>) -> Option<Identity> {
+        let payload = self.verify(cookie_value(cookie_header, SESSION_COOKIE)?)?;
+        let identity: Identity = serde_json::from_slice(&BASE64_URL.decode(payload).ok()?).ok()?;
+        (identity.exp > chrono::Utc::now().timestamp() && self.is_email_allowed(&identity.email))
+            .then_some(identity)
+    }
+
+    /// Whether an email may sign in under this policy
+    pub fn is_email_allowed(&self, email: &str) -> bool {
+        if self.allowed_emails.is_empty() {
+            return true;
+        }
+        let email = email.to_ascii_lowercase();
+        self.allowed_emails
+            .iter()
+            .any(|entry| match entry.strip_prefix('@') {
+                Some(domain) => email.rsplit_once('@').is_some_and(|(_, d)| d == domain),
+                None => *entry == email,
+            })
+    }
+
+    /// Start an OIDC sign-in: returns the signed `state` parameter and the
+    /// `Set-Cookie` value that binds it to this browser.
+    pub fn begin_state(&self, redirect_to: &str, secure: bool) -> (String, String) {
+        let mut nonce = [0u8; 16];
+        rand::rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let exp = chrono::Utc::now().timestamp() + STATE_TTL_SECS;
+        let payload = format!("{}:{}:{}", nonce, exp, BASE64_URL.encode(redirect_to));
+        (
+            self.sign(&payload),
+            cookie(STATE_COOKIE, &nonce, STATE_TTL_SECS, secure),
+        )
+    }
+
+    /// Validate an OIDC `state` against the browser's state cookie; returns
+    /// where to send the visitor afterwards.
+    pub fn finish_state(&self, state: &str, cookie_header: Option<&str>) -> Option<String> {
+        let mut parts = self.verify(state)?.splitn(3, ':');
+        let (nonce, exp, redirect_to) = (parts.next()?, parts.next()?, parts.next()?);
+        let expected = cookie_value(cookie_header, STATE_COOKIE)?;
+        if !bool::from(subtle::ConstantTimeEq::ct_eq(
+            nonce.as_bytes(),
+            expected.as_bytes(),
+        )) || exp.parse::<i64>().ok()? < chrono::Utc::now().timestamp()
+        {
+            return None;
+        }
+        let redirect_to = String::from_utf8(BASE64_URL.decode(redirect_to).ok()?).ok()?;
+        Some(safe_redirect_target(Some(&redirect_to)))
+    }
+}
+
+fn cookie(name: &str, value: &str, max_age: i64, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        name,
+        value,
+        max_age,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+/// `Set-Cookie` value that removes a cookie
+pub fn expired_cookie(name: &str) -> String {
+    format!("{}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax", name)
+}
+
+/// Value of a cookie in a `Cookie` header
+pub fn cookie_value<'a>(cookie_header: Option<&'a str>, name: &str) -> Option<&'a str> {
+    cookie_header?.split(';').find_map(|c| {
+        let (key, value) = c.trim().split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// Replace any client-supplied identity headers with the signed-in identity
+pub fn set_identity_headers(headers: &mut HeaderMap, identity: Option<&Identity>) {
+    for name in [USER_HEADER, EMAIL_HEADER, NAME_HEADER] {
+        headers.remove(name);
+    }
+    let Some(identity) = identity else {
+        return;
+    };
+    let values = [
+        (USER_HEADER, Some(identity.email.as_str())),
+        (EMAIL_HEADER, Some(identity.email.as_str())),
+        (NAME_HEADER, identity.name.as_deref()),
+    ];
+    for (name, value) in values {
+        if let Some(value) = value.and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// Only same-site absolute paths are accepted as post-login redirects.
+/// Control characters are rejected too: browsers strip tabs and newlines, so
+/// `/\t/evil.com` would become the protocol-relative `//evil.com`.
+pub fn safe_redirect_target(target: Option<&str>) -> String {
+    match target {
+        Some(t)
+            if t.starts_with('/')
+                && !t.starts_with("//")
+                && !t.contains('\\')
+                && !t.chars().any(char::is_control) =>
+        {
+            t.to_string()
+        }
+        _ => "/".to_string(),
+    }
+}
+
+/// URL of the login page that returns the visitor to `path_and_query`
+pub fn login_url(path_and_query: &str) -> String {
+    format!("{}?rd={}", LOGIN_PATH, url_encode(path_and_query))
+}
+
+/// Decode an `application/x-www-form-urlencoded` string (query or form body)
+pub fn parse_form(input: &str) -> Vec<(String, String)> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (form_decode(key), form_decode(value))
+        })
+        .collect()
+}
+
+/// First value of a form field
+pub fn form_field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn form_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hi = (bytes[i + 1] as char).to_digit(16);
+                let lo = (bytes[i + 2] as char).to_digit(16);
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => {
+                        out.push((hi * 16 + lo) as u8);
+                        i += 2;
+                    }
+                    _ => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// URL-encode a string for use in query parameters
+fn url_encode(s: &str) -> String {
+    let mut encoded = String::new();
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Sign-in form for Rivetr accounts
+pub fn login_page(redirect_to: &str, error: Option<&str>) -> String {
+    let error = error
+        .map(|e| format!(r#"<p class="error">{}</p>"#, html_escape(e)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Sign in - Rivetr</title>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <meta name="robots" content="noindex, nofollow">
+    <style>
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Oxygen, Ubuntu, sans-serif;
+            display: flex;
+            justify-content: center;
+            align-items: center;
+            height: 100vh;
+            margin: 0;
+            background: #f5f5f5;
+        }}
+        form {{
+            width: 320px;
+            padding: 40px;
+            background: white;
+            border-radius: 8px;
+            box-shadow: 0 2px 10px rgba(0,0,0,0.1);
+        }}
+        h1 {{ color: #333; font-size: 20px; margin: 0 0 20px; }}
+        label {{ display: block; color: #666; font-size: 13px; margin-bottom: 12px; }}
+        input {{ display: block; width: 100%; box-sizing: border-box; padding: 8px; margin-top: 4px; }}
+        button {{ width: 100%; padding: 10px; margin-top: 8px; }}
+        .error {{ color: #e74c3c; font-size: 13px; }}
+        .footer {{ color: #999; font-size: 12px; text-align: center; margin-top: 16px; }}
+    </style>
+</head>
+<body>
+    <form method="post" action="{}">
+        <h1>Sign in to continue</h1>
+        {}
+        <input type="hidden" name="rd" value="{}">
+        <label>Email <input type="email" name="email" autocomplete="username" required autofocus></label>
+        <label>Password <input type="password" name="password" autocomplete="current-password" required></label>
+        <label>2FA code (if enabled) <input type="text" name="code" inputmode="numeric" autocomplete="one-time-code"></label>
+        <button type="submit">Sign in</button>
+        <p class="footer">Powered by Rivetr</p>
+    </form>
+</body>
+</html>"#,
+        LOGIN_PATH,
+        error,
+        html_escape(redirect_to)
+    )
+}
+
+/// Check Rivetr account credentials (and the TOTP code when 2FA is enabled).
+/// Any failure is reported as `INVALID_CREDENTIALS`.
+pub async fn authenticate_rivetr_user(
+    db: &SqlitePool,
+    encryption_key: Option<&[u8; 32]>,
+    email: &str,
+    password: &str,
+    code: Option<&str>,
+) -> Result<User, &'static str> {
+    let user: Option<User> = sqlx::query_as("SELECT * FROM users WHERE email = ?")
+        .bind(email.trim())
+        .fetch_optional(db)
+        .await
+        .map_err(|_| "Sign-in is temporarily unavailable")?;
+    let Some(user) = user else {
+        verify_password(password, &DUMMY_PASSWORD_HASH);
+        return Err(INVALID_CREDENTIALS);
+    };
+
+    if !verify_password(password, &user.password_hash) {
+        return Err(INVALID_CREDENTIALS);
+    }
+
+    if user.totp_enabled {
+        let code = code.map(str::trim).filter(|c| !c.is_empty());
+        let secret = user
+            .totp_secret
+            .as_deref()
+            .and_then(|s| crate::crypto::decrypt_if_encrypted(s, encryption_key).ok());
+        match (code, secret) {
+            (Some(code), Some(secret)) if totp_matches(&secret, &user.email, code) => {}
+            _ => return Err(INVALID_CREDENTIALS),
+        }
+    }
+
+    Ok(user)
+}
+
+fn totp_matches(secret_base32: &str, email: &str, code: &str) -> bool {
+    let Ok(secret) = totp_rs::Secret::Encoded(secret_base32.to_string()).to_bytes() else {
+        return false;
+    };
+    totp_rs::TOTP::new(
+        totp_rs::Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret,
+        Some("Rivetr".to_string()),
+        email.to_string(),
+    )
+    .ok()
+    .and_then(|totp| totp.check_current(code).ok())
+    .unwrap_or(false)
+}
+
+/// OIDC discovery document fields used by the proxy
+#[derive(Deserialize)]
+struct OidcDiscovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct OidcTokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    email: Option<String>,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+async fn load_oidc_provider(
+    db: &SqlitePool,
+    provider_id: &str,
+) -> Result<(OidcProvider, OidcDiscovery), String> {
+    let provider: OidcProvider =
+        sqlx::query_as("SELECT * FROM oidc_providers WHERE id = ? AND enabled = 1")
+            .bind(provider_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "OIDC provider not found or disabled".to_string())?;
+
+    let discovery: OidcDiscovery = reqwest::Client::new()
+        .get(&provider.discovery_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch OIDC discovery document: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OIDC discovery document: {}", e))?;
+
+    Ok((provider, discovery))
+}
+
+/// Authorization URL to send the visitor to
+pub async fn oidc_authorize_url(
+    db: &SqlitePool,
+    provider_id: &str,
+    redirect_uri: &str,
+    state: &str,
+) -> Result<String, String> {
+    let (provider, discovery) = load_oidc_provider(db, provider_id).await?;
+    Ok(format!(
+        "{}?client_id={}&redirect_uri={}&scope={}&response_type=code&state={}",
+        discovery.authorization_endpoint,
+        url_encode(&provider.client_id),
+        url_encode(redirect_uri),
+        url_encode(&provider.scopes),
+        url_encode(state),
+    ))
+}
+
+/// Exchange an authorization code and return `(sub, email, name)`
+pub async fn oidc_user(
+    db: &SqlitePool,
+    encryption_key: Option<&[u8; 32]>,
+    provider_id: &str,
+    code: &str,
+    redirect_uri: &str,
+) -> Result<(String, String, Option<String>), String> {
+    let (provider, discovery) = load_oidc_provider(db, provider_id).await?;
+    let client_secret =
+        crate::crypto::decrypt_if_encrypted(&provider.client_secret, encryption_key)
+            .map_err(|e| format!("Decryption failed: {}", e))?;
+
+    let http_client = reqwest::Client::new();
+    let token: OidcTokenResponse = http_client
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", &provider.client_id),
+            ("client_secret", &client_secret),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to exchange code for token: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+
+    let user_info: OidcUserInfo = http_client
+        .get(&discovery.userinfo_endpoint)
+        .bearer_auth(&token.access_token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch user info: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse user info: {}", e))?;
+
+    let email = user_info
+        .email
+        .ok_or_else(|| "The provider did not return an email address".to_string())?;
+    Ok((
+        user_info.sub,
+        email,
+        user_info.name.or(user_info.preferred_username),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed: &[&str]) -> ForwardAuthPolicy {
+        ForwardAuthPolicy {
+            app_id: "app".to_string(),
+            provider: ForwardAuthProvider::Rivetr,
+            allowed_emails: allowed.iter().map(|e| e.to_string()).collect(),
+            session_secs: 3600,
+            cookie_secret: "00112233445566778899aabbccddeeff".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_session_cookie_roundtrip() {
+        let policy = policy(&[]);
+        let identity = policy.identity("u1", "ada@example.com", Some("Ada"));
+        let set_cookie = policy.session_cookie(&identity, true);
+        assert!(set_cookie.contains("; Secure"));
+
+        let value = set_cookie.split(';').next().unwrap();
+        let header = format!("theme=dark; {}", value);
+        assert_eq!(policy.verify_session(Some(&header)), Some(identity));
+
+        // Tampered or foreign cookies are rejected
+        let tampered = header.replacen("rivetr_auth=e", "rivetr_auth=f", 1);
+        assert_eq!(policy.verify_session(Some(&tampered)), None);
+        let mut other = policy.clone();
+        other.cookie_secret = "ffeeddccbbaa99887766554433221100".to_string();
+        assert_eq!(other.verify_session(Some(&header)), None);
+    }
+
+    #[test]
+    fn test_email_allowlist() {
+        let policy = policy(&["ada@example.com", "@corp.io"]);
+        assert!(policy.is_email_allowed("Ada@Example.com"));
+        assert!(policy.is_email_allowed("bob@corp.io"));
+        assert!(!policy.is_email_allowed("bob@evilcorp.io"));
+        assert!(!policy.is_email_allowed("eve@example.com"));
+    }
+
+    #[test]
+    fn test_oidc_state() {
+        let policy = policy(&[]);
+        let (state, set_cookie) = policy.begin_state("/dashboard?tab=1", false);
+        let nonce_cookie = set_cookie.split(';').next().unwrap();
+        assert_eq!(
+            policy.finish_state(&state, Some(nonce_cookie)),
+            Some("/dashboard?tab=1".to_string())
+        );
+        assert_eq!(
+            policy.finish_state(&state, Some("rivetr_auth_state=other")),
+            None
+        );
+        assert_eq!(policy.finish_state(&state, None), None);
+    }
+
+    #[test]
+    fn test_redirect_target_and_forms() {
+        assert_eq!(safe_redirect_target(Some("/a?b=c")), "/a?b=c");
+        assert_eq!(safe_redirect_target(Some("//evil.com")), "/");
+        assert_eq!(safe_redirect_target(Some("https://evil.com")), "/");
+        assert_eq!(safe_redirect_target(None), "/");
+        assert_eq!(safe_redirect_target(Some("/\t/evil.com")), "/");
+        assert_eq!(safe_redirect_target(Some("/\n/evil.com")), "/");
+        assert_eq!(safe_redirect_target(Some("/a\r\nSet-Cookie: x=1")), "/");
+
+        let fields = parse_form("email=ada%40example.com&password=a+b%26c&rd=%2Fx");
+        assert_eq!(form_field(&fields, "email"), Some("ada@example.com"));
+        assert_eq!(form_field(&fields, "password"), Some("a b&c"));
+        assert_eq!(form_field(&fields, "rd"), Some("/x"));
+        assert_eq!(login_url("/x?y=1"), "/_rivetr/auth/login?rd=%2Fx%3Fy%3D1");
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_failures_are_indistinguishable() {
+        let (_dir, db) = crate::db::test_pool().await;
+        let hash = hash_password("correct horse").unwrap();
+        for (id, email, totp) in [("u1", "ada@example.com", 0), ("u2", "bob@example.com", 1)] {
+            sqlx::query(
+                "INSERT INTO users (id, email, password_hash, name, role, totp_enabled, totp_secret) VALUES (?, ?, ?, ?, 'member', ?, 'JBSWY3DPEHPK3PXP')",
+            )
+            .bind(id)
+            .bind(email)
+            .bind(&hash)
+            .bind(id)
+            .bind(totp)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        let user = authenticate_rivetr_user(&db, None, "ada@example.com", "correct horse", None)
+            .await
+            .unwrap();
+        assert_eq!(user.id, "u1");
+
+        // Unknown email, wrong password, missing and wrong 2FA code all fail alike
+        for (email, password, code) in [
+            ("eve@example.com", "correct horse", None),
+            ("ada@example.com", "wrong", None),
+            ("bob@example.com", "correct horse", None),
+            ("bob@example.com", "correct horse", Some("not-a-code")),
+            ("bob@example.com", "wrong", Some("not-a-code")),
+        ] {
+            assert_eq!(
+                authenticate_rivetr_user(&db, None, email, password, code)
+                    .await
+                    .unwrap_err(),
+                INVALID_CREDENTIALS
+            );
+        }
+    }
+}
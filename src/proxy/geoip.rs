@@ -0,0 +1,114 @@
+// GeoIP country lookups
+//
+// Resolves client addresses to ISO 3166-1 alpha-2 country codes using a
+// MaxMind-compatible database (GeoLite2-Country, GeoIP2-Country or City, or
+// any DB-IP/IPinfo export in the same format). Used by per-app
+// `allow_countries` / `block_countries` access rules.
+
+use maxminddb::{geoip2, Reader};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+
+/// Country code reported for addresses the database has no country for
+/// (private ranges, anycast, new allocations). Add it to an `allow_countries`
+/// rule to let such clients through.
+pub const UNKNOWN_COUNTRY: &str = "XX";
+
+/// A loaded country database
+pub struct GeoIp {
+    reader: Reader<Vec<u8>>,
+}
+
+impl std::fmt::Debug for GeoIp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeoIp")
+            .field("database_type", &self.reader.metadata.database_type)
+            .field("build_epoch", &self.reader.metadata.build_epoch)
+            .finish()
+    }
+}
+
+impl GeoIp {
+    /// Load a `.mmdb` database into memory
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let reader = Reader::open_readfile(path).map_err(|e| {
+            anyhow::anyhow!("Failed to open GeoIP database {}: {}", path.display(), e)
+        })?;
+        Ok(Self { reader })
+    }
+
+    /// Database type from the file metadata, e.g. `GeoLite2-Country`
+    pub fn database_type(&self) -> &str {
+        &self.reader.metadata.database_type
+    }
+
+    /// Upper-cased country code for an address, or [`UNKNOWN_COUNTRY`]
+    pub fn country(&self, ip: IpAddr) -> String {
+        self.reader
+            .lookup::<geoip2::Country>(ip.to_canonical())
+            .ok()
+            .and_then(|record| record.country)
+            .and_then(|country| country.iso_code)
+            .map(|code| code.to_ascii_uppercase())
+            .unwrap_or_else(|| UNKNOWN_COUNTRY.to_string())
+    }
+}
+
+/// Address to geolocate for a request.
+///
+/// The connecting peer is used unless it is a loopback or private address
+/// (a local load balancer or tunnel in front of the proxy), in which case the
+/// first `X-Forwarded-For` hop is trusted instead. Public peers cannot spoof
+/// their country with a forged header.
+pub fn client_addr(remote_addr: SocketAddr, forwarded_for: Option<&str>) -> IpAddr {
+    let peer = remote_addr.ip().to_canonical();
+    if !is_local(peer) {
+        return peer;
+    }
+    forwarded_for
+        .and_then(|value| value.split(',').next())
+        .and_then(|hop| hop.trim().parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical())
+        .unwrap_or(peer)
+}
+
+fn is_local(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        // fc00::/7 unique local addresses
+        IpAddr::V6(v6) => v6.is_loopback() || (v6.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_addr_trusts_forwarded_for_only_from_local_peers() {
+        let public: SocketAddr = "203.0.113.7:50000".parse().unwrap();
+        let local: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+        let tunnel: SocketAddr = "[fd00::2]:50000".parse().unwrap();
+
+        assert_eq!(
+            client_addr(public, Some("198.51.100.1")),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_addr(local, Some("198.51.100.1, 10.0.0.1")),
+            "198.51.100.1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_addr(tunnel, Some("2001:db8::1")),
+            "2001:db8::1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_addr(local, Some("not-an-ip")),
+            "127.0.0.1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_addr(local, None),
+            "127.0.0.1".parse::<IpAddr>().unwrap()
+        );
+    }
+}
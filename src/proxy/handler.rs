@@ -7,12 +7,12 @@ use arc_swap::ArcSwap;
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use bytes::Bytes;
-use http_body_util::{combinators::BoxBody, BodyExt, Full};
-use hyper::body::Incoming;
+use http_body_util::{combinators::BoxBody, BodyExt, Empty, Full, Limited};
+use hyper::body::{Body, Incoming};
 use hyper::header::{AUTHORIZATION, CONNECTION, UPGRADE, WWW_AUTHENTICATE};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
-use hyper::{Request, Response, StatusCode};
+use hyper::{Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
 use regex::Regex;
 use std::net::SocketAddr;
@@ -22,12 +22,26 @@ use tokio::net::TcpStream;
 use tokio_rustls::server::TlsStream;
 use tracing::{debug, error, info, warn};
 
+use crate::api::metrics::{observe_proxy_upstream_duration, record_proxy_request};
+use crate::api::rate_limit::{RateLimitTier, RateLimiter};
+use crate::chaos::{FaultInjector, ProxyFault};
+use crate::engine::scale_to_zero::AppWaker;
+
 use super::acme::AcmeChallenges;
+use super::bots;
+use super::cors::{self, Preflight};
+use super::forward_auth::{self, ForwardAuthPolicy, ForwardAuthProvider};
+use super::geoip::{self, GeoIp};
+use super::traffic;
+use super::waf::{self, WafMode, WafPolicy, WafRequest, WafVerdict};
 use super::{Backend, ProxyService, RouteTable};
 
 /// ACME HTTP-01 challenge path prefix
 const ACME_CHALLENGE_PREFIX: &str = "/.well-known/acme-challenge/";
 
+/// Largest accepted forward auth sign-in form body
+const FORWARD_AUTH_FORM_LIMIT: usize = 16 * 1024;
+
 /// Owned fields needed to write a proxy access log row.
 struct ProxyLogEntry {
     host: String,
@@ -39,6 +53,20 @@ struct ProxyLogEntry {
     user_agent: String,
 }
 
+/// Owned fields needed to write a WAF event row.
+struct WafEventEntry {
+    app_id: String,
+    host: String,
+    method: String,
+    path: String,
+    query: Option<String>,
+    client_ip: String,
+    user_agent: String,
+    score: u32,
+    matched_rules: String,
+    action: &'static str,
+}
+
 /// Handles incoming proxy connections
 #[derive(Clone)]
 pub struct ProxyHandler {
@@ -55,6 +83,16 @@ pub struct ProxyHandler {
     /// The HTTPS listener sets this to "https" so upstream apps (Laravel, Rails,
     /// etc.) generate correct absolute URLs instead of insecure http:// ones.
     forwarded_proto: &'static str,
+    /// Key for secrets encrypted at rest, used by forward auth sign-in
+    encryption_key: Option<[u8; 32]>,
+    /// Country database for GeoIP access rules (rules are skipped without it)
+    geoip: Option<Arc<GeoIp>>,
+    /// Faults injected into forwarded requests (chaos mode only)
+    faults: Option<Arc<FaultInjector>>,
+    /// Starts apps scaled to zero when a request for them arrives
+    waker: Option<Arc<AppWaker>>,
+    /// Throttles forward auth sign-in attempts per client IP and per email
+    login_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl ProxyHandler {
@@ -67,6 +105,11 @@ impl ProxyHandler {
             https_redirect_enabled: None,
             db: None,
             forwarded_proto: "http",
+            encryption_key: None,
+            geoip: None,
+            faults: None,
+            waker: None,
+            login_limiter: None,
         }
     }
 
@@ -76,6 +119,42 @@ impl ProxyHandler {
         self
     }
 
+    /// Forward through `service`, sharing its backend connection pool
+    pub fn with_proxy_service(mut self, service: ProxyService) -> Self {
+        self.proxy_service = service;
+        self
+    }
+
+    /// Set the key used to decrypt OIDC client secrets and TOTP secrets
+    pub fn with_encryption_key(mut self, key: Option<[u8; 32]>) -> Self {
+        self.encryption_key = key;
+        self
+    }
+
+    /// Set the country database used by GeoIP access rules
+    pub fn with_geoip(mut self, geoip: Option<Arc<GeoIp>>) -> Self {
+        self.geoip = geoip;
+        self
+    }
+
+    /// Fail or delay forwarded requests as `faults` dictates
+    pub fn with_fault_injector(mut self, faults: Option<Arc<FaultInjector>>) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Wake apps scaled to zero through `waker`
+    pub fn with_app_waker(mut self, waker: Option<Arc<AppWaker>>) -> Self {
+        self.waker = waker;
+        self
+    }
+
+    /// Limit forward auth sign-in attempts with the API's auth tier
+    pub fn with_login_limiter(mut self, limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.login_limiter = limiter;
+        self
+    }
+
     /// Mark this handler as serving TLS, so forwarded requests report
     /// X-Forwarded-Proto: https to backends.
     pub fn with_tls(mut self) -> Self {
@@ -146,8 +225,12 @@ impl ProxyHandler {
         Ok(())
     }
 
-    /// Write a proxy access log entry to the database (fire-and-forget)
+    /// Record per-domain request metrics and write a proxy access log entry
+    /// to the database (fire-and-forget)
     fn log_request(&self, entry: ProxyLogEntry) {
+        let domain = traffic::domain_label(&self.routes.load(), Some(&entry.host));
+        record_proxy_request(&domain, entry.status, entry.response_ms as f64 / 1000.0);
+
         if let Some(ref db) = self.db {
             let db = db.clone();
             tokio::spawn(async move {
@@ -177,10 +260,328 @@ impl ProxyHandler {
         }
     }
 
+    /// Record a request that reached the WAF anomaly threshold
+    fn log_waf_event(&self, entry: WafEventEntry) {
+        if let Some(ref db) = self.db {
+            let db = db.clone();
+            tokio::spawn(async move {
+                let _ = sqlx::query(
+                    "INSERT INTO waf_events (app_id, host, method, path, query, client_ip, user_agent, score, matched_rules, action) \
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&entry.app_id)
+                .bind(&entry.host)
+                .bind(&entry.method)
+                .bind(&entry.path)
+                .bind(&entry.query)
+                .bind(if entry.client_ip.is_empty() { None } else { Some(&entry.client_ip) })
+                .bind(if entry.user_agent.is_empty() { None } else { Some(&entry.user_agent) })
+                .bind(entry.score as i64)
+                .bind(&entry.matched_rules)
+                .bind(entry.action)
+                .execute(&db)
+                .await;
+            });
+        }
+    }
+
+    /// Country code of the client, or `None` if no GeoIP database is loaded
+    fn client_country<T>(&self, req: &Request<T>, remote_addr: SocketAddr) -> Option<String> {
+        let geoip = self.geoip.as_ref()?;
+        let forwarded_for = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|v| v.to_str().ok());
+        Some(geoip.country(geoip::client_addr(remote_addr, forwarded_for)))
+    }
+
+    /// Apply a bot policy to a request.
+    /// Returns `Some(response)` to answer directly (robots.txt or challenge page),
+    /// or `None` to continue proxying.
+    fn apply_bot_policy<T>(
+        &self,
+        req: &Request<T>,
+        policy: &bots::BotPolicy,
+        client_ip: &str,
+        user_agent: &str,
+    ) -> Option<Response<BoxBody<Bytes, hyper::Error>>> {
+        if policy.deny_robots && req.uri().path() == "/robots.txt" {
+            return Some(
+                Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "text/plain; charset=utf-8")
+                    .header("X-Robots-Tag", bots::NOINDEX_HEADER_VALUE)
+                    .header("X-Powered-By", "Rivetr")
+                    .body(
+                        Full::new(Bytes::from_static(bots::DENY_ALL_ROBOTS_TXT.as_bytes()))
+                            .map_err(|e| match e {})
+                            .boxed(),
+                    )
+                    .unwrap_or_else(|_| empty_response(StatusCode::OK)),
+            );
+        }
+
+        if policy.challenge_bad_bots && bots::is_bad_bot(user_agent) {
+            let cookie = req
+                .headers()
+                .get(hyper::header::COOKIE)
+                .and_then(|v| v.to_str().ok());
+            if !bots::has_valid_challenge_cookie(cookie, client_ip) {
+                debug!(client_ip = %client_ip, user_agent = %user_agent, "Challenging bad bot");
+                let body = bots::challenge_page(&bots::challenge_token(client_ip));
+                return Some(
+                    Response::builder()
+                        .status(StatusCode::FORBIDDEN)
+                        .header("Content-Type", "text/html; charset=utf-8")
+                        .header("Cache-Control", "no-store")
+                        .header("X-Powered-By", "Rivetr")
+                        .body(Full::new(Bytes::from(body)).map_err(|e| match e {}).boxed())
+                        .unwrap_or_else(|_| empty_response(StatusCode::FORBIDDEN)),
+                );
+            }
+        }
+
+        None
+    }
+
+    /// Response for a request without a valid forward auth session: browsers
+    /// are sent to the sign-in page, other clients get a 401.
+    fn forward_auth_challenge<T>(
+        &self,
+        req: &Request<T>,
+    ) -> Response<BoxBody<Bytes, hyper::Error>> {
+        let wants_html = req
+            .headers()
+            .get(hyper::header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|accept| accept.contains("text/html"));
+        if !wants_html || !matches!(*req.method(), hyper::Method::GET | hyper::Method::HEAD) {
+            return self.error_response(StatusCode::UNAUTHORIZED, "Authentication required");
+        }
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or("/");
+        forward_auth_redirect(
+            StatusCode::FOUND,
+            &forward_auth::login_url(path_and_query),
+            &[],
+        )
+    }
+
+    /// Serve the forward auth endpoints under `/_rivetr/auth/`: sign-in form or
+    /// OIDC redirect, form submission, OIDC callback and sign-out.
+    async fn serve_forward_auth(
+        &self,
+        req: Request<Incoming>,
+        remote_addr: SocketAddr,
+        policy: &ForwardAuthPolicy,
+        host: &str,
+    ) -> Response<BoxBody<Bytes, hyper::Error>> {
+        let Some(db) = self.db.as_ref() else {
+            return self
+                .error_response(StatusCode::SERVICE_UNAVAILABLE, "Sign-in is not available");
+        };
+        let secure = self.forwarded_proto == "https";
+        let callback_url = format!(
+            "{}://{}{}",
+            self.forwarded_proto,
+            host,
+            forward_auth::CALLBACK_PATH
+        );
+        let query = forward_auth::parse_form(req.uri().query().unwrap_or(""));
+        let cookies = req
+            .headers()
+            .get(hyper::header::COOKIE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let forwarded_for = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|v| v.to_str().ok());
+        let client_ip = geoip::client_addr(remote_addr, forwarded_for);
+        let is_get = req.method() == hyper::Method::GET;
+        let is_post = req.method() == hyper::Method::POST;
+
+        match (req.uri().path(), &policy.provider) {
+            (forward_auth::LOGIN_PATH, ForwardAuthProvider::Rivetr) if is_get => {
+                let rd = forward_auth::safe_redirect_target(forward_auth::form_field(&query, "rd"));
+                forward_auth_page(StatusCode::OK, forward_auth::login_page(&rd, None))
+            }
+            (forward_auth::LOGIN_PATH, ForwardAuthProvider::Rivetr) if is_post => {
+                let body = match Limited::new(req.into_body(), FORWARD_AUTH_FORM_LIMIT)
+                    .collect()
+                    .await
+                {
+                    Ok(body) => body.to_bytes(),
+                    Err(_) => {
+                        return self.error_response(StatusCode::PAYLOAD_TOO_LARGE, "Invalid form")
+                    }
+                };
+                let form = forward_auth::parse_form(&String::from_utf8_lossy(&body));
+                let field = |name| forward_auth::form_field(&form, name).unwrap_or("");
+                let rd = forward_auth::safe_redirect_target(Some(field("rd")));
+
+                if let Some(limiter) = self.login_limiter.as_ref() {
+                    let limited = limiter
+                        .check_rate_limit(client_ip, RateLimitTier::Auth)
+                        .and_then(|_| {
+                            limiter.check_account_rate_limit(field("email"), RateLimitTier::Auth)
+                        });
+                    if let Err(retry_after) = limited {
+                        warn!(
+                            app_id = %policy.app_id,
+                            client = %client_ip,
+                            "Forward auth sign-in rate limited"
+                        );
+                        let message = format!(
+                            "Too many sign-in attempts. Try again in {} seconds.",
+                            retry_after
+                        );
+                        return forward_auth_page(
+                            StatusCode::TOO_MANY_REQUESTS,
+                            forward_auth::login_page(&rd, Some(&message)),
+                        );
+                    }
+                }
+
+                match forward_auth::authenticate_rivetr_user(
+                    db,
+                    self.encryption_key.as_ref(),
+                    field("email"),
+                    field("password"),
+                    Some(field("code")),
+                )
+                .await
+                {
+                    Ok(user) if policy.is_email_allowed(&user.email) => {
+                        info!(app_id = %policy.app_id, email = %user.email, "Forward auth sign-in");
+                        let identity = policy.identity(&user.id, &user.email, Some(&user.name));
+                        forward_auth_redirect(
+                            StatusCode::SEE_OTHER,
+                            &rd,
+                            &[policy.session_cookie(&identity, secure)],
+                        )
+                    }
+                    Ok(user) => {
+                        info!(
+                            app_id = %policy.app_id,
+                            email = %user.email,
+                            "Forward auth sign-in denied: email not allowed"
+                        );
+                        forward_auth_page(
+                            StatusCode::UNAUTHORIZED,
+                            forward_auth::login_page(&rd, Some(forward_auth::INVALID_CREDENTIALS)),
+                        )
+                    }
+                    Err(message) => forward_auth_page(
+                        StatusCode::UNAUTHORIZED,
+                        forward_auth::login_page(&rd, Some(message)),
+                    ),
+                }
+            }
+            (forward_auth::LOGIN_PATH, ForwardAuthProvider::Oidc(provider_id)) if is_get => {
+                let rd = forward_auth::safe_redirect_target(forward_auth::form_field(&query, "rd"));
+                let (state, state_cookie) = policy.begin_state(&rd, secure);
+                match forward_auth::oidc_authorize_url(db, provider_id, &callback_url, &state).await
+                {
+                    Ok(url) => forward_auth_redirect(StatusCode::FOUND, &url, &[state_cookie]),
+                    Err(e) => {
+                        warn!(
+                            app_id = %policy.app_id,
+                            error = %e,
+                            "Forward auth OIDC sign-in failed"
+                        );
+                        self.error_response(StatusCode::BAD_GATEWAY, "Sign-in provider unavailable")
+                    }
+                }
+            }
+            (forward_auth::CALLBACK_PATH, ForwardAuthProvider::Oidc(provider_id)) if is_get => {
+                if forward_auth::form_field(&query, "error").is_some() {
+                    return self.error_response(StatusCode::UNAUTHORIZED, "Sign-in was denied");
+                }
+                let rd = forward_auth::form_field(&query, "state")
+                    .and_then(|state| policy.finish_state(state, cookies.as_deref()));
+                let code = forward_auth::form_field(&query, "code");
+                let (Some(rd), Some(code)) = (rd, code) else {
+                    return self.error_response(
+                        StatusCode::BAD_REQUEST,
+                        "Invalid or expired sign-in request",
+                    );
+                };
+
+                match forward_auth::oidc_user(
+                    db,
+                    self.encryption_key.as_ref(),
+                    provider_id,
+                    code,
+                    &callback_url,
+                )
+                .await
+                {
+                    Ok((sub, email, name)) if policy.is_email_allowed(&email) => {
+                        info!(app_id = %policy.app_id, email = %email, "Forward auth sign-in");
+                        let identity = policy.identity(&sub, &email, name.as_deref());
+                        forward_auth_redirect(
+                            StatusCode::FOUND,
+                            &rd,
+                            &[
+                                policy.session_cookie(&identity, secure),
+                                forward_auth::expired_cookie(forward_auth::STATE_COOKIE),
+                            ],
+                        )
+                    }
+                    Ok((_, email, _)) => {
+                        info!(
+                            app_id = %policy.app_id,
+                            email = %email,
+                            "Forward auth sign-in denied: email not allowed"
+                        );
+                        self.error_response(StatusCode::FORBIDDEN, "Sign-in failed")
+                    }
+                    Err(e) => {
+                        warn!(
+                            app_id = %policy.app_id,
+                            error = %e,
+                            "Forward auth OIDC callback failed"
+                        );
+                        self.error_response(StatusCode::BAD_GATEWAY, "Sign-in failed")
+                    }
+                }
+            }
+            (forward_auth::LOGOUT_PATH, _) => forward_auth_redirect(
+                StatusCode::FOUND,
+                "/",
+                &[forward_auth::expired_cookie(forward_auth::SESSION_COOKIE)],
+            ),
+            _ => self.error_response(StatusCode::NOT_FOUND, "Not Found"),
+        }
+    }
+
+    /// Run the built-in WAF for a request.
+    /// Returns the verdict when the request reached the policy's anomaly threshold.
+    fn inspect_waf<T>(&self, req: &Request<T>, policy: &WafPolicy) -> Option<WafVerdict> {
+        let header =
+            |name: hyper::header::HeaderName| req.headers().get(name).and_then(|v| v.to_str().ok());
+        let verdict = waf::evaluate(&WafRequest {
+            path: req.uri().path(),
+            query: req.uri().query(),
+            user_agent: header(hyper::header::USER_AGENT),
+            referer: header(hyper::header::REFERER),
+            cookie: header(hyper::header::COOKIE),
+        });
+        if verdict.exceeds(policy.anomaly_threshold) {
+            Some(verdict)
+        } else {
+            None
+        }
+    }
+
     /// Handle a single HTTP request
     async fn handle_request(
         &self,
-        req: Request<Incoming>,
+        mut req: Request<Incoming>,
         remote_addr: SocketAddr,
     ) -> Result<Response<BoxBody<Bytes, hyper::Error>>, hyper::Error> {
         let start = Instant::now();
@@ -197,7 +598,7 @@ impl ProxyHandler {
             .and_then(|v| v.to_str().ok())
             .map(|s| s.split(',').next().unwrap_or("").trim().to_string())
             .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| remote_addr.ip().to_string());
+            .unwrap_or_else(|| remote_addr.ip().to_canonical().to_string());
         let log_user_agent = req
             .headers()
             .get(hyper::header::USER_AGENT)
@@ -259,16 +660,67 @@ impl ProxyHandler {
         );
 
         // Get the route table
-        let routes = self.routes.load();
+        let mut routes = self.routes.load();
 
         // Look up the backend
-        let backend = match &host {
+        let mut backend = match &host {
             Some(h) => routes.get_backend(h),
             None => None,
         };
 
+        // Scale-to-zero: hold the request while its sleeping app starts up
+        if backend.is_none() {
+            let sleeping = host.as_deref().and_then(|h| routes.sleeping_app(h));
+            if let (Some(waker), Some(app_id)) = (&self.waker, sleeping) {
+                info!(host = ?host, "Waking sleeping app");
+                if let Err(e) = waker.wake(&app_id, &self.routes).await {
+                    error!(host = ?host, error = %e, "Failed to wake sleeping app");
+                }
+                routes = self.routes.load();
+                backend = host.as_deref().and_then(|h| routes.get_backend(h));
+                if backend.is_none() {
+                    let response = self.error_response(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "This app is starting up. Please try again in a moment.",
+                    );
+                    let ms = start.elapsed().as_millis() as u64;
+                    self.log_request(ProxyLogEntry {
+                        host: log_host,
+                        method: log_method,
+                        path: log_path,
+                        status: response.status().as_u16(),
+                        response_ms: ms,
+                        client_ip: log_client_ip,
+                        user_agent: log_user_agent,
+                    });
+                    return Ok(response);
+                }
+            }
+        }
+
         let response = match backend {
-            Some(backend) if backend.healthy => {
+            Some(backend)
+                if backend.healthy()
+                    && backend.tls_passthrough
+                    && self.forwarded_proto == "http" =>
+            {
+                // Passthrough backends only speak TLS; send plain HTTP clients to HTTPS
+                let query = uri.query().map(|q| format!("?{}", q)).unwrap_or_default();
+                let host_without_port = host
+                    .as_deref()
+                    .unwrap_or("")
+                    .split(':')
+                    .next()
+                    .unwrap_or("");
+                let location = format!("https://{}{}{}", host_without_port, path, query);
+                Response::builder()
+                    .status(hyper::StatusCode::MOVED_PERMANENTLY)
+                    .header(hyper::header::LOCATION, location)
+                    .header("X-Powered-By", "Rivetr")
+                    .body(Full::new(Bytes::new()).map_err(|e| match e {}).boxed())
+                    .unwrap_or_else(|_| empty_response(StatusCode::BAD_REQUEST))
+            }
+            Some(backend) if backend.healthy() => {
                 // If this backend is a www-redirect proxy, issue a permanent redirect
                 if let Some(ref target_host) = backend.www_redirect_target {
                     let query = uri.query().map(|q| format!("?{}", q)).unwrap_or_default();
@@ -285,16 +737,213 @@ impl ProxyHandler {
                         .body(Full::new(Bytes::new()).map_err(|e| match e {}).boxed())
                         .unwrap_or_else(|_| empty_response(StatusCode::BAD_REQUEST))
                 } else {
-                    // Check HTTP Basic Auth if enabled (but bypass for health check path)
-                    if backend.basic_auth.enabled {
-                        let is_healthcheck = backend
-                            .healthcheck_path
-                            .as_ref()
-                            .map(|p| path == p)
-                            .unwrap_or(false);
+                    // Bot handling: deny-all robots.txt and bad bot challenge
+                    if let Some(ref bot_policy) = backend.bots {
+                        if let Some(response) =
+                            self.apply_bot_policy(&req, bot_policy, &log_client_ip, &log_user_agent)
+                        {
+                            let ms = start.elapsed().as_millis() as u64;
+                            self.log_request(ProxyLogEntry {
+                                host: log_host,
+                                method: log_method,
+                                path: log_path,
+                                status: response.status().as_u16(),
+                                response_ms: ms,
+                                client_ip: log_client_ip,
+                                user_agent: log_user_agent,
+                            });
+                            return Ok(response);
+                        }
+                    }
+
+                    // Apply access rules first so blocked clients never reach the auth prompt
+                    if !backend.access_rules.is_empty() {
+                        let country = if backend.access_rules.iter().any(|r| r.is_country_rule()) {
+                            self.client_country(&req, remote_addr)
+                        } else {
+                            None
+                        };
+                        if let Some(status) = check_access_rules(
+                            &backend.access_rules,
+                            method.as_str(),
+                            path,
+                            &log_user_agent,
+                            country.as_deref(),
+                        ) {
+                            debug!(
+                                host = ?host,
+                                path = %path,
+                                method = %method,
+                                country = ?country,
+                                status = status.as_u16(),
+                                "Request denied by access rule"
+                            );
+                            let response = self.error_response(
+                                status,
+                                status.canonical_reason().unwrap_or("Forbidden"),
+                            );
+                            let ms = start.elapsed().as_millis() as u64;
+                            self.log_request(ProxyLogEntry {
+                                host: log_host,
+                                method: log_method,
+                                path: log_path,
+                                status: response.status().as_u16(),
+                                response_ms: ms,
+                                client_ip: log_client_ip,
+                                user_agent: log_user_agent,
+                            });
+                            return Ok(response);
+                        }
+                    }
+
+                    // Built-in WAF: score the request and block or report attacks
+                    if let Some(ref policy) = backend.waf {
+                        if let Some(verdict) = self.inspect_waf(&req, policy) {
+                            let blocked = policy.mode == WafMode::Block;
+                            warn!(
+                                host = ?host,
+                                path = %path,
+                                client_ip = %log_client_ip,
+                                score = verdict.score,
+                                rules = ?verdict.matched_rules,
+                                mode = policy.mode.as_str(),
+                                "WAF anomaly threshold reached"
+                            );
+                            self.log_waf_event(WafEventEntry {
+                                app_id: policy.app_id.clone(),
+                                host: log_host.clone(),
+                                method: log_method.clone(),
+                                path: log_path.clone(),
+                                query: uri.query().map(|q| q.to_string()),
+                                client_ip: log_client_ip.clone(),
+                                user_agent: log_user_agent.clone(),
+                                score: verdict.score,
+                                matched_rules: verdict.matched_rules.join(","),
+                                action: if blocked { "blocked" } else { "reported" },
+                            });
+                            if blocked {
+                                let response = self.error_response(
+                                    StatusCode::FORBIDDEN,
+                                    "Request blocked by web application firewall",
+                                );
+                                let ms = start.elapsed().as_millis() as u64;
+                                self.log_request(ProxyLogEntry {
+                                    host: log_host,
+                                    method: log_method,
+                                    path: log_path,
+                                    status: response.status().as_u16(),
+                                    response_ms: ms,
+                                    client_ip: log_client_ip,
+                                    user_agent: log_user_agent,
+                                });
+                                return Ok(response);
+                            }
+                        }
+                    }
+
+                    // CORS: answer preflights here (they carry no credentials, so this
+                    // comes before auth) and remember the origin for the response headers
+                    let cors_origin = match backend.cors {
+                        Some(ref policy) => {
+                            if cors::is_preflight(req.method(), req.headers()) {
+                                let header =
+                                    |name| req.headers().get(name).and_then(|v| v.to_str().ok());
+                                let response = match policy.preflight(
+                                    header(hyper::header::ORIGIN).unwrap_or(""),
+                                    header(hyper::header::ACCESS_CONTROL_REQUEST_METHOD)
+                                        .unwrap_or(""),
+                                    header(hyper::header::ACCESS_CONTROL_REQUEST_HEADERS),
+                                ) {
+                                    Preflight::Allowed(headers) => cors_preflight_response(headers),
+                                    Preflight::Denied(reason) => {
+                                        debug!(host = ?host, path = %path, reason, "CORS preflight denied");
+                                        self.error_response(
+                                            StatusCode::FORBIDDEN,
+                                            "CORS preflight denied",
+                                        )
+                                    }
+                                };
+                                let ms = start.elapsed().as_millis() as u64;
+                                self.log_request(ProxyLogEntry {
+                                    host: log_host,
+                                    method: log_method,
+                                    path: log_path,
+                                    status: response.status().as_u16(),
+                                    response_ms: ms,
+                                    client_ip: log_client_ip,
+                                    user_agent: log_user_agent,
+                                });
+                                return Ok(response);
+                            }
+                            req.headers()
+                                .get(hyper::header::ORIGIN)
+                                .and_then(|v| v.to_str().ok())
+                                .map(|o| o.to_string())
+                        }
+                        None => None,
+                    };
+
+                    let is_healthcheck = backend
+                        .healthcheck_path
+                        .as_ref()
+                        .map(|p| path == p)
+                        .unwrap_or(false);
+
+                    // Forward auth: serve the sign-in endpoints, then require a valid
+                    // session (except on the health check path) and pass the identity on
+                    if let Some(ref policy) = backend.forward_auth {
+                        if path.starts_with(forward_auth::AUTH_PATH_PREFIX) {
+                            let response = self
+                                .serve_forward_auth(
+                                    req,
+                                    remote_addr,
+                                    policy,
+                                    host.as_deref().unwrap_or(""),
+                                )
+                                .await;
+                            let ms = start.elapsed().as_millis() as u64;
+                            self.log_request(ProxyLogEntry {
+                                host: log_host,
+                                method: log_method,
+                                path: log_path,
+                                status: response.status().as_u16(),
+                                response_ms: ms,
+                                client_ip: log_client_ip,
+                                user_agent: log_user_agent,
+                            });
+                            return Ok(response);
+                        }
+
+                        let cookies = req
+                            .headers()
+                            .get(hyper::header::COOKIE)
+                            .and_then(|v| v.to_str().ok());
+                        let identity = policy.verify_session(cookies);
+                        if identity.is_none() && !is_healthcheck {
+                            debug!(host = ?host, path = %path, "Forward auth session required");
+                            let response = self.forward_auth_challenge(&req);
+                            let ms = start.elapsed().as_millis() as u64;
+                            self.log_request(ProxyLogEntry {
+                                host: log_host,
+                                method: log_method,
+                                path: log_path,
+                                status: response.status().as_u16(),
+                                response_ms: ms,
+                                client_ip: log_client_ip,
+                                user_agent: log_user_agent,
+                            });
+                            return Ok(response);
+                        }
+                        forward_auth::set_identity_headers(req.headers_mut(), identity.as_ref());
+                    }
 
+                    // Check HTTP Basic Auth if enabled for this path (but bypass for health check path)
+                    if let Some((username, password_hash)) = basic_auth_credentials(&backend, path)
+                    {
                         if !is_healthcheck {
-                            if let Err(response) = self.check_basic_auth(&req, &backend) {
+                            if let Err(response) =
+                                self.check_basic_auth(&req, username, password_hash)
+                            {
                                 debug!(
                                     host = ?host,
                                     path = %path,
@@ -334,6 +983,39 @@ impl ProxyHandler {
                         }
                     }
 
+                    // Chaos mode: fail or slow the request as an unhealthy backend would
+                    match self
+                        .faults
+                        .as_ref()
+                        .and_then(|f| f.proxy_fault(host.as_deref()))
+                    {
+                        Some(ProxyFault::Error) => {
+                            warn!(host = ?host, path = %path, "Injected backend failure");
+                            let response =
+                                self.error_response(StatusCode::BAD_GATEWAY, "Backend unavailable");
+                            let ms = start.elapsed().as_millis() as u64;
+                            self.log_request(ProxyLogEntry {
+                                host: log_host,
+                                method: log_method,
+                                path: log_path,
+                                status: response.status().as_u16(),
+                                response_ms: ms,
+                                client_ip: log_client_ip,
+                                user_agent: log_user_agent,
+                            });
+                            return Ok(response);
+                        }
+                        Some(ProxyFault::Delay(delay)) => {
+                            debug!(
+                                host = ?host,
+                                delay_ms = delay.as_millis() as u64,
+                                "Injected slow response"
+                            );
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => {}
+                    }
+
                     info!(
                         method = %method,
                         uri = %uri,
@@ -343,13 +1025,72 @@ impl ProxyHandler {
                         "Forwarding request"
                     );
 
+                    // Count the request against the backend container so a route swap
+                    // can drain it before the old container is stopped
+                    let connection = routes.track_connection(&backend.container_id);
+
                     // Handle WebSocket upgrades specially (skip logging for WS)
                     if is_websocket {
                         return self.handle_websocket_upgrade(req, &backend).await;
                     }
 
-                    match self.proxy_service.forward(req, &backend, self.forwarded_proto).await {
-                        Ok(response) => response,
+                    // Keep a copy of bodiless idempotent requests so they can be retried
+                    // against the previous deployment's container if this one is unreachable
+                    let failover = backend
+                        .fallback_backend()
+                        .and_then(|fallback| replayable_request(&req).map(|r| (fallback, r)));
+
+                    let domain = traffic::domain_label(&routes, host.as_deref());
+                    traffic::record_request(&domain);
+                    let req = traffic::count_request_bytes(req, domain.clone());
+                    let upstream_start = Instant::now();
+                    let result = match self
+                        .proxy_service
+                        .forward(req, &backend, self.forwarded_proto)
+                        .await
+                    {
+                        Ok(response) => Ok((response, connection)),
+                        Err(e) => match failover {
+                            Some((fallback, replay)) => {
+                                drop(connection);
+                                warn!(
+                                    error = %e,
+                                    backend = %backend.addr(),
+                                    fallback = %fallback.addr(),
+                                    "Backend request failed; retrying on previous container"
+                                );
+                                let connection = routes.track_connection(&fallback.container_id);
+                                self.proxy_service
+                                    .forward(replay, &fallback, self.forwarded_proto)
+                                    .await
+                                    .map(|response| (response, connection))
+                            }
+                            None => Err(e),
+                        },
+                    };
+                    match result {
+                        Ok((mut response, connection)) => {
+                            observe_proxy_upstream_duration(
+                                &domain,
+                                upstream_start.elapsed().as_secs_f64(),
+                            );
+                            if backend.bots.as_ref().is_some_and(|b| b.deny_robots) {
+                                response.headers_mut().insert(
+                                    "X-Robots-Tag",
+                                    hyper::header::HeaderValue::from_static(
+                                        bots::NOINDEX_HEADER_VALUE,
+                                    ),
+                                );
+                            }
+                            if let Some(ref policy) = backend.cors {
+                                policy.apply_response_headers(
+                                    cors_origin.as_deref(),
+                                    response.headers_mut(),
+                                );
+                            }
+                            let response = traffic::count_response_bytes(response, domain);
+                            super::drain::track_response(response, connection)
+                        }
                         Err(e) => {
                             error!(error = %e, backend = %backend.addr(), "Backend request failed");
                             self.error_response(StatusCode::BAD_GATEWAY, "Backend unavailable")
@@ -364,16 +1105,25 @@ impl ProxyHandler {
                     "Service temporarily unavailable",
                 )
             }
-            None => {
-                warn!(host = ?host, "No backend found for host");
-                self.error_response(
-                    StatusCode::NOT_FOUND,
-                    &format!(
-                        "No application found for host: {}",
-                        host.as_deref().unwrap_or("unknown")
-                    ),
-                )
-            }
+            None => match host.as_deref().and_then(|h| routes.parked_message(h)) {
+                Some(message) => {
+                    debug!(host = ?host, "App is stopped by its power schedule");
+                    self.error_response(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        &forward_auth::html_escape(&message),
+                    )
+                }
+                None => {
+                    warn!(host = ?host, "No backend found for host");
+                    self.error_response(
+                        StatusCode::NOT_FOUND,
+                        &format!(
+                            "No application found for host: {}",
+                            host.as_deref().unwrap_or("unknown")
+                        ),
+                    )
+                }
+            },
         };
 
         let ms = start.elapsed().as_millis() as u64;
@@ -505,7 +1255,8 @@ impl ProxyHandler {
     fn check_basic_auth<T>(
         &self,
         req: &Request<T>,
-        backend: &Backend,
+        expected_username: &str,
+        password_hash: &str,
     ) -> Result<(), Response<BoxBody<Bytes, hyper::Error>>> {
         // Get Authorization header
         let auth_header = req
@@ -539,10 +1290,6 @@ impl ProxyHandler {
             }
         };
 
-        // Verify credentials
-        let expected_username = backend.basic_auth.username.as_deref().unwrap_or("");
-        let password_hash = backend.basic_auth.password_hash.as_deref().unwrap_or("");
-
         // Check username
         if username != expected_username {
             return Err(self.unauthorized_response("Protected Application"));
@@ -714,6 +1461,100 @@ impl ProxyHandler {
     }
 }
 
+/// Evaluate access rules in order against a request.
+///
+/// Returns `Some(status)` with the denial status (403 for blocked user agents,
+/// paths and countries, 405 for a method not allowed on a path) or `None` to
+/// continue. Country rules are skipped when `country` is `None` (no GeoIP
+/// database configured).
+fn check_access_rules(
+    rules: &[crate::proxy::AccessRule],
+    method: &str,
+    path: &str,
+    user_agent: &str,
+    country: Option<&str>,
+) -> Option<StatusCode> {
+    for rule in rules {
+        if rule.is_country_rule() {
+            let Some(country) = country else {
+                continue;
+            };
+            let listed = rule.countries.iter().any(|c| c == country);
+            let denied = match rule.rule_type.as_str() {
+                crate::db::ACCESS_RULE_ALLOW_COUNTRIES => !listed,
+                _ => listed,
+            };
+            if denied {
+                return Some(StatusCode::FORBIDDEN);
+            }
+            continue;
+        }
+
+        // Invalid patterns are rejected when saved and logged when loaded
+        let Some(re) = &rule.regex else {
+            continue;
+        };
+
+        match rule.rule_type.as_str() {
+            crate::db::ACCESS_RULE_BLOCK_USER_AGENT if re.is_match(user_agent) => {
+                return Some(StatusCode::FORBIDDEN);
+            }
+            crate::db::ACCESS_RULE_BLOCK_PATH if re.is_match(path) => {
+                return Some(StatusCode::FORBIDDEN);
+            }
+            crate::db::ACCESS_RULE_ALLOW_METHODS
+                if re.is_match(path)
+                    && !rule.methods.iter().any(|m| m.eq_ignore_ascii_case(method)) =>
+            {
+                return Some(StatusCode::METHOD_NOT_ALLOWED);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Credentials (username, password hash) required for a request path, if any.
+///
+/// The most specific matching path rule wins; paths no rule covers fall back to
+/// the app-wide basic auth settings.
+fn basic_auth_credentials<'a>(backend: &'a Backend, path: &str) -> Option<(&'a str, &'a str)> {
+    if let Some(rule) = backend
+        .basic_auth_paths
+        .iter()
+        .filter(|r| r.matches(path))
+        .max_by_key(|r| r.path_prefix.len())
+    {
+        return Some((&rule.username, &rule.password_hash));
+    }
+
+    if backend.basic_auth.enabled {
+        return Some((
+            backend.basic_auth.username.as_deref().unwrap_or(""),
+            backend.basic_auth.password_hash.as_deref().unwrap_or(""),
+        ));
+    }
+    None
+}
+
+/// A bodiless copy of an idempotent request for retrying against another
+/// backend, or `None` if the request is not safe to replay.
+fn replayable_request<B: Body>(req: &Request<B>) -> Option<Request<BoxBody<Bytes, hyper::Error>>> {
+    let idempotent = matches!(
+        *req.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE
+    );
+    if !idempotent || !req.body().is_end_stream() {
+        return None;
+    }
+    let mut replay = Request::new(Empty::new().map_err(|e| match e {}).boxed());
+    *replay.method_mut() = req.method().clone();
+    *replay.uri_mut() = req.uri().clone();
+    *replay.version_mut() = req.version();
+    *replay.headers_mut() = req.headers().clone();
+    Some(replay)
+}
+
 /// Build a header-less response with just a status and empty body.
 ///
 /// `Response::new` cannot fail (no header/status validation), so this is a
@@ -726,3 +1567,184 @@ fn empty_response(status: StatusCode) -> Response<BoxBody<Bytes, hyper::Error>>
     *resp.status_mut() = status;
     resp
 }
+
+/// Empty 204 answer to an allowed CORS preflight
+fn cors_preflight_response(
+    headers: Vec<(hyper::header::HeaderName, String)>,
+) -> Response<BoxBody<Bytes, hyper::Error>> {
+    let mut builder = Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header("X-Powered-By", "Rivetr");
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(Full::new(Bytes::new()).map_err(|e| match e {}).boxed())
+        .unwrap_or_else(|_| empty_response(StatusCode::BAD_REQUEST))
+}
+
+/// Uncacheable redirect that sets the given cookies
+fn forward_auth_redirect(
+    status: StatusCode,
+    location: &str,
+    cookies: &[String],
+) -> Response<BoxBody<Bytes, hyper::Error>> {
+    let mut builder = Response::builder()
+        .status(status)
+        .header(hyper::header::LOCATION, location)
+        .header("Cache-Control", "no-store")
+        .header("X-Powered-By", "Rivetr");
+    for cookie in cookies {
+        builder = builder.header(hyper::header::SET_COOKIE, cookie);
+    }
+    builder
+        .body(Full::new(Bytes::new()).map_err(|e| match e {}).boxed())
+        .unwrap_or_else(|_| empty_response(StatusCode::BAD_REQUEST))
+}
+
+/// Uncacheable HTML page for the forward auth endpoints
+fn forward_auth_page(status: StatusCode, body: String) -> Response<BoxBody<Bytes, hyper::Error>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .header("Cache-Control", "no-store")
+        .header("X-Powered-By", "Rivetr")
+        .body(Full::new(Bytes::from(body)).map_err(|e| match e {}).boxed())
+        .unwrap_or_else(|_| empty_response(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::AccessRule;
+
+    fn rule(rule_type: &str, pattern: &str, methods: &[&str]) -> AccessRule {
+        AccessRule {
+            rule_type: rule_type.to_string(),
+            pattern: pattern.to_string(),
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+            countries: crate::db::parse_country_codes(pattern).unwrap_or_default(),
+            regex: Regex::new(pattern).ok(),
+        }
+    }
+
+    #[test]
+    fn test_check_access_rules() {
+        let rules = vec![
+            rule("block_user_agent", "(?i)sqlmap|nikto", &[]),
+            rule("block_path", r"^/\.env", &[]),
+            rule("allow_methods", "^/uploads", &["GET", "HEAD"]),
+        ];
+
+        assert_eq!(
+            check_access_rules(&rules, "GET", "/", "sqlmap/1.7", None),
+            Some(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            check_access_rules(&rules, "GET", "/.env", "curl/8.0", None),
+            Some(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            check_access_rules(&rules, "DELETE", "/uploads/a.png", "curl/8.0", None),
+            Some(StatusCode::METHOD_NOT_ALLOWED)
+        );
+        assert_eq!(
+            check_access_rules(&rules, "GET", "/uploads/a.png", "curl/8.0", None),
+            None
+        );
+        assert_eq!(
+            check_access_rules(&rules, "POST", "/api", "curl/8.0", None),
+            None
+        );
+
+        // A pattern that doesn't compile is skipped rather than blocking everything
+        let invalid = vec![rule("block_path", "(", &[])];
+        assert_eq!(
+            check_access_rules(&invalid, "GET", "/", "curl/8.0", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_country_access_rules() {
+        let allow = vec![rule("allow_countries", "DE,FR", &[])];
+        let block = vec![rule("block_countries", "RU, KP", &[])];
+        let check = |rules: &[AccessRule], country| {
+            check_access_rules(rules, "GET", "/", "curl/8.0", country)
+        };
+
+        assert_eq!(check(&allow, Some("DE")), None);
+        assert_eq!(check(&allow, Some("US")), Some(StatusCode::FORBIDDEN));
+        assert_eq!(check(&allow, Some("XX")), Some(StatusCode::FORBIDDEN));
+        assert_eq!(check(&block, Some("KP")), Some(StatusCode::FORBIDDEN));
+        assert_eq!(check(&block, Some("DE")), None);
+        // Without a GeoIP database country rules are skipped
+        assert_eq!(check(&allow, None), None);
+        assert_eq!(check(&block, None), None);
+    }
+
+    #[test]
+    fn test_basic_auth_credentials() {
+        let path_rule = |prefix: &str, username: &str| crate::proxy::PathBasicAuth {
+            path_prefix: prefix.to_string(),
+            username: username.to_string(),
+            password_hash: format!("{}-hash", username),
+        };
+
+        let mut backend = Backend::new("c1".into(), "127.0.0.1".into(), 3000);
+        backend.set_basic_auth_paths(vec![
+            path_rule("/admin", "admin"),
+            path_rule("/admin/billing", "finance"),
+        ]);
+
+        assert_eq!(basic_auth_credentials(&backend, "/"), None);
+        assert_eq!(basic_auth_credentials(&backend, "/administrator"), None);
+        assert_eq!(
+            basic_auth_credentials(&backend, "/admin/users"),
+            Some(("admin", "admin-hash"))
+        );
+        assert_eq!(
+            basic_auth_credentials(&backend, "/admin/billing/invoices"),
+            Some(("finance", "finance-hash"))
+        );
+
+        backend.set_basic_auth(crate::proxy::BasicAuthConfig::new(
+            "site".into(),
+            "site-hash".into(),
+        ));
+        assert_eq!(
+            basic_auth_credentials(&backend, "/"),
+            Some(("site", "site-hash"))
+        );
+        assert_eq!(
+            basic_auth_credentials(&backend, "/admin"),
+            Some(("admin", "admin-hash"))
+        );
+    }
+
+    #[test]
+    fn test_replayable_request() {
+        let get = Request::builder()
+            .method(Method::GET)
+            .uri("/items?page=2")
+            .header("X-Forwarded-User", "alice")
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+        let replay = replayable_request(&get).unwrap();
+        assert_eq!(replay.method(), Method::GET);
+        assert_eq!(replay.uri(), "/items?page=2");
+        assert_eq!(replay.headers()["X-Forwarded-User"], "alice");
+
+        let post = Request::builder()
+            .method(Method::POST)
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+        assert!(replayable_request(&post).is_none());
+
+        let put_with_body = Request::builder()
+            .method(Method::PUT)
+            .body(Full::new(Bytes::from_static(b"{}")))
+            .unwrap();
+        assert!(replayable_request(&put_with_body).is_none());
+    }
+}
@@ -3,30 +3,149 @@
 // Periodically checks the health of all registered backends and updates
 // their health status in the route table. Supports automatic recovery
 // detection when previously unhealthy backends become healthy again.
+//
+// Backends are probed with an HTTP GET by default. Apps without an HTTP
+// endpoint (workers, TCP services) can use a TCP connect or a command run
+// inside the container instead.
+//
+// Each routed domain has its own schedule. New domains are spread over the
+// first interval and every check is rescheduled with random jitter, so
+// hundreds of routes don't get probed in the same instant. A semaphore
+// bounds how many checks run at once.
+//
+// Every result is kept in `health_history`. Backends of apps send
+// `backend_unhealthy` / `backend_recovered` notifications when their health
+// changes, except while they flap between the two.
 
 use arc_swap::ArcSwap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::time::interval;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
+use super::health_history::{self, FlapTransition, HealthCheckRecord};
 use crate::api::metrics::{
     record_health_check_failure, record_health_check_success, set_backend_healthy,
     set_health_check_consecutive_failures,
 };
 
-use super::RouteTable;
+use super::{Backend, RouteTable};
 use crate::config::ProxyConfig;
+use crate::db::{App, NotificationEventType};
+use crate::notifications::{outbox, NotificationPayload};
+use crate::runtime::ContainerRuntime;
+use crate::DbPool;
+
+/// Probe with an HTTP GET of the health check path
+pub const HEALTHCHECK_HTTP: &str = "http";
+/// Probe by opening a TCP connection to the backend port
+pub const HEALTHCHECK_TCP: &str = "tcp";
+/// Probe by running a command inside the container
+pub const HEALTHCHECK_COMMAND: &str = "command";
+
+/// All accepted `healthcheck_type` values.
+pub const HEALTHCHECK_TYPES: &[&str] = &[HEALTHCHECK_HTTP, HEALTHCHECK_TCP, HEALTHCHECK_COMMAND];
+
+/// How a backend's health is probed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HealthProbe {
+    /// GET the health check path; 2xx and 3xx are healthy
+    #[default]
+    Http,
+    /// Connect to the backend port
+    Tcp,
+    /// Run the command with `sh -c` in the container; exit code 0 is healthy
+    Command(String),
+}
+
+impl HealthProbe {
+    /// Probe for an app's `healthcheck_type` and `healthcheck_command`.
+    /// Unknown types and command probes without a command fall back to HTTP.
+    pub fn from_settings(kind: &str, command: Option<&str>) -> Self {
+        match kind {
+            HEALTHCHECK_TCP => Self::Tcp,
+            HEALTHCHECK_COMMAND => match command.map(str::trim).filter(|c| !c.is_empty()) {
+                Some(command) => Self::Command(command.to_string()),
+                None => Self::Http,
+            },
+            _ => Self::Http,
+        }
+    }
+}
+
+/// Probe `backend` once. `Ok` and `Err` carry a short description for logs.
+pub async fn probe_backend(
+    backend: &Backend,
+    client: &reqwest::Client,
+    runtime: Option<&dyn ContainerRuntime>,
+    timeout: Duration,
+) -> Result<String, String> {
+    match backend.health_probe {
+        HealthProbe::Http => {
+            let url = backend.health_url();
+            match client.get(&url).timeout(timeout).send().await {
+                // Accept 2xx (success) and 3xx (redirect) as healthy —
+                // apps that redirect HTTP→HTTPS are still alive.
+                Ok(response)
+                    if response.status().is_success() || response.status().is_redirection() =>
+                {
+                    Ok(format!("status {}", response.status()))
+                }
+                Ok(response) => Err(format!("status {}", response.status())),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        HealthProbe::Tcp => {
+            let connect = tokio::net::TcpStream::connect((backend.host.as_str(), backend.port));
+            match tokio::time::timeout(timeout, connect).await {
+                Ok(Ok(_)) => Ok(format!("connected to port {}", backend.port)),
+                Ok(Err(e)) => Err(format!("connect to port {} failed: {}", backend.port, e)),
+                Err(_) => Err(format!("connect to port {} timed out", backend.port)),
+            }
+        }
+        HealthProbe::Command(ref command) => {
+            let Some(runtime) = runtime else {
+                return Err("no container runtime to run the health command".to_string());
+            };
+            let cmd = vec!["sh".to_string(), "-c".to_string(), command.clone()];
+            match tokio::time::timeout(timeout, runtime.run_command(&backend.container_id, cmd))
+                .await
+            {
+                Ok(Ok(result)) if result.exit_code == 0 => Ok("command exited 0".to_string()),
+                Ok(Ok(result)) => Err(format!("command exited {}", result.exit_code)),
+                Ok(Err(e)) => Err(format!("command failed: {}", e)),
+                Err(_) => Err("command timed out".to_string()),
+            }
+        }
+    }
+}
+
+/// Longest jitter accepted, as a fraction of the interval
+const MAX_JITTER: f64 = 0.5;
+
+/// Longest the scheduler sleeps before looking for new routes
+const MAX_SCHEDULER_SLEEP: Duration = Duration::from_secs(1);
 
 /// Configuration for the health checker
 #[derive(Debug, Clone)]
 pub struct HealthCheckerConfig {
-    /// Interval between health check rounds
+    /// Interval between checks of each backend
     pub interval: Duration,
-    /// Timeout for individual health check requests
+    /// Timeout for a whole health check
     pub timeout: Duration,
+    /// Timeout for opening the connection (HTTP and TCP probes)
+    pub connect_timeout: Duration,
+    /// Timeout for each read of an HTTP probe's response
+    pub read_timeout: Duration,
     /// Number of consecutive failures before marking backend unhealthy
     pub failure_threshold: u32,
+    /// Most checks run at the same time
+    pub max_concurrency: usize,
+    /// Fraction of the interval each check is moved earlier or later by, at random
+    pub jitter: f64,
 }
 
 impl HealthCheckerConfig {
@@ -34,7 +153,11 @@ impl HealthCheckerConfig {
         Self {
             interval: Duration::from_secs(config.health_check_interval),
             timeout: Duration::from_secs(config.health_check_timeout),
+            connect_timeout: Duration::from_secs(config.health_check_connect_timeout),
+            read_timeout: Duration::from_secs(config.health_check_read_timeout),
             failure_threshold: config.health_check_threshold,
+            max_concurrency: config.health_check_concurrency.max(1),
+            jitter: (config.health_check_jitter_percent as f64 / 100.0).min(MAX_JITTER),
         }
     }
 }
@@ -44,8 +167,97 @@ impl Default for HealthCheckerConfig {
         Self {
             interval: Duration::from_secs(30),
             timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(2),
+            read_timeout: Duration::from_secs(4),
             failure_threshold: 3,
+            max_concurrency: 32,
+            jitter: 0.1,
+        }
+    }
+}
+
+/// `interval` moved earlier or later by up to `jitter` of itself, for a
+/// random `sample` in `[0, 1)`
+fn jittered(interval: Duration, jitter: f64, sample: f64) -> Duration {
+    interval.mul_f64(1.0 + jitter.clamp(0.0, MAX_JITTER) * (2.0 * sample - 1.0))
+}
+
+/// When each routed domain is next due a health check
+#[derive(Debug, Default)]
+struct Schedule {
+    next_due: HashMap<String, Instant>,
+}
+
+impl Schedule {
+    /// Track new domains, spread over the first interval by `sample`, and
+    /// forget removed ones, which are returned
+    fn sync<'a>(
+        &mut self,
+        domains: impl Iterator<Item = &'a str>,
+        now: Instant,
+        interval: Duration,
+        mut sample: impl FnMut() -> f64,
+    ) -> Vec<String> {
+        let current: HashSet<&str> = domains.collect();
+        let removed: Vec<String> = self
+            .next_due
+            .keys()
+            .filter(|domain| !current.contains(domain.as_str()))
+            .cloned()
+            .collect();
+        for domain in &removed {
+            self.next_due.remove(domain);
+        }
+        for domain in current {
+            self.next_due
+                .entry(domain.to_string())
+                .or_insert_with(|| now + interval.mul_f64(sample()));
+        }
+        removed
+    }
+
+    fn is_due(&self, domain: &str, now: Instant) -> bool {
+        self.next_due.get(domain).is_some_and(|due| *due <= now)
+    }
+
+    fn reschedule(&mut self, domain: &str, at: Instant) {
+        if let Some(due) = self.next_due.get_mut(domain) {
+            *due = at;
+        }
+    }
+
+    /// The earliest time a check is due
+    fn next(&self) -> Option<Instant> {
+        self.next_due.values().min().copied()
+    }
+}
+
+/// A health change worth notifying about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HealthNotice {
+    /// The backend was marked healthy or unhealthy
+    Changed { healthy: bool },
+    /// The backend started flapping; notifications pause
+    Flapping,
+    /// The backend stopped flapping and is now healthy or not
+    Settled { healthy: bool },
+}
+
+/// The notice a check calls for, if any. Plain health changes are
+/// suppressed while the backend flaps.
+fn health_notice(
+    status_changed: bool,
+    flap: FlapTransition,
+    flapping: bool,
+    healthy: bool,
+) -> Option<HealthNotice> {
+    match flap {
+        FlapTransition::Started => Some(HealthNotice::Flapping),
+        FlapTransition::Stopped => Some(HealthNotice::Settled { healthy }),
+        FlapTransition::None if status_changed && !flapping => {
+            Some(HealthNotice::Changed { healthy })
         }
+        FlapTransition::None => None,
     }
 }
 
@@ -54,12 +266,18 @@ pub struct HealthChecker {
     routes: Arc<ArcSwap<RouteTable>>,
     config: HealthCheckerConfig,
     client: reqwest::Client,
+    /// Runs command probes inside containers (None = command probes fail)
+    runtime: Option<Arc<dyn ContainerRuntime>>,
+    /// Queues health notifications for apps' backends (None = no notifications)
+    db: Option<DbPool>,
 }
 
 impl HealthChecker {
     pub fn new(routes: Arc<ArcSwap<RouteTable>>, config: HealthCheckerConfig) -> Self {
         let client = reqwest::Client::builder()
             .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .read_timeout(config.read_timeout)
             .redirect(reqwest::redirect::Policy::none())
             .build()
             .expect("Failed to build HTTP client");
@@ -68,141 +286,256 @@ impl HealthChecker {
             routes,
             config,
             client,
+            runtime: None,
+            db: None,
         }
     }
 
+    /// Set the container runtime used for command probes
+    pub fn with_runtime(mut self, runtime: Arc<dyn ContainerRuntime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Set the database used to notify about apps' backends changing health
+    pub fn with_db(mut self, db: DbPool) -> Self {
+        self.db = Some(db);
+        self
+    }
+
     /// Start the health checker background task
     pub async fn run(self) {
         info!(
             interval_secs = self.config.interval.as_secs(),
             timeout_secs = self.config.timeout.as_secs(),
             threshold = self.config.failure_threshold,
+            concurrency = self.config.max_concurrency,
+            jitter = self.config.jitter,
             "Health checker started"
         );
 
-        let mut ticker = interval(self.config.interval);
+        let checker = Arc::new(self);
+        let permits = Arc::new(Semaphore::new(checker.config.max_concurrency.max(1)));
+        let mut schedule = Schedule::default();
+        let mut checks: JoinSet<String> = JoinSet::new();
+        let mut running: HashSet<String> = HashSet::new();
 
         loop {
-            ticker.tick().await;
-            self.check_all_backends().await;
+            while let Some(finished) = checks.try_join_next() {
+                if let Ok(domain) = finished {
+                    running.remove(&domain);
+                }
+            }
+
+            let now = Instant::now();
+            let backends = checker.routes.load().all_backends();
+            let removed = schedule.sync(
+                backends.iter().map(|(domain, _)| domain.as_str()),
+                now,
+                checker.config.interval,
+                rand::random::<f64>,
+            );
+            for domain in removed {
+                health_history::forget(&domain);
+            }
+
+            for (domain, backend) in backends {
+                // A slow check is never overlapped by the next one
+                if running.contains(&domain) || !schedule.is_due(&domain, now) {
+                    continue;
+                }
+                let next = jittered(
+                    checker.config.interval,
+                    checker.config.jitter,
+                    rand::random::<f64>(),
+                );
+                schedule.reschedule(&domain, now + next);
+                running.insert(domain.clone());
+
+                let checker = checker.clone();
+                let permits = permits.clone();
+                checks.spawn(async move {
+                    let _permit = permits.acquire_owned().await;
+                    crate::utils::supervise::guarded(
+                        "health_check",
+                        checker.check_backend(&domain, backend),
+                    )
+                    .await;
+                    domain
+                });
+            }
+
+            let wake = schedule.next().map_or(now + MAX_SCHEDULER_SLEEP, |due| {
+                due.min(now + MAX_SCHEDULER_SLEEP)
+            });
+            tokio::time::sleep_until(wake).await;
         }
     }
 
-    /// Check health of all registered backends
-    async fn check_all_backends(&self) {
-        let routes = self.routes.load();
-        let backends = routes.all_backends();
+    /// Probe one backend and update its health in the route table
+    async fn check_backend(&self, domain: &str, backend: Backend) {
+        let was_healthy = backend.healthy();
+        let failure_threshold = self.config.failure_threshold;
+        // A TCP probe is only a connect
+        let timeout = match backend.health_probe {
+            HealthProbe::Tcp => self.config.connect_timeout.min(self.config.timeout),
+            _ => self.config.timeout,
+        };
+
+        // Time the health check
+        let start = Instant::now();
+        let (check_passed, detail) =
+            match probe_backend(&backend, &self.client, self.runtime.as_deref(), timeout).await {
+                Ok(detail) => {
+                    debug!(
+                        domain = %domain,
+                        probe = ?backend.health_probe,
+                        detail = %detail,
+                        "Health check passed"
+                    );
+                    (true, detail)
+                }
+                Err(reason) => {
+                    debug!(
+                        domain = %domain,
+                        probe = ?backend.health_probe,
+                        reason = %reason,
+                        "Health check failed"
+                    );
+                    (false, reason)
+                }
+            };
+        let elapsed = start.elapsed();
+        let duration_secs = elapsed.as_secs_f64();
+
+        // Record health check metrics
+        if check_passed {
+            record_health_check_success(domain, duration_secs);
+        } else {
+            record_health_check_failure(domain, duration_secs);
+        }
 
-        if backends.is_empty() {
-            debug!("No backends to health check");
-            return;
+        // Update health status in route table
+        let routes_ref = self.routes.load();
+        let status_changed = routes_ref.update_health(domain, check_passed, failure_threshold);
+
+        // Get current failure count and update metrics
+        let current_failures = routes_ref
+            .get_backend(domain)
+            .map(|b| b.failure_count())
+            .unwrap_or(0);
+        let is_healthy = routes_ref
+            .get_backend(domain)
+            .map(|b| b.healthy())
+            .unwrap_or(false);
+
+        // Update gauge metrics
+        set_backend_healthy(domain, is_healthy);
+        set_health_check_consecutive_failures(domain, current_failures);
+
+        let flap = health_history::record(
+            domain,
+            HealthCheckRecord {
+                checked_at: chrono::Utc::now(),
+                passed: check_passed,
+                healthy: is_healthy,
+                duration_ms: elapsed.as_millis() as u64,
+                detail: detail.clone(),
+            },
+        );
+        match flap {
+            FlapTransition::Started => warn!(domain = %domain, "Backend is flapping"),
+            FlapTransition::Stopped => info!(domain = %domain, "Backend stopped flapping"),
+            FlapTransition::None => {}
+        }
+        let flapping = health_history::is_domain_flapping(domain);
+        if let Some(notice) = health_notice(status_changed, flap, flapping, is_healthy) {
+            self.notify(domain, notice, &detail).await;
         }
 
-        debug!(count = backends.len(), "Running health checks");
+        if status_changed {
+            if check_passed {
+                info!(
+                    domain = %domain,
+                    "Backend recovered - marked healthy"
+                );
+            } else {
+                warn!(
+                    domain = %domain,
+                    threshold = failure_threshold,
+                    "Backend marked unhealthy after consecutive failures"
+                );
+            }
+        } else if !check_passed && was_healthy {
+            // Backend is failing but not yet unhealthy
+            debug!(
+                domain = %domain,
+                failures = current_failures,
+                threshold = failure_threshold,
+                "Backend health check failed, still within threshold"
+            );
+        }
+    }
 
-        // Run health checks concurrently for all backends
-        let checks: Vec<_> = backends
+    /// Queue a notification for the app owning `domain`. Domains of services
+    /// and routes added through the API have no app and aren't notified about.
+    async fn notify(&self, domain: &str, notice: HealthNotice, detail: &str) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let apps: Vec<App> = match sqlx::query_as("SELECT * FROM apps").fetch_all(db).await {
+            Ok(apps) => apps,
+            Err(e) => {
+                warn!(domain = %domain, error = %e, "Failed to look up app for health notification");
+                return;
+            }
+        };
+        let Some(app) = apps
             .into_iter()
-            .map(|(domain, backend)| {
-                let client = self.client.clone();
-                let failure_threshold = self.config.failure_threshold;
-                let routes = self.routes.clone();
-
-                async move {
-                    let health_url = backend.health_url();
-                    let was_healthy = backend.healthy;
-
-                    // Time the health check
-                    let start = Instant::now();
-                    let check_passed = match client.get(&health_url).send().await {
-                        Ok(response) => {
-                            let status = response.status();
-                            // Accept 2xx (success) and 3xx (redirect) as healthy —
-                            // apps that redirect HTTP→HTTPS are still alive.
-                            if status.is_success() || status.is_redirection() {
-                                debug!(
-                                    domain = %domain,
-                                    url = %health_url,
-                                    status = %status,
-                                    "Health check passed"
-                                );
-                                true
-                            } else {
-                                debug!(
-                                    domain = %domain,
-                                    url = %health_url,
-                                    status = %status,
-                                    "Health check returned non-success status"
-                                );
-                                false
-                            }
-                        }
-                        Err(e) => {
-                            debug!(
-                                domain = %domain,
-                                url = %health_url,
-                                error = %e,
-                                "Health check failed"
-                            );
-                            false
-                        }
-                    };
-                    let duration_secs = start.elapsed().as_secs_f64();
-
-                    // Record health check metrics
-                    if check_passed {
-                        record_health_check_success(&domain, duration_secs);
-                    } else {
-                        record_health_check_failure(&domain, duration_secs);
-                    }
-
-                    // Update health status in route table
-                    let routes_ref = routes.load();
-                    let status_changed =
-                        routes_ref.update_health(&domain, check_passed, failure_threshold);
-
-                    // Get current failure count and update metrics
-                    let current_failures = routes_ref
-                        .get_backend(&domain)
-                        .map(|b| b.failure_count)
-                        .unwrap_or(0);
-                    let is_healthy = routes_ref
-                        .get_backend(&domain)
-                        .map(|b| b.healthy)
-                        .unwrap_or(false);
-
-                    // Update gauge metrics
-                    set_backend_healthy(&domain, is_healthy);
-                    set_health_check_consecutive_failures(&domain, current_failures);
-
-                    if status_changed {
-                        if check_passed {
-                            info!(
-                                domain = %domain,
-                                "Backend recovered - marked healthy"
-                            );
-                        } else {
-                            warn!(
-                                domain = %domain,
-                                threshold = failure_threshold,
-                                "Backend marked unhealthy after consecutive failures"
-                            );
-                        }
-                    } else if !check_passed && was_healthy {
-                        // Backend is failing but not yet unhealthy
-                        debug!(
-                            domain = %domain,
-                            failures = current_failures,
-                            threshold = failure_threshold,
-                            "Backend health check failed, still within threshold"
-                        );
-                    }
-                }
-            })
-            .collect();
-
-        // Wait for all health checks to complete
-        futures::future::join_all(checks).await;
+            .find(|app| app.get_all_domain_names().iter().any(|d| d == domain))
+        else {
+            return;
+        };
+
+        let (event_type, message) = match notice {
+            HealthNotice::Changed { healthy: true } => (
+                NotificationEventType::BackendRecovered,
+                format!("{} passed its health check and is healthy again", domain),
+            ),
+            HealthNotice::Changed { healthy: false } => (
+                NotificationEventType::BackendUnhealthy,
+                format!(
+                    "{} failed {} health checks in a row and was marked unhealthy: {}",
+                    domain, self.config.failure_threshold, detail
+                ),
+            ),
+            HealthNotice::Flapping => (
+                NotificationEventType::BackendUnhealthy,
+                format!(
+                    "{} is flapping: its health changed at least {} times in the last {} \
+                     checks. Health notifications for it are paused until it settles.",
+                    domain,
+                    health_history::FLAP_START_CHANGES,
+                    health_history::FLAP_WINDOW
+                ),
+            ),
+            HealthNotice::Settled { healthy } => (
+                if healthy {
+                    NotificationEventType::BackendRecovered
+                } else {
+                    NotificationEventType::BackendUnhealthy
+                },
+                format!(
+                    "{} stopped flapping and is {}. Health notifications resume.",
+                    domain,
+                    if healthy { "healthy" } else { "unhealthy" }
+                ),
+            ),
+        };
+        let payload = NotificationPayload::app_event(event_type, app.id, app.name, message);
+        if let Err(e) = outbox::enqueue(db, &payload).await {
+            warn!(domain = %domain, error = %e, "Failed to queue health notification");
+        }
     }
 }
 
@@ -219,6 +552,106 @@ mod tests {
         assert_eq!(config.failure_threshold, 3);
     }
 
+    #[test]
+    fn test_jittered_interval() {
+        let interval = Duration::from_secs(30);
+        assert_eq!(jittered(interval, 0.1, 0.5), interval);
+        assert_eq!(jittered(interval, 0.1, 0.0), Duration::from_secs(27));
+        assert_eq!(jittered(interval, 0.0, 0.9), interval);
+        // Jitter is capped at half the interval
+        assert_eq!(jittered(interval, 2.0, 0.0), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_schedule_spreads_new_domains_and_forgets_removed() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(30);
+        let mut schedule = Schedule::default();
+        let mut samples = [0.0, 0.5].into_iter();
+        schedule.sync(
+            ["a.example.com", "b.example.com"].into_iter(),
+            now,
+            interval,
+            || samples.next().unwrap(),
+        );
+        assert_eq!(schedule.next(), Some(now));
+        let due_now = ["a.example.com", "b.example.com"]
+            .into_iter()
+            .filter(|d| schedule.is_due(d, now))
+            .count();
+        assert_eq!(due_now, 1);
+        assert!(schedule.is_due("a.example.com", now + Duration::from_secs(15)));
+        assert!(schedule.is_due("b.example.com", now + Duration::from_secs(15)));
+
+        // Known domains keep their schedule; removed ones are dropped
+        schedule.reschedule("a.example.com", now + interval);
+        schedule.sync(["a.example.com"].into_iter(), now, interval, || 0.0);
+        assert_eq!(schedule.next(), Some(now + interval));
+        assert!(!schedule.is_due("b.example.com", now + interval));
+    }
+
+    #[test]
+    fn test_health_notice_suppressed_while_flapping() {
+        assert_eq!(
+            health_notice(true, FlapTransition::None, false, false),
+            Some(HealthNotice::Changed { healthy: false })
+        );
+        assert_eq!(
+            health_notice(false, FlapTransition::None, false, true),
+            None
+        );
+        assert_eq!(
+            health_notice(true, FlapTransition::Started, true, true),
+            Some(HealthNotice::Flapping)
+        );
+        assert_eq!(health_notice(true, FlapTransition::None, true, false), None);
+        assert_eq!(
+            health_notice(false, FlapTransition::Stopped, false, true),
+            Some(HealthNotice::Settled { healthy: true })
+        );
+    }
+
+    #[test]
+    fn test_health_probe_from_settings() {
+        assert_eq!(HealthProbe::from_settings("http", None), HealthProbe::Http);
+        assert_eq!(HealthProbe::from_settings("tcp", None), HealthProbe::Tcp);
+        assert_eq!(
+            HealthProbe::from_settings("command", Some(" pg_isready ")),
+            HealthProbe::Command("pg_isready".to_string())
+        );
+        // A command probe without a command can't run; fall back to HTTP
+        assert_eq!(
+            HealthProbe::from_settings("command", Some("")),
+            HealthProbe::Http
+        );
+        assert_eq!(HealthProbe::from_settings("bogus", None), HealthProbe::Http);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_probe() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = reqwest::Client::new();
+        let timeout = Duration::from_secs(2);
+
+        let backend = Backend::new("container-123".into(), "127.0.0.1".into(), port)
+            .with_health_probe(HealthProbe::Tcp);
+        assert!(probe_backend(&backend, &client, None, timeout)
+            .await
+            .is_ok());
+
+        drop(listener);
+        assert!(probe_backend(&backend, &client, None, timeout)
+            .await
+            .is_err());
+
+        // Command probes need a runtime
+        let backend = backend.with_health_probe(HealthProbe::Command("true".to_string()));
+        assert!(probe_backend(&backend, &client, None, timeout)
+            .await
+            .is_err());
+    }
+
     #[test]
     fn test_backend_health_url() {
         let backend = Backend::new("container-123".into(), "127.0.0.1".into(), 3000);
@@ -240,35 +673,35 @@ mod tests {
 
         // Initial state: healthy
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 0);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 0);
 
         // First failure: still healthy
         let changed = table.update_health("example.com", false, 3);
         assert!(!changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 1);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 1);
 
         // Second failure: still healthy
         let changed = table.update_health("example.com", false, 3);
         assert!(!changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 2);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 2);
 
         // Third failure: now unhealthy
         let changed = table.update_health("example.com", false, 3);
         assert!(changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(!b.healthy);
-        assert_eq!(b.failure_count, 3);
+        assert!(!b.healthy());
+        assert_eq!(b.failure_count(), 3);
 
         // Recovery: healthy again
         let changed = table.update_health("example.com", true, 3);
         assert!(changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 0);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 0);
     }
 }
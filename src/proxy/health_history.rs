@@ -0,0 +1,192 @@
+// Recent health check results and flap detection per routed domain
+//
+// The health checker records the outcome of every check here. The last
+// `HISTORY_LEN` results per domain are kept in memory (they don't survive a
+// restart) and served by `GET /api/routes/:domain/health/history`.
+//
+// A backend is flapping when its health state (healthy/unhealthy after the
+// failure threshold is applied) changed at least `FLAP_START_CHANGES` times
+// in the last `FLAP_WINDOW` checks. It stops flapping once the state changed
+// at most `FLAP_STOP_CHANGES` times in the window. Up/down notifications are
+// suppressed while a backend flaps; one notice is sent when it starts and
+// one when it settles.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Results kept per domain
+pub const HISTORY_LEN: usize = 50;
+/// Most recent checks flap detection looks at
+pub const FLAP_WINDOW: usize = 20;
+/// State changes in the window that make a backend flapping
+pub const FLAP_START_CHANGES: usize = 4;
+/// State changes in the window at or below which a flapping backend settles
+pub const FLAP_STOP_CHANGES: usize = 1;
+
+/// Outcome of one health check
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheckRecord {
+    pub checked_at: DateTime<Utc>,
+    /// Whether this check's probe succeeded
+    pub passed: bool,
+    /// Whether the backend was healthy after this check
+    pub healthy: bool,
+    pub duration_ms: u64,
+    /// Probe result, e.g. `status 200 OK` or the failure reason
+    pub detail: String,
+}
+
+/// How a check moved a backend in or out of flapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlapTransition {
+    None,
+    Started,
+    Stopped,
+}
+
+#[derive(Debug, Default)]
+struct DomainHistory {
+    records: VecDeque<HealthCheckRecord>,
+    flapping_since: Option<DateTime<Utc>>,
+}
+
+/// A domain's recent checks and flap state
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthHistory {
+    pub flapping: bool,
+    pub flapping_since: Option<DateTime<Utc>>,
+    /// Health state changes in the last `FLAP_WINDOW` checks
+    pub state_changes: usize,
+    /// Checks, newest first
+    pub checks: Vec<HealthCheckRecord>,
+}
+
+lazy_static! {
+    static ref HISTORY: DashMap<String, DomainHistory> = DashMap::new();
+}
+
+/// Health state changes in the last `FLAP_WINDOW` of `records` (oldest first)
+fn state_changes(records: &VecDeque<HealthCheckRecord>) -> usize {
+    let window: Vec<bool> = records
+        .iter()
+        .skip(records.len().saturating_sub(FLAP_WINDOW))
+        .map(|r| r.healthy)
+        .collect();
+    window.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+/// Whether a backend is flapping after a check, with hysteresis so it
+/// doesn't flip in and out of flapping on every check
+fn is_flapping(was_flapping: bool, changes: usize) -> bool {
+    if was_flapping {
+        changes > FLAP_STOP_CHANGES
+    } else {
+        changes >= FLAP_START_CHANGES
+    }
+}
+
+/// Record a check of `domain` and return how it changed the flap state
+pub fn record(domain: &str, record: HealthCheckRecord) -> FlapTransition {
+    let mut history = HISTORY.entry(domain.to_string()).or_default();
+    let now = record.checked_at;
+    history.records.push_back(record);
+    while history.records.len() > HISTORY_LEN {
+        history.records.pop_front();
+    }
+
+    let was_flapping = history.flapping_since.is_some();
+    let flapping = is_flapping(was_flapping, state_changes(&history.records));
+    match (was_flapping, flapping) {
+        (false, true) => {
+            history.flapping_since = Some(now);
+            FlapTransition::Started
+        }
+        (true, false) => {
+            history.flapping_since = None;
+            FlapTransition::Stopped
+        }
+        _ => FlapTransition::None,
+    }
+}
+
+/// Whether `domain` is flapping right now
+pub fn is_domain_flapping(domain: &str) -> bool {
+    HISTORY
+        .get(domain)
+        .is_some_and(|h| h.flapping_since.is_some())
+}
+
+/// Recent checks and flap state of `domain`, if it was checked since startup
+pub fn history(domain: &str) -> Option<HealthHistory> {
+    HISTORY.get(domain).map(|h| HealthHistory {
+        flapping: h.flapping_since.is_some(),
+        flapping_since: h.flapping_since,
+        state_changes: state_changes(&h.records),
+        checks: h.records.iter().rev().cloned().collect(),
+    })
+}
+
+/// Drop the history of a domain that is no longer routed
+pub fn forget(domain: &str) {
+    HISTORY.remove(domain);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(healthy: bool) -> HealthCheckRecord {
+        HealthCheckRecord {
+            checked_at: Utc::now(),
+            passed: healthy,
+            healthy,
+            duration_ms: 5,
+            detail: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_flapping_starts_and_settles() {
+        let domain = "flap.example.com";
+        let mut transitions = Vec::new();
+        // Up, down, up, down, up: four state changes
+        for healthy in [true, false, true, false, true] {
+            transitions.push(record(domain, check(healthy)));
+        }
+        assert_eq!(transitions.last(), Some(&FlapTransition::Started));
+        assert!(is_domain_flapping(domain));
+
+        // Steady checks push the changes out of the window
+        let mut settled_after = None;
+        for i in 0..FLAP_WINDOW {
+            if record(domain, check(true)) == FlapTransition::Stopped {
+                settled_after = Some(i + 1);
+                break;
+            }
+        }
+        assert_eq!(settled_after, Some(FLAP_WINDOW - 2));
+        assert!(!is_domain_flapping(domain));
+
+        let snapshot = history(domain).unwrap();
+        assert!(!snapshot.flapping);
+        assert_eq!(snapshot.state_changes, FLAP_STOP_CHANGES);
+        forget(domain);
+        assert!(history(domain).is_none());
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_newest_first() {
+        let domain = "bounded.example.com";
+        for _ in 0..HISTORY_LEN + 5 {
+            record(domain, check(true));
+        }
+        record(domain, check(false));
+        let snapshot = history(domain).unwrap();
+        assert_eq!(snapshot.checks.len(), HISTORY_LEN);
+        assert!(!snapshot.checks[0].healthy);
+        forget(domain);
+    }
+}
@@ -4,28 +4,55 @@
 // to containers based on the Host header.
 
 pub mod acme;
+pub mod bench;
+pub mod bots;
+pub mod cors;
+pub mod drain;
+pub mod forward_auth;
+pub mod geoip;
 mod handler;
 mod health_checker;
+pub mod health_history;
 mod service;
+pub mod sni;
 pub mod tls;
+pub mod traffic;
+pub mod waf;
 
 use arc_swap::ArcSwap;
-use dashmap::DashMap;
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tracing::{error, info};
 
 pub use acme::{
     AcmeChallenges, AcmeClient, AcmeConfig, CertificateRenewalManager, CertificateResult,
+    ExternalAccountBinding,
 };
+pub use bots::BotPolicy;
+pub use cors::CorsPolicy;
+pub use drain::{ConnectionGuard, ConnectionTracker};
+pub use forward_auth::{ForwardAuthPolicy, ForwardAuthProvider};
+pub use geoip::GeoIp;
 pub use handler::ProxyHandler;
-pub use health_checker::{HealthChecker, HealthCheckerConfig};
-pub use service::ProxyService;
+pub use health_checker::{
+    probe_backend, HealthChecker, HealthCheckerConfig, HealthProbe, HEALTHCHECK_COMMAND,
+    HEALTHCHECK_TYPES,
+};
+pub use service::{ProxyService, UpstreamPoolConfig};
 pub use tls::{CertStore, TlsConfig, TlsReloadHandle};
+pub use waf::{WafMode, WafPolicy};
+
+pub use crate::db::{AccessRule, PathBasicAuth, RedirectRule};
 
-pub use crate::db::RedirectRule;
+use crate::api::rate_limit::RateLimiter;
+use crate::chaos::FaultInjector;
+use crate::engine::scale_to_zero::AppWaker;
+use crate::utils::handover::{self, ListenerRole};
+use crate::utils::net::{accept_either, bind_ipv6_only};
 
 /// HTTP Basic Auth configuration for a backend
 #[derive(Debug, Clone, Default)]
@@ -63,20 +90,92 @@ pub struct Backend {
     pub host: String,
     /// Port the container is listening on
     pub port: u16,
-    /// Whether the backend is healthy
-    pub healthy: bool,
+    /// Health check state, shared by every copy of the backend
+    pub health: Arc<BackendHealth>,
     /// Health check endpoint path (from app config)
     pub healthcheck_path: Option<String>,
-    /// Consecutive failure count for health checks
-    pub failure_count: u32,
+    /// How health checks probe this backend
+    pub health_probe: HealthProbe,
     /// HTTP Basic Auth configuration
     pub basic_auth: BasicAuthConfig,
+    /// Basic auth rules for path prefixes; a matching rule's credentials apply
+    /// instead of the app-wide ones
+    pub basic_auth_paths: Vec<PathBasicAuth>,
     /// URL redirect rules (evaluated in sort_order before forwarding)
     pub redirect_rules: Vec<RedirectRule>,
+    /// Request filtering rules (evaluated in sort_order before auth and redirects)
+    pub access_rules: Vec<AccessRule>,
+    /// Built-in WAF policy (None = WAF disabled for this app)
+    pub waf: Option<WafPolicy>,
+    /// Bot/crawler handling (None = pass all clients through)
+    pub bots: Option<BotPolicy>,
+    /// Sign-in required before proxying (None = no forward auth)
+    pub forward_auth: Option<ForwardAuthPolicy>,
+    /// CORS policy enforced at the proxy (None = backend handles CORS)
+    pub cors: Option<CorsPolicy>,
+    /// Route raw TLS for this domain to the backend by SNI instead of terminating it
+    pub tls_passthrough: bool,
     /// URL prefix to strip from incoming requests before forwarding (e.g. "/api")
     pub strip_prefix: Option<String>,
     /// If set, this backend is a www-redirect proxy: return 301 to this host instead of proxying
     pub www_redirect_target: Option<String>,
+    /// Previous deployment's container, retried for idempotent requests when
+    /// this backend is unreachable
+    pub fallback: Option<FallbackTarget>,
+}
+
+/// Health of a backend. It lives outside the route snapshot (every snapshot
+/// and clone of the backend shares it), so health checks update it in place
+/// instead of copying the route table.
+#[derive(Debug)]
+pub struct BackendHealth {
+    healthy: AtomicBool,
+    /// Consecutive failed health checks
+    failure_count: AtomicU32,
+}
+
+impl Default for BackendHealth {
+    fn default() -> Self {
+        Self {
+            healthy: AtomicBool::new(true),
+            failure_count: AtomicU32::new(0),
+        }
+    }
+}
+
+impl BackendHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    pub fn failure_count(&self) -> u32 {
+        self.failure_count.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+
+    /// Count a health check result; returns true if the health changed
+    fn record(&self, check_passed: bool, failure_threshold: u32) -> bool {
+        if check_passed {
+            self.failure_count.store(0, Ordering::Relaxed);
+            return !self.healthy.swap(true, Ordering::Relaxed);
+        }
+        let failures = self
+            .failure_count
+            .fetch_add(1, Ordering::Relaxed)
+            .saturating_add(1);
+        failures >= failure_threshold && self.healthy.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// A replaced container kept running as a failover target after a deploy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackTarget {
+    pub container_id: String,
+    pub host: String,
+    pub port: u16,
 }
 
 impl Backend {
@@ -85,22 +184,46 @@ impl Backend {
             container_id,
             host,
             port,
-            healthy: true,
+            health: Arc::new(BackendHealth::default()),
             healthcheck_path: None,
-            failure_count: 0,
+            health_probe: HealthProbe::Http,
             basic_auth: BasicAuthConfig::disabled(),
+            basic_auth_paths: Vec::new(),
             redirect_rules: Vec::new(),
+            access_rules: Vec::new(),
+            waf: None,
+            bots: None,
+            forward_auth: None,
+            cors: None,
+            tls_passthrough: false,
             strip_prefix: None,
             www_redirect_target: None,
+            fallback: None,
         }
     }
 
+    /// Whether the backend is healthy
+    pub fn healthy(&self) -> bool {
+        self.health.is_healthy()
+    }
+
+    /// Consecutive failed health checks
+    pub fn failure_count(&self) -> u32 {
+        self.health.failure_count()
+    }
+
     /// Create a new backend with a health check path
     pub fn with_healthcheck(mut self, path: Option<String>) -> Self {
         self.healthcheck_path = path;
         self
     }
 
+    /// Set how health checks probe this backend
+    pub fn with_health_probe(mut self, probe: HealthProbe) -> Self {
+        self.health_probe = probe;
+        self
+    }
+
     /// Create a new backend with basic auth enabled
     pub fn with_basic_auth(mut self, username: String, password_hash: String) -> Self {
         self.basic_auth = BasicAuthConfig::new(username, password_hash);
@@ -112,6 +235,11 @@ impl Backend {
         self.basic_auth = config;
     }
 
+    /// Set path-scoped basic auth rules
+    pub fn set_basic_auth_paths(&mut self, rules: Vec<PathBasicAuth>) {
+        self.basic_auth_paths = rules;
+    }
+
     /// Set redirect rules for this backend
     pub fn with_redirect_rules(mut self, rules: Vec<RedirectRule>) -> Self {
         self.redirect_rules = rules;
@@ -123,6 +251,37 @@ impl Backend {
         self.redirect_rules = rules;
     }
 
+    /// Set access rules for this backend
+    pub fn with_access_rules(mut self, rules: Vec<AccessRule>) -> Self {
+        self.access_rules = rules;
+        self
+    }
+
+    /// Set access rules (mutable reference version)
+    pub fn set_access_rules(&mut self, rules: Vec<AccessRule>) {
+        self.access_rules = rules;
+    }
+
+    /// Set the WAF policy (mutable reference version)
+    pub fn set_waf(&mut self, policy: Option<WafPolicy>) {
+        self.waf = policy;
+    }
+
+    /// Set the bot policy (mutable reference version)
+    pub fn set_bots(&mut self, policy: Option<BotPolicy>) {
+        self.bots = policy;
+    }
+
+    /// Set the forward auth policy (mutable reference version)
+    pub fn set_forward_auth(&mut self, policy: Option<ForwardAuthPolicy>) {
+        self.forward_auth = policy;
+    }
+
+    /// Set the CORS policy (mutable reference version)
+    pub fn set_cors(&mut self, policy: Option<CorsPolicy>) {
+        self.cors = policy;
+    }
+
     /// Set the URL prefix to strip from incoming requests before forwarding
     pub fn with_strip_prefix(mut self, prefix: Option<String>) -> Self {
         self.strip_prefix = prefix;
@@ -130,6 +289,17 @@ impl Backend {
     }
 
     /// Get the backend address as a URI authority
+    /// This backend pointed at its failover container, if it has one
+    pub fn fallback_backend(&self) -> Option<Backend> {
+        let target = self.fallback.as_ref()?;
+        let mut backend = self.clone();
+        backend.container_id = target.container_id.clone();
+        backend.host = target.host.clone();
+        backend.port = target.port;
+        backend.fallback = None;
+        Some(backend)
+    }
+
     pub fn addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
@@ -141,25 +311,44 @@ impl Backend {
     }
 }
 
+/// One replica container in a round-robin pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolMember {
+    /// Container serving at `addr`, so draining and in-flight tracking count
+    /// against the replica itself
+    pub container_id: String,
+    /// "host:port" address of the replica
+    pub addr: String,
+}
+
+impl PoolMember {
+    pub fn new(container_id: impl Into<String>, addr: impl Into<String>) -> Self {
+        Self {
+            container_id: container_id.into(),
+            addr: addr.into(),
+        }
+    }
+}
+
 /// Round-robin backend pool for load balancing across multiple container replicas
 #[derive(Debug, Clone)]
 pub struct RoundRobinBackend {
-    /// List of "host:port" addresses for the backend pool
-    pub backends: Vec<String>,
+    /// Replicas in the backend pool
+    pub backends: Vec<PoolMember>,
     /// Atomic counter for round-robin selection
     pub current: Arc<AtomicUsize>,
 }
 
 impl RoundRobinBackend {
-    pub fn new(backends: Vec<String>) -> Self {
+    pub fn new(backends: Vec<PoolMember>) -> Self {
         Self {
             backends,
             current: Arc::new(AtomicUsize::new(0)),
         }
     }
 
-    /// Get the next backend address using round-robin selection
-    pub fn next(&self) -> Option<&str> {
+    /// Get the next replica using round-robin selection
+    pub fn next(&self) -> Option<&PoolMember> {
         if self.backends.is_empty() {
             return None;
         }
@@ -168,78 +357,418 @@ impl RoundRobinBackend {
     }
 }
 
-/// Thread-safe route table for mapping domains to backends
+/// Default time to wait for in-flight requests before stopping a replaced container
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// Immutable view of the routes at one point in time. Updates never touch a
+/// published snapshot; they clone it, change the copy and swap it in.
+#[derive(Debug, Clone, Default)]
+struct RouteSnapshot {
+    routes: HashMap<String, Arc<Backend>>,
+    /// Multi-backend routes for round-robin load balancing
+    multi_routes: HashMap<String, RoundRobinBackend>,
+    /// Domains of apps stopped by a power schedule, with the message served
+    /// until the app is started again
+    parked: HashMap<String, String>,
+    /// Domains of apps stopped for inactivity, with the ID of the app the
+    /// next request wakes
+    sleeping: HashMap<String, String>,
+}
+
+impl RouteSnapshot {
+    fn add_route(&mut self, domain: String, backend: Backend) {
+        // Remove any multi-backend route for this domain (single takes precedence)
+        self.multi_routes.remove(&domain);
+        self.parked.remove(&domain);
+        self.sleeping.remove(&domain);
+        self.routes.insert(domain, Arc::new(backend));
+    }
+
+    fn add_backends(
+        &mut self,
+        domain: String,
+        backends: Vec<PoolMember>,
+        primary_backend: Backend,
+    ) {
+        self.parked.remove(&domain);
+        self.sleeping.remove(&domain);
+        if backends.len() <= 1 {
+            // Single backend: use normal route
+            self.routes.insert(domain, Arc::new(primary_backend));
+        } else {
+            // Store the primary backend in routes as the canonical backend (for health checks, etc.)
+            self.routes
+                .insert(domain.clone(), Arc::new(primary_backend));
+            // Store round-robin pool
+            self.multi_routes
+                .insert(domain, RoundRobinBackend::new(backends));
+        }
+    }
+
+    fn set_pool(&mut self, domain: &str, backends: Vec<PoolMember>) -> bool {
+        if !self.routes.contains_key(domain) {
+            return false;
+        }
+        if backends.is_empty() {
+            self.multi_routes.remove(domain);
+        } else {
+            self.multi_routes
+                .insert(domain.to_string(), RoundRobinBackend::new(backends));
+        }
+        true
+    }
+
+    fn remove_route(&mut self, domain: &str) {
+        self.routes.remove(domain);
+        self.multi_routes.remove(domain);
+        self.parked.remove(domain);
+        self.sleeping.remove(domain);
+    }
+
+    fn park(&mut self, domain: &str, message: &str) {
+        self.routes.remove(domain);
+        self.multi_routes.remove(domain);
+        self.sleeping.remove(domain);
+        self.parked.insert(domain.to_string(), message.to_string());
+    }
+
+    fn sleep(&mut self, domain: &str, app_id: &str) {
+        self.routes.remove(domain);
+        self.multi_routes.remove(domain);
+        self.parked.remove(domain);
+        self.sleeping.insert(domain.to_string(), app_id.to_string());
+    }
+}
+
+/// A set of route changes applied to the route table in one swap, so the proxy
+/// never serves a half-updated set of routes for an app.
 #[derive(Debug, Default)]
+pub struct RouteBatch {
+    ops: Vec<RouteOp>,
+}
+
+#[derive(Debug)]
+enum RouteOp {
+    Add(String, Backend),
+    AddBackends(String, Vec<PoolMember>, Backend),
+    Remove(String),
+}
+
+impl RouteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or update a route for a domain
+    pub fn add_route(&mut self, domain: String, backend: Backend) -> &mut Self {
+        self.ops.push(RouteOp::Add(domain, backend));
+        self
+    }
+
+    /// Add round-robin backends for a domain (see `RouteTable::add_backends`)
+    pub fn add_backends(
+        &mut self,
+        domain: String,
+        backends: Vec<PoolMember>,
+        primary_backend: Backend,
+    ) -> &mut Self {
+        self.ops
+            .push(RouteOp::AddBackends(domain, backends, primary_backend));
+        self
+    }
+
+    /// Remove the route for a domain
+    pub fn remove_route(&mut self, domain: impl Into<String>) -> &mut Self {
+        self.ops.push(RouteOp::Remove(domain.into()));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    fn apply_to(&self, snapshot: &mut RouteSnapshot) {
+        for op in &self.ops {
+            match op {
+                RouteOp::Add(domain, backend) => {
+                    snapshot.add_route(domain.clone(), backend.clone())
+                }
+                RouteOp::AddBackends(domain, backends, primary) => {
+                    snapshot.add_backends(domain.clone(), backends.clone(), primary.clone())
+                }
+                RouteOp::Remove(domain) => snapshot.remove_route(domain),
+            }
+        }
+    }
+}
+
+/// Thread-safe route table for mapping domains to backends.
+///
+/// Routes live in an immutable snapshot behind an `ArcSwap`. Lookups read a
+/// single snapshot without locking; every update builds a new snapshot from
+/// the current one and swaps it in (retrying if another update won the race),
+/// so a request always sees either all or none of an update. Use `apply` to
+/// change several routes at once. Backend health is not part of the snapshot
+/// (see `BackendHealth`), so health checks never copy the routes.
+#[derive(Debug)]
 pub struct RouteTable {
-    routes: DashMap<String, Backend>,
-    /// Multi-backend routes for round-robin load balancing
-    multi_routes: DashMap<String, RoundRobinBackend>,
+    snapshot: ArcSwap<RouteSnapshot>,
+    /// In-flight request counts per backend container (for draining)
+    connections: ConnectionTracker,
+    /// Max seconds to wait for a replaced container to drain
+    drain_timeout_secs: AtomicU64,
+    /// Seconds a replaced container is kept as a failover target
+    failover_keep_secs: AtomicU64,
+}
+
+impl Default for RouteTable {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RouteTable {
     pub fn new() -> Self {
         Self {
-            routes: DashMap::new(),
-            multi_routes: DashMap::new(),
+            snapshot: ArcSwap::from_pointee(RouteSnapshot::default()),
+            connections: ConnectionTracker::new(),
+            drain_timeout_secs: AtomicU64::new(DEFAULT_DRAIN_TIMEOUT_SECS),
+            failover_keep_secs: AtomicU64::new(0),
+        }
+    }
+
+    /// Set how long `drain` waits for in-flight requests (config: proxy.drain_timeout_secs)
+    pub fn set_drain_timeout(&self, timeout: Duration) {
+        self.drain_timeout_secs
+            .store(timeout.as_secs(), Ordering::Relaxed);
+    }
+
+    /// Set how long a replaced container is kept as a failover target
+    /// (config: proxy.failover_keep_secs)
+    pub fn set_failover_keep(&self, keep: Duration) {
+        self.failover_keep_secs
+            .store(keep.as_secs(), Ordering::Relaxed);
+    }
+
+    /// How long a replaced container is kept as a failover target
+    pub fn failover_keep(&self) -> Duration {
+        Duration::from_secs(self.failover_keep_secs.load(Ordering::Relaxed))
+    }
+
+    /// Count a request to a backend container as in flight until the guard is dropped
+    pub fn track_connection(&self, container_id: &str) -> ConnectionGuard {
+        self.connections.track(container_id)
+    }
+
+    /// Number of requests currently in flight to a backend container
+    pub fn active_connections(&self, container_id: &str) -> usize {
+        self.connections.active(container_id)
+    }
+
+    /// Wait for in-flight requests to the given (already unrouted) containers to
+    /// finish, up to the configured drain timeout. Call after swapping routes
+    /// and before stopping the old containers. Returns true if fully drained.
+    pub async fn drain(&self, container_ids: &[String]) -> bool {
+        let timeout = Duration::from_secs(self.drain_timeout_secs.load(Ordering::Relaxed));
+        self.connections.drain(container_ids, timeout).await
+    }
+
+    /// Build a new snapshot from the current one and publish it atomically.
+    /// `update` may run more than once if a concurrent update wins the race.
+    fn modify<R>(&self, mut update: impl FnMut(&mut RouteSnapshot) -> R) -> R {
+        let mut result = None;
+        self.snapshot.rcu(|current| {
+            let mut next = RouteSnapshot::clone(current);
+            result = Some(update(&mut next));
+            next
+        });
+        result.expect("rcu runs the update at least once")
+    }
+
+    /// Modify the backend for a domain in a new snapshot.
+    /// Returns false (without swapping) if the domain has no route.
+    fn modify_backend(&self, domain: &str, mut update: impl FnMut(&mut Backend)) -> bool {
+        if !self.has_domain(domain) {
+            return false;
         }
+        self.modify(|snapshot| match snapshot.routes.get_mut(domain) {
+            Some(backend) => {
+                update(Arc::make_mut(backend));
+                true
+            }
+            None => false,
+        })
     }
 
     /// Add or update a route for a domain
     pub fn add_route(&self, domain: String, backend: Backend) {
         info!(domain = %domain, backend = ?backend.addr(), "Adding proxy route");
-        // Remove any multi-backend route for this domain (single takes precedence)
-        self.multi_routes.remove(&domain);
-        self.routes.insert(domain, backend);
+        self.modify(|snapshot| snapshot.add_route(domain.clone(), backend.clone()));
     }
 
     /// Add multiple backends for a domain with round-robin load balancing.
     /// If only one backend is provided, falls back to single-backend route.
-    pub fn add_backends(&self, domain: String, backends: Vec<String>, primary_backend: Backend) {
-        if backends.len() <= 1 {
-            // Single backend: use normal route
-            self.routes.insert(domain, primary_backend);
-        } else {
+    pub fn add_backends(
+        &self,
+        domain: String,
+        backends: Vec<PoolMember>,
+        primary_backend: Backend,
+    ) {
+        if backends.len() > 1 {
             info!(domain = %domain, count = backends.len(), "Adding round-robin backends for proxy route");
-            // Store the primary backend in routes as the canonical backend (for health checks, etc.)
-            self.routes.insert(domain.clone(), primary_backend);
-            // Store round-robin pool
-            self.multi_routes
-                .insert(domain, RoundRobinBackend::new(backends));
         }
+        self.modify(|snapshot| {
+            snapshot.add_backends(domain.clone(), backends.clone(), primary_backend.clone())
+        });
+    }
+
+    /// Replicas currently load-balanced for a domain: the round-robin pool,
+    /// or the single backend. Empty if the domain has no route.
+    pub fn pool(&self, domain: &str) -> Vec<PoolMember> {
+        let snapshot = self.snapshot.load();
+        match snapshot.multi_routes.get(domain) {
+            Some(rr) => rr.backends.clone(),
+            None => snapshot
+                .routes
+                .get(domain)
+                .map(|b| vec![PoolMember::new(b.container_id.clone(), b.addr())])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Replace the round-robin pool of a routed domain, keeping its primary
+    /// backend (and its metadata). Rolling deploys use this to move replicas in
+    /// and out one at a time. Returns false if the domain has no route.
+    pub fn set_pool(&self, domain: &str, backends: Vec<PoolMember>) -> bool {
+        if !self.has_domain(domain) {
+            return false;
+        }
+        info!(domain = %domain, count = backends.len(), "Updating round-robin pool");
+        self.modify(|snapshot| snapshot.set_pool(domain, backends.clone()))
     }
 
     /// Remove a route for a domain
     pub fn remove_route(&self, domain: &str) {
         info!(domain = %domain, "Removing proxy route");
-        self.routes.remove(domain);
-        self.multi_routes.remove(domain);
+        self.modify(|snapshot| snapshot.remove_route(domain));
+    }
+
+    /// Replace the routes for a domain with a "stopped" page showing `message`,
+    /// used while an app is stopped by its power schedule. Adding a route for
+    /// the domain again lifts it.
+    pub fn park(&self, domain: &str, message: &str) {
+        info!(domain = %domain, "Parking proxy route");
+        self.modify(|snapshot| snapshot.park(domain, message));
+    }
+
+    /// Replace the routes for a domain with a wake-on-request marker for
+    /// `app_id`, used while an app is scaled to zero. Adding a route for the
+    /// domain again lifts it.
+    pub fn sleep(&self, domain: &str, app_id: &str) {
+        info!(domain = %domain, "Marking proxy route as sleeping");
+        self.modify(|snapshot| snapshot.sleep(domain, app_id));
+    }
+
+    /// ID of the sleeping app a request for `domain` wakes (port in the host
+    /// is ignored)
+    pub fn sleeping_app(&self, domain: &str) -> Option<String> {
+        let snapshot = self.snapshot.load();
+        snapshot
+            .sleeping
+            .get(domain)
+            .or_else(|| {
+                domain
+                    .split(':')
+                    .next()
+                    .and_then(|host| snapshot.sleeping.get(host))
+            })
+            .cloned()
+    }
+
+    /// Message to serve for a parked domain (port in the host is ignored)
+    pub fn parked_message(&self, domain: &str) -> Option<String> {
+        let snapshot = self.snapshot.load();
+        snapshot
+            .parked
+            .get(domain)
+            .or_else(|| {
+                domain
+                    .split(':')
+                    .next()
+                    .and_then(|host| snapshot.parked.get(host))
+            })
+            .cloned()
+    }
+
+    /// Apply a batch of route changes in a single atomic swap. Typically used
+    /// to replace all of an app's routes at once: remove its stale domains and
+    /// add the new backends in the same batch.
+    pub fn apply(&self, batch: &RouteBatch) {
+        if batch.is_empty() {
+            return;
+        }
+        info!(changes = batch.ops.len(), "Applying proxy route batch");
+        self.modify(|snapshot| batch.apply_to(snapshot));
+    }
+
+    /// Stop failing over to the given containers, typically right before
+    /// they are stopped.
+    pub fn clear_fallback(&self, container_ids: &[String]) {
+        let is_target = |backend: &Backend| {
+            backend
+                .fallback
+                .as_ref()
+                .is_some_and(|f| container_ids.contains(&f.container_id))
+        };
+        if !self.snapshot.load().routes.values().any(|b| is_target(b)) {
+            return;
+        }
+        self.modify(|snapshot| {
+            for backend in snapshot.routes.values_mut() {
+                if is_target(backend) {
+                    Arc::make_mut(backend).fallback = None;
+                }
+            }
+        });
     }
 
     /// Get the backend for a domain, using round-robin if multiple backends are registered
     pub fn get_backend(&self, domain: &str) -> Option<Backend> {
+        // Resolve against one snapshot so a concurrent update cannot mix old and new state
+        let snapshot = self.snapshot.load();
+
         // Check if there is a round-robin multi-backend route
         let lookup_domain = |d: &str| -> Option<Backend> {
-            if let Some(rr) = self.multi_routes.get(d) {
-                // Round-robin: pick next address and construct a temporary Backend
-                if let Some(addr) = rr.next() {
-                    let mut parts = addr.splitn(2, ':');
+            if let Some(rr) = snapshot.multi_routes.get(d) {
+                // Round-robin: pick next replica and construct a temporary Backend
+                if let Some(member) = rr.next() {
+                    let mut parts = member.addr.splitn(2, ':');
                     let host = parts.next().unwrap_or("127.0.0.1").to_string();
                     let port: u16 = parts.next().and_then(|p| p.parse().ok()).unwrap_or(80);
                     // Use primary backend metadata (healthcheck, auth) from routes
-                    if let Some(primary) = self.routes.get(d) {
+                    if let Some(primary) = snapshot.routes.get(d) {
                         let mut replica_backend =
-                            Backend::new(primary.container_id.clone(), host, port);
-                        replica_backend.healthy = primary.healthy;
+                            Backend::new(member.container_id.clone(), host, port);
+                        replica_backend.health = primary.health.clone();
                         replica_backend.healthcheck_path = primary.healthcheck_path.clone();
+                        replica_backend.health_probe = primary.health_probe.clone();
                         replica_backend.basic_auth = primary.basic_auth.clone();
+                        replica_backend.basic_auth_paths = primary.basic_auth_paths.clone();
                         replica_backend.redirect_rules = primary.redirect_rules.clone();
+                        replica_backend.access_rules = primary.access_rules.clone();
+                        replica_backend.waf = primary.waf.clone();
+                        replica_backend.bots = primary.bots.clone();
+                        replica_backend.forward_auth = primary.forward_auth.clone();
+                        replica_backend.cors = primary.cors.clone();
+                        replica_backend.tls_passthrough = primary.tls_passthrough;
                         replica_backend.strip_prefix = primary.strip_prefix.clone();
+                        replica_backend.fallback = primary.fallback.clone();
                         return Some(replica_backend);
                     }
                 }
             }
             // Fall back to single-backend route
-            self.routes.get(d).map(|b| b.clone())
+            snapshot.routes.get(d).map(|b| Backend::clone(b))
         };
 
         // Try exact match first
@@ -259,61 +788,87 @@ impl RouteTable {
 
     /// Mark a backend as healthy or unhealthy
     pub fn set_health(&self, domain: &str, healthy: bool) {
-        if let Some(mut backend) = self.routes.get_mut(domain) {
-            backend.healthy = healthy;
+        if let Some(backend) = self.snapshot.load().routes.get(domain) {
+            backend.health.set(healthy);
         }
     }
 
     /// Update health status based on check result
     /// Returns true if health status changed
     pub fn update_health(&self, domain: &str, check_passed: bool, failure_threshold: u32) -> bool {
-        if let Some(mut backend) = self.routes.get_mut(domain) {
-            let was_healthy = backend.healthy;
-
-            if check_passed {
-                // Reset failure count on success
-                backend.failure_count = 0;
-                backend.healthy = true;
-            } else {
-                // Increment failure count
-                backend.failure_count += 1;
-                if backend.failure_count >= failure_threshold {
-                    backend.healthy = false;
-                }
-            }
-
-            return was_healthy != backend.healthy;
-        }
-        false
+        self.snapshot
+            .load()
+            .routes
+            .get(domain)
+            .is_some_and(|backend| backend.health.record(check_passed, failure_threshold))
     }
 
     /// Get all registered domains
     pub fn domains(&self) -> Vec<String> {
-        self.routes.iter().map(|r| r.key().clone()).collect()
+        self.snapshot.load().routes.keys().cloned().collect()
     }
 
     /// Get all backends with their domains for health checking
     pub fn all_backends(&self) -> Vec<(String, Backend)> {
-        self.routes
+        self.snapshot
+            .load()
+            .routes
             .iter()
-            .map(|r| (r.key().clone(), r.value().clone()))
+            .map(|(domain, backend)| (domain.clone(), Backend::clone(backend)))
             .collect()
     }
 
     /// Check if a domain is registered
     pub fn has_domain(&self, domain: &str) -> bool {
-        self.routes.contains_key(domain)
+        self.snapshot.load().routes.contains_key(domain)
     }
 
-    /// Update redirect rules for a specific domain in-place.
+    /// Update redirect rules for a specific domain.
     /// Returns true if the domain was found and updated.
     pub fn update_redirect_rules(&self, domain: &str, rules: Vec<RedirectRule>) -> bool {
-        if let Some(mut backend) = self.routes.get_mut(domain) {
-            backend.redirect_rules = rules;
-            true
-        } else {
-            false
-        }
+        self.modify_backend(domain, |backend| backend.redirect_rules = rules.clone())
+    }
+
+    /// Update access rules for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_access_rules(&self, domain: &str, rules: Vec<AccessRule>) -> bool {
+        self.modify_backend(domain, |backend| backend.access_rules = rules.clone())
+    }
+
+    /// Update path-scoped basic auth rules for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_basic_auth_paths(&self, domain: &str, rules: Vec<PathBasicAuth>) -> bool {
+        self.modify_backend(domain, |backend| backend.basic_auth_paths = rules.clone())
+    }
+
+    /// Update the WAF policy for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_waf(&self, domain: &str, policy: Option<WafPolicy>) -> bool {
+        self.modify_backend(domain, |backend| backend.waf = policy.clone())
+    }
+
+    /// Enable or disable TLS passthrough for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn set_tls_passthrough(&self, domain: &str, enabled: bool) -> bool {
+        self.modify_backend(domain, |backend| backend.tls_passthrough = enabled)
+    }
+
+    /// Update the bot policy for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_bots(&self, domain: &str, policy: Option<BotPolicy>) -> bool {
+        self.modify_backend(domain, |backend| backend.bots = policy.clone())
+    }
+
+    /// Update the forward auth policy for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_forward_auth(&self, domain: &str, policy: Option<ForwardAuthPolicy>) -> bool {
+        self.modify_backend(domain, |backend| backend.forward_auth = policy.clone())
+    }
+
+    /// Update the CORS policy for a specific domain.
+    /// Returns true if the domain was found and updated.
+    pub fn update_cors(&self, domain: &str, policy: Option<CorsPolicy>) -> bool {
+        self.modify_backend(domain, |backend| backend.cors = policy.clone())
     }
 }
 
@@ -321,8 +876,24 @@ impl RouteTable {
 pub struct ProxyServer {
     routes: Arc<ArcSwap<RouteTable>>,
     bind_addr: SocketAddr,
+    /// Extra IPv6-only listener address, with a socket inherited on handover
+    ipv6: Option<(SocketAddr, Option<TcpListener>)>,
     /// Optional database pool for proxy access logging
     db: Option<sqlx::SqlitePool>,
+    /// Key for secrets encrypted at rest (OIDC client secrets, TOTP secrets)
+    encryption_key: Option<[u8; 32]>,
+    /// Country database for GeoIP access rules
+    geoip: Option<Arc<GeoIp>>,
+    /// Faults to inject into proxied requests (chaos testing)
+    faults: Option<Arc<FaultInjector>>,
+    /// Starts apps scaled to zero on request
+    waker: Option<Arc<AppWaker>>,
+    /// Throttles forward auth sign-in attempts
+    login_limiter: Option<Arc<RateLimiter>>,
+    /// Flipped to true once the listener is bound (reported by `/readyz`)
+    bound_flag: Option<Arc<std::sync::atomic::AtomicBool>>,
+    /// Forwards requests to backends over pooled connections
+    proxy_service: ProxyService,
 }
 
 impl ProxyServer {
@@ -330,21 +901,84 @@ impl ProxyServer {
         Self {
             routes: Arc::new(ArcSwap::new(Arc::new(RouteTable::new()))),
             bind_addr,
+            ipv6: None,
             db: None,
+            encryption_key: None,
+            geoip: None,
+            faults: None,
+            waker: None,
+            login_limiter: None,
+            bound_flag: None,
+            proxy_service: ProxyService::new(),
         }
     }
 
+    /// Configure the keep-alive pool used for backend connections
+    pub fn with_upstream_pool(mut self, pool: UpstreamPoolConfig) -> Self {
+        self.proxy_service = ProxyService::with_pool(pool);
+        self
+    }
+
+    /// Also listen on an IPv6-only socket at `addr` (dual-stack serving).
+    /// `listener` is an already-bound socket to use instead of binding.
+    pub fn with_ipv6(mut self, addr: SocketAddr, listener: Option<TcpListener>) -> Self {
+        self.ipv6 = Some((addr, listener));
+        self
+    }
+
+    /// Set a flag that is flipped to true once the listener is bound
+    pub fn with_bound_flag(mut self, flag: Arc<std::sync::atomic::AtomicBool>) -> Self {
+        self.bound_flag = Some(flag);
+        self
+    }
+
     /// Enable proxy access logging by providing a database pool
     pub fn with_db(mut self, db: sqlx::SqlitePool) -> Self {
         self.db = Some(db);
         self
     }
 
+    /// Set the key used to decrypt secrets needed by forward auth
+    pub fn with_encryption_key(mut self, key: Option<[u8; 32]>) -> Self {
+        self.encryption_key = key;
+        self
+    }
+
+    /// Set the country database used by GeoIP access rules
+    pub fn with_geoip(mut self, geoip: Option<Arc<GeoIp>>) -> Self {
+        self.geoip = geoip;
+        self
+    }
+
+    /// Inject the faults switched on in `faults` into proxied requests
+    pub fn with_fault_injector(mut self, faults: Option<Arc<FaultInjector>>) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Wake apps scaled to zero through `waker` when requests for them arrive
+    pub fn with_app_waker(mut self, waker: Option<Arc<AppWaker>>) -> Self {
+        self.waker = waker;
+        self
+    }
+
+    /// Rate limit forward auth sign-in attempts through `limiter`
+    pub fn with_login_limiter(mut self, limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.login_limiter = limiter;
+        self
+    }
+
     /// Get a reference to the route table for updates
     pub fn routes(&self) -> Arc<ArcSwap<RouteTable>> {
         self.routes.clone()
     }
 
+    /// The backend forwarding service, to share its connection pool with the
+    /// HTTPS server
+    pub fn proxy_service(&self) -> ProxyService {
+        self.proxy_service.clone()
+    }
+
     /// Start the proxy server (HTTP), with optional HTTPS redirect.
     /// `redirect_enabled` is an Arc<AtomicBool> — set it to true once TLS cert is confirmed.
     /// `listener` is an already-bound TcpListener; if None a fresh bind is performed.
@@ -360,8 +994,19 @@ impl ProxyServer {
             None => TcpListener::bind(self.bind_addr).await?,
         };
         info!("Proxy server listening on http://{}", self.bind_addr);
+        handover::register(ListenerRole::Http, &listener);
+        let listener_v6 = open_ipv6_listener(self.ipv6, ListenerRole::Http6, "http")?;
+        if let Some(flag) = &self.bound_flag {
+            flag.store(true, Ordering::Relaxed);
+        }
 
-        let mut handler = ProxyHandler::new(self.routes.clone());
+        let mut handler = ProxyHandler::new(self.routes.clone())
+            .with_proxy_service(self.proxy_service)
+            .with_encryption_key(self.encryption_key)
+            .with_geoip(self.geoip)
+            .with_fault_injector(self.faults)
+            .with_app_waker(self.waker)
+            .with_login_limiter(self.login_limiter);
         if let Some(db) = self.db {
             handler = handler.with_db(db);
         }
@@ -373,10 +1018,16 @@ impl ProxyServer {
         }
 
         loop {
-            match listener.accept().await {
+            let accepted = tokio::select! {
+                _ = handover::shutdown_requested() => break,
+                accepted = accept_either(&listener, listener_v6.as_ref()) => accepted,
+            };
+            match accepted {
                 Ok((stream, remote_addr)) => {
                     let handler = handler.clone();
+                    let connection = handover::track_connection();
                     tokio::spawn(async move {
+                        let _connection = connection;
                         if let Err(e) = handler.handle_connection(stream, remote_addr).await {
                             error!(error = %e, "Error handling proxy connection");
                         }
@@ -387,6 +1038,12 @@ impl ProxyServer {
                 }
             }
         }
+
+        info!(
+            "Proxy server stopped accepting on http://{}",
+            self.bind_addr
+        );
+        Ok(())
     }
 
     /// Start the proxy server (HTTP)
@@ -399,9 +1056,23 @@ impl ProxyServer {
 pub struct HttpsProxyServer {
     routes: Arc<ArcSwap<RouteTable>>,
     bind_addr: SocketAddr,
+    /// Extra IPv6-only listener address, with a socket inherited on handover
+    ipv6: Option<(SocketAddr, Option<TcpListener>)>,
     tls_reload: Arc<tls::TlsReloadHandle>,
     /// Optional database pool for proxy access logging
     db: Option<sqlx::SqlitePool>,
+    /// Key for secrets encrypted at rest (OIDC client secrets, TOTP secrets)
+    encryption_key: Option<[u8; 32]>,
+    /// Country database for GeoIP access rules
+    geoip: Option<Arc<GeoIp>>,
+    /// Faults to inject into proxied requests (chaos testing)
+    faults: Option<Arc<FaultInjector>>,
+    /// Starts apps scaled to zero on request
+    waker: Option<Arc<AppWaker>>,
+    /// Throttles forward auth sign-in attempts
+    login_limiter: Option<Arc<RateLimiter>>,
+    /// Forwards requests to backends over pooled connections
+    proxy_service: ProxyService,
 }
 
 impl HttpsProxyServer {
@@ -413,17 +1084,67 @@ impl HttpsProxyServer {
         Self {
             routes,
             bind_addr,
+            ipv6: None,
             tls_reload,
             db: None,
+            encryption_key: None,
+            geoip: None,
+            faults: None,
+            waker: None,
+            login_limiter: None,
+            proxy_service: ProxyService::new(),
         }
     }
 
+    /// Forward through `service`, sharing its backend connection pool
+    pub fn with_proxy_service(mut self, service: ProxyService) -> Self {
+        self.proxy_service = service;
+        self
+    }
+
     /// Enable proxy access logging by providing a database pool
     pub fn with_db(mut self, db: sqlx::SqlitePool) -> Self {
         self.db = Some(db);
         self
     }
 
+    /// Set the key used to decrypt secrets needed by forward auth
+    pub fn with_encryption_key(mut self, key: Option<[u8; 32]>) -> Self {
+        self.encryption_key = key;
+        self
+    }
+
+    /// Set the country database used by GeoIP access rules
+    pub fn with_geoip(mut self, geoip: Option<Arc<GeoIp>>) -> Self {
+        self.geoip = geoip;
+        self
+    }
+
+    /// Inject the faults switched on in `faults` into proxied requests
+    pub fn with_fault_injector(mut self, faults: Option<Arc<FaultInjector>>) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Wake apps scaled to zero through `waker` when requests for them arrive
+    pub fn with_app_waker(mut self, waker: Option<Arc<AppWaker>>) -> Self {
+        self.waker = waker;
+        self
+    }
+
+    /// Rate limit forward auth sign-in attempts through `limiter`
+    pub fn with_login_limiter(mut self, limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.login_limiter = limiter;
+        self
+    }
+
+    /// Also listen on an IPv6-only socket at `addr` (dual-stack serving).
+    /// `listener` is an already-bound socket to use instead of binding.
+    pub fn with_ipv6(mut self, addr: SocketAddr, listener: Option<TcpListener>) -> Self {
+        self.ipv6 = Some((addr, listener));
+        self
+    }
+
     /// Start the HTTPS proxy server.
     /// `listener` is an already-bound TcpListener; if None a fresh bind is performed.
     pub async fn run(self, listener: Option<TcpListener>) -> anyhow::Result<()> {
@@ -432,21 +1153,49 @@ impl HttpsProxyServer {
             None => TcpListener::bind(self.bind_addr).await?,
         };
         info!("Proxy server listening on https://{}", self.bind_addr);
-
-        let mut handler = ProxyHandler::new(self.routes.clone()).with_tls();
+        handover::register(ListenerRole::Https, &listener);
+        let listener_v6 = open_ipv6_listener(self.ipv6, ListenerRole::Https6, "https")?;
+
+        let mut handler = ProxyHandler::new(self.routes.clone())
+            .with_proxy_service(self.proxy_service)
+            .with_tls()
+            .with_encryption_key(self.encryption_key)
+            .with_geoip(self.geoip)
+            .with_fault_injector(self.faults)
+            .with_app_waker(self.waker)
+            .with_login_limiter(self.login_limiter);
         if let Some(db) = self.db {
             handler = handler.with_db(db);
         }
         let tls_reload = self.tls_reload;
+        let routes = self.routes;
 
         loop {
-            match listener.accept().await {
+            let accepted = tokio::select! {
+                _ = handover::shutdown_requested() => break,
+                accepted = accept_either(&listener, listener_v6.as_ref()) => accepted,
+            };
+            match accepted {
                 Ok((stream, remote_addr)) => {
                     let handler = handler.clone();
+                    let routes = routes.clone();
                     // Read current acceptor per-connection so cert renewals take effect immediately
                     let acceptor = tls_reload.current();
+                    let connection = handover::track_connection();
 
                     tokio::spawn(async move {
+                        let _connection = connection;
+                        // TLS passthrough: route raw TLS by SNI to backends that
+                        // terminate TLS themselves. The ClientHello is only peeked,
+                        // so it is forwarded (or handed to rustls) untouched.
+                        if let Some(server_name) = sni::peek_sni(&stream).await {
+                            let backend = routes.load().get_backend(&server_name);
+                            if let Some(backend) = backend.filter(|b| b.tls_passthrough) {
+                                passthrough_tls(stream, &backend, &server_name).await;
+                                return;
+                            }
+                        }
+
                         // Perform TLS handshake
                         match acceptor.accept(stream).await {
                             Ok(tls_stream) => {
@@ -467,6 +1216,46 @@ impl HttpsProxyServer {
                 }
             }
         }
+
+        info!(
+            "Proxy server stopped accepting on https://{}",
+            self.bind_addr
+        );
+        Ok(())
+    }
+}
+
+/// Bind (or adopt) the optional IPv6-only companion listener
+fn open_ipv6_listener(
+    ipv6: Option<(SocketAddr, Option<TcpListener>)>,
+    role: ListenerRole,
+    scheme: &str,
+) -> anyhow::Result<Option<TcpListener>> {
+    let Some((addr, inherited)) = ipv6 else {
+        return Ok(None);
+    };
+    let listener = match inherited {
+        Some(l) => l,
+        None => bind_ipv6_only(addr)?,
+    };
+    info!("Proxy server listening on {}://{}", scheme, addr);
+    handover::register(role, &listener);
+    Ok(Some(listener))
+}
+
+/// Tunnel a raw TLS connection to a passthrough backend
+async fn passthrough_tls(mut client: tokio::net::TcpStream, backend: &Backend, server_name: &str) {
+    let mut upstream = match tokio::net::TcpStream::connect(backend.addr()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, sni = %server_name, backend = %backend.addr(), "TLS passthrough backend unavailable");
+            return;
+        }
+    };
+    let _ = upstream.set_nodelay(true);
+    tracing::debug!(sni = %server_name, backend = %backend.addr(), "TLS passthrough");
+    if let Err(e) = tokio::io::copy_bidirectional(&mut client, &mut upstream).await {
+        tracing::debug!(error = %e, sni = %server_name, "TLS passthrough tunnel closed");
     }
 }
 
@@ -474,6 +1263,10 @@ impl HttpsProxyServer {
 mod tests {
     use super::*;
 
+    fn member(port: u16) -> PoolMember {
+        PoolMember::new(format!("replica-{}", port), format!("127.0.0.1:{}", port))
+    }
+
     #[test]
     fn test_route_table_add_get() {
         let table = RouteTable::new();
@@ -510,6 +1303,138 @@ mod tests {
         assert!(table.get_backend("example.com").is_none());
     }
 
+    #[test]
+    fn test_route_table_park() {
+        let table = RouteTable::new();
+        table.add_route(
+            "example.com".into(),
+            Backend::new("container-123".into(), "127.0.0.1".into(), 3000),
+        );
+
+        table.park("example.com", "Back at 8am");
+        assert!(table.get_backend("example.com").is_none());
+        assert_eq!(
+            table.parked_message("example.com:443").as_deref(),
+            Some("Back at 8am")
+        );
+
+        table.add_route(
+            "example.com".into(),
+            Backend::new("container-456".into(), "127.0.0.1".into(), 3001),
+        );
+        assert!(table.parked_message("example.com").is_none());
+        assert!(table.get_backend("example.com").is_some());
+    }
+
+    #[test]
+    fn test_route_table_sleep() {
+        let table = RouteTable::new();
+        table.add_route(
+            "example.com".into(),
+            Backend::new("container-123".into(), "127.0.0.1".into(), 3000),
+        );
+
+        table.sleep("example.com", "app-1");
+        assert!(table.get_backend("example.com").is_none());
+        assert_eq!(
+            table.sleeping_app("example.com:443").as_deref(),
+            Some("app-1")
+        );
+
+        table.add_route(
+            "example.com".into(),
+            Backend::new("container-456".into(), "127.0.0.1".into(), 3001),
+        );
+        assert!(table.sleeping_app("example.com").is_none());
+
+        table.sleep("example.com", "app-1");
+        table.park("example.com", "Back at 8am");
+        assert!(table.sleeping_app("example.com").is_none());
+    }
+
+    #[test]
+    fn test_route_table_apply_batch() {
+        let table = RouteTable::new();
+        table.add_route(
+            "old.example.com".into(),
+            Backend::new("old".into(), "127.0.0.1".into(), 3000),
+        );
+        let before = table.snapshot.load_full();
+
+        let mut batch = RouteBatch::new();
+        batch
+            .remove_route("old.example.com")
+            .add_route(
+                "new.example.com".into(),
+                Backend::new("new".into(), "127.0.0.1".into(), 4000),
+            )
+            .add_backends(
+                "www.example.com".into(),
+                vec![member(4000), member(4001)],
+                Backend::new("new".into(), "127.0.0.1".into(), 4000),
+            );
+        table.apply(&batch);
+
+        assert!(!table.has_domain("old.example.com"));
+        assert_eq!(table.get_backend("new.example.com").unwrap().port, 4000);
+        let ports: Vec<u16> = (0..2)
+            .map(|_| table.get_backend("www.example.com").unwrap().port)
+            .collect();
+        assert_eq!(ports, vec![4000, 4001]);
+
+        // Snapshots already handed out are never mutated
+        assert!(before.routes.contains_key("old.example.com"));
+        assert!(!before.routes.contains_key("new.example.com"));
+    }
+
+    #[test]
+    fn test_route_table_concurrent_updates() {
+        let table = Arc::new(RouteTable::new());
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let table = table.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        table.add_route(
+                            format!("app-{}-{}.example.com", t, i),
+                            Backend::new("c".into(), "127.0.0.1".into(), 3000),
+                        );
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(table.domains().len(), 400);
+    }
+
+    #[test]
+    fn test_route_table_fallback() {
+        let table = RouteTable::new();
+        let mut backend = Backend::new("new".into(), "127.0.0.1".into(), 4000);
+        backend.fallback = Some(FallbackTarget {
+            container_id: "old".into(),
+            host: "127.0.0.1".into(),
+            port: 3000,
+        });
+        table.add_route("example.com".into(), backend);
+
+        let fallback = table
+            .get_backend("example.com")
+            .and_then(|b| b.fallback_backend())
+            .unwrap();
+        assert_eq!(fallback.container_id, "old");
+        assert_eq!(fallback.port, 3000);
+        assert!(fallback.fallback.is_none());
+
+        table.clear_fallback(&["other".to_string()]);
+        assert!(table.get_backend("example.com").unwrap().fallback.is_some());
+        table.clear_fallback(&["old".to_string()]);
+        assert!(table.get_backend("example.com").unwrap().fallback.is_none());
+    }
+
     #[test]
     fn test_backend_health_url_default() {
         let backend = Backend::new("container-123".into(), "127.0.0.1".into(), 3000);
@@ -531,29 +1456,29 @@ mod tests {
 
         // Initial state: healthy
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 0);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 0);
 
         // First failure: still healthy (threshold is 3)
         let changed = table.update_health("example.com", false, 3);
         assert!(!changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 1);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 1);
 
         // Second failure: still healthy
         let changed = table.update_health("example.com", false, 3);
         assert!(!changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 2);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 2);
 
         // Third failure: now unhealthy
         let changed = table.update_health("example.com", false, 3);
         assert!(changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(!b.healthy);
-        assert_eq!(b.failure_count, 3);
+        assert!(!b.healthy());
+        assert_eq!(b.failure_count(), 3);
     }
 
     #[test]
@@ -565,14 +1490,38 @@ mod tests {
         // Make it unhealthy first
         table.update_health("example.com", false, 1);
         let b = table.get_backend("example.com").unwrap();
-        assert!(!b.healthy);
+        assert!(!b.healthy());
 
         // Recovery: single success makes it healthy again
         let changed = table.update_health("example.com", true, 1);
         assert!(changed);
         let b = table.get_backend("example.com").unwrap();
-        assert!(b.healthy);
-        assert_eq!(b.failure_count, 0);
+        assert!(b.healthy());
+        assert_eq!(b.failure_count(), 0);
+    }
+
+    #[test]
+    fn test_health_updates_do_not_swap_the_routes() {
+        let table = RouteTable::new();
+        let backend = Backend::new("container-123".into(), "127.0.0.1".into(), 3000);
+        table.add_backends(
+            "example.com".into(),
+            vec![member(3000), member(3001)],
+            backend,
+        );
+        let before = table.snapshot.load_full();
+
+        table.update_health("example.com", false, 1);
+        table.set_health("example.com", false);
+        assert!(Arc::ptr_eq(&before, &table.snapshot.load_full()));
+
+        // Every replica reports the shared health
+        for _ in 0..2 {
+            assert!(!table.get_backend("example.com").unwrap().healthy());
+        }
+        table.update_health("example.com", true, 1);
+        assert!(table.get_backend("example.com").unwrap().healthy());
+        assert!(!table.update_health("missing.com", false, 1));
     }
 
     #[test]
@@ -591,17 +1540,13 @@ mod tests {
 
     #[test]
     fn test_round_robin_backend_cycles() {
-        let rr = RoundRobinBackend::new(vec![
-            "127.0.0.1:3000".into(),
-            "127.0.0.1:3001".into(),
-            "127.0.0.1:3002".into(),
-        ]);
-
-        assert_eq!(rr.next(), Some("127.0.0.1:3000"));
-        assert_eq!(rr.next(), Some("127.0.0.1:3001"));
-        assert_eq!(rr.next(), Some("127.0.0.1:3002"));
+        let rr = RoundRobinBackend::new(vec![member(3000), member(3001), member(3002)]);
+
+        assert_eq!(rr.next(), Some(&member(3000)));
+        assert_eq!(rr.next(), Some(&member(3001)));
+        assert_eq!(rr.next(), Some(&member(3002)));
         // wraps back to start
-        assert_eq!(rr.next(), Some("127.0.0.1:3000"));
+        assert_eq!(rr.next(), Some(&member(3000)));
     }
 
     #[test]
@@ -615,11 +1560,7 @@ mod tests {
         let table = RouteTable::new();
         let primary = Backend::new("container-1".into(), "127.0.0.1".into(), 3000)
             .with_healthcheck(Some("/health".into()));
-        let backends = vec![
-            "127.0.0.1:3000".into(),
-            "127.0.0.1:3001".into(),
-            "127.0.0.1:3002".into(),
-        ];
+        let backends = vec![member(3000), member(3001), member(3002)];
 
         table.add_backends("app.example.com".into(), backends, primary);
 
@@ -629,6 +1570,12 @@ mod tests {
             .collect();
 
         assert_eq!(ports, vec![3000, 3001, 3002, 3000, 3001, 3002]);
+
+        // Each replica is tracked (and drained) under its own container
+        let ids: Vec<String> = (0..3)
+            .map(|_| table.get_backend("app.example.com").unwrap().container_id)
+            .collect();
+        assert_eq!(ids, vec!["replica-3000", "replica-3001", "replica-3002"]);
     }
 
     #[test]
@@ -637,11 +1584,7 @@ mod tests {
         let primary = Backend::new("container-1".into(), "127.0.0.1".into(), 3000);
 
         // Only one backend: should use the normal single-backend path, not round-robin
-        table.add_backends(
-            "app.example.com".into(),
-            vec!["127.0.0.1:3000".into()],
-            primary,
-        );
+        table.add_backends("app.example.com".into(), vec![member(3000)], primary);
 
         // multi_routes should be empty; every call returns the same backend
         for _ in 0..3 {
@@ -658,7 +1601,7 @@ mod tests {
 
         table.add_backends(
             "app.example.com".into(),
-            vec!["127.0.0.1:3000".into(), "127.0.0.1:3001".into()],
+            vec![member(3000), member(3001)],
             primary,
         );
 
@@ -668,13 +1611,54 @@ mod tests {
         assert!(b.basic_auth.enabled);
     }
 
+    #[test]
+    fn test_route_table_set_pool() {
+        let table = RouteTable::new();
+        let primary = Backend::new("container-1".into(), "127.0.0.1".into(), 3000)
+            .with_healthcheck(Some("/health".into()));
+        table.add_backends(
+            "app.example.com".into(),
+            vec![member(3000), member(3001)],
+            primary,
+        );
+
+        // Swap the replica on 3001 for a new one on 3002
+        assert!(table.set_pool("app.example.com", vec![member(3000), member(3002)]));
+        assert_eq!(
+            table.pool("app.example.com"),
+            vec![member(3000), member(3002)]
+        );
+        let ports: Vec<u16> = (0..4)
+            .map(|_| table.get_backend("app.example.com").unwrap().port)
+            .collect();
+        assert_eq!(ports, vec![3000, 3002, 3000, 3002]);
+        let backend = table.get_backend("app.example.com").unwrap();
+        assert_eq!(backend.healthcheck_path, Some("/health".into()));
+
+        // An unrouted domain is left alone
+        assert!(!table.set_pool("other.example.com", vec![member(4000)]));
+        assert!(table.pool("other.example.com").is_empty());
+    }
+
+    #[test]
+    fn test_route_table_pool_single_backend() {
+        let table = RouteTable::new();
+        let primary = Backend::new("container-1".into(), "127.0.0.1".into(), 3000);
+        table.add_route("app.example.com".into(), primary);
+
+        assert_eq!(
+            table.pool("app.example.com"),
+            vec![PoolMember::new("container-1", "127.0.0.1:3000")]
+        );
+    }
+
     #[test]
     fn test_route_table_remove_clears_multi_routes() {
         let table = RouteTable::new();
         let primary = Backend::new("container-1".into(), "127.0.0.1".into(), 3000);
         table.add_backends(
             "app.example.com".into(),
-            vec!["127.0.0.1:3000".into(), "127.0.0.1:3001".into()],
+            vec![member(3000), member(3001)],
             primary,
         );
 
@@ -2,11 +2,15 @@
 //
 // Handles the actual HTTP request forwarding to container backends.
 // Includes WebSocket upgrade support.
+//
+// Upstream connections are HTTP/1.1 keep-alive connections pooled per backend
+// address and shared by the HTTP and HTTPS listeners.
 
 use bytes::Bytes;
 use http_body_util::{combinators::BoxBody, BodyExt};
-use hyper::body::Incoming;
-use hyper::{Request, Response};
+use hyper::body::{Body, Incoming};
+use hyper::header::{HeaderMap, HeaderName, CONNECTION, TE, TRAILER};
+use hyper::{Request, Response, Version};
 use hyper_util::client::legacy::Client;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use std::time::Duration;
@@ -14,21 +18,50 @@ use tracing::{debug, info};
 
 use super::Backend;
 
-/// Service for forwarding HTTP requests to backends
+/// Keep-alive pool settings for backend connections
+#[derive(Debug, Clone, Copy)]
+pub struct UpstreamPoolConfig {
+    /// Idle connections kept open per backend address
+    pub max_idle_per_backend: usize,
+    /// How long an idle connection is kept before it is closed
+    pub idle_timeout: Duration,
+}
+
+impl Default for UpstreamPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_backend: 32,
+            idle_timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+/// Service for forwarding HTTP requests to backends.
+///
+/// Clones share one connection pool.
 #[derive(Clone)]
 pub struct ProxyService {
-    client: Client<hyper_util::client::legacy::connect::HttpConnector, Incoming>,
+    client:
+        Client<hyper_util::client::legacy::connect::HttpConnector, BoxBody<Bytes, hyper::Error>>,
 }
 
 impl ProxyService {
     pub fn new() -> Self {
+        Self::with_pool(UpstreamPoolConfig::default())
+    }
+
+    /// Create a service whose backend connections are pooled with `pool`
+    pub fn with_pool(pool: UpstreamPoolConfig) -> Self {
         let mut connector = hyper_util::client::legacy::connect::HttpConnector::new();
         connector.set_connect_timeout(Some(Duration::from_secs(10)));
         connector.set_nodelay(true);
+        // Detect backends that vanished while a pooled connection sat idle
+        connector.set_keepalive(Some(Duration::from_secs(60)));
 
         let client = Client::builder(TokioExecutor::new())
-            .pool_idle_timeout(Duration::from_secs(30))
-            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(pool.idle_timeout)
+            .pool_max_idle_per_host(pool.max_idle_per_backend)
+            .pool_timer(hyper_util::rt::TokioTimer::new())
             .build(connector);
 
         Self { client }
@@ -37,12 +70,17 @@ impl ProxyService {
     /// Forward a request to the specified backend.
     /// `scheme` is the scheme the client used to reach the proxy ("http" or
     /// "https") and is reported to the backend via X-Forwarded-Proto.
-    pub async fn forward(
+    pub async fn forward<B>(
         &self,
-        mut req: Request<Incoming>,
+        req: Request<B>,
         backend: &Backend,
         scheme: &str,
-    ) -> anyhow::Result<Response<BoxBody<Bytes, hyper::Error>>> {
+    ) -> anyhow::Result<Response<BoxBody<Bytes, hyper::Error>>>
+    where
+        B: Body<Data = Bytes, Error = hyper::Error> + Send + Sync + 'static,
+    {
+        let mut req = req.map(|body| body.boxed());
+
         // Compute path, stripping the prefix if configured
         let original_pq = req
             .uri()
@@ -80,6 +118,12 @@ impl ProxyService {
         // Update the request URI
         *req.uri_mut() = backend_uri.parse()?;
 
+        // Talk HTTP/1.1 keep-alive upstream whatever the client used, so a
+        // client's `Connection: close` or HTTP/1.0 request doesn't close (and
+        // force a reconnect of) a pooled backend connection
+        *req.version_mut() = Version::HTTP_11;
+        strip_hop_by_hop_headers(req.headers_mut());
+
         // Add/update forwarding headers
         let headers = req.headers_mut();
 
@@ -227,8 +271,57 @@ impl ProxyService {
     }
 }
 
+/// Remove headers that describe the client's connection to the proxy rather
+/// than the request itself (RFC 9110 section 7.6.1). Upgrade requests don't
+/// come through here.
+fn strip_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let listed: Vec<HeaderName> = headers
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect();
+    for name in listed {
+        headers.remove(name);
+    }
+    for name in [
+        CONNECTION.as_str(),
+        "keep-alive",
+        "proxy-connection",
+        TE.as_str(),
+        TRAILER.as_str(),
+    ] {
+        headers.remove(name);
+    }
+}
+
 impl Default for ProxyService {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::{HeaderValue, HOST};
+
+    #[test]
+    fn test_strip_hop_by_hop_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(HOST, HeaderValue::from_static("app.example.com"));
+        headers.insert(CONNECTION, HeaderValue::from_static("close, X-Debug"));
+        headers.insert("keep-alive", HeaderValue::from_static("timeout=5"));
+        headers.insert("x-debug", HeaderValue::from_static("1"));
+        headers.insert("x-request-id", HeaderValue::from_static("abc"));
+
+        strip_hop_by_hop_headers(&mut headers);
+
+        assert!(headers.get(CONNECTION).is_none());
+        assert!(headers.get("keep-alive").is_none());
+        assert!(headers.get("x-debug").is_none());
+        assert_eq!(headers.get(HOST).unwrap(), "app.example.com");
+        assert_eq!(headers.get("x-request-id").unwrap(), "abc");
+    }
+}
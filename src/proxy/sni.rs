@@ -0,0 +1,230 @@
+// TLS SNI inspection for passthrough routing
+//
+// Reads (without consuming) the ClientHello at the start of an HTTPS connection
+// to learn the requested server name. Domains configured for TLS passthrough
+// are then tunnelled as raw TCP to their backend, which terminates TLS itself;
+// every other connection continues into the normal rustls acceptor with the
+// ClientHello bytes still unread.
+
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+/// Upper bound on how long we wait for a client to send its ClientHello
+const CLIENT_HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// TLS record header (5) + max plaintext record (16 KiB)
+const MAX_CLIENT_HELLO: usize = 5 + 16 * 1024;
+
+const CONTENT_TYPE_HANDSHAKE: u8 = 0x16;
+const HANDSHAKE_CLIENT_HELLO: u8 = 0x01;
+const EXTENSION_SERVER_NAME: u16 = 0x0000;
+const NAME_TYPE_HOST_NAME: u8 = 0x00;
+
+/// Outcome of parsing the start of a TLS stream
+#[derive(Debug, PartialEq, Eq)]
+pub enum SniParse {
+    /// The ClientHello carried this server name
+    Found(String),
+    /// Not a TLS ClientHello, or one without SNI
+    Missing,
+    /// More bytes are needed to finish parsing
+    Incomplete,
+}
+
+/// Peek the server name from a connection's ClientHello without consuming it.
+/// Returns `None` on timeout, malformed input or a ClientHello without SNI.
+pub async fn peek_sni(stream: &TcpStream) -> Option<String> {
+    let mut buf = vec![0u8; MAX_CLIENT_HELLO];
+    let peek = async {
+        let mut last_len = 0;
+        loop {
+            let n = stream.peek(&mut buf).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            match parse_sni(&buf[..n]) {
+                SniParse::Found(name) => return Some(name),
+                SniParse::Missing => return None,
+                SniParse::Incomplete if n >= MAX_CLIENT_HELLO => return None,
+                SniParse::Incomplete => {
+                    // peek returns immediately with what's buffered; back off
+                    // until the rest of the ClientHello arrives
+                    if n == last_len {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                    last_len = n;
+                }
+            }
+        }
+    };
+    tokio::time::timeout(CLIENT_HELLO_TIMEOUT, peek)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Parse the SNI host name out of the first TLS record of a connection.
+pub fn parse_sni(data: &[u8]) -> SniParse {
+    let mut r = Reader::new(data);
+
+    // TLS record header
+    let Some(content_type) = r.u8() else {
+        return SniParse::Incomplete;
+    };
+    if content_type != CONTENT_TYPE_HANDSHAKE {
+        return SniParse::Missing;
+    }
+    let (Some(_version), Some(record_len)) = (r.u16(), r.u16()) else {
+        return SniParse::Incomplete;
+    };
+    let Some(record) = r.take(record_len as usize) else {
+        return SniParse::Incomplete;
+    };
+
+    match parse_client_hello(record) {
+        Some(Some(name)) => SniParse::Found(name),
+        // A complete record that doesn't parse is malformed (or SNI-less)
+        Some(None) | None => SniParse::Missing,
+    }
+}
+
+/// Returns `None` if malformed, `Some(None)` if there is no SNI extension.
+fn parse_client_hello(record: &[u8]) -> Option<Option<String>> {
+    let mut r = Reader::new(record);
+    if r.u8()? != HANDSHAKE_CLIENT_HELLO {
+        return None;
+    }
+    let len = r.u24()?;
+    let mut r = Reader::new(r.take(len)?);
+
+    r.skip(2 + 32)?; // client_version + random
+    let session_id_len = r.u8()? as usize;
+    r.skip(session_id_len)?;
+    let cipher_suites_len = r.u16()? as usize;
+    r.skip(cipher_suites_len)?;
+    let compression_len = r.u8()? as usize;
+    r.skip(compression_len)?;
+
+    if r.remaining() == 0 {
+        return Some(None); // no extensions
+    }
+    let extensions_len = r.u16()? as usize;
+    let mut exts = Reader::new(r.take(extensions_len)?);
+
+    while exts.remaining() > 0 {
+        let ext_type = exts.u16()?;
+        let ext_len = exts.u16()? as usize;
+        let ext = exts.take(ext_len)?;
+        if ext_type != EXTENSION_SERVER_NAME {
+            continue;
+        }
+
+        let mut sni = Reader::new(ext);
+        let list_len = sni.u16()? as usize;
+        let mut list = Reader::new(sni.take(list_len)?);
+        while list.remaining() > 0 {
+            let name_type = list.u8()?;
+            let name_len = list.u16()? as usize;
+            let name = list.take(name_len)?;
+            if name_type == NAME_TYPE_HOST_NAME {
+                let name = std::str::from_utf8(name).ok()?;
+                return Some(Some(name.to_ascii_lowercase()));
+            }
+        }
+        return Some(None);
+    }
+
+    Some(None)
+}
+
+/// Minimal big-endian byte reader
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.remaining() < n {
+            return None;
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u24(&mut self) -> Option<usize> {
+        self.take(3)
+            .map(|b| ((b[0] as usize) << 16) | ((b[1] as usize) << 8) | b[2] as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a minimal ClientHello record carrying the given SNI
+    fn client_hello(server_name: &str) -> Vec<u8> {
+        let name = server_name.as_bytes();
+
+        let mut sni_ext = Vec::new();
+        sni_ext.extend_from_slice(&((name.len() + 3) as u16).to_be_bytes()); // list len
+        sni_ext.push(NAME_TYPE_HOST_NAME);
+        sni_ext.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        sni_ext.extend_from_slice(name);
+
+        let mut extensions = Vec::new();
+        extensions.extend_from_slice(&EXTENSION_SERVER_NAME.to_be_bytes());
+        extensions.extend_from_slice(&(sni_ext.len() as u16).to_be_bytes());
+        extensions.extend_from_slice(&sni_ext);
+
+        let mut hello = Vec::new();
+        hello.extend_from_slice(&[0x03, 0x03]); // client_version
+        hello.extend_from_slice(&[0u8; 32]); // random
+        hello.push(0); // session id
+        hello.extend_from_slice(&[0x00, 0x02, 0x13, 0x01]); // one cipher suite
+        hello.extend_from_slice(&[0x01, 0x00]); // null compression
+        hello.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        hello.extend_from_slice(&extensions);
+
+        let mut handshake = vec![HANDSHAKE_CLIENT_HELLO];
+        handshake.extend_from_slice(&(hello.len() as u32).to_be_bytes()[1..]);
+        handshake.extend_from_slice(&hello);
+
+        let mut record = vec![CONTENT_TYPE_HANDSHAKE, 0x03, 0x01];
+        record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+        record.extend_from_slice(&handshake);
+        record
+    }
+
+    #[test]
+    fn test_parse_sni() {
+        let record = client_hello("Mail.Example.com");
+        assert_eq!(
+            parse_sni(&record),
+            SniParse::Found("mail.example.com".to_string())
+        );
+        assert_eq!(parse_sni(&record[..20]), SniParse::Incomplete);
+        assert_eq!(parse_sni(b"GET / HTTP/1.1\r\n"), SniParse::Missing);
+    }
+}
@@ -0,0 +1,302 @@
+// Per-domain traffic metrics for the reverse proxy
+//
+// Request counts, latency and response bytes are exported on /metrics labeled
+// by the routed domain. Hosts without a route share the "unknown" label so
+// arbitrary Host headers cannot create unbounded label sets.
+//
+// The time of the last request to each routed domain is kept in memory so
+// idle preview deployments can be found (see `engine::preview_expiry`).
+//
+// Request and response body bytes are also added to an in-memory ledger per
+// domain, which `engine::bandwidth` flushes into the per-app bandwidth table.
+// Domains over their app's monthly cap can be throttled to a fixed rate.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use http_body_util::{combinators::BoxBody, BodyExt};
+use hyper::body::{Body, Frame, SizeHint};
+use hyper::{Request, Response};
+use lazy_static::lazy_static;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{Instant, Sleep};
+
+use super::RouteTable;
+use crate::api::metrics::add_proxy_response_bytes;
+
+/// Label used for requests whose host has no route
+pub const UNKNOWN_DOMAIN: &str = "unknown";
+
+/// The metrics label for a request host: the routed domain (with any port
+/// stripped, matching route lookup) or `unknown`.
+pub fn domain_label(routes: &RouteTable, host: Option<&str>) -> String {
+    let Some(host) = host else {
+        return UNKNOWN_DOMAIN.to_string();
+    };
+    if routes.has_domain(host) {
+        return host.to_string();
+    }
+    match host.split(':').next() {
+        Some(domain) if routes.has_domain(domain) => domain.to_string(),
+        _ => UNKNOWN_DOMAIN.to_string(),
+    }
+}
+
+lazy_static! {
+    static ref LAST_REQUEST: DashMap<String, DateTime<Utc>> = DashMap::new();
+    /// Body bytes (in, out) per domain since the last flush
+    static ref BANDWIDTH: DashMap<String, (u64, u64)> = DashMap::new();
+    /// Bytes per second each throttled domain is limited to
+    static ref THROTTLES: DashMap<String, u64> = DashMap::new();
+}
+
+/// Remember that a request for `domain` (a label from `domain_label`) was
+/// just served
+pub fn record_request(domain: &str) {
+    if domain == UNKNOWN_DOMAIN {
+        return;
+    }
+    LAST_REQUEST.insert(domain.to_string(), Utc::now());
+}
+
+/// When the proxy last served a request for `domain`, since startup
+pub fn last_request(domain: &str) -> Option<DateTime<Utc>> {
+    LAST_REQUEST.get(domain).map(|t| *t)
+}
+
+/// Take the body bytes (in, out) counted per domain since the last call
+pub fn take_bandwidth() -> Vec<(String, u64, u64)> {
+    let domains: Vec<String> = BANDWIDTH.iter().map(|e| e.key().clone()).collect();
+    domains
+        .into_iter()
+        .filter_map(|domain| {
+            BANDWIDTH
+                .remove(&domain)
+                .map(|(domain, (bytes_in, bytes_out))| (domain, bytes_in, bytes_out))
+        })
+        .collect()
+}
+
+/// Limit the request and response bodies of `domain` to `bytes_per_sec`,
+/// or lift the limit with `None`. Applies to requests started afterwards.
+pub fn set_throttle(domain: &str, bytes_per_sec: Option<u64>) {
+    match bytes_per_sec {
+        Some(rate) if rate > 0 => {
+            THROTTLES.insert(domain.to_string(), rate);
+        }
+        _ => {
+            THROTTLES.remove(domain);
+        }
+    }
+}
+
+/// Domains currently throttled
+pub fn throttled_domains() -> Vec<String> {
+    THROTTLES.iter().map(|e| e.key().clone()).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    /// Request body, from the client to the app
+    In,
+    /// Response body, from the app to the client
+    Out,
+}
+
+/// Body that counts the bytes passing through and records them when finished
+/// (or when the client goes away). When throttled, it waits after each frame
+/// until the average rate is back under the limit.
+struct CountedBody {
+    inner: BoxBody<Bytes, hyper::Error>,
+    domain: String,
+    direction: Direction,
+    bytes: u64,
+    throttle: Option<Throttle>,
+}
+
+struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+    pause: Option<Pin<Box<Sleep>>>,
+}
+
+impl CountedBody {
+    fn new(inner: BoxBody<Bytes, hyper::Error>, domain: String, direction: Direction) -> Self {
+        let throttle = THROTTLES.get(&domain).map(|rate| Throttle {
+            bytes_per_sec: *rate,
+            started: Instant::now(),
+            pause: None,
+        });
+        Self {
+            inner,
+            domain,
+            direction,
+            bytes: 0,
+            throttle,
+        }
+    }
+}
+
+impl Body for CountedBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(pause) = self.throttle.as_mut().and_then(|t| t.pause.as_mut()) {
+            if pause.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+        if let Some(throttle) = self.throttle.as_mut() {
+            throttle.pause = None;
+        }
+
+        let poll = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(Some(Ok(ref frame))) = poll {
+            if let Some(data) = frame.data_ref() {
+                let bytes = self.bytes + data.len() as u64;
+                self.bytes = bytes;
+                if let Some(throttle) = self.throttle.as_mut() {
+                    let due = throttle.started
+                        + Duration::from_secs_f64(bytes as f64 / throttle.bytes_per_sec as f64);
+                    if due > Instant::now() {
+                        throttle.pause = Some(Box::pin(tokio::time::sleep_until(due)));
+                    }
+                }
+            }
+        }
+        poll
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for CountedBody {
+    fn drop(&mut self) {
+        if self.bytes == 0 {
+            return;
+        }
+        if let Direction::Out = self.direction {
+            add_proxy_response_bytes(&self.domain, self.bytes);
+        }
+        if self.domain == UNKNOWN_DOMAIN {
+            return;
+        }
+        let mut entry = BANDWIDTH.entry(self.domain.clone()).or_default();
+        match self.direction {
+            Direction::In => entry.0 += self.bytes,
+            Direction::Out => entry.1 += self.bytes,
+        }
+    }
+}
+
+/// Count the request body bytes received from the client against a domain.
+pub fn count_request_bytes<B>(
+    request: Request<B>,
+    domain: String,
+) -> Request<BoxBody<Bytes, hyper::Error>>
+where
+    B: Body<Data = Bytes, Error = hyper::Error> + Send + Sync + 'static,
+{
+    let (parts, body) = request.into_parts();
+    let body = CountedBody::new(body.boxed(), domain, Direction::In);
+    Request::from_parts(parts, body.boxed())
+}
+
+/// Count the response body bytes sent to the client against a domain.
+pub fn count_response_bytes(
+    response: Response<BoxBody<Bytes, hyper::Error>>,
+    domain: String,
+) -> Response<BoxBody<Bytes, hyper::Error>> {
+    let (parts, body) = response.into_parts();
+    let body = CountedBody::new(body, domain, Direction::Out);
+    Response::from_parts(parts, body.boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::Backend;
+
+    #[test]
+    fn test_domain_label() {
+        let routes = RouteTable::new();
+        routes.add_route(
+            "app.example.com".into(),
+            Backend::new("c".into(), "127.0.0.1".into(), 3000),
+        );
+
+        assert_eq!(
+            domain_label(&routes, Some("app.example.com")),
+            "app.example.com"
+        );
+        assert_eq!(
+            domain_label(&routes, Some("app.example.com:8080")),
+            "app.example.com"
+        );
+        assert_eq!(domain_label(&routes, Some("random.test")), UNKNOWN_DOMAIN);
+        assert_eq!(domain_label(&routes, None), UNKNOWN_DOMAIN);
+    }
+
+    #[tokio::test]
+    async fn test_counted_body_passes_data_through() {
+        let body = http_body_util::Full::new(Bytes::from_static(b"hello"))
+            .map_err(|e| match e {})
+            .boxed();
+        let response = count_response_bytes(Response::new(body), "app.example.com".into());
+        let collected = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&collected[..], b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_counted_bodies_add_to_bandwidth() {
+        let domain = "bandwidth.example.com";
+        let request = Request::new(
+            http_body_util::Full::new(Bytes::from_static(b"ping"))
+                .map_err(|e| match e {})
+                .boxed(),
+        );
+        let request = count_request_bytes(request, domain.into());
+        request.into_body().collect().await.unwrap();
+        let body = http_body_util::Full::new(Bytes::from_static(b"hello"))
+            .map_err(|e| match e {})
+            .boxed();
+        let response = count_response_bytes(Response::new(body), domain.into());
+        response.into_body().collect().await.unwrap();
+
+        let counted = take_bandwidth()
+            .into_iter()
+            .find(|(d, _, _)| d == domain)
+            .map(|(_, bytes_in, bytes_out)| (bytes_in, bytes_out));
+        assert_eq!(counted, Some((4, 5)));
+    }
+
+    #[tokio::test]
+    async fn test_throttled_body_is_paced() {
+        let domain = "throttled.example.com";
+        set_throttle(domain, Some(10_000));
+        let frames: Vec<Result<Frame<Bytes>, hyper::Error>> = (0..3)
+            .map(|_| Ok(Frame::data(Bytes::from(vec![0u8; 1000]))))
+            .collect();
+        let body = http_body_util::StreamBody::new(futures::stream::iter(frames)).boxed();
+        let response = count_response_bytes(Response::new(body), domain.into());
+
+        let start = Instant::now();
+        response.into_body().collect().await.unwrap();
+        set_throttle(domain, None);
+        // The last frame is sent without waiting for its own allowance
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
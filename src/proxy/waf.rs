@@ -0,0 +1,284 @@
+// Built-in web application firewall
+//
+// A small OWASP-CRS-inspired rule engine evaluated in the proxy. Each request's
+// decoded path, query string and a few client-controlled headers are matched
+// against built-in SQLi/XSS/traversal/command-injection signatures plus some
+// protocol anomaly checks. Matching rules add to an anomaly score; once the
+// score reaches the app's threshold the request is blocked (block mode) or
+// only recorded (report mode). Request bodies are not inspected.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Default anomaly score at which a request is considered an attack
+pub const DEFAULT_ANOMALY_THRESHOLD: u32 = 5;
+
+/// Score added by a rule that is a strong attack indicator on its own
+const CRITICAL: u32 = 5;
+/// Score added by a suspicious pattern that needs corroboration
+const WARNING: u32 = 3;
+/// Score added by a protocol anomaly
+const NOTICE: u32 = 2;
+
+/// What the proxy does when a request reaches the anomaly threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WafMode {
+    /// Reject the request with 403 and record it
+    Block,
+    /// Let the request through but record it
+    Report,
+}
+
+impl WafMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "block" => Some(Self::Block),
+            "report" => Some(Self::Report),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Report => "report",
+        }
+    }
+}
+
+/// Per-app WAF policy attached to a proxy backend
+#[derive(Debug, Clone)]
+pub struct WafPolicy {
+    /// App the policy belongs to (used when recording events)
+    pub app_id: String,
+    pub mode: WafMode,
+    pub anomaly_threshold: u32,
+}
+
+/// The parts of a request the WAF inspects
+#[derive(Debug, Default)]
+pub struct WafRequest<'a> {
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+    pub referer: Option<&'a str>,
+    pub cookie: Option<&'a str>,
+}
+
+/// Result of evaluating a request
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WafVerdict {
+    /// Sum of the scores of all matched rules
+    pub score: u32,
+    /// IDs of the matched rules, in evaluation order
+    pub matched_rules: Vec<&'static str>,
+}
+
+impl WafVerdict {
+    /// Whether the request reached the given anomaly threshold
+    pub fn exceeds(&self, threshold: u32) -> bool {
+        self.score > 0 && self.score >= threshold
+    }
+}
+
+struct WafRule {
+    id: &'static str,
+    score: u32,
+    pattern: Regex,
+}
+
+fn rule(id: &'static str, score: u32, pattern: &str) -> WafRule {
+    WafRule {
+        id,
+        score,
+        pattern: Regex::new(pattern).expect("built-in WAF rule must compile"),
+    }
+}
+
+lazy_static! {
+    static ref RULES: Vec<WafRule> = vec![
+        // SQL injection
+        rule("sqli-union-select", CRITICAL, r"(?i)\bunion\b[\s\S]{0,40}\bselect\b"),
+        rule(
+            "sqli-tautology",
+            CRITICAL,
+            r#"(?i)['"]\s*(or|and)\s+['"]?\w+['"]?\s*(=|<|>|like)\s*['"]?\w+"#
+        ),
+        rule(
+            "sqli-stacked-query",
+            CRITICAL,
+            r"(?i);\s*(drop|delete|insert|update|alter|create|truncate|exec)\s"
+        ),
+        rule(
+            "sqli-time-based",
+            CRITICAL,
+            r"(?i)\b(sleep|benchmark|pg_sleep)\s*\(|\bwaitfor\s+delay\b"
+        ),
+        rule("sqli-comment", WARNING, r#"(?i)['"]\s*(--|#|/\*)"#),
+        rule(
+            "sqli-schema-probe",
+            WARNING,
+            r"(?i)\b(information_schema|sqlite_master|pg_catalog)\b"
+        ),
+        // Cross-site scripting
+        rule("xss-script-tag", CRITICAL, r"(?i)<\s*script[\s>/]"),
+        rule("xss-event-handler", CRITICAL, r"(?i)<[^>]*\bon[a-z]+\s*="),
+        rule("xss-javascript-uri", WARNING, r"(?i)javascript\s*:"),
+        rule("xss-embed-tag", WARNING, r"(?i)<\s*(iframe|object|embed|svg)\b"),
+        rule("xss-dom-sink", WARNING, r"(?i)\b(document\.cookie|alert\s*\(|eval\s*\()"),
+        // Path traversal / local file inclusion
+        rule("lfi-traversal", WARNING, r"\.\./|\.\.\\"),
+        rule(
+            "lfi-sensitive-file",
+            CRITICAL,
+            r"(?i)/etc/(passwd|shadow)|/proc/self/|\bboot\.ini\b|\bwin\.ini\b"
+        ),
+        // Command injection
+        rule(
+            "rce-shell-command",
+            CRITICAL,
+            r"(?i)(;|\||&&|`|\$\()\s*(cat|wget|curl|bash|sh|nc|whoami|uname|id)(\s|$)"
+        ),
+    ];
+}
+
+/// Evaluate a request against the built-in rule set.
+pub fn evaluate(req: &WafRequest<'_>) -> WafVerdict {
+    let mut verdict = WafVerdict::default();
+
+    // Protocol anomalies
+    if req.user_agent.map(str::trim).unwrap_or("").is_empty() {
+        verdict.score += NOTICE;
+        verdict.matched_rules.push("protocol-missing-user-agent");
+    }
+
+    let decoded: Vec<String> = [
+        Some(req.path),
+        req.query,
+        req.user_agent,
+        req.referer,
+        req.cookie,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|s| !s.is_empty())
+    .map(percent_decode)
+    .collect();
+
+    if decoded.iter().any(|s| s.contains('\0')) {
+        verdict.score += CRITICAL;
+        verdict.matched_rules.push("protocol-null-byte");
+    }
+
+    // Each rule contributes at most once per request
+    for rule in RULES.iter() {
+        if decoded.iter().any(|s| rule.pattern.is_match(s)) {
+            verdict.score += rule.score;
+            verdict.matched_rules.push(rule.id);
+        }
+    }
+
+    verdict
+}
+
+/// Decode `%XX` escapes and `+` (as space), repeatedly, so double-encoded
+/// payloads are seen in their final form. Invalid escapes are kept verbatim.
+fn percent_decode(input: &str) -> String {
+    let mut current = input.to_string();
+    // Bounded so pathological inputs can't loop forever
+    for _ in 0..3 {
+        let bytes = current.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'%' if i + 2 < bytes.len() => {
+                    let hi = (bytes[i + 1] as char).to_digit(16);
+                    let lo = (bytes[i + 2] as char).to_digit(16);
+                    match (hi, lo) {
+                        (Some(hi), Some(lo)) => {
+                            out.push((hi * 16 + lo) as u8);
+                            i += 3;
+                        }
+                        _ => {
+                            out.push(b'%');
+                            i += 1;
+                        }
+                    }
+                }
+                b'+' => {
+                    out.push(b' ');
+                    i += 1;
+                }
+                b => {
+                    out.push(b);
+                    i += 1;
+                }
+            }
+        }
+        let next = String::from_utf8_lossy(&out).into_owned();
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request<'a>(path: &'a str, query: Option<&'a str>) -> WafRequest<'a> {
+        WafRequest {
+            path,
+            query,
+            user_agent: Some("Mozilla/5.0"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_request_scores_zero() {
+        let verdict = evaluate(&request("/products/42", Some("sort=price&page=2")));
+        assert_eq!(verdict, WafVerdict::default());
+        assert!(!verdict.exceeds(DEFAULT_ANOMALY_THRESHOLD));
+    }
+
+    #[test]
+    fn test_detects_common_attacks() {
+        let sqli = evaluate(&request("/items", Some("id=1%27%20OR%20%271%27%3D%271")));
+        assert!(sqli.matched_rules.contains(&"sqli-tautology"));
+        assert!(sqli.exceeds(DEFAULT_ANOMALY_THRESHOLD));
+
+        let union = evaluate(&request(
+            "/items",
+            Some("id=1+UNION+SELECT+password+FROM+users"),
+        ));
+        assert!(union.matched_rules.contains(&"sqli-union-select"));
+
+        let xss = evaluate(&request(
+            "/search",
+            Some("q=%3Cscript%3Ealert(1)%3C/script%3E"),
+        ));
+        assert!(xss.matched_rules.contains(&"xss-script-tag"));
+        assert!(xss.exceeds(DEFAULT_ANOMALY_THRESHOLD));
+
+        let lfi = evaluate(&request("/download", Some("file=..%252f..%252fetc/passwd")));
+        assert!(lfi.matched_rules.contains(&"lfi-traversal"));
+        assert!(lfi.matched_rules.contains(&"lfi-sensitive-file"));
+    }
+
+    #[test]
+    fn test_anomaly_scoring_accumulates() {
+        let verdict = evaluate(&WafRequest {
+            path: "/",
+            query: Some("next=javascript:void(0)"),
+            ..Default::default()
+        });
+        // Missing user agent (2) + javascript URI (3)
+        assert_eq!(verdict.score, 5);
+        assert!(verdict.exceeds(5));
+        assert!(!verdict.exceeds(6));
+    }
+}
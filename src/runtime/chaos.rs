@@ -0,0 +1,159 @@
+//! Container runtime wrapper that injects failures (see `crate::chaos`).
+//!
+//! Every call that talks to the runtime API first draws against the active
+//! `runtime_error_rate`; a hit fails the call without reaching the runtime.
+//! Log streaming and availability checks are passed through untouched.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::{
+    BuildContext, CommandResult, ContainerAddress, ContainerInfo, ContainerRuntime, ContainerStats,
+    ExecConfig, ExecHandle, ImageInfo, LogLine, RegistryAuth, RunConfig,
+};
+use crate::chaos::FaultInjector;
+
+pub struct ChaosRuntime {
+    inner: Arc<dyn ContainerRuntime>,
+    faults: Arc<FaultInjector>,
+}
+
+impl ChaosRuntime {
+    pub fn new(inner: Arc<dyn ContainerRuntime>, faults: Arc<FaultInjector>) -> Self {
+        Self { inner, faults }
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for ChaosRuntime {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+    async fn build(&self, ctx: &BuildContext) -> Result<String> {
+        self.faults.runtime_fault("build")?;
+        self.inner.build(ctx).await
+    }
+    async fn run(&self, config: &RunConfig) -> Result<String> {
+        self.faults.runtime_fault("run")?;
+        self.inner.run(config).await
+    }
+    async fn start(&self, container_id: &str) -> Result<()> {
+        self.faults.runtime_fault("start")?;
+        self.inner.start(container_id).await
+    }
+    async fn stop(&self, container_id: &str) -> Result<()> {
+        self.faults.runtime_fault("stop")?;
+        self.inner.stop(container_id).await
+    }
+    async fn stop_timeout(&self, container_id: &str, timeout_secs: Option<i64>) -> Result<()> {
+        self.faults.runtime_fault("stop")?;
+        self.inner.stop_timeout(container_id, timeout_secs).await
+    }
+    async fn remove(&self, container_id: &str) -> Result<()> {
+        self.faults.runtime_fault("remove")?;
+        self.inner.remove(container_id).await
+    }
+    async fn wait(&self, container_id: &str) -> Result<i64> {
+        self.faults.runtime_fault("wait")?;
+        self.inner.wait(container_id).await
+    }
+    async fn logs(
+        &self,
+        container_id: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        self.inner.logs(container_id).await
+    }
+    async fn logs_stream(
+        &self,
+        container_id: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        self.inner.logs_stream(container_id).await
+    }
+    async fn inspect(&self, container_id: &str) -> Result<ContainerInfo> {
+        self.faults.runtime_fault("inspect")?;
+        self.inner.inspect(container_id).await
+    }
+    async fn network_addresses(&self, container_id: &str) -> Result<Vec<ContainerAddress>> {
+        self.faults.runtime_fault("network inspect")?;
+        self.inner.network_addresses(container_id).await
+    }
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+    async fn engine_version(&self) -> Result<Option<String>> {
+        self.inner.engine_version().await
+    }
+    async fn list_containers(&self, name_prefix: &str) -> Result<Vec<ContainerInfo>> {
+        self.faults.runtime_fault("list")?;
+        self.inner.list_containers(name_prefix).await
+    }
+    async fn list_compose_containers(&self, project_name: &str) -> Result<Vec<ContainerInfo>> {
+        self.faults.runtime_fault("list")?;
+        self.inner.list_compose_containers(project_name).await
+    }
+    async fn list_labeled_containers(&self, label: &str) -> Result<Vec<ContainerInfo>> {
+        self.faults.runtime_fault("list")?;
+        self.inner.list_labeled_containers(label).await
+    }
+    async fn stats(&self, container_id: &str) -> Result<ContainerStats> {
+        self.faults.runtime_fault("stats")?;
+        self.inner.stats(container_id).await
+    }
+    async fn remove_image(&self, image: &str) -> Result<()> {
+        self.faults.runtime_fault("image remove")?;
+        self.inner.remove_image(image).await
+    }
+    async fn prune_images(&self) -> Result<u64> {
+        self.faults.runtime_fault("image prune")?;
+        self.inner.prune_images().await
+    }
+    async fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.faults.runtime_fault("image tag")?;
+        self.inner.tag_image(source, target).await
+    }
+    async fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        self.faults.runtime_fault("image list")?;
+        self.inner.list_images(repository).await
+    }
+    async fn prune_all_images(&self) -> Result<u64> {
+        self.faults.runtime_fault("image prune")?;
+        self.inner.prune_all_images().await
+    }
+    async fn prune_build_cache(&self) -> Result<u64> {
+        self.faults.runtime_fault("build cache prune")?;
+        self.inner.prune_build_cache().await
+    }
+    async fn exec(&self, config: &ExecConfig) -> Result<ExecHandle> {
+        self.faults.runtime_fault("exec")?;
+        self.inner.exec(config).await
+    }
+    async fn run_command(&self, container_id: &str, cmd: Vec<String>) -> Result<CommandResult> {
+        self.faults.runtime_fault("exec")?;
+        self.inner.run_command(container_id, cmd).await
+    }
+    async fn pull_image(&self, image: &str, auth: Option<&RegistryAuth>) -> Result<()> {
+        self.faults.runtime_fault("pull")?;
+        self.inner.pull_image(image, auth).await
+    }
+    async fn setup_shared_network(&self) {
+        self.inner.setup_shared_network().await
+    }
+    async fn rename_container(&self, container_id: &str, new_name: &str) -> Result<()> {
+        self.faults.runtime_fault("rename")?;
+        self.inner.rename_container(container_id, new_name).await
+    }
+    async fn apply_resource_limits(
+        &self,
+        container_id: &str,
+        memory_limit: Option<&str>,
+        cpu_limit: Option<&str>,
+    ) -> Result<()> {
+        self.faults.runtime_fault("update")?;
+        self.inner
+            .apply_resource_limits(container_id, memory_limit, cpu_limit)
+            .await
+    }
+}
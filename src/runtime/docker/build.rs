@@ -2,7 +2,6 @@ use anyhow::{Context, Result};
 use bollard::image::BuildImageOptions;
 use bytes::Bytes;
 use futures::StreamExt;
-use tokio::io::AsyncWriteExt;
 
 use crate::runtime::BuildContext;
 
@@ -106,18 +105,25 @@ pub(super) fn parse_cpu_limits(cpu_limit: Option<&str>) -> (Option<i64>, Option<
     }
 }
 
-pub async fn build(runtime: &DockerRuntime, ctx: &BuildContext) -> Result<String> {
-    // When build secrets are present, or when a non-default platform is requested,
-    // we must use `docker buildx build` via CLI because the Bollard API does not
-    // support BuildKit --secret flags or --platform builds.
-    let needs_buildx = !ctx.build_secrets.is_empty()
+/// When build secrets are present, when a non-default platform is requested,
+/// when a sandbox builder is set, when the build uses a persistent cache or
+/// runs on a build host, we must use `docker buildx build` via CLI because
+/// the Bollard API does not support BuildKit --secret flags, --platform
+/// builds, other builders, cache import/export or other daemons.
+fn needs_buildx(ctx: &BuildContext) -> bool {
+    !ctx.build_secrets.is_empty()
+        || ctx.isolation.builder.is_some()
+        || ctx.cache.is_some()
+        || ctx.build_host.is_some()
         || ctx
             .build_platforms
             .as_deref()
             .map(|p| !p.is_empty() && p != "linux/amd64")
-            .unwrap_or(false);
+            .unwrap_or(false)
+}
 
-    if needs_buildx {
+pub async fn build(runtime: &DockerRuntime, ctx: &BuildContext) -> Result<String> {
+    if needs_buildx(ctx) {
         return build_with_secrets_cli(ctx).await;
     }
 
@@ -125,24 +131,38 @@ pub async fn build(runtime: &DockerRuntime, ctx: &BuildContext) -> Result<String
     build_via_bollard(runtime, ctx).await
 }
 
-/// Use `docker buildx build` CLI when BuildKit secrets or custom platforms are required.
+/// Use `docker buildx build` CLI when BuildKit secrets, custom platforms, a
+/// sandbox builder, a build cache or a build host are required.
 /// Writes each secret value to a tmpfile, passes `--secret id=KEY,src=TMPFILE`,
-/// then cleans up tmpfiles on completion (success or failure).
+/// then cleans up tmpfiles on completion, failure or cancellation.
 async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
-    use tokio::process::Command;
-
     let dockerfile = ctx.dockerfile.trim_start_matches("./");
 
     let mut args: Vec<String> = vec![
         "buildx".to_string(),
         "build".to_string(),
-        "--load".to_string(), // export to local Docker daemon
+        "--load".to_string(), // export to the daemon that ran the build
         "-t".to_string(),
         ctx.tag.clone(),
         "-f".to_string(),
         dockerfile.to_string(),
     ];
 
+    // Sandboxed builds: dedicated builder and no network for RUN steps
+    let builder = ctx
+        .isolation
+        .builder
+        .clone()
+        .or_else(|| ctx.cache.as_ref().and_then(|c| c.builder.clone()));
+    if let Some(ref builder) = builder {
+        args.push("--builder".to_string());
+        args.push(builder.clone());
+    }
+    if ctx.isolation.no_network {
+        args.push("--network".to_string());
+        args.push("none".to_string());
+    }
+
     // Inject --platform when a target platform is specified
     if let Some(ref platforms) = ctx.build_platforms {
         if !platforms.is_empty() {
@@ -185,6 +205,40 @@ async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
         }
     }
 
+    // Persistent layer cache: import the previous build's cache and export this
+    // one next to it, replacing the old cache only once the build succeeds
+    let mut cache_export: Option<(String, String)> = None;
+    if let Some(ref cache) = ctx.cache {
+        if !extra.no_cache && !ctx.no_cache {
+            match buildx_driver(builder.as_deref(), ctx.build_host.as_deref())
+                .await
+                .as_deref()
+            {
+                Some("docker") | None => {
+                    if let Some(ref tx) = ctx.log_tx {
+                        let _ = tx.send(
+                            "Build cache skipped: the buildx builder uses the docker driver, \
+                             which can't export caches (set [build_cache] builder to a \
+                             docker-container builder)"
+                                .to_string(),
+                        );
+                    }
+                }
+                Some(_) => {
+                    if std::path::Path::new(&cache.dir).join("index.json").exists() {
+                        args.push("--cache-from".to_string());
+                        args.push(format!("type=local,src={}", cache.dir));
+                    }
+                    let staging = format!("{}.new", cache.dir);
+                    let _ = tokio::fs::remove_dir_all(&staging).await;
+                    args.push("--cache-to".to_string());
+                    args.push(format!("type=local,dest={},mode=max", staging));
+                    cache_export = Some((cache.dir.clone(), staging));
+                }
+            }
+        }
+    }
+
     for (key, value) in &ctx.build_args {
         args.push("--build-arg".to_string());
         args.push(format!("{}={}", key, value));
@@ -196,20 +250,16 @@ async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
         args.push(format!("SOURCE_COMMIT={}", sha));
     }
 
-    // Write secrets to tmpfiles
+    // Write secrets to tmpfiles, readable only by us and removed when dropped,
+    // including when a cancelled deployment drops this future mid-build
     let tag_safe = ctx.tag.replace([':', '/'], "-");
-    let mut secret_tmp_paths: Vec<std::path::PathBuf> = vec![];
+    let mut secret_files: Vec<tempfile::NamedTempFile> = vec![];
     for (key, value) in &ctx.build_secrets {
-        let tmp_path = std::path::PathBuf::from(format!("/tmp/rivetr-secret-{}-{}", tag_safe, key));
-        let mut f = tokio::fs::File::create(&tmp_path)
-            .await
-            .context(format!("Failed to create secret tmpfile for '{}'", key))?;
-        f.write_all(value.as_bytes())
-            .await
+        let file = write_secret_file(&format!("rivetr-secret-{}-{}-", tag_safe, key), value)
             .context(format!("Failed to write secret tmpfile for '{}'", key))?;
         args.push("--secret".to_string());
-        args.push(format!("id={},src={}", key, tmp_path.display()));
-        secret_tmp_paths.push(tmp_path);
+        args.push(format!("id={},src={}", key, file.path().display()));
+        secret_files.push(file);
     }
 
     args.push(ctx.path.clone());
@@ -219,19 +269,20 @@ async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
         "Building image with BuildKit secrets via CLI"
     );
 
-    let output = Command::new("docker")
+    if let (Some(ref host), Some(ref tx)) = (&ctx.build_host, &ctx.log_tx) {
+        let _ = tx.send(format!("Building on Docker host {}", host));
+    }
+
+    let output = docker_cli(ctx.build_host.as_deref())
         .args(&args)
         .env("DOCKER_BUILDKIT", "1")
+        .kill_on_drop(true)
         .output()
         .await
         .context("Failed to spawn docker buildx build")?;
 
     // Clean up secret tmpfiles regardless of outcome
-    for tmp_path in &secret_tmp_paths {
-        if let Err(e) = tokio::fs::remove_file(tmp_path).await {
-            tracing::warn!("Failed to remove secret tmpfile {:?}: {}", tmp_path, e);
-        }
-    }
+    drop(secret_files);
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -242,9 +293,19 @@ async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
                 let _ = tx.send(line.to_string());
             }
         }
+        if let Some((_, ref staging)) = cache_export {
+            let _ = tokio::fs::remove_dir_all(staging).await;
+        }
         anyhow::bail!("docker buildx build failed:\n{}", stderr);
     }
 
+    if let Some((ref dir, ref staging)) = cache_export {
+        let _ = tokio::fs::remove_dir_all(dir).await;
+        if let Err(e) = tokio::fs::rename(staging, dir).await {
+            tracing::warn!(cache_dir = %dir, error = %e, "Failed to store build cache");
+        }
+    }
+
     // Forward stdout to log_tx
     if let Some(ref tx) = ctx.log_tx {
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -253,9 +314,116 @@ async fn build_with_secrets_cli(ctx: &BuildContext) -> Result<String> {
         }
     }
 
+    if let Some(ref host) = ctx.build_host {
+        if let Some(ref tx) = ctx.log_tx {
+            let _ = tx.send(format!("Transferring image from build host {}", host));
+        }
+        load_from_build_host(host, &ctx.tag).await?;
+    }
+
     Ok(ctx.tag.clone())
 }
 
+/// Write a build secret to a new tmpfile (mode 0600) that is deleted when the
+/// returned handle is dropped
+fn write_secret_file(prefix: &str, value: &str) -> std::io::Result<tempfile::NamedTempFile> {
+    use std::io::Write;
+
+    let mut file = tempfile::Builder::new().prefix(prefix).tempfile()?;
+    file.write_all(value.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// `docker` CLI command against `build_host` (`DOCKER_HOST` syntax), or
+/// against the local daemon when unset
+fn docker_cli(build_host: Option<&str>) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("docker");
+    if let Some(host) = build_host {
+        cmd.env("DOCKER_HOST", host);
+    }
+    cmd
+}
+
+/// Stream an image built on `build_host` into the local daemon
+/// (`docker save | docker load`), then remove it from the build host. The
+/// build host keeps its BuildKit layer cache for the next build.
+async fn load_from_build_host(build_host: &str, tag: &str) -> Result<()> {
+    use std::process::Stdio;
+
+    let mut save = docker_cli(Some(build_host))
+        .args(["save", tag])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to spawn docker save on the build host")?;
+    let mut load = docker_cli(None)
+        .arg("load")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to spawn docker load")?;
+
+    let mut image = save.stdout.take().context("docker save has no stdout")?;
+    let mut stdin = load.stdin.take().context("docker load has no stdin")?;
+    let copied = tokio::io::copy(&mut image, &mut stdin).await;
+    // Closing stdin ends the archive for docker load
+    drop(stdin);
+
+    let save_output = save.wait_with_output().await?;
+    let load_output = load.wait_with_output().await?;
+    if !save_output.status.success() {
+        anyhow::bail!(
+            "docker save on build host {} failed: {}",
+            build_host,
+            String::from_utf8_lossy(&save_output.stderr).trim()
+        );
+    }
+    if !load_output.status.success() {
+        anyhow::bail!(
+            "docker load of the image from build host {} failed: {}",
+            build_host,
+            String::from_utf8_lossy(&load_output.stderr).trim()
+        );
+    }
+    copied.context("Failed to stream the image from the build host")?;
+
+    if let Err(e) = docker_cli(Some(build_host))
+        .args(["image", "rm", tag])
+        .output()
+        .await
+    {
+        tracing::warn!(build_host, error = %e, "Failed to remove image from build host");
+    }
+    Ok(())
+}
+
+/// Driver of a buildx builder (`docker`, `docker-container`, ...), or `None`
+/// when it can't be inspected. `None` inspects the default builder.
+async fn buildx_driver(builder: Option<&str>, build_host: Option<&str>) -> Option<String> {
+    let mut cmd = docker_cli(build_host);
+    cmd.args(["buildx", "inspect"]);
+    if let Some(builder) = builder {
+        cmd.arg(builder);
+    }
+    let output = cmd.output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_buildx_driver(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The `Driver:` line of `docker buildx inspect` output
+fn parse_buildx_driver(inspect_output: &str) -> Option<String> {
+    inspect_output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Driver:"))
+        .map(|driver| driver.trim().to_string())
+}
+
 /// Standard Bollard-based build (no secrets).
 async fn build_via_bollard(runtime: &DockerRuntime, ctx: &BuildContext) -> Result<String> {
     // Create a tar archive of the build context
@@ -301,6 +469,7 @@ async fn build_via_bollard(runtime: &DockerRuntime, ctx: &BuildContext) -> Resul
         memswap: memory, // Set memswap equal to memory to disable swap
         cpuperiod: cpuperiod.map(|p| p as u64),
         cpuquota: cpuquota.map(|q| q as u64),
+        networkmode: if ctx.isolation.no_network { "none" } else { "" },
         buildargs: build_args_map,
         ..Default::default()
     };
@@ -343,3 +512,85 @@ async fn build_via_bollard(runtime: &DockerRuntime, ctx: &BuildContext) -> Resul
 
     Ok(ctx.tag.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_buildx_driver() {
+        let output = "Name:          rivetr-cache\n\
+                      Driver:        docker-container\n\
+                      Last Activity: 2026-01-01 00:00:00 +0000 UTC\n\n\
+                      Nodes:\n\
+                      Name:      rivetr-cache0\n";
+        assert_eq!(
+            parse_buildx_driver(output).as_deref(),
+            Some("docker-container")
+        );
+        assert_eq!(
+            parse_buildx_driver("Name: default\nDriver: docker\n").as_deref(),
+            Some("docker")
+        );
+        assert_eq!(parse_buildx_driver("ERROR: no builder found"), None);
+    }
+
+    #[test]
+    fn test_secret_files_are_private_and_removed_on_drop() {
+        let file = write_secret_file("rivetr-secret-test-", "s3cret").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "s3cret");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_build_host_builds_with_buildx() {
+        let mut ctx = BuildContext {
+            path: ".".to_string(),
+            dockerfile: "Dockerfile".to_string(),
+            tag: "rivetr-web:1".to_string(),
+            build_args: Vec::new(),
+            build_target: None,
+            custom_options: None,
+            cpu_limit: None,
+            memory_limit: None,
+            log_tx: None,
+            build_secrets: Vec::new(),
+            build_platforms: None,
+            no_cache: false,
+            source_commit: None,
+            isolation: Default::default(),
+            cache: None,
+            build_host: None,
+        };
+        assert!(!needs_buildx(&ctx));
+        ctx.build_platforms = Some("linux/amd64".to_string());
+        assert!(!needs_buildx(&ctx));
+
+        ctx.build_host = Some("ssh://builder@build.example.com".to_string());
+        assert!(needs_buildx(&ctx));
+    }
+
+    #[test]
+    fn test_docker_cli_targets_the_build_host() {
+        let docker_host = |cmd: &tokio::process::Command| {
+            cmd.as_std()
+                .get_envs()
+                .find(|(key, _)| *key == "DOCKER_HOST")
+                .and_then(|(_, value)| value.map(|v| v.to_string_lossy().into_owned()))
+        };
+        assert_eq!(
+            docker_host(&docker_cli(Some("tcp://builder:2376"))).as_deref(),
+            Some("tcp://builder:2376")
+        );
+        assert_eq!(docker_host(&docker_cli(None)), None);
+    }
+}
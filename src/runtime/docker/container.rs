@@ -2,10 +2,12 @@ use anyhow::{Context, Result};
 use bollard::container::LogOutput;
 use bollard::container::{
     Config, CreateContainerOptions, ListContainersOptions, RemoveContainerOptions, StatsOptions,
-    StopContainerOptions, UpdateContainerOptions,
+    StopContainerOptions, UpdateContainerOptions, WaitContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecResults};
-use bollard::image::{CreateImageOptions, PruneImagesOptions, RemoveImageOptions};
+use bollard::image::{
+    CreateImageOptions, ListImagesOptions, PruneImagesOptions, RemoveImageOptions, TagImageOptions,
+};
 use bollard::models::EndpointSettings;
 use bollard::network::{ConnectNetworkOptions, CreateNetworkOptions};
 use bytes::Bytes;
@@ -15,8 +17,8 @@ use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc;
 
 use crate::runtime::{
-    CommandResult, ContainerInfo, ContainerStats, ExecConfig, ExecHandle, RegistryAuth, RunConfig,
-    TtySize,
+    CommandResult, ContainerAddress, ContainerInfo, ContainerStats, ExecConfig, ExecHandle,
+    ImageInfo, RegistryAuth, RunConfig, TtySize,
 };
 
 use super::DockerRuntime;
@@ -81,16 +83,18 @@ pub async fn run(runtime: &DockerRuntime, config: &RunConfig) -> Result<String>
         HashMap::new();
     let mut exposed_ports: HashMap<String, HashMap<(), ()>> = HashMap::new();
 
-    // Add primary port (legacy)
-    let primary_port_binding = format!("{}/tcp", config.port);
-    port_bindings.insert(
-        primary_port_binding.clone(),
-        Some(vec![bollard::service::PortBinding {
-            host_ip: Some("0.0.0.0".to_string()),
-            host_port: None, // Let Docker assign a random port
-        }]),
-    );
-    exposed_ports.insert(primary_port_binding, HashMap::new());
+    // Add primary port (legacy); 0 publishes none (worker apps)
+    if config.port != 0 {
+        let primary_port_binding = format!("{}/tcp", config.port);
+        port_bindings.insert(
+            primary_port_binding.clone(),
+            Some(vec![bollard::service::PortBinding {
+                host_ip: Some("0.0.0.0".to_string()),
+                host_port: None, // Let Docker assign a random port
+            }]),
+        );
+        exposed_ports.insert(primary_port_binding, HashMap::new());
+    }
 
     // Add additional port mappings
     for mapping in &config.port_mappings {
@@ -546,6 +550,20 @@ pub async fn remove(runtime: &DockerRuntime, container_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Wait for a container to exit and return its exit code
+pub async fn wait(runtime: &DockerRuntime, container_id: &str) -> Result<i64> {
+    let mut responses = runtime
+        .client
+        .wait_container(container_id, None::<WaitContainerOptions<String>>);
+    match responses.next().await {
+        Some(Ok(response)) => Ok(response.status_code),
+        // bollard reports a non-zero exit as an error carrying the code
+        Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => Ok(code),
+        Some(Err(e)) => Err(e).context("Failed to wait for container"),
+        None => anyhow::bail!("Container wait ended without an exit code"),
+    }
+}
+
 pub async fn inspect(runtime: &DockerRuntime, container_id: &str) -> Result<ContainerInfo> {
     let info = runtime
         .client
@@ -594,6 +612,44 @@ pub async fn inspect(runtime: &DockerRuntime, container_id: &str) -> Result<Cont
     })
 }
 
+pub async fn network_addresses(
+    runtime: &DockerRuntime,
+    container_id: &str,
+) -> Result<Vec<ContainerAddress>> {
+    let info = runtime
+        .client
+        .inspect_container(container_id, None)
+        .await
+        .context("Failed to inspect container")?;
+
+    let networks = info
+        .network_settings
+        .and_then(|ns| ns.networks)
+        .unwrap_or_default();
+    Ok(networks
+        .into_iter()
+        .filter_map(|(network, endpoint)| {
+            let ipv6 = endpoint
+                .global_ipv6_address
+                .as_deref()
+                .and_then(|ip| ip.parse().ok())
+                .zip(
+                    endpoint
+                        .global_ipv6_prefix_len
+                        .and_then(|len| u8::try_from(len).ok()),
+                );
+            let ip = endpoint.ip_address?.parse().ok()?;
+            let prefix_len = endpoint.ip_prefix_len?;
+            Some(ContainerAddress {
+                network,
+                ip,
+                prefix_len: u8::try_from(prefix_len).ok()?,
+                ipv6,
+            })
+        })
+        .collect())
+}
+
 pub async fn list_containers(
     runtime: &DockerRuntime,
     name_prefix: &str,
@@ -653,16 +709,32 @@ pub async fn list_compose_containers(
     runtime: &DockerRuntime,
     project_name: &str,
 ) -> Result<Vec<ContainerInfo>> {
-    let mut filters = HashMap::new();
-    filters.insert("status".to_string(), vec!["running".to_string()]);
     // Filter by Docker Compose project label
-    filters.insert(
-        "label".to_string(),
-        vec![format!("com.docker.compose.project={}", project_name)],
-    );
+    let label = format!("com.docker.compose.project={}", project_name);
+    list_by_label(runtime, &label, false).await
+}
+
+pub async fn list_labeled_containers(
+    runtime: &DockerRuntime,
+    label: &str,
+) -> Result<Vec<ContainerInfo>> {
+    list_by_label(runtime, label, true).await
+}
+
+/// Containers carrying `label`; stopped ones too when `all` is set
+async fn list_by_label(
+    runtime: &DockerRuntime,
+    label: &str,
+    all: bool,
+) -> Result<Vec<ContainerInfo>> {
+    let mut filters = HashMap::new();
+    if !all {
+        filters.insert("status".to_string(), vec!["running".to_string()]);
+    }
+    filters.insert("label".to_string(), vec![label.to_string()]);
 
     let options = ListContainersOptions {
-        all: false,
+        all,
         filters,
         ..Default::default()
     };
@@ -671,7 +743,7 @@ pub async fn list_compose_containers(
         .client
         .list_containers(Some(options))
         .await
-        .context("Failed to list compose containers")?;
+        .context("Failed to list containers by label")?;
 
     let mut result = Vec::new();
     for container in containers {
@@ -800,6 +872,49 @@ pub async fn remove_image(runtime: &DockerRuntime, image: &str) -> Result<()> {
     Ok(())
 }
 
+pub async fn tag_image(runtime: &DockerRuntime, source: &str, target: &str) -> Result<()> {
+    // The tag is whatever follows the last ':' after the last '/' (a registry
+    // host may carry a port)
+    let (repo, tag) = match target.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => (repo, tag),
+        _ => (target, "latest"),
+    };
+    let options = TagImageOptions { repo, tag };
+
+    runtime
+        .client
+        .tag_image(source, Some(options))
+        .await
+        .context("Failed to tag image")?;
+
+    Ok(())
+}
+
+pub async fn list_images(runtime: &DockerRuntime, repository: &str) -> Result<Vec<ImageInfo>> {
+    let mut filters: HashMap<String, Vec<String>> = HashMap::new();
+    filters.insert("reference".to_string(), vec![repository.to_string()]);
+    let options = ListImagesOptions::<String> {
+        filters,
+        ..Default::default()
+    };
+
+    let images = runtime
+        .client
+        .list_images(Some(options))
+        .await
+        .context("Failed to list images")?;
+
+    Ok(images
+        .into_iter()
+        .map(|image| ImageInfo {
+            id: image.id,
+            tags: image.repo_tags,
+            created: image.created,
+            size: image.size,
+        })
+        .collect())
+}
+
 pub async fn prune_build_cache(_runtime: &DockerRuntime) -> Result<u64> {
     let output = tokio::process::Command::new("docker")
         .args(["builder", "prune", "-f"])
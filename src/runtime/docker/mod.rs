@@ -11,8 +11,8 @@ use futures::Stream;
 use std::pin::Pin;
 
 use super::{
-    BuildContext, CommandResult, ContainerInfo, ContainerRuntime, ContainerStats, ExecConfig,
-    ExecHandle, LogLine, RegistryAuth, RunConfig,
+    BuildContext, CommandResult, ContainerAddress, ContainerInfo, ContainerRuntime, ContainerStats,
+    ExecConfig, ExecHandle, ImageInfo, LogLine, RegistryAuth, RunConfig,
 };
 
 use super::RuntimeDefaults;
@@ -76,6 +76,10 @@ impl ContainerRuntime for DockerRuntime {
         container::remove(self, container_id).await
     }
 
+    async fn wait(&self, container_id: &str) -> Result<i64> {
+        container::wait(self, container_id).await
+    }
+
     async fn logs(
         &self,
         container_id: &str,
@@ -94,10 +98,18 @@ impl ContainerRuntime for DockerRuntime {
         container::inspect(self, container_id).await
     }
 
+    async fn network_addresses(&self, container_id: &str) -> Result<Vec<ContainerAddress>> {
+        container::network_addresses(self, container_id).await
+    }
+
     async fn is_available(&self) -> bool {
         self.client.ping().await.is_ok()
     }
 
+    async fn engine_version(&self) -> Result<Option<String>> {
+        Ok(self.client.version().await?.version)
+    }
+
     async fn list_containers(&self, name_prefix: &str) -> Result<Vec<ContainerInfo>> {
         container::list_containers(self, name_prefix).await
     }
@@ -106,6 +118,10 @@ impl ContainerRuntime for DockerRuntime {
         container::list_compose_containers(self, project_name).await
     }
 
+    async fn list_labeled_containers(&self, label: &str) -> Result<Vec<ContainerInfo>> {
+        container::list_labeled_containers(self, label).await
+    }
+
     async fn stats(&self, container_id: &str) -> Result<ContainerStats> {
         container::stats(self, container_id).await
     }
@@ -118,6 +134,14 @@ impl ContainerRuntime for DockerRuntime {
         container::prune_images(self).await
     }
 
+    async fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        container::tag_image(self, source, target).await
+    }
+
+    async fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        container::list_images(self, repository).await
+    }
+
     async fn prune_all_images(&self) -> Result<u64> {
         container::prune_all_images(self).await
     }
@@ -0,0 +1,576 @@
+//! In-memory container runtime for tests (feature `test-utils`).
+//!
+//! `MockRuntime` keeps containers and images in memory and never talks to a
+//! daemon. Its behavior is scriptable through `MockBehavior` — build delays
+//! and failures, failing pulls and starts, containers that crash shortly
+//! after starting — and can be changed while a test runs. Everything the
+//! engine asked for is recorded so tests can assert on it.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{
+    BuildContext, CommandResult, ContainerInfo, ContainerRuntime, ContainerStats, ExecConfig,
+    ExecHandle, ImageInfo, LogLine, LogStream, RegistryAuth, RunConfig, TtySize,
+};
+
+/// How a `MockRuntime` responds
+#[derive(Debug, Clone, Default)]
+pub struct MockBehavior {
+    /// How long each image build takes
+    pub build_delay: Duration,
+    /// Fail image builds with this error
+    pub build_error: Option<String>,
+    /// Fail image pulls with this error
+    pub pull_error: Option<String>,
+    /// Fail starting containers with this error
+    pub run_error: Option<String>,
+    /// How long starting a container takes once it has been created
+    pub start_delay: Duration,
+    /// Containers exit (code 1) this long after they start
+    pub crash_after_start: Option<Duration>,
+    /// Output of `run_command` in any container
+    pub command_result: Option<CommandResult>,
+    /// Lines every container logs
+    pub log_lines: Vec<String>,
+}
+
+/// A container the mock started
+#[derive(Debug, Clone)]
+pub struct MockContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub running: bool,
+    pub exit_code: Option<i64>,
+    pub host_port: Option<u16>,
+    pub restart_count: u32,
+    pub env: Vec<(String, String)>,
+    pub labels: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    behavior: MockBehavior,
+    containers: Vec<MockContainer>,
+    images: Vec<String>,
+    builds: Vec<String>,
+    pulls: Vec<String>,
+    commands: Vec<(String, Vec<String>)>,
+    next_port: u16,
+}
+
+impl MockState {
+    fn find(&self, id_or_name: &str) -> Option<usize> {
+        self.containers
+            .iter()
+            .position(|c| c.id == id_or_name || c.name == id_or_name)
+    }
+}
+
+/// In-memory `ContainerRuntime`; clones share the same containers
+#[derive(Clone, Default)]
+pub struct MockRuntime {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runtime that responds as `behavior` describes
+    pub fn with_behavior(behavior: MockBehavior) -> Self {
+        let runtime = Self::new();
+        runtime.set_behavior(behavior);
+        runtime
+    }
+
+    /// Change how the runtime responds from now on
+    pub fn set_behavior(&self, behavior: MockBehavior) {
+        self.state.lock().behavior = behavior;
+    }
+
+    /// Adjust the current behavior in place
+    pub fn update_behavior(&self, update: impl FnOnce(&mut MockBehavior)) {
+        update(&mut self.state.lock().behavior);
+    }
+
+    /// All containers that exist, running or not
+    pub fn containers(&self) -> Vec<MockContainer> {
+        self.state.lock().containers.clone()
+    }
+
+    /// Containers that are running
+    pub fn running_containers(&self) -> Vec<MockContainer> {
+        self.containers()
+            .into_iter()
+            .filter(|c| c.running)
+            .collect()
+    }
+
+    /// A container by ID or name
+    pub fn container(&self, id_or_name: &str) -> Option<MockContainer> {
+        let state = self.state.lock();
+        state.find(id_or_name).map(|i| state.containers[i].clone())
+    }
+
+    /// Make a running container exit with `exit_code`, as if its process died
+    pub fn crash(&self, id_or_name: &str, exit_code: i64) {
+        let mut state = self.state.lock();
+        if let Some(i) = state.find(id_or_name) {
+            let container = &mut state.containers[i];
+            container.running = false;
+            container.exit_code = Some(exit_code);
+        }
+    }
+
+    /// Tags of the images built, in order
+    pub fn builds(&self) -> Vec<String> {
+        self.state.lock().builds.clone()
+    }
+
+    /// Image references pulled, in order
+    pub fn pulls(&self) -> Vec<String> {
+        self.state.lock().pulls.clone()
+    }
+
+    /// Commands run through `run_command`: (container, command)
+    pub fn commands(&self) -> Vec<(String, Vec<String>)> {
+        self.state.lock().commands.clone()
+    }
+
+    fn behavior(&self) -> MockBehavior {
+        self.state.lock().behavior.clone()
+    }
+
+    fn get(&self, id_or_name: &str) -> Result<MockContainer> {
+        self.container(id_or_name)
+            .ok_or_else(|| anyhow::anyhow!("No such container: {}", id_or_name))
+    }
+
+    fn info(container: &MockContainer) -> ContainerInfo {
+        ContainerInfo {
+            id: container.id.clone(),
+            name: container.name.clone(),
+            status: if container.running {
+                "running".to_string()
+            } else {
+                "exited".to_string()
+            },
+            port: container.host_port,
+            running: container.running,
+            host_port: container.host_port,
+            restart_count: container.restart_count,
+        }
+    }
+
+    fn log_stream(&self, id_or_name: &str) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        let container = self.get(id_or_name)?;
+        let mut lines = vec![format!("mock container {} started", container.name)];
+        lines.extend(self.behavior().log_lines);
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        Ok(Box::pin(futures::stream::iter(lines.into_iter().map(
+            move |message| LogLine {
+                timestamp: timestamp.clone(),
+                message,
+                stream: LogStream::Stdout,
+            },
+        ))))
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for MockRuntime {
+    fn name(&self) -> &'static str {
+        "Mock"
+    }
+
+    async fn build(&self, ctx: &BuildContext) -> Result<String> {
+        let behavior = self.behavior();
+        if let Some(log_tx) = &ctx.log_tx {
+            let _ = log_tx.send(format!("mock build of {}", ctx.tag));
+        }
+        tokio::time::sleep(behavior.build_delay).await;
+        if let Some(error) = behavior.build_error {
+            anyhow::bail!(error);
+        }
+
+        let mut state = self.state.lock();
+        state.builds.push(ctx.tag.clone());
+        state.images.push(ctx.tag.clone());
+        Ok(ctx.tag.clone())
+    }
+
+    async fn run(&self, config: &RunConfig) -> Result<String> {
+        let behavior = self.behavior();
+        if let Some(error) = behavior.run_error {
+            anyhow::bail!(error);
+        }
+
+        let id = {
+            let mut state = self.state.lock();
+            if state.find(&config.name).is_some() {
+                anyhow::bail!("Conflict: container name {} is already in use", config.name);
+            }
+            let host_port = (config.port > 0).then(|| {
+                state.next_port += 1;
+                40000 + state.next_port
+            });
+            let id = uuid::Uuid::new_v4().simple().to_string();
+            state.containers.push(MockContainer {
+                id: id.clone(),
+                name: config.name.clone(),
+                image: config.image.clone(),
+                running: true,
+                exit_code: None,
+                host_port,
+                restart_count: 0,
+                env: config.env.clone(),
+                labels: config.labels.clone(),
+            });
+            id
+        };
+
+        if !behavior.start_delay.is_zero() {
+            tokio::time::sleep(behavior.start_delay).await;
+        }
+
+        if let Some(delay) = behavior.crash_after_start {
+            let runtime = self.clone();
+            let container_id = id.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                runtime.crash(&container_id, 1);
+            });
+        }
+
+        Ok(id)
+    }
+
+    async fn start(&self, container_id: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let i = state
+            .find(container_id)
+            .ok_or_else(|| anyhow::anyhow!("No such container: {}", container_id))?;
+        let container = &mut state.containers[i];
+        container.running = true;
+        container.exit_code = None;
+        Ok(())
+    }
+
+    async fn stop(&self, container_id: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let i = state
+            .find(container_id)
+            .ok_or_else(|| anyhow::anyhow!("No such container: {}", container_id))?;
+        let container = &mut state.containers[i];
+        if container.running {
+            container.running = false;
+            container.exit_code = Some(0);
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, container_id: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let i = state
+            .find(container_id)
+            .ok_or_else(|| anyhow::anyhow!("No such container: {}", container_id))?;
+        state.containers.remove(i);
+        Ok(())
+    }
+
+    async fn wait(&self, container_id: &str) -> Result<i64> {
+        loop {
+            let container = self.get(container_id)?;
+            if !container.running {
+                return Ok(container.exit_code.unwrap_or(0));
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    async fn logs(
+        &self,
+        container_id: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        self.log_stream(container_id)
+    }
+
+    async fn logs_stream(
+        &self,
+        container_id: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        self.log_stream(container_id)
+    }
+
+    async fn inspect(&self, container_id: &str) -> Result<ContainerInfo> {
+        Ok(Self::info(&self.get(container_id)?))
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn list_containers(&self, name_prefix: &str) -> Result<Vec<ContainerInfo>> {
+        Ok(self
+            .running_containers()
+            .iter()
+            .filter(|c| c.name.starts_with(name_prefix))
+            .map(Self::info)
+            .collect())
+    }
+
+    async fn list_compose_containers(&self, project_name: &str) -> Result<Vec<ContainerInfo>> {
+        Ok(self
+            .running_containers()
+            .iter()
+            .filter(|c| {
+                c.labels
+                    .get("com.docker.compose.project")
+                    .map(String::as_str)
+                    == Some(project_name)
+            })
+            .map(Self::info)
+            .collect())
+    }
+
+    async fn list_labeled_containers(&self, label: &str) -> Result<Vec<ContainerInfo>> {
+        let (key, value) = label.split_once('=').unwrap_or((label, ""));
+        Ok(self
+            .containers()
+            .iter()
+            .filter(|c| c.labels.get(key).map(String::as_str) == Some(value))
+            .map(Self::info)
+            .collect())
+    }
+
+    async fn stats(&self, container_id: &str) -> Result<ContainerStats> {
+        let container = self.get(container_id)?;
+        Ok(ContainerStats {
+            cpu_percent: if container.running { 1.0 } else { 0.0 },
+            memory_usage: if container.running {
+                32 * 1024 * 1024
+            } else {
+                0
+            },
+            memory_limit: 0,
+            network_rx: 0,
+            network_tx: 0,
+        })
+    }
+
+    async fn remove_image(&self, image: &str) -> Result<()> {
+        self.state.lock().images.retain(|i| i != image);
+        Ok(())
+    }
+
+    async fn prune_images(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if !state.images.iter().any(|i| i == source) {
+            anyhow::bail!("No such image: {}", source);
+        }
+        if !state.images.iter().any(|i| i == target) {
+            state.images.push(target.to_string());
+        }
+        Ok(())
+    }
+
+    async fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        let prefix = format!("{}:", repository);
+        Ok(self
+            .state
+            .lock()
+            .images
+            .iter()
+            .filter(|i| i.starts_with(&prefix))
+            .map(|i| ImageInfo {
+                id: i.clone(),
+                tags: vec![i.clone()],
+                created: 0,
+                size: 0,
+            })
+            .collect())
+    }
+
+    async fn exec(&self, config: &ExecConfig) -> Result<ExecHandle> {
+        self.get(&config.container_id)?;
+        // Echo stdin back as output, like a shell that prints what it reads
+        let (stdin_tx, mut stdin_rx) = mpsc::channel(32);
+        let (stdout_tx, stdout_rx) = mpsc::channel(32);
+        let (resize_tx, _resize_rx) = mpsc::channel::<TtySize>(4);
+        tokio::spawn(async move {
+            while let Some(data) = stdin_rx.recv().await {
+                if stdout_tx.send(data).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(ExecHandle {
+            stdin_tx,
+            stdout_rx,
+            resize_tx,
+        })
+    }
+
+    async fn run_command(&self, container_id: &str, cmd: Vec<String>) -> Result<CommandResult> {
+        let container = self.get(container_id)?;
+        if !container.running {
+            anyhow::bail!("Container {} is not running", container_id);
+        }
+        let mut state = self.state.lock();
+        state.commands.push((container_id.to_string(), cmd));
+        Ok(state
+            .behavior
+            .command_result
+            .clone()
+            .unwrap_or(CommandResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+            }))
+    }
+
+    async fn pull_image(&self, image: &str, _auth: Option<&RegistryAuth>) -> Result<()> {
+        if let Some(error) = self.behavior().pull_error {
+            anyhow::bail!(error);
+        }
+        let mut state = self.state.lock();
+        state.pulls.push(image.to_string());
+        state.images.push(image.to_string());
+        Ok(())
+    }
+
+    async fn rename_container(&self, container_id: &str, new_name: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.find(new_name).is_some() {
+            anyhow::bail!("Conflict: container name {} is already in use", new_name);
+        }
+        let i = state
+            .find(container_id)
+            .ok_or_else(|| anyhow::anyhow!("No such container: {}", container_id))?;
+        state.containers[i].name = new_name.to_string();
+        Ok(())
+    }
+
+    async fn apply_resource_limits(
+        &self,
+        container_id: &str,
+        _memory_limit: Option<&str>,
+        _cpu_limit: Option<&str>,
+    ) -> Result<()> {
+        self.get(container_id).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_config(name: &str) -> RunConfig {
+        RunConfig {
+            image: "nginx:alpine".to_string(),
+            name: name.to_string(),
+            port: 80,
+            env: vec![],
+            memory_limit: None,
+            cpu_limit: None,
+            port_mappings: vec![],
+            network_aliases: vec![],
+            extra_hosts: vec![],
+            labels: HashMap::new(),
+            binds: vec![],
+            restart_policy: "unless-stopped".to_string(),
+            privileged: false,
+            cap_add: vec![],
+            devices: vec![],
+            shm_size: None,
+            init: false,
+            app_id: None,
+            cap_drop: vec![],
+            gpus: None,
+            ulimits: vec![],
+            security_opt: vec![],
+            cmd: None,
+            network: None,
+            custom_labels: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_containers_can_be_found_by_id_or_name() {
+        let runtime = MockRuntime::new();
+        let id = runtime.run(&run_config("rivetr-web")).await.unwrap();
+
+        assert!(runtime.inspect(&id).await.unwrap().running);
+        runtime
+            .rename_container("rivetr-web", "rivetr-web-prev")
+            .await
+            .unwrap();
+        assert_eq!(runtime.inspect(&id).await.unwrap().name, "rivetr-web-prev");
+        assert!(runtime.inspect("rivetr-web").await.is_err());
+
+        runtime.stop(&id).await.unwrap();
+        assert_eq!(runtime.wait(&id).await.unwrap(), 0);
+        runtime.remove(&id).await.unwrap();
+        assert!(runtime.containers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scripted_failures_and_crashes() {
+        let runtime = MockRuntime::with_behavior(MockBehavior {
+            run_error: Some("no space left on device".to_string()),
+            ..Default::default()
+        });
+        let error = runtime.run(&run_config("rivetr-web")).await.unwrap_err();
+        assert!(error.to_string().contains("no space left"));
+
+        runtime.set_behavior(MockBehavior {
+            crash_after_start: Some(Duration::from_millis(10)),
+            ..Default::default()
+        });
+        let id = runtime.run(&run_config("rivetr-web")).await.unwrap();
+        assert_eq!(runtime.wait(&id).await.unwrap(), 1);
+        assert!(!runtime.inspect(&id).await.unwrap().running);
+    }
+
+    #[tokio::test]
+    async fn test_images_can_be_tagged_and_listed() {
+        let runtime = MockRuntime::new();
+        runtime.pull_image("rivetr-web:1", None).await.unwrap();
+
+        runtime
+            .tag_image("rivetr-web:1", "rivetr/web:3f2a9c1")
+            .await
+            .unwrap();
+        runtime
+            .tag_image("rivetr-web:1", "rivetr/web:latest")
+            .await
+            .unwrap();
+        assert!(runtime
+            .tag_image("rivetr-web:2", "rivetr/web:latest")
+            .await
+            .is_err());
+
+        let tags: Vec<String> = runtime
+            .list_images("rivetr/web")
+            .await
+            .unwrap()
+            .into_iter()
+            .flat_map(|image| image.tags)
+            .collect();
+        assert_eq!(tags, vec!["rivetr/web:3f2a9c1", "rivetr/web:latest"]);
+    }
+}
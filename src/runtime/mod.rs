@@ -1,8 +1,14 @@
+mod chaos;
 mod docker;
+#[cfg(feature = "test-utils")]
+mod mock;
 mod podman;
 
+pub use chaos::ChaosRuntime;
 pub use docker::parse_shm_size;
 pub use docker::DockerRuntime;
+#[cfg(feature = "test-utils")]
+pub use mock::{MockBehavior, MockContainer, MockRuntime};
 pub use podman::PodmanRuntime;
 
 use anyhow::Result;
@@ -84,6 +90,34 @@ pub struct BuildContext {
     /// Inject `SOURCE_COMMIT` build arg with the current git SHA.
     /// Value is `None` when the feature is disabled or the SHA is unknown.
     pub source_commit: Option<String>,
+    /// Restrictions for sandboxed builds of untrusted code
+    pub isolation: BuildIsolation,
+    /// Persistent layer cache of the app's builds (Docker only)
+    pub cache: Option<BuildCache>,
+    /// Docker host to build on instead of the local daemon (`DOCKER_HOST`
+    /// syntax). The image is loaded into the local daemon afterwards.
+    pub build_host: Option<String>,
+}
+
+/// BuildKit layer cache kept on disk between an app's builds
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    /// Directory the cache is imported from and exported to (`type=local`)
+    pub dir: String,
+    /// buildx builder that can export caches (the `docker` driver can't).
+    /// Unset uses the default builder.
+    pub builder: Option<String>,
+}
+
+/// How a build of untrusted code is isolated from the host and network
+#[derive(Debug, Clone, Default)]
+pub struct BuildIsolation {
+    /// Run build steps without network access (`--network none`)
+    pub no_network: bool,
+    /// Docker: buildx builder to build with (e.g. a rootless or gVisor BuildKit)
+    pub builder: Option<String>,
+    /// Podman: OCI runtime for build containers (e.g. `runsc`)
+    pub oci_runtime: Option<String>,
 }
 
 impl std::fmt::Debug for BuildContext {
@@ -108,6 +142,9 @@ impl std::fmt::Debug for BuildContext {
             .field("build_platforms", &self.build_platforms)
             .field("no_cache", &self.no_cache)
             .field("source_commit", &self.source_commit)
+            .field("isolation", &self.isolation)
+            .field("cache", &self.cache)
+            .field("build_host", &self.build_host)
             .finish()
     }
 }
@@ -137,7 +174,7 @@ impl PortMapping {
 pub struct RunConfig {
     pub image: String,
     pub name: String,
-    /// Primary port (legacy, used if port_mappings is empty)
+    /// Primary port (legacy, used if port_mappings is empty); 0 publishes none
     pub port: u16,
     pub env: Vec<(String, String)>,
     pub memory_limit: Option<String>,
@@ -201,6 +238,32 @@ pub struct ContainerInfo {
     pub restart_count: u32,
 }
 
+/// The addresses a container holds on one of its networks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerAddress {
+    /// Network name
+    pub network: String,
+    /// The container's IPv4 address on the network
+    pub ip: std::net::Ipv4Addr,
+    /// Prefix length of the network's subnet
+    pub prefix_len: u8,
+    /// The container's global IPv6 address and prefix length, on networks
+    /// with IPv6 enabled
+    pub ipv6: Option<(std::net::Ipv6Addr, u8)>,
+}
+
+/// A local image and the references that point at it
+#[derive(Debug, Clone)]
+pub struct ImageInfo {
+    pub id: String,
+    /// References of the image, e.g. "rivetr/web:3f2a9c1"
+    pub tags: Vec<String>,
+    /// Unix timestamp the image was created at
+    pub created: i64,
+    /// Size of the image in bytes
+    pub size: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct LogLine {
     pub timestamp: String,
@@ -310,6 +373,8 @@ pub trait ContainerRuntime: Send + Sync {
         self.stop(container_id).await
     }
     async fn remove(&self, container_id: &str) -> Result<()>;
+    /// Wait for a container to exit, returning its exit code
+    async fn wait(&self, container_id: &str) -> Result<i64>;
     async fn logs(&self, container_id: &str)
         -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>>;
     /// Stream logs from a container in real-time (follow mode)
@@ -318,17 +383,40 @@ pub trait ContainerRuntime: Send + Sync {
         container_id: &str,
     ) -> Result<Pin<Box<dyn Stream<Item = LogLine> + Send>>>;
     async fn inspect(&self, container_id: &str) -> Result<ContainerInfo>;
+    /// IPv4 addresses of a container on each network it is attached to.
+    /// Default implementation reports none; runtimes that expose container
+    /// networking override this.
+    async fn network_addresses(&self, _container_id: &str) -> Result<Vec<ContainerAddress>> {
+        Ok(Vec::new())
+    }
     async fn is_available(&self) -> bool;
+    /// Version of the container engine, e.g. `28.0.4`.
+    /// Default implementation reports none.
+    async fn engine_version(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
     /// List running containers with names matching the given prefix
     async fn list_containers(&self, name_prefix: &str) -> Result<Vec<ContainerInfo>>;
     /// List running containers belonging to a Docker Compose project
     async fn list_compose_containers(&self, project_name: &str) -> Result<Vec<ContainerInfo>>;
+    /// List containers carrying `label` (`key=value`), including stopped ones
+    async fn list_labeled_containers(&self, label: &str) -> Result<Vec<ContainerInfo>>;
     /// Get container resource statistics (CPU, memory, network)
     async fn stats(&self, container_id: &str) -> Result<ContainerStats>;
     /// Remove a container image by tag or ID
     async fn remove_image(&self, image: &str) -> Result<()>;
     /// Prune unused/dangling images, returns bytes reclaimed
     async fn prune_images(&self) -> Result<u64>;
+    /// Add the reference `target` to the local image `source`.
+    /// Default implementation returns an error (not all runtimes can tag images).
+    async fn tag_image(&self, _source: &str, _target: &str) -> Result<()> {
+        anyhow::bail!("Tagging images is not supported by this runtime")
+    }
+    /// List local images with a reference in `repository` (e.g. "rivetr/web").
+    /// Default implementation reports none.
+    async fn list_images(&self, _repository: &str) -> Result<Vec<ImageInfo>> {
+        Ok(Vec::new())
+    }
     /// Prune ALL unused images (like `docker image prune -a`), not just
     /// dangling — reclaims orphaned tagged per-deployment images. Used under
     /// disk pressure. Defaults to the dangling-only prune for runtimes that
@@ -390,6 +478,9 @@ impl ContainerRuntime for NoopRuntime {
     async fn remove(&self, _container_id: &str) -> Result<()> {
         anyhow::bail!("No container runtime available")
     }
+    async fn wait(&self, _container_id: &str) -> Result<i64> {
+        anyhow::bail!("No container runtime available")
+    }
     async fn logs(
         &self,
         _container_id: &str,
@@ -414,6 +505,9 @@ impl ContainerRuntime for NoopRuntime {
     async fn list_compose_containers(&self, _project_name: &str) -> Result<Vec<ContainerInfo>> {
         Ok(vec![])
     }
+    async fn list_labeled_containers(&self, _label: &str) -> Result<Vec<ContainerInfo>> {
+        Ok(vec![])
+    }
     async fn stats(&self, _container_id: &str) -> Result<ContainerStats> {
         anyhow::bail!("No container runtime available")
     }
@@ -9,8 +9,8 @@ use tokio::process::Command;
 use tokio::sync::mpsc;
 
 use super::{
-    BuildContext, CommandResult, ContainerInfo, ContainerRuntime, ContainerStats, ExecConfig,
-    ExecHandle, LogLine, LogStream, RegistryAuth, RunConfig, TtySize,
+    BuildContext, CommandResult, ContainerAddress, ContainerInfo, ContainerRuntime, ContainerStats,
+    ExecConfig, ExecHandle, ImageInfo, LogLine, LogStream, RegistryAuth, RunConfig, TtySize,
 };
 
 #[derive(Default)]
@@ -32,6 +32,7 @@ impl PodmanRuntime {
     async fn run_command(&self, args: &[String]) -> Result<String> {
         let output = Command::new("podman")
             .args(args)
+            .kill_on_drop(true)
             .output()
             .await
             .context("Failed to execute podman command")?;
@@ -43,6 +44,49 @@ impl PodmanRuntime {
 
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
+
+    /// Containers carrying `label`; stopped ones too when `all` is set
+    async fn list_by_label(&self, label: &str, all: bool) -> Result<Vec<ContainerInfo>> {
+        let mut args = vec!["ps".to_string()];
+        if all {
+            args.push("--all".to_string());
+        }
+        args.extend([
+            "--filter".to_string(),
+            format!("label={}", label),
+            "--format".to_string(),
+            "{{.ID}}|{{.Names}}|{{.State}}|{{.Ports}}".to_string(),
+        ]);
+        let output = self.run_command(&args).await?;
+
+        let mut result = Vec::new();
+        for line in output.lines() {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() >= 3 {
+                let port = parts.get(3).and_then(|ports| {
+                    ports
+                        .split("->")
+                        .next()
+                        .and_then(|host_part| host_part.split(':').next_back())
+                        .and_then(|p| p.parse().ok())
+                });
+
+                let is_running = parts[2].to_lowercase() == "running";
+
+                result.push(ContainerInfo {
+                    id: parts[0].to_string(),
+                    name: parts[1].to_string(),
+                    status: parts[2].to_string(),
+                    port,
+                    running: is_running,
+                    host_port: port,
+                    restart_count: 0,
+                });
+            }
+        }
+
+        Ok(result)
+    }
 }
 
 #[async_trait]
@@ -70,6 +114,16 @@ impl ContainerRuntime for PodmanRuntime {
             }
         }
 
+        // Sandboxed builds: no network for RUN steps, optional OCI runtime
+        if ctx.isolation.no_network {
+            args.push("--network".to_string());
+            args.push("none".to_string());
+        }
+        if let Some(ref oci_runtime) = ctx.isolation.oci_runtime {
+            args.push("--runtime".to_string());
+            args.push(oci_runtime.clone());
+        }
+
         // Add build resource limits
         if let Some(ref memory) = ctx.memory_limit {
             args.push("--memory".to_string());
@@ -150,18 +204,25 @@ impl ContainerRuntime for PodmanRuntime {
     }
 
     async fn run(&self, config: &RunConfig) -> Result<String> {
+        // Podman spells the "never" policy "no"
+        let restart_policy = match config.restart_policy.as_str() {
+            "never" => "no".to_string(),
+            policy => policy.to_string(),
+        };
         let mut args = vec![
             "run".to_string(),
             "-d".to_string(),
             "--restart".to_string(),
-            config.restart_policy.clone(),
+            restart_policy,
             "--name".to_string(),
             config.name.clone(),
         ];
 
-        // Add primary port mapping (let podman auto-assign host port)
-        args.push("-p".to_string());
-        args.push(format!(":{}", config.port));
+        // Add primary port mapping (let podman auto-assign host port); 0 publishes none
+        if config.port != 0 {
+            args.push("-p".to_string());
+            args.push(format!(":{}", config.port));
+        }
 
         // Add additional port mappings
         for mapping in &config.port_mappings {
@@ -334,6 +395,15 @@ impl ContainerRuntime for PodmanRuntime {
         Ok(())
     }
 
+    async fn wait(&self, container_id: &str) -> Result<i64> {
+        let output = self
+            .run_command(&["wait".to_string(), container_id.to_string()])
+            .await?;
+        output
+            .parse()
+            .with_context(|| format!("Unexpected podman wait output: {}", output))
+    }
+
     async fn logs(
         &self,
         container_id: &str,
@@ -453,6 +523,37 @@ impl ContainerRuntime for PodmanRuntime {
         }
     }
 
+    async fn network_addresses(&self, container_id: &str) -> Result<Vec<ContainerAddress>> {
+        let output = self
+            .run_command(&[
+                "inspect".to_string(),
+                "--format".to_string(),
+                "{{range $name, $net := .NetworkSettings.Networks}}{{$name}}|{{$net.IPAddress}}|{{$net.IPPrefixLen}}|{{$net.GlobalIPv6Address}}|{{$net.GlobalIPv6PrefixLen}}\n{{end}}".to_string(),
+                container_id.to_string(),
+            ])
+            .await?;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.trim().split('|');
+                let network = parts.next()?.to_string();
+                let ip = parts.next()?.parse().ok()?;
+                let prefix_len = parts.next()?.parse().ok()?;
+                let ipv6 = parts
+                    .next()
+                    .and_then(|ip| ip.parse().ok())
+                    .zip(parts.next().and_then(|len| len.parse().ok()));
+                Some(ContainerAddress {
+                    network,
+                    ip,
+                    prefix_len,
+                    ipv6,
+                })
+            })
+            .collect())
+    }
+
     async fn is_available(&self) -> bool {
         Command::new("podman")
             .arg("--version")
@@ -462,6 +563,17 @@ impl ContainerRuntime for PodmanRuntime {
             .unwrap_or(false)
     }
 
+    async fn engine_version(&self) -> Result<Option<String>> {
+        let output = self
+            .run_command(&[
+                "version".to_string(),
+                "--format".to_string(),
+                "{{.Client.Version}}".to_string(),
+            ])
+            .await?;
+        Ok(Some(output).filter(|v| !v.is_empty()))
+    }
+
     async fn list_containers(&self, name_prefix: &str) -> Result<Vec<ContainerInfo>> {
         let output = self
             .run_command(&[
@@ -506,43 +618,12 @@ impl ContainerRuntime for PodmanRuntime {
 
     async fn list_compose_containers(&self, project_name: &str) -> Result<Vec<ContainerInfo>> {
         // Filter by Docker Compose project label
-        let output = self
-            .run_command(&[
-                "ps".to_string(),
-                "--filter".to_string(),
-                format!("label=com.docker.compose.project={}", project_name),
-                "--format".to_string(),
-                "{{.ID}}|{{.Names}}|{{.State}}|{{.Ports}}".to_string(),
-            ])
-            .await?;
-
-        let mut result = Vec::new();
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 3 {
-                let port = parts.get(3).and_then(|ports| {
-                    ports
-                        .split("->")
-                        .next()
-                        .and_then(|host_part| host_part.split(':').next_back())
-                        .and_then(|p| p.parse().ok())
-                });
-
-                let is_running = parts[2].to_lowercase() == "running";
-
-                result.push(ContainerInfo {
-                    id: parts[0].to_string(),
-                    name: parts[1].to_string(),
-                    status: parts[2].to_string(),
-                    port,
-                    running: is_running,
-                    host_port: port,
-                    restart_count: 0,
-                });
-            }
-        }
+        let label = format!("com.docker.compose.project={}", project_name);
+        self.list_by_label(&label, false).await
+    }
 
-        Ok(result)
+    async fn list_labeled_containers(&self, label: &str) -> Result<Vec<ContainerInfo>> {
+        self.list_by_label(label, true).await
     }
 
     async fn stats(&self, container_id: &str) -> Result<ContainerStats> {
@@ -664,6 +745,47 @@ impl ContainerRuntime for PodmanRuntime {
         Ok(())
     }
 
+    async fn tag_image(&self, source: &str, target: &str) -> Result<()> {
+        self.run_command(&["tag".to_string(), source.to_string(), target.to_string()])
+            .await?;
+        Ok(())
+    }
+
+    async fn list_images(&self, repository: &str) -> Result<Vec<ImageInfo>> {
+        let stdout = self
+            .run_command(&[
+                "images".to_string(),
+                "--filter".to_string(),
+                format!("reference={}", repository),
+                "--format".to_string(),
+                "json".to_string(),
+            ])
+            .await?;
+        if stdout.is_empty() {
+            return Ok(Vec::new());
+        }
+        let images: Vec<serde_json::Value> =
+            serde_json::from_str(&stdout).context("Failed to parse podman images JSON")?;
+
+        Ok(images
+            .iter()
+            .map(|image| ImageInfo {
+                id: image["Id"].as_str().unwrap_or_default().to_string(),
+                tags: image["Names"]
+                    .as_array()
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(|n| n.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                created: image["Created"].as_i64().unwrap_or(0),
+                size: image["Size"].as_i64().unwrap_or(0),
+            })
+            .collect())
+    }
+
     async fn prune_images(&self) -> Result<u64> {
         // Podman image prune returns the IDs of pruned images
         // We can't easily get space reclaimed from CLI, so return 0
@@ -427,6 +427,7 @@ pub async fn get_system_health(config: &Config, db: &DbPool) -> SystemHealthStat
         disk_healthy,
         checks,
         version: env!("CARGO_PKG_VERSION").to_string(),
+        host_hygiene: None,
     }
 }
 
@@ -445,6 +446,10 @@ pub struct SystemHealthStatus {
     pub checks: Vec<CheckResult>,
     /// Rivetr version
     pub version: String,
+    /// Latest host hygiene check (pending security updates, container engine
+    /// support), once one has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_hygiene: Option<crate::engine::host_hygiene::HostHygieneReport>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,510 @@
+//! Content-addressed artifact store.
+//!
+//! Artifacts of deployments (diagnostics bundles, build log archives) are kept
+//! once per content under `artifacts/<sha256>` in the configured storage, no
+//! matter how many deployments refer to the same bytes. `artifact_refs`
+//! records which deployment refers to which blob, one ref per deployment and
+//! kind; refs go away with their deployment (retention cleanup). A periodic
+//! garbage collection removes blobs nothing refers to, so the disk used by
+//! artifacts follows the deployments that are kept.
+
+use anyhow::Result;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::interval;
+
+use super::Storage;
+use crate::DbPool;
+
+/// Diagnostics bundle of a failed deployment (JSON)
+pub const KIND_DIAGNOSTICS: &str = "diagnostics";
+/// Build and deploy log of a finished deployment (gzipped text)
+pub const KIND_BUILD_LOG: &str = "build_log";
+
+/// Storage directory of artifact blobs
+const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Unreferenced blobs stored more recently than this are kept, so one stored
+/// moments before its ref is recorded isn't collected
+const GC_GRACE: chrono::Duration = chrono::Duration::hours(1);
+
+/// How often garbage collection runs
+const GC_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+/// Number of locks digests are spread over
+const DIGEST_LOCK_STRIPES: usize = 64;
+
+lazy_static! {
+    /// Serialize `put` and garbage collection of the same blob, so a blob
+    /// stored again while it is being collected isn't deleted from under
+    /// its new row
+    static ref DIGEST_LOCKS: Vec<Mutex<()>> =
+        (0..DIGEST_LOCK_STRIPES).map(|_| Mutex::new(())).collect();
+}
+
+/// Hex SHA-256 of `data`, the address of its blob
+pub fn digest_of(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn blob_key(digest: &str) -> String {
+    format!("{}/{}", ARTIFACTS_DIR, digest)
+}
+
+/// The lock guarding the blob of `digest`
+fn digest_lock(digest: &str) -> &'static Mutex<()> {
+    let stripe = u8::from_str_radix(digest.get(..2).unwrap_or("0"), 16).unwrap_or(0);
+    &DIGEST_LOCKS[stripe as usize % DIGEST_LOCK_STRIPES]
+}
+
+/// Store `data` as the `kind` artifact of a deployment, replacing any earlier
+/// one, and return its digest. Content already stored isn't written again.
+pub async fn put(
+    db: &DbPool,
+    storage: &dyn Storage,
+    deployment_id: &str,
+    kind: &str,
+    data: Vec<u8>,
+) -> Result<String> {
+    let digest = digest_of(&data);
+    let size = data.len() as i64;
+    let now = Utc::now().to_rfc3339();
+
+    // Touching the row keeps garbage collection off the blob from here on
+    let _blob = digest_lock(&digest).lock().await;
+    let known = sqlx::query("UPDATE artifacts SET last_stored_at = ? WHERE digest = ?")
+        .bind(&now)
+        .bind(&digest)
+        .execute(db)
+        .await?
+        .rows_affected()
+        > 0;
+    if !known {
+        storage.put(&blob_key(&digest), data).await?;
+        sqlx::query(
+            "INSERT INTO artifacts (digest, size, created_at, last_stored_at) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(digest) DO UPDATE SET last_stored_at = excluded.last_stored_at",
+        )
+        .bind(&digest)
+        .bind(size)
+        .bind(&now)
+        .bind(&now)
+        .execute(db)
+        .await?;
+    }
+
+    sqlx::query(
+        "INSERT INTO artifact_refs (deployment_id, kind, digest, created_at) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(deployment_id, kind) DO UPDATE SET \
+         digest = excluded.digest, created_at = excluded.created_at",
+    )
+    .bind(deployment_id)
+    .bind(kind)
+    .bind(&digest)
+    .bind(&now)
+    .execute(db)
+    .await?;
+    Ok(digest)
+}
+
+/// Read the `kind` artifact of a deployment, or `None` if it has none
+pub async fn get(
+    db: &DbPool,
+    storage: &dyn Storage,
+    deployment_id: &str,
+    kind: &str,
+) -> Result<Option<Vec<u8>>> {
+    let digest: Option<String> =
+        sqlx::query_scalar("SELECT digest FROM artifact_refs WHERE deployment_id = ? AND kind = ?")
+            .bind(deployment_id)
+            .bind(kind)
+            .fetch_optional(db)
+            .await?;
+    match digest {
+        Some(digest) => storage.get(&blob_key(&digest)).await,
+        None => Ok(None),
+    }
+}
+
+/// Render log lines as a text archive, one `timestamp [level] message` line each
+fn format_log(lines: &[(String, String, String)]) -> String {
+    lines
+        .iter()
+        .map(|(timestamp, level, message)| format!("{} [{}] {}\n", timestamp, level, message))
+        .collect()
+}
+
+/// Archive a finished deployment's log as a gzipped `build_log` artifact, so
+/// it outlives log retention. Deployments without log lines are skipped.
+pub async fn archive_build_log(db: &DbPool, storage: &dyn Storage, deployment_id: &str) {
+    let result: Result<()> = async {
+        let lines: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT timestamp, level, message FROM deployment_logs \
+             WHERE deployment_id = ? ORDER BY id",
+        )
+        .bind(deployment_id)
+        .fetch_all(db)
+        .await?;
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(format_log(&lines).as_bytes())?;
+        let archive = encoder.finish()?;
+        put(db, storage, deployment_id, KIND_BUILD_LOG, archive).await?;
+        Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(deployment_id = %deployment_id, error = %e, "Failed to archive build log");
+    }
+}
+
+/// Totals of the artifact store
+#[derive(Debug, Default, Clone, Serialize, sqlx::FromRow)]
+pub struct ArtifactStats {
+    pub blobs: i64,
+    pub bytes: i64,
+    /// Blobs no deployment refers to (collected once older than an hour)
+    pub unreferenced_blobs: i64,
+    pub unreferenced_bytes: i64,
+    pub refs: i64,
+}
+
+/// Count blobs, their size and refs
+pub async fn stats(db: &DbPool) -> Result<ArtifactStats> {
+    Ok(sqlx::query_as(
+        "SELECT COUNT(*) AS blobs, COALESCE(SUM(size), 0) AS bytes, \
+         COALESCE(SUM(CASE WHEN r.digest IS NULL THEN 1 ELSE 0 END), 0) AS unreferenced_blobs, \
+         COALESCE(SUM(CASE WHEN r.digest IS NULL THEN size ELSE 0 END), 0) AS unreferenced_bytes, \
+         (SELECT COUNT(*) FROM artifact_refs) AS refs \
+         FROM artifacts a \
+         LEFT JOIN (SELECT DISTINCT digest FROM artifact_refs) r ON r.digest = a.digest",
+    )
+    .fetch_one(db)
+    .await?)
+}
+
+/// What a garbage collection removed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GcStats {
+    pub refs_removed: u64,
+    pub blobs_removed: u64,
+    pub bytes_freed: u64,
+    /// Blobs in storage the database didn't know about
+    pub orphans_removed: u64,
+}
+
+/// Remove refs of deleted deployments, then blobs nothing refers to
+pub async fn collect_garbage(db: &DbPool, storage: &dyn Storage) -> Result<GcStats> {
+    let mut stats = GcStats::default();
+    let cutoff = (Utc::now() - GC_GRACE).to_rfc3339();
+
+    // Deployments are usually deleted with their refs (ON DELETE CASCADE);
+    // this covers any deleted while foreign keys were off
+    stats.refs_removed = sqlx::query(
+        "DELETE FROM artifact_refs WHERE deployment_id NOT IN (SELECT id FROM deployments)",
+    )
+    .execute(db)
+    .await?
+    .rows_affected();
+
+    let unreferenced: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT digest, size FROM artifacts a WHERE last_stored_at < ? \
+         AND NOT EXISTS (SELECT 1 FROM artifact_refs r WHERE r.digest = a.digest)",
+    )
+    .bind(&cutoff)
+    .fetch_all(db)
+    .await?;
+    for (digest, size) in unreferenced {
+        // Held until the blob is gone, and the delete re-checks the row:
+        // `put` may have just reused the blob
+        let _blob = digest_lock(&digest).lock().await;
+        let deleted = sqlx::query(
+            "DELETE FROM artifacts WHERE digest = ? AND last_stored_at < ? \
+             AND NOT EXISTS (SELECT 1 FROM artifact_refs r WHERE r.digest = artifacts.digest)",
+        )
+        .bind(&digest)
+        .bind(&cutoff)
+        .execute(db)
+        .await?
+        .rows_affected();
+        if deleted == 0 {
+            continue;
+        }
+        storage.delete(&blob_key(&digest)).await?;
+        stats.blobs_removed += 1;
+        stats.bytes_freed += size.max(0) as u64;
+    }
+
+    // Blobs written by a `put` that failed before recording them
+    let known: HashSet<String> = sqlx::query_scalar("SELECT digest FROM artifacts")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+    let cutoff = Utc::now() - GC_GRACE;
+    for object in storage.list(ARTIFACTS_DIR).await? {
+        let old = !matches!(object.modified, Some(modified) if modified >= cutoff);
+        if old && !known.contains(object.name()) && storage.delete(&object.key).await? {
+            stats.orphans_removed += 1;
+            stats.bytes_freed += object.size;
+        }
+    }
+    Ok(stats)
+}
+
+/// Spawn the artifact garbage collector (runs every 6 hours)
+pub fn spawn_artifact_gc(db: DbPool, storage: Arc<dyn Storage>) {
+    tracing::info!("Starting artifact garbage collector (6h interval)");
+
+    tokio::spawn(async move {
+        let mut tick = interval(GC_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tick.tick().await;
+            crate::utils::supervise::guarded("artifact_gc", async {
+                match collect_garbage(&db, storage.as_ref()).await {
+                    Ok(stats) if stats != GcStats::default() => tracing::info!(
+                        refs = stats.refs_removed,
+                        blobs = stats.blobs_removed,
+                        orphans = stats.orphans_removed,
+                        bytes_freed = stats.bytes_freed,
+                        "Artifact garbage collection removed unreferenced blobs"
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(error = %e, "Artifact garbage collection failed"),
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_of() {
+        assert_eq!(
+            digest_of(b"hello"),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            blob_key(&digest_of(b"")),
+            "artifacts/e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_format_log() {
+        let lines = vec![
+            (
+                "2026-01-01T00:00:00Z".to_string(),
+                "info".to_string(),
+                "Cloning".to_string(),
+            ),
+            (
+                "2026-01-01T00:00:05Z".to_string(),
+                "error".to_string(),
+                "Build failed".to_string(),
+            ),
+        ];
+        assert_eq!(
+            format_log(&lines),
+            "2026-01-01T00:00:00Z [info] Cloning\n2026-01-01T00:00:05Z [error] Build failed\n"
+        );
+    }
+
+    async fn store() -> (tempfile::TempDir, DbPool, super::super::LocalStorage) {
+        let (dir, db) = crate::db::test_pool().await;
+        let storage = super::super::LocalStorage::new(dir.path().join("storage"));
+        (dir, db, storage)
+    }
+
+    /// Backdate a blob past the grace period, as if stored long ago
+    async fn age(db: &DbPool, digest: &str) {
+        let old = (Utc::now() - GC_GRACE * 2).to_rfc3339();
+        sqlx::query("UPDATE artifacts SET last_stored_at = ? WHERE digest = ?")
+            .bind(old)
+            .bind(digest)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_put_dedups_and_counts_refs() {
+        let (_dir, db, storage) = store().await;
+        let app = crate::db::test_app(&db, "api").await;
+        let first = crate::db::test_deployment(&db, &app, "failed").await;
+        let second = crate::db::test_deployment(&db, &app, "failed").await;
+
+        let digest = put(&db, &storage, &first, KIND_DIAGNOSTICS, b"{}".to_vec())
+            .await
+            .unwrap();
+        let again = put(&db, &storage, &second, KIND_DIAGNOSTICS, b"{}".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(digest, again);
+
+        let stats = stats(&db).await.unwrap();
+        assert_eq!((stats.blobs, stats.bytes, stats.refs), (1, 2, 2));
+        assert_eq!(stats.unreferenced_blobs, 0);
+        assert_eq!(
+            get(&db, &storage, &second, KIND_DIAGNOSTICS).await.unwrap(),
+            Some(b"{}".to_vec())
+        );
+        assert_eq!(
+            get(&db, &storage, &second, KIND_BUILD_LOG).await.unwrap(),
+            None
+        );
+
+        // Replacing a deployment's artifact moves its ref to the new blob
+        put(&db, &storage, &second, KIND_DIAGNOSTICS, b"[]".to_vec())
+            .await
+            .unwrap();
+        let stats = super::stats(&db).await.unwrap();
+        assert_eq!((stats.blobs, stats.refs), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_gc_keeps_referenced_and_recent_blobs() {
+        let (_dir, db, storage) = store().await;
+        let app = crate::db::test_app(&db, "api").await;
+        let kept = crate::db::test_deployment(&db, &app, "failed").await;
+        let deleted = crate::db::test_deployment(&db, &app, "failed").await;
+
+        let shared = put(&db, &storage, &kept, KIND_DIAGNOSTICS, b"shared".to_vec())
+            .await
+            .unwrap();
+        put(
+            &db,
+            &storage,
+            &deleted,
+            KIND_DIAGNOSTICS,
+            b"shared".to_vec(),
+        )
+        .await
+        .unwrap();
+        let own = put(&db, &storage, &deleted, KIND_BUILD_LOG, b"log".to_vec())
+            .await
+            .unwrap();
+        age(&db, &shared).await;
+        age(&db, &own).await;
+
+        sqlx::query("DELETE FROM deployments WHERE id = ?")
+            .bind(&deleted)
+            .execute(&db)
+            .await
+            .unwrap();
+        let gc = collect_garbage(&db, &storage).await.unwrap();
+        assert_eq!((gc.blobs_removed, gc.bytes_freed), (1, 3));
+        assert!(storage.get(&blob_key(&own)).await.unwrap().is_none());
+        assert_eq!(
+            get(&db, &storage, &kept, KIND_DIAGNOSTICS).await.unwrap(),
+            Some(b"shared".to_vec())
+        );
+
+        // Unreferenced but stored within the grace period: kept
+        let kept_ref = crate::db::test_deployment(&db, &app, "failed").await;
+        let fresh = put(&db, &storage, &kept_ref, KIND_BUILD_LOG, b"new".to_vec())
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM artifact_refs WHERE digest = ?")
+            .bind(&fresh)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            collect_garbage(&db, &storage).await.unwrap().blobs_removed,
+            0
+        );
+        assert!(storage.get(&blob_key(&fresh)).await.unwrap().is_some());
+    }
+
+    /// Local storage whose deletes stall, widening the window between GC
+    /// dropping a blob's row and deleting the blob
+    struct SlowDeletes(super::super::LocalStorage);
+
+    #[async_trait::async_trait]
+    impl Storage for SlowDeletes {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+        async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+            self.0.put(key, data).await
+        }
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            self.0.get(key).await
+        }
+        async fn delete(&self, key: &str) -> Result<bool> {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            self.0.delete(key).await
+        }
+        async fn list(&self, dir: &str) -> Result<Vec<super::super::StoredObject>> {
+            self.0.list(dir).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_during_gc_keeps_blob() {
+        let (_dir, db, storage) = store().await;
+        let storage: Arc<dyn Storage> = Arc::new(SlowDeletes(storage));
+        let app = crate::db::test_app(&db, "api").await;
+        let old = crate::db::test_deployment(&db, &app, "failed").await;
+        let new = crate::db::test_deployment(&db, &app, "failed").await;
+
+        let digest = put(
+            &db,
+            storage.as_ref(),
+            &old,
+            KIND_DIAGNOSTICS,
+            b"bundle".to_vec(),
+        )
+        .await
+        .unwrap();
+        age(&db, &digest).await;
+        sqlx::query("DELETE FROM deployments WHERE id = ?")
+            .bind(&old)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // A new deployment stores the same content while GC is deleting it
+        let gc = tokio::spawn({
+            let (db, storage) = (db.clone(), storage.clone());
+            async move { collect_garbage(&db, storage.as_ref()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        put(
+            &db,
+            storage.as_ref(),
+            &new,
+            KIND_DIAGNOSTICS,
+            b"bundle".to_vec(),
+        )
+        .await
+        .unwrap();
+        gc.await.unwrap().unwrap();
+
+        assert_eq!(
+            get(&db, storage.as_ref(), &new, KIND_DIAGNOSTICS)
+                .await
+                .unwrap(),
+            Some(b"bundle".to_vec())
+        );
+        let stats = stats(&db).await.unwrap();
+        assert_eq!((stats.blobs, stats.refs), (1, 1));
+    }
+}
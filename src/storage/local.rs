@@ -0,0 +1,152 @@
+//! Local filesystem storage.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use super::{validate_key, Storage, StoredObject};
+
+/// Stores objects as files under a root directory
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    async fn create_parent(path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key)?;
+        Self::create_parent(&path).await?;
+        // Write beside the target and rename, so readers never see a partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, data)
+            .await
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, source: &Path) -> Result<()> {
+        let path = self.path(key)?;
+        Self::create_parent(&path).await?;
+        // Same filesystem: move instead of copying
+        if tokio::fs::rename(source, &path).await.is_err() {
+            tokio::fs::copy(source, &path)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    async fn list(&self, dir: &str) -> Result<Vec<StoredObject>> {
+        let path = self.path(dir)?;
+        let mut entries = match tokio::fs::read_dir(&path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to list {}", path.display())),
+        };
+
+        let mut objects = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !metadata.is_file() || name.ends_with(".partial") {
+                continue;
+            }
+            objects.push(StoredObject {
+                key: format!("{}/{}", dir, name),
+                size: metadata.len(),
+                modified: metadata.modified().ok().map(Into::into),
+            });
+        }
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_list_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        assert!(storage.get("backups/a.tar.gz").await.unwrap().is_none());
+        assert!(storage.list("backups").await.unwrap().is_empty());
+
+        storage
+            .put("backups/a.tar.gz", b"hello".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get("backups/a.tar.gz").await.unwrap().as_deref(),
+            Some(&b"hello"[..])
+        );
+        assert!(dir.path().join("backups/a.tar.gz").exists());
+
+        let source = dir.path().join("staged");
+        std::fs::write(&source, b"staged").unwrap();
+        storage.put_file("backups/b.tar.gz", &source).await.unwrap();
+
+        let mut names: Vec<(String, u64)> = storage
+            .list("backups")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| (o.name().to_string(), o.size))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![("a.tar.gz".to_string(), 5), ("b.tar.gz".to_string(), 6)]
+        );
+
+        assert!(storage.delete("backups/a.tar.gz").await.unwrap());
+        assert!(!storage.delete("backups/a.tar.gz").await.unwrap());
+        assert!(storage.get("../outside").await.is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Pluggable object storage for files Rivetr keeps: instance backups and
+//! deployment artifacts (diagnostics bundles, build log archives, see
+//! [`artifacts`]).
+//!
+//! Everything is addressed by a relative key such as
+//! `backups/rivetr-backup-20250101-000000.tar.gz`, and the backend configured
+//! in `[storage]` decides where it lives: on the local filesystem (under
+//! `data_dir` by default, which is where these files always lived), in an S3
+//! bucket, or on another server over SFTP.
+
+pub mod artifacts;
+mod local;
+mod s3;
+mod sftp;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+pub use sftp::SftpStorage;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::{StorageBackend, StorageConfig};
+
+/// An object in storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredObject {
+    /// Key relative to the listed directory's parent, e.g. "backups/x.tar.gz"
+    pub key: String,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+impl StoredObject {
+    /// Last component of the key
+    pub fn name(&self) -> &str {
+        self.key.rsplit('/').next().unwrap_or(&self.key)
+    }
+}
+
+/// Object storage backend
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Backend name for logs ("local", "s3", "sftp")
+    fn name(&self) -> &'static str;
+
+    /// Store `data` under `key`, replacing any existing object
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Store the file at `path` under `key`
+    async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        let data = tokio::fs::read(path).await?;
+        self.put(key, data).await
+    }
+
+    /// Read the object under `key`, or `None` if there is none
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Delete the object under `key`; returns whether it existed
+    async fn delete(&self, key: &str) -> Result<bool>;
+
+    /// Objects directly inside the directory `dir` (not recursive)
+    async fn list(&self, dir: &str) -> Result<Vec<StoredObject>>;
+}
+
+/// Reject keys that could escape the storage root
+pub fn validate_key(key: &str) -> Result<()> {
+    if key.is_empty()
+        || key.starts_with('/')
+        || key.contains('\\')
+        || key
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+    {
+        anyhow::bail!("Invalid storage key: {:?}", key);
+    }
+    Ok(())
+}
+
+/// Build the backend configured in `[storage]`
+pub fn from_config(config: &StorageConfig, data_dir: &Path) -> Result<Arc<dyn Storage>> {
+    Ok(match config.backend {
+        StorageBackend::Local => Arc::new(LocalStorage::new(
+            config
+                .path
+                .clone()
+                .unwrap_or_else(|| data_dir.to_path_buf()),
+        )),
+        StorageBackend::S3 => Arc::new(S3Storage::new(&config.s3)?),
+        StorageBackend::Sftp => Arc::new(SftpStorage::new(&config.sftp)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("backups/rivetr-backup-1.tar.gz").is_ok());
+        assert!(validate_key("diagnostics/abc.json").is_ok());
+        for bad in [
+            "",
+            "/etc/passwd",
+            "../x",
+            "a/../b",
+            "a//b",
+            "a/./b",
+            "a\\b",
+            "a/",
+        ] {
+            assert!(validate_key(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! S3 (and S3-compatible) storage.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::backup::s3::S3Client;
+use crate::config::S3StorageConfig;
+
+use super::{validate_key, Storage, StoredObject};
+
+/// Stores objects in an S3 bucket, under an optional key prefix
+pub struct S3Storage {
+    client: S3Client,
+    prefix: String,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3StorageConfig) -> Result<Self> {
+        if config.bucket.is_empty() {
+            anyhow::bail!("storage.s3.bucket must be set for the s3 storage backend");
+        }
+        let prefix = config
+            .prefix
+            .as_deref()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty());
+        let client = S3Client::new(
+            config.endpoint.as_deref(),
+            &config.bucket,
+            &config.region,
+            &config.access_key,
+            &config.secret_key,
+            prefix,
+        )?;
+        Ok(Self {
+            client,
+            prefix: prefix.map(|p| format!("{}/", p)).unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        validate_key(key)?;
+        self.client.upload_backup(key, data).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        validate_key(key)?;
+        self.client.try_download(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        validate_key(key)?;
+        // S3 deletes succeed whether or not the object exists
+        if !self.client.exists(key).await? {
+            return Ok(false);
+        }
+        self.client.delete_backup(key).await?;
+        Ok(true)
+    }
+
+    async fn list(&self, dir: &str) -> Result<Vec<StoredObject>> {
+        validate_key(dir)?;
+        let objects = self.client.list_backups(&format!("{}/", dir)).await?;
+        Ok(objects
+            .into_iter()
+            .filter_map(|obj| {
+                let key = obj.key.strip_prefix(&self.prefix)?.to_string();
+                // Only direct children of `dir`
+                let name = key.strip_prefix(dir)?.strip_prefix('/')?;
+                if name.is_empty() || name.contains('/') {
+                    return None;
+                }
+                Some(StoredObject {
+                    size: obj.size.max(0) as u64,
+                    modified: obj
+                        .last_modified
+                        .as_deref()
+                        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                        .map(|t| t.with_timezone(&chrono::Utc)),
+                    key,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,258 @@
+//! SFTP storage, using the system `sftp` client in batch mode.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::SftpStorageConfig;
+
+use super::{validate_key, Storage, StoredObject};
+
+/// Stores objects under a directory on a remote server
+pub struct SftpStorage {
+    host: String,
+    port: u16,
+    username: String,
+    identity_file: Option<PathBuf>,
+    root: String,
+}
+
+/// Result of one `sftp` batch run
+struct BatchOutput {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl SftpStorage {
+    pub fn new(config: &SftpStorageConfig) -> Result<Self> {
+        if config.host.is_empty() {
+            anyhow::bail!("storage.sftp.host must be set for the sftp storage backend");
+        }
+        Ok(Self {
+            host: config.host.clone(),
+            port: config.port,
+            username: config.username.clone(),
+            identity_file: config.identity_file.clone(),
+            root: config
+                .path
+                .as_deref()
+                .map(|p| p.trim_end_matches('/').to_string())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Remote path for `key`, quoted for a batch file
+    fn remote_path(&self, key: &str) -> String {
+        if self.root.is_empty() {
+            quote(key)
+        } else {
+            quote(&format!("{}/{}", self.root, key))
+        }
+    }
+
+    /// Run `commands` in one sftp session. A command prefixed with `-` may
+    /// fail without aborting the batch.
+    async fn batch(&self, commands: &[String]) -> Result<BatchOutput> {
+        let mut command = Command::new("sftp");
+        command
+            .arg("-b")
+            .arg("-")
+            .arg("-o")
+            .arg("StrictHostKeyChecking=no")
+            .arg("-o")
+            .arg("ConnectTimeout=10")
+            .arg("-o")
+            .arg("BatchMode=yes")
+            .arg("-P")
+            .arg(self.port.to_string());
+        if let Some(ref key) = self.identity_file {
+            command.arg("-i").arg(key);
+        }
+        command
+            .arg(format!("{}@{}", self.username, self.host))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command.spawn().context("Failed to run sftp")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(commands.join("\n").as_bytes()).await?;
+            stdin.write_all(b"\n").await?;
+        }
+        let output = child.wait_with_output().await?;
+
+        Ok(BatchOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
+    async fn upload(&self, key: &str, local: &Path) -> Result<()> {
+        validate_key(key)?;
+        // Create each parent directory; existing ones just fail harmlessly
+        let mut commands: Vec<String> = Vec::new();
+        let parts: Vec<&str> = key.split('/').collect();
+        for depth in 1..parts.len() {
+            commands.push(format!(
+                "-mkdir {}",
+                self.remote_path(&parts[..depth].join("/"))
+            ));
+        }
+        commands.push(format!(
+            "put {} {}",
+            quote(&local.to_string_lossy()),
+            self.remote_path(key)
+        ));
+
+        let output = self.batch(&commands).await?;
+        if !output.success {
+            anyhow::bail!("sftp upload of {} failed: {}", key, output.stderr.trim());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for SftpStorage {
+    fn name(&self) -> &'static str {
+        "sftp"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let staged = tempfile::NamedTempFile::new()?;
+        tokio::fs::write(staged.path(), data).await?;
+        self.upload(key, staged.path()).await
+    }
+
+    async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        self.upload(key, path).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        validate_key(key)?;
+        let staged = tempfile::NamedTempFile::new()?;
+        let output = self
+            .batch(&[format!(
+                "get {} {}",
+                self.remote_path(key),
+                quote(&staged.path().to_string_lossy())
+            )])
+            .await?;
+        if !output.success {
+            if is_not_found(&output.stderr) {
+                return Ok(None);
+            }
+            anyhow::bail!("sftp download of {} failed: {}", key, output.stderr.trim());
+        }
+        Ok(Some(tokio::fs::read(staged.path()).await?))
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        validate_key(key)?;
+        let output = self
+            .batch(&[format!("rm {}", self.remote_path(key))])
+            .await?;
+        if !output.success {
+            if is_not_found(&output.stderr) {
+                return Ok(false);
+            }
+            anyhow::bail!("sftp delete of {} failed: {}", key, output.stderr.trim());
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, dir: &str) -> Result<Vec<StoredObject>> {
+        validate_key(dir)?;
+        let output = self
+            .batch(&[format!("ls -ln {}", self.remote_path(dir))])
+            .await?;
+        if !output.success {
+            if is_not_found(&output.stderr) {
+                return Ok(Vec::new());
+            }
+            anyhow::bail!("sftp listing of {} failed: {}", dir, output.stderr.trim());
+        }
+        Ok(parse_listing(dir, &output.stdout))
+    }
+}
+
+fn quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn is_not_found(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("not found") || stderr.contains("no such file")
+}
+
+/// Parse `ls -ln` output into the regular files it lists. sftp's listing
+/// times aren't machine readable, so `modified` is left unset.
+fn parse_listing(dir: &str, stdout: &str) -> Vec<StoredObject> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with('-'))
+        .filter_map(|line| {
+            // mode links uid gid size month day time-or-year name
+            let mut fields = line.split_whitespace();
+            let size = fields.nth(4)?.parse().ok()?;
+            let path = fields.nth(3)?;
+            let name = path.rsplit('/').next()?;
+            if name.is_empty() || name.ends_with(".partial") {
+                return None;
+            }
+            Some(StoredObject {
+                key: format!("{}/{}", dir, name),
+                size,
+                modified: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing() {
+        let stdout = "sftp> ls -ln \"/srv/rivetr/backups\"\n\
+            drwxr-xr-x    2 0        0            4096 Jan  2 10:00 /srv/rivetr/backups/old\n\
+            -rw-r--r--    1 0        0         1048576 Jan  2 10:00 /srv/rivetr/backups/rivetr-backup-20250102-100000.tar.gz\n\
+            -rw-r--r--    1 0        0              12 Dec 31  2024 /srv/rivetr/backups/notes.txt\n";
+        let objects = parse_listing("backups", stdout);
+        assert_eq!(
+            objects,
+            vec![
+                StoredObject {
+                    key: "backups/rivetr-backup-20250102-100000.tar.gz".to_string(),
+                    size: 1048576,
+                    modified: None,
+                },
+                StoredObject {
+                    key: "backups/notes.txt".to_string(),
+                    size: 12,
+                    modified: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remote_path_quoting() {
+        let storage = SftpStorage::new(&SftpStorageConfig {
+            host: "backup.example.com".to_string(),
+            path: Some("/srv/rivetr files/".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            storage.remote_path("backups/a.tar.gz"),
+            "\"/srv/rivetr files/backups/a.tar.gz\""
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! End-to-end test harness (feature `test-utils`).
+//!
+//! `TestApp::spawn` boots the real server — migrations on a fresh SQLite
+//! database in a temp directory, the deployment engine, and the axum router
+//! on an ephemeral port — against a `MockRuntime`, so tests can drive the
+//! API over HTTP and watch deployments run without Docker.
+//!
+//! ```ignore
+//! let app = TestApp::spawn(MockRuntime::new()).await;
+//! let (status, body) = app.post("/api/apps", json!({ ... })).await;
+//! ```
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+use crate::config::Config;
+use crate::engine::updater::UpdateChecker;
+use crate::engine::{BuildLimits, DeploymentEngine};
+use crate::proxy::RouteTable;
+use crate::runtime::MockRuntime;
+use crate::{AppState, DbPool};
+
+/// Password of the admin account `TestApp::spawn` creates
+pub const ADMIN_PASSWORD: &str = "Rivetr-test-password-1";
+
+/// A running server backed by a `MockRuntime` and a temporary database
+pub struct TestApp {
+    /// Base URL of the API, e.g. `http://127.0.0.1:41234`
+    pub url: String,
+    /// Session token of the admin account
+    pub token: String,
+    pub db: DbPool,
+    pub runtime: MockRuntime,
+    pub routes: Arc<ArcSwap<RouteTable>>,
+    pub state: Arc<AppState>,
+    client: reqwest::Client,
+    // Removed (with the database) when the app is dropped
+    _data_dir: TempDir,
+}
+
+impl TestApp {
+    /// Boot a server with the default configuration, and rate limiting off
+    pub async fn spawn(runtime: MockRuntime) -> Self {
+        let mut config = Config::default();
+        // Tests poll the API far faster than the default limits allow
+        config.rate_limit.enabled = false;
+        Self::spawn_with_config(runtime, config)
+            .await
+            .expect("failed to start test server")
+    }
+
+    /// Boot a server with `config`; its data directory is replaced with a
+    /// temporary one
+    pub async fn spawn_with_config(runtime: MockRuntime, mut config: Config) -> Result<Self> {
+        let data_dir = tempfile::tempdir().context("Failed to create data directory")?;
+        config.server.data_dir = data_dir.path().to_path_buf();
+
+        let db = crate::db::init(&config.server.data_dir).await?;
+        let routes = Arc::new(ArcSwap::from_pointee(RouteTable::new()));
+        let (deploy_tx, deploy_rx) = mpsc::channel(100);
+
+        let deploy_limit = config.runtime.max_concurrent_deployments.max(1);
+        let deploy_semaphore = Arc::new(tokio::sync::Semaphore::new(deploy_limit));
+        let deploy_concurrency = Arc::new(std::sync::atomic::AtomicUsize::new(deploy_limit));
+        let cancel_tokens = crate::engine::CancelTokens::default();
+        let engine_counters = crate::engine::load::SharedEngineCounters::default();
+
+        let state = Arc::new(
+            AppState::new(
+                config.clone(),
+                db.clone(),
+                deploy_tx,
+                Arc::new(runtime.clone()),
+                routes.clone(),
+                Arc::new(UpdateChecker::new(config.auto_update.clone())),
+            )
+            .with_deploy_concurrency(deploy_semaphore.clone(), deploy_concurrency)
+            .with_cancel_tokens(cancel_tokens.clone())
+            .with_engine_counters(engine_counters.clone()),
+        );
+
+        let engine = DeploymentEngine::new(
+            db.clone(),
+            Arc::new(runtime.clone()),
+            routes.clone(),
+            deploy_rx,
+            BuildLimits::from_runtime_config(&config.runtime),
+            &config.auth,
+            deploy_semaphore,
+        )
+        .with_keep_previous(Duration::from_secs(
+            config.runtime.keep_previous_minutes * 60,
+        ))
+        .with_cancel_tokens(cancel_tokens)
+        .with_counters(engine_counters);
+        tokio::spawn(engine.run());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let router = crate::api::create_router(state.clone());
+        tokio::spawn(async move {
+            let _ = axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await;
+        });
+
+        let mut app = Self {
+            url,
+            token: String::new(),
+            db,
+            runtime,
+            routes,
+            state,
+            client: reqwest::Client::new(),
+            _data_dir: data_dir,
+        };
+
+        let (status, body) = app
+            .request(
+                Method::POST,
+                "/api/auth/setup",
+                Some(json!({
+                    "email": "admin@example.com",
+                    "password": ADMIN_PASSWORD,
+                    "name": "Admin",
+                })),
+            )
+            .await;
+        anyhow::ensure!(
+            status.is_success(),
+            "Admin setup failed: {} {}",
+            status,
+            body
+        );
+        app.token = body["token"]
+            .as_str()
+            .context("Setup response has no token")?
+            .to_string();
+
+        Ok(app)
+    }
+
+    /// Call the API as the admin; returns the status and the JSON body
+    /// (`Value::Null` when the body is empty or not JSON)
+    pub async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = self.client.request(method, format!("{}{}", self.url, path));
+        if !self.token.is_empty() {
+            request = request.bearer_auth(&self.token);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await.expect("request to test server failed");
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        (status, serde_json::from_str(&text).unwrap_or(Value::Null))
+    }
+
+    pub async fn get(&self, path: &str) -> (StatusCode, Value) {
+        self.request(Method::GET, path, None).await
+    }
+
+    pub async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::POST, path, Some(body)).await
+    }
+
+    pub async fn put(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::PUT, path, Some(body)).await
+    }
+
+    pub async fn delete(&self, path: &str) -> (StatusCode, Value) {
+        self.request(Method::DELETE, path, None).await
+    }
+
+    /// Create an app from `body` and return its JSON
+    pub async fn create_app(&self, body: Value) -> Value {
+        let (status, app) = self.post("/api/apps", body).await;
+        assert!(
+            status.is_success(),
+            "creating app failed: {} {}",
+            status,
+            app
+        );
+        app
+    }
+
+    /// Trigger a deployment of an app and return its ID
+    pub async fn deploy(&self, app_id: &str) -> String {
+        let (status, deployment) = self
+            .post(&format!("/api/apps/{}/deploy", app_id), json!({}))
+            .await;
+        assert!(
+            status.is_success(),
+            "triggering deploy failed: {} {}",
+            status,
+            deployment
+        );
+        deployment["id"].as_str().unwrap_or_default().to_string()
+    }
+
+    /// Wait until a deployment reaches `running`, `failed`, `stopped` or
+    /// `cancelled` and return its JSON; panics after `timeout`
+    pub async fn wait_for_deployment(&self, deployment_id: &str, timeout: Duration) -> Value {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let (_, deployment) = self
+                .get(&format!("/api/deployments/{}", deployment_id))
+                .await;
+            if matches!(
+                deployment["status"].as_str(),
+                Some("running" | "failed" | "stopped" | "cancelled")
+            ) {
+                return deployment;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "deployment {} did not finish within {:?}: {}",
+                deployment_id,
+                timeout,
+                deployment
+            );
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Messages logged for a deployment, oldest first
+    pub async fn deployment_logs(&self, deployment_id: &str) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT message FROM deployment_logs WHERE deployment_id = ? ORDER BY id ASC",
+        )
+        .bind(deployment_id)
+        .fetch_all(&self.db)
+        .await
+        .unwrap_or_default()
+    }
+}
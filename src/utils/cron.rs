@@ -0,0 +1,129 @@
+//! Cron expression parsing shared by the schedulers.
+//!
+//! Users and the frontend write conventional 5-field cron (`min hour dom month
+//! dow`), while the `cron` crate wants a leading seconds field. Every
+//! scheduler goes through these helpers so an expression means the same thing
+//! for scheduled jobs, backups, restarts, power schedules and pre-warms.
+//!
+//! Each schedule stores an optional IANA timezone (`Europe/Berlin`) its
+//! expression is evaluated in, so "9am" stays 9am local time across DST
+//! changes. Schedules without one are evaluated in UTC. Next-run times are
+//! always stored in UTC.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+
+/// Most upcoming run times a preview returns
+pub const MAX_PREVIEW_RUNS: usize = 50;
+
+/// Normalize a cron expression to the 6-field format expected by the `cron` crate.
+/// Standard 5-field cron (min hour dom month dow) is prefixed with "0 " (seconds=0).
+pub fn normalize(expr: &str) -> String {
+    let expr = expr.trim();
+    if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    }
+}
+
+/// Parse a 5- or 6-field cron expression
+pub fn parse(expr: &str) -> Result<Schedule, String> {
+    if expr.trim().is_empty() {
+        return Err("Cron expression cannot be empty".to_string());
+    }
+    Schedule::from_str(&normalize(expr))
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expr.trim(), e))
+}
+
+/// Validate a cron expression, returning it normalized
+pub fn validate(expr: &str) -> Result<String, String> {
+    parse(expr).map(|_| normalize(expr))
+}
+
+/// Parse an IANA timezone name; `None` or empty is UTC
+pub fn parse_timezone(name: Option<&str>) -> Result<Tz, String> {
+    match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => Tz::from_str(name).map_err(|_| format!("Unknown timezone '{}'", name)),
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// Validate a schedule's timezone, returning its canonical name (`None`
+/// for empty, which means UTC)
+pub fn validate_timezone(name: Option<&str>) -> Result<Option<String>, String> {
+    match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => parse_timezone(Some(name)).map(|tz| Some(tz.name().to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Next time a cron expression fires in `timezone` (UTC when `None`), as
+/// RFC 3339 in UTC
+pub fn next_run(expr: &str, timezone: Option<&str>) -> Option<String> {
+    let tz = parse_timezone(timezone).ok()?;
+    parse(expr)
+        .ok()?
+        .upcoming(tz)
+        .next()
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+}
+
+/// The next `count` times a cron expression fires after `after`, in the
+/// timezone of `after`
+pub fn upcoming<Tz: TimeZone>(
+    expr: &str,
+    after: &DateTime<Tz>,
+    count: usize,
+) -> Result<Vec<DateTime<Tz>>, String> {
+    Ok(parse(expr)?.after(after).take(count).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            validate("0 19 * * Mon-Fri").as_deref(),
+            Ok("0 0 19 * * Mon-Fri")
+        );
+        assert_eq!(validate("  */5 * * * *  ").as_deref(), Ok("0 */5 * * * *"));
+        assert!(validate("0 0 8 * * Mon-Fri").is_ok());
+        assert!(validate("every night").is_err());
+        assert!(validate("   ").is_err());
+    }
+
+    #[test]
+    fn test_timezones() {
+        // 09:00 in Berlin is 08:00 UTC in winter and 07:00 UTC in summer
+        let berlin = parse_timezone(Some("Europe/Berlin")).unwrap();
+        for (month, utc) in [(1, "08:00"), (7, "07:00")] {
+            let after = berlin.with_ymd_and_hms(2026, month, 1, 0, 0, 0).unwrap();
+            let run = upcoming("0 9 * * *", &after, 1).unwrap()[0].with_timezone(&Utc);
+            assert_eq!(run.format("%H:%M").to_string(), utc);
+        }
+
+        assert_eq!(parse_timezone(None), Ok(Tz::UTC));
+        assert!(next_run("0 9 * * *", Some("Mars/Olympus")).is_none());
+        assert_eq!(
+            validate_timezone(Some(" America/New_York ")),
+            Ok(Some("America/New_York".to_string()))
+        );
+        assert_eq!(validate_timezone(Some("")), Ok(None));
+        assert!(validate_timezone(Some("Eastern")).is_err());
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let after = Utc.with_ymd_and_hms(2026, 1, 1, 10, 7, 0).unwrap();
+        let runs = upcoming("*/15 * * * *", &after, 3).unwrap();
+        let times: Vec<String> = runs.iter().map(|t| t.format("%H:%M").to_string()).collect();
+        assert_eq!(times, ["10:15", "10:30", "10:45"]);
+        assert!(upcoming("61 * * * *", &after, 3).is_err());
+    }
+}
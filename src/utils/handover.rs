@@ -0,0 +1,397 @@
+//! Zero-downtime process handover for upgrades.
+//!
+//! A running Rivetr can start its replacement and pass it the listening
+//! sockets for the proxy (80/443) and API ports, so no connection is refused
+//! while the binary changes:
+//!
+//! 1. Listeners are registered here as they are bound.
+//! 2. [`hand_over`] starts the current executable with those sockets inherited,
+//!    using the systemd `LISTEN_FDS` protocol plus `RIVETR_LISTEN_ROLES` to say
+//!    which socket is which.
+//! 3. The successor adopts the sockets instead of binding them and, once it is
+//!    serving, reports readiness over a pipe (and as `MAINPID=` to systemd, so
+//!    a `Type=notify` unit keeps running when the old process exits).
+//! 4. The old process stops accepting, lets in-flight connections finish and
+//!    exits.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Comma-separated listener roles, in `LISTEN_FDS` order, set for a successor
+pub const LISTEN_ROLES_ENV: &str = "RIVETR_LISTEN_ROLES";
+/// Pipe the successor writes to once it is serving
+pub const READY_FD_ENV: &str = "RIVETR_HANDOVER_READY_FD";
+
+/// How long a successor gets to start serving before the handover is abandoned
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What a listening socket is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListenerRole {
+    Http,
+    Https,
+    Api,
+    /// IPv6-only companions of the above (`server.ipv6_host`)
+    Http6,
+    Https6,
+    Api6,
+}
+
+impl ListenerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListenerRole::Http => "http",
+            ListenerRole::Https => "https",
+            ListenerRole::Api => "api",
+            ListenerRole::Http6 => "http6",
+            ListenerRole::Https6 => "https6",
+            ListenerRole::Api6 => "api6",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "http" => Some(ListenerRole::Http),
+            "https" => Some(ListenerRole::Https),
+            "api" => Some(ListenerRole::Api),
+            "http6" => Some(ListenerRole::Http6),
+            "https6" => Some(ListenerRole::Https6),
+            "api6" => Some(ListenerRole::Api6),
+            _ => None,
+        }
+    }
+}
+
+/// Roles of inherited sockets, in order. Without `RIVETR_LISTEN_ROLES` the
+/// systemd socket unit layout is assumed: HTTP, then HTTPS.
+fn parse_roles(value: Option<&str>) -> Vec<Option<ListenerRole>> {
+    match value {
+        Some(roles) => roles.split(',').map(ListenerRole::parse).collect(),
+        None => vec![Some(ListenerRole::Http), Some(ListenerRole::Https)],
+    }
+}
+
+/// Duplicates of the listening sockets this process serves on, by role
+static LISTENERS: Mutex<Vec<(ListenerRole, std::net::TcpListener)>> = Mutex::new(Vec::new());
+
+/// Set while a successor is being started
+static HANDOVER_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Open proxy/API connections this process is still serving
+static ACTIVE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+static SHUTDOWN: OnceLock<watch::Sender<bool>> = OnceLock::new();
+
+fn shutdown_sender() -> &'static watch::Sender<bool> {
+    SHUTDOWN.get_or_init(|| watch::channel(false).0)
+}
+
+/// Take the listening sockets passed in by systemd socket activation or by a
+/// process handing over to this one. Must be called once, early in startup:
+/// the `LISTEN_FDS` variables are cleared afterwards.
+pub fn inherited_listeners() -> HashMap<ListenerRole, tokio::net::TcpListener> {
+    let roles = parse_roles(std::env::var(LISTEN_ROLES_ENV).ok().as_deref());
+    std::env::remove_var(LISTEN_ROLES_ENV);
+
+    let mut listenfd = listenfd::ListenFd::from_env();
+    let mut listeners = HashMap::new();
+    for (index, role) in roles.into_iter().enumerate() {
+        let Some(role) = role else { continue };
+        if let Ok(Some(std_listener)) = listenfd.take_tcp_listener(index) {
+            std_listener.set_nonblocking(true).ok();
+            if let Ok(listener) = tokio::net::TcpListener::from_std(std_listener) {
+                tracing::info!(role = role.as_str(), "Using inherited listening socket");
+                listeners.insert(role, listener);
+            }
+        }
+    }
+    listeners
+}
+
+/// Remember a listening socket so it can be passed to a successor.
+pub fn register(role: ListenerRole, listener: &tokio::net::TcpListener) {
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+        match listener.as_fd().try_clone_to_owned() {
+            Ok(fd) => {
+                let mut listeners = LISTENERS.lock().unwrap_or_else(|e| e.into_inner());
+                listeners.retain(|(r, _)| *r != role);
+                listeners.push((role, std::net::TcpListener::from(fd)));
+            }
+            Err(e) => {
+                tracing::warn!(role = role.as_str(), error = %e, "Cannot keep listener for handover")
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (role, listener);
+}
+
+/// Ask this process to stop accepting connections and shut down.
+pub fn request_shutdown() {
+    shutdown_sender().send_replace(true);
+}
+
+/// Resolves once a shutdown has been requested.
+pub async fn shutdown_requested() {
+    let mut rx = shutdown_sender().subscribe();
+    let _ = rx.wait_for(|requested| *requested).await;
+}
+
+/// Counts a connection as open until dropped
+#[derive(Debug)]
+pub struct ActiveConnection(());
+
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Count an accepted connection as open until the returned value is dropped.
+pub fn track_connection() -> ActiveConnection {
+    ACTIVE_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+    ActiveConnection(())
+}
+
+/// Wait for open connections to finish, up to `timeout`. Returns true if
+/// they all did.
+pub async fn wait_for_connections(timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let open = ACTIVE_CONNECTIONS.load(Ordering::Relaxed);
+        if open == 0 {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            tracing::warn!(open, "Shutting down with connections still open");
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Report that this process is serving: to the process handing over to it
+/// (if any) and to systemd (`READY=1`, `MAINPID=`).
+pub fn notify_ready() {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::fd::FromRawFd;
+
+        if let Some(fd) = std::env::var(READY_FD_ENV)
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+        {
+            std::env::remove_var(READY_FD_ENV);
+            // SAFETY: the fd was opened for us by the parent and is owned by nothing else
+            let mut pipe = unsafe { std::fs::File::from_raw_fd(fd) };
+            if let Err(e) = pipe.write_all(b"1") {
+                tracing::warn!(error = %e, "Failed to report readiness to previous process");
+            }
+        }
+
+        sd_notify(&format!("READY=1\nMAINPID={}", std::process::id()));
+    }
+}
+
+#[cfg(unix)]
+fn sd_notify(message: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    let Ok(socket) = UnixDatagram::unbound() else {
+        return;
+    };
+    let path = path.to_string_lossy().into_owned();
+    let result = match path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())
+                .and_then(|addr| socket.send_to_addr(message.as_bytes(), &addr))
+        }
+        _ => socket.send_to(message.as_bytes(), &path),
+    };
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "Failed to notify systemd");
+    }
+}
+
+/// Start a replacement process on this process's listening sockets, wait
+/// until it is serving, then begin shutting this process down. Returns the
+/// new process ID. If the successor fails to start, it is killed and this
+/// process keeps serving.
+pub async fn hand_over(ready_timeout: Duration) -> Result<u32> {
+    if HANDOVER_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        bail!("A handover is already in progress");
+    }
+    let result = spawn_and_wait(ready_timeout).await;
+    if result.is_err() {
+        HANDOVER_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+    let pid = result?;
+
+    tracing::info!(pid, "Replacement process is serving; shutting down");
+    request_shutdown();
+    Ok(pid)
+}
+
+#[cfg(unix)]
+async fn spawn_and_wait(ready_timeout: Duration) -> Result<u32> {
+    use std::io::Read;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::process::CommandExt;
+
+    /// Inherited fds start here (after stdin/stdout/stderr), as in systemd
+    const FIRST_FD: RawFd = 3;
+    /// Listener roles plus the ready pipe
+    const MAX_FDS: usize = 7;
+
+    let (roles, sockets): (Vec<ListenerRole>, Vec<std::net::TcpListener>) = {
+        let listeners = LISTENERS.lock().unwrap_or_else(|e| e.into_inner());
+        if listeners.is_empty() {
+            bail!("No listening sockets to hand over");
+        }
+        listeners
+            .iter()
+            .map(|(role, l)| Ok((*role, l.try_clone()?)))
+            .collect::<std::io::Result<Vec<_>>>()?
+            .into_iter()
+            .unzip()
+    };
+
+    let mut pipe_fds = [0 as RawFd; 2];
+    // SAFETY: pipe2 writes two fds into the array
+    if unsafe { libc::pipe2(pipe_fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("Failed to create ready pipe");
+    }
+    // SAFETY: both fds were just created and are owned here
+    let (ready_read, ready_write) = unsafe {
+        (
+            OwnedFd::from_raw_fd(pipe_fds[0]),
+            OwnedFd::from_raw_fd(pipe_fds[1]),
+        )
+    };
+
+    let mut sources = [-1 as RawFd; MAX_FDS];
+    for (slot, socket) in sources.iter_mut().zip(&sockets) {
+        *slot = socket.as_raw_fd();
+    }
+    let count = sockets.len() + 1;
+    sources[sockets.len()] = ready_write.as_raw_fd();
+
+    // After an update the running binary has been replaced on disk
+    let exe = std::env::current_exe().context("Failed to get current executable path")?;
+    let exe = match exe.to_str().and_then(|p| p.strip_suffix(" (deleted)")) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => exe,
+    };
+
+    let roles_value: Vec<&str> = roles.iter().map(|r| r.as_str()).collect();
+    let mut command = std::process::Command::new(&exe);
+    command
+        .args(std::env::args_os().skip(1))
+        .env("LISTEN_FDS", sockets.len().to_string())
+        .env("LISTEN_FDS_FIRST_FD", FIRST_FD.to_string())
+        .env_remove("LISTEN_PID")
+        .env(LISTEN_ROLES_ENV, roles_value.join(","))
+        .env(
+            READY_FD_ENV,
+            (FIRST_FD + sockets.len() as RawFd).to_string(),
+        );
+    // SAFETY: only async-signal-safe calls (fcntl, dup2) on fds owned by this
+    // process, with no allocation
+    unsafe {
+        command.pre_exec(move || {
+            // Move every fd above the target range first so placing one cannot
+            // clobber another that is still to be placed
+            let mut high = [-1 as RawFd; MAX_FDS];
+            for i in 0..count {
+                high[i] = libc::fcntl(sources[i], libc::F_DUPFD_CLOEXEC, 64);
+                if high[i] < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            for (i, fd) in high.iter().enumerate().take(count) {
+                // dup2 clears close-on-exec on the new fd
+                if libc::dup2(*fd, FIRST_FD + i as RawFd) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+
+    tracing::info!(
+        exe = %exe.display(),
+        listeners = ?roles_value,
+        "Starting replacement process with inherited sockets"
+    );
+    let mut child = command
+        .spawn()
+        .context("Failed to start replacement process")?;
+    drop(ready_write);
+    drop(sockets);
+
+    let pid = child.id();
+    let mut ready = std::fs::File::from(ready_read);
+    let wait = tokio::task::spawn_blocking(move || {
+        let mut buf = [0u8; 1];
+        ready.read(&mut buf).map(|n| n == 1)
+    });
+    match tokio::time::timeout(ready_timeout, wait).await {
+        Ok(Ok(Ok(true))) => Ok(pid),
+        outcome => {
+            let _ = child.kill();
+            let _ = child.wait();
+            match outcome {
+                Err(_) => bail!(
+                    "Replacement process {} did not become ready within {}s",
+                    pid,
+                    ready_timeout.as_secs()
+                ),
+                _ => bail!("Replacement process {} exited before becoming ready", pid),
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn spawn_and_wait(_ready_timeout: Duration) -> Result<u32> {
+    bail!("Process handover is only supported on Unix")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_roles() {
+        assert_eq!(
+            parse_roles(None),
+            vec![Some(ListenerRole::Http), Some(ListenerRole::Https)]
+        );
+        assert_eq!(
+            parse_roles(Some("https,api,bogus")),
+            vec![Some(ListenerRole::Https), Some(ListenerRole::Api), None]
+        );
+        for role in [
+            ListenerRole::Http,
+            ListenerRole::Https,
+            ListenerRole::Api,
+            ListenerRole::Http6,
+            ListenerRole::Https6,
+            ListenerRole::Api6,
+        ] {
+            assert_eq!(ListenerRole::parse(role.as_str()), Some(role));
+        }
+    }
+}
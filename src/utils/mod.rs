@@ -1,6 +1,10 @@
 // Utility modules
 
+pub mod cron;
+pub mod handover;
+pub mod net;
 pub mod supervise;
+pub mod time;
 
 use std::path::Path;
 
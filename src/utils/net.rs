@@ -0,0 +1,48 @@
+//! Listener helpers for dual-stack (IPv4 + IPv6) serving.
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Bind an IPv6 listener with `IPV6_V6ONLY` set, so it can share its port
+/// with an IPv4 listener on `0.0.0.0` regardless of the OS default.
+pub fn bind_ipv6_only(addr: SocketAddr) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::IPV6, Type::STREAM, Some(Protocol::TCP))?;
+    socket.set_only_v6(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
+}
+
+/// Accept the next connection from `primary` or, if present, `secondary`
+pub async fn accept_either(
+    primary: &TcpListener,
+    secondary: Option<&TcpListener>,
+) -> std::io::Result<(TcpStream, SocketAddr)> {
+    match secondary {
+        Some(secondary) => tokio::select! {
+            accepted = primary.accept() => accepted,
+            accepted = secondary.accept() => accepted,
+        },
+        None => primary.accept().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ipv6_only_listener_shares_port_with_ipv4() {
+        let v4 = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+        let port = v4.local_addr().unwrap().port();
+        // Hosts without IPv6 cannot run this test
+        let Ok(v6) = bind_ipv6_only(SocketAddr::from(([0u16; 8], port))) else {
+            return;
+        };
+        assert_eq!(v6.local_addr().unwrap().port(), port);
+    }
+}
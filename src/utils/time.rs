@@ -0,0 +1,10 @@
+//! Timestamp parsing shared by the engine and API.
+
+use chrono::{DateTime, Utc};
+
+/// Parse an RFC 3339 timestamp as stored by the engine (e.g. `started_at`)
+pub fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
@@ -0,0 +1,452 @@
+//! End-to-end deployment tests against the mock runtime.
+//!
+//! Run with `cargo test --features test-utils`.
+
+use rivetr::runtime::{MockBehavior, MockRuntime};
+use rivetr::testing::TestApp;
+use serde_json::json;
+use std::time::Duration;
+
+const DEPLOY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[tokio::test]
+async fn test_registry_app_deploys_and_is_routed() {
+    let app = TestApp::spawn(MockRuntime::new()).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+            "domain": "web.example.test",
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+
+    let deployment_id = app.deploy(app_id).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "running", "{}", deployment);
+
+    assert_eq!(app.runtime.pulls(), vec!["nginx:alpine".to_string()]);
+    let running = app.runtime.running_containers();
+    assert_eq!(running.len(), 1);
+
+    let backend = app
+        .routes
+        .load()
+        .get_backend("web.example.test")
+        .expect("domain is routed to the new container");
+    assert_eq!(backend.container_id, running[0].id);
+}
+
+#[tokio::test]
+async fn test_failed_pull_fails_the_deployment() {
+    let runtime = MockRuntime::with_behavior(MockBehavior {
+        pull_error: Some("manifest unknown".to_string()),
+        ..Default::default()
+    });
+    let app = TestApp::spawn(runtime).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "does-not-exist",
+            "port": 80,
+        }))
+        .await;
+
+    let deployment_id = app.deploy(created["id"].as_str().unwrap()).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "failed", "{}", deployment);
+    assert!(app.runtime.running_containers().is_empty());
+}
+
+#[tokio::test]
+async fn test_worker_deploys_without_a_port() {
+    let app = TestApp::spawn(MockRuntime::new()).await;
+    let created = app
+        .create_app(json!({
+            "name": "queue-worker",
+            "docker_image": "busybox",
+            "docker_image_tag": "latest",
+            "app_type": "worker",
+        }))
+        .await;
+
+    let deployment_id = app.deploy(created["id"].as_str().unwrap()).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "running", "{}", deployment);
+
+    let running = app.runtime.running_containers();
+    assert_eq!(running.len(), 1);
+    assert_eq!(running[0].host_port, None);
+    assert!(!running[0].env.iter().any(|(key, _)| key == "PORT"));
+}
+
+#[tokio::test]
+async fn test_worker_that_crashes_after_start_fails_the_deployment() {
+    let runtime = MockRuntime::with_behavior(MockBehavior {
+        crash_after_start: Some(Duration::from_millis(500)),
+        ..Default::default()
+    });
+    let app = TestApp::spawn(runtime).await;
+    let created = app
+        .create_app(json!({
+            "name": "queue-worker",
+            "docker_image": "busybox",
+            "docker_image_tag": "latest",
+            "app_type": "worker",
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+    // Keep retrying the crashed worker for less than the deploy timeout
+    let (status, body) = app
+        .put(
+            &format!("/api/apps/{}", app_id),
+            json!({ "healthcheck_window_secs": 10 }),
+        )
+        .await;
+    assert!(status.is_success(), "{} {}", status, body);
+
+    let deployment_id = app.deploy(app_id).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "failed", "{}", deployment);
+}
+
+/// Poll `condition` until it holds; panics after `timeout`
+async fn wait_until(timeout: Duration, what: &str, condition: impl Fn() -> bool) {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while !condition() {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "timed out waiting for {}",
+            what
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_removes_a_container_whose_id_was_not_recorded() {
+    let runtime = MockRuntime::with_behavior(MockBehavior {
+        start_delay: Duration::from_secs(60),
+        ..Default::default()
+    });
+    let app = TestApp::spawn(runtime).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+        }))
+        .await;
+    let deployment_id = app.deploy(created["id"].as_str().unwrap()).await;
+
+    // Cancel while the container exists but `run` has not returned its ID
+    let runtime = app.runtime.clone();
+    wait_until(DEPLOY_TIMEOUT, "the container to be created", || {
+        !runtime.containers().is_empty()
+    })
+    .await;
+    let (status, body) = app
+        .post(
+            &format!("/api/deployments/{}/cancel", deployment_id),
+            json!({}),
+        )
+        .await;
+    assert!(status.is_success(), "cancel failed: {} {}", status, body);
+
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "cancelled", "{}", deployment);
+    wait_until(DEPLOY_TIMEOUT, "the container to be removed", || {
+        runtime.containers().is_empty()
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_scale_changes_the_running_replicas() {
+    let app = TestApp::spawn(MockRuntime::new()).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+    let deployment_id = app.deploy(app_id).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "running", "{}", deployment);
+
+    let scale = format!("/api/apps/{}/scale", app_id);
+    for replicas in [0, 11] {
+        let (status, body) = app.post(&scale, json!({ "replicas": replicas })).await;
+        assert_eq!(status.as_u16(), 400, "{} replicas: {}", replicas, body);
+    }
+    assert_eq!(app.runtime.running_containers().len(), 1);
+
+    let (status, body) = app.post(&scale, json!({ "replicas": 3 })).await;
+    assert!(status.is_success(), "{} {}", status, body);
+    assert_eq!(body.as_array().map(Vec::len), Some(3), "{}", body);
+    assert_eq!(app.runtime.running_containers().len(), 3);
+
+    // Scaled-down replicas are kept as stopped
+    let (status, body) = app.post(&scale, json!({ "replicas": 1 })).await;
+    assert!(status.is_success(), "{} {}", status, body);
+    let running = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|replica| replica["status"] == "running")
+        .count();
+    assert_eq!(running, 1, "{}", body);
+    assert_eq!(app.runtime.running_containers().len(), 1);
+}
+
+/// Poll a command run until it finishes
+async fn wait_for_run(app: &TestApp, app_id: &str, run_id: &str) -> serde_json::Value {
+    let path = format!("/api/apps/{}/runs/{}", app_id, run_id);
+    let deadline = tokio::time::Instant::now() + DEPLOY_TIMEOUT;
+    loop {
+        let (_, run) = app.get(&path).await;
+        if run["status"] != "running" {
+            return run;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "timed out waiting for run {}",
+            run
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_command_runs_record_exit_code_and_output() {
+    let app = TestApp::spawn(MockRuntime::new()).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+    let runs = format!("/api/apps/{}/runs", app_id);
+
+    // Nothing to take the image from yet
+    let (status, run) = app.post(&runs, json!({ "command": "./migrate" })).await;
+    assert_eq!(status.as_u16(), 202, "{}", run);
+    let run = wait_for_run(&app, app_id, run["id"].as_str().unwrap()).await;
+    assert_eq!(run["status"], "failed", "{}", run);
+    assert!(
+        run["error_message"]
+            .as_str()
+            .unwrap()
+            .contains("no running deployment"),
+        "{}",
+        run
+    );
+
+    let deployment_id = app.deploy(app_id).await;
+    let deployment = app
+        .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(deployment["status"], "running", "{}", deployment);
+    app.runtime.update_behavior(|b| {
+        b.log_lines = vec!["Applying migrations".to_string(), "Done".to_string()]
+    });
+
+    for (exit_code, expected) in [(0, "success"), (3, "failed")] {
+        let (status, run) = app.post(&runs, json!({ "command": "./migrate" })).await;
+        assert_eq!(status.as_u16(), 202, "{}", run);
+        assert_eq!(run["status"], "running", "{}", run);
+        let run_id = run["id"].as_str().unwrap();
+
+        // The command runs in its own container until its process exits
+        let name = format!("rivetr-web-run-{}", &run_id[..8]);
+        let runtime = app.runtime.clone();
+        wait_until(DEPLOY_TIMEOUT, "the run's container", || {
+            runtime.container(&name).is_some_and(|c| c.running)
+        })
+        .await;
+        app.runtime.crash(&name, exit_code);
+
+        let run = wait_for_run(&app, app_id, run_id).await;
+        assert_eq!(run["status"], expected, "{}", run);
+        assert_eq!(run["exit_code"], exit_code, "{}", run);
+        assert_eq!(run["image"], "nginx:alpine", "{}", run);
+        assert!(run["container_id"].is_null(), "{}", run);
+        assert!(
+            run["output"]
+                .as_str()
+                .unwrap()
+                .ends_with("Applying migrations\nDone"),
+            "{}",
+            run
+        );
+        assert!(app.runtime.container(&name).is_none());
+    }
+
+    let (_, history) = app.get(&runs).await;
+    let statuses: Vec<_> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|run| run["status"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(statuses.len(), 3, "{}", history);
+    assert_eq!(statuses.iter().filter(|s| *s == "failed").count(), 2);
+}
+
+#[tokio::test]
+async fn test_rollback_switches_back_to_the_kept_container() {
+    let mut config = rivetr::config::Config::default();
+    config.rate_limit.enabled = false;
+    config.runtime.keep_previous_minutes = 5;
+    let app = TestApp::spawn_with_config(MockRuntime::new(), config)
+        .await
+        .unwrap();
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+            "domain": "web.example.test",
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+
+    let first = app.deploy(app_id).await;
+    let first = app.wait_for_deployment(&first, DEPLOY_TIMEOUT).await;
+    assert_eq!(first["status"], "running", "{}", first);
+    let second = app.deploy(app_id).await;
+    let second = app.wait_for_deployment(&second, DEPLOY_TIMEOUT).await;
+    assert_eq!(second["status"], "running", "{}", second);
+
+    // The replaced container keeps running without traffic
+    let kept_id = first["container_id"].as_str().unwrap().to_string();
+    let runtime = app.runtime.clone();
+    wait_until(DEPLOY_TIMEOUT, "both containers running", || {
+        runtime.running_containers().len() == 2
+    })
+    .await;
+    assert!(app.runtime.container(&kept_id).unwrap().running);
+    let routed = |app: &TestApp| {
+        app.routes
+            .load()
+            .get_backend("web.example.test")
+            .map(|backend| backend.container_id)
+    };
+    assert_eq!(routed(&app).as_deref(), second["container_id"].as_str());
+
+    let started = app.runtime.containers().len();
+    let (status, rollback) = app
+        .post(
+            &format!(
+                "/api/deployments/{}/rollback",
+                second["id"].as_str().unwrap()
+            ),
+            json!({ "target_deployment_id": first["id"] }),
+        )
+        .await;
+    assert!(status.is_success(), "{} {}", status, rollback);
+    let rollback = app
+        .wait_for_deployment(rollback["id"].as_str().unwrap(), DEPLOY_TIMEOUT)
+        .await;
+    assert_eq!(rollback["status"], "running", "{}", rollback);
+
+    // No container was started: traffic moved back to the kept one
+    assert_eq!(rollback["container_id"].as_str(), Some(kept_id.as_str()));
+    assert!(app.runtime.containers().len() <= started);
+    let runtime = app.runtime.clone();
+    let second_id = second["container_id"].as_str().unwrap().to_string();
+    wait_until(DEPLOY_TIMEOUT, "the rolled back container to go", || {
+        runtime.container(&second_id).is_none()
+    })
+    .await;
+    let kept = app.runtime.container(&kept_id).unwrap();
+    assert!(kept.running);
+    assert_eq!(kept.name, "rivetr-web");
+    assert_eq!(routed(&app), Some(kept_id));
+    assert!(app
+        .deployment_logs(rollback["id"].as_str().unwrap())
+        .await
+        .iter()
+        .any(|line| line.contains("switching traffic back")));
+}
+
+#[tokio::test]
+async fn test_failing_test_command_stops_the_release() {
+    let app = TestApp::spawn(MockRuntime::new()).await;
+    let created = app
+        .create_app(json!({
+            "name": "web",
+            "docker_image": "nginx",
+            "docker_image_tag": "alpine",
+            "port": 80,
+        }))
+        .await;
+    let app_id = created["id"].as_str().unwrap();
+    let (status, body) = app
+        .put(
+            &format!("/api/apps/{}", app_id),
+            json!({ "test_commands": ["npm test", "./smoke.sh"] }),
+        )
+        .await;
+    assert!(status.is_success(), "{} {}", status, body);
+    app.runtime
+        .update_behavior(|b| b.log_lines = vec!["42 passing".to_string()]);
+
+    // Each command runs to completion in its own test container
+    for (exit_codes, expected) in [(vec![0, 1], "failed"), (vec![0, 0], "running")] {
+        let deployment_id = app.deploy(app_id).await;
+        for exit_code in exit_codes {
+            let runtime = app.runtime.clone();
+            wait_until(DEPLOY_TIMEOUT, "the test container", || {
+                runtime
+                    .container("rivetr-web-test")
+                    .is_some_and(|c| c.running)
+            })
+            .await;
+            let test_container = app.runtime.container("rivetr-web-test").unwrap();
+            assert_eq!(test_container.host_port, None);
+            app.runtime.crash(&test_container.id, exit_code);
+        }
+
+        let deployment = app
+            .wait_for_deployment(&deployment_id, DEPLOY_TIMEOUT)
+            .await;
+        assert_eq!(deployment["status"], expected, "{}", deployment);
+        let output = deployment["test_output"].as_str().unwrap();
+        let lines: Vec<_> = output.lines().filter(|l| !l.starts_with("mock ")).collect();
+        assert_eq!(
+            lines,
+            ["$ npm test", "42 passing", "$ ./smoke.sh", "42 passing"],
+            "{}",
+            output
+        );
+        assert!(app.runtime.container("rivetr-web-test").is_none());
+    }
+
+    // The failed release never started a container; the passing one did
+    let running = app.runtime.running_containers();
+    assert_eq!(running.len(), 1, "{:?}", running);
+}